@@ -7,18 +7,23 @@ extern crate log;
 
 use clap::{App, Arg, ArgMatches};
 use llhd;
-use moore_circt::{self as circt, mlir, prelude::*, sys::*};
-// use llhd::opt::{Pass, PassContext};
 use moore::common::score::NodeRef;
+use moore::design_units::{split_lib_prefix, DesignUnitKind};
 use moore::errors::*;
+use moore::foreign::ForeignLang;
 use moore::name::Name;
-use moore::score::{ScoreBoard, ScoreContext};
+use moore::score::{InstTargetCollector, ScoreBoard, ScoreContext};
 use moore::source::Span;
 use moore::svlog::{ast::AcceptVisitor as _, hir::Visitor as _, QueryDatabase as _};
+use moore::util::{HasDesc, HasSpan};
 use moore::*;
-use std::path::Path;
+use moore_circt::{self as circt, mlir, prelude::*, sys::*};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Language {
     Verilog,
     SystemVerilog,
@@ -37,6 +42,22 @@ fn main() {
     );
     builder.try_init().unwrap();
 
+    // Expand the argument files referenced on the command line. A leading
+    // `lint`, `fmt`, `doc`, or `summary` is a shorthand for the option of the
+    // same name.
+    let mut session = Session::new();
+    let mut args = match expand_arg_files(&session, std::env::args()) {
+        Ok(x) => x,
+        Err(()) => exit(1),
+    };
+    match args.get(1).map(String::as_str) {
+        Some("lint") => args[1] = "--lint".to_string(),
+        Some("fmt") => args[1] = "--fmt".to_string(),
+        Some("doc") => args[1] = "--doc".to_string(),
+        Some("summary") => args[1] = "--summary".to_string(),
+        _ => (),
+    }
+
     // Parse the command-line arguments.
     let matches = App::new(env!("CARGO_PKG_NAME"))
         .version(clap::crate_version!())
@@ -95,13 +116,134 @@ fn main() {
         .arg(
             Arg::with_name("dump-ast")
                 .long("dump-ast")
-                .help("Dump the parsed abstract syntax tree"),
+                .value_name("FORMAT")
+                .help("Dump the parsed abstract syntax tree as text or as JSON with spans")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .possible_values(&["json", "text"]),
+        )
+        .arg(
+            Arg::with_name("dump-hir")
+                .long("dump-hir")
+                .value_name("FORMAT")
+                .help("Dump the HIR of the elaborated module instead of generating code")
+                .takes_value(true)
+                .possible_values(&["json", "text"]),
         )
         .arg(
             Arg::with_name("check-syntax")
                 .long("syntax")
+                .visible_alias("parse-only")
                 .help("Preprocess and check the input for syntax errors"),
         )
+        .arg(
+            Arg::with_name("preprocess-only")
+                .long("preprocess-only")
+                .help("Stop after preprocessing the input, reporting diagnostics only")
+                .conflicts_with("preproc"),
+        )
+        .arg(
+            Arg::with_name("fmt")
+                .long("fmt")
+                .help("Reformat the SystemVerilog input files and write them to stdout"),
+        )
+        .arg(
+            Arg::with_name("fmt-indent")
+                .long("fmt-indent")
+                .value_name("N")
+                .help("Indent formatted files by N spaces per level")
+                .default_value("4")
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("fmt-no-align")
+                .long("fmt-no-align")
+                .help("Do not align ports, port connections, and assignments into columns"),
+        )
+        .arg(
+            Arg::with_name("fmt-in-place")
+                .long("fmt-in-place")
+                .help("Overwrite the input files with their formatted contents")
+                .requires("fmt"),
+        )
+        .arg(
+            Arg::with_name("doc")
+                .long("doc")
+                .help("Document the packages, modules, and interfaces of the input"),
+        )
+        .arg(
+            Arg::with_name("doc-format")
+                .long("doc-format")
+                .value_name("FORMAT")
+                .help("Format of the generated documentation")
+                .default_value("markdown")
+                .takes_value(true)
+                .possible_values(&["markdown", "html"]),
+        )
+        .arg(
+            Arg::with_name("summary")
+                .long("summary")
+                .help("Write a JSON summary of the ports and parameters of the `--top` or all modules"),
+        )
+        .arg(
+            Arg::with_name("param")
+                .long("param")
+                .value_name("NAME=VALUE")
+                .help("Override a parameter with an integer in the module summary")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .requires("summary"),
+        )
+        .arg(
+            Arg::with_name("call-graph")
+                .long("call-graph")
+                .value_name("FORMAT")
+                .help("Write the call graph of all tasks and functions instead of code")
+                .takes_value(true)
+                .possible_values(&["dot", "json"]),
+        )
+        .arg(
+            Arg::with_name("semantic-tokens")
+                .long("semantic-tokens")
+                .help("Write the classified tokens of the SystemVerilog input for editor highlighting"),
+        )
+        .arg(
+            Arg::with_name("xref")
+                .long("xref")
+                .value_name("FILE")
+                .help("Write a ctags index of all definitions and references (`-` for stdout)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("node-at")
+                .long("node-at")
+                .value_name("FILE:OFFSET")
+                .help("Print the innermost HIR node covering a byte offset in a file instead of code")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("lower-only").long("lower-only").help(
+                "Stop after lowering the SystemVerilog input to HIR, reporting diagnostics only",
+            ),
+        )
+        .arg(
+            Arg::with_name("elaborate-only")
+                .long("elaborate-only")
+                .help("Stop after elaborating the design, reporting diagnostics only")
+                .conflicts_with_all(&[
+                    "dump-hir",
+                    "output",
+                    "output-format",
+                    "emit",
+                    "elab-report",
+                    "fsm-report",
+                ]),
+        )
         .arg(
             Arg::with_name("emit_pkgs")
                 .long("emit-pkgs")
@@ -111,11 +253,134 @@ fn main() {
             Arg::with_name("opt-level")
                 .short("O")
                 .long("opt-level")
-                .help("Sets optimization level applied to the output")
+                .help(
+                    "Sets optimization level applied to the output; 2 and above run constant \
+                     folding, dead code elimination, and signal forwarding on the LLHD output",
+                )
                 .default_value("1")
                 .takes_value(true)
                 .number_of_values(1),
         )
+        .arg(
+            Arg::with_name("diag-format")
+                .long("diag-format")
+                .value_name("FORMAT")
+                .help("Format in which diagnostics are printed")
+                .takes_value(true)
+                .possible_values(&["human", "json", "sarif"])
+                .default_value("human"),
+        )
+        .arg(
+            Arg::with_name("warnings")
+                .short("W")
+                .value_name("GROUP")
+                .help(
+                    "Enable (-W<group>), disable (-Wno-<group>), or turn into errors \
+                     (-Werror=<group>) the warnings in a group, or turn all warnings into \
+                     errors (-Werror)",
+                )
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("waiver-file")
+                .long("waiver-file")
+                .value_name("FILE")
+                .help("Do not report the warnings listed in a waiver or baseline file")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("write-baseline")
+                .long("write-baseline")
+                .value_name("FILE")
+                .help("Record the reported warnings in a baseline file, which waives them in later runs")
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("error-limit")
+                .long("error-limit")
+                .value_name("N")
+                .help("Stop after N errors (0 for no limit)")
+                .default_value("0")
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("fail-fast")
+                .long("fail-fast")
+                .help("Stop after the first error")
+                .overrides_with("error-limit"),
+        )
+        .arg(
+            Arg::with_name("stats")
+                .long("stats")
+                .help("Report the time, query count, and peak memory of each compilation phase"),
+        )
+        .arg(
+            Arg::with_name("max-inst-depth")
+                .long("max-inst-depth")
+                .value_name("DEPTH")
+                .help("Sets the maximum depth of the instance hierarchy (0 for no limit)")
+                .default_value("256")
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .value_name("N")
+                .help("Parse the input files on N threads (0 for one per CPU)")
+                .default_value("1")
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("allow-blackbox")
+                .long("allow-blackbox")
+                .help("Replace instantiations of unknown modules with empty blackboxes"),
+        )
+        .arg(
+            Arg::with_name("flatten")
+                .long("flatten")
+                .help("Inline instantiated modules, except those marked `(* keep_hierarchy *)`"),
+        )
+        .arg(
+            Arg::with_name("range-checks")
+                .long("range-checks")
+                .help("Check that values assigned to VHDL scalar subtypes are within their range"),
+        )
+        .arg(
+            Arg::with_name("library-dir")
+                .short("y")
+                .value_name("DIR")
+                .help("Add a directory searched for files named after unknown modules")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("library-file")
+                .short("v")
+                .value_name("FILE")
+                .help("Add a library file searched for unknown modules")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("library-ext")
+                .long("libext")
+                .value_name("EXT")
+                .help("Add a file extension considered in library directories [default: .v, .sv]")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
         .arg(
             Arg::with_name("lib")
                 .short("l")
@@ -135,6 +400,15 @@ fn main() {
                 .takes_value(true)
                 .number_of_values(1),
         )
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .value_name("MODULE")
+                .help("Elaborate the SystemVerilog modules matching a name or `*`/`?` glob pattern")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
         .arg(
             Arg::with_name("output")
                 .short("o")
@@ -143,12 +417,92 @@ fn main() {
                 .takes_value(true),
         )
         .arg(
-            Arg::with_name("output-format")
+            Arg::with_name("arg-file")
                 .short("f")
+                .value_name("FILE")
+                .help("Read additional arguments from a file, relative to the working directory")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("arg-file-relative")
+                .short("F")
+                .value_name("FILE")
+                .help("Read additional arguments from a file, relative to the file itself")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("output-format")
                 .long("format")
                 .help("Output format")
                 .takes_value(true)
-                .possible_values(&["llhd", "mlir", "mlir-native"]),
+                .possible_values(&[
+                    "llhd",
+                    "llhd-mlir",
+                    "mlir",
+                    "mlir-native",
+                    "firrtl",
+                    "verilog",
+                    "yosys-json",
+                ]),
+        )
+        .arg(
+            Arg::with_name("emit")
+                .long("emit")
+                .value_name("GRAPH")
+                .help("Emit a Graphviz graph or the instance tree of the design instead of code")
+                .takes_value(true)
+                .possible_values(&["dot-hierarchy", "dot-dataflow", "hierarchy.json"]),
+        )
+        .arg(
+            Arg::with_name("elab-report")
+                .long("elab-report")
+                .value_name("FORMAT")
+                .help("Print the module specializations after elaboration")
+                .takes_value(true)
+                .possible_values(&["text", "json"]),
+        )
+        .arg(
+            Arg::with_name("lint")
+                .long("lint")
+                .help("Run the lint rules on the elaborated design instead of generating code"),
+        )
+        .arg(
+            Arg::with_name("lint-option")
+                .long("lint-option")
+                .value_name("RULE.OPTION=VALUE")
+                .help("Configure a lint rule")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("lint-config")
+                .long("lint-config")
+                .value_name("FILE")
+                .help("Configure lint rules with the options listed in a file")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("fsm-report")
+                .long("fsm-report")
+                .value_name("FORMAT")
+                .help("Report the state machines found in the design")
+                .takes_value(true)
+                .possible_values(&["note", "json"]),
+        )
+        .arg(
+            Arg::with_name("boundary-report")
+                .long("boundary-report")
+                .value_name("FORMAT")
+                .help("Report how ports and parameters are converted between languages")
+                .takes_value(true)
+                .possible_values(&["note", "json"]),
         )
         .arg(
             Arg::with_name("debug-info")
@@ -160,12 +514,34 @@ fn main() {
             Arg::with_name("INPUT")
                 .help("The input files to compile")
                 .multiple(true)
-                .required(true),
+                .required_unless("explain"),
+        )
+        .arg(
+            Arg::with_name("explain")
+                .long("explain")
+                .value_name("CODE")
+                .help("Print an explanation of a diagnostic code, such as SV0001")
+                .takes_value(true)
+                .number_of_values(1),
         )
-        .get_matches();
+        .get_matches_from(args);
+
+    // Explain a diagnostic code if requested.
+    if let Some(code) = matches.value_of("explain") {
+        match svlog::explain::explain(code) {
+            Some(text) => print!("{}", text),
+            None => {
+                session.emit(DiagBuilder2::error(format!(
+                    "`{}` is not a known diagnostic code",
+                    code
+                )));
+                exit(1);
+            }
+        }
+        return;
+    }
 
     // Configure the session.
-    let mut session = Session::new();
     session.opts.trace_scoreboard = matches.is_present("trace_scoreboard");
     for v in matches
         .values_of("verbosity-opts")
@@ -188,9 +564,352 @@ fn main() {
         };
     }
     session.opts.opt_level = matches.value_of("opt-level").unwrap().parse().unwrap();
+    session.opts.max_inst_depth = matches.value_of("max-inst-depth").unwrap().parse().unwrap();
+    session.opts.allow_blackbox = matches.is_present("allow-blackbox");
+    session.opts.flatten = matches.is_present("flatten");
+    session.opts.range_checks = matches.is_present("range-checks");
+    session.opts.stats = matches.is_present("stats");
+    set_diag_format(match matches.value_of("diag-format").unwrap() {
+        "json" => DiagFormat::Json,
+        "sarif" => DiagFormat::Sarif,
+        _ => DiagFormat::Human,
+    });
+    for value in matches.values_of("warnings").into_iter().flatten() {
+        let (group, level) = if value == "error" {
+            session.opts.warnings_as_errors = true;
+            continue;
+        } else if let Some(group) = value.strip_prefix("error=") {
+            (group, WarningLevel::Error)
+        } else if let Some(group) = value.strip_prefix("no-") {
+            (group, WarningLevel::Ignore)
+        } else {
+            (value, WarningLevel::Warn)
+        };
+        if !WARNING_GROUPS.iter().any(|&(x, _)| x == group) {
+            let mut d = DiagBuilder2::warning(format!("unknown warning group `{}`", group))
+                .add_note("The following warning groups exist:");
+            for &(name, desc) in WARNING_GROUPS {
+                d = d.add_note(format!("- {}: {}", name, desc));
+            }
+            session.emit(d);
+            continue;
+        }
+        session.opts.warning_levels.insert(group.to_string(), level);
+    }
+    for path in matches.values_of("waiver-file").into_iter().flatten() {
+        let text = match std::fs::read_to_string(path) {
+            Ok(x) => x,
+            Err(e) => {
+                session.emit(DiagBuilder2::fatal(format!(
+                    "unable to read waiver file `{}`: {}",
+                    path, e
+                )));
+                exit(1);
+            }
+        };
+        if let Err((line, msg)) = session.opts.waivers.parse(&text) {
+            session.emit(DiagBuilder2::fatal(format!(
+                "invalid waiver in `{}` on line {}: {}",
+                path, line, msg
+            )));
+            exit(1);
+        }
+    }
+    // Options given on the command line override the ones in config files.
+    let mut lint_options = vec![];
+    for path in matches.values_of("lint-config").into_iter().flatten() {
+        let text = match std::fs::read_to_string(path) {
+            Ok(x) => x,
+            Err(e) => {
+                session.emit(DiagBuilder2::fatal(format!(
+                    "unable to read lint config `{}`: {}",
+                    path, e
+                )));
+                exit(1);
+            }
+        };
+        lint_options.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from),
+        );
+    }
+    lint_options.extend(
+        matches
+            .values_of("lint-option")
+            .into_iter()
+            .flatten()
+            .map(String::from),
+    );
+    for value in &lint_options {
+        let (key, option_value) = match value.find('=') {
+            Some(index) => (value[..index].trim(), value[index + 1..].trim()),
+            None => {
+                session.emit(DiagBuilder2::fatal(format!(
+                    "expected `<rule>.<option>=<value>`, found `{}`",
+                    value
+                )));
+                exit(1);
+            }
+        };
+        let option = key.find('.').and_then(|index| {
+            svlog::lint::find_rule(&key[..index])?.find_option(&key[index + 1..])
+        });
+        if let Some(option) = option {
+            if let Err(msg) = option.validate(option_value) {
+                session.emit(DiagBuilder2::fatal(format!(
+                    "invalid value `{}` for lint option `{}`: {}",
+                    option_value, key, msg
+                )));
+                exit(1);
+            }
+        } else {
+            let mut d = DiagBuilder2::warning(format!("unknown lint option `{}`", key));
+            let options: Vec<_> = svlog::lint::RULES
+                .iter()
+                .flat_map(|rule| rule.options.iter().map(move |option| (rule, option)))
+                .collect();
+            if !options.is_empty() {
+                d = d.add_note("The following lint options exist:");
+            }
+            for (rule, option) in options {
+                d = d.add_note(format!(
+                    "- {}.{}: {} (default `{}`)",
+                    rule.name, option.name, option.desc, option.default
+                ));
+            }
+            session.emit(d);
+            continue;
+        }
+        session
+            .opts
+            .lint_options
+            .insert(key.to_string(), option_value.to_string());
+    }
+    if let Some(path) = matches.value_of("write-baseline") {
+        waiver::start_baseline(PathBuf::from(path));
+    }
+    session.opts.error_limit = if matches.is_present("fail-fast") {
+        1
+    } else {
+        matches.value_of("error-limit").unwrap().parse().unwrap()
+    };
+    let values = |name| -> Vec<String> {
+        matches
+            .values_of(name)
+            .into_iter()
+            .flatten()
+            .map(String::from)
+            .collect()
+    };
+    session.opts.library_dirs = values("library-dir")
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    session.opts.library_files = values("library-file")
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    session.opts.library_exts = values("library-ext");
+    session.opts.include_paths = values("inc").into_iter().map(PathBuf::from).collect();
+    session.opts.defines = values("def")
+        .into_iter()
+        .map(|x| {
+            let mut iter = x.splitn(2, '=');
+            (
+                iter.next().unwrap().to_string(),
+                iter.next().map(String::from),
+            )
+        })
+        .collect();
 
     // Invoke the compiler.
     score(&session, &matches);
+    flush_diags();
+}
+
+/// Exit the process, printing the deferred diagnostics first.
+fn exit(code: i32) -> ! {
+    flush_diags();
+    std::process::exit(code)
+}
+
+/// The options that consume the following argument as their value.
+const VALUE_OPTIONS: &[&str] = &[
+    "-V",
+    "-W",
+    "-I",
+    "-D",
+    "-y",
+    "-v",
+    "--libext",
+    "-O",
+    "--opt-level",
+    "--max-inst-depth",
+    "-j",
+    "--jobs",
+    "--diag-format",
+    "--explain",
+    "--error-limit",
+    "--waiver-file",
+    "--write-baseline",
+    "--lint-option",
+    "--lint-config",
+    "-l",
+    "--lib",
+    "-e",
+    "--elaborate",
+    "-o",
+    "--output",
+    "--format",
+    "--emit",
+    "--elab-report",
+    "--fsm-report",
+    "--boundary-report",
+    "--dump-hir",
+    "--fmt-indent",
+    "--doc-format",
+    "--param",
+    "--xref",
+    "--call-graph",
+    "--node-at",
+];
+
+/// Expand the argument files referenced with `-f` and `-F` in the command-line
+/// arguments.
+///
+/// Argument files contain whitespace-separated source files and options, with
+/// `//` and `#` comments running to the end of the line. `+incdir+<dir>`,
+/// `+define+<macro>[=<value>]`, and `+libext+<ext>` are translated to `-I`,
+/// `-D`, and `--libext` options, and argument files may reference further
+/// argument files. Relative paths in a file given with `-F` are resolved
+/// relative to the directory containing the file, whereas the ones in a file
+/// given with `-f` are left relative to the working directory.
+fn expand_arg_files(sess: &Session, args: impl Iterator<Item = String>) -> Result<Vec<String>, ()> {
+    let mut expanded = vec![];
+    let mut args = args;
+    expanded.extend(args.next());
+    expand_args(sess, args.collect(), None, &mut vec![], &mut expanded)?;
+    Ok(expanded)
+}
+
+/// Expand the argument files in a list of arguments.
+///
+/// `base` is the directory against which relative paths are resolved, and
+/// `stack` contains the argument files currently being expanded.
+fn expand_args(
+    sess: &Session,
+    args: Vec<String>,
+    base: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+    into: &mut Vec<String>,
+) -> Result<(), ()> {
+    let resolve = |path: &str| match base {
+        Some(base) if Path::new(path).is_relative() => {
+            base.join(path).to_string_lossy().into_owned()
+        }
+        _ => path.to_string(),
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "-f" || arg == "-F" {
+            let file = match args.next() {
+                Some(x) => PathBuf::from(resolve(&x)),
+                None => {
+                    sess.emit(DiagBuilder2::fatal(format!(
+                        "`{}` requires an argument file",
+                        arg
+                    )));
+                    return Err(());
+                }
+            };
+            if stack.contains(&file) {
+                sess.emit(DiagBuilder2::fatal(format!(
+                    "argument file `{}` includes itself",
+                    file.display()
+                )));
+                return Err(());
+            }
+            let text = match std::fs::read_to_string(&file) {
+                Ok(x) => x,
+                Err(e) => {
+                    sess.emit(
+                        DiagBuilder2::fatal(format!(
+                            "unable to open argument file `{}`",
+                            file.display()
+                        ))
+                        .add_note(format!("{}", e)),
+                    );
+                    return Err(());
+                }
+            };
+            let nested: Vec<String> = text
+                .lines()
+                .map(|line| line.split("//").next().unwrap())
+                .flat_map(|line| line.split_whitespace().take_while(|x| !x.starts_with('#')))
+                .map(String::from)
+                .collect();
+            let nested_base = match arg.as_str() {
+                "-F" => Some(file.parent().unwrap_or(Path::new("")).to_path_buf()),
+                _ => None,
+            };
+            stack.push(file);
+            expand_args(sess, nested, nested_base.as_deref(), stack, into)?;
+            stack.pop();
+        } else if let Some(dirs) = arg.strip_prefix("+incdir+") {
+            for dir in dirs.split('+').filter(|x| !x.is_empty()) {
+                into.push("-I".to_string());
+                into.push(resolve(dir));
+            }
+        } else if let Some(defines) = arg.strip_prefix("+define+") {
+            for define in defines.split('+').filter(|x| !x.is_empty()) {
+                into.push("-D".to_string());
+                into.push(define.to_string());
+            }
+        } else if let Some(exts) = arg.strip_prefix("+libext+") {
+            for ext in exts.split('+').filter(|x| !x.is_empty()) {
+                into.push("--libext".to_string());
+                into.push(ext.to_string());
+            }
+        } else if arg.starts_with('+') {
+            sess.emit(DiagBuilder2::warning(format!(
+                "ignoring unsupported option `{}`",
+                arg
+            )));
+        } else if arg == "-I" || arg == "-y" || arg == "-v" {
+            into.push(arg);
+            into.extend(args.next().map(|x| resolve(&x)));
+        } else if let Some(dir) = arg.strip_prefix("-I") {
+            into.push(format!("-I{}", resolve(dir)));
+        } else if VALUE_OPTIONS.contains(&arg.as_str()) {
+            into.push(arg);
+            into.extend(args.next());
+        } else if arg.starts_with('-') {
+            into.push(arg);
+        } else {
+            into.push(resolve(&arg));
+        }
+    }
+    Ok(())
+}
+
+/// Parse an input file.
+fn parse_input<'a>(
+    language: Language,
+    source: source::Source,
+    include_paths: &[&Path],
+    defines: &[(&str, Option<&str>)],
+    arena: &'a svlog::ast::Arena<'a>,
+) -> Result<score::Ast<'a>, ()> {
+    match language {
+        Language::SystemVerilog | Language::Verilog => {
+            let preproc = svlog::preproc::Preprocessor::new(source, include_paths, defines);
+            let lexer = svlog::lexer::Lexer::new(preproc);
+            svlog::parser::parse(lexer, arena).map(score::Ast::Svlog)
+        }
+        Language::Vhdl => vhdl::syntax::parse(source).map(score::Ast::Vhdl),
+    }
 }
 
 fn score(sess: &Session, matches: &ArgMatches) {
@@ -216,10 +935,12 @@ fn score(sess: &Session, matches: &ArgMatches) {
     // Establish into which library the entities will be compiled. Later on this
     // should be made configurable per entity.
     let lib = get_name_table().intern(matches.value_of("lib").unwrap_or("work"), true);
+    sess.units.set_work(lib);
 
-    // Parse the input files.
+    // Determine the language of the input files and add them to the source
+    // manager.
     let mut failed = false;
-    let mut asts = Vec::new();
+    let mut inputs = vec![];
     for filename in matches.values_of("INPUT").unwrap() {
         if filename.is_empty() {
             continue;
@@ -261,50 +982,143 @@ fn score(sess: &Session, matches: &ArgMatches) {
                 continue;
             }
         };
+        inputs.push((language, source));
+    }
 
-        // Parse the file.
-        match language {
-            Language::SystemVerilog | Language::Verilog => {
-                let preproc = svlog::preproc::Preprocessor::new(source, &include_paths, &defines);
-                if matches.is_present("preproc") {
-                    for token in preproc {
-                        print!(
-                            "{}",
-                            match token {
-                                Ok((_token, span)) => span.extract(),
-                                Err(diag) => {
-                                    sess.emit(diag);
-                                    failed = true;
-                                    continue;
-                                }
-                            }
-                        );
+    // Write the preprocessed input files to stdout if requested, or only check
+    // them for errors.
+    if matches.is_present("preproc") || matches.is_present("preprocess-only") {
+        let print = matches.is_present("preproc");
+        for &(language, source) in &inputs {
+            // VHDL has no preprocessor, but is still checked for syntax errors.
+            if language == Language::Vhdl {
+                if vhdl::syntax::parse(source).is_err() {
+                    failed = true;
+                }
+                continue;
+            }
+            let preproc = svlog::preproc::Preprocessor::new(source, &include_paths, &defines);
+            for token in preproc {
+                match token {
+                    Ok((_token, span)) if print => print!("{}", span.extract()),
+                    Ok(_) => (),
+                    Err(diag) => {
+                        sess.emit(diag);
+                        failed = true;
                     }
-                    continue;
                 }
+            }
+        }
+        if failed || sess.failed() {
+            exit(1);
+        }
+        return;
+    }
 
-                let lexer = svlog::lexer::Lexer::new(preproc);
-                match svlog::parser::parse(lexer, &svlog_arenas.ast) {
-                    Ok(x) => asts.push(score::Ast::Svlog(x)),
-                    Err(()) => failed = true,
-                }
+    // Preprocess the input files separately if statistics are requested.
+    // Preprocessing is otherwise interleaved with parsing, such that the time
+    // of the parse phase includes it. Errors are reported during parsing.
+    let mut stats = Stats::new(sess.opts.stats);
+    if stats.enabled {
+        let start = Instant::now();
+        for &(language, source) in &inputs {
+            if language != Language::Vhdl {
+                svlog::preproc::Preprocessor::new(source, &include_paths, &defines).for_each(drop);
             }
-            Language::Vhdl => match vhdl::syntax::parse(source) {
-                Ok(x) => asts.push(score::Ast::Vhdl(x)),
-                Err(()) => failed = true,
-            },
         }
+        stats.record("preprocess", start.elapsed(), None);
     }
+
+    // Parse the input files. Every file is parsed into its own arena, such that
+    // the files can be parsed on multiple threads. The diagnostics of each file
+    // are collected and reported in the order of the input files afterwards.
+    let start = Instant::now();
+    let jobs: usize = matches.value_of("jobs").unwrap().parse().unwrap();
+    let mut file_arenas: Vec<svlog::ast::Arena> =
+        inputs.iter().map(|_| Default::default()).collect();
+    let parsed: Vec<_> = if jobs == 1 {
+        inputs
+            .iter()
+            .zip(file_arenas.iter_mut())
+            .map(|(&(language, source), arena)| {
+                let arena: &_ = arena;
+                capture_diags(|| parse_input(language, source, &include_paths, &defines, arena))
+            })
+            .collect()
+    } else {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            inputs
+                .par_iter()
+                .zip(file_arenas.par_iter_mut())
+                .map(|(&(language, source), arena)| {
+                    let arena: &_ = arena;
+                    capture_diags(|| parse_input(language, source, &include_paths, &defines, arena))
+                })
+                .collect()
+        })
+    };
+    let mut asts = Vec::new();
+    for (&(language, _), (result, diags)) in inputs.iter().zip(parsed) {
+        for diag in diags {
+            // The parser separates its diagnostics with an empty line.
+            if language != Language::Vhdl && diag_format() == DiagFormat::Human {
+                eprintln!();
+            }
+            print_diag(&diag);
+        }
+        match result {
+            Ok(x) => asts.push(x),
+            Err(()) => failed = true,
+        }
+    }
+    stats.record("parse", start.elapsed(), None);
     if failed || sess.failed() {
-        std::process::exit(1);
+        stats.report();
+        exit(1);
     }
-    if matches.is_present("preproc") {
-        return;
+
+    // Reformat the SystemVerilog input files if requested.
+    if matches.is_present("fmt") {
+        let opts = svlog::reformat::FormatOptions {
+            indent: matches.value_of("fmt-indent").unwrap().parse().unwrap(),
+            align: !matches.is_present("fmt-no-align"),
+        };
+        for (&(_, source), ast) in inputs.iter().zip(asts.iter()) {
+            let file = match ast {
+                score::Ast::Svlog(file) => file,
+                score::Ast::Vhdl(_) => continue,
+            };
+            let text = svlog::reformat::format(source, file, &opts);
+            if !matches.is_present("fmt-in-place") {
+                print!("{}", text);
+            } else if let Err(e) = std::fs::write(&*source.get_path(), text) {
+                sess.emit(DiagBuilder2::error(format!(
+                    "cannot write `{}`: {}",
+                    source.get_path(),
+                    e
+                )));
+                failed = true;
+            }
+        }
+        exit(failed as i32);
     }
 
     // Dump the AST if so requested.
     if matches.is_present("dump-ast") {
-        println!("{:#99?}", asts);
+        match matches.value_of("dump-ast") {
+            Some("json") => {
+                use std::io::Write;
+                let stdout = std::io::stdout();
+                let mut stdout = stdout.lock();
+                serde_json::to_writer_pretty(&mut stdout, &asts).unwrap();
+                writeln!(stdout).unwrap();
+            }
+            _ => println!("{:#99?}", asts),
+        }
     }
 
     if matches.is_present("emit_pkgs") {
@@ -321,10 +1135,13 @@ fn score(sess: &Session, matches: &ArgMatches) {
 
     // Stop processing if requested.
     if matches.is_present("check-syntax") {
-        std::process::exit(0);
+        stats.report();
+        exit(0);
     }
 
-    // Create the scoreboard and add the initial map of libraries.
+    // Create the scoreboard and add the initial map of libraries. The VHDL
+    // stand-ins for SystemVerilog modules live as long as the parsed input.
+    let foreign_asts = typed_arena::Arena::new();
     let arenas = score::Arenas::new();
     let sb = ScoreBoard::new(&arenas);
     let vhdl_sb = vhdl::score::ScoreBoard::new(&arenas.vhdl);
@@ -340,8 +1157,19 @@ fn score(sess: &Session, matches: &ArgMatches) {
             vhdl_phases: &vhdl_phases,
             svlog: &svlog_sb,
         };
+        let start = Instant::now();
         let lib_id = ctx.add_library(lib, &asts);
 
+        // Make the SystemVerilog modules instantiated from VHDL available,
+        // before the VHDL entities instantiated from SystemVerilog, which
+        // analyzes the library.
+        if ctx.add_foreign_modules(lib_id, &foreign_asts).is_err() {
+            failed = true;
+        }
+        if ctx.add_foreign_units(lib_id).is_err() {
+            failed = true;
+        }
+
         // Emit the function argument canonicalization details if requested.
         for root in ctx.svlog.roots() {
             if ctx.sess.has_verbosity(Verbosity::FUNC_ARGS) {
@@ -360,6 +1188,148 @@ fn score(sess: &Session, matches: &ArgMatches) {
             }
         }
 
+        let setup_time = start.elapsed();
+        let setup_queries = ctx.svlog.query_stats();
+
+        // Stop after lowering to HIR if requested.
+        if matches.is_present("lower-only") {
+            lower_svlog(&ctx);
+            stats.record_queries(ctx.svlog, start, setup_time, &setup_queries);
+            stats.report();
+            exit(if sess.failed() { 1 } else { 0 });
+        }
+
+        // Write the cross-reference index and stop if requested.
+        if let Some(path) = matches.value_of("xref") {
+            let xref = svlog::xref::Xref::new(ctx.svlog);
+            let result = match path {
+                "-" => xref.write_ctags(&mut std::io::stdout().lock()),
+                _ => std::fs::File::create(path).and_then(|mut file| xref.write_ctags(&mut file)),
+            };
+            if let Err(e) = result {
+                sess.emit(DiagBuilder2::error(format!(
+                    "cannot write cross-reference index `{}`: {}",
+                    path, e
+                )));
+            }
+            stats.report();
+            exit(if sess.failed() { 1 } else { 0 });
+        }
+
+        // Print the HIR nodes at the requested locations and stop if requested.
+        if let Some(values) = matches.values_of("node-at") {
+            for value in values {
+                let parsed = value.rfind(':').and_then(|index| {
+                    let offset = value[index + 1..].parse::<usize>().ok()?;
+                    let path = std::fs::canonicalize(&value[..index]).ok()?;
+                    let input = inputs.iter().map(|&(_, input)| input).find(|input| {
+                        std::fs::canonicalize(&*input.get_path()).ok().as_ref() == Some(&path)
+                    })?;
+                    Some(source::Location::new(input, offset))
+                });
+                let loc = match parsed {
+                    Some(loc) => loc,
+                    None => {
+                        sess.emit(DiagBuilder2::error(format!(
+                            "expected `<input file>:<offset>`, found `{}`",
+                            value
+                        )));
+                        continue;
+                    }
+                };
+                match svlog::hir::node_at(ctx.svlog, loc) {
+                    Some(node) => {
+                        let span = node.span();
+                        println!(
+                            "{}: {} `{}`",
+                            loc.offset,
+                            node.desc(),
+                            span.source.extract(span.begin, span.end)
+                        );
+                    }
+                    None => println!("{}: no node", loc.offset),
+                }
+            }
+            stats.report();
+            exit(if sess.failed() { 1 } else { 0 });
+        }
+
+        // Write the semantic tokens and stop if requested.
+        if matches.is_present("semantic-tokens") {
+            let sources = inputs
+                .iter()
+                .filter(|&&(language, _)| language != Language::Vhdl)
+                .map(|&(_, source)| source);
+            let tokens = svlog::semantic_tokens::SemanticTokens::new(ctx.svlog, sources);
+            let result = open_output(matches, &ctx).and_then(|mut output| {
+                tokens.write_json(&mut output).map_err(|e| {
+                    sess.emit(DiagBuilder2::error(format!(
+                        "cannot write semantic tokens: {}",
+                        e
+                    )))
+                })
+            });
+            if result.is_err() {
+                failed = true;
+            }
+            stats.report();
+            exit(if failed || sess.failed() { 1 } else { 0 });
+        }
+
+        // Write the documentation and stop if requested.
+        if matches.is_present("doc") {
+            let result = svlog::docs::Docs::new(ctx.svlog).and_then(|docs| {
+                let mut output = open_output(matches, &ctx)?;
+                match matches.value_of("doc-format").unwrap() {
+                    "html" => docs.write_html(&mut output),
+                    _ => docs.write_markdown(&mut output),
+                }
+                .map_err(|e| {
+                    sess.emit(DiagBuilder2::error(format!(
+                        "cannot write documentation: {}",
+                        e
+                    )))
+                })
+            });
+            if result.is_err() {
+                failed = true;
+            }
+            stats.report();
+            exit(if failed || sess.failed() { 1 } else { 0 });
+        }
+
+        // Write the call graph and stop if requested.
+        if let Some(fmt) = matches.value_of("call-graph") {
+            let graph = svlog::call_graph::CallGraph::new(ctx.svlog);
+            let result = open_output(matches, &ctx).and_then(|mut output| {
+                match fmt {
+                    "json" => graph.write_json(&mut output),
+                    _ => graph.write_dot(&mut output),
+                }
+                .map_err(|e| {
+                    sess.emit(DiagBuilder2::error(format!(
+                        "cannot write call graph: {}",
+                        e
+                    )))
+                })
+            });
+            if result.is_err() {
+                failed = true;
+            }
+            stats.report();
+            exit(if failed || sess.failed() { 1 } else { 0 });
+        }
+
+        // Write the summary of the module interfaces and stop if requested.
+        if matches.is_present("summary") {
+            let result = summarize_svlog(matches, &ctx);
+            if result.is_err() {
+                failed = true;
+            }
+            stats.report();
+            exit(if failed || sess.failed() { 1 } else { 0 });
+        }
+
         // Elaborate what has been requested by the user.
         if let Some(names) = matches.values_of("elaborate") {
             debug!("lib_id = {:?}", lib_id);
@@ -374,9 +1344,57 @@ fn score(sess: &Session, matches: &ArgMatches) {
                 failed = true;
             }
         }
+
+        // Elaborate the selected top-level modules together.
+        if let Some(patterns) = matches.values_of("top") {
+            let result =
+                select_tops(&ctx, patterns).and_then(|tops| elaborate_svlog(matches, &ctx, &tops));
+            if result.is_err() || sess.failed() {
+                failed = true;
+            }
+        }
+
+        // Otherwise pick the top-level module from the instantiation graph, if
+        // any output has been requested. Without one, the input is only
+        // checked.
+        let wants_output = [
+            "elaborate-only",
+            "output",
+            "output-format",
+            "emit",
+            "elab-report",
+            "fsm-report",
+            "boundary-report",
+            "lint",
+        ]
+        .iter()
+        .any(|&x| matches.is_present(x));
+        if wants_output && !matches.is_present("elaborate") && !matches.is_present("top") {
+            let result = detect_top(&ctx).and_then(|top| match top {
+                Some(top) => elaborate_svlog(matches, &ctx, &[top]),
+                None => Ok(()),
+            });
+            if result.is_err() || sess.failed() {
+                failed = true;
+            }
+        }
+
+        // Emit the report of the conversions across language boundaries if
+        // requested.
+        if let Some(fmt) = matches.value_of("boundary-report") {
+            let report = foreign::BoundaryReport {
+                crossings: ctx.sess.units.crossings(),
+            };
+            match fmt {
+                "json" => report.write_json(&mut std::io::stdout().lock()).unwrap(),
+                _ => report.emit_notes(ctx.sess),
+            }
+        }
+        stats.record_queries(ctx.svlog, start, setup_time, &setup_queries);
     }
+    stats.report();
     if failed || sess.failed() {
-        std::process::exit(1);
+        exit(1);
     }
 
     // Extract the populated LLHD modules from the scoreboards and link them
@@ -388,7 +1406,7 @@ fn score(sess: &Session, matches: &ArgMatches) {
     // llhd::assembly::write_module(&mut std::io::stdout().lock(), &vhdl_module);
 
     if sess.failed() {
-        std::process::exit(1);
+        exit(1);
     }
 }
 
@@ -406,9 +1424,11 @@ fn elaborate_name(
         input_name, lib, name, arch
     );
 
-    // Resolve the library name if one was provided.
+    // Resolve the library name if one was provided. The `work` library refers
+    // to the library the design is compiled into.
     let lib = {
         if let Some(lib) = lib {
+            let lib = ctx.sess.units.resolve_lib(lib);
             let rid = ctx.sb.root;
             let defs = ctx.defs(score::ScopeRef::Root(rid))?;
             match defs.get(&lib) {
@@ -437,6 +1457,7 @@ fn elaborate_name(
     #[derive(Debug)]
     enum Elaborate {
         VhdlEntity(vhdl::score::EntityRef, vhdl::score::ArchRef),
+        VhdlCfg(vhdl::score::CfgRef),
         VhdlPkg(vhdl::score::PkgDeclRef),
         Svlog(NodeId), // TODO: handle svlog case
     }
@@ -474,6 +1495,7 @@ fn elaborate_name(
             };
             Elaborate::VhdlEntity(entity, arch_ref)
         }
+        Some(&score::Def::Vhdl(vhdl::score::Def::Cfg(cfg))) => Elaborate::VhdlCfg(cfg),
         Some(&score::Def::Vhdl(vhdl::score::Def::Pkg(p))) => Elaborate::VhdlPkg(p),
         Some(&score::Def::Svlog(e)) => Elaborate::Svlog(e),
         _ => {
@@ -498,6 +1520,10 @@ fn elaborate_name(
             let def = ctx.vhdl().llunit(arch)?;
             eprintln!("Architecture declared as {:?}", def);
         }
+        Elaborate::VhdlCfg(cfg) => {
+            let def = ctx.vhdl().cfg_llunit(cfg)?;
+            eprintln!("Configuration declared as {:?}", def);
+        }
         Elaborate::VhdlPkg(pkg) => {
             use moore::vhdl::typeck::{Typeck, TypeckContext};
             let sbc = ctx.vhdl();
@@ -506,79 +1532,348 @@ fn elaborate_name(
             // use moore::vhdl::codegen::Codegen;
             // ctx.vhdl().codegen(pkg, &mut ())?;
         }
-        Elaborate::Svlog(m) => {
-            // Emit the detailed type analysis if requested.
-            if ctx.sess.has_verbosity(Verbosity::TYPES) {
-                use svlog::Context;
-                TypeVerbosityVisitor(ctx.svlog, ctx.svlog.default_param_env())
-                    .visit_node_with_id(m, false);
-            }
-
-            // Emit the instantiation details if requested.
-            if ctx.sess.has_verbosity(Verbosity::INSTS) {
-                svlog::InstVerbosityVisitor::new(ctx.svlog).visit_node_with_id(m, false);
-            }
-
-            // Create an MLIR context and load the dialects we need.
-            let mlir_cx = mlir::OwnedContext::new();
-            mlir_cx.load_dialect(circt::func::dialect());
-            mlir_cx.load_dialect(circt::cf::dialect());
-            mlir_cx.load_dialect(circt::hw::dialect());
-            mlir_cx.load_dialect(circt::comb::dialect());
-            mlir_cx.load_dialect(circt::llhd::dialect());
-            mlir_cx.load_dialect(circt::moore::dialect());
-            mlir_cx.load_dialect(circt::seq::dialect());
-            mlir_cx.set_allow_unregistered_dialects(true);
-
-            // Attach a custom diagnostic handler to the context such that we
-            // can print MLIR diagnostics through Moore's own diagnostics
-            // engine.
-            unsafe {
-                mlirContextAttachDiagnosticHandler(
-                    mlir_cx.raw(),
-                    Some(moore_mlir_diagnostic_handler),
-                    // SAFETY: The session reference in the context must outlive
-                    // the MLIR context, which is the case since that context
-                    // is dropped at the end of this function.
-                    ctx.sess as *const _ as *mut _,
-                    None,
-                );
+        Elaborate::Svlog(m) => elaborate_svlog(matches, ctx, &[m])?,
+    }
+    Ok(())
+}
+
+/// Determine the SystemVerilog modules selected by a list of `--top` names or
+/// glob patterns, in the order they are declared in. Patterns may be prefixed
+/// with the library to search, as in `mylib.top*`.
+fn select_tops<'a>(
+    ctx: &ScoreContext,
+    patterns: impl Iterator<Item = &'a str>,
+) -> Result<Vec<NodeId>, ()> {
+    let mut tops = vec![];
+    let mut failed = false;
+    for pattern in patterns {
+        let (lib, pattern) = split_lib_prefix(pattern);
+        let lib = match lib {
+            Some(lib) => ctx
+                .sess
+                .units
+                .resolve_lib(name::get_name_table().intern(lib, true)),
+            None => ctx.sess.units.work(),
+        };
+        let modules: Vec<(Name, NodeId)> = ctx
+            .sess
+            .units
+            .units_in(lib)
+            .into_iter()
+            .filter(|unit| unit.lang == ForeignLang::Svlog && unit.kind == DesignUnitKind::Module)
+            .map(|unit| (unit.name, unit.id))
+            .collect();
+        let mut matched = false;
+        for &(name, id) in &modules {
+            if glob_matches(pattern, &name.as_str()) {
+                matched = true;
+                if !tops.contains(&id) {
+                    tops.push(id);
+                }
+            }
+        }
+        if !matched {
+            let mut d = DiagBuilder2::error(format!("no module matches `{}`", pattern))
+                .add_note("The following modules are defined:");
+            let mut names: Vec<_> = modules.iter().map(|&(name, _)| name).collect();
+            names.sort();
+            for name in names {
+                d = d.add_note(format!("- {}", name));
             }
+            ctx.sess.emit(d);
+            failed = true;
+        }
+    }
+    if failed {
+        return Err(());
+    }
+    tops.sort();
+    Ok(tops)
+}
 
-            // Create the top-level MLIR module.
-            let mlir_module = circt::ModuleOp::new(*mlir_cx);
-
-            let mut cg = svlog::CodeGenerator::new(ctx.svlog, mlir_module);
-            for root in ctx.svlog.roots() {
-                cg.emit_globals(root)?;
-            }
-            cg.emit_module(m)?;
-            let module = cg.finalize();
-            // let mut module = cg.finalize();
-            // let pass_ctx = PassContext;
-            // if ctx.sess.opts.opt_level > 0 {
-            //     llhd::pass::ConstFolding::run_on_module(&pass_ctx, &mut module);
-            //     llhd::pass::VarToPhiPromotion::run_on_module(&pass_ctx, &mut module); // broken in llhd 0.13
-            //     llhd::pass::DeadCodeElim::run_on_module(&pass_ctx, &mut module);
-            //     llhd::pass::GlobalCommonSubexprElim::run_on_module(&pass_ctx, &mut module);
-            //     llhd::pass::InstSimplification::run_on_module(&pass_ctx, &mut module);
-            //     llhd::pass::DeadCodeElim::run_on_module(&pass_ctx, &mut module);
-            // }
-
-            // Verify the module.
-            if !mlir_module.verify() {
-                ctx.sess.emit(DiagBuilder2::bug(
-                    "verification failed for emitted MLIR module",
-                ));
-                // mlir_module.dump();
+/// Summarize the ports and parameters of the modules selected with `--top`, or
+/// of all SystemVerilog modules, with the parameters overridden by `--param`.
+fn summarize_svlog(matches: &ArgMatches, ctx: &ScoreContext) -> Result<(), ()> {
+    let ids = match matches.values_of("top") {
+        Some(patterns) => select_tops(ctx, patterns)?,
+        None => {
+            let mut ids: Vec<NodeId> = ctx.svlog.modules().map(|(_, id)| id).collect();
+            ids.sort();
+            ids
+        }
+    };
+    let mut overrides = vec![];
+    for value in matches.values_of("param").into_iter().flatten() {
+        let parsed = value.find('=').and_then(|index| {
+            let int = value[index + 1..].trim().parse::<num::BigInt>().ok()?;
+            Some((value[..index].trim(), int))
+        });
+        match parsed {
+            Some((name, int)) => {
+                overrides.push((name::get_name_table().intern(name, true), int));
+            }
+            None => {
+                ctx.sess.emit(DiagBuilder2::error(format!(
+                    "expected `<name>=<integer>`, found `{}`",
+                    value
+                )));
                 return Err(());
             }
+        }
+    }
+    let summary = svlog::summary::Summary::new(ctx.svlog, ids, &overrides)?;
+    let mut output = open_output(matches, ctx)?;
+    summary.write_json(&mut output).map_err(|e| {
+        ctx.sess.emit(DiagBuilder2::error(format!(
+            "cannot write module summary: {}",
+            e
+        )))
+    })
+}
+
+/// Determine the top-level module of the SystemVerilog design, as the only
+/// module that is never instantiated.
+///
+/// Returns `None` if there are no modules.
+fn detect_top(ctx: &ScoreContext) -> Result<Option<NodeId>, ()> {
+    let mut modules: Vec<(Name, NodeId)> = ctx.svlog.modules().collect();
+    if modules.is_empty() {
+        return Ok(None);
+    }
+    modules.sort_by_key(|&(_, id)| id);
+    let mut targets = InstTargetCollector(HashSet::new());
+    for root in ctx.svlog.roots() {
+        root.accept(&mut targets);
+    }
+    let candidates: Vec<_> = modules
+        .into_iter()
+        .filter(|(name, _)| !targets.0.contains(name))
+        .collect();
+    match candidates.as_slice() {
+        [(name, id)] => {
+            ctx.sess.emit(
+                DiagBuilder2::note(format!("using `{}` as top-level module", name))
+                    .add_note("It is the only module that is never instantiated"),
+            );
+            Ok(Some(*id))
+        }
+        [] => {
+            ctx.sess.emit(
+                DiagBuilder2::error("cannot determine the top-level module")
+                    .add_note("Every module is instantiated by another module")
+                    .add_note("Select the top-level module with `--top`"),
+            );
+            Err(())
+        }
+        _ => {
+            let mut d = DiagBuilder2::error("cannot determine the top-level module")
+                .add_note("The following modules are never instantiated:");
+            for (name, _) in &candidates {
+                d = d.add_note(format!("- {}", name));
+            }
+            ctx.sess
+                .emit(d.add_note("Select the top-level modules with `--top`"));
+            Err(())
+        }
+    }
+}
 
-            // Decide what format to use for the output.
-            emit_output(matches, ctx, &module, mlir_module)?
+/// Check whether a name matches a glob pattern, where `*` matches any sequence
+/// of characters and `?` matches a single character.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // `matches[j]` indicates whether the pattern so far matches `name[..j]`.
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for &p in &pattern {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match p {
+                '*' => matches[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matches[j - 1],
+                c => j > 0 && matches[j - 1] && name[j - 1] == c,
+            };
         }
+        matches = next;
     }
-    Ok(())
+    matches[name.len()]
+}
+
+/// Elaborate a list of SystemVerilog top-level modules and emit the design.
+///
+/// Each top-level module is elaborated with its default parameters. Modules
+/// instantiated below multiple top-level modules are emitted only once.
+fn elaborate_svlog(matches: &ArgMatches, ctx: &ScoreContext, tops: &[NodeId]) -> Result<(), ()> {
+    // Emit the detailed type analysis if requested.
+    if ctx.sess.has_verbosity(Verbosity::TYPES) {
+        use svlog::Context;
+        for &m in tops {
+            TypeVerbosityVisitor(ctx.svlog, ctx.svlog.default_param_env())
+                .visit_node_with_id(m, false);
+        }
+    }
+
+    // Emit the instantiation details if requested.
+    if ctx.sess.has_verbosity(Verbosity::INSTS) {
+        for &m in tops {
+            svlog::InstVerbosityVisitor::new(ctx.svlog).visit_node_with_id(m, false);
+        }
+    }
+
+    // Run the lint rules instead of generating code if requested.
+    if matches.is_present("lint") {
+        use svlog::Context;
+        let design =
+            svlog::design::Design::with_tops(ctx.svlog, tops, ctx.svlog.default_param_env())?;
+        return svlog::lint::run(ctx.svlog, &design);
+    }
+
+    // Dump the HIR instead of generating code if requested.
+    if matches.is_present("dump-hir") {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        use svlog::Context;
+        for &m in tops {
+            match matches.value_of("dump-hir").unwrap() {
+                "text" => svlog::hir::print::write_text(
+                    ctx.svlog,
+                    m,
+                    ctx.svlog.default_param_env(),
+                    &mut stdout,
+                ),
+                _ => svlog::hir::dump::write_json(ctx.svlog, m, &mut stdout),
+            }
+            .unwrap();
+        }
+        return Ok(());
+    }
+
+    // Emit a Graphviz graph of the design if requested.
+    if let Some(graph) = matches.value_of("emit") {
+        use svlog::Context;
+        let design =
+            svlog::design::Design::with_tops(ctx.svlog, tops, ctx.svlog.default_param_env())?;
+        let text = match graph {
+            "hierarchy.json" => {
+                let hierarchy = svlog::hierarchy::Hierarchy::new(ctx.svlog, &design)?;
+                hierarchy
+                    .write_json(&mut open_output(matches, ctx)?)
+                    .unwrap();
+                return Ok(());
+            }
+            "dot-dataflow" => svlog::dot::emit_dataflow(ctx.svlog, &design)?,
+            _ => svlog::dot::emit_hierarchy(ctx.svlog, &design)?,
+        };
+        open_output(matches, ctx)?
+            .write_all(text.as_bytes())
+            .unwrap();
+        return Ok(());
+    }
+
+    // Emit the design in the Moore dialect, as FIRRTL, as Verilog, or
+    // as a Yosys netlist if requested. These work on the elaborated
+    // design directly and do not need the LLHD code generator.
+    let fmt = output_format(matches, ctx)?;
+    if let OutputFormat::Mlir
+    | OutputFormat::Firrtl
+    | OutputFormat::Verilog
+    | OutputFormat::YosysJson = fmt
+    {
+        use svlog::Context;
+        let design =
+            svlog::design::Design::with_tops(ctx.svlog, tops, ctx.svlog.default_param_env())?;
+        let text = match fmt {
+            OutputFormat::Firrtl => svlog::firrtl::emit_design(ctx.svlog, &design)?,
+            OutputFormat::Verilog => svlog::verilog::emit_design(ctx.svlog, &design)?,
+            OutputFormat::YosysJson => svlog::yosys::emit_design(ctx.svlog, &design)?,
+            _ => svlog::moore_dialect::emit_design(ctx.svlog, &design)?,
+        };
+        open_output(matches, ctx)?
+            .write_all(text.as_bytes())
+            .unwrap();
+        return Ok(());
+    }
+
+    // Create an MLIR context and load the dialects we need.
+    let mlir_cx = mlir::OwnedContext::new();
+    mlir_cx.load_dialect(circt::func::dialect());
+    mlir_cx.load_dialect(circt::cf::dialect());
+    mlir_cx.load_dialect(circt::hw::dialect());
+    mlir_cx.load_dialect(circt::comb::dialect());
+    mlir_cx.load_dialect(circt::llhd::dialect());
+    mlir_cx.load_dialect(circt::moore::dialect());
+    mlir_cx.load_dialect(circt::seq::dialect());
+    mlir_cx.set_allow_unregistered_dialects(true);
+
+    // Attach a custom diagnostic handler to the context such that we
+    // can print MLIR diagnostics through Moore's own diagnostics
+    // engine.
+    unsafe {
+        mlirContextAttachDiagnosticHandler(
+            mlir_cx.raw(),
+            Some(moore_mlir_diagnostic_handler),
+            // SAFETY: The session reference in the context must outlive
+            // the MLIR context, which is the case since that context
+            // is dropped at the end of this function.
+            ctx.sess as *const _ as *mut _,
+            None,
+        );
+    }
+
+    // Create the top-level MLIR module.
+    let mlir_module = circt::ModuleOp::new(*mlir_cx);
+
+    let mut cg = svlog::CodeGenerator::new(ctx.svlog, mlir_module);
+    for root in ctx.svlog.roots() {
+        cg.emit_globals(root)?;
+    }
+    for &m in tops {
+        cg.emit_module(m)?;
+    }
+
+    // Stop after elaboration if requested.
+    if matches.is_present("elaborate-only") {
+        return Ok(());
+    }
+
+    // Emit the elaboration report if requested.
+    if let Some(fmt) = matches.value_of("elab-report") {
+        let report =
+            svlog::elab_report::ElabReport::new(ctx.svlog, cg.emitted_modules().iter().cloned())?;
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        match fmt {
+            "json" => report.write_json(&mut stdout),
+            _ => report.write_text(&mut stdout),
+        }
+        .unwrap();
+    }
+
+    // Emit the state machine report if requested.
+    if let Some(fmt) = matches.value_of("fsm-report") {
+        let report = svlog::fsm::FsmReport::new(ctx.svlog, cg.emitted_modules().iter().cloned())?;
+        match fmt {
+            "json" => report.write_json(&mut std::io::stdout().lock()).unwrap(),
+            _ => report.emit_notes(ctx.svlog),
+        }
+    }
+
+    // Clean up the generated LLHD if requested.
+    let mut module = cg.finalize();
+    svlog::opt::optimize_module(&mut module, ctx.sess.opts.opt_level);
+
+    // Verify the module.
+    if !mlir_module.verify() {
+        ctx.sess.emit(DiagBuilder2::bug(
+            "verification failed for emitted MLIR module",
+        ));
+        // mlir_module.dump();
+        return Err(());
+    }
+
+    // Decide what format to use for the output.
+    emit_output(matches, ctx, fmt, &module, mlir_module)
 }
 
 /// A custom handler for MLIR diagnostics, which prints them through Moore's own
@@ -671,21 +1966,25 @@ unsafe fn mlir_location_to_span(loc: MlirLocation) -> Option<Span> {
 #[derive(Debug)]
 enum OutputFormat {
     Llhd,
+    LlhdMlir,
     Mlir,
     MlirNative,
+    Firrtl,
+    Verilog,
+    YosysJson,
 }
 
-fn emit_output(
-    matches: &ArgMatches,
-    ctx: &ScoreContext,
-    module: &llhd::ir::Module,
-    mlir_module: circt::ModuleOp,
-) -> Result<(), ()> {
+/// Decide what format to use for the output.
+fn output_format(matches: &ArgMatches, ctx: &ScoreContext) -> Result<OutputFormat, ()> {
     // Check if the user has provided an explicit output format.
     let fmt = match matches.value_of("output-format") {
         Some("llhd") => Some(OutputFormat::Llhd),
+        Some("llhd-mlir") => Some(OutputFormat::LlhdMlir),
         Some("mlir") => Some(OutputFormat::Mlir),
         Some("mlir-native") => Some(OutputFormat::MlirNative),
+        Some("firrtl") => Some(OutputFormat::Firrtl),
+        Some("verilog") => Some(OutputFormat::Verilog),
+        Some("yosys-json") => Some(OutputFormat::YosysJson),
         Some(x) => {
             ctx.sess.emit(DiagBuilder2::fatal(format!(
                 "unknown output format: `{}`",
@@ -705,6 +2004,9 @@ fn emit_output(
         {
             Some("llhd") => Some(OutputFormat::Llhd),
             Some("mlir") => Some(OutputFormat::Mlir),
+            Some("fir") => Some(OutputFormat::Firrtl),
+            Some("v") => Some(OutputFormat::Verilog),
+            Some("json") => Some(OutputFormat::YosysJson),
             _ => None,
         }
     });
@@ -712,11 +2014,13 @@ fn emit_output(
     // Otherwise fall back to the LLHD default output format.
     let fmt = fmt.unwrap_or(OutputFormat::Llhd);
     debug!("Using {:?} output format", fmt);
+    Ok(fmt)
+}
 
-    // Open the output.
-    let stdout = std::io::stdout();
-    let output: Box<dyn std::io::Write> = match matches.value_of("output") {
-        Some("-") | None => Box::new(stdout.lock()),
+/// Open the output file, or stdout if none was specified.
+fn open_output(matches: &ArgMatches, ctx: &ScoreContext) -> Result<Box<dyn std::io::Write>, ()> {
+    Ok(match matches.value_of("output") {
+        Some("-") | None => Box::new(std::io::stdout()),
         Some(x) => Box::new(std::fs::File::create(x).map_err(|e| {
             ctx.sess.emit(
                 DiagBuilder2::fatal(format!("unable to create file: `{}`", x))
@@ -724,12 +2028,28 @@ fn emit_output(
             );
             ()
         })?),
-    };
+    })
+}
+
+fn emit_output(
+    matches: &ArgMatches,
+    ctx: &ScoreContext,
+    fmt: OutputFormat,
+    module: &llhd::ir::Module,
+    mlir_module: circt::ModuleOp,
+) -> Result<(), ()> {
+    let output = open_output(matches, ctx)?;
 
     // Emit the appropriate output.
     match fmt {
         OutputFormat::Llhd => llhd::assembly::write_module(output, &module),
-        OutputFormat::Mlir => llhd::mlir::write_module(output, &module),
+        OutputFormat::LlhdMlir => llhd::mlir::write_module(output, &module),
+        OutputFormat::Mlir
+        | OutputFormat::Firrtl
+        | OutputFormat::Verilog
+        | OutputFormat::YosysJson => {
+            unreachable!("handled before code generation")
+        }
         OutputFormat::MlirNative => mlir_module.print(output, matches.is_present("debug-info")),
     };
     Ok(())
@@ -801,6 +2121,153 @@ fn parse_elaborate_name<S: AsRef<str>>(name: S) -> Result<(Option<Name>, Name, O
 }
 
 /// A visitor that emits detailed type information to stdout.
+/// The time, query count, and peak memory of the compilation phases, reported
+/// with `--stats`.
+struct Stats {
+    /// Whether statistics are recorded.
+    enabled: bool,
+    /// The time at which the compilation started.
+    start: Instant,
+    /// The name, time, query count, and peak memory at the end of each phase.
+    phases: Vec<(&'static str, Duration, Option<usize>, Option<u64>)>,
+}
+
+impl Stats {
+    fn new(enabled: bool) -> Self {
+        Stats {
+            enabled,
+            start: Instant::now(),
+            phases: vec![],
+        }
+    }
+
+    /// Record a compilation phase.
+    fn record(&mut self, name: &'static str, time: Duration, queries: Option<usize>) {
+        if self.enabled {
+            self.phases.push((name, time, queries, peak_memory()));
+        }
+    }
+
+    /// Record the lowering, type checking, and code generation phases.
+    ///
+    /// These phases are interleaved, since the queries are executed on demand.
+    /// The time since `start` is therefore broken down by the phases of the
+    /// queries executed in addition to `setup_queries`, with the time outside
+    /// of queries attributed to code generation. The initial `setup_time`,
+    /// spent preparing the AST for the queries, is attributed to lowering.
+    fn record_queries(
+        &mut self,
+        gcx: &svlog::GlobalContext,
+        start: Instant,
+        setup_time: Duration,
+        setup_queries: &[(&'static str, svlog::stats::QueryStats)],
+    ) {
+        use svlog::stats::Phase;
+        if !self.enabled {
+            return;
+        }
+        let setup_queries: HashMap<_, _> = setup_queries.iter().cloned().collect();
+        let mut counts: HashMap<Phase, usize> = HashMap::new();
+        let mut times: HashMap<Phase, Duration> = HashMap::new();
+        for (name, stats) in gcx.query_stats() {
+            let phase = Phase::of_query(name);
+            *counts.entry(phase).or_default() += stats.count;
+            let setup_time = setup_queries.get(name).map(|x| x.time).unwrap_or_default();
+            *times.entry(phase).or_default() +=
+                stats.time.checked_sub(setup_time).unwrap_or_default();
+        }
+        let elapsed = start.elapsed().checked_sub(setup_time).unwrap_or_default();
+        let time = |phase| times.get(&phase).cloned().unwrap_or_default();
+        let codegen = elapsed
+            .checked_sub(time(Phase::Lower) + time(Phase::Typecheck))
+            .unwrap_or_default();
+        for &(name, phase, time) in &[
+            ("lower", Phase::Lower, setup_time + time(Phase::Lower)),
+            ("typecheck", Phase::Typecheck, time(Phase::Typecheck)),
+            ("codegen", Phase::Codegen, codegen),
+        ] {
+            let count = counts.get(&phase).cloned().unwrap_or_default();
+            self.record(name, time, Some(count));
+        }
+    }
+
+    /// Print the recorded phases to stderr.
+    fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+        let memory = |x: Option<u64>| match x {
+            Some(x) => format!("{:.1} MiB", x as f64 / (1 << 20) as f64),
+            None => "-".to_string(),
+        };
+        eprintln!(
+            "{:<12} {:>12} {:>10} {:>14}",
+            "phase", "time", "queries", "peak memory"
+        );
+        let mut total_queries = 0;
+        for &(name, time, queries, peak) in &self.phases {
+            total_queries += queries.unwrap_or(0);
+            eprintln!(
+                "{:<12} {:>9.3} ms {:>10} {:>14}",
+                name,
+                time.as_secs_f64() * 1e3,
+                queries
+                    .map(|x| x.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                memory(peak)
+            );
+        }
+        eprintln!(
+            "{:<12} {:>9.3} ms {:>10} {:>14}",
+            "total",
+            self.start.elapsed().as_secs_f64() * 1e3,
+            total_queries,
+            memory(peak_memory())
+        );
+    }
+}
+
+/// Determine the peak memory usage of the process in bytes.
+///
+/// Only supported on Linux.
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kib: u64 = line["VmHWM:".len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+/// Lower all SystemVerilog modules, interfaces, and packages to HIR.
+fn lower_svlog(ctx: &ScoreContext) {
+    let mut ids: Vec<NodeId> = ctx
+        .svlog
+        .modules()
+        .chain(ctx.svlog.interfaces())
+        .chain(ctx.svlog.packages())
+        .map(|(_, id)| id)
+        .collect();
+    ids.sort();
+    for id in ids {
+        LowerVisitor(ctx.svlog).visit_node_with_id(id, false);
+    }
+}
+
+/// A visitor that lowers every node it encounters to HIR.
+struct LowerVisitor<'a, 'gcx>(&'a svlog::GlobalContext<'gcx>);
+
+impl<'a, 'gcx> svlog::hir::Visitor<'gcx> for LowerVisitor<'a, 'gcx> {
+    type Context = svlog::GlobalContext<'gcx>;
+
+    fn context(&self) -> &Self::Context {
+        self.0
+    }
+}
+
 pub struct TypeVerbosityVisitor<'a, 'gcx>(&'a svlog::GlobalContext<'gcx>, svlog::ParamEnv);
 
 impl<'a, 'gcx> svlog::hir::Visitor<'gcx> for TypeVerbosityVisitor<'a, 'gcx> {
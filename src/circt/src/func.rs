@@ -91,6 +91,23 @@ impl<'a> FunctionBuilder<'a> {
             }
         })
     }
+
+    /// Build an external function declaration without a body.
+    pub fn declare(&mut self, builder: &mut Builder) -> FuncOp {
+        builder.build_with(|builder, state| {
+            let arg_types = self.args.iter().map(|(_, ty)| *ty);
+            let result_types = self.results.iter().map(|(_, ty)| *ty);
+            state.add_attribute("sym_name", get_string_attr(builder.cx, self.name));
+            state.add_attribute("sym_visibility", get_string_attr(builder.cx, "private"));
+            state.add_attribute(
+                "function_type",
+                get_type_attr(get_function_type(builder.cx, arg_types, result_types)),
+            );
+            unsafe {
+                state.add_region(mlirRegionCreate());
+            }
+        })
+    }
 }
 
 impl CallOp {
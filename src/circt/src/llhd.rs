@@ -338,6 +338,22 @@ impl DriveOp {
             state.add_operand(delay);
         })
     }
+
+    /// Drive a value onto a signal if an enable condition holds.
+    pub fn with_enable(
+        builder: &mut Builder,
+        sig: Value,
+        value: Value,
+        delay: Value,
+        enable: Value,
+    ) -> Self {
+        builder.build_with(|_, state| {
+            state.add_operand(sig);
+            state.add_operand(value);
+            state.add_operand(delay);
+            state.add_operand(enable);
+        })
+    }
 }
 
 impl LoadOp {
@@ -0,0 +1,214 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A registry of the design units declared in all languages.
+//!
+//! Both front ends register the design units they declare, such as VHDL
+//! entities and SystemVerilog modules, under the logical library they are
+//! compiled into. The elaborators then look up units by name regardless of the
+//! language they are declared in. Names may carry a library prefix, as in
+//! `mylib.top`, where the library `work` denotes the working library.
+
+use crate::foreign::{Crossing, ForeignLang, ForeignUnit};
+use crate::name::{get_name_table, Name};
+use crate::source::Span;
+use crate::NodeId;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+/// The registry of design units.
+#[derive(Debug, Default)]
+pub struct DesignUnits {
+    /// The working library, if one has been selected.
+    work: Cell<Option<Name>>,
+    /// The registered units, in the order they were registered.
+    units: RefCell<Vec<DesignUnit>>,
+    /// The units instantiated across a language boundary.
+    crossings: RefCell<Vec<Crossing>>,
+}
+
+/// A design unit registered by one of the front ends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DesignUnit {
+    /// The library the unit is compiled into.
+    pub lib: Name,
+    /// The name of the unit.
+    pub name: Name,
+    /// The language the unit is declared in.
+    pub lang: ForeignLang,
+    /// The kind of unit.
+    pub kind: DesignUnitKind,
+    /// The node ID the declaring front end assigned to the unit.
+    pub id: NodeId,
+    /// The location of the unit's name, which also identifies its file.
+    pub span: Span,
+    /// The interface of the unit as seen from other languages, once it has
+    /// been described.
+    pub interface: Option<ForeignUnit>,
+}
+
+/// The kind of a design unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DesignUnitKind {
+    Module,
+    Interface,
+    Package,
+    Entity,
+    Config,
+}
+
+impl fmt::Display for DesignUnitKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DesignUnitKind::Module => write!(f, "module"),
+            DesignUnitKind::Interface => write!(f, "interface"),
+            DesignUnitKind::Package => write!(f, "package"),
+            DesignUnitKind::Entity => write!(f, "entity"),
+            DesignUnitKind::Config => write!(f, "configuration"),
+        }
+    }
+}
+
+impl DesignUnits {
+    /// Select the library into which units are compiled, and which the `work`
+    /// prefix refers to.
+    pub fn set_work(&self, lib: Name) {
+        self.work.set(Some(lib));
+    }
+
+    /// Determine the working library. Defaults to `work`.
+    pub fn work(&self) -> Name {
+        self.work
+            .get()
+            .unwrap_or_else(|| get_name_table().intern("work", false))
+    }
+
+    /// Map a library name to the library it denotes, such that `work` refers
+    /// to the working library.
+    pub fn resolve_lib(&self, lib: Name) -> Name {
+        if lib == get_name_table().intern("work", false) {
+            self.work()
+        } else {
+            lib
+        }
+    }
+
+    /// Register a design unit.
+    pub fn add(&self, unit: DesignUnit) {
+        self.units.borrow_mut().push(unit);
+    }
+
+    /// Record the interface of a unit as seen from other languages.
+    pub fn set_interface(&self, id: NodeId, interface: ForeignUnit) {
+        for unit in self.units.borrow_mut().iter_mut() {
+            if unit.id == id {
+                unit.interface = Some(interface.clone());
+            }
+        }
+    }
+
+    /// Find the units of a given name in a library, or in the working library
+    /// if none is given.
+    pub fn find(&self, lib: Option<Name>, name: Name) -> Vec<DesignUnit> {
+        let lib = lib.map(|lib| self.resolve_lib(lib)).unwrap_or(self.work());
+        self.units
+            .borrow()
+            .iter()
+            .filter(|unit| unit.lib == lib && unit.name == name)
+            .cloned()
+            .collect()
+    }
+
+    /// Find the units named by a string with an optional library prefix, as
+    /// in `top` or `mylib.top`.
+    pub fn lookup(&self, name: &str) -> Vec<DesignUnit> {
+        let (lib, name) = split_lib_prefix(name);
+        let table = get_name_table();
+        self.find(
+            lib.map(|lib| table.intern(lib, false)),
+            table.intern(name, true),
+        )
+    }
+
+    /// Get all units registered in a library, in the order they were
+    /// registered.
+    pub fn units_in(&self, lib: Name) -> Vec<DesignUnit> {
+        let lib = self.resolve_lib(lib);
+        self.units
+            .borrow()
+            .iter()
+            .filter(|unit| unit.lib == lib)
+            .cloned()
+            .collect()
+    }
+
+    /// Get all registered units, in the order they were registered.
+    pub fn units(&self) -> Vec<DesignUnit> {
+        self.units.borrow().clone()
+    }
+
+    /// Record the conversions made when instantiating a unit across a
+    /// language boundary. Crossings that have already been recorded are
+    /// ignored.
+    pub fn add_crossing(&self, crossing: Crossing) {
+        let mut crossings = self.crossings.borrow_mut();
+        if !crossings.contains(&crossing) {
+            crossings.push(crossing);
+        }
+    }
+
+    /// Get the units instantiated across a language boundary, in the order
+    /// they were instantiated.
+    pub fn crossings(&self) -> Vec<Crossing> {
+        self.crossings.borrow().clone()
+    }
+}
+
+/// Split the library prefix off a name, as in `mylib.top`.
+pub fn split_lib_prefix(name: &str) -> (Option<&str>, &str) {
+    match name.find('.') {
+        Some(index) => (Some(&name[..index]), &name[index + 1..]),
+        None => (None, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::INVALID_SPAN;
+    use DesignUnitKind::*;
+    use ForeignLang::*;
+
+    fn unit(lib: &str, name: &str, lang: ForeignLang, kind: DesignUnitKind) -> DesignUnit {
+        let table = get_name_table();
+        DesignUnit {
+            lib: table.intern(lib, false),
+            name: table.intern(name, true),
+            lang,
+            kind,
+            id: NodeId::alloc(),
+            span: INVALID_SPAN,
+            interface: None,
+        }
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(split_lib_prefix("top"), (None, "top"));
+        assert_eq!(split_lib_prefix("mylib.top"), (Some("mylib"), "top"));
+    }
+
+    #[test]
+    fn lookup() {
+        let units = DesignUnits::default();
+        units.set_work(get_name_table().intern("mylib", false));
+        units.add(unit("mylib", "top", Svlog, Module));
+        units.add(unit("mylib", "counter", Vhdl, Entity));
+        units.add(unit("other", "counter", Svlog, Module));
+        assert_eq!(units.lookup("top").len(), 1);
+        assert_eq!(units.lookup("work.top").len(), 1);
+        assert_eq!(units.lookup("mylib.counter")[0].lang, Vhdl);
+        assert_eq!(units.lookup("other.counter")[0].lang, Svlog);
+        assert!(units.lookup("other.top").is_empty());
+        assert_eq!(units.units_in(units.work()).len(), 2);
+    }
+}
@@ -2,8 +2,14 @@
 
 //! Utilities to implement diagnostics and error reporting facilities.
 
-use crate::source::Span;
+use crate::source::{Location, Span};
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
 use std::fmt;
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Mutex,
+};
 
 /// Print debug information. Omitted in release builds.
 #[macro_export]
@@ -75,12 +81,31 @@ pub struct DiagBuilder2 {
     pub severity: Severity,
     pub message: String,
     pub segments: Vec<DiagSegment>,
+    /// The stable code identifying the kind of diagnostic, such as `SV0001`.
+    pub code: Option<&'static str>,
+    /// The warning group the diagnostic belongs to, such as `latch`.
+    pub group: Option<&'static str>,
 }
 
 #[derive(Clone, Debug)]
 pub enum DiagSegment {
     Span(Span),
+    /// A span with a label that describes its role in the diagnostic.
+    Label(Span, String),
     Note(String),
+    Suggestion(Suggestion),
+}
+
+/// A machine-applicable fix for a diagnostic, which replaces the source text in
+/// a span with a new text.
+///
+/// An empty span inserts the text, and an empty text removes the span.
+#[derive(Clone, Debug)]
+pub struct Suggestion {
+    /// The source text to be replaced.
+    pub span: Span,
+    /// The text to replace it with.
+    pub replacement: String,
 }
 
 /// A diagnostic result type. Either carries the result `T` in the Ok variant,
@@ -93,6 +118,8 @@ impl DiagBuilder2 {
             severity: severity,
             message: message.into(),
             segments: Vec::new(),
+            code: None,
+            group: None,
         }
     }
 
@@ -129,10 +156,32 @@ impl DiagBuilder2 {
         self.segment(DiagSegment::Span(span.into()))
     }
 
+    /// Add a span with a label, such as "first driver here".
+    pub fn span_label<S: Into<Span>, L: Into<String>>(self, span: S, label: L) -> DiagBuilder2 {
+        self.segment(DiagSegment::Label(span.into(), label.into()))
+    }
+
     pub fn add_note<S: Into<String>>(self, message: S) -> DiagBuilder2 {
         self.segment(DiagSegment::Note(message.into()))
     }
 
+    /// Suggest replacing the source text in a span with a new text.
+    pub fn suggest<S: Into<String>>(self, span: Span, replacement: S) -> DiagBuilder2 {
+        self.segment(DiagSegment::Suggestion(Suggestion {
+            span,
+            replacement: replacement.into(),
+        }))
+    }
+
+    /// Assign a stable code to the diagnostic, which `--explain` describes in
+    /// more detail.
+    pub fn code(self, code: &'static str) -> DiagBuilder2 {
+        DiagBuilder2 {
+            code: Some(code),
+            ..self
+        }
+    }
+
     pub fn get_severity(&self) -> Severity {
         self.severity
     }
@@ -144,6 +193,24 @@ impl DiagBuilder2 {
     pub fn get_segments(&self) -> &[DiagSegment] {
         &self.segments
     }
+
+    /// Assign the diagnostic to one of the `WARNING_GROUPS`, which can be
+    /// disabled or turned into errors with `-W` options.
+    pub fn group(self, group: &'static str) -> DiagBuilder2 {
+        debug_assert!(WARNING_GROUPS.iter().any(|&(x, _)| x == group));
+        DiagBuilder2 {
+            group: Some(group),
+            ..self
+        }
+    }
+
+    pub fn get_code(&self) -> Option<&'static str> {
+        self.code
+    }
+
+    pub fn get_group(&self) -> Option<&'static str> {
+        self.group
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -173,90 +240,422 @@ impl fmt::Display for Severity {
     }
 }
 
+/// The warning groups and their descriptions, sorted by name.
+pub static WARNING_GROUPS: &[(&str, &str)] = &[
+    (
+        "blackbox",
+        "instantiations of unknown modules replaced by blackboxes",
+    ),
+    (
+        "blocking-ff",
+        "blocking assignments in `always_ff` procedures",
+    ),
+    (
+        "cdc",
+        "signals crossing clock domains without a synchronizer",
+    ),
+    ("comb-loop", "combinational loops"),
+    (
+        "dead-generate",
+        "generate branches not elaborated in any specialization",
+    ),
+    (
+        "implicit-net",
+        "nets declared implicitly by port connections or assignments",
+    ),
+    (
+        "incomplete-case",
+        "`case` statements without `default` not covering all values",
+    ),
+    ("latch", "latches inferred in `always_comb` procedures"),
+    (
+        "naming",
+        "names violating the configured naming conventions",
+    ),
+    (
+        "nonblocking-comb",
+        "nonblocking assignments in `always_comb` and `always_latch` procedures",
+    ),
+    ("pattern", "pattern elements overwriting previous ones"),
+    (
+        "port-conn",
+        "unconnected ports, and port connections of mismatched width or direction",
+    ),
+    ("positional", "positional connections after named ones"),
+    (
+        "readmem",
+        "memory files not filling the loaded address range",
+    ),
+    (
+        "reset",
+        "inconsistent reset styles and registers without a reset",
+    ),
+    ("style", "declarations in unusual places"),
+    ("timescale", "assumed timescales"),
+    (
+        "unsupported",
+        "constructs that are ignored since they are not supported",
+    ),
+    (
+        "unused",
+        "signals, parameters, and ports that are never used",
+    ),
+    ("width", "constants that are too large for their width"),
+    (
+        "x-assign",
+        "assignments of X values outside of default and reset idioms",
+    ),
+    (
+        "x-compare",
+        "comparisons with X or Z values using `==` or `!=`",
+    ),
+];
+
+/// What to do with the warnings in a group.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WarningLevel {
+    /// Do not emit the warnings.
+    Ignore,
+    /// Emit the warnings.
+    Warn,
+    /// Emit the warnings as errors.
+    Error,
+}
+
+/// The format in which diagnostics are printed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiagFormat {
+    /// Human-readable text with the affected source lines.
+    Human,
+    /// One JSON object per line.
+    Json,
+    /// A SARIF 2.1 log of all diagnostics, printed by `flush_diags`.
+    Sarif,
+}
+
+static DIAG_FORMAT: AtomicU8 = AtomicU8::new(DiagFormat::Human as u8);
+
+/// The SARIF results of the diagnostics printed so far.
+static SARIF_RESULTS: Lazy<Mutex<Vec<serde_json::Value>>> = Lazy::new(Default::default);
+
+/// Set the format in which diagnostics are printed.
+pub fn set_diag_format(format: DiagFormat) {
+    DIAG_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Get the format in which diagnostics are printed.
+pub fn diag_format() -> DiagFormat {
+    match DIAG_FORMAT.load(Ordering::Relaxed) {
+        x if x == DiagFormat::Json as u8 => DiagFormat::Json,
+        x if x == DiagFormat::Sarif as u8 => DiagFormat::Sarif,
+        _ => DiagFormat::Human,
+    }
+}
+
+thread_local! {
+    /// The diagnostics held back on this thread by `capture_diags`.
+    static CAPTURED_DIAGS: RefCell<Option<Vec<DiagBuilder2>>> = const { RefCell::new(None) };
+}
+
+/// Run a function and collect the diagnostics it prints, instead of printing
+/// them.
+///
+/// This allows work spread across multiple threads to report its diagnostics
+/// in a deterministic order, by passing them to `print_diag` once all threads
+/// are done.
+pub fn capture_diags<R>(f: impl FnOnce() -> R) -> (R, Vec<DiagBuilder2>) {
+    let outer = CAPTURED_DIAGS.with(|c| c.replace(Some(vec![])));
+    let result = f();
+    let diags = CAPTURED_DIAGS.with(|c| c.replace(outer));
+    (result, diags.unwrap_or_default())
+}
+
+/// Check whether `capture_diags` collects the diagnostics of this thread.
+pub fn capturing_diags() -> bool {
+    CAPTURED_DIAGS.with(|c| c.borrow().is_some())
+}
+
+/// Print a diagnostic to stderr in the format set with `set_diag_format`.
+///
+/// The diagnostic is collected instead if called within `capture_diags`.
+pub fn print_diag(diag: &DiagBuilder2) {
+    let captured = CAPTURED_DIAGS.with(|c| match *c.borrow_mut() {
+        Some(ref mut diags) => {
+            diags.push(diag.clone());
+            true
+        }
+        None => false,
+    });
+    if captured {
+        return;
+    }
+    match diag_format() {
+        DiagFormat::Human => eprintln!("{}", diag),
+        DiagFormat::Json => eprintln!("{}", diag.to_json()),
+        DiagFormat::Sarif => SARIF_RESULTS.lock().unwrap().push(diag.to_sarif()),
+    }
+}
+
+/// Print the diagnostics that have been deferred until the end of the
+/// compilation.
+///
+/// In the SARIF format, the diagnostics are printed to stderr as one log with a
+/// single run. The baseline file of the reported warnings is written as well,
+/// if one has been requested. Call this before the process exits.
+pub fn flush_diags() {
+    crate::waiver::write_baseline();
+    if diag_format() != DiagFormat::Sarif {
+        return;
+    }
+    let results = std::mem::take(&mut *SARIF_RESULTS.lock().unwrap());
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "moore",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/fabianschuiki/moore",
+                }
+            },
+            "results": results,
+        }],
+    });
+    eprintln!("{}", serde_json::to_string_pretty(&log).unwrap());
+}
+
+impl DiagBuilder2 {
+    /// Convert the diagnostic into a JSON object.
+    ///
+    /// The object has the diagnostic's `severity`, `code`, `message`, `notes`,
+    /// `spans`, and `suggestions`. The first span is the primary one, and every
+    /// span carries its `file`, and the 1-based `line` and `column` of its
+    /// beginning and end, as well as its `label` if it has one. The suggestions
+    /// carry the same location information as spans, and the `replacement`
+    /// text.
+    pub fn to_json(&self) -> serde_json::Value {
+        let location = |sp: Span| {
+            let (line, column, _) = sp.begin().human();
+            let (end_line, end_column, _) = sp.end().human();
+            serde_json::json!({
+                "file": &*sp.source.get_path(),
+                "line": line,
+                "column": column,
+                "end_line": end_line,
+                "end_column": end_column,
+            })
+        };
+        let mut spans = vec![];
+        let mut notes = vec![];
+        let mut suggestions = vec![];
+        for segment in &self.segments {
+            match *segment {
+                DiagSegment::Span(sp) => {
+                    let mut span = location(sp);
+                    span["primary"] = spans.is_empty().into();
+                    spans.push(span);
+                }
+                DiagSegment::Label(sp, ref label) => {
+                    let mut span = location(sp);
+                    span["primary"] = spans.is_empty().into();
+                    span["label"] = label.clone().into();
+                    spans.push(span);
+                }
+                DiagSegment::Note(ref message) => notes.push(message.clone()),
+                DiagSegment::Suggestion(ref sugg) => {
+                    let mut suggestion = location(sugg.span);
+                    suggestion["replacement"] = sugg.replacement.clone().into();
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        serde_json::json!({
+            "severity": self.severity.to_str(),
+            "code": self.code,
+            "group": self.group,
+            "message": self.message,
+            "spans": spans,
+            "notes": notes,
+            "suggestions": suggestions,
+        })
+    }
+
+    /// Convert the diagnostic into a SARIF result object.
+    ///
+    /// The first span becomes the location of the result, and the others
+    /// become related locations. The labels of spans become the messages of
+    /// their locations, the notes are appended to the message, and the
+    /// suggestions become fixes.
+    pub fn to_sarif(&self) -> serde_json::Value {
+        let region = |sp: Span| {
+            let (line, column, _) = sp.begin().human();
+            let (end_line, end_column, _) = sp.end().human();
+            serde_json::json!({
+                "startLine": line,
+                "startColumn": column,
+                "endLine": end_line,
+                "endColumn": end_column,
+            })
+        };
+        let mut locations = vec![];
+        let mut fixes = vec![];
+        let mut text = self.message.clone();
+        for segment in &self.segments {
+            match *segment {
+                DiagSegment::Span(sp) => {
+                    locations.push(serde_json::json!({
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": &*sp.source.get_path() },
+                            "region": region(sp),
+                        },
+                    }));
+                }
+                DiagSegment::Label(sp, ref label) => {
+                    locations.push(serde_json::json!({
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": &*sp.source.get_path() },
+                            "region": region(sp),
+                        },
+                        "message": { "text": label },
+                    }));
+                }
+                DiagSegment::Note(ref message) => {
+                    text.push('\n');
+                    text.push_str(message);
+                }
+                DiagSegment::Suggestion(ref sugg) => {
+                    fixes.push(serde_json::json!({
+                        "description": { "text": sugg.describe() },
+                        "artifactChanges": [{
+                            "artifactLocation": { "uri": &*sugg.span.source.get_path() },
+                            "replacements": [{
+                                "deletedRegion": region(sugg.span),
+                                "insertedContent": { "text": &sugg.replacement },
+                            }],
+                        }],
+                    }));
+                }
+            }
+        }
+        let level = match self.severity {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error | Severity::Fatal | Severity::Bug => "error",
+        };
+        let mut result = serde_json::json!({
+            "level": level,
+            "message": { "text": text },
+        });
+        if let Some(code) = self.code {
+            result["ruleId"] = code.into();
+        }
+        if !locations.is_empty() {
+            let related = locations.split_off(1);
+            result["locations"] = locations.into();
+            if !related.is_empty() {
+                result["relatedLocations"] = related.into();
+            }
+        }
+        if !fixes.is_empty() {
+            result["fixes"] = fixes.into();
+        }
+        result
+    }
+}
+
+impl Suggestion {
+    /// Describe the change the suggestion makes to the source text, such as
+    /// "replace `a` with `b`".
+    pub fn describe(&self) -> String {
+        let old = self.span.extract();
+        if old.is_empty() {
+            format!("insert `{}`", self.replacement)
+        } else if self.replacement.is_empty() {
+            format!("remove `{}`", old)
+        } else {
+            format!("replace `{}` with `{}`", old, self.replacement)
+        }
+    }
+}
+
 impl fmt::Display for DiagBuilder2 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut colorcode = match self.get_severity() {
+        let colorcode = match self.get_severity() {
             Severity::Bug | Severity::Fatal | Severity::Error => "\x1B[31;1m",
             Severity::Warning => "\x1B[33;1m",
             Severity::Note => "\x1B[36;1m",
         };
-        write!(
-            f,
-            "{}{}:\x1B[m\x1B[1m {}\x1B[m\n",
-            colorcode,
-            self.get_severity(),
-            self.get_message()
-        )?;
-
-        for segment in &self.segments {
-            match *segment {
-                DiagSegment::Span(sp) => {
-                    let c = sp.source.get_content();
-
-                    // Look for the start of the line.
-                    let (line, col, line_offset) = sp.begin().human();
-
-                    // Print the line in question.
-                    let text: String = c
-                        .iter_from(line_offset)
-                        .map(|x| x.1)
-                        .take_while(|c| *c != '\n' && *c != '\r')
-                        .collect();
-                    write!(
-                        f,
-                        "  --> {}:{}:{}-{}:\n",
-                        sp.source.get_path(),
-                        line,
-                        col,
-                        col + sp.extract().len()
-                    )?;
-                    write!(f, "   | \n")?;
-                    write!(f, "   | ")?;
-                    for (mut i, c) in text.char_indices() {
-                        i += line_offset;
-                        if sp.begin != sp.end {
-                            if i == sp.begin {
-                                write!(f, "{}", colorcode)?;
-                            }
-                            if i == sp.end {
-                                write!(f, "\x1B[m")?;
-                            }
-                        }
-                        match c {
-                            '\t' => write!(f, "    ")?,
-                            c => write!(f, "{}", c)?,
-                        }
-                    }
-                    write!(f, "\x1B[m\n")?;
-                    write!(f, "   | ")?;
-
-                    // Print the caret markers for the line in question.
-                    let mut pd = ' ';
-                    for (mut i, c) in text.char_indices() {
-                        i += line_offset;
-                        let d = if (i >= sp.begin && i < sp.end)
-                            || (i == sp.begin && sp.begin == sp.end)
-                        {
-                            '^'
-                        } else {
-                            ' '
+        match self.code {
+            Some(code) => write!(
+                f,
+                "{}{}[{}]:\x1B[m\x1B[1m {}",
+                colorcode,
+                self.get_severity(),
+                code,
+                self.get_message()
+            )?,
+            None => write!(
+                f,
+                "{}{}:\x1B[m\x1B[1m {}",
+                colorcode,
+                self.get_severity(),
+                self.get_message()
+            )?,
+        }
+        match self.group {
+            Some(group) if self.severity >= Severity::Error => write!(f, " [-Werror={}]", group)?,
+            Some(group) => write!(f, " [-W{}]", group)?,
+            None => (),
+        }
+        write!(f, "\x1B[m\n")?;
+
+        // All snippets share the width of the line number gutter.
+        let gutter = Gutter(
+            self.segments
+                .iter()
+                .filter_map(|segment| match *segment {
+                    DiagSegment::Span(sp) | DiagSegment::Label(sp, _) => Some(sp),
+                    DiagSegment::Suggestion(ref sugg) => Some(sugg.span),
+                    DiagSegment::Note(_) => None,
+                })
+                .map(|sp| sp.begin().human_line().to_string().len())
+                .max()
+                .unwrap_or(1),
+        );
+
+        // Print consecutive spans in the same file as one snippet. The first
+        // span of the diagnostic, and the first span after every note, is the
+        // primary one.
+        let mut primary_color = colorcode;
+        let mut i = 0;
+        while i < self.segments.len() {
+            match self.segments[i] {
+                DiagSegment::Span(first) | DiagSegment::Label(first, _) => {
+                    let mut spans = vec![];
+                    while let Some(segment) = self.segments.get(i) {
+                        let span = match *segment {
+                            DiagSegment::Span(sp) => (sp, None),
+                            DiagSegment::Label(sp, ref label) => (sp, Some(label.as_str())),
+                            _ => break,
                         };
-                        if d != pd {
-                            write!(f, "{}", if d == ' ' { "\x1B[m" } else { colorcode })?;
-                        }
-                        pd = d;
-                        match c {
-                            '\t' => write!(f, "{}{}{}{}", d, d, d, d)?,
-                            _ => write!(f, "{}", d)?,
+                        if span.0.source != first.source {
+                            break;
                         }
+                        spans.push(span);
+                        i += 1;
                     }
-                    write!(f, "\x1B[m\n")?;
-                    colorcode = "\x1B[1m";
+                    write_snippet(f, gutter, &spans, primary_color)?;
+                    primary_color = "\x1B[1m";
+                    continue;
                 }
                 DiagSegment::Note(ref message) => {
-                    write!(f, "   = \x1B[1mnote:\x1B[m {}\n", message)?
+                    write!(f, "{}= \x1B[1mnote:\x1B[m {}\n", gutter.pad(), message)?;
+                    primary_color = "\x1B[1m";
                 }
+                DiagSegment::Suggestion(ref sugg) => write_suggestion(f, gutter, sugg)?,
             }
+            i += 1;
         }
 
         if self.get_severity() == Severity::Bug {
@@ -272,3 +671,280 @@ impl fmt::Display for DiagBuilder2 {
         Ok(())
     }
 }
+
+/// The color of the gutter and the secondary span markers.
+const SECONDARY_COLOR: &str = "\x1B[34;1m";
+
+/// The line number gutter to the left of the source lines in a diagnostic.
+#[derive(Clone, Copy)]
+struct Gutter(usize);
+
+impl Gutter {
+    /// The empty gutter, for lines without a line number.
+    fn pad(self) -> String {
+        " ".repeat(self.0 + 1)
+    }
+
+    /// Print the line pointing at a location, such as `--> foo.sv:4:2`.
+    fn write_location(self, f: &mut fmt::Formatter, sp: Span) -> fmt::Result {
+        let (line, col, _) = sp.begin().human();
+        write!(
+            f,
+            "{}{}-->\x1B[m {}:{}:{}\n",
+            " ".repeat(self.0),
+            SECONDARY_COLOR,
+            sp.source.get_path(),
+            line,
+            col
+        )
+    }
+
+    /// Print a gutter without a line number, followed by some text.
+    fn write_empty(self, f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+        if text.is_empty() {
+            write!(f, "{}{}|\x1B[m\n", self.pad(), SECONDARY_COLOR)
+        } else {
+            write!(f, "{}{}|\x1B[m {}\n", self.pad(), SECONDARY_COLOR, text)
+        }
+    }
+
+    /// Print a source line with its line number.
+    fn write_line(self, f: &mut fmt::Formatter, line: usize, text: &str) -> fmt::Result {
+        write!(
+            f,
+            "{}{:>w$} |\x1B[m {}\n",
+            SECONDARY_COLOR,
+            line,
+            text,
+            w = self.0
+        )
+    }
+}
+
+/// A source line of a diagnostic, with tabs expanded.
+struct SourceLine {
+    /// The line number.
+    number: usize,
+    /// The offset of the beginning of the line in the source file.
+    offset: usize,
+    /// The byte length of the line, without the line break.
+    len: usize,
+    /// The line with tabs expanded.
+    text: String,
+    /// The display column of every byte of the line.
+    columns: Vec<usize>,
+}
+
+impl SourceLine {
+    /// Find the line that a location falls onto.
+    fn at(loc: Location) -> SourceLine {
+        let (number, _, offset) = loc.human();
+        let raw: String = loc
+            .source
+            .get_content()
+            .iter_from(offset)
+            .map(|x| x.1)
+            .take_while(|c| *c != '\n' && *c != '\r')
+            .collect();
+        let mut text = String::new();
+        let mut columns = vec![];
+        let mut column = 0;
+        for c in raw.chars() {
+            let width = if c == '\t' { 4 } else { 1 };
+            for _ in 0..c.len_utf8() {
+                columns.push(column);
+            }
+            match c {
+                '\t' => text.push_str("    "),
+                c => text.push(c),
+            }
+            column += width;
+        }
+        columns.push(column);
+        SourceLine {
+            number,
+            offset,
+            len: raw.len(),
+            text,
+            columns,
+        }
+    }
+
+    /// Determine the display column of an offset in the source file, clamped
+    /// to the line.
+    fn column(&self, offset: usize) -> usize {
+        let offset = offset.saturating_sub(self.offset).min(self.len);
+        self.columns[offset]
+    }
+}
+
+/// Print a snippet of the source lines covered by a group of spans in the same
+/// file, with the spans marked and labeled.
+///
+/// The first span is the primary one and is marked with `^` in the given
+/// color. The other spans are marked with `-`. A span that covers multiple
+/// lines is marked up to the end of its first line. Labels are printed after
+/// the rightmost marker, or below the markers with a line pointing up at them.
+fn write_snippet(
+    f: &mut fmt::Formatter,
+    gutter: Gutter,
+    spans: &[(Span, Option<&str>)],
+    primary_color: &str,
+) -> fmt::Result {
+    gutter.write_location(f, spans[0].0)?;
+    gutter.write_empty(f, "")?;
+
+    // Group the spans by line.
+    let mut lines: Vec<(SourceLine, Vec<Marker>)> = vec![];
+    for (index, &(sp, label)) in spans.iter().enumerate() {
+        let line = SourceLine::at(sp.begin());
+        let (color, symbol) = if index == 0 {
+            (primary_color, '^')
+        } else {
+            (SECONDARY_COLOR, '-')
+        };
+        let begin = line.column(sp.begin);
+        let end = line.column(sp.end).max(begin + 1);
+        let marker = Marker {
+            begin,
+            end,
+            color,
+            symbol,
+            label,
+        };
+        match lines.iter_mut().find(|(x, _)| x.number == line.number) {
+            Some((_, markers)) => markers.push(marker),
+            None => lines.push((line, vec![marker])),
+        }
+    }
+    lines.sort_by_key(|(line, _)| line.number);
+
+    let mut prev_line = None;
+    for (line, mut markers) in lines {
+        if prev_line.map(|x| line.number > x + 1).unwrap_or(false) {
+            write!(f, "{}...\x1B[m\n", SECONDARY_COLOR)?;
+        }
+        prev_line = Some(line.number);
+        gutter.write_line(f, line.number, &line.text)?;
+        markers.sort_by_key(|m| (m.begin, m.end));
+
+        // Print the markers, and the label of the rightmost one.
+        let mut text = String::new();
+        let mut column = 0;
+        for m in &markers {
+            let begin = m.begin.max(column);
+            if begin >= m.end {
+                continue;
+            }
+            text.push_str(&" ".repeat(begin - column));
+            text.push_str(m.color);
+            text.extend(std::iter::repeat(m.symbol).take(m.end - begin));
+            text.push_str("\x1B[m");
+            column = m.end;
+        }
+        let mut below: Vec<&Marker> = markers.iter().filter(|m| m.label.is_some()).collect();
+        let last = markers.iter().map(|m| m.end).max().unwrap_or(0);
+        if let Some(m) = below.last() {
+            if m.end == last {
+                text.push(' ');
+                text.push_str(m.color);
+                text.push_str(m.label.unwrap());
+                text.push_str("\x1B[m");
+                below.pop();
+            }
+        }
+        gutter.write_empty(f, &text)?;
+
+        // Print the remaining labels below the markers, from right to left.
+        let pointers = |markers: &[&Marker], label: Option<&Marker>| {
+            let mut text = String::new();
+            let mut column = 0;
+            for m in markers {
+                text.push_str(&" ".repeat(m.begin - column));
+                text.push_str(m.color);
+                text.push('|');
+                text.push_str("\x1B[m");
+                column = m.begin + 1;
+            }
+            if let Some(m) = label {
+                text.push_str(&" ".repeat(m.begin - column));
+                text.push_str(m.color);
+                text.push_str(m.label.unwrap());
+                text.push_str("\x1B[m");
+            }
+            text
+        };
+        below.dedup_by_key(|m| m.begin);
+        if !below.is_empty() {
+            gutter.write_empty(f, &pointers(&below, None))?;
+        }
+        for i in (0..below.len()).rev() {
+            gutter.write_empty(f, &pointers(&below[..i], Some(below[i])))?;
+        }
+    }
+    Ok(())
+}
+
+/// A marker underlining a span in a source line.
+struct Marker<'a> {
+    /// The display column of the first marker.
+    begin: usize,
+    /// The display column one past the last marker.
+    end: usize,
+    /// The color of the marker.
+    color: &'a str,
+    /// The symbol to underline the span with.
+    symbol: char,
+    /// The label to print next to the marker.
+    label: Option<&'a str>,
+}
+
+/// Print a suggestion, together with the source line as it looks after the
+/// suggestion has been applied.
+fn write_suggestion(f: &mut fmt::Formatter, gutter: Gutter, sugg: &Suggestion) -> fmt::Result {
+    write!(
+        f,
+        "{}= \x1B[32;1mhelp:\x1B[m {}\n",
+        gutter.pad(),
+        sugg.describe()
+    )?;
+
+    // Only show the changed line if the change does not span multiple lines.
+    let sp = sugg.span;
+    let line = SourceLine::at(sp.begin());
+    if sp.end > line.offset + line.len || sugg.replacement.contains('\n') {
+        return Ok(());
+    }
+    let begin = line.column(sp.begin);
+    let end = line.column(sp.end);
+    let untab = |s: &str| s.replace('\t', "    ");
+    let (text, changed) = if sugg.replacement.is_empty() {
+        (line.text.clone(), end - begin)
+    } else {
+        let replacement = untab(&sugg.replacement);
+        let width = replacement.chars().count();
+        let before: String = line.text.chars().take(begin).collect();
+        let after: String = line.text.chars().skip(end).collect();
+        (
+            format!("{}\x1B[32;1m{}\x1B[m{}", before, replacement, after),
+            width,
+        )
+    };
+    let marker = if sugg.replacement.is_empty() {
+        '-'
+    } else {
+        '+'
+    };
+    gutter.write_location(f, sp)?;
+    gutter.write_empty(f, "")?;
+    gutter.write_line(f, line.number, &text)?;
+    gutter.write_empty(
+        f,
+        &format!(
+            "{}\x1B[32;1m{}\x1B[m",
+            " ".repeat(begin),
+            marker.to_string().repeat(changed)
+        ),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Design units shared across languages.
+//!
+//! A design unit declared in one language may be instantiated from another,
+//! such as a VHDL entity from a SystemVerilog module. The front end of the
+//! declaring language describes the interface of the unit in terms of the
+//! language-neutral types in this module, which the front end of the
+//! instantiating language then maps onto its own types.
+
+use crate::errors::{DiagBuilder2, DiagEmitter};
+use crate::name::Name;
+use crate::source::Span;
+use serde::Serialize;
+use std::fmt;
+use std::io::{Result as IoResult, Write};
+
+/// The interface of a design unit declared in another language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignUnit {
+    /// The name of the unit.
+    pub name: Name,
+    /// The location of the unit's declaration.
+    pub span: Span,
+    /// The language the unit is declared in.
+    pub lang: ForeignLang,
+    /// The parameters of the unit, such as the generics of an entity.
+    pub params: Vec<ForeignParam>,
+    /// The ports of the unit.
+    pub ports: Vec<ForeignPort>,
+}
+
+/// A language a design unit can be declared in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ForeignLang {
+    Vhdl,
+    Svlog,
+}
+
+impl fmt::Display for ForeignLang {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ForeignLang::Vhdl => write!(f, "VHDL"),
+            ForeignLang::Svlog => write!(f, "SystemVerilog"),
+        }
+    }
+}
+
+/// A parameter of a design unit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignParam {
+    /// The name of the parameter.
+    pub name: Name,
+    /// The type of the parameter.
+    pub ty: ForeignType,
+    /// The type of the parameter as declared.
+    pub decl_ty: String,
+    /// The default value of the parameter, if any.
+    pub default: Option<ForeignValue>,
+    /// The caveats of mapping the declared type to `ty`.
+    pub notes: Vec<String>,
+}
+
+/// A port of a design unit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignPort {
+    /// The name of the port.
+    pub name: Name,
+    /// The direction of the port.
+    pub dir: ForeignDir,
+    /// The type of the port.
+    pub ty: ForeignType,
+    /// The type of the port as declared.
+    pub decl_ty: String,
+    /// The caveats of mapping the declared type to `ty`.
+    pub notes: Vec<String>,
+}
+
+/// The direction of a port.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ForeignDir {
+    Input,
+    Output,
+    Inout,
+}
+
+impl fmt::Display for ForeignDir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ForeignDir::Input => write!(f, "input"),
+            ForeignDir::Output => write!(f, "output"),
+            ForeignDir::Inout => write!(f, "inout"),
+        }
+    }
+}
+
+/// The type of a port or parameter.
+///
+/// Only types that have a direct counterpart in all supported languages can
+/// cross a language boundary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ForeignType {
+    /// A single bit. Four-valued logic if `logic` is set, such as VHDL's
+    /// `STD_LOGIC`, or two-valued otherwise, such as VHDL's `BIT`.
+    Bit { logic: bool },
+    /// A vector of bits, with the indices of the leftmost and rightmost bit.
+    Vector {
+        logic: bool,
+        left: isize,
+        right: isize,
+    },
+    /// An integer of a given width.
+    Int { width: usize, signed: bool },
+}
+
+impl ForeignType {
+    /// Determine the number of bits of the type.
+    pub fn width(&self) -> usize {
+        match *self {
+            ForeignType::Bit { .. } => 1,
+            ForeignType::Vector { left, right, .. } => (left - right).unsigned_abs() + 1,
+            ForeignType::Int { width, .. } => width,
+        }
+    }
+}
+
+impl fmt::Display for ForeignType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ForeignType::Bit { logic: true } => write!(f, "logic bit"),
+            ForeignType::Bit { logic: false } => write!(f, "bit"),
+            ForeignType::Vector { logic, left, right } => write!(
+                f,
+                "{} vector [{}:{}]",
+                if logic { "logic" } else { "bit" },
+                left,
+                right
+            ),
+            ForeignType::Int { width, signed } => write!(
+                f,
+                "{} {}-bit integer",
+                if signed { "signed" } else { "unsigned" },
+                width
+            ),
+        }
+    }
+}
+
+/// The value of a parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ForeignValue {
+    /// An integer.
+    Int(i64),
+    /// A sequence of bits, most significant bit first, each one of `0`, `1`,
+    /// `x`, or `z`.
+    Bits(String),
+}
+
+/// A design unit instantiated across a language boundary.
+///
+/// Records how the parameters and ports of the unit are converted, such that
+/// the assumptions made at the boundary can be audited.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Crossing {
+    /// The name of the unit.
+    pub unit: Name,
+    /// The location of the unit's declaration.
+    pub span: Span,
+    /// The language the unit is declared in.
+    pub from: ForeignLang,
+    /// The language the unit is instantiated from.
+    pub to: ForeignLang,
+    /// The conversions of the parameters, followed by those of the ports.
+    pub conversions: Vec<Conversion>,
+}
+
+/// The conversion of a parameter or port across a language boundary.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Conversion {
+    /// The name of the parameter or port.
+    pub name: Name,
+    /// The direction of the port, or `None` for a parameter.
+    pub dir: Option<ForeignDir>,
+    /// The type as declared.
+    pub from_ty: String,
+    /// The type as seen by the instantiating language.
+    pub to_ty: String,
+    /// The caveats of the conversion, such as values that cannot be
+    /// represented on the other side.
+    pub notes: Vec<String>,
+}
+
+impl Crossing {
+    /// Describe the conversions made when instantiating a unit from another
+    /// language.
+    ///
+    /// `map_ty` maps a type to the instantiating language, adding the caveats
+    /// of the mapping to the notes.
+    pub fn new(
+        unit: &ForeignUnit,
+        to: ForeignLang,
+        mut map_ty: impl FnMut(&ForeignType, &mut Vec<String>) -> String,
+    ) -> Self {
+        let mut conversions = vec![];
+        for param in &unit.params {
+            let mut notes = param.notes.clone();
+            let to_ty = map_ty(&param.ty, &mut notes);
+            let two_valued = match param.ty {
+                ForeignType::Bit { logic } | ForeignType::Vector { logic, .. } => !logic,
+                ForeignType::Int { .. } => false,
+            };
+            if let Some(ForeignValue::Bits(ref bits)) = param.default {
+                if two_valued && bits.contains(['x', 'z']) {
+                    notes.push("the unknown bits of the default value become `0`".to_string());
+                }
+            }
+            conversions.push(Conversion {
+                name: param.name,
+                dir: None,
+                from_ty: param.decl_ty.clone(),
+                to_ty,
+                notes,
+            });
+        }
+        for port in &unit.ports {
+            let mut notes = port.notes.clone();
+            let to_ty = map_ty(&port.ty, &mut notes);
+            conversions.push(Conversion {
+                name: port.name,
+                dir: Some(port.dir),
+                from_ty: port.decl_ty.clone(),
+                to_ty,
+                notes,
+            });
+        }
+        Crossing {
+            unit: unit.name,
+            span: unit.span,
+            from: unit.lang,
+            to,
+            conversions,
+        }
+    }
+
+    /// Emit the conversions as a diagnostic note.
+    pub fn emit_note(&self, cx: &impl DiagEmitter) {
+        let mut d = DiagBuilder2::note(format!(
+            "{} unit `{}` instantiated from {}",
+            self.from, self.unit, self.to
+        ))
+        .span(self.span);
+        for conv in &self.conversions {
+            let what = match conv.dir {
+                Some(dir) => format!("{} port", dir),
+                None => "parameter".to_string(),
+            };
+            d = d.add_note(format!(
+                "{} `{}`: `{}` -> `{}`",
+                what, conv.name, conv.from_ty, conv.to_ty
+            ));
+            for note in &conv.notes {
+                d = d.add_note(format!("{} `{}`: {}", what, conv.name, note));
+            }
+        }
+        cx.emit(d);
+    }
+}
+
+/// A report of the design units instantiated across language boundaries.
+#[derive(Debug)]
+pub struct BoundaryReport {
+    /// The crossings, in the order the units were instantiated.
+    pub crossings: Vec<Crossing>,
+}
+
+impl BoundaryReport {
+    /// Emit the report as diagnostic notes.
+    pub fn emit_notes(&self, cx: &impl DiagEmitter) {
+        for crossing in &self.crossings {
+            crossing.emit_note(cx);
+        }
+    }
+
+    /// Write the report as JSON.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        serde_json::to_writer_pretty(&mut *w, &self.crossings)?;
+        writeln!(w)
+    }
+}
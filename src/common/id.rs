@@ -1,4 +1,5 @@
 // Copyright (c) 2016-2021 Fabian Schuiki
+use serde::{Serialize, Serializer};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// A positive, small ID assigned to nodes in the AST and derived data
@@ -39,3 +40,9 @@ impl std::fmt::Debug for NodeId {
         write!(f, "n{}", self.0)
     }
 }
+
+impl Serialize for NodeId {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_u32(self.0)
+    }
+}
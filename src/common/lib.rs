@@ -8,7 +8,9 @@ extern crate bitflags;
 
 #[macro_use]
 pub mod arenas;
+pub mod design_units;
 pub mod errors;
+pub mod foreign;
 pub mod grind;
 pub mod id;
 pub mod lexer;
@@ -16,15 +18,24 @@ pub mod name;
 pub mod score;
 pub mod source;
 pub mod util;
+pub mod waiver;
 
 pub use self::id::NodeId;
-use crate::errors::{DiagBuilder2, DiagEmitter, Severity};
+use crate::design_units::DesignUnits;
+use crate::errors::{DiagBuilder2, DiagEmitter, Severity, WarningLevel};
+use crate::waiver::Waivers;
 use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 pub struct Session {
     pub opts: SessionOptions,
+    /// The design units declared in all languages.
+    pub units: DesignUnits,
     /// Whether any error diagnostics were produced.
     pub failed: Cell<bool>,
+    /// The number of error diagnostics produced.
+    pub num_errors: Cell<usize>,
 }
 
 impl Session {
@@ -32,7 +43,9 @@ impl Session {
     pub fn new() -> Session {
         Session {
             opts: Default::default(),
+            units: Default::default(),
             failed: Cell::new(false),
+            num_errors: Cell::new(0),
         }
     }
 
@@ -42,11 +55,39 @@ impl Session {
 }
 
 impl DiagEmitter for Session {
-    fn emit(&self, diag: DiagBuilder2) {
+    fn emit(&self, mut diag: DiagBuilder2) {
+        if diag.severity == Severity::Warning {
+            if self.opts.waivers.is_waived(&diag) {
+                return;
+            }
+            match self.opts.warning_level(diag.group) {
+                WarningLevel::Ignore => return,
+                WarningLevel::Warn => (),
+                WarningLevel::Error => diag.severity = Severity::Error,
+            }
+            waiver::record_baseline(&diag);
+        }
         if diag.severity >= Severity::Error {
             self.failed.set(true);
+            self.num_errors.set(self.num_errors.get() + 1);
+        }
+        errors::print_diag(&diag);
+
+        // Stop the compilation once the error limit is reached.
+        let limit = self.opts.error_limit;
+        if limit > 0 && self.num_errors.get() >= limit {
+            if limit > 1 {
+                errors::print_diag(
+                    &DiagBuilder2::fatal(format!(
+                        "too many errors emitted, stopping after {}",
+                        limit
+                    ))
+                    .add_note("Use `--error-limit` to change the limit, or 0 to disable it"),
+                );
+            }
+            errors::flush_diags();
+            std::process::exit(1);
         }
-        eprintln!("{}", diag);
     }
 }
 
@@ -75,6 +116,55 @@ pub struct SessionOptions {
     pub verbosity: Verbosity,
     /// The optimization level.
     pub opt_level: usize,
+    /// The maximum depth of the instance hierarchy during elaboration. A value
+    /// of zero disables the limit.
+    pub max_inst_depth: usize,
+    /// Replace instantiations of unknown modules with empty blackboxes instead
+    /// of reporting an error.
+    pub allow_blackbox: bool,
+    /// Inline instantiated modules into their parents during code generation,
+    /// except for the ones marked with a `keep_hierarchy` attribute.
+    pub flatten: bool,
+    /// Insert checks that the values assigned to VHDL signals and variables
+    /// of scalar subtypes lie within their range.
+    pub range_checks: bool,
+    /// The directories searched for a file named after a module that is not
+    /// declared in the input files.
+    pub library_dirs: Vec<PathBuf>,
+    /// The library files parsed when a module is not declared in the input
+    /// files.
+    pub library_files: Vec<PathBuf>,
+    /// The file extensions considered in the library directories.
+    pub library_exts: Vec<String>,
+    /// The include paths used when parsing library files.
+    pub include_paths: Vec<PathBuf>,
+    /// The macro definitions used when parsing library files.
+    pub defines: Vec<(String, Option<String>)>,
+    /// Record the number of executed queries and the time spent in them.
+    pub stats: bool,
+    /// The number of errors after which the compilation is stopped. A value of
+    /// zero disables the limit.
+    pub error_limit: usize,
+    /// The warning groups that are disabled or turned into errors.
+    pub warning_levels: HashMap<String, WarningLevel>,
+    /// Turn all warnings into errors, except for the groups configured in
+    /// `warning_levels`.
+    pub warnings_as_errors: bool,
+    /// The warnings that are not reported.
+    pub waivers: Waivers,
+    /// The options of the lint rules, as `<rule>.<option>` and value.
+    pub lint_options: HashMap<String, String>,
+}
+
+impl SessionOptions {
+    /// Determine what to do with a warning in a group.
+    pub fn warning_level(&self, group: Option<&str>) -> WarningLevel {
+        match group.and_then(|g| self.warning_levels.get(g)) {
+            Some(&level) => level,
+            None if self.warnings_as_errors => WarningLevel::Error,
+            None => WarningLevel::Warn,
+        }
+    }
 }
 
 bitflags! {
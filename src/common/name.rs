@@ -2,16 +2,18 @@
 
 //! A name table that internalizes all names presented to it and allows for them
 //! to be referred to by a lightweight tag. This structure is heavily inspired
-//! by the interner used in the Rust compiler.
+//! by the interner used in the Rust compiler. The table is shared among all
+//! threads, such that source files can be processed in parallel.
 
+use once_cell::sync::Lazy;
+use serde::{Serialize, Serializer};
 use std::borrow::Borrow;
-use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::Hash;
 use std::ops::Deref;
-use std::rc::Rc;
+use std::sync::{Arc, RwLock};
 
 /// A name is a lightweight 32 bit tag that refers to a string in a name table.
 /// During parsing, encountered strings are inserted into the name table and
@@ -45,6 +47,12 @@ impl fmt::Display for Name {
     }
 }
 
+impl Serialize for Name {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.as_str())
+    }
+}
+
 // impl Encodable for Name {
 //     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
 //         s.emit_bool(self.is_case_sensitive())?;
@@ -74,20 +82,20 @@ impl<'a> From<&'a str> for Name {
 }
 
 /// A reference-counted string that acts like a regular str slice, hiding the
-/// fact that it is wrapped in Rc<>.
+/// fact that it is wrapped in Arc<>.
 #[derive(Clone, PartialEq, Hash, PartialOrd)]
-pub struct RcStr(Rc<String>);
+pub struct RcStr(Arc<String>);
 
 impl RcStr {
     /// Create a new ref-counted string which is a copy of `value`.
     pub fn new(value: &str) -> RcStr {
-        RcStr(Rc::new(value.to_string()))
+        RcStr(Arc::new(value.to_string()))
     }
 
     /// Create a new ref-counted string that contains `value`, without
     /// allocating any new storage.
     pub fn from(value: String) -> RcStr {
-        RcStr(Rc::new(value))
+        RcStr(Arc::new(value))
     }
 }
 
@@ -140,16 +148,16 @@ impl AsRef<str> for RcStr {
 /// case insensitive way. Allows for bidirectional lookup, i.e. by string or by
 /// assigned name.
 pub struct NameTable {
-    map: RefCell<HashMap<RcStr, Name>>,
-    vect: RefCell<Vec<RcStr>>,
+    map: RwLock<HashMap<RcStr, Name>>,
+    vect: RwLock<Vec<RcStr>>,
 }
 
 impl NameTable {
     /// Create a new empty name table.
     pub fn new() -> NameTable {
         NameTable {
-            map: RefCell::new(HashMap::new()),
-            vect: RefCell::new(Vec::new()),
+            map: RwLock::new(HashMap::new()),
+            vect: RwLock::new(Vec::new()),
         }
     }
 
@@ -157,7 +165,10 @@ impl NameTable {
     /// table and returns the new name, or returns the existing name if the
     /// string already exists in the table.
     pub fn intern(&self, value: &str, case_sensitive: bool) -> Name {
-        let mut map = self.map.borrow_mut();
+        if let Some(&idx) = self.map.read().unwrap().get(value) {
+            return idx;
+        }
+        let mut map = self.map.write().unwrap();
         if let Some(&idx) = map.get(value) {
             return idx;
         }
@@ -165,7 +176,7 @@ impl NameTable {
         // Since the name is not present in the table yet, we allocate a new idx
         // for it. Also, if it is a case-insensitive name, we insert both its
         // original form as well as its lowercase form into the lookup table.
-        let mut vect = self.vect.borrow_mut();
+        let mut vect = self.vect.write().unwrap();
         if case_sensitive {
             let new_idx = Name((vect.len() as u32) << 1 | 1);
             let v = RcStr::new(value);
@@ -188,7 +199,7 @@ impl NameTable {
 
     /// Retrieve the string given a name tag.
     pub fn get(&self, idx: Name) -> RcStr {
-        (*self.vect.borrow())[(idx.0 >> 1) as usize].clone()
+        self.vect.read().unwrap()[(idx.0 >> 1) as usize].clone()
     }
 
     /// Try to find a string.
@@ -197,16 +208,12 @@ impl NameTable {
         RcStr: Borrow<Q>,
         Q: Eq + Hash,
     {
-        (*self.map.borrow()).get(value).map(|v| *v)
+        self.map.read().unwrap().get(value).map(|v| *v)
     }
 }
 
-/// Get this thread's current name table.
-pub fn get_name_table() -> Rc<NameTable> {
-    thread_local!(static TBL: Rc<NameTable> = {
-        let nt = NameTable::new();
-        // token::prefill_name_table(&mut nt);
-        Rc::new(nt)
-    });
-    TBL.with(|x| x.clone())
+/// Get the global name table.
+pub fn get_name_table() -> &'static NameTable {
+    static TBL: Lazy<NameTable> = Lazy::new(NameTable::new);
+    &TBL
 }
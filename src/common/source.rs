@@ -2,21 +2,22 @@
 
 //! A global source file table that assigns an opaque ID to each processed
 //! source file. This helps keeping the source location lean and allow for
-//! simple querying of information.
+//! simple querying of information. The table is shared among all threads, such
+//! that source files can be processed in parallel.
 
 use crate::name::RcStr;
 use memmap2::Mmap;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use std;
 use std::borrow::Borrow;
-use std::cell::RefCell;
 use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::fmt;
 use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
 
 pub const INVALID_SOURCE: Source = Source(0);
 pub const INVALID_LOCATION: Location = Location {
@@ -39,7 +40,7 @@ impl Source {
     }
 
     /// Access the contents of this source file.
-    pub fn get_content(self) -> Rc<dyn SourceContent> {
+    pub fn get_content(self) -> Arc<dyn SourceContent> {
         get_source_manager().with(self, |x| x.get_content())
     }
 
@@ -67,6 +68,12 @@ impl fmt::Display for Source {
     }
 }
 
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.get_path())
+    }
+}
+
 // impl Encodable for Source {
 //     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
 //         s.emit_bool(self.0 == 0)?;
@@ -92,7 +99,7 @@ impl fmt::Display for Source {
 //     }
 // }
 
-pub trait SourceFile {
+pub trait SourceFile: Send + Sync {
     fn get_id(&self) -> Source;
     fn get_path(&self) -> RcStr;
     // TODO: getter for character iterator
@@ -100,7 +107,7 @@ pub trait SourceFile {
 
     /// Obtain the content of this source file. The returned object may be used
     /// to iterate over the characters in the file or extract portions of it.
-    fn get_content(&self) -> Rc<dyn SourceContent>;
+    fn get_content(&self) -> Arc<dyn SourceContent>;
 
     /// Copy a range of the source content into a String instance owned by the
     /// caller, possibly converting the encoding such that the result is in
@@ -110,7 +117,7 @@ pub trait SourceFile {
     }
 }
 
-pub trait SourceContent {
+pub trait SourceContent: Send + Sync {
     /// Obtain an iterator over the characters within the source file, together
     /// with their respective byte positions.
     fn iter(&self) -> Box<CharIter>;
@@ -140,15 +147,15 @@ pub trait SourceContent {
 
 /// A manager for source files and their assigned IDs.
 pub struct SourceManager {
-    map: RefCell<HashMap<RcStr, Source>>,
-    vect: RefCell<Vec<Box<dyn SourceFile>>>,
+    map: RwLock<HashMap<RcStr, Source>>,
+    vect: RwLock<Vec<Arc<dyn SourceFile>>>,
 }
 
 impl SourceManager {
     fn new() -> SourceManager {
         SourceManager {
-            map: RefCell::new(HashMap::new()),
-            vect: RefCell::new(Vec::new()),
+            map: RwLock::new(HashMap::new()),
+            vect: RwLock::new(Vec::new()),
         }
     }
 
@@ -157,15 +164,18 @@ impl SourceManager {
     where
         F: FnOnce(&dyn SourceFile) -> R,
     {
-        let ref vect = *self.vect.borrow();
-        assert!(id.0 > 0, "invalid source");
-        assert!(
-            (id.0 as usize - 1) < vect.len(),
-            "unknown source file: Source({}) >= {}",
-            id.0,
-            vect.len()
-        );
-        f(&*vect[id.0 as usize - 1])
+        let file = {
+            let vect = self.vect.read().unwrap();
+            assert!(id.0 > 0, "invalid source");
+            assert!(
+                (id.0 as usize - 1) < vect.len(),
+                "unknown source file: Source({}) >= {}",
+                id.0,
+                vect.len()
+            );
+            vect[id.0 as usize - 1].clone()
+        };
+        f(&*file)
     }
 
     pub fn find<Q: ?Sized>(&self, filename: &Q) -> Option<Source>
@@ -173,26 +183,26 @@ impl SourceManager {
         RcStr: Borrow<Q>,
         Q: Eq + Hash,
     {
-        (*self.map.borrow()).get(filename).map(|v| *v)
+        self.map.read().unwrap().get(filename).map(|v| *v)
     }
 
     pub fn open(&self, filename: &str) -> Option<Source> {
         // Check if the file has already been opened and return its pointer.
-        let mut map = self.map.borrow_mut();
+        let mut map = self.map.write().unwrap();
         if let Some(&id) = map.get(filename) {
             return Some(id);
         }
 
         // Check whether the file exists and allocate a new index for it.
         if Path::new(filename).exists() {
-            let mut vect = self.vect.borrow_mut();
+            let mut vect = self.vect.write().unwrap();
             let new_id = Source(vect.len() as u32 + 1);
             let v = RcStr::new(filename);
             map.insert(v.clone(), new_id);
-            vect.push(Box::new(DiskSourceFile {
+            vect.push(Arc::new(DiskSourceFile {
                 id: new_id,
                 filename: v,
-                content: RefCell::new(None),
+                content: Mutex::new(None),
             }));
             Some(new_id)
         } else {
@@ -204,20 +214,20 @@ impl SourceManager {
     /// source manager. Future calls to `open()` with the given filename will
     /// yield the provided contents.
     pub fn add(&self, filename: &str, content: &str) -> Source {
-        let mut map = self.map.borrow_mut();
+        let mut map = self.map.write().unwrap();
         assert!(
             !map.contains_key(filename),
             "add failed: source \"{}\" already exists",
             filename
         );
-        let mut vect = self.vect.borrow_mut();
+        let mut vect = self.vect.write().unwrap();
         let new_id = Source(vect.len() as u32 + 1);
         let v = RcStr::new(filename);
         map.insert(v.clone(), new_id);
-        vect.push(Box::new(VirtualSourceFile {
+        vect.push(Arc::new(VirtualSourceFile {
             id: new_id,
             filename: v,
-            content: Rc::new(VirtualSourceContent(content.to_string(), OnceCell::new())),
+            content: Arc::new(VirtualSourceContent(content.to_string(), OnceCell::new())),
         }));
         new_id
     }
@@ -230,23 +240,21 @@ impl SourceManager {
     where
         S: Into<String>,
     {
-        let mut vect = self.vect.borrow_mut();
+        let mut vect = self.vect.write().unwrap();
         let new_id = Source(vect.len() as u32 + 1);
-        vect.push(Box::new(VirtualSourceFile {
+        vect.push(Arc::new(VirtualSourceFile {
             id: new_id,
             filename: RcStr::new("<anonymous>"),
-            content: Rc::new(VirtualSourceContent(content.into(), OnceCell::new())),
+            content: Arc::new(VirtualSourceContent(content.into(), OnceCell::new())),
         }));
         new_id
     }
 }
 
 /// Get the global source manager.
-pub fn get_source_manager() -> Rc<SourceManager> {
-    thread_local!(static MNGR: Rc<SourceManager> = {
-        Rc::new(SourceManager::new())
-    });
-    MNGR.with(|x| x.clone())
+pub fn get_source_manager() -> &'static SourceManager {
+    static MNGR: Lazy<SourceManager> = Lazy::new(SourceManager::new);
+    &MNGR
 }
 
 fn line_starts(iter: impl Iterator<Item = (usize, char)>) -> impl Iterator<Item = usize> {
@@ -260,7 +268,7 @@ fn line_starts(iter: impl Iterator<Item = (usize, char)>) -> impl Iterator<Item
 struct VirtualSourceFile {
     id: Source,
     filename: RcStr,
-    content: Rc<VirtualSourceContent>,
+    content: Arc<VirtualSourceContent>,
 }
 
 struct VirtualSourceContent(pub String, OnceCell<Vec<usize>>);
@@ -274,7 +282,7 @@ impl SourceFile for VirtualSourceFile {
         self.filename.clone()
     }
 
-    fn get_content(&self) -> Rc<dyn SourceContent> {
+    fn get_content(&self) -> Arc<dyn SourceContent> {
         self.content.clone()
     }
 }
@@ -309,7 +317,7 @@ impl SourceContent for VirtualSourceContent {
 struct DiskSourceFile {
     id: Source,
     filename: RcStr,
-    content: RefCell<Option<Rc<DiskSourceContent>>>,
+    content: Mutex<Option<Arc<DiskSourceContent>>>,
 }
 
 #[derive(Debug)]
@@ -324,18 +332,17 @@ impl SourceFile for DiskSourceFile {
         self.filename.clone()
     }
 
-    fn get_content(&self) -> Rc<dyn SourceContent> {
-        let is_none = self.content.borrow().is_none();
-        if is_none {
-            let c = Rc::new(DiskSourceContent(
-                unsafe { Mmap::map(&File::open(&*self.filename).unwrap()).unwrap() },
-                OnceCell::new(),
-            ));
-            *self.content.borrow_mut() = Some(c.clone());
-            c
-        } else {
-            self.content.borrow().clone().unwrap()
-        }
+    fn get_content(&self) -> Arc<dyn SourceContent> {
+        self.content
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                Arc::new(DiskSourceContent(
+                    unsafe { Mmap::map(&File::open(&*self.filename).unwrap()).unwrap() },
+                    OnceCell::new(),
+                ))
+            })
+            .clone()
     }
 }
 
@@ -401,7 +408,7 @@ impl Location {
     }
 
     /// Obtain an iterator into the source file at this location.
-    pub fn iter<'a>(self, content: &'a Rc<dyn SourceContent>) -> Box<CharIter<'a>> {
+    pub fn iter<'a>(self, content: &'a Arc<dyn SourceContent>) -> Box<CharIter<'a>> {
         content.iter_from(self.offset)
     }
 
@@ -441,6 +448,15 @@ impl fmt::Debug for Location {
     }
 }
 
+impl Serialize for Location {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut st = s.serialize_struct("Location", 2)?;
+        st.serialize_field("source", &self.source)?;
+        st.serialize_field("offset", &self.offset)?;
+        st.end()
+    }
+}
+
 impl From<Location> for Span {
     fn from(l: Location) -> Span {
         Span::new(l.source, l.offset, l.offset)
@@ -511,7 +527,7 @@ impl Span {
 
     /// Obtain an iterator over the extract of the source file describe by this
     /// span.
-    pub fn iter<'a>(self, content: &'a Rc<dyn SourceContent>) -> Box<CharIter<'a>> {
+    pub fn iter<'a>(self, content: &'a Arc<dyn SourceContent>) -> Box<CharIter<'a>> {
         content.extract_iter(self.begin, self.end)
     }
 }
@@ -522,6 +538,16 @@ impl fmt::Debug for Span {
     }
 }
 
+impl Serialize for Span {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut st = s.serialize_struct("Span", 3)?;
+        st.serialize_field("source", &self.source)?;
+        st.serialize_field("begin", &self.begin)?;
+        st.serialize_field("end", &self.end)?;
+        st.end()
+    }
+}
+
 /// A wrapper that associates a span with a value.
 #[derive(PartialOrd, Ord, PartialEq, Eq)]
 pub struct Spanned<T> {
@@ -573,6 +599,18 @@ where
     }
 }
 
+impl<T> Serialize for Spanned<T>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut st = s.serialize_struct("Spanned", 2)?;
+        st.serialize_field("value", &self.value)?;
+        st.serialize_field("span", &self.span)?;
+        st.end()
+    }
+}
+
 impl<T> Copy for Spanned<T> where T: Copy {}
 
 impl<T> Clone for Spanned<T>
@@ -612,7 +650,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "unknown source file")]
     fn unknown_source_id() {
-        get_source_manager().with(Source(1), |_| ());
+        get_source_manager().with(Source(u32::MAX), |_| ());
     }
 
     #[test]
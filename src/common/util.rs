@@ -46,3 +46,96 @@ where
         self.value.desc()
     }
 }
+
+/// Compute the edit distance between two strings.
+///
+/// This is the minimum number of characters that need to be inserted, removed,
+/// or substituted, or pairs of adjacent characters that need to be swapped, to
+/// turn one string into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, x) in d[0].iter_mut().enumerate() {
+        *x = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Find the candidate most similar to a name, to suggest it in place of a name
+/// that does not exist.
+///
+/// Candidates that differ from the name only in case are preferred. Otherwise
+/// the candidate with the smallest edit distance is returned, as long as the
+/// distance is at most a third of the name's length, and smaller than the
+/// length itself. Returns `None` if no candidate is similar enough.
+pub fn find_similar<S: AsRef<str>>(
+    name: &str,
+    candidates: impl IntoIterator<Item = S>,
+) -> Option<S> {
+    let len = name.chars().count();
+    let limit = std::cmp::min(std::cmp::max(len / 3, 1), len.saturating_sub(1));
+    let lower = name.to_lowercase();
+    let mut best: Option<(usize, S)> = None;
+    for candidate in candidates {
+        let c = candidate.as_ref();
+        if c == name {
+            continue;
+        }
+        let dist = if c.to_lowercase() == lower {
+            0
+        } else {
+            edit_distance(name, c)
+        };
+        let better = match best {
+            Some((best_dist, ref best)) => {
+                dist < best_dist || (dist == best_dist && c < best.as_ref())
+            }
+            None => true,
+        };
+        if dist <= limit && better {
+            best = Some((dist, candidate));
+        }
+    }
+    best.map(|(_, x)| x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("input", "input"), 0);
+        assert_eq!(edit_distance("inptu", "input"), 1);
+        assert_eq!(edit_distance("ouput", "output"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn similar_names() {
+        let names = ["fifo_ctrl", "fifo_data", "Clk", "rst"];
+        assert_eq!(find_similar("fifo_ctr", names), Some("fifo_ctrl"));
+        assert_eq!(find_similar("fifo_dat", names), Some("fifo_data"));
+        assert_eq!(find_similar("clk", names), Some("Clk"));
+        assert_eq!(find_similar("rst", names), None);
+        assert_eq!(find_similar("foo", names), None);
+        assert_eq!(find_similar("x", ["y"]), None);
+    }
+}
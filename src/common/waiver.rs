@@ -0,0 +1,289 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Waivers that suppress known warnings.
+//!
+//! A waiver file lists warnings that are not reported, such that the checks of
+//! the compiler can be adopted incrementally in a code base with many existing
+//! findings. Every line of the file holds one waiver of the form
+//!
+//! ```text
+//! <code> <file>[:<line>[-<line>]]
+//! hash <fingerprint>
+//! ```
+//!
+//! The first form waives the warnings with a code or warning group in the files
+//! whose path matches a pattern, optionally only on a line or range of lines.
+//! In the code and the pattern, `*` matches any sequence of characters and `?`
+//! any single character. The second form waives a single warning by its
+//! fingerprint, which is computed from the warning's code, message, file, and
+//! the text of the line it points at, such that it does not change if lines are
+//! added or removed elsewhere in the file. Everything after a `#` is a comment.
+//!
+//! A baseline file contains a fingerprint waiver for every warning reported
+//! during a compilation, and can be passed as a waiver file in later runs.
+
+use crate::{
+    errors::{print_diag, DiagBuilder2, DiagSegment},
+    source::Span,
+};
+use once_cell::sync::Lazy;
+use std::{path::PathBuf, sync::Mutex};
+
+/// A set of waivers.
+#[derive(Debug, Default)]
+pub struct Waivers {
+    waivers: Vec<Waiver>,
+}
+
+/// A single waiver.
+#[derive(Debug)]
+enum Waiver {
+    /// Waive warnings by code and location.
+    Pattern {
+        code: String,
+        file: String,
+        lines: Option<(usize, usize)>,
+    },
+    /// Waive the warning with a fingerprint.
+    Hash(u64),
+}
+
+impl Waivers {
+    /// Parse the contents of a waiver file and add its waivers to the set.
+    ///
+    /// Returns the line number and a description of the first malformed line
+    /// as an error.
+    pub fn parse(&mut self, text: &str) -> Result<(), (usize, String)> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap();
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let waiver = match fields[..] {
+                [] => continue,
+                ["hash", hash] => match u64::from_str_radix(hash, 16) {
+                    Ok(x) => Waiver::Hash(x),
+                    Err(_) => return Err((index + 1, format!("`{}` is not a fingerprint", hash))),
+                },
+                [code, location] => {
+                    let (file, lines) = match parse_location(location) {
+                        Some(x) => x,
+                        None => {
+                            return Err((index + 1, format!("`{}` is not a location", location)))
+                        }
+                    };
+                    Waiver::Pattern {
+                        code: code.to_string(),
+                        file: file.to_string(),
+                        lines,
+                    }
+                }
+                _ => {
+                    return Err((
+                        index + 1,
+                        "expected `<code> <file>[:<line>]` or `hash <fingerprint>`".to_string(),
+                    ))
+                }
+            };
+            self.waivers.push(waiver);
+        }
+        Ok(())
+    }
+
+    /// Check whether a warning is waived.
+    pub fn is_waived(&self, diag: &DiagBuilder2) -> bool {
+        if self.waivers.is_empty() {
+            return false;
+        }
+        let span = match primary_span(diag) {
+            Some(x) => x,
+            None => return false,
+        };
+        let path = span.source.get_path();
+        let line = span.begin().human_line();
+        let hash = fingerprint(diag, span);
+        self.waivers.iter().any(|waiver| match *waiver {
+            Waiver::Pattern {
+                ref code,
+                ref file,
+                lines,
+            } => {
+                [diag.get_code(), diag.get_group()]
+                    .iter()
+                    .flatten()
+                    .any(|x| matches_pattern(code, x))
+                    && matches_pattern(file, &path)
+                    && lines.map(|(a, b)| a <= line && line <= b).unwrap_or(true)
+            }
+            Waiver::Hash(x) => x == hash,
+        })
+    }
+}
+
+/// Parse a location of the form `<file>[:<line>[-<line>]]`.
+fn parse_location(location: &str) -> Option<(&str, Option<(usize, usize)>)> {
+    let (file, lines) = match location.rfind(':') {
+        Some(index) => (&location[..index], &location[index + 1..]),
+        None => return Some((location, None)),
+    };
+    let mut bounds = lines.splitn(2, '-');
+    let first = bounds.next()?.parse().ok()?;
+    let last = match bounds.next() {
+        Some(x) => x.parse().ok()?,
+        None => first,
+    };
+    Some((file, Some((first, last))))
+}
+
+/// Check whether a string matches a pattern, in which `*` matches any sequence
+/// of characters and `?` any single character.
+fn matches_pattern(pattern: &str, s: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = s.chars().collect();
+    let (mut p, mut i) = (0, 0);
+    let mut backtrack = None;
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, i));
+            p += 1;
+        } else if let Some((bp, bi)) = backtrack {
+            p = bp + 1;
+            i = bi + 1;
+            backtrack = Some((bp, bi + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Determine the span a diagnostic primarily points at.
+fn primary_span(diag: &DiagBuilder2) -> Option<Span> {
+    diag.get_segments().iter().find_map(|seg| match *seg {
+        DiagSegment::Span(x) | DiagSegment::Label(x, _) => Some(x),
+        _ => None,
+    })
+}
+
+/// Compute the fingerprint of a diagnostic.
+///
+/// This is a 64 bit FNV-1a hash, which unlike the hashers of the standard
+/// library is guaranteed to be the same across compiler versions.
+fn fingerprint(diag: &DiagBuilder2, span: Span) -> u64 {
+    let content = span.source.get_content();
+    let lines = content.lines();
+    let index = lines.partition_point(|&x| x <= span.begin) - 1;
+    let end = lines
+        .get(index + 1)
+        .cloned()
+        .unwrap_or(content.bytes().len());
+    let text = content.extract(lines[index], end);
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let parts = [
+        diag.get_code().or(diag.get_group()).unwrap_or(""),
+        diag.get_message(),
+        &span.source.get_path(),
+        text.trim(),
+    ];
+    for part in parts.iter() {
+        for &byte in part.as_bytes().iter().chain(Some(&0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// The path of a baseline file being written, and its entries.
+type Baseline = Option<(PathBuf, Vec<String>)>;
+
+/// The baseline file being written, and its entries.
+static BASELINE: Lazy<Mutex<Baseline>> = Lazy::new(Default::default);
+
+/// Start recording the reported warnings in a baseline file.
+///
+/// The file is written by `flush_diags`.
+pub fn start_baseline(path: PathBuf) {
+    *BASELINE.lock().unwrap() = Some((path, vec![]));
+}
+
+/// Record a reported warning in the baseline, if one is being written.
+pub(crate) fn record_baseline(diag: &DiagBuilder2) {
+    let mut baseline = BASELINE.lock().unwrap();
+    let entries = match *baseline {
+        Some((_, ref mut x)) => x,
+        None => return,
+    };
+    let span = match primary_span(diag) {
+        Some(x) => x,
+        None => return,
+    };
+    let (line, col, _) = span.begin().human();
+    let entry = format!(
+        "hash {:016x}  # {}:{}:{}: {}",
+        fingerprint(diag, span),
+        span.source.get_path(),
+        line,
+        col,
+        diag.get_message().lines().next().unwrap_or("")
+    );
+    if !entries.contains(&entry) {
+        entries.push(entry);
+    }
+}
+
+/// Write the baseline file, if one is being recorded.
+pub(crate) fn write_baseline() {
+    let (path, entries) = match BASELINE.lock().unwrap().take() {
+        Some(x) => x,
+        None => return,
+    };
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&entry);
+        text.push('\n');
+    }
+    if let Err(e) = std::fs::write(&path, text) {
+        print_diag(&DiagBuilder2::error(format!(
+            "unable to write baseline file `{}`: {}",
+            path.display(),
+            e
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("*", "foo.sv"));
+        assert!(matches_pattern("src/*.sv", "src/foo.sv"));
+        assert!(matches_pattern("SV000?", "SV0002"));
+        assert!(matches_pattern("*/legacy/*", "rtl/legacy/old.sv"));
+        assert!(!matches_pattern("src/*.sv", "src/foo.v"));
+        assert!(!matches_pattern("latch", "latches"));
+    }
+
+    #[test]
+    fn locations() {
+        assert_eq!(parse_location("foo.sv"), Some(("foo.sv", None)));
+        assert_eq!(
+            parse_location("foo.sv:12"),
+            Some(("foo.sv", Some((12, 12))))
+        );
+        assert_eq!(parse_location("foo.sv:3-7"), Some(("foo.sv", Some((3, 7)))));
+        assert_eq!(parse_location("foo.sv:x"), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut waivers = Waivers::default();
+        assert!(waivers.parse("# comment\nlatch *.sv\nhash 00ff\n").is_ok());
+        assert_eq!(waivers.waivers.len(), 2);
+        assert_eq!(waivers.parse("\nlatch").unwrap_err().0, 2);
+        assert_eq!(waivers.parse("hash xyz").unwrap_err().0, 1);
+    }
+}
@@ -54,6 +54,7 @@ pub(crate) fn derive_query_db(input: TokenStream) -> TokenStream {
     let mut caches = vec![];
     let mut tags = vec![];
     let mut tag_debugs = vec![];
+    let mut tag_names = vec![];
     let mut keys = vec![];
 
     for raw_query in &queries {
@@ -181,6 +182,9 @@ pub(crate) fn derive_query_db(input: TokenStream) -> TokenStream {
         tag_debugs.push(quote! {
             QueryTag::#tag_name (x) => write!(f, "{}{:?}", stringify!(#name), x),
         });
+        tag_names.push(quote! {
+            QueryTag::#tag_name (_) => stringify!(#name),
+        });
 
         // Render the query for the database trait.
         funcs.push(quote! {
@@ -205,6 +209,7 @@ pub(crate) fn derive_query_db(input: TokenStream) -> TokenStream {
                     self.handle_cycle();
                     // The above never returns.
                 }
+                self.start_query(&query_tag);
 
                 // Execute the query.
                 #[allow(deprecated)]
@@ -262,6 +267,10 @@ pub(crate) fn derive_query_db(input: TokenStream) -> TokenStream {
             /// Called before a query is executed.
             fn before_query(&self, tag: &QueryTag #lts) {}
 
+            /// Called when a query is not served from the cache and starts
+            /// executing.
+            fn start_query(&self, tag: &QueryTag #lts) {}
+
             /// Called after a query is executed.
             fn after_query(&self, tag: &QueryTag #lts) {}
 
@@ -291,6 +300,15 @@ pub(crate) fn derive_query_db(input: TokenStream) -> TokenStream {
             #(#tags)*
         }
 
+        impl #lts QueryTag #lts {
+            /// Get the name of the query.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#tag_names)*
+                }
+            }
+        }
+
         impl #lts std::fmt::Debug for QueryTag #lts {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
                 match self {
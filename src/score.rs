@@ -6,15 +6,18 @@
 //! additional compilation steps are initiated. This enables on-demand
 //! compilation.
 
+use crate::common::design_units::{DesignUnit, DesignUnitKind};
 use crate::common::errors::*;
+use crate::common::foreign::ForeignLang;
 use crate::common::name::Name;
 use crate::common::score::{GenericContext, NodeMaker, NodeRef, Result};
 use crate::common::source::Spanned;
 use crate::common::NodeId;
 use crate::common::Session;
-use crate::svlog::{self, ast as svlog_ast, Context};
+use crate::svlog::{self, ast as svlog_ast, ast::AcceptVisitor as _, Context};
 use crate::vhdl;
 use crate::vhdl::syntax::ast as vhdl_ast;
+use serde::Serialize;
 use std;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -118,6 +121,119 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         id
     }
 
+    /// Make the VHDL entities of a library available for instantiation from
+    /// SystemVerilog.
+    ///
+    /// Only entities instantiated under a name that no SystemVerilog module or
+    /// interface declares are described, such that the other VHDL design units
+    /// are still analyzed on demand.
+    pub fn add_foreign_units(&self, lib: LibRef) -> Result<()> {
+        let mut targets = InstTargetCollector(HashSet::new());
+        for root in self.svlog.roots() {
+            root.accept(&mut targets);
+        }
+        let declared: HashSet<Name> = self
+            .svlog
+            .modules()
+            .chain(self.svlog.interfaces())
+            .map(|(name, _)| name)
+            .collect();
+        let mut names: Vec<Name> = targets.0.difference(&declared).cloned().collect();
+        if names.is_empty() {
+            return Ok(());
+        }
+        names.sort();
+
+        let defs = self.defs(lib.into())?;
+        let mut failed = false;
+        for name in names {
+            if let Some(&Def::Vhdl(vhdl::score::Def::Entity(entity))) = defs.get(&name) {
+                match self.vhdl().foreign_unit(entity) {
+                    Ok(unit) => self.sess.units.set_interface(entity.into(), unit),
+                    Err(()) => failed = true,
+                }
+            }
+        }
+        if failed {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Make the SystemVerilog modules of a library available for instantiation
+    /// from VHDL.
+    ///
+    /// Only modules instantiated under a name that no VHDL entity declares are
+    /// described. Their stand-in entities are allocated in `arena` and added to
+    /// the library, which must not have been analyzed yet.
+    pub fn add_foreign_modules(
+        &self,
+        lib: LibRef,
+        arena: &'ast Arena<Vec<vhdl_ast::DesignUnit>>,
+    ) -> Result<()> {
+        let (lib_name, asts) = self.sb.libs.borrow()[&lib];
+        let mut targets = HashSet::new();
+        let mut declared = HashSet::new();
+        for ast in asts {
+            if let Ast::Vhdl(ref units) = *ast {
+                vhdl::foreign::inst_target_names(units, &mut targets);
+                declared.extend(units.iter().filter_map(|unit| match unit.data {
+                    vhdl_ast::DesignUnitData::EntityDecl(ref decl) => Some(decl.name.value),
+                    _ => None,
+                }));
+            }
+        }
+        let mut names: Vec<Name> = targets.difference(&declared).cloned().collect();
+        names.sort();
+
+        let mut failed = false;
+        for name in names {
+            let id = match self
+                .sess
+                .units
+                .find(Some(lib_name), name)
+                .into_iter()
+                .find(|unit| unit.lang == ForeignLang::Svlog && unit.kind == DesignUnitKind::Module)
+            {
+                Some(unit) => unit.id,
+                None => continue,
+            };
+            let unit = match svlog::foreign::foreign_unit(self.svlog, id) {
+                Ok(unit) => unit,
+                Err(()) => {
+                    failed = true;
+                    continue;
+                }
+            };
+            match vhdl::foreign::stand_in(&unit) {
+                Ok(stand_in) => {
+                    self.sess.units.add_crossing(vhdl::foreign::crossing(&unit));
+                    self.vhdl().add_foreign_entity(
+                        vhdl::score::LibRef::new(lib.into()),
+                        unit,
+                        arena.alloc(stand_in),
+                    )
+                }
+                Err(()) => {
+                    self.sess.emit(
+                        DiagBuilder2::bug(format!(
+                            "stand-in for {} unit `{}` failed to parse",
+                            unit.lang, unit.name
+                        ))
+                        .span(unit.span),
+                    );
+                    failed = true;
+                }
+            }
+        }
+        if failed {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Obtain the definitions in a scope. Calculate them if needed.
     pub fn defs(&self, id: ScopeRef) -> Result<&'ctx Defs> {
         if let Some(&node) = self.sb.defs.borrow().get(&id) {
@@ -175,6 +291,17 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ScopeRef, &'ctx Defs>
                     println!("[SB] vhdl_sb returned {:?}", vhdl);
                 }
 
+                // Ask the design unit registry for the modules in this library.
+                let svlog_modules: Vec<DesignUnit> = self
+                    .sess
+                    .units
+                    .units_in(lib.0)
+                    .into_iter()
+                    .filter(|unit| {
+                        unit.lang == ForeignLang::Svlog && unit.kind == DesignUnitKind::Module
+                    })
+                    .collect();
+
                 // Build a union of the names defined by the above scoreboards.
                 // Then determine the actual definition for each name, and throw
                 // an error if multiple definitions are encountered.
@@ -184,7 +311,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ScopeRef, &'ctx Defs>
                         vhdl::score::ResolvableName::Ident(n) => Some(n),
                         _ => None,
                     })
-                    .chain(self.svlog.modules().map(|(k, _)| k))
+                    .chain(svlog_modules.iter().map(|unit| unit.name))
                     .collect();
                 debug!("names defined in library: {:?}", names);
 
@@ -195,12 +322,18 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ScopeRef, &'ctx Defs>
                         Some(v) => v.iter(),
                         None => [].iter(),
                     };
-                    let svlog_defs = self.svlog.find_module(name.into());
+                    let svlog_defs = svlog_modules.iter().filter(|unit| unit.name == name);
+                    // The stand-ins for SystemVerilog modules instantiated
+                    // from VHDL are no definitions of their own.
                     let both_defs: Vec<Spanned<Def>> = vhdl_defs
+                        .filter(|d| match d.value {
+                            vhdl::score::Def::Entity(id) => {
+                                self.vhdl().foreign_entity(id).is_none()
+                            }
+                            _ => true,
+                        })
                         .map(|d| Spanned::new(Def::Vhdl(d.value), d.span))
-                        .chain(
-                            svlog_defs.map(|id| Spanned::new(Def::Svlog(id), self.svlog.span(id))),
-                        )
+                        .chain(svlog_defs.map(|unit| Spanned::new(Def::Svlog(unit.id), unit.span)))
                         .collect();
 
                     if both_defs.len() > 1 {
@@ -247,7 +380,8 @@ impl Arenas {
 
 /// Roots for every AST that we support. During parsing, a list of these entries
 /// is generated that is then passed to the `ScoreBoard` as a reference.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "language", content = "ast", rename_all = "lowercase")]
 pub enum Ast<'a> {
     Vhdl(Vec<vhdl_ast::DesignUnit>),
     Svlog(svlog_ast::SourceFile<'a>),
@@ -256,6 +390,16 @@ pub enum Ast<'a> {
 /// The definitions in a scope.
 pub type Defs = HashMap<Name, Def>;
 
+/// A visitor that collects the names of all instantiated SystemVerilog modules.
+pub struct InstTargetCollector(pub HashSet<Name>);
+
+impl<'a> svlog_ast::Visitor<'a> for InstTargetCollector {
+    fn pre_visit_inst(&mut self, node: &'a svlog_ast::Inst<'a>) -> bool {
+        self.0.insert(node.target.value);
+        true
+    }
+}
+
 // Declare some node references.
 node_ref!(RootRef);
 node_ref!(LibRef);
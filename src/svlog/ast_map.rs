@@ -77,6 +77,8 @@ pub enum AstNode<'ast> {
     GenFor(&'ast ast::GenerateFor<'ast>),
     /// A case-generate statement.
     GenCase(&'ast ast::GenerateCase<'ast>),
+    /// A block of a generate statement.
+    GenBlock(&'ast ast::GenerateBlock<'ast>),
     /// A genvar declaration.
     GenvarDecl(&'ast ast::GenvarDecl<'ast>),
     /// A typedef.
@@ -132,6 +134,7 @@ impl<'a> AstNode<'a> {
             AstNode::GenIf(x) => Some(x),
             AstNode::GenFor(x) => Some(x),
             AstNode::GenCase(x) => Some(x),
+            AstNode::GenBlock(x) => Some(x),
             AstNode::GenvarDecl(x) => Some(x),
             AstNode::Typedef(x) => Some(x),
             AstNode::ContAssign(x, _, _) => Some(x),
@@ -172,15 +175,18 @@ impl<'a> AstNode<'a> {
                 ))
                 .into_iter(),
             ),
-            AllNode::VarDecl(x) => {
-                Box::new(x.names.iter().map(move |n| AstNode::VarDecl(n, x, n.id())))
-            }
+            AllNode::VarDecl(x) => Box::new(
+                x.names
+                    .iter()
+                    .map(move |n| AstNode::VarDecl(n, x, x.ty.id())),
+            ),
             // AllNode::NetDecl(x) => x.names.iter().map(|n| AstNode::NetDecl(n, x, n.id())),
             AllNode::Procedure(x) => Box::new(Some(AstNode::Proc(x)).into_iter()),
             AllNode::Stmt(x) => Box::new(Some(AstNode::Stmt(x)).into_iter()),
             AllNode::GenerateIf(x) => Box::new(Some(AstNode::GenIf(x)).into_iter()),
             AllNode::GenerateFor(x) => Box::new(Some(AstNode::GenFor(x)).into_iter()),
             AllNode::GenerateCase(x) => Box::new(Some(AstNode::GenCase(x)).into_iter()),
+            AllNode::GenerateBlock(x) => Box::new(Some(AstNode::GenBlock(x)).into_iter()),
             AllNode::GenvarDecl(x) => Box::new(Some(AstNode::GenvarDecl(x)).into_iter()),
             AllNode::Typedef(x) => Box::new(Some(AstNode::Typedef(x)).into_iter()),
             AllNode::ContAssign(x) => Box::new(
@@ -226,6 +232,7 @@ impl<'ast> HasSpan for AstNode<'ast> {
             AstNode::GenIf(x) => x.span(),
             AstNode::GenFor(x) => x.span(),
             AstNode::GenCase(x) => x.span(),
+            AstNode::GenBlock(x) => x.span(),
             AstNode::GenvarDecl(x) => x.span(),
             AstNode::Typedef(x) => x.span(),
             AstNode::ContAssign(x, _, _) => x.span(),
@@ -259,6 +266,7 @@ impl<'ast> HasSpan for AstNode<'ast> {
             AstNode::GenIf(x) => x.human_span(),
             AstNode::GenFor(x) => x.human_span(),
             AstNode::GenCase(x) => x.human_span(),
+            AstNode::GenBlock(x) => x.human_span(),
             AstNode::GenvarDecl(x) => x.human_span(),
             AstNode::Typedef(x) => x.human_span(),
             AstNode::ContAssign(x, _, _) => x.human_span(),
@@ -295,6 +303,7 @@ impl<'ast> HasDesc for AstNode<'ast> {
             AstNode::GenIf(x) => "if-generate statement",
             AstNode::GenFor(x) => "for-generate statement",
             AstNode::GenCase(x) => "case-generate statement",
+            AstNode::GenBlock(x) => "generate block",
             AstNode::GenvarDecl(x) => "genvar",
             AstNode::Typedef(x) => "typedef",
             AstNode::ContAssign(x, _, _) => "continuous assignment",
@@ -328,6 +337,7 @@ impl<'ast> HasDesc for AstNode<'ast> {
             AstNode::GenIf(x) => x.to_definite_string(),
             AstNode::GenFor(x) => x.to_definite_string(),
             AstNode::GenCase(x) => x.to_definite_string(),
+            AstNode::GenBlock(x) => x.to_definite_string(),
             AstNode::GenvarDecl(x) => x.to_definite_string(),
             AstNode::Typedef(x) => x.to_definite_string(),
             AstNode::ContAssign(x, _, _) => x.to_definite_string(),
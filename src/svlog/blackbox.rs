@@ -0,0 +1,159 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Blackbox stand-ins for unresolved modules.
+//!
+//! With the `--allow-blackbox` option, instantiating a module that does not
+//! exist is not an error. Instead, an empty module is synthesized with a
+//! parameter for every parameter assignment and an input port for every port
+//! connection of the instantiation, with the port types inferred from the
+//! connected expressions. This allows partial designs and designs that use
+//! vendor macros to be elaborated.
+
+use crate::{
+    common::{arenas::Alloc, source::get_source_manager},
+    crate_prelude::*,
+    lexer::Lexer,
+    parser,
+    preproc::Preprocessor,
+    resolver::DefNode,
+    ty::UnpackedType,
+};
+
+/// Create or look up the blackbox module for an instantiation of an unknown
+/// module.
+pub(crate) fn blackbox_module<'a>(
+    cx: &impl Context<'a>,
+    inst: &'a ast::Inst<'a>,
+) -> Result<&'a ast::Module<'a>> {
+    // Use the same stand-in for all instantiations of a module.
+    let name = inst.target.value;
+    if let Some(id) = cx.gcx().find_module(name) {
+        if let Some(module) = cx.ast_for_id(id).as_all().get_module() {
+            return Ok(module);
+        }
+    }
+    let env = cx.default_param_env();
+
+    // Declare a parameter for every parameter assignment.
+    let mut params = vec![];
+    for (index, param) in inst.params.iter().enumerate() {
+        let param_name = match param.name {
+            Some(ident) => ident.name.to_string(),
+            None => format!("param{}", index),
+        };
+        params.push(match param.expr {
+            ast::TypeOrExpr::Type(_) => format!("parameter type {} = logic", param_name),
+            ast::TypeOrExpr::Expr(_) => format!("parameter {} = 0", param_name),
+        });
+    }
+
+    // Declare an input port for every port connection of the first instance.
+    let mut ports = vec![];
+    let loc = cx.scope_location(inst);
+    let conns = inst
+        .names
+        .first()
+        .map(|n| n.conns.as_slice())
+        .unwrap_or(&[]);
+    for (index, conn) in conns.iter().enumerate() {
+        let (port_name, ty) = match conn.data {
+            ast::PortConnData::Auto => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "ports of blackbox `{}` cannot be inferred from `.*`",
+                        name
+                    ))
+                    .code("SV0047")
+                    .span(conn.span()),
+                );
+                return Err(());
+            }
+            ast::PortConnData::Named(port_name, ast::PortConnMode::Auto) => {
+                let ty = match cx.resolve_local(port_name.value, loc, false)? {
+                    Some(def) => match def.node {
+                        DefNode::Ast(node) => Some(cx.type_of(node.id(), env)?),
+                        DefNode::IntPort(port) => Some(cx.type_of_int_port(Ref(port), env)),
+                    },
+                    None => None,
+                };
+                (port_name.value.to_string(), ty)
+            }
+            ast::PortConnData::Named(port_name, ast::PortConnMode::Unconnected) => {
+                (port_name.value.to_string(), None)
+            }
+            ast::PortConnData::Named(port_name, ast::PortConnMode::Connected(ref expr)) => (
+                port_name.value.to_string(),
+                cx.self_determined_type(expr.id(), env),
+            ),
+            ast::PortConnData::Positional(ref expr) => (
+                format!("port{}", index),
+                cx.self_determined_type(expr.id(), env),
+            ),
+        };
+        ports.push(format!(
+            "input {} {}",
+            port_type(cx, ty, conn.span())?,
+            port_name
+        ));
+    }
+
+    // Parse the stand-in and add it to the design.
+    let mut text = format!("module {}", name);
+    if !params.is_empty() {
+        text.push_str(&format!(" #({})", params.join(", ")));
+    }
+    if !ports.is_empty() {
+        text.push_str(&format!(" ({})", ports.join(", ")));
+    }
+    text.push_str(";\nendmodule\n");
+    let source = get_source_manager().add(&format!("<blackbox {}>", name), &text);
+    let preproc = Preprocessor::new(source, &[], &[]);
+    let file = match parser::parse(Lexer::new(preproc), &cx.arena().ast) {
+        Ok(x) => cx.arena().alloc(x),
+        Err(()) => bug_span!(inst.target.span, cx, "blackbox `{}` failed to parse", name),
+    };
+    cx.gcx().add_files(Some(&*file).into_iter());
+    let module = match file.items.first().map(|item| &item.data) {
+        Some(ast::ItemData::ModuleDecl(ref x)) => x,
+        _ => unreachable!(),
+    };
+
+    let mut d = DiagBuilder2::warning(format!(
+        "module `{}` not found; instantiating an empty blackbox",
+        name
+    ))
+    .group("blackbox")
+    .span(inst.target.span);
+    if !ports.is_empty() {
+        d = d.add_note(format!(
+            "The blackbox has ports inferred from this instantiation: {}",
+            ports.join(", ")
+        ));
+    }
+    cx.emit(d);
+    Ok(module)
+}
+
+/// Determine the type of a blackbox port from the connected expression.
+fn port_type<'a>(
+    cx: &impl Context<'a>,
+    ty: Option<&'a UnpackedType<'a>>,
+    span: Span,
+) -> Result<String> {
+    let ty = match ty {
+        Some(ty) if ty.is_error() => return Err(()),
+        Some(ty) => ty,
+        None => return Ok("logic".to_string()),
+    };
+    match ty.get_simple_bit_vector() {
+        Some(sbv) => Ok(sbv.to_string()),
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("blackbox port of type `{}` cannot be inferred", ty))
+                    .code("SV0047")
+                    .span(span),
+            );
+            Err(())
+        }
+    }
+}
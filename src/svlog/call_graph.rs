@@ -0,0 +1,449 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Call graph of the tasks and functions in the input.
+//!
+//! The graph has a node for every task, function, and DPI import declared in
+//! the input, and for every module, interface, package, and class that calls
+//! one of them outside of a subroutine. Calls by plain name and through a
+//! package scope, as in `pkg::f()`, are resolved to the called declaration.
+//! Subroutines that are part of a cycle in the graph are marked as recursive,
+//! and subroutines that are never called as unused, which helps spotting
+//! unintended recursion and dead code. The graph can be written as a Graphviz
+//! graph or as JSON.
+
+use crate::{ast::AcceptVisitor as _, crate_prelude::*, dot::quote, resolver::DefNode};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{Result as IoResult, Write};
+
+/// A call graph.
+#[derive(Debug, Default, Serialize)]
+pub struct CallGraph {
+    /// The subroutines and calling units, in the order they appear in the
+    /// input.
+    pub nodes: Vec<CallNode>,
+    /// The calls between the nodes, in the order they first appear in the
+    /// input.
+    pub edges: Vec<CallEdge>,
+}
+
+/// A subroutine or a unit calling one.
+#[derive(Debug, Serialize)]
+pub struct CallNode {
+    /// The name, qualified with the enclosing units, as in `top.f` or
+    /// `pkg::f`.
+    pub name: String,
+    /// What the node is.
+    pub kind: CallNodeKind,
+    /// The lifetime of a task or function, either as declared or inherited
+    /// from the enclosing unit. `None` for units and DPI imports.
+    pub lifetime: Option<CallLifetime>,
+    /// Whether this is a task or function imported through DPI.
+    pub dpi: bool,
+    /// Whether the subroutine calls itself, directly or through others.
+    pub recursive: bool,
+    /// Whether the subroutine is called anywhere in the input.
+    pub called: bool,
+    /// The path of the file the node is declared in.
+    pub file: String,
+    /// The 1-based line the node is declared on.
+    pub line: usize,
+}
+
+/// The things a node of a call graph may denote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallNodeKind {
+    /// A function.
+    Function,
+    /// A task.
+    Task,
+    /// A module.
+    Module,
+    /// An interface.
+    Interface,
+    /// A package.
+    Package,
+    /// A class.
+    Class,
+}
+
+impl CallNodeKind {
+    /// Check whether this is a task or function.
+    pub fn is_subroutine(self) -> bool {
+        match self {
+            CallNodeKind::Function | CallNodeKind::Task => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for CallNodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CallNodeKind::Function => write!(f, "function"),
+            CallNodeKind::Task => write!(f, "task"),
+            CallNodeKind::Module => write!(f, "module"),
+            CallNodeKind::Interface => write!(f, "interface"),
+            CallNodeKind::Package => write!(f, "package"),
+            CallNodeKind::Class => write!(f, "class"),
+        }
+    }
+}
+
+/// The lifetime of a task or function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallLifetime {
+    /// The variables of the subroutine are shared by all calls.
+    Static,
+    /// Every call gets its own variables.
+    Automatic,
+}
+
+impl std::fmt::Display for CallLifetime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CallLifetime::Static => write!(f, "static"),
+            CallLifetime::Automatic => write!(f, "automatic"),
+        }
+    }
+}
+
+/// The calls from one node of a call graph to another.
+#[derive(Debug, Serialize)]
+pub struct CallEdge {
+    /// The calling node, as an index into `nodes`.
+    pub caller: usize,
+    /// The called node, as an index into `nodes`.
+    pub callee: usize,
+    /// The number of call sites.
+    pub calls: usize,
+}
+
+impl CallGraph {
+    /// Collect the call graph of all files of the input.
+    pub fn new<'a>(cx: &impl Context<'a>) -> Self {
+        let mut collector = Collector {
+            cx,
+            graph: Default::default(),
+            ids: HashMap::new(),
+            stack: vec![],
+        };
+        for root in cx.gcx().roots() {
+            root.accept(&mut collector);
+        }
+        let mut graph = collector.graph;
+        for index in 0..graph.nodes.len() {
+            graph.nodes[index].recursive = graph.reaches(index, index);
+            graph.nodes[index].called = graph.edges.iter().any(|e| e.callee == index);
+        }
+        graph
+    }
+
+    /// Check whether a node calls another, directly or through others.
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut todo = vec![from];
+        while let Some(node) = todo.pop() {
+            for edge in self.edges.iter().filter(|e| e.caller == node) {
+                if edge.callee == to {
+                    return true;
+                }
+                if !seen[edge.callee] {
+                    seen[edge.callee] = true;
+                    todo.push(edge.callee);
+                }
+            }
+        }
+        false
+    }
+
+    /// Write the call graph as a Graphviz graph.
+    ///
+    /// Units are drawn as boxes, DPI imports as hexagons, unused subroutines
+    /// dashed, and recursive subroutines in red.
+    pub fn write_dot(&self, w: &mut impl Write) -> IoResult<()> {
+        writeln!(w, "digraph calls {{")?;
+        for (index, node) in self.nodes.iter().enumerate() {
+            let mut label = node.name.clone();
+            let mut attrs = vec![];
+            if node.kind.is_subroutine() {
+                label.push('\n');
+                if node.dpi {
+                    label.push_str("DPI ");
+                    attrs.push("shape=hexagon");
+                }
+                label.push_str(&node.kind.to_string());
+                if let Some(lifetime) = node.lifetime {
+                    label.push_str(&format!(", {}", lifetime));
+                }
+                if !node.called {
+                    attrs.push("style=dashed");
+                }
+                if node.recursive {
+                    attrs.push("color=red");
+                }
+            } else {
+                attrs.push("shape=box");
+            }
+            write!(w, "  n{} [label={}", index, quote(&label))?;
+            for attr in attrs {
+                write!(w, ", {}", attr)?;
+            }
+            writeln!(w, "];")?;
+        }
+        for edge in &self.edges {
+            write!(w, "  n{} -> n{}", edge.caller, edge.callee)?;
+            if edge.calls > 1 {
+                write!(w, " [label=\"{}\"]", edge.calls)?;
+            }
+            writeln!(w, ";")?;
+        }
+        writeln!(w, "}}")
+    }
+
+    /// Write the call graph as JSON.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        serde_json::to_writer_pretty(&mut *w, self)?;
+        writeln!(w)
+    }
+}
+
+/// A visitor that collects the subroutines and calls in the AST.
+struct Collector<'a, 'cx, C> {
+    cx: &'cx C,
+    graph: CallGraph,
+    /// The index of the node of each declaration.
+    ids: HashMap<NodeId, usize>,
+    /// The units and subroutines enclosing the visited node.
+    stack: Vec<&'a dyn ast::AnyNode<'a>>,
+}
+
+impl<'a, 'cx, C> Collector<'a, 'cx, C>
+where
+    C: Context<'a>,
+    'a: 'cx,
+{
+    /// Find or create the node of a unit or subroutine declaration.
+    fn node(&mut self, node: &'a dyn ast::AnyNode<'a>) -> Option<usize> {
+        if let Some(&index) = self.ids.get(&node.id()) {
+            return Some(index);
+        }
+        let (name, kind, lifetime, dpi) = match node.as_all() {
+            ast::AllNode::SubroutineDecl(x) => (
+                x.prototype.name,
+                subroutine_kind(x.prototype.kind),
+                Some(match x.prototype.lifetime {
+                    Some(ref lifetime) => call_lifetime(lifetime),
+                    None => default_lifetime(node),
+                }),
+                false,
+            ),
+            ast::AllNode::DpiDecl(x) => match x.data {
+                ast::DpiDeclData::Import { ref prototype, .. } => {
+                    (prototype.name, subroutine_kind(prototype.kind), None, true)
+                }
+                ast::DpiDeclData::Export { .. } => return None,
+            },
+            ast::AllNode::Module(x) => (x.name, CallNodeKind::Module, None, false),
+            ast::AllNode::Interface(x) => (x.name, CallNodeKind::Interface, None, false),
+            ast::AllNode::Package(x) => (x.name, CallNodeKind::Package, None, false),
+            ast::AllNode::ClassDecl(x) => (x.name, CallNodeKind::Class, None, false),
+            _ => return None,
+        };
+        let loc = name.span.begin();
+        let index = self.graph.nodes.len();
+        self.graph.nodes.push(CallNode {
+            name: format!("{}{}", qualifier(node), name.value),
+            kind,
+            lifetime,
+            dpi,
+            recursive: false,
+            called: false,
+            file: loc.source.get_path().to_string(),
+            line: loc.human_line(),
+        });
+        self.ids.insert(node.id(), index);
+        Some(index)
+    }
+
+    /// Add a call to the subroutine a callee expression refers to.
+    ///
+    /// Does nothing if the callee does not resolve to a subroutine.
+    fn call(&mut self, callee: &'a ast::Expr<'a>) {
+        let cx = self.cx;
+        let def = match callee.data {
+            ast::IdentExpr(name) => cx
+                .resolve_local(name.value, cx.scope_location(callee), false)
+                .unwrap_or(None),
+            ast::ScopeExpr(ref target, name) => match target.data {
+                ast::IdentExpr(pkg_name) => cx
+                    .resolve_local(pkg_name.value, cx.scope_location(target.as_ref()), false)
+                    .unwrap_or(None)
+                    .and_then(|def| match def.node {
+                        DefNode::Ast(node) => node.as_all().get_package(),
+                        _ => None,
+                    })
+                    .and_then(|pkg| cx.resolve_namespace(name.value, pkg)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let target = match def.map(|def| def.node) {
+            Some(DefNode::Ast(node)) => match node.as_all() {
+                ast::AllNode::SubroutineDecl(_) | ast::AllNode::DpiDecl(_) => node,
+                _ => return,
+            },
+            _ => return,
+        };
+        let caller = match self.stack.last() {
+            Some(&node) => self.node(node),
+            None => None,
+        };
+        let (caller, callee) = match (caller, self.node(target)) {
+            (Some(caller), Some(callee)) => (caller, callee),
+            _ => return,
+        };
+        match self
+            .graph
+            .edges
+            .iter_mut()
+            .find(|e| e.caller == caller && e.callee == callee)
+        {
+            Some(edge) => edge.calls += 1,
+            None => self.graph.edges.push(CallEdge {
+                caller,
+                callee,
+                calls: 1,
+            }),
+        }
+    }
+}
+
+impl<'a, 'cx, C> ast::Visitor<'a> for Collector<'a, 'cx, C>
+where
+    C: Context<'a>,
+    'a: 'cx,
+{
+    fn pre_visit_module(&mut self, node: &'a ast::Module<'a>) -> bool {
+        self.stack.push(node);
+        true
+    }
+
+    fn post_visit_module(&mut self, _: &'a ast::Module<'a>) {
+        self.stack.pop();
+    }
+
+    fn pre_visit_interface(&mut self, node: &'a ast::Interface<'a>) -> bool {
+        self.stack.push(node);
+        true
+    }
+
+    fn post_visit_interface(&mut self, _: &'a ast::Interface<'a>) {
+        self.stack.pop();
+    }
+
+    fn pre_visit_package(&mut self, node: &'a ast::Package<'a>) -> bool {
+        self.stack.push(node);
+        true
+    }
+
+    fn post_visit_package(&mut self, _: &'a ast::Package<'a>) {
+        self.stack.pop();
+    }
+
+    fn pre_visit_class_decl(&mut self, node: &'a ast::ClassDecl<'a>) -> bool {
+        self.stack.push(node);
+        true
+    }
+
+    fn post_visit_class_decl(&mut self, _: &'a ast::ClassDecl<'a>) {
+        self.stack.pop();
+    }
+
+    fn pre_visit_subroutine_decl(&mut self, node: &'a ast::SubroutineDecl<'a>) -> bool {
+        self.node(node);
+        self.stack.push(node);
+        true
+    }
+
+    fn post_visit_subroutine_decl(&mut self, _: &'a ast::SubroutineDecl<'a>) {
+        self.stack.pop();
+    }
+
+    fn pre_visit_dpi_decl(&mut self, node: &'a ast::DpiDecl<'a>) -> bool {
+        self.node(node);
+        true
+    }
+
+    fn pre_visit_stmt(&mut self, node: &'a ast::Stmt<'a>) -> bool {
+        // Tasks and void functions may be called without parentheses.
+        if let ast::ExprStmt(ref expr) = node.kind {
+            if let ast::IdentExpr(_) = expr.data {
+                self.call(expr);
+            }
+        }
+        true
+    }
+
+    fn pre_visit_expr(&mut self, node: &'a ast::Expr<'a>) -> bool {
+        if let ast::CallExpr(ref callee, _) = node.data {
+            self.call(callee);
+        }
+        true
+    }
+}
+
+/// Map the kind of a subroutine to the kind of its node.
+fn subroutine_kind(kind: ast::SubroutineKind) -> CallNodeKind {
+    match kind {
+        ast::SubroutineKind::Func => CallNodeKind::Function,
+        ast::SubroutineKind::Task => CallNodeKind::Task,
+    }
+}
+
+/// Map a declared lifetime to the lifetime of a node.
+fn call_lifetime(lifetime: &ast::Lifetime) -> CallLifetime {
+    match lifetime {
+        ast::Lifetime::Static => CallLifetime::Static,
+        ast::Lifetime::Automatic => CallLifetime::Automatic,
+    }
+}
+
+/// Determine the lifetime of a subroutine declared without one.
+///
+/// Subroutines inherit the lifetime of the enclosing module, interface, or
+/// package. Class methods are always automatic.
+fn default_lifetime<'a>(node: &'a dyn ast::AnyNode<'a>) -> CallLifetime {
+    let mut next = node.get_parent();
+    while let Some(node) = next {
+        match node.as_all() {
+            ast::AllNode::Module(x) => return call_lifetime(&x.lifetime),
+            ast::AllNode::Interface(x) => return call_lifetime(&x.lifetime),
+            ast::AllNode::Package(x) => return call_lifetime(&x.lifetime),
+            ast::AllNode::ClassDecl(_) => return CallLifetime::Automatic,
+            _ => next = node.get_parent(),
+        }
+    }
+    CallLifetime::Static
+}
+
+/// Determine the prefix that qualifies the name of a node with the units it
+/// is declared in, as in `top.` or `pkg::`.
+fn qualifier<'a>(node: &'a dyn ast::AnyNode<'a>) -> String {
+    let mut parts = vec![];
+    let mut next = node.get_parent();
+    while let Some(node) = next {
+        match node.as_all() {
+            ast::AllNode::Module(x) => parts.push(format!("{}.", x.name)),
+            ast::AllNode::Interface(x) => parts.push(format!("{}.", x.name)),
+            ast::AllNode::Package(x) => parts.push(format!("{}::", x.name)),
+            ast::AllNode::ClassDecl(x) => parts.push(format!("{}::", x.name)),
+            _ => (),
+        }
+        next = node.get_parent();
+    }
+    parts.reverse();
+    parts.concat()
+}
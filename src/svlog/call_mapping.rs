@@ -101,6 +101,7 @@ pub(crate) fn call_mapping<'a>(
                 decl_args.args.len(),
                 call_args.len(),
             ))
+            .code("SV0027")
             .span(call_span),
         );
         return Arc::new(CallMapping::error(call_span));
@@ -131,6 +132,7 @@ pub(crate) fn call_mapping<'a>(
                 } else {
                     cx.emit(
                         DiagBuilder2::error(format!("unknown argument: `{}`", call_name))
+                            .code("SV0027")
                             .span(call_name.span)
                             .add_note(format!(
                                 "Subroutine `{}` was declared here:",
@@ -145,6 +147,7 @@ pub(crate) fn call_mapping<'a>(
             None if seen_named => {
                 cx.emit(
                     DiagBuilder2::error("positional argument after named")
+                        .code("SV0027")
                         .span(call_arg.span())
                         .add_note(
                             "IEEE 1800-2017 requires all positional arguments to appear before \
@@ -166,6 +169,7 @@ pub(crate) fn call_mapping<'a>(
                         .map(|x| x.to_string())
                         .unwrap_or_else(|| "<unnamed>".to_string())
                 ))
+                .code("SV0027")
                 .span(call_arg.span())
                 .add_note("Previous assignment was here:")
                 .span(previous.span()),
@@ -213,6 +217,7 @@ pub(crate) fn call_mapping<'a>(
                                 .map(|x| x.to_string())
                                 .unwrap_or_else(|| "<unnamed>".to_string())
                         ))
+                        .code("SV0027")
                         .span(call_span)
                         .add_note("Argument was declared here:")
                         .span(decl_arg.span),
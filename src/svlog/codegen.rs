@@ -5,6 +5,7 @@
 
 use crate::{
     crate_prelude::*,
+    format::{format_value, DisplayTask, FormatKind, FormatSpec, Radix},
     hir::{AccessedNode, HirNode},
     port_list::PortList,
     resolver::InstTarget,
@@ -39,6 +40,8 @@ pub struct CodeGenerator<'gcx, C> {
     into_mlir: circt::ModuleOp,
     /// Tables holding mappings and interned values.
     tables: Tables<'gcx>,
+    /// The current depth of the instance hierarchy being emitted.
+    inst_depth: usize,
 }
 
 impl<'gcx, C> CodeGenerator<'gcx, C> {
@@ -50,9 +53,18 @@ impl<'gcx, C> CodeGenerator<'gcx, C> {
             into: llhd::ir::Module::new(),
             into_mlir,
             tables: Default::default(),
+            inst_depth: 0,
         }
     }
 
+    /// Get the modules emitted so far, together with their entity names.
+    ///
+    /// Each module appears once for every distinct parameter environment it
+    /// was emitted with, in the order the emission completed.
+    pub fn emitted_modules(&self) -> &[(NodeEnvId, String)] {
+        &self.tables.module_order
+    }
+
     /// Finalize code generation and return the generated LLHD module.
     pub fn finalize(self) -> llhd::ir::Module {
         self.into
@@ -62,9 +74,15 @@ impl<'gcx, C> CodeGenerator<'gcx, C> {
 #[derive(Default)]
 struct Tables<'gcx> {
     module_defs: HashMap<NodeEnvId, Result<Rc<EmittedModule<'gcx>>>>,
+    module_order: Vec<(NodeEnvId, String)>,
     module_signatures: HashMap<NodeEnvId, (llhd::ir::UnitName, llhd::ir::Signature)>,
     interned_types: HashMap<&'gcx UnpackedType<'gcx>, Result<HybridType>>,
     function_defs: HashMap<NodeEnvId, Result<Rc<EmittedFunction>>>,
+    intrinsics: HashSet<String>,
+    /// The initial values of variables assigned during elaboration.
+    init_values: HashMap<NodeEnvId, Value<'gcx>>,
+    /// The statements of `initial` procedures performed during elaboration.
+    elab_stmts: HashSet<NodeEnvId>,
 }
 
 impl<'gcx, C> Deref for CodeGenerator<'gcx, C> {
@@ -106,6 +124,18 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         if let Some(x) = self.tables.module_defs.get(&id.env(env)) {
             return x.clone();
         }
+        let cx = self.cx;
+        cx.gcx()
+            .elaborate_specialization(id.env(env), || self.emit_specialization(id, env))
+    }
+
+    /// Emit the code for a module specialization that has not been emitted
+    /// before.
+    fn emit_specialization(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+    ) -> Result<Rc<EmittedModule<'gcx>>> {
         let hir = match self.hir_of(id)? {
             HirNode::Module(m) => m,
             _ => panic!("expected {:?} to be a module", id),
@@ -177,34 +207,14 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         }
 
         // Emit the actual contents of the entity.
-        gen.emit_module_block(id, env, &hir.block, &entity_name)?;
+        gen.emit_module_block(id, env, &hir.block, &entity_name, "")?;
 
         // Assign default values to undriven output ports.
-        for port in ports.outputs.iter() {
-            let value = gen.values[&port.accnode];
-            let driven = gen
-                .builder
-                .all_insts()
-                .any(|inst| match gen.builder[inst].opcode() {
-                    llhd::ir::Opcode::Drv => gen.builder[inst].args()[0] == value.0,
-                    llhd::ir::Opcode::Inst => gen.builder[inst].output_args().contains(&value.0),
-                    _ => false,
-                });
-            if driven {
-                continue;
-            }
-            let default_value = gen.emit_const(
-                if let Some(default) = port.default {
-                    gen.constant_value_of(default, env)
-                } else {
-                    gen.type_default_value(port.ty)
-                },
-                env,
-                port.port.span(),
-            )?;
-            let zero_time = gen.mk_const_time(&num::zero(), 1, 0);
-            gen.mk_drv(value, default_value, zero_time);
-        }
+        gen.emit_undriven_outputs(&ports.outputs, env)?;
+
+        // Check for multiply-driven variables and combinational loops.
+        crate::drivers::check_module(self.cx, id, env)?;
+        crate::comb_loops::check_module(self.cx, id, env)?;
 
         let unit = self.into.add_unit(ent);
         let result = Ok(Rc::new(EmittedModule {
@@ -213,6 +223,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             ports,
         }));
         self.tables.module_defs.insert(id.env(env), result.clone());
+        self.tables.module_order.push((id.env(env), entity_name));
         result
     }
 
@@ -239,15 +250,9 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                 // If a modport was specified, make a list of directions for
                 // each port by name.
                 let mut dirs = HashMap::new();
-                for port in intf.modport.iter().flat_map(|modport| modport.ports.iter()) {
-                    match port.data {
-                        ast::ModportPortData::Simple { dir, ref port } => {
-                            for port_name in port {
-                                if port_name.expr.is_none() {
-                                    dirs.insert(port_name.name.value, dir.value);
-                                }
-                            }
-                        }
+                if let Some(modport) = intf.modport {
+                    for port in &self.resolve_modport(modport.name, intf.ast)?.ports {
+                        dirs.insert(port.name.value, port.dir);
                     }
                 }
                 trace!("    Modport-derived directions: {:?}", dirs);
@@ -370,6 +375,11 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             _ => unreachable!(),
         };
 
+        // Warn about latches inferred from combinational processes.
+        if hir.kind == ast::ProcedureKind::AlwaysComb {
+            crate::latch::check_procedure(self.cx, id, env)?;
+        }
+
         // Find the accessed nodes.
         let acc = self.accessed_nodes(hir.stmt, env)?;
         trace!("Process accesses {:#?}", acc);
@@ -573,8 +583,10 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             }
         };
 
-        // Emit the main statement.
-        pg.emit_stmt(hir.stmt, env)?;
+        // Emit the main statement, with any `@*` replaced by an explicit
+        // sensitivity list.
+        let stmt = pg.desugar_implicit_events(hir.stmt, env);
+        pg.emit_stmt(stmt, env)?;
 
         // Emit epilogue.
         match hir.kind {
@@ -628,6 +640,12 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             return Ok((llhd::int_ty(bits), mlir::get_integer_type(self.mcx, bits)));
         }
 
+        // Handle reals, which are represented by their IEEE 754 bit pattern.
+        if ty.is_real() {
+            let bits = ty.get_bit_size().unwrap();
+            return Ok((llhd::int_ty(bits), mlir::get_integer_type(self.mcx, bits)));
+        }
+
         // Handle arrays.
         if let Some(dim) = ty.outermost_dim() {
             let size = match dim.get_size() {
@@ -680,87 +698,6 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         panic!("cannot map `{}` to LLHD", ty);
     }
 
-    /// Execute the initialization step of a generate loop.
-    fn execute_genvar_init(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
-        let hir = self.hir_of(id)?;
-        match hir {
-            HirNode::GenvarDecl(_) => Ok(env),
-            HirNode::Stmt(stmt) => match stmt.kind {
-                hir::StmtKind::Assign {
-                    lhs,
-                    rhs,
-                    kind: hir::AssignKind::Block(ast::AssignOp::Identity),
-                } => {
-                    let target_id = self.resolve_node(lhs, env)?;
-                    let init_value = self.constant_value_of(rhs, env);
-                    let mut env_data = self.param_env_data(env).clone();
-                    env_data.set_value(target_id, init_value);
-                    Ok(self.intern_param_env(env_data))
-                }
-                _ => unreachable!(),
-            },
-            _ => unreachable!(),
-        }
-    }
-
-    /// Execute the iteration step of a generate loop.
-    fn execute_genvar_step(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
-        let hir = self.hir_of(id)?;
-        let mut env_data = self.param_env_data(env).clone();
-        let next = match hir {
-            HirNode::Expr(expr) => match expr.kind {
-                hir::ExprKind::Unary(op, target_id) => {
-                    let target_id = self.resolve_node(target_id, env)?;
-                    let current_value = self.constant_value_of(target_id, env);
-                    let next_value = match current_value.kind {
-                        ValueKind::Int(ref v, ..) => match op {
-                            hir::UnaryOp::PostInc | hir::UnaryOp::PreInc => Some(v + 1),
-                            hir::UnaryOp::PostDec | hir::UnaryOp::PreDec => Some(v - 1),
-                            _ => None,
-                        }
-                        .map(|v| value::make_int(current_value.ty, v)),
-                        _ => unreachable!(),
-                    };
-                    next_value.map(|v| (target_id, self.intern_value(v)))
-                }
-                hir::ExprKind::Assign { .. } => {
-                    let mir = self.mir_rvalue(id, env);
-                    match mir.kind {
-                        mir::RvalueKind::Error => return Err(()),
-                        mir::RvalueKind::Assignment { lvalue, rvalue, .. } => {
-                            let target_id = match lvalue.kind {
-                                mir::LvalueKind::Error => return Err(()),
-                                mir::LvalueKind::Genvar(id) => id,
-                                _ => unreachable!(),
-                            };
-                            let next_value = self.const_mir_rvalue(Ref(rvalue));
-                            Some((target_id, next_value))
-                        }
-                        _ => unreachable!(),
-                    }
-                }
-                _ => None,
-            },
-            _ => None,
-        };
-        match next {
-            Some((target_id, next_value)) => {
-                env_data.set_value(target_id, next_value);
-                return Ok(self.intern_param_env(env_data));
-            }
-            None => {
-                self.emit(
-                    DiagBuilder2::error(format!(
-                        "{} is not a valid genvar iteration step",
-                        hir.desc_full()
-                    ))
-                    .span(hir.human_span()),
-                );
-                Err(())
-            }
-        }
-    }
-
     /// Emit the code for a function or task.
     pub fn emit_function(&mut self, id: NodeId, env: ParamEnv) -> Result<Rc<EmittedFunction>> {
         if let Some(x) = self.tables.function_defs.get(&id.env(env)) {
@@ -872,6 +809,29 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         self.tables.function_defs.insert(id.env(env), x.clone());
         x
     }
+
+    /// Declare an intrinsic function provided by the simulator.
+    ///
+    /// The declaration is only emitted the first time the intrinsic is used.
+    fn declare_intrinsic(
+        &mut self,
+        name: &str,
+        sig: &llhd::ir::Signature,
+        mlir_args: impl IntoIterator<Item = mlir::Type>,
+    ) {
+        if !self.tables.intrinsics.insert(name.to_string()) {
+            return;
+        }
+        self.into
+            .declare(llhd::ir::UnitName::global(name), sig.clone());
+        let mut mlir_builder = mlir::Builder::new(self.mcx);
+        mlir_builder.set_insertion_point_to_end(self.into_mlir.block());
+        let mut func_op = circt::func::FunctionBuilder::new(name);
+        for ty in mlir_args {
+            func_op.add_arg(None, ty);
+        }
+        func_op.declare(&mut mlir_builder);
+    }
 }
 
 /// A name uniquifier.
@@ -934,6 +894,10 @@ struct UnitGenerator<'a, 'gcx, C> {
     /// A stack of blocks, the last of which will be branched to by a `break`
     /// statement.
     break_stack: Vec<HybridBlock>,
+    /// The condition under which drives are currently emitted. This is set
+    /// while lowering an `if` that only contains nonblocking assignments, such
+    /// as the write port of a memory.
+    drive_enable: Option<HybridValue>,
 }
 
 impl<'a, 'gcx, C> Deref for UnitGenerator<'a, 'gcx, C> {
@@ -970,6 +934,7 @@ impl<'a, 'gcx, C> UnitGenerator<'a, 'gcx, C> {
             terminated: false,
             break_stack: Default::default(),
             continue_stack: Default::default(),
+            drive_enable: None,
         }
     }
 }
@@ -1013,9 +978,21 @@ where
         &mut self,
         id: NodeId,
         env: ParamEnv,
-        hir: &hir::ModuleBlock,
+        hir: &'gcx hir::ModuleBlock,
         name_prefix: &str,
+        scope: &str,
     ) -> Result<()> {
+        // Perform the parts of the initial procedures that are done during
+        // elaboration, such that the declarations below pick up the assigned
+        // values as initial value.
+        let inits = self.initial_values(Ref(hir), env)?;
+        for (&decl_id, &value) in &inits.values {
+            self.tables.init_values.insert(decl_id.env(env), value);
+        }
+        for &stmt_id in &inits.stmts {
+            self.tables.elab_stmts.insert(stmt_id.env(env));
+        }
+
         // Emit declarations.
         for &decl_id in &hir.decls {
             let hir = match self.hir_of(decl_id)? {
@@ -1023,8 +1000,9 @@ where
                 _ => unreachable!(),
             };
             let ty = self.type_of(decl_id, env)?;
-            let value = self.emit_varnet_decl(decl_id, ty, env, hir.init)?;
-            self.builder.set_name(value.0, hir.name.value.into());
+            let value = self.emit_varnet_decl(decl_id, ty, env, hir.init, scope)?;
+            self.builder
+                .set_name(value.0, format!("{}{}", scope, hir.name.value));
             self.values.insert(decl_id.into(), value);
         }
 
@@ -1054,10 +1032,17 @@ where
             let signals = self.determine_interface_signals(intf_ty, &inst_ty.dims)?;
             let mut signal_lookup = HashMap::new();
             for signal in signals {
-                let value =
-                    self.emit_varnet_decl(signal.decl_id, signal.ty, intf_ty.env, signal.default)?;
-                self.builder
-                    .set_name(value.0, format!("{}.{}", inst.hir.name, signal.name));
+                let value = self.emit_varnet_decl(
+                    signal.decl_id,
+                    signal.ty,
+                    intf_ty.env,
+                    signal.default,
+                    scope,
+                )?;
+                self.builder.set_name(
+                    value.0,
+                    format!("{}{}.{}", scope, inst.hir.name, signal.name),
+                );
                 let src = AccessedNode::Intf(inst_id, signal.decl_id);
                 trace!(
                     "Emitted value for {:?} {}.{}",
@@ -1098,6 +1083,16 @@ where
             }
         }
 
+        // Emit generate blocks. This happens before the assignments and
+        // instantiations below, such that these can refer to declarations in
+        // named generate blocks.
+        let cx = self.cx;
+        for &gen_id in &hir.gens {
+            crate::design::expand_generate(cx, gen_id, env, |block_id, env, index| {
+                self.emit_generate_block(id, env, block_id, name_prefix, scope, index.as_deref())
+            })?;
+        }
+
         // Emit assignments.
         for &assign_id in &hir.assigns {
             let hir = match self.hir_of(assign_id)? {
@@ -1116,10 +1111,10 @@ where
 
             // Check for sanity.
             for &assign in &simplified {
-                assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
                 if assign.is_error() {
                     return Err(());
                 }
+                assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
             }
 
             // Emit the assignments.
@@ -1144,8 +1139,39 @@ where
                 _ => continue,
             };
 
-            // Emit the instantiated module.
-            let target = self.emit_module_with_env(target_module.id, inst.inner_env)?;
+            // Emit the instantiated module, guarding against unbounded
+            // recursive instantiations.
+            let max_depth = self.sess().opts.max_inst_depth;
+            if max_depth > 0 && self.inst_depth >= max_depth {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "instance hierarchy exceeds the maximum depth of {}",
+                        max_depth
+                    ))
+                    .code("SV0032")
+                    .span(inst.hir.ast.span())
+                    .add_note(format!(
+                        "Instantiation of module `{}` is nested too deeply; use \
+                         `--max-inst-depth` to raise the limit",
+                        target_module.ast.name
+                    )),
+                );
+                return Err(());
+            }
+
+            // Inline the instantiated module when flattening the hierarchy.
+            if self.sess().opts.flatten && !self.keeps_hierarchy(inst_id, target_module.id) {
+                self.inst_depth += 1;
+                let result = self.emit_inlined_module(target_module, &inst, name_prefix, scope);
+                self.inst_depth -= 1;
+                result?;
+                continue;
+            }
+
+            self.inst_depth += 1;
+            let target = self.emit_module_with_env(target_module.id, inst.inner_env);
+            self.inst_depth -= 1;
+            let target = target?;
 
             // Prepare the port assignments.
             let (inputs, outputs) = self.emit_port_connections(
@@ -1174,54 +1200,15 @@ where
             );
             circt::llhd::InstanceOp::new(
                 self.mlir_builder,
-                &self.unique_names.add(&inst.hir.name.value.to_string()),
+                &self
+                    .unique_names
+                    .add(&format!("{}{}", scope, inst.hir.name.value)),
                 &target.mlir_symbol,
                 inputs.iter().map(|x| x.1),
                 outputs.iter().map(|x| x.1),
             );
         }
 
-        // Emit generate blocks.
-        for &gen_id in &hir.gens {
-            let hir = match self.hir_of(gen_id)? {
-                HirNode::Gen(x) => x,
-                _ => unreachable!(),
-            };
-            #[allow(unreachable_patterns)]
-            match hir.kind {
-                hir::GenKind::If {
-                    cond,
-                    ref main_body,
-                    ref else_body,
-                } => {
-                    let k = self.constant_value_of(cond, env);
-                    if k.is_false() {
-                        if let Some(else_body) = else_body {
-                            self.emit_module_block(id, env, else_body, name_prefix)?;
-                        }
-                    } else {
-                        self.emit_module_block(id, env, main_body, name_prefix)?;
-                    }
-                }
-                hir::GenKind::For {
-                    ref init,
-                    cond,
-                    step,
-                    ref body,
-                } => {
-                    let mut local_env = env;
-                    for &i in init {
-                        local_env = self.execute_genvar_init(i, local_env)?;
-                    }
-                    while self.constant_value_of(cond, local_env).is_true() {
-                        self.emit_module_block(id, local_env, body, name_prefix)?;
-                        local_env = self.execute_genvar_step(step, local_env)?;
-                    }
-                }
-                _ => return self.unimp_msg("code generation for", hir),
-            }
-        }
-
         // Emit and instantiate procedures.
         for &proc_id in &hir.procs {
             let prok = self.emit_procedure(proc_id, env, name_prefix)?;
@@ -1262,6 +1249,129 @@ where
         Ok(())
     }
 
+    /// Check whether an instance is kept when flattening the hierarchy.
+    ///
+    /// This is the case if the instance or the instantiated module carries a
+    /// `keep_hierarchy` attribute that is not explicitly set to zero, `"no"`,
+    /// or `"false"`. An attribute on the instance takes precedence.
+    fn keeps_hierarchy(&self, inst_id: NodeId, module_id: NodeId) -> bool {
+        let name = "keep_hierarchy".into();
+        let attr = self
+            .attribute(inst_id, name)
+            .or_else(|| self.attribute(module_id, name));
+        match attr.and_then(|attr| attr.value.as_ref()) {
+            Some(value) => match value.trim_matches('"').to_lowercase().as_str() {
+                "0" | "no" | "false" => false,
+                _ => true,
+            },
+            None => attr.is_some(),
+        }
+    }
+
+    /// Emit the contents of an instantiated module directly into the current
+    /// entity, as part of flattening the hierarchy.
+    ///
+    /// The declarations of the module are prefixed with the instance name, and
+    /// its ports map directly to the values connected to the instance. The
+    /// module is emitted with a separate table of values, since the same
+    /// module may be inlined several times.
+    fn emit_inlined_module(
+        &mut self,
+        module: &'gcx hir::Module<'gcx>,
+        inst: &InstDetails<'gcx>,
+        name_prefix: &str,
+        scope: &str,
+    ) -> Result<()> {
+        let env = inst.inner_env;
+        let ports = self.determine_module_ports(&module.ports_new.int, env)?;
+        let (inputs, outputs) =
+            self.emit_port_connections(module.ports_new, inst, &ports.inputs, &ports.outputs)?;
+        let mut values: HashMap<_, _> = ports
+            .inputs
+            .iter()
+            .zip(inputs)
+            .chain(ports.outputs.iter().zip(outputs))
+            .map(|(port, value)| (port.accnode, value))
+            .collect();
+
+        std::mem::swap(&mut *self.values, &mut values);
+        self.flush_mir();
+        let name = inst.hir.name.value;
+        let result = self
+            .emit_module_block(
+                module.id,
+                env,
+                &module.block,
+                &format!("{}.{}", name_prefix, name),
+                &format!("{}{}.", scope, name),
+            )
+            .and_then(|_| self.emit_undriven_outputs(&ports.outputs, env));
+        self.flush_mir();
+        std::mem::swap(&mut *self.values, &mut values);
+        result?;
+
+        // Check for multiply-driven variables and combinational loops.
+        crate::drivers::check_module(self.cx, module.id, env)?;
+        crate::comb_loops::check_module(self.cx, module.id, env)?;
+        Ok(())
+    }
+
+    /// Drive output ports that are not driven by the module with their default
+    /// value.
+    fn emit_undriven_outputs(&mut self, outputs: &[ModulePort<'gcx>], env: ParamEnv) -> Result<()> {
+        for port in outputs {
+            let value = self.values[&port.accnode];
+            let driven = self
+                .builder
+                .all_insts()
+                .any(|inst| match self.builder[inst].opcode() {
+                    llhd::ir::Opcode::Drv => self.builder[inst].args()[0] == value.0,
+                    llhd::ir::Opcode::Inst => self.builder[inst].output_args().contains(&value.0),
+                    _ => false,
+                });
+            if driven {
+                continue;
+            }
+            let default_value = self.emit_const(
+                if let Some(default) = port.default {
+                    self.constant_value_of(default, env)
+                } else {
+                    self.type_default_value(port.ty)
+                },
+                env,
+                port.port.span(),
+            )?;
+            let zero_time = self.mk_const_time(&num::zero(), 1, 0);
+            self.mk_drv(value, default_value, zero_time);
+        }
+        Ok(())
+    }
+
+    /// Emit code for a block of a generate statement.
+    ///
+    /// Labeled blocks extend the hierarchical scope of the declarations they
+    /// contain, e.g. `label.signal` or `label[index].signal` for loops.
+    fn emit_generate_block(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+        block_id: NodeId,
+        name_prefix: &str,
+        scope: &str,
+        index: Option<&str>,
+    ) -> Result<()> {
+        let block = match self.hir_of(block_id)? {
+            HirNode::GenerateBlock(x) => x,
+            _ => unreachable!(),
+        };
+        let scope = match (block.label(), index) {
+            (Some(label), Some(index)) => format!("{}{}[{}].", scope, label, index),
+            (Some(label), None) => format!("{}{}.", scope, label),
+            (None, _) => scope.to_string(),
+        };
+        self.emit_module_block(id, env, &block.block, name_prefix, &scope)
+    }
+
     /// Emit code for the connections made in a port list.
     fn emit_port_connections(
         &mut self,
@@ -1304,6 +1414,7 @@ where
             if port_mapping_int.insert(int.id, mapping).is_some() {
                 self.emit(
                     DiagBuilder2::error(format!("port `{}` connected multiple times", int.name))
+                        .code("SV0039")
                         .span(self.span(mapping.id())),
                 );
             }
@@ -1433,6 +1544,16 @@ where
                     circt::hw::ConstantOp::new(self.mlir_builder, std::cmp::max(size, 1), k).into(),
                 ))
             }
+            ValueKind::Real(k) => {
+                let (size, bits) = match value.ty.get_real() {
+                    Some(ty::RealType::ShortReal) => (32, BigInt::from((k.0 as f32).to_bits())),
+                    _ => (64, BigInt::from(k.0.to_bits())),
+                };
+                Ok((
+                    self.builder.ins().const_int((size, bits.clone())),
+                    circt::hw::ConstantOp::new(self.mlir_builder, size, &bits).into(),
+                ))
+            }
             ValueKind::Time(ref k) => Ok((
                 self.builder
                     .ins()
@@ -1688,7 +1809,9 @@ where
 
             mir::RvalueKind::Intf(_) => {
                 self.emit(
-                    DiagBuilder2::error("interface cannot be used in an expression").span(mir.span),
+                    DiagBuilder2::error("interface cannot be used in an expression")
+                        .code("SV0036")
+                        .span(mir.span),
                 );
                 return Err(());
             }
@@ -1709,6 +1832,31 @@ where
                 return self.emit_mir_rvalue_inner(value, mode_hint);
             }
 
+            mir::RvalueKind::CastRealPrecision(value)
+                if value.ty.get_bit_size() == mir.ty.get_bit_size() =>
+            {
+                // Casts between reals of the same size are no-ops.
+                return self.emit_mir_rvalue_inner(value, mode_hint);
+            }
+
+            mir::RvalueKind::CastRealToInt { .. }
+            | mir::RvalueKind::CastIntToReal(..)
+            | mir::RvalueKind::CastRealPrecision(..)
+            | mir::RvalueKind::RealUnaryArith { .. }
+            | mir::RvalueKind::RealBinaryArith { .. }
+            | mir::RvalueKind::RealComp { .. } => {
+                self.emit(
+                    DiagBuilder2::error("real arithmetic is only supported on constant values")
+                        .code("SV0045")
+                        .span(mir.span)
+                        .add_note(
+                            "LLHD has no floating-point operations; real values can only be \
+                             used in constant expressions, such as parameters",
+                        ),
+                );
+                return Err(());
+            }
+
             mir::RvalueKind::CastSign(_, value) => {
                 // Sign conversions are no-ops in LLHD since they merely
                 // influence the type system.
@@ -1899,6 +2047,7 @@ where
                         // Otherwise we just complain.
                         self.emit(
                             DiagBuilder2::error("`**` operator on non-constants not supported")
+                                .code("SV0045")
                                 .span(mir.span),
                         );
                         return Err(());
@@ -1981,6 +2130,29 @@ where
                 value
             }
 
+            mir::RvalueKind::CountOnes(arg) => {
+                let width = arg.ty.simple_bit_vector(self.cx, arg.span).size;
+                let llty = self.emit_type_both(mir.ty)?;
+                let zero = self.emit_zero_for_type_both(llty);
+                let arg = self.emit_mir_rvalue(arg)?;
+                let mut value = zero;
+                for i in 0..width {
+                    let bit = self.mk_ext_slice_const_offset(arg, i, 1);
+                    let bit = self.mk_ins_slice(zero, bit, 0, 1);
+                    value = self.mk_add(value, bit);
+                }
+                value
+            }
+
+            mir::RvalueKind::Clog2(_) => {
+                self.emit(
+                    DiagBuilder2::error("`$clog2` is only supported on constant values")
+                        .code("SV0045")
+                        .span(mir.span),
+                );
+                return Err(());
+            }
+
             mir::RvalueKind::Assignment {
                 lvalue,
                 rvalue,
@@ -2008,6 +2180,20 @@ where
                 "runtime int-to-time conversion not implemented"
             ),
 
+            mir::RvalueKind::Display { .. } => {
+                bug_span!(mir.span, self.cx, "display task used as a value")
+            }
+
+            mir::RvalueKind::ReadMem { .. } => {
+                bug_span!(mir.span, self.cx, "memory load task used as a value")
+            }
+
+            mir::RvalueKind::Format(..) => bug_span!(
+                mir.span,
+                self.cx,
+                "runtime string formatting not implemented"
+            ),
+
             mir::RvalueKind::Call { target, ref args } => {
                 // Ensure the function is emitted.
                 let func_env = self.default_param_env();
@@ -2423,6 +2609,41 @@ where
         }
     }
 
+    /// Check whether a statement consists only of nonblocking assignments.
+    ///
+    /// Such statements can be guarded by an enable condition on their drives,
+    /// rather than by a branch around them.
+    fn is_drive_only(&self, stmt_id: NodeId) -> Result<bool> {
+        let hir = match self.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(false),
+        };
+        Ok(match hir.kind {
+            hir::StmtKind::Assign {
+                kind: hir::AssignKind::Nonblock,
+                ..
+            }
+            | hir::StmtKind::Assign {
+                kind: hir::AssignKind::NonblockDelay(_),
+                ..
+            } => true,
+            hir::StmtKind::Block(ref stmts) => {
+                for &stmt in stmts {
+                    if !self.is_drive_only(stmt)? {
+                        return Ok(false);
+                    }
+                }
+                !stmts.is_empty()
+            }
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt: None,
+                ..
+            } => self.is_drive_only(main_stmt)?,
+            _ => false,
+        })
+    }
+
     /// Emit the code for a statement.
     fn emit_stmt(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
         // If we attempt to emit additional statements after a terminator,
@@ -2435,6 +2656,9 @@ where
         self.mlir_builder
             .set_loc(span_to_loc(self.mcx, self.span(stmt_id)));
         self.flush_mir();
+        if self.tables.elab_stmts.contains(&stmt_id.env(env)) {
+            return Ok(());
+        }
         match self.hir_of(stmt_id)? {
             HirNode::Stmt(x) => self.emit_stmt_regular(stmt_id, x, env),
             HirNode::VarDecl(x) => self.emit_stmt_var_decl(stmt_id, x, env),
@@ -2596,26 +2820,49 @@ where
             }
             hir::StmtKind::Timed {
                 control: hir::TimingControl::ImplicitEvent,
-                stmt,
-            } => {
-                // Wait for any of the inputs to the statement to change.
-                let trigger_blk = self.mk_block(Some("trigger"));
-                let acc = self.accessed_nodes(stmt, env)?;
-                let trigger_on: Vec<_> = acc
-                    .read
-                    .iter()
-                    .map(|&id| self.emitted_value(id).clone())
-                    .collect();
-                self.mk_wait(trigger_blk, trigger_on, None);
-                self.append_to(trigger_blk);
-                self.flush_mir(); // ensure we don't reuse earlier expr probe
-                self.emit_shadow_update();
-
-                // Emit the actual statement.
-                self.emit_stmt(stmt, env)?;
-            }
+                ..
+            } => bug_span!(
+                hir.span,
+                self.cx,
+                "implicit event control should have been desugared"
+            ),
             hir::StmtKind::Expr(expr_id) => {
-                self.emit_rvalue(expr_id, env)?;
+                let mir = self.mir_rvalue(expr_id, env);
+                match mir.kind {
+                    mir::RvalueKind::Display { task, ref pieces } => {
+                        self.emit_display(task, pieces)?;
+                    }
+                    mir::RvalueKind::ReadMem {
+                        radix,
+                        file,
+                        mem,
+                        start,
+                        end,
+                    } => {
+                        self.emit_readmem(radix, file, mem, start, end)?;
+                    }
+                    _ => {
+                        self.emit_mir_rvalue(mir)?;
+                    }
+                }
+            }
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt: None,
+            } if self.is_drive_only(main_stmt)? => {
+                // Lower `if (en) x <= y;` to a drive with an enable condition
+                // rather than a branch. This keeps write ports such as
+                // `mem[addr] <= data` a single drive on the memory signal.
+                let cond = self.emit_rvalue_bool(cond, env)?;
+                let enable = match self.drive_enable {
+                    Some(outer) => self.mk_and(outer, cond),
+                    None => cond,
+                };
+                let outer = self.drive_enable.replace(enable);
+                let result = self.emit_stmt(main_stmt, env);
+                self.drive_enable = outer;
+                result?;
             }
             hir::StmtKind::If {
                 cond,
@@ -2758,6 +3005,7 @@ where
                 None => {
                     self.emit(
                         DiagBuilder2::error("break statement outside of loop")
+                            .code("SV0033")
                             .span(stmt.human_span()),
                     );
                     return Err(());
@@ -2770,6 +3018,7 @@ where
                 None => {
                     self.emit(
                         DiagBuilder2::error("continue statement outside of loop")
+                            .code("SV0033")
                             .span(stmt.human_span()),
                     );
                     return Err(());
@@ -2788,6 +3037,172 @@ where
         Ok(())
     }
 
+    /// Emit the code for a display task.
+    ///
+    /// The message is assembled piece by piece in the simulator's message
+    /// buffer through calls to the `moore.print.*` intrinsics, and is then
+    /// emitted by a call to `moore.display`, `moore.strobe`, or
+    /// `moore.monitor`. Pieces whose value is known at compile time are
+    /// formatted right away and printed as text. Note that `$monitor` prints
+    /// its message when it is executed, and it is up to the simulator to print
+    /// it again whenever one of the values changes.
+    fn emit_display(
+        &mut self,
+        task: DisplayTask,
+        pieces: &'gcx [mir::FormatPiece<'gcx>],
+    ) -> Result<()> {
+        let mut text = String::new();
+        for piece in pieces {
+            match *piece {
+                mir::FormatPiece::Text(ref x) => text.push_str(x),
+                mir::FormatPiece::Value(spec, value) if value.is_const() => {
+                    let value = self.const_mir_rvalue(value.into());
+                    text.push_str(&format_value(spec, &value).ok_or(())?);
+                }
+                mir::FormatPiece::Value(spec, value) => {
+                    self.emit_print_text(&mut text);
+                    self.emit_print_value(spec, value)?;
+                }
+                mir::FormatPiece::Scope => {
+                    self.emit_print_text(&mut text);
+                    self.emit_intrinsic("moore.print.scope", &[]);
+                }
+            }
+        }
+        self.emit_print_text(&mut text);
+        let name = match task {
+            DisplayTask::Strobe => "moore.strobe",
+            DisplayTask::Monitor => "moore.monitor",
+            _ => "moore.display",
+        };
+        self.emit_intrinsic(name, &[]);
+        Ok(())
+    }
+
+    /// Print and clear a piece of text that has been accumulated for a display
+    /// task. The text is passed as an integer with one byte per character.
+    fn emit_print_text(&mut self, text: &mut String) {
+        if text.is_empty() {
+            return;
+        }
+        let bytes = std::mem::take(text).into_bytes();
+        let value = BigInt::from_bytes_be(num::bigint::Sign::Plus, &bytes);
+        let value = self.mk_const_int(bytes.len() * 8, &value);
+        self.emit_intrinsic("moore.print.text", &[value]);
+    }
+
+    /// Print a value that is only known at runtime for a display task.
+    fn emit_print_value(&mut self, spec: FormatSpec, value: &'gcx mir::Rvalue<'gcx>) -> Result<()> {
+        let name = match spec.kind {
+            FormatKind::Int(Radix::Bin) => "moore.print.bin",
+            FormatKind::Int(Radix::Oct) => "moore.print.oct",
+            FormatKind::Int(Radix::Dec)
+                if value
+                    .ty
+                    .get_simple_bit_vector()
+                    .map(|sbv| sbv.is_signed())
+                    .unwrap_or(false) =>
+            {
+                "moore.print.sdec"
+            }
+            FormatKind::Int(Radix::Dec) => "moore.print.dec",
+            FormatKind::Int(Radix::Hex) => "moore.print.hex",
+            FormatKind::Char => "moore.print.char",
+            FormatKind::String => "moore.print.str",
+            FormatKind::Time => "moore.print.time",
+            FormatKind::Exp | FormatKind::Float | FormatKind::General => {
+                self.emit(
+                    DiagBuilder2::error("real formatting is only supported on constant values")
+                        .code("SV0045")
+                        .span(value.span),
+                );
+                return Err(());
+            }
+        };
+        let mut args = vec![self.emit_mir_rvalue(value)?];
+        if spec.kind != FormatKind::Char {
+            args.push(self.mk_const_int(32, &spec.width.into()));
+        }
+        self.emit_intrinsic(name, &args);
+        Ok(())
+    }
+
+    /// Emit the code for a memory load task that is performed at runtime.
+    ///
+    /// The load is performed by the simulator through a call to the
+    /// `moore.readmemb` or `moore.readmemh` intrinsic, which is passed the file
+    /// name, the memory signal, and the first and last address to load.
+    /// Omitted addresses default to the bounds of the memory.
+    fn emit_readmem(
+        &mut self,
+        radix: Radix,
+        file: &'gcx mir::Rvalue<'gcx>,
+        mem: &'gcx mir::Lvalue<'gcx>,
+        start: Option<&'gcx mir::Rvalue<'gcx>>,
+        end: Option<&'gcx mir::Rvalue<'gcx>>,
+    ) -> Result<()> {
+        if !file.is_const() {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "file name of `{}` must be a constant",
+                    crate::readmem::task_name(radix)
+                ))
+                .code("SV0030")
+                .span(file.span),
+            );
+            return Err(());
+        }
+        let (range, _) = crate::readmem::memory_shape(self.cx, mem.ty, mem.span)?;
+        let bytes = crate::readmem::file_name(self.cx, file)?.into_bytes();
+        let name = BigInt::from_bytes_be(num::bigint::Sign::Plus, &bytes);
+        let name = self.mk_const_int(bytes.len() * 8, &name);
+        let mem = self.emit_mir_lvalue(mem)?.0;
+        let low = self.mk_const_int(32, &range.low().into());
+        let high = self.mk_const_int(32, &range.high().into());
+        let start = match start {
+            Some(x) => self.emit_mir_rvalue(x)?,
+            None => low,
+        };
+        let end = match end {
+            Some(x) => self.emit_mir_rvalue(x)?,
+            None => high,
+        };
+        let intrinsic = match radix {
+            Radix::Bin => "moore.readmemb",
+            _ => "moore.readmemh",
+        };
+        self.emit_intrinsic(intrinsic, &[name, mem, start, end]);
+        Ok(())
+    }
+
+    /// Emit a call to an intrinsic function provided by the simulator.
+    ///
+    /// The argument types are appended to the name of the intrinsic, such that
+    /// every declaration has a unique signature.
+    fn emit_intrinsic(&mut self, name: &str, args: &[HybridValue]) {
+        let mut name = name.to_string();
+        let mut sig = llhd::ir::Signature::new();
+        for arg in args {
+            let ty = self.llhd_type(arg.0);
+            name.push_str(&format!(".{}", ty));
+            sig.add_input(ty);
+        }
+        sig.set_return_type(llhd::void_ty());
+        self.declare_intrinsic(&name, &sig, args.iter().map(|x| x.1.ty()));
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::global(name.clone()), sig);
+        self.builder
+            .ins()
+            .call(ext_unit, args.iter().map(|x| x.0).collect());
+        circt::func::CallOp::new(
+            self.mlir_builder,
+            &name,
+            args.iter().map(|x| x.1),
+            Option::<mlir::Type>::None,
+        );
+    }
+
     /// Emit the code for a loop statement.
     fn emit_loop_stmt(
         &mut self,
@@ -2995,11 +3410,12 @@ where
         ty: &'gcx UnpackedType<'gcx>,
         env: ParamEnv,
         default: Option<NodeId>,
+        scope: &str,
     ) -> Result<HybridValue> {
         // Check if this is a variable or a net declaration.
         let (is_var, name) = match self.hir_of(decl_id)? {
-            HirNode::VarDecl(x) => (x.kind.is_var(), x.name.value.as_str()),
-            HirNode::IntPort(x) => (x.kind.is_var(), x.name.value.as_str()),
+            HirNode::VarDecl(x) => (x.kind.is_var(), format!("{}{}", scope, x.name.value)),
+            HirNode::IntPort(x) => (x.kind.is_var(), format!("{}{}", scope, x.name.value)),
             x => unreachable!("emit_varnet_decl on HIR {:?}", x),
         };
 
@@ -3009,9 +3425,10 @@ where
             // For variables we require that the initial value is a
             // constant.
             let init = self.emit_const(
-                match default {
-                    Some(expr) => self.constant_value_of(expr, env),
-                    None => self.type_default_value(ty),
+                match (self.tables.init_values.get(&decl_id.env(env)), default) {
+                    (Some(&value), _) => value,
+                    (None, Some(expr)) => self.constant_value_of(expr, env),
+                    (None, None) => self.type_default_value(ty),
                 },
                 env,
                 self.span(default.unwrap_or(decl_id)),
@@ -3573,10 +3990,16 @@ where
     }
 
     fn mk_drv(&mut self, lhs: HybridValue, rhs: HybridValue, delay: HybridValue) {
-        (
-            self.builder.ins().drv(lhs.0, rhs.0, delay.0),
-            circt::llhd::DriveOp::new(self.mlir_builder, lhs.1, rhs.1, delay.1),
-        );
+        match self.drive_enable {
+            Some(cond) => (
+                self.builder.ins().drv_cond(lhs.0, rhs.0, delay.0, cond.0),
+                circt::llhd::DriveOp::with_enable(self.mlir_builder, lhs.1, rhs.1, delay.1, cond.1),
+            ),
+            None => (
+                self.builder.ins().drv(lhs.0, rhs.0, delay.0),
+                circt::llhd::DriveOp::new(self.mlir_builder, lhs.1, rhs.1, delay.1),
+            ),
+        };
     }
 
     fn mk_var(&mut self, init: HybridValue) -> HybridValue {
@@ -0,0 +1,350 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of combinational loops.
+//!
+//! Continuous assignments and combinational processes react to changes of
+//! their inputs without any delay. If the signals they read and drive form a
+//! cycle, the design either oscillates or settles in a state that depends on
+//! the order of evaluation. This module builds a graph from every signal to
+//! the signals driven from it within a module, and reports the cycles in it.
+//! Instantiated modules contribute the paths from their inputs to their
+//! outputs, such that loops through instances are found as well.
+
+use crate::{
+    crate_prelude::*,
+    hir::{AccessedNode, HirNode},
+    resolver::InstTarget,
+    ParamEnv,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// Check a module for combinational loops.
+///
+/// The assignments, processes, and instances directly within the module are
+/// considered.
+pub(crate) fn check_module<'a>(
+    cx: &impl Context<'a>,
+    module_id: NodeId,
+    env: ParamEnv,
+) -> Result<()> {
+    let graph = module_graph(cx, module_id, env)?;
+
+    // Report one loop for every strongly connected component of the graph.
+    for component in graph.cycles() {
+        let chain = graph.find_cycle(&component);
+        let names: Vec<_> = chain
+            .iter()
+            .map(|&(from, _)| format!("`{}`", node_name(cx, from)))
+            .chain(Some(format!("`{}`", node_name(cx, chain[0].0))))
+            .collect();
+        let mut d =
+            DiagBuilder2::warning(format!("combinational loop through {}", names.join(" -> ")))
+                .group("comb-loop");
+        for (i, &(from, span)) in chain.iter().enumerate() {
+            let to = chain[(i + 1) % chain.len()].0;
+            d = d
+                .add_note(format!(
+                    "`{}` drives `{}` here:",
+                    node_name(cx, from),
+                    node_name(cx, to)
+                ))
+                .span(span);
+        }
+        cx.emit(d);
+    }
+    Ok(())
+}
+
+/// Determine which inputs of a module combinationally drive which of its
+/// outputs, as pairs of internal port IDs.
+#[moore_derive::query]
+pub(crate) fn comb_paths<'a>(
+    cx: &impl Context<'a>,
+    module_id: NodeId,
+    env: ParamEnv,
+) -> Result<Arc<Vec<(NodeId, NodeId)>>> {
+    let hir = match cx.hir_of(module_id)? {
+        HirNode::Module(x) => x,
+        _ => unreachable!(),
+    };
+    let graph = module_graph(cx, module_id, env)?;
+    let ports = &hir.ports_new.int;
+    let mut paths = vec![];
+    for input in ports.iter().filter(|p| p.dir == ast::PortDir::Input) {
+        let reached = graph.reachable(AccessedNode::Regular(input.id));
+        for output in ports.iter().filter(|p| p.dir == ast::PortDir::Output) {
+            if reached.contains(&AccessedNode::Regular(output.id)) {
+                paths.push((input.id, output.id));
+            }
+        }
+    }
+    Ok(Arc::new(paths))
+}
+
+/// Build the graph of the signals driven by other signals within a module.
+fn module_graph<'a>(cx: &impl Context<'a>, module_id: NodeId, env: ParamEnv) -> Result<Graph> {
+    let hir = match cx.hir_of(module_id)? {
+        HirNode::Module(x) => x,
+        _ => unreachable!(),
+    };
+    let mut graph = Graph::default();
+
+    // Every continuous assignment drives its left-hand side from all signals
+    // read on the right-hand side.
+    for &assign_id in &hir.block.assigns {
+        let acc = cx.accessed_nodes(assign_id, env)?;
+        let span = cx.span(assign_id);
+        for &to in &acc.written {
+            for &from in &acc.read {
+                graph.add_edge(from, to, span);
+            }
+        }
+    }
+
+    // Combinational processes drive their outputs from all of their inputs.
+    // Signals that a process both reads and writes are assumed to be written
+    // before they are read, and do not form a loop by themselves.
+    for &proc_id in &hir.block.procs {
+        let proc = match cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        if !is_combinational(cx, proc)? {
+            continue;
+        }
+        let acc = cx.accessed_nodes(proc.stmt, env)?;
+        for &to in &acc.written {
+            for &from in acc.read.iter().filter(|id| !acc.written.contains(id)) {
+                graph.add_edge(from, to, proc.span);
+            }
+        }
+    }
+
+    // Instances drive the signals connected to their outputs from the signals
+    // connected to the inputs that reach these outputs within the module.
+    for &inst_id in &hir.block.insts {
+        let inst = match cx.hir_of(inst_id)? {
+            HirNode::Inst(x) => x,
+            _ => unreachable!(),
+        };
+        let details = cx.inst_details(Ref(inst), env)?;
+        let target = match details.target.kind {
+            InstTarget::Module(x) => cx.hir_of_module(x)?,
+            _ => continue,
+        };
+        let paths = cx.comb_paths(target.id, details.inner_env)?;
+        if paths.is_empty() {
+            continue;
+        }
+
+        // Map the internal ports to the expressions connected to them.
+        let mut connections = HashMap::new();
+        for port in &target.ports_new.ext_pos {
+            let mapping = match details.ports.find(port.id) {
+                Some(m) => m,
+                None => continue,
+            };
+            if let [expr] = port.exprs.as_slice() {
+                if expr.selects.is_empty() {
+                    connections.insert(target.ports_new.int[expr.port].id, mapping);
+                }
+            }
+        }
+
+        let span = inst.ast.span();
+        for &(input, output) in paths.iter() {
+            let (input, output) = match (connections.get(&input), connections.get(&output)) {
+                (Some(&input), Some(&output)) => (input, output),
+                _ => continue,
+            };
+            let read = cx.accessed_nodes(input.id(), input.env())?;
+            let mut written = vec![];
+            written_nodes(cx.mir_lvalue(output.id(), output.env()), &mut written);
+            for &to in &written {
+                for &from in &read.read {
+                    graph.add_edge(from, to, span);
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Collect the signals written through an lvalue.
+fn written_nodes(mir: &mir::Lvalue, into: &mut Vec<AccessedNode>) {
+    match mir.kind {
+        mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+            into.push(AccessedNode::Regular(id))
+        }
+        mir::LvalueKind::Transmute(value)
+        | mir::LvalueKind::Index { value, .. }
+        | mir::LvalueKind::Member { value, .. }
+        | mir::LvalueKind::Repeat(_, value) => written_nodes(value, into),
+        mir::LvalueKind::DestructArray(ref values)
+        | mir::LvalueKind::DestructStruct(ref values)
+        | mir::LvalueKind::Concat(ref values) => {
+            for value in values {
+                written_nodes(value, into);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Check whether a procedure describes combinational logic.
+pub(crate) fn is_combinational<'a>(cx: &impl Context<'a>, proc: &hir::Proc) -> Result<bool> {
+    Ok(match proc.kind {
+        ast::ProcedureKind::AlwaysComb => true,
+        ast::ProcedureKind::Always => match cx.hir_of(proc.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind:
+                    hir::StmtKind::Timed {
+                        control: hir::TimingControl::ImplicitEvent,
+                        ..
+                    },
+                ..
+            }) => true,
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// Determine a human-readable name for an accessed node.
+fn node_name<'a>(cx: &impl Context<'a>, node: AccessedNode) -> String {
+    let name = |id| match cx.hir_of(id) {
+        Ok(HirNode::VarDecl(x)) => x.name.value.to_string(),
+        Ok(HirNode::IntPort(x)) => x.name.value.to_string(),
+        Ok(HirNode::Inst(x)) => x.name.value.to_string(),
+        _ => format!("{:?}", id),
+    };
+    match node {
+        AccessedNode::Regular(id) => name(id),
+        AccessedNode::Intf(intf, id) => format!("{}.{}", name(intf), name(id)),
+    }
+}
+
+/// A graph of the signals driven by other signals.
+#[derive(Default)]
+struct Graph {
+    edges: BTreeMap<AccessedNode, Vec<(AccessedNode, Span)>>,
+}
+
+impl Graph {
+    fn add_edge(&mut self, from: AccessedNode, to: AccessedNode, span: Span) {
+        self.edges.entry(to).or_default();
+        let edges = self.edges.entry(from).or_default();
+        if !edges.iter().any(|&(x, _)| x == to) {
+            edges.push((to, span));
+        }
+    }
+
+    /// Find all nodes reachable from a node.
+    fn reachable(&self, start: AccessedNode) -> BTreeSet<AccessedNode> {
+        let mut seen = BTreeSet::new();
+        let mut todo = vec![start];
+        while let Some(node) = todo.pop() {
+            for &(next, _) in self.edges.get(&node).into_iter().flatten() {
+                if seen.insert(next) {
+                    todo.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Find the strongly connected components of the graph that contain a
+    /// cycle, using Tarjan's algorithm.
+    fn cycles(&self) -> Vec<BTreeSet<AccessedNode>> {
+        struct State {
+            index: BTreeMap<AccessedNode, usize>,
+            lowlink: BTreeMap<AccessedNode, usize>,
+            stack: Vec<AccessedNode>,
+            on_stack: BTreeSet<AccessedNode>,
+            components: Vec<BTreeSet<AccessedNode>>,
+        }
+        fn visit(graph: &Graph, state: &mut State, node: AccessedNode) {
+            let index = state.index.len();
+            state.index.insert(node, index);
+            state.lowlink.insert(node, index);
+            state.stack.push(node);
+            state.on_stack.insert(node);
+            for &(next, _) in &graph.edges[&node] {
+                if !state.index.contains_key(&next) {
+                    visit(graph, state, next);
+                    let low = state.lowlink[&node].min(state.lowlink[&next]);
+                    state.lowlink.insert(node, low);
+                } else if state.on_stack.contains(&next) {
+                    let low = state.lowlink[&node].min(state.index[&next]);
+                    state.lowlink.insert(node, low);
+                }
+            }
+            if state.lowlink[&node] == state.index[&node] {
+                let mut component = BTreeSet::new();
+                loop {
+                    let x = state.stack.pop().unwrap();
+                    state.on_stack.remove(&x);
+                    component.insert(x);
+                    if x == node {
+                        break;
+                    }
+                }
+                let is_cycle =
+                    component.len() > 1 || graph.edges[&node].iter().any(|&(next, _)| next == node);
+                if is_cycle {
+                    state.components.push(component);
+                }
+            }
+        }
+        let mut state = State {
+            index: BTreeMap::new(),
+            lowlink: BTreeMap::new(),
+            stack: vec![],
+            on_stack: BTreeSet::new(),
+            components: vec![],
+        };
+        for &node in self.edges.keys() {
+            if !state.index.contains_key(&node) {
+                visit(self, &mut state, node);
+            }
+        }
+        state.components.sort();
+        state.components
+    }
+
+    /// Find a cycle within a strongly connected component. Returns the nodes
+    /// along the cycle, together with the span of the edge leaving each node.
+    fn find_cycle(&self, component: &BTreeSet<AccessedNode>) -> Vec<(AccessedNode, Span)> {
+        let start = *component.iter().next().unwrap();
+        let mut pred: BTreeMap<AccessedNode, (AccessedNode, Span)> = BTreeMap::new();
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(start);
+        'outer: while let Some(node) = queue.pop_front() {
+            for &(next, span) in &self.edges[&node] {
+                if !component.contains(&next) || pred.contains_key(&next) {
+                    continue;
+                }
+                pred.insert(next, (node, span));
+                if next == start {
+                    break 'outer;
+                }
+                queue.push_back(next);
+            }
+        }
+        let mut chain = vec![];
+        let mut node = start;
+        loop {
+            let (prev, span) = pred[&node];
+            chain.push((prev, span));
+            node = prev;
+            if node == start {
+                break;
+            }
+        }
+        chain.reverse();
+        chain
+    }
+}
@@ -24,17 +24,29 @@ use crate::crate_prelude::*;
 use crate::{
     ast::{self, Visitor},
     ast_map::{AstMap, AstNode},
-    common::{arenas::Alloc, arenas::TypedArena, Session},
+    common::{
+        arenas::Alloc,
+        arenas::TypedArena,
+        design_units::{DesignUnit, DesignUnitKind},
+        foreign::{ForeignLang, ForeignUnit},
+        source::Location,
+        Session,
+    },
+    dedup::Deduplicator,
     func_args::FuncArgList,
     hir::{self, HirNode},
+    implicit_nets::{find_implicit_net, note_disallowed_net, ImplicitNet},
     port_list::PortList,
     resolver::Scope,
+    stats::{QueryRecorder, QueryStats},
+    suppress::Suppressions,
     value::{Value, ValueData, ValueKind},
     ParamEnv, ParamEnvData, QueryDatabase, QueryStorage,
 };
 use std::{
     cell::RefCell,
     collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
 };
 
 /// The central data structure of the compiler. It stores references to various
@@ -63,6 +75,18 @@ pub struct GlobalContext<'gcx> {
     imports: RefCell<Vec<NodeId>>,
     /// A mapping from node ids to spans for diagnostics.
     node_id_to_span: RefCell<HashMap<NodeId, Span>>,
+    /// The documentation comments, by the location of the token they precede.
+    doc_comments: RefCell<HashMap<Location, &'gcx ast::DocComment>>,
+    /// The attributes, by the location of the token they precede.
+    attributes: RefCell<HashMap<Location, Vec<&'gcx ast::Attribute>>>,
+    /// The library files that have been parsed.
+    library_files: RefCell<HashSet<PathBuf>>,
+    /// The statistics of the executed queries.
+    query_stats: QueryRecorder,
+    /// The regions in which lint directive comments disable warnings.
+    suppressions: Suppressions,
+    /// The diagnostics held back while module specializations are elaborated.
+    dedup: Deduplicator,
     /// The tables.
     tables: GlobalTables<'gcx>,
 }
@@ -82,6 +106,12 @@ impl<'gcx> GlobalContext<'gcx> {
             interfaces: Default::default(),
             imports: Default::default(),
             node_id_to_span: Default::default(),
+            doc_comments: Default::default(),
+            attributes: Default::default(),
+            library_files: Default::default(),
+            query_stats: Default::default(),
+            suppressions: Default::default(),
+            dedup: Default::default(),
             tables: Default::default(),
         }
     }
@@ -117,19 +147,33 @@ impl<'gcx> GlobalContext<'gcx> {
 
         // Keep track of some names for now.
         for file in &root.files {
+            self.suppressions.add_file(file);
+            for doc in &file.docs {
+                self.doc_comments.borrow_mut().insert(doc.target, doc);
+            }
+            for attr in &file.attrs {
+                self.attributes
+                    .borrow_mut()
+                    .entry(attr.target)
+                    .or_default()
+                    .push(attr);
+            }
             for item in &file.items {
                 match &item.data {
                     ast::ItemData::ModuleDecl(ref n) => {
                         let id = self.map_ast(AstNode::Module(n));
                         self.modules.borrow_mut().insert(n.name.value, id);
+                        self.register_unit(n.name, DesignUnitKind::Module, id);
                     }
                     ast::ItemData::PackageDecl(ref n) => {
                         let id = self.map_ast(AstNode::Package(n));
                         self.packages.borrow_mut().insert(n.name.value, id);
+                        self.register_unit(n.name, DesignUnitKind::Package, id);
                     }
                     ast::ItemData::InterfaceDecl(ref n) => {
                         let id = self.map_ast(AstNode::Interface(n));
                         self.interfaces.borrow_mut().insert(n.name.value, id);
+                        self.register_unit(n.name, DesignUnitKind::Interface, id);
                     }
                     ast::ItemData::ImportDecl(ref n) => {
                         for item in &n.items {
@@ -178,14 +222,100 @@ impl<'gcx> GlobalContext<'gcx> {
         self.packages.borrow().get(&name).cloned()
     }
 
+    /// Get an iterator over all packages in the AST.
+    pub fn packages(&self) -> impl Iterator<Item = (Name, NodeId)> {
+        self.packages.borrow().clone().into_iter()
+    }
+
+    /// Get an iterator over all interfaces in the AST.
+    pub fn interfaces(&self) -> impl Iterator<Item = (Name, NodeId)> {
+        self.interfaces.borrow().clone().into_iter()
+    }
+
     /// Get an iterator over all root imports in the AST.
     pub fn imports(&self) -> impl Iterator<Item = NodeId> {
         self.imports.borrow().clone().into_iter()
     }
+
+    /// Find the documentation comment preceding a location in the source.
+    pub fn doc_comment_at(&self, loc: Location) -> Option<&'gcx ast::DocComment> {
+        self.doc_comments.borrow().get(&loc).cloned()
+    }
+
+    /// Find a design unit of the working library that is declared in another
+    /// language and whose interface has been described.
+    pub fn find_foreign_unit(&self, name: Name) -> Option<ForeignUnit> {
+        self.sess
+            .units
+            .find(None, name)
+            .into_iter()
+            .filter(|unit| unit.lang != ForeignLang::Svlog)
+            .find_map(|unit| unit.interface)
+    }
+
+    /// Register a design unit with the registry shared by all languages.
+    ///
+    /// Modules that stand in for a unit declared in another language are
+    /// registered in that language already.
+    fn register_unit(&self, name: Spanned<Name>, kind: DesignUnitKind, id: NodeId) {
+        if self.find_foreign_unit(name.value).is_some() {
+            return;
+        }
+        self.sess.units.add(DesignUnit {
+            lib: self.sess.units.work(),
+            name: name.value,
+            lang: ForeignLang::Svlog,
+            kind,
+            id,
+            span: name.span,
+            interface: None,
+        });
+    }
+
+    /// Mark a library file as parsed.
+    ///
+    /// Returns `false` if the file has already been parsed before.
+    pub(crate) fn mark_library_file(&self, path: &Path) -> bool {
+        self.library_files.borrow_mut().insert(path.to_path_buf())
+    }
+
+    /// Get the number of executions of every query and the time spent in it.
+    ///
+    /// Only recorded if the `stats` session option is set.
+    pub fn query_stats(&self) -> Vec<(&'static str, QueryStats)> {
+        self.query_stats.get()
+    }
+
+    /// Find the attributes preceding a location in the source.
+    pub fn attributes_at(&self, loc: Location) -> Vec<&'gcx ast::Attribute> {
+        self.attributes
+            .borrow()
+            .get(&loc)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Elaborate a module specialization.
+    ///
+    /// The diagnostics emitted in the process are merged with identical ones
+    /// from other specializations, and emitted once the outermost
+    /// specialization has been elaborated.
+    pub(crate) fn elaborate_specialization<R>(&self, spec: NodeEnvId, f: impl FnOnce() -> R) -> R {
+        self.dedup.enter(spec);
+        let result = f();
+        for (diag, specs) in self.dedup.leave() {
+            self.sess
+                .emit(crate::dedup::add_specializations(self, diag, &specs));
+        }
+        result
+    }
 }
 
 impl DiagEmitter for GlobalContext<'_> {
     fn emit(&self, mut diag: DiagBuilder2) {
+        if self.suppressions.is_suppressed(&diag) {
+            return;
+        }
         let sev = diag.get_severity();
 
         // Extend the diagnostic with some context information as to where in
@@ -219,7 +349,12 @@ impl DiagEmitter for GlobalContext<'_> {
             }
         }
 
-        // Emit the diagnostic.
+        // Emit the diagnostic, unless it is held back to be merged with the
+        // same diagnostic from other module specializations.
+        let diag = match self.dedup.hold(diag) {
+            Some(x) => x,
+            None => return,
+        };
         self.sess.emit(diag);
 
         // If this is anything more than a warning, emit a backtrace in debug
@@ -253,6 +388,18 @@ impl<'gcx> QueryDatabase<'gcx> for GlobalContext<'gcx> {
     fn storage(&self) -> &QueryStorage<'gcx> {
         &self.storage
     }
+
+    fn start_query(&self, _tag: &QueryTag<'gcx>) {
+        if self.sess.opts.stats {
+            self.query_stats.start();
+        }
+    }
+
+    fn after_query(&self, tag: &QueryTag<'gcx>) {
+        if self.sess.opts.stats {
+            self.query_stats.finish(tag.name());
+        }
+    }
 }
 
 impl<'gcx> ty::HasTypeStorage<'gcx> for GlobalContext<'gcx> {
@@ -283,6 +430,9 @@ pub struct GlobalArenas<'t> {
     ast_types: TypedArena<ast::Type<'t>>,
     /// Additional AST expressions generated during HIR lowering.
     ast_exprs: TypedArena<ast::Expr<'t>>,
+    /// Additional AST net declarations for implicit nets.
+    ast_net_decls: TypedArena<ast::NetDecl<'t>>,
+    implicit_nets: TypedArena<ImplicitNet<'t>>,
     /// The underlying storage for type operations.
     type_storage: ty::TypeStorage<'t>,
     func_arg_lists: TypedArena<FuncArgList<'t>>,
@@ -348,6 +498,19 @@ impl<'t> GlobalArenas<'t> {
         self.ast_exprs.alloc(ast)
     }
 
+    /// Allocate an AST net declaration.
+    pub fn alloc_ast_net_decl(&'t self, ast: ast::NetDecl<'t>) -> &'t ast::NetDecl {
+        self.ast_net_decls.alloc(ast)
+    }
+
+    /// Allocate a list of implicit nets.
+    pub fn alloc_implicit_nets(
+        &'t self,
+        nets: impl IntoIterator<Item = ImplicitNet<'t>>,
+    ) -> &'t [ImplicitNet<'t>] {
+        self.implicit_nets.alloc_extend(nets)
+    }
+
     /// Allocate a func arg list.
     pub fn alloc_func_arg_list(&'t self, func_arg_list: FuncArgList<'t>) -> &'t FuncArgList<'t> {
         self.func_arg_lists.alloc(func_arg_list)
@@ -375,6 +538,7 @@ pub struct GlobalTables<'t> {
     node_id_to_parent_node_id: RefCell<HashMap<NodeId, NodeId>>,
     interned_values: RefCell<HashSet<Value<'t>>>,
     lowering_hints: RefCell<HashMap<NodeId, hir::Hint>>,
+    referenced_nodes: RefCell<HashSet<NodeId>>,
     interned_hir: RefCell<HashMap<NodeId, HirNode<'t>>>,
 }
 
@@ -524,12 +688,17 @@ pub trait Context<'gcx>: DiagEmitter + QueryDatabase<'gcx> + ty::HasTypeStorage<
 
     /// Obtain an `ast::AnyNode` associated with a node id.
     fn ast_for_id(&self, node_id: NodeId) -> &'gcx dyn ast::AnyNode<'gcx> {
-        match self.gcx().ast_map2.borrow().get(&node_id) {
-            Some(&node) => node,
+        match self.try_ast_for_id(node_id) {
+            Some(node) => node,
             None => panic!("no AST node for {:?} registered", node_id),
         }
     }
 
+    /// Get the AST node with a given ID, if one is registered.
+    fn try_ast_for_id(&self, node_id: NodeId) -> Option<&'gcx dyn ast::AnyNode<'gcx>> {
+        self.gcx().ast_map2.borrow().get(&node_id).cloned()
+    }
+
     /// Internalize an HIR node.
     fn intern_hir(&self, id: NodeId, hir: HirNode<'gcx>) {
         self.tables().interned_hir.borrow_mut().insert(id, hir);
@@ -654,13 +823,22 @@ pub trait Context<'gcx>: DiagEmitter + QueryDatabase<'gcx> + ty::HasTypeStorage<
     }
 
     /// Resolve a name upwards or emit a diagnostic if nothing is found.
+    ///
+    /// Names that are not declared may refer to an implicit net.
     fn resolve_upwards_or_error(&self, name: Spanned<Name>, start_at: NodeId) -> Result<NodeId> {
         match self.gcx().resolve_upwards(name.value, start_at)? {
             Some(id) => Ok(id),
             None => {
-                self.emit(
-                    DiagBuilder2::error(format!("`{}` not found", name.value)).span(name.span),
-                );
+                let ast = self.ast_for_id(start_at);
+                let net = find_implicit_net(self.gcx(), name.value, ast, ast.order());
+                if let Some(decl) = net.and_then(|net| net.decl) {
+                    return Ok(decl.names[0].id());
+                }
+                let d = DiagBuilder2::error(format!("`{}` not found", name.value))
+                    .code("SV0001")
+                    .span(name.span);
+                let d = note_disallowed_net(net, d);
+                self.emit(d);
                 Err(())
             }
         }
@@ -677,6 +855,7 @@ pub trait Context<'gcx>: DiagEmitter + QueryDatabase<'gcx> + ty::HasTypeStorage<
                         name.value,
                         self.ast_of(start_at)?.desc_full()
                     ))
+                    .code("SV0001")
                     .span(name.span),
                 );
                 Err(())
@@ -697,6 +876,18 @@ pub trait Context<'gcx>: DiagEmitter + QueryDatabase<'gcx> + ty::HasTypeStorage<
         self.tables().lowering_hints.borrow().get(&node_id).cloned()
     }
 
+    /// Mark a node as referenced by name.
+    fn mark_referenced(&self, node_id: NodeId) {
+        self.tables().referenced_nodes.borrow_mut().insert(node_id);
+    }
+
+    /// Check if a node has been referenced by name.
+    ///
+    /// This only covers the references that have been resolved so far.
+    fn is_referenced(&self, node_id: NodeId) -> bool {
+        self.tables().referenced_nodes.borrow().contains(&node_id)
+    }
+
     /// Compute the constant value of a node and make sure it is an integer.
     fn constant_int_value_of(&self, node_id: NodeId, env: ParamEnv) -> Result<&'gcx num::BigInt> {
         match self.gcx().constant_value_of(node_id, env).kind {
@@ -706,6 +897,7 @@ pub trait Context<'gcx>: DiagEmitter + QueryDatabase<'gcx> + ty::HasTypeStorage<
                 let hir = self.gcx().hir_of(node_id)?;
                 self.emit(
                     DiagBuilder2::error(format!("{} is not a constant integer", hir.desc_full()))
+                        .code("SV0030")
                         .span(hir.human_span()),
                 );
                 Err(())
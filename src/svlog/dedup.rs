@@ -0,0 +1,154 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Deduplication of diagnostics across module specializations.
+//!
+//! A module is elaborated once for every parameter environment it is
+//! instantiated with, which repeats every diagnostic in its body that does not
+//! depend on the parameters. While a specialization is being elaborated, the
+//! diagnostics are therefore held back, and merged with identical diagnostics
+//! of other specializations, that is ones with the same code, message, and
+//! location. Once the outermost specialization has been elaborated, the
+//! diagnostics are emitted in their original order, with a note listing the
+//! parameterizations that produced them if there is more than one.
+
+use crate::{
+    common::{
+        errors::{DiagBuilder2, DiagSegment, Severity},
+        source::{Span, INVALID_SPAN},
+    },
+    crate_prelude::*,
+    hir::HirNode,
+    NodeEnvId,
+};
+use std::{cell::RefCell, collections::HashMap};
+
+/// The maximum number of parameterizations listed in the note.
+const MAX_LISTED: usize = 4;
+
+/// The diagnostics held back while module specializations are elaborated.
+#[derive(Default)]
+pub(crate) struct Deduplicator {
+    /// The specializations being elaborated, innermost last.
+    stack: RefCell<Vec<NodeEnvId>>,
+    /// The held back diagnostics, and the specializations that produced them.
+    diags: RefCell<Vec<(DiagBuilder2, Vec<NodeEnvId>)>>,
+    /// The index of every held back diagnostic, by code, message, and span.
+    index: RefCell<HashMap<(Option<&'static str>, String, Span), usize>>,
+}
+
+impl Deduplicator {
+    /// Mark the beginning of the elaboration of a specialization.
+    pub fn enter(&self, spec: NodeEnvId) {
+        self.stack.borrow_mut().push(spec);
+    }
+
+    /// Mark the end of the elaboration of a specialization.
+    ///
+    /// Returns the held back diagnostics if this was the outermost
+    /// specialization.
+    pub fn leave(&self) -> Vec<(DiagBuilder2, Vec<NodeEnvId>)> {
+        let mut stack = self.stack.borrow_mut();
+        stack.pop();
+        if !stack.is_empty() {
+            return vec![];
+        }
+        self.index.borrow_mut().clear();
+        std::mem::take(&mut *self.diags.borrow_mut())
+    }
+
+    /// Hold back a diagnostic until the outermost specialization has been
+    /// elaborated.
+    ///
+    /// Returns the diagnostic back if no specialization is being elaborated,
+    /// or if it is a fatal error or a bug.
+    pub fn hold(&self, diag: DiagBuilder2) -> Option<DiagBuilder2> {
+        // Fatal errors and bugs abort the compilation, so they are never held
+        // back.
+        if diag.get_severity() >= Severity::Fatal {
+            return Some(diag);
+        }
+        let spec = match self.stack.borrow().last() {
+            Some(&x) => x,
+            None => return Some(diag),
+        };
+        let span = diag
+            .get_segments()
+            .iter()
+            .find_map(|seg| match *seg {
+                DiagSegment::Span(x) | DiagSegment::Label(x, _) => Some(x),
+                _ => None,
+            })
+            .unwrap_or(INVALID_SPAN);
+        let key = (diag.get_code(), diag.get_message().clone(), span);
+        let mut diags = self.diags.borrow_mut();
+        match self.index.borrow_mut().get(&key) {
+            Some(&index) => {
+                let specs = &mut diags[index].1;
+                if !specs.contains(&spec) {
+                    specs.push(spec);
+                }
+                return None;
+            }
+            None => (),
+        }
+        self.index.borrow_mut().insert(key, diags.len());
+        diags.push((diag, vec![spec]));
+        None
+    }
+}
+
+/// Add a note listing the parameterizations that produced a diagnostic, if
+/// there is more than one.
+pub(crate) fn add_specializations<'a>(
+    cx: &impl Context<'a>,
+    diag: DiagBuilder2,
+    specs: &[NodeEnvId],
+) -> DiagBuilder2 {
+    if specs.len() < 2 {
+        return diag;
+    }
+    let mut names: Vec<String> = specs
+        .iter()
+        .take(MAX_LISTED)
+        .map(|&spec| format!("`{}`", describe(cx, spec)))
+        .collect();
+    if specs.len() > MAX_LISTED {
+        names.push(format!("and {} more", specs.len() - MAX_LISTED));
+    }
+    diag.add_note(format!(
+        "Occurs in {} parameterizations: {}",
+        specs.len(),
+        names.join(", ")
+    ))
+}
+
+/// Describe a module specialization by the module name and the values of its
+/// parameters, as in `foo #(A = 4, T = logic)`.
+fn describe<'a>(cx: &impl Context<'a>, spec: NodeEnvId) -> String {
+    let hir = match cx.hir_of(spec.id()) {
+        Ok(HirNode::Module(x)) => x,
+        _ => return format!("{}", spec.env()),
+    };
+    let params: Vec<String> = hir
+        .params
+        .iter()
+        .filter_map(|&param_id| match cx.hir_of(param_id) {
+            Ok(HirNode::ValueParam(p)) if !p.local => Some(format!(
+                "{} = {}",
+                p.name.value,
+                cx.constant_value_of(param_id, spec.env())
+            )),
+            Ok(HirNode::TypeParam(p)) if !p.local => Some(format!(
+                "{} = {}",
+                p.name.value,
+                cx.map_to_type_or_error(Ref(cx.ast_for_id(param_id)), spec.env())
+            )),
+            _ => None,
+        })
+        .collect();
+    if params.is_empty() {
+        format!("{}", hir.name.value)
+    } else {
+        format!("{} #({})", hir.name.value, params.join(", "))
+    }
+}
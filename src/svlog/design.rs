@@ -0,0 +1,746 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A flattened view of the elaborated design.
+//!
+//! Output backends other than LLHD need the same information the code
+//! generator derives during elaboration: which module specializations exist,
+//! which generate blocks are active, how instances connect to the ports of the
+//! instantiated module. This module collects that information once, such that
+//! each backend only has to translate the individual assignments and
+//! procedures.
+
+use crate::{
+    crate_prelude::*, hir::HirNode, port_list::IntPort, resolver::InstTarget, ty::UnpackedType,
+    value::ValueKind, IntoNodeEnvId, NodeEnvId, ParamEnv,
+};
+use std::collections::{HashMap, HashSet};
+
+/// An elaborated design.
+#[derive(Debug)]
+pub struct Design<'a> {
+    /// The module specializations, ordered such that every module comes after
+    /// all modules it instantiates. The last top-level module is last.
+    pub modules: Vec<DesignModule<'a>>,
+    /// The top-level modules, as indices into `modules`.
+    pub tops: Vec<usize>,
+}
+
+/// A module specialization.
+#[derive(Debug)]
+pub struct DesignModule<'a> {
+    /// The module and parameter environment.
+    pub id: NodeEnvId,
+    /// The module HIR.
+    pub hir: &'a hir::Module<'a>,
+    /// The name of the specialization, unique within the design.
+    pub name: String,
+    /// The ports of the module.
+    pub ports: Vec<DesignPort<'a>>,
+    /// The variables and nets declared in the module and its active generate
+    /// blocks.
+    pub decls: Vec<DesignDecl<'a>>,
+    /// The continuous assignments in the module and its active generate
+    /// blocks.
+    pub assigns: Vec<NodeEnvId>,
+    /// The procedures in the module and its active generate blocks.
+    pub procs: Vec<NodeEnvId>,
+    /// The module instances in the module and its active generate blocks.
+    pub insts: Vec<DesignInst>,
+}
+
+/// A port of a module specialization.
+#[derive(Debug)]
+pub struct DesignPort<'a> {
+    /// The internal port.
+    pub port: &'a IntPort<'a>,
+    /// The type of the port.
+    pub ty: &'a UnpackedType<'a>,
+}
+
+/// A variable or net declaration.
+#[derive(Debug)]
+pub struct DesignDecl<'a> {
+    /// The declaration.
+    pub id: NodeId,
+    /// The parameter environment of the declaration, including the values of
+    /// any surrounding generate loop variables.
+    pub env: ParamEnv,
+    /// The hierarchical name of the declaration within the module, e.g.
+    /// `g[1].x` for a declaration in a generate loop.
+    pub name: String,
+    /// The declaration HIR.
+    pub hir: &'a hir::VarDecl,
+    /// The type of the declaration.
+    pub ty: &'a UnpackedType<'a>,
+}
+
+/// A module instance.
+#[derive(Debug)]
+pub struct DesignInst {
+    /// The instantiation.
+    pub id: NodeId,
+    /// The parameter environment around the instantiation, including the
+    /// values of any surrounding generate loop variables.
+    pub env: ParamEnv,
+    /// The hierarchical name of the instance within the module.
+    pub name: String,
+    /// The instantiated module specialization, as an index into
+    /// `Design::modules`.
+    pub module: usize,
+    /// The expression connected to each port of the instantiated module, in
+    /// the order of `DesignModule::ports`. `None` for unconnected ports.
+    pub ports: Vec<Option<NodeEnvId>>,
+}
+
+impl<'a> Design<'a> {
+    /// Collect the design below a top-level module.
+    pub fn new(cx: &impl Context<'a>, top: NodeId, env: ParamEnv) -> Result<Self> {
+        Self::with_tops(cx, &[top], env)
+    }
+
+    /// Collect the design below multiple top-level modules.
+    ///
+    /// Modules instantiated below several of the top-level modules are only
+    /// collected once.
+    pub fn with_tops(cx: &impl Context<'a>, tops: &[NodeId], env: ParamEnv) -> Result<Self> {
+        let mut builder = Builder {
+            cx,
+            modules: vec![],
+            lookup: HashMap::new(),
+            depth: 0,
+        };
+        let mut indices = vec![];
+        for &top in tops {
+            indices.push(builder.add_module(top.env(env))?);
+        }
+        Ok(Design {
+            modules: builder.modules,
+            tops: indices,
+        })
+    }
+
+    /// Get the top-level module, or the last one if there are several.
+    pub fn top(&self) -> &DesignModule<'a> {
+        &self.modules[*self.tops.last().unwrap()]
+    }
+}
+
+impl<'a> DesignModule<'a> {
+    /// Find a declaration as referenced from within a parameter environment.
+    ///
+    /// Declarations in generate loops exist once per iteration. References
+    /// from within the same iteration resolve to that iteration's declaration,
+    /// all others to the only instance of the declaration.
+    pub fn find_decl(&self, id: NodeId, env: ParamEnv) -> Option<&DesignDecl<'a>> {
+        self.decls
+            .iter()
+            .find(|d| d.id == id && d.env == env)
+            .or_else(|| self.decls.iter().find(|d| d.id == id))
+    }
+
+    /// Find the index of the port with a given node ID.
+    pub fn find_port(&self, id: NodeId) -> Option<usize> {
+        self.ports.iter().position(|p| p.port.id == id)
+    }
+
+    /// Find the storage of a declaration or port as seen from a parameter
+    /// environment.
+    ///
+    /// Variables declared within procedures are looked up in the `storage` an
+    /// output backend has allocated so far. Ports are stored in the
+    /// environment of the module.
+    pub fn storage_key<V>(
+        &self,
+        storage: &HashMap<(NodeId, ParamEnv), V>,
+        id: NodeId,
+        env: ParamEnv,
+    ) -> Option<(NodeId, ParamEnv)> {
+        if storage.contains_key(&(id, env)) {
+            return Some((id, env));
+        }
+        if let Some(decl) = self.find_decl(id, env) {
+            return Some((decl.id, decl.env));
+        }
+        self.find_port(id).map(|_| (id, self.id.env()))
+    }
+}
+
+/// A helper to collect the module specializations of a design.
+struct Builder<'a, 'c, C> {
+    cx: &'c C,
+    modules: Vec<DesignModule<'a>>,
+    lookup: HashMap<NodeEnvId, usize>,
+    /// The number of instances enclosing the module being collected.
+    depth: usize,
+}
+
+impl<'a, 'c, C: Context<'a>> Builder<'a, 'c, C> {
+    fn add_module(&mut self, id: NodeEnvId) -> Result<usize> {
+        if let Some(&index) = self.lookup.get(&id) {
+            return Ok(index);
+        }
+        let hir = match self.cx.hir_of(id.id())? {
+            HirNode::Module(x) => x,
+            _ => unreachable!(),
+        };
+        let env = id.env();
+
+        // Name the specialization the same way the code generator names the
+        // emitted entity.
+        let mut name: String = hir.name.value.into();
+        if env != self.cx.default_param_env() {
+            name.push_str(&format!(".param{}", env.0));
+        }
+
+        // Collect the ports.
+        let mut ports = vec![];
+        for port in &hir.ports_new.int {
+            let ty = self.cx.type_of_int_port(Ref(port), env);
+            if ty.resolve_full().core.get_interface().is_some() {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "interface port `{}` not supported by this output format",
+                        port.name
+                    ))
+                    .code("SV0046")
+                    .span(port.span),
+                );
+                return Err(());
+            }
+            ports.push(DesignPort { port, ty });
+        }
+
+        // Collect the contents.
+        let mut module = DesignModule {
+            id,
+            hir,
+            name,
+            ports,
+            decls: vec![],
+            assigns: vec![],
+            procs: vec![],
+            insts: vec![],
+        };
+        self.add_block(&mut module, &hir.block, env, "")?;
+
+        let index = self.modules.len();
+        self.modules.push(module);
+        self.lookup.insert(id, index);
+        Ok(index)
+    }
+
+    fn add_block(
+        &mut self,
+        module: &mut DesignModule<'a>,
+        block: &'a hir::ModuleBlock,
+        env: ParamEnv,
+        scope: &str,
+    ) -> Result<()> {
+        for &decl_id in &block.decls {
+            let hir = match self.cx.hir_of(decl_id)? {
+                HirNode::VarDecl(x) => x,
+                _ => unreachable!(),
+            };
+            module.decls.push(DesignDecl {
+                id: decl_id,
+                env,
+                name: format!("{}{}", scope, hir.name.value),
+                hir,
+                ty: self.cx.type_of(decl_id, env)?,
+            });
+        }
+        let cx = self.cx;
+        for &gen_id in &block.gens {
+            expand_generate(cx, gen_id, env, |block_id, env, index| {
+                let block = match cx.hir_of(block_id)? {
+                    HirNode::GenerateBlock(x) => x,
+                    _ => unreachable!(),
+                };
+                let scope = match (block.label(), index) {
+                    (Some(label), Some(index)) => format!("{}{}[{}].", scope, label, index),
+                    (Some(label), None) => format!("{}{}.", scope, label),
+                    (None, _) => scope.to_string(),
+                };
+                self.add_block(module, &block.block, env, &scope)
+            })?;
+        }
+        module
+            .assigns
+            .extend(block.assigns.iter().map(|&id| id.env(env)));
+        module
+            .procs
+            .extend(block.procs.iter().map(|&id| id.env(env)));
+        for &inst_id in &block.insts {
+            let inst = match self.cx.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let details = self.cx.inst_details(Ref(inst), env)?;
+            let target = match details.target.kind {
+                InstTarget::Module(x) => self.cx.hir_of_module(x)?,
+                _ => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "interface instance `{}` not supported by this output format",
+                            inst.name
+                        ))
+                        .code("SV0046")
+                        .span(inst.ast.span()),
+                    );
+                    return Err(());
+                }
+            };
+            if !inst.ast.dims.is_empty() {
+                self.cx.emit(
+                    DiagBuilder2::error("instance arrays not supported by this output format")
+                        .code("SV0046")
+                        .span(inst.ast.span()),
+                );
+                return Err(());
+            }
+
+            // Collect the instantiated module, guarding against unbounded
+            // recursive instantiations.
+            let max_depth = self.cx.sess().opts.max_inst_depth;
+            if max_depth > 0 && self.depth >= max_depth {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "instance hierarchy exceeds the maximum depth of {}",
+                        max_depth
+                    ))
+                    .code("SV0032")
+                    .span(inst.ast.span())
+                    .add_note(format!(
+                        "Instantiation of module `{}` is nested too deeply; use \
+                         `--max-inst-depth` to raise the limit",
+                        target.ast.name
+                    )),
+                );
+                return Err(());
+            }
+            self.depth += 1;
+            let index = self.add_module(target.id.env(details.inner_env));
+            self.depth -= 1;
+            let index = index?;
+
+            // Map the connections to external ports to the internal ports.
+            let mut ports = vec![None; target.ports_new.int.len()];
+            for port in &target.ports_new.ext_pos {
+                let mapping = match details.ports.find(port.id) {
+                    Some(m) => m,
+                    None => continue,
+                };
+                match port.exprs.as_slice() {
+                    [] => (),
+                    [expr] if expr.selects.is_empty() => ports[expr.port] = Some(mapping),
+                    _ => {
+                        self.cx.emit(
+                            DiagBuilder2::error(
+                                "port expressions with concatenations or selections not \
+                                 supported by this output format",
+                            )
+                            .code("SV0046")
+                            .span(inst.ast.span())
+                            .add_note("Port declared here:")
+                            .span(port.span),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+            module.insts.push(DesignInst {
+                id: inst_id,
+                env,
+                name: format!("{}{}", scope, inst.name.value),
+                module: index,
+                ports,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Visit the active blocks of a generate statement.
+///
+/// Calls `f` with the generate block, the parameter environment with the
+/// generate loop variable bound, and the loop index for each block that is
+/// active in the given environment. The blocks are visited as the loop is
+/// unrolled, such that parameter environments created while handling one
+/// block are interned before the ones of the next iteration.
+pub(crate) fn expand_generate<'a>(
+    cx: &impl Context<'a>,
+    gen_id: NodeId,
+    env: ParamEnv,
+    mut f: impl FnMut(NodeId, ParamEnv, Option<String>) -> Result<()>,
+) -> Result<()> {
+    let hir = match cx.hir_of(gen_id)? {
+        HirNode::Gen(x) => x,
+        _ => unreachable!(),
+    };
+    match hir.kind {
+        hir::GenKind::If {
+            cond,
+            main_body,
+            else_body,
+        } => {
+            let k = cx.constant_value_of(cond, env);
+            if k.is_false() {
+                if let Some(else_body) = else_body {
+                    f(else_body, env, None)?;
+                }
+            } else {
+                f(main_body, env, None)?;
+            }
+        }
+        hir::GenKind::For {
+            ref init,
+            cond,
+            step,
+            body,
+        } => {
+            let mut local_env = env;
+            for &i in init {
+                local_env = execute_genvar_init(cx, i, local_env)?;
+            }
+            let genvar = genvar_of_init(cx, init[0], env)?;
+            while cx.constant_value_of(cond, local_env).is_true() {
+                let index = cx.constant_value_of(genvar, local_env);
+                f(body, local_env, Some(index.kind.to_string()))?;
+                local_env = execute_genvar_step(cx, step, local_env)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Determine the signal an event or reset condition refers to, looking
+/// through negations.
+pub(crate) fn event_signal<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    env: ParamEnv,
+) -> Result<Option<NodeId>> {
+    match cx.hir_of(expr_id)? {
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Ident(..),
+            ..
+        }) => Ok(Some(cx.resolve_node(expr_id, env)?)),
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Unary(hir::UnaryOp::LogicNot, arg),
+            ..
+        })
+        | HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Unary(hir::UnaryOp::BitNot, arg),
+            ..
+        }) => event_signal(cx, *arg, env),
+        _ => Ok(None),
+    }
+}
+
+/// The events of a procedure triggered by a clock edge.
+pub(crate) struct ClockedEvents<'a> {
+    /// The event the procedure is clocked by.
+    pub clock: &'a hir::Event,
+    /// The asynchronous reset of the procedure, if it has one.
+    pub reset: Option<AsyncReset<'a>>,
+}
+
+/// The asynchronous reset of a procedure triggered by a clock edge.
+pub(crate) struct AsyncReset<'a> {
+    /// The event the reset triggers.
+    pub event: &'a hir::Event,
+    /// The condition of the `if` statement that tests the reset.
+    pub cond: NodeId,
+    /// The statements executed while the reset is active.
+    pub main_stmt: NodeId,
+    /// The statements executed on a clock edge.
+    pub else_stmt: Option<NodeId>,
+}
+
+/// Separate the events of a clocked procedure into the clock and an optional
+/// asynchronous reset.
+pub(crate) fn clocked_events<'a>(
+    cx: &impl Context<'a>,
+    event_id: NodeId,
+    body: NodeId,
+    env: ParamEnv,
+) -> Result<ClockedEvents<'a>> {
+    let events = match cx.hir_of(event_id)? {
+        HirNode::EventExpr(x) => &x.events,
+        _ => unreachable!(),
+    };
+
+    // Identify an asynchronous reset, which is an event whose signal is
+    // tested by an `if` around the entire body.
+    let reset_if = match cx.hir_of(single_stmt(cx, body)?)? {
+        HirNode::Stmt(hir::Stmt {
+            kind:
+                hir::StmtKind::If {
+                    cond,
+                    main_stmt,
+                    else_stmt,
+                },
+            ..
+        }) => Some((*cond, *main_stmt, *else_stmt)),
+        _ => None,
+    };
+    let mut clock = None;
+    let mut reset = None;
+    for event in events {
+        let signal = event_signal(cx, event.expr, env)?;
+        let is_reset = match (reset_if, signal) {
+            (Some((cond, ..)), Some(signal)) if events.len() > 1 => {
+                event_signal(cx, cond, env)? == Some(signal)
+            }
+            _ => false,
+        };
+        if is_reset && reset.is_none() {
+            reset = reset_if.map(|(cond, main_stmt, else_stmt)| AsyncReset {
+                event,
+                cond,
+                main_stmt,
+                else_stmt,
+            });
+        } else if clock.is_none() {
+            clock = Some(event);
+        } else {
+            cx.emit(
+                DiagBuilder2::error(
+                    "procedures triggered by multiple clocks not supported by this output format",
+                )
+                .code("SV0046")
+                .span(event.span),
+            );
+            return Err(());
+        }
+    }
+    match clock {
+        Some(clock) => Ok(ClockedEvents { clock, reset }),
+        None => {
+            cx.emit(
+                DiagBuilder2::error("procedure has no clock")
+                    .code("SV0046")
+                    .span(cx.span(event_id)),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Skip blocks that contain a single statement.
+fn single_stmt<'a>(cx: &impl Context<'a>, stmt_id: NodeId) -> Result<NodeId> {
+    match cx.hir_of(stmt_id)? {
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Block(stmts),
+            ..
+        }) if stmts.len() == 1 => single_stmt(cx, stmts[0]),
+        _ => Ok(stmt_id),
+    }
+}
+
+/// Determine the width of a type in an output backend that only supports
+/// bit vectors.
+pub(crate) fn width<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    span: Span,
+) -> Result<usize> {
+    match ty.get_simple_bit_vector() {
+        Some(sbv) if ty.coalesces_to_llhd_scalar() && sbv.size > 0 => Ok(sbv.size),
+        _ => {
+            if !ty.is_error() {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "type `{}` not supported by this output format",
+                        ty
+                    ))
+                    .code("SV0046")
+                    .span(span),
+                );
+            }
+            Err(())
+        }
+    }
+}
+
+/// Report a statement that an output backend cannot translate.
+pub(crate) fn unsupported<'a>(cx: &impl Context<'a>, hir: &hir::Stmt) -> Result<()> {
+    cx.emit(
+        DiagBuilder2::error(format!(
+            "{} not supported by this output format",
+            hir.desc_full()
+        ))
+        .code("SV0046")
+        .span(hir.span),
+    );
+    Err(())
+}
+
+/// Report an expression that an output backend cannot translate.
+pub(crate) fn unsupported_expr<'a, T>(cx: &impl Context<'a>, mir: &mir::Rvalue) -> Result<T> {
+    cx.emit(
+        DiagBuilder2::error(format!(
+            "expression `{}` not supported by this output format",
+            mir.span.extract()
+        ))
+        .code("SV0046")
+        .span(mir.span),
+    );
+    Err(())
+}
+
+/// Replace characters that are not valid in the identifiers of the output
+/// formats.
+pub(crate) fn sanitize(name: &str) -> String {
+    let mut name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '$' => c,
+            _ => '_',
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit() || c == '$') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Make a name unique among a set of names.
+pub(crate) fn unique(names: &mut HashSet<String>, name: &str) -> String {
+    let mut candidate = name.to_string();
+    let mut index = 0;
+    while !names.insert(candidate.clone()) {
+        index += 1;
+        candidate = format!("{}_{}", name, index);
+    }
+    candidate
+}
+
+/// An expression emitted by an output backend, with a fixed number of bits.
+pub(crate) trait WidthExpr: Sized {
+    /// The number of bits of the expression.
+    fn width(&self) -> usize;
+
+    /// Keep the `width` least significant bits of the expression.
+    fn truncate(self, width: usize) -> Self;
+
+    /// Extend the expression to `width` bits with leading zeros.
+    fn zero_extend(self, width: usize) -> Self;
+
+    /// Reduce the expression to a single bit that is set if any bit is set.
+    fn reduce_or(self) -> Self;
+}
+
+/// Truncate or zero-extend an expression to a width.
+pub(crate) fn fit<E: WidthExpr>(value: E, width: usize) -> E {
+    if value.width() > width {
+        value.truncate(width)
+    } else if value.width() < width {
+        value.zero_extend(width)
+    } else {
+        value
+    }
+}
+
+/// Reduce a value to a single bit that is set if any bit is set.
+pub(crate) fn to_bool<E: WidthExpr>(value: E) -> E {
+    if value.width() == 1 {
+        value
+    } else {
+        value.reduce_or()
+    }
+}
+
+/// Execute the initialization step of a generate loop.
+fn execute_genvar_init<'a>(cx: &impl Context<'a>, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
+    let hir = cx.hir_of(id)?;
+    match hir {
+        HirNode::GenvarDecl(_) => Ok(env),
+        HirNode::Stmt(stmt) => match stmt.kind {
+            hir::StmtKind::Assign {
+                lhs,
+                rhs,
+                kind: hir::AssignKind::Block(ast::AssignOp::Identity),
+            } => {
+                let target_id = cx.resolve_node(lhs, env)?;
+                let init_value = cx.constant_value_of(rhs, env);
+                let mut env_data = cx.param_env_data(env).clone();
+                env_data.set_value(target_id, init_value);
+                Ok(cx.intern_param_env(env_data))
+            }
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Determine the genvar initialized by a generate loop.
+fn genvar_of_init<'a>(cx: &impl Context<'a>, id: NodeId, env: ParamEnv) -> Result<NodeId> {
+    match cx.hir_of(id)? {
+        HirNode::GenvarDecl(_) => Ok(id),
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Assign { lhs, .. },
+            ..
+        }) => cx.resolve_node(*lhs, env),
+        _ => unreachable!(),
+    }
+}
+
+/// Execute the iteration step of a generate loop.
+fn execute_genvar_step<'a>(cx: &impl Context<'a>, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
+    let hir = cx.hir_of(id)?;
+    let mut env_data = cx.param_env_data(env).clone();
+    let next = match hir {
+        HirNode::Expr(expr) => match expr.kind {
+            hir::ExprKind::Unary(op, target_id) => {
+                let target_id = cx.resolve_node(target_id, env)?;
+                let current_value = cx.constant_value_of(target_id, env);
+                let next_value = match current_value.kind {
+                    ValueKind::Int(ref v, ..) => match op {
+                        hir::UnaryOp::PostInc | hir::UnaryOp::PreInc => Some(v + 1),
+                        hir::UnaryOp::PostDec | hir::UnaryOp::PreDec => Some(v - 1),
+                        _ => None,
+                    }
+                    .map(|v| crate::value::make_int(current_value.ty, v)),
+                    _ => unreachable!(),
+                };
+                next_value.map(|v| (target_id, cx.intern_value(v)))
+            }
+            hir::ExprKind::Assign { .. } => {
+                let mir = cx.mir_rvalue(id, env);
+                match mir.kind {
+                    mir::RvalueKind::Error => return Err(()),
+                    mir::RvalueKind::Assignment { lvalue, rvalue, .. } => {
+                        let target_id = match lvalue.kind {
+                            mir::LvalueKind::Error => return Err(()),
+                            mir::LvalueKind::Genvar(id) => id,
+                            _ => unreachable!(),
+                        };
+                        let next_value = cx.const_mir_rvalue(Ref(rvalue));
+                        Some((target_id, next_value))
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    match next {
+        Some((target_id, next_value)) => {
+            env_data.set_value(target_id, next_value);
+            Ok(cx.intern_param_env(env_data))
+        }
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "{} is not a valid genvar iteration step",
+                    hir.desc_full()
+                ))
+                .code("SV0041")
+                .span(hir.human_span()),
+            );
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,369 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Documentation generated from the doc comments in the input.
+//!
+//! This module collects the packages, modules, and interfaces of the input,
+//! together with their parameters and ports and the documentation comments
+//! attached to each of them. Parameters are listed with their default value as
+//! written in the source, and ports with their direction and type. The result
+//! can be written as Markdown or as a standalone HTML page.
+
+use crate::{
+    ast_map::AstNode,
+    common::{design_units::DesignUnitKind, foreign::ForeignLang},
+    crate_prelude::*,
+    hir::HirNode,
+    port_list::PortList,
+};
+use std::io::{Result as IoResult, Write};
+
+/// The documentation of all design units in the input.
+#[derive(Debug)]
+pub struct Docs<'a> {
+    /// The documented units, in the order they were declared.
+    pub units: Vec<DocUnit<'a>>,
+}
+
+/// The documentation of a package, module, or interface.
+#[derive(Debug)]
+pub struct DocUnit<'a> {
+    /// Whether this is a package, module, or interface.
+    pub kind: DesignUnitKind,
+    /// The name of the unit.
+    pub name: Spanned<Name>,
+    /// The documentation comment attached to the unit.
+    pub doc: Option<&'a str>,
+    /// The parameters of the unit. Local parameters are only listed for
+    /// packages.
+    pub params: Vec<DocParam<'a>>,
+    /// The ports of the unit.
+    pub ports: Vec<DocPort<'a>>,
+}
+
+/// The documentation of a parameter.
+#[derive(Debug)]
+pub struct DocParam<'a> {
+    /// The name of the parameter.
+    pub name: Name,
+    /// The type of the parameter as written, `type` for a type parameter, or
+    /// empty if the type is implicit.
+    pub ty: String,
+    /// The default value of the parameter as written.
+    pub default: Option<String>,
+    /// The documentation comment attached to the parameter.
+    pub doc: Option<&'a str>,
+}
+
+/// The documentation of a port.
+#[derive(Debug)]
+pub struct DocPort<'a> {
+    /// The name of the port.
+    pub name: Name,
+    /// The direction of the port.
+    pub dir: ast::PortDir,
+    /// The type of the port.
+    pub ty: String,
+    /// The documentation comment attached to the port.
+    pub doc: Option<&'a str>,
+}
+
+impl<'a> Docs<'a> {
+    /// Collect the documentation of all packages, modules, and interfaces.
+    pub fn new(cx: &impl Context<'a>) -> Result<Self> {
+        let mut units = vec![];
+        for unit in cx.sess().units.units() {
+            if unit.lang != ForeignLang::Svlog {
+                continue;
+            }
+            units.push(match cx.ast_of(unit.id)? {
+                AstNode::Package(x) => package(cx, x),
+                AstNode::Module(x) => module(cx, unit.id, x)?,
+                AstNode::Interface(x) => interface(cx, unit.id, x)?,
+                _ => continue,
+            });
+        }
+        Ok(Docs { units })
+    }
+
+    /// Write the documentation as Markdown.
+    pub fn write_markdown(&self, w: &mut impl Write) -> IoResult<()> {
+        for (i, unit) in self.units.iter().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
+            }
+            writeln!(w, "## {} `{}`", title(unit.kind), unit.name)?;
+            if let Some(doc) = unit.doc {
+                writeln!(w)?;
+                writeln!(w, "{}", doc.trim_end())?;
+            }
+            if !unit.params.is_empty() {
+                writeln!(w)?;
+                writeln!(w, "### Parameters")?;
+                writeln!(w)?;
+                writeln!(w, "| Name | Type | Default | Description |")?;
+                writeln!(w, "|------|------|---------|-------------|")?;
+                for param in &unit.params {
+                    writeln!(
+                        w,
+                        "| `{}` | {} | {} | {} |",
+                        param.name,
+                        md_code(&param.ty),
+                        param.default.as_deref().map(md_code).unwrap_or_default(),
+                        md_cell(param.doc)
+                    )?;
+                }
+            }
+            if !unit.ports.is_empty() {
+                writeln!(w)?;
+                writeln!(w, "### Ports")?;
+                writeln!(w)?;
+                writeln!(w, "| Name | Direction | Type | Description |")?;
+                writeln!(w, "|------|-----------|------|-------------|")?;
+                for port in &unit.ports {
+                    writeln!(
+                        w,
+                        "| `{}` | {} | {} | {} |",
+                        port.name,
+                        port.dir,
+                        md_code(&port.ty),
+                        md_cell(port.doc)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the documentation as a standalone HTML page.
+    pub fn write_html(&self, w: &mut impl Write) -> IoResult<()> {
+        writeln!(w, "<!DOCTYPE html>")?;
+        writeln!(w, "<html>")?;
+        writeln!(w, "<head>")?;
+        writeln!(w, "<meta charset=\"utf-8\">")?;
+        writeln!(w, "<title>Design Documentation</title>")?;
+        writeln!(w, "</head>")?;
+        writeln!(w, "<body>")?;
+        for unit in &self.units {
+            writeln!(
+                w,
+                "<h2 id=\"{}\">{} <code>{}</code></h2>",
+                unit.name,
+                title(unit.kind),
+                unit.name
+            )?;
+            if let Some(doc) = unit.doc {
+                for para in doc.split("\n\n").filter(|x| !x.trim().is_empty()) {
+                    writeln!(w, "<p>{}</p>", html_escape(para.trim()))?;
+                }
+            }
+            if !unit.params.is_empty() {
+                writeln!(w, "<h3>Parameters</h3>")?;
+                writeln!(w, "<table>")?;
+                writeln!(
+                    w,
+                    "<tr><th>Name</th><th>Type</th><th>Default</th><th>Description</th></tr>"
+                )?;
+                for param in &unit.params {
+                    writeln!(
+                        w,
+                        "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        param.name,
+                        html_code(&param.ty),
+                        param.default.as_deref().map(html_code).unwrap_or_default(),
+                        html_escape(param.doc.unwrap_or("").trim())
+                    )?;
+                }
+                writeln!(w, "</table>")?;
+            }
+            if !unit.ports.is_empty() {
+                writeln!(w, "<h3>Ports</h3>")?;
+                writeln!(w, "<table>")?;
+                writeln!(
+                    w,
+                    "<tr><th>Name</th><th>Direction</th><th>Type</th><th>Description</th></tr>"
+                )?;
+                for port in &unit.ports {
+                    writeln!(
+                        w,
+                        "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        port.name,
+                        port.dir,
+                        html_code(&port.ty),
+                        html_escape(port.doc.unwrap_or("").trim())
+                    )?;
+                }
+                writeln!(w, "</table>")?;
+            }
+        }
+        writeln!(w, "</body>")?;
+        writeln!(w, "</html>")
+    }
+}
+
+/// Document a package.
+fn package<'a>(cx: &impl Context<'a>, ast: &'a ast::Package<'a>) -> DocUnit<'a> {
+    DocUnit {
+        kind: DesignUnitKind::Package,
+        name: ast.name,
+        doc: doc(cx, ast.id()),
+        params: params(cx, &[], &ast.items, true),
+        ports: vec![],
+    }
+}
+
+/// Document a module.
+fn module<'a>(cx: &impl Context<'a>, id: NodeId, ast: &'a ast::Module<'a>) -> Result<DocUnit<'a>> {
+    let hir = match cx.hir_of(id)? {
+        HirNode::Module(x) => x,
+        _ => unreachable!(),
+    };
+    Ok(DocUnit {
+        kind: DesignUnitKind::Module,
+        name: ast.name,
+        doc: doc(cx, ast.id()),
+        params: params(cx, &ast.params, &ast.items, false),
+        ports: ports(cx, hir.ports_new),
+    })
+}
+
+/// Document an interface.
+fn interface<'a>(
+    cx: &impl Context<'a>,
+    id: NodeId,
+    ast: &'a ast::Interface<'a>,
+) -> Result<DocUnit<'a>> {
+    let hir = match cx.hir_of(id)? {
+        HirNode::Interface(x) => x,
+        _ => unreachable!(),
+    };
+    Ok(DocUnit {
+        kind: DesignUnitKind::Interface,
+        name: ast.name,
+        doc: doc(cx, ast.id()),
+        params: params(cx, &ast.params, &ast.items, false),
+        ports: ports(cx, hir.ports),
+    })
+}
+
+/// Document the parameters declared in the header and the body of a unit.
+fn params<'a>(
+    cx: &impl Context<'a>,
+    header: &'a [ast::ParamDecl<'a>],
+    items: &'a [ast::Item<'a>],
+    local: bool,
+) -> Vec<DocParam<'a>> {
+    let body = items.iter().flat_map(|item| match item.data {
+        ast::ItemData::ParamDecl(ref x) => Some(x),
+        _ => None,
+    });
+    let mut params = vec![];
+    for decl in header.iter().chain(body) {
+        if decl.local && !local {
+            continue;
+        }
+        match decl.kind {
+            ast::ParamKind::Type(ref decls) => {
+                for decl in decls {
+                    params.push(DocParam {
+                        name: decl.name.value,
+                        ty: "type".to_string(),
+                        default: decl.ty.as_ref().map(|ty| ty.span.extract()),
+                        doc: doc(cx, decl.id()),
+                    });
+                }
+            }
+            ast::ParamKind::Value(ref decls) => {
+                for decl in decls {
+                    let ty = if decl.ty.is_implicit() && decl.ty.dims.is_empty() {
+                        String::new()
+                    } else {
+                        decl.ty.span.extract()
+                    };
+                    params.push(DocParam {
+                        name: decl.name.value,
+                        ty,
+                        default: decl.expr.as_ref().map(|expr| expr.span.extract()),
+                        doc: doc(cx, decl.id()),
+                    });
+                }
+            }
+        }
+    }
+    params
+}
+
+/// Document the ports of a unit.
+fn ports<'a>(cx: &impl Context<'a>, ports: &'a PortList<'a>) -> Vec<DocPort<'a>> {
+    ports
+        .int
+        .iter()
+        .map(|port| {
+            // Use the type as written if possible, and the resolved type for
+            // implicit types and unpacked arrays.
+            let ty = match port.data {
+                Some(ref data) if !data.ty.is_implicit() && data.unpacked_dims.is_empty() => {
+                    data.ty.span.extract()
+                }
+                _ => cx
+                    .type_of_int_port(Ref(port), cx.default_param_env())
+                    .to_string(),
+            };
+            DocPort {
+                name: port.name.value,
+                dir: port.dir,
+                ty,
+                doc: doc(cx, port.id),
+            }
+        })
+        .collect()
+}
+
+/// Find the text of the documentation comment attached to a node.
+fn doc<'a>(cx: &impl Context<'a>, id: NodeId) -> Option<&'a str> {
+    cx.doc_comment(id).map(|doc| doc.text.as_str())
+}
+
+/// The heading used for a kind of unit.
+fn title(kind: DesignUnitKind) -> &'static str {
+    match kind {
+        DesignUnitKind::Package => "Package",
+        DesignUnitKind::Interface => "Interface",
+        _ => "Module",
+    }
+}
+
+/// Format text as inline code in a Markdown table cell.
+fn md_code(text: &str) -> String {
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("`{}`", collapse(text).replace('|', "\\|"))
+    }
+}
+
+/// Format a documentation comment for a Markdown table cell.
+fn md_cell(doc: Option<&str>) -> String {
+    collapse(doc.unwrap_or("")).replace('|', "\\|")
+}
+
+/// Format text as inline code in an HTML table cell.
+fn html_code(text: &str) -> String {
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("<code>{}</code>", html_escape(&collapse(text)))
+    }
+}
+
+/// Join the lines of a text with single spaces.
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escape the characters of a text that have a meaning in HTML.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,260 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Graphviz renderings of an elaborated design.
+//!
+//! The hierarchy graph has a node for every module specialization, labeled
+//! with the values of its parameters, and an edge for every instance, labeled
+//! with the parameters that the instantiation assigns. The dataflow graphs
+//! show, for every module specialization, which signals and instances drive
+//! which other signals.
+
+use crate::{
+    comb_loops::is_combinational,
+    crate_prelude::*,
+    design::{Design, DesignModule},
+    hir::{AccessedNode, HirNode},
+    ParamEnv,
+};
+use std::collections::{BTreeSet, HashMap};
+
+/// Render the instantiation hierarchy of a design.
+pub fn emit_hierarchy<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<String> {
+    let mut out = String::new();
+    out.push_str("digraph hierarchy {\n");
+    out.push_str("  node [shape=box];\n");
+    for (index, module) in design.modules.iter().enumerate() {
+        let mut label = module.name.clone();
+        for &param_id in module.hir.params {
+            if let Some(param) = param_label(cx, param_id, module.id.env())? {
+                label.push('\n');
+                label.push_str(&param);
+            }
+        }
+        out.push_str(&format!("  m{} [label={}];\n", index, quote(&label)));
+    }
+    for (index, module) in design.modules.iter().enumerate() {
+        for inst in &module.insts {
+            let hir = match cx.hir_of(inst.id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let details = cx.inst_details(Ref(hir), inst.env)?;
+            let params = details.target.params;
+            let mut label = inst.name.clone();
+            for &param_id in design.modules[inst.module].hir.params {
+                if params.find_value(param_id).is_none() && params.find_type(param_id).is_none() {
+                    continue;
+                }
+                if let Some(param) = param_label(cx, param_id, details.inner_env)? {
+                    label.push('\n');
+                    label.push_str(&param);
+                }
+            }
+            out.push_str(&format!(
+                "  m{} -> m{} [label={}];\n",
+                index,
+                inst.module,
+                quote(&label)
+            ));
+        }
+    }
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// Render the dataflow graph of every module specialization in a design.
+pub fn emit_dataflow<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<String> {
+    let mut out = String::new();
+    for (index, module) in design.modules.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        let mut graph = Dataflow {
+            cx,
+            design,
+            module,
+            signals: HashMap::new(),
+            nodes: vec![],
+            edges: BTreeSet::new(),
+        };
+        graph.build()?;
+        graph.write(&mut out);
+    }
+    Ok(out)
+}
+
+/// The dataflow graph of a single module specialization.
+struct Dataflow<'a, 'c, C> {
+    cx: &'c C,
+    design: &'c Design<'a>,
+    module: &'c DesignModule<'a>,
+    /// The node of every port and declaration of the module.
+    signals: HashMap<(NodeId, ParamEnv), usize>,
+    /// The label and attributes of every node.
+    nodes: Vec<String>,
+    /// The edges between nodes, with their label and whether they pass
+    /// through a register.
+    edges: BTreeSet<(usize, usize, String, bool)>,
+}
+
+impl<'a, 'c, C: Context<'a>> Dataflow<'a, 'c, C> {
+    fn build(&mut self) -> Result<()> {
+        let module = self.module;
+        let env = module.id.env();
+        for port in &module.ports {
+            self.signals.insert((port.port.id, env), self.nodes.len());
+            self.nodes.push(format!(
+                "label={}, shape=box",
+                quote(&format!("{} {}", port.port.dir, port.port.name))
+            ));
+        }
+        for decl in &module.decls {
+            self.signals.insert((decl.id, decl.env), self.nodes.len());
+            self.nodes.push(format!("label={}", quote(&decl.name)));
+        }
+
+        // Continuous assignments drive their left-hand side from all signals
+        // read on the right-hand side.
+        for &id in &module.assigns {
+            let acc = self.cx.accessed_nodes(id.id(), id.env())?;
+            for &to in &acc.written {
+                for &from in &acc.read {
+                    self.add_edge(from, to, id.env(), false);
+                }
+            }
+        }
+
+        // Procedures drive their outputs from all of their inputs. In
+        // combinational procedures, signals that are both read and written are
+        // assumed to be written first.
+        for &id in &module.procs {
+            let proc = match self.cx.hir_of(id.id())? {
+                HirNode::Proc(x) => x,
+                _ => unreachable!(),
+            };
+            let comb = is_combinational(self.cx, proc)?;
+            let acc = self.cx.accessed_nodes(proc.stmt, id.env())?;
+            for &to in &acc.written {
+                for &from in &acc.read {
+                    if comb && acc.written.contains(&from) {
+                        continue;
+                    }
+                    self.add_edge(from, to, id.env(), !comb);
+                }
+            }
+        }
+
+        // Instances are driven by the signals connected to their inputs, and
+        // drive the signals connected to their outputs.
+        for inst in &module.insts {
+            let target = &self.design.modules[inst.module];
+            let node = self.nodes.len();
+            self.nodes.push(format!(
+                "label={}, shape=component",
+                quote(&format!("{}\n{}", inst.name, target.name))
+            ));
+            for (port, expr) in target.ports.iter().zip(&inst.ports) {
+                let expr = match expr {
+                    Some(x) => *x,
+                    None => continue,
+                };
+                let acc = self.cx.accessed_nodes(expr.id(), expr.env())?;
+                let name = port.port.name.value.to_string();
+                if port.port.dir != ast::PortDir::Output {
+                    for &from in &acc.read {
+                        if let Some(from) = self.signal(from, expr.env()) {
+                            self.edges.insert((from, node, name.clone(), false));
+                        }
+                    }
+                }
+                if port.port.dir != ast::PortDir::Input {
+                    for &to in acc.read.iter().chain(&acc.written) {
+                        if let Some(to) = self.signal(to, expr.env()) {
+                            self.edges.insert((node, to, name.clone(), false));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add an edge between two signals. Accesses to signals outside the
+    /// module are ignored.
+    fn add_edge(&mut self, from: AccessedNode, to: AccessedNode, env: ParamEnv, registered: bool) {
+        if let (Some(from), Some(to)) = (self.signal(from, env), self.signal(to, env)) {
+            self.edges.insert((from, to, String::new(), registered));
+        }
+    }
+
+    /// Find the node of a signal accessed from within a parameter
+    /// environment.
+    fn signal(&self, node: AccessedNode, env: ParamEnv) -> Option<usize> {
+        let id = match node {
+            AccessedNode::Regular(id) => id,
+            AccessedNode::Intf(..) => return None,
+        };
+        if let Some(&index) = self.signals.get(&(id, env)) {
+            return Some(index);
+        }
+        if let Some(decl) = self.module.find_decl(id, env) {
+            return self.signals.get(&(decl.id, decl.env)).cloned();
+        }
+        self.signals.get(&(id, self.module.id.env())).cloned()
+    }
+
+    fn write(&self, out: &mut String) {
+        out.push_str(&format!("digraph {} {{\n", quote(&self.module.name)));
+        for (index, node) in self.nodes.iter().enumerate() {
+            out.push_str(&format!("  n{} [{}];\n", index, node));
+        }
+        for (from, to, label, registered) in &self.edges {
+            let mut attrs = vec![];
+            if !label.is_empty() {
+                attrs.push(format!("label={}", quote(label)));
+            }
+            if *registered {
+                attrs.push("style=dashed".to_string());
+            }
+            out.push_str(&format!("  n{} -> n{}", from, to));
+            if !attrs.is_empty() {
+                out.push_str(&format!(" [{}]", attrs.join(", ")));
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+    }
+}
+
+/// Describe the value a parameter has in a given environment. Returns `None`
+/// for localparams.
+fn param_label<'a>(cx: &impl Context<'a>, id: NodeId, env: ParamEnv) -> Result<Option<String>> {
+    Ok(match cx.hir_of(id)? {
+        HirNode::ValueParam(p) if !p.local => Some(format!(
+            "{} = {}",
+            p.name.value,
+            cx.constant_value_of(id, env).kind
+        )),
+        HirNode::TypeParam(p) if !p.local => Some(format!(
+            "{} = {}",
+            p.name.value,
+            cx.map_to_type_or_error(Ref(cx.ast_for_id(id)), env)
+        )),
+        _ => None,
+    })
+}
+
+/// Quote a string for use as a Graphviz identifier or label.
+pub(crate) fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,212 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of variables with multiple drivers.
+//!
+//! A variable that is written by an `always_comb`, `always_ff`, or
+//! `always_latch` procedure must not be written by any other procedure, and a
+//! variable written by a continuous assignment must not be written by any
+//! procedure. Nets may have multiple drivers, which are resolved according to
+//! the net type. The statements of `initial` procedures that are performed
+//! during elaboration act like the initializer of a declaration and are not
+//! considered drivers.
+
+use crate::{crate_prelude::*, hir::HirNode, ParamEnv};
+use std::collections::{BTreeMap, HashSet};
+
+/// Check a module for variables with multiple drivers.
+///
+/// Emits an error listing every driving location for each offending
+/// variable. Only the assignments and processes directly within the module
+/// are considered.
+pub(crate) fn check_module<'a>(
+    cx: &impl Context<'a>,
+    module_id: NodeId,
+    env: ParamEnv,
+) -> Result<()> {
+    let hir = match cx.hir_of(module_id)? {
+        HirNode::Module(x) => x,
+        _ => unreachable!(),
+    };
+    let mut collector = Collector {
+        cx,
+        env,
+        drivers: BTreeMap::new(),
+        performed: cx.initial_values(Ref(&hir.block), env)?.stmts.clone(),
+    };
+
+    // Collect the variables written by every assignment and procedure.
+    for &assign_id in &hir.block.assigns {
+        let assign = match cx.hir_of(assign_id)? {
+            HirNode::Assign(x) => x,
+            _ => unreachable!(),
+        };
+        let driver = Driver {
+            id: assign_id,
+            continuous: true,
+            exclusive: false,
+            what: "a continuous assignment",
+        };
+        collector.collect_lhs(assign.lhs, driver, assign.span)?;
+    }
+    for &proc_id in &hir.block.procs {
+        let proc = match cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        let (exclusive, what) = match proc.kind {
+            ast::ProcedureKind::AlwaysComb => (true, "an `always_comb` procedure"),
+            ast::ProcedureKind::AlwaysFf => (true, "an `always_ff` procedure"),
+            ast::ProcedureKind::AlwaysLatch => (true, "an `always_latch` procedure"),
+            ast::ProcedureKind::Always => (false, "an `always` procedure"),
+            ast::ProcedureKind::Initial => (false, "an `initial` procedure"),
+            ast::ProcedureKind::Final => (false, "a `final` procedure"),
+        };
+        let driver = Driver {
+            id: proc_id,
+            continuous: false,
+            exclusive,
+            what,
+        };
+        collector.collect_stmt(proc.stmt, driver)?;
+    }
+
+    // Report variables driven by a procedure together with a continuous
+    // assignment, or by an exclusive procedure together with any other
+    // procedure. Multiple continuous assignments to a variable are left to
+    // the code generator.
+    let mut failed = false;
+    for (var, writes) in collector.drivers {
+        let mut drivers: Vec<&(Driver, Span)> = vec![];
+        for write in &writes {
+            if !drivers.iter().any(|(d, _)| d.id == write.0.id) {
+                drivers.push(write);
+            }
+        }
+        let procs = drivers.iter().filter(|(d, _)| !d.continuous).count();
+        let conflict = procs > 0
+            && drivers.len() > 1
+            && drivers.iter().any(|(d, _)| d.exclusive || d.continuous);
+        if !conflict {
+            continue;
+        }
+        drivers.sort_by_key(|(_, span)| span.begin);
+        let name = match cx.hir_of(var)? {
+            HirNode::VarDecl(x) => x.name,
+            HirNode::IntPort(x) => x.name,
+            _ => unreachable!(),
+        };
+        let mut d = DiagBuilder2::error(format!(
+            "`{}` is driven by multiple processes or assignments",
+            name.value
+        ))
+        .code("SV0038")
+        .span_label(name.span, "declared here");
+        for (driver, span) in drivers {
+            d = d.span_label(*span, format!("driven by {} here", driver.what));
+        }
+        cx.emit(d);
+        failed = true;
+    }
+    if failed {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// An assignment or procedure that drives a variable.
+#[derive(Clone, Copy)]
+struct Driver {
+    /// The assignment or procedure.
+    id: NodeId,
+    /// Whether this is a continuous assignment.
+    continuous: bool,
+    /// Whether the variable may not be driven by any other procedure.
+    exclusive: bool,
+    /// A description of the driver.
+    what: &'static str,
+}
+
+/// A helper to collect the drivers of variables in a module.
+struct Collector<'c, C> {
+    cx: &'c C,
+    env: ParamEnv,
+    drivers: BTreeMap<NodeId, Vec<(Driver, Span)>>,
+    /// The statements performed during elaboration.
+    performed: HashSet<NodeId>,
+}
+
+impl<'a, 'c, C: Context<'a>> Collector<'c, C> {
+    fn collect_stmt(&mut self, stmt_id: NodeId, driver: Driver) -> Result<()> {
+        if self.performed.contains(&stmt_id) {
+            return Ok(());
+        }
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(()),
+        };
+        match hir.kind {
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.collect_stmt(stmt, driver)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, .. } => self.collect_lhs(lhs, driver, hir.span)?,
+            hir::StmtKind::Timed { stmt, .. } | hir::StmtKind::Loop { body: stmt, .. } => {
+                self.collect_stmt(stmt, driver)?
+            }
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt,
+                ..
+            } => {
+                self.collect_stmt(main_stmt, driver)?;
+                if let Some(else_stmt) = else_stmt {
+                    self.collect_stmt(else_stmt, driver)?;
+                }
+            }
+            hir::StmtKind::Case {
+                ref ways, default, ..
+            } => {
+                for &(_, stmt) in ways {
+                    self.collect_stmt(stmt, driver)?;
+                }
+                if let Some(default) = default {
+                    self.collect_stmt(default, driver)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Record the variables written by the left-hand side of an assignment.
+    fn collect_lhs(&mut self, expr_id: NodeId, driver: Driver, span: Span) -> Result<()> {
+        let hir = match self.cx.hir_of(expr_id)? {
+            HirNode::Expr(x) => x,
+            _ => return Ok(()),
+        };
+        match hir.kind {
+            hir::ExprKind::Ident(..) => {
+                let decl = self.cx.resolve_node(expr_id, self.env)?;
+                let is_var = match self.cx.hir_of(decl)? {
+                    HirNode::VarDecl(x) => x.kind == ast::VarKind::Var,
+                    HirNode::IntPort(x) => x.kind == ast::VarKind::Var,
+                    _ => false,
+                };
+                if is_var {
+                    self.drivers.entry(decl).or_default().push((driver, span));
+                }
+            }
+            hir::ExprKind::Concat(None, ref exprs) => {
+                for &expr in exprs {
+                    self.collect_lhs(expr, driver, span)?;
+                }
+            }
+            // Different bits and fields of a variable may be driven by
+            // different processes, so we do not look into selects.
+            _ => (),
+        }
+        Ok(())
+    }
+}
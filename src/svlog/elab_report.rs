@@ -0,0 +1,208 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A report of the module specializations produced by elaboration.
+//!
+//! Every module may be instantiated under multiple parameter environments.
+//! This module collects each of these specializations together with the final
+//! values of the module's parameters, such that they can be printed in a human
+//! readable form or as JSON for consumption by other tools.
+
+use crate::{crate_prelude::*, hir::HirNode, ty::UnpackedType, value::Value, ParamEnv};
+use std::io::{Result as IoResult, Write};
+
+/// A report of all module specializations in a design.
+#[derive(Debug)]
+pub struct ElabReport<'a> {
+    /// The elaborated modules, in the order they were first emitted.
+    pub modules: Vec<ReportModule<'a>>,
+}
+
+/// A module and all its specializations.
+#[derive(Debug)]
+pub struct ReportModule<'a> {
+    /// The name of the module.
+    pub name: Name,
+    /// The distinct parametrizations the module was elaborated with.
+    pub specializations: Vec<ReportSpecialization<'a>>,
+}
+
+/// A single specialization of a module.
+#[derive(Debug)]
+pub struct ReportSpecialization<'a> {
+    /// The name of the emitted entity.
+    pub entity: String,
+    /// The parameter environment of the specialization.
+    pub env: ParamEnv,
+    /// The final values of the module's parameters.
+    pub params: Vec<ReportParam<'a>>,
+}
+
+/// A parameter and its final value.
+#[derive(Debug)]
+pub struct ReportParam<'a> {
+    /// The name of the parameter.
+    pub name: Name,
+    /// Whether the parameter is a localparam.
+    pub local: bool,
+    /// The value assigned to the parameter.
+    pub value: ReportParamValue<'a>,
+}
+
+/// The value assigned to a parameter.
+#[derive(Debug, Clone, Copy)]
+pub enum ReportParamValue<'a> {
+    /// A value parameter.
+    Value(Value<'a>),
+    /// A type parameter.
+    Type(&'a UnpackedType<'a>),
+}
+
+impl<'a> ElabReport<'a> {
+    /// Collect the parameter values of a list of emitted module
+    /// specializations.
+    pub fn new(
+        cx: &impl Context<'a>,
+        specializations: impl IntoIterator<Item = (NodeEnvId, String)>,
+    ) -> Result<Self> {
+        let mut ids: Vec<NodeId> = vec![];
+        let mut modules: Vec<ReportModule<'a>> = vec![];
+        for (id, entity) in specializations {
+            let hir = match cx.hir_of(id.id())? {
+                HirNode::Module(x) => x,
+                _ => unreachable!(),
+            };
+            let index = match ids.iter().position(|&x| x == id.id()) {
+                Some(index) => index,
+                None => {
+                    ids.push(id.id());
+                    modules.push(ReportModule {
+                        name: hir.name.value,
+                        specializations: vec![],
+                    });
+                    modules.len() - 1
+                }
+            };
+            let mut params = vec![];
+            for &param_id in hir.params.iter().chain(hir.block.params.iter()) {
+                params.push(match cx.hir_of(param_id)? {
+                    HirNode::ValueParam(p) => ReportParam {
+                        name: p.name.value,
+                        local: p.local,
+                        value: ReportParamValue::Value(cx.constant_value_of(param_id, id.env())),
+                    },
+                    HirNode::TypeParam(p) => ReportParam {
+                        name: p.name.value,
+                        local: p.local,
+                        value: ReportParamValue::Type(
+                            cx.map_to_type_or_error(Ref(cx.ast_for_id(param_id)), id.env()),
+                        ),
+                    },
+                    _ => continue,
+                });
+            }
+            modules[index].specializations.push(ReportSpecialization {
+                entity,
+                env: id.env(),
+                params,
+            });
+        }
+        Ok(ElabReport { modules })
+    }
+
+    /// Write the report in a human-readable form.
+    pub fn write_text(&self, w: &mut impl Write) -> IoResult<()> {
+        for module in &self.modules {
+            writeln!(
+                w,
+                "module `{}` ({} specialization{})",
+                module.name,
+                module.specializations.len(),
+                if module.specializations.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            )?;
+            for spec in &module.specializations {
+                writeln!(w, "  entity @{} ({})", spec.entity, spec.env)?;
+                for param in &spec.params {
+                    write!(
+                        w,
+                        "    {}{} = ",
+                        if param.local { "localparam " } else { "" },
+                        param.name
+                    )?;
+                    match param.value {
+                        ReportParamValue::Value(v) => writeln!(w, "{} ({})", v.kind, v.ty)?,
+                        ReportParamValue::Type(t) => writeln!(w, "type {}", t)?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the report as JSON.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        write!(w, "[")?;
+        for (i, module) in self.modules.iter().enumerate() {
+            if i > 0 {
+                write!(w, ",")?;
+            }
+            write!(w, "\n  {{\"module\": ")?;
+            write_json_str(w, &module.name.to_string())?;
+            write!(w, ", \"specializations\": [")?;
+            for (j, spec) in module.specializations.iter().enumerate() {
+                if j > 0 {
+                    write!(w, ",")?;
+                }
+                write!(w, "\n    {{\"entity\": ")?;
+                write_json_str(w, &spec.entity)?;
+                write!(w, ", \"env\": ")?;
+                write_json_str(w, &spec.env.to_string())?;
+                write!(w, ", \"params\": [")?;
+                for (k, param) in spec.params.iter().enumerate() {
+                    if k > 0 {
+                        write!(w, ", ")?;
+                    }
+                    write!(w, "{{\"name\": ")?;
+                    write_json_str(w, &param.name.to_string())?;
+                    write!(w, ", \"local\": {}, ", param.local)?;
+                    match param.value {
+                        ReportParamValue::Value(v) => {
+                            write!(w, "\"kind\": \"value\", \"type\": ")?;
+                            write_json_str(w, &v.ty.to_string())?;
+                            write!(w, ", \"value\": ")?;
+                            write_json_str(w, &v.kind.to_string())?;
+                        }
+                        ReportParamValue::Type(t) => {
+                            write!(w, "\"kind\": \"type\", \"type\": ")?;
+                            write_json_str(w, &t.to_string())?;
+                        }
+                    }
+                    write!(w, "}}")?;
+                }
+                write!(w, "]}}")?;
+            }
+            write!(w, "\n  ]}}")?;
+        }
+        writeln!(w, "\n]")
+    }
+}
+
+/// Write a string as a quoted and escaped JSON string.
+fn write_json_str(w: &mut impl Write, s: &str) -> IoResult<()> {
+    write!(w, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(w, "\\\"")?,
+            '\\' => write!(w, "\\\\")?,
+            '\n' => write!(w, "\\n")?,
+            '\r' => write!(w, "\\r")?,
+            '\t' => write!(w, "\\t")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => write!(w, "{}", c)?,
+        }
+    }
+    write!(w, "\"")
+}
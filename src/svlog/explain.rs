@@ -0,0 +1,1045 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Extended explanations of the diagnostic codes.
+//!
+//! Some diagnostics carry a stable code, such as `SV0001`, and print it next to
+//! their severity. The `--explain` option looks up the code here and prints a
+//! description of the problem together with an example. Every error reported for
+//! SystemVerilog input carries a code.
+
+/// The explanation of every diagnostic code, sorted by code.
+static EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "SV0001",
+        r#"A name could not be found.
+
+Every name used in an expression, type, or instantiation must be declared
+before it is used, either in the enclosing scopes, in an imported package, or
+in the scope selected by a hierarchical name.
+
+Example:
+
+    module foo;
+        logic [3:0] x;
+        assign x = y; // `y` is not declared
+    endmodule
+
+Declare the name, check its spelling, or import the package that declares it.
+"#,
+    ),
+    (
+        "SV0002",
+        r#"A name is declared multiple times in the same scope.
+
+Every declaration in a scope must have a unique name.
+
+Example:
+
+    module foo;
+        logic x;
+        logic x; // `x` is already declared above
+    endmodule
+
+Rename or remove one of the declarations.
+"#,
+    ),
+    (
+        "SV0003",
+        r#"A value cannot be cast to the type required by its context.
+
+The type of an expression is converted implicitly to the type its context
+expects, for example the type of the left-hand side of an assignment. Only
+some conversions are possible implicitly, such as between integral types.
+
+Example:
+
+    module foo;
+        logic a [4];
+        logic [3:0] x;
+        assign x = a; // an unpacked array cannot be converted to a vector
+    endmodule
+
+Convert the value explicitly, or change the type of the context.
+"#,
+    ),
+    (
+        "SV0004",
+        r#"A named port connection refers to a port the module does not have.
+
+Example:
+
+    module foo (input logic a);
+    endmodule
+
+    module bar;
+        foo u (.b(1'b0)); // `foo` has no port `b`
+    endmodule
+
+Check the name of the port against the module's port list.
+"#,
+    ),
+    (
+        "SV0005",
+        r#"A named parameter assignment refers to a parameter the module does not
+have.
+
+Example:
+
+    module foo #(parameter int N = 1);
+    endmodule
+
+    module bar;
+        foo #(.M(2)) u (); // `foo` has no parameter `M`
+    endmodule
+
+Check the name of the parameter against the module's parameter list.
+"#,
+    ),
+    (
+        "SV0006",
+        r#"An instantiation has more positional port connections than the module
+has ports.
+
+Example:
+
+    module foo (input logic a);
+    endmodule
+
+    module bar;
+        foo u (1'b0, 1'b1); // `foo` only has one port
+    endmodule
+
+Remove the excess connections.
+"#,
+    ),
+    (
+        "SV0007",
+        r#"An instantiation has more positional parameter assignments than the module
+has parameters.
+
+Example:
+
+    module foo #(parameter int N = 1);
+    endmodule
+
+    module bar;
+        foo #(1, 2) u (); // `foo` only has one parameter
+    endmodule
+
+Remove the excess assignments.
+"#,
+    ),
+    (
+        "SV0008",
+        r#"A parameter has no default value and is not assigned a value.
+
+A parameter without a default must be assigned in every instantiation of the
+module. If the module is the top-level module, the parameter must have a
+default.
+
+Example:
+
+    module foo #(parameter int N);
+        logic [N-1:0] x;
+    endmodule
+
+    module bar;
+        foo u (); // `N` is not assigned
+    endmodule
+
+Assign the parameter in the instantiation, or give it a default value.
+"#,
+    ),
+    (
+        "SV0009",
+        r#"An instantiation assigns a value to a local parameter.
+
+Parameters declared with `localparam` cannot be overridden from outside the
+module.
+
+Example:
+
+    module foo #(localparam int N = 1);
+    endmodule
+
+    module bar;
+        foo #(.N(2)) u (); // `N` is a localparam
+    endmodule
+
+Declare the parameter with `parameter` if it is meant to be overridden.
+"#,
+    ),
+    (
+        "SV0010",
+        r#"A module instantiates itself with the same parameters.
+
+Such a recursion never ends. Recursive instantiations must change a parameter
+that eventually stops the recursion, for example in a generate block.
+
+Example:
+
+    module foo;
+        foo u (); // instantiates itself indefinitely
+    endmodule
+
+    module bar;
+        foo u ();
+    endmodule
+
+Guard the recursive instantiation with a generate condition on a parameter.
+"#,
+    ),
+    (
+        "SV0011",
+        r#"A field access names a field that the struct does not have.
+
+Example:
+
+    module foo;
+        struct packed { logic a, b; } s;
+        logic x;
+        assign x = s.c; // the struct has no field `c`
+    endmodule
+
+Check the name of the field against the struct's declaration.
+"#,
+    ),
+    (
+        "SV0012",
+        r#"A value is indexed that is neither an array nor a vector.
+
+Example:
+
+    module foo;
+        real r;
+        logic x;
+        assign x = r[0]; // `real` cannot be indexed
+    endmodule
+
+Only index into packed or unpacked arrays and vectors.
+"#,
+    ),
+    (
+        "SV0013",
+        r#"A name used as a type does not refer to a type.
+
+Example:
+
+    module foo;
+        logic x;
+        x y; // `x` is a variable, not a type
+    endmodule
+
+Use a type, or declare the name with `typedef`.
+"#,
+    ),
+    (
+        "SV0014",
+        r#"A parameter is assigned something that is not a value, such as a type.
+
+Example:
+
+    module foo #(parameter int N = 1);
+    endmodule
+
+    module bar;
+        foo #(.N(logic)) u (); // `logic` is a type, not a value
+    endmodule
+
+Assign a value to value parameters, and a type to `parameter type`s.
+"#,
+    ),
+    (
+        "SV0015",
+        r#"An instantiation refers to something that is neither a module nor an
+interface.
+
+Example:
+
+    package p;
+    endpackage
+
+    module foo;
+        p u (); // `p` is a package
+    endmodule
+
+Instantiate a module or interface instead.
+"#,
+    ),
+    (
+        "SV0016",
+        r#"The source text does not follow the SystemVerilog grammar.
+
+The parser expected a different token at this point, for example a closing
+parenthesis, a semicolon, or the keyword that continues a statement.
+
+Example:
+
+    module foo;
+        logic x
+        logic y; // missing `;` after the declaration of `x`
+    endmodule
+
+Insert the expected token or remove the unexpected one.
+"#,
+    ),
+    (
+        "SV0017",
+        r#"The labels of a block do not match.
+
+A block may be given a label before it, after its `begin`, and after its
+`end`. All labels given to the same block must be identical. The same holds for
+the name of a class and the label after its `endclass`.
+
+Example:
+
+    module foo;
+        initial begin : a
+        end : b // `b` does not match `a`
+    endmodule
+
+Use the same label at the beginning and the end of the block.
+"#,
+    ),
+    (
+        "SV0018",
+        r#"The syntax is valid SystemVerilog but cannot be parsed yet.
+
+Some parts of the language, such as program declarations, case-generate
+statements, or some sequence and constraint expressions, are not yet
+supported by the parser.
+
+Example:
+
+    program foo; // program declarations are not supported
+    endprogram
+
+Rewrite the code with constructs that are supported, for example a module
+instead of a program.
+"#,
+    ),
+    (
+        "SV0019",
+        r#"The source text contains a character sequence that is not a valid token.
+
+Examples of this are number literals followed directly by letters, unknown
+escape sequences or line breaks in string literals, and invalid bases of
+number literals.
+
+Example:
+
+    module foo;
+        localparam x = 12ab; // a number may not be followed by letters
+    endmodule
+
+Separate the tokens with whitespace or correct the literal.
+"#,
+    ),
+    (
+        "SV0020",
+        r#"A compiler directive is unknown or malformed.
+
+Compiler directives start with a backtick, such as `` `define `` or
+`` `include ``. Each directive expects a specific set of arguments, and
+conditional directives such as `` `else `` and `` `endif `` must be preceded
+by a matching `` `ifdef `` or `` `ifndef ``.
+
+Example:
+
+    `endif // no earlier `ifdef
+    module foo;
+    endmodule
+
+Check the spelling and the arguments of the directive, or define the macro
+that is used.
+"#,
+    ),
+    (
+        "SV0021",
+        r#"A file named in an `` `include `` directive cannot be opened.
+
+Included files are searched relative to the including file and in the
+include directories passed with the `-I` option.
+
+Example:
+
+    `include "missing.svh" // the file does not exist
+    module foo;
+    endmodule
+
+Check the file name or add the directory that contains the file with `-I`.
+"#,
+    ),
+    (
+        "SV0022",
+        r#"A macro is expanded with the wrong arguments.
+
+A macro defined with arguments must be followed by a parenthesized list of
+them. Every argument without a default value must be given a value.
+
+Example:
+
+    `define add(a, b) ((a) + (b))
+    module foo;
+        localparam x = `add(1); // `b` is missing
+    endmodule
+
+Pass one value for every argument of the macro.
+"#,
+    ),
+    (
+        "SV0023",
+        r#"A number literal is malformed.
+
+The size of a sized literal must be a positive decimal number, the base must
+be one of `b`, `o`, `d`, or `h`, and the digits must be valid for the base.
+
+Example:
+
+    module foo;
+        logic [3:0] x = 4'b102; // `2` is not a binary digit
+    endmodule
+
+Correct the size, base, or digits of the literal.
+"#,
+    ),
+    (
+        "SV0024",
+        r#"A declaration appears in a place where it is not allowed.
+
+Port declarations may only appear in the body of a module or interface with a
+non-ANSI port list, modports only in interfaces, and packages may not contain
+declarations such as processes or instances. A port list is either entirely
+ANSI-style, declaring direction and type of each port, or entirely
+non-ANSI-style, listing only the port names.
+
+Example:
+
+    module foo (input logic a);
+        input b; // the module already has an ANSI-style port list
+    endmodule
+
+Move the declaration to where it is allowed, or use one style of port list
+consistently.
+"#,
+    ),
+    (
+        "SV0025",
+        r#"A port declaration is invalid or conflicts with another declaration of
+the same port.
+
+A port in a non-ANSI port list must be declared exactly once in the body,
+possibly split into a port declaration and a matching net or variable
+declaration. The declarations must agree on the kind, sign, and range of the
+port. An `inout` port must be a net, and a `ref` port must be a variable.
+
+Example:
+
+    module foo (a);
+        input a;
+        input a; // `a` is declared twice
+    endmodule
+
+Remove the conflicting declaration or make the declarations agree.
+"#,
+    ),
+    (
+        "SV0026",
+        r#"An assignment pattern does not match its type.
+
+An assignment pattern `'{...}` constructs an array or struct. Its fields must
+be either all positional or all named, named fields must refer to existing
+members or valid indices, and every element of the type must be assigned
+either explicitly or by a `default:` entry.
+
+Example:
+
+    module foo;
+        struct packed { logic a; logic b; } x;
+        assign x = '{a: 0, c: 1}; // `c` is not a member
+    endmodule
+
+Name only existing members and indices, and cover all of them.
+"#,
+    ),
+    (
+        "SV0027",
+        r#"The arguments of a function, task, or system function call do not match
+its arguments.
+
+A call must pass a value for every argument without a default and may not
+pass more arguments than the subroutine has. Named arguments must refer to
+existing arguments, may only be assigned once, and may not be followed by
+positional arguments.
+
+Example:
+
+    module foo;
+        function int f(int a);
+            return a;
+        endfunction
+        int x = f(1, 2); // `f` only has one argument
+    endmodule
+
+Pass one value for each argument of the subroutine.
+"#,
+    ),
+    (
+        "SV0028",
+        r#"A format string does not match its arguments.
+
+The format string of a function such as `$display` or `$sformatf` contains
+format specifiers such as `%d` or `%h`. Every specifier consumes one argument,
+and every argument must be consumed by a specifier.
+
+Example:
+
+    module foo;
+        initial $display("%d %d", 1); // second `%d` has no argument
+    endmodule
+
+Add the missing arguments or remove the superfluous specifiers.
+"#,
+    ),
+    (
+        "SV0029",
+        r#"A name refers to the wrong kind of declaration.
+
+Some positions require a specific kind of declaration, such as a package
+before `::`, an interface or modport in a port type, or a function or task in
+a call.
+
+Example:
+
+    module foo;
+        logic x;
+        initial x(); // `x` is not a function or task
+    endmodule
+
+Refer to a declaration of the kind required at this position.
+"#,
+    ),
+    (
+        "SV0030",
+        r#"A value is required to be constant but is not.
+
+Parameters, array bounds, and the indices of some selects must be known at
+compile time. They may only refer to parameters, literals, and calls to
+constant functions, but not to nets, variables, or ports.
+
+Example:
+
+    module foo (input logic [3:0] a);
+        localparam x = a; // `a` is not constant
+        logic [x:0] y;
+    endmodule
+
+Use a parameter or literal instead of the runtime value.
+"#,
+    ),
+    (
+        "SV0031",
+        r#"A function cannot be evaluated as a constant function.
+
+Functions called in constant expressions are evaluated during elaboration.
+They may not contain timing controls, non-blocking assignments, or calls to
+tasks, and they may not be passed `output`, `inout`, or `ref` arguments.
+
+Example:
+
+    module foo;
+        function int f();
+            int y;
+            y <= 1; // non-blocking assignment
+            return y;
+        endfunction
+        localparam x = f();
+        int z = x;
+    endmodule
+
+Remove the construct from the function, or call the function outside of
+constant expressions.
+"#,
+    ),
+    (
+        "SV0032",
+        r#"The design exceeds a limit of the compiler.
+
+The depth of the instance hierarchy, the call depth and number of statements
+executed during constant function evaluation, and the repetition counts of
+patterns are limited. Exceeding the limit usually indicates an infinite
+recursion.
+
+Example:
+
+    module foo;
+        function int f();
+            while (1); // never terminates
+            return 0;
+        endfunction
+        localparam x = f();
+        int y = x;
+    endmodule
+
+Make sure recursions terminate.
+"#,
+    ),
+    (
+        "SV0033",
+        r#"A `break` or `continue` statement is used outside of a loop.
+
+Example:
+
+    module foo;
+        function int f();
+            break; // not inside a loop
+        endfunction
+        localparam x = f();
+        int y = x;
+    endmodule
+
+Only use `break` and `continue` within the body of a loop.
+"#,
+    ),
+    (
+        "SV0034",
+        r#"The type of an expression or declaration cannot be determined.
+
+Some expressions, such as unsized literals or assignment patterns, take their
+type from their context. Declarations with an implicit type, such as
+`parameter x`, take their type from their initial value. If neither is
+available, the type cannot be determined.
+
+Example:
+
+    module foo;
+        parameter x; // no type and no default value
+        int y = x;
+    endmodule
+
+Give the declaration an explicit type or a default value, or cast the
+expression to a type.
+"#,
+    ),
+    (
+        "SV0035",
+        r#"A type is malformed.
+
+Examples of this are packed dimensions or signing on unpacked types, enums
+with an unpacked base type, and array bounds that are not integers or are
+too large.
+
+Example:
+
+    module foo;
+        real [3:0] x; // `real` is unpacked and cannot have packed dimensions
+    endmodule
+
+Use a packed type or move the dimensions after the name.
+"#,
+    ),
+    (
+        "SV0036",
+        r#"An operation cannot be applied to a value of the given type.
+
+For example, only integral values can be concatenated, size-cast, or
+sign-cast, only structs have fields, and interfaces cannot be used as values
+in expressions.
+
+Example:
+
+    module foo;
+        real r;
+        logic [7:0] x;
+        assign x = {r, r}; // `real` values cannot be concatenated
+    endmodule
+
+Convert the value to a suitable type first.
+"#,
+    ),
+    (
+        "SV0037",
+        r#"An expression cannot be assigned to.
+
+The left-hand side of an assignment must refer to a net, variable, or port,
+optionally with selects, or a concatenation thereof.
+
+Example:
+
+    module foo (input logic a);
+        localparam x = 1;
+        assign x = a; // a parameter cannot be assigned
+    endmodule
+
+Assign to a net or variable instead.
+"#,
+    ),
+    (
+        "SV0038",
+        r#"A variable is driven by more than one process or continuous assignment.
+
+A variable written by an `always_comb`, `always_ff`, or `always_latch`
+procedure must not be written by any other procedure or assignment. A
+variable written by a continuous assignment must not be written by any
+procedure. Nets may have multiple drivers.
+
+Example:
+
+    module foo (input logic a, b);
+        logic x;
+        assign x = a;
+        always_comb x = b; // `x` is already driven above
+    endmodule
+
+Drive the variable from only one place, or declare it as a net.
+"#,
+    ),
+    (
+        "SV0039",
+        r#"An instance port is connected incorrectly.
+
+Each port may only be connected once. Interface ports must be connected to an
+instance of a matching interface, and the modport of the connected interface
+must match the one of the port. Modules whose ports have no names can only be
+connected by position.
+
+Example:
+
+    interface bus;
+    endinterface
+
+    module bar (bus b);
+    endmodule
+
+    module foo;
+        logic x;
+        bar u (.b(x)); // `x` is not an interface
+    endmodule
+
+Connect every port at most once and with a compatible signal.
+"#,
+    ),
+    (
+        "SV0040",
+        r#"A parameter depends on its own value.
+
+The value of a parameter is computed from its default or the value it is
+assigned in the instantiation. If this computation refers back to the
+parameter itself, its value cannot be determined.
+
+Example:
+
+    module foo;
+        localparam int a = f();
+        function int f();
+            return a + 1; // `a` depends on itself
+        endfunction
+        int x = a;
+    endmodule
+
+Break the cycle by giving one of the parameters an independent value.
+"#,
+    ),
+    (
+        "SV0041",
+        r#"The initialization or step of a generate loop is invalid.
+
+A loop generate construct must initialize a genvar in its first statement and
+update the same genvar in its step, for example with `i = i + 1` or `i++`.
+
+Example:
+
+    module foo;
+        genvar i;
+        for (i = 0; i < 4; i + 1) begin // does not update `i`
+        end
+    endmodule
+
+Initialize and update the genvar of the loop.
+"#,
+    ),
+    (
+        "SV0042",
+        r#"A hierarchical name refers into a generate block that is not elaborated.
+
+Conditional generate blocks only exist if their condition holds. Names inside
+a block whose condition is false cannot be accessed from outside.
+
+Example:
+
+    module foo;
+        if (0) begin : g
+            logic x;
+        end
+        logic y;
+        assign y = g.x; // `g` is not elaborated
+    endmodule
+
+Only refer to names in generate blocks that are elaborated.
+"#,
+    ),
+    (
+        "SV0043",
+        r#"A case statement has more than one `default` item.
+
+Example:
+
+    module foo (input logic [1:0] a);
+        always_comb begin
+            case (a)
+                0: ;
+                default: ;
+                default: ; // second default item
+            endcase
+        end
+    endmodule
+
+Remove all but one of the `default` items.
+"#,
+    ),
+    (
+        "SV0044",
+        r#"A memory file passed to `$readmemh` or `$readmemb` cannot be loaded.
+
+The file must exist and contain words with the digits of the selected radix,
+separated by whitespace or comments. Addresses given with `@` and the number
+of words must fit the address range of the memory.
+
+Example:
+
+    module foo;
+        logic [7:0] mem [4];
+        initial $readmemh("missing.mem", mem); // the file does not exist
+    endmodule
+
+Check the file name, its contents, and the address range.
+"#,
+    ),
+    (
+        "SV0045",
+        r#"A construct is valid SystemVerilog but not supported by the compiler.
+
+Examples of this are arithmetic on `real` values and `$clog2` or the `**`
+operator on values that are not constant.
+
+Example:
+
+    module foo (input logic [3:0] a, output logic [3:0] z);
+        assign z = $clog2(a); // `a` is not constant
+    endmodule
+
+Rewrite the code with constructs that are supported, or compute the value from
+constants.
+"#,
+    ),
+    (
+        "SV0046",
+        r#"A construct cannot be represented in the selected output format.
+
+Output formats other than LLHD, such as the Verilog, FIRRTL, or Yosys
+formats, only support a subset of the design. For example, they do not
+support interfaces, and require every clocked process to be triggered by a
+single clock edge.
+
+Example:
+
+    interface bus;
+        logic x;
+    endinterface
+
+    module foo (bus b); // interface ports are not supported
+    endmodule
+
+Rewrite the design with supported constructs, or choose a different output
+format.
+"#,
+    ),
+    (
+        "SV0047",
+        r#"The ports of a blackbox cannot be inferred.
+
+With the `--allow-blackbox` option, a module without a definition is treated
+as a blackbox that takes its ports from the connections of its instances.
+This is only possible if the ports are connected by name, and with values
+whose type can be represented on a port.
+
+Example:
+
+    module foo;
+        bar u (.*); // the ports of the blackbox `bar` are unknown
+    endmodule
+
+Connect the ports of the blackbox by name, or provide its definition.
+"#,
+    ),
+    (
+        "SV0048",
+        r#"A module is instantiated across a language boundary in an unsupported
+way.
+
+Modules defined in another language, such as VHDL, may only be connected with
+single bits, one-dimensional packed vectors, and integer atoms, and may not
+have type parameters or `ref` ports.
+
+Example:
+
+    module foo;
+        real x;
+        vhdl_entity u (.a(x)); // `real` cannot cross the boundary
+    endmodule
+
+Connect the ports with values of a supported type.
+"#,
+    ),
+    (
+        "SV0049",
+        r#"A library file cannot be opened.
+
+Library files passed with the `-v` option, or found in the directories passed
+with the `-y` option, are read when an instantiated module is not defined in
+the input files.
+
+Example:
+
+    moore foo.sv -v missing.sv
+
+Check the name of the library file.
+"#,
+    ),
+    (
+        "SV0050",
+        r#"A parameter override for the design summary is invalid.
+
+Parameter overrides passed on the command line must name a parameter of one
+of the summarized modules, and the parameter must accept an integer value.
+
+Example:
+
+    moore foo.sv --summary --param missing=1
+
+Check the name of the parameter and the value assigned to it.
+"#,
+    ),
+];
+
+/// Get the extended explanation of a diagnostic code.
+///
+/// Returns `None` if the code is unknown.
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .binary_search_by_key(&code, |&(x, _)| x)
+        .ok()
+        .map(|i| EXPLANATIONS[i].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// Collect the Rust source files of this crate and the syntax crate.
+    fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                source_files(&path, files);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    /// Find the builder chains of all errors and fatal errors in the sources.
+    ///
+    /// Returns the location of each diagnostic and the text from its
+    /// constructor up to the end of the enclosing expression.
+    fn diagnostics() -> Vec<(String, String)> {
+        let mut files = vec![];
+        source_files(Path::new(env!("CARGO_MANIFEST_DIR")), &mut files);
+        let mut diags = vec![];
+        for path in files {
+            let src = std::fs::read_to_string(&path).unwrap();
+            for severity in &["error", "fatal"] {
+                let needle = format!("DiagBuilder2::{}(", severity);
+                for (start, _) in src.match_indices(&needle) {
+                    let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+                    if src[line_start..start].trim_start().starts_with("//") {
+                        continue;
+                    }
+                    let chain = builder_chain(&src[start..]);
+                    let line = src[..start].matches('\n').count() + 1;
+                    diags.push((format!("{}:{}", path.display(), line), chain.to_string()));
+                }
+            }
+        }
+        diags
+    }
+
+    /// Extract the text up to the end of the expression that starts at the
+    /// beginning of `src`, skipping over string literals.
+    fn builder_chain(src: &str) -> &str {
+        let mut depth = 0;
+        let mut chars = src.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    while let Some((_, c)) = chars.next() {
+                        match c {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => (),
+                        }
+                    }
+                }
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => return &src[..i],
+                ')' | ']' | '}' => depth -= 1,
+                ';' if depth == 0 => return &src[..i],
+                _ => (),
+            }
+        }
+        src
+    }
+
+    #[test]
+    fn all_errors_have_a_code() {
+        let missing: Vec<_> = diagnostics()
+            .into_iter()
+            .filter(|(_, chain)| !chain.contains(".code(\""))
+            .map(|(loc, _)| loc)
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "errors without a code:\n{}",
+            missing.join("\n")
+        );
+    }
+
+    #[test]
+    fn all_codes_are_explained() {
+        let mut unexplained = vec![];
+        for (loc, chain) in diagnostics() {
+            for (i, _) in chain.match_indices(".code(\"") {
+                let code = &chain[i + 7..];
+                let code = &code[..code.find('"').unwrap()];
+                if explain(code).is_none() {
+                    unexplained.push(format!("{}: {}", loc, code));
+                }
+            }
+        }
+        assert!(
+            unexplained.is_empty(),
+            "codes without an explanation:\n{}",
+            unexplained.join("\n")
+        );
+    }
+
+    #[test]
+    fn explanations_are_sorted() {
+        for pair in EXPLANATIONS.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{} before {}", pair[0].0, pair[1].0);
+        }
+    }
+}
@@ -0,0 +1,1068 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Emission of the elaborated design as FIRRTL.
+//!
+//! This backend maps the synthesizable subset of a design onto FIRRTL, such
+//! that it can be processed by `firtool` and combined with Chisel-generated
+//! designs. Every module specialization becomes a FIRRTL module. Variables
+//! driven by a clocked procedure become registers, all other variables
+//! become wires. Procedures are translated into `when` blocks, which relies
+//! on FIRRTL's last-connect semantics to resolve multiple assignments.
+//!
+//! All values are represented as `UInt`, with signed operations performed on
+//! reinterpreted operands. FIRRTL does not have `x` and `z` values, so these
+//! bits map to zero.
+
+use crate::{
+    crate_prelude::*,
+    design::{
+        clocked_events, fit, sanitize, to_bool, unique, unsupported, unsupported_expr, width,
+        ClockedEvents, Design, DesignModule, WidthExpr,
+    },
+    hir::HirNode,
+    value::{Value, ValueKind},
+    ParamEnv,
+};
+use num::{BigInt, One, Signed, ToPrimitive, Zero};
+use std::collections::{HashMap, HashSet};
+
+/// Serialize a design as FIRRTL.
+pub fn emit_design<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<String> {
+    // Assign every module a unique FIRRTL name.
+    let mut used = HashSet::new();
+    let names: Vec<String> = design
+        .modules
+        .iter()
+        .map(|m| unique(&mut used, &sanitize(&m.name)))
+        .collect();
+
+    let mut out = String::new();
+    out.push_str("FIRRTL version 3.3.0\n");
+    out.push_str(&format!("circuit {} :\n", names.last().unwrap()));
+    let mut clocks: Vec<HashSet<NodeId>> = vec![];
+    for (index, module) in design.modules.iter().enumerate() {
+        let mut emitter = Emitter {
+            cx,
+            design,
+            module,
+            module_names: &names,
+            module_clocks: &clocks,
+            names: HashSet::new(),
+            storage: HashMap::new(),
+            inputs: HashMap::new(),
+            clocks: HashSet::new(),
+            regs: HashMap::new(),
+            lines: String::new(),
+            indent: 1,
+        };
+        emitter.emit_module(&names[index])?;
+        out.push_str(&emitter.lines);
+        clocks.push(emitter.clocks);
+    }
+    Ok(out)
+}
+
+/// An emitted FIRRTL expression of type `UInt`.
+#[derive(Debug, Clone)]
+struct Expr {
+    text: String,
+    width: usize,
+}
+
+impl Expr {
+    fn new(text: impl Into<String>, width: usize) -> Self {
+        Expr {
+            text: text.into(),
+            width,
+        }
+    }
+}
+
+impl WidthExpr for Expr {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn truncate(self, width: usize) -> Self {
+        Expr::new(format!("bits({}, {}, 0)", self.text, width - 1), width)
+    }
+
+    fn zero_extend(self, width: usize) -> Self {
+        Expr::new(format!("pad({}, {})", self.text, width), width)
+    }
+
+    fn reduce_or(self) -> Self {
+        Expr::new(format!("orr({})", self.text), 1)
+    }
+}
+
+/// A wire, register, or output port that can be connected.
+#[derive(Debug, Clone)]
+struct Storage {
+    name: String,
+    width: usize,
+}
+
+/// The clock and reset of a register.
+#[derive(Debug, Clone)]
+struct RegInfo {
+    /// The clock port, and whether the register triggers on its falling edge.
+    clock: (NodeId, bool),
+    /// The reset condition, its environment, and the reset value.
+    reset: Option<(NodeId, ParamEnv, Expr)>,
+}
+
+/// A clocked procedure.
+struct ClockedProc {
+    /// The statement executed on a clock edge while not in reset, if any.
+    body: Option<NodeId>,
+}
+
+/// A helper to emit a single module specialization.
+struct Emitter<'a, 'c, C> {
+    cx: &'c C,
+    design: &'c Design<'a>,
+    module: &'c DesignModule<'a>,
+    /// The FIRRTL names of all modules in the design.
+    module_names: &'c [String],
+    /// The clock ports of the modules emitted so far.
+    module_clocks: &'c [HashSet<NodeId>],
+    /// The names used within the module.
+    names: HashSet<String>,
+    /// The wires, registers, and output ports of the module.
+    storage: HashMap<(NodeId, ParamEnv), Storage>,
+    /// The input ports of the module.
+    inputs: HashMap<NodeId, Storage>,
+    /// The input ports used as clocks.
+    clocks: HashSet<NodeId>,
+    /// The variables driven by clocked procedures.
+    regs: HashMap<(NodeId, ParamEnv), RegInfo>,
+    lines: String,
+    indent: usize,
+}
+
+impl<'a, 'c, C: Context<'a>> Emitter<'a, 'c, C> {
+    fn emit_module(&mut self, name: &str) -> Result<()> {
+        let module = self.module;
+        let env = module.id.env();
+
+        // Find the clocked procedures first, since they determine which input
+        // ports are clocks and which variables are registers.
+        let mut procs = vec![];
+        for &id in &module.procs {
+            procs.push((id, self.analyze_proc(id.id(), id.env())?));
+        }
+
+        // Input ports that are directly connected to the clock of an
+        // instantiated module are clocks as well.
+        for inst in &module.insts {
+            let target = &self.design.modules[inst.module];
+            for (port, mapping) in target.ports.iter().zip(&inst.ports) {
+                let mapping = match mapping {
+                    Some(m) if self.module_clocks[inst.module].contains(&port.port.id) => m,
+                    _ => continue,
+                };
+                if let Some(id) = signal(self.cx, mapping.id(), mapping.env())? {
+                    if self.is_input(id) {
+                        self.clocks.insert(id);
+                    }
+                }
+            }
+        }
+
+        // Emit the ports.
+        self.line(format!("module {} :", name));
+        self.indent += 1;
+        let mut outputs = vec![];
+        for port in &module.ports {
+            let width = width(self.cx, port.ty, port.port.span)?;
+            let name = self.unique_name(&port.port.name.value.to_string());
+            match port.port.dir {
+                ast::PortDir::Input if self.clocks.contains(&port.port.id) => {
+                    self.line(format!("input {} : Clock", name));
+                    self.inputs.insert(port.port.id, Storage { name, width });
+                }
+                ast::PortDir::Input => {
+                    self.line(format!("input {} : UInt<{}>", name, width));
+                    self.inputs.insert(port.port.id, Storage { name, width });
+                }
+                ast::PortDir::Output => {
+                    self.line(format!("output {} : UInt<{}>", name, width));
+                    outputs.push((port.port.id, Storage { name, width }));
+                }
+                dir => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "{} port `{}` not supported by this output format",
+                            dir, port.port.name
+                        ))
+                        .code("SV0046")
+                        .span(port.port.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        self.line("");
+
+        // Emit the registers and wires. Output ports driven by a clocked
+        // procedure get a separate register that is connected to the port.
+        let mut reg_outputs = vec![];
+        for (id, port) in &outputs {
+            match self.regs.get(&(*id, env)).cloned() {
+                Some(reg) => {
+                    let name = self.unique_name(&format!("{}_reg", port.name));
+                    self.declare_reg(&name, port.width, &reg)?;
+                    let storage = Storage {
+                        name,
+                        width: port.width,
+                    };
+                    reg_outputs.push((port.clone(), storage.clone()));
+                    self.storage.insert((*id, env), storage);
+                }
+                None => {
+                    self.line(format!("invalidate {}", port.name));
+                    self.storage.insert((*id, env), port.clone());
+                }
+            }
+        }
+        let mut inits = vec![];
+        for decl in &module.decls {
+            let width = width(self.cx, decl.ty, decl.hir.span)?;
+            let name = self.unique_name(&decl.name);
+            match self.regs.get(&(decl.id, decl.env)).cloned() {
+                Some(reg) => self.declare_reg(&name, width, &reg)?,
+                None => {
+                    self.line(format!("wire {} : UInt<{}>", name, width));
+                    self.line(format!("invalidate {}", name));
+                    if let Some(init) = decl.hir.init {
+                        inits.push((name.clone(), width, init, decl.env));
+                    }
+                }
+            }
+            self.storage
+                .insert((decl.id, decl.env), Storage { name, width });
+        }
+        let mut inst_names = vec![];
+        for inst in &module.insts {
+            let name = self.unique_name(&inst.name);
+            self.line(format!(
+                "inst {} of {}",
+                name, self.module_names[inst.module]
+            ));
+            inst_names.push(name);
+        }
+
+        // Emit the initial values of wires.
+        for (name, width, init, env) in inits {
+            let value = self.rvalue_expr(init, env, width)?;
+            self.line(format!("connect {}, {}", name, value.text));
+        }
+
+        // Emit the continuous assignments.
+        for &id in &module.assigns {
+            let hir = match self.cx.hir_of(id.id())? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+            let assign = self.cx.mir_assignment_from_concurrent(Ref(hir), id.env());
+            self.emit_assignment(assign)?;
+        }
+
+        // Emit the instance port connections.
+        for (inst, name) in module.insts.iter().zip(inst_names) {
+            self.emit_inst_connections(inst, &name)?;
+        }
+
+        // Emit the procedures.
+        for (id, clocked) in procs {
+            match clocked {
+                Some(ClockedProc { body: Some(body) }) => self.emit_stmt(body, id.env())?,
+                Some(ClockedProc { body: None }) => (),
+                None => self.emit_stmt(id.id(), id.env())?,
+            }
+        }
+
+        // Forward the registered outputs.
+        for (port, reg) in reg_outputs {
+            self.line(format!("connect {}, {}", port.name, reg.name));
+        }
+        self.indent -= 1;
+        self.line("");
+        Ok(())
+    }
+
+    fn declare_reg(&mut self, name: &str, width: usize, reg: &RegInfo) -> Result<()> {
+        let clock = match reg.clock {
+            (id, false) => self.inputs[&id].name.clone(),
+            (id, true) => format!("asClock(not(asUInt({})))", self.inputs[&id].name),
+        };
+        match reg.reset {
+            Some((cond, env, ref value)) => {
+                let cond = self.bool_expr(cond, env)?;
+                self.line(format!(
+                    "regreset {} : UInt<{}>, {}, asAsyncReset({}), {}",
+                    name,
+                    width,
+                    clock,
+                    cond.text,
+                    fit(value.clone(), width).text
+                ))
+            }
+            None => self.line(format!("reg {} : UInt<{}>, {}", name, width, clock)),
+        }
+        Ok(())
+    }
+
+    /// Determine the clock and reset of a procedure, and record the variables
+    /// it drives as registers. Returns `None` for combinational procedures.
+    fn analyze_proc(&mut self, proc_id: NodeId, env: ParamEnv) -> Result<Option<ClockedProc>> {
+        let hir = match self.cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        let timed = match self.cx.hir_of(hir.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Timed { control, stmt },
+                ..
+            }) => Some((*control, *stmt)),
+            _ => None,
+        };
+        let (event_id, body) = match (hir.kind, timed) {
+            (
+                ast::ProcedureKind::AlwaysComb | ast::ProcedureKind::AlwaysLatch,
+                None | Some((hir::TimingControl::ImplicitEvent, _)),
+            )
+            | (ast::ProcedureKind::Always, Some((hir::TimingControl::ImplicitEvent, _))) => {
+                return Ok(None)
+            }
+            (
+                ast::ProcedureKind::Always | ast::ProcedureKind::AlwaysFf,
+                Some((hir::TimingControl::ExplicitEvent(event), body)),
+            ) => (event, body),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "{} not supported by this output format",
+                        hir.desc_full()
+                    ))
+                    .code("SV0046")
+                    .span(hir.span)
+                    .add_note(
+                        "Only `always_comb`, `always_latch`, `always @*`, and procedures \
+                         triggered by a clock edge can be mapped to hardware",
+                    ),
+                );
+                return Err(());
+            }
+        };
+        let ClockedEvents {
+            clock: clock_event,
+            reset,
+        } = clocked_events(self.cx, event_id, body, env)?;
+        if !clock_event.iff.is_empty() {
+            self.cx.emit(
+                DiagBuilder2::error("`iff` event qualifiers not supported by this output format")
+                    .code("SV0046")
+                    .span(clock_event.span),
+            );
+            return Err(());
+        }
+
+        // Determine the clock port.
+        let clock = match signal(self.cx, clock_event.expr, env)? {
+            Some(id) if self.is_input(id) => id,
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error("clock must be an input port for this output format")
+                        .code("SV0046")
+                        .span(clock_event.span),
+                );
+                return Err(());
+            }
+        };
+        let negedge = match clock_event.edge {
+            ast::EdgeIdent::Posedge => false,
+            ast::EdgeIdent::Negedge => true,
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(
+                        "only `posedge` and `negedge` clocks are supported by this output format",
+                    )
+                    .code("SV0046")
+                    .span(clock_event.span),
+                );
+                return Err(());
+            }
+        };
+        self.clocks.insert(clock);
+
+        // Record the driven variables as registers, together with their reset
+        // values.
+        let mut reset_values = HashMap::new();
+        let reset = match reset {
+            Some(reset) => {
+                let mut stmts = vec![];
+                flatten_stmts(self.cx, reset.main_stmt, &mut stmts)?;
+                for stmt in stmts {
+                    let (target, value) = self.reset_assignment(stmt, env)?;
+                    reset_values.insert(target, value);
+                }
+                Some((reset.cond, reset.main_stmt, reset.else_stmt))
+            }
+            None => None,
+        };
+        let acc = self.cx.accessed_nodes(body, env)?;
+        for node in &acc.written {
+            let key = match self.module.storage_key(&self.storage, node.id(), env) {
+                Some(key) => key,
+                None => continue,
+            };
+            let reset = match (&reset, reset_values.get(&key)) {
+                (Some((cond, ..)), Some(value)) => Some((cond, value.clone())),
+                _ => None,
+            };
+            let reset = reset.map(|(&cond, value)| (cond, env, value));
+            self.regs.insert(
+                key,
+                RegInfo {
+                    clock: (clock, negedge),
+                    reset,
+                },
+            );
+        }
+
+        let body = match reset {
+            Some((_, _, else_stmt)) => else_stmt,
+            None => Some(body),
+        };
+        Ok(Some(ClockedProc { body }))
+    }
+
+    /// Map an assignment in a reset branch to the assigned variable and the
+    /// constant reset value.
+    fn reset_assignment(
+        &mut self,
+        stmt_id: NodeId,
+        env: ParamEnv,
+    ) -> Result<((NodeId, ParamEnv), Expr)> {
+        if let HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Assign { lhs, rhs, kind },
+            span,
+            ..
+        }) = self.cx.hir_of(stmt_id)?
+        {
+            let assign = self
+                .cx
+                .mir_assignment_from_procedural(stmt_id, *lhs, *rhs, env, *span, *kind);
+            if assign.is_error() {
+                return Err(());
+            }
+            let key = match assign.lhs.kind {
+                mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                    self.module.storage_key(&self.storage, id, env)
+                }
+                _ => None,
+            };
+            if let (Some(key), true) = (key, assign.rhs.is_const()) {
+                let value = self.rvalue(assign.rhs)?;
+                return Ok((key, value));
+            }
+        }
+        self.cx.emit(
+            DiagBuilder2::error(
+                "reset branch must assign constants to entire variables for this output format",
+            )
+            .code("SV0046")
+            .span(self.cx.span(stmt_id)),
+        );
+        Err(())
+    }
+
+    fn emit_inst_connections(
+        &mut self,
+        inst: &crate::design::DesignInst,
+        name: &str,
+    ) -> Result<()> {
+        let target = &self.design.modules[inst.module];
+        let clocks = &self.module_clocks[inst.module];
+        let inner_env = target.id.env();
+        for (port, &mapping) in target.ports.iter().zip(&inst.ports) {
+            let width = width(self.cx, port.ty, port.port.span)?;
+            let port_name = format!("{}.{}", name, sanitize(&port.port.name.value.to_string()));
+            if port.port.dir == ast::PortDir::Output {
+                let mapping = match mapping {
+                    Some(m) => m,
+                    None => continue,
+                };
+                let mir = self.cx.mir_lvalue(mapping.id(), mapping.env());
+                if mir.is_error() {
+                    return Err(());
+                }
+                let lhs = self.lvalue(mir)?;
+                let value = fit(Expr::new(port_name, width), lhs.width);
+                self.line(format!("connect {}, {}", lhs.name, value.text));
+                continue;
+            }
+            let value = match (mapping, port.port.data.as_ref().and_then(|d| d.default)) {
+                (Some(mapping), _) => self.rvalue_expr(mapping.id(), mapping.env(), width)?,
+                (None, Some(default)) => self.rvalue_expr(default, inner_env, width)?,
+                (None, None) => {
+                    self.line(format!("invalidate {}", port_name));
+                    continue;
+                }
+            };
+            if clocks.contains(&port.port.id) {
+                let clock = match mapping {
+                    Some(m) => signal(self.cx, m.id(), m.env())?,
+                    None => None,
+                };
+                match clock {
+                    Some(id) if self.clocks.contains(&id) => {
+                        let name = self.inputs[&id].name.clone();
+                        self.line(format!("connect {}, {}", port_name, name))
+                    }
+                    _ => self.line(format!("connect {}, asClock({})", port_name, value.text)),
+                }
+            } else {
+                self.line(format!("connect {}, {}", port_name, value.text));
+            }
+        }
+        Ok(())
+    }
+
+    fn emit_assignment(&mut self, assign: &'a mir::Assignment<'a>) -> Result<()> {
+        for assign in self.cx.mir_simplify_assignment(Ref(assign)) {
+            if assign.is_error() {
+                return Err(());
+            }
+            let lhs = self.lvalue(assign.lhs)?;
+            let rhs = self.rvalue(assign.rhs)?;
+            let rhs = fit(rhs, lhs.width);
+            self.line(format!("connect {}, {}", lhs.name, rhs.text));
+        }
+        Ok(())
+    }
+
+    fn emit_stmt(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            HirNode::VarDecl(decl) => {
+                let ty = self.cx.type_of(stmt_id, env)?;
+                let width = width(self.cx, ty, decl.span)?;
+                let name = self.unique_name(&decl.name.value.to_string());
+                self.line(format!("wire {} : UInt<{}>", name, width));
+                self.line(format!("invalidate {}", name));
+                if let Some(init) = decl.init {
+                    let value = self.rvalue_expr(init, env, width)?;
+                    self.line(format!("connect {}, {}", name, value.text));
+                }
+                self.storage.insert((stmt_id, env), Storage { name, width });
+                return Ok(());
+            }
+            HirNode::Proc(x) => return self.emit_stmt(x.stmt, env),
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Null => (),
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.emit_stmt(stmt, env)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, kind } => {
+                if let hir::AssignKind::NonblockDelay(_) = kind {
+                    return unsupported(self.cx, hir);
+                }
+                let assign = self
+                    .cx
+                    .mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                self.emit_assignment(assign)?;
+            }
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ImplicitEvent,
+                stmt,
+            } => self.emit_stmt(stmt, env)?,
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                let cond = self.bool_expr(cond, env)?;
+                self.emit_when(&format!("when {} :", cond.text), main_stmt, env)?;
+                if let Some(else_stmt) = else_stmt {
+                    self.emit_when("else :", else_stmt, env)?;
+                }
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                let value = self.rvalue(self.cx.mir_rvalue(expr, env))?;
+                let mut keyword = "when";
+                for &(ref labels, stmt) in ways {
+                    let mut checks = vec![];
+                    for &label in labels {
+                        let label_value = self.cx.constant_value_of(label, env);
+                        checks.push(self.case_check(&value, label_value, kind, label)?);
+                    }
+                    let check = match checks
+                        .into_iter()
+                        .reduce(|a, b| format!("or({}, {})", a, b))
+                    {
+                        Some(check) => check,
+                        None => continue,
+                    };
+                    self.emit_when(&format!("{} {} :", keyword, check), stmt, env)?;
+                    keyword = "else when";
+                }
+                if let Some(default) = default {
+                    if keyword == "when" {
+                        self.emit_stmt(default, env)?;
+                    } else {
+                        self.emit_when("else :", default, env)?;
+                    }
+                }
+            }
+            _ => return unsupported(self.cx, hir),
+        }
+        Ok(())
+    }
+
+    /// Emit a `when` or `else` block.
+    fn emit_when(&mut self, header: &str, stmt: NodeId, env: ParamEnv) -> Result<()> {
+        self.line(header);
+        self.indent += 1;
+        let len = self.lines.len();
+        self.emit_stmt(stmt, env)?;
+        if self.lines.len() == len {
+            self.line("skip");
+        }
+        self.indent -= 1;
+        Ok(())
+    }
+
+    /// Emit the comparison of a `case` expression against a label.
+    fn case_check(
+        &mut self,
+        value: &Expr,
+        label: Value<'a>,
+        kind: ast::CaseKind,
+        label_id: NodeId,
+    ) -> Result<String> {
+        let (int, special, x) = match label.kind {
+            ValueKind::Int(ref int, ref special, ref x) => (int, special, x),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error("case label must be an integer")
+                        .code("SV0046")
+                        .span(self.cx.span(label_id)),
+                );
+                return Err(());
+            }
+        };
+
+        // Determine which bits take part in the comparison. The special bits
+        // are stored with the most significant digit first and only cover the
+        // digits spelled out in the literal.
+        let width = value.width;
+        let mut mask = BigInt::zero();
+        for bit in 0..width {
+            let ignore = match special.len().checked_sub(bit + 1) {
+                Some(i) if special[i] => match kind {
+                    ast::CaseKind::Normal => false,
+                    ast::CaseKind::DontCareZ => !x[i],
+                    ast::CaseKind::DontCareXZ => true,
+                },
+                _ => false,
+            };
+            if !ignore {
+                mask |= BigInt::one() << bit;
+            }
+        }
+        if mask == (BigInt::one() << width) - 1 {
+            let label = constant(int, width);
+            Ok(format!("eq({}, {})", value.text, label.text))
+        } else {
+            let label = constant(&(int & &mask), width);
+            let mask = constant(&mask, width);
+            Ok(format!(
+                "eq(and({}, {}), {})",
+                value.text, mask.text, label.text
+            ))
+        }
+    }
+
+    /// Emit an expression, converted to a given width.
+    fn rvalue_expr(&mut self, expr_id: NodeId, env: ParamEnv, width: usize) -> Result<Expr> {
+        let value = self.rvalue(self.cx.mir_rvalue(expr_id, env))?;
+        Ok(fit(value, width))
+    }
+
+    /// Emit an expression as a single-bit condition.
+    fn bool_expr(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<Expr> {
+        let value = self.rvalue(self.cx.mir_rvalue(expr_id, env))?;
+        Ok(to_bool(value))
+    }
+
+    fn rvalue(&mut self, mir: &'a mir::Rvalue<'a>) -> Result<Expr> {
+        if mir.is_error() {
+            return Err(());
+        }
+        let width = width(self.cx, mir.ty, mir.span)?;
+
+        // Emit constants as their folded value.
+        if mir.is_const() {
+            let value = self.cx.const_mir_rvalue(mir.into());
+            return match value.kind {
+                ValueKind::Int(ref int, ..) => Ok(constant(int, width)),
+                _ => unsupported_expr(self.cx, mir),
+            };
+        }
+
+        let expr = match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
+                if let Some(input) = self.inputs.get(&id) {
+                    if self.clocks.contains(&id) {
+                        Expr::new(format!("asUInt({})", input.name), 1)
+                    } else {
+                        Expr::new(input.name.clone(), input.width)
+                    }
+                } else {
+                    match self.module.storage_key(&self.storage, id, mir.env) {
+                        Some(key) => {
+                            let storage = &self.storage[&key];
+                            Expr::new(storage.name.clone(), storage.width)
+                        }
+                        None => return unsupported_expr(self.cx, mir),
+                    }
+                }
+            }
+            mir::RvalueKind::CastValueDomain { value, .. }
+            | mir::RvalueKind::Transmute(value)
+            | mir::RvalueKind::CastSign(_, value)
+            | mir::RvalueKind::Truncate(_, value)
+            | mir::RvalueKind::ZeroExtend(_, value) => self.rvalue(value)?,
+            mir::RvalueKind::CastToBool(value) => to_bool(self.rvalue(value)?),
+            mir::RvalueKind::SignExtend(_, value) => {
+                let value = self.rvalue(value)?;
+                Expr::new(
+                    format!("asUInt(pad(asSInt({}), {}))", value.text, width),
+                    width,
+                )
+            }
+            mir::RvalueKind::UnaryBitwise { arg, .. } => {
+                let arg = self.rvalue(arg)?;
+                Expr::new(format!("not({})", arg.text), arg.width)
+            }
+            mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let op = match op {
+                    mir::BinaryBitwiseOp::And => "and",
+                    mir::BinaryBitwiseOp::Or => "or",
+                    mir::BinaryBitwiseOp::Xor => "xor",
+                };
+                binary(op, &lhs, &rhs, lhs.width.max(rhs.width))
+            }
+            mir::RvalueKind::IntUnaryArith { arg, .. } => {
+                let arg = self.rvalue(arg)?;
+                Expr::new(format!("asUInt(neg({}))", arg.text), arg.width + 1)
+            }
+            mir::RvalueKind::IntBinaryArith {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let max = lhs.width.max(rhs.width);
+                match (op, sign.is_signed()) {
+                    (mir::IntBinaryArithOp::Add, _) => binary("add", &lhs, &rhs, max + 1),
+                    (mir::IntBinaryArithOp::Sub, _) => binary("sub", &lhs, &rhs, max + 1),
+                    (mir::IntBinaryArithOp::Mul, _) => {
+                        binary("mul", &lhs, &rhs, lhs.width + rhs.width)
+                    }
+                    (mir::IntBinaryArithOp::Div, false) => binary("div", &lhs, &rhs, lhs.width),
+                    (mir::IntBinaryArithOp::Mod, false) => {
+                        binary("rem", &lhs, &rhs, lhs.width.min(rhs.width))
+                    }
+                    (mir::IntBinaryArithOp::Div, true) => {
+                        signed_binary("div", &lhs, &rhs, lhs.width + 1)
+                    }
+                    (mir::IntBinaryArithOp::Mod, true) => {
+                        signed_binary("rem", &lhs, &rhs, lhs.width.min(rhs.width))
+                    }
+                    (mir::IntBinaryArithOp::Pow, _) => return unsupported_expr(self.cx, mir),
+                }
+            }
+            mir::RvalueKind::IntComp {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let op = match op {
+                    mir::IntCompOp::Eq => "eq",
+                    mir::IntCompOp::Neq => "neq",
+                    mir::IntCompOp::Lt => "lt",
+                    mir::IntCompOp::Leq => "leq",
+                    mir::IntCompOp::Gt => "gt",
+                    mir::IntCompOp::Geq => "geq",
+                };
+                if sign.is_signed() {
+                    Expr::new(
+                        format!("{}(asSInt({}), asSInt({}))", op, lhs.text, rhs.text),
+                        1,
+                    )
+                } else {
+                    binary(op, &lhs, &rhs, 1)
+                }
+            }
+            mir::RvalueKind::Concat(ref values) => {
+                let mut values = values
+                    .iter()
+                    .map(|&v| self.rvalue(v))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut result = values.pop().unwrap();
+                while let Some(value) = values.pop() {
+                    result = binary("cat", &value, &result, value.width + result.width);
+                }
+                result
+            }
+            mir::RvalueKind::Repeat(times, value) => {
+                let value = self.rvalue(value)?;
+                let mut result = value.clone();
+                for _ in 1..times {
+                    result = binary("cat", &value, &result, value.width + result.width);
+                }
+                result
+            }
+            mir::RvalueKind::Index {
+                value,
+                base,
+                length,
+            } => {
+                let value = self.rvalue(value)?;
+                let length = std::cmp::max(1, length);
+                if base.is_const() {
+                    let offset = match self.cx.const_mir_rvalue(base.into()).get_int() {
+                        Some(x) if !x.is_negative() => x.to_usize().unwrap_or(usize::MAX),
+                        _ => return unsupported_expr(self.cx, mir),
+                    };
+                    let value = fit(value.clone(), value.width.max(offset + length));
+                    Expr::new(
+                        format!("bits({}, {}, {})", value.text, offset + length - 1, offset),
+                        length,
+                    )
+                } else {
+                    let base = self.rvalue(base)?;
+                    let value = fit(
+                        Expr::new(format!("dshr({}, {})", value.text, base.text), value.width),
+                        value.width.max(length),
+                    );
+                    Expr::new(format!("bits({}, {}, 0)", value.text, length - 1), length)
+                }
+            }
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = to_bool(self.rvalue(cond)?);
+                let a = fit(self.rvalue(true_value)?, width);
+                let b = fit(self.rvalue(false_value)?, width);
+                Expr::new(format!("mux({}, {}, {})", cond.text, a.text, b.text), width)
+            }
+            mir::RvalueKind::Shift {
+                op,
+                arith,
+                value,
+                amount,
+            } => {
+                let value = self.rvalue(value)?;
+                if amount.is_const() {
+                    let amount = match self.cx.const_mir_rvalue(amount.into()).get_int() {
+                        Some(x) if !x.is_negative() => x.to_usize().unwrap_or(usize::MAX),
+                        _ => return unsupported_expr(self.cx, mir),
+                    };
+                    let amount = amount.min(value.width);
+                    let shrunk = value.width.saturating_sub(amount).max(1);
+                    return Ok(fit(
+                        match (op, arith) {
+                            (mir::ShiftOp::Left, _) => Expr::new(
+                                format!("shl({}, {})", value.text, amount),
+                                value.width + amount,
+                            ),
+                            (mir::ShiftOp::Right, true) => Expr::new(
+                                format!("asUInt(shr(asSInt({}), {}))", value.text, amount),
+                                shrunk,
+                            ),
+                            (mir::ShiftOp::Right, false) => {
+                                Expr::new(format!("shr({}, {})", value.text, amount), shrunk)
+                            }
+                        },
+                        width,
+                    ));
+                }
+                let amount = self.rvalue(amount)?;
+                match op {
+                    mir::ShiftOp::Left => shift_left(value, amount),
+                    mir::ShiftOp::Right if arith => Expr::new(
+                        format!("asUInt(dshr(asSInt({}), {}))", value.text, amount.text),
+                        value.width,
+                    ),
+                    mir::ShiftOp::Right => binary("dshr", &value, &amount, value.width),
+                }
+            }
+            mir::RvalueKind::Reduction { op, arg } => {
+                let arg = self.rvalue(arg)?;
+                let op = match op {
+                    mir::BinaryBitwiseOp::And => "andr",
+                    mir::BinaryBitwiseOp::Or => "orr",
+                    mir::BinaryBitwiseOp::Xor => "xorr",
+                };
+                Expr::new(format!("{}({})", op, arg.text), 1)
+            }
+            _ => return unsupported_expr(self.cx, mir),
+        };
+        Ok(fit(expr, width))
+    }
+
+    /// Emit the storage targeted by an lvalue.
+    fn lvalue(&mut self, mir: &'a mir::Lvalue<'a>) -> Result<Storage> {
+        if mir.is_error() {
+            return Err(());
+        }
+        match mir.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                let key = self.module.storage_key(&self.storage, id, mir.env);
+                if let Some(storage) = key.and_then(|key| self.storage.get(&key)) {
+                    return Ok(storage.clone());
+                }
+            }
+            mir::LvalueKind::Transmute(value) => return self.lvalue(value),
+            mir::LvalueKind::Index { .. } => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "assignment to a part of a variable `{}` not supported by this output \
+                         format",
+                        mir.span.extract()
+                    ))
+                    .code("SV0046")
+                    .span(mir.span),
+                );
+                return Err(());
+            }
+            _ => (),
+        }
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "assignment to `{}` not supported by this output format",
+                mir.span.extract()
+            ))
+            .code("SV0046")
+            .span(mir.span),
+        );
+        Err(())
+    }
+
+    /// Check whether a node is an input port of the module.
+    fn is_input(&self, id: NodeId) -> bool {
+        match self.module.find_port(id) {
+            Some(index) => self.module.ports[index].port.dir == ast::PortDir::Input,
+            None => false,
+        }
+    }
+
+    /// Allocate a unique name derived from a name in the source text.
+    fn unique_name(&mut self, name: &str) -> String {
+        unique(&mut self.names, &sanitize(name))
+    }
+
+    fn line(&mut self, line: impl AsRef<str>) {
+        let line = line.as_ref();
+        if !line.is_empty() {
+            for _ in 0..self.indent {
+                self.lines.push_str("  ");
+            }
+        }
+        self.lines.push_str(line);
+        self.lines.push('\n');
+    }
+}
+
+/// Collect the statements within nested blocks.
+fn flatten_stmts<'a>(cx: &impl Context<'a>, stmt_id: NodeId, into: &mut Vec<NodeId>) -> Result<()> {
+    match cx.hir_of(stmt_id)? {
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Block(stmts),
+            ..
+        }) => {
+            for &stmt in stmts {
+                flatten_stmts(cx, stmt, into)?;
+            }
+        }
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Null,
+            ..
+        }) => (),
+        _ => into.push(stmt_id),
+    }
+    Ok(())
+}
+
+/// Determine the signal an expression refers to, if it is a plain
+/// identifier.
+fn signal<'a>(cx: &impl Context<'a>, expr_id: NodeId, env: ParamEnv) -> Result<Option<NodeId>> {
+    match cx.hir_of(expr_id)? {
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Ident(..),
+            ..
+        }) => Ok(Some(cx.resolve_node(expr_id, env)?)),
+        _ => Ok(None),
+    }
+}
+
+fn binary(op: &str, lhs: &Expr, rhs: &Expr, width: usize) -> Expr {
+    Expr::new(format!("{}({}, {})", op, lhs.text, rhs.text), width)
+}
+
+fn signed_binary(op: &str, lhs: &Expr, rhs: &Expr, width: usize) -> Expr {
+    Expr::new(
+        format!("asUInt({}(asSInt({}), asSInt({})))", op, lhs.text, rhs.text),
+        width,
+    )
+}
+
+/// Shift a value left, limiting the width of the shift amount such that the
+/// intermediate result does not grow beyond twice the value's width.
+fn shift_left(value: Expr, amount: Expr) -> Expr {
+    let bits = (usize::BITS - value.width.leading_zeros()) as usize;
+    if amount.width <= bits {
+        let width = value.width + (1 << amount.width) - 1;
+        return fit(binary("dshl", &value, &amount, width), value.width);
+    }
+    let low = Expr::new(format!("bits({}, {}, 0)", amount.text, bits - 1), bits);
+    let high = format!("orr(bits({}, {}, {}))", amount.text, amount.width - 1, bits);
+    let width = value.width + (1 << bits) - 1;
+    let shifted = fit(binary("dshl", &value, &low, width), value.width);
+    Expr::new(
+        format!(
+            "mux({}, {}, {})",
+            high,
+            constant(&BigInt::zero(), value.width).text,
+            shifted.text
+        ),
+        value.width,
+    )
+}
+
+/// Emit an integer constant of a given width.
+fn constant(value: &BigInt, width: usize) -> Expr {
+    let modulus = BigInt::one() << width;
+    let mut value = value % &modulus;
+    if value.is_negative() {
+        value += modulus;
+    }
+    Expr::new(format!("UInt<{}>({})", width, value), width)
+}
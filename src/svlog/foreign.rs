@@ -0,0 +1,328 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Instantiation of design units across language boundaries.
+//!
+//! Instantiating a module that is not declared in the SystemVerilog input
+//! falls back to the design units declared in other languages, such as VHDL
+//! entities. A stand-in module is synthesized from the interface of the unit,
+//! with a parameter for every generic and a port for every port of the unit.
+//! Single bits become `logic` or `bit`, vectors become packed arrays with the
+//! same bounds, and integers become two-valued numbers of the same width.
+//!
+//! Conversely, a module instantiated from another language is described by
+//! the language-neutral interface of `moore_common::foreign`. Single bits and
+//! one-dimensional packed vectors of them keep their bounds, and integer atoms
+//! such as `int` keep their width. Port types that depend on parameters are
+//! evaluated with the default values of the parameters.
+//!
+//! Both directions describe the conversion of every port and parameter as a
+//! `Crossing`, noting the caveats of the conversion such as signed vectors
+//! losing their signedness.
+
+use crate::{
+    common::{arenas::Alloc, foreign::*, source::get_source_manager},
+    crate_prelude::*,
+    hir::HirNode,
+    lexer::Lexer,
+    parser,
+    preproc::Preprocessor,
+    ty::{Domain, PackedCore, RangeDir, Sign, UnpackedType},
+    value::{Value, ValueKind},
+};
+use num::ToPrimitive;
+
+/// Describe the interface of a module for instantiation from another language.
+pub fn foreign_unit<'a>(cx: &impl Context<'a>, id: NodeId) -> Result<ForeignUnit> {
+    let hir = match cx.hir_of(id)? {
+        HirNode::Module(x) => x,
+        _ => unreachable!(),
+    };
+    let env = cx.default_param_env();
+    let mut params = vec![];
+    for &param_id in hir.params {
+        let param = match cx.hir_of(param_id)? {
+            HirNode::ValueParam(p) => p,
+            HirNode::TypeParam(p) => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "module `{}` has a type parameter `{}`",
+                        hir.name, p.name
+                    ))
+                    .code("SV0048")
+                    .span(p.name.span)
+                    .add_note("Only value parameters can be assigned from other languages."),
+                );
+                return Err(());
+            }
+            _ => unreachable!(),
+        };
+        if param.local {
+            continue;
+        }
+        let decl_ty = cx.type_of(param_id, env)?;
+        let ty = foreign_type(cx, decl_ty, param.name.span)?;
+        let default = match param.default {
+            Some(_) => Some(foreign_value(
+                cx,
+                cx.constant_value_of(param_id, env),
+                &ty,
+                param.name.span,
+            )?),
+            None => None,
+        };
+        params.push(ForeignParam {
+            name: param.name.value,
+            ty,
+            decl_ty: decl_ty.to_string(),
+            default,
+            notes: foreign_notes(decl_ty),
+        });
+    }
+    let mut ports = vec![];
+    for port in &hir.ports_new.int {
+        let dir = match port.dir {
+            ast::PortDir::Input => ForeignDir::Input,
+            ast::PortDir::Output => ForeignDir::Output,
+            ast::PortDir::Inout => ForeignDir::Inout,
+            ast::PortDir::Ref => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "ref port `{}` cannot be connected from another language",
+                        port.name
+                    ))
+                    .code("SV0048")
+                    .span(port.name.span),
+                );
+                return Err(());
+            }
+        };
+        let decl_ty = cx.type_of_int_port(Ref(port), env);
+        ports.push(ForeignPort {
+            name: port.name.value,
+            dir,
+            ty: foreign_type(cx, decl_ty, port.name.span)?,
+            decl_ty: decl_ty.to_string(),
+            notes: foreign_notes(decl_ty),
+        });
+    }
+    Ok(ForeignUnit {
+        name: hir.name.value,
+        span: hir.name.span,
+        lang: ForeignLang::Svlog,
+        params,
+        ports,
+    })
+}
+
+/// Map the type of a port or parameter to a language-neutral type.
+fn foreign_type<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    span: Span,
+) -> Result<ForeignType> {
+    if ty.is_error() {
+        return Err(());
+    }
+    if let Some(packed) = ty.get_packed() {
+        let packed = packed.resolve_full();
+        let logic = packed.domain() == Domain::FourValued;
+        match packed.core {
+            PackedCore::IntAtom(_) if packed.dims.is_empty() => {
+                if let Some(sbv) = packed.get_simple_bit_vector() {
+                    return Ok(ForeignType::Int {
+                        width: sbv.size,
+                        signed: sbv.sign == Sign::Signed,
+                    });
+                }
+            }
+            PackedCore::IntVec(_) if packed.dims.is_empty() => {
+                return Ok(ForeignType::Bit { logic });
+            }
+            PackedCore::IntVec(_) if packed.dims.len() == 1 => {
+                if let Some(range) = packed.dims[0].get_range() {
+                    let lo = range.offset;
+                    let hi = lo + range.size as isize - 1;
+                    let (left, right) = match range.dir {
+                        RangeDir::Up => (lo, hi),
+                        RangeDir::Down => (hi, lo),
+                    };
+                    return Ok(ForeignType::Vector { logic, left, right });
+                }
+            }
+            _ => (),
+        }
+    }
+    cx.emit(
+        DiagBuilder2::error(format!("type `{}` cannot cross a language boundary", ty))
+            .code("SV0048")
+            .span(span)
+            .add_note(
+                "Only single bits, one-dimensional packed vectors thereof, and integer atoms \
+                 are supported.",
+            ),
+    );
+    Err(())
+}
+
+/// Determine the caveats of mapping the type of a port or parameter to a
+/// language-neutral type.
+fn foreign_notes<'a>(ty: &'a UnpackedType<'a>) -> Vec<String> {
+    let mut notes = vec![];
+    if let Some(packed) = ty.get_packed() {
+        let packed = packed.resolve_full();
+        match packed.core {
+            PackedCore::IntAtom(_) if packed.domain() == Domain::FourValued => notes.push(format!(
+                "the `x` and `z` bits of `{}` cannot be represented by an integer",
+                ty
+            )),
+            PackedCore::IntVec(_) if packed.sign == Sign::Signed => notes.push(format!(
+                "the signedness of `{}` is dropped, leaving a plain vector",
+                ty
+            )),
+            _ => (),
+        }
+    }
+    notes
+}
+
+/// Map the value of a parameter to a language-neutral value.
+fn foreign_value<'a>(
+    cx: &impl Context<'a>,
+    value: Value<'a>,
+    ty: &ForeignType,
+    span: Span,
+) -> Result<ForeignValue> {
+    match value.kind {
+        ValueKind::Int(ref int, ref special, ref x) => match *ty {
+            ForeignType::Int { .. } if !special.any() => {
+                if let Some(v) = int.to_i64() {
+                    return Ok(ForeignValue::Int(v));
+                }
+            }
+            ForeignType::Bit { .. } | ForeignType::Vector { .. } => {
+                // The special bits are stored with the most significant digit
+                // first and only cover the digits spelled out in the literal.
+                let bits = (0..ty.width())
+                    .rev()
+                    .map(|bit| match special.len().checked_sub(bit + 1) {
+                        Some(i) if special[i] && x[i] => 'x',
+                        Some(i) if special[i] => 'z',
+                        _ if int.bit(bit as u64) => '1',
+                        _ => '0',
+                    })
+                    .collect();
+                return Ok(ForeignValue::Bits(bits));
+            }
+            _ => (),
+        },
+        ValueKind::Error => return Err(()),
+        _ => (),
+    }
+    cx.emit(
+        DiagBuilder2::error(format!(
+            "value `{}` cannot cross a language boundary",
+            value
+        ))
+        .code("SV0048")
+        .span(span),
+    );
+    Err(())
+}
+
+/// Find a module that is not declared in the SystemVerilog input among the
+/// design units declared in other languages.
+///
+/// Returns `None` if no other language declares the unit.
+pub(crate) fn foreign_module<'a>(
+    cx: &impl Context<'a>,
+    name: Spanned<Name>,
+) -> Result<Option<&'a ast::Module<'a>>> {
+    let unit = match cx.gcx().find_foreign_unit(name.value) {
+        Some(unit) => unit,
+        None => return Ok(None),
+    };
+    debug!("Instantiating {} unit `{}`", unit.lang, unit.name);
+    cx.sess().units.add_crossing(crossing(&unit));
+
+    // Parse the stand-in and add it to the design.
+    let text = module_text(&unit);
+    let source = get_source_manager().add(&format!("<{} {}>", unit.lang, unit.name), &text);
+    let preproc = Preprocessor::new(source, &[], &[]);
+    let file = match parser::parse(Lexer::new(preproc), &cx.arena().ast) {
+        Ok(x) => cx.arena().alloc(x),
+        Err(()) => bug_span!(
+            name.span,
+            cx,
+            "stand-in for {} unit `{}` failed to parse",
+            unit.lang,
+            unit.name
+        ),
+    };
+    cx.gcx().add_files(Some(&*file).into_iter());
+    match file.items.first().map(|item| &item.data) {
+        Some(ast::ItemData::ModuleDecl(ref x)) => Ok(Some(x)),
+        _ => unreachable!(),
+    }
+}
+
+/// Describe the conversions made when instantiating a design unit declared in
+/// another language through its stand-in module.
+pub fn crossing(unit: &ForeignUnit) -> Crossing {
+    Crossing::new(unit, ForeignLang::Svlog, |ty, _| svlog_type(ty))
+}
+
+/// Generate the declaration of the stand-in module for a foreign unit.
+fn module_text(unit: &ForeignUnit) -> String {
+    let params: Vec<_> = unit
+        .params
+        .iter()
+        .map(|param| {
+            let mut decl = format!("parameter {} {}", svlog_type(&param.ty), param.name);
+            if let Some(ref value) = param.default {
+                decl.push_str(&format!(" = {}", svlog_value(value)));
+            }
+            decl
+        })
+        .collect();
+    let ports: Vec<_> = unit
+        .ports
+        .iter()
+        .map(|port| format!("{} {} {}", port.dir, svlog_type(&port.ty), port.name))
+        .collect();
+    let mut text = format!("module {}", unit.name);
+    if !params.is_empty() {
+        text.push_str(&format!(" #({})", params.join(", ")));
+    }
+    if !ports.is_empty() {
+        text.push_str(&format!(" ({})", ports.join(", ")));
+    }
+    text.push_str(";\nendmodule\n");
+    text
+}
+
+/// Map a type of a foreign unit to a SystemVerilog type.
+fn svlog_type(ty: &ForeignType) -> String {
+    match *ty {
+        ForeignType::Bit { logic: true } => "logic".to_string(),
+        ForeignType::Bit { logic: false } => "bit".to_string(),
+        ForeignType::Vector { logic, left, right } => format!(
+            "{} [{}:{}]",
+            if logic { "logic" } else { "bit" },
+            left,
+            right
+        ),
+        ForeignType::Int { width, signed } => format!(
+            "bit{} [{}:0]",
+            if signed { " signed" } else { "" },
+            width.max(1) - 1
+        ),
+    }
+}
+
+/// Map a parameter value of a foreign unit to a SystemVerilog literal.
+fn svlog_value(value: &ForeignValue) -> String {
+    match *value {
+        ForeignValue::Int(v) => v.to_string(),
+        ForeignValue::Bits(ref bits) => format!("{}'b{}", bits.len(), bits),
+    }
+}
@@ -0,0 +1,627 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Format strings of the display and formatting system tasks.
+//!
+//! This module parses the format strings passed to `$display`, `$write`,
+//! `$strobe`, `$monitor`, and `$sformatf`, and matches their format specifiers
+//! against the remaining arguments of the call. The result is a sequence of
+//! literal text and values to be printed, with the formatting of each value
+//! fully resolved. See IEEE 1800-2017 §21.2.1 "The display and write tasks".
+
+use crate::crate_prelude::*;
+use crate::{
+    syntax::token::Lit,
+    value::{Value, ValueKind},
+    ParamEnv,
+};
+use num::{BigInt, Integer, One, ToPrimitive, Zero};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// The display and formatting system tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum DisplayTask {
+    /// `$display`, which prints a message followed by a newline.
+    Display,
+    /// `$write`, which prints a message without a trailing newline.
+    Write,
+    /// `$strobe`, which prints a message at the end of the current time step.
+    Strobe,
+    /// `$monitor`, which prints a message whenever one of its arguments
+    /// changes.
+    Monitor,
+    /// `$sformatf`, which returns the formatted message as a string.
+    Sformatf,
+}
+
+impl DisplayTask {
+    /// Get the name of the system task, e.g. `$display`.
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayTask::Display => "$display",
+            DisplayTask::Write => "$write",
+            DisplayTask::Strobe => "$strobe",
+            DisplayTask::Monitor => "$monitor",
+            DisplayTask::Sformatf => "$sformatf",
+        }
+    }
+}
+
+/// The radix in which an integer is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Radix {
+    /// Binary, as printed by `%b`.
+    Bin,
+    /// Octal, as printed by `%o`.
+    Oct,
+    /// Decimal, as printed by `%d`.
+    Dec,
+    /// Hexadecimal, as printed by `%h`.
+    Hex,
+}
+
+impl Radix {
+    /// Get the number of bits represented by one digit, or `None` for
+    /// decimal.
+    pub(crate) fn digit_bits(self) -> Option<usize> {
+        match self {
+            Radix::Bin => Some(1),
+            Radix::Oct => Some(3),
+            Radix::Dec => None,
+            Radix::Hex => Some(4),
+        }
+    }
+}
+
+/// How a value is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatKind {
+    /// An integer in a radix, as printed by `%b`, `%o`, `%d`, and `%h`.
+    Int(Radix),
+    /// The lowest byte of an integer as a character, as printed by `%c`.
+    Char,
+    /// A string, as printed by `%s`.
+    String,
+    /// A simulation time, as printed by `%t`.
+    Time,
+    /// A real number in exponential notation, as printed by `%e`.
+    Exp,
+    /// A real number in decimal notation, as printed by `%f`.
+    Float,
+    /// The shorter of `%e` and `%f`, as printed by `%g`.
+    General,
+}
+
+impl FormatKind {
+    /// Check whether this formats real numbers.
+    pub fn is_real(self) -> bool {
+        match self {
+            FormatKind::Exp | FormatKind::Float | FormatKind::General => true,
+            _ => false,
+        }
+    }
+}
+
+/// A format specifier with its field width resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatSpec {
+    /// How the value is formatted.
+    pub kind: FormatKind,
+    /// The minimum number of characters printed. Binary, octal, and
+    /// hexadecimal integers are padded with zeros, everything else with
+    /// spaces.
+    pub width: usize,
+    /// The number of digits after the decimal point of real numbers.
+    pub precision: usize,
+}
+
+/// A piece of a formatted message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatPiece<'a> {
+    /// Literal text.
+    Text(String),
+    /// A value formatted according to a specifier.
+    Value(FormatSpec, &'a ast::Expr<'a>),
+    /// The hierarchical name of the current scope, as printed by `%m`.
+    Scope,
+}
+
+/// The pieces of the message printed or returned by a display task.
+#[derive(Debug)]
+pub struct FormatMapping<'a> {
+    /// The pieces of the message, in order.
+    pub pieces: Vec<FormatPiece<'a>>,
+    /// Set if the mapping is incomplete due to an error.
+    error: bool,
+}
+
+impl<'a> FormatMapping<'a> {
+    /// Is this a tombstone?
+    pub fn is_error(&self) -> bool {
+        self.error
+    }
+}
+
+/// Match the format strings of a display task call against its arguments.
+#[moore_derive::query]
+pub(crate) fn format_mapping<'a>(
+    cx: &impl Context<'a>,
+    Ref(args): Ref<'a, [ast::CallArg<'a>]>,
+    task: DisplayTask,
+    radix: Radix,
+    span: Span,
+    env: ParamEnv,
+) -> Arc<FormatMapping<'a>> {
+    let mut mapper = FormatMapper {
+        cx,
+        task,
+        env,
+        args: args.iter(),
+        pieces: vec![],
+        failed: false,
+    };
+    mapper.map_args(radix, span);
+    if task != DisplayTask::Write && task != DisplayTask::Sformatf {
+        mapper.push_text("\n");
+    }
+    Arc::new(FormatMapping {
+        pieces: mapper.pieces,
+        error: mapper.failed,
+    })
+}
+
+/// A helper to consume the arguments of a display task call.
+struct FormatMapper<'a, 'c, C> {
+    cx: &'c C,
+    task: DisplayTask,
+    env: ParamEnv,
+    args: std::slice::Iter<'a, ast::CallArg<'a>>,
+    pieces: Vec<FormatPiece<'a>>,
+    failed: bool,
+}
+
+impl<'a, 'c, C: Context<'a>> FormatMapper<'a, 'c, C> {
+    fn map_args(&mut self, radix: Radix, span: Span) {
+        // `$sformatf` requires its first argument to be the format string.
+        // Any further arguments are consumed by the format specifiers.
+        if self.task == DisplayTask::Sformatf {
+            match self.next_arg() {
+                Some(Some(expr)) => match expr.data {
+                    ast::LiteralExpr(Lit::Str(fmt)) => self.map_format(fmt, expr.span),
+                    _ => {
+                        self.cx.emit(
+                            DiagBuilder2::error(
+                                "format string of `$sformatf` must be a string literal",
+                            )
+                            .code("SV0028")
+                            .span(expr.span),
+                        );
+                        self.failed = true;
+                        return;
+                    }
+                },
+                _ => {
+                    self.cx.emit(
+                        DiagBuilder2::error("`$sformatf` requires a format string")
+                            .code("SV0028")
+                            .span(span),
+                    );
+                    self.failed = true;
+                    return;
+                }
+            }
+            if let Some(arg) = self.args.next() {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "argument `{}` is not consumed by the format string",
+                        arg.span().extract()
+                    ))
+                    .code("SV0028")
+                    .span(arg.span()),
+                );
+                self.failed = true;
+            }
+            return;
+        }
+
+        // The other tasks treat every string literal as a format string, and
+        // print any other argument in the default format.
+        while let Some(arg) = self.next_arg() {
+            let expr = match arg {
+                Some(expr) => expr,
+                None => {
+                    self.push_text(" ");
+                    continue;
+                }
+            };
+            match expr.data {
+                ast::LiteralExpr(Lit::Str(fmt)) => self.map_format(fmt, expr.span),
+                _ => {
+                    let ty = self.cx.need_self_determined_type(expr.id(), self.env);
+                    let kind = if ty.is_real() {
+                        FormatKind::General
+                    } else if ty.is_string() {
+                        FormatKind::String
+                    } else {
+                        FormatKind::Int(radix)
+                    };
+                    self.push_value(kind, None, None, expr);
+                }
+            }
+        }
+    }
+
+    /// Get the next argument, skipping named arguments.
+    fn next_arg(&mut self) -> Option<Option<&'a ast::Expr<'a>>> {
+        loop {
+            let arg = self.args.next()?;
+            if let Some(name) = arg.name {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` does not accept named arguments",
+                        self.task.name()
+                    ))
+                    .code("SV0027")
+                    .span(name.span),
+                );
+                self.failed = true;
+                continue;
+            }
+            return Some(arg.expr.as_ref());
+        }
+    }
+
+    /// Parse a format string and consume the arguments of its specifiers.
+    fn map_format(&mut self, fmt: Name, span: Span) {
+        let fmt = fmt.as_str();
+        let mut chars = fmt.chars().peekable();
+        let mut text = String::new();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+
+            // Parse the optional field width and precision.
+            let mut spec = String::from("%");
+            let mut width = None;
+            let mut precision = None;
+            while let Some(&c) = chars.peek() {
+                let field = if precision.is_some() {
+                    &mut precision
+                } else {
+                    &mut width
+                };
+                match c.to_digit(10) {
+                    Some(d) => {
+                        let value = field.get_or_insert(0);
+                        *value = *value * 10 + d as usize;
+                    }
+                    None if c == '.' && precision.is_none() => precision = Some(0),
+                    None => break,
+                }
+                spec.push(c);
+                chars.next();
+            }
+
+            // Parse the specifier character.
+            let c = match chars.next() {
+                Some(c) => c,
+                None => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "incomplete format specifier `{}` at end of format string",
+                            spec
+                        ))
+                        .code("SV0028")
+                        .span(span),
+                    );
+                    self.failed = true;
+                    break;
+                }
+            };
+            spec.push(c);
+            let kind = match c.to_ascii_lowercase() {
+                '%' => {
+                    text.push('%');
+                    continue;
+                }
+                'm' => {
+                    self.push_text(&text);
+                    text.clear();
+                    self.pieces.push(FormatPiece::Scope);
+                    continue;
+                }
+                'b' => FormatKind::Int(Radix::Bin),
+                'o' => FormatKind::Int(Radix::Oct),
+                'd' => FormatKind::Int(Radix::Dec),
+                'h' | 'x' => FormatKind::Int(Radix::Hex),
+                'c' => FormatKind::Char,
+                's' => FormatKind::String,
+                't' => FormatKind::Time,
+                'e' => FormatKind::Exp,
+                'f' => FormatKind::Float,
+                'g' => FormatKind::General,
+                'l' | 'v' | 'u' | 'z' | 'p' => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!("unsupported: format specifier `{}`", spec))
+                            .code("SV0045")
+                            .span(span),
+                    );
+                    self.failed = true;
+                    continue;
+                }
+                _ => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!("unknown format specifier `{}`", spec))
+                            .code("SV0028")
+                            .span(span),
+                    );
+                    self.failed = true;
+                    continue;
+                }
+            };
+
+            // Consume the argument printed by the specifier.
+            self.push_text(&text);
+            text.clear();
+            match self.next_arg() {
+                Some(Some(expr)) => self.push_value(kind, width, precision, expr),
+                Some(None) => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "format specifier `{}` requires an argument, but the argument is empty",
+                            spec
+                        ))
+                        .code("SV0028")
+                        .span(span),
+                    );
+                    self.failed = true;
+                }
+                None => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "missing argument for format specifier `{}`",
+                            spec
+                        ))
+                        .code("SV0028")
+                        .span(span),
+                    );
+                    self.failed = true;
+                }
+            }
+        }
+        self.push_text(&text);
+    }
+
+    /// Check that a value can be printed in a format, and add it to the
+    /// message.
+    fn push_value(
+        &mut self,
+        kind: FormatKind,
+        width: Option<usize>,
+        precision: Option<usize>,
+        expr: &'a ast::Expr<'a>,
+    ) {
+        let ty = self.cx.need_self_determined_type(expr.id(), self.env);
+        if ty.is_error() {
+            self.failed = true;
+            return;
+        }
+        let sbv = ty.get_simple_bit_vector();
+        let (accepted, expected) = match kind {
+            FormatKind::Int(_) | FormatKind::Char | FormatKind::Time => {
+                (sbv.is_some(), "an integral value")
+            }
+            FormatKind::String => (sbv.is_some() || ty.is_string(), "a string"),
+            FormatKind::Exp | FormatKind::Float | FormatKind::General => {
+                (sbv.is_some() || ty.is_real(), "a real number")
+            }
+        };
+        if !accepted {
+            self.cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` cannot be printed as {}",
+                    expr.span.extract(),
+                    expected
+                ))
+                .code("SV0028")
+                .span(expr.span)
+                .add_note(format!("`{}` has type `{}`", expr.span.extract(), ty)),
+            );
+            self.failed = true;
+            return;
+        }
+
+        // Determine the default field width from the type of the value.
+        let width = width.unwrap_or_else(|| match (kind, sbv) {
+            (FormatKind::Int(radix), Some(sbv)) => default_int_width(radix, &sbv),
+            (FormatKind::Time, _) => 20,
+            _ => 0,
+        });
+        let spec = FormatSpec {
+            kind,
+            width,
+            precision: precision.unwrap_or(6),
+        };
+        self.pieces.push(FormatPiece::Value(spec, expr));
+    }
+
+    /// Add literal text to the message.
+    fn push_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(FormatPiece::Text(prev)) = self.pieces.last_mut() {
+            prev.push_str(text);
+        } else {
+            self.pieces.push(FormatPiece::Text(text.to_string()));
+        }
+    }
+}
+
+/// Determine the number of characters needed to print any value of a type in
+/// a radix.
+fn default_int_width(radix: Radix, sbv: &ty::SbvType) -> usize {
+    match radix.digit_bits() {
+        Some(bits) => (sbv.size + bits - 1) / bits,
+        None if sbv.is_signed() => {
+            (BigInt::one() << sbv.size.saturating_sub(1))
+                .to_string()
+                .len()
+                + 1
+        }
+        None => ((BigInt::one() << sbv.size) - BigInt::one())
+            .to_string()
+            .len(),
+    }
+}
+
+/// Format a constant value according to a specifier.
+///
+/// Returns `None` if the value cannot be formatted.
+pub fn format_value(spec: FormatSpec, value: &Value) -> Option<String> {
+    let text = match (spec.kind, &value.kind) {
+        (FormatKind::Int(radix), ValueKind::Int(v, special, x)) => {
+            let sbv = value.ty.get_simple_bit_vector()?;
+            return Some(format_int(radix, spec.width, &sbv, v, special, x));
+        }
+        (FormatKind::Char, ValueKind::Int(v, ..)) => {
+            let byte = v.mod_floor(&BigInt::from(256)).to_u8()?;
+            (byte as char).to_string()
+        }
+        (FormatKind::String, ValueKind::String(bytes)) => {
+            bytes.iter().map(|&b| b as char).collect()
+        }
+        (FormatKind::String, ValueKind::Int(v, ..)) => {
+            let size = value.ty.get_bit_size()?;
+            let mut v = v.mod_floor(&(BigInt::one() << size));
+            let mut bytes = vec![];
+            while !v.is_zero() {
+                let byte = (&v & BigInt::from(0xFF)).to_u8().unwrap();
+                if byte != 0 {
+                    bytes.push(byte as char);
+                }
+                v >>= 8;
+            }
+            bytes.iter().rev().collect()
+        }
+        (FormatKind::Time, ValueKind::Int(v, ..)) => v.to_string(),
+        (FormatKind::Time, ValueKind::Time(v)) => v.to_integer().to_string(),
+        (kind, ValueKind::Real(v)) if kind.is_real() => format_real(kind, spec.precision, v.0),
+        (kind, ValueKind::Int(v, ..)) if kind.is_real() => {
+            format_real(kind, spec.precision, v.to_f64()?)
+        }
+        _ => return None,
+    };
+    Some(format!("{:>1$}", text, spec.width))
+}
+
+/// Format an integer in a radix, padded to a minimum width.
+fn format_int(
+    radix: Radix,
+    width: usize,
+    sbv: &ty::SbvType,
+    value: &BigInt,
+    special: &bit_vec::BitVec,
+    x: &bit_vec::BitVec,
+) -> String {
+    let size = sbv.size;
+    let bits = value.mod_floor(&(BigInt::one() << size));
+    let is_special = |i: usize| special.get(i).unwrap_or(false);
+    let is_x = |i: usize| x.get(i).unwrap_or(false);
+
+    // Describe a group of bits that contains x or z bits. The digit is
+    // lowercase if all bits of the group are x or z, and uppercase otherwise.
+    let special_digit = |bits: std::ops::Range<usize>| {
+        let num_special = bits.clone().filter(|&i| is_special(i)).count();
+        if num_special == 0 {
+            return None;
+        }
+        let c = if bits.clone().any(|i| is_special(i) && is_x(i)) {
+            'x'
+        } else {
+            'z'
+        };
+        if num_special == bits.len() {
+            Some(c)
+        } else {
+            Some(c.to_ascii_uppercase())
+        }
+    };
+
+    match radix.digit_bits() {
+        Some(digit_bits) => {
+            let num_digits = (size + digit_bits - 1) / digit_bits;
+            let mut digits: Vec<char> = (0..num_digits)
+                .rev()
+                .map(|d| {
+                    let lo = d * digit_bits;
+                    let hi = std::cmp::min(lo + digit_bits, size);
+                    special_digit(lo..hi).unwrap_or_else(|| {
+                        let digit = (&bits >> lo).mod_floor(&BigInt::from(1 << digit_bits));
+                        std::char::from_digit(digit.to_u32().unwrap(), 16).unwrap()
+                    })
+                })
+                .collect();
+            let leading = digits.iter().take_while(|&&c| c == '0').count();
+            let leading = std::cmp::min(leading, digits.len() - 1);
+            digits.drain(..leading);
+            let digits: String = digits.into_iter().collect();
+            format!("{:0>1$}", digits, width)
+        }
+        None => {
+            let text = match special_digit(0..size) {
+                Some(c) => c.to_string(),
+                None if sbv.is_signed() && !bits.is_zero() && bits.bits() as usize == size => {
+                    (bits - (BigInt::one() << size)).to_string()
+                }
+                None => bits.to_string(),
+            };
+            format!("{:>1$}", text, width)
+        }
+    }
+}
+
+/// Format a real number in the style of C's `printf`.
+fn format_real(kind: FormatKind, precision: usize, value: f64) -> String {
+    match kind {
+        FormatKind::Exp => format_exp(precision, value),
+        FormatKind::General => {
+            // Use exponential notation for very small or large numbers,
+            // and drop trailing zeros.
+            let precision = std::cmp::max(precision, 1);
+            let exp = if value == 0.0 {
+                0
+            } else {
+                value.abs().log10().floor() as isize
+            };
+            let text = if exp < -4 || exp >= precision as isize {
+                format_exp(precision - 1, value)
+            } else {
+                format!("{:.*}", (precision as isize - 1 - exp) as usize, value)
+            };
+            let (mantissa, exponent) = match text.find('e') {
+                Some(i) => text.split_at(i),
+                None => (text.as_str(), ""),
+            };
+            let mantissa = if mantissa.contains('.') {
+                mantissa.trim_end_matches('0').trim_end_matches('.')
+            } else {
+                mantissa
+            };
+            format!("{}{}", mantissa, exponent)
+        }
+        _ => format!("{:.*}", precision, value),
+    }
+}
+
+/// Format a real number in exponential notation, with a sign and at least two
+/// digits in the exponent.
+fn format_exp(precision: usize, value: f64) -> String {
+    let text = format!("{:.*e}", precision, value);
+    let (mantissa, exponent) = text.split_at(text.find('e').unwrap());
+    let exponent: i32 = exponent[1..].parse().unwrap();
+    let sign = if exponent.is_negative() { '-' } else { '+' };
+    format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+}
@@ -0,0 +1,505 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Extraction of finite state machines from the processes of a design.
+//!
+//! This module looks for the common RTL idiom of a state register updated in an
+//! `always_ff` process, whose transitions are described by a `case` statement
+//! over the current state. Both the one-process style, where the case assigns
+//! the register directly, and the two-process style, where a separate next
+//! state variable is assigned and then registered, are detected. The extracted
+//! state machines can be reported as diagnostic notes or as JSON.
+
+use crate::{crate_prelude::*, hir::HirNode, ParamEnv};
+use num::BigInt;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{Result as IoResult, Write};
+
+/// A report of all state machines in a design.
+#[derive(Debug, Serialize)]
+pub struct FsmReport {
+    /// The extracted state machines, in the order their modules were emitted.
+    pub fsms: Vec<Fsm>,
+}
+
+/// A state machine.
+#[derive(Debug, Serialize)]
+pub struct Fsm {
+    /// The name of the module that contains the state machine.
+    pub module: Name,
+    /// The name of the entity the module was emitted as.
+    pub entity: String,
+    /// The state register.
+    pub register: Spanned<Name>,
+    /// The variable that holds the next state, if it is separate from the
+    /// state register.
+    pub next: Option<Spanned<Name>>,
+    /// The states of the machine.
+    pub states: Vec<FsmState>,
+    /// The states the register is reset to, as indices into `states`.
+    pub reset: Vec<usize>,
+    /// The transitions between the states.
+    pub transitions: Vec<FsmTransition>,
+}
+
+/// A state of a state machine.
+#[derive(Debug, Serialize)]
+pub struct FsmState {
+    /// The name of the state.
+    pub name: String,
+    /// The value of the state register in this state.
+    #[serde(serialize_with = "crate::hir::dump::ser_display")]
+    pub value: BigInt,
+    /// Whether the state can be reached from the reset state.
+    pub reachable: bool,
+}
+
+/// A transition between two states of a state machine.
+#[derive(Debug, Serialize)]
+pub struct FsmTransition {
+    /// The state the transition starts in, as an index into `states`.
+    pub from: usize,
+    /// The state the transition leads to, as an index into `states`.
+    pub to: usize,
+    /// The assignment that performs the transition.
+    pub span: Span,
+}
+
+impl FsmReport {
+    /// Extract the state machines of a list of emitted module
+    /// specializations.
+    pub fn new<'a>(
+        cx: &impl Context<'a>,
+        specializations: impl IntoIterator<Item = (NodeEnvId, String)>,
+    ) -> Result<Self> {
+        let mut fsms = vec![];
+        for (id, entity) in specializations {
+            let hir = match cx.hir_of(id.id())? {
+                HirNode::Module(x) => x,
+                _ => unreachable!(),
+            };
+            let mut extractor = Extractor {
+                cx,
+                env: id.env(),
+                module: hir.name.value,
+                entity: &entity,
+                cases: vec![],
+                assigns: vec![],
+                case_stack: vec![],
+            };
+            for &proc_id in &hir.block.procs {
+                extractor.collect_proc(proc_id)?;
+            }
+            fsms.extend(extractor.extract()?);
+        }
+        Ok(FsmReport { fsms })
+    }
+
+    /// Emit the report as diagnostic notes.
+    pub fn emit_notes<'a>(&self, cx: &impl Context<'a>) {
+        for fsm in &self.fsms {
+            let names = |indices: &mut dyn Iterator<Item = usize>| {
+                indices
+                    .map(|i| format!("`{}`", fsm.states[i].name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let mut d = DiagBuilder2::note(format!(
+                "found state machine with register `{}` in `{}`",
+                fsm.register.value, fsm.module
+            ))
+            .span(fsm.register.span)
+            .add_note(format!(
+                "states: {}",
+                names(&mut (0..fsm.states.len()).into_iter())
+            ));
+            if !fsm.reset.is_empty() {
+                d = d.add_note(format!("reset: {}", names(&mut fsm.reset.iter().cloned())));
+            }
+            for t in &fsm.transitions {
+                d = d.add_note(format!(
+                    "transition `{}` -> `{}`",
+                    fsm.states[t.from].name, fsm.states[t.to].name
+                ));
+            }
+            let mut unreachable = fsm
+                .states
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| !s.reachable)
+                .map(|(i, _)| i)
+                .peekable();
+            if unreachable.peek().is_some() {
+                d = d.add_note(format!("unreachable: {}", names(&mut unreachable)));
+            }
+            cx.emit(d);
+        }
+    }
+
+    /// Write the report as JSON.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        serde_json::to_writer_pretty(&mut *w, &self.fsms)?;
+        writeln!(w)
+    }
+}
+
+/// A `case` statement encountered in a process.
+struct CaseInfo {
+    /// The variable the statement switches on.
+    subject: NodeId,
+    /// The label expressions of each way.
+    ways: Vec<Vec<NodeId>>,
+}
+
+/// An assignment encountered in a process.
+struct AssignInfo {
+    /// The kind of process that contains the assignment.
+    proc_kind: ast::ProcedureKind,
+    /// The variable being assigned.
+    lhs: NodeId,
+    /// The assigned expression.
+    rhs: NodeId,
+    /// The span of the assignment.
+    span: Span,
+    /// The case statements and ways the assignment is nested in, as indices
+    /// into the list of cases and ways. `None` indicates the default way.
+    cases: Vec<(usize, Option<usize>)>,
+}
+
+/// A helper to collect the cases and assignments in a module and extract
+/// state machines from them.
+struct Extractor<'c, C> {
+    cx: &'c C,
+    env: ParamEnv,
+    module: Name,
+    entity: &'c str,
+    cases: Vec<CaseInfo>,
+    assigns: Vec<AssignInfo>,
+    case_stack: Vec<(usize, Option<usize>)>,
+}
+
+impl<'a, 'c, C: Context<'a>> Extractor<'c, C> {
+    fn collect_proc(&mut self, proc_id: NodeId) -> Result<()> {
+        let hir = match self.cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        self.collect_stmt(hir.stmt, hir.kind)
+    }
+
+    fn collect_stmt(&mut self, stmt_id: NodeId, proc_kind: ast::ProcedureKind) -> Result<()> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(()),
+        };
+        match hir.kind {
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.collect_stmt(stmt, proc_kind)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, .. } => {
+                if let Some(lhs) = self.resolve_var(lhs)? {
+                    self.assigns.push(AssignInfo {
+                        proc_kind,
+                        lhs,
+                        rhs,
+                        span: hir.span,
+                        cases: self.case_stack.clone(),
+                    });
+                }
+            }
+            hir::StmtKind::Timed { stmt, .. } | hir::StmtKind::Loop { body: stmt, .. } => {
+                self.collect_stmt(stmt, proc_kind)?;
+            }
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt,
+                ..
+            } => {
+                self.collect_stmt(main_stmt, proc_kind)?;
+                if let Some(else_stmt) = else_stmt {
+                    self.collect_stmt(else_stmt, proc_kind)?;
+                }
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                ..
+            } => {
+                let subject = self.resolve_var(expr)?;
+                let index = subject.map(|subject| {
+                    self.cases.push(CaseInfo {
+                        subject,
+                        ways: ways.iter().map(|(exprs, _)| exprs.clone()).collect(),
+                    });
+                    self.cases.len() - 1
+                });
+                for (i, &(_, stmt)) in ways.iter().enumerate() {
+                    if let Some(index) = index {
+                        self.case_stack.push((index, Some(i)));
+                    }
+                    let result = self.collect_stmt(stmt, proc_kind);
+                    if index.is_some() {
+                        self.case_stack.pop();
+                    }
+                    result?;
+                }
+                if let Some(default) = default {
+                    if let Some(index) = index {
+                        self.case_stack.push((index, None));
+                    }
+                    let result = self.collect_stmt(default, proc_kind);
+                    if index.is_some() {
+                        self.case_stack.pop();
+                    }
+                    result?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Resolve an expression to the variable or port it refers to, if it is a
+    /// plain reference to one.
+    fn resolve_var(&self, expr_id: NodeId) -> Result<Option<NodeId>> {
+        match self.cx.hir_of(expr_id)? {
+            HirNode::Expr(hir::Expr {
+                kind: hir::ExprKind::Ident(..),
+                ..
+            }) => (),
+            _ => return Ok(None),
+        }
+        let decl = self.cx.resolve_node(expr_id, self.env)?;
+        Ok(match self.cx.hir_of(decl)? {
+            HirNode::VarDecl(_) | HirNode::IntPort(_) => Some(decl),
+            _ => None,
+        })
+    }
+
+    /// Determine the value of an expression that names a state, if it is a
+    /// constant.
+    fn state_value(&self, expr_id: NodeId) -> Result<Option<BigInt>> {
+        let constant = match self.cx.hir_of(expr_id)? {
+            HirNode::Expr(expr) => match expr.kind {
+                hir::ExprKind::IntConst { .. } | hir::ExprKind::UnsizedConst(..) => true,
+                hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) => {
+                    let decl = self.cx.resolve_node(expr_id, self.env)?;
+                    self.cx.is_constant(decl)?
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if !constant {
+            return Ok(None);
+        }
+        let value = self.cx.constant_value_of(expr_id, self.env);
+        Ok(value.get_int().cloned())
+    }
+
+    /// Get the name and span of a variable.
+    fn var_name(&self, decl_id: NodeId) -> Result<Spanned<Name>> {
+        Ok(match self.cx.hir_of(decl_id)? {
+            HirNode::VarDecl(x) => x.name,
+            HirNode::IntPort(x) => x.name,
+            _ => unreachable!(),
+        })
+    }
+
+    /// Extract the state machines from the collected cases and assignments.
+    fn extract(&self) -> Result<Vec<Fsm>> {
+        let mut fsms = vec![];
+        let mut seen = HashSet::new();
+        for case in &self.cases {
+            if !seen.insert(case.subject) {
+                continue;
+            }
+            if let Some(fsm) = self.extract_fsm(case.subject)? {
+                fsms.push(fsm);
+            }
+        }
+        Ok(fsms)
+    }
+
+    /// Try to extract a state machine with a given state register.
+    fn extract_fsm(&self, register: NodeId) -> Result<Option<Fsm>> {
+        // The register must be assigned in a clocked process.
+        let reg_assigns: Vec<_> = self
+            .assigns
+            .iter()
+            .filter(|a| a.lhs == register && a.proc_kind == ast::ProcedureKind::AlwaysFf)
+            .collect();
+        if reg_assigns.is_empty() {
+            return Ok(None);
+        }
+        let in_case_on = |a: &AssignInfo, var: NodeId| {
+            a.cases
+                .iter()
+                .rev()
+                .find(|&&(case, _)| self.cases[case].subject == var)
+                .cloned()
+        };
+
+        // Determine the next state variable. If the register is assigned
+        // within a case over itself, it is its own next state. Otherwise look
+        // for the variable that is registered.
+        let next = if reg_assigns
+            .iter()
+            .any(|a| in_case_on(a, register).is_some())
+        {
+            register
+        } else {
+            let mut next = None;
+            for a in &reg_assigns {
+                if let Some(var) = self.resolve_var(a.rhs)? {
+                    if var != register {
+                        next = Some(var);
+                        break;
+                    }
+                }
+            }
+            match next {
+                Some(next) => next,
+                None => return Ok(None),
+            }
+        };
+
+        // Determine the states. Enums provide the full list of states, which
+        // allows unused variants to be reported as unreachable.
+        let mut states: Vec<FsmState> = vec![];
+        let mut lookup: HashMap<BigInt, usize> = HashMap::new();
+        let mut add_state = |states: &mut Vec<FsmState>, name: String, value: BigInt| {
+            *lookup.entry(value.clone()).or_insert_with(|| {
+                states.push(FsmState {
+                    name,
+                    value,
+                    reachable: false,
+                });
+                states.len() - 1
+            })
+        };
+        let ty = self.cx.type_of(register, self.env)?;
+        if let Some(enm) = ty.get_enum() {
+            for (name, ast) in &enm.variants {
+                let value = self.cx.constant_value_of(ast.id(), self.env);
+                if let Some(value) = value.get_int() {
+                    add_state(&mut states, name.value.to_string(), value.clone());
+                }
+            }
+        }
+        let mut state_of = |states: &mut Vec<FsmState>, expr_id: NodeId| -> Result<Option<usize>> {
+            Ok(self
+                .state_value(expr_id)?
+                .map(|value| add_state(states, self.cx.span(expr_id).extract(), value)))
+        };
+
+        // Determine the source states of every way of the cases over the
+        // register.
+        let mut way_states: HashMap<(usize, Option<usize>), Vec<usize>> = HashMap::new();
+        for (index, case) in self.cases.iter().enumerate() {
+            if case.subject != register {
+                continue;
+            }
+            for (i, labels) in case.ways.iter().enumerate() {
+                let mut from = vec![];
+                for &label in labels {
+                    if let Some(state) = state_of(&mut states, label)? {
+                        from.push(state);
+                    }
+                }
+                way_states.insert((index, Some(i)), from);
+            }
+        }
+
+        // Collect the reset states and transitions.
+        let mut reset = vec![];
+        let mut pending = vec![];
+        for a in &self.assigns {
+            let way = in_case_on(a, register);
+            if a.lhs == register && a.proc_kind == ast::ProcedureKind::AlwaysFf && way.is_none() {
+                if let Some(state) = state_of(&mut states, a.rhs)? {
+                    if !reset.contains(&state) {
+                        reset.push(state);
+                    }
+                }
+                continue;
+            }
+            if a.lhs != next {
+                continue;
+            }
+            let way = match way {
+                Some(way) => way,
+                None => continue,
+            };
+            let to = if self.resolve_var(a.rhs)? == Some(register) {
+                None
+            } else {
+                match state_of(&mut states, a.rhs)? {
+                    Some(to) => Some(to),
+                    None => continue,
+                }
+            };
+            pending.push((way, to, a.span));
+        }
+
+        // Resolve the source states of the transitions. The default way of a
+        // case covers all states that are not listed explicitly.
+        let mut transitions = vec![];
+        for ((case, way), to, span) in pending {
+            let from = match way {
+                Some(_) => way_states[&(case, way)].clone(),
+                None => {
+                    let listed: HashSet<_> = way_states
+                        .iter()
+                        .filter(|((c, _), _)| *c == case)
+                        .flat_map(|(_, states)| states.iter().cloned())
+                        .collect();
+                    (0..states.len()).filter(|s| !listed.contains(s)).collect()
+                }
+            };
+            for from in from {
+                let to = to.unwrap_or(from);
+                if !transitions
+                    .iter()
+                    .any(|t: &FsmTransition| t.from == from && t.to == to)
+                {
+                    transitions.push(FsmTransition { from, to, span });
+                }
+            }
+        }
+        if transitions.is_empty() {
+            return Ok(None);
+        }
+
+        // Mark the states reachable from the reset states. Without a reset,
+        // the first state is assumed to be the initial one.
+        let mut worklist = if reset.is_empty() {
+            vec![0]
+        } else {
+            reset.clone()
+        };
+        while let Some(state) = worklist.pop() {
+            if std::mem::replace(&mut states[state].reachable, true) {
+                continue;
+            }
+            worklist.extend(transitions.iter().filter(|t| t.from == state).map(|t| t.to));
+        }
+
+        Ok(Some(Fsm {
+            module: self.module,
+            entity: self.entity.to_string(),
+            register: self.var_name(register)?,
+            next: if next != register {
+                Some(self.var_name(next)?)
+            } else {
+                None
+            },
+            states,
+            reset,
+            transitions,
+        }))
+    }
+}
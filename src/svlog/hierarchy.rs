@@ -0,0 +1,158 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! The instance tree of an elaborated design.
+//!
+//! Starting at the top-level modules, the tree lists every instance together
+//! with the module it instantiates, the values of the module's parameters in
+//! that instance, and the location of the instantiation. A module instantiated
+//! several times appears once per instance, such that scripts aggregating
+//! area or power along the hierarchy can walk the tree directly.
+
+use crate::{
+    crate_prelude::*,
+    design::{Design, DesignModule},
+    hir::HirNode,
+    ParamEnv,
+};
+use serde::Serialize;
+use std::io::{Result as IoResult, Write};
+
+/// The instance tree of a design.
+#[derive(Debug, Serialize)]
+pub struct Hierarchy {
+    /// The top-level modules, in the order they were elaborated.
+    pub tops: Vec<HierarchyNode>,
+}
+
+/// A top-level module or an instance.
+#[derive(Debug, Serialize)]
+pub struct HierarchyNode {
+    /// The name of the instance, or of the module for a top-level module.
+    /// Instances in generate blocks are prefixed with the block, as in
+    /// `g[1].u0`.
+    pub name: String,
+    /// The hierarchical path of the instance, as in `top.g[1].u0`.
+    pub path: String,
+    /// The name of the instantiated module.
+    pub module: Name,
+    /// The name of the module specialization, as emitted by the code
+    /// generator.
+    pub entity: String,
+    /// The values of the module's parameters, excluding localparams.
+    pub params: Vec<HierarchyParam>,
+    /// The location of the instance, or of the module for a top-level module.
+    pub location: HierarchyLocation,
+    /// The instances in the module.
+    pub children: Vec<HierarchyNode>,
+}
+
+/// A parameter of an instance.
+#[derive(Debug, Serialize)]
+pub struct HierarchyParam {
+    /// The name of the parameter.
+    pub name: Name,
+    /// The value of a value parameter, or the type of a type parameter.
+    pub value: String,
+}
+
+/// A location in the source files.
+#[derive(Debug, Serialize)]
+pub struct HierarchyLocation {
+    /// The path of the file.
+    pub file: String,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column number.
+    pub column: usize,
+}
+
+impl Hierarchy {
+    /// Collect the instance tree below the top-level modules of a design.
+    pub fn new<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<Self> {
+        let mut tops = vec![];
+        for &index in &design.tops {
+            let module = &design.modules[index];
+            let name = module.hir.name.value.to_string();
+            tops.push(node(
+                cx,
+                design,
+                module,
+                module.id.env(),
+                name.clone(),
+                name,
+                module.hir.name.span,
+            )?);
+        }
+        Ok(Hierarchy { tops })
+    }
+
+    /// Write the instance tree as JSON.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        serde_json::to_writer_pretty(&mut *w, &self.tops)?;
+        writeln!(w)
+    }
+}
+
+/// Collect the subtree of a module specialization.
+fn node<'a>(
+    cx: &impl Context<'a>,
+    design: &Design<'a>,
+    module: &DesignModule<'a>,
+    env: ParamEnv,
+    name: String,
+    path: String,
+    span: Span,
+) -> Result<HierarchyNode> {
+    let mut params = vec![];
+    for &param_id in module.hir.params {
+        let (param_name, value) = match cx.hir_of(param_id)? {
+            HirNode::ValueParam(p) if !p.local => (
+                p.name.value,
+                cx.constant_value_of(param_id, env).kind.to_string(),
+            ),
+            HirNode::TypeParam(p) if !p.local => (
+                p.name.value,
+                cx.map_to_type_or_error(Ref(cx.ast_for_id(param_id)), env)
+                    .to_string(),
+            ),
+            _ => continue,
+        };
+        params.push(HierarchyParam {
+            name: param_name,
+            value,
+        });
+    }
+
+    let mut children = vec![];
+    for inst in &module.insts {
+        let hir = match cx.hir_of(inst.id)? {
+            HirNode::Inst(x) => x,
+            _ => unreachable!(),
+        };
+        let details = cx.inst_details(Ref(hir), inst.env)?;
+        children.push(node(
+            cx,
+            design,
+            &design.modules[inst.module],
+            details.inner_env,
+            inst.name.clone(),
+            format!("{}.{}", path, inst.name),
+            hir.name.span,
+        )?);
+    }
+
+    let (line, column, _) = span.begin().human();
+    Ok(HierarchyNode {
+        name,
+        path,
+        module: module.hir.name.value,
+        entity: module.name.clone(),
+        params,
+        location: HierarchyLocation {
+            file: span.source.get_path().to_string(),
+            line,
+            column,
+        },
+        children,
+    })
+}
@@ -0,0 +1,282 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Dumping of the HIR for consumption by other tools.
+//!
+//! This module collects all HIR nodes reachable from a root node and writes
+//! them out as a flat list keyed by node ID. Nodes refer to each other by ID,
+//! which allows tools to reconstruct the tree without linking against this
+//! crate.
+
+use crate::crate_prelude::*;
+use crate::{
+    ast_map::AstNode,
+    hir::{nodes::*, HirNode, Visitor},
+    Context,
+};
+use bit_vec::BitVec;
+use serde::{ser::SerializeMap, ser::SerializeSeq, ser::SerializeStruct, Serialize, Serializer};
+use std::{collections::HashSet, io::Write};
+
+/// Collect all HIR nodes reachable from a root node.
+///
+/// The nodes are returned in the order in which they are first encountered
+/// during a depth-first traversal of the HIR.
+pub fn collect_nodes<'a>(cx: &impl Context<'a>, root: NodeId) -> Vec<(NodeId, HirNode<'a>)> {
+    let mut collector = Collector {
+        cx,
+        seen: Default::default(),
+        nodes: vec![],
+    };
+    collector.visit_node_with_id(root, false);
+    collector.nodes
+}
+
+/// Write the HIR nodes reachable from a root node as JSON.
+pub fn write_json<'a>(
+    cx: &impl Context<'a>,
+    root: NodeId,
+    w: &mut impl Write,
+) -> std::io::Result<()> {
+    let nodes: Vec<_> = collect_nodes(cx, root)
+        .into_iter()
+        .map(|(id, node)| Entry { id, node })
+        .collect();
+    serde_json::to_writer_pretty(&mut *w, &nodes)?;
+    writeln!(w)
+}
+
+/// A visitor that records every HIR node it encounters.
+struct Collector<'a, 'c, C> {
+    cx: &'c C,
+    seen: HashSet<NodeId>,
+    nodes: Vec<(NodeId, HirNode<'a>)>,
+}
+
+impl<'a, 'c, C: Context<'a>> Visitor<'a> for Collector<'a, 'c, C> {
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_node_with_id(&mut self, node_id: NodeId, lvalue: bool) {
+        if !self.seen.insert(node_id) {
+            return;
+        }
+        let node = match self.cx.hir_of(node_id) {
+            Ok(x) => x,
+            Err(()) => return,
+        };
+        self.nodes.push((node_id, node));
+
+        // Descend into the nodes that the default visitor does not cover.
+        match node {
+            HirNode::Interface(x) => hir::walk_module_block(self, &x.block),
+            HirNode::Type(x) => self.visit_type_kind(&x.kind),
+            HirNode::TypeParam(x) => {
+                if let Some(default) = x.default {
+                    self.visit_node_with_id(default, false);
+                }
+            }
+            HirNode::ValueParam(x) => {
+                self.visit_type_with_id(x.ty);
+                if let Some(default) = x.default {
+                    self.visit_node_with_id(default, false);
+                }
+            }
+            HirNode::Gen(x) => match x.kind {
+                GenKind::If {
+                    cond,
+                    main_body,
+                    else_body,
+                } => {
+                    self.visit_node_with_id(cond, false);
+                    self.visit_node_with_id(main_body, false);
+                    if let Some(else_body) = else_body {
+                        self.visit_node_with_id(else_body, false);
+                    }
+                }
+                GenKind::For {
+                    ref init,
+                    cond,
+                    step,
+                    body,
+                } => {
+                    for &id in init {
+                        self.visit_node_with_id(id, false);
+                    }
+                    self.visit_node_with_id(cond, false);
+                    self.visit_node_with_id(step, false);
+                    self.visit_node_with_id(body, false);
+                }
+            },
+            HirNode::GenerateBlock(x) => hir::walk_module_block(self, &x.block),
+            HirNode::GenvarDecl(x) => {
+                if let Some(init) = x.init {
+                    self.visit_node_with_id(init, false);
+                }
+            }
+            HirNode::Package(x) => {
+                for &id in x.params.iter().chain(x.decls.iter()) {
+                    self.visit_node_with_id(id, false);
+                }
+            }
+            HirNode::EnumVariant(x) => {
+                if let Some(value) = x.value {
+                    self.visit_node_with_id(value, false);
+                }
+            }
+            _ => (),
+        }
+        self.visit_node(node, lvalue);
+    }
+
+    fn visit_var_decl(&mut self, decl: &'a VarDecl) {
+        self.visit_type_with_id(decl.ty);
+        if let Some(init) = decl.init {
+            self.visit_node_with_id(init, false);
+        }
+    }
+
+    fn visit_int_port(&mut self, int_port: &'a IntPort) {
+        if let Some(data) = &int_port.data {
+            if hir::lowering::is_type_lowerable(data.ty) {
+                self.visit_node_with_id(data.ty.id(), false);
+            }
+            if let Some(default) = data.default {
+                self.visit_node_with_id(default, false);
+            }
+        }
+    }
+}
+
+impl<'a, 'c, C: Context<'a>> Collector<'a, 'c, C> {
+    /// Visit a type, unless it is one that is only ever handled on the AST.
+    fn visit_type_with_id(&mut self, node_id: NodeId) {
+        if let Ok(AstNode::Type(ty)) = self.cx.ast_of(node_id) {
+            if !hir::lowering::is_type_lowerable(ty) {
+                return;
+            }
+        }
+        self.visit_node_with_id(node_id, false);
+    }
+
+    fn visit_type_kind(&mut self, kind: &TypeKind) {
+        match *kind {
+            TypeKind::Struct(ref members) => {
+                for &id in members {
+                    self.visit_node_with_id(id, false);
+                }
+            }
+            TypeKind::PackedArray(ref inner, lhs, rhs) => {
+                self.visit_type_kind(inner);
+                self.visit_node_with_id(lhs, false);
+                self.visit_node_with_id(rhs, false);
+            }
+            TypeKind::Scope(id, _) | TypeKind::RefExpr(id) | TypeKind::RefType(id) => {
+                self.visit_node_with_id(id, false);
+            }
+            TypeKind::Enum(ref variants, base) => {
+                for &(_, id) in variants {
+                    self.visit_node_with_id(id, false);
+                }
+                if let Some(base) = base {
+                    self.visit_node_with_id(base, false);
+                }
+            }
+            TypeKind::Implicit | TypeKind::Builtin(_) | TypeKind::Named(_) => (),
+        }
+    }
+}
+
+/// A node in the dump, together with its ID.
+struct Entry<'a> {
+    id: NodeId,
+    node: HirNode<'a>,
+}
+
+impl Serialize for Entry<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = s.serialize_map(Some(2))?;
+        map.serialize_entry("id", &self.id)?;
+        map.serialize_entry("node", &self.node)?;
+        map.end()
+    }
+}
+
+/// A reference to an AST node.
+///
+/// Serializes the node's ID, kind, name, and span, but not its contents.
+struct AstRef<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: ast::AnyNode<'a> + ?Sized> Serialize for AstRef<'a, T> {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut st = s.serialize_struct("AstRef", 4)?;
+        st.serialize_field("id", &self.0.id())?;
+        st.serialize_field("kind", self.0.type_name())?;
+        st.serialize_field("name", &self.0.get_name())?;
+        st.serialize_field("span", &self.0.span())?;
+        st.end()
+    }
+}
+
+/// Serialize a reference to an AST node.
+pub(crate) fn ser_ast<'a, T, S>(node: &&'a T, s: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: ast::AnyNode<'a> + ?Sized,
+    S: Serializer,
+{
+    AstRef(*node).serialize(s)
+}
+
+/// Serialize an optional reference to an AST node.
+pub(crate) fn ser_ast_opt<'a, T, S>(
+    node: &Option<&'a T>,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    T: ast::AnyNode<'a> + ?Sized,
+    S: Serializer,
+{
+    node.map(AstRef).serialize(s)
+}
+
+/// Serialize a slice of AST nodes as references.
+pub(crate) fn ser_ast_slice<'a, T, S>(nodes: &&'a [T], s: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: ast::AnyNode<'a>,
+    S: Serializer,
+{
+    let mut seq = s.serialize_seq(Some(nodes.len()))?;
+    for node in nodes.iter() {
+        seq.serialize_element(&AstRef(node))?;
+    }
+    seq.end()
+}
+
+/// Serialize a value as its `Display` string.
+pub(crate) fn ser_display<T, S>(value: &T, s: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: std::fmt::Display,
+    S: Serializer,
+{
+    s.collect_str(value)
+}
+
+/// Serialize a bit vector as a string of `0` and `1`.
+pub(crate) fn ser_bits<S: Serializer>(bits: &BitVec, s: S) -> std::result::Result<S::Ok, S::Error> {
+    let string: String = bits.iter().map(|b| if b { '1' } else { '0' }).collect();
+    s.serialize_str(&string)
+}
+
+/// Serialize a slice of type dimensions as their source text.
+pub(crate) fn ser_type_dims<S: Serializer>(
+    dims: &&[ast::TypeDim],
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let mut seq = s.serialize_seq(Some(dims.len()))?;
+    for dim in dims.iter() {
+        seq.serialize_element(dim.desc_full().trim_matches('`'))?;
+    }
+    seq.end()
+}
@@ -0,0 +1,428 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! An implementation of the folder pattern for the HIR.
+//!
+//! This module defines the [`Folder`] trait that allows the HIR tree graph to
+//! be rewritten. Folding a node yields the ID of the transformed node. Nodes
+//! whose contents do not change are returned as they are. Otherwise a copy of
+//! the node is allocated in the arena under a fresh node ID, such that the
+//! original HIR remains intact for other queries.
+
+use crate::crate_prelude::*;
+use crate::{
+    ast_map::AstNode,
+    hir::{nodes::*, HirNode},
+    Context,
+};
+
+/// A folder of the HIR.
+pub trait Folder<'a>: Sized {
+    /// The type of context that this folder uses.
+    type Context: Context<'a>;
+
+    /// Get the context to be used to resolve queries and allocate nodes.
+    fn context(&self) -> &Self::Context;
+
+    /// Fold the node with the given ID, returning the ID of the result.
+    fn fold_node_with_id(&mut self, node_id: NodeId) -> NodeId {
+        match self.context().hir_of(node_id) {
+            Ok(HirNode::Proc(x)) => self.fold_proc(x),
+            Ok(HirNode::Stmt(x)) => self.fold_stmt(x),
+            Ok(HirNode::Expr(x)) => self.fold_expr(x),
+            Ok(HirNode::EventExpr(x)) => self.fold_event_expr(x),
+            Ok(HirNode::VarDecl(x)) => self.fold_var_decl(x),
+            Ok(HirNode::Assign(x)) => self.fold_assign(x),
+            _ => node_id,
+        }
+    }
+
+    fn fold_module_block(&mut self, blk: &ModuleBlock) -> ModuleBlock {
+        walk_module_block(self, blk)
+    }
+
+    fn fold_proc(&mut self, prok: &'a Proc) -> NodeId {
+        walk_proc(self, prok)
+    }
+
+    fn fold_stmt(&mut self, stmt: &'a Stmt<'a>) -> NodeId {
+        walk_stmt(self, stmt)
+    }
+
+    fn fold_expr(&mut self, expr: &'a Expr<'a>) -> NodeId {
+        walk_expr(self, expr)
+    }
+
+    fn fold_timing_control(&mut self, ctrl: &TimingControl) -> TimingControl {
+        walk_timing_control(self, ctrl)
+    }
+
+    fn fold_event_expr(&mut self, expr: &'a EventExpr) -> NodeId {
+        walk_event_expr(self, expr)
+    }
+
+    fn fold_var_decl(&mut self, decl: &'a VarDecl) -> NodeId {
+        walk_var_decl(self, decl)
+    }
+
+    fn fold_assign(&mut self, assign: &'a Assign) -> NodeId {
+        walk_assign(self, assign)
+    }
+}
+
+/// Fold the contents of a module block.
+pub fn walk_module_block<'a>(folder: &mut impl Folder<'a>, blk: &ModuleBlock) -> ModuleBlock {
+    let mut fold_all = |ids: &[NodeId]| -> Vec<NodeId> {
+        ids.iter().map(|&id| folder.fold_node_with_id(id)).collect()
+    };
+    ModuleBlock {
+        insts: fold_all(&blk.insts),
+        decls: fold_all(&blk.decls),
+        procs: fold_all(&blk.procs),
+        gens: fold_all(&blk.gens),
+        params: fold_all(&blk.params),
+        assigns: fold_all(&blk.assigns),
+        last_rib: blk.last_rib,
+    }
+}
+
+/// Fold the contents of a procedure.
+pub fn walk_proc<'a>(folder: &mut impl Folder<'a>, prok: &'a Proc) -> NodeId {
+    let stmt = folder.fold_node_with_id(prok.stmt);
+    if stmt == prok.stmt {
+        return prok.id;
+    }
+    let cx = folder.context();
+    let id = alloc_id_like(cx, prok.id, prok.span);
+    let hir = cx.arena().alloc_hir(Proc {
+        id,
+        span: prok.span,
+        kind: prok.kind,
+        stmt,
+    });
+    intern_like(cx, id, HirNode::Proc(hir), prok.id);
+    id
+}
+
+/// Fold the contents of a statement.
+pub fn walk_stmt<'a>(folder: &mut impl Folder<'a>, stmt: &'a Stmt<'a>) -> NodeId {
+    let kind = match stmt.kind {
+        StmtKind::Null => StmtKind::Null,
+        StmtKind::Block(ref stmts) => StmtKind::Block(fold_ids(folder, stmts)),
+        StmtKind::Assign { lhs, rhs, kind } => StmtKind::Assign {
+            lhs: folder.fold_node_with_id(lhs),
+            rhs: folder.fold_node_with_id(rhs),
+            kind: match kind {
+                AssignKind::NonblockDelay(delay) => {
+                    AssignKind::NonblockDelay(folder.fold_node_with_id(delay))
+                }
+                kind => kind,
+            },
+        },
+        StmtKind::Timed { ref control, stmt } => StmtKind::Timed {
+            control: folder.fold_timing_control(control),
+            stmt: folder.fold_node_with_id(stmt),
+        },
+        StmtKind::Expr(expr) => StmtKind::Expr(folder.fold_node_with_id(expr)),
+        StmtKind::If {
+            cond,
+            main_stmt,
+            else_stmt,
+        } => StmtKind::If {
+            cond: folder.fold_node_with_id(cond),
+            main_stmt: folder.fold_node_with_id(main_stmt),
+            else_stmt: else_stmt.map(|id| folder.fold_node_with_id(id)),
+        },
+        StmtKind::Loop { kind, body } => StmtKind::Loop {
+            kind: match kind {
+                LoopKind::Forever => LoopKind::Forever,
+                LoopKind::Repeat(id) => LoopKind::Repeat(folder.fold_node_with_id(id)),
+                LoopKind::While(id) => LoopKind::While(folder.fold_node_with_id(id)),
+                LoopKind::Do(id) => LoopKind::Do(folder.fold_node_with_id(id)),
+                LoopKind::For(init, cond, step) => LoopKind::For(
+                    folder.fold_node_with_id(init),
+                    folder.fold_node_with_id(cond),
+                    folder.fold_node_with_id(step),
+                ),
+            },
+            body: folder.fold_node_with_id(body),
+        },
+        StmtKind::InlineGroup { ref stmts, rib } => StmtKind::InlineGroup {
+            stmts: fold_ids(folder, stmts),
+            rib,
+        },
+        StmtKind::Case {
+            expr,
+            ref ways,
+            default,
+            kind,
+        } => StmtKind::Case {
+            expr: folder.fold_node_with_id(expr),
+            ways: ways
+                .iter()
+                .map(|(exprs, stmt)| (fold_ids(folder, exprs), folder.fold_node_with_id(*stmt)))
+                .collect(),
+            default: default.map(|id| folder.fold_node_with_id(id)),
+            kind,
+        },
+        StmtKind::Ast(ast) => StmtKind::Ast(ast),
+    };
+    if kind == stmt.kind {
+        return stmt.id;
+    }
+    alloc_stmt(folder.context(), stmt, kind)
+}
+
+/// Fold the contents of an expression.
+pub fn walk_expr<'a>(folder: &mut impl Folder<'a>, expr: &'a Expr<'a>) -> NodeId {
+    let kind = match expr.kind {
+        ExprKind::Unary(op, arg) => ExprKind::Unary(op, folder.fold_node_with_id(arg)),
+        ExprKind::Binary(op, lhs, rhs) => ExprKind::Binary(
+            op,
+            folder.fold_node_with_id(lhs),
+            folder.fold_node_with_id(rhs),
+        ),
+        ExprKind::Field(target, name) => ExprKind::Field(folder.fold_node_with_id(target), name),
+        ExprKind::Index(target, mode) => ExprKind::Index(
+            folder.fold_node_with_id(target),
+            match mode {
+                IndexMode::One(index) => IndexMode::One(folder.fold_node_with_id(index)),
+                IndexMode::Many(mode, lhs, rhs) => IndexMode::Many(
+                    mode,
+                    folder.fold_node_with_id(lhs),
+                    folder.fold_node_with_id(rhs),
+                ),
+            },
+        ),
+        ExprKind::Builtin(call) => ExprKind::Builtin(match call {
+            BuiltinCall::Clog2(arg) => BuiltinCall::Clog2(folder.fold_node_with_id(arg)),
+            BuiltinCall::Signed(arg) => BuiltinCall::Signed(folder.fold_node_with_id(arg)),
+            BuiltinCall::Unsigned(arg) => BuiltinCall::Unsigned(folder.fold_node_with_id(arg)),
+            BuiltinCall::CountOnes(arg) => BuiltinCall::CountOnes(folder.fold_node_with_id(arg)),
+            BuiltinCall::OneHot(arg) => BuiltinCall::OneHot(folder.fold_node_with_id(arg)),
+            BuiltinCall::OneHot0(arg) => BuiltinCall::OneHot0(folder.fold_node_with_id(arg)),
+            BuiltinCall::Rtoi(arg) => BuiltinCall::Rtoi(folder.fold_node_with_id(arg)),
+            BuiltinCall::Itor(arg) => BuiltinCall::Itor(folder.fold_node_with_id(arg)),
+            BuiltinCall::RealToBits(arg) => BuiltinCall::RealToBits(folder.fold_node_with_id(arg)),
+            BuiltinCall::BitsToReal(arg) => BuiltinCall::BitsToReal(folder.fold_node_with_id(arg)),
+            BuiltinCall::ShortRealToBits(arg) => {
+                BuiltinCall::ShortRealToBits(folder.fold_node_with_id(arg))
+            }
+            BuiltinCall::BitsToShortReal(arg) => {
+                BuiltinCall::BitsToShortReal(folder.fold_node_with_id(arg))
+            }
+            BuiltinCall::ReadMem(radix, file, mem, start, end) => BuiltinCall::ReadMem(
+                radix,
+                folder.fold_node_with_id(file),
+                folder.fold_node_with_id(mem),
+                start.map(|id| folder.fold_node_with_id(id)),
+                end.map(|id| folder.fold_node_with_id(id)),
+            ),
+            // The remaining builtins refer to AST nodes directly.
+            call => call,
+        }),
+        ExprKind::Ternary(cond, true_expr, false_expr) => ExprKind::Ternary(
+            folder.fold_node_with_id(cond),
+            folder.fold_node_with_id(true_expr),
+            folder.fold_node_with_id(false_expr),
+        ),
+        ExprKind::Scope(target, name) => ExprKind::Scope(folder.fold_node_with_id(target), name),
+        ExprKind::PositionalPattern(ref exprs) => {
+            ExprKind::PositionalPattern(fold_ids(folder, exprs))
+        }
+        ExprKind::NamedPattern(ref mappings) => ExprKind::NamedPattern(
+            mappings
+                .iter()
+                .map(|&(key, value)| {
+                    let key = match key {
+                        PatternMapping::Type(ty) => PatternMapping::Type(ty),
+                        PatternMapping::Member(expr) => {
+                            PatternMapping::Member(folder.fold_node_with_id(expr))
+                        }
+                        PatternMapping::Default => PatternMapping::Default,
+                    };
+                    (key, folder.fold_node_with_id(value))
+                })
+                .collect(),
+        ),
+        ExprKind::RepeatPattern(count, ref exprs) => {
+            ExprKind::RepeatPattern(folder.fold_node_with_id(count), fold_ids(folder, exprs))
+        }
+        ExprKind::Concat(repeat, ref exprs) => ExprKind::Concat(
+            repeat.map(|id| folder.fold_node_with_id(id)),
+            fold_ids(folder, exprs),
+        ),
+        ExprKind::Cast(ty, expr) => ExprKind::Cast(ty, folder.fold_node_with_id(expr)),
+        ExprKind::CastSign(sign, expr) => ExprKind::CastSign(sign, folder.fold_node_with_id(expr)),
+        ExprKind::CastSize(size, expr) => ExprKind::CastSize(
+            folder.fold_node_with_id(size),
+            folder.fold_node_with_id(expr),
+        ),
+        ExprKind::Inside(expr, ref ranges) => ExprKind::Inside(
+            folder.fold_node_with_id(expr),
+            ranges
+                .iter()
+                .map(|r| {
+                    r.map(|r| match r {
+                        InsideRange::Single(id) => {
+                            InsideRange::Single(folder.fold_node_with_id(id))
+                        }
+                        InsideRange::Range(lo, hi) => InsideRange::Range(
+                            folder.fold_node_with_id(lo),
+                            folder.fold_node_with_id(hi),
+                        ),
+                    })
+                })
+                .collect(),
+        ),
+        // Leaves and expressions that refer to AST nodes directly.
+        ref kind => kind.clone(),
+    };
+    if kind == expr.kind {
+        return expr.id();
+    }
+    alloc_expr(folder.context(), expr, kind)
+}
+
+/// Fold the contents of a timing control block.
+pub fn walk_timing_control<'a>(
+    folder: &mut impl Folder<'a>,
+    ctrl: &TimingControl,
+) -> TimingControl {
+    match *ctrl {
+        TimingControl::Delay(id) => TimingControl::Delay(folder.fold_node_with_id(id)),
+        TimingControl::ImplicitEvent => TimingControl::ImplicitEvent,
+        TimingControl::ExplicitEvent(id) => {
+            TimingControl::ExplicitEvent(folder.fold_node_with_id(id))
+        }
+    }
+}
+
+/// Fold the contents of an event expression.
+pub fn walk_event_expr<'a>(folder: &mut impl Folder<'a>, expr: &'a EventExpr) -> NodeId {
+    let events: Vec<_> = expr
+        .events
+        .iter()
+        .map(|event| Event {
+            span: event.span,
+            edge: event.edge,
+            expr: folder.fold_node_with_id(event.expr),
+            iff: fold_ids(folder, &event.iff),
+        })
+        .collect();
+    if events == expr.events {
+        return expr.id;
+    }
+    let cx = folder.context();
+    let id = alloc_id_like(cx, expr.id, expr.span);
+    let hir = cx.arena().alloc_hir(EventExpr {
+        id,
+        span: expr.span,
+        events,
+    });
+    intern_like(cx, id, HirNode::EventExpr(hir), expr.id);
+    id
+}
+
+/// Fold the contents of a variable declaration.
+pub fn walk_var_decl<'a>(folder: &mut impl Folder<'a>, decl: &'a VarDecl) -> NodeId {
+    let init = decl.init.map(|id| folder.fold_node_with_id(id));
+    if init == decl.init {
+        return decl.id;
+    }
+    let cx = folder.context();
+    let id = alloc_id_like(cx, decl.id, decl.span);
+    let hir = cx.arena().alloc_hir(VarDecl {
+        id,
+        name: decl.name,
+        span: decl.span,
+        ty: decl.ty,
+        init,
+        kind: decl.kind,
+    });
+    intern_like(cx, id, HirNode::VarDecl(hir), decl.id);
+    id
+}
+
+/// Fold the contents of a continuous assignment.
+pub fn walk_assign<'a>(folder: &mut impl Folder<'a>, assign: &'a Assign) -> NodeId {
+    let lhs = folder.fold_node_with_id(assign.lhs);
+    let rhs = folder.fold_node_with_id(assign.rhs);
+    if lhs == assign.lhs && rhs == assign.rhs {
+        return assign.id;
+    }
+    let cx = folder.context();
+    let id = alloc_id_like(cx, assign.id, assign.span);
+    let hir = cx.arena().alloc_hir(Assign {
+        id,
+        span: assign.span,
+        lhs,
+        rhs,
+    });
+    intern_like(cx, id, HirNode::Assign(hir), assign.id);
+    id
+}
+
+/// Allocate a copy of an expression with different contents.
+///
+/// The copy is backed by a fresh AST node that is attached to the original
+/// one, such that name resolution and diagnostics behave as for the original.
+pub fn alloc_expr<'a>(cx: &impl Context<'a>, like: &'a Expr<'a>, kind: ExprKind<'a>) -> NodeId {
+    let ast = cx
+        .arena()
+        .alloc_ast_expr(ast::Expr::new(like.ast.span, like.ast.data.clone()));
+    ast.link_attach(like.ast, like.ast.order());
+    let id = ast.id();
+    cx.set_span(id, like.ast.human_span());
+    cx.set_ast(id, AstNode::Expr(ast));
+    let hir = cx.arena().alloc_hir(Expr { ast, kind });
+    intern_like(cx, id, HirNode::Expr(hir), like.id());
+    id
+}
+
+/// Allocate a copy of a statement with different contents.
+pub fn alloc_stmt<'a>(cx: &impl Context<'a>, like: &'a Stmt<'a>, kind: StmtKind<'a>) -> NodeId {
+    let id = alloc_id_like(cx, like.id, like.span);
+    let hir = cx.arena().alloc_hir(Stmt {
+        id,
+        label: like.label,
+        span: like.span,
+        kind,
+    });
+    intern_like(cx, id, HirNode::Stmt(hir), like.id);
+    id
+}
+
+/// Allocate a new event expression as the child of another node.
+pub fn alloc_event_expr<'a>(
+    cx: &impl Context<'a>,
+    parent: NodeId,
+    span: Span,
+    events: Vec<Event>,
+) -> NodeId {
+    let id = cx.alloc_id(span);
+    let hir = cx.arena().alloc_hir(EventExpr { id, span, events });
+    cx.intern_hir_with_parent(id, HirNode::EventExpr(hir), parent);
+    id
+}
+
+/// Fold a list of nodes.
+fn fold_ids<'a>(folder: &mut impl Folder<'a>, ids: &[NodeId]) -> Vec<NodeId> {
+    ids.iter().map(|&id| folder.fold_node_with_id(id)).collect()
+}
+
+/// Allocate a node ID that refers to the same AST node as another ID.
+fn alloc_id_like<'a>(cx: &impl Context<'a>, like: NodeId, span: Span) -> NodeId {
+    let id = cx.alloc_id(span);
+    if let Ok(ast) = cx.ast_of(like) {
+        cx.set_ast(id, ast);
+    }
+    id
+}
+
+/// Internalize a new HIR node at the same position in the tree as another.
+fn intern_like<'a>(cx: &impl Context<'a>, id: NodeId, hir: HirNode<'a>, like: NodeId) {
+    match cx.parent_node_id(like) {
+        Some(parent) => cx.intern_hir_with_parent(id, hir, parent),
+        None => cx.intern_hir(id, hir),
+    }
+}
@@ -3,7 +3,12 @@
 //! Lowering of AST nodes to HIR nodes.
 
 use crate::crate_prelude::*;
-use crate::{ast_map::AstNode, hir::HirNode, resolver::DefNode};
+use crate::{
+    ast_map::AstNode,
+    format::{DisplayTask, Radix},
+    hir::HirNode,
+    resolver::DefNode,
+};
 use bit_vec::BitVec;
 use num::BigInt;
 
@@ -51,6 +56,7 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
                     if !is_pos {
                         cx.emit(
                             DiagBuilder2::warning("positional parameters must appear before named")
+                                .group("positional")
                                 .span(param.span)
                                 .add_note(format!(
                                     "assuming this refers to argument #{}",
@@ -98,6 +104,7 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
                         if !is_pos {
                             cx.emit(
                                 DiagBuilder2::warning("positional port must appear before named")
+                                    .group("positional")
                                     .span(port.span)
                                     .add_note(format!(
                                         "assuming this refers to argument #{}",
@@ -200,17 +207,11 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
         }
         AstNode::GenIf(gen) => {
             let cond = cx.map_ast_with_parent(AstNode::Expr(&gen.cond), node_id);
-            let main_body = lower_module_block(cx, node_id, &gen.main_block.items, false, false)?;
-            let else_body = match gen.else_block {
-                Some(ref else_block) => Some(lower_module_block(
-                    cx,
-                    node_id,
-                    &else_block.items,
-                    false,
-                    false,
-                )?),
-                None => None,
-            };
+            let main_body = cx.map_ast_with_parent(AstNode::GenBlock(&gen.main_block), node_id);
+            let else_body = gen
+                .else_block
+                .as_ref()
+                .map(|else_block| cx.map_ast_with_parent(AstNode::GenBlock(else_block), node_id));
             let hir = hir::Gen {
                 id: node_id,
                 span: gen.span(),
@@ -227,7 +228,7 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
             let rib = *init.last().unwrap();
             let cond = cx.map_ast_with_parent(AstNode::Expr(&gen.cond), rib);
             let step = cx.map_ast_with_parent(AstNode::Expr(&gen.step), rib);
-            let body = lower_module_block(cx, rib, &gen.block.items, false, false)?;
+            let body = cx.map_ast_with_parent(AstNode::GenBlock(&gen.block), rib);
             let hir = hir::Gen {
                 id: node_id,
                 span: gen.span(),
@@ -240,6 +241,14 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
             };
             Ok(HirNode::Gen(cx.arena().alloc_hir(hir)))
         }
+        AstNode::GenBlock(block) => {
+            let hir = hir::GenerateBlock {
+                id: node_id,
+                ast: block,
+                block: lower_module_block(cx, node_id, &block.items, false, false)?,
+            };
+            Ok(HirNode::GenerateBlock(cx.arena().alloc_hir(hir)))
+        }
         AstNode::GenvarDecl(decl) => {
             let hir = hir::GenvarDecl {
                 id: node_id,
@@ -348,8 +357,17 @@ pub(crate) fn hir_of_module<'a>(
     let ports_new = cx.canonicalize_ports(ast);
     next_rib = ports_new.tail_rib;
 
+    // Allocate the implicit nets.
+    let mut implicit_nets = Vec::new();
+    for net in cx.implicit_nets(ast) {
+        if let Some(decl) = net.decl {
+            next_rib = alloc_net_decl(cx, decl, next_rib, &mut implicit_nets);
+        }
+    }
+
     // Lower the module body.
-    let block = lower_module_block(cx, next_rib, &ast.items, true, false)?;
+    let mut block = lower_module_block(cx, next_rib, &ast.items, true, false)?;
+    block.decls.splice(0..0, implicit_nets);
 
     // Create the HIR module.
     let hir = hir::Module {
@@ -384,8 +402,23 @@ pub(crate) fn hir_of_interface<'a>(
     // Lower the interface body.
     let block = lower_module_block(cx, ast.id(), &ast.items, true, true)?;
 
+    // Lower the modports.
+    let mut modports = vec![];
+    for item in &ast.items {
+        if let ast::ItemData::ModportDecl(ref decl) = item.data {
+            for &name in &decl.names {
+                modports.push(lower_modport(cx, name, ast.id()));
+            }
+        }
+    }
+
     // Create the HIR node.
-    let hir = hir::Interface { ast, ports, block };
+    let hir = hir::Interface {
+        ast,
+        ports,
+        block,
+        modports,
+    };
     let hir = cx.arena().alloc_hir(hir);
 
     // Internalize the ports.
@@ -399,6 +432,30 @@ pub(crate) fn hir_of_interface<'a>(
     Ok(hir)
 }
 
+fn lower_modport<'a>(
+    cx: &impl Context<'a>,
+    ast: &'a ast::ModportName<'a>,
+    intf: NodeId,
+) -> &'a hir::Modport<'a> {
+    let mut ports = vec![];
+    for port in &ast.ports {
+        match port.data {
+            ast::ModportPortData::Simple { dir, ref port } => {
+                // Ports with an explicit expression (`.a(expr)`) are not
+                // supported yet and are ignored.
+                for port in port.iter().filter(|port| port.expr.is_none()) {
+                    ports.push(hir::ModportPort {
+                        name: port.name,
+                        dir: dir.value,
+                        span: port.span,
+                    });
+                }
+            }
+        }
+    }
+    cx.arena().alloc_hir(hir::Modport { ast, intf, ports })
+}
+
 fn lower_module_block<'gcx>(
     cx: &impl Context<'gcx>,
     parent_rib: NodeId,
@@ -445,6 +502,7 @@ fn lower_module_block_into<'gcx>(
                 // into.procs.push(id);
                 cx.emit(
                     DiagBuilder2::warning("unsupported: interface declaration; ignored")
+                        .group("unsupported")
                         .span(decl.span),
                 );
             }
@@ -452,9 +510,10 @@ fn lower_module_block_into<'gcx>(
                 // let id = cx.map_ast_with_parent(AstNode::Program(decl), into.last_rib);
                 // into.last_rib = id;
                 // into.procs.push(id);
-                cx.emit(DiagBuilder2::warning(
-                    "unsupported: program declaration; ignored",
-                ));
+                cx.emit(
+                    DiagBuilder2::warning("unsupported: program declaration; ignored")
+                        .group("unsupported"),
+                );
             }
             ast::ItemData::Inst(ref inst) => {
                 let target_id = cx.map_ast_with_parent(AstNode::InstTarget(inst), into.last_rib);
@@ -494,7 +553,8 @@ fn lower_module_block_into<'gcx>(
                 into.last_rib = id;
                 into.gens.push(id);
             }
-            ast::ItemData::GenerateRegion(_span, ref items) => {
+            ast::ItemData::GenerateRegion(_span, ref items)
+            | ast::ItemData::SpecifyBlock(_span, ref items) => {
                 lower_module_block_into(cx, items, allow_ports, allow_modports, into)?;
             }
             ast::ItemData::ParamDecl(ref param) => {
@@ -522,6 +582,7 @@ fn lower_module_block_into<'gcx>(
                 if !allow_ports {
                     cx.emit(
                         DiagBuilder2::error("misplaced port declaration")
+                            .code("SV0024")
                             .span(decl.span)
                             .add_note(
                                 "Port declarations can only appear directly in a module body",
@@ -533,6 +594,7 @@ fn lower_module_block_into<'gcx>(
             ast::ItemData::ModportDecl(ref decl) => {
                 cx.emit(
                     DiagBuilder2::error("modport declaration in module")
+                        .code("SV0024")
                         .span(decl.span)
                         .add_note("Modport declarations can only appear in an interface"),
                 );
@@ -540,6 +602,7 @@ fn lower_module_block_into<'gcx>(
             ast::ItemData::ClassDecl(ref decl) => {
                 cx.emit(
                     DiagBuilder2::warning("unsupported: class declaration; ignored")
+                        .group("unsupported")
                         .span(decl.span),
                 );
             }
@@ -550,6 +613,7 @@ fn lower_module_block_into<'gcx>(
             ast::ItemData::Assertion(ref assert) => {
                 cx.emit(
                     DiagBuilder2::warning("unsupported: concurrent assertion; ignored")
+                        .group("unsupported")
                         .span(assert.span),
                 );
             }
@@ -563,6 +627,28 @@ fn lower_module_block_into<'gcx>(
     Ok(())
 }
 
+/// Check whether a type has an HIR representation.
+///
+/// Some types are only handled directly on the AST, and lowering them to HIR
+/// is a compiler bug.
+pub(crate) fn is_type_lowerable(ty: &ast::Type) -> bool {
+    match ty.kind.data {
+        ast::ChandleType
+        | ast::VirtIntfType(..)
+        | ast::EventType
+        | ast::MailboxType
+        | ast::ImplicitSignedType
+        | ast::ImplicitUnsignedType
+        | ast::ShortRealType
+        | ast::RealType
+        | ast::RealtimeType
+        | ast::SpecializedType(..)
+        | ast::ForwardType { .. }
+        | ast::ScopedType { member: true, .. } => false,
+        _ => true,
+    }
+}
+
 fn lower_type<'gcx>(
     cx: &impl Context<'gcx>,
     node_id: NodeId,
@@ -689,6 +775,7 @@ fn lower_type<'gcx>(
                         "{} is not a valid packed dimension",
                         dim.desc_full()
                     ))
+                    .code("SV0035")
                     .span(ty.human_span())
                     .add_note("packed array dimensions can only be given as range, e.g. `[31:0]`"),
                 );
@@ -733,12 +820,38 @@ fn lower_expr_inner<'gcx>(
             Err(e) => {
                 cx.emit(
                     DiagBuilder2::error(format!("`{}` is not a valid integer literal", v))
+                        .code("SV0023")
                         .span(expr.span)
                         .add_note(format!("{}", e)),
                 );
                 return Err(());
             }
         },
+        ast::LiteralExpr(Lit::Number(int, Some(frac))) => {
+            hir::ExprKind::RealConst(parse_fixed_point_number(cx, expr.span, int, Some(frac))?)
+        }
+        ast::LiteralExpr(Lit::Real(int, frac, exp)) => {
+            let value = parse_fixed_point_number(cx, expr.span, int, frac)?;
+            let exp: i32 = match exp.as_str().parse() {
+                Ok(x) => x,
+                Err(e) => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("`{}` is not a valid exponent", exp))
+                            .code("SV0023")
+                            .span(expr.span)
+                            .add_note(format!("{}", e)),
+                    );
+                    return Err(());
+                }
+            };
+            let scale =
+                num::BigRational::from_integer(num::pow(BigInt::from(10), exp.abs() as usize));
+            hir::ExprKind::RealConst(if exp < 0 {
+                value / scale
+            } else {
+                value * scale
+            })
+        }
         ast::LiteralExpr(Lit::UnbasedUnsized(c)) => hir::ExprKind::UnsizedConst(c),
 
         ast::LiteralExpr(Lit::BasedInteger(maybe_size, signed, base, value)) => {
@@ -762,6 +875,7 @@ fn lower_expr_inner<'gcx>(
                     _ => {
                         cx.emit(
                             DiagBuilder2::error(format!("`{}` is not a valid integer base", base))
+                                .code("SV0023")
                                 .span(expr.span)
                                 .add_note("valid bases are `b`, `o`, `d`, and `h`"),
                         );
@@ -774,6 +888,7 @@ fn lower_expr_inner<'gcx>(
                 None => {
                     cx.emit(
                         DiagBuilder2::error(format!("`{}` is not a valid integer literal", value))
+                            .code("SV0023")
                             .span(expr.span),
                     );
                     return Err(());
@@ -788,6 +903,7 @@ fn lower_expr_inner<'gcx>(
                     Err(e) => {
                         cx.emit(
                             DiagBuilder2::error(format!("`{}` is not a valid integer size", size))
+                                .code("SV0023")
                                 .span(expr.span)
                                 .add_note(format!("{}", e)),
                         );
@@ -799,6 +915,7 @@ fn lower_expr_inner<'gcx>(
             if size_needed > size {
                 cx.emit(
                     DiagBuilder2::warning(format!("`{}` is too large", value,))
+                        .group("width")
                         .span(expr.span)
                         .add_note(format!(
                             "constant is {} bits wide, but the value `{}{}` needs {} bits to not \
@@ -896,6 +1013,7 @@ fn lower_expr_inner<'gcx>(
                                 false => "prefix",
                             }
                         ))
+                        .code("SV0016")
                         .span(expr.span()),
                     );
                     return Err(());
@@ -943,6 +1061,7 @@ fn lower_expr_inner<'gcx>(
                 _ => {
                     cx.emit(
                         DiagBuilder2::error(format!("`{}` is not a valid binary operator", op,))
+                            .code("SV0016")
                             .span(expr.span()),
                     );
                     error!("Offending operator is {:?}", op);
@@ -952,6 +1071,11 @@ fn lower_expr_inner<'gcx>(
             cx.map_ast_with_parent(AstNode::Expr(lhs), node_id),
             cx.map_ast_with_parent(AstNode::Expr(rhs), node_id),
         ),
+        // Accesses into a named generate block are scoped names, e.g. the
+        // `g.x` in `if (...) begin : g int x; end`.
+        ast::MemberExpr { ref expr, name } if resolve_generate_block(cx, expr).is_some() => {
+            hir::ExprKind::Scope(cx.map_ast_with_parent(AstNode::Expr(expr), node_id), name)
+        }
         ast::MemberExpr { ref expr, name } => {
             hir::ExprKind::Field(cx.map_ast_with_parent(AstNode::Expr(expr), node_id), name)
         }
@@ -976,6 +1100,8 @@ fn lower_expr_inner<'gcx>(
             hir::ExprKind::Index(indexee, mode)
         }
         ast::CallExpr(ref callee, ref args) => lower_call(cx, expr, callee, args)?,
+        // System tasks may be called without parentheses, as in `$display;`.
+        ast::SysIdentExpr(_) => lower_call(cx, expr, expr, &[])?,
         ast::TernaryExpr {
             ref cond,
             ref true_expr,
@@ -990,7 +1116,11 @@ fn lower_expr_inner<'gcx>(
             name,
         ),
         ast::PatternExpr(ref fields) if fields.is_empty() => {
-            cx.emit(DiagBuilder2::error("pattern must have at least one field").span(expr.span()));
+            cx.emit(
+                DiagBuilder2::error("pattern must have at least one field")
+                    .code("SV0026")
+                    .span(expr.span()),
+            );
             return Err(());
         }
         ast::PatternExpr(ref fields) => {
@@ -1009,6 +1139,7 @@ fn lower_expr_inner<'gcx>(
                                         "`{}` not a positional pattern",
                                         field.span.extract()
                                     ))
+                                    .code("SV0026")
                                     .span(field.span)
                                     .add_note(
                                         "required because first field was a positional pattern,
@@ -1029,6 +1160,7 @@ fn lower_expr_inner<'gcx>(
                                 "`{}` after repeat pattern",
                                 field.span.extract()
                             ))
+                            .code("SV0026")
                             .span(field.span)
                             .add_note("repeat patterns must have the form `'{<expr>{...}}`"),
                         );
@@ -1069,6 +1201,7 @@ fn lower_expr_inner<'gcx>(
                                         "`{}` not a named pattern",
                                         field.span.extract()
                                     ))
+                                    .code("SV0026")
                                     .span(field.span)
                                     .add_note(
                                         "required because first field was a named pattern,
@@ -1324,6 +1457,7 @@ fn lower_stmt_inner<'a>(
                         } else {
                             cx.emit(
                                 DiagBuilder2::error("multiple default cases")
+                                    .code("SV0043")
                                     .span(stmt.human_span()),
                             );
                         }
@@ -1347,6 +1481,7 @@ fn lower_stmt_inner<'a>(
         ast::AssertionStmt { .. } => {
             cx.emit(
                 DiagBuilder2::warning("unsupported: immediate assertion; ignored")
+                    .group("unsupported")
                     .span(stmt.human_span()),
             );
             hir::StmtKind::Null
@@ -1381,6 +1516,7 @@ fn parse_fixed_point_number<'gcx>(
             };
             cx.emit(
                 DiagBuilder2::error(format!("`{}` is not a number literal", value))
+                    .code("SV0023")
                     .span(span)
                     .add_note(format!("{}", e)),
             );
@@ -1451,6 +1587,7 @@ fn alloc_genvar_init<'gcx>(
         _ => {
             cx.emit(
                 DiagBuilder2::error(format!("{:#} is not a valid genvar initialization", stmt))
+                    .code("SV0041")
                     .span(stmt.human_span()),
             );
             error!("Offending AST is {:#02?}", stmt);
@@ -1460,6 +1597,29 @@ fn alloc_genvar_init<'gcx>(
     Ok(ids)
 }
 
+/// Resolve an expression which refers to a generate block.
+///
+/// Returns the block if the expression is a name or hierarchical path that
+/// resolves to a labeled generate block, e.g. the `g` or `g.h` in `g.h.x`.
+fn resolve_generate_block<'a>(
+    cx: &impl Context<'a>,
+    expr: &'a ast::Expr<'a>,
+) -> Option<&'a ast::GenerateBlock<'a>> {
+    let def = match expr.data {
+        ast::IdentExpr(name) => cx
+            .resolve_local(name.value, cx.scope_location(expr), false)
+            .ok()??,
+        ast::MemberExpr { ref expr, name } => {
+            cx.resolve_hierarchical(name.value, resolve_generate_block(cx, expr)?)?
+        }
+        _ => return None,
+    };
+    match def.node {
+        DefNode::Ast(node) => node.as_all().get_generate_block(),
+        _ => None,
+    }
+}
+
 /// Allocate node IDs for a parameter declaration.
 fn alloc_param_decl<'gcx>(
     cx: &impl Context<'gcx>,
@@ -1571,6 +1731,7 @@ fn lower_package<'gcx>(
             _ => {
                 cx.emit(
                     DiagBuilder2::error(format!("{:#} cannot appear in a package", item))
+                        .code("SV0024")
                         .span(item.human_span()),
                 );
                 return Err(());
@@ -1630,6 +1791,7 @@ fn lower_call<'a>(
                 _ => {
                     cx.emit(
                         DiagBuilder2::error(format!("`{}` takes one argument", ident))
+                            .code("SV0027")
                             .span(expr.human_span()),
                     );
                     Err(())
@@ -1664,31 +1826,95 @@ fn lower_call<'a>(
                 _ => {
                     cx.emit(
                         DiagBuilder2::error(format!("`{}` takes one or two arguments", ident))
+                            .code("SV0027")
                             .span(expr.human_span()),
                     );
                     Err(())
                 }
             };
+            let map_display = |task, radix| {
+                for arg in args {
+                    cx.map_ast_with_parent(AstNode::CallArg(arg), expr.id());
+                }
+                hir::BuiltinCall::Display(task, radix, args)
+            };
+            let map_readmem = |radix| {
+                let mut ids = vec![];
+                for arg in args {
+                    match arg.data.expr {
+                        Some(ref arg) if ids.len() < 4 => {
+                            ids.push(cx.map_ast_with_parent(AstNode::Expr(arg), expr.id()))
+                        }
+                        _ => {
+                            ids.clear();
+                            break;
+                        }
+                    }
+                }
+                if ids.len() < 2 {
+                    cx.emit(
+                        DiagBuilder2::error(format!("`{}` takes two to four arguments", ident))
+                            .code("SV0027")
+                            .span(expr.human_span()),
+                    );
+                    return Err(());
+                }
+                Ok(hir::BuiltinCall::ReadMem(
+                    radix,
+                    ids[0],
+                    ids[1],
+                    ids.get(2).cloned(),
+                    ids.get(3).cloned(),
+                ))
+            };
             hir::ExprKind::Builtin(match &*ident.value.as_str() {
                 "clog2" => hir::BuiltinCall::Clog2(map_unary_id()?),
                 "signed" => hir::BuiltinCall::Signed(map_unary_id()?),
                 "unsigned" => hir::BuiltinCall::Unsigned(map_unary_id()?),
-                "countones" => hir::BuiltinCall::CountOnes(map_unary()?),
-                "onehot" => hir::BuiltinCall::OneHot(map_unary()?),
-                "onehot0" => hir::BuiltinCall::OneHot0(map_unary()?),
+                "countones" => hir::BuiltinCall::CountOnes(map_unary_id()?),
+                "onehot" => hir::BuiltinCall::OneHot(map_unary_id()?),
+                "onehot0" => hir::BuiltinCall::OneHot0(map_unary_id()?),
                 "isunknown" => hir::BuiltinCall::IsUnknown(map_unary()?),
+                "rtoi" => hir::BuiltinCall::Rtoi(map_unary_id()?),
+                "itor" => hir::BuiltinCall::Itor(map_unary_id()?),
+                "realtobits" => hir::BuiltinCall::RealToBits(map_unary_id()?),
+                "bitstoreal" => hir::BuiltinCall::BitsToReal(map_unary_id()?),
+                "shortrealtobits" => hir::BuiltinCall::ShortRealToBits(map_unary_id()?),
+                "bitstoshortreal" => hir::BuiltinCall::BitsToShortReal(map_unary_id()?),
                 "left" => map_array_dim(hir::ArrayDim::Left)?,
                 "right" => map_array_dim(hir::ArrayDim::Right)?,
                 "low" => map_array_dim(hir::ArrayDim::Low)?,
                 "high" => map_array_dim(hir::ArrayDim::High)?,
                 "increment" => map_array_dim(hir::ArrayDim::Increment)?,
                 "size" => map_array_dim(hir::ArrayDim::Size)?,
+                "dimensions" => hir::BuiltinCall::Dimensions(map_unary()?),
+                "unpacked_dimensions" => hir::BuiltinCall::UnpackedDimensions(map_unary()?),
+                "display" => map_display(DisplayTask::Display, Radix::Dec),
+                "displayb" => map_display(DisplayTask::Display, Radix::Bin),
+                "displayo" => map_display(DisplayTask::Display, Radix::Oct),
+                "displayh" => map_display(DisplayTask::Display, Radix::Hex),
+                "write" => map_display(DisplayTask::Write, Radix::Dec),
+                "writeb" => map_display(DisplayTask::Write, Radix::Bin),
+                "writeo" => map_display(DisplayTask::Write, Radix::Oct),
+                "writeh" => map_display(DisplayTask::Write, Radix::Hex),
+                "strobe" => map_display(DisplayTask::Strobe, Radix::Dec),
+                "strobeb" => map_display(DisplayTask::Strobe, Radix::Bin),
+                "strobeo" => map_display(DisplayTask::Strobe, Radix::Oct),
+                "strobeh" => map_display(DisplayTask::Strobe, Radix::Hex),
+                "monitor" => map_display(DisplayTask::Monitor, Radix::Dec),
+                "monitorb" => map_display(DisplayTask::Monitor, Radix::Bin),
+                "monitoro" => map_display(DisplayTask::Monitor, Radix::Oct),
+                "monitorh" => map_display(DisplayTask::Monitor, Radix::Hex),
+                "sformatf" => map_display(DisplayTask::Sformatf, Radix::Dec),
+                "readmemb" => map_readmem(Radix::Bin)?,
+                "readmemh" => map_readmem(Radix::Hex)?,
                 _ => {
                     cx.emit(
                         DiagBuilder2::warning(format!(
                             "unsupported: system task `${}`; ignored",
                             ident
                         ))
+                        .group("unsupported")
                         .span(expr.human_span()),
                     );
                     hir::BuiltinCall::Unsupported
@@ -1717,6 +1943,7 @@ fn lower_call<'a>(
                             "uncallable: {} is not a function/task",
                             def.node
                         ))
+                        .code("SV0029")
                         .span(name.span())
                         .add_note(format!("`{}` defined here:", def.name))
                         .span(def.name.span),
@@ -1741,6 +1968,7 @@ fn lower_call<'a>(
                     "`{}` is not something that can be called",
                     callee.span().extract()
                 ))
+                .code("SV0029")
                 .span(expr.human_span()),
             );
             return Err(());
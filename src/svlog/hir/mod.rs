@@ -5,14 +5,19 @@
 //! After parsing the AST is lowered into this representation, eliminating a lot
 //! of syntactic sugar and resolving any syntactic ambiguities.
 
+use crate::common::foreign::ForeignLang;
 use crate::crate_prelude::*;
 use crate::mir::WalkVisitor as _;
 use std::{collections::BTreeSet, sync::Arc};
 
+pub mod dump;
+pub mod fold;
 pub(crate) mod lowering;
 mod nodes;
+pub mod print;
 mod visit;
 
+pub use self::fold::Folder;
 pub use self::lowering::Hint;
 pub use self::nodes::*;
 pub use self::visit::*;
@@ -22,6 +27,8 @@ make_arenas!(
     pub struct Arena<'hir> {
         modules: Module<'hir>,
         interfaces: Interface<'hir>,
+        modports: Modport<'hir>,
+        generate_blocks: GenerateBlock<'hir>,
         ports: Port,
         types: Type,
         exprs: Expr<'hir>,
@@ -179,3 +186,112 @@ where
         !self.cx.is_parent_of(self.table.node_id, binding)
     }
 }
+
+/// Find the innermost HIR node below `root` that covers a source location.
+///
+/// This considers the full span of every node reachable from `root`, and
+/// returns the node with the smallest span that contains `loc`. If multiple
+/// nodes share that span, the one nested deepest in the HIR is returned.
+#[moore_derive::query]
+pub(crate) fn hir_node_at<'a>(
+    cx: &impl Context<'a>,
+    root: NodeId,
+    loc: crate::common::source::Location,
+) -> Option<NodeId> {
+    let mut best: Option<(usize, NodeId)> = None;
+    for (id, node) in dump::collect_nodes(cx, root) {
+        let span = node.span();
+        if span.source != loc.source || span.begin > loc.offset || span.end <= loc.offset {
+            continue;
+        }
+        let len = span.end - span.begin;
+        if best.map(|(best_len, _)| len <= best_len).unwrap_or(true) {
+            best = Some((len, id));
+        }
+    }
+    best.map(|(_, id)| id)
+}
+
+/// Find the innermost HIR node of any SystemVerilog design unit that covers a
+/// source location.
+///
+/// This is the entry point for tools outside the compiler, such as the
+/// `--node-at` debug flag, and tries each unit declared in the location's file.
+pub fn node_at<'a>(
+    cx: &impl Context<'a>,
+    loc: crate::common::source::Location,
+) -> Option<HirNode<'a>> {
+    cx.sess()
+        .units
+        .units()
+        .into_iter()
+        .filter(|unit| unit.lang == ForeignLang::Svlog && unit.span.source == loc.source)
+        .find_map(|unit| cx.hir_node_at(unit.id, loc))
+        .and_then(|id| cx.hir_of(id).ok())
+}
+
+/// Find an attribute attached to a node.
+///
+/// This looks for the attribute in the `(* ... *)` instances immediately
+/// preceding the node. Module instances inherit the attributes preceding the
+/// instantiation they are part of, such as `u0` in `(* a *) foo u0(), u1();`.
+#[moore_derive::query]
+pub(crate) fn attribute<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    name: Name,
+) -> Option<&'a ast::Attribute> {
+    let ast = cx.try_ast_for_id(node_id)?;
+    let attr_at = |node: &dyn ast::AnyNode| {
+        let span = node.span();
+        cx.gcx()
+            .attributes_at(crate::common::source::Location::new(
+                span.source,
+                span.begin,
+            ))
+            .into_iter()
+            .rev()
+            .find(|attr| attr.name == name)
+    };
+    if let Some(attr) = attr_at(ast) {
+        return Some(attr);
+    }
+    match ast.get_parent()?.as_all() {
+        ast::AllNode::Inst(x) => attr_at(x),
+        _ => None,
+    }
+}
+
+/// Find the documentation comment attached to a node.
+///
+/// This is the `///` or `/** */` comment immediately preceding the node. Nodes
+/// that are declared as part of a larger declaration, such as `b` in
+/// `logic a, b;`, inherit the comment preceding that declaration.
+#[moore_derive::query]
+pub(crate) fn doc_comment<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+) -> Option<&'a ast::DocComment> {
+    let ast = match cx.get_interned_hir(node_id) {
+        Some(HirNode::IntPort(port)) => port.ast,
+        _ => cx.try_ast_for_id(node_id)?,
+    };
+    let doc_at = |node: &dyn ast::AnyNode| {
+        let span = node.span();
+        cx.gcx()
+            .doc_comment_at(crate::common::source::Location::new(
+                span.source,
+                span.begin,
+            ))
+    };
+    if let Some(doc) = doc_at(ast) {
+        return Some(doc);
+    }
+    match ast.get_parent()?.as_all() {
+        ast::AllNode::VarDecl(x) => doc_at(x),
+        ast::AllNode::NetDecl(x) => doc_at(x),
+        ast::AllNode::ParamDecl(x) => doc_at(x),
+        ast::AllNode::PortDecl(x) => doc_at(x),
+        _ => None,
+    }
+}
@@ -5,13 +5,14 @@
 use crate::crate_prelude::*;
 use bit_vec::BitVec;
 use num::{BigInt, BigRational};
+use serde::Serialize;
 use std::ops::Deref;
 
 // Re-export the ports as part of the HIR.
 pub use crate::port_list::{ExtPort, ExtPortExpr, ExtPortSelect, IntPort, IntPortData, PortList};
 
 /// A reference to an HIR node.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum HirNode<'a> {
     Module(&'a Module<'a>),
     Interface(&'a Interface<'a>),
@@ -28,12 +29,15 @@ pub enum HirNode<'a> {
     Stmt(&'a Stmt<'a>),
     EventExpr(&'a EventExpr),
     Gen(&'a Gen),
+    GenerateBlock(&'a GenerateBlock<'a>),
     GenvarDecl(&'a GenvarDecl),
     Typedef(&'a Typedef),
     Assign(&'a Assign),
     Package(&'a Package),
     EnumVariant(&'a EnumVariant),
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     SubroutinePort(&'a ast::SubroutinePort<'a>),
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     CallArg(&'a ast::CallArg<'a>),
 }
 
@@ -55,6 +59,7 @@ impl<'hir> HasSpan for HirNode<'hir> {
             HirNode::Stmt(x) => x.span(),
             HirNode::EventExpr(x) => x.span(),
             HirNode::Gen(x) => x.span(),
+            HirNode::GenerateBlock(x) => x.span(),
             HirNode::GenvarDecl(x) => x.span(),
             HirNode::Typedef(x) => x.span(),
             HirNode::Assign(x) => x.span(),
@@ -82,6 +87,7 @@ impl<'hir> HasSpan for HirNode<'hir> {
             HirNode::Stmt(x) => x.human_span(),
             HirNode::EventExpr(x) => x.human_span(),
             HirNode::Gen(x) => x.human_span(),
+            HirNode::GenerateBlock(x) => x.human_span(),
             HirNode::GenvarDecl(x) => x.human_span(),
             HirNode::Typedef(x) => x.human_span(),
             HirNode::Assign(x) => x.human_span(),
@@ -111,6 +117,7 @@ impl<'hir> HasDesc for HirNode<'hir> {
             HirNode::Stmt(x) => x.desc(),
             HirNode::EventExpr(x) => x.desc(),
             HirNode::Gen(x) => x.desc(),
+            HirNode::GenerateBlock(x) => x.desc(),
             HirNode::GenvarDecl(x) => x.desc(),
             HirNode::Typedef(x) => x.desc(),
             HirNode::Assign(x) => x.desc(),
@@ -138,6 +145,7 @@ impl<'hir> HasDesc for HirNode<'hir> {
             HirNode::Stmt(x) => x.desc_full(),
             HirNode::EventExpr(x) => x.desc_full(),
             HirNode::Gen(x) => x.desc_full(),
+            HirNode::GenerateBlock(x) => x.desc_full(),
             HirNode::GenvarDecl(x) => x.desc_full(),
             HirNode::Typedef(x) => x.desc_full(),
             HirNode::Assign(x) => x.desc_full(),
@@ -150,9 +158,10 @@ impl<'hir> HasDesc for HirNode<'hir> {
 }
 
 /// A module.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Module<'a> {
     /// The AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ast: &'a ast::Module<'a>,
     /// The ports of the module.
     pub ports_new: &'a PortList<'a>,
@@ -193,7 +202,7 @@ impl HasDesc for Module<'_> {
 }
 
 /// The contents of a module.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ModuleBlock {
     /// The module/interface instances in the module.
     pub insts: Vec<NodeId>,
@@ -216,9 +225,10 @@ pub struct ModuleBlock {
 /// In an instantiation `foo #(...) a(), b(), c();` this struct represents the
 /// `foo #(...)` part. Multiple instantiations (`a()`, `b()`, `c()`) may share
 /// the same target.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct InstTarget<'a> {
     /// The underlying AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ast: &'a ast::Inst<'a>,
     /// The positional parameters.
     pub pos_params: Vec<PosParam>,
@@ -264,9 +274,10 @@ pub type NamedParam = (Span, Spanned<Name>, Option<NodeId>);
 ///
 /// In an instantiation `foo #(...) a(), b(), c();`, this struct represents the
 /// `a()` part.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Inst<'a> {
     /// The underlying AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ast: &'a ast::InstName<'a>,
     /// The target of the instantiation.
     pub target: NodeId,
@@ -307,7 +318,7 @@ impl HasDesc for Inst<'_> {
 }
 
 /// A type parameter.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct TypeParam {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -337,7 +348,7 @@ impl HasDesc for TypeParam {
 }
 
 /// A value parameter.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct ValueParam {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -368,14 +379,27 @@ impl HasDesc for ValueParam {
 }
 
 /// An interface.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Interface<'a> {
     /// The AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ast: &'a ast::Interface<'a>,
     /// The ports of the interface.
     pub ports: &'a PortList<'a>,
     /// The contents of the interface.
     pub block: ModuleBlock,
+    /// The modports declared in the interface.
+    pub modports: Vec<&'a Modport<'a>>,
+}
+
+impl<'a> Interface<'a> {
+    /// Find a modport by name.
+    pub fn find_modport(&self, name: Name) -> Option<&'a Modport<'a>> {
+        self.modports
+            .iter()
+            .cloned()
+            .find(|modport| modport.name.value == name)
+    }
 }
 
 impl<'a> Deref for Interface<'a> {
@@ -406,6 +430,64 @@ impl HasDesc for Interface<'_> {
     }
 }
 
+/// A modport of an interface.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Modport<'a> {
+    /// The AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
+    pub ast: &'a ast::ModportName<'a>,
+    /// The interface that declares the modport.
+    pub intf: NodeId,
+    /// The interface signals listed in the modport.
+    pub ports: Vec<ModportPort>,
+}
+
+impl<'a> Modport<'a> {
+    /// Find the direction of a signal listed in the modport.
+    pub fn find_port(&self, name: Name) -> Option<&ModportPort> {
+        self.ports.iter().find(|port| port.name.value == name)
+    }
+}
+
+impl<'a> Deref for Modport<'a> {
+    type Target = &'a ast::ModportName<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ast
+    }
+}
+
+impl HasSpan for Modport<'_> {
+    fn span(&self) -> Span {
+        self.ast.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.ast.name.span
+    }
+}
+
+impl HasDesc for Modport<'_> {
+    fn desc(&self) -> &'static str {
+        "modport"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("modport `{}`", self.ast.name)
+    }
+}
+
+/// A signal listed in a modport.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct ModportPort {
+    /// The name of the interface signal.
+    pub name: Spanned<Name>,
+    /// The direction of the signal as seen through the modport.
+    pub dir: ast::PortDir,
+    /// The location of the port in the source text.
+    pub span: Span,
+}
+
 // /// A package.
 // pub struct Package {
 //     pub name: Name,
@@ -438,7 +520,7 @@ impl HasDesc for Interface<'_> {
 // }
 
 /// A module or interface port.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Port {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -469,7 +551,7 @@ impl HasDesc for Port {
 }
 
 /// A type.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Type {
     pub id: NodeId,
     pub span: Span,
@@ -505,7 +587,7 @@ impl HasDesc for Type {
 }
 
 /// The different forms a type can take.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum TypeKind {
     /// An implicit type.
     Implicit,
@@ -560,7 +642,7 @@ impl HasDesc for TypeKind {
 }
 
 /// A builtin type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum BuiltinType {
     Void,
     Bit,
@@ -575,9 +657,10 @@ pub enum BuiltinType {
 }
 
 /// An expression.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Expr<'a> {
     /// The AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ast: &'a ast::Expr<'a>,
     /// The specific expression data.
     pub kind: ExprKind<'a>,
@@ -607,6 +690,7 @@ impl HasDesc for Expr<'_> {
         match self.kind {
             ExprKind::IntConst { .. } => "integer constant",
             ExprKind::TimeConst(_) => "time constant",
+            ExprKind::RealConst(_) => "real constant",
             ExprKind::Ident(_) => "identifier",
             _ => "expression",
         }
@@ -617,6 +701,7 @@ impl HasDesc for Expr<'_> {
         match self.kind {
             ExprKind::IntConst { value: ref k, .. } => format!("{} `{}`", self.desc(), k),
             ExprKind::TimeConst(ref k) => format!("{} `{}`", self.desc(), k),
+            ExprKind::RealConst(ref k) => format!("{} `{}`", self.desc(), k),
             ExprKind::Ident(n) => format!("`{}`", n.value),
             ExprKind::PositionalPattern(..) => format!("positional pattern"),
             ExprKind::NamedPattern(..) => format!("named pattern"),
@@ -627,7 +712,7 @@ impl HasDesc for Expr<'_> {
 }
 
 /// The different forms an expression can take.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ExprKind<'a> {
     /// An integer constant literal such as `42` or `'d42` or `32'd42`.
     ///
@@ -635,15 +720,20 @@ pub enum ExprKind<'a> {
     /// or `z`. The `x_bits` mask tracks which of these special bits are `x`.
     IntConst {
         width: usize,
+        #[serde(serialize_with = "crate::hir::dump::ser_display")]
         value: BigInt,
         signed: bool,
+        #[serde(serialize_with = "crate::hir::dump::ser_bits")]
         special_bits: BitVec,
+        #[serde(serialize_with = "crate::hir::dump::ser_bits")]
         x_bits: BitVec,
     },
     /// An unsized and unbased constant literal such as `'0`.
     UnsizedConst(char),
     /// A time constant literal.
-    TimeConst(BigRational),
+    TimeConst(#[serde(serialize_with = "crate::hir::dump::ser_display")] BigRational),
+    /// A real constant literal such as `4.2` or `1e-3`.
+    RealConst(#[serde(serialize_with = "crate::hir::dump::ser_display")] BigRational),
     /// A string constant literal.
     StringConst(Spanned<Name>),
     /// An identifier.
@@ -679,19 +769,24 @@ pub enum ExprKind<'a> {
     /// An inside expression such as `a inside {b, c}`.
     Inside(NodeId, Vec<Spanned<InsideRange>>),
     /// A function call such as `foo(a, b, c)`.
-    FunctionCall(&'a ast::SubroutineDecl<'a>, &'a [ast::CallArg<'a>]),
+    FunctionCall(
+        #[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::SubroutineDecl<'a>,
+        #[serde(serialize_with = "crate::hir::dump::ser_ast_slice")] &'a [ast::CallArg<'a>],
+    ),
     /// An assignment.
     Assign {
         op: ast::AssignOp,
+        #[serde(serialize_with = "crate::hir::dump::ser_ast")]
         lhs: &'a ast::Expr<'a>,
+        #[serde(serialize_with = "crate::hir::dump::ser_ast")]
         rhs: &'a ast::Expr<'a>,
     },
     /// An expression in the AST that requires no representational change.
-    Ast(&'a ast::Expr<'a>),
+    Ast(#[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::Expr<'a>),
 }
 
 /// The different unary operators.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum UnaryOp {
     /// The plus operator `+x`.
     Pos,
@@ -745,7 +840,7 @@ impl HasDesc for UnaryOp {
 }
 
 /// The different binary operators.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum BinaryOp {
     /// The addition operator `x + y`.
     Add,
@@ -829,7 +924,7 @@ impl HasDesc for BinaryOp {
 }
 
 /// The different forms an index expression can take.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum IndexMode {
     /// A single value access such as `[a]`.
     One(NodeId),
@@ -838,32 +933,69 @@ pub enum IndexMode {
 }
 
 /// The different builtin function calls that are supported.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum BuiltinCall<'a> {
     /// An unsupported builtin. Will yield constant 0.
     Unsupported,
     /// A call to the ceil-log2 function `$clog2(x)`.
     Clog2(NodeId),
     /// A call to the storage size function `$bits(x)`.
-    Bits(&'a ast::TypeOrExpr<'a>),
+    Bits(#[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::TypeOrExpr<'a>),
     /// A call to the convert-to-signed function `$signed(x)`.
     Signed(NodeId),
     /// A call to the convert-to-unsigned function `$unsigned(x)`.
     Unsigned(NodeId),
     /// A call to the `$countones(x)` function.
-    CountOnes(&'a ast::Expr<'a>),
+    CountOnes(NodeId),
     /// A call to the `$onehot(x)` function.
-    OneHot(&'a ast::Expr<'a>),
+    OneHot(NodeId),
     /// A call to the `$onehot0(x)` function.
-    OneHot0(&'a ast::Expr<'a>),
+    OneHot0(NodeId),
     /// A call to the `$isunknown(x)` function.
-    IsUnknown(&'a ast::Expr<'a>),
+    IsUnknown(#[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::Expr<'a>),
+    /// A call to the real-to-integer conversion function `$rtoi(x)`.
+    Rtoi(NodeId),
+    /// A call to the integer-to-real conversion function `$itor(x)`.
+    Itor(NodeId),
+    /// A call to the `$realtobits(x)` function.
+    RealToBits(NodeId),
+    /// A call to the `$bitstoreal(x)` function.
+    BitsToReal(NodeId),
+    /// A call to the `$shortrealtobits(x)` function.
+    ShortRealToBits(NodeId),
+    /// A call to the `$bitstoshortreal(x)` function.
+    BitsToShortReal(NodeId),
     /// A call to one of the array dimension functions.
-    ArrayDim(ArrayDim, &'a ast::Expr<'a>, Option<&'a ast::Expr<'a>>),
+    ArrayDim(
+        ArrayDim,
+        #[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::Expr<'a>,
+        #[serde(serialize_with = "crate::hir::dump::ser_ast_opt")] Option<&'a ast::Expr<'a>>,
+    ),
+    /// A call to the `$dimensions(x)` function.
+    Dimensions(#[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::Expr<'a>),
+    /// A call to the `$unpacked_dimensions(x)` function.
+    UnpackedDimensions(#[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::Expr<'a>),
+    /// A call to one of the display tasks or `$sformatf`, with the radix in
+    /// which integers are printed by default.
+    Display(
+        crate::format::DisplayTask,
+        crate::format::Radix,
+        #[serde(serialize_with = "crate::hir::dump::ser_ast_slice")] &'a [ast::CallArg<'a>],
+    ),
+    /// A call to `$readmemb` or `$readmemh`, with the radix of the words in
+    /// the file, the file name, the memory, and the optional start and end
+    /// addresses.
+    ReadMem(
+        crate::format::Radix,
+        NodeId,
+        NodeId,
+        Option<NodeId>,
+        Option<NodeId>,
+    ),
 }
 
 /// The different builtin array dimension function calls that are supported.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ArrayDim {
     /// The `$left` function.
     Left,
@@ -880,7 +1012,7 @@ pub enum ArrayDim {
 }
 
 /// A variable or net declaration.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct VarDecl {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -920,7 +1052,7 @@ impl HasDesc for VarDecl {
 }
 
 /// A procedure.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Proc {
     pub id: NodeId,
     pub span: Span,
@@ -948,7 +1080,7 @@ impl HasDesc for Proc {
 }
 
 /// A variable declaration.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Stmt<'a> {
     pub id: NodeId,
     pub label: Option<Spanned<Name>>,
@@ -979,7 +1111,7 @@ impl HasDesc for Stmt<'_> {
 }
 
 /// The different forms a statement can take.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum StmtKind<'a> {
     /// A null statement.
     Null,
@@ -1025,11 +1157,11 @@ pub enum StmtKind<'a> {
         kind: ast::CaseKind,
     },
     /// A statement in the AST that requires no representational change.
-    Ast(&'a ast::Stmt<'a>),
+    Ast(#[serde(serialize_with = "crate::hir::dump::ser_ast")] &'a ast::Stmt<'a>),
 }
 
 /// The different forms an assignment can take.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum AssignKind {
     /// A blocking assignment.
     Block(ast::AssignOp),
@@ -1040,7 +1172,7 @@ pub enum AssignKind {
 }
 
 /// The different forms a loop can take.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum LoopKind {
     /// A `forever` loop.
     Forever,
@@ -1055,7 +1187,7 @@ pub enum LoopKind {
 }
 
 /// The different forms of timing control that can be applied to a statement.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum TimingControl {
     /// A delayed statement. Contains an expression that evaluates to a time.
     Delay(NodeId),
@@ -1068,7 +1200,7 @@ pub enum TimingControl {
 /// An event expression.
 ///
 /// Contains multiple events separated by `,` or `or`.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct EventExpr {
     pub id: NodeId,
     pub span: Span,
@@ -1088,7 +1220,7 @@ impl HasDesc for EventExpr {
 }
 
 /// An individual event within an event expression.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Event {
     pub span: Span,
     pub edge: ast::EdgeIdent,
@@ -1097,7 +1229,7 @@ pub struct Event {
 }
 
 /// A generate statement.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Gen {
     pub id: NodeId,
     pub span: Span,
@@ -1117,25 +1249,72 @@ impl HasDesc for Gen {
 }
 
 /// The different forms a generate statement can take.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// The bodies refer to `GenerateBlock` nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum GenKind {
     /// An if-generate statement.
     If {
         cond: NodeId,
-        main_body: ModuleBlock,
-        else_body: Option<ModuleBlock>,
+        main_body: NodeId,
+        else_body: Option<NodeId>,
     },
     /// A for-generate statement.
     For {
         init: Vec<NodeId>,
         cond: NodeId,
         step: NodeId,
-        body: ModuleBlock,
+        body: NodeId,
     },
 }
 
+/// A block of a generate statement.
+///
+/// Each block introduces a new scope. Labeled blocks may be referred to by
+/// name from the enclosing scope, e.g. `label.signal`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct GenerateBlock<'a> {
+    /// The node ID of the block.
+    pub id: NodeId,
+    /// The AST node.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
+    pub ast: &'a ast::GenerateBlock<'a>,
+    /// The contents of the block.
+    pub block: ModuleBlock,
+}
+
+impl<'a> GenerateBlock<'a> {
+    /// Get the optional label of the block.
+    pub fn label(&self) -> Option<Spanned<Name>> {
+        self.ast.label
+    }
+}
+
+impl HasSpan for GenerateBlock<'_> {
+    fn span(&self) -> Span {
+        self.ast.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.ast.label.map(|l| l.span).unwrap_or(self.ast.span)
+    }
+}
+
+impl HasDesc for GenerateBlock<'_> {
+    fn desc(&self) -> &'static str {
+        "generate block"
+    }
+
+    fn desc_full(&self) -> String {
+        match self.ast.label {
+            Some(label) => format!("generate block `{}`", label),
+            None => "generate block".to_string(),
+        }
+    }
+}
+
 /// A genvar declaration.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GenvarDecl {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -1164,7 +1343,7 @@ impl HasDesc for GenvarDecl {
 }
 
 /// A typedef.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Typedef {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -1193,7 +1372,7 @@ impl HasDesc for Typedef {
 }
 
 /// A continuous assignment.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Assign {
     pub id: NodeId,
     pub span: Span,
@@ -1214,7 +1393,7 @@ impl HasDesc for Assign {
 }
 
 /// A package.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Package {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -1250,7 +1429,7 @@ impl HasDesc for Package {
 }
 
 /// A single variant of an enum.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct EnumVariant {
     pub id: NodeId,
     pub name: Spanned<Name>,
@@ -1284,7 +1463,7 @@ impl HasDesc for EnumVariant {
 }
 
 /// A named pattern mapping.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum PatternMapping {
     /// A field with a type as key, e.g. `'{logic: ...}`.
     Type(NodeId),
@@ -1295,7 +1474,7 @@ pub enum PatternMapping {
 }
 
 /// Single values or value ranges admissible in `inside` sets.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum InsideRange {
     Single(NodeId),
     Range(NodeId, NodeId),
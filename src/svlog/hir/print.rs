@@ -0,0 +1,840 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A pretty printer for the HIR.
+//!
+//! This module renders the HIR back into SystemVerilog-like text. Every item
+//! and statement is annotated with its node ID, and every expression is listed
+//! below the line it appears on together with its node ID and resolved type.
+//! This is mainly intended to debug the lowering from the AST.
+
+use crate::crate_prelude::*;
+use crate::{
+    hir::{nodes::*, HirNode},
+    Context, ParamEnv,
+};
+use std::io::{Result as IoResult, Write};
+
+/// Write the HIR of a node as SystemVerilog-like text.
+pub fn write_text<'a>(
+    cx: &impl Context<'a>,
+    root: NodeId,
+    env: ParamEnv,
+    w: &mut impl Write,
+) -> IoResult<()> {
+    let mut printer = Printer {
+        cx,
+        env,
+        w,
+        indent: 0,
+        notes: vec![],
+        genvar_depth: 0,
+    };
+    printer.print_node(root)
+}
+
+/// A pretty printer for HIR nodes.
+struct Printer<'c, C, W> {
+    cx: &'c C,
+    env: ParamEnv,
+    w: &'c mut W,
+    indent: usize,
+    /// The expressions rendered since the last line was emitted.
+    notes: Vec<(NodeId, String)>,
+    /// The number of for-generate blocks we are in. Types inside these depend
+    /// on the genvar and cannot be resolved without an iteration's parameters.
+    genvar_depth: usize,
+}
+
+impl<'a, 'c, C: Context<'a>, W: Write> Printer<'c, C, W> {
+    /// Emit a line of text, annotated with a node ID and the expressions
+    /// rendered as part of it.
+    fn line(&mut self, text: impl std::fmt::Display, id: NodeId) -> IoResult<()> {
+        self.emit(text, Some((id, None)))
+    }
+
+    /// Emit a line of text, annotated with a node ID and its resolved type.
+    fn typed_line(&mut self, text: impl std::fmt::Display, id: NodeId) -> IoResult<()> {
+        let ty = self.type_note(id);
+        self.emit(text, Some((id, ty)))
+    }
+
+    /// Emit a line of text, followed by one annotation for each expression
+    /// rendered as part of it.
+    fn emit(
+        &mut self,
+        text: impl std::fmt::Display,
+        note: Option<(NodeId, Option<String>)>,
+    ) -> IoResult<()> {
+        let indent = "    ".repeat(self.indent);
+        match note {
+            Some((id, Some(ty))) => writeln!(self.w, "{}{} // {:?}: {}", indent, text, id, ty)?,
+            Some((id, None)) => writeln!(self.w, "{}{} // {:?}", indent, text, id)?,
+            None => writeln!(self.w, "{}{}", indent, text)?,
+        }
+        for (id, text) in std::mem::take(&mut self.notes) {
+            match self.type_note(id) {
+                Some(ty) => writeln!(self.w, "{}    // {:?} `{}`: {}", indent, id, text, ty)?,
+                None => writeln!(self.w, "{}    // {:?} `{}`", indent, id, text)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit a line of text without annotations.
+    fn plain(&mut self, text: impl std::fmt::Display) -> IoResult<()> {
+        writeln!(self.w, "{}{}", "    ".repeat(self.indent), text)
+    }
+
+    /// Determine the resolved type of a node, if it can be determined.
+    fn type_note(&self, id: NodeId) -> Option<String> {
+        if self.genvar_depth > 0 {
+            return None;
+        }
+        self.cx.type_of(id, self.env).ok().map(|ty| ty.to_string())
+    }
+
+    /// Emit the documentation comment attached to a node, if any.
+    fn print_doc(&mut self, id: NodeId) -> IoResult<()> {
+        if let Some(doc) = self.cx.doc_comment(id) {
+            for line in doc.text.lines() {
+                self.plain(format!("/// {}", line).trim_end())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn print_node(&mut self, id: NodeId) -> IoResult<()> {
+        let hir = match self.cx.hir_of(id) {
+            Ok(x) => x,
+            Err(()) => return self.line("<error>", id),
+        };
+        self.print_doc(id)?;
+        match hir {
+            HirNode::Module(x) => self.print_module(x),
+            HirNode::Interface(x) => {
+                self.line(format!("interface {};", x.name), id)?;
+                self.print_block(&x.block)?;
+                self.plain("endinterface")
+            }
+            HirNode::Package(x) => {
+                self.line(format!("package {};", x.name), id)?;
+                self.indent += 1;
+                for &id in x.params.iter().chain(x.decls.iter()) {
+                    self.print_node(id)?;
+                }
+                self.indent -= 1;
+                self.plain("endpackage")
+            }
+            HirNode::ValueParam(x) => {
+                let mut text = format!(
+                    "{} {} {}",
+                    if x.local { "localparam" } else { "parameter" },
+                    self.render_type(x.ty),
+                    x.name
+                );
+                if let Some(default) = x.default {
+                    text += &format!(" = {}", self.render_expr(default));
+                }
+                self.typed_line(format!("{};", text), id)
+            }
+            HirNode::TypeParam(x) => {
+                let mut text = format!(
+                    "{} type {}",
+                    if x.local { "localparam" } else { "parameter" },
+                    x.name
+                );
+                if let Some(default) = x.default {
+                    text += &format!(" = {}", self.render_type(default));
+                }
+                self.line(format!("{};", text), id)
+            }
+            HirNode::VarDecl(x) => {
+                let mut text = format!("{} {} {}", x.kind, self.render_type(x.ty), x.name);
+                if let Some(init) = x.init {
+                    text += &format!(" = {}", self.render_expr(init));
+                }
+                self.typed_line(format!("{};", text), id)
+            }
+            HirNode::Typedef(x) => {
+                let text = format!("typedef {} {};", self.render_type(x.ty), x.name);
+                self.line(text, id)
+            }
+            HirNode::GenvarDecl(x) => {
+                let text = self.render_genvar(x);
+                self.line(format!("{};", text), id)
+            }
+            HirNode::Assign(x) => {
+                let text = format!(
+                    "assign {} = {};",
+                    self.render_expr(x.lhs),
+                    self.render_expr(x.rhs)
+                );
+                self.line(text, id)
+            }
+            HirNode::Inst(x) => self.print_inst(x),
+            HirNode::Proc(x) => {
+                let kw = match x.kind {
+                    ast::ProcedureKind::Initial => "initial",
+                    ast::ProcedureKind::Always => "always",
+                    ast::ProcedureKind::AlwaysComb => "always_comb",
+                    ast::ProcedureKind::AlwaysLatch => "always_latch",
+                    ast::ProcedureKind::AlwaysFf => "always_ff",
+                    ast::ProcedureKind::Final => "final",
+                };
+                self.line(kw, id)?;
+                self.indent += 1;
+                self.print_stmt(x.stmt)?;
+                self.indent -= 1;
+                Ok(())
+            }
+            HirNode::Stmt(_) => self.print_stmt(id),
+            HirNode::Gen(x) => self.print_gen(x),
+            HirNode::GenerateBlock(x) => self.print_generate_block(x, ""),
+            _ => {
+                let text = format!("/* {} */", hir.desc_full());
+                self.line(text, id)
+            }
+        }
+    }
+
+    fn print_module(&mut self, module: &'a Module<'a>) -> IoResult<()> {
+        let mut header = format!("module {}", module.name);
+        let params: Vec<_> = module
+            .params
+            .iter()
+            .map(|&id| match self.cx.hir_of(id) {
+                Ok(HirNode::ValueParam(p)) => format!("{}", p.name),
+                Ok(HirNode::TypeParam(p)) => format!("type {}", p.name),
+                _ => format!("{:?}", id),
+            })
+            .collect();
+        if !params.is_empty() {
+            header += &format!(" #({})", params.join(", "));
+        }
+        if !module.ports_new.int.is_empty() {
+            header += " (";
+        } else {
+            header += ";";
+        }
+        self.line(header, module.ast.id())?;
+
+        // Print the ports.
+        self.indent += 1;
+        let num_ports = module.ports_new.int.len();
+        for (i, port) in module.ports_new.int.iter().enumerate() {
+            self.print_doc(port.id)?;
+            let ty = self.type_note(port.id);
+            let sep = if i + 1 < num_ports { "," } else { "" };
+            let text = match ty {
+                Some(ty) => format!("{} {} {}{}", port.dir, ty, port.name, sep),
+                None => format!("{} {}{}", port.dir, port.name, sep),
+            };
+            self.line(text, port.id)?;
+        }
+        self.indent -= 1;
+        if num_ports > 0 {
+            self.plain(");")?;
+        }
+
+        // Print the parameters and the body.
+        self.indent += 1;
+        for &id in module.params {
+            self.print_node(id)?;
+        }
+        self.indent -= 1;
+        self.print_block(&module.block)?;
+        self.plain("endmodule")
+    }
+
+    fn print_block(&mut self, block: &ModuleBlock) -> IoResult<()> {
+        self.indent += 1;
+        for &id in block
+            .params
+            .iter()
+            .chain(block.decls.iter())
+            .chain(block.insts.iter())
+            .chain(block.assigns.iter())
+            .chain(block.procs.iter())
+            .chain(block.gens.iter())
+        {
+            self.print_node(id)?;
+        }
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn print_inst(&mut self, inst: &'a Inst<'a>) -> IoResult<()> {
+        let mut text = match self.cx.hir_of(inst.target) {
+            Ok(HirNode::InstTarget(target)) => {
+                let mut text = format!("{}", target.ast.target);
+                let params = self.render_connections(&target.pos_params, &target.named_params);
+                if !params.is_empty() {
+                    text += &format!(" #({})", params);
+                }
+                text
+            }
+            _ => "<error>".to_string(),
+        };
+        text += &format!(" {} (", inst.ast.name);
+        text += &self.render_connections(&inst.pos_ports, &inst.named_ports);
+        if inst.has_wildcard_port {
+            if !inst.pos_ports.is_empty() || !inst.named_ports.is_empty() {
+                text += ", ";
+            }
+            text += ".*";
+        }
+        text += ");";
+        self.line(text, inst.ast.id())
+    }
+
+    fn render_connections(&mut self, pos: &[PosParam], named: &[NamedParam]) -> String {
+        let mut items = vec![];
+        for &(_, id) in pos {
+            items.push(match id {
+                Some(id) => self.render_node(id),
+                None => String::new(),
+            });
+        }
+        for &(_, name, id) in named {
+            items.push(match id {
+                Some(id) => format!(".{}({})", name, self.render_node(id)),
+                None => format!(".{}()", name),
+            });
+        }
+        items.join(", ")
+    }
+
+    fn print_gen(&mut self, gen: &'a Gen) -> IoResult<()> {
+        match gen.kind {
+            GenKind::If {
+                cond,
+                main_body,
+                else_body,
+            } => {
+                let text = format!("if ({})", self.render_expr(cond));
+                self.line(text, gen.id)?;
+                self.print_generate_block_with_id(main_body)?;
+                if let Some(else_body) = else_body {
+                    self.plain("else")?;
+                    self.print_generate_block_with_id(else_body)?;
+                }
+                Ok(())
+            }
+            GenKind::For {
+                ref init,
+                cond,
+                step,
+                body,
+            } => {
+                let init: Vec<_> = init
+                    .iter()
+                    .map(|&id| match self.cx.hir_of(id) {
+                        Ok(HirNode::GenvarDecl(x)) => self.render_genvar(x),
+                        _ => self.render_node(id),
+                    })
+                    .collect();
+                let text = format!(
+                    "for ({}; {}; {})",
+                    init.join(", "),
+                    self.render_expr(cond),
+                    self.render_expr(step)
+                );
+                self.line(text, gen.id)?;
+                self.genvar_depth += 1;
+                self.print_generate_block_with_id(body)?;
+                self.genvar_depth -= 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn print_generate_block_with_id(&mut self, id: NodeId) -> IoResult<()> {
+        match self.cx.hir_of(id) {
+            Ok(HirNode::GenerateBlock(x)) => self.print_generate_block(x, "    "),
+            _ => self.line("<error>", id),
+        }
+    }
+
+    fn print_generate_block(&mut self, block: &'a GenerateBlock<'a>, pad: &str) -> IoResult<()> {
+        let text = match block.label() {
+            Some(label) => format!("{}begin : {}", pad, label),
+            None => format!("{}begin", pad),
+        };
+        self.line(text, block.id)?;
+        self.indent += 1;
+        self.print_block(&block.block)?;
+        self.indent -= 1;
+        self.plain(format!("{}end", pad))
+    }
+
+    fn print_stmt(&mut self, id: NodeId) -> IoResult<()> {
+        let stmt = match self.cx.hir_of(id) {
+            Ok(HirNode::Stmt(x)) => x,
+            Ok(_) => return self.print_node(id),
+            Err(()) => return self.line("<error>", id),
+        };
+        let label = match stmt.label {
+            Some(label) => format!("{}: ", label),
+            None => String::new(),
+        };
+        match stmt.kind {
+            StmtKind::Null => self.line(format!("{};", label), id),
+            StmtKind::Block(ref stmts) => {
+                self.line(format!("{}begin", label), id)?;
+                self.indent += 1;
+                for &stmt in stmts {
+                    self.print_stmt(stmt)?;
+                }
+                self.indent -= 1;
+                self.plain("end")
+            }
+            StmtKind::Assign { lhs, rhs, kind } => {
+                let lhs = self.render_expr(lhs);
+                let (op, delay) = match kind {
+                    AssignKind::Block(op) => (assign_op(op), String::new()),
+                    AssignKind::Nonblock => ("<=", String::new()),
+                    AssignKind::NonblockDelay(delay) => {
+                        ("<=", format!("#{} ", self.render_expr(delay)))
+                    }
+                };
+                let rhs = self.render_expr(rhs);
+                self.line(format!("{}{} {} {}{};", label, lhs, op, delay, rhs), id)
+            }
+            StmtKind::Timed { ref control, stmt } => {
+                let control = self.render_timing_control(control);
+                self.line(format!("{}{}", label, control), id)?;
+                self.indent += 1;
+                self.print_stmt(stmt)?;
+                self.indent -= 1;
+                Ok(())
+            }
+            StmtKind::Expr(expr) => {
+                let text = format!("{}{};", label, self.render_expr(expr));
+                self.line(text, id)
+            }
+            StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                let text = format!("{}if ({})", label, self.render_expr(cond));
+                self.line(text, id)?;
+                self.indent += 1;
+                self.print_stmt(main_stmt)?;
+                self.indent -= 1;
+                if let Some(else_stmt) = else_stmt {
+                    self.plain("else")?;
+                    self.indent += 1;
+                    self.print_stmt(else_stmt)?;
+                    self.indent -= 1;
+                }
+                Ok(())
+            }
+            StmtKind::Loop { kind, body } => {
+                let text = match kind {
+                    LoopKind::Forever => "forever".to_string(),
+                    LoopKind::Repeat(count) => format!("repeat ({})", self.render_expr(count)),
+                    LoopKind::While(cond) => format!("while ({})", self.render_expr(cond)),
+                    LoopKind::Do(cond) => format!("do /* while ({}) */", self.render_expr(cond)),
+                    LoopKind::For(init, cond, step) => format!(
+                        "for ({}; {}; {})",
+                        self.render_node(init),
+                        self.render_expr(cond),
+                        self.render_expr(step)
+                    ),
+                };
+                self.line(format!("{}{}", label, text), id)?;
+                self.indent += 1;
+                self.print_stmt(body)?;
+                self.indent -= 1;
+                Ok(())
+            }
+            StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.print_stmt(stmt)?;
+                }
+                Ok(())
+            }
+            StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                let kw = match kind {
+                    ast::CaseKind::Normal => "case",
+                    ast::CaseKind::DontCareZ => "casez",
+                    ast::CaseKind::DontCareXZ => "casex",
+                };
+                let text = format!("{}{} ({})", label, kw, self.render_expr(expr));
+                self.line(text, id)?;
+                self.indent += 1;
+                for (exprs, stmt) in ways {
+                    let exprs: Vec<_> = exprs.iter().map(|&e| self.render_expr(e)).collect();
+                    let text = format!("{}:", exprs.join(", "));
+                    self.emit(text, None)?;
+                    self.indent += 1;
+                    self.print_stmt(*stmt)?;
+                    self.indent -= 1;
+                }
+                if let Some(default) = default {
+                    self.plain("default:")?;
+                    self.indent += 1;
+                    self.print_stmt(default)?;
+                    self.indent -= 1;
+                }
+                self.indent -= 1;
+                self.plain("endcase")
+            }
+            StmtKind::Ast(ast) => {
+                let text = format!("{}/* {} */", label, ast.span().extract());
+                self.line(text, id)
+            }
+        }
+    }
+
+    fn render_timing_control(&mut self, control: &TimingControl) -> String {
+        match *control {
+            TimingControl::Delay(delay) => format!("#{}", self.render_expr(delay)),
+            TimingControl::ImplicitEvent => "@*".to_string(),
+            TimingControl::ExplicitEvent(id) => match self.cx.hir_of(id) {
+                Ok(HirNode::EventExpr(x)) => {
+                    let events: Vec<_> = x
+                        .events
+                        .iter()
+                        .map(|event| {
+                            let mut text = match event.edge {
+                                ast::EdgeIdent::Implicit => String::new(),
+                                ast::EdgeIdent::Edge => "edge ".to_string(),
+                                ast::EdgeIdent::Posedge => "posedge ".to_string(),
+                                ast::EdgeIdent::Negedge => "negedge ".to_string(),
+                            };
+                            text += &self.render_expr(event.expr);
+                            for &iff in &event.iff {
+                                text += &format!(" iff {}", self.render_expr(iff));
+                            }
+                            text
+                        })
+                        .collect();
+                    format!("@({})", events.join(" or "))
+                }
+                _ => format!("@({})", self.render_node(id)),
+            },
+        }
+    }
+
+    fn render_genvar(&mut self, decl: &GenvarDecl) -> String {
+        match decl.init {
+            Some(init) => format!("genvar {} = {}", decl.name, self.render_expr(init)),
+            None => format!("genvar {}", decl.name),
+        }
+    }
+
+    /// Render a node that may either be an expression or a type.
+    fn render_node(&mut self, id: NodeId) -> String {
+        match self.cx.hir_of(id) {
+            Ok(HirNode::Expr(_)) => self.render_expr(id),
+            Ok(HirNode::Type(_)) => self.render_type(id),
+            Ok(HirNode::Stmt(x)) => match x.kind {
+                StmtKind::Assign { lhs, rhs, kind } => {
+                    let lhs = self.render_expr(lhs);
+                    let op = match kind {
+                        AssignKind::Block(op) => assign_op(op),
+                        _ => "<=",
+                    };
+                    format!("{} {} {}", lhs, op, self.render_expr(rhs))
+                }
+                StmtKind::Expr(expr) => self.render_expr(expr),
+                _ => self.cx.span(id).extract(),
+            },
+            Ok(HirNode::VarDecl(x)) => {
+                let mut text = format!("{} {}", self.render_type(x.ty), x.name);
+                if let Some(init) = x.init {
+                    text += &format!(" = {}", self.render_expr(init));
+                }
+                text
+            }
+            _ => self.cx.span(id).extract(),
+        }
+    }
+
+    fn render_type(&mut self, id: NodeId) -> String {
+        match self.cx.hir_of(id) {
+            Ok(HirNode::Type(x)) => self.render_type_kind(&x.kind),
+            _ => self.cx.span(id).extract(),
+        }
+    }
+
+    fn render_type_kind(&mut self, kind: &TypeKind) -> String {
+        match *kind {
+            TypeKind::Implicit => "<implicit>".to_string(),
+            TypeKind::Builtin(builtin) => match builtin {
+                BuiltinType::Void => "void",
+                BuiltinType::Bit => "bit",
+                BuiltinType::Logic => "logic",
+                BuiltinType::Byte => "byte",
+                BuiltinType::ShortInt => "shortint",
+                BuiltinType::Int => "int",
+                BuiltinType::Integer => "integer",
+                BuiltinType::LongInt => "longint",
+                BuiltinType::Time => "time",
+                BuiltinType::String => "string",
+            }
+            .to_string(),
+            TypeKind::Named(name) => format!("{}", name),
+            TypeKind::Struct(ref members) => {
+                let members: Vec<_> = members
+                    .iter()
+                    .map(|&id| format!("{}; ", self.cx.span(id).extract()))
+                    .collect();
+                format!("struct {{ {}}}", members.concat())
+            }
+            TypeKind::PackedArray(ref inner, lhs, rhs) => {
+                let inner = self.render_type_kind(inner);
+                format!(
+                    "{} [{}:{}]",
+                    inner,
+                    self.render_expr(lhs),
+                    self.render_expr(rhs)
+                )
+            }
+            TypeKind::Scope(id, name) => format!("{}::{}", self.render_type(id), name),
+            TypeKind::Enum(ref variants, base) => {
+                let mut text = "enum ".to_string();
+                if let Some(base) = base {
+                    text += &format!("{} ", self.render_type(base));
+                }
+                let variants: Vec<_> = variants
+                    .iter()
+                    .map(|&(name, id)| match self.cx.hir_of(id) {
+                        Ok(HirNode::EnumVariant(EnumVariant {
+                            value: Some(value), ..
+                        })) => format!("{} = {}", name, self.render_expr(*value)),
+                        _ => format!("{}", name),
+                    })
+                    .collect();
+                text += &format!("{{ {} }}", variants.join(", "));
+                text
+            }
+            TypeKind::RefExpr(id) => format!("type({})", self.render_expr(id)),
+            TypeKind::RefType(id) => format!("type({})", self.render_type(id)),
+        }
+    }
+
+    /// Render an expression and note it for annotation on the current line.
+    fn render_expr(&mut self, id: NodeId) -> String {
+        let expr = match self.cx.hir_of(id) {
+            Ok(HirNode::Expr(x)) => x,
+            Ok(HirNode::Type(_)) => return self.render_type(id),
+            _ => return self.cx.span(id).extract(),
+        };
+        let index = self.notes.len();
+        self.notes.push((id, String::new()));
+        let text = self.render_expr_kind(expr);
+        self.notes[index].1 = text.clone();
+        text
+    }
+
+    fn render_expr_kind(&mut self, expr: &'a Expr<'a>) -> String {
+        match expr.kind {
+            ExprKind::IntConst { .. }
+            | ExprKind::UnsizedConst(_)
+            | ExprKind::TimeConst(_)
+            | ExprKind::RealConst(_)
+            | ExprKind::StringConst(_) => expr.span().extract(),
+            ExprKind::Ident(name) => format!("{}", name),
+            ExprKind::Unary(op, arg) => {
+                let arg = self.render_expr(arg);
+                match op {
+                    UnaryOp::PostInc => format!("{}++", arg),
+                    UnaryOp::PostDec => format!("{}--", arg),
+                    _ => format!("{}{}", unary_op(op), arg),
+                }
+            }
+            ExprKind::Binary(op, lhs, rhs) => format!(
+                "({} {} {})",
+                self.render_expr(lhs),
+                binary_op(op),
+                self.render_expr(rhs)
+            ),
+            ExprKind::Field(target, name) => format!("{}.{}", self.render_expr(target), name),
+            ExprKind::Index(target, mode) => {
+                let target = self.render_expr(target);
+                let index = match mode {
+                    IndexMode::One(index) => self.render_expr(index),
+                    IndexMode::Many(mode, lhs, rhs) => {
+                        let sep = match mode {
+                            ast::RangeMode::Absolute => ":",
+                            ast::RangeMode::RelativeUp => "+:",
+                            ast::RangeMode::RelativeDown => "-:",
+                        };
+                        format!("{}{}{}", self.render_expr(lhs), sep, self.render_expr(rhs))
+                    }
+                };
+                format!("{}[{}]", target, index)
+            }
+            ExprKind::Builtin(call) => self.render_builtin(expr, call),
+            ExprKind::Ternary(cond, true_expr, false_expr) => format!(
+                "({} ? {} : {})",
+                self.render_expr(cond),
+                self.render_expr(true_expr),
+                self.render_expr(false_expr)
+            ),
+            ExprKind::Scope(target, name) => format!("{}::{}", self.render_expr(target), name),
+            ExprKind::PositionalPattern(ref exprs) => {
+                let exprs: Vec<_> = exprs.iter().map(|&e| self.render_expr(e)).collect();
+                format!("'{{{}}}", exprs.join(", "))
+            }
+            ExprKind::NamedPattern(ref mappings) => {
+                let mappings: Vec<_> = mappings
+                    .iter()
+                    .map(|&(key, value)| {
+                        let key = match key {
+                            PatternMapping::Type(ty) => self.render_type(ty),
+                            PatternMapping::Member(expr) => self.render_expr(expr),
+                            PatternMapping::Default => "default".to_string(),
+                        };
+                        format!("{}: {}", key, self.render_expr(value))
+                    })
+                    .collect();
+                format!("'{{{}}}", mappings.join(", "))
+            }
+            ExprKind::RepeatPattern(count, ref exprs) => {
+                let count = self.render_expr(count);
+                let exprs: Vec<_> = exprs.iter().map(|&e| self.render_expr(e)).collect();
+                format!("'{{{}{{{}}}}}", count, exprs.join(", "))
+            }
+            ExprKind::Concat(repeat, ref exprs) => {
+                let repeat = repeat.map(|r| self.render_expr(r));
+                let exprs: Vec<_> = exprs.iter().map(|&e| self.render_expr(e)).collect();
+                match repeat {
+                    Some(repeat) => format!("{{{}{{{}}}}}", repeat, exprs.join(", ")),
+                    None => format!("{{{}}}", exprs.join(", ")),
+                }
+            }
+            ExprKind::Cast(ty, expr) => {
+                format!("{}'({})", self.render_type(ty), self.render_expr(expr))
+            }
+            ExprKind::CastSign(sign, expr) => {
+                format!("{}'({})", sign.value, self.render_expr(expr))
+            }
+            ExprKind::CastSize(size, expr) => {
+                format!("{}'({})", self.render_expr(size), self.render_expr(expr))
+            }
+            ExprKind::Inside(expr, ref ranges) => {
+                let expr = self.render_expr(expr);
+                let ranges: Vec<_> = ranges
+                    .iter()
+                    .map(|r| match r.value {
+                        InsideRange::Single(id) => self.render_expr(id),
+                        InsideRange::Range(lo, hi) => {
+                            format!("[{}:{}]", self.render_expr(lo), self.render_expr(hi))
+                        }
+                    })
+                    .collect();
+                format!("({} inside {{{}}})", expr, ranges.join(", "))
+            }
+            ExprKind::FunctionCall(decl, args) => {
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|arg| match arg.expr {
+                        Some(ref expr) => self.render_expr(expr.id()),
+                        None => String::new(),
+                    })
+                    .collect();
+                format!("{}({})", decl.prototype.name, args.join(", "))
+            }
+            ExprKind::Assign { .. } | ExprKind::Ast(_) => {
+                format!("/* {} */", expr.span().extract())
+            }
+        }
+    }
+
+    fn render_builtin(&mut self, expr: &'a Expr<'a>, call: BuiltinCall<'a>) -> String {
+        let (name, arg) = match call {
+            BuiltinCall::Unsupported => return format!("/* {} */", expr.span().extract()),
+            BuiltinCall::Clog2(arg) => ("$clog2", arg),
+            BuiltinCall::Signed(arg) => ("$signed", arg),
+            BuiltinCall::Unsigned(arg) => ("$unsigned", arg),
+            BuiltinCall::CountOnes(arg) => ("$countones", arg),
+            BuiltinCall::OneHot(arg) => ("$onehot", arg),
+            BuiltinCall::OneHot0(arg) => ("$onehot0", arg),
+            BuiltinCall::Rtoi(arg) => ("$rtoi", arg),
+            BuiltinCall::Itor(arg) => ("$itor", arg),
+            BuiltinCall::RealToBits(arg) => ("$realtobits", arg),
+            BuiltinCall::BitsToReal(arg) => ("$bitstoreal", arg),
+            BuiltinCall::ShortRealToBits(arg) => ("$shortrealtobits", arg),
+            BuiltinCall::BitsToShortReal(arg) => ("$bitstoshortreal", arg),
+            // The remaining builtins refer to AST nodes directly.
+            _ => return expr.span().extract(),
+        };
+        format!("{}({})", name, self.render_expr(arg))
+    }
+}
+
+/// Get the SystemVerilog symbol of a unary operator.
+fn unary_op(op: UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Pos => "+",
+        UnaryOp::Neg => "-",
+        UnaryOp::BitNot => "~",
+        UnaryOp::LogicNot => "!",
+        UnaryOp::PreInc | UnaryOp::PostInc => "++",
+        UnaryOp::PreDec | UnaryOp::PostDec => "--",
+        UnaryOp::RedAnd => "&",
+        UnaryOp::RedNand => "~&",
+        UnaryOp::RedOr => "|",
+        UnaryOp::RedNor => "~|",
+        UnaryOp::RedXor => "^",
+        UnaryOp::RedXnor => "^~",
+    }
+}
+
+/// Get the SystemVerilog symbol of a binary operator.
+fn binary_op(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Mod => "%",
+        BinaryOp::Pow => "**",
+        BinaryOp::Eq => "==",
+        BinaryOp::Neq => "!=",
+        BinaryOp::Lt => "<",
+        BinaryOp::Leq => "<=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Geq => ">=",
+        BinaryOp::LogicAnd => "&&",
+        BinaryOp::LogicOr => "||",
+        BinaryOp::BitAnd => "&",
+        BinaryOp::BitNand => "~&",
+        BinaryOp::BitOr => "|",
+        BinaryOp::BitNor => "~|",
+        BinaryOp::BitXor => "^",
+        BinaryOp::BitXnor => "~^",
+        BinaryOp::LogicShL => "<<",
+        BinaryOp::LogicShR => ">>",
+        BinaryOp::ArithShL => "<<<",
+        BinaryOp::ArithShR => ">>>",
+    }
+}
+
+/// Get the SystemVerilog symbol of an assignment operator.
+fn assign_op(op: ast::AssignOp) -> &'static str {
+    match op {
+        ast::AssignOp::Identity => "=",
+        ast::AssignOp::Add => "+=",
+        ast::AssignOp::Sub => "-=",
+        ast::AssignOp::Mul => "*=",
+        ast::AssignOp::Div => "/=",
+        ast::AssignOp::Mod => "%=",
+        ast::AssignOp::BitAnd => "&=",
+        ast::AssignOp::BitOr => "|=",
+        ast::AssignOp::BitXor => "^=",
+        ast::AssignOp::LogicShL => "<<=",
+        ast::AssignOp::LogicShR => ">>=",
+        ast::AssignOp::ArithShL => "<<<=",
+        ast::AssignOp::ArithShR => ">>>=",
+    }
+}
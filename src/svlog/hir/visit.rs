@@ -224,6 +224,7 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         | ExprKind::IntConst { .. }
         | ExprKind::UnsizedConst(_)
         | ExprKind::TimeConst(_)
+        | ExprKind::RealConst(_)
         | ExprKind::StringConst(_) => (),
         ExprKind::Ident(x) => {
             visitor.visit_ident(x);
@@ -251,14 +252,22 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
             }
         }
         ExprKind::Builtin(BuiltinCall::Clog2(arg))
+        | ExprKind::Builtin(BuiltinCall::CountOnes(arg))
+        | ExprKind::Builtin(BuiltinCall::OneHot(arg))
+        | ExprKind::Builtin(BuiltinCall::OneHot0(arg))
         | ExprKind::Builtin(BuiltinCall::Signed(arg))
-        | ExprKind::Builtin(BuiltinCall::Unsigned(arg)) => {
+        | ExprKind::Builtin(BuiltinCall::Unsigned(arg))
+        | ExprKind::Builtin(BuiltinCall::Rtoi(arg))
+        | ExprKind::Builtin(BuiltinCall::Itor(arg))
+        | ExprKind::Builtin(BuiltinCall::RealToBits(arg))
+        | ExprKind::Builtin(BuiltinCall::BitsToReal(arg))
+        | ExprKind::Builtin(BuiltinCall::ShortRealToBits(arg))
+        | ExprKind::Builtin(BuiltinCall::BitsToShortReal(arg)) => {
             visitor.visit_node_with_id(arg, false);
         }
-        ExprKind::Builtin(BuiltinCall::CountOnes(arg))
-        | ExprKind::Builtin(BuiltinCall::OneHot(arg))
-        | ExprKind::Builtin(BuiltinCall::OneHot0(arg))
-        | ExprKind::Builtin(BuiltinCall::IsUnknown(arg)) => {
+        ExprKind::Builtin(BuiltinCall::IsUnknown(arg))
+        | ExprKind::Builtin(BuiltinCall::Dimensions(arg))
+        | ExprKind::Builtin(BuiltinCall::UnpackedDimensions(arg)) => {
             visitor.visit_node_with_id(arg.id(), false);
         }
         ExprKind::Builtin(BuiltinCall::ArrayDim(_, arg, dim)) => {
@@ -270,6 +279,20 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         ExprKind::Builtin(BuiltinCall::Bits(arg)) => {
             visitor.visit_node_with_id(arg.id(), false);
         }
+        ExprKind::Builtin(BuiltinCall::Display(_, _, args)) => {
+            for arg in args {
+                if let Some(ref expr) = arg.expr {
+                    visitor.visit_node_with_id(expr.id(), false);
+                }
+            }
+        }
+        ExprKind::Builtin(BuiltinCall::ReadMem(_, file, mem, start, end)) => {
+            visitor.visit_node_with_id(file, false);
+            visitor.visit_node_with_id(mem, true);
+            for arg in start.into_iter().chain(end) {
+                visitor.visit_node_with_id(arg, false);
+            }
+        }
         ExprKind::Ternary(cond, true_expr, false_expr) => {
             visitor.visit_node_with_id(cond, false);
             visitor.visit_node_with_id(true_expr, lvalue);
@@ -0,0 +1,186 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Implicitly declared nets.
+//!
+//! An identifier that is connected to a port of an instance, or assigned by a
+//! continuous assignment in a module body, and that is not declared, becomes
+//! an implicit scalar net of the default net type. That type is `wire`, unless
+//! a `` `default_nettype `` directive earlier in the same file selects another
+//! one, or disallows implicit nets with `` `default_nettype none ``. The nets
+//! are synthesized as net declarations, which the module body declares in
+//! addition to its explicit declarations, and which names resolve to if they
+//! are not found otherwise.
+
+use crate::{
+    crate_prelude::*,
+    resolver::{Def, DefNode, DefVis},
+};
+
+/// An implicitly declared net.
+#[derive(Debug)]
+pub struct ImplicitNet<'a> {
+    /// The reference that declares the net.
+    pub name: Spanned<Name>,
+    /// The lexical order of the reference.
+    pub order: usize,
+    /// The synthesized declaration, or `None` if implicit nets are disallowed
+    /// by a `` `default_nettype none `` directive.
+    pub decl: Option<&'a ast::NetDecl<'a>>,
+    /// The definition of the net's name, if it is declared.
+    pub def: Option<Def<'a>>,
+    /// The `` `default_nettype `` directive in effect, if any.
+    pub directive: Option<ast::DefaultNettype>,
+}
+
+/// Determine the nets implicitly declared in a module body.
+#[moore_derive::query]
+pub(crate) fn implicit_nets<'a>(
+    cx: &impl Context<'a>,
+    module: &'a ast::Module<'a>,
+) -> &'a [ImplicitNet<'a>] {
+    let directive = default_nettype(module);
+    let net_type = match directive {
+        Some(d) => d.net_type,
+        None => Some(ast::NetType::Wire),
+    };
+    let mut refs = vec![];
+    collect_refs(&module.items, &mut refs);
+
+    let mut nets: Vec<ImplicitNet> = vec![];
+    for expr in refs {
+        let name = match expr.data {
+            ast::IdentExpr(name) => name,
+            _ => continue,
+        };
+        if nets.iter().any(|net| net.name.value == name.value) {
+            continue;
+        }
+        match cx.resolve_local(name.value, cx.scope_location(expr), false) {
+            Ok(None) => (),
+            _ => continue,
+        }
+        let decl = net_type.map(|net_type| {
+            let span = name.span;
+            let decl = cx.arena().alloc_ast_net_decl(ast::NetDecl::new(
+                span,
+                ast::NetDeclData {
+                    net_type,
+                    strength: None,
+                    kind: ast::NetKind::None,
+                    ty: ast::Type::new(
+                        span,
+                        ast::TypeData {
+                            kind: ast::TypeKind::new(span, ast::ImplicitType),
+                            sign: ast::TypeSign::None,
+                            dims: vec![],
+                        },
+                    ),
+                    delay: None,
+                    names: vec![ast::VarDeclName::new(
+                        span,
+                        ast::VarDeclNameData {
+                            name: name.value,
+                            name_span: span,
+                            dims: vec![],
+                            init: None,
+                        },
+                    )],
+                },
+            ));
+            decl.link_attach(module, expr.order());
+            cx.register_ast(decl);
+            decl
+        });
+        let def = decl.map(|decl| Def {
+            node: DefNode::Ast(&decl.names[0]),
+            name,
+            vis: DefVis::LOCAL | DefVis::HIERARCHICAL,
+            may_override: false,
+            ordered: false,
+        });
+        nets.push(ImplicitNet {
+            name,
+            order: expr.order(),
+            decl,
+            def,
+            directive,
+        });
+    }
+    cx.arena().alloc_implicit_nets(nets)
+}
+
+/// Find the implicit net a name refers to at a location in a module.
+///
+/// Only references after the one that declares the net refer to it. This is
+/// used to resolve names that are not declared otherwise.
+pub(crate) fn find_implicit_net<'a>(
+    cx: &impl Context<'a>,
+    name: Name,
+    node: &'a dyn ast::AnyNode<'a>,
+    order: usize,
+) -> Option<&'a ImplicitNet<'a>> {
+    let mut parent = Some(node);
+    while let Some(p) = parent {
+        if let ast::AllNode::Module(module) = p.as_all() {
+            return cx
+                .implicit_nets(module)
+                .iter()
+                .find(|net| net.name.value == name && net.order <= order);
+        }
+        parent = p.get_parent();
+    }
+    None
+}
+
+/// Add a note to an error about a name that cannot be found, if the name
+/// would be an implicit net that is disallowed by `` `default_nettype none ``.
+pub(crate) fn note_disallowed_net(net: Option<&ImplicitNet>, diag: DiagBuilder2) -> DiagBuilder2 {
+    match net.and_then(|net| net.directive) {
+        Some(directive) if directive.net_type.is_none() => diag
+            .add_note("Implicit nets are disallowed by `default_nettype none`:")
+            .span(directive.span),
+        _ => diag,
+    }
+}
+
+/// Determine the `` `default_nettype `` directive in effect for a module.
+fn default_nettype(module: &ast::Module) -> Option<ast::DefaultNettype> {
+    let mut parent = module.get_parent();
+    while let Some(p) = parent {
+        if let ast::AllNode::SourceFile(file) = p.as_all() {
+            return file
+                .nettypes
+                .iter()
+                .filter(|d| d.span.source == module.span.source && d.span.end <= module.span.begin)
+                .last()
+                .cloned();
+        }
+        parent = p.get_parent();
+    }
+    None
+}
+
+/// Collect the expressions connected to instance ports or assigned by
+/// continuous assignments in a list of items.
+fn collect_refs<'a>(items: &'a [ast::Item<'a>], into: &mut Vec<&'a ast::Expr<'a>>) {
+    for item in items {
+        match item.data {
+            ast::ItemData::ContAssign(ref assign) => {
+                into.extend(assign.assignments.iter().map(|(lhs, _)| lhs));
+            }
+            ast::ItemData::Inst(ref inst) => {
+                for name in &inst.names {
+                    for conn in &name.conns {
+                        match conn.data {
+                            ast::PortConnData::Named(_, ast::PortConnMode::Connected(ref expr))
+                            | ast::PortConnData::Positional(ref expr) => into.push(expr),
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            ast::ItemData::GenerateRegion(_, ref items) => collect_refs(items, into),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Elaboration-time evaluation of `initial` procedures.
+//!
+//! The statements at the beginning of an `initial` procedure that assign a
+//! constant to a variable, or load a memory with `$readmemb` or `$readmemh`,
+//! are performed during elaboration. The resulting values become the initial
+//! values of the variables, such that register reset values appear directly on
+//! the declared signals. The remaining statements are left to the process
+//! emitted for the procedure.
+//!
+//! Folding an assignment into the initial value of a variable removes the
+//! value change the assignment causes at time zero. Variables that an event
+//! control, a continuous assignment, or an instance port connection reads
+//! would miss this change, so they are never folded.
+
+use crate::{
+    crate_prelude::*,
+    hir::{HirNode, Visitor},
+    readmem::load_memory,
+    value::Value,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// The parts of the `initial` procedures of a module that are performed during
+/// elaboration.
+#[derive(Debug, Default)]
+pub struct InitialValues<'a> {
+    /// The initial value of every variable declaration that is assigned.
+    pub values: HashMap<NodeId, Value<'a>>,
+    /// The statements that have been performed.
+    pub stmts: HashSet<NodeId>,
+}
+
+/// Perform the statements at the beginning of the `initial` procedures of a
+/// module or generate block during elaboration.
+///
+/// A statement is performed if it is a blocking assignment of a constant, or a
+/// memory load with a constant file name and addresses, and it targets an
+/// entire variable declared in the block itself that is not observed by an
+/// event control, a continuous assignment, or an instance port connection.
+/// The statements of a procedure are performed up to the first statement that
+/// is not such an assignment or load.
+#[moore_derive::query]
+pub(crate) fn initial_values<'a>(
+    cx: &impl Context<'a>,
+    Ref(block): Ref<'a, hir::ModuleBlock>,
+    env: ParamEnv,
+) -> Result<Arc<InitialValues<'a>>> {
+    let mut observed = None;
+    let mut inits = InitialValues::default();
+    for &proc_id in &block.procs {
+        let prok = match cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        if prok.kind != ast::ProcedureKind::Initial {
+            continue;
+        }
+        let stmts = match cx.hir_of(prok.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Block(ref ids),
+                ..
+            }) => ids.clone(),
+            _ => vec![prok.stmt],
+        };
+        for stmt_id in stmts {
+            let (decl_id, value) = match perform_stmt(cx, block, stmt_id, env, &inits.values)? {
+                Some(x) => x,
+                None => break,
+            };
+            // Only analyze the rest of the block once there is something to
+            // fold.
+            if observed.is_none() {
+                observed = Some(observed_decls(cx, block, env)?);
+            }
+            if observed.as_ref().unwrap().contains(&decl_id) {
+                break;
+            }
+            inits.values.insert(decl_id, value);
+            inits.stmts.insert(stmt_id);
+        }
+    }
+    Ok(Arc::new(inits))
+}
+
+/// Perform a statement of an `initial` procedure during elaboration.
+///
+/// Returns the variable declaration the statement assigns and its new value,
+/// or `None` if the statement cannot be performed.
+fn perform_stmt<'a>(
+    cx: &impl Context<'a>,
+    block: &hir::ModuleBlock,
+    stmt_id: NodeId,
+    env: ParamEnv,
+    values: &HashMap<NodeId, Value<'a>>,
+) -> Result<Option<(NodeId, Value<'a>)>> {
+    let hir = match cx.hir_of(stmt_id)? {
+        HirNode::Stmt(x) => x,
+        _ => return Ok(None),
+    };
+    match hir.kind {
+        // `x = <const>`
+        hir::StmtKind::Assign {
+            lhs,
+            rhs,
+            kind: kind @ hir::AssignKind::Block(ast::AssignOp::Identity),
+        } => {
+            let assign = cx.mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+            let decl_id = match target_decl(cx, block, assign.lhs)? {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            if !assign.rhs.is_const() {
+                return Ok(None);
+            }
+            let value = cx.const_mir_rvalue(assign.rhs.into());
+            if value.is_error() {
+                return Err(());
+            }
+            Ok(Some((decl_id, value)))
+        }
+
+        // `$readmemh(<const>, x, ...)`
+        hir::StmtKind::Expr(expr_id) => {
+            match cx.hir_of(expr_id)? {
+                HirNode::Expr(hir::Expr {
+                    kind: hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem(..)),
+                    ..
+                }) => (),
+                _ => return Ok(None),
+            }
+            let mir = cx.mir_rvalue(expr_id, env);
+            let (radix, file, mem, start, end) = match mir.kind {
+                mir::RvalueKind::ReadMem {
+                    radix,
+                    file,
+                    mem,
+                    start,
+                    end,
+                } => (radix, file, mem, start, end),
+                _ => return Err(()),
+            };
+            let decl_id = match target_decl(cx, block, mem)? {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            let is_const = |x: Option<&mir::Rvalue>| x.map(|x| x.is_const()).unwrap_or(true);
+            if !file.is_const() || !is_const(start) || !is_const(end) {
+                return Ok(None);
+            }
+            let init = match values.get(&decl_id) {
+                Some(&x) => x,
+                None => initial_value_of(cx, decl_id, mem.ty, env)?,
+            };
+            let value = load_memory(cx, radix, file, init, start, end, mir.span)?;
+            Ok(Some((decl_id, value)))
+        }
+
+        _ => Ok(None),
+    }
+}
+
+/// Determine the variable declaration written by an lvalue, if the lvalue
+/// covers the entire variable and the variable is declared in `block`.
+fn target_decl<'a>(
+    cx: &impl Context<'a>,
+    block: &hir::ModuleBlock,
+    lvalue: &mir::Lvalue<'a>,
+) -> Result<Option<NodeId>> {
+    match lvalue.kind {
+        mir::LvalueKind::Var(id) if block.decls.contains(&id) => match cx.hir_of(id)? {
+            HirNode::VarDecl(x) if x.kind.is_var() => Ok(Some(id)),
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Determine the declarations whose value changes are observed within a module
+/// or generate block by something other than a regular procedure statement.
+fn observed_decls<'a>(
+    cx: &impl Context<'a>,
+    block: &'a hir::ModuleBlock,
+    env: ParamEnv,
+) -> Result<HashSet<NodeId>> {
+    let mut collector = ObservedCollector {
+        cx,
+        env,
+        observed: HashSet::new(),
+        failed: false,
+    };
+
+    // Continuous assignments and instance port connections re-evaluate
+    // whenever any signal they read changes.
+    for &assign_id in &block.assigns {
+        collector.add_reads(assign_id);
+    }
+    for &inst_id in &block.insts {
+        let inst = match cx.hir_of(inst_id)? {
+            HirNode::Inst(x) => x,
+            _ => unreachable!(),
+        };
+        let pos_ports = inst.pos_ports.iter().flat_map(|&(_, p)| p);
+        let named_ports = inst.named_ports.iter().flat_map(|&(_, _, p)| p);
+        for p in pos_ports.chain(named_ports) {
+            collector.add_reads(p);
+        }
+    }
+
+    // Combinational and latch processes are sensitive to everything they
+    // read. All other processes are sensitive to their event controls.
+    for &proc_id in &block.procs {
+        let prok = match cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        match prok.kind {
+            ast::ProcedureKind::AlwaysComb | ast::ProcedureKind::AlwaysLatch => {
+                collector.add_reads(prok.stmt)
+            }
+            _ => collector.visit_proc(prok),
+        }
+    }
+
+    if collector.failed {
+        return Err(());
+    }
+    Ok(collector.observed)
+}
+
+/// A visitor that collects the declarations read by event controls.
+struct ObservedCollector<'a, C> {
+    cx: &'a C,
+    env: ParamEnv,
+    observed: HashSet<NodeId>,
+    failed: bool,
+}
+
+impl<'a, 'gcx: 'a, C> ObservedCollector<'a, C>
+where
+    C: Context<'gcx>,
+{
+    /// Mark all declarations read by a node as observed.
+    fn add_reads(&mut self, node_id: NodeId) {
+        match self.cx.accessed_nodes(node_id, self.env) {
+            Ok(acc) => self.observed.extend(acc.read.iter().map(|x| x.id())),
+            Err(()) => self.failed = true,
+        }
+    }
+}
+
+impl<'a, 'gcx: 'a, C> Visitor<'gcx> for ObservedCollector<'a, C>
+where
+    C: Context<'gcx>,
+{
+    type Context = C;
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt) {
+        if let hir::StmtKind::Timed {
+            control: hir::TimingControl::ImplicitEvent,
+            stmt: inner,
+        } = stmt.kind
+        {
+            self.add_reads(inner);
+        }
+        hir::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, _expr: &'gcx hir::Expr, _lvalue: bool) {}
+
+    fn visit_event(&mut self, event: &'gcx hir::Event) {
+        self.add_reads(event.expr);
+        for &iff in &event.iff {
+            self.add_reads(iff);
+        }
+    }
+}
+
+/// Determine the value a variable declaration has before the `initial`
+/// procedures run.
+fn initial_value_of<'a>(
+    cx: &impl Context<'a>,
+    decl_id: NodeId,
+    ty: &'a ty::UnpackedType<'a>,
+    env: ParamEnv,
+) -> Result<Value<'a>> {
+    match cx.hir_of(decl_id)? {
+        HirNode::VarDecl(hir::VarDecl {
+            init: Some(init), ..
+        }) => Ok(cx.constant_value_of(*init, env)),
+        _ => Ok(cx.type_default_value(ty)),
+    }
+}
@@ -6,7 +6,7 @@
 use crate::{
     crate_prelude::*,
     hir::{self, HirNode},
-    port_list::AsPortedNode,
+    port_list::{AsPortedNode, ExtPort, PortedNode},
     resolver::InstTarget,
     Context, ParamEnv, ParamEnvData, ParamEnvSource, PortMapping,
 };
@@ -67,6 +67,29 @@ pub(crate) fn inst_details<'a>(
     // Determine the details of the instantiation target.
     let target = cx.inst_target_details(Ref(inst_target), env)?;
 
+    // Detect modules that instantiate themselves without changing any of
+    // their parameters, which would recurse indefinitely.
+    if let InstTarget::Module(node) = target.kind {
+        if cx.param_env_data(env).module() == Some(node.id()) {
+            let module = cx.hir_of_module(node)?;
+            if is_identical_recursion(cx, module, env, target.inner_env)? {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "module `{}` instantiates itself with identical parameters",
+                        module.ast.name
+                    ))
+                    .code("SV0010")
+                    .span(inst_target.ast.span())
+                    .add_note(
+                        "This would recurse indefinitely; recursive instantiations must change \
+                         at least one parameter",
+                    ),
+                );
+                return Err(());
+            }
+        }
+    }
+
     // Determine the port connections of the instantiations. Connections
     // are made to the module's external ports, and must later be mapped
     // to the actual internal ports in a second step.
@@ -99,9 +122,13 @@ pub(crate) fn inst_details<'a>(
 
         // Check if the port is actually an interface.
         let ty = cx.packed_type_from_ast(Ref(data.ty), target.inner_env, None);
-        if ty.get_interface().is_none() {
-            continue;
-        }
+        let intf = match ty.get_interface() {
+            Some(x) => x,
+            None => continue,
+        };
+
+        // Make sure the connected interface is compatible with the port.
+        check_interface_connection(cx, int_port.name, intf, assigned)?;
 
         // Add this parametrization.
         trace!(
@@ -127,6 +154,15 @@ pub(crate) fn inst_details<'a>(
         target.inner_env
     };
 
+    // Check the port connections.
+    check_port_connections(
+        cx,
+        inst,
+        target.kind.as_any().as_all().get_ported().unwrap(),
+        &port_mapping,
+        inner_env,
+    )?;
+
     // Wrap everything up.
     Ok(Arc::new(InstDetails {
         hir: inst,
@@ -174,6 +210,288 @@ pub(crate) fn inst_target_details<'a>(
     }))
 }
 
+/// Check that an interface port is connected to a compatible interface.
+///
+/// The connected expression must be an instance of the same interface. If
+/// both the port and the connection specify a modport, the two must be
+/// identical (see IEEE 1800-2017 §25.5).
+fn check_interface_connection<'a>(
+    cx: &impl Context<'a>,
+    port_name: Spanned<Name>,
+    port_intf: &'a ty::InterfaceType<'a>,
+    assigned: NodeEnvId,
+) -> Result<()> {
+    let span = cx.span(assigned.id());
+    let ty = match cx.self_determined_type(assigned.id(), assigned.env()) {
+        Some(ty) => ty,
+        None => return Ok(()),
+    };
+    if ty.is_error() {
+        return Err(());
+    }
+    let intf = match ty.resolve_full().core.get_interface() {
+        Some(x) => x,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` is not an interface; cannot connect it to interface port `{}`",
+                    span.extract(),
+                    port_name
+                ))
+                .code("SV0039")
+                .span(span)
+                .add_note(format!("`{}` has type `{}`", span.extract(), ty)),
+            );
+            return Err(());
+        }
+    };
+
+    // The connection must refer to the same interface.
+    if intf.ast != port_intf.ast {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "cannot connect {} to port `{}` of {}",
+                intf.ast, port_name, port_intf.ast
+            ))
+            .code("SV0039")
+            .span(span)
+            .add_note(format!("Port `{}` declared here:", port_name))
+            .span(port_name.span),
+        );
+        return Err(());
+    }
+
+    // If both sides specify a modport, they must be identical.
+    let (port_mp, conn_mp) = match (port_intf.modport, intf.modport) {
+        (Some(p), Some(c)) if p.name.value != c.name.value => (p, c),
+        _ => return Ok(()),
+    };
+    let port_mp = cx.resolve_modport(port_mp.name, port_intf.ast)?;
+    let conn_mp = cx.resolve_modport(conn_mp.name, intf.ast)?;
+    let mut d = DiagBuilder2::error(format!(
+        "modport `{}` does not match modport `{}` of port `{}`",
+        conn_mp.name, port_mp.name, port_name
+    ))
+    .code("SV0039")
+    .span(span);
+    for port in &port_mp.ports {
+        if let Some(other) = conn_mp.find_port(port.name.value) {
+            if other.dir != port.dir {
+                d = d
+                    .add_note(format!(
+                        "`{}` is {} in modport `{}`, but {} in modport `{}`",
+                        port.name, other.dir, conn_mp.name, port.dir, port_mp.name
+                    ))
+                    .span(other.span);
+            }
+        }
+    }
+    cx.emit(
+        d.add_note(format!(
+            "Port `{}` expects modport `{}`:",
+            port_name, port_mp.name
+        ))
+        .span(port_name.span),
+    );
+    Err(())
+}
+
+/// Check the port connections of an instantiation.
+///
+/// Warns about ports that are not connected and have no default value, inputs
+/// connected to expressions of a different width, and outputs connected to
+/// expressions that cannot be assigned to. Ports left open explicitly, as in
+/// `.x()`, are not reported.
+fn check_port_connections<'a>(
+    cx: &impl Context<'a>,
+    inst: &'a hir::Inst<'a>,
+    node: &'a dyn PortedNode<'a>,
+    ports: &PortMapping<'a>,
+    inner_env: ParamEnv,
+) -> Result<()> {
+    let port_list = cx.canonicalize_ports(node);
+    let declared_here = |d: DiagBuilder2, name: Spanned<Name>| {
+        d.add_note(format!(
+            "Port `{}` of instance `{}` declared here:",
+            name, inst.name
+        ))
+        .span(name.span)
+    };
+    let int_port = |port: &ExtPort<'a>| match port.exprs.as_slice() {
+        [expr] if expr.selects.is_empty() => Some(&port_list.int[expr.port]),
+        _ => None,
+    };
+
+    // Report the ports that are neither connected by position, by name, nor
+    // through a wildcard.
+    if !inst.has_wildcard_port {
+        for port in port_list.ext_pos.iter().skip(inst.pos_ports.len()) {
+            let name = match port.name {
+                Some(x) => x,
+                None => continue,
+            };
+            if inst
+                .named_ports
+                .iter()
+                .any(|&(_, n, _)| n.value == name.value)
+            {
+                continue;
+            }
+            let has_default = int_port(port)
+                .and_then(|p| p.data.as_ref())
+                .map(|data| data.default.is_some())
+                .unwrap_or(false);
+            if has_default {
+                continue;
+            }
+            let d = DiagBuilder2::warning(format!("port `{}` of {} is not connected", name, node))
+                .group("port-conn")
+                .span(inst.name.span)
+                .add_note(format!(
+                    "Connect it, or leave it open explicitly with `.{}()`",
+                    name
+                ));
+            cx.emit(declared_here(d, name));
+        }
+    }
+
+    // Check the connected expressions against the port directions and types.
+    for &(Ref(port), assigned) in &ports.0 {
+        let int = match int_port(port) {
+            Some(x) => x,
+            None => continue,
+        };
+        // Connections made through a wildcard refer to the port declaration.
+        let span = cx.span(assigned.id());
+        let within = span.source == inst.span.source
+            && span.begin >= inst.span.begin
+            && span.end <= inst.span.end;
+        let (span, text) = if within {
+            (span, span.extract())
+        } else {
+            (inst.name.span, int.name.to_string())
+        };
+        match int.dir {
+            ast::PortDir::Input => {
+                if is_unsized_literal(cx, assigned.id()) {
+                    continue;
+                }
+                let port_ty = cx.type_of_int_port(Ref(int), inner_env);
+                if port_ty.is_error() || port_ty.get_interface().is_some() {
+                    continue;
+                }
+                let expr_ty = match cx.self_determined_type(assigned.id(), assigned.env()) {
+                    Some(x) if !x.is_error() => x,
+                    _ => continue,
+                };
+                let (port_width, expr_width) =
+                    match (port_ty.get_bit_size(), expr_ty.get_bit_size()) {
+                        (Some(p), Some(e)) if p != e => (p, e),
+                        _ => continue,
+                    };
+                let d = DiagBuilder2::warning(format!(
+                    "`{}` is {} than input port `{}` of {}",
+                    text,
+                    if expr_width < port_width {
+                        "narrower"
+                    } else {
+                        "wider"
+                    },
+                    int.name,
+                    node
+                ))
+                .group("port-conn")
+                .span(span)
+                .add_note(format!(
+                    "`{}` has {} bits, but the port has {} bits",
+                    text, expr_width, port_width
+                ));
+                cx.emit(declared_here(d, int.name));
+            }
+            ast::PortDir::Output | ast::PortDir::Inout => {
+                if is_assignable(cx, assigned.id())? {
+                    continue;
+                }
+                let d = DiagBuilder2::warning(format!(
+                    "{} port `{}` of {} is connected to `{}`, which is not a variable or net",
+                    int.dir, int.name, node, text
+                ))
+                .group("port-conn")
+                .span(span);
+                cx.emit(declared_here(d, int.name));
+            }
+            ast::PortDir::Ref => (),
+        }
+    }
+    Ok(())
+}
+
+/// Check whether an expression is an integer literal without explicit size,
+/// such as `0` or `'1`.
+fn is_unsized_literal<'a>(cx: &impl Context<'a>, expr_id: NodeId) -> bool {
+    use crate::syntax::token::Lit;
+    match cx.ast_for_id(expr_id).as_all() {
+        ast::AllNode::Expr(expr) => match expr.data {
+            ast::LiteralExpr(Lit::Number(..))
+            | ast::LiteralExpr(Lit::UnbasedUnsized(..))
+            | ast::LiteralExpr(Lit::BasedInteger(None, ..)) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check whether an expression refers to a variable or net, or a part of one.
+fn is_assignable<'a>(cx: &impl Context<'a>, expr_id: NodeId) -> Result<bool> {
+    Ok(match cx.hir_of(expr_id)? {
+        HirNode::Expr(expr) => match expr.kind {
+            hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) => true,
+            hir::ExprKind::Index(base, _) | hir::ExprKind::Field(base, _) => {
+                is_assignable(cx, base)?
+            }
+            hir::ExprKind::Concat(None, ref exprs) => {
+                for &expr in exprs {
+                    if !is_assignable(cx, expr)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            _ => false,
+        },
+        _ => true,
+    })
+}
+
+/// Check whether all parameters of a module are the same in two environments.
+fn is_identical_recursion<'a>(
+    cx: &impl Context<'a>,
+    module: &'a hir::Module<'a>,
+    outer_env: ParamEnv,
+    inner_env: ParamEnv,
+) -> Result<bool> {
+    for &param_id in module.params.iter().chain(module.block.params.iter()) {
+        let same = match cx.hir_of(param_id)? {
+            HirNode::ValueParam(_) => {
+                let a = cx.constant_value_of(param_id, outer_env);
+                let b = cx.constant_value_of(param_id, inner_env);
+                a.kind == b.kind && a.ty.is_identical(b.ty)
+            }
+            HirNode::TypeParam(_) => {
+                let ast = cx.ast_for_id(param_id);
+                let a = cx.map_to_type_or_error(Ref(ast), outer_env);
+                let b = cx.map_to_type_or_error(Ref(ast), inner_env);
+                a.is_identical(b)
+            }
+            _ => true,
+        };
+        if !same {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
 /// A visitor that emits instantiation details diagnostics.
 pub struct InstVerbosityVisitor<'a, 'gcx> {
     cx: &'a GlobalContext<'gcx>,
@@ -0,0 +1,847 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! An interpreter for constant function calls.
+//!
+//! This module implements the elaboration-time evaluation of constant
+//! functions as described in §13.4.3 of the standard. Calls to user-defined
+//! functions within a constant expression, such as a parameter default value,
+//! are executed by walking the HIR statements of the function body and
+//! evaluating the expressions in them via their MIR representation. Local
+//! variables and arguments are kept in a stack of frames, which allows the
+//! function to be recursive.
+//!
+//! To guard against runaway evaluations, the interpreter imposes a limit on the
+//! recursion depth and on the total number of statements executed.
+
+use crate::crate_prelude::*;
+use crate::{
+    hir::HirNode,
+    mir::{CallArg, Lvalue, LvalueKind, Rvalue, RvalueKind},
+    value::{self, Value, ValueData, ValueKind},
+    ParamEnv,
+};
+use num::{BigInt, Integer, One, ToPrimitive, Zero};
+use std::{collections::HashMap, convert::TryFrom};
+
+/// The maximum nesting depth of constant function calls.
+pub const MAX_CALL_DEPTH: usize = 128;
+
+/// The maximum number of statements executed during the evaluation of a single
+/// constant expression.
+pub const MAX_STEPS: usize = 1_000_000;
+
+/// Evaluate a function call in a constant expression.
+///
+/// The arguments of the call are evaluated with `eval`, the function body is
+/// executed by a fresh interpreter.
+pub(crate) fn const_call<'a>(
+    cx: &impl Context<'a>,
+    mir: &'a Rvalue<'a>,
+    eval: &mut dyn FnMut(&'a Rvalue<'a>) -> Value<'a>,
+) -> Value<'a> {
+    let (target, args) = match mir.kind {
+        RvalueKind::Call { target, ref args } => (target, args),
+        _ => unreachable!("const_call on non-call `{}`", mir.span.extract()),
+    };
+    let mut interp = Interpreter::new(cx);
+
+    // Evaluate the input arguments in the context of the caller. Output
+    // arguments have nowhere to go in a constant expression.
+    let mut inputs = vec![];
+    for arg in args {
+        match *arg {
+            CallArg::Input(rv) | CallArg::Inout(rv, None) => inputs.push(Some(eval(rv))),
+            CallArg::Output(_, None) => inputs.push(None),
+            CallArg::Output(_, Some(lv)) | CallArg::Inout(_, Some(lv)) | CallArg::Ref(lv) => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be passed to an `output`, `inout`, or `ref` argument in a \
+                         constant expression",
+                        lv.span.extract()
+                    ))
+                    .code("SV0031")
+                    .span(lv.span),
+                );
+                return cx.intern_value(value::make_error(mir.ty));
+            }
+        }
+    }
+    if inputs.iter().flatten().any(|v| v.is_error()) {
+        return cx.intern_value(value::make_error(mir.ty));
+    }
+    match interp.call(mir, target, inputs) {
+        Ok((v, _)) => v,
+        Err(()) => cx.intern_value(value::make_error(mir.ty)),
+    }
+}
+
+/// An interpreter for constant function calls.
+pub struct Interpreter<'a, 'c, C> {
+    /// The context within which the functions are evaluated.
+    cx: &'c C,
+    /// The stack of function calls currently being executed.
+    frames: Vec<Frame<'a>>,
+    /// The number of statements executed so far.
+    steps: usize,
+    /// Whether an error has been emitted during evaluation.
+    failed: bool,
+}
+
+/// A single function call being executed.
+struct Frame<'a> {
+    /// The function being executed.
+    func: &'a ast::SubroutineDecl<'a>,
+    /// The location of the call.
+    span: Span,
+    /// The current values of the arguments and local variables.
+    vars: HashMap<NodeId, Value<'a>>,
+}
+
+/// The control flow resulting from the execution of a statement.
+enum Flow<'a> {
+    /// Continue with the next statement.
+    Next,
+    /// Break out of the innermost loop.
+    Break,
+    /// Continue with the next iteration of the innermost loop.
+    Continue,
+    /// Return from the function, optionally with a value.
+    Return(Option<Value<'a>>),
+}
+
+impl<'a, 'c, C: Context<'a>> Interpreter<'a, 'c, C> {
+    /// Create a new interpreter.
+    pub fn new(cx: &'c C) -> Self {
+        Self {
+            cx,
+            frames: vec![],
+            steps: 0,
+            failed: false,
+        }
+    }
+
+    /// Emit an error diagnostic, annotated with the call in the constant
+    /// expression that triggered the evaluation.
+    fn error(&mut self, diag: DiagBuilder2) {
+        let diag = match self.frames.first() {
+            Some(frame) => diag
+                .add_note(format!(
+                    "during constant evaluation of function `{}` called here:",
+                    frame.func.prototype.name
+                ))
+                .span(frame.span),
+            None => diag,
+        };
+        self.cx.emit(diag);
+        self.failed = true;
+    }
+
+    /// Access the innermost frame.
+    fn frame(&mut self) -> &mut Frame<'a> {
+        self.frames.last_mut().expect("no frame")
+    }
+
+    /// Call a function with the given input arguments.
+    ///
+    /// Returns the return value of the function, together with the final
+    /// values of its arguments.
+    fn call(
+        &mut self,
+        mir: &'a Rvalue<'a>,
+        target: &'a ast::SubroutineDecl<'a>,
+        inputs: Vec<Option<Value<'a>>>,
+    ) -> Result<(Value<'a>, Vec<Value<'a>>)> {
+        let cx = self.cx;
+        let env = mir.env;
+
+        // Only functions can be called in constant expressions.
+        if target.prototype.kind == ast::SubroutineKind::Task {
+            self.error(
+                DiagBuilder2::error(format!(
+                    "task `{}` cannot be called in a constant expression",
+                    target.prototype.name
+                ))
+                .code("SV0031")
+                .span(mir.span),
+            );
+            return Err(());
+        }
+
+        // Guard against infinite recursion.
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            self.error(
+                DiagBuilder2::error(format!(
+                    "constant evaluation of function `{}` exceeds the maximum call depth of {}",
+                    target.prototype.name, MAX_CALL_DEPTH
+                ))
+                .code("SV0032")
+                .span(mir.span),
+            );
+            return Err(());
+        }
+        debug!(
+            "Calling constant function `{}` at `{}`",
+            target.prototype.name,
+            mir.span.extract()
+        );
+
+        // Bind the arguments. Outputs start out with their default value.
+        let decl_args = cx.canonicalize_func_args(Ref(target));
+        let mut vars = HashMap::new();
+        for (decl, input) in decl_args.args.iter().zip(inputs.into_iter()) {
+            let value = match input {
+                Some(v) => v,
+                None => {
+                    let ty = cx.type_of_func_arg(Ref(decl), env);
+                    match decl.default {
+                        Some(expr) => self.eval_expr(expr.id(), env)?,
+                        None => cx.type_default_value(ty),
+                    }
+                }
+            };
+            vars.insert(decl.ast.id(), value);
+        }
+
+        // Execute the function body.
+        self.frames.push(Frame {
+            func: target,
+            span: mir.span,
+            vars,
+        });
+        let mut result = Ok(None);
+        for item in &target.items {
+            if let ast::SubroutineItem::Stmt(stmt) = item {
+                match self.exec(stmt.id(), env) {
+                    Ok(Flow::Next) => continue,
+                    Ok(Flow::Return(v)) => {
+                        result = Ok(v);
+                        break;
+                    }
+                    Ok(Flow::Break) | Ok(Flow::Continue) => {
+                        self.error(
+                            DiagBuilder2::error("`break` or `continue` outside of a loop")
+                                .code("SV0033")
+                                .span(stmt.span()),
+                        );
+                        result = Err(());
+                        break;
+                    }
+                    Err(()) => {
+                        result = Err(());
+                        break;
+                    }
+                }
+            }
+        }
+        let frame = self.frames.pop().unwrap();
+        let result = result?;
+
+        // Package up the return value and final argument values.
+        let value = match result {
+            Some(v) => v,
+            None => cx.type_default_value(mir.ty),
+        };
+        let value = cx.intern_value(ValueData {
+            ty: mir.ty,
+            kind: value.kind.clone(),
+        });
+        let args = decl_args
+            .args
+            .iter()
+            .map(|decl| frame.vars[&decl.ast.id()])
+            .collect();
+        Ok((value, args))
+    }
+
+    /// Evaluate an MIR rvalue.
+    pub fn eval(&mut self, mir: &'a Rvalue<'a>) -> Value<'a> {
+        let cx = self.cx;
+        if self.failed || mir.is_error() {
+            return cx.intern_value(value::make_error(mir.ty));
+        }
+
+        // Constant expressions do not depend on the frame.
+        if mir.is_const() {
+            return cx.const_mir_rvalue(mir.into());
+        }
+
+        match mir.kind {
+            RvalueKind::Var(id) | RvalueKind::Arg(id) => {
+                if let Some(&v) = self.frames.last().and_then(|f| f.vars.get(&id)) {
+                    return v;
+                }
+                self.error(
+                    DiagBuilder2::error(format!("`{}` is not constant", mir.span.extract()))
+                        .code("SV0030")
+                        .span(mir.span)
+                        .add_note(
+                            "constant functions may only refer to their arguments, local \
+                         variables, and parameters",
+                        ),
+                );
+                cx.intern_value(value::make_error(mir.ty))
+            }
+
+            RvalueKind::Port(_) | RvalueKind::Intf(_) | RvalueKind::IntfSignal(..) => {
+                self.error(
+                    DiagBuilder2::error(format!("`{}` is not constant", mir.span.extract()))
+                        .code("SV0030")
+                        .span(mir.span),
+                );
+                cx.intern_value(value::make_error(mir.ty))
+            }
+
+            RvalueKind::Assignment {
+                lvalue,
+                rvalue,
+                result,
+            } => {
+                let value = self.eval(rvalue);
+                let result = if std::ptr::eq(result, rvalue) {
+                    value
+                } else {
+                    self.eval(result)
+                };
+                match self.store(lvalue, value) {
+                    Ok(()) => result,
+                    Err(()) => cx.intern_value(value::make_error(mir.ty)),
+                }
+            }
+
+            RvalueKind::Call { target, ref args } => match self.eval_call(mir, target, args) {
+                Ok(v) => v,
+                Err(()) => cx.intern_value(value::make_error(mir.ty)),
+            },
+
+            _ => value::const_mir_rvalue_inner(cx, mir, &mut |v| self.eval(v)),
+        }
+    }
+
+    /// Evaluate a function call from within another function.
+    fn eval_call(
+        &mut self,
+        mir: &'a Rvalue<'a>,
+        target: &'a ast::SubroutineDecl<'a>,
+        args: &'a [CallArg<'a>],
+    ) -> Result<Value<'a>> {
+        let mut inputs = vec![];
+        for arg in args {
+            let value = match *arg {
+                CallArg::Input(rv) | CallArg::Inout(rv, _) => Some(self.eval(rv)),
+                CallArg::Output(..) => None,
+                CallArg::Ref(lv) => Some(self.load(lv)?),
+            };
+            if self.failed {
+                return Err(());
+            }
+            inputs.push(value);
+        }
+        let (value, outputs) = self.call(mir, target, inputs)?;
+
+        // Copy the output arguments back to the caller.
+        for (arg, value) in args.iter().zip(outputs.into_iter()) {
+            match *arg {
+                CallArg::Output(_, Some(lv)) | CallArg::Inout(_, Some(lv)) | CallArg::Ref(lv) => {
+                    self.store(lv, value)?
+                }
+                _ => (),
+            }
+        }
+        Ok(value)
+    }
+
+    /// Evaluate an expression.
+    fn eval_expr(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<Value<'a>> {
+        let mir = self.cx.mir_rvalue(expr_id, env);
+        let value = self.eval(mir);
+        if self.failed || value.is_error() {
+            Err(())
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Evaluate an expression to an integer.
+    fn eval_int(&mut self, mir: &'a Rvalue<'a>) -> Result<BigInt> {
+        let value = self.eval(mir);
+        if self.failed || value.is_error() {
+            return Err(());
+        }
+        match value.get_int() {
+            Some(v) => Ok(v.clone()),
+            None => {
+                self.error(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a constant integer",
+                        mir.span.extract()
+                    ))
+                    .code("SV0030")
+                    .span(mir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Read the current value of an MIR lvalue.
+    fn load(&mut self, lv: &'a Lvalue<'a>) -> Result<Value<'a>> {
+        let cx = self.cx;
+        match lv.kind {
+            LvalueKind::Var(id) | LvalueKind::Arg(id) => {
+                match self.frames.last().and_then(|f| f.vars.get(&id)) {
+                    Some(&v) => Ok(v),
+                    None => {
+                        self.not_assignable(lv);
+                        Err(())
+                    }
+                }
+            }
+            LvalueKind::Transmute(inner) => {
+                let v = self.load(inner)?;
+                Ok(cx.intern_value(ValueData {
+                    ty: lv.ty,
+                    kind: v.kind.clone(),
+                }))
+            }
+            LvalueKind::Index {
+                value,
+                base,
+                length,
+            } => {
+                let outer = self.load(value)?;
+                let base = self.eval_int(base)?;
+                let base = base.to_isize().unwrap_or(std::isize::MAX);
+                match outer.kind {
+                    ValueKind::Int(ref int, ..) => {
+                        let length = std::cmp::max(length, 1);
+                        let v = if base < 0 {
+                            int << (-base) as usize
+                        } else {
+                            int >> base as usize
+                        };
+                        let v = v.mod_floor(&(BigInt::one() << length));
+                        Ok(cx.intern_value(value::make_int(lv.ty, v)))
+                    }
+                    ValueKind::StructOrArray(ref elements) if length == 0 => {
+                        match usize::try_from(base).ok().and_then(|i| elements.get(i)) {
+                            Some(&v) => Ok(v),
+                            None => Ok(cx.type_default_value(lv.ty)),
+                        }
+                    }
+                    ValueKind::StructOrArray(ref elements) => {
+                        let elem_ty = lv.ty.pop_dim(cx).unwrap();
+                        let default = cx.type_default_value(elem_ty);
+                        let new = (0..length as isize)
+                            .map(|i| {
+                                usize::try_from(base + i)
+                                    .ok()
+                                    .and_then(|i| elements.get(i))
+                                    .cloned()
+                                    .unwrap_or(default)
+                            })
+                            .collect();
+                        Ok(cx.intern_value(value::make_array(lv.ty, new)))
+                    }
+                    _ => {
+                        self.not_assignable(lv);
+                        Err(())
+                    }
+                }
+            }
+            LvalueKind::Member { value, field } => {
+                let outer = self.load(value)?;
+                match outer.kind {
+                    ValueKind::StructOrArray(ref fields) => Ok(fields[field]),
+                    _ => {
+                        self.not_assignable(lv);
+                        Err(())
+                    }
+                }
+            }
+            LvalueKind::Error => Err(()),
+            _ => {
+                self.not_assignable(lv);
+                Err(())
+            }
+        }
+    }
+
+    /// Assign a value to an MIR lvalue.
+    fn store(&mut self, lv: &'a Lvalue<'a>, value: Value<'a>) -> Result<()> {
+        let cx = self.cx;
+        if value.is_error() {
+            return Err(());
+        }
+        match lv.kind {
+            LvalueKind::Var(id) | LvalueKind::Arg(id) => {
+                match self.frames.last().map(|f| f.vars.contains_key(&id)) {
+                    Some(true) => {
+                        let value = cx.intern_value(ValueData {
+                            ty: lv.ty,
+                            kind: value.kind.clone(),
+                        });
+                        self.frame().vars.insert(id, value);
+                        Ok(())
+                    }
+                    _ => {
+                        self.not_assignable(lv);
+                        Err(())
+                    }
+                }
+            }
+            LvalueKind::Transmute(inner) => {
+                let value = cx.intern_value(ValueData {
+                    ty: inner.ty,
+                    kind: value.kind.clone(),
+                });
+                self.store(inner, value)
+            }
+            LvalueKind::Index {
+                value: outer_lv,
+                base,
+                length,
+            } => {
+                let outer = self.load(outer_lv)?;
+                let base = self.eval_int(base)?;
+                let base = base.to_isize().unwrap_or(std::isize::MAX);
+                let new = match outer.kind {
+                    ValueKind::Int(ref int, ..) => {
+                        let width = outer_lv.ty.simple_bit_vector(cx, lv.span).size;
+                        let length = std::cmp::max(length, 1);
+                        let modulus = BigInt::one() << width;
+                        let mut bits = value
+                            .get_int()
+                            .expect("bit-select assignment of non-integer")
+                            .mod_floor(&(BigInt::one() << length));
+                        let mut mask = (BigInt::one() << length) - BigInt::one();
+                        if base < 0 {
+                            bits >>= (-base) as usize;
+                            mask >>= (-base) as usize;
+                        } else {
+                            bits <<= base as usize;
+                            mask <<= base as usize;
+                        }
+                        let int = int.mod_floor(&modulus);
+                        let cleared = &int - (&int & &mask);
+                        let new = (cleared | (bits & mask)).mod_floor(&modulus);
+                        value::make_int(outer.ty, new)
+                    }
+                    ValueKind::StructOrArray(ref elements) if length == 0 => {
+                        let mut elements = elements.clone();
+                        if let Some(elem) =
+                            usize::try_from(base).ok().and_then(|i| elements.get_mut(i))
+                        {
+                            *elem = value;
+                        }
+                        value::make_array(outer.ty, elements)
+                    }
+                    ValueKind::StructOrArray(ref elements) => {
+                        let mut elements = elements.clone();
+                        let new = match value.kind {
+                            ValueKind::StructOrArray(ref v) => v,
+                            _ => unreachable!("slice assignment of non-array"),
+                        };
+                        for (i, &v) in new.iter().enumerate() {
+                            if let Some(elem) = usize::try_from(base + i as isize)
+                                .ok()
+                                .and_then(|i| elements.get_mut(i))
+                            {
+                                *elem = v;
+                            }
+                        }
+                        value::make_array(outer.ty, elements)
+                    }
+                    _ => {
+                        self.not_assignable(lv);
+                        return Err(());
+                    }
+                };
+                let new = cx.intern_value(new);
+                self.store(outer_lv, new)
+            }
+            LvalueKind::Member {
+                value: outer_lv,
+                field,
+            } => {
+                let outer = self.load(outer_lv)?;
+                let mut fields = match outer.kind {
+                    ValueKind::StructOrArray(ref fields) => fields.clone(),
+                    _ => {
+                        self.not_assignable(lv);
+                        return Err(());
+                    }
+                };
+                fields[field] = value;
+                let new = cx.intern_value(value::make_struct(outer.ty, fields));
+                self.store(outer_lv, new)
+            }
+            LvalueKind::Error => Err(()),
+            _ => {
+                self.not_assignable(lv);
+                Err(())
+            }
+        }
+    }
+
+    /// Emit an error for an lvalue that cannot be accessed by a constant
+    /// function.
+    fn not_assignable(&mut self, lv: &'a Lvalue<'a>) {
+        self.error(
+            DiagBuilder2::error(format!(
+                "`{}` cannot be accessed in a constant function",
+                lv.span.extract()
+            ))
+            .code("SV0031")
+            .span(lv.span)
+            .add_note("constant functions may only assign to their arguments and local variables"),
+        );
+    }
+
+    /// Execute a statement.
+    fn exec(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<Flow<'a>> {
+        let cx = self.cx;
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            self.error(
+                DiagBuilder2::error(format!(
+                    "constant function evaluation exceeds the limit of {} statements",
+                    MAX_STEPS
+                ))
+                .code("SV0032")
+                .span(cx.span(stmt_id))
+                .add_note("the function may contain an infinite loop"),
+            );
+            return Err(());
+        }
+        match cx.hir_of(stmt_id)? {
+            HirNode::Stmt(hir) => self.exec_stmt(stmt_id, hir, env),
+            HirNode::VarDecl(hir) => {
+                let ty = cx.type_of_var_decl(
+                    Ref(cx.ast_for_id(stmt_id).as_all().get_var_decl_name().unwrap()),
+                    env,
+                );
+                let value = match hir.init {
+                    Some(expr) => self.eval_expr(expr, env)?,
+                    None => cx.type_default_value(ty),
+                };
+                self.frame().vars.insert(stmt_id, value);
+                Ok(Flow::Next)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Execute a statement, given its HIR.
+    fn exec_stmt(
+        &mut self,
+        stmt_id: NodeId,
+        hir: &'a hir::Stmt<'a>,
+        env: ParamEnv,
+    ) -> Result<Flow<'a>> {
+        let cx = self.cx;
+        match hir.kind {
+            hir::StmtKind::Null => Ok(Flow::Next),
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    match self.exec(stmt, env)? {
+                        Flow::Next => (),
+                        flow => return Ok(flow),
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            hir::StmtKind::Assign {
+                lhs,
+                rhs,
+                kind: kind @ hir::AssignKind::Block(_),
+            } => {
+                let assign =
+                    cx.mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                if assign.is_error() {
+                    return Err(());
+                }
+                for assign in cx.mir_simplify_assignment(Ref(assign)) {
+                    let value = self.eval(assign.rhs);
+                    if self.failed || value.is_error() {
+                        return Err(());
+                    }
+                    self.store(assign.lhs, value)?;
+                }
+                Ok(Flow::Next)
+            }
+            hir::StmtKind::Expr(expr) => {
+                self.eval_expr(expr, env)?;
+                Ok(Flow::Next)
+            }
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                if self.eval_expr(cond, env)?.is_true() {
+                    self.exec(main_stmt, env)
+                } else if let Some(else_stmt) = else_stmt {
+                    self.exec(else_stmt, env)
+                } else {
+                    Ok(Flow::Next)
+                }
+            }
+            hir::StmtKind::Loop { kind, body } => self.exec_loop(kind, body, env),
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                let value = self.eval_expr(expr, env)?;
+                for &(ref way_exprs, stmt) in ways {
+                    for &way_expr in way_exprs {
+                        let way = self.eval_expr(way_expr, env)?;
+                        if case_matches(kind, value, way) {
+                            return self.exec(stmt, env);
+                        }
+                    }
+                }
+                match default {
+                    Some(stmt) => self.exec(stmt, env),
+                    None => Ok(Flow::Next),
+                }
+            }
+            hir::StmtKind::Ast(ast) => match ast.kind {
+                ast::ReturnStmt(None) => Ok(Flow::Return(None)),
+                ast::ReturnStmt(Some(ref expr)) => {
+                    Ok(Flow::Return(Some(self.eval_expr(expr.id(), env)?)))
+                }
+                ast::BreakStmt => Ok(Flow::Break),
+                ast::ContinueStmt => Ok(Flow::Continue),
+                _ => {
+                    self.error(
+                        DiagBuilder2::error(format!(
+                            "{} is not supported in a constant function",
+                            ast.format_indefinite()
+                        ))
+                        .code("SV0031")
+                        .span(ast.human_span()),
+                    );
+                    Err(())
+                }
+            },
+            hir::StmtKind::Assign { .. } => {
+                self.error(
+                    DiagBuilder2::error("non-blocking assignment in a constant function")
+                        .code("SV0031")
+                        .span(hir.human_span()),
+                );
+                Err(())
+            }
+            hir::StmtKind::Timed { .. } => {
+                self.error(
+                    DiagBuilder2::error("timing control in a constant function")
+                        .code("SV0031")
+                        .span(hir.human_span()),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Execute a loop statement.
+    fn exec_loop(&mut self, kind: hir::LoopKind, body: NodeId, env: ParamEnv) -> Result<Flow<'a>> {
+        // Execute the loop initialization.
+        let mut count = match kind {
+            hir::LoopKind::Repeat(count) => {
+                let mir = self.cx.mir_rvalue(count, env);
+                Some(self.eval_int(mir)?)
+            }
+            hir::LoopKind::For(init, _, _) => {
+                match self.exec(init, env)? {
+                    Flow::Next => (),
+                    flow => return Ok(flow),
+                }
+                None
+            }
+            _ => None,
+        };
+
+        loop {
+            // Check whether to enter the loop body.
+            let enter = match kind {
+                hir::LoopKind::Forever | hir::LoopKind::Do(_) => true,
+                hir::LoopKind::Repeat(_) => {
+                    let count = count.as_mut().unwrap();
+                    if *count > BigInt::zero() {
+                        *count -= 1;
+                        true
+                    } else {
+                        false
+                    }
+                }
+                hir::LoopKind::While(cond) | hir::LoopKind::For(_, cond, _) => {
+                    self.eval_expr(cond, env)?.is_true()
+                }
+            };
+            if !enter {
+                break;
+            }
+
+            // Execute the body.
+            match self.exec(body, env)? {
+                Flow::Next | Flow::Continue => (),
+                Flow::Break => break,
+                flow @ Flow::Return(_) => return Ok(flow),
+            }
+
+            // Execute the loop epilogue.
+            match kind {
+                hir::LoopKind::Do(cond) => {
+                    if !self.eval_expr(cond, env)?.is_true() {
+                        break;
+                    }
+                }
+                hir::LoopKind::For(_, _, step) => {
+                    self.eval_expr(step, env)?;
+                }
+                _ => (),
+            }
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                self.error(
+                    DiagBuilder2::error(format!(
+                        "constant function evaluation exceeds the limit of {} statements",
+                        MAX_STEPS
+                    ))
+                    .code("SV0032")
+                    .span(self.cx.span(body))
+                    .add_note("the function may contain an infinite loop"),
+                );
+                return Err(());
+            }
+        }
+        Ok(Flow::Next)
+    }
+}
+
+/// Check whether a case expression matches one of the case labels.
+fn case_matches<'a>(kind: ast::CaseKind, value: Value<'a>, way: Value<'a>) -> bool {
+    match (&value.kind, &way.kind) {
+        (ValueKind::Int(lhs, ..), ValueKind::Int(rhs, special_bits, x_bits)) => {
+            // Determine which bits of the label are wildcards. The special bits
+            // are ordered MSB first.
+            let mut ignore = BigInt::zero();
+            for (special, x) in special_bits.iter().zip(x_bits.iter()) {
+                ignore <<= 1;
+                let wildcard = match kind {
+                    ast::CaseKind::Normal => false,
+                    ast::CaseKind::DontCareZ => special && !x,
+                    ast::CaseKind::DontCareXZ => special,
+                };
+                if wildcard {
+                    ignore |= BigInt::one();
+                }
+            }
+            let diff = lhs ^ rhs;
+            (&diff | &ignore) == ignore
+        }
+        (lhs, rhs) => lhs == rhs,
+    }
+}
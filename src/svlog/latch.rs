@@ -0,0 +1,388 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of latches inferred from combinational processes.
+//!
+//! An `always_comb` process describes combinational logic only if it assigns
+//! every variable it drives on every path through its body. A variable that
+//! keeps its value on some path needs to be stored, which turns the process
+//! into a latch. This is rarely intended, so we warn about such variables and
+//! point at the branch where the assignment is missing.
+
+use crate::{crate_prelude::*, hir::HirNode, ParamEnv};
+use num::{BigInt, Integer, Zero};
+use std::collections::HashSet;
+
+/// Check an `always_comb` procedure for inferred latches.
+///
+/// Emits a warning for every variable that is assigned on some but not all
+/// paths through the procedure.
+pub(crate) fn check_procedure<'a>(
+    cx: &impl Context<'a>,
+    proc_id: NodeId,
+    env: ParamEnv,
+) -> Result<()> {
+    let hir = match cx.hir_of(proc_id)? {
+        HirNode::Proc(x) => x,
+        _ => unreachable!(),
+    };
+    let checker = LatchChecker { cx, env, proc_id };
+    let assigned = checker.assigned(hir.stmt)?;
+    let mut driven = vec![];
+    checker.driven(hir.stmt, &mut driven)?;
+    for var in driven {
+        if assigned.contains(&var) {
+            continue;
+        }
+        let name = match cx.hir_of(var)? {
+            HirNode::VarDecl(x) => x.name.value,
+            HirNode::IntPort(x) => x.name.value,
+            _ => unreachable!(),
+        };
+        let mut d = DiagBuilder2::warning(format!(
+            "`{}` is not assigned on all paths through this `always_comb`; a latch will be \
+             inferred",
+            name
+        ))
+        .group("latch");
+        if let Some(missing) = checker.find_missing(hir.stmt, var)? {
+            d = d.span(missing.span).add_note(match missing.kind {
+                Missing::Branch => format!("`{}` is not assigned in this branch", name),
+                Missing::Else => format!("`{}` is not assigned if the condition is false", name),
+                Missing::Default => format!(
+                    "`{}` is not assigned if none of the cases match; consider adding a \
+                     `default`",
+                    name
+                ),
+            });
+        } else {
+            d = d.span(hir.span);
+        }
+        cx.emit(d);
+    }
+    Ok(())
+}
+
+/// A helper to determine which variables a procedure assigns.
+struct LatchChecker<'c, C> {
+    cx: &'c C,
+    env: ParamEnv,
+    proc_id: NodeId,
+}
+
+/// A location where an assignment to a variable is missing.
+struct MissingAssign {
+    span: Span,
+    kind: Missing,
+}
+
+/// The different ways an assignment can be missing.
+enum Missing {
+    /// An explicit branch lacks the assignment.
+    Branch,
+    /// An `if` lacks the `else` branch that would assign the variable.
+    Else,
+    /// A `case` lacks the `default` that would assign the variable.
+    Default,
+}
+
+impl<'a, 'c, C: Context<'a>> LatchChecker<'c, C> {
+    /// Determine the variables a statement assigns on every path.
+    fn assigned(&self, stmt_id: NodeId) -> Result<HashSet<NodeId>> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(HashSet::new()),
+        };
+        Ok(match hir.kind {
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                let mut set = HashSet::new();
+                for &stmt in stmts {
+                    set.extend(self.assigned(stmt)?);
+                }
+                set
+            }
+            hir::StmtKind::Assign { lhs, .. } => {
+                let mut set = HashSet::new();
+                self.lhs_vars(lhs, &mut set)?;
+                set
+            }
+            hir::StmtKind::Timed { stmt, .. } => self.assigned(stmt)?,
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt: Some(else_stmt),
+                ..
+            } => {
+                let main = self.assigned(main_stmt)?;
+                let other = self.assigned(else_stmt)?;
+                main.intersection(&other).cloned().collect()
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                ..
+            } => {
+                let mut branches = vec![];
+                for &(_, stmt) in ways {
+                    branches.push(self.assigned(stmt)?);
+                }
+                match default {
+                    Some(default) => branches.push(self.assigned(default)?),
+                    None if self.is_full_case(expr, ways)? => (),
+                    None => return Ok(HashSet::new()),
+                }
+                let mut branches = branches.into_iter();
+                let first = branches.next().unwrap_or_default();
+                branches.fold(first, |acc, set| acc.intersection(&set).cloned().collect())
+            }
+            _ => HashSet::new(),
+        })
+    }
+
+    /// Collect the variables a statement assigns on any path, in the order in
+    /// which they first appear.
+    fn driven(&self, stmt_id: NodeId, into: &mut Vec<NodeId>) -> Result<()> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(()),
+        };
+        match hir.kind {
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.driven(stmt, into)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, .. } => {
+                let mut set = HashSet::new();
+                self.lhs_vars(lhs, &mut set)?;
+                let mut vars: Vec<_> = set.into_iter().filter(|v| !into.contains(v)).collect();
+                vars.sort();
+                into.extend(vars);
+            }
+            hir::StmtKind::Timed { stmt, .. } | hir::StmtKind::Loop { body: stmt, .. } => {
+                self.driven(stmt, into)?;
+            }
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt,
+                ..
+            } => {
+                self.driven(main_stmt, into)?;
+                if let Some(else_stmt) = else_stmt {
+                    self.driven(else_stmt, into)?;
+                }
+            }
+            hir::StmtKind::Case {
+                ref ways, default, ..
+            } => {
+                for &(_, stmt) in ways {
+                    self.driven(stmt, into)?;
+                }
+                if let Some(default) = default {
+                    self.driven(default, into)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Check whether a statement assigns a variable on any path.
+    fn drives(&self, stmt_id: NodeId, var: NodeId) -> Result<bool> {
+        let mut driven = vec![];
+        self.driven(stmt_id, &mut driven)?;
+        Ok(driven.contains(&var))
+    }
+
+    /// Find the innermost branch of a statement that fails to assign a
+    /// variable.
+    fn find_missing(&self, stmt_id: NodeId, var: NodeId) -> Result<Option<MissingAssign>> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(None),
+        };
+        let branch = |stmt: NodeId| -> Result<Option<MissingAssign>> {
+            if self.drives(stmt, var)? {
+                self.find_missing(stmt, var)
+            } else {
+                Ok(Some(MissingAssign {
+                    span: self.cx.span(stmt),
+                    kind: Missing::Branch,
+                }))
+            }
+        };
+        match hir.kind {
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    if self.drives(stmt, var)? {
+                        return self.find_missing(stmt, var);
+                    }
+                }
+                Ok(None)
+            }
+            hir::StmtKind::Timed { stmt, .. } | hir::StmtKind::Loop { body: stmt, .. } => {
+                self.find_missing(stmt, var)
+            }
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt,
+                ..
+            } => {
+                if !self.assigned(main_stmt)?.contains(&var) {
+                    return branch(main_stmt);
+                }
+                match else_stmt {
+                    Some(else_stmt) => branch(else_stmt),
+                    None => Ok(Some(MissingAssign {
+                        span: hir.span,
+                        kind: Missing::Else,
+                    })),
+                }
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                ..
+            } => {
+                for &(_, stmt) in ways {
+                    if !self.assigned(stmt)?.contains(&var) {
+                        return branch(stmt);
+                    }
+                }
+                match default {
+                    Some(default) => branch(default),
+                    None if self.is_full_case(expr, ways)? => Ok(None),
+                    None => Ok(Some(MissingAssign {
+                        span: hir.span,
+                        kind: Missing::Default,
+                    })),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Collect the variables an assignment fully overwrites.
+    ///
+    /// Assignments to individual bits or fields of a variable are not
+    /// considered, since they leave the rest of the variable untouched.
+    fn lhs_vars(&self, expr_id: NodeId, into: &mut HashSet<NodeId>) -> Result<()> {
+        let hir = match self.cx.hir_of(expr_id)? {
+            HirNode::Expr(x) => x,
+            _ => return Ok(()),
+        };
+        match hir.kind {
+            hir::ExprKind::Ident(..) => {
+                let decl = self.cx.resolve_node(expr_id, self.env)?;
+                match self.cx.hir_of(decl)? {
+                    HirNode::VarDecl(_) | HirNode::IntPort(_)
+                        if !self.cx.is_parent_of(self.proc_id, decl) =>
+                    {
+                        into.insert(decl);
+                    }
+                    _ => (),
+                }
+            }
+            hir::ExprKind::Concat(None, ref exprs) => {
+                for &expr in exprs {
+                    self.lhs_vars(expr, into)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Check whether the labels of a `case` statement cover every possible
+    /// value of its expression.
+    fn is_full_case(&self, expr_id: NodeId, ways: &[(Vec<NodeId>, NodeId)]) -> Result<bool> {
+        Ok(missing_cases(self.cx, self.env, expr_id, ways)?
+            .map(|missing| missing.count.is_zero())
+            .unwrap_or(false))
+    }
+}
+
+/// The values of the expression of a `case` statement that no label covers.
+pub(crate) struct MissingCases {
+    /// The missing enumerators if the expression is an enum, or the first few
+    /// missing values otherwise.
+    pub names: Vec<String>,
+    /// The number of missing values.
+    pub count: BigInt,
+}
+
+/// Determine the values of the expression of a `case` statement that no label
+/// covers.
+///
+/// Returns `None` if the expression is neither an enum nor a bit vector narrow
+/// enough for its values to be enumerated.
+pub(crate) fn missing_cases<'a>(
+    cx: &impl Context<'a>,
+    env: ParamEnv,
+    expr_id: NodeId,
+    ways: &[(Vec<NodeId>, NodeId)],
+) -> Result<Option<MissingCases>> {
+    let mut labels = HashSet::new();
+    for &label in ways.iter().flat_map(|(labels, _)| labels) {
+        if !is_constant(cx, env, label)? {
+            continue;
+        }
+        if let Some(value) = cx.constant_value_of(label, env).get_int() {
+            labels.insert(value.clone());
+        }
+    }
+    let ty = cx.type_of(expr_id, env)?;
+    if let Some(enm) = ty.get_enum() {
+        let mut names = vec![];
+        for (name, variant) in &enm.variants {
+            match cx.constant_value_of(variant.id(), env).get_int() {
+                Some(value) if labels.contains(value) => (),
+                _ => names.push(name.value.to_string()),
+            }
+        }
+        return Ok(Some(MissingCases {
+            count: names.len().into(),
+            names,
+        }));
+    }
+    let width = match ty.get_bit_size() {
+        Some(width) if width < 32 => width,
+        _ => return Ok(None),
+    };
+    let modulus = BigInt::from(1) << width;
+    let labels: HashSet<BigInt> = labels
+        .into_iter()
+        .map(|value| value.mod_floor(&modulus))
+        .collect();
+    let mut names = vec![];
+    let mut value = BigInt::zero();
+    while value < modulus && names.len() < 8 {
+        if !labels.contains(&value) {
+            names.push(format!("{}'d{}", width, value));
+        }
+        value += 1;
+    }
+    Ok(Some(MissingCases {
+        count: modulus - labels.len(),
+        names,
+    }))
+}
+
+/// Check whether an expression is a constant.
+pub(crate) fn is_constant<'a>(
+    cx: &impl Context<'a>,
+    env: ParamEnv,
+    expr_id: NodeId,
+) -> Result<bool> {
+    Ok(match cx.hir_of(expr_id)? {
+        HirNode::Expr(expr) => match expr.kind {
+            hir::ExprKind::IntConst { .. } | hir::ExprKind::UnsizedConst(..) => true,
+            hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) => {
+                let decl = cx.resolve_node(expr_id, env)?;
+                cx.is_constant(decl)?
+            }
+            _ => false,
+        },
+        _ => false,
+    })
+}
@@ -106,27 +106,82 @@ macro_rules! assert_type {
 }
 
 mod ast_map;
+mod blackbox;
+#[warn(missing_docs)]
+pub mod call_graph;
 #[warn(missing_docs)]
 pub mod call_mapping;
 mod codegen;
+mod comb_loops;
 mod context;
+mod dedup;
+#[warn(missing_docs)]
+pub mod design;
+#[warn(missing_docs)]
+pub mod docs;
+#[warn(missing_docs)]
+pub mod dot;
+mod drivers;
+#[warn(missing_docs)]
+pub mod elab_report;
+#[warn(missing_docs)]
+pub mod explain;
+#[warn(missing_docs)]
+pub mod firrtl;
+#[warn(missing_docs)]
+pub mod foreign;
+#[warn(missing_docs)]
+pub mod format;
+#[warn(missing_docs)]
+pub mod fsm;
 #[warn(missing_docs)]
 pub mod func_args;
+#[warn(missing_docs)]
+pub mod hierarchy;
 pub mod hir;
+#[warn(missing_docs)]
+pub mod implicit_nets;
+#[warn(missing_docs)]
+pub mod initial;
 mod inst_details;
+mod interp;
+mod latch;
+mod library;
+#[warn(missing_docs)]
+pub mod lint;
 pub mod mir;
+#[warn(missing_docs)]
+pub mod moore_dialect;
+#[warn(missing_docs)]
+pub mod opt;
 mod param_env;
 #[warn(missing_docs)]
 pub mod pattern_mapping;
 #[warn(missing_docs)]
 pub mod port_list;
 mod port_mapping;
+#[warn(missing_docs)]
+pub mod readmem;
 pub mod resolver;
 pub mod rst;
 #[warn(missing_docs)]
+pub mod semantic_tokens;
+mod sensitivity;
+#[warn(missing_docs)]
+pub mod stats;
+#[warn(missing_docs)]
+pub mod summary;
+mod suppress;
+#[warn(missing_docs)]
 pub mod ty;
 pub mod typeck;
 pub mod value;
+#[warn(missing_docs)]
+pub mod verilog;
+#[warn(missing_docs)]
+pub mod xref;
+#[warn(missing_docs)]
+pub mod yosys;
 
 pub use moore_common::{
     name::Name,
@@ -264,9 +319,13 @@ mod queries {
     #[allow(deprecated)]
     use crate::{
         call_mapping::*,
+        comb_loops::comb_paths,
+        format::*,
         func_args::*,
         hir::lowering::*,
-        hir::{accessed_nodes, AccessTable, HirNode},
+        hir::{accessed_nodes, attribute, doc_comment, hir_node_at, AccessTable, HirNode},
+        implicit_nets::*,
+        initial::*,
         inst_details::*,
         mir::lower::{
             assign::{
@@ -282,6 +341,7 @@ mod queries {
         port_mapping::*,
         resolver::*,
         rst::*,
+        sensitivity::*,
         ty::UnpackedType,
         typeck::*,
         value::*,
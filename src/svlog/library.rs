@@ -0,0 +1,101 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Lazy module resolution from libraries.
+//!
+//! Instantiating a module that is not declared in the input files looks for
+//! the module in the libraries, as is common among Verilog tools. The library
+//! files given with `-v` are parsed in their entirety the first time a module
+//! is missing. The library directories given with `-y` are searched for a file
+//! named after the module, with one of the extensions given with `+libext+`,
+//! which is parsed on demand.
+
+use crate::{
+    common::{arenas::Alloc, source::get_source_manager},
+    crate_prelude::*,
+    lexer::Lexer,
+    parser,
+    preproc::Preprocessor,
+};
+use std::path::{Path, PathBuf};
+
+/// The extensions considered in the library directories if no `+libext+` is
+/// given.
+const DEFAULT_EXTS: &[&str] = &[".v", ".sv"];
+
+/// Find a module that is not declared in the input files in the libraries.
+///
+/// Returns `None` if no library declares the module.
+pub(crate) fn library_module<'a>(
+    cx: &impl Context<'a>,
+    name: Spanned<Name>,
+) -> Result<Option<&'a ast::Module<'a>>> {
+    let find = || {
+        cx.gcx()
+            .find_module(name.value)
+            .and_then(|id| cx.ast_for_id(id).as_all().get_module())
+    };
+    if let Some(module) = find() {
+        return Ok(Some(module));
+    }
+    let opts = &cx.sess().opts;
+
+    // Parse the library files.
+    for path in &opts.library_files {
+        parse_library_file(cx, path, name.span)?;
+    }
+    if let Some(module) = find() {
+        return Ok(Some(module));
+    }
+
+    // Look for a file named after the module in the library directories.
+    let exts: Vec<&str> = if opts.library_exts.is_empty() {
+        DEFAULT_EXTS.to_vec()
+    } else {
+        opts.library_exts.iter().map(String::as_str).collect()
+    };
+    for dir in &opts.library_dirs {
+        for ext in &exts {
+            let path = dir.join(format!("{}{}", name.value, ext));
+            if !path.is_file() {
+                continue;
+            }
+            parse_library_file(cx, &path, name.span)?;
+            if let Some(module) = find() {
+                return Ok(Some(module));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Parse a library file and add it to the design, unless this has already been
+/// done before.
+fn parse_library_file<'a>(cx: &impl Context<'a>, path: &Path, span: Span) -> Result<()> {
+    if !cx.gcx().mark_library_file(path) {
+        return Ok(());
+    }
+    debug!("Parsing library file {}", path.display());
+    let source = match get_source_manager().open(&path.to_string_lossy()) {
+        Some(x) => x,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("unable to open library file `{}`", path.display()))
+                    .code("SV0049")
+                    .span(span),
+            );
+            return Err(());
+        }
+    };
+    let opts = &cx.sess().opts;
+    let include_paths: Vec<&Path> = opts.include_paths.iter().map(PathBuf::as_path).collect();
+    let defines: Vec<(&str, Option<&str>)> = opts
+        .defines
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_deref()))
+        .collect();
+    let preproc = Preprocessor::new(source, &include_paths, &defines);
+    let file = parser::parse(Lexer::new(preproc), &cx.arena().ast)?;
+    let file = cx.arena().alloc(file);
+    cx.gcx().add_files(Some(&*file).into_iter());
+    Ok(())
+}
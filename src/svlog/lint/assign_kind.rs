@@ -0,0 +1,148 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of assignments of the wrong kind for a procedure.
+//!
+//! Sequential logic in an `always_ff` procedure should update its signals with
+//! nonblocking assignments, such that all flip-flops sample their inputs at
+//! the same time. Blocking assignments to variables declared inside the
+//! procedure are allowed, since they only hold intermediate results.
+//! Combinational logic in an `always_comb` or `always_latch` procedure should
+//! use blocking assignments, such that later statements see the updated
+//! values. Every finding carries a suggestion that swaps the operator.
+
+use super::LintContext;
+use crate::{
+    crate_prelude::*,
+    hir::{self, HirNode, Visitor},
+};
+
+/// Report blocking assignments to signals in `always_ff` procedures.
+pub(super) fn check_blocking(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    for (prok, assigns) in collect(lcx, &[ast::ProcedureKind::AlwaysFf])? {
+        let written = &cx.accessed_nodes(prok.id, lcx.env())?.written;
+        for stmt in assigns {
+            let op = match stmt.kind {
+                hir::AssignKind::Block(op) => op,
+                _ => continue,
+            };
+            let acc = cx.accessed_nodes(stmt.id, lcx.env())?;
+            if !acc.written.iter().any(|x| written.contains(x)) {
+                continue;
+            }
+            let mut diag = DiagBuilder2::warning("blocking assignment in `always_ff` procedure")
+                .span(stmt.span)
+                .add_note("Sequential logic should be updated with nonblocking assignments");
+            if op == ast::AssignOp::Identity {
+                if let Some(span) = find_operator(cx, stmt.lhs, stmt.rhs, "=") {
+                    diag = diag.suggest(span, "<=");
+                }
+            }
+            lcx.emit(diag);
+        }
+    }
+    Ok(())
+}
+
+/// Report nonblocking assignments in `always_comb` and `always_latch`
+/// procedures.
+pub(super) fn check_nonblocking(lcx: &LintContext) -> Result<()> {
+    let kinds = [
+        ast::ProcedureKind::AlwaysComb,
+        ast::ProcedureKind::AlwaysLatch,
+    ];
+    for (prok, assigns) in collect(lcx, &kinds)? {
+        for stmt in assigns {
+            if let hir::AssignKind::Block(_) = stmt.kind {
+                continue;
+            }
+            let mut diag =
+                DiagBuilder2::warning(format!("nonblocking assignment in {}", prok.desc()))
+                    .span(stmt.span)
+                    .add_note("Combinational logic should be updated with blocking assignments");
+            if stmt.kind == hir::AssignKind::Nonblock {
+                if let Some(span) = find_operator(lcx.cx, stmt.lhs, stmt.rhs, "<=") {
+                    diag = diag.suggest(span, "=");
+                }
+            }
+            lcx.emit(diag);
+        }
+    }
+    Ok(())
+}
+
+/// An assignment statement.
+struct AssignStmt {
+    id: NodeId,
+    span: Span,
+    lhs: NodeId,
+    rhs: NodeId,
+    kind: hir::AssignKind,
+}
+
+/// Collect the assignment statements of the procedures of a module that are
+/// of one of the given kinds.
+fn collect<'a>(
+    lcx: &LintContext<'a, '_>,
+    kinds: &[ast::ProcedureKind],
+) -> Result<Vec<(&'a hir::Proc, Vec<AssignStmt>)>> {
+    let mut procs = vec![];
+    for &id in &lcx.module.procs {
+        let prok = match lcx.cx.hir_of(id.id())? {
+            HirNode::Proc(x) if kinds.contains(&x.kind) => x,
+            _ => continue,
+        };
+        let mut collector = AssignCollector {
+            cx: lcx.cx,
+            assigns: vec![],
+        };
+        collector.visit_proc(prok);
+        procs.push((prok, collector.assigns));
+    }
+    Ok(procs)
+}
+
+/// Find the span of an assignment operator between its left- and right-hand
+/// side.
+pub(super) fn find_operator<'a>(
+    cx: &impl Context<'a>,
+    lhs: NodeId,
+    rhs: NodeId,
+    op: &str,
+) -> Option<Span> {
+    let lhs = cx.span(lhs);
+    let rhs = cx.span(rhs);
+    if lhs.source != rhs.source || lhs.end > rhs.begin {
+        return None;
+    }
+    let text = lhs.source.get_content().extract(lhs.end, rhs.begin);
+    let offset = lhs.end + text.find(op)?;
+    Some(Span::new(lhs.source, offset, offset + op.len()))
+}
+
+/// A visitor that collects the assignment statements of a procedure.
+struct AssignCollector<'a, 'c> {
+    cx: &'c GlobalContext<'a>,
+    assigns: Vec<AssignStmt>,
+}
+
+impl<'a, 'c> Visitor<'a> for AssignCollector<'a, 'c> {
+    type Context = GlobalContext<'a>;
+
+    fn context(&self) -> &Self::Context {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a hir::Stmt) {
+        if let hir::StmtKind::Assign { lhs, rhs, kind } = stmt.kind {
+            self.assigns.push(AssignStmt {
+                id: stmt.id,
+                span: stmt.span,
+                lhs,
+                rhs,
+                kind,
+            });
+        }
+        hir::walk_stmt(self, stmt);
+    }
+}
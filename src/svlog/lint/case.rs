@@ -0,0 +1,85 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of incomplete `case` statements.
+//!
+//! A `case` statement without a `default` arm does nothing if none of its
+//! labels match. Over an enum or a narrow bit vector this is usually an
+//! oversight, so we warn if the labels do not cover every enumerator or every
+//! value, and list the ones that are missing. Statements with `casez` and
+//! `casex` wildcards are not checked.
+
+use super::LintContext;
+use crate::{
+    crate_prelude::*,
+    hir::{self, Visitor},
+    latch::missing_cases,
+};
+use num::Zero;
+
+/// Report the incomplete `case` statements in the procedures of a module.
+pub(super) fn check(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    for &id in &lcx.module.procs {
+        let mut collector = CaseCollector { cx, cases: vec![] };
+        collector.visit_node_with_id(id.id(), false);
+        for (expr, ways) in collector.cases {
+            let missing = match missing_cases(cx, lcx.env(), expr, &ways)? {
+                Some(x) if !x.count.is_zero() => x,
+                _ => continue,
+            };
+            let ty = cx.type_of(expr, lcx.env())?;
+            let what = if ty.get_enum().is_some() {
+                "enumerators"
+            } else {
+                "values"
+            };
+            let mut list = missing
+                .names
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let more = missing.count - missing.names.len();
+            if !more.is_zero() {
+                list.push_str(&format!(", and {} more", more));
+            }
+            lcx.emit(
+                DiagBuilder2::warning(format!(
+                    "`case` statement does not cover all {} of `{}` and has no `default`",
+                    what,
+                    cx.span(expr).extract()
+                ))
+                .span(cx.span(expr))
+                .add_note(format!("Missing {}: {}", what, list)),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A visitor that collects the `case` statements without a `default` arm.
+struct CaseCollector<'a, 'c> {
+    cx: &'c GlobalContext<'a>,
+    cases: Vec<(NodeId, Vec<(Vec<NodeId>, NodeId)>)>,
+}
+
+impl<'a, 'c> Visitor<'a> for CaseCollector<'a, 'c> {
+    type Context = GlobalContext<'a>;
+
+    fn context(&self) -> &Self::Context {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a hir::Stmt) {
+        if let hir::StmtKind::Case {
+            expr,
+            ref ways,
+            default: None,
+            kind: ast::CaseKind::Normal,
+        } = stmt.kind
+        {
+            self.cases.push((expr, ways.clone()));
+        }
+        hir::walk_stmt(self, stmt);
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of unsynchronized clock domain crossings.
+//!
+//! Every register belongs to the clock domain of the clocked procedure that
+//! drives it. Combinational logic belongs to the domains of the signals it
+//! reads. A clocked procedure that samples a signal of another domain may see
+//! it change during its setup or hold time, which renders the register
+//! metastable. This is only safe if the signal is a register of the other
+//! domain that is copied into a register of the sampling domain, which in turn
+//! is only read by further registers of the sampling domain, as in a two-stage
+//! synchronizer. Other crossings are reported. The domains are only tracked
+//! within a module; signals entering through ports or from instances are not
+//! assigned to a domain.
+
+use super::{
+    clocking::{clocked_procs, ClockedProc},
+    LintContext,
+};
+use crate::{
+    crate_prelude::*,
+    hir::{self, HirNode, Visitor},
+    ParamEnv,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Report the signals of a module specialization that cross clock domains
+/// without a synchronizer.
+pub(super) fn check(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    let env = lcx.env();
+    let clocked = clocked_procs(lcx)?;
+    let clocked_ids: HashSet<NodeId> = clocked.iter().map(|p| p.prok.id).collect();
+
+    // Assign the registers to the domains of their clocks.
+    let mut domains: HashMap<NodeId, BTreeSet<NodeId>> = HashMap::new();
+    let mut registers = HashSet::new();
+    for prok in &clocked {
+        let clock = match prok.clock {
+            Some((x, _)) => x,
+            None => continue,
+        };
+        for node in &cx.accessed_nodes(prok.prok.id, env)?.written {
+            domains.entry(node.id()).or_default().insert(clock);
+            registers.insert(node.id());
+        }
+    }
+
+    // Propagate the domains through combinational logic, and note which
+    // signals are read where.
+    let mut comb = vec![];
+    let mut readers: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+    for &id in lcx.module.assigns.iter().chain(&lcx.module.procs) {
+        let acc = cx.accessed_nodes(id.id(), env)?;
+        for node in &acc.read {
+            readers.entry(node.id()).or_default().push(id.id());
+        }
+        if !clocked_ids.contains(&id.id()) {
+            comb.push(acc);
+        }
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for acc in &comb {
+            let mut incoming = BTreeSet::new();
+            for node in &acc.read {
+                if let Some(d) = domains.get(&node.id()) {
+                    incoming.extend(d.iter().cloned());
+                }
+            }
+            for node in &acc.written {
+                let entry = domains.entry(node.id()).or_default();
+                let before = entry.len();
+                entry.extend(incoming.iter().cloned());
+                changed |= entry.len() != before;
+            }
+        }
+    }
+
+    // Check the signals sampled by every clocked procedure.
+    let clock_of: HashMap<NodeId, Option<NodeId>> = clocked
+        .iter()
+        .map(|p| (p.prok.id, p.clock.map(|(x, _)| x)))
+        .collect();
+    for prok in &clocked {
+        let clock = match prok.clock {
+            Some((x, _)) => x,
+            None => continue,
+        };
+        let mut reported = HashSet::new();
+        for point in sampling_points(lcx, prok)? {
+            for node in &cx.accessed_nodes(point.expr, env)?.read {
+                let signal = node.id();
+                if Some(signal) == prok.reset.map(|(x, _)| x) || signal == clock {
+                    continue;
+                }
+                let other = match domains
+                    .get(&signal)
+                    .and_then(|d| d.iter().find(|&&d| d != clock))
+                {
+                    Some(&x) => x,
+                    None => continue,
+                };
+                if reported.contains(&signal) {
+                    continue;
+                }
+
+                // A register copied into a register that only feeds
+                // registers of the same domain is synchronized.
+                let copied = point.copies(cx, env, signal)?;
+                let synchronized = registers.contains(&signal)
+                    && domains[&signal].len() == 1
+                    && match point.copy_into {
+                        Some(dst) if copied => readers
+                            .get(&dst)
+                            .map(|r| r.iter().all(|id| clock_of.get(id) == Some(&Some(clock))))
+                            .unwrap_or(false),
+                        _ => false,
+                    };
+                if synchronized {
+                    continue;
+                }
+                reported.insert(signal);
+                lcx.emit(
+                    DiagBuilder2::warning(format!(
+                        "`{}` crosses from the domain of `{}` into the domain of `{}` without \
+                         a synchronizer",
+                        signal_name(cx, signal)?,
+                        signal_name(cx, other)?,
+                        signal_name(cx, clock)?
+                    ))
+                    .span(cx.span(point.expr))
+                    .add_note(format!(
+                        "Sample it with a register clocked by `{}`, whose output is only read \
+                         by other registers clocked by `{}`",
+                        signal_name(cx, clock)?,
+                        signal_name(cx, clock)?
+                    )),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An expression in a clocked procedure whose value is sampled.
+struct SamplingPoint {
+    /// The expression.
+    expr: NodeId,
+    /// The signal the expression is assigned to, if it is the right-hand side
+    /// of an assignment to a single signal.
+    copy_into: Option<NodeId>,
+}
+
+impl SamplingPoint {
+    /// Check whether the expression is a plain reference to a signal.
+    fn copies<'a>(&self, cx: &impl Context<'a>, env: ParamEnv, signal: NodeId) -> Result<bool> {
+        Ok(match cx.hir_of(self.expr)? {
+            HirNode::Expr(hir::Expr {
+                kind: hir::ExprKind::Ident(..),
+                ..
+            }) => cx.resolve_node(self.expr, env)? == signal,
+            _ => false,
+        })
+    }
+}
+
+/// Collect the right-hand sides of the assignments and the conditions in a
+/// clocked procedure.
+fn sampling_points(lcx: &LintContext, prok: &ClockedProc) -> Result<Vec<SamplingPoint>> {
+    let mut collector = SamplingCollector {
+        cx: lcx.cx,
+        exprs: vec![],
+    };
+    collector.visit_node_with_id(prok.body, false);
+    let mut points = vec![];
+    for (expr, lhs) in collector.exprs {
+        let copy_into = match lhs {
+            Some(lhs) => match lcx.cx.hir_of(lhs)? {
+                HirNode::Expr(hir::Expr {
+                    kind: hir::ExprKind::Ident(..),
+                    ..
+                }) => Some(lcx.cx.resolve_node(lhs, lcx.env())?),
+                _ => None,
+            },
+            None => None,
+        };
+        points.push(SamplingPoint { expr, copy_into });
+    }
+    Ok(points)
+}
+
+/// A visitor that collects the sampled expressions of a procedure, together
+/// with the left-hand side of the assignment they are part of.
+struct SamplingCollector<'a, 'c> {
+    cx: &'c GlobalContext<'a>,
+    exprs: Vec<(NodeId, Option<NodeId>)>,
+}
+
+impl<'a, 'c> Visitor<'a> for SamplingCollector<'a, 'c> {
+    type Context = GlobalContext<'a>;
+
+    fn context(&self) -> &Self::Context {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a hir::Stmt) {
+        match stmt.kind {
+            hir::StmtKind::Assign { lhs, rhs, .. } => self.exprs.push((rhs, Some(lhs))),
+            hir::StmtKind::If { cond, .. } => self.exprs.push((cond, None)),
+            hir::StmtKind::Case { expr, .. } => self.exprs.push((expr, None)),
+            _ => (),
+        }
+        hir::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, _expr: &'a hir::Expr<'a>, _lvalue: bool) {}
+}
+
+/// Determine the name of a signal.
+fn signal_name<'a>(cx: &impl Context<'a>, id: NodeId) -> Result<Name> {
+    Ok(match cx.hir_of(id)? {
+        HirNode::VarDecl(x) => x.name.value,
+        HirNode::IntPort(x) => x.name.value,
+        _ => cx.span(id).extract().as_str().into(),
+    })
+}
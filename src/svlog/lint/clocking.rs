@@ -0,0 +1,102 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Identification of the clocks and resets of clocked procedures.
+//!
+//! A procedure is clocked if it is triggered by the edges of signals, as in
+//! `always_ff @(posedge clk)`. If it is triggered by more than one edge, the
+//! signal tested by an `if` around the entire body is its asynchronous reset,
+//! and the remaining one its clock.
+
+use super::LintContext;
+use crate::{
+    crate_prelude::*,
+    design::event_signal,
+    hir::{self, HirNode},
+};
+
+/// A procedure triggered by clock edges.
+pub(super) struct ClockedProc<'a> {
+    /// The procedure.
+    pub prok: &'a hir::Proc,
+    /// The clock signal and the edge of it that triggers the procedure.
+    pub clock: Option<(NodeId, ast::EdgeIdent)>,
+    /// The asynchronous reset signal and the edge of it that triggers the
+    /// procedure.
+    pub reset: Option<(NodeId, ast::EdgeIdent)>,
+    /// The statement executed while the reset is asserted.
+    pub reset_stmt: Option<NodeId>,
+    /// The statement executed on every trigger.
+    pub body: NodeId,
+}
+
+/// Find the clocked procedures of a module specialization.
+pub(super) fn clocked_procs<'a>(lcx: &LintContext<'a, '_>) -> Result<Vec<ClockedProc<'a>>> {
+    let cx = lcx.cx;
+    let env = lcx.env();
+    let mut procs = vec![];
+    for &id in &lcx.module.procs {
+        let prok = match cx.hir_of(id.id())? {
+            HirNode::Proc(x) => x,
+            _ => continue,
+        };
+        let (event, body) = match cx.hir_of(prok.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind:
+                    hir::StmtKind::Timed {
+                        control: hir::TimingControl::ExplicitEvent(event),
+                        stmt,
+                    },
+                ..
+            }) => (*event, *stmt),
+            _ => continue,
+        };
+        let events = match cx.hir_of(event)? {
+            HirNode::EventExpr(x) => &x.events,
+            _ => continue,
+        };
+        if events.is_empty() || events.iter().any(|e| e.edge == ast::EdgeIdent::Implicit) {
+            continue;
+        }
+        let (reset_cond, reset_branch) = match cx.hir_of(single_stmt(cx, body)?)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::If {
+                    cond, main_stmt, ..
+                },
+                ..
+            }) if events.len() > 1 => (event_signal(cx, *cond, env)?, Some(*main_stmt)),
+            _ => (None, None),
+        };
+        let mut clock = None;
+        let mut reset = None;
+        for event in events {
+            let signal = match event_signal(cx, event.expr, env)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if reset.is_none() && reset_cond == Some(signal) {
+                reset = Some((signal, event.edge));
+            } else if clock.is_none() {
+                clock = Some((signal, event.edge));
+            }
+        }
+        procs.push(ClockedProc {
+            prok,
+            clock,
+            reset,
+            reset_stmt: reset.and(reset_branch),
+            body,
+        });
+    }
+    Ok(procs)
+}
+
+/// Look through blocks that contain a single statement.
+pub(super) fn single_stmt<'a>(cx: &impl Context<'a>, stmt_id: NodeId) -> Result<NodeId> {
+    match cx.hir_of(stmt_id)? {
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Block(stmts),
+            ..
+        }) if stmts.len() == 1 => single_stmt(cx, stmts[0]),
+        _ => Ok(stmt_id),
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of generate branches that are never elaborated.
+//!
+//! The condition of an if-generate statement is evaluated in every
+//! specialization of its module, and in every iteration of the surrounding
+//! generate loops. A branch that is not taken in any of them is dead code
+//! under the elaborated design, which often indicates configuration code that
+//! can no longer be exercised. Statements inside a dead branch are not checked
+//! themselves.
+
+use super::LintContext;
+use crate::{crate_prelude::*, design::expand_generate, hir::HirNode, ParamEnv};
+use std::collections::HashMap;
+
+/// Report the generate branches of a module that are not taken in any of its
+/// specializations.
+pub(super) fn check(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    let module_id = lcx.module.id.id();
+
+    // Consider all specializations of the module at once, and report from the
+    // first one only.
+    let specs: Vec<_> = lcx
+        .design
+        .modules
+        .iter()
+        .filter(|m| m.id.id() == module_id)
+        .collect();
+    if !std::ptr::eq(specs[0], lcx.module) {
+        return Ok(());
+    }
+    let mut taken = HashMap::new();
+    for spec in &specs {
+        collect_block(cx, &spec.hir.block, spec.id.env(), &mut taken)?;
+    }
+
+    let mut gens: Vec<_> = taken.into_iter().collect();
+    gens.sort_by_key(|&(id, _)| cx.span(id).begin);
+    for (gen_id, (main, other)) in gens {
+        let (cond, else_body) = match cx.hir_of(gen_id)? {
+            HirNode::Gen(hir::Gen {
+                kind: hir::GenKind::If {
+                    cond, else_body, ..
+                },
+                ..
+            }) => (*cond, *else_body),
+            _ => unreachable!(),
+        };
+        let (branch, value) = if !main {
+            ("`if`", "false")
+        } else if !other && else_body.is_some() {
+            ("`else`", "true")
+        } else {
+            continue;
+        };
+        let span = cx.span(cond);
+        lcx.emit(
+            DiagBuilder2::warning(format!(
+                "the {} branch of this generate statement is never elaborated",
+                branch
+            ))
+            .span(span)
+            .add_note(format!(
+                "`{}` is {} in every specialization of module `{}`",
+                span.extract(),
+                value,
+                lcx.module.hir.name
+            )),
+        );
+    }
+    Ok(())
+}
+
+/// Record which branches of the if-generate statements in a block are taken.
+///
+/// Maps each statement to whether its main and its else branch are taken.
+fn collect_block<'a>(
+    cx: &impl Context<'a>,
+    block: &'a hir::ModuleBlock,
+    env: ParamEnv,
+    taken: &mut HashMap<NodeId, (bool, bool)>,
+) -> Result<()> {
+    for &gen_id in &block.gens {
+        if let HirNode::Gen(hir::Gen {
+            kind: hir::GenKind::If { cond, .. },
+            ..
+        }) = cx.hir_of(gen_id)?
+        {
+            let entry = taken.entry(gen_id).or_insert((false, false));
+            if cx.constant_value_of(*cond, env).is_false() {
+                entry.1 = true;
+            } else {
+                entry.0 = true;
+            }
+        }
+        expand_generate(cx, gen_id, env, |block_id, env, _| {
+            match cx.hir_of(block_id)? {
+                HirNode::GenerateBlock(x) => collect_block(cx, &x.block, env, taken),
+                _ => unreachable!(),
+            }
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,377 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Lint rules that check the elaborated design.
+//!
+//! The lint rules run on the module specializations of the elaborated design
+//! when invoked as `moore lint` or with `--lint`, instead of generating code.
+//! Every rule reports its findings as warnings in the warning group of the
+//! same name. Rules can therefore be enabled with `-W<rule>`, disabled with
+//! `-Wno-<rule>`, turned into errors with `-Werror=<rule>`, and suppressed with
+//! lint directive comments or waivers like any other warning. Rules that are
+//! off by default only run if they are enabled explicitly. Some rules take
+//! additional options, which are set with `--lint-option <rule>.<name>=<value>`,
+//! or listed one per line in a file passed with `--lint-config`.
+//!
+//! To add a rule, implement its check in a submodule, add its name to the
+//! `WARNING_GROUPS`, and register it in `RULES`.
+
+use crate::{
+    common::{errors::WarningLevel, Session},
+    crate_prelude::*,
+    design::{Design, DesignModule},
+    hir::HirNode,
+    GlobalContext, ParamEnv,
+};
+use regex::Regex;
+
+mod assign_kind;
+mod case;
+mod cdc;
+mod clocking;
+mod dead_generate;
+mod naming;
+mod reset;
+mod unused;
+mod xvalue;
+
+/// A lint rule.
+pub struct LintRule {
+    /// The name of the rule, which is also the warning group of its findings.
+    pub name: &'static str,
+    /// Whether the rule runs unless it is disabled.
+    pub default: bool,
+    /// The options that configure the rule.
+    pub options: &'static [LintOption],
+    /// Check a module specialization.
+    pub check: for<'a, 'c> fn(&LintContext<'a, 'c>) -> Result<()>,
+}
+
+/// An option that configures a lint rule.
+pub struct LintOption {
+    /// The name of the option.
+    pub name: &'static str,
+    /// The value of the option if none is given on the command line.
+    pub default: &'static str,
+    /// A short description of the option.
+    pub desc: &'static str,
+    /// The kind of value the option takes.
+    pub kind: LintOptionKind,
+}
+
+/// The kind of value a lint option takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintOptionKind {
+    /// Arbitrary text.
+    Text,
+    /// A regular expression, or nothing to disable the corresponding check.
+    Regex,
+    /// One of a fixed set of values.
+    Choice(&'static [&'static str]),
+}
+
+/// The lint rules, in the order in which they run.
+pub static RULES: &[LintRule] = &[
+    LintRule {
+        name: "blocking-ff",
+        default: true,
+        options: &[],
+        check: assign_kind::check_blocking,
+    },
+    LintRule {
+        name: "cdc",
+        default: true,
+        options: &[],
+        check: cdc::check,
+    },
+    LintRule {
+        name: "comb-loop",
+        default: true,
+        options: &[],
+        check: check_comb_loops,
+    },
+    LintRule {
+        name: "dead-generate",
+        default: true,
+        options: &[],
+        check: dead_generate::check,
+    },
+    LintRule {
+        name: "implicit-net",
+        default: true,
+        options: &[],
+        check: check_implicit_nets,
+    },
+    LintRule {
+        name: "incomplete-case",
+        default: true,
+        options: &[],
+        check: case::check,
+    },
+    LintRule {
+        name: "latch",
+        default: true,
+        options: &[],
+        check: check_latches,
+    },
+    LintRule {
+        name: "naming",
+        default: false,
+        options: &[
+            LintOption {
+                name: "module",
+                default: "^[a-z][a-z0-9_]*$",
+                desc: "pattern for module names",
+                kind: LintOptionKind::Regex,
+            },
+            LintOption {
+                name: "parameter",
+                default: "^[A-Z][A-Z0-9_]*$",
+                desc: "pattern for parameter names",
+                kind: LintOptionKind::Regex,
+            },
+            LintOption {
+                name: "signal",
+                default: "^[a-z][a-z0-9_]*$",
+                desc: "pattern for port, variable, and net names",
+                kind: LintOptionKind::Regex,
+            },
+            LintOption {
+                name: "clock",
+                default: "^clk",
+                desc: "pattern for clock names",
+                kind: LintOptionKind::Regex,
+            },
+            LintOption {
+                name: "reset",
+                default: "^rst",
+                desc: "pattern for asynchronous reset names",
+                kind: LintOptionKind::Regex,
+            },
+        ],
+        check: naming::check,
+    },
+    LintRule {
+        name: "nonblocking-comb",
+        default: true,
+        options: &[],
+        check: assign_kind::check_nonblocking,
+    },
+    LintRule {
+        name: "reset",
+        default: true,
+        options: &[
+            LintOption {
+                name: "style",
+                default: "any",
+                desc: "required reset style, or `any` to only require consistency",
+                kind: LintOptionKind::Choice(&["any", "async", "sync"]),
+            },
+            LintOption {
+                name: "polarity",
+                default: "any",
+                desc: "required reset polarity, or `any` to only require consistency",
+                kind: LintOptionKind::Choice(&["any", "low", "high"]),
+            },
+            LintOption {
+                name: "required",
+                default: "false",
+                desc: "whether every register needs a reset",
+                kind: LintOptionKind::Choice(&["false", "true"]),
+            },
+        ],
+        check: reset::check,
+    },
+    LintRule {
+        name: "unused",
+        default: true,
+        options: &[],
+        check: unused::check,
+    },
+    LintRule {
+        name: "x-assign",
+        default: true,
+        options: &[],
+        check: xvalue::check_assign,
+    },
+    LintRule {
+        name: "x-compare",
+        default: true,
+        options: &[],
+        check: xvalue::check_compare,
+    },
+];
+
+/// Find a lint rule by name.
+pub fn find_rule(name: &str) -> Option<&'static LintRule> {
+    RULES.iter().find(|rule| rule.name == name)
+}
+
+impl LintRule {
+    /// Check whether the rule is enabled in a session.
+    pub fn is_enabled(&self, sess: &Session) -> bool {
+        match sess.opts.warning_levels.get(self.name) {
+            Some(WarningLevel::Ignore) => false,
+            Some(_) => true,
+            None => self.default,
+        }
+    }
+
+    /// Find an option of the rule by name.
+    pub fn find_option(&self, name: &str) -> Option<&'static LintOption> {
+        self.options.iter().find(|option| option.name == name)
+    }
+}
+
+impl LintOption {
+    /// Check whether a value is valid for the option.
+    ///
+    /// Returns a description of the problem as an error.
+    pub fn validate(&self, value: &str) -> std::result::Result<(), String> {
+        match self.kind {
+            LintOptionKind::Text => Ok(()),
+            LintOptionKind::Regex => Regex::new(value).map(|_| ()).map_err(|e| e.to_string()),
+            LintOptionKind::Choice(choices) if choices.contains(&value) => Ok(()),
+            LintOptionKind::Choice(choices) => Err(format!(
+                "expected one of {}",
+                choices
+                    .iter()
+                    .map(|c| format!("`{}`", c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+/// The context in which a lint rule checks a module specialization.
+pub struct LintContext<'a, 'c> {
+    /// The compilation context.
+    pub cx: &'c GlobalContext<'a>,
+    /// The elaborated design.
+    pub design: &'c Design<'a>,
+    /// The module specialization being checked.
+    pub module: &'c DesignModule<'a>,
+    /// The rule performing the check.
+    rule: &'static LintRule,
+}
+
+impl<'a, 'c> LintContext<'a, 'c> {
+    /// Get the parameter environment of the module specialization.
+    pub fn env(&self) -> ParamEnv {
+        self.module.id.env()
+    }
+
+    /// Get the value of an option of the rule.
+    pub fn option(&self, name: &str) -> &'c str {
+        let option = match self.rule.find_option(name) {
+            Some(x) => x,
+            None => bug_span!(
+                self.module.hir.span,
+                self.cx,
+                "lint rule `{}` has no option `{}`",
+                self.rule.name,
+                name
+            ),
+        };
+        self.cx
+            .sess
+            .opts
+            .lint_options
+            .get(&format!("{}.{}", self.rule.name, name))
+            .map(String::as_str)
+            .unwrap_or(option.default)
+    }
+
+    /// Get the value of an option of the rule that holds a regular
+    /// expression.
+    ///
+    /// Returns `None` if the option is empty. The value has been validated
+    /// when the option was set.
+    pub fn regex_option(&self, name: &str) -> Option<Regex> {
+        let value = self.option(name);
+        if value.is_empty() {
+            None
+        } else {
+            Some(Regex::new(value).expect("lint option not validated"))
+        }
+    }
+
+    /// Report a finding of the rule.
+    ///
+    /// The diagnostic is assigned to the warning group of the rule.
+    pub fn emit(&self, diag: DiagBuilder2) {
+        self.cx.emit(diag.group(self.rule.name));
+    }
+}
+
+/// Run the enabled lint rules on every module specialization of a design.
+///
+/// Identical findings in multiple specializations of a module are reported
+/// once.
+pub fn run<'a>(cx: &GlobalContext<'a>, design: &Design<'a>) -> Result<()> {
+    let rules: Vec<_> = RULES
+        .iter()
+        .filter(|rule| rule.is_enabled(cx.sess))
+        .collect();
+    let top = match design.tops.last() {
+        Some(&x) => design.modules[x].id,
+        None => return Ok(()),
+    };
+    let mut result = Ok(());
+    cx.elaborate_specialization(top, || {
+        for module in &design.modules {
+            cx.elaborate_specialization(module.id, || {
+                for &rule in &rules {
+                    debug!("Running lint `{}` on {:?}", rule.name, module.id);
+                    let lcx = LintContext {
+                        cx,
+                        design,
+                        module,
+                        rule,
+                    };
+                    if (rule.check)(&lcx).is_err() {
+                        result = Err(());
+                    }
+                }
+            });
+        }
+    });
+    result
+}
+
+/// Report combinational loops, as detected by the `comb_loops` module.
+fn check_comb_loops(lcx: &LintContext) -> Result<()> {
+    crate::comb_loops::check_module(lcx.cx, lcx.module.id.id(), lcx.env())
+}
+
+/// Report implicitly declared nets, as determined by the `implicit_nets`
+/// module.
+fn check_implicit_nets(lcx: &LintContext) -> Result<()> {
+    for net in lcx.cx.implicit_nets(lcx.module.hir.ast) {
+        if net.decl.is_none() {
+            continue;
+        }
+        lcx.emit(
+            DiagBuilder2::warning(format!("`{}` is implicitly declared as a net", net.name))
+                .span(net.name.span)
+                .add_note(
+                    "Declare it explicitly, or disallow implicit nets with `default_nettype none`",
+                ),
+        );
+    }
+    Ok(())
+}
+
+/// Report latches inferred from `always_comb` procedures, as detected by the
+/// `latch` module.
+fn check_latches(lcx: &LintContext) -> Result<()> {
+    for &id in &lcx.module.procs {
+        match lcx.cx.hir_of(id.id())? {
+            HirNode::Proc(x) if x.kind == ast::ProcedureKind::AlwaysComb => {
+                crate::latch::check_procedure(lcx.cx, id.id(), id.env())?
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
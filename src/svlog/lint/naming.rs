@@ -0,0 +1,89 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Checking of naming conventions.
+//!
+//! The names of modules, parameters, and signals are checked against regular
+//! expressions given as options of the `naming` rule. Clocks and asynchronous
+//! resets of clocked procedures are checked against the `clock` and `reset`
+//! patterns instead of the `signal` pattern. The rule is off by default, since
+//! naming conventions differ between code bases.
+
+use super::{clocking::clocked_procs, LintContext};
+use crate::{crate_prelude::*, hir::HirNode};
+use std::collections::HashSet;
+
+/// Report the names in a module specialization that violate the naming
+/// conventions.
+pub(super) fn check(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    let check_name = |option: &str, what: &str, name: Spanned<Name>| {
+        let regex = match lcx.regex_option(option) {
+            Some(x) => x,
+            None => return,
+        };
+        if regex.is_match(&name.value.as_str()) {
+            return;
+        }
+        lcx.emit(
+            DiagBuilder2::warning(format!(
+                "{} `{}` does not match the naming convention `{}`",
+                what,
+                name,
+                regex.as_str()
+            ))
+            .span(name.span)
+            .add_note(format!(
+                "The convention is set with `--lint-option naming.{}=<regex>`",
+                option
+            )),
+        );
+    };
+
+    // Check the module name. Specializations of a module report the same
+    // finding, which is deduplicated.
+    check_name("module", "module", lcx.module.hir.name);
+
+    // Check the parameter names.
+    for &param_id in lcx.module.hir.params {
+        let name = match cx.hir_of(param_id)? {
+            HirNode::ValueParam(p) => p.name,
+            HirNode::TypeParam(p) => p.name,
+            _ => continue,
+        };
+        check_name("parameter", "parameter", name);
+    }
+
+    // Check the signal names, using the clock and reset patterns for the
+    // signals that trigger clocked procedures.
+    let mut clocks = HashSet::new();
+    let mut resets = HashSet::new();
+    for prok in clocked_procs(lcx)? {
+        clocks.extend(prok.clock.map(|(x, _)| x));
+        resets.extend(prok.reset.map(|(x, _)| x));
+    }
+    let check_signal = |ids: &[NodeId], name: Spanned<Name>| {
+        if ids.iter().any(|id| resets.contains(id)) {
+            check_name("reset", "reset", name);
+        } else if ids.iter().any(|id| clocks.contains(id)) {
+            check_name("clock", "clock", name);
+        } else {
+            check_name("signal", "signal", name);
+        }
+    };
+    let mut port_decls = HashSet::new();
+    for port in &lcx.module.ports {
+        let mut ids = vec![port.port.id];
+        if port.port.data.is_none() {
+            let decl = cx.resolve_node(port.port.id, lcx.env())?;
+            port_decls.insert(decl);
+            ids.push(decl);
+        }
+        check_signal(&ids, port.port.name);
+    }
+    for decl in &lcx.module.decls {
+        if !port_decls.contains(&decl.id) {
+            check_signal(&[decl.id], decl.hir.name);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,306 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Checking of the reset style of clocked procedures.
+//!
+//! A clocked procedure has an asynchronous reset if the reset is one of its
+//! trigger events, and a synchronous reset if the entire body is an `if` that
+//! tests a signal and only assigns constants in its main branch. The reset is
+//! active-low if it triggers on a falling edge or is tested negated. Resets
+//! must follow the style and polarity set with the `style` and `polarity`
+//! options; if these are `any`, all resets of a module must agree with the
+//! first one. With the `required` option, registers that are not assigned
+//! when the reset is asserted are reported as well.
+
+use super::{
+    clocking::{clocked_procs, single_stmt, ClockedProc},
+    LintContext,
+};
+use crate::{
+    crate_prelude::*,
+    hir::{self, HirNode, Visitor},
+    latch::is_constant,
+};
+
+/// Report the clocked procedures of a module specialization whose reset
+/// deviates from the reset style, and the registers without a reset.
+pub(super) fn check(lcx: &LintContext) -> Result<()> {
+    let mut first: Option<Reset> = None;
+    for prok in clocked_procs(lcx)? {
+        let reset = match classify(lcx, &prok)? {
+            Some(x) => x,
+            None => {
+                check_required(lcx, &prok, None)?;
+                continue;
+            }
+        };
+        check_required(lcx, &prok, Some(reset.stmt))?;
+
+        // Compare the reset against the policy, or against the first reset
+        // of the module if the policy leaves it open.
+        let required = Style {
+            sync: match lcx.option("style") {
+                "async" => Some(false),
+                "sync" => Some(true),
+                _ => None,
+            },
+            low: match lcx.option("polarity") {
+                "low" => Some(true),
+                "high" => Some(false),
+                _ => None,
+            },
+        };
+        let actual = reset.style();
+        let violates = |a: Option<bool>, b: Option<bool>| b.is_some() && a != b;
+        if violates(actual.sync, required.sync) || violates(actual.low, required.low) {
+            lcx.emit(
+                DiagBuilder2::warning(format!(
+                    "`{}` is {} reset, but resets must be {}",
+                    reset.name,
+                    actual.describe_indefinite(),
+                    required.describe()
+                ))
+                .span(reset.name.span)
+                .add_note(
+                    "The reset style is set with `--lint-option reset.style=<style>` and \
+                     `--lint-option reset.polarity=<polarity>`",
+                ),
+            );
+            continue;
+        }
+        let first = match first {
+            Some(ref x) => x,
+            None => {
+                first = Some(reset);
+                continue;
+            }
+        };
+        let expected = first.style();
+        if violates(actual.sync, expected.sync) || violates(actual.low, expected.low) {
+            lcx.emit(
+                DiagBuilder2::warning(format!(
+                    "`{}` is {} reset, unlike the {} reset `{}` of module `{}`",
+                    reset.name,
+                    actual.describe_indefinite(),
+                    expected.describe(),
+                    first.name,
+                    lcx.module.hir.name
+                ))
+                .span(reset.name.span)
+                .add_note(format!("`{}` is used here:", first.name))
+                .span(first.name.span),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The reset of a clocked procedure.
+struct Reset {
+    /// The reset signal as it appears in the procedure.
+    name: Spanned<Name>,
+    /// Whether the reset is synchronous.
+    sync: bool,
+    /// Whether the reset is active-low.
+    low: bool,
+    /// The statement executed while the reset is asserted.
+    stmt: NodeId,
+}
+
+impl Reset {
+    /// Get the style of the reset.
+    fn style(&self) -> Style {
+        Style {
+            sync: Some(self.sync),
+            low: Some(self.low),
+        }
+    }
+}
+
+/// A reset style, with `None` for the aspects that are left open.
+struct Style {
+    sync: Option<bool>,
+    low: Option<bool>,
+}
+
+impl Style {
+    /// Describe the style, as in "asynchronous active-low".
+    fn describe(&self) -> String {
+        let mut words = vec![];
+        match self.sync {
+            Some(true) => words.push("synchronous"),
+            Some(false) => words.push("asynchronous"),
+            None => (),
+        }
+        match self.low {
+            Some(true) => words.push("active-low"),
+            Some(false) => words.push("active-high"),
+            None => (),
+        }
+        words.join(" ")
+    }
+
+    /// Describe the style with an indefinite article, as in "an asynchronous
+    /// active-low".
+    fn describe_indefinite(&self) -> String {
+        let desc = self.describe();
+        if desc.starts_with('a') {
+            format!("an {}", desc)
+        } else {
+            format!("a {}", desc)
+        }
+    }
+}
+
+/// Determine the reset of a clocked procedure, if it has one.
+fn classify<'a>(lcx: &LintContext<'a, '_>, prok: &ClockedProc<'a>) -> Result<Option<Reset>> {
+    let cx = lcx.cx;
+    let env = lcx.env();
+    let (cond, main_stmt, has_else) = match cx.hir_of(single_stmt(cx, prok.body)?)? {
+        HirNode::Stmt(hir::Stmt {
+            kind:
+                hir::StmtKind::If {
+                    cond,
+                    main_stmt,
+                    else_stmt,
+                },
+            ..
+        }) => (*cond, *main_stmt, else_stmt.is_some()),
+        _ => return Ok(None),
+    };
+    let (signal_expr, negated) = match tested_signal(cx, cond, false)? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let signal = cx.resolve_node(signal_expr, env)?;
+    let name = match cx.hir_of(signal_expr)? {
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Ident(name),
+            ..
+        }) => *name,
+        _ => return Ok(None),
+    };
+
+    // An asynchronous reset is identified together with the clock.
+    if let Some((reset, edge)) = prok.reset {
+        if reset != signal {
+            return Ok(None);
+        }
+        return Ok(Some(Reset {
+            name,
+            sync: false,
+            low: edge == ast::EdgeIdent::Negedge,
+            stmt: main_stmt,
+        }));
+    }
+
+    // A synchronous reset only assigns constants.
+    if !has_else || prok.clock.map(|(x, _)| x) == Some(signal) {
+        return Ok(None);
+    }
+    let mut collector = AssignCollector { cx, rhs: vec![] };
+    collector.visit_node_with_id(main_stmt, false);
+    if collector.rhs.is_empty() {
+        return Ok(None);
+    }
+    for rhs in collector.rhs {
+        if !is_constant(cx, env, rhs)? {
+            return Ok(None);
+        }
+    }
+    Ok(Some(Reset {
+        name,
+        sync: true,
+        low: negated,
+        stmt: main_stmt,
+    }))
+}
+
+/// Determine the signal a condition tests, and whether it is negated.
+fn tested_signal<'a>(
+    cx: &impl Context<'a>,
+    expr_id: NodeId,
+    negated: bool,
+) -> Result<Option<(NodeId, bool)>> {
+    match cx.hir_of(expr_id)? {
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Ident(..),
+            ..
+        }) => Ok(Some((expr_id, negated))),
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Unary(hir::UnaryOp::LogicNot, arg),
+            ..
+        })
+        | HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Unary(hir::UnaryOp::BitNot, arg),
+            ..
+        }) => tested_signal(cx, *arg, !negated),
+        _ => Ok(None),
+    }
+}
+
+/// Report the registers of a clocked procedure that are not assigned by its
+/// reset, if the `required` option is set.
+fn check_required(lcx: &LintContext, prok: &ClockedProc, reset: Option<NodeId>) -> Result<()> {
+    if lcx.option("required") != "true" {
+        return Ok(());
+    }
+    let cx = lcx.cx;
+    let env = lcx.env();
+    let reset_written = match reset {
+        Some(id) => cx.accessed_nodes(id, env)?.written.clone(),
+        None => Default::default(),
+    };
+    let mut missing: Vec<_> = cx
+        .accessed_nodes(prok.prok.id, env)?
+        .written
+        .difference(&reset_written)
+        .cloned()
+        .collect();
+    missing.sort_by_key(|node| cx.span(node.id()).begin);
+    for node in missing {
+        // Variables declared inside the procedure hold intermediate results.
+        let (name, span) = match cx.hir_of(node.id())? {
+            HirNode::VarDecl(x) => (x.name.value, x.name.span),
+            HirNode::IntPort(x) => (x.name.value, x.name.span),
+            _ => continue,
+        };
+        if span.begin >= prok.prok.span.begin && span.end <= prok.prok.span.end {
+            continue;
+        }
+        let mut diag = DiagBuilder2::warning(format!("register `{}` has no reset", name))
+            .span(span)
+            .add_note("Every register needs a reset, as set with `--lint-option reset.required`");
+        diag = match reset {
+            Some(id) => diag
+                .add_note("It is not assigned in the reset branch here:")
+                .span(cx.span(id)),
+            None => diag
+                .add_note("It is assigned in a procedure without a reset here:")
+                .span(prok.prok.span),
+        };
+        lcx.emit(diag);
+    }
+    Ok(())
+}
+
+/// A visitor that collects the right-hand sides of the assignments in a
+/// statement.
+struct AssignCollector<'a, 'c> {
+    cx: &'c GlobalContext<'a>,
+    rhs: Vec<NodeId>,
+}
+
+impl<'a, 'c> Visitor<'a> for AssignCollector<'a, 'c> {
+    type Context = GlobalContext<'a>;
+
+    fn context(&self) -> &Self::Context {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a hir::Stmt) {
+        if let hir::StmtKind::Assign { rhs, .. } = stmt.kind {
+            self.rhs.push(rhs);
+        }
+        hir::walk_stmt(self, stmt);
+    }
+}
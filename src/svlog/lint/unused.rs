@@ -0,0 +1,162 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of unused signals, parameters, and ports.
+//!
+//! A variable or net is unused if no assignment, procedure, or instance port
+//! connection in the design reads it. Since the reads of the entire design are
+//! considered, a signal that is only read through a hierarchical name from
+//! another scope counts as used. An input port is unused if it is never read,
+//! an output port if it is never driven. A parameter is unused if no name in
+//! the elaborated design refers to it. Declarations carrying an `unused`
+//! attribute, as in `(* unused *) logic x;`, are exempt.
+
+use super::LintContext;
+use crate::{
+    common::source::Location,
+    crate_prelude::*,
+    hir::{AccessTable, AccessedNode, HirNode},
+};
+use std::collections::HashSet;
+
+/// Report the unused declarations of a module specialization.
+pub(super) fn check(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    let usage = Usage::collect(lcx)?;
+
+    // Ports without details refer to a declaration inside the module, which
+    // is reported as the port.
+    let mut port_decls = HashSet::new();
+    for port in &lcx.module.ports {
+        let mut ids = vec![port.port.id];
+        if port.port.data.is_none() {
+            let decl = cx.resolve_node(port.port.id, lcx.env())?;
+            port_decls.insert(decl);
+            ids.push(decl);
+        }
+        if is_exempt(lcx, port.port.ast) {
+            continue;
+        }
+        let read = ids.iter().any(|id| usage.read.contains(id));
+        let written = ids.iter().any(|id| usage.written.contains(id));
+        let msg = match port.port.dir {
+            ast::PortDir::Input if !read => "is never read",
+            ast::PortDir::Output if !written => "is never driven",
+            ast::PortDir::Inout | ast::PortDir::Ref if !read && !written => "is never used",
+            _ => continue,
+        };
+        lcx.emit(
+            DiagBuilder2::warning(format!(
+                "{} port `{}` {}",
+                port.port.dir, port.port.name, msg
+            ))
+            .span(port.port.name.span),
+        );
+    }
+
+    // Report variables and nets that are never read.
+    for decl in &lcx.module.decls {
+        if port_decls.contains(&decl.id) || usage.read.contains(&decl.id) {
+            continue;
+        }
+        if is_exempt(lcx, cx.ast_for_id(decl.id)) {
+            continue;
+        }
+        let msg = if usage.written.contains(&decl.id) || decl.hir.init.is_some() {
+            format!("`{}` is assigned but never read", decl.name)
+        } else {
+            format!("`{}` is never used", decl.name)
+        };
+        lcx.emit(DiagBuilder2::warning(msg).span(decl.hir.name.span));
+    }
+
+    // Report parameters that are never referred to.
+    for &param_id in lcx.module.hir.params {
+        if cx.is_referenced(param_id) {
+            continue;
+        }
+        let name = match cx.hir_of(param_id)? {
+            HirNode::ValueParam(p) => p.name,
+            HirNode::TypeParam(p) => p.name,
+            _ => continue,
+        };
+        if is_exempt(lcx, cx.ast_for_id(param_id)) {
+            continue;
+        }
+        lcx.emit(
+            DiagBuilder2::warning(format!("parameter `{}` is never used", name)).span(name.span),
+        );
+    }
+    Ok(())
+}
+
+/// The signals read and written in a design.
+#[derive(Default)]
+struct Usage {
+    read: HashSet<NodeId>,
+    written: HashSet<NodeId>,
+}
+
+impl Usage {
+    /// Collect the signals read and written anywhere in the design.
+    ///
+    /// This also resolves every name in the expressions of the design, which
+    /// marks the parameters they refer to as referenced.
+    fn collect(lcx: &LintContext) -> Result<Self> {
+        let cx = lcx.cx;
+        let mut usage = Usage::default();
+        for module in &lcx.design.modules {
+            for &id in module.assigns.iter().chain(&module.procs) {
+                let acc = cx.accessed_nodes(id.id(), id.env())?;
+                usage.add_reads(&acc);
+                usage.add_writes(&acc.written);
+            }
+            for decl in &module.decls {
+                if let Some(init) = decl.hir.init {
+                    usage.add_reads(&*cx.accessed_nodes(init, decl.env)?);
+                }
+            }
+            for inst in &module.insts {
+                let ports = &lcx.design.modules[inst.module].ports;
+                for (port, expr) in ports.iter().zip(&inst.ports) {
+                    let expr = match expr {
+                        Some(x) => x,
+                        None => continue,
+                    };
+                    let acc = cx.accessed_nodes(expr.id(), expr.env())?;
+                    match port.port.dir {
+                        ast::PortDir::Input => usage.add_reads(&acc),
+                        ast::PortDir::Output => usage.add_writes(&acc.read),
+                        ast::PortDir::Inout | ast::PortDir::Ref => {
+                            usage.add_reads(&acc);
+                            usage.add_writes(&acc.read);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(usage)
+    }
+
+    fn add_reads(&mut self, acc: &AccessTable) {
+        self.read.extend(acc.read.iter().map(|x| x.id()));
+    }
+
+    fn add_writes<'b>(&mut self, nodes: impl IntoIterator<Item = &'b AccessedNode>) {
+        self.written.extend(nodes.into_iter().map(|x| x.id()));
+    }
+}
+
+/// Check whether a declaration carries an `unused` attribute.
+///
+/// The attribute may precede the declared name, or the declaration it is part
+/// of, such as `(* unused *) logic a, b;`.
+fn is_exempt<'a>(lcx: &LintContext<'a, '_>, ast: &'a dyn ast::AnyNode<'a>) -> bool {
+    let name = "unused".into();
+    std::iter::once(ast).chain(ast.get_parent()).any(|node| {
+        let span = node.span();
+        lcx.cx
+            .attributes_at(Location::new(span.source, span.begin))
+            .iter()
+            .any(|attr| attr.name == name)
+    })
+}
@@ -0,0 +1,261 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Detection of questionable uses of X and Z values.
+//!
+//! Assigning X is a common way to tell synthesis that a value does not matter,
+//! but the same assignment elsewhere masks uninitialized logic in simulation.
+//! We therefore only accept it in the `default` arm of a `case` statement, in
+//! the reset branch of a clocked procedure, and as one of the default
+//! assignments at the beginning of a combinational procedure. Comparing with a
+//! constant that contains X or Z bits using `==` or `!=` always yields X,
+//! which is why we suggest `===` or `!==` instead.
+
+use super::{assign_kind::find_operator, clocking::clocked_procs, LintContext};
+use crate::{
+    crate_prelude::*,
+    hir::{self, HirNode, Visitor},
+    value::ValueKind,
+    ParamEnv,
+};
+use std::collections::HashSet;
+
+/// Report assignments of X values outside of default and reset idioms.
+pub(super) fn check_assign(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    let env = lcx.env();
+    let clocked = clocked_procs(lcx)?;
+    let mut assigns = vec![];
+
+    // Collect the assignments in procedures, skipping the statements where X
+    // is expected.
+    for &id in &lcx.module.procs {
+        let prok = match cx.hir_of(id.id())? {
+            HirNode::Proc(x) => x,
+            _ => continue,
+        };
+        let mut exempt = HashSet::new();
+        match clocked.iter().find(|p| p.prok.id == prok.id) {
+            Some(p) => exempt.extend(p.reset_stmt),
+            None => exempt.extend(default_assigns(cx, prok.stmt)?),
+        }
+        let mut collector = XAssignCollector {
+            cx,
+            exempt,
+            depth: 0,
+            assigns: vec![],
+        };
+        collector.visit_proc(prok);
+        assigns.extend(collector.assigns);
+    }
+
+    // Collect the continuous assignments.
+    for &id in &lcx.module.assigns {
+        if let HirNode::Assign(x) = cx.hir_of(id.id())? {
+            assigns.push((x.span, x.rhs));
+        }
+    }
+
+    for (span, rhs) in assigns {
+        if !unknown_bits(cx, env, rhs)?.0 {
+            continue;
+        }
+        lcx.emit(
+            DiagBuilder2::warning(format!(
+                "`{}` assigned outside of a `default` case or reset",
+                cx.span(rhs).extract()
+            ))
+            .span(span)
+            .add_note(
+                "X values are expected only where they mean \"don't care\"; elsewhere they hide \
+                 uninitialized logic in simulation",
+            ),
+        );
+    }
+    Ok(())
+}
+
+/// Report comparisons with X or Z values using `==` or `!=`.
+pub(super) fn check_compare(lcx: &LintContext) -> Result<()> {
+    let cx = lcx.cx;
+    let env = lcx.env();
+    let mut collector = CompareCollector {
+        cx,
+        compares: vec![],
+    };
+    for &id in lcx.module.procs.iter().chain(&lcx.module.assigns) {
+        collector.visit_node_with_id(id.id(), false);
+    }
+    for (expr_id, op, lhs, rhs) in collector.compares {
+        let constant = if unknown_bits(cx, env, rhs)? != (false, false) {
+            rhs
+        } else if unknown_bits(cx, env, lhs)? != (false, false) {
+            lhs
+        } else {
+            continue;
+        };
+        let (text, fixed) = match op {
+            hir::BinaryOp::Eq => ("==", "==="),
+            _ => ("!=", "!=="),
+        };
+        let mut diag = DiagBuilder2::warning(format!(
+            "comparison with `{}` using `{}` is never true",
+            cx.span(constant).extract(),
+            text
+        ))
+        .span(cx.span(expr_id))
+        .add_note(format!(
+            "X and Z bits make `{}` yield X; use `{}` to compare them exactly, or a wildcard \
+             operator to ignore them",
+            text, fixed
+        ));
+        if let Some(span) = find_operator(cx, lhs, rhs, text) {
+            diag = diag.suggest(span, fixed);
+        }
+        lcx.emit(diag);
+    }
+    Ok(())
+}
+
+/// Find the default assignments at the beginning of a procedure, which are
+/// followed by further statements that may override them.
+fn default_assigns<'a>(cx: &impl Context<'a>, stmt_id: NodeId) -> Result<Vec<NodeId>> {
+    Ok(match cx.hir_of(stmt_id)? {
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Timed { stmt, .. },
+            ..
+        }) => default_assigns(cx, *stmt)?,
+        HirNode::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Block(stmts),
+            ..
+        }) => {
+            let mut assigns = vec![];
+            for &id in stmts.iter().take(stmts.len().saturating_sub(1)) {
+                match cx.hir_of(id)? {
+                    HirNode::Stmt(hir::Stmt {
+                        kind: hir::StmtKind::Assign { .. },
+                        ..
+                    }) => assigns.push(id),
+                    _ => break,
+                }
+            }
+            assigns
+        }
+        _ => vec![],
+    })
+}
+
+/// Determine whether a constant expression contains X bits and Z bits.
+///
+/// Returns `(false, false)` for expressions that are not constant.
+fn unknown_bits<'a>(cx: &impl Context<'a>, env: ParamEnv, expr_id: NodeId) -> Result<(bool, bool)> {
+    let expr = match cx.hir_of(expr_id)? {
+        HirNode::Expr(x) => x,
+        _ => return Ok((false, false)),
+    };
+    let (special_bits, x_bits) = match expr.kind {
+        hir::ExprKind::UnsizedConst('x') => return Ok((true, false)),
+        hir::ExprKind::UnsizedConst('z') => return Ok((false, true)),
+        hir::ExprKind::IntConst {
+            ref special_bits,
+            ref x_bits,
+            ..
+        } => (special_bits.clone(), x_bits.clone()),
+        hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) => {
+            let decl = cx.resolve_node(expr_id, env)?;
+            if !cx.is_constant(decl)? {
+                return Ok((false, false));
+            }
+            match cx.constant_value_of(expr_id, env).kind {
+                ValueKind::Int(_, ref special_bits, ref x_bits) => {
+                    (special_bits.clone(), x_bits.clone())
+                }
+                _ => return Ok((false, false)),
+            }
+        }
+        _ => return Ok((false, false)),
+    };
+    let x = x_bits.iter().any(|b| b);
+    let z = special_bits.iter().zip(x_bits.iter()).any(|(s, x)| s && !x);
+    Ok((x, z))
+}
+
+/// A visitor that collects the assignments of a procedure, except for the
+/// ones in exempt statements.
+struct XAssignCollector<'a, 'c> {
+    cx: &'c GlobalContext<'a>,
+    exempt: HashSet<NodeId>,
+    depth: usize,
+    assigns: Vec<(Span, NodeId)>,
+}
+
+impl<'a, 'c> Visitor<'a> for XAssignCollector<'a, 'c> {
+    type Context = GlobalContext<'a>;
+
+    fn context(&self) -> &Self::Context {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a hir::Stmt) {
+        let exempt = self.exempt.contains(&stmt.id);
+        if exempt {
+            self.depth += 1;
+        }
+        match stmt.kind {
+            hir::StmtKind::Assign { rhs, .. } if self.depth == 0 => {
+                self.assigns.push((stmt.span, rhs))
+            }
+            hir::StmtKind::Case {
+                default: Some(default),
+                ..
+            } => {
+                self.exempt.insert(default);
+            }
+            _ => (),
+        }
+        hir::walk_stmt(self, stmt);
+        if exempt {
+            self.depth -= 1;
+        }
+    }
+}
+
+/// A visitor that collects the `==` and `!=` comparisons in a procedure or
+/// continuous assignment.
+struct CompareCollector<'a, 'c> {
+    cx: &'c GlobalContext<'a>,
+    compares: Vec<(NodeId, hir::BinaryOp, NodeId, NodeId)>,
+}
+
+impl<'a, 'c> Visitor<'a> for CompareCollector<'a, 'c> {
+    type Context = GlobalContext<'a>;
+
+    fn context(&self) -> &Self::Context {
+        self.cx
+    }
+
+    fn visit_expr(&mut self, expr: &'a hir::Expr, lvalue: bool) {
+        if let hir::ExprKind::Binary(op @ hir::BinaryOp::Eq, lhs, rhs)
+        | hir::ExprKind::Binary(op @ hir::BinaryOp::Neq, lhs, rhs) = expr.kind
+        {
+            // The case and wildcard equality operators map to the same HIR
+            // operators, so look at the syntax to tell them apart.
+            use crate::syntax::token::Op;
+            if let ast::AllNode::Expr(ast::Expr {
+                data: ast::BinaryExpr {
+                    op: Op::LogicEq, ..
+                },
+                ..
+            })
+            | ast::AllNode::Expr(ast::Expr {
+                data: ast::BinaryExpr {
+                    op: Op::LogicNeq, ..
+                },
+                ..
+            }) = self.cx.ast_for_id(expr.id).as_all()
+            {
+                self.compares.push((expr.id, op, lhs, rhs));
+            }
+        }
+        hir::walk_expr(self, expr, lvalue);
+    }
+}
@@ -147,6 +147,7 @@ fn lower_expr_inner<'a>(
                             "{} cannot be used as the target of an assignment",
                             x.desc_full()
                         ))
+                        .code("SV0037")
                         .span(span),
                     );
                     Err(())
@@ -262,7 +263,11 @@ fn lower_expr_inner<'a>(
     // Show an error informing the user that the given expression cannot be
     // assigned to.
     error!("{:#?}", hir);
-    cx.emit(DiagBuilder2::error(format!("{} cannot be assigned to", hir.desc_full())).span(span));
+    cx.emit(
+        DiagBuilder2::error(format!("{} cannot be assigned to", hir.desc_full()))
+            .code("SV0037")
+            .span(span),
+    );
     Err(())
 }
 
@@ -5,6 +5,7 @@
 use crate::crate_prelude::*;
 use crate::{
     call_mapping::CallArgSource,
+    format::{self, DisplayTask},
     hir::HirNode,
     mir::rvalue::*,
     syntax::ast::BasicNode,
@@ -161,6 +162,10 @@ fn lower_expr_inner<'gcx>(
             bug_span!(span, cx, "unsized const with weird '{}' char", c)
         }
         hir::ExprKind::TimeConst(ref k) => Ok(builder.constant(value::make_time(k.clone()))),
+        hir::ExprKind::RealConst(ref k) => Ok(builder.constant(value::make_real(
+            ty,
+            k.to_f64().unwrap_or(std::f64::INFINITY),
+        ))),
         hir::ExprKind::StringConst(string) => Ok(builder.constant(value::make_int(
             // TODO: This could use `value::make_string` to build a string
             // value, and then resort to the conversion function there to map
@@ -189,18 +194,11 @@ fn lower_expr_inner<'gcx>(
             Ok(builder.constant(value::make_int(ty, num::zero())))
         }
         hir::ExprKind::Builtin(hir::BuiltinCall::Clog2(arg)) => {
-            let arg_val = cx.constant_value_of(arg, env);
-            let arg_int = match arg_val.kind {
-                ValueKind::Int(ref arg, ..) => arg,
-                ValueKind::Error => return Ok(builder.error()),
-                _ => unreachable!(),
-            };
-            let value = if arg_int <= &BigInt::one() {
-                BigInt::zero()
-            } else {
-                BigInt::from((arg_int - BigInt::one()).bits())
-            };
-            Ok(builder.constant(value::make_int(ty, value)))
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(ty, RvalueKind::Clog2(value)))
         }
         hir::ExprKind::Builtin(hir::BuiltinCall::Bits(arg)) => {
             let arg_ty = match cx.disamb_type_or_expr(Ref(arg))? {
@@ -215,16 +213,66 @@ fn lower_expr_inner<'gcx>(
                             "value of type `{}` does not have a fixed number of bits",
                             arg_ty
                         ))
+                        .code("SV0036")
                         .span(hir.span()),
                     );
                     Ok(builder.error())
                 }
             }
         }
-        hir::ExprKind::Builtin(hir::BuiltinCall::CountOnes(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_)) => {
-            bug_span!(span, cx, "unsupported system function {:?}", hir.kind)
+        hir::ExprKind::Builtin(hir::BuiltinCall::CountOnes(arg)) => {
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(ty, RvalueKind::CountOnes(value)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(arg)) => {
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+
+            // Count the set bits and compare the result against one.
+            let int_ty = ty::PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx);
+            let count = builder.build(int_ty, RvalueKind::CountOnes(value));
+            let one = builder.constant(value::make_int(int_ty, BigInt::one()));
+            let op = match hir.kind {
+                hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_)) => IntCompOp::Eq,
+                _ => IntCompOp::Leq,
+            };
+            Ok(make_int_comparison(&builder, ty, int_ty, op, count, one))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Rtoi(arg)) => {
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(
+                ty,
+                RvalueKind::CastRealToInt {
+                    value,
+                    round: false,
+                },
+            ))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Itor(arg)) => {
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(ty, RvalueKind::CastIntToReal(value)))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealToBits(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::BitsToReal(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ShortRealToBits(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::BitsToShortReal(arg)) => {
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(ty, RvalueKind::Transmute(value)))
         }
         hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_)) => {
             // Since we currently don't emit logic types, this is always zero.
@@ -248,19 +296,21 @@ fn lower_expr_inner<'gcx>(
             }
 
             // Extract the dimension of interest.
-            let ty_dim = match arg_ty.dims().nth(dim - 1) {
-                Some(x) => x,
+            let dims = array_query_dims(arg_ty);
+            let ty_dim = match dim.checked_sub(1).and_then(|d| dims.get(d)) {
+                Some(&x) => x,
                 None => {
                     cx.emit(
                         DiagBuilder2::error(format!(
                             "value of type `{}` does not have a dimension {}",
                             arg_ty, dim,
                         ))
+                        .code("SV0036")
                         .span(hir.span())
                         .add_note(format!(
                             "Argument type `{}` has {} dimension(s)",
                             arg_ty.resolve_full(),
-                            arg_ty.dims().count()
+                            dims.len()
                         ))
                         .span(arg.span()),
                     );
@@ -288,6 +338,73 @@ fn lower_expr_inner<'gcx>(
 
             Ok(builder.constant(value::make_int(ty, value.into())))
         }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Dimensions(arg)) => {
+            let arg_ty = cx.type_of_expr(Ref(cx.hir_of_expr(Ref(arg))?), env);
+            if arg_ty.is_error() {
+                return Err(());
+            }
+            let count = match array_query_dims(arg_ty).len() {
+                0 if arg_ty.is_string() || arg_ty.get_simple_bit_vector().is_some() => 1,
+                n => n,
+            };
+            Ok(builder.constant(value::make_int(ty, count.into())))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::UnpackedDimensions(arg)) => {
+            let arg_ty = cx.type_of_expr(Ref(cx.hir_of_expr(Ref(arg))?), env);
+            if arg_ty.is_error() {
+                return Err(());
+            }
+            let count = arg_ty.unpacked_dims().count();
+            Ok(builder.constant(value::make_int(ty, count.into())))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Display(task, radix, args)) => {
+            let mapping = cx.format_mapping(Ref(args), task, radix, span, env);
+            if mapping.is_error() {
+                return Ok(builder.error());
+            }
+            let mut pieces = vec![];
+            for piece in &mapping.pieces {
+                pieces.push(match *piece {
+                    format::FormatPiece::Text(ref text) => FormatPiece::Text(text.clone()),
+                    format::FormatPiece::Value(spec, expr) => {
+                        let value = cx.mir_rvalue(expr.id(), env);
+                        if value.is_error() {
+                            return Ok(builder.error());
+                        }
+                        FormatPiece::Value(spec, value)
+                    }
+                    format::FormatPiece::Scope => FormatPiece::Scope,
+                });
+            }
+            let kind = match task {
+                DisplayTask::Sformatf => RvalueKind::Format(pieces),
+                _ => RvalueKind::Display { task, pieces },
+            };
+            Ok(builder.build(ty, kind))
+        }
+
+        hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem(radix, file, mem, start, end)) => {
+            let file = cx.mir_rvalue(file, env);
+            let mem = cx.mir_lvalue(mem, env);
+            let start = start.map(|x| cx.mir_rvalue(x, env));
+            let end = end.map(|x| cx.mir_rvalue(x, env));
+            if file.is_error()
+                || mem.is_error()
+                || start.iter().chain(end.iter()).any(|x| x.is_error())
+            {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(
+                ty,
+                RvalueKind::ReadMem {
+                    radix,
+                    file,
+                    mem,
+                    start,
+                    end,
+                },
+            ))
+        }
 
         hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) => {
             let binding = builder.cx.resolve_node(expr_id, env)?;
@@ -301,6 +418,9 @@ fn lower_expr_inner<'gcx>(
                     Ok(builder.build(ty, RvalueKind::Intf(inst.id)))
                 }
                 HirNode::EnumVariant(..) | HirNode::ValueParam(..) | HirNode::GenvarDecl(..) => {
+                    if let HirNode::ValueParam(..) = builder.cx.hir_of(binding)? {
+                        value::check_param_cycle(builder.cx, binding, env, span)?;
+                    }
                     let k = builder.cx.constant_value_of(binding, env);
                     Ok(builder.build(ty, RvalueKind::Const(k)))
                 }
@@ -311,6 +431,7 @@ fn lower_expr_inner<'gcx>(
                             "{} cannot be used in an expression",
                             x.desc_full()
                         ))
+                        .code("SV0036")
                         .span(span),
                     );
                     error!("Offending HIR: {:?}", hir);
@@ -634,6 +755,27 @@ pub(crate) fn adjust_indexing<'a>(
     }
 }
 
+/// Determine the dimensions of a type as seen by the array query functions.
+///
+/// Integer atoms such as `int` behave as if they were declared with a single
+/// packed dimension `[w-1:0]`.
+fn array_query_dims<'a>(ty: &'a UnpackedType<'a>) -> Vec<ty::Dim<'a>> {
+    let mut dims: Vec<_> = ty.dims().collect();
+    let core = ty
+        .resolve_full()
+        .core
+        .get_packed()
+        .map(|packed| &packed.resolve_full().core);
+    if let Some(&ty::PackedCore::IntAtom(atom)) = core {
+        dims.push(ty::Dim::Packed(ty::PackedDim::Range(ty::Range {
+            size: atom.bit_size(),
+            dir: ty::RangeDir::Down,
+            offset: 0,
+        })));
+    }
+    dims
+}
+
 /// Generate the nodes necessary for a cast operation.
 fn lower_cast<'gcx>(
     builder: &Builder<'_, impl Context<'gcx>>,
@@ -669,7 +811,11 @@ fn lower_cast<'gcx>(
         debug!("- {:?} from `{}` to `{}`", op, value.ty, to);
         match op {
             CastOp::Bool => {
-                assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
+                assert_span!(
+                    value.ty.is_simple_bit_vector() || value.ty.is_real(),
+                    value.span,
+                    builder.cx
+                );
                 value = builder.build(to, RvalueKind::CastToBool(value));
             }
             CastOp::Sign(sign) => {
@@ -721,6 +867,21 @@ fn lower_cast<'gcx>(
                 assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
                 value = builder.build(to, RvalueKind::UnpackString(value));
             }
+            CastOp::RealToInt => {
+                assert_span!(value.ty.is_real(), value.span, builder.cx);
+                assert_span!(to.is_simple_bit_vector(), value.span, builder.cx);
+                value = builder.build(to, RvalueKind::CastRealToInt { value, round: true });
+            }
+            CastOp::IntToReal => {
+                assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
+                assert_span!(to.is_real(), value.span, builder.cx);
+                value = builder.build(to, RvalueKind::CastIntToReal(value));
+            }
+            CastOp::RealPrecision => {
+                assert_span!(value.ty.is_real(), value.span, builder.cx);
+                assert_span!(to.is_real(), value.span, builder.cx);
+                value = builder.build(to, RvalueKind::CastRealPrecision(value));
+            }
         }
         if !value.ty.is_identical(to) {
             error!(
@@ -864,6 +1025,7 @@ fn unpack_simple_bit_vector<'a>(
                 "assuming `1ns` timescale for conversion from `{}` to `time`",
                 value.ty
             ))
+            .group("timescale")
             .span(value.span),
         );
         builder.build(
@@ -1026,6 +1188,23 @@ fn lower_unary<'gcx>(
 ) -> &'gcx Rvalue<'gcx> {
     // Determine the category of the operation.
     match op {
+        hir::UnaryOp::Pos | hir::UnaryOp::Neg if ty.is_real() => {
+            lower_real_unary_arith(builder, ty, op, arg)
+        }
+        hir::UnaryOp::BitNot
+        | hir::UnaryOp::RedAnd
+        | hir::UnaryOp::RedOr
+        | hir::UnaryOp::RedXor
+        | hir::UnaryOp::RedNand
+        | hir::UnaryOp::RedNor
+        | hir::UnaryOp::RedXnor
+            if builder
+                .cx
+                .need_operation_type(builder.expr, builder.env)
+                .is_real() =>
+        {
+            real_operator_error(builder, op.desc())
+        }
         hir::UnaryOp::Pos | hir::UnaryOp::Neg => lower_int_unary_arith(builder, ty, op, arg),
         hir::UnaryOp::BitNot => lower_unary_bitwise(builder, ty, op, arg),
         hir::UnaryOp::LogicNot => lower_unary_logic(builder, ty, op, arg),
@@ -1051,6 +1230,33 @@ fn lower_binary<'gcx>(
     rhs: NodeId,
 ) -> &'gcx Rvalue<'gcx> {
     match op {
+        hir::BinaryOp::Add
+        | hir::BinaryOp::Sub
+        | hir::BinaryOp::Mul
+        | hir::BinaryOp::Div
+        | hir::BinaryOp::Pow
+            if ty.is_real() =>
+        {
+            lower_real_binary_arith(builder, ty, op, lhs, rhs)
+        }
+        hir::BinaryOp::Mod
+        | hir::BinaryOp::LogicShL
+        | hir::BinaryOp::LogicShR
+        | hir::BinaryOp::ArithShL
+        | hir::BinaryOp::ArithShR
+        | hir::BinaryOp::BitAnd
+        | hir::BinaryOp::BitOr
+        | hir::BinaryOp::BitXor
+        | hir::BinaryOp::BitNand
+        | hir::BinaryOp::BitNor
+        | hir::BinaryOp::BitXnor
+            if builder
+                .cx
+                .need_operation_type(builder.expr, builder.env)
+                .is_real() =>
+        {
+            real_operator_error(builder, op.desc())
+        }
         hir::BinaryOp::Add
         | hir::BinaryOp::Sub
         | hir::BinaryOp::Mul
@@ -1066,6 +1272,8 @@ fn lower_binary<'gcx>(
             let op_ty = builder.cx.need_operation_type(builder.expr, builder.env);
             if op_ty.is_string() {
                 lower_string_comparison(builder, ty, op_ty, op, lhs, rhs)
+            } else if op_ty.is_real() {
+                lower_real_comparison(builder, ty, op_ty, op, lhs, rhs)
             } else {
                 lower_int_comparison(builder, ty, op_ty, op, lhs, rhs)
             }
@@ -1162,6 +1370,88 @@ fn lower_int_binary_arith<'a>(
     make_int_binary_arith(builder, result_ty, op, lhs, rhs)
 }
 
+/// Map a real unary arithmetic operator to MIR.
+fn lower_real_unary_arith<'gcx>(
+    builder: &Builder<'_, impl Context<'gcx>>,
+    result_ty: &'gcx UnpackedType<'gcx>,
+    op: hir::UnaryOp,
+    arg: NodeId,
+) -> &'gcx Rvalue<'gcx> {
+    // Lower the operand.
+    let arg = builder.cx.mir_rvalue(arg, builder.env);
+    if arg.is_error() {
+        return builder.error();
+    }
+    assert_type!(arg.ty, result_ty, builder.span, builder.cx);
+
+    // Determine the operation.
+    let op = match op {
+        hir::UnaryOp::Pos => return arg,
+        hir::UnaryOp::Neg => IntUnaryArithOp::Neg,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real unary arithmetic operator",
+            op
+        ),
+    };
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealUnaryArith { op, arg })
+}
+
+/// Map a real binary arithmetic operator to MIR.
+fn lower_real_binary_arith<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op: hir::BinaryOp,
+    lhs: NodeId,
+    rhs: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operands.
+    let lhs = builder.cx.mir_rvalue(lhs, builder.env);
+    let rhs = builder.cx.mir_rvalue(rhs, builder.env);
+    if lhs.is_error() || rhs.is_error() {
+        return builder.error();
+    }
+
+    // Determine the operation.
+    let op = match op {
+        hir::BinaryOp::Add => RealBinaryArithOp::Add,
+        hir::BinaryOp::Sub => RealBinaryArithOp::Sub,
+        hir::BinaryOp::Mul => RealBinaryArithOp::Mul,
+        hir::BinaryOp::Div => RealBinaryArithOp::Div,
+        hir::BinaryOp::Pow => RealBinaryArithOp::Pow,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real binary arithmetic operator",
+            op
+        ),
+    };
+
+    // Check that the operands are of the right type.
+    assert_type!(lhs.ty, result_ty, builder.span, builder.cx);
+    assert_type!(rhs.ty, result_ty, builder.span, builder.cx);
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealBinaryArith { op, lhs, rhs })
+}
+
+/// Emit an error for an operator that cannot be applied to real operands.
+fn real_operator_error<'a>(builder: &Builder<'_, impl Context<'a>>, desc: &str) -> &'a Rvalue<'a> {
+    let op_ty = builder.cx.need_operation_type(builder.expr, builder.env);
+    builder.cx.emit(
+        DiagBuilder2::error(format!(
+            "{} cannot be applied to a value of type `{}`",
+            desc, op_ty
+        ))
+        .code("SV0036")
+        .span(builder.span),
+    );
+    builder.error()
+}
+
 /// Map an integer binary arithmetic operator to MIR.
 pub fn make_int_binary_arith<'a>(
     builder: &Builder<'_, impl Context<'a>>,
@@ -1226,6 +1516,46 @@ fn lower_int_comparison<'a>(
     make_int_comparison(builder, result_ty, op_ty, op, lhs, rhs)
 }
 
+/// Map a real comparison operator to MIR.
+fn lower_real_comparison<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op_ty: &'a UnpackedType<'a>,
+    op: hir::BinaryOp,
+    lhs: NodeId,
+    rhs: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operands.
+    let lhs = builder.cx.mir_rvalue(lhs, builder.env);
+    let rhs = builder.cx.mir_rvalue(rhs, builder.env);
+    if lhs.is_error() || rhs.is_error() || op_ty.is_error() {
+        return builder.error();
+    }
+
+    // Determine the operation.
+    let op = match op {
+        hir::BinaryOp::Eq => IntCompOp::Eq,
+        hir::BinaryOp::Neq => IntCompOp::Neq,
+        hir::BinaryOp::Lt => IntCompOp::Lt,
+        hir::BinaryOp::Leq => IntCompOp::Leq,
+        hir::BinaryOp::Gt => IntCompOp::Gt,
+        hir::BinaryOp::Geq => IntCompOp::Geq,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real comparison operator",
+            op
+        ),
+    };
+
+    // Check that the operands are of the right type.
+    assert_type!(lhs.ty, op_ty, builder.span, builder.cx);
+    assert_type!(rhs.ty, op_ty, builder.span, builder.cx);
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealComp { op, lhs, rhs })
+}
+
 /// Map an integer comparison operator to MIR.
 fn make_int_comparison<'a>(
     builder: &Builder<'_, impl Context<'a>>,
@@ -1586,8 +1916,26 @@ fn lower_int_incdec<'gcx>(
     }
 
     // Compute the new value, depending on the operand type.
-    let sbvt = lv.ty.simple_bit_vector(builder.cx, builder.span);
-    let new = if sbvt.size == 1 {
+    let new = if lv.ty.is_real() {
+        // Real values add/subtract one.
+        let op = match op {
+            hir::UnaryOp::PreInc | hir::UnaryOp::PostInc => RealBinaryArithOp::Add,
+            hir::UnaryOp::PreDec | hir::UnaryOp::PostDec => RealBinaryArithOp::Sub,
+            _ => unreachable!(),
+        };
+        let one = builder.build(
+            lv.ty,
+            RvalueKind::Const(builder.cx.intern_value(value::make_real(lv.ty, 1.0))),
+        );
+        builder.build(
+            lv.ty,
+            RvalueKind::RealBinaryArith {
+                op,
+                lhs: rv,
+                rhs: one,
+            },
+        )
+    } else if lv.ty.simple_bit_vector(builder.cx, builder.span).size == 1 {
         // Single bit values simply toggle the bit.
         builder.build(
             lv.ty,
@@ -1598,6 +1946,7 @@ fn lower_int_incdec<'gcx>(
         )
     } else {
         // Bit vector values add/subtract one.
+        let sbvt = lv.ty.simple_bit_vector(builder.cx, builder.span);
         let op = match op {
             hir::UnaryOp::PreInc | hir::UnaryOp::PostInc => IntBinaryArithOp::Add,
             hir::UnaryOp::PreDec | hir::UnaryOp::PostDec => IntBinaryArithOp::Sub,
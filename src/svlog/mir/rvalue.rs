@@ -7,6 +7,7 @@
 
 use crate::crate_prelude::*;
 use crate::{
+    format::{DisplayTask, FormatSpec, Radix},
     mir::{
         lvalue::Lvalue,
         print::{Context, Print},
@@ -91,6 +92,18 @@ impl<'a> Print for Rvalue<'a> {
                 write!(inner, "CastSign({}, {})", sign, ctx.print(outer, arg))?
             }
             RvalueKind::CastToBool(arg) => write!(inner, "CastToBool({})", ctx.print(outer, arg))?,
+            RvalueKind::CastRealToInt { value, round } => write!(
+                inner,
+                "CastRealToInt({}, {})",
+                if round { "round" } else { "trunc" },
+                ctx.print(outer, value)
+            )?,
+            RvalueKind::CastIntToReal(arg) => {
+                write!(inner, "CastIntToReal({})", ctx.print(outer, arg))?
+            }
+            RvalueKind::CastRealPrecision(arg) => {
+                write!(inner, "CastRealPrecision({})", ctx.print(outer, arg))?
+            }
             RvalueKind::ApplyTimescale(arg, ref scale) => write!(
                 inner,
                 "ApplyTimescale({}, {})",
@@ -170,6 +183,23 @@ impl<'a> Print for Rvalue<'a> {
                 sign,
                 domain
             )?,
+            RvalueKind::RealUnaryArith { op, arg } => {
+                write!(inner, "RealUnaryArith {:?} {}", op, ctx.print(outer, arg))?
+            }
+            RvalueKind::RealBinaryArith { op, lhs, rhs } => write!(
+                inner,
+                "RealBinaryArith {} {:?} {}",
+                ctx.print(outer, lhs),
+                op,
+                ctx.print(outer, rhs)
+            )?,
+            RvalueKind::RealComp { op, lhs, rhs } => write!(
+                inner,
+                "RealComp {} {:?} {}",
+                ctx.print(outer, lhs),
+                op,
+                ctx.print(outer, rhs)
+            )?,
             RvalueKind::Concat(ref args) => {
                 write!(inner, "Concat({})", ctx.print_comma_separated(outer, args))?
             }
@@ -235,6 +265,8 @@ impl<'a> Print for Rvalue<'a> {
             RvalueKind::Reduction { op, arg } => {
                 write!(inner, "Reduce({:?}, {})", op, ctx.print(outer, arg))?
             }
+            RvalueKind::Clog2(arg) => write!(inner, "Clog2({})", ctx.print(outer, arg))?,
+            RvalueKind::CountOnes(arg) => write!(inner, "CountOnes({})", ctx.print(outer, arg))?,
             RvalueKind::Assignment {
                 lvalue,
                 rvalue,
@@ -265,6 +297,36 @@ impl<'a> Print for Rvalue<'a> {
                     ctx.print_comma_separated(outer, args),
                 )?;
             }
+            RvalueKind::Display { task, ref pieces } => write!(
+                inner,
+                "{}({})",
+                task.name(),
+                ctx.print_comma_separated(outer, pieces)
+            )?,
+            RvalueKind::Format(ref pieces) => write!(
+                inner,
+                "Format({})",
+                ctx.print_comma_separated(outer, pieces)
+            )?,
+            RvalueKind::ReadMem {
+                radix,
+                file,
+                mem,
+                start,
+                end,
+            } => {
+                write!(
+                    inner,
+                    "{}({}, {}",
+                    crate::readmem::task_name(radix),
+                    ctx.print(outer, file),
+                    ctx.print(outer, mem)
+                )?;
+                for arg in start.into_iter().chain(end) {
+                    write!(inner, ", {}", ctx.print(outer, arg))?;
+                }
+                write!(inner, ")")?;
+            }
             RvalueKind::Error => write!(inner, "<error>")?,
         }
         write!(inner, " : {}", self.ty)?;
@@ -294,6 +356,14 @@ pub enum RvalueKind<'a> {
     /// A cast from a simple bit type to a boolean.
     // TODO: Add SBVT
     CastToBool(&'a Rvalue<'a>),
+    /// A conversion from a real type to a simple bit vector type. Rounds to the
+    /// nearest integer if `round` is set, and truncates towards zero otherwise.
+    CastRealToInt { value: &'a Rvalue<'a>, round: bool },
+    /// A conversion from a simple bit vector type to a real type.
+    CastIntToReal(&'a Rvalue<'a>),
+    /// A cast between real types of different precision. E.g. `real` to
+    /// `shortreal`.
+    CastRealPrecision(&'a Rvalue<'a>),
     /// Shrink the width of a vector type. E.g. `bit [31:0]` to `bit [7:0]`.
     // TODO: Add SBVT
     Truncate(usize, &'a Rvalue<'a>),
@@ -356,6 +426,23 @@ pub enum RvalueKind<'a> {
         lhs: &'a Rvalue<'a>,
         rhs: &'a Rvalue<'a>,
     },
+    /// A real unary arithmetic operator.
+    RealUnaryArith {
+        op: IntUnaryArithOp,
+        arg: &'a Rvalue<'a>,
+    },
+    /// A real binary arithmetic operator.
+    RealBinaryArith {
+        op: RealBinaryArithOp,
+        lhs: &'a Rvalue<'a>,
+        rhs: &'a Rvalue<'a>,
+    },
+    /// A real comparison operator.
+    RealComp {
+        op: IntCompOp,
+        lhs: &'a Rvalue<'a>,
+        rhs: &'a Rvalue<'a>,
+    },
     /// Concatenate multiple values.
     ///
     /// The values are cast to and treated as packed bit vectors, and the result
@@ -407,6 +494,11 @@ pub enum RvalueKind<'a> {
         // TODO: Add SBVT
         arg: &'a Rvalue<'a>,
     },
+    /// The ceil-log2 of an integer, as computed by `$clog2`.
+    Clog2(&'a Rvalue<'a>),
+    /// The number of set bits in a packed bit vector, as computed by
+    /// `$countones`.
+    CountOnes(&'a Rvalue<'a>),
     /// An assignment operator.
     Assignment {
         lvalue: &'a Lvalue<'a>,
@@ -435,6 +527,30 @@ pub enum RvalueKind<'a> {
         /// The call arguments.
         args: Vec<CallArg<'a>>,
     },
+    /// A call to one of the display tasks, which prints a message.
+    Display {
+        /// The called task.
+        #[dont_visit]
+        task: DisplayTask,
+        /// The pieces of the printed message.
+        pieces: Vec<FormatPiece<'a>>,
+    },
+    /// A message formatted into a string, as returned by `$sformatf`.
+    Format(Vec<FormatPiece<'a>>),
+    /// A call to `$readmemb` or `$readmemh`, which loads a memory from a file.
+    ReadMem {
+        /// The radix of the words in the file.
+        #[dont_visit]
+        radix: Radix,
+        /// The name of the file.
+        file: &'a Rvalue<'a>,
+        /// The loaded memory.
+        mem: &'a Lvalue<'a>,
+        /// The address of the first word loaded.
+        start: Option<&'a Rvalue<'a>>,
+        /// The address of the last word loaded.
+        end: Option<&'a Rvalue<'a>>,
+    },
     /// An error occurred during lowering.
     Error,
 }
@@ -455,6 +571,9 @@ impl<'a> RvalueKind<'a> {
             | RvalueKind::Transmute(value)
             | RvalueKind::CastSign(_, value)
             | RvalueKind::CastToBool(value)
+            | RvalueKind::CastRealToInt { value, .. }
+            | RvalueKind::CastIntToReal(value)
+            | RvalueKind::CastRealPrecision(value)
             | RvalueKind::Truncate(_, value)
             | RvalueKind::ZeroExtend(_, value)
             | RvalueKind::SignExtend(_, value)
@@ -462,16 +581,21 @@ impl<'a> RvalueKind<'a> {
             | RvalueKind::Member { value, .. }
             | RvalueKind::PackString(value)
             | RvalueKind::UnpackString(value)
+            | RvalueKind::Clog2(value)
+            | RvalueKind::CountOnes(value)
             | RvalueKind::ApplyTimescale(value, _) => value.is_const(),
             RvalueKind::ConstructArray(values) => values.values().all(|v| v.is_const()),
             RvalueKind::ConstructStruct(values) => values.iter().all(|v| v.is_const()),
             RvalueKind::Const(_) => true,
             RvalueKind::UnaryBitwise { arg, .. }
             | RvalueKind::IntUnaryArith { arg, .. }
+            | RvalueKind::RealUnaryArith { arg, .. }
             | RvalueKind::Reduction { arg, .. } => arg.is_const(),
             RvalueKind::BinaryBitwise { lhs, rhs, .. }
             | RvalueKind::IntBinaryArith { lhs, rhs, .. }
             | RvalueKind::IntComp { lhs, rhs, .. }
+            | RvalueKind::RealBinaryArith { lhs, rhs, .. }
+            | RvalueKind::RealComp { lhs, rhs, .. }
             | RvalueKind::StringComp { lhs, rhs, .. } => lhs.is_const() && rhs.is_const(),
             RvalueKind::Concat(values) => values.iter().all(|v| v.is_const()),
             RvalueKind::Var(_) => false,
@@ -491,6 +615,12 @@ impl<'a> RvalueKind<'a> {
             // TODO(fschuiki): This is wrong; function calls *may* be constant
             // under certain circumstances.
             RvalueKind::Call { .. } => false,
+            RvalueKind::Display { .. } | RvalueKind::ReadMem { .. } => false,
+            RvalueKind::Format(ref pieces) => pieces.iter().all(|p| match p {
+                FormatPiece::Value(_, value) => value.is_const(),
+                FormatPiece::Text(_) => true,
+                FormatPiece::Scope => false,
+            }),
             RvalueKind::Error => true,
         }
     }
@@ -535,6 +665,18 @@ pub enum IntBinaryArithOp {
     Pow,
 }
 
+/// The real binary arithmetic operators.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RealBinaryArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
 /// The integer comparison operators.
 #[moore_derive::visit_without_foreach]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -590,6 +732,36 @@ impl<'a> Print for CallInput<'a> {
     }
 }
 
+/// A piece of a formatted message.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatPiece<'a> {
+    /// Literal text.
+    Text(#[dont_visit] String),
+    /// A value formatted according to a specifier.
+    Value(#[dont_visit] FormatSpec, &'a Rvalue<'a>),
+    /// The hierarchical name of the current scope.
+    Scope,
+}
+
+impl<'a> Print for FormatPiece<'a> {
+    fn print_context(
+        &self,
+        outer: &mut impl Write,
+        inner: &mut impl Write,
+        ctx: &mut Context,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Text(x) => write!(inner, "{:?}", x),
+            Self::Value(spec, x) => {
+                write!(inner, "{:?}/{}:", spec.kind, spec.width)?;
+                x.print_context(outer, inner, ctx)
+            }
+            Self::Scope => write!(inner, "Scope"),
+        }
+    }
+}
+
 /// A call argument.
 #[moore_derive::visit_without_foreach]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
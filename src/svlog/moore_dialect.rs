@@ -0,0 +1,1406 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Emission of the elaborated design as CIRCT Moore dialect MLIR.
+//!
+//! This backend serializes the design into the textual form of the `moore`
+//! dialect, which the CIRCT tools can lower further. Every module
+//! specialization becomes a `moore.module`, variables and nets become
+//! `moore.variable` and `moore.net` operations, and procedures keep their
+//! control flow as blocks connected by `cf` branches. Only types that map to
+//! a plain bit vector are supported.
+
+use crate::{
+    crate_prelude::*,
+    design::{Design, DesignModule},
+    format::{format_value, DisplayTask, FormatKind, Radix},
+    hir::{AccessedNode, HirNode},
+    ty::{Domain, UnpackedType},
+    value::{Value, ValueKind},
+    ParamEnv,
+};
+use num::{BigInt, One, Signed, Zero};
+use std::collections::{HashMap, HashSet};
+
+/// Serialize a design as Moore dialect MLIR.
+pub fn emit_design<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<String> {
+    let mut out = String::new();
+    out.push_str("module {\n");
+    for module in &design.modules {
+        let mut emitter = Emitter {
+            cx,
+            design,
+            module,
+            refs: HashMap::new(),
+            inputs: HashMap::new(),
+            names: HashSet::new(),
+            next_value: 0,
+            next_block: 1,
+            lines: String::new(),
+            indent: 1,
+            terminated: false,
+            loops: vec![],
+        };
+        emitter.emit_module()?;
+        out.push_str(&emitter.lines);
+    }
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// A Moore dialect integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IntType {
+    /// Whether this is a four-valued `l<N>` rather than a two-valued `i<N>`.
+    four_valued: bool,
+    /// The width in bits.
+    width: usize,
+}
+
+impl IntType {
+    fn bool(four_valued: bool) -> Self {
+        IntType {
+            four_valued,
+            width: 1,
+        }
+    }
+}
+
+impl std::fmt::Display for IntType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let prefix = if self.four_valued { "l" } else { "i" };
+        write!(f, "{}{}", prefix, self.width)
+    }
+}
+
+/// An emitted SSA value, or a reference to a variable or net.
+#[derive(Debug, Clone)]
+struct Val {
+    /// The SSA name, including the leading `%`.
+    name: String,
+    /// The type of the value, or the type of the referenced variable or net.
+    ty: IntType,
+}
+
+/// The loop statements surrounding the statement being emitted.
+struct LoopBlocks {
+    /// The block to branch to on `continue`.
+    next: String,
+    /// The block to branch to on `break`.
+    exit: String,
+}
+
+/// A helper to emit a single module specialization.
+struct Emitter<'a, 'c, C> {
+    cx: &'c C,
+    design: &'c Design<'a>,
+    module: &'c DesignModule<'a>,
+    /// The references to variables, nets, and output ports.
+    refs: HashMap<(NodeId, ParamEnv), Val>,
+    /// The values of input ports.
+    inputs: HashMap<NodeId, Val>,
+    /// The SSA names used so far.
+    names: HashSet<String>,
+    next_value: usize,
+    next_block: usize,
+    lines: String,
+    indent: usize,
+    /// Whether the current block has been terminated.
+    terminated: bool,
+    loops: Vec<LoopBlocks>,
+}
+
+impl<'a, 'c, C: Context<'a>> Emitter<'a, 'c, C> {
+    fn emit_module(&mut self) -> Result<()> {
+        let module = self.module;
+        let env = module.id.env();
+
+        // Assemble the module signature.
+        let mut signature = vec![];
+        let mut outputs = vec![];
+        for port in &module.ports {
+            let ty = self.int_type(port.ty, port.port.span)?;
+            match port.port.dir {
+                ast::PortDir::Input => {
+                    let name = self.unique_name(&port.port.name.value.to_string());
+                    signature.push(format!("in {} : !moore.{}", name, ty));
+                    self.inputs.insert(port.port.id, Val { name, ty });
+                }
+                ast::PortDir::Output => {
+                    signature.push(format!("out {} : !moore.{}", port.port.name, ty));
+                    outputs.push(port);
+                }
+                dir => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "{} port `{}` not supported by this output format",
+                            dir, port.port.name
+                        ))
+                        .code("SV0046")
+                        .span(port.port.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        self.line(format!(
+            "moore.module {}({}) {{",
+            symbol(&module.name),
+            signature.join(", ")
+        ));
+        self.indent += 1;
+
+        // Emit the storage for the output ports.
+        for port in &outputs {
+            let ty = self.int_type(port.ty, port.port.span)?;
+            let init = match port.port.data.as_ref().and_then(|d| d.default) {
+                Some(default) => Some(self.rvalue_expr(default, env, ty)?),
+                None => None,
+            };
+            let name = port.port.name.value.to_string();
+            let value = self.storage(&name, port.port.kind, init, ty);
+            self.refs.insert((port.port.id, env), value);
+        }
+
+        // Emit the declarations.
+        for decl in &module.decls {
+            let ty = self.int_type(decl.ty, decl.hir.span)?;
+            let init = match decl.hir.init {
+                Some(init) => Some(self.rvalue_expr(init, decl.env, ty)?),
+                None => None,
+            };
+            let value = self.storage(&decl.name, decl.hir.kind, init, ty);
+            self.refs.insert((decl.id, decl.env), value);
+        }
+
+        // Emit the continuous assignments.
+        for &id in &module.assigns {
+            let hir = match self.cx.hir_of(id.id())? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+            let assign = self.cx.mir_assignment_from_concurrent(Ref(hir), id.env());
+            for assign in self.cx.mir_simplify_assignment(Ref(assign)) {
+                if assign.is_error() {
+                    return Err(());
+                }
+                let lhs = self.lvalue(assign.lhs)?;
+                let rhs = self.rvalue(assign.rhs)?;
+                let rhs = self.convert(rhs, lhs.ty);
+                self.line(format!(
+                    "moore.assign {}, {} : {}",
+                    lhs.name, rhs.name, lhs.ty
+                ));
+            }
+        }
+
+        // Emit the instances.
+        for inst in &module.insts {
+            self.emit_inst(inst)?;
+        }
+
+        // Emit the procedures.
+        for &id in &module.procs {
+            self.emit_proc(id.id(), id.env())?;
+        }
+
+        // Forward the output port values.
+        let mut values = vec![];
+        for port in &outputs {
+            let value = self.refs[&(port.port.id, env)].clone();
+            values.push(self.read(&value));
+        }
+        if values.is_empty() {
+            self.line("moore.output");
+        } else {
+            self.line(format!(
+                "moore.output {} : {}",
+                values
+                    .iter()
+                    .map(|v| v.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                values
+                    .iter()
+                    .map(|v| format!("!moore.{}", v.ty))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        self.indent -= 1;
+        self.line("}");
+        Ok(())
+    }
+
+    /// Emit a variable or net.
+    fn storage(&mut self, name: &str, kind: ast::VarKind, init: Option<Val>, ty: IntType) -> Val {
+        let name = self.unique_name(name);
+        let init = init.map(|v| format!(" {}", v.name)).unwrap_or_default();
+        match kind {
+            ast::VarKind::Var => self.line(format!("{} = moore.variable{} : <{}>", name, init, ty)),
+            ast::VarKind::Net { ty: net, .. } => {
+                self.line(format!("{} = moore.net {}{} : <{}>", name, net, init, ty))
+            }
+        }
+        Val { name, ty }
+    }
+
+    fn emit_inst(&mut self, inst: &crate::design::DesignInst) -> Result<()> {
+        let target = &self.design.modules[inst.module];
+        let inner_env = target.id.env();
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        for (port, &mapping) in target.ports.iter().zip(&inst.ports) {
+            let ty = self.int_type(port.ty, port.port.span)?;
+            if port.port.dir == ast::PortDir::Output {
+                outputs.push((port, ty, mapping));
+                continue;
+            }
+            let value = match (mapping, port.port.data.as_ref().and_then(|d| d.default)) {
+                (Some(mapping), _) => self.rvalue_expr(mapping.id(), mapping.env(), ty)?,
+                (None, Some(default)) => self.rvalue_expr(default, inner_env, ty)?,
+                (None, None) => {
+                    let value = self.cx.type_default_value(port.ty);
+                    self.constant(value, ty, port.port.span)?
+                }
+            };
+            inputs.push(format!("{}: {}: !moore.{}", port.port.name, value.name, ty));
+        }
+        let results: Vec<_> = outputs.iter().map(|_| self.fresh()).collect();
+        self.line(format!(
+            "{}moore.instance \"{}\" {}({}) -> ({})",
+            if results.is_empty() {
+                String::new()
+            } else {
+                format!("{} = ", results.join(", "))
+            },
+            inst.name,
+            symbol(&target.name),
+            inputs.join(", "),
+            outputs
+                .iter()
+                .map(|(port, ty, _)| format!("{}: !moore.{}", port.port.name, ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+
+        // Drive the signals connected to the outputs.
+        for ((_, ty, mapping), name) in outputs.into_iter().zip(results) {
+            let mapping = match mapping {
+                Some(m) => m,
+                None => continue,
+            };
+            let mir = self.cx.mir_lvalue(mapping.id(), mapping.env());
+            if mir.is_error() {
+                return Err(());
+            }
+            let lhs = self.lvalue(mir)?;
+            let value = self.convert(Val { name, ty }, lhs.ty);
+            self.line(format!(
+                "moore.assign {}, {} : {}",
+                lhs.name, value.name, lhs.ty
+            ));
+        }
+        Ok(())
+    }
+
+    fn emit_proc(&mut self, proc_id: NodeId, env: ParamEnv) -> Result<()> {
+        let hir = match self.cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        let kind = match hir.kind {
+            ast::ProcedureKind::Initial => "initial",
+            ast::ProcedureKind::Always => "always",
+            ast::ProcedureKind::AlwaysComb => "always_comb",
+            ast::ProcedureKind::AlwaysLatch => "always_latch",
+            ast::ProcedureKind::AlwaysFf => "always_ff",
+            ast::ProcedureKind::Final => "final",
+        };
+        self.line(format!("moore.procedure {} {{", kind));
+        self.indent += 1;
+        self.terminated = false;
+        self.emit_stmt(hir.stmt, env)?;
+        self.line("moore.return");
+        self.indent -= 1;
+        self.line("}");
+        Ok(())
+    }
+
+    fn emit_stmt(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            HirNode::VarDecl(decl) => {
+                let ty = self.cx.type_of(stmt_id, env)?;
+                let ty = self.int_type(ty, decl.span)?;
+                let init = match decl.init {
+                    Some(init) => Some(self.rvalue_expr(init, env, ty)?),
+                    None => None,
+                };
+                let value = self.storage(&decl.name.value.to_string(), decl.kind, init, ty);
+                self.refs.insert((stmt_id, env), value);
+                return Ok(());
+            }
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Null => (),
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.emit_stmt(stmt, env)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, kind } => {
+                let op = match kind {
+                    hir::AssignKind::Block(_) => "blocking_assign",
+                    hir::AssignKind::Nonblock => "nonblocking_assign",
+                    hir::AssignKind::NonblockDelay(_) => return self.unsupported(hir),
+                };
+                let assign = self
+                    .cx
+                    .mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                for assign in self.cx.mir_simplify_assignment(Ref(assign)) {
+                    if assign.is_error() {
+                        return Err(());
+                    }
+                    let rhs = self.rvalue(assign.rhs)?;
+                    let lhs = self.lvalue(assign.lhs)?;
+                    let rhs = self.convert(rhs, lhs.ty);
+                    self.line(format!(
+                        "moore.{} {}, {} : {}",
+                        op, lhs.name, rhs.name, lhs.ty
+                    ));
+                }
+            }
+            hir::StmtKind::Expr(expr) => {
+                let mir = self.cx.mir_rvalue(expr, env);
+                match mir.kind {
+                    mir::RvalueKind::Display { task, ref pieces } => {
+                        self.emit_display(task, pieces, mir.span)?;
+                    }
+                    _ => {
+                        self.rvalue(mir)?;
+                    }
+                }
+            }
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                let cond = self.rvalue(self.cx.mir_rvalue(cond, env))?;
+                let cond = self.builtin_bool(cond);
+                let main_blk = self.block();
+                let else_blk = self.block();
+                let exit_blk = match else_stmt {
+                    Some(_) => self.block(),
+                    None => else_blk.clone(),
+                };
+                self.cond_br(&cond, &main_blk, &else_blk);
+                self.start_block(&main_blk);
+                self.emit_stmt(main_stmt, env)?;
+                self.br(&exit_blk);
+                if let Some(else_stmt) = else_stmt {
+                    self.start_block(&else_blk);
+                    self.emit_stmt(else_stmt, env)?;
+                    self.br(&exit_blk);
+                }
+                self.start_block(&exit_blk);
+            }
+            hir::StmtKind::Loop { kind, body } => self.emit_loop(kind, body, env)?,
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                let op = match kind {
+                    ast::CaseKind::Normal => "case_eq",
+                    ast::CaseKind::DontCareZ => "casez_eq",
+                    ast::CaseKind::DontCareXZ => "casexz_eq",
+                };
+                let value = self.rvalue(self.cx.mir_rvalue(expr, env))?;
+                let exit_blk = self.block();
+                for &(ref labels, stmt) in ways {
+                    let mut check: Option<Val> = None;
+                    for &label in labels {
+                        let label_value = self.cx.constant_value_of(label, env);
+                        let label_value =
+                            self.constant(label_value, value.ty, self.cx.span(label))?;
+                        let eq = self.fresh();
+                        self.line(format!(
+                            "{} = moore.{} {}, {} : {}",
+                            eq, op, value.name, label_value.name, value.ty
+                        ));
+                        let eq = Val {
+                            name: eq,
+                            ty: IntType::bool(false),
+                        };
+                        check = Some(match check {
+                            Some(check) => self.binary("or", &check, &eq),
+                            None => eq,
+                        });
+                    }
+                    if let Some(check) = check {
+                        let check = self.builtin_bool(check);
+                        let taken_blk = self.block();
+                        let untaken_blk = self.block();
+                        self.cond_br(&check, &taken_blk, &untaken_blk);
+                        self.start_block(&taken_blk);
+                        self.emit_stmt(stmt, env)?;
+                        self.br(&exit_blk);
+                        self.start_block(&untaken_blk);
+                    }
+                }
+                if let Some(default) = default {
+                    self.emit_stmt(default, env)?;
+                }
+                self.br(&exit_blk);
+                self.start_block(&exit_blk);
+            }
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ExplicitEvent(expr_id),
+                stmt,
+            } => {
+                let expr = match self.cx.hir_of(expr_id)? {
+                    HirNode::EventExpr(x) => x,
+                    _ => unreachable!(),
+                };
+                self.line("moore.wait_event {");
+                self.indent += 1;
+                for event in &expr.events {
+                    if !event.iff.is_empty() {
+                        self.cx.emit(
+                            DiagBuilder2::error(
+                                "`iff` event qualifiers not supported by this output format",
+                            )
+                            .code("SV0046")
+                            .span(event.span),
+                        );
+                        return Err(());
+                    }
+                    let edge = match event.edge {
+                        ast::EdgeIdent::Implicit => "any",
+                        ast::EdgeIdent::Edge => "edge",
+                        ast::EdgeIdent::Posedge => "posedge",
+                        ast::EdgeIdent::Negedge => "negedge",
+                    };
+                    let value = self.rvalue(self.cx.mir_rvalue(event.expr, env))?;
+                    self.line(format!(
+                        "moore.detect_event {} {} : {}",
+                        edge, value.name, value.ty
+                    ));
+                }
+                self.indent -= 1;
+                self.line("}");
+                self.emit_stmt(stmt, env)?;
+            }
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ImplicitEvent,
+                stmt,
+            } => {
+                let acc = self.cx.accessed_nodes(stmt, env)?;
+                self.line("moore.wait_event {");
+                self.indent += 1;
+                for &node in &acc.read {
+                    let id = match node {
+                        AccessedNode::Regular(id) => id,
+                        AccessedNode::Intf(..) => continue,
+                    };
+                    let value = if let Some(value) = self.inputs.get(&id) {
+                        value.clone()
+                    } else if let Some(value) = self.find_ref(id, env) {
+                        self.read(&value)
+                    } else {
+                        continue;
+                    };
+                    self.line(format!(
+                        "moore.detect_event any {} : {}",
+                        value.name, value.ty
+                    ));
+                }
+                self.indent -= 1;
+                self.line("}");
+                self.emit_stmt(stmt, env)?;
+            }
+            hir::StmtKind::Ast(ast) => {
+                let (target, what) = match ast.kind {
+                    ast::BreakStmt => (self.loops.last().map(|b| b.exit.clone()), "break"),
+                    ast::ContinueStmt => (self.loops.last().map(|b| b.next.clone()), "continue"),
+                    _ => return self.unsupported(hir),
+                };
+                match target {
+                    Some(target) => self.br(&target),
+                    None => {
+                        self.cx.emit(
+                            DiagBuilder2::error(format!("{} statement outside of loop", what))
+                                .code("SV0033")
+                                .span(ast.human_span()),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::Delay(_),
+                ..
+            } => {
+                self.cx.emit(
+                    DiagBuilder2::error("delays not supported by this output format")
+                        .code("SV0046")
+                        .span(hir.span),
+                );
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
+    fn emit_loop(&mut self, kind: hir::LoopKind, body: NodeId, env: ParamEnv) -> Result<()> {
+        // Emit the loop initialization.
+        let counter = match kind {
+            hir::LoopKind::Repeat(count) => {
+                let count = self.rvalue(self.cx.mir_rvalue(count, env))?;
+                Some(self.storage(
+                    "loop_count",
+                    ast::VarKind::Var,
+                    Some(count.clone()),
+                    count.ty,
+                ))
+            }
+            hir::LoopKind::For(init, _, _) => {
+                self.emit_stmt(init, env)?;
+                None
+            }
+            _ => None,
+        };
+
+        // Check whether to enter the loop body.
+        let check_blk = self.block();
+        let body_blk = self.block();
+        let next_blk = self.block();
+        let exit_blk = self.block();
+        self.br(&check_blk);
+        self.start_block(&check_blk);
+        let enter = match kind {
+            hir::LoopKind::Repeat(_) => {
+                let counter = counter.as_ref().unwrap();
+                let value = self.read(counter);
+                let zero = self.constant_int(&BigInt::zero(), value.ty);
+                let ne = self.fresh();
+                self.line(format!(
+                    "{} = moore.ne {}, {} : {} -> {}",
+                    ne,
+                    value.name,
+                    zero.name,
+                    value.ty,
+                    IntType::bool(value.ty.four_valued)
+                ));
+                Some(Val {
+                    name: ne,
+                    ty: IntType::bool(value.ty.four_valued),
+                })
+            }
+            hir::LoopKind::While(cond) | hir::LoopKind::For(_, cond, _) => {
+                Some(self.rvalue(self.cx.mir_rvalue(cond, env))?)
+            }
+            hir::LoopKind::Forever | hir::LoopKind::Do(_) => None,
+        };
+        match enter {
+            Some(enter) => {
+                let enter = self.builtin_bool(enter);
+                self.cond_br(&enter, &body_blk, &exit_blk);
+            }
+            None => self.br(&body_blk),
+        }
+
+        // Emit the loop body.
+        self.start_block(&body_blk);
+        self.loops.push(LoopBlocks {
+            next: next_blk.clone(),
+            exit: exit_blk.clone(),
+        });
+        let result = self.emit_stmt(body, env);
+        self.loops.pop();
+        result?;
+        self.br(&next_blk);
+
+        // Advance to the next iteration.
+        self.start_block(&next_blk);
+        match kind {
+            hir::LoopKind::Repeat(_) => {
+                let counter = counter.as_ref().unwrap();
+                let value = self.read(counter);
+                let one = self.constant_int(&BigInt::one(), value.ty);
+                let value = self.binary("sub", &value, &one);
+                self.line(format!(
+                    "moore.blocking_assign {}, {} : {}",
+                    counter.name, value.name, counter.ty
+                ));
+                self.br(&check_blk);
+            }
+            hir::LoopKind::Do(cond) => {
+                let cond = self.rvalue(self.cx.mir_rvalue(cond, env))?;
+                let cond = self.builtin_bool(cond);
+                self.cond_br(&cond, &body_blk, &exit_blk);
+            }
+            hir::LoopKind::For(_, _, step) => {
+                self.rvalue(self.cx.mir_rvalue(step, env))?;
+                self.br(&check_blk);
+            }
+            hir::LoopKind::Forever | hir::LoopKind::While(_) => self.br(&check_blk),
+        }
+        self.start_block(&exit_blk);
+        Ok(())
+    }
+
+    /// Emit a display task as a concatenation of formatted fragments. Values
+    /// that are known at compile time are formatted right away.
+    fn emit_display(
+        &mut self,
+        task: DisplayTask,
+        pieces: &'a [mir::FormatPiece<'a>],
+        span: Span,
+    ) -> Result<()> {
+        if task != DisplayTask::Display && task != DisplayTask::Write {
+            self.cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` not supported by this output format",
+                    task.name()
+                ))
+                .code("SV0046")
+                .span(span),
+            );
+            return Err(());
+        }
+        let mut fragments = vec![];
+        let mut text = String::new();
+        for piece in pieces {
+            let (spec, value) = match *piece {
+                mir::FormatPiece::Text(ref x) => {
+                    text.push_str(x);
+                    continue;
+                }
+                mir::FormatPiece::Scope => {
+                    self.cx.emit(
+                        DiagBuilder2::error("`%m` not supported by this output format")
+                            .code("SV0046")
+                            .span(span),
+                    );
+                    return Err(());
+                }
+                mir::FormatPiece::Value(spec, value) => (spec, value),
+            };
+            if value.is_const() {
+                let value = self.cx.const_mir_rvalue(value.into());
+                text.push_str(&format_value(spec, &value).ok_or(())?);
+                continue;
+            }
+            let (format, pad) = match spec.kind {
+                FormatKind::Int(Radix::Bin) => ("binary", "zero"),
+                FormatKind::Int(Radix::Oct) => ("octal", "zero"),
+                FormatKind::Int(Radix::Dec) => ("decimal", "space"),
+                FormatKind::Int(Radix::Hex) => ("hex_lower", "zero"),
+                _ => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "formatting `{}` at runtime not supported by this output format",
+                            value.span.extract()
+                        ))
+                        .code("SV0046")
+                        .span(value.span),
+                    );
+                    return Err(());
+                }
+            };
+            self.fmt_literal(&mut text, &mut fragments);
+            let value = self.rvalue(value)?;
+            let name = self.fresh();
+            self.line(format!(
+                "{} = moore.fmt.int {} {}, width {}, align right, pad {} : {}",
+                name, format, value.name, spec.width, pad, value.ty
+            ));
+            fragments.push(name);
+        }
+        self.fmt_literal(&mut text, &mut fragments);
+        let message = match fragments.len() {
+            0 => {
+                let name = self.fresh();
+                self.line(format!("{} = moore.fmt.literal \"\"", name));
+                name
+            }
+            1 => fragments.pop().unwrap(),
+            _ => {
+                let name = self.fresh();
+                self.line(format!(
+                    "{} = moore.fmt.concat ({})",
+                    name,
+                    fragments.join(", ")
+                ));
+                name
+            }
+        };
+        self.line(format!("moore.builtin.display {}", message));
+        Ok(())
+    }
+
+    /// Emit and clear the text accumulated for a display task.
+    fn fmt_literal(&mut self, text: &mut String, fragments: &mut Vec<String>) {
+        if text.is_empty() {
+            return;
+        }
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.fmt.literal {}",
+            name,
+            string_literal(&std::mem::take(text))
+        ));
+        fragments.push(name);
+    }
+
+    /// Emit an expression, converted to a given type.
+    fn rvalue_expr(&mut self, expr_id: NodeId, env: ParamEnv, ty: IntType) -> Result<Val> {
+        let mir = self.cx.mir_rvalue(expr_id, env);
+        let value = self.rvalue(mir)?;
+        Ok(self.convert(value, ty))
+    }
+
+    fn rvalue(&mut self, mir: &'a mir::Rvalue<'a>) -> Result<Val> {
+        if mir.is_error() {
+            return Err(());
+        }
+        let ty = self.int_type(mir.ty, mir.span)?;
+
+        // Emit constants as their folded value.
+        if mir.is_const() {
+            let value = self.cx.const_mir_rvalue(mir.into());
+            return self.constant(value, ty, mir.span);
+        }
+
+        Ok(match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
+                if let Some(value) = self.inputs.get(&id) {
+                    return Ok(value.clone());
+                }
+                let value = self.lookup_ref(id, mir.env, mir.span)?;
+                self.read(&value)
+            }
+            mir::RvalueKind::CastValueDomain { value, to, .. } => {
+                let value = self.rvalue(value)?;
+                match to {
+                    Domain::FourValued => self.cast("int_to_logic", value, ty),
+                    Domain::TwoValued => self.cast("logic_to_int", value, ty),
+                }
+            }
+            mir::RvalueKind::Transmute(value) | mir::RvalueKind::CastSign(_, value) => {
+                let value = self.rvalue(value)?;
+                self.convert(value, ty)
+            }
+            mir::RvalueKind::CastToBool(value) => {
+                let value = self.rvalue(value)?;
+                if value.ty.width == 1 {
+                    self.convert(value, ty)
+                } else {
+                    self.conversion("bool_cast", value, ty)
+                }
+            }
+            mir::RvalueKind::Truncate(_, value) => {
+                let value = self.rvalue(value)?;
+                self.conversion("trunc", value, ty)
+            }
+            mir::RvalueKind::ZeroExtend(_, value) => {
+                let value = self.rvalue(value)?;
+                self.conversion("zext", value, ty)
+            }
+            mir::RvalueKind::SignExtend(_, value) => {
+                let value = self.rvalue(value)?;
+                self.conversion("sext", value, ty)
+            }
+            mir::RvalueKind::UnaryBitwise { op, arg } => {
+                let arg = self.rvalue(arg)?;
+                match op {
+                    mir::UnaryBitwiseOp::Not => self.unary("not", &arg),
+                }
+            }
+            mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let op = match op {
+                    mir::BinaryBitwiseOp::And => "and",
+                    mir::BinaryBitwiseOp::Or => "or",
+                    mir::BinaryBitwiseOp::Xor => "xor",
+                };
+                self.binary(op, &lhs, &rhs)
+            }
+            mir::RvalueKind::IntUnaryArith { op, arg, .. } => {
+                let arg = self.rvalue(arg)?;
+                match op {
+                    mir::IntUnaryArithOp::Neg => self.unary("neg", &arg),
+                }
+            }
+            mir::RvalueKind::IntBinaryArith {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let signed = sign.is_signed();
+                let op = match op {
+                    mir::IntBinaryArithOp::Add => "add",
+                    mir::IntBinaryArithOp::Sub => "sub",
+                    mir::IntBinaryArithOp::Mul => "mul",
+                    mir::IntBinaryArithOp::Div if signed => "divs",
+                    mir::IntBinaryArithOp::Div => "divu",
+                    mir::IntBinaryArithOp::Mod if signed => "mods",
+                    mir::IntBinaryArithOp::Mod => "modu",
+                    mir::IntBinaryArithOp::Pow if signed => "pows",
+                    mir::IntBinaryArithOp::Pow => "powu",
+                };
+                self.binary(op, &lhs, &rhs)
+            }
+            mir::RvalueKind::IntComp {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let signed = sign.is_signed();
+                let op = match op {
+                    mir::IntCompOp::Eq => "eq",
+                    mir::IntCompOp::Neq => "ne",
+                    mir::IntCompOp::Lt if signed => "slt",
+                    mir::IntCompOp::Lt => "ult",
+                    mir::IntCompOp::Leq if signed => "sle",
+                    mir::IntCompOp::Leq => "ule",
+                    mir::IntCompOp::Gt if signed => "sgt",
+                    mir::IntCompOp::Gt => "ugt",
+                    mir::IntCompOp::Geq if signed => "sge",
+                    mir::IntCompOp::Geq => "uge",
+                };
+                let rhs = self.convert(rhs, lhs.ty);
+                let result = IntType::bool(lhs.ty.four_valued);
+                let name = self.fresh();
+                self.line(format!(
+                    "{} = moore.{} {}, {} : {} -> {}",
+                    name, op, lhs.name, rhs.name, lhs.ty, result
+                ));
+                self.convert(Val { name, ty: result }, ty)
+            }
+            mir::RvalueKind::Concat(ref values) => {
+                let values = values
+                    .iter()
+                    .map(|&v| self.rvalue(v))
+                    .collect::<Result<Vec<_>>>()?;
+                let name = self.fresh();
+                self.line(format!(
+                    "{} = moore.concat {} : ({}) -> {}",
+                    name,
+                    values
+                        .iter()
+                        .map(|v| v.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    values
+                        .iter()
+                        .map(|v| v.ty.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    ty
+                ));
+                Val { name, ty }
+            }
+            mir::RvalueKind::Repeat(_, value) => {
+                let value = self.rvalue(value)?;
+                self.conversion("replicate", value, ty)
+            }
+            mir::RvalueKind::Index {
+                value,
+                base,
+                length: _,
+            } => {
+                let value = self.rvalue(value)?;
+                if base.is_const() {
+                    let offset = self.const_offset(base)?;
+                    let name = self.fresh();
+                    self.line(format!(
+                        "{} = moore.extract {} from {} : {} -> {}",
+                        name, value.name, offset, value.ty, ty
+                    ));
+                    Val { name, ty }
+                } else {
+                    let base = self.rvalue(base)?;
+                    let name = self.fresh();
+                    self.line(format!(
+                        "{} = moore.dyn_extract {} from {} : {}, {} -> {}",
+                        name, value.name, base.name, value.ty, base.ty, ty
+                    ));
+                    Val { name, ty }
+                }
+            }
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = self.rvalue(cond)?;
+                let name = self.fresh();
+                self.line(format!(
+                    "{} = moore.conditional {} : {} -> {} {{",
+                    name, cond.name, cond.ty, ty
+                ));
+                self.indent += 1;
+                let value = self.rvalue(true_value)?;
+                let value = self.convert(value, ty);
+                self.line(format!("moore.yield {} : {}", value.name, ty));
+                self.indent -= 1;
+                self.line("} {");
+                self.indent += 1;
+                let value = self.rvalue(false_value)?;
+                let value = self.convert(value, ty);
+                self.line(format!("moore.yield {} : {}", value.name, ty));
+                self.indent -= 1;
+                self.line("}");
+                Val { name, ty }
+            }
+            mir::RvalueKind::Shift {
+                op,
+                arith,
+                value,
+                amount,
+            } => {
+                let value = self.rvalue(value)?;
+                let amount = self.rvalue(amount)?;
+                let op = match op {
+                    mir::ShiftOp::Left => "shl",
+                    mir::ShiftOp::Right if arith => "ashr",
+                    mir::ShiftOp::Right => "shr",
+                };
+                let name = self.fresh();
+                self.line(format!(
+                    "{} = moore.{} {}, {} : {}, {}",
+                    name, op, value.name, amount.name, value.ty, amount.ty
+                ));
+                Val { name, ty: value.ty }
+            }
+            mir::RvalueKind::Reduction { op, arg } => {
+                let arg = self.rvalue(arg)?;
+                let op = match op {
+                    mir::BinaryBitwiseOp::And => "reduce_and",
+                    mir::BinaryBitwiseOp::Or => "reduce_or",
+                    mir::BinaryBitwiseOp::Xor => "reduce_xor",
+                };
+                self.conversion(op, arg, ty)
+            }
+            mir::RvalueKind::Assignment {
+                lvalue,
+                rvalue,
+                result,
+            } => {
+                let value = self.rvalue(rvalue)?;
+                let lhs = self.lvalue(lvalue)?;
+                let value = self.convert(value, lhs.ty);
+                self.line(format!(
+                    "moore.blocking_assign {}, {} : {}",
+                    lhs.name, value.name, lhs.ty
+                ));
+                self.rvalue(result)?
+            }
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "expression `{}` not supported by this output format",
+                        mir.span.extract()
+                    ))
+                    .code("SV0046")
+                    .span(mir.span),
+                );
+                return Err(());
+            }
+        })
+    }
+
+    /// Emit a reference to the storage targeted by an lvalue.
+    fn lvalue(&mut self, mir: &'a mir::Lvalue<'a>) -> Result<Val> {
+        if mir.is_error() {
+            return Err(());
+        }
+        let ty = self.int_type(mir.ty, mir.span)?;
+        match mir.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                self.lookup_ref(id, mir.env, mir.span)
+            }
+            mir::LvalueKind::Transmute(value) => {
+                let value = self.lvalue(value)?;
+                if value.ty == ty {
+                    Ok(value)
+                } else {
+                    self.unsupported_lvalue(mir)
+                }
+            }
+            mir::LvalueKind::Index {
+                value,
+                base,
+                length: _,
+            } => {
+                let value = self.lvalue(value)?;
+                if base.is_const() {
+                    let offset = self.const_offset(base)?;
+                    let name = self.fresh();
+                    self.line(format!(
+                        "{} = moore.extract_ref {} from {} : <{}> -> <{}>",
+                        name, value.name, offset, value.ty, ty
+                    ));
+                    Ok(Val { name, ty })
+                } else {
+                    let base = self.rvalue(base)?;
+                    let name = self.fresh();
+                    self.line(format!(
+                        "{} = moore.dyn_extract_ref {} from {} : <{}>, {} -> <{}>",
+                        name, value.name, base.name, value.ty, base.ty, ty
+                    ));
+                    Ok(Val { name, ty })
+                }
+            }
+            _ => self.unsupported_lvalue(mir),
+        }
+    }
+
+    /// Evaluate the constant bit offset of an index.
+    fn const_offset(&mut self, base: &'a mir::Rvalue<'a>) -> Result<BigInt> {
+        let value = self.cx.const_mir_rvalue(base.into());
+        match value.get_int() {
+            Some(offset) if !offset.is_negative() => Ok(offset.clone()),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "index `{}` out of bounds; not supported by this output format",
+                        base.span.extract()
+                    ))
+                    .code("SV0046")
+                    .span(base.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Emit a constant value.
+    fn constant(&mut self, value: Value<'a>, ty: IntType, span: Span) -> Result<Val> {
+        let (int, special, x) = match value.kind {
+            ValueKind::Int(ref int, ref special, ref x) => (int, special, x),
+            ValueKind::Error => return Err(()),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "constant `{}` not supported by this output format",
+                        value
+                    ))
+                    .code("SV0046")
+                    .span(span),
+                );
+                return Err(());
+            }
+        };
+        if !special.any() {
+            return Ok(self.constant_int(int, ty));
+        }
+
+        // The special bits are stored with the most significant digit first
+        // and only cover the digits spelled out in the literal.
+        let int = normalize(int, ty.width);
+        let digits: String = (0..ty.width)
+            .rev()
+            .map(|bit| match special.len().checked_sub(bit + 1) {
+                Some(i) if special[i] && x[i] => 'X',
+                Some(i) if special[i] => 'Z',
+                _ if int.bit(bit as u64) => '1',
+                _ => '0',
+            })
+            .collect();
+        let name = self.fresh();
+        self.line(format!("{} = moore.constant b{} : {}", name, digits, ty));
+        Ok(Val { name, ty })
+    }
+
+    /// Emit an integer constant.
+    fn constant_int(&mut self, value: &BigInt, ty: IntType) -> Val {
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.constant {} : {}",
+            name,
+            normalize(value, ty.width),
+            ty
+        ));
+        Val { name, ty }
+    }
+
+    /// Convert a value to a different width and domain.
+    fn convert(&mut self, value: Val, ty: IntType) -> Val {
+        let width = IntType {
+            four_valued: value.ty.four_valued,
+            width: ty.width,
+        };
+        let value = if value.ty.width > ty.width {
+            self.conversion("trunc", value, width)
+        } else if value.ty.width < ty.width {
+            self.conversion("zext", value, width)
+        } else {
+            value
+        };
+        match (value.ty.four_valued, ty.four_valued) {
+            (false, true) => self.cast("int_to_logic", value, ty),
+            (true, false) => self.cast("logic_to_int", value, ty),
+            _ => value,
+        }
+    }
+
+    /// Convert a single-bit value to a builtin `i1` for use in branches.
+    fn builtin_bool(&mut self, value: Val) -> Val {
+        let value = if value.ty.width != 1 {
+            self.conversion(
+                "bool_cast",
+                value.clone(),
+                IntType::bool(value.ty.four_valued),
+            )
+        } else {
+            value
+        };
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.to_builtin_bool {} : {}",
+            name, value.name, value.ty
+        ));
+        Val {
+            name,
+            ty: IntType::bool(false),
+        }
+    }
+
+    /// Emit an operation of the form `op %a : T -> U`.
+    fn conversion(&mut self, op: &str, value: Val, ty: IntType) -> Val {
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.{} {} : {} -> {}",
+            name, op, value.name, value.ty, ty
+        ));
+        Val { name, ty }
+    }
+
+    /// Emit an operation of the form `op %a : T` that changes the domain.
+    fn cast(&mut self, op: &str, value: Val, ty: IntType) -> Val {
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.{} {} : {}",
+            name, op, value.name, value.ty
+        ));
+        Val { name, ty }
+    }
+
+    fn unary(&mut self, op: &str, arg: &Val) -> Val {
+        let name = self.fresh();
+        self.line(format!("{} = moore.{} {} : {}", name, op, arg.name, arg.ty));
+        Val { name, ty: arg.ty }
+    }
+
+    fn binary(&mut self, op: &str, lhs: &Val, rhs: &Val) -> Val {
+        let rhs = self.convert(rhs.clone(), lhs.ty);
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.{} {}, {} : {}",
+            name, op, lhs.name, rhs.name, lhs.ty
+        ));
+        Val { name, ty: lhs.ty }
+    }
+
+    /// Read the current value of a variable or net.
+    fn read(&mut self, target: &Val) -> Val {
+        let name = self.fresh();
+        self.line(format!(
+            "{} = moore.read {} : <{}>",
+            name, target.name, target.ty
+        ));
+        Val {
+            name,
+            ty: target.ty,
+        }
+    }
+
+    /// Find the reference to a declaration as seen from an environment.
+    fn find_ref(&self, id: NodeId, env: ParamEnv) -> Option<Val> {
+        if let Some(value) = self.refs.get(&(id, env)) {
+            return Some(value.clone());
+        }
+        if let Some(decl) = self.module.find_decl(id, env) {
+            return self.refs.get(&(decl.id, decl.env)).cloned();
+        }
+        self.refs.get(&(id, self.module.id.env())).cloned()
+    }
+
+    fn lookup_ref(&mut self, id: NodeId, env: ParamEnv, span: Span) -> Result<Val> {
+        match self.find_ref(id, env) {
+            Some(value) => Ok(value),
+            None => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "reference to `{}` not supported by this output format",
+                        span.extract()
+                    ))
+                    .code("SV0046")
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Map a type to a Moore dialect integer type.
+    fn int_type(&self, ty: &'a UnpackedType<'a>, span: Span) -> Result<IntType> {
+        match ty.get_simple_bit_vector() {
+            Some(sbv) if ty.coalesces_to_llhd_scalar() && sbv.size > 0 => Ok(IntType {
+                four_valued: sbv.domain == Domain::FourValued,
+                width: sbv.size,
+            }),
+            _ => {
+                if !ty.is_error() {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "type `{}` not supported by this output format",
+                            ty
+                        ))
+                        .code("SV0046")
+                        .span(span),
+                    );
+                }
+                Err(())
+            }
+        }
+    }
+
+    fn unsupported(&self, hir: &hir::Stmt) -> Result<()> {
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "{} not supported by this output format",
+                hir.desc_full()
+            ))
+            .code("SV0046")
+            .span(hir.span),
+        );
+        Err(())
+    }
+
+    fn unsupported_lvalue(&self, mir: &mir::Lvalue) -> Result<Val> {
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "assignment to `{}` not supported by this output format",
+                mir.span.extract()
+            ))
+            .code("SV0046")
+            .span(mir.span),
+        );
+        Err(())
+    }
+
+    /// Allocate a fresh SSA value name.
+    fn fresh(&mut self) -> String {
+        let name = format!("%{}", self.next_value);
+        self.next_value += 1;
+        name
+    }
+
+    /// Allocate an SSA name derived from a name in the source text.
+    fn unique_name(&mut self, name: &str) -> String {
+        let base: String = name
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '$' | '.' => c,
+                _ => '_',
+            })
+            .collect();
+        let mut name = format!("%{}", base);
+        let mut index = 0;
+        while !self.names.insert(name.clone()) {
+            index += 1;
+            name = format!("%{}_{}", base, index);
+        }
+        name
+    }
+
+    /// Allocate a new block label.
+    fn block(&mut self) -> String {
+        let label = format!("^bb{}", self.next_block);
+        self.next_block += 1;
+        label
+    }
+
+    /// Start emitting into a new block.
+    fn start_block(&mut self, label: &str) {
+        self.terminated = false;
+        self.indent -= 1;
+        self.line(format!("{}:", label));
+        self.indent += 1;
+    }
+
+    fn br(&mut self, target: &str) {
+        if !self.terminated {
+            self.line(format!("cf.br {}", target));
+            self.terminated = true;
+        }
+    }
+
+    fn cond_br(&mut self, cond: &Val, main: &str, other: &str) {
+        self.line(format!("cf.cond_br {}, {}, {}", cond.name, main, other));
+        self.terminated = true;
+    }
+
+    /// Emit a line of output. Operations following a terminator are placed
+    /// in a new, unreachable block.
+    fn line(&mut self, line: impl AsRef<str>) {
+        if self.terminated {
+            let label = self.block();
+            self.start_block(&label);
+        }
+        for _ in 0..self.indent {
+            self.lines.push_str("  ");
+        }
+        self.lines.push_str(line.as_ref());
+        self.lines.push('\n');
+    }
+}
+
+/// Map a module name to an MLIR symbol reference.
+fn symbol(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.');
+    if plain {
+        format!("@{}", name)
+    } else {
+        format!("@{:?}", name)
+    }
+}
+
+/// Quote a string as an MLIR string literal.
+fn string_literal(text: &str) -> String {
+    let mut out = String::from("\"");
+    for &byte in text.as_bytes() {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            b' '..=b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("\\{:02X}", byte)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Map an integer to its unsigned representation with a given width.
+fn normalize(value: &BigInt, width: usize) -> BigInt {
+    let modulus = BigInt::one() << width;
+    let value = value % &modulus;
+    if value.is_negative() {
+        value + modulus
+    } else {
+        value
+    }
+}
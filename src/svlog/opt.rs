@@ -0,0 +1,134 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Cleanup passes on the generated LLHD.
+//!
+//! The code generator emits LLHD in a straightforward manner, with a probe for
+//! every read of a signal and a separate signal for every port connection.
+//! This module runs a few passes on the result that fold constants, remove
+//! unused instructions, and forward trivially connected signals, such that the
+//! output is smaller and easier to consume by other tools.
+
+use llhd::{
+    ir::prelude::*,
+    opt::{Pass, PassContext},
+    pass::{ConstFolding, DeadCodeElim, InstSimplification},
+};
+use std::collections::HashMap;
+
+/// The lowest optimization level at which the cleanup passes run.
+pub const CLEANUP_OPT_LEVEL: usize = 2;
+
+/// The maximum number of times the cleanup passes are repeated.
+const MAX_ITERATIONS: usize = 16;
+
+/// Run the cleanup passes appropriate for an optimization level on a module.
+pub fn optimize_module(module: &mut Module, opt_level: usize) {
+    if opt_level < CLEANUP_OPT_LEVEL {
+        return;
+    }
+    let ctx = PassContext;
+    for _ in 0..MAX_ITERATIONS {
+        let mut modified = false;
+        modified |= ConstFolding::run_on_module(&ctx, module);
+        modified |= InstSimplification::run_on_module(&ctx, module);
+        modified |= SignalForwarding::run_on_module(&ctx, module);
+        modified |= DeadCodeElim::run_on_module(&ctx, module);
+        if !modified {
+            break;
+        }
+    }
+}
+
+/// Trivial Signal Forwarding
+///
+/// This pass simplifies the signals of entities. Repeated probes of the same
+/// signal are replaced with the first probe, since an entity has no control
+/// flow and all probes observe the same value. A local signal that is
+/// connected to another signal with the same initial value is replaced with
+/// that signal.
+pub struct SignalForwarding;
+
+impl Pass for SignalForwarding {
+    fn run_on_cfg(_ctx: &PassContext, unit: &mut UnitBuilder) -> bool {
+        if !unit.is_entity() {
+            return false;
+        }
+        let mut modified = false;
+        let insts: Vec<_> = unit.all_insts().collect();
+        let order: HashMap<Inst, usize> = insts.iter().enumerate().map(|(i, &x)| (x, i)).collect();
+
+        // Merge repeated probes of the same signal.
+        let mut probes: HashMap<Value, Value> = HashMap::new();
+        for &inst in &insts {
+            if unit[inst].opcode() != Opcode::Prb {
+                continue;
+            }
+            let signal = unit[inst].args()[0];
+            let value = unit.inst_result(inst);
+            match probes.get(&signal) {
+                Some(&first) => {
+                    debug!("Forward {} to {}", value.dump(&unit), first.dump(&unit));
+                    unit.replace_use(value, first);
+                    unit.delete_inst(inst);
+                    modified = true;
+                }
+                None => {
+                    probes.insert(signal, value);
+                }
+            }
+        }
+
+        // Replace local signals that are connected to another signal. The
+        // other signal must be an argument or defined before the local one,
+        // such that it is available at all uses of the local signal.
+        for &inst in &insts {
+            if !unit.is_inst_inserted(inst) || unit[inst].opcode() != Opcode::Con {
+                continue;
+            }
+            let args = unit[inst].args();
+            let (a, b) = (args[0], args[1]);
+            let definition = |value| match unit.get_value_inst(value) {
+                Some(inst) if unit[inst].opcode() == Opcode::Sig => Some(order[&inst] + 1),
+                Some(_) => None,
+                None => Some(0),
+            };
+            let (local, other) = match (definition(a), definition(b)) {
+                (Some(x), Some(y)) if x > y => (a, b),
+                (Some(x), Some(y)) if y > x => (b, a),
+                _ => continue,
+            };
+            // The merged signal starts out with the value of the other signal,
+            // so the local one may only be dropped if its initial value agrees.
+            if !same_initial_value(unit, local, other) {
+                continue;
+            }
+            debug!("Forward {} to {}", local.dump(&unit), other.dump(&unit));
+            unit.delete_inst(inst);
+            unit.replace_use(local, other);
+            unit.prune_if_unused(unit.value_inst(local));
+            modified = true;
+        }
+
+        modified
+    }
+}
+
+/// Check whether two signals are declared with the same initial value.
+///
+/// The initial value of an argument is not known within the unit, such that
+/// an argument never matches.
+fn same_initial_value(unit: &UnitBuilder, a: Value, b: Value) -> bool {
+    let init = |signal| {
+        let inst = unit.get_value_inst(signal)?;
+        Some(unit[inst].args()[0])
+    };
+    let (a, b) = match (init(a), init(b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return false,
+    };
+    let const_int = |value| {
+        unit.get_value_inst(value)
+            .and_then(|inst| unit[inst].get_const_int().cloned())
+    };
+    a == b || const_int(a).map_or(false, |x| const_int(b) == Some(x))
+}
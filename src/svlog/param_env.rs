@@ -84,6 +84,11 @@ pub struct ParamEnvData<'t> {
 }
 
 impl<'t> ParamEnvData<'t> {
+    /// Get the module or interface which generated this environment.
+    pub fn module(&self) -> Option<NodeId> {
+        self.module
+    }
+
     /// Find the value assigned to a node.
     pub fn find_value(&self, node_id: NodeId) -> Option<ParamEnvBinding<Value<'t>>> {
         self.values
@@ -221,16 +226,22 @@ fn param_env_from_instance<'a>(
         .iter()
         .enumerate()
         .map(|(index, &(span, assign_id))| match params.get(index) {
-            Some(&param_id) => Ok((param_id, (assign_id, env))),
+            Some(&param_id) => {
+                if assign_id.is_some() {
+                    reject_local_override(cx, param_id, span)?;
+                }
+                Ok((param_id, (assign_id, env)))
+            }
             None => {
                 cx.emit(
                     DiagBuilder2::error(format!("{} only has {} parameter(s)", node, params.len()))
+                        .code("SV0007")
                         .span(span),
                 );
                 Err(())
             }
         })
-        .chain(named.iter().map(|&(_span, name, assign_id)| {
+        .chain(named.iter().map(|&(span, name, assign_id)| {
             let names: Vec<_> = params
                 .iter()
                 .flat_map(|&id| match cx.ast_of(id) {
@@ -244,10 +255,16 @@ fn param_env_from_instance<'a>(
                 .iter()
                 .find(|&(param_name, _)| *param_name == name.value)
             {
-                Some(&(_, param_id)) => Ok((param_id, (assign_id, env))),
+                Some(&(_, param_id)) => {
+                    if assign_id.is_some() {
+                        reject_local_override(cx, param_id, span)?;
+                    }
+                    Ok((param_id, (assign_id, env)))
+                }
                 None => {
                     cx.emit(
                         DiagBuilder2::error(format!("no parameter `{}` in {}", name, node,))
+                            .code("SV0005")
                             .span(name.span)
                             .add_note(format!(
                                 "declared parameters are {}",
@@ -281,7 +298,23 @@ fn param_env_from_instance<'a>(
                 cx.set_lowering_hint(assign_id.0, hir::Hint::Type);
                 types.push((param_id, ParamEnvBinding::Indirect(assign_id)))
             }
-            AstNode::ValueParam(..) => {
+            AstNode::ValueParam(_, decl) => {
+                // Make sure we are not assigning a type to a value parameter.
+                if let Ok(AstNode::TypeOrExpr(assigned)) = cx.ast_of(assign_id.0) {
+                    if let Ok(&ast::TypeOrExpr::Type(ty)) = cx.disamb_type_or_expr(Ref(assigned)) {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is not a value",
+                                ty.span().extract()
+                            ))
+                            .code("SV0014")
+                            .span(ty.span())
+                            .add_note(format!("Assigned to value parameter `{}` here:", decl.name))
+                            .span(decl.human_span()),
+                        );
+                        return Err(());
+                    }
+                }
                 cx.set_lowering_hint(assign_id.0, hir::Hint::Expr);
                 values.push((param_id, ParamEnvBinding::Indirect(assign_id)))
             }
@@ -298,3 +331,23 @@ fn param_env_from_instance<'a>(
     cx.add_param_env_context(env, node.id());
     Ok(env)
 }
+
+/// Emit an error if a parameter assignment targets a localparam.
+fn reject_local_override<'a>(cx: &impl Context<'a>, param_id: NodeId, span: Span) -> Result<()> {
+    let (param, name, decl_span) = match cx.ast_of(param_id)? {
+        AstNode::TypeParam(param, decl) => (param, decl.name, decl.human_span()),
+        AstNode::ValueParam(param, decl) => (param, decl.name, decl.human_span()),
+        _ => unreachable!(),
+    };
+    if !param.local {
+        return Ok(());
+    }
+    cx.emit(
+        DiagBuilder2::error(format!("cannot override localparam `{}`", name))
+            .code("SV0009")
+            .span(span)
+            .add_note(format!("`{}` is declared as localparam here:", name))
+            .span(decl_span),
+    );
+    Err(())
+}
@@ -75,6 +75,7 @@ pub(crate) fn map_pattern<'a>(
                             "repetition count {} is outside copable range",
                             const_count,
                         ))
+                        .code("SV0032")
                         .span(cx.span(count)),
                     );
                     return Err(());
@@ -93,6 +94,7 @@ pub(crate) fn map_pattern<'a>(
                         "cannot construct a value of type `{}` with `'{{...}}`",
                         ty
                     ))
+                    .code("SV0026")
                     .span(expr.span)
                     .add_note("Named patterns can only construct arrays or structs."),
                 );
@@ -162,7 +164,9 @@ fn map_named_array_pattern<'a>(
         match map {
             hir::PatternMapping::Type(type_id) => {
                 cx.emit(
-                    DiagBuilder2::error("types cannot index into an array").span(cx.span(type_id)),
+                    DiagBuilder2::error("types cannot index into an array")
+                        .code("SV0026")
+                        .span(cx.span(type_id)),
                 );
                 continue;
             }
@@ -176,6 +180,7 @@ fn map_named_array_pattern<'a>(
                         _ => {
                             cx.emit(
                                 DiagBuilder2::error("array index must be a constant integer")
+                                    .code("SV0030")
                                     .span(cx.span(member_id)),
                             );
                             return Err(());
@@ -186,6 +191,7 @@ fn map_named_array_pattern<'a>(
                         _ => {
                             cx.emit(
                                 DiagBuilder2::error(format!("index `{}` out of bounds", index))
+                                    .code("SV0026")
                                     .span(cx.span(member_id)),
                             );
                             return Err(());
@@ -210,6 +216,7 @@ fn map_named_array_pattern<'a>(
                             prev.span.extract(),
                             index
                         ))
+                        .group("pattern")
                         .span(to.span)
                         .add_note("Previous value was here:")
                         .span(prev.span),
@@ -220,6 +227,7 @@ fn map_named_array_pattern<'a>(
                 Some(ref default) => {
                     cx.emit(
                         DiagBuilder2::error("pattern has multiple default mappings")
+                            .code("SV0026")
                             .span(to.span)
                             .add_note("Previous default mapping was here:")
                             .span(default.span),
@@ -242,6 +250,7 @@ fn map_named_array_pattern<'a>(
         } else {
             cx.emit(
                 DiagBuilder2::error("`default:` missing in non-exhaustive array pattern")
+                    .code("SV0026")
                     .span(span)
                     .add_note("Array patterns must assign a value to every index."),
             );
@@ -321,6 +330,7 @@ fn map_named_struct_pattern<'a>(
                         None => {
                             cx.emit(
                                 DiagBuilder2::error(format!("`{}` member does not exist", name))
+                                    .code("SV0026")
                                     .span(name.span)
                                     .add_note("Struct definition was here:")
                                     .span(strukt.ast.span()),
@@ -340,6 +350,7 @@ fn map_named_struct_pattern<'a>(
                                 prev.span.extract(),
                                 name
                             ))
+                            .group("pattern")
                             .span(to.span)
                             .add_note("Previous value was here:")
                             .span(prev.span),
@@ -353,6 +364,7 @@ fn map_named_struct_pattern<'a>(
                             "`{}` is not a valid struct member name",
                             span.extract()
                         ))
+                        .code("SV0026")
                         .span(span),
                     );
                     failed = true;
@@ -367,6 +379,7 @@ fn map_named_struct_pattern<'a>(
                 Some(default) => {
                     cx.emit(
                         DiagBuilder2::error("pattern has multiple default mappings")
+                            .code("SV0026")
                             .span(to.span)
                             .add_note("Previous mapping default mapping was here:")
                             .span(default.span),
@@ -410,6 +423,7 @@ fn map_named_struct_pattern<'a>(
         } else {
             cx.emit(
                 DiagBuilder2::error(format!("`{}` member missing in struct pattern", field.name))
+                    .code("SV0026")
                     .span(span)
                     .add_note("Struct patterns must assign a value to every member."),
             );
@@ -485,6 +499,7 @@ fn map_positional_pattern<'a>(
                          has no fixed size",
                         ty, dim,
                     ))
+                    .code("SV0026")
                     .span(span),
                 );
                 return Err(());
@@ -513,6 +528,7 @@ fn map_positional_pattern<'a>(
                 "pattern has {} fields, but type `{}` requires {}",
                 len, ty, exp_len
             ))
+            .code("SV0026")
             .span(span),
         );
         return Err(());
@@ -22,6 +22,7 @@
 
 use crate::crate_prelude::*;
 use crate::{ast_map::AstNode, common::arenas::Alloc};
+use serde::Serialize;
 use std::{
     borrow::Cow,
     collections::HashMap,
@@ -33,7 +34,7 @@ use std::{
 /// A `PortList` consists of an ordered list of internal and external ports. The
 /// external ports map to one or more internal ports via `PortExpr`. An optional
 /// name lookup table allows for external ports to be connected to by name.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct PortList<'a> {
     /// The internal ports.
     pub int: Vec<IntPort<'a>>,
@@ -42,20 +43,24 @@ pub struct PortList<'a> {
     pub ext_pos: Vec<ExtPort<'a>>,
     /// The external ports, for named connections. Values are indices into
     /// `ext_pos`. `None` if there are any purely positional external ports.
+    #[serde(skip)]
     pub ext_named: Option<HashMap<Name, usize>>,
     /// The last rib in the list. Useful for name resolution. Obsoleted by new
     /// resolver.
+    #[serde(skip)]
     pub(crate) tail_rib: NodeId,
 }
 
 /// An internal port.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct IntPort<'a> {
     /// Node ID of the port.
     pub id: NodeId,
     /// The AST node that spawned this port.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ast: &'a dyn ast::AnyNode<'a>,
     /// The node containing the port.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub node: &'a dyn PortedNode<'a>,
     /// Location of the port declaration in the source file.
     pub span: Span,
@@ -72,11 +77,13 @@ pub struct IntPort<'a> {
 }
 
 /// Additional internal port details.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct IntPortData<'a> {
     /// Type of the port.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub ty: &'a ast::Type<'a>,
     /// Unpacked dimensions of the port.
+    #[serde(serialize_with = "crate::hir::dump::ser_type_dims")]
     pub unpacked_dims: &'a [ast::TypeDim<'a>],
     /// Optional redundant type (possible in non-ANSI ports), which must be
     /// checked against `ty`.
@@ -88,11 +95,12 @@ pub struct IntPortData<'a> {
 }
 
 /// An external port.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct ExtPort<'a> {
     /// Node ID of the port.
     pub id: NodeId,
     /// The node containing the port.
+    #[serde(serialize_with = "crate::hir::dump::ser_ast")]
     pub node: &'a dyn PortedNode<'a>,
     /// Location of the port declaration in the source file.
     pub span: Span,
@@ -104,7 +112,7 @@ pub struct ExtPort<'a> {
 }
 
 /// A port expression associating an external port with an internal port.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct ExtPortExpr {
     /// Index of the internal port this expression targets.
     pub port: usize,
@@ -113,7 +121,7 @@ pub struct ExtPortExpr {
 }
 
 /// A select operation into an internal port.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub enum ExtPortSelect {
     /// Tombstone.
     Error,
@@ -324,6 +332,7 @@ pub(crate) fn canonicalize_ports<'a>(
                     "inout port `{}` must be a net; but is declared as variable",
                     port.name
                 ))
+                .code("SV0025")
                 .span(port.name.span),
             ),
             (ast::PortDir::Ref, ast::VarKind::Net { .. }) => cx.emit(
@@ -331,6 +340,7 @@ pub(crate) fn canonicalize_ports<'a>(
                     "ref port `{}` must be a variable; but is declared as net",
                     port.name
                 ))
+                .code("SV0025")
                 .span(port.name.span),
             ),
             _ => (),
@@ -419,6 +429,7 @@ fn lower_node_ports_ansi<'a>(
         };
         cx.emit(
             DiagBuilder2::error(format!("port declaration in body of ANSI-style {:#}", node))
+                .code("SV0024")
                 .span(ast.span)
                 .add_note(format!(
                     "A {:#} with an ANSI-style port list cannot have port declarations in the \
@@ -611,6 +622,7 @@ fn lower_node_ports_ansi<'a>(
             _ => {
                 cx.emit(
                     DiagBuilder2::error("non-ANSI port in ANSI port list")
+                        .code("SV0024")
                         .span(port.span())
                         .add_note("First port uses ANSI style:")
                         .span(first_span),
@@ -627,6 +639,7 @@ fn lower_node_ports_ansi<'a>(
                     "port `{}` declared multiple times",
                     port.name.unwrap().value
                 ))
+                .code("SV0025")
                 .span(port.name.unwrap().span)
                 .add_note("Previous declaration was here:")
                 .span(ext_pos[prev].name.unwrap().span),
@@ -688,6 +701,7 @@ fn lower_node_ports_nonansi<'a>(
                         "port `{}` declared multiple times",
                         data.name.value
                     ))
+                    .code("SV0025")
                     .span(data.name.span)
                     .add_note("Previous declaration was here:")
                     .span(decl_order[prev].name.span),
@@ -714,6 +728,7 @@ fn lower_node_ports_nonansi<'a>(
                                 "port variable `{}` declared multiple times",
                                 name.name
                             ))
+                            .code("SV0025")
                             .span(name.name_span)
                             .add_note("previous declaration was here:")
                             .span(prev.1.name_span),
@@ -734,6 +749,7 @@ fn lower_node_ports_nonansi<'a>(
                                 "port net `{}` declared multiple times",
                                 name.name
                             ))
+                            .code("SV0025")
                             .span(name.name_span)
                             .add_note("previous declaration was here:")
                             .span(prev.1.name_span),
@@ -763,6 +779,7 @@ fn lower_node_ports_nonansi<'a>(
                         "port `{}` is complete; additional declaration forbidden",
                         port.name
                     ))
+                    .code("SV0025")
                     .span(span)
                     .add_note(
                         "Port already has a net/variable type. Cannot declare an additional \
@@ -791,6 +808,7 @@ fn lower_node_ports_nonansi<'a>(
                                 "net port `{}` redeclared as variable",
                                 port.name
                             ))
+                            .code("SV0025")
                             .span(vd.1.span)
                             .add_note("Port declaration was here:")
                             .span(port.span),
@@ -816,6 +834,7 @@ fn lower_node_ports_nonansi<'a>(
                                 "variable port `{}` redeclared as net",
                                 port.name
                             ))
+                            .code("SV0025")
                             .span(nd.1.span)
                             .add_note("Port declaration was here:")
                             .span(port.span),
@@ -840,6 +859,7 @@ fn lower_node_ports_nonansi<'a>(
                             "port `{}` doubly declared as variable and net",
                             port.name
                         ))
+                        .code("SV0025")
                         .span(vd.1.span)
                         .span(nd.1.span)
                         .add_note("Port declaration was here:")
@@ -859,6 +879,7 @@ fn lower_node_ports_nonansi<'a>(
             (_, _) => {
                 cx.emit(
                     DiagBuilder2::error(format!("port `{}` has contradicting signs", port.name))
+                        .code("SV0025")
                         .span(port.span)
                         .span(add_span),
                 );
@@ -950,6 +971,7 @@ fn lower_node_ports_nonansi<'a>(
                                     dim.desc_full(),
                                     name
                                 ))
+                                .code("SV0025")
                                 .span(port.span()),
                             );
                             ExtPortSelect::Error
@@ -974,6 +996,7 @@ fn lower_node_ports_nonansi<'a>(
             _ => {
                 cx.emit(
                     DiagBuilder2::error("ANSI port in non-ANSI port list")
+                        .code("SV0024")
                         .span(port.span())
                         .add_note("First port uses non-ANSI style:")
                         .span(first_span),
@@ -998,6 +1021,7 @@ fn lower_node_ports_nonansi<'a>(
                             "port `{}` not declared in {:#} body",
                             expr.name, node
                         ))
+                        .code("SV0025")
                         .span(expr.name.span)
                         .add_note(format!("Declare the port inside the {:#}, e.g.:", node))
                         .add_note(format!("input {};", expr.name)),
@@ -1088,6 +1112,7 @@ fn lower_port_ref<'gcx>(
                     "invalid port expression: `{}`",
                     expr.span.extract()
                 ))
+                .code("SV0025")
                 .span(expr.span),
             );
             error!("{:?}", expr);
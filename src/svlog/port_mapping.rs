@@ -91,6 +91,7 @@ pub(crate) fn port_mapping<'a>(
                         node,
                         port_list.ext_pos.len()
                     ))
+                    .code("SV0006")
                     .span(span),
                 );
                 Err(())
@@ -105,6 +106,7 @@ pub(crate) fn port_mapping<'a>(
             None => {
                 cx.emit(
                     DiagBuilder2::error(format!("{} requires positional connections", node))
+                        .code("SV0039")
                         .span(name.span)
                         .add_note(format!(
                             "The {:#} has unnamed ports which require connecting by position.",
@@ -120,6 +122,7 @@ pub(crate) fn port_mapping<'a>(
             None => {
                 cx.emit(
                     DiagBuilder2::error(format!("no port `{}` in {}", name, node,))
+                        .code("SV0004")
                         .span(name.span)
                         .add_note(format!(
                             "Declared ports are {}",
@@ -164,6 +167,7 @@ pub(crate) fn port_mapping<'a>(
                             "unnamed ports in {} cannot be connected with wildcard `.*`",
                             node
                         ))
+                        .code("SV0039")
                         .span(inst.span),
                     );
                     return Err(());
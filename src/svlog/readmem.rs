@@ -0,0 +1,388 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Memory initialization from files with `$readmemb` and `$readmemh`.
+//!
+//! The memory load tasks read a text file of binary or hexadecimal words into
+//! an unpacked array, as described in IEEE 1800-2017 §21.4. Loads at the
+//! beginning of an `initial` procedure whose arguments are known at compile
+//! time are performed during elaboration (see the `initial` module), such that
+//! the loaded words become the initial value of the memory. All other loads are
+//! left to the simulator.
+
+use crate::{
+    common::source::{get_source_manager, Source},
+    crate_prelude::*,
+    format::{format_value, FormatKind, FormatSpec, Radix},
+    ty::{Range, RangeDir, UnpackedDim, UnpackedType},
+    value::{make_array, make_int_special, Value, ValueKind},
+};
+use bit_vec::BitVec;
+use num::{BigInt, ToPrimitive, Zero};
+use std::path::Path;
+
+/// Get the name of the memory load task that reads words in a radix.
+pub fn task_name(radix: Radix) -> &'static str {
+    match radix {
+        Radix::Bin => "$readmemb",
+        _ => "$readmemh",
+    }
+}
+
+/// Determine the address range and word type of a memory.
+///
+/// Only memories with a single unpacked dimension of simple bit vector words
+/// are supported.
+pub(crate) fn memory_shape<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    span: Span,
+) -> Result<(Range, &'a UnpackedType<'a>)> {
+    if ty.is_error() {
+        return Err(());
+    }
+    let range = match ty.outermost_dim() {
+        Some(ty::Dim::Unpacked(UnpackedDim::Range(x))) => Some(x),
+        Some(ty::Dim::Unpacked(UnpackedDim::Array(size))) => Some(Range {
+            size,
+            dir: RangeDir::Up,
+            offset: 0,
+        }),
+        _ => None,
+    };
+    let word_ty = ty.pop_dim(cx);
+    match (range, word_ty) {
+        (Some(range), Some(word_ty)) if word_ty.get_simple_bit_vector().is_some() => {
+            Ok((range, word_ty))
+        }
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("cannot load a memory of type `{}`", ty))
+                    .code("SV0044")
+                    .span(span)
+                    .add_note(
+                        "Memories must have one unpacked dimension of simple bit vector words",
+                    ),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Determine the constant file name passed to a memory load task.
+pub(crate) fn file_name<'a>(cx: &impl Context<'a>, file: &'a mir::Rvalue<'a>) -> Result<String> {
+    let value = cx.const_mir_rvalue(file.into());
+    if value.is_error() {
+        return Err(());
+    }
+    let spec = FormatSpec {
+        kind: FormatKind::String,
+        width: 0,
+        precision: 0,
+    };
+    match format_value(spec, &value) {
+        Some(x) => Ok(x),
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` is not a file name", file.span.extract()))
+                    .code("SV0044")
+                    .span(file.span),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Load the words of a memory file into the value of a memory.
+pub(crate) fn load_memory<'a>(
+    cx: &impl Context<'a>,
+    radix: Radix,
+    file: &'a mir::Rvalue<'a>,
+    init: Value<'a>,
+    start: Option<&'a mir::Rvalue<'a>>,
+    end: Option<&'a mir::Rvalue<'a>>,
+    span: Span,
+) -> Result<Value<'a>> {
+    if init.is_error() {
+        return Err(());
+    }
+    let (range, word_ty) = memory_shape(cx, init.ty, span)?;
+    let width = word_ty.get_bit_size().unwrap();
+
+    // Determine the range of addresses to load.
+    let address = |x: &'a mir::Rvalue<'a>| -> Result<isize> {
+        let value = cx.const_mir_rvalue_int(x.into())?;
+        match value.to_isize() {
+            Some(x) if x >= range.low() && x <= range.high() => Ok(x),
+            _ => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "address {} is outside the memory's address range {}:{}",
+                        value,
+                        range.low(),
+                        range.high()
+                    ))
+                    .code("SV0044")
+                    .span(x.span),
+                );
+                Err(())
+            }
+        }
+    };
+    let (from, to) = match (start, end) {
+        (Some(start), Some(end)) => (address(start)?, address(end)?),
+        (Some(start), None) => (address(start)?, range.high()),
+        _ => (range.low(), range.high()),
+    };
+    let step = if to >= from { 1 } else { -1 };
+    let in_range = |x: isize| x >= from.min(to) && x <= from.max(to);
+
+    // Parse the file and assign an address to every word.
+    let path = file_name(cx, file)?;
+    let source = open_file(cx, &path, file.span)?;
+    let items = parse_file(cx, source, radix, width)?;
+    let mut elements = match init.kind {
+        ValueKind::StructOrArray(ref x) => x.clone(),
+        _ => bug_span!(span, cx, "memory initial value is not an array"),
+    };
+    let mut next = from;
+    let mut num_words = 0;
+    let mut has_addresses = false;
+    for item in items {
+        match item {
+            Item::Address(value, span) => {
+                match value.to_isize() {
+                    Some(x) if in_range(x) => next = x,
+                    _ => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "address {} is outside the loaded address range {}:{}",
+                                value, from, to
+                            ))
+                            .code("SV0044")
+                            .span(span),
+                        );
+                        return Err(());
+                    }
+                }
+                has_addresses = true;
+            }
+            Item::Word(value, special_bits, x_bits, span) => {
+                if !in_range(next) {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "memory file has more words than the loaded address range {}:{}",
+                            from, to
+                        ))
+                        .code("SV0044")
+                        .span(span),
+                    );
+                    return Err(());
+                }
+                let index = (next - range.offset) as usize;
+                elements[index] =
+                    cx.intern_value(make_int_special(word_ty, value, special_bits, x_bits));
+                next += step;
+                num_words += 1;
+            }
+        }
+    }
+
+    // Warn if an explicitly specified range was not filled completely.
+    let expected = (to - from).abs() as usize + 1;
+    if end.is_some() && !has_addresses && num_words != expected {
+        cx.emit(
+            DiagBuilder2::warning(format!(
+                "memory file `{}` has {} words, but the loaded address range {}:{} has {}",
+                path, num_words, from, to, expected
+            ))
+            .group("readmem")
+            .span(span),
+        );
+    }
+    Ok(cx.intern_value(make_array(init.ty, elements)))
+}
+
+/// Locate and open a memory file.
+///
+/// Relative paths are first resolved against the current working directory,
+/// and then against the directory of the source file that loads the memory.
+fn open_file<'a>(cx: &impl Context<'a>, path: &str, span: Span) -> Result<Source> {
+    let mut candidates = vec![Path::new(path).to_path_buf()];
+    if let Some(dir) = Path::new(&*span.source.get_path()).parent() {
+        candidates.push(dir.join(path));
+    }
+    for candidate in candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        let candidate = candidate.to_string_lossy();
+        if let Some(source) = get_source_manager().open(&candidate) {
+            return Ok(source);
+        }
+    }
+    cx.emit(
+        DiagBuilder2::error(format!("memory file `{}` not found", path))
+            .code("SV0044")
+            .span(span),
+    );
+    Err(())
+}
+
+/// An item in a memory file.
+enum Item {
+    /// An address specification `@hh...`, which determines the address of the
+    /// next word.
+    Address(BigInt, Span),
+    /// A word, with its value, special bits (x or z), and x bits.
+    Word(BigInt, BitVec, BitVec, Span),
+}
+
+/// Parse the addresses and words in a memory file.
+///
+/// Words are separated by white space and comments, and consist of binary or
+/// hexadecimal digits, including `x` and `z` digits and `_` separators.
+/// Addresses are always given in hexadecimal.
+fn parse_file<'a>(
+    cx: &impl Context<'a>,
+    source: Source,
+    radix: Radix,
+    width: usize,
+) -> Result<Vec<Item>> {
+    let content = source.get_content();
+    let chars: Vec<(usize, char)> = content.iter().collect();
+    let end_offset = chars.last().map(|&(i, c)| i + c.len_utf8()).unwrap_or(0);
+    let offset = |i: usize| chars.get(i).map(|&(x, _)| x).unwrap_or(end_offset);
+
+    let mut items = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+
+        // Skip white space and comments.
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i].1 != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            let begin = i;
+            i += 2;
+            while i < chars.len()
+                && !(chars[i].1 == '*' && chars.get(i + 1).map(|x| x.1) == Some('/'))
+            {
+                i += 1;
+            }
+            if i >= chars.len() {
+                cx.emit(
+                    DiagBuilder2::error("unterminated comment in memory file")
+                        .code("SV0044")
+                        .span(Span::new(source, offset(begin), offset(begin + 2))),
+                );
+                return Err(());
+            }
+            i += 2;
+            continue;
+        }
+
+        // Consume the characters of an address or word.
+        let begin = i;
+        while i < chars.len() && !chars[i].1.is_whitespace() && chars[i].1 != '/' {
+            i += 1;
+        }
+        let span = Span::new(source, offset(begin), offset(i));
+        let is_address = c == '@';
+        let digits = &chars[if is_address { begin + 1 } else { begin }..i];
+        let (radix, width) = if is_address {
+            (Radix::Hex, None)
+        } else {
+            (radix, Some(width))
+        };
+        let digit_bits = radix.digit_bits().unwrap();
+        let mut value = BigInt::zero();
+        let mut special = vec![];
+        let mut x = vec![];
+        for &(pos, digit) in digits {
+            if digit == '_' {
+                continue;
+            }
+            let (v, s, is_x) = match digit {
+                'x' | 'X' if !is_address => (0, true, true),
+                'z' | 'Z' | '?' if !is_address => (0, true, false),
+                _ => match digit.to_digit(1 << digit_bits) {
+                    Some(v) => (v, false, false),
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is not a valid {} digit",
+                                digit,
+                                match radix {
+                                    Radix::Bin => "binary",
+                                    _ => "hexadecimal",
+                                }
+                            ))
+                            .code("SV0044")
+                            .span(Span::new(
+                                source,
+                                pos,
+                                pos + digit.len_utf8(),
+                            )),
+                        );
+                        return Err(());
+                    }
+                },
+            };
+            value = (value << digit_bits) | BigInt::from(v);
+            for _ in 0..digit_bits {
+                special.push(s);
+                x.push(is_x);
+            }
+        }
+        if special.is_empty() {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` is not a valid {}",
+                    span.extract(),
+                    if is_address { "address" } else { "word" }
+                ))
+                .code("SV0044")
+                .span(span),
+            );
+            return Err(());
+        }
+        let width = match width {
+            Some(x) => x,
+            None => {
+                items.push(Item::Address(value, span));
+                continue;
+            }
+        };
+
+        // Make sure the word fits into the memory. Excess x and z digits are
+        // truncated silently.
+        if value.bits() as usize > width {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "word `{}` does not fit into {} bits",
+                    span.extract(),
+                    width
+                ))
+                .code("SV0044")
+                .span(span),
+            );
+            return Err(());
+        }
+        let lsb_first = |bits: &[bool]| -> BitVec {
+            (0..width)
+                .map(|i| bits.len() > i && bits[bits.len() - 1 - i])
+                .collect()
+        };
+        items.push(Item::Word(value, lsb_first(&special), lsb_first(&x), span));
+    }
+    Ok(items)
+}
@@ -9,8 +9,9 @@ use crate::crate_prelude::*;
 use crate::{
     ast::AnyNode,
     ast_map::AstNode,
-    common::{SessionContext, Verbosity},
+    common::{design_units::DesignUnitKind, util::find_similar, SessionContext, Verbosity},
     hir::HirNode,
+    implicit_nets::{find_implicit_net, note_disallowed_net},
     port_list::{self, AsPortedNode},
     ParamEnv,
 };
@@ -124,10 +125,14 @@ pub(crate) fn local_rib<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<&'
             let pkg_id = match cx.gcx().find_package(import.pkg.value) {
                 Some(id) => id,
                 None => {
-                    cx.emit(
-                        DiagBuilder2::error(format!("`{}` not found", import.pkg.value))
-                            .span(import.pkg.span),
-                    );
+                    let mut d = DiagBuilder2::error(format!("`{}` not found", import.pkg.value))
+                        .code("SV0001")
+                        .span(import.pkg.span);
+                    let names = cx.gcx().packages().map(|(name, _)| name.to_string());
+                    if let Some(similar) = find_similar(&import.pkg.value.as_str(), names) {
+                        d = d.add_note(format!("did you mean `{}`?", similar));
+                    }
+                    cx.emit(d);
                     return Err(());
                 }
             };
@@ -295,11 +300,24 @@ pub(crate) fn resolve_downwards<'a>(
 }
 
 /// Resolve a node to its target.
+///
+/// Marks the target as referenced.
 #[moore_derive::query]
 pub(crate) fn resolve_node<'a>(
     cx: &impl Context<'a>,
     node_id: NodeId,
     env: ParamEnv,
+) -> Result<NodeId> {
+    let target = resolve_node_target(cx, node_id, env)?;
+    cx.mark_referenced(target);
+    Ok(target)
+}
+
+/// Resolve a node to its target, without marking it as referenced.
+fn resolve_node_target<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    env: ParamEnv,
 ) -> Result<NodeId> {
     let hir = cx.hir_of(node_id)?;
     match hir {
@@ -307,6 +325,12 @@ pub(crate) fn resolve_node<'a>(
             hir::ExprKind::Ident(ident) => return cx.resolve_upwards_or_error(ident, node_id),
             hir::ExprKind::Scope(scope_id, name) => {
                 let within = cx.resolve_node(scope_id, env)?;
+                if let AstNode::GenBlock(block) = cx.ast_of(within)? {
+                    check_generate_block_active(cx, block, expr.ast.span, env)?;
+                    return cx
+                        .resolve_hierarchical_or_error(name, block)
+                        .map(|def| def.node.id());
+                }
                 return cx.resolve_downwards_or_error(name, within);
             }
             _ => (),
@@ -334,6 +358,62 @@ pub(crate) fn resolve_node<'a>(
     Err(())
 }
 
+/// Ensure that a generate block referred to by a hierarchical name is
+/// actually elaborated under a parameter environment.
+fn check_generate_block_active<'a>(
+    cx: &impl Context<'a>,
+    block: &'a ast::GenerateBlock<'a>,
+    span: Span,
+    env: ParamEnv,
+) -> Result<()> {
+    let gen = match block.get_parent().map(|p| p.as_all()) {
+        Some(ast::AllNode::GenerateIf(gen)) => gen,
+        Some(ast::AllNode::GenerateFor(_)) => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "hierarchical references into for-generate block `{}` are not supported",
+                    block.label.unwrap()
+                ))
+                .code("SV0045")
+                .span(span),
+            );
+            return Err(());
+        }
+        _ => return Ok(()),
+    };
+    let (cond, main_body, else_body) = match cx.hir_of(gen.id())? {
+        HirNode::Gen(hir::Gen {
+            kind:
+                hir::GenKind::If {
+                    cond,
+                    main_body,
+                    else_body,
+                },
+            ..
+        }) => (*cond, *main_body, *else_body),
+        _ => unreachable!(),
+    };
+    let taken = if cx.constant_value_of(cond, env).is_false() {
+        else_body
+    } else {
+        Some(main_body)
+    };
+    if taken == Some(block.id()) {
+        return Ok(());
+    }
+    cx.emit(
+        DiagBuilder2::error(format!(
+            "generate block `{}` is not elaborated",
+            block.label.unwrap()
+        ))
+        .code("SV0042")
+        .span(span)
+        .add_note("The condition of the if-generate statement selects a different block:")
+        .span(gen.cond.span()),
+    );
+    Err(())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructDef {
     pub packed: bool,
@@ -359,6 +439,7 @@ pub(crate) fn struct_def<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<A
         _ => {
             cx.emit(
                 DiagBuilder2::error(format!("{} is not a struct", hir.desc_full()))
+                    .code("SV0036")
                     .span(hir.human_span()),
             );
             return Err(());
@@ -405,6 +486,7 @@ pub(crate) fn resolve_field_access<'a>(
         strukt
     } else {
         let mut d = DiagBuilder2::error(format!("value of type `{}` is not a struct", ty))
+            .code("SV0036")
             .span(hir.human_span());
         if ty.resolve_full() != ty {
             d = d.add_note(format!("`{}` is defined as `{}`", ty, ty.resolve_full()));
@@ -422,6 +504,7 @@ pub(crate) fn resolve_field_access<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("value of type `{}` has no field `{}`", ty, name))
+                    .code("SV0011")
                     .span(name.span())
                     .add_note(format!("`{}` was defined here:", ty))
                     .span(strukt.ast.span()),
@@ -739,6 +822,19 @@ impl<'a, 'c, C: Context<'a>> ScopeGenerator<'a, 'c, C> {
         self.scope.wildcard_imports.push(node);
     }
 
+    /// Make the label of a generate block visible in the enclosing scope.
+    fn add_generate_block_label(&mut self, node: &'a ast::GenerateBlock<'a>) {
+        if let Some(name) = node.label {
+            self.add_def(Def {
+                node: DefNode::Ast(node),
+                name,
+                vis: DefVis::LOCAL | DefVis::HIERARCHICAL,
+                may_override: false,
+                ordered: false,
+            });
+        }
+    }
+
     /// Register a definition.
     pub fn add_def(&mut self, mut def: Def<'a>) {
         trace!(" - Adding definition {:?}", def);
@@ -772,9 +868,12 @@ impl<'a, 'c, C: Context<'a>> ScopeGenerator<'a, 'c, C> {
 
             if !def.may_override {
                 let d = DiagBuilder2::error(format!("`{}` is defined multiple times", def.name))
-                    .span(def.name.span)
-                    .add_note(format!("Previous definition of `{}` was here:", def.name))
-                    .span(existing.name.span);
+                    .code("SV0002")
+                    .span_label(def.name.span, "redefined here")
+                    .span_label(
+                        existing.name.span,
+                        format!("previous definition of `{}` here", def.name),
+                    );
                 self.cx.emit(d);
                 return;
             }
@@ -996,11 +1095,19 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
 
     fn pre_visit_generate_for(&mut self, node: &'a ast::GenerateFor<'a>) -> bool {
         self.add_subscope(node);
+        self.add_generate_block_label(&node.block);
         false
     }
 
     fn pre_visit_generate_if(&mut self, node: &'a ast::GenerateIf<'a>) -> bool {
         self.add_subscope(node);
+        self.add_generate_block_label(&node.main_block);
+        if let Some(ref else_block) = node.else_block {
+            // Alternative blocks may share the same label.
+            if else_block.label.map(|l| l.value) != node.main_block.label.map(|l| l.value) {
+                self.add_generate_block_label(else_block);
+            }
+        }
         false
     }
 
@@ -1011,15 +1118,7 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
 
     fn pre_visit_generate_block(&mut self, node: &'a ast::GenerateBlock<'a>) -> bool {
         self.add_subscope(node);
-        if let Some(name) = node.label {
-            self.add_def(Def {
-                node: DefNode::Ast(node),
-                name,
-                vis: DefVis::LOCAL | DefVis::HIERARCHICAL,
-                may_override: false,
-                ordered: false,
-            });
-        }
+        self.add_generate_block_label(node);
         false
     }
 
@@ -1208,12 +1307,70 @@ pub(crate) fn resolve_local_or_error<'a>(
             Ok(def)
         }
         None => {
-            cx.emit(DiagBuilder2::error(format!("`{}` not found", name.value)).span(name.span));
+            let net = find_implicit_net(cx, name.value, at.scope.as_any(), at.order);
+            if let Some(def) = net.and_then(|net| net.def.as_ref()) {
+                return Ok(def);
+            }
+            let mut d = DiagBuilder2::error(format!("`{}` not found", name.value))
+                .code("SV0001")
+                .span(name.span);
+            let names = local_names(cx, at, skip_imports)?;
+            if let Some(similar) = find_similar(&name.value.as_str(), names) {
+                d = d.add_note(format!("did you mean `{}`?", similar));
+            }
+            cx.emit(note_disallowed_net(net, d));
             Err(())
         }
     }
 }
 
+/// Determine the names visible to local name resolution at a location.
+///
+/// Used to suggest similar names if a name cannot be resolved.
+fn local_names<'a>(
+    cx: &impl Context<'a>,
+    at: ScopeLocation<'a>,
+    skip_imports: bool,
+) -> Result<Vec<String>> {
+    let mut names = vec![];
+    let mut next = Some(cx.generated_scope(at.scope));
+    while let Some(scope) = next {
+        next = scope.parent.map(|p| cx.generated_scope(p));
+        for def in scope.defs.values() {
+            if def.vis.contains(DefVis::LOCAL) && (!def.ordered || def.node.order() < at.order) {
+                names.push(def.name.value.to_string());
+            }
+        }
+        if skip_imports {
+            continue;
+        }
+        for &import in &scope.wildcard_imports {
+            if import.order() > at.order {
+                continue;
+            }
+            let inside = cx.resolve_imported_scope(import)?;
+            names.extend(scope_names(cx, inside, DefVis::NAMESPACE));
+        }
+    }
+    Ok(names)
+}
+
+/// Determine the names defined in a scope with a visibility.
+///
+/// Used to suggest similar names if a name cannot be resolved.
+fn scope_names<'a>(
+    cx: &impl Context<'a>,
+    inside: &'a dyn ScopedNode<'a>,
+    vis: DefVis,
+) -> Vec<String> {
+    cx.generated_scope(inside)
+        .defs
+        .values()
+        .filter(|def| def.vis.contains(vis))
+        .map(|def| def.name.value.to_string())
+        .collect()
+}
+
 /// Resolve a name in a scope as a namespace lookup.
 ///
 /// This checks if the scope contains a definition with visibility
@@ -1257,10 +1414,15 @@ pub(crate) fn resolve_namespace_or_error<'a>(
             Ok(def)
         }
         None => {
+            let mut d = DiagBuilder2::error(format!("`{}` not found in {}", name.value, inside))
+                .code("SV0001")
+                .span(name.span);
+            let names = scope_names(cx, inside, DefVis::NAMESPACE);
+            if let Some(similar) = find_similar(&name.value.as_str(), names) {
+                d = d.add_note(format!("did you mean `{}`?", similar));
+            }
             cx.emit(
-                DiagBuilder2::error(format!("`{}` not found in {}", name.value, inside))
-                    .span(name.span)
-                    .add_note(format!("{} was defined here:", inside))
+                d.add_note(format!("{} was defined here:", inside))
                     .span(inside.human_span()),
             );
             Err(())
@@ -1311,10 +1473,15 @@ pub(crate) fn resolve_hierarchical_or_error<'a>(
             Ok(def)
         }
         None => {
+            let mut d = DiagBuilder2::error(format!("`{}` not found in {}", name.value, inside))
+                .code("SV0001")
+                .span(name.span);
+            let names = scope_names(cx, inside, DefVis::HIERARCHICAL);
+            if let Some(similar) = find_similar(&name.value.as_str(), names) {
+                d = d.add_note(format!("did you mean `{}`?", similar));
+            }
             cx.emit(
-                DiagBuilder2::error(format!("`{}` not found in {}", name.value, inside))
-                    .span(name.span)
-                    .add_note(format!("{} was defined here:", inside))
+                d.add_note(format!("{} was defined here:", inside))
                     .span(inside.human_span()),
             );
             Err(())
@@ -1322,6 +1489,31 @@ pub(crate) fn resolve_hierarchical_or_error<'a>(
     }
 }
 
+/// Resolve the name of a modport within an interface.
+///
+/// Emits a diagnostic if the name does not refer to a modport of the
+/// interface.
+#[moore_derive::query]
+pub(crate) fn resolve_modport<'a>(
+    cx: &impl Context<'a>,
+    name: Spanned<Name>,
+    intf: &'a ast::Interface<'a>,
+) -> Result<&'a hir::Modport<'a>> {
+    let hir = cx.hir_of_interface(intf)?;
+    if let Some(modport) = hir.find_modport(name.value) {
+        return Ok(modport);
+    }
+    let def = cx.resolve_hierarchical_or_error(name, intf)?;
+    cx.emit(
+        DiagBuilder2::error(format!("`{}` is not a modport of {}", name, intf))
+            .code("SV0029")
+            .span(name.span)
+            .add_note(format!("`{}` was defined here:", name))
+            .span(def.name.span),
+    );
+    Err(())
+}
+
 /// Resolve an import to the scope it imports.
 ///
 /// This function emits a diagnostic if the target of the import has no scope.
@@ -1347,6 +1539,7 @@ pub(crate) fn resolve_imported_scope<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("name `{}` does not refer to a package", node.pkg))
+                    .code("SV0029")
                     .span(node.pkg.span),
             );
             Err(())
@@ -1487,8 +1680,38 @@ pub(crate) fn resolve_inst_target<'a>(
     cx: &impl Context<'a>,
     inst: &'a ast::Inst<'a>,
 ) -> Result<InstTarget<'a>> {
-    // Resolve the name of the instantiated module.
+    // Resolve the name of the instantiated module. Unknown modules are looked
+    // up in the libraries and among the design units declared in other
+    // languages, or replaced with a blackbox if requested.
     let loc = cx.scope_location(inst);
+    if cx.resolve_local(inst.target.value, loc, false)?.is_none() {
+        if let Some(module) = crate::library::library_module(cx, inst.target)? {
+            return Ok(InstTarget::Module(module));
+        }
+        if let Some(module) = crate::foreign::foreign_module(cx, inst.target)? {
+            return Ok(InstTarget::Module(module));
+        }
+        if cx.sess().opts.allow_blackbox {
+            return crate::blackbox::blackbox_module(cx, inst).map(InstTarget::Module);
+        }
+        let mut d = DiagBuilder2::error(format!("`{}` not found", inst.target.value))
+            .code("SV0001")
+            .span(inst.target.span);
+        let units = &cx.sess().units;
+        let names = units
+            .units_in(units.work())
+            .into_iter()
+            .filter(|unit| match unit.kind {
+                DesignUnitKind::Module | DesignUnitKind::Interface | DesignUnitKind::Entity => true,
+                _ => false,
+            })
+            .map(|unit| unit.name.to_string());
+        if let Some(similar) = find_similar(&inst.target.value.as_str(), names) {
+            d = d.add_note(format!("did you mean `{}`?", similar));
+        }
+        cx.emit(d);
+        return Err(());
+    }
     let def = cx.resolve_local_or_error(inst.target, loc, false)?;
     trace!("Resolved instance `{}` to {:?}", inst.target, def);
 
@@ -1506,6 +1729,7 @@ pub(crate) fn resolve_inst_target<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("`{}` is not a module or interface", inst.target))
+                    .code("SV0015")
                     .span(inst.target.span)
                     .add_note(format!("{} was declared here:", def.node))
                     .span(def.node.span()),
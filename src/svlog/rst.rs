@@ -85,6 +85,7 @@ pub(crate) fn disamb_type_or_expr<'a>(
                         None => {
                             cx.emit(
                                 DiagBuilder2::error(format!("`{}` is not a package", pkg_name))
+                                    .code("SV0029")
                                     .span(pkg_name.span)
                                     .add_note(format!("`{}` was declared here:", pkg_name))
                                     .span(def.node.span()),
@@ -137,11 +138,80 @@ pub(crate) fn disamb_type_or_expr<'a>(
                             "`{}` is not a package",
                             expr.span().extract()
                         ))
+                        .code("SV0029")
                         .span(expr.span()),
                     );
                     return Err(());
                 }
             },
+
+            // Something like `foo_t [7:0]` is parsed as an index expression,
+            // but is actually a type with packed dimensions if the indexee
+            // turns out to be a type.
+            ast::IndexExpr { .. } => {
+                // Peel off the indices, innermost dimension first.
+                let mut base = expr;
+                let mut indices = vec![];
+                while let ast::IndexExpr {
+                    ref indexee,
+                    ref index,
+                } = base.data
+                {
+                    indices.push(index.as_ref());
+                    base = indexee.as_ref();
+                }
+
+                // Only proceed if the indexee is a type.
+                let base_ty = match *cx
+                    .disamb_type_or_expr(Ref(cx.arena().alloc(ast::TypeOrExpr::Expr(base))))?
+                {
+                    ast::TypeOrExpr::Type(ty) => ty,
+                    ast::TypeOrExpr::Expr(_) => return Ok(ast),
+                };
+
+                // Convert the indices into packed dimensions.
+                let mut dims = base_ty.dims.clone();
+                for index in indices.into_iter().rev() {
+                    match index.data {
+                        ast::RangeExpr {
+                            mode: ast::RangeMode::Absolute,
+                            ref lhs,
+                            ref rhs,
+                        } => dims.push(ast::TypeDim::Range(
+                            lhs.as_ref().clone(),
+                            rhs.as_ref().clone(),
+                        )),
+                        _ => {
+                            cx.emit(
+                                DiagBuilder2::error(format!(
+                                    "`[{}]` is not a valid packed dimension",
+                                    index.span().extract()
+                                ))
+                                .code("SV0035")
+                                .span(index.span())
+                                .add_note(format!(
+                                    "`{}` is a type; packed dimensions must be of the form \
+                                     `[a:b]`",
+                                    base.span().extract()
+                                )),
+                            );
+                            return Err(());
+                        }
+                    }
+                }
+                let ty = cx.arena().alloc(ast::Type::new(
+                    expr.span,
+                    ast::TypeData {
+                        kind: base_ty.kind.clone(),
+                        sign: base_ty.sign,
+                        dims,
+                    },
+                ));
+                ty.link_attach(expr, expr.order());
+                cx.register_ast(ty);
+                cx.map_ast_with_parent(AstNode::Type(ty), ty.id());
+                Ok(cx.arena().alloc(ast::TypeOrExpr::Type(ty)))
+            }
             _ => Ok(ast),
         },
         ast::TypeOrExpr::Type(_ty) => Ok(ast),
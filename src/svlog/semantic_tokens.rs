@@ -0,0 +1,498 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Classified tokens of the input, for syntax highlighting in editors.
+//!
+//! Keywords, numbers, strings, comments, and macro uses are found by scanning
+//! the source text, such that they are classified even inside macro bodies and
+//! inactive `ifdef` regions. Identifiers are classified by the parsed and
+//! resolved AST instead, which tells a parameter from a signal, a typedef from
+//! a module, and a macro definition from its uses. The tokens are written as
+//! JSON in the semantic tokens model of the Language Server Protocol: a legend
+//! of the token types and modifiers, and for every file the relative encoding
+//! of the tokens, next to their byte ranges.
+
+use crate::{
+    ast::AcceptVisitor as _,
+    common::source::Source,
+    crate_prelude::*,
+    token::find_keyword,
+    xref::{Xref, XrefKind},
+};
+use std::collections::HashMap;
+use std::io::{Result as IoResult, Write};
+
+/// The classified tokens of a list of files.
+#[derive(Debug)]
+pub struct SemanticTokens {
+    /// The files, in the order they were passed in.
+    pub files: Vec<FileTokens>,
+}
+
+/// The classified tokens of a file.
+#[derive(Debug)]
+pub struct FileTokens {
+    /// The file.
+    pub source: Source,
+    /// The tokens, ordered by their position and not overlapping.
+    pub tokens: Vec<SemanticToken>,
+}
+
+/// A classified token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The byte offset of the first character.
+    pub begin: usize,
+    /// The byte offset after the last character.
+    pub end: usize,
+    /// What the token denotes.
+    pub ty: TokenType,
+    /// Whether the token declares the name, rather than referencing it.
+    pub declaration: bool,
+}
+
+/// The types of tokens, in the order of the legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenType {
+    /// A keyword or compiler directive.
+    Keyword,
+    /// A built-in or user-defined type.
+    Type,
+    /// A text macro.
+    Macro,
+    /// A parameter.
+    Parameter,
+    /// A module.
+    Module,
+    /// An interface.
+    Interface,
+    /// A package.
+    Package,
+    /// The name of a module or interface instance.
+    Instance,
+    /// A port of a module or interface.
+    Port,
+    /// A net or variable.
+    Signal,
+    /// A task or function.
+    Function,
+    /// A number.
+    Number,
+    /// A string.
+    String,
+    /// A comment.
+    Comment,
+}
+
+/// The token types, in the order of the `TokenType` variants.
+pub const TOKEN_TYPES: &[&str] = &[
+    "keyword",
+    "type",
+    "macro",
+    "parameter",
+    "module",
+    "interface",
+    "package",
+    "instance",
+    "port",
+    "signal",
+    "function",
+    "number",
+    "string",
+    "comment",
+];
+
+/// The token modifiers. Bit 0 marks declarations.
+pub const TOKEN_MODIFIERS: &[&str] = &["declaration"];
+
+/// The keywords that name built-in types.
+const TYPE_KEYWORDS: &[&str] = &[
+    "bit",
+    "logic",
+    "reg",
+    "byte",
+    "shortint",
+    "int",
+    "longint",
+    "integer",
+    "time",
+    "real",
+    "shortreal",
+    "realtime",
+    "string",
+    "chandle",
+    "event",
+    "void",
+];
+
+/// The compiler directives, which are classified as keywords rather than
+/// macros.
+const DIRECTIVES: &[&str] = &[
+    "begin_keywords",
+    "celldefine",
+    "default_nettype",
+    "define",
+    "else",
+    "elsif",
+    "end_keywords",
+    "endcelldefine",
+    "endif",
+    "ifdef",
+    "ifndef",
+    "include",
+    "line",
+    "nounconnected_drive",
+    "pragma",
+    "resetall",
+    "timescale",
+    "unconnected_drive",
+    "undef",
+    "undefineall",
+];
+
+impl TokenType {
+    /// The name of the token type in the legend.
+    pub fn as_str(self) -> &'static str {
+        TOKEN_TYPES[self as usize]
+    }
+}
+
+impl SemanticTokens {
+    /// Classify the tokens of a list of files.
+    pub fn new<'a>(cx: &impl Context<'a>, sources: impl IntoIterator<Item = Source>) -> Self {
+        // Classify the names in the AST once for all files.
+        let mut names: HashMap<Source, Vec<SemanticToken>> = HashMap::new();
+        for entry in Xref::new(cx).entries {
+            names
+                .entry(entry.span.source)
+                .or_default()
+                .push(SemanticToken {
+                    begin: entry.span.begin,
+                    end: entry.span.end,
+                    ty: xref_type(entry.kind),
+                    declaration: entry.def,
+                });
+        }
+        let mut collector = Collector { names: &mut names };
+        for root in cx.gcx().roots() {
+            root.accept(&mut collector);
+        }
+
+        // Merge the names with the tokens of the source text. Names take
+        // precedence over overlapping tokens.
+        let files = sources
+            .into_iter()
+            .map(|source| {
+                let content = source.get_content();
+                let text = content.extract(0, content.bytes().len());
+                let mut tokens = names.remove(&source).unwrap_or_default();
+                let num_names = tokens.len();
+                tokens.extend(scan(&text));
+                let mut order: Vec<usize> = (0..tokens.len()).collect();
+                order.sort_by_key(|&i| (tokens[i].begin, i >= num_names));
+                let mut merged: Vec<SemanticToken> = vec![];
+                for i in order {
+                    let token = tokens[i];
+                    if token.begin >= merged.last().map(|t| t.end).unwrap_or(0) {
+                        merged.push(token);
+                    }
+                }
+                FileTokens {
+                    source,
+                    tokens: merged,
+                }
+            })
+            .collect();
+        SemanticTokens { files }
+    }
+
+    /// Write the tokens as JSON.
+    ///
+    /// Every file lists its tokens with their byte ranges, and the tokens in
+    /// the relative encoding of the Language Server Protocol as `data`. Tokens
+    /// spanning multiple lines are split at the line breaks in `data`, and
+    /// columns are counted in UTF-16 code units.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        writeln!(w, "{{")?;
+        writeln!(
+            w,
+            "  \"legend\": {{\"tokenTypes\": {}, \"tokenModifiers\": {}}},",
+            serde_json::to_string(TOKEN_TYPES)?,
+            serde_json::to_string(TOKEN_MODIFIERS)?
+        )?;
+        write!(w, "  \"files\": [")?;
+        for (i, file) in self.files.iter().enumerate() {
+            if i > 0 {
+                write!(w, ",")?;
+            }
+            writeln!(w)?;
+            writeln!(
+                w,
+                "    {{\"file\": {},",
+                serde_json::to_string(&*file.source.get_path())?
+            )?;
+            write!(w, "     \"tokens\": [")?;
+            for (j, token) in file.tokens.iter().enumerate() {
+                if j > 0 {
+                    write!(w, ",")?;
+                }
+                write!(
+                    w,
+                    "\n      {{\"begin\": {}, \"end\": {}, \"type\": \"{}\", \"modifiers\": [{}]}}",
+                    token.begin,
+                    token.end,
+                    token.ty.as_str(),
+                    if token.declaration {
+                        "\"declaration\""
+                    } else {
+                        ""
+                    }
+                )?;
+            }
+            writeln!(w, "],")?;
+            write!(w, "     \"data\": [")?;
+            for (j, entry) in file.encode().iter().enumerate() {
+                if j > 0 {
+                    write!(w, ",")?;
+                }
+                write!(
+                    w,
+                    "\n      {}, {}, {}, {}, {}",
+                    entry[0], entry[1], entry[2], entry[3], entry[4]
+                )?;
+            }
+            write!(w, "]}}")?;
+        }
+        writeln!(w, "\n  ]")?;
+        writeln!(w, "}}")
+    }
+}
+
+impl FileTokens {
+    /// Encode the tokens in the relative format of the Language Server
+    /// Protocol.
+    ///
+    /// Every token becomes the line relative to the previous token, the column
+    /// relative to the previous token if on the same line, the length, the
+    /// index of the type in the legend, and the modifier bits.
+    pub fn encode(&self) -> Vec<[usize; 5]> {
+        let content = self.source.get_content();
+        let text = content.extract(0, content.bytes().len());
+        let mut data = vec![];
+        let mut line = 0;
+        let mut line_start = 0;
+        let mut offset = 0;
+        let (mut prev_line, mut prev_column) = (0, 0);
+        for token in &self.tokens {
+            let mut begin = token.begin;
+            while begin < token.end {
+                // Advance to the line the segment starts on.
+                while let Some(i) = text[offset..begin].find('\n') {
+                    offset += i + 1;
+                    line += 1;
+                    line_start = offset;
+                }
+                offset = begin;
+                let end = text[begin..token.end]
+                    .find('\n')
+                    .map(|i| begin + i)
+                    .unwrap_or(token.end);
+                let length = utf16_len(text[begin..end].trim_end_matches('\r'));
+                if length > 0 {
+                    let column = utf16_len(&text[line_start..begin]);
+                    data.push([
+                        line - prev_line,
+                        if line == prev_line {
+                            column - prev_column
+                        } else {
+                            column
+                        },
+                        length,
+                        token.ty as usize,
+                        token.declaration as usize,
+                    ]);
+                    prev_line = line;
+                    prev_column = column;
+                }
+                begin = if end < token.end { end + 1 } else { end };
+            }
+        }
+        data
+    }
+}
+
+/// Count the UTF-16 code units of a string.
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Map the kind of a name in the cross-reference index to a token type.
+fn xref_type(kind: XrefKind) -> TokenType {
+    match kind {
+        XrefKind::Module => TokenType::Module,
+        XrefKind::Interface => TokenType::Interface,
+        XrefKind::Package => TokenType::Package,
+        XrefKind::Instance => TokenType::Instance,
+        XrefKind::Port => TokenType::Port,
+        XrefKind::Signal => TokenType::Signal,
+        XrefKind::Param => TokenType::Parameter,
+        XrefKind::Macro => TokenType::Macro,
+    }
+}
+
+/// A visitor that classifies the type and subroutine names the
+/// cross-reference index does not cover.
+struct Collector<'n> {
+    names: &'n mut HashMap<Source, Vec<SemanticToken>>,
+}
+
+impl Collector<'_> {
+    fn add(&mut self, span: Span, ty: TokenType, declaration: bool) {
+        self.names
+            .entry(span.source)
+            .or_default()
+            .push(SemanticToken {
+                begin: span.begin,
+                end: span.end,
+                ty,
+                declaration,
+            });
+    }
+}
+
+impl<'a> ast::Visitor<'a> for Collector<'_> {
+    fn pre_visit_typedef(&mut self, node: &'a ast::Typedef<'a>) -> bool {
+        self.add(node.name.span, TokenType::Type, true);
+        true
+    }
+
+    fn pre_visit_type_kind(&mut self, node: &'a ast::TypeKind<'a>) -> bool {
+        match node.data {
+            ast::NamedType(name) => self.add(name.span, TokenType::Type, false),
+            ast::ScopedType {
+                ref ty,
+                member: false,
+                name,
+            } => {
+                if let ast::NamedType(pkg) = ty.kind.data {
+                    self.add(pkg.span, TokenType::Package, false);
+                }
+                self.add(name.span, TokenType::Type, false);
+                return false;
+            }
+            _ => (),
+        }
+        true
+    }
+
+    fn pre_visit_subroutine_prototype(&mut self, node: &'a ast::SubroutinePrototype<'a>) -> bool {
+        self.add(node.name.span, TokenType::Function, true);
+        true
+    }
+
+    fn pre_visit_expr(&mut self, node: &'a ast::Expr<'a>) -> bool {
+        if let ast::CallExpr(ref callee, _) = node.data {
+            match callee.data {
+                ast::IdentExpr(name) => self.add(name.span, TokenType::Function, false),
+                ast::ScopeExpr(_, name) => self.add(name.span, TokenType::Function, false),
+                _ => (),
+            }
+        }
+        true
+    }
+}
+
+/// Find the keywords, numbers, strings, comments, and macro uses in a source
+/// text.
+fn scan(text: &str) -> Vec<SemanticToken> {
+    let bytes = text.as_bytes();
+    let len = bytes.len();
+    let is_word = |c: u8| c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80;
+    let is_base = |c: u8| b"bBoOdDhH".contains(&c);
+    let is_digit = |c: u8| c.is_ascii_hexdigit() || b"xXzZ?_".contains(&c);
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < len {
+        let begin = i;
+        let c = bytes[i];
+        let next = bytes.get(i + 1).cloned().unwrap_or(0);
+        let ty = if c == b'/' && next == b'/' {
+            i = text[i..].find('\n').map(|x| i + x).unwrap_or(len);
+            Some(TokenType::Comment)
+        } else if c == b'/' && next == b'*' {
+            i = text[i + 2..].find("*/").map(|x| i + x + 4).unwrap_or(len);
+            Some(TokenType::Comment)
+        } else if c == b'"' {
+            i += 1;
+            while i < len && bytes[i] != b'"' && bytes[i] != b'\n' {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += text[i..].chars().next().map(char::len_utf8).unwrap_or(0);
+            }
+            i = (i + 1).min(len);
+            Some(TokenType::String)
+        } else if c == b'`' && is_word(next) {
+            i += 1;
+            while i < len && is_word(bytes[i]) {
+                i += 1;
+            }
+            if DIRECTIVES.contains(&&text[begin + 1..i]) {
+                Some(TokenType::Keyword)
+            } else {
+                Some(TokenType::Macro)
+            }
+        } else if c == b'\\' {
+            // Escaped identifiers extend up to the next whitespace.
+            while i < len && !bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            None
+        } else if c.is_ascii_digit() || (c == b'\'' && (is_base(next) || next == b's')) {
+            // Consume the size or the integral part of a real, followed by a
+            // base and digits, as in `8'hff`.
+            while i < len && (is_word(bytes[i]) || bytes[i] == b'.') {
+                i += 1;
+            }
+            if i + 1 < len && bytes[i] == b'\'' {
+                let mut j = i + 1;
+                if bytes[j] == b's' || bytes[j] == b'S' {
+                    j += 1;
+                }
+                if j < len && is_base(bytes[j]) {
+                    i = j + 1;
+                    while i < len && is_digit(bytes[i]) {
+                        i += 1;
+                    }
+                }
+            }
+            Some(TokenType::Number)
+        } else if c == b'\'' && b"01xXzZ".contains(&next) {
+            i += 2;
+            Some(TokenType::Number)
+        } else if is_word(c) {
+            while i < len && is_word(bytes[i]) {
+                i += 1;
+            }
+            let word = &text[begin..i];
+            if TYPE_KEYWORDS.contains(&word) {
+                Some(TokenType::Type)
+            } else if find_keyword(word).is_some() {
+                Some(TokenType::Keyword)
+            } else {
+                None
+            }
+        } else {
+            i += text[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+            None
+        };
+        if let Some(ty) = ty {
+            tokens.push(SemanticToken {
+                begin,
+                end: i,
+                ty,
+                declaration: false,
+            });
+        }
+    }
+    tokens
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Desugaring of implicit event controls.
+//!
+//! A statement guarded by `@*` or `@(*)` is sensitive to every variable and
+//! net that it reads. This module rewrites such implicit event controls into
+//! explicit event expressions that list these signals, as if the user had
+//! written `@(a or b or c)`. The code generator then only has to deal with
+//! explicit sensitivity lists.
+
+use crate::{
+    crate_prelude::*,
+    hir::{fold, AccessedNode, Folder, HirNode, Visitor},
+    mir::WalkVisitor as _,
+    ParamEnv,
+};
+use std::collections::BTreeMap;
+
+/// Rewrite the implicit event controls in a statement into explicit ones.
+///
+/// Returns the ID of the rewritten statement, or the ID of the statement
+/// itself if it contains no implicit event controls.
+#[moore_derive::query]
+pub(crate) fn desugar_implicit_events<'a>(
+    cx: &impl Context<'a>,
+    stmt_id: NodeId,
+    env: ParamEnv,
+) -> NodeId {
+    ImplicitEventDesugarer { cx, env }.fold_node_with_id(stmt_id)
+}
+
+/// A folder that replaces `@*` with an explicit event expression.
+struct ImplicitEventDesugarer<'a, C> {
+    cx: &'a C,
+    env: ParamEnv,
+}
+
+impl<'a, 'gcx: 'a, C> Folder<'gcx> for ImplicitEventDesugarer<'a, C>
+where
+    C: Context<'gcx>,
+{
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn fold_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> NodeId {
+        let inner = match stmt.kind {
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ImplicitEvent,
+                stmt: inner,
+            } => inner,
+            _ => return fold::walk_stmt(self, stmt),
+        };
+
+        // Collect the signals the guarded statement reads. Signals declared
+        // within the statement itself are not part of the sensitivity list.
+        let mut collector = SensitivityCollector {
+            cx: self.cx,
+            env: self.env,
+            stmt_id: stmt.id,
+            exprs: Default::default(),
+        };
+        collector.visit_node_with_id(inner, false);
+        let events = collector
+            .exprs
+            .into_values()
+            .map(|expr_id| {
+                // Use a copy of the expression that is detached from its
+                // surroundings, such that it is not cast to the type that the
+                // statement imposes on it.
+                let expr = match self.cx.hir_of(expr_id) {
+                    Ok(HirNode::Expr(x)) => x,
+                    _ => unreachable!(),
+                };
+                hir::Event {
+                    span: expr.span(),
+                    edge: ast::EdgeIdent::Implicit,
+                    expr: fold::alloc_expr(self.cx, expr, expr.kind.clone()),
+                    iff: vec![],
+                }
+            })
+            .collect();
+        let event = fold::alloc_event_expr(self.cx, stmt.id, stmt.span, events);
+
+        let inner = self.fold_node_with_id(inner);
+        fold::alloc_stmt(
+            self.cx,
+            stmt,
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ExplicitEvent(event),
+                stmt: inner,
+            },
+        )
+    }
+}
+
+/// A visitor that finds an expression reading each signal accessed by a
+/// statement.
+struct SensitivityCollector<'a, C> {
+    cx: &'a C,
+    env: ParamEnv,
+    stmt_id: NodeId,
+    exprs: BTreeMap<AccessedNode, NodeId>,
+}
+
+impl<'a, 'gcx: 'a, C> Visitor<'gcx> for SensitivityCollector<'a, C>
+where
+    C: Context<'gcx>,
+{
+    type Context = C;
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr, lvalue: bool) {
+        if lvalue {
+            self.cx.mir_lvalue(expr.id, self.env).walk(self);
+        } else {
+            self.cx.mir_rvalue(expr.id, self.env).walk(self);
+        }
+    }
+}
+
+impl<'a, 'gcx: 'a, C> mir::Visitor<'gcx> for SensitivityCollector<'a, C>
+where
+    C: Context<'gcx>,
+{
+    fn pre_visit_rvalue(&mut self, mir: &mir::Rvalue) -> bool {
+        let node = match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => AccessedNode::Regular(id),
+            mir::RvalueKind::IntfSignal(intf, sig) => match intf.get_intf() {
+                Some(intf) => AccessedNode::Intf(intf, sig),
+                None => return true,
+            },
+            _ => return true,
+        };
+        if self.cx.is_parent_of(self.stmt_id, node.id()) || !self.is_signal_access(mir.origin) {
+            return true;
+        }
+        self.exprs.entry(node).or_insert(mir.origin);
+        false
+    }
+}
+
+impl<'a, 'gcx: 'a, C> SensitivityCollector<'a, C>
+where
+    C: Context<'gcx>,
+{
+    /// Check whether an expression names a signal, such that a copy of it can
+    /// be used in the sensitivity list.
+    fn is_signal_access(&self, expr_id: NodeId) -> bool {
+        match self.cx.hir_of(expr_id) {
+            Ok(HirNode::Expr(expr)) => matches!(
+                expr.kind,
+                hir::ExprKind::Ident(..) | hir::ExprKind::Scope(..) | hir::ExprKind::Field(..)
+            ),
+            _ => false,
+        }
+    }
+}
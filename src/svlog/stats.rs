@@ -0,0 +1,117 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Statistics about the executed queries.
+//!
+//! If the `stats` session option is set, the number of executions of every
+//! query and the time spent in it are recorded. The time of a query excludes
+//! the time spent in the queries it calls, such that the times of all queries
+//! add up to the total time spent in queries. Every query is associated with a
+//! compilation phase, which allows the time to be broken down by phase.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// A compilation phase that queries belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Phase {
+    /// Lowering of the AST to HIR, including name resolution.
+    Lower,
+    /// Type checking and constant evaluation.
+    Typecheck,
+    /// Lowering of the HIR to MIR and code generation.
+    Codegen,
+}
+
+impl Phase {
+    /// Determine the phase a query belongs to.
+    pub fn of_query(name: &str) -> Phase {
+        match name {
+            "accessed_nodes"
+            | "attribute"
+            | "canonicalize_func_args"
+            | "canonicalize_ports"
+            | "disamb_kind"
+            | "disamb_type_or_expr"
+            | "doc_comment"
+            | "generated_scope"
+            | "hierarchical_rib"
+            | "local_rib"
+            | "nameck"
+            | "scope_location"
+            | "struct_def" => Phase::Lower,
+            _ if name.starts_with("hir_") || name.starts_with("resolve_") => Phase::Lower,
+            "call_mapping"
+            | "format_mapping"
+            | "inst_details"
+            | "inst_target_details"
+            | "is_constant"
+            | "constant_value_of"
+            | "map_pattern"
+            | "param_env"
+            | "port_mapping" => Phase::Typecheck,
+            _ if name.contains("type") || name.starts_with("const_") => Phase::Typecheck,
+            _ => Phase::Codegen,
+        }
+    }
+}
+
+/// The number of executions of a query and the time spent in it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueryStats {
+    /// The number of times the query was executed, not counting the results
+    /// served from the cache.
+    pub count: usize,
+    /// The time spent in the query, excluding the queries it called.
+    pub time: Duration,
+}
+
+/// A recorder for the statistics of the executed queries.
+#[derive(Default)]
+pub(crate) struct QueryRecorder {
+    /// The start time of every executing query, and the time spent in the
+    /// queries it has called so far.
+    stack: RefCell<Vec<(Instant, Duration)>>,
+    /// The statistics of every query.
+    queries: RefCell<HashMap<&'static str, QueryStats>>,
+}
+
+impl QueryRecorder {
+    /// Record the start of a query execution.
+    pub fn start(&self) {
+        self.stack
+            .borrow_mut()
+            .push((Instant::now(), Duration::default()));
+    }
+
+    /// Record the end of the most recently started query execution.
+    pub fn finish(&self, name: &'static str) {
+        let mut stack = self.stack.borrow_mut();
+        let (start, callees) = match stack.pop() {
+            Some(x) => x,
+            None => return,
+        };
+        let elapsed = start.elapsed();
+        if let Some(caller) = stack.last_mut() {
+            caller.1 += elapsed;
+        }
+        let mut queries = self.queries.borrow_mut();
+        let stats = queries.entry(name).or_default();
+        stats.count += 1;
+        stats.time += elapsed.checked_sub(callees).unwrap_or_default();
+    }
+
+    /// Get the statistics of every query, sorted by name.
+    pub fn get(&self) -> Vec<(&'static str, QueryStats)> {
+        let mut queries: Vec<_> = self
+            .queries
+            .borrow()
+            .iter()
+            .map(|(&name, &stats)| (name, stats))
+            .collect();
+        queries.sort_by_key(|&(name, _)| name);
+        queries
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A machine-readable summary of the interface of modules.
+//!
+//! The summary lists the parameters and ports of a module, with the types and
+//! widths of the ports resolved under a parameter environment. The environment
+//! is derived from the default values of the parameters, some of which may be
+//! overridden with integer values. This is meant for tools that generate
+//! instantiation templates or register abstraction layers from the design.
+
+use crate::{crate_prelude::*, hir::HirNode, value::make_int, ParamEnvData};
+use num::BigInt;
+use serde::Serialize;
+use std::io::{Result as IoResult, Write};
+
+/// A summary of the interface of a list of modules.
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    /// The summarized modules, in the order they were requested.
+    pub modules: Vec<ModuleSummary>,
+}
+
+/// The interface of a module.
+#[derive(Debug, Serialize)]
+pub struct ModuleSummary {
+    /// The name of the module.
+    pub name: Name,
+    /// The parameters of the module, including localparams.
+    pub params: Vec<ParamSummary>,
+    /// The ports of the module.
+    pub ports: Vec<PortSummary>,
+}
+
+/// A parameter of a module.
+#[derive(Debug, Serialize)]
+pub struct ParamSummary {
+    /// The name of the parameter.
+    pub name: Name,
+    /// Whether the parameter is a localparam.
+    pub local: bool,
+    /// Whether this is a value or a type parameter.
+    pub kind: &'static str,
+    /// The type of the parameter, or the type assigned to a type parameter.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The value of a value parameter.
+    pub value: Option<String>,
+    /// Whether the value was overridden, rather than taken from the default.
+    pub overridden: bool,
+}
+
+/// A port of a module.
+#[derive(Debug, Serialize)]
+pub struct PortSummary {
+    /// The name of the port.
+    pub name: Name,
+    /// The direction of the port.
+    #[serde(serialize_with = "crate::hir::dump::ser_display")]
+    pub dir: ast::PortDir,
+    /// The resolved type of the port.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The number of bits of the port, or `None` if the type has no fixed
+    /// size.
+    pub width: Option<usize>,
+}
+
+impl Summary {
+    /// Summarize the interface of a list of modules.
+    ///
+    /// Each override assigns an integer to the non-local value parameters of
+    /// that name. An error is emitted for overrides that match no parameter in
+    /// any of the modules.
+    pub fn new<'a>(
+        cx: &impl Context<'a>,
+        ids: impl IntoIterator<Item = NodeId>,
+        overrides: &[(Name, BigInt)],
+    ) -> Result<Self> {
+        let mut used = vec![false; overrides.len()];
+        let mut modules = vec![];
+        for id in ids {
+            modules.push(module(cx, id, overrides, &mut used)?);
+        }
+        let mut failed = false;
+        for (&(name, _), used) in overrides.iter().zip(used) {
+            if !used {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "no parameter `{}` to override in the summarized modules",
+                        name
+                    ))
+                    .code("SV0050"),
+                );
+                failed = true;
+            }
+        }
+        if failed {
+            return Err(());
+        }
+        Ok(Summary { modules })
+    }
+
+    /// Write the summary as JSON.
+    pub fn write_json(&self, w: &mut impl Write) -> IoResult<()> {
+        serde_json::to_writer_pretty(&mut *w, &self.modules)?;
+        writeln!(w)
+    }
+}
+
+/// Summarize the interface of a module.
+fn module<'a>(
+    cx: &impl Context<'a>,
+    id: NodeId,
+    overrides: &[(Name, BigInt)],
+    used: &mut [bool],
+) -> Result<ModuleSummary> {
+    let hir = match cx.hir_of(id)? {
+        HirNode::Module(x) => x,
+        _ => unreachable!(),
+    };
+    let param_ids: Vec<NodeId> = hir
+        .params
+        .iter()
+        .chain(hir.block.params.iter())
+        .cloned()
+        .collect();
+
+    // Assign the overridden values to the parameters.
+    let default_env = cx.default_param_env();
+    let mut env_data = ParamEnvData::default();
+    let mut overridden = vec![];
+    for &param_id in &param_ids {
+        let param = match cx.hir_of(param_id)? {
+            HirNode::ValueParam(p) if !p.local => p,
+            _ => continue,
+        };
+        let (index, value) = match overrides
+            .iter()
+            .position(|&(name, _)| name == param.name.value)
+        {
+            Some(index) => (index, &overrides[index].1),
+            None => continue,
+        };
+        used[index] = true;
+        let ty = cx.type_of(param_id, default_env)?;
+        if ty.get_simple_bit_vector().is_none() {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "parameter `{}` of type `{}` cannot be overridden with an integer",
+                    param.name, ty
+                ))
+                .code("SV0050")
+                .span(param.name.span),
+            );
+            return Err(());
+        }
+        env_data.set_value(param_id, cx.intern_value(make_int(ty, value.clone())));
+        overridden.push(param_id);
+    }
+    let env = cx.intern_param_env(env_data);
+    cx.add_param_env_context(env, id);
+
+    // Collect the parameters.
+    let mut params = vec![];
+    for &param_id in &param_ids {
+        params.push(match cx.hir_of(param_id)? {
+            HirNode::ValueParam(p) => {
+                let value = cx.constant_value_of(param_id, env);
+                ParamSummary {
+                    name: p.name.value,
+                    local: p.local,
+                    kind: "value",
+                    ty: value.ty.to_string(),
+                    value: Some(value.kind.to_string()),
+                    overridden: overridden.contains(&param_id),
+                }
+            }
+            HirNode::TypeParam(p) => ParamSummary {
+                name: p.name.value,
+                local: p.local,
+                kind: "type",
+                ty: cx
+                    .map_to_type_or_error(Ref(cx.ast_for_id(param_id)), env)
+                    .to_string(),
+                value: None,
+                overridden: false,
+            },
+            _ => continue,
+        });
+    }
+
+    // Collect the ports.
+    let ports = hir
+        .ports_new
+        .int
+        .iter()
+        .map(|port| {
+            let ty = cx.type_of_int_port(Ref(port), env);
+            PortSummary {
+                name: port.name.value,
+                dir: port.dir,
+                ty: ty.to_string(),
+                width: ty.get_bit_size(),
+            }
+        })
+        .collect();
+
+    Ok(ModuleSummary {
+        name: hir.name.value,
+        params,
+        ports,
+    })
+}
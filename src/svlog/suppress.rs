@@ -0,0 +1,166 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Suppression of warnings with lint directive comments.
+//!
+//! A `// moore lint_off <names>` comment disables the named warnings until a
+//! matching `// moore lint_on <names>` comment, or the end of the file. A
+//! `// moore lint_off_line <names>` comment disables the named warnings on the
+//! line of the comment only. Without names, all warnings are disabled. The
+//! names are the warning groups that can be passed to `-W`, in any case. The
+//! `verilator` spelling of the comments and some of Verilator's warning names
+//! are accepted as well, such that existing waivers carry over.
+
+use crate::{
+    ast::{LintDirective, LintDirectiveKind, SourceFile},
+    common::{
+        errors::{DiagBuilder2, DiagSegment, Severity, WARNING_GROUPS},
+        source::{Source, Span},
+    },
+};
+use std::cell::RefCell;
+
+/// Verilator warning names and the warning groups they correspond to.
+static VERILATOR_NAMES: &[(&str, &str)] = &[
+    ("alwcombordr", "comb-loop"),
+    ("blkseq", "blocking-ff"),
+    ("caseincomplete", "incomplete-case"),
+    ("combdly", "nonblocking-comb"),
+    ("pinconnectempty", "positional"),
+    ("pinmissing", "port-conn"),
+    ("unoptflat", "comb-loop"),
+    ("widthexpand", "width"),
+    ("widthtrunc", "width"),
+];
+
+/// A region of a source file in which warnings are disabled.
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    /// The source file.
+    source: Source,
+    /// The offset of the first byte of the region.
+    begin: usize,
+    /// The offset one past the last byte of the region.
+    end: usize,
+    /// The disabled warning group, or `None` for all warnings.
+    group: Option<&'static str>,
+}
+
+/// The regions of the source files in which warnings are disabled.
+#[derive(Default)]
+pub(crate) struct Suppressions {
+    regions: RefCell<Vec<Region>>,
+}
+
+impl Suppressions {
+    /// Add the lint directive comments of a source file.
+    pub fn add_file(&self, file: &SourceFile) {
+        let mut regions = self.regions.borrow_mut();
+        let mut open: Vec<Region> = vec![];
+        for lint in &file.lints {
+            let groups = match groups(lint) {
+                Some(x) => x,
+                None => continue,
+            };
+            let source = lint.span.source;
+            match lint.kind {
+                LintDirectiveKind::Off => {
+                    for group in groups {
+                        open.push(Region {
+                            source,
+                            begin: lint.span.end,
+                            end: usize::MAX,
+                            group,
+                        });
+                    }
+                }
+                LintDirectiveKind::On => {
+                    let mut i = 0;
+                    while i < open.len() {
+                        let closes = open[i].source == source
+                            && (groups == [None] || groups.contains(&open[i].group));
+                        if closes {
+                            let mut region = open.remove(i);
+                            region.end = lint.span.begin;
+                            regions.push(region);
+                        } else {
+                            i += 1;
+                        }
+                    }
+                }
+                LintDirectiveKind::OffLine => {
+                    let (begin, end) = line_extent(lint.span);
+                    for group in groups {
+                        regions.push(Region {
+                            source,
+                            begin,
+                            end,
+                            group,
+                        });
+                    }
+                }
+            }
+        }
+        regions.extend(open);
+    }
+
+    /// Check whether a diagnostic is a warning in a region where it has been
+    /// disabled.
+    pub fn is_suppressed(&self, diag: &DiagBuilder2) -> bool {
+        if diag.get_severity() != Severity::Warning {
+            return false;
+        }
+        let span = match diag.get_segments().iter().find_map(|seg| match *seg {
+            DiagSegment::Span(x) | DiagSegment::Label(x, _) => Some(x),
+            _ => None,
+        }) {
+            Some(x) => x,
+            None => return false,
+        };
+        let group = diag.get_group();
+        self.regions.borrow().iter().any(|r| {
+            r.source == span.source
+                && r.begin <= span.begin
+                && span.begin < r.end
+                && (r.group.is_none() || r.group == group)
+        })
+    }
+}
+
+/// Determine the warning groups a lint directive refers to.
+///
+/// Returns `[None]` if the directive refers to all warnings, and `None` if it
+/// only refers to warnings that are unknown.
+fn groups(lint: &LintDirective) -> Option<Vec<Option<&'static str>>> {
+    if lint.names.is_empty() {
+        return Some(vec![None]);
+    }
+    let groups: Vec<_> = lint
+        .names
+        .iter()
+        .filter_map(|name| {
+            let name = name.to_lowercase();
+            match WARNING_GROUPS.iter().find(|&&(x, _)| x == name) {
+                Some(&(group, _)) => Some(Some(group)),
+                None => VERILATOR_NAMES
+                    .iter()
+                    .find(|&&(x, _)| x == name)
+                    .map(|&(_, group)| Some(group)),
+            }
+        })
+        .collect();
+    if groups.is_empty() {
+        None
+    } else {
+        Some(groups)
+    }
+}
+
+/// Determine the offsets of the beginning and end of the line that a span
+/// starts on.
+fn line_extent(span: Span) -> (usize, usize) {
+    let content = span.source.get_content();
+    let lines = content.lines();
+    let index = lines.partition_point(|&x| x <= span.begin) - 1;
+    let end = lines.get(index + 1).cloned().unwrap_or(usize::MAX);
+    (lines[index], end)
+}
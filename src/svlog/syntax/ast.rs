@@ -8,10 +8,11 @@ use crate::token::{Lit, Op};
 use moore_common::{
     id::NodeId,
     name::Name,
-    source::{Span, Spanned},
+    source::{Location, Span, Spanned},
     util::{HasDesc, HasSpan},
 };
 use moore_derive::AnyNodeData;
+use serde::Serialize;
 use std::{
     cell::Cell,
     hash::{Hash, Hasher},
@@ -242,7 +243,12 @@ impl<'a> ForEachNode<'a> for bool {}
 impl<'a> ForEachNode<'a> for usize {}
 
 /// Common denominator across all AST nodes.
+///
+/// The layout is fixed such that the node header precedes the per-node data.
+/// Nodes are compared and hashed by address, and this guarantees that a node
+/// never shares its address with one of its children.
 #[derive(Clone)]
+#[repr(C)]
 pub struct Node<'a, T> {
     /// Unique ID assigned to the node.
     pub id: NodeId,
@@ -276,6 +282,24 @@ impl<'a, T> Node<'a, T> {
 unsafe impl<'a, T> Send for Node<'a, T> where T: Send {}
 unsafe impl<'a, T> Sync for Node<'a, T> where T: Sync {}
 
+/// Serialize nodes with their type name, ID, and span, followed by the
+/// per-node data.
+impl<'a, T> Serialize for Node<'a, T>
+where
+    Self: BasicNode<'a>,
+    T: Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = s.serialize_struct("Node", 4)?;
+        st.serialize_field("kind", self.type_name())?;
+        st.serialize_field("id", &self.id)?;
+        st.serialize_field("span", &self.span)?;
+        st.serialize_field("data", &self.data)?;
+        st.end()
+    }
+}
+
 /// Automatically implement `AnyNode` for `Node<T>` if enough information is
 /// present.
 impl<'a, T> AnyNode<'a> for Node<'a, T>
@@ -601,7 +625,7 @@ pub type PackageDecl<'a> = Package<'a>;
 pub type ModportDecl<'a> = Modport<'a>;
 
 /// A parsing result which may be ambiguous.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Ambiguous<R> {
     Unique(R),
     Ambiguous(Vec<R>),
@@ -634,7 +658,7 @@ where
 /// All things being compiled.
 #[moore_derive::node]
 #[indefinite("root")]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Root<'a> {
     pub files: Vec<&'a SourceFile<'a>>,
 }
@@ -642,10 +666,95 @@ pub struct Root<'a> {
 /// An entire source file.
 #[moore_derive::node]
 #[indefinite("source file")]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct SourceFile<'a> {
     pub timeunits: Timeunit,
     pub items: Vec<Item<'a>>,
+    #[dont_visit]
+    pub docs: Vec<DocComment>,
+    #[dont_visit]
+    pub attrs: Vec<Attribute>,
+    #[dont_visit]
+    pub lints: Vec<LintDirective>,
+    #[dont_visit]
+    pub nettypes: Vec<DefaultNettype>,
+    #[dont_visit]
+    pub macros: Vec<MacroUse>,
+}
+
+/// A documentation comment.
+///
+/// These are the `///` and `/** */` comments in the source text. Consecutive
+/// documentation comments are merged into one, and are associated with the
+/// token that immediately follows them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocComment {
+    /// The location of the first token after the comment.
+    pub target: Location,
+    /// The span of the comment.
+    pub span: Span,
+    /// The text of the comment, with the comment markers removed.
+    pub text: String,
+}
+
+/// An attribute, such as `keep_hierarchy` in `(* keep_hierarchy *)`.
+///
+/// Attributes are associated with the token that immediately follows the
+/// attribute instance they appear in, like documentation comments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Attribute {
+    /// The location of the first token after the attribute instance.
+    pub target: Location,
+    /// The span of the attribute instance.
+    pub span: Span,
+    /// The name of the attribute.
+    pub name: Name,
+    /// The value assigned to the attribute, as written in the source text.
+    pub value: Option<String>,
+}
+
+/// A comment that disables or enables warnings, such as
+/// `// moore lint_off width` or `/* verilator lint_off WIDTH */`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintDirective {
+    /// The span of the comment.
+    pub span: Span,
+    /// Whether the comment disables or enables the warnings.
+    pub kind: LintDirectiveKind,
+    /// The names of the warnings, or empty for all warnings.
+    pub names: Vec<String>,
+}
+
+/// A `` `default_nettype `` directive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DefaultNettype {
+    /// The span of the directive.
+    pub span: Span,
+    /// The type of the implicit nets declared after the directive, or `None`
+    /// if `none` disallows implicit nets.
+    pub net_type: Option<NetType>,
+}
+
+/// The definition or expansion of a macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MacroUse {
+    /// The span of the macro name.
+    pub span: Span,
+    /// The name of the macro.
+    pub name: Name,
+    /// Whether the macro is defined here, rather than expanded.
+    pub def: bool,
+}
+
+/// The different lint directive comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LintDirectiveKind {
+    /// `lint_off`, which disables warnings until a matching `lint_on`.
+    Off,
+    /// `lint_on`, which enables warnings again.
+    On,
+    /// `lint_off_line`, which disables warnings on the line of the comment.
+    OffLine,
 }
 
 /// An item that may appear in a hierarchical scope.
@@ -659,7 +768,7 @@ pub struct SourceFile<'a> {
 /// - generates
 #[moore_derive::node]
 #[indefinite("item")]
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Item<'a> {
     #[indefinite("dummy item")]
     Dummy,
@@ -683,6 +792,7 @@ pub enum Item<'a> {
     GenerateIf(#[forward] GenerateIf<'a>),
     GenerateCase(#[forward] GenerateCase<'a>),
     Assertion(Assertion<'a>),
+    SpecifyBlock(Span, Vec<Item<'a>>),
     NetDecl(NetDecl<'a>),
     VarDecl(#[forward] VarDecl<'a>),
     Inst(Inst<'a>),
@@ -693,7 +803,7 @@ pub enum Item<'a> {
 #[moore_derive::node]
 #[indefinite("module")]
 #[definite("module `{}`", name)]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Module<'a> {
     pub lifetime: Lifetime, // default static
     #[name]
@@ -708,7 +818,7 @@ pub struct Module<'a> {
 #[moore_derive::node]
 #[indefinite("interface")]
 #[definite("interface `{}`", name)]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Interface<'a> {
     pub lifetime: Lifetime, // default static
     #[name]
@@ -722,7 +832,7 @@ pub struct Interface<'a> {
 #[moore_derive::node]
 #[indefinite("package")]
 #[definite("package `{}`", name)]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Package<'a> {
     pub lifetime: Lifetime,
     #[name]
@@ -732,7 +842,7 @@ pub struct Package<'a> {
 
 /// Lifetime specifier for variables, tasks, and functions. Defaults to static.
 #[moore_derive::visit]
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Lifetime {
     Static,
     Automatic,
@@ -745,7 +855,7 @@ pub enum Lifetime {
 /// "timeprecision" time_literal ";"
 /// ```
 #[moore_derive::visit]
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct Timeunit {
     pub unit: Option<Spanned<Lit>>,
     pub prec: Option<Spanned<Lit>>,
@@ -754,7 +864,7 @@ pub struct Timeunit {
 /// A type.
 #[moore_derive::node]
 #[indefinite("type")]
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct Type<'a> {
     pub kind: TypeKind<'a>,
     pub sign: TypeSign,
@@ -771,7 +881,7 @@ impl<'a> Type<'a> {
 /// A type without sign and packed dimensions.
 #[moore_derive::node]
 #[indefinite("type")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum TypeKind<'a> {
     ImplicitType,
     VoidType,
@@ -838,7 +948,7 @@ impl<'a> TypeKind<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum TypeSign {
     None,
     Signed,
@@ -846,7 +956,7 @@ pub enum TypeSign {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum TypeDim<'a> {
     Expr(Expr<'a>),
     Range(Expr<'a>, Expr<'a>),
@@ -880,7 +990,7 @@ impl HasDesc for TypeDim<'_> {
 /// For example `enum { FOO = 42 }`.
 #[moore_derive::node]
 #[indefinite("enum definition")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Enum<'a> {
     pub base_type: Option<Box<Type<'a>>>,
     pub variants: Vec<EnumName<'a>>,
@@ -891,7 +1001,7 @@ pub struct Enum<'a> {
 /// For example the `FOO = 42` in `enum { FOO = 42 }`.
 #[moore_derive::node]
 #[indefinite("enum variant")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct EnumName<'a> {
     #[name]
     pub name: Spanned<Name>,
@@ -904,7 +1014,7 @@ pub struct EnumName<'a> {
 /// For example `struct packed { byte foo; }`.
 #[moore_derive::node]
 #[indefinite("struct definition")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Struct<'a> {
     pub kind: StructKind,
     pub packed: bool,
@@ -913,7 +1023,7 @@ pub struct Struct<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash, Serialize)]
 pub enum StructKind {
     /// A `struct`.
     Struct,
@@ -938,7 +1048,7 @@ impl std::fmt::Display for StructKind {
 /// For example the `byte foo;` in `struct packed { byte foo; }`.
 #[moore_derive::node]
 #[indefinite("struct member")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct StructMember<'a> {
     pub rand_qualifier: Option<RandomQualifier>,
     pub ty: Box<Type<'a>>,
@@ -948,7 +1058,7 @@ pub struct StructMember<'a> {
 /// A module or interface port as declared in the port list.
 #[moore_derive::node]
 #[indefinite("port")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Port<'a> {
     Intf {
         modport: Option<Spanned<Name>>,
@@ -999,7 +1109,7 @@ impl HasDesc for Port<'_> {
 /// A port declaration in an item body.
 #[moore_derive::node]
 #[indefinite("port declaration")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PortDecl<'a> {
     pub dir: PortDir,
     pub kind: Option<VarKind>,
@@ -1009,7 +1119,7 @@ pub struct PortDecl<'a> {
 
 /// Whether a declaration is a variable or a net.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum VarKind {
     /// A variable declaration.
     Var,
@@ -1057,7 +1167,7 @@ impl std::fmt::Display for VarKind {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash, Serialize)]
 pub enum PortDir {
     Input,
     Output,
@@ -1077,7 +1187,7 @@ impl std::fmt::Display for PortDir {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum NetType {
     Supply0,
     Supply1,
@@ -1115,14 +1225,14 @@ impl std::fmt::Display for NetType {
 /// A procedure such as `always*`, `initial`, or `final`.
 #[moore_derive::node]
 #[indefinite("procedure")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Procedure<'a> {
     pub kind: ProcedureKind,
     pub stmt: Stmt<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum ProcedureKind {
     Initial,
     Always,
@@ -1135,7 +1245,7 @@ pub enum ProcedureKind {
 /// A statement.
 #[moore_derive::node]
 #[indefinite("statement")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Stmt<'a> {
     pub label: Option<Name>,
     pub kind: StmtKind<'a>,
@@ -1143,7 +1253,7 @@ pub struct Stmt<'a> {
 
 /// The different kinds of statement.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum StmtKind<'a> {
     NullStmt,
     SequentialBlock(Vec<Stmt<'a>>),
@@ -1206,7 +1316,7 @@ impl<'a> Stmt<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum JoinKind {
     All,
     Any,
@@ -1214,7 +1324,7 @@ pub enum JoinKind {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum UniquePriority {
     Unique,
     Unique0,
@@ -1222,7 +1332,7 @@ pub enum UniquePriority {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum CaseKind {
     Normal,
     DontCareZ,
@@ -1230,7 +1340,7 @@ pub enum CaseKind {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
 pub enum CaseMode {
     Normal,
     Inside,
@@ -1238,39 +1348,39 @@ pub enum CaseMode {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum CaseItem<'a> {
     Default(Box<Stmt<'a>>),
     Expr(Vec<Expr<'a>>, Box<Stmt<'a>>),
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct DelayControl<'a> {
     pub span: Span,
     pub expr: Expr<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct EventControl<'a> {
     pub span: Span,
     pub data: EventControlData<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum EventControlData<'a> {
     Implicit,
     Expr(EventExpr<'a>),
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CycleDelay {}
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum TimingControl<'a> {
     Delay(DelayControl<'a>),
     Event(EventControl<'a>),
@@ -1278,7 +1388,7 @@ pub enum TimingControl<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum AssignOp {
     Identity,
     Add,
@@ -1300,7 +1410,7 @@ pub enum AssignOp {
 /// For example `logic x, y, z`.
 #[moore_derive::node]
 #[indefinite("variable declaration")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct VarDecl<'a> {
     pub konst: bool,
     pub var: bool,
@@ -1314,7 +1424,7 @@ pub struct VarDecl<'a> {
 /// For example the `x` in `logic x, y, z`.
 #[moore_derive::node]
 #[indefinite("variable")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct VarDeclName<'a> {
     pub name: Name,
     pub name_span: Span,
@@ -1325,7 +1435,7 @@ pub struct VarDeclName<'a> {
 /// A generate variable declaration.
 #[moore_derive::node]
 #[indefinite("genvar")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GenvarDecl<'a> {
     #[name]
     pub name: Spanned<Name>,
@@ -1335,7 +1445,7 @@ pub struct GenvarDecl<'a> {
 /// A foreach-loop index variable.
 #[moore_derive::node]
 #[indefinite("index variable")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ForeachIndex {
     /// The name of the index.
     #[name]
@@ -1347,7 +1457,7 @@ pub struct ForeachIndex {
 /// An expression.
 #[moore_derive::node]
 #[indefinite("expression")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Expr<'a> {
     DummyExpr,
     LiteralExpr(Lit),
@@ -1430,7 +1540,7 @@ pub enum Expr<'a> {
 /// resolution.
 #[moore_derive::arena]
 #[moore_derive::visit]
-#[derive(AnyNodeData, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(AnyNodeData, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[forward]
 pub enum TypeOrExpr<'a> {
     Type(&'a Type<'a>),
@@ -1521,7 +1631,7 @@ impl<'a> std::fmt::Display for TypeOrExpr<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ValueRange<'a> {
     Single(Expr<'a>),
     Range {
@@ -1532,14 +1642,14 @@ pub enum ValueRange<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum RangeMode {
     Absolute,
     RelativeDown,
     RelativeUp,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct Identifier {
     pub span: Span,
     pub name: Name,
@@ -1548,7 +1658,7 @@ pub struct Identifier {
 /// An argument to a a function or task call.
 #[moore_derive::node]
 #[indefinite("call argument")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CallArg<'a> {
     /// An optional name of the argument, e.g. `.foo(x)` instead of just `x`.
     pub name: Option<Spanned<Name>>,
@@ -1558,21 +1668,21 @@ pub struct CallArg<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum StreamConcatSlice<'a> {
     Expr(Box<Expr<'a>>),
     Type(Type<'a>),
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct StreamExpr<'a> {
     pub expr: Box<Expr<'a>>,
     pub range: Option<Box<Expr<'a>>>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum EventExpr<'a> {
     Edge {
         span: Span,
@@ -1592,7 +1702,7 @@ pub enum EventExpr<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum EdgeIdent {
     Implicit,
     Edge,
@@ -1620,7 +1730,7 @@ impl HasDesc for EventExpr<'_> {
 #[moore_derive::node]
 #[indefinite("class declaration")]
 #[definite("class `{}`", name)]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ClassDecl<'a> {
     pub virt: bool,
     pub lifetime: Lifetime, // default static
@@ -1632,7 +1742,7 @@ pub struct ClassDecl<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ClassItem<'a> {
     pub span: Span,
     pub qualifiers: Vec<(ClassItemQualifier, Span)>,
@@ -1640,7 +1750,7 @@ pub struct ClassItem<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ClassItemQualifier {
     Static,
     Protected,
@@ -1653,7 +1763,7 @@ pub enum ClassItemQualifier {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ClassItemData<'a> {
     Property,
     Typedef(Typedef<'a>),
@@ -1667,7 +1777,7 @@ pub enum ClassItemData<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum RandomQualifier {
     Rand,
     Randc,
@@ -1678,7 +1788,7 @@ pub enum RandomQualifier {
 /// For example `typedef int my_type_t`.
 #[moore_derive::node]
 #[indefinite("typedef")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Typedef<'a> {
     #[name]
     pub name: Spanned<Name>,
@@ -1687,7 +1797,7 @@ pub struct Typedef<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Constraint<'a> {
     pub span: Span,
     pub kind: ConstraintKind,
@@ -1698,7 +1808,7 @@ pub struct Constraint<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ConstraintKind {
     Decl,
     Proto,
@@ -1707,14 +1817,14 @@ pub enum ConstraintKind {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ConstraintItem<'a> {
     pub span: Span,
     pub data: ConstraintItemData<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ConstraintItemData<'a> {
     If,
     Foreach,
@@ -1724,7 +1834,7 @@ pub enum ConstraintItemData<'a> {
 /// A function or task declaration.
 #[moore_derive::node]
 #[indefinite("subroutine declaration")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SubroutineDecl<'a> {
     #[forward]
     pub prototype: SubroutinePrototype<'a>,
@@ -1734,7 +1844,7 @@ pub struct SubroutineDecl<'a> {
 /// A function or task prototype.
 #[moore_derive::node]
 #[indefinite("subroutine prototype")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SubroutinePrototype<'a> {
     /// Whether this is a function or a task.
     pub kind: SubroutineKind,
@@ -1751,7 +1861,7 @@ pub struct SubroutinePrototype<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum SubroutineKind {
     Func,
     Task,
@@ -1769,7 +1879,7 @@ pub enum SubroutineKind {
 /// a defined type.
 #[moore_derive::node]
 #[indefinite("subroutine port")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SubroutinePort<'a> {
     /// The direction of the port, if provided.
     pub dir: Option<SubroutinePortDir>,
@@ -1783,7 +1893,7 @@ pub struct SubroutinePort<'a> {
 ///
 /// This is the `x[3]` part in `inout logic [7:0] x[3]`.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SubroutinePortName<'a> {
     /// The name of the port.
     pub name: Spanned<Name>,
@@ -1794,7 +1904,7 @@ pub struct SubroutinePortName<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum SubroutineItem<'a> {
     PortDecl(SubroutinePortDecl<'a>),
     Stmt(Stmt<'a>),
@@ -1803,7 +1913,7 @@ pub enum SubroutineItem<'a> {
 /// A function or task port declaration located in the body.
 #[moore_derive::node]
 #[indefinite("subroutine port")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SubroutinePortDecl<'a> {
     /// The direction of the port.
     pub dir: SubroutinePortDir,
@@ -1816,7 +1926,7 @@ pub struct SubroutinePortDecl<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum SubroutinePortDir {
     Input,
     Output,
@@ -1842,7 +1952,7 @@ impl std::fmt::Display for SubroutinePortDir {
 /// For example `wire x, y, z`.
 #[moore_derive::node]
 #[indefinite("net declaration")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct NetDecl<'a> {
     pub net_type: NetType,
     pub strength: Option<NetStrength>,
@@ -1853,7 +1963,7 @@ pub struct NetDecl<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum NetKind {
     Vectored,
     Scalared,
@@ -1861,14 +1971,14 @@ pub enum NetKind {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum NetStrength {
     Drive(DriveStrength, DriveStrength),
     Charge(ChargeStrength),
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DriveStrength {
     Supply0,
     Strong0,
@@ -1883,7 +1993,7 @@ pub enum DriveStrength {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ChargeStrength {
     Small,
     Medium,
@@ -1893,7 +2003,7 @@ pub enum ChargeStrength {
 /// A field in a `'{...}` pattern.
 #[moore_derive::node]
 #[indefinite("pattern field")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PatternField<'a> {
     Default(Box<Expr<'a>>),
     Member(Box<Expr<'a>>, Box<Expr<'a>>),
@@ -1907,7 +2017,7 @@ pub enum PatternField<'a> {
 /// For example `import a::b, c::*`.
 #[moore_derive::node]
 #[indefinite("import declaration")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ImportDecl<'a> {
     pub items: Vec<ImportItem<'a>>,
 }
@@ -1917,14 +2027,14 @@ pub struct ImportDecl<'a> {
 /// For example the `a::b` in `import a::b, c::*`.
 #[moore_derive::node]
 #[indefinite("import")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ImportItem {
     pub pkg: Spanned<Name>,
     pub name: Option<Spanned<Name>>, // None means `import pkg::*`
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Assertion<'a> {
     pub span: Span,
     pub label: Option<(Name, Span)>,
@@ -1932,7 +2042,7 @@ pub struct Assertion<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AssertionData<'a> {
     Immediate(BlockingAssertion<'a>),
     Deferred(AssertionDeferred, BlockingAssertion<'a>),
@@ -1940,7 +2050,7 @@ pub enum AssertionData<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AssertionDeferred {
     /// `assert #0`
     Observed,
@@ -1949,7 +2059,7 @@ pub enum AssertionDeferred {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum BlockingAssertion<'a> {
     Assert(Expr<'a>, AssertionActionBlock<'a>),
     Assume(Expr<'a>, AssertionActionBlock<'a>),
@@ -1957,7 +2067,7 @@ pub enum BlockingAssertion<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ConcurrentAssertion<'a> {
     AssertProperty(PropSpec, AssertionActionBlock<'a>),
     AssumeProperty(PropSpec, AssertionActionBlock<'a>),
@@ -1968,7 +2078,7 @@ pub enum ConcurrentAssertion<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AssertionActionBlock<'a> {
     Positive(Stmt<'a>),
     Negative(Stmt<'a>),
@@ -1976,14 +2086,14 @@ pub enum AssertionActionBlock<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SeqExpr<'a> {
     pub span: Span,
     pub data: SeqExprData<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum SeqExprData<'a> {
     Expr(Expr<'a>, Option<SeqRep<'a>>),
     BinOp(SeqBinOp, Box<SeqExpr<'a>>, Box<SeqExpr<'a>>),
@@ -1992,7 +2102,7 @@ pub enum SeqExprData<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum SeqRep<'a> {
     Consec(Expr<'a>),    // [* expr]
     ConsecStar,          // [*]
@@ -2002,7 +2112,7 @@ pub enum SeqRep<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum SeqBinOp {
     Or,
     And,
@@ -2011,18 +2121,18 @@ pub enum SeqBinOp {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PropSpec;
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PropExpr<'a> {
     pub span: Span,
     pub data: PropExprData<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PropExprData<'a> {
     SeqOp(PropSeqOp, SeqExpr<'a>),
     SeqBinOp(PropSeqBinOp, PropSeqOp, SeqExpr<'a>, Box<PropExpr<'a>>),
@@ -2032,7 +2142,7 @@ pub enum PropExprData<'a> {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum PropSeqOp {
     None,
     Weak,
@@ -2040,7 +2150,7 @@ pub enum PropSeqOp {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum PropSeqBinOp {
     ImplOverlap,
     ImplNonoverlap,
@@ -2049,7 +2159,7 @@ pub enum PropSeqBinOp {
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum PropBinOp {
     Or,
     And,
@@ -2071,7 +2181,7 @@ pub enum PropBinOp {
 #[moore_derive::node]
 #[indefinite("instantiation")]
 #[definite("`{}` instantiation", target)]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Inst<'a> {
     /// The name of the module to instantiate.
     #[name]
@@ -2087,7 +2197,7 @@ pub struct Inst<'a> {
 /// For example the `u0()` in `foo u0(), u1();`.
 #[moore_derive::node]
 #[indefinite("instance")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct InstName<'a> {
     /// The name of the instance.
     #[name]
@@ -2116,7 +2226,7 @@ impl<'a> InstName<'a> {
 /// For example `modport in (...), out (...);`.
 #[moore_derive::node]
 #[indefinite("modport")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Modport<'a> {
     /// The names of the modports.
     pub names: Vec<&'a ModportName<'a>>,
@@ -2127,7 +2237,7 @@ pub struct Modport<'a> {
 /// For example the `in (...)` in `modport in (...), out (...);`.
 #[moore_derive::node]
 #[indefinite("modport")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ModportName<'a> {
     /// The name of the modport.
     #[name]
@@ -2141,7 +2251,7 @@ pub struct ModportName<'a> {
 /// For example `input a, .b(expr)`, or `import ...`, or `clocking foo`.
 #[moore_derive::node]
 #[indefinite("modport port")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ModportPort<'a> {
     /// A simple port, for example `input a, .b(expr)`.
     Simple {
@@ -2155,7 +2265,7 @@ pub enum ModportPort<'a> {
 /// For example the `a` or `.b(expr)` in `input a, .b(expr)`.
 #[moore_derive::node]
 #[indefinite("simple modport port")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ModportSimplePort<'a> {
     /// The name of the port.
     #[name]
@@ -2174,14 +2284,14 @@ pub struct ModportSimplePort<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("parameter")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ParamDecl<'a> {
     pub local: bool,
     pub kind: ParamKind<'a>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ParamKind<'a> {
     Type(Vec<ParamTypeDecl<'a>>),
     Value(Vec<ParamValueDecl<'a>>),
@@ -2194,7 +2304,7 @@ pub enum ParamKind<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("type parameter")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ParamTypeDecl<'a> {
     #[name]
     pub name: Spanned<Name>,
@@ -2208,7 +2318,7 @@ pub struct ParamTypeDecl<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("value parameter")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ParamValueDecl<'a> {
     pub ty: Type<'a>,
     #[name]
@@ -2225,7 +2335,7 @@ pub struct ParamValueDecl<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("continuous assignment")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ContAssign<'a> {
     pub strength: Option<(DriveStrength, DriveStrength)>,
     pub delay: Option<Expr<'a>>,
@@ -2236,7 +2346,7 @@ pub struct ContAssign<'a> {
 /// A `for` generate statement.
 #[moore_derive::node]
 #[indefinite("for-generate statement")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GenerateFor<'a> {
     pub init: Stmt<'a>,
     pub cond: Expr<'a>,
@@ -2247,7 +2357,7 @@ pub struct GenerateFor<'a> {
 /// An `if` generate statement.
 #[moore_derive::node]
 #[indefinite("if-generate statement")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GenerateIf<'a> {
     pub cond: Expr<'a>,
     pub main_block: GenerateBlock<'a>,
@@ -2257,7 +2367,7 @@ pub struct GenerateIf<'a> {
 /// A `case` generate statement.
 #[moore_derive::node]
 #[indefinite("case-generate statement")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GenerateCase {
     // TODO
 }
@@ -2267,14 +2377,14 @@ pub struct GenerateCase {
 /// May contains hierarchy items or more generate constructs.
 #[moore_derive::node]
 #[indefinite("generate block")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct GenerateBlock<'a> {
     pub label: Option<Spanned<Name>>,
     pub items: Vec<Item<'a>>,
 }
 
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ParamAssignment<'a> {
     pub span: Span,
     pub name: Option<Identifier>,
@@ -2295,7 +2405,7 @@ pub struct ParamAssignment<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("port connection")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PortConn<'a> {
     /// The `.*` case,
     Auto,
@@ -2307,7 +2417,7 @@ pub enum PortConn<'a> {
 
 /// How a named port connection is made.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PortConnMode<'a> {
     /// The `.name` case.
     Auto,
@@ -2320,7 +2430,7 @@ pub enum PortConnMode<'a> {
 /// A DPI declaration such as `import "DPI-C"` or `export "DPI-C"`.
 #[moore_derive::node]
 #[indefinite("DPI declaration")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum DpiDecl<'a> {
     /// An `import`.
     Import {
@@ -2342,7 +2452,7 @@ pub enum DpiDecl<'a> {
 
 /// A DPI function/task property.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum DpiProperty {
     /// `context`
     Context,
@@ -2374,7 +2484,7 @@ pub enum DpiProperty {
 /// ```
 #[moore_derive::node]
 #[indefinite("data type")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum DataType<'a> {
     /// An integer type, like `bit`, `logic signed`, `reg signed [42:0]`, `int`,
     /// or `int unsigned`.
@@ -2421,7 +2531,7 @@ pub enum DataType<'a> {
 
 /// An integer type.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum IntType {
     /// A `bit`.
     Bit,
@@ -2445,7 +2555,7 @@ pub enum IntType {
 
 /// A real type.
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum RealType {
     /// A `shortreal`.
     ShortReal,
@@ -2464,7 +2574,7 @@ pub enum RealType {
 /// ```
 #[moore_derive::node]
 #[indefinite("implicit data type")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ImplicitDataType<'a> {
     pub signing: Option<TypeSign>,
     pub packed_dims: Vec<PackedDim<'a>>,
@@ -2479,7 +2589,7 @@ pub struct ImplicitDataType<'a> {
 ///     implicit_data_type
 /// ```
 #[moore_derive::visit]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum DataTypeOrImplicit<'a> {
     /// An explicit data type.
     Explicit(DataType<'a>),
@@ -2498,7 +2608,7 @@ pub enum DataTypeOrImplicit<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("variable dimension")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum VarDim<'a> {
     /// An unsized dimension, like `[]`.
     Unsized,
@@ -2519,7 +2629,7 @@ pub enum VarDim<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("packed dimension")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PackedDim<'a> {
     /// Such as `[41:0]`.
     Range(Expr<'a>, Expr<'a>),
@@ -2536,7 +2646,7 @@ pub enum PackedDim<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("unpacked dimension")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum UnpackedDim<'a> {
     /// Such as `[41:0]`.
     Range(Expr<'a>, Expr<'a>),
@@ -2557,7 +2667,7 @@ pub enum UnpackedDim<'a> {
 /// ```
 #[moore_derive::node]
 #[indefinite("type name segment")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PathSegment<'a> {
     /// A `$unit`.
     Unit,
@@ -2580,3 +2690,49 @@ moore_derive::derive_visitor!(
 );
 moore_derive::derive_all_node!();
 moore_derive::derive_arena!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser, preproc::Preprocessor};
+    use moore_common::source::get_source_manager;
+    use std::collections::HashMap;
+
+    /// Collect the addresses of a node and all its descendants.
+    fn collect<'a>(node: &'a dyn AnyNode<'a>, into: &mut HashMap<*const u8, Vec<&'a str>>) {
+        into.entry(node.as_ptr())
+            .or_default()
+            .push(node.type_name());
+        node.for_each_child(&mut |child| collect(child, into));
+    }
+
+    /// Nodes are compared and hashed by address, such that a node must not
+    /// share its address with a child stored inline in its data.
+    #[test]
+    fn nodes_have_distinct_addresses() {
+        let source = get_source_manager().add(
+            "ast_test_distinct_addresses.sv",
+            "package p;
+                typedef struct packed { logic [7:0] a; logic b; } s_t;
+                function automatic int f(int x); return x + 1; endfunction
+            endpackage
+            module foo #(parameter int N = 4) (input logic clk, output logic [N-1:0] q);
+                p::s_t s;
+                logic [N-1:0] r [0:3];
+                always_ff @(posedge clk) begin
+                    if (s.b) q <= r[0] + {N{1'b1}};
+                    else q <= p::f(q);
+                end
+                bar #(.M(N)) b(.x(q[0]), .y());
+            endmodule",
+        );
+        let arena = Arena::default();
+        let lexer = Lexer::new(Preprocessor::new(source, &[], &[]));
+        let file = parser::parse(lexer, &arena).unwrap();
+        let mut nodes = HashMap::new();
+        collect(file.as_any(), &mut nodes);
+        for (_, names) in nodes {
+            assert_eq!(names.len(), 1, "nodes {:?} share an address", names);
+        }
+    }
+}
@@ -3,6 +3,9 @@
 //! A lexical analyzer for SystemVerilog files, based on IEEE 1800-2009, section
 //! 5.
 
+use crate::ast::{
+    Attribute, DefaultNettype, DocComment, LintDirective, LintDirectiveKind, MacroUse,
+};
 use crate::cat::CatTokenKind;
 use crate::preproc::*;
 pub use crate::token::*;
@@ -17,6 +20,16 @@ pub type TokenAndSpan = (Token, Span);
 pub struct Lexer<'a> {
     input: Preprocessor<'a>,
     peek: [CatTokenAndSpan; 4],
+    /// The documentation comments encountered so far.
+    docs: Vec<DocComment>,
+    /// The documentation comments not yet associated with a token.
+    pending_docs: Vec<Span>,
+    /// The attributes encountered so far.
+    attrs: Vec<Attribute>,
+    /// The attribute instances not yet associated with a token.
+    pending_attrs: Vec<Span>,
+    /// The lint directive comments encountered so far.
+    lints: Vec<LintDirective>,
 }
 
 impl<'a> Lexer<'a> {
@@ -24,9 +37,45 @@ impl<'a> Lexer<'a> {
         Lexer {
             input: input,
             peek: [(CatTokenKind::Eof, INVALID_SPAN); 4],
+            docs: Vec::new(),
+            pending_docs: Vec::new(),
+            attrs: Vec::new(),
+            pending_attrs: Vec::new(),
+            lints: Vec::new(),
         }
     }
 
+    /// Take the documentation comments encountered so far.
+    ///
+    /// Each comment is associated with the location of the token that follows
+    /// it.
+    pub fn take_doc_comments(&mut self) -> Vec<DocComment> {
+        std::mem::take(&mut self.docs)
+    }
+
+    /// Take the attributes encountered so far.
+    ///
+    /// Each attribute is associated with the location of the token that
+    /// follows the attribute instance it appears in.
+    pub fn take_attributes(&mut self) -> Vec<Attribute> {
+        std::mem::take(&mut self.attrs)
+    }
+
+    /// Take the lint directive comments encountered so far.
+    pub fn take_lint_directives(&mut self) -> Vec<LintDirective> {
+        std::mem::take(&mut self.lints)
+    }
+
+    /// Take the `` `default_nettype `` directives encountered so far.
+    pub fn take_default_nettypes(&mut self) -> Vec<DefaultNettype> {
+        self.input.take_default_nettypes()
+    }
+
+    /// Take the macro definitions and expansions encountered so far.
+    pub fn take_macro_uses(&mut self) -> Vec<MacroUse> {
+        self.input.take_macro_uses()
+    }
+
     pub fn bump(&mut self) -> DiagResult2<()> {
         self.peek[0] = self.peek[1];
         self.peek[1] = self.peek[2];
@@ -282,6 +331,7 @@ impl<'a> Lexer<'a> {
                         return Err(DiagBuilder2::fatal(
                             "Expected escaped identifier after backslash '\\'",
                         )
+                        .code("SV0019")
                         .span(sp));
                     }
                     return Ok((EscIdent(name_table.intern(&s, true)), sp));
@@ -319,12 +369,19 @@ impl<'a> Lexer<'a> {
                         self.bump()?; // eat the unit
                         return Ok((Literal(Time(value, frac, unit)), sp));
                     }
+                    if let Some(exp) = self.try_exponent(&mut sp)? {
+                        return Ok((
+                            Literal(Real(value, frac, name_table.intern(&exp, true))),
+                            sp,
+                        ));
+                    }
                     if self.peek[0].0 == CatTokenKind::Text {
                         return Err(DiagBuilder2::fatal(format!(
                             "number literal `{}` may not directly be followed by letters `{}`",
                             sp.extract(),
                             self.peek[0].1.extract(),
                         ))
+                        .code("SV0019")
                         .span(sp));
                     }
                     if frac.is_some() {
@@ -367,14 +424,15 @@ impl<'a> Lexer<'a> {
                                         span.expand(sp);
                                         s.push('"');
                                     }
-                                    (CatTokenKind::Text, sp) => {
+                                    (CatTokenKind::Text, sp) | (CatTokenKind::Digits, sp) => {
                                         span.expand(sp);
-                                        s.push_str(&sp.extract());
+                                        push_escape(&mut s, &sp.extract());
                                     }
                                     _ => {
                                         return Err(DiagBuilder2::fatal(
                                             "Unknown escape sequence in string",
                                         )
+                                        .code("SV0019")
                                         .span(span))
                                     }
                                 }
@@ -383,6 +441,7 @@ impl<'a> Lexer<'a> {
                                 return Err(DiagBuilder2::fatal(
                                     "String literals cannot contain unescaped newlines",
                                 )
+                                .code("SV0019")
                                 .span(sp))
                             }
                             (_, sp) => {
@@ -397,7 +456,9 @@ impl<'a> Lexer<'a> {
 
                 (CatTokenKind::Eof, sp) => return Ok((Eof, sp)),
                 (tkn, sp) => {
-                    return Err(DiagBuilder2::fatal(format!("Unknown token {:?}", tkn)).span(sp))
+                    return Err(DiagBuilder2::fatal(format!("Unknown token {:?}", tkn))
+                        .code("SV0019")
+                        .span(sp))
                 }
             }
         }
@@ -445,12 +506,14 @@ impl<'a> Lexer<'a> {
                 (CatTokenKind::Symbol('('), CatTokenKind::Symbol('*'))
                     if self.peek[2].0 != CatTokenKind::Symbol(')') =>
                 {
+                    let begin = self.peek[0].1;
                     self.bump()?;
                     self.bump()?;
                     loop {
                         match (self.peek[0].0, self.peek[1].0) {
                             (CatTokenKind::Eof, _) => break,
                             (CatTokenKind::Symbol('*'), CatTokenKind::Symbol(')')) => {
+                                self.pending_attrs.push(Span::union(begin, self.peek[1].1));
                                 self.bump()?;
                                 self.bump()?;
                                 break;
@@ -461,15 +524,65 @@ impl<'a> Lexer<'a> {
                 }
                 _ => (),
             }
-            match self.peek[0].0 {
-                CatTokenKind::Whitespace | CatTokenKind::Newline | CatTokenKind::Comment => {
+            match self.peek[0] {
+                (CatTokenKind::Comment, sp) => {
+                    let text = sp.extract();
+                    if is_doc_comment(&text) {
+                        self.pending_docs.push(sp);
+                    } else if let Some((kind, names)) = parse_lint_directive(&text) {
+                        self.lints.push(LintDirective {
+                            span: sp,
+                            kind,
+                            names,
+                        });
+                    }
                     self.bump()?
                 }
-                _ => return Ok(()),
+                (CatTokenKind::Whitespace, _) | (CatTokenKind::Newline, _) => self.bump()?,
+                (_, sp) => {
+                    self.attach_docs(sp);
+                    self.attach_attrs(sp);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Associate the pending attributes with the token at `sp`.
+    fn attach_attrs(&mut self, sp: Span) {
+        let target = Location::new(sp.source, sp.begin);
+        for span in std::mem::take(&mut self.pending_attrs) {
+            for (name, value) in split_attributes(&span.extract()) {
+                self.attrs.push(Attribute {
+                    target,
+                    span,
+                    name: get_name_table().intern(&name, true),
+                    value,
+                });
             }
         }
     }
 
+    /// Associate the pending documentation comments with the token at `sp`.
+    fn attach_docs(&mut self, sp: Span) {
+        if self.pending_docs.is_empty() {
+            return;
+        }
+        let text: Vec<_> = self
+            .pending_docs
+            .iter()
+            .map(|sp| strip_doc_comment(&sp.extract()))
+            .collect();
+        let first = self.pending_docs[0];
+        let last = self.pending_docs[self.pending_docs.len() - 1];
+        self.pending_docs.clear();
+        self.docs.push(DocComment {
+            target: Location::new(sp.source, sp.begin),
+            span: Span::union(first, last),
+            text: text.join("\n"),
+        });
+    }
+
     /// Matches an identifier. This consumes all tokens from the input that when
     /// combined still make up a valid identifier and returns the consumed
     /// characters as a String, alongside the span they covered. In
@@ -492,7 +605,9 @@ impl<'a> Lexer<'a> {
             }
         }
         if s.is_empty() {
-            return Err(DiagBuilder2::fatal("Could not match an identifier here").span(sp));
+            return Err(DiagBuilder2::fatal("Could not match an identifier here")
+                .code("SV0019")
+                .span(sp));
         }
         assert!(!s.is_empty());
         Ok((s, sp))
@@ -541,9 +656,14 @@ impl<'a> Lexer<'a> {
                             "`{}` is not a valid number base",
                             x
                         ))
+                        .code("SV0019")
                         .span(span))
                     }
-                    None => return Err(DiagBuilder2::fatal("Missing number base").span(span)),
+                    None => {
+                        return Err(DiagBuilder2::fatal("Missing number base")
+                            .code("SV0019")
+                            .span(span))
+                    }
                 };
                 c = chars.next();
 
@@ -586,6 +706,7 @@ impl<'a> Lexer<'a> {
                         return Err(DiagBuilder2::fatal(
                             "Unbased unsized literal may only be '0, '1, 'x, or 'z",
                         )
+                        .code("SV0019")
                         .span(span))
                     }
                 }
@@ -631,6 +752,46 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    /// Try to parse the exponent of a real number, such as `e3` or `E-12`.
+    ///
+    /// Returns the exponent including its sign, or `None` if the next tokens
+    /// do not form an exponent.
+    fn try_exponent(&mut self, span: &mut Span) -> DiagResult2<Option<String>> {
+        if self.peek[0].0 != CatTokenKind::Text {
+            return Ok(None);
+        }
+        let text = self.peek[0].1.extract();
+        let digits = match text.strip_prefix(|c| c == 'e' || c == 'E') {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let mut s = String::new();
+        if !digits.is_empty() {
+            // Exponents such as `e3`, where the digits are part of the text.
+            if !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Ok(None);
+            }
+            s.push_str(digits);
+            span.expand(self.peek[0].1);
+            self.bump()?; // eat the `e3`
+        } else {
+            // Exponents such as `e-3`, where the sign splits the tokens.
+            match (self.peek[1].0, self.peek[2].0) {
+                (CatTokenKind::Symbol(c), CatTokenKind::Digits) if c == '+' || c == '-' => {
+                    if c == '-' {
+                        s.push('-');
+                    }
+                    span.expand(self.peek[1].1);
+                    self.bump()?; // eat the `e`
+                    self.bump()?; // eat the sign
+                }
+                _ => return Ok(None),
+            }
+        }
+        self.eat_number_body_into(&mut s, span, false)?;
+        Ok(Some(s))
+    }
+
     /// Try to parse the next text token as a time unit.
     fn try_time_unit(&mut self) -> Option<TimeUnit> {
         if self.peek[0].0 == CatTokenKind::Text {
@@ -660,20 +821,150 @@ impl<'a> Iterator for Lexer<'a> {
     }
 }
 
+/// Check whether a comment is a `///` or `/** */` documentation comment.
+fn is_doc_comment(text: &str) -> bool {
+    (text.starts_with("///") && !text.starts_with("////"))
+        || (text.starts_with("/**") && !text.starts_with("/***") && text != "/**/")
+}
+
+/// Parse a `moore lint_off`, `moore lint_on`, or `moore lint_off_line` comment,
+/// also accepting `verilator` instead of `moore`.
+fn parse_lint_directive(text: &str) -> Option<(LintDirectiveKind, Vec<String>)> {
+    let body = match text.strip_prefix("//") {
+        Some(x) => x,
+        None => text.strip_prefix("/*")?.strip_suffix("*/")?,
+    };
+    let mut words = body.split(|c: char| c.is_whitespace() || c == ',');
+    let mut words = std::iter::from_fn(|| words.find(|w| !w.is_empty()));
+    match words.next()? {
+        "moore" | "verilator" => (),
+        _ => return None,
+    }
+    let kind = match words.next()? {
+        "lint_off" => LintDirectiveKind::Off,
+        "lint_on" => LintDirectiveKind::On,
+        "lint_off_line" => LintDirectiveKind::OffLine,
+        _ => return None,
+    };
+    Some((kind, words.map(String::from).collect()))
+}
+
+/// Remove the comment markers from a documentation comment.
+fn strip_doc_comment(text: &str) -> String {
+    if let Some(line) = text.strip_prefix("///") {
+        return line
+            .strip_prefix(' ')
+            .unwrap_or(line)
+            .trim_end()
+            .to_string();
+    }
+    let body = text.trim_start_matches("/**").trim_end_matches("*/");
+    let lines: Vec<_> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+    let begin = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(begin);
+    lines[begin..end].join("\n")
+}
+
+/// Split an attribute instance `(* a = 1, b *)` into the names and values of
+/// its attributes.
+fn split_attributes(text: &str) -> Vec<(String, Option<String>)> {
+    let body = text.trim_start_matches("(*").trim_end_matches("*)");
+    let mut specs = vec![];
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut begin = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' | '[' | '{' if !quoted => depth += 1,
+            ')' | ']' | '}' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                specs.push(&body[begin..i]);
+                begin = i + 1;
+            }
+            _ => (),
+        }
+    }
+    specs.push(&body[begin..]);
+    specs
+        .into_iter()
+        .filter_map(|spec| {
+            let (name, value) = match spec.find('=') {
+                Some(i) => (&spec[..i], Some(spec[i + 1..].trim().to_string())),
+                None => (spec, None),
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                None
+            } else {
+                Some((name.to_string(), value))
+            }
+        })
+        .collect()
+}
+
+/// Append the character denoted by an escape sequence in a string literal,
+/// given the text that follows the backslash. See IEEE 1800-2017 §5.9.1.
+fn push_escape(s: &mut String, text: &str) {
+    let (c, len) = match text.as_bytes()[0] {
+        b'n' => ('\n', 1),
+        b't' => ('\t', 1),
+        b'v' => ('\x0B', 1),
+        b'f' => ('\x0C', 1),
+        b'a' => ('\x07', 1),
+        b'x' => {
+            let len = text[1..]
+                .bytes()
+                .take(2)
+                .take_while(u8::is_ascii_hexdigit)
+                .count();
+            match u8::from_str_radix(&text[1..1 + len], 16) {
+                Ok(v) => (v as char, 1 + len),
+                Err(_) => ('x', 1),
+            }
+        }
+        b'0'..=b'7' => {
+            let len = text
+                .bytes()
+                .take(3)
+                .take_while(|c| (b'0'..=b'7').contains(c))
+                .count();
+            let value = u32::from_str_radix(&text[..len], 8).unwrap();
+            ((value & 0xFF) as u8 as char, len)
+        }
+        _ => {
+            s.push_str(text);
+            return;
+        }
+    };
+    s.push(c);
+    s.push_str(&text[len..]);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn check(input: &str, expected: &[Token]) {
-        use std::cell::Cell;
-        thread_local!(static INDEX: Cell<usize> = Cell::new(0));
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static INDEX: AtomicUsize = AtomicUsize::new(0);
         let sm = get_source_manager();
-        let idx = INDEX.with(|i| {
-            let v = i.get();
-            i.set(v + 1);
-            v
-        });
-        let source = sm.add(&format!("test_{}.sv", idx), input);
+        let idx = INDEX.fetch_add(1, Ordering::SeqCst);
+        let source = sm.add(&format!("lexer_test_{}.sv", idx), input);
         let pp = Preprocessor::new(source, &[], &[]);
         let lexer = Lexer::new(pp);
         let actual: Vec<_> = lexer.map(|x| x.unwrap().0).collect();
@@ -831,6 +1122,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn string_literal_escapes() {
+        check(
+            "\"a\\nb\\t\\101\\x42c\\\\\\\"\"",
+            &[Literal(Str(name("a\nb\tABc\\\"")))],
+        );
+    }
+
     #[test]
     fn time_literal() {
         check(
@@ -856,4 +1155,66 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn real_literal() {
+        check(
+            "1e3 4.2E10 1.5e-3 7e+2",
+            &[
+                Literal(Real(name("1"), None, name("3"))),
+                Literal(Real(name("4"), Some(name("2")), name("10"))),
+                Literal(Real(name("1"), Some(name("5")), name("-3"))),
+                Literal(Real(name("7"), None, name("2"))),
+            ],
+        );
+    }
+
+    #[test]
+    fn doc_comments() {
+        let sm = get_source_manager();
+        let source = sm.add(
+            "test_doc_comments.sv",
+            "// plain\n/// Hello\n/// world\nmodule /* plain */ /** Block\n * doc\n */ foo;",
+        );
+        let pp = Preprocessor::new(source, &[], &[]);
+        let mut lexer = Lexer::new(pp);
+        while lexer.next_token().unwrap().0 != Eof {}
+        let docs: Vec<_> = lexer
+            .take_doc_comments()
+            .into_iter()
+            .map(|doc| (doc.target.offset, doc.text))
+            .collect();
+        assert_eq!(
+            docs,
+            vec![
+                (29, "Hello\nworld".to_string()),
+                (69, "Block\ndoc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        let sm = get_source_manager();
+        let source = sm.add(
+            "test_attributes.sv",
+            "(* keep_hierarchy *) module foo; (* a = 1, b = \"x,y\" *) bar u0(); @(*);",
+        );
+        let pp = Preprocessor::new(source, &[], &[]);
+        let mut lexer = Lexer::new(pp);
+        while lexer.next_token().unwrap().0 != Eof {}
+        let attrs: Vec<_> = lexer
+            .take_attributes()
+            .into_iter()
+            .map(|attr| (attr.target.offset, attr.name.to_string(), attr.value))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                (21, "keep_hierarchy".to_string(), None),
+                (56, "a".to_string(), Some("1".to_string())),
+                (56, "b".to_string(), Some("\"x,y\"".to_string())),
+            ]
+        );
+    }
 }
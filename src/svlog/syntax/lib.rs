@@ -11,4 +11,5 @@ pub mod cat;
 pub mod lexer;
 pub mod parser;
 pub mod preproc;
+pub mod reformat;
 pub mod token;
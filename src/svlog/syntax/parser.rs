@@ -10,7 +10,13 @@ use crate::ast;
 use crate::ast::*;
 use crate::lexer::{Lexer, TokenAndSpan};
 use crate::token::*;
-use moore_common::{arenas::Alloc, errors::*, name::*, source::*, util::HasSpan};
+use moore_common::{
+    arenas::Alloc,
+    errors::*,
+    name::*,
+    source::*,
+    util::{edit_distance, HasSpan},
+};
 use std;
 use std::collections::VecDeque;
 
@@ -66,9 +72,11 @@ trait AbstractParser<'n> {
                 self.bump();
                 Ok((name, span))
             }
-            (tkn, span) => {
-                Err(DiagBuilder2::error(format!("expected {} before `{}`", msg, tkn)).span(span))
-            }
+            (tkn, span) => Err(
+                DiagBuilder2::error(format!("expected {} before `{}`", msg, tkn))
+                    .code("SV0016")
+                    .span(span),
+            ),
         }
     }
 
@@ -84,7 +92,9 @@ trait AbstractParser<'n> {
             }
             (tkn, span) => {
                 self.add_diag(
-                    DiagBuilder2::error(format!("expected {} before `{}`", msg, tkn)).span(span),
+                    DiagBuilder2::error(format!("expected {} before `{}`", msg, tkn))
+                        .code("SV0016")
+                        .span(span),
                 );
                 Err(())
             }
@@ -104,12 +114,29 @@ trait AbstractParser<'n> {
                 self.bump();
                 Ok(())
             }
-            (wrong, span) => Err(DiagBuilder2::error(format!(
-                "expected `{}`, but found `{}` instead",
-                expect, wrong
-            ))
-            .span(span)),
+            (wrong, span) => {
+                let d = DiagBuilder2::error(format!(
+                    "expected `{}`, but found `{}` instead",
+                    expect, wrong
+                ))
+                .code("SV0016")
+                .span(span);
+                if expect == Semicolon {
+                    Err(self.suggest_semicolon(d))
+                } else {
+                    Err(d)
+                }
+            }
+        }
+    }
+
+    /// Suggest inserting a missing semicolon after the last consumed token.
+    fn suggest_semicolon(&self, diag: DiagBuilder2) -> DiagBuilder2 {
+        if self.consumed() == 0 {
+            return diag;
         }
+        let last = self.last_span();
+        diag.suggest(Span::new(last.source, last.end, last.end), ";")
     }
 
     fn require_reported(&mut self, expect: Token) -> ReportedResult<()> {
@@ -180,6 +207,7 @@ trait AbstractParser<'n> {
                                     CloseDelim(x),
                                     OpenDelim(open)
                                 ))
+                                .code("SV0016")
                                 .span(sp),
                             );
                             break;
@@ -191,6 +219,7 @@ trait AbstractParser<'n> {
                                 CloseDelim(x),
                                 OpenDelim(x)
                             ))
+                            .code("SV0016")
                             .span(sp),
                         );
                         break;
@@ -223,6 +252,7 @@ trait AbstractParser<'n> {
                 "expected {:?}, but found {:?} instead",
                 tokens, tkn
             ))
+            .code("SV0016")
             .span(sp),
         );
         Err(())
@@ -279,8 +309,10 @@ impl<'a, 'n> AbstractParser<'n> for Parser<'a, 'n> {
     }
 
     fn add_diag(&mut self, diag: DiagBuilder2) {
-        eprintln!("");
-        eprintln!("{}", diag);
+        if diag_format() == DiagFormat::Human && !capturing_diags() {
+            eprintln!("");
+        }
+        print_diag(&diag);
 
         // Emit a backtrace for this diagnostic.
         if diag.get_severity() >= Severity::Warning {
@@ -407,15 +439,29 @@ where
         } else if p.try_eat(Comma) {
             if term.matches(p) {
                 let q = p.last_span();
-                p.add_diag(DiagBuilder2::warning("superfluous trailing comma").span(q));
+                p.add_diag(
+                    DiagBuilder2::warning("superfluous trailing comma")
+                        .span(q)
+                        .suggest(q, ""),
+                );
                 break;
             }
         } else {
             let sp = p.peek(0).1;
-            p.add_diag(
+            let mut d =
                 DiagBuilder2::error(format!("expected , or {} after {}", term.describe(), msg))
-                    .span(sp),
-            );
+                    .code("SV0016")
+                    .span(sp);
+            // A missing semicolon at the end of a line is most likely just
+            // that, so leave the next line to be parsed as the next item.
+            if term.describe() == ";" {
+                d = p.suggest_semicolon(d);
+                if at_line_start(p) {
+                    p.add_diag(d);
+                    return Err(());
+                }
+            }
+            p.add_diag(d);
             term.recover(p, false);
             return Err(());
         }
@@ -437,7 +483,11 @@ where
     let q = p.peek(0).1;
     let v = comma_list(p, term, msg, item)?;
     if v.is_empty() {
-        p.add_diag(DiagBuilder2::error(format!("expected at least one {}", msg)).span(q));
+        p.add_diag(
+            DiagBuilder2::error(format!("expected at least one {}", msg))
+                .code("SV0016")
+                .span(q),
+        );
         Err(())
     } else {
         Ok(v)
@@ -482,6 +532,134 @@ where
     }
 }
 
+/// Check whether the next token is the first token on its line.
+fn at_line_start<'n>(p: &mut dyn AbstractParser<'n>) -> bool {
+    let next = p.peek(0).1;
+    let last = p.last_span();
+    next.source != last.source || next.begin().human_line() > last.end().human_line()
+}
+
+/// Check whether a token terminates a design element, subroutine, or any
+/// other construct that contains items or statements.
+fn is_end_keyword(tkn: Token) -> bool {
+    match tkn {
+        Keyword(Kw::Endcase)
+        | Keyword(Kw::Endchecker)
+        | Keyword(Kw::Endclass)
+        | Keyword(Kw::Endclocking)
+        | Keyword(Kw::Endconfig)
+        | Keyword(Kw::Endfunction)
+        | Keyword(Kw::Endgenerate)
+        | Keyword(Kw::Endgroup)
+        | Keyword(Kw::Endinterface)
+        | Keyword(Kw::Endmodule)
+        | Keyword(Kw::Endpackage)
+        | Keyword(Kw::Endprimitive)
+        | Keyword(Kw::Endprogram)
+        | Keyword(Kw::Endproperty)
+        | Keyword(Kw::Endsequence)
+        | Keyword(Kw::Endspecify)
+        | Keyword(Kw::Endtable)
+        | Keyword(Kw::Endtask)
+        | Keyword(Kw::Join)
+        | Keyword(Kw::JoinAny)
+        | Keyword(Kw::JoinNone) => true,
+        _ => false,
+    }
+}
+
+/// Check whether a token can only appear at the beginning of an item.
+fn is_item_start(tkn: Token) -> bool {
+    match tkn {
+        Keyword(Kw::Always)
+        | Keyword(Kw::AlwaysComb)
+        | Keyword(Kw::AlwaysFf)
+        | Keyword(Kw::AlwaysLatch)
+        | Keyword(Kw::Assign)
+        | Keyword(Kw::Class)
+        | Keyword(Kw::Function)
+        | Keyword(Kw::Generate)
+        | Keyword(Kw::Genvar)
+        | Keyword(Kw::Import)
+        | Keyword(Kw::Initial)
+        | Keyword(Kw::Final)
+        | Keyword(Kw::Input)
+        | Keyword(Kw::Inout)
+        | Keyword(Kw::Interface)
+        | Keyword(Kw::Localparam)
+        | Keyword(Kw::Modport)
+        | Keyword(Kw::Module)
+        | Keyword(Kw::Output)
+        | Keyword(Kw::Package)
+        | Keyword(Kw::Parameter)
+        | Keyword(Kw::Program)
+        | Keyword(Kw::Task)
+        | Keyword(Kw::Typedef) => true,
+        tkn => is_end_keyword(tkn),
+    }
+}
+
+/// Check whether a token can only appear at the beginning of a statement.
+fn is_stmt_start(tkn: Token) -> bool {
+    match tkn {
+        Keyword(Kw::Case)
+        | Keyword(Kw::Casex)
+        | Keyword(Kw::Casez)
+        | Keyword(Kw::Do)
+        | Keyword(Kw::For)
+        | Keyword(Kw::Foreach)
+        | Keyword(Kw::Forever)
+        | Keyword(Kw::Fork)
+        | Keyword(Kw::If)
+        | Keyword(Kw::Priority)
+        | Keyword(Kw::Repeat)
+        | Keyword(Kw::Return)
+        | Keyword(Kw::Unique)
+        | Keyword(Kw::Unique0)
+        | Keyword(Kw::While) => true,
+        tkn => is_end_keyword(tkn),
+    }
+}
+
+/// Skip ahead to the next item or statement after a syntax error, such that
+/// parsing can continue and report further errors.
+///
+/// The parser is considered synchronized after the next `;`, or before a
+/// closing delimiter or a token for which `is_sync` returns true. If the parse
+/// that failed has consumed tokens and stopped at the beginning of a new line,
+/// which is usually the case after a missing `;`, nothing is skipped. If the
+/// parse has not consumed any tokens since `start`, at least one token is
+/// skipped to guarantee progress.
+fn synchronize<'n>(p: &mut dyn AbstractParser<'n>, start: usize, is_sync: fn(Token) -> bool) {
+    let mut force = p.consumed() == start;
+    if !force && at_line_start(p) {
+        return;
+    }
+    let mut depth = 0;
+    loop {
+        let tkn = p.peek(0).0;
+        if depth == 0 && !force {
+            match tkn {
+                Semicolon => {
+                    p.skip();
+                    return;
+                }
+                CloseDelim(_) => return,
+                tkn if is_sync(tkn) => return,
+                _ => (),
+            }
+        }
+        match tkn {
+            Eof => return,
+            OpenDelim(_) => depth += 1,
+            CloseDelim(_) if depth > 0 => depth -= 1,
+            _ => (),
+        }
+        p.skip();
+        force = false;
+    }
+}
+
 /// Speculatively apply a parse function. If it fails, the parser `p` is left
 /// untouched. If it succeeds, `p` is in the same state as if `parse` was called
 /// on it directly. Use a ParallelParser for better error reporting.
@@ -525,6 +703,7 @@ fn parse_identifier_name<'n, M: std::fmt::Display>(
         x => {
             p.add_diag(
                 DiagBuilder2::error(format!("expected {}, but found `{}` instead", msg, x))
+                    .code("SV0016")
                     .span(span),
             );
             Err(())
@@ -546,6 +725,7 @@ fn parse_string_literal<'n, M: std::fmt::Display>(
         x => {
             p.add_diag(
                 DiagBuilder2::error(format!("expected {}, but found `{}` instead", msg, x))
+                    .code("SV0016")
                     .span(span),
             );
             Err(())
@@ -624,7 +804,12 @@ impl<
 
 pub fn parse<'n>(input: Lexer, arena: &'n ast::Arena<'n>) -> Result<ast::SourceFile<'n>, ()> {
     let mut p = Parser::new(input, arena);
-    let root = parse_source_text(&mut p);
+    let mut root = parse_source_text(&mut p);
+    root.docs = p.input.take_doc_comments();
+    root.attrs = p.input.take_attributes();
+    root.lints = p.input.take_lint_directives();
+    root.nettypes = p.input.take_default_nettypes();
+    root.macros = p.input.take_macro_uses();
     if p.is_error() {
         Err(())
     } else {
@@ -640,6 +825,11 @@ fn parse_source_text<'n>(p: &mut dyn AbstractParser<'n>) -> ast::SourceFile<'n>
             prec: None,
         },
         items: Vec::new(),
+        docs: Vec::new(),
+        attrs: Vec::new(),
+        lints: Vec::new(),
+        nettypes: Vec::new(),
+        macros: Vec::new(),
     };
 
     // Parse the optional timeunits declaration.
@@ -650,9 +840,10 @@ fn parse_source_text<'n>(p: &mut dyn AbstractParser<'n>) -> ast::SourceFile<'n>
 
     // Parse the descriptions in the source text.
     while !p.is_fatal() && p.peek(0).0 != Eof {
+        let start = p.consumed();
         match parse_item(p) {
             Ok(item) => root.items.push(item),
-            Err(()) => (), // parse_item handles recovery, so no need to do anything here
+            Err(()) => synchronize(p, start, is_item_start),
         }
     }
 
@@ -693,6 +884,7 @@ fn parse_time_literal<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Span
         _ => {
             p.add_diag(
                 DiagBuilder2::error(format!("expected time literal, instead got `{}`", tkn))
+                    .code("SV0016")
                     .span(sp),
             );
             Err(())
@@ -745,13 +937,14 @@ fn parse_interface_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<In
         // Eat the semicolon at the end of the header.
         if !p.try_eat(Semicolon) {
             let q = p.peek(0).1.end();
-            p.add_diag(
-                DiagBuilder2::error(format!(
-                    "Missing semicolon \";\" after header of interface \"{}\"",
-                    name
-                ))
-                .span(q),
-            );
+            let d = DiagBuilder2::error(format!(
+                "Missing semicolon \";\" after header of interface \"{}\"",
+                name
+            ))
+            .code("SV0016")
+            .span(q);
+            let d = p.suggest_semicolon(d);
+            p.add_diag(d);
         }
 
         // Eat the items in the interface.
@@ -760,7 +953,11 @@ fn parse_interface_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<In
             if p.try_eat(Semicolon) {
                 continue;
             }
-            items.push(parse_item(p)?);
+            let start = p.consumed();
+            match parse_item(p) {
+                Ok(x) => items.push(x),
+                Err(()) => synchronize(p, start, is_item_start),
+            }
         }
 
         span.expand(p.last_span());
@@ -912,10 +1109,11 @@ fn parse_module_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Modul
         // Eat the semicolon after the header.
         if !p.try_eat(Semicolon) {
             let q = p.peek(0).1.end();
-            p.add_diag(
-                DiagBuilder2::error(format!("Missing ; after header of module \"{}\"", name))
-                    .span(q),
-            );
+            let d = DiagBuilder2::error(format!("Missing ; after header of module \"{}\"", name))
+                .code("SV0016")
+                .span(q);
+            let d = p.suggest_semicolon(d);
+            p.add_diag(d);
         }
 
         // Parse the module items.
@@ -924,7 +1122,11 @@ fn parse_module_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Modul
             if p.try_eat(Semicolon) {
                 continue;
             }
-            items.push(parse_item(p)?);
+            let start = p.consumed();
+            match parse_item(p) {
+                Ok(x) => items.push(x),
+                Err(()) => synchronize(p, start, is_item_start),
+            }
         }
 
         span.expand(p.last_span());
@@ -971,7 +1173,11 @@ fn parse_package_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Pack
             if p.try_eat(Semicolon) {
                 continue;
             }
-            items.push(parse_item(p)?);
+            let start = p.consumed();
+            match parse_item(p) {
+                Ok(x) => items.push(x),
+                Err(()) => synchronize(p, start, is_item_start),
+            }
         }
 
         span.expand(p.last_span());
@@ -995,7 +1201,11 @@ fn parse_program_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<()>
     p.require_reported(Keyword(Kw::Program))?;
     let result = recovered(p, Keyword(Kw::Endprogram), |p| {
         let q = p.peek(0).1;
-        p.add_diag(DiagBuilder2::error("Don't know how to parse program declarations").span(q));
+        p.add_diag(
+            DiagBuilder2::error("Don't know how to parse program declarations")
+                .code("SV0018")
+                .span(q),
+        );
         Err(())
     });
     p.require_reported(Keyword(Kw::Endprogram))?;
@@ -1038,6 +1248,12 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
             p.require_reported(Semicolon)?;
             return Ok(ItemData::ParamDecl(decl));
         }
+        Keyword(Kw::Specparam) => {
+            let decl = parse_specparam_decl(p)?;
+            p.require_reported(Semicolon)?;
+            return Ok(ItemData::ParamDecl(decl));
+        }
+        Keyword(Kw::Specify) => return parse_specify_block(p),
         Keyword(Kw::Modport) => return parse_modport_decl(p).map(|x| ItemData::ModportDecl(x)),
         Keyword(Kw::Typedef) => return parse_typedef(p).map(|x| ItemData::Typedef(x)),
         Keyword(Kw::Import) if strlit_follows => return parse_dpi_decl(p).map(ItemData::DpiDecl),
@@ -1130,7 +1346,9 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
                 return Ok(ItemData::Dummy);
             }
             p.add_diag(
-                DiagBuilder2::error("expected `clocking` or `disable` after `default`").span(span),
+                DiagBuilder2::error("expected `clocking` or `disable` after `default`")
+                    .code("SV0016")
+                    .span(span),
             );
             p.recover_balanced(&[Semicolon], true);
             return Err(());
@@ -1153,11 +1371,7 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
     pp.add("variable declaration", |p| {
         parse_var_decl(p).map(|d| ItemData::VarDecl(d))
     });
-    let res = pp.finish(p, "hierarchy item");
-    if res.is_err() {
-        p.recover_balanced(&[Semicolon], true);
-    }
-    res
+    pp.finish(p, "hierarchy item")
 }
 
 fn parse_elab_system_task<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<()> {
@@ -1215,6 +1429,7 @@ fn parse_localparam_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<(
             (x, sp) => {
                 p.add_diag(
                     DiagBuilder2::error(format!("expected , or ; after localparam, found {}", x))
+                        .code("SV0016")
                         .span(sp),
                 );
                 return Err(());
@@ -1407,7 +1622,9 @@ fn parse_modport_ports_decl<'n>(
     if p.try_eat(Keyword(Kw::Import)) || p.try_eat(Keyword(Kw::Export)) {
         // TODO: Parse modport_tf_ports_declaration.
         p.add_diag(
-            DiagBuilder2::error("modport task/function ports not implemented").span(p.last_span()),
+            DiagBuilder2::error("modport task/function ports not implemented")
+                .code("SV0018")
+                .span(p.last_span()),
         );
         return Err(());
     }
@@ -1415,17 +1632,35 @@ fn parse_modport_ports_decl<'n>(
     // Attempt to parse a clocking declaration.
     if p.try_eat(Keyword(Kw::Clocking)) {
         // TODO: Parse modport_clocking_declaration.
-        p.add_diag(DiagBuilder2::error("modport clocking declaration not implemented").span(span));
+        p.add_diag(
+            DiagBuilder2::error("modport clocking declaration not implemented")
+                .code("SV0018")
+                .span(span),
+        );
         return Err(());
     }
 
     // If we've come this far, none of the above matched.
-    p.add_diag(DiagBuilder2::error("expected modport port declaration").span(span));
+    p.add_diag(
+        DiagBuilder2::error("expected modport port declaration")
+            .code("SV0016")
+            .span(span),
+    );
     Err(())
 }
 
 /// Convert a token to the corresponding PortDir. The token may be one of the
 /// keywords `input`, `output`, `inout`, or `ref`. Otherwise `None` is returned.
+/// Find the port direction keyword that an identifier is a misspelling of.
+fn misspelled_port_direction(name: &str) -> Option<&'static str> {
+    ["input", "output", "inout"]
+        .iter()
+        .map(|&dir| (edit_distance(name, dir), dir))
+        .filter(|&(dist, _)| dist <= 2 && name.len() > 2)
+        .min_by_key(|&(dist, _)| dist)
+        .map(|(_, dir)| dir)
+}
+
 fn as_port_direction(tkn: Token) -> Option<PortDir> {
     match tkn {
         Keyword(Kw::Input) => Some(PortDir::Input),
@@ -1684,7 +1919,7 @@ fn parse_type_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<TypeKin
 
         _ => {
             let q = p.peek(0).1;
-            p.add_diag(DiagBuilder2::error("expected type").span(q));
+            p.add_diag(DiagBuilder2::error("expected type").code("SV0016").span(q));
             return Err(());
         }
     }
@@ -1758,7 +1993,9 @@ fn parse_struct_type<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<TypeK
         }
         _ => {
             p.add_diag(
-                DiagBuilder2::error("expected `struct`, `union`, or `union tagged`").span(span),
+                DiagBuilder2::error("expected `struct`, `union`, or `union tagged`")
+                    .code("SV0016")
+                    .span(span),
             );
             return Err(());
         }
@@ -2310,7 +2547,11 @@ fn parse_expr_first<'n>(
         }
 
         (Keyword(Kw::Tagged), sp) => {
-            p.add_diag(DiagBuilder2::error("Tagged union expressions not implemented").span(sp));
+            p.add_diag(
+                DiagBuilder2::error("Tagged union expressions not implemented")
+                    .code("SV0018")
+                    .span(sp),
+            );
             return Err(());
         }
 
@@ -2418,6 +2659,7 @@ fn parse_primary_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Expr
         tkn => {
             p.add_diag(
                 DiagBuilder2::error(format!("expected expression, found `{}` instead", tkn))
+                    .code("SV0016")
                     .span(sp),
             );
             return Err(());
@@ -2605,7 +2847,11 @@ fn parse_expr_list<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Vec<Exp
             }
             (CloseDelim(Brace), _) => break,
             (_, sp) => {
-                p.add_diag(DiagBuilder2::error("expected , or } after expression").span(sp));
+                p.add_diag(
+                    DiagBuilder2::error("expected , or } after expression")
+                        .code("SV0016")
+                        .span(sp),
+                );
                 return Err(());
             }
         }
@@ -2771,6 +3017,7 @@ fn parse_port_list<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Vec<Por
 
     loop {
         // Parse a port.
+        let first = p.peek(0);
         match parse_port(p) {
             Ok(x) => v.push(x),
             Err(()) => p.recover_balanced(&[Comma, CloseDelim(Paren)], false),
@@ -2781,13 +3028,27 @@ fn parse_port_list<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Vec<Por
             (Comma, sp) => {
                 p.bump();
                 if p.peek(0).0 == CloseDelim(Paren) {
-                    p.add_diag(DiagBuilder2::warning("superfluous trailing comma").span(sp));
+                    p.add_diag(
+                        DiagBuilder2::warning("superfluous trailing comma")
+                            .span(sp)
+                            .suggest(sp, ""),
+                    );
                     break;
                 }
             }
             (CloseDelim(Paren), _) => break,
             (_, sp) => {
-                p.add_diag(DiagBuilder2::error("expected , or ) after port").span(sp));
+                let mut d = DiagBuilder2::error("expected , or ) after port")
+                    .code("SV0016")
+                    .span(sp);
+                if let (Ident(name), span) = first {
+                    if let Some(dir) = misspelled_port_direction(&name.as_str()) {
+                        d = d
+                            .add_note(format!("`{}` is not a port direction", name))
+                            .suggest(span, dir);
+                    }
+                }
+                p.add_diag(d);
                 p.recover_balanced(&[CloseDelim(Paren)], false);
                 break;
             }
@@ -3198,7 +3459,11 @@ fn parse_subroutine_kind<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<S
             Ok(SubroutineKind::Task)
         }
         _ => {
-            p.add_diag(DiagBuilder2::error("expected `function` or `task`").span(span));
+            p.add_diag(
+                DiagBuilder2::error("expected `function` or `task`")
+                    .code("SV0016")
+                    .span(span),
+            );
             Err(())
         }
     }
@@ -3316,7 +3581,9 @@ fn parse_subroutine_prototype_tail<'n>(
                     (Comma, _) | (CloseDelim(Paren), _) => Ok(data),
                     (_, sp) => {
                         p.add_diag(
-                            DiagBuilder2::error("expected , or ) after subroutine port").span(sp),
+                            DiagBuilder2::error("expected , or ) after subroutine port")
+                                .code("SV0016")
+                                .span(sp),
                         );
                         Err(())
                     }
@@ -3529,7 +3796,11 @@ fn parse_stmt_kind<'n>(
             let stmt = Box::new(parse_stmt(p)?);
             let q = p.last_span();
             if !p.try_eat(Keyword(Kw::While)) {
-                p.add_diag(DiagBuilder2::error("Do loop requires a while clause").span(q));
+                p.add_diag(
+                    DiagBuilder2::error("Do loop requires a while clause")
+                        .code("SV0016")
+                        .span(q),
+                );
                 return Err(());
             }
             let expr = flanked(p, Paren, parse_expr)?;
@@ -3637,6 +3908,7 @@ fn parse_stmt_kind<'n>(
                             "expected (<expr>) or fork after wait, found {} instead",
                             tkn
                         ))
+                        .code("SV0016")
                         .span(sp),
                     );
                     return Err(());
@@ -3645,7 +3917,9 @@ fn parse_stmt_kind<'n>(
         }
         Keyword(Kw::WaitOrder) => {
             p.add_diag(
-                DiagBuilder2::error("Don't know how to parse wait_order statements").span(sp),
+                DiagBuilder2::error("Don't know how to parse wait_order statements")
+                    .code("SV0018")
+                    .span(sp),
             );
             return Err(());
         }
@@ -3666,22 +3940,13 @@ fn parse_stmt_kind<'n>(
         // Everything else needs special treatment as things such as variable
         // declarations look very similar to other expressions.
         _ => {
-            let result = {
-                let mut pp = ParallelParser::new();
-                pp.add("variable declaration", |p| {
-                    parse_var_decl(p).map(|d| ast::VarDeclStmt(d))
-                });
-                pp.add("assign statement", |p| parse_assign_stmt(p));
-                pp.add("expression statement", |p| parse_expr_stmt(p));
-                pp.finish(p, "statement")
-            };
-            match result {
-                Ok(x) => x,
-                Err(_) => {
-                    p.recover_balanced(&[Semicolon], true);
-                    return Err(());
-                }
-            }
+            let mut pp = ParallelParser::new();
+            pp.add("variable declaration", |p| {
+                parse_var_decl(p).map(|d| ast::VarDeclStmt(d))
+            });
+            pp.add("assign statement", |p| parse_assign_stmt(p));
+            pp.add("expression statement", |p| parse_expr_stmt(p));
+            pp.finish(p, "statement")?
         }
     })
 }
@@ -3708,6 +3973,7 @@ fn parse_block<'n>(
                         "Block has been given two conflicting labels, {} and {}",
                         existing, name
                     ))
+                    .code("SV0017")
                     .span(name_span),
                 );
             }
@@ -3730,14 +3996,17 @@ fn parse_block<'n>(
             }
         }
 
-        // Otherwise parse the next statement.
+        // Otherwise parse the next statement. After an error, skip to the next
+        // statement, unless the block is missing its terminator altogether.
+        let start = p.consumed();
         match parse_stmt(p) {
             Ok(x) => v.push(x),
             Err(()) => {
-                p.recover_balanced(terminators, false);
-                terminator = p.peek(0).0;
-                p.bump();
-                break;
+                let tkn = p.peek(0).0;
+                if tkn == Eof || (is_end_keyword(tkn) && !terminators.contains(&tkn)) {
+                    return Err(());
+                }
+                synchronize(p, start, is_stmt_start);
             }
         }
     }
@@ -3754,6 +4023,7 @@ fn parse_block<'n>(
                          block",
                         name, before
                     ))
+                    .code("SV0017")
                     .span(name_span),
                 );
             }
@@ -3763,6 +4033,7 @@ fn parse_block<'n>(
                     "Block label {} provided at the end of the block, but not at the beginning",
                     name
                 ))
+                .code("SV0017")
                 .span(name_span),
             );
         }
@@ -3786,7 +4057,11 @@ fn parse_continuous_assign<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult
         match try_drive_strength(p)? {
             Some(x) => Ok(x),
             None => {
-                p.add_diag(DiagBuilder2::error("expected drive strength").span(span));
+                p.add_diag(
+                    DiagBuilder2::error("expected drive strength")
+                        .code("SV0016")
+                        .span(span),
+                );
                 Err(())
             }
         }
@@ -3840,6 +4115,7 @@ fn parse_if_or_case<'n>(
         x => {
             p.add_diag(
                 DiagBuilder2::error(format!("expected case or if statement, got {:?}", x))
+                    .code("SV0016")
                     .span(span),
             );
             Err(())
@@ -3927,7 +4203,9 @@ fn parse_case<'n>(
                     (Colon, _) => break,
                     (_, sp) => {
                         p.add_diag(
-                            DiagBuilder2::error("expected , or : after case expression").span(sp),
+                            DiagBuilder2::error("expected , or : after case expression")
+                                .code("SV0016")
+                                .span(sp),
                         );
                         break;
                     }
@@ -4008,13 +4286,17 @@ fn try_delay_control<'n>(
         }
 
         // Literals
-        Literal(Number(..)) | Literal(Time(..)) | Ident(..) => {
+        Literal(Number(..)) | Literal(Real(..)) | Literal(Time(..)) | Ident(..) => {
             parse_expr_first(p, Precedence::Max)?
         }
 
         // TODO: Parse "1step" keyword
         _ => {
-            p.add_diag(DiagBuilder2::error("expected delay value or expression after #").span(sp));
+            p.add_diag(
+                DiagBuilder2::error("expected delay value or expression after #")
+                    .code("SV0016")
+                    .span(sp),
+            );
             return Err(());
         }
     };
@@ -4075,7 +4357,11 @@ fn try_cycle_delay<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Option<
     }
 
     let q = p.last_span();
-    p.add_diag(DiagBuilder2::error("Don't know how to parse cycle delay").span(q));
+    p.add_diag(
+        DiagBuilder2::error("Don't know how to parse cycle delay")
+            .code("SV0018")
+            .span(q),
+    );
     Err(())
 }
 
@@ -4124,7 +4410,11 @@ fn parse_assign_stmt<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<StmtK
         });
     }
 
-    p.add_diag(DiagBuilder2::error("expected blocking or non-blocking assign statement").span(sp));
+    p.add_diag(
+        DiagBuilder2::error("expected blocking or non-blocking assign statement")
+            .code("SV0016")
+            .span(sp),
+    );
     Err(())
 }
 
@@ -4272,7 +4562,11 @@ fn parse_call_args<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Vec<Cal
             (Comma, sp) => p.bump(),
             (CloseDelim(Paren), _) => (),
             (_, sp) => {
-                p.add_diag(DiagBuilder2::error("expected , or ) after call argument").span(sp));
+                p.add_diag(
+                    DiagBuilder2::error("expected , or ) after call argument")
+                        .code("SV0016")
+                        .span(sp),
+                );
                 return Err(());
             }
         }
@@ -4395,7 +4689,11 @@ fn parse_generate_case<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Gen
         parse_identifier_name(p, "generate block label")?;
     }
     span.expand(p.last_span());
-    p.add_diag(DiagBuilder2::error("case-generate statements not supported").span(span));
+    p.add_diag(
+        DiagBuilder2::error("case-generate statements not supported")
+            .code("SV0018")
+            .span(span),
+    );
     Ok(GenerateCase::new(span, GenerateCaseData {}))
 }
 
@@ -4421,6 +4719,7 @@ fn parse_generate_block<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Ge
                     "expected `begin` keyword after generate block label, found {} instead",
                     t
                 ))
+                .code("SV0016")
                 .span(q),
             );
             return Err(());
@@ -4451,6 +4750,7 @@ fn parse_generate_block<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Ge
                         "Generate block given conflicting labels {} and {}",
                         existing, n
                     ))
+                    .code("SV0017")
                     .span(n.span),
                 );
                 return Err(());
@@ -4474,6 +4774,7 @@ fn parse_generate_block<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Ge
                          the block",
                         n, existing
                     ))
+                    .code("SV0017")
                     .span(n.span),
                 );
                 return Err(());
@@ -4565,6 +4866,7 @@ fn parse_class_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ClassD
                     "Class name {} disagrees with name {} given before",
                     n, name
                 ))
+                .code("SV0017")
                 .span(n.span),
             );
             return Err(());
@@ -4748,6 +5050,7 @@ fn parse_constraint<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Constr
         if kind == ConstraintKind::ExternProto || kind == ConstraintKind::PureProto {
             p.add_diag(
                 DiagBuilder2::error("Only constraint prototypes can be extern or pure")
+                    .code("SV0016")
                     .span(kind_span),
             );
             return Err(());
@@ -4784,14 +5087,20 @@ fn parse_constraint_item_data<'n>(
     // Handle the trivial cases that start with a keyword first.
     if p.try_eat(Keyword(Kw::If)) {
         let q = p.last_span();
-        p.add_diag(DiagBuilder2::error("Don't know how to parse `if` constraint items").span(q));
+        p.add_diag(
+            DiagBuilder2::error("Don't know how to parse `if` constraint items")
+                .code("SV0018")
+                .span(q),
+        );
         return Err(());
     }
 
     if p.try_eat(Keyword(Kw::Foreach)) {
         let q = p.last_span();
         p.add_diag(
-            DiagBuilder2::error("Don't know how to parse `foreach` constraint items").span(q),
+            DiagBuilder2::error("Don't know how to parse `foreach` constraint items")
+                .code("SV0018")
+                .span(q),
         );
         return Err(());
     }
@@ -4889,7 +5198,11 @@ impl<'a, 'n, R: Clone> ParallelParser<'a, 'n, R> {
                 names.push_str(", or ");
                 names.push_str(&results[results.len() - 1].0);
             }
-            p.add_diag(DiagBuilder2::fatal(format!("ambiguous code, could be {}", names)).span(q));
+            p.add_diag(
+                DiagBuilder2::fatal(format!("ambiguous code, could be {}", names))
+                    .code("SV0016")
+                    .span(q),
+            );
             for &(ref name, _, _, _, span) in &results {
                 p.add_diag(DiagBuilder2::note(format!("{} would be this part", name)).span(span));
             }
@@ -4918,6 +5231,7 @@ impl<'a, 'n, R: Clone> ParallelParser<'a, 'n, R> {
             if num_errors != 1 {
                 p.add_diag(
                     DiagBuilder2::error(format!("expected {}, found `{}` instead", msg, tkn))
+                        .code("SV0016")
                         .span(q),
                 );
                 for (name, _, _, ds) in errors {
@@ -5107,6 +5421,7 @@ fn parse_port_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<PortDec
         None => {
             p.add_diag(
                 DiagBuilder2::error("expected port direction (inout, input, output, or ref)")
+                    .code("SV0016")
                     .span(span),
             );
             return Err(());
@@ -5191,7 +5506,11 @@ fn parse_net_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<NetDecl<
         }
         None => {
             let q = p.peek(0).1;
-            p.add_diag(DiagBuilder2::error("expected net type").span(q));
+            p.add_diag(
+                DiagBuilder2::error("expected net type")
+                    .code("SV0016")
+                    .span(q),
+            );
             return Err(());
         }
     };
@@ -5267,7 +5586,11 @@ fn try_drive_strength<'n>(
             Ok(Some((a, b)))
         } else {
             let q = p.peek(0).1;
-            p.add_diag(DiagBuilder2::error("expected second drive strength").span(q));
+            p.add_diag(
+                DiagBuilder2::error("expected second drive strength")
+                    .code("SV0016")
+                    .span(q),
+            );
             Err(())
         }
     } else {
@@ -5283,7 +5606,11 @@ fn parse_net_strength<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<NetS
         Ok(NetStrength::Charge(s))
     } else {
         let q = p.peek(0).1;
-        p.add_diag(DiagBuilder2::error("expected drive or charge strength").span(q));
+        p.add_diag(
+            DiagBuilder2::error("expected drive or charge strength")
+                .code("SV0016")
+                .span(q),
+        );
         Err(())
     }
 }
@@ -5366,6 +5693,7 @@ fn parse_dpi_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ast::Dpi
         let span = p.peek(0).1;
         p.add_diag(
             DiagBuilder2::error("expected `import` or `export` at the start of a DPI declaration")
+                .code("SV0016")
                 .span(span),
         );
         return Err(());
@@ -5416,6 +5744,7 @@ fn parse_import_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Impor
                     DiagBuilder2::error(
                         "expected identifier or `*` after `::` in import declaration",
                     )
+                    .code("SV0016")
                     .span(sp),
                 );
                 Err(())
@@ -5481,7 +5810,11 @@ fn parse_assertion<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Asserti
         Keyword(Kw::Cover) if is_sequence => {
             p.bump();
             p.bump();
-            p.add_diag(DiagBuilder2::error("Don't know how to parse cover sequences").span(span));
+            p.add_diag(
+                DiagBuilder2::error("Don't know how to parse cover sequences")
+                    .code("SV0018")
+                    .span(span),
+            );
             return Err(());
             // AssertionData::Concurrent(ConcurrentAssertion::CoverSequence)
         }
@@ -5565,6 +5898,7 @@ fn parse_assertion<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Asserti
         _ => {
             p.add_diag(
                 DiagBuilder2::error("expected assert, assume, cover, expect, or restrict")
+                    .code("SV0016")
                     .span(span),
             );
             return Err(());
@@ -5707,7 +6041,11 @@ fn parse_propexpr_nonseq<'n>(
 
         _ => {
             let q = p.peek(0).1;
-            p.add_diag(DiagBuilder2::error("expected primary property expression").span(q));
+            p.add_diag(
+                DiagBuilder2::error("expected primary property expression")
+                    .code("SV0016")
+                    .span(q),
+            );
             return Err(());
         }
     }
@@ -5816,6 +6154,7 @@ fn parse_seqexpr_expr<'n>(
         DiagBuilder2::error(
             "Don't know how to parse sequence expression that don't start with an expression",
         )
+        .code("SV0018")
         .span(q),
     );
     Err(())
@@ -5888,6 +6227,7 @@ fn parse_seqrep<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<SeqRep<'n>
                 DiagBuilder2::error(
                     "expected sequence repetition [+], [*], [* <expr>], [= <expr>], or [-> <expr>]",
                 )
+                .code("SV0016")
                 .span(q),
             );
             Err(())
@@ -6014,6 +6354,7 @@ fn parse_param_decl<'n>(
                     "expected `parameter` or `localparam`, but found {} instead",
                     tkn
                 ))
+                .code("SV0016")
                 .span(sp),
             );
             return Err(());
@@ -6114,6 +6455,84 @@ fn parse_param_decl<'n>(
     ))
 }
 
+/// Parse a specparam declaration.
+///
+/// ```text
+/// specparam_decl: "specparam" [packed_dimension] specparam_assignment {"," specparam_assignment}
+/// specparam_assignment: ident "=" expr
+/// ```
+///
+/// Specparams cannot be overridden and are thus treated as localparams.
+fn parse_specparam_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ast::ParamDecl<'n>> {
+    let mut span = p.peek(0).1;
+    p.require_reported(Keyword(Kw::Specparam))?;
+    let decls = comma_list_nonempty(p, Semicolon, "specparam name", |p| {
+        let ty = parse_implicit_type(p)?;
+        let mut span = p.peek(0).1;
+        let name = parse_identifier_name(p, "specparam name")?;
+        p.require_reported(Operator(Op::Assign))?;
+        let expr = parse_expr(p)?;
+        span.expand(p.last_span());
+        Ok(ast::ParamValueDecl::new(
+            span,
+            ast::ParamValueDeclData {
+                ty,
+                name,
+                dims: vec![],
+                expr: Some(expr),
+            },
+        ))
+    })?;
+    span.expand(p.last_span());
+    Ok(ast::ParamDecl::new(
+        span,
+        ast::ParamDeclData {
+            local: true,
+            kind: ast::ParamKind::Value(decls),
+        },
+    ))
+}
+
+/// Parse a specify block.
+///
+/// ```text
+/// specify_block: "specify" {specify_item} "endspecify"
+/// specify_item: specparam_decl | path_decl | system_timing_check | ...
+/// ```
+///
+/// Only the specparam declarations are retained. Path declarations and timing
+/// checks have no bearing on the elaborated design and are skipped.
+fn parse_specify_block<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemData<'n>> {
+    let mut span = p.peek(0).1;
+    p.require_reported(Keyword(Kw::Specify))?;
+    let mut items = vec![];
+    let mut skipped = 0;
+    while p.peek(0).0 != Keyword(Kw::Endspecify) && p.peek(0).0 != Eof {
+        let mut item_span = p.peek(0).1;
+        if p.peek(0).0 == Keyword(Kw::Specparam) {
+            let decl = recovered(p, Semicolon, parse_specparam_decl)?;
+            p.require_reported(Semicolon)?;
+            item_span.expand(p.last_span());
+            items.push(Item::new(item_span, ItemData::ParamDecl(decl)));
+        } else {
+            p.recover_balanced(&[Semicolon], true);
+            skipped += 1;
+        }
+    }
+    p.require_reported(Keyword(Kw::Endspecify))?;
+    span.expand(p.last_span());
+    if skipped > 0 {
+        p.add_diag(
+            DiagBuilder2::warning(format!(
+                "ignoring {} path declaration(s) and timing check(s) in specify block",
+                skipped
+            ))
+            .span(span),
+        );
+    }
+    Ok(ItemData::SpecifyBlock(span, items))
+}
+
 fn parse_hname<'n>(p: &mut dyn AbstractParser<'n>, msg: &str) -> ReportedResult<ast::Identifier> {
     parse_identifier(p, msg)
 }
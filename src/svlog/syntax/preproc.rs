@@ -4,10 +4,13 @@
 //! tokens generated by a lexer and performs include and macro
 //! resolution.
 
+use crate::ast::{DefaultNettype, MacroUse, NetType};
 use crate::cat::*;
 use moore_common::errors::{DiagBuilder2, DiagResult2};
+use moore_common::name::get_name_table;
 use moore_common::source::*;
-use std::{collections::HashMap, fmt, path::Path, rc::Rc};
+use moore_common::util::find_similar;
+use std::{collections::HashMap, fmt, path::Path, sync::Arc};
 
 use once_cell::sync::Lazy;
 
@@ -22,7 +25,7 @@ pub struct Preprocessor<'a> {
     /// Keeping these around ensures that all emitted tokens remain valid (and
     /// point to valid memory locations) at least until the preprocessor is
     /// dropped.
-    contents: Vec<Rc<dyn SourceContent>>,
+    contents: Vec<Arc<dyn SourceContent>>,
     /// The current token, or None if either the end of the stream has been
     /// encountered, or at the beginning when no token has been read yet.
     token: Option<TokenAndSpan>,
@@ -39,6 +42,10 @@ pub struct Preprocessor<'a> {
     defcond_stack: Vec<Defcond>,
     /// Currently enabled directives.
     dirs: Directives,
+    /// The `default_nettype directives encountered so far.
+    nettypes: Vec<DefaultNettype>,
+    /// The macro definitions and expansions encountered so far.
+    macro_uses: Vec<MacroUse>,
 }
 
 impl<'a> Preprocessor<'a> {
@@ -88,9 +95,21 @@ impl<'a> Preprocessor<'a> {
             include_paths: include_paths,
             defcond_stack: Vec::new(),
             dirs: Default::default(),
+            nettypes: Vec::new(),
+            macro_uses: Vec::new(),
         }
     }
 
+    /// Take the `default_nettype directives encountered so far.
+    pub fn take_default_nettypes(&mut self) -> Vec<DefaultNettype> {
+        std::mem::take(&mut self.nettypes)
+    }
+
+    /// Take the macro definitions and expansions encountered so far.
+    pub fn take_macro_uses(&mut self) -> Vec<MacroUse> {
+        std::mem::take(&mut self.macro_uses)
+    }
+
     /// Advance to the next token in the input stream.
     fn bump(&mut self) {
         self.token = self.macro_stack.pop();
@@ -154,6 +173,7 @@ impl<'a> Preprocessor<'a> {
                             "expected filename inside double quotes (\"...\") or angular brackets \
                              (<...>) after `include",
                         )
+                        .code("SV0020")
                         .span(span)),
                     };
 
@@ -169,6 +189,7 @@ impl<'a> Preprocessor<'a> {
                             return Err(DiagBuilder2::fatal(
                                 "expected end of included file's name before line break",
                             )
+                            .code("SV0020")
                             .span(sp));
                         }
                         Some((_, sp)) => {
@@ -180,6 +201,7 @@ impl<'a> Preprocessor<'a> {
                                 "expected filename after `include directive before the end of the \
                                  input",
                             )
+                            .code("SV0020")
                             .span(span));
                         }
                     }
@@ -196,6 +218,7 @@ impl<'a> Preprocessor<'a> {
                             "cannot open included file \"{}\"",
                             filename
                         ))
+                        .code("SV0021")
                         .span(Span::union(name_p, name_q)));
                     }
                 };
@@ -226,6 +249,11 @@ impl<'a> Preprocessor<'a> {
 
                 let makro = self.handle_macro_definition(span)?;
 
+                self.macro_uses.push(MacroUse {
+                    span: makro.span,
+                    name: get_name_table().intern(&makro.name, true),
+                    def: true,
+                });
                 self.macro_defs.insert(makro.name.clone(), makro);
                 return Ok(());
             }
@@ -245,9 +273,9 @@ impl<'a> Preprocessor<'a> {
                 let (name, _) = match self.try_eat_name() {
                     Some(x) => x,
                     None => {
-                        return Err(
-                            DiagBuilder2::fatal("expected macro name after \"`undef\"").span(span)
-                        );
+                        return Err(DiagBuilder2::fatal("expected macro name after \"`undef\"")
+                            .code("SV0020")
+                            .span(span));
                     }
                 };
 
@@ -278,6 +306,7 @@ impl<'a> Preprocessor<'a> {
                             "expected macro name after {}",
                             dir_name
                         ))
+                        .code("SV0020")
                         .span(span));
                     }
                 };
@@ -319,6 +348,7 @@ impl<'a> Preprocessor<'a> {
                                     "found `elsif without any preceeding `ifdef, `ifndef, or \
                                      `elsif directive",
                                 )
+                                .code("SV0020")
                                 .span(span))
                             }
                         };
@@ -340,6 +370,7 @@ impl<'a> Preprocessor<'a> {
                             "found `else without any preceeding `ifdef, `ifndef, or `elsif \
                              directive",
                         )
+                        .code("SV0020")
                         .span(span))
                     }
                 }
@@ -352,6 +383,7 @@ impl<'a> Preprocessor<'a> {
                         "found `endif without any preceeding `ifdef, `ifndef, `else, or `elsif \
                          directive",
                     )
+                    .code("SV0020")
                     .span(span));
                 }
                 return Ok(());
@@ -370,6 +402,11 @@ impl<'a> Preprocessor<'a> {
                     .macro_defs
                     .get(dir_name)
                 {
+                    self.macro_uses.push(MacroUse {
+                        span,
+                        name: get_name_table().intern(dir_name, true),
+                        def: false,
+                    });
                     let args = self.handle_macro_expansion_args(makro, span)?;
 
                     // Now we have a problem. All the tokens of the macro name
@@ -480,16 +517,41 @@ impl<'a> Preprocessor<'a> {
                             return Err(DiagBuilder2::fatal(
                                 "expected nettype after `default_nettype",
                             )
+                            .code("SV0020")
                             .span(span));
                         }
                     };
 
                     // Store the nettype in the directive set.
-                    self.dirs.default_nettype = if tkn.1.extract() == "none" {
-                        None
-                    } else {
-                        Some(tkn)
+                    let text = tkn.1.extract();
+                    let net_type = match text.as_str() {
+                        "none" => None,
+                        "supply0" => Some(NetType::Supply0),
+                        "supply1" => Some(NetType::Supply1),
+                        "tri" => Some(NetType::Tri),
+                        "triand" => Some(NetType::TriAnd),
+                        "trior" => Some(NetType::TriOr),
+                        "trireg" => Some(NetType::TriReg),
+                        "tri0" => Some(NetType::Tri0),
+                        "tri1" => Some(NetType::Tri1),
+                        "uwire" => Some(NetType::Uwire),
+                        "wire" => Some(NetType::Wire),
+                        "wand" => Some(NetType::WireAnd),
+                        "wor" => Some(NetType::WireOr),
+                        _ => {
+                            return Err(DiagBuilder2::fatal(format!(
+                                "unknown nettype `{}` after `default_nettype",
+                                text
+                            ))
+                            .code("SV0020")
+                            .span(tkn.1));
+                        }
                     };
+                    self.nettypes.push(DefaultNettype {
+                        span: Span::union(span, tkn.1),
+                        net_type,
+                    });
+                    self.dirs.default_nettype = net_type.map(|_| tkn);
                     debug!(
                         "Set default_nettype to `{}`",
                         self.dirs
@@ -514,6 +576,7 @@ impl<'a> Preprocessor<'a> {
                         Some((Symbol('"'), _)) => self.bump(),
                         _ => {
                             return Err(DiagBuilder2::fatal("expected `\"` after `begin_keywords")
+                                .code("SV0020")
                                 .span(span));
                         }
                     };
@@ -535,6 +598,7 @@ impl<'a> Preprocessor<'a> {
                             return Err(DiagBuilder2::fatal(
                                 "expected `\"` after version specifier",
                             )
+                            .code("SV0020")
                             .span(span));
                         }
                     };
@@ -547,6 +611,7 @@ impl<'a> Preprocessor<'a> {
                                 "unknown `begin_keywords version specifier `{}`",
                                 spec
                             ))
+                            .code("SV0020")
                             .span(span));
                         }
                     };
@@ -562,6 +627,7 @@ impl<'a> Preprocessor<'a> {
                         return Err(DiagBuilder2::fatal(
                             "`end_keywords without earlier `begin_keywords",
                         )
+                        .code("SV0020")
                         .span(span));
                     }
                     debug!("Pop keywords; now `{:?}`", self.dirs.keywords.last());
@@ -585,9 +651,9 @@ impl<'a> Preprocessor<'a> {
                             sp
                         }
                         _ => {
-                            return Err(
-                                DiagBuilder2::fatal("expected line number after `line").span(span)
-                            );
+                            return Err(DiagBuilder2::fatal("expected line number after `line")
+                                .code("SV0020")
+                                .span(span));
                         }
                     };
 
@@ -604,6 +670,7 @@ impl<'a> Preprocessor<'a> {
                             return Err(DiagBuilder2::fatal(
                                 "expected `\"` after line number in `line",
                             )
+                            .code("SV0020")
                             .span(span));
                         }
                     };
@@ -625,6 +692,7 @@ impl<'a> Preprocessor<'a> {
                             return Err(DiagBuilder2::fatal(
                                 "expected `\"` after filename in `line",
                             )
+                            .code("SV0020")
                             .span(span));
                         }
                     };
@@ -643,9 +711,9 @@ impl<'a> Preprocessor<'a> {
                             sp
                         }
                         _ => {
-                            return Err(
-                                DiagBuilder2::fatal("expected level after `line").span(span)
-                            );
+                            return Err(DiagBuilder2::fatal("expected level after `line")
+                                .code("SV0020")
+                                .span(span));
                         }
                     };
 
@@ -674,6 +742,7 @@ impl<'a> Preprocessor<'a> {
                             return Err(DiagBuilder2::fatal(
                                 "expected `pull0` or `pull1` after `unconnected_drive",
                             )
+                            .code("SV0020")
                             .span(span));
                         }
                     };
@@ -695,9 +764,13 @@ impl<'a> Preprocessor<'a> {
             }
         }
 
-        return Err(
-            DiagBuilder2::fatal(format!("unknown compiler directive '`{}'", dir_name)).span(span),
-        );
+        let mut d = DiagBuilder2::fatal(format!("unknown compiler directive '`{}'", dir_name))
+            .code("SV0020")
+            .span(span);
+        if let Some(similar) = find_similar(dir_name, self.macro_defs.keys()) {
+            d = d.add_note(format!("did you mean `{}`?", similar));
+        }
+        return Err(d);
     }
 
     fn open_include(&mut self, filename: &str, current_file: &str) -> Option<Source> {
@@ -773,9 +846,9 @@ impl<'a> Preprocessor<'a> {
         let (name, name_span) = match self.try_eat_name() {
             Some(x) => x,
             None => {
-                return Err(
-                    DiagBuilder2::fatal("expected macro name after \"`define\"").span(define_span)
-                );
+                return Err(DiagBuilder2::fatal("expected macro name after \"`define\"")
+                    .code("SV0020")
+                    .span(define_span));
             }
         };
         all_span.expand(define_span);
@@ -838,9 +911,9 @@ impl<'a> Preprocessor<'a> {
                     break;
                 }
                 None => {
-                    return Err(
-                        DiagBuilder2::fatal("expected `)` after macro arguments").span(all_span)
-                    )
+                    return Err(DiagBuilder2::fatal("expected `)` after macro arguments")
+                        .code("SV0020")
+                        .span(all_span))
                 }
                 _ => (),
             }
@@ -849,9 +922,9 @@ impl<'a> Preprocessor<'a> {
             let (name, name_span) = match self.try_eat_name() {
                 Some(x) => x,
                 _ => {
-                    return Err(
-                        DiagBuilder2::fatal("expected macro argument name").span(all_span.end())
-                    );
+                    return Err(DiagBuilder2::fatal("expected macro argument name")
+                        .code("SV0020")
+                        .span(all_span.end()));
                 }
             };
             self.skip_whitespace();
@@ -917,7 +990,9 @@ impl<'a> Preprocessor<'a> {
                 Some((Symbol(')'), _)) => (),
                 Some((_, sp)) => {
                     return Err(
-                        DiagBuilder2::fatal("expected `,` or `)` after macro argument").span(sp),
+                        DiagBuilder2::fatal("expected `,` or `)` after macro argument")
+                            .code("SV0020")
+                            .span(sp),
                     )
                 }
                 None => (),
@@ -951,6 +1026,7 @@ impl<'a> Preprocessor<'a> {
                     "expected macro arguments for `{}`",
                     makro.name
                 ))
+                .code("SV0022")
                 .span(all_span)
                 .add_note(format!(
                     "At least parenthesis are needed: `{}()`",
@@ -1014,6 +1090,7 @@ impl<'a> Preprocessor<'a> {
                     }
                     None => {
                         return Err(DiagBuilder2::fatal("expected `)` after macro arguments")
+                            .code("SV0022")
                             .span(all_span));
                     }
                 }
@@ -1038,6 +1115,7 @@ impl<'a> Preprocessor<'a> {
                 makro.name,
                 makro.args.len()
             ))
+            .code("SV0022")
             .span(all_span)
             .add_note(format!("Definition of `{}` was here:", makro.name))
             .span(makro.span);
@@ -1066,6 +1144,7 @@ impl<'a> Preprocessor<'a> {
                             "macro expansion missing value for `{}`",
                             def.name
                         ))
+                        .code("SV0022")
                         .span(all_span)
                         .add_note(format!(
                             "Macro argument `{}` needs a value because it has no default:",
@@ -1132,6 +1211,7 @@ impl<'a> Iterator for Preprocessor<'a> {
                         return Some(Err(DiagBuilder2::fatal(
                             "expected compiler directive after '`', or '``', '`\"', or '`\\'",
                         )
+                        .code("SV0020")
                         .span(sp_backtick)));
                     }
                 }
@@ -1315,15 +1395,11 @@ mod tests {
     use super::*;
 
     fn preproc(input: &str) -> Preprocessor {
-        use std::cell::Cell;
-        thread_local!(static INDEX: Cell<usize> = Cell::new(0));
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static INDEX: AtomicUsize = AtomicUsize::new(0);
         let sm = get_source_manager();
-        let idx = INDEX.with(|i| {
-            let v = i.get();
-            i.set(v + 1);
-            v
-        });
-        let source = sm.add(&format!("test_{}.sv", idx), input);
+        let idx = INDEX.fetch_add(1, Ordering::SeqCst);
+        let source = sm.add(&format!("preproc_test_{}.sv", idx), input);
         Preprocessor::new(source, &[], &[])
     }
 
@@ -1336,9 +1412,9 @@ mod tests {
     #[test]
     fn include() {
         let sm = get_source_manager();
-        sm.add("other.sv", "bar\n");
-        sm.add("test.sv", "foo\n`include \"other.sv\"\nbaz");
-        let pp = Preprocessor::new(sm.open("test.sv").unwrap(), &[], &[]);
+        sm.add("include_other.sv", "bar\n");
+        sm.add("include_test.sv", "foo\n`include \"include_other.sv\"\nbaz");
+        let pp = Preprocessor::new(sm.open("include_test.sv").unwrap(), &[], &[]);
         let actual: Vec<_> = pp.map(|x| x.unwrap().0).collect();
         assert_eq!(actual, &[Text, Newline, Text, Newline, Newline, Text,]);
     }
@@ -1346,12 +1422,15 @@ mod tests {
     #[test]
     fn include_and_define() {
         let sm = get_source_manager();
-        sm.add("other.sv", "/* World */\n`define foo 42\nbar");
         sm.add(
-            "test.sv",
-            "// Hello\n`include \"other.sv\"\n`foo something\n",
+            "include_and_define_other.sv",
+            "/* World */\n`define foo 42\nbar",
+        );
+        sm.add(
+            "include_and_define_test.sv",
+            "// Hello\n`include \"include_and_define_other.sv\"\n`foo something\n",
         );
-        let pp = Preprocessor::new(sm.open("test.sv").unwrap(), &[], &[]);
+        let pp = Preprocessor::new(sm.open("include_and_define_test.sv").unwrap(), &[], &[]);
         let actual: String = pp
             .map(|x| {
                 let x = x.unwrap();
@@ -1366,7 +1445,10 @@ mod tests {
     #[should_panic(expected = "unknown compiler directive")]
     fn conditional_define() {
         let sm = get_source_manager();
-        let source = sm.add("test.sv", "`ifdef FOO\n`define BAR\n`endif\n`BAR");
+        let source = sm.add(
+            "conditional_define.sv",
+            "`ifdef FOO\n`define BAR\n`endif\n`BAR",
+        );
         let mut pp = Preprocessor::new(source, &[], &[]);
         while let Some(tkn) = pp.next() {
             tkn.unwrap();
@@ -1395,4 +1477,28 @@ mod tests {
     fn macro_name_with_digits_and_underscores() {
         check_str("`define AXI_BUS21_SV 42\n`AXI_BUS21_SV", "42");
     }
+
+    #[test]
+    fn macro_uses() {
+        let sm = get_source_manager();
+        let source = sm.add("macro_uses.sv", "`define FOO 4\n`define BAR `FOO\n`BAR\n");
+        let mut pp = Preprocessor::new(source, &[], &[]);
+        while let Some(tkn) = pp.next() {
+            tkn.unwrap();
+        }
+        let actual: Vec<_> = pp
+            .take_macro_uses()
+            .into_iter()
+            .map(|x| (x.name.to_string(), x.def, x.span.extract()))
+            .collect();
+        assert_eq!(
+            actual,
+            &[
+                ("FOO".to_string(), true, "FOO".to_string()),
+                ("BAR".to_string(), true, "BAR".to_string()),
+                ("BAR".to_string(), false, "`BAR".to_string()),
+                ("FOO".to_string(), false, "`FOO".to_string()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,711 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A formatter for SystemVerilog source files.
+//!
+//! The formatter reprints the source text rather than the AST, such that
+//! comments, macro uses, and preprocessor directives are preserved exactly.
+//! Only the whitespace between tokens changes: lines are reindented according
+//! to the nesting of blocks and parentheses, runs of spaces are collapsed,
+//! trailing whitespace and repeated blank lines are removed. The spans of the
+//! parsed AST locate the ports, port connections, and assignments, which are
+//! aligned into columns across consecutive lines.
+
+use crate::ast::{self, AcceptVisitor as _};
+use moore_common::source::{Source, Span};
+use std::collections::HashMap;
+
+/// Options that control how a file is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The number of spaces per level of indentation.
+    pub indent: usize,
+    /// Whether to align ports, port connections, and assignments on
+    /// consecutive lines into columns.
+    pub align: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: 4,
+            align: true,
+        }
+    }
+}
+
+/// Format a source file.
+///
+/// `file` is the AST parsed from `source`, which determines the columns to
+/// align.
+pub fn format<'a>(source: Source, file: &'a ast::SourceFile<'a>, opts: &FormatOptions) -> String {
+    let content = source.get_content();
+    let text = content.extract(0, content.bytes().len());
+    let tokens = scan(&text);
+    let mut lines = layout(&text, &tokens);
+    if opts.align {
+        let mut collector = AnchorCollector {
+            source,
+            tokens: &tokens,
+            anchors: HashMap::new(),
+        };
+        file.accept(&mut collector);
+        align(&text, &mut lines, &collector.anchors, opts);
+    }
+    render(&text, &lines, opts)
+}
+
+/// A token of the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token {
+    kind: TokenKind,
+    begin: usize,
+    end: usize,
+}
+
+/// The kinds of tokens the formatter distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    /// A line break.
+    Newline,
+    /// A run of whitespace other than line breaks.
+    Space,
+    /// A single-line or multi-line comment.
+    Comment,
+    /// An identifier, keyword, number, string, or compiler directive.
+    Word,
+    /// A single punctuation character.
+    Symbol(char),
+    /// A macro definition, which is reprinted as is.
+    Verbatim,
+}
+
+/// Split the source text into tokens. Concatenating the tokens yields the
+/// original text.
+fn scan(text: &str) -> Vec<Token> {
+    let bytes = text.as_bytes();
+    let len = bytes.len();
+    let is_space = |c: u8| c == b' ' || c == b'\t' || c == b'\r' || c == 0x0b || c == 0x0c;
+    let is_word = |c: u8| c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80;
+    let line_end = |mut i: usize| {
+        while i < len && bytes[i] != b'\n' {
+            i += 1;
+        }
+        i
+    };
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < len {
+        let begin = i;
+        let c = bytes[i];
+        let next = bytes.get(i + 1).cloned();
+        let kind = if c == b'\n' {
+            i += 1;
+            TokenKind::Newline
+        } else if is_space(c) {
+            while i < len && is_space(bytes[i]) {
+                i += 1;
+            }
+            TokenKind::Space
+        } else if c == b'/' && next == Some(b'/') {
+            i = line_end(i);
+            TokenKind::Comment
+        } else if c == b'/' && next == Some(b'*') {
+            i = text[i + 2..].find("*/").map(|x| i + x + 4).unwrap_or(len);
+            TokenKind::Comment
+        } else if c == b'"' {
+            i += 1;
+            while i < len && bytes[i] != b'"' && bytes[i] != b'\n' {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += text[i..].chars().next().map(char::len_utf8).unwrap_or(0);
+            }
+            i = (i + 1).min(len);
+            TokenKind::Word
+        } else if text[i..].starts_with("`define") {
+            // Consume the macro body up to the first line not continued with
+            // a backslash.
+            loop {
+                i = line_end(i);
+                if text[..i].trim_end_matches('\r').ends_with('\\') && i < len {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            TokenKind::Verbatim
+        } else if c == b'\\' {
+            // Escaped identifiers extend up to the next whitespace.
+            while i < len && !is_space(bytes[i]) && bytes[i] != b'\n' {
+                i += 1;
+            }
+            TokenKind::Word
+        } else if is_word(c) || (c == b'`' && next.map(is_word).unwrap_or(false)) {
+            i += 1;
+            while i < len && is_word(bytes[i]) {
+                i += 1;
+            }
+            TokenKind::Word
+        } else {
+            let c = text[i..].chars().next().unwrap();
+            i += c.len_utf8();
+            TokenKind::Symbol(c)
+        };
+        tokens.push(Token {
+            kind,
+            begin,
+            end: i,
+        });
+    }
+    tokens
+}
+
+/// A line of formatted output.
+#[derive(Debug, Default)]
+struct Line {
+    /// The level of indentation.
+    indent: usize,
+    /// The tokens on the line, other than whitespace.
+    items: Vec<Item>,
+}
+
+/// A token placed on a line of formatted output.
+#[derive(Debug, Clone, Copy)]
+struct Item {
+    token: Token,
+    /// Whether the token was preceded by whitespace in the input.
+    space: bool,
+    /// The number of spaces inserted before the token to align it.
+    pad: usize,
+}
+
+/// A level of nesting that indents the lines it contains.
+#[derive(Debug)]
+struct Frame {
+    /// The number of parentheses, brackets, and braces this frame accounts
+    /// for, or zero for a block such as `begin`/`end`.
+    parens: usize,
+    /// The line on which the frame was opened.
+    line: usize,
+    /// The level of indentation of the lines within the frame.
+    indent: usize,
+    /// The level of indentation of the line that opened the frame, which is
+    /// also the one of the line that closes it.
+    outer: usize,
+}
+
+/// Keywords that open a block closed by another keyword.
+static OPENERS: &[&str] = &[
+    "begin",
+    "case",
+    "casex",
+    "casez",
+    "checker",
+    "class",
+    "clocking",
+    "config",
+    "covergroup",
+    "fork",
+    "function",
+    "generate",
+    "interface",
+    "macromodule",
+    "module",
+    "package",
+    "primitive",
+    "program",
+    "property",
+    "randcase",
+    "randsequence",
+    "sequence",
+    "specify",
+    "table",
+    "task",
+];
+
+/// Keywords that close a block.
+static CLOSERS: &[&str] = &[
+    "end",
+    "endcase",
+    "endchecker",
+    "endclass",
+    "endclocking",
+    "endconfig",
+    "endfunction",
+    "endgenerate",
+    "endgroup",
+    "endinterface",
+    "endmodule",
+    "endpackage",
+    "endprimitive",
+    "endprogram",
+    "endproperty",
+    "endsequence",
+    "endspecify",
+    "endtable",
+    "endtask",
+    "join",
+    "join_any",
+    "join_none",
+];
+
+/// Split the tokens into lines and determine the indentation of each line.
+fn layout(text: &str, tokens: &[Token]) -> Vec<Line> {
+    let mut lines = vec![];
+    let mut line = Line::default();
+    let mut space = false;
+    for &token in tokens {
+        match token.kind {
+            TokenKind::Newline => {
+                lines.push(std::mem::take(&mut line));
+                space = false;
+            }
+            TokenKind::Space => space = true,
+            _ => {
+                line.items.push(Item {
+                    token,
+                    space: space && !line.items.is_empty(),
+                    pad: 0,
+                });
+                space = false;
+            }
+        }
+    }
+    if !line.items.is_empty() {
+        lines.push(line);
+    }
+
+    // Track the nesting of blocks and parentheses. The lines within a frame
+    // are indented by one level relative to the line that opened it, and the
+    // line that closes it returns to that level. A line that does not
+    // complete its statement indents the next line by one more level.
+    let mut frames: Vec<Frame> = vec![];
+    let mut cont = false;
+    let mut modifier = false;
+    for (index, line) in lines.iter_mut().enumerate() {
+        let first = match line.items.first() {
+            Some(item) => item.token,
+            None => continue,
+        };
+        let base = frames.last().map(|f| f.indent).unwrap_or(0);
+        let first_text = &text[first.begin..first.end];
+        if first_text.starts_with('`') || first.kind == TokenKind::Verbatim {
+            line.indent = base;
+            continue;
+        }
+        let in_parens = frames.last().map(|f| f.parens > 0).unwrap_or(false);
+        line.indent = base + (cont && !in_parens && first_text != "begin") as usize;
+        let mut leading = true;
+        let mut blocks = false;
+        let mut last: Option<&str> = None;
+        let mut prev: Option<&str> = None;
+        for (i, item) in line.items.iter().enumerate() {
+            let token = item.token;
+            let word = &text[token.begin..token.end];
+            match token.kind {
+                TokenKind::Symbol(')') | TokenKind::Symbol(']') | TokenKind::Symbol('}') => {
+                    if let Some(frame) = frames.last_mut() {
+                        if frame.parens > 0 {
+                            if leading {
+                                line.indent = frame.outer;
+                            }
+                            frame.parens -= 1;
+                            if frame.parens == 0 {
+                                frames.pop();
+                            }
+                        }
+                    }
+                }
+                TokenKind::Word if CLOSERS.contains(&word) => {
+                    while let Some(frame) = frames.pop() {
+                        if leading {
+                            line.indent = frame.outer;
+                        }
+                        if frame.parens == 0 {
+                            break;
+                        }
+                    }
+                    blocks = true;
+                }
+                TokenKind::Comment => (),
+                _ => leading = false,
+            }
+            match token.kind {
+                TokenKind::Symbol('(') | TokenKind::Symbol('[') | TokenKind::Symbol('{') => {
+                    match frames.last_mut() {
+                        Some(frame) if frame.parens > 0 && frame.line == index => frame.parens += 1,
+                        _ => frames.push(Frame {
+                            parens: 1,
+                            line: index,
+                            indent: line.indent + 1,
+                            outer: line.indent,
+                        }),
+                    }
+                }
+                TokenKind::Word if OPENERS.contains(&word) => {
+                    let next = line
+                        .items
+                        .get(i + 1)
+                        .map(|x| &text[x.token.begin..x.token.end]);
+                    let in_parens = frames.last().map(|f| f.parens > 0).unwrap_or(false);
+                    if opens_block(word, prev, next, modifier, in_parens) {
+                        frames.push(Frame {
+                            parens: 0,
+                            line: index,
+                            indent: line.indent + 1,
+                            outer: line.indent,
+                        });
+                        blocks = true;
+                    }
+                }
+                TokenKind::Word
+                    if ["extern", "pure", "import", "export", "typedef"].contains(&word) =>
+                {
+                    modifier = true
+                }
+                TokenKind::Symbol(';') => modifier = false,
+                _ => (),
+            }
+            if token.kind != TokenKind::Comment {
+                last = Some(word);
+                prev = Some(word);
+            }
+        }
+        match last {
+            Some(";") | Some("(") | Some("[") | Some("{") => cont = false,
+            Some(",") if frames.last().map(|f| f.parens > 0).unwrap_or(false) => cont = false,
+            Some(_) if blocks => cont = false,
+            Some(_) => cont = true,
+            None => (),
+        }
+    }
+    lines
+}
+
+/// Check whether a keyword that may open a block does so in its context.
+fn opens_block(
+    word: &str,
+    prev: Option<&str>,
+    next: Option<&str>,
+    modifier: bool,
+    in_parens: bool,
+) -> bool {
+    match word {
+        "function" | "task" | "class" => !modifier,
+        "interface" => !modifier && !in_parens && prev != Some("virtual") && next != Some("."),
+        "property" | "sequence" => !matches!(
+            prev,
+            Some("assert") | Some("assume") | Some("cover") | Some("expect") | Some("restrict")
+        ),
+        "fork" => !matches!(prev, Some("wait") | Some("disable")),
+        "clocking" => !(prev == Some("default") && next == Some("@")),
+        _ => true,
+    }
+}
+
+/// The kinds of constructs aligned into columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AlignKind {
+    /// The types and names of port declarations.
+    Port,
+    /// The opening parenthesis of named port connections.
+    Conn,
+    /// The operators of assignments and initializers.
+    Assign,
+}
+
+/// The tokens to align, by the offset of the token in the source, together
+/// with the column they belong to.
+type Anchors = HashMap<usize, (AlignKind, usize)>;
+
+/// A visitor that finds the tokens to align in the AST.
+struct AnchorCollector<'t> {
+    source: Source,
+    tokens: &'t [Token],
+    anchors: Anchors,
+}
+
+impl AnchorCollector<'_> {
+    /// Mark the token at the beginning of a span.
+    fn add(&mut self, span: Span, kind: AlignKind, column: usize) {
+        if span.source == self.source {
+            self.anchors.insert(span.begin, (kind, column));
+        }
+    }
+
+    /// Mark the first token after a span if it is a given symbol.
+    fn add_after(&mut self, span: Span, symbol: char, kind: AlignKind) {
+        if span.source != self.source {
+            return;
+        }
+        let index = self.tokens.partition_point(|t| t.begin < span.end);
+        let token = self.tokens[index..]
+            .iter()
+            .find(|t| t.kind != TokenKind::Space && t.kind != TokenKind::Comment);
+        if let Some(&token) = token {
+            if token.kind == TokenKind::Symbol(symbol) {
+                self.anchors.insert(token.begin, (kind, 0));
+            }
+        }
+    }
+
+    /// Mark the first `=` after a span, up to the end of the declaration.
+    fn add_assign_after(&mut self, span: Span) {
+        if span.source != self.source {
+            return;
+        }
+        let index = self.tokens.partition_point(|t| t.begin < span.end);
+        for token in &self.tokens[index..] {
+            match token.kind {
+                TokenKind::Symbol('=') => {
+                    self.anchors.insert(token.begin, (AlignKind::Assign, 0));
+                    return;
+                }
+                TokenKind::Symbol(';') | TokenKind::Symbol(',') | TokenKind::Symbol(')') => return,
+                _ => (),
+            }
+        }
+    }
+
+    /// Mark the type and name of a port declaration.
+    fn add_port(&mut self, ty: &ast::Type, name: Span) {
+        if ty.span.source == name.source && ty.span.begin < name.begin {
+            self.add(ty.span, AlignKind::Port, 0);
+        }
+        self.add(name, AlignKind::Port, 1);
+    }
+}
+
+impl<'a> ast::Visitor<'a> for AnchorCollector<'_> {
+    fn pre_visit_port(&mut self, node: &'a ast::Port<'a>) -> bool {
+        match node.data {
+            ast::PortData::Named {
+                dir: Some(_),
+                ref ty,
+                name,
+                ..
+            } => self.add_port(ty, name.span),
+            ast::PortData::Named { name, .. } => self.add(name.span, AlignKind::Port, 1),
+            _ => (),
+        }
+        true
+    }
+
+    fn pre_visit_port_decl(&mut self, node: &'a ast::PortDecl<'a>) -> bool {
+        if let Some(name) = node.names.first() {
+            self.add_port(&node.ty, name.name_span);
+        }
+        true
+    }
+
+    fn pre_visit_port_conn(&mut self, node: &'a ast::PortConn<'a>) -> bool {
+        match node.data {
+            ast::PortConnData::Named(name, ast::PortConnMode::Connected(_))
+            | ast::PortConnData::Named(name, ast::PortConnMode::Unconnected) => {
+                self.add_after(name.span, '(', AlignKind::Conn)
+            }
+            _ => (),
+        }
+        true
+    }
+
+    fn pre_visit_cont_assign(&mut self, node: &'a ast::ContAssign<'a>) -> bool {
+        for (lhs, _) in &node.assignments {
+            self.add_after(lhs.span, '=', AlignKind::Assign);
+        }
+        true
+    }
+
+    fn pre_visit_stmt(&mut self, node: &'a ast::Stmt<'a>) -> bool {
+        match node.kind {
+            ast::BlockingAssignStmt { ref lhs, .. }
+            | ast::NonblockingAssignStmt { ref lhs, .. } => {
+                let index = self.tokens.partition_point(|t| t.begin < lhs.span.end);
+                let op = self.tokens[index..]
+                    .iter()
+                    .find(|t| t.kind != TokenKind::Space && t.kind != TokenKind::Comment);
+                if let Some(&op) = op {
+                    if lhs.span.source == self.source && matches!(op.kind, TokenKind::Symbol(_)) {
+                        self.anchors.insert(op.begin, (AlignKind::Assign, 0));
+                    }
+                }
+            }
+            _ => (),
+        }
+        true
+    }
+
+    fn pre_visit_param_value_decl(&mut self, node: &'a ast::ParamValueDecl<'a>) -> bool {
+        if node.expr.is_some() {
+            self.add_assign_after(node.name.span);
+        }
+        true
+    }
+
+    fn pre_visit_var_decl_name(&mut self, node: &'a ast::VarDeclName<'a>) -> bool {
+        if node.init.is_some() {
+            self.add_assign_after(node.name_span);
+        }
+        true
+    }
+}
+
+/// Align the marked tokens on consecutive lines with the same indentation
+/// into columns.
+fn align(text: &str, lines: &mut [Line], anchors: &Anchors, opts: &FormatOptions) {
+    // Determine the marked tokens of every line. Lines with tokens of
+    // different kinds or with multiple tokens in one column are not aligned.
+    let marks: Vec<Option<(AlignKind, Vec<(usize, usize)>)>> = lines
+        .iter()
+        .map(|line| {
+            let mut kind = None;
+            let mut columns: Vec<(usize, usize)> = vec![];
+            for (i, item) in line.items.iter().enumerate() {
+                let (k, column) = match anchors.get(&item.token.begin) {
+                    Some(&x) => x,
+                    None => continue,
+                };
+                if i == 0 || kind.map(|x| x != k).unwrap_or(false) {
+                    return None;
+                }
+                if columns.iter().any(|&(c, _)| c >= column) {
+                    return None;
+                }
+                if line.items[..i]
+                    .iter()
+                    .any(|x| text[x.token.begin..x.token.end].contains('\n'))
+                {
+                    return None;
+                }
+                kind = Some(k);
+                columns.push((column, i));
+            }
+            kind.map(|k| (k, columns))
+        })
+        .collect();
+
+    let mut start = 0;
+    while start < lines.len() {
+        let (kind, indent) = match marks[start] {
+            Some((kind, _)) => (kind, lines[start].indent),
+            None => {
+                start += 1;
+                continue;
+            }
+        };
+        let mut end = start + 1;
+        while end < lines.len()
+            && lines[end].indent == indent
+            && marks[end].as_ref().map(|m| m.0) == Some(kind)
+        {
+            end += 1;
+        }
+        let num_columns = (start..end)
+            .flat_map(|i| marks[i].as_ref().unwrap().1.iter().map(|&(c, _)| c + 1))
+            .max()
+            .unwrap_or(0);
+        for column in 0..num_columns {
+            let positions: Vec<_> = (start..end)
+                .flat_map(|i| {
+                    let item = marks[i]
+                        .as_ref()
+                        .unwrap()
+                        .1
+                        .iter()
+                        .find(|&&(c, _)| c == column)
+                        .map(|&(_, item)| item);
+                    item.map(|item| (i, item, position(text, &lines[i], item, opts)))
+                })
+                .collect();
+            let target = positions.iter().map(|&(_, _, p)| p).max().unwrap_or(0);
+            for (i, item, p) in positions {
+                lines[i].items[item].pad += target - p;
+            }
+        }
+        start = end;
+    }
+}
+
+/// Determine the column at which a token of a line is printed.
+fn position(text: &str, line: &Line, index: usize, opts: &FormatOptions) -> usize {
+    let mut column = line.indent * opts.indent;
+    for item in &line.items[..index] {
+        column += item.space as usize + item.pad;
+        column += text[item.token.begin..item.token.end].chars().count();
+    }
+    let item = &line.items[index];
+    column + item.space as usize + item.pad
+}
+
+/// Print the formatted lines.
+fn render(text: &str, lines: &[Line], opts: &FormatOptions) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut blank = true;
+    for line in lines {
+        if line.items.is_empty() {
+            if !blank {
+                output.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        blank = false;
+        for _ in 0..line.indent * opts.indent {
+            output.push(' ');
+        }
+        for item in &line.items {
+            for _ in 0..item.space as usize + item.pad {
+                output.push(' ');
+            }
+            output.push_str(&text[item.token.begin..item.token.end]);
+        }
+        output.push('\n');
+    }
+    if blank && output.ends_with("\n\n") {
+        output.pop();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser, preproc::Preprocessor};
+    use moore_common::source::get_source_manager;
+
+    fn check(name: &str, input: &str, expected: &str) {
+        let source = get_source_manager().add(name, input);
+        let arena = ast::Arena::default();
+        let preproc = Preprocessor::new(source, &[], &[]);
+        let file = parser::parse(Lexer::new(preproc), &arena).unwrap();
+        let actual = format(source, &file, &FormatOptions::default());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn indentation() {
+        check(
+            "reformat_indentation.sv",
+            "module foo;\n  always_comb begin\nif (a)\n  b = 1;   // one\n      else begin\nb = 0;\n end\nend\n\n\n\n/* two\n   three */\nendmodule\n\n",
+            "module foo;\n    always_comb begin\n        if (a)\n            b = 1; // one\n        else begin\n            b = 0;\n        end\n    end\n\n    /* two\n   three */\nendmodule\n",
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        check(
+            "reformat_alignment.sv",
+            "module foo (\ninput logic [7:0] a,\noutput logic b\n);\nassign b = a[0];\nassign cc = a[1];\nbar i_bar (\n.x(a),\n.yyy(b)\n);\nendmodule\n",
+            "module foo (\n    input  logic [7:0] a,\n    output logic       b\n);\n    assign b  = a[0];\n    assign cc = a[1];\n    bar i_bar (\n        .x  (a),\n        .yyy(b)\n    );\nendmodule\n",
+        );
+    }
+
+    #[test]
+    fn preserves_tokens() {
+        check(
+            "reformat_preserves_tokens.sv",
+            "`define FOO(x) \\\n    x + 1\nmodule foo;\n    localparam string S = \"a;  b\";\n\n    wire \\a+b = `FOO(1);\nendmodule\n",
+            "`define FOO(x) \\\n    x + 1\nmodule foo;\n    localparam string S = \"a;  b\";\n\n    wire \\a+b = `FOO(1);\nendmodule\n",
+        );
+    }
+}
@@ -8,6 +8,7 @@ pub use self::DelimToken::*;
 pub use self::Lit::*;
 pub use self::Token::*;
 use moore_common::name::Name;
+use serde::Serialize;
 use std::fmt::{Display, Formatter, Result};
 
 /// A primary token emitted by the lexer.
@@ -117,7 +118,7 @@ pub enum DelimToken {
 }
 
 /// Abstract literals such as strings.
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub enum Lit {
     Str(Name),
     BasedInteger(Option<Name>, bool, char, Name),
@@ -125,12 +126,15 @@ pub enum Lit {
     UnbasedUnsized(char),
     /// A number given as integer and optional fractional part.
     Number(Name, Option<Name>),
+    /// A real number in scientific notation given as integer part, fractional
+    /// part, and signed exponent.
+    Real(Name, Option<Name>, Name),
     /// A time literal given as integer part, fractional part, and unit.
     Time(Name, Option<Name>, TimeUnit),
 }
 
 /// The unit of a time literal.
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub enum TimeUnit {
     Second,
     MilliSecond,
@@ -141,7 +145,7 @@ pub enum TimeUnit {
 }
 
 /// Operator symbols.
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub enum Op {
     // Assignment
     Assign,
@@ -88,6 +88,7 @@
 use crate::crate_prelude::*;
 use crate::{common::arenas::TypedArena, ParamEnv};
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::{
     cell::RefCell,
     collections::HashSet,
@@ -194,7 +195,7 @@ pub enum Domain {
 }
 
 /// Whether a type is signed or unsigned.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum Sign {
     /// A `signed` type.
     Signed,
@@ -1465,6 +1466,11 @@ impl<'a> UnpackedType<'a> {
         self.dims.is_empty() && self.resolve_full().core == UnpackedCore::String
     }
 
+    /// Check if this type is a real type, like `real` or `shortreal`.
+    pub fn is_real(&self) -> bool {
+        self.get_real().is_some()
+    }
+
     /// Check if this type will coalesce to a scalar type in LLHD, like `i42`.
     pub fn coalesces_to_llhd_scalar(&self) -> bool {
         self.get_packed()
@@ -1590,6 +1596,15 @@ impl<'a> UnpackedType<'a> {
         }
     }
 
+    /// Get the underlying real type, or `None` if the type is no real.
+    pub fn get_real(&self) -> Option<RealType> {
+        if self.dims.is_empty() {
+            self.resolve_full().core.get_real()
+        } else {
+            None
+        }
+    }
+
     /// Get the underlying module, or `None` if the type is not a module.
     pub fn get_module(&self) -> Option<&ModuleType<'a>> {
         if self.dims.is_empty() {
@@ -1750,6 +1765,15 @@ impl<'a> UnpackedCore<'a> {
         self.get_packed().and_then(|packed| packed.get_enum())
     }
 
+    /// Get the underlying real type, or `None` if the type is no real.
+    pub fn get_real(&self) -> Option<RealType> {
+        match *self {
+            UnpackedCore::Real(x) => Some(x),
+            UnpackedCore::Named { ty, .. } | UnpackedCore::Ref { ty, .. } => ty.get_real(),
+            _ => None,
+        }
+    }
+
     /// Get the underlying module, or `None` if the type is not a module.
     pub fn get_module(&self) -> Option<&ModuleType<'a>> {
         match *self {
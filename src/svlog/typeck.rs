@@ -32,6 +32,7 @@ use crate::crate_prelude::*;
 use crate::{
     call_mapping::CallArgSource,
     common::arenas::Alloc,
+    format::DisplayTask,
     hir::HirNode,
     port_list,
     resolver::{DefNode, InstTarget},
@@ -307,6 +308,7 @@ fn type_of_varlike<'a>(
             "`{}` has implicit type but is not initialized",
             ast_name.name
         ))
+        .code("SV0034")
         .span(ast_name.name_span)
         .add_note("specify a type or add an initial value"),
     );
@@ -356,6 +358,7 @@ pub(crate) fn type_of_value_param<'a>(
             "{} has implicit type but was not assigned and has no default",
             ast
         ))
+        .code("SV0034")
         .span(ast.human_span())
         .add_note("specify a type for the parameter; or")
         .add_note("add a default value for the parameter; or")
@@ -498,6 +501,7 @@ pub(crate) fn map_to_type<'a>(
                         None => {
                             cx.emit(
                                 DiagBuilder2::error(format!("{} is not a type", assigned_ast))
+                                    .code("SV0013")
                                     .span(assigned_ast.human_span())
                                     .add_note(format!(
                                         "Assigned to type parameter `{}` here:",
@@ -520,7 +524,8 @@ pub(crate) fn map_to_type<'a>(
             }
 
             // Otherwise complain.
-            let d = DiagBuilder2::error(format!("{} not assigned and has no default", ast,));
+            let d = DiagBuilder2::error(format!("{} not assigned and has no default", ast,))
+                .code("SV0008");
             let contexts = cx.param_env_contexts(env);
             for &context in &contexts {
                 cx.emit(
@@ -556,6 +561,7 @@ pub(crate) fn map_to_type_or_error<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("`{}` is not a type", ast.span().extract()))
+                    .code("SV0013")
                     .span(ast.span()),
             );
             error!("Offending node: {:#2?}", ast);
@@ -597,6 +603,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                 None => {
                     cx.emit(
                         DiagBuilder2::error(format!("cannot infer implicit type"))
+                            .code("SV0034")
                             .span(ast.span())
                             .add_note(
                                 "This usually indicates that a declaration is missing a default \
@@ -670,6 +677,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                     DiagBuilder2::error(format!(
                         "`signed` or `unsigned` goes before the struct contents"
                     ))
+                    .code("SV0035")
                     .span(ast.span()),
                 );
                 failed = true;
@@ -701,6 +709,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                                 "enum base type must be packed, but `{}` is unpacked",
                                 bty
                             ))
+                            .code("SV0035")
                             .span(ty.span()),
                         );
                         return UnpackedType::make_error();
@@ -764,6 +773,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                     None => {
                         cx.emit(
                             DiagBuilder2::error(format!("`{}` is not a package", pkg_name))
+                                .code("SV0029")
                                 .span(pkg_name.span)
                                 .add_note(format!("`{}` was declared here:", pkg_name))
                                 .span(def.node.span()),
@@ -782,6 +792,7 @@ pub(crate) fn packed_type_from_ast<'a>(
             _ => {
                 cx.emit(
                     DiagBuilder2::error(format!("`{}` is not a package", ty.span().extract()))
+                        .code("SV0029")
                         .span(ty.span()),
                 );
                 return UnpackedType::make_error();
@@ -795,32 +806,11 @@ pub(crate) fn packed_type_from_ast<'a>(
             let inner_ty = cx.packed_type_from_ast(Ref(ty), env, None);
             if let Some(intf) = inner_ty.get_interface() {
                 // Resolve the modport name within the interface.
-                let def = match cx.resolve_hierarchical_or_error(name, intf.ast) {
-                    Ok(def) => def,
+                let modport = match cx.resolve_modport(name, intf.ast) {
+                    Ok(x) => x.ast,
                     _ => return UnpackedType::make_error(),
                 };
 
-                // Make sure that we have selected a modport.
-                let modport = match def.node {
-                    DefNode::Ast(node) => node.as_all().get_modport_name(),
-                    _ => None,
-                };
-                let modport = match modport {
-                    Some(x) => x,
-                    None => {
-                        cx.emit(
-                            DiagBuilder2::error(format!(
-                                "`{}` is not a modport of {}",
-                                name, intf.ast
-                            ))
-                            .span(name.span)
-                            .add_note(format!("`{}` was defined here:", name))
-                            .span(def.name.span),
-                        );
-                        return UnpackedType::make_error();
-                    }
-                };
-
                 // Package up a new interface type with the modport annotated.
                 let new_intf = ty::InterfaceType {
                     modport: Some(modport),
@@ -830,6 +820,7 @@ pub(crate) fn packed_type_from_ast<'a>(
             } else {
                 cx.emit(
                     DiagBuilder2::error(format!("`{}` is not an interface", ty.span().extract()))
+                        .code("SV0029")
                         .span(ty.span()),
                 );
                 error!("Offending AST: {:#2?}", ast);
@@ -896,6 +887,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                             DiagBuilder2::error(format!(
                                 "unpacked dimension in the position of a packed dimension",
                             ))
+                            .code("SV0035")
                             .span(ast.span()),
                         );
                         failed = true;
@@ -915,6 +907,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                         "unpacked type `{}` cannot be signed or unsigned",
                         core
                     ))
+                    .code("SV0035")
                     .span(ast.span()),
                 );
                 failed = true;
@@ -925,6 +918,7 @@ pub(crate) fn packed_type_from_ast<'a>(
                         "unpacked type `{}` cannot have packed dimensions",
                         core
                     ))
+                    .code("SV0035")
                     .span(ast.span()),
                 );
                 failed = true;
@@ -958,6 +952,7 @@ fn packed_type_from_def<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("`{}` is not a type", def.name))
+                    .code("SV0013")
                     .span(span)
                     .add_note(format!("`{}` was declared here:", def.name))
                     .span(def.node.span()),
@@ -1075,6 +1070,7 @@ pub(crate) fn type_of_expr<'a>(
         // These expressions have a fully self-determined type.
         hir::ExprKind::IntConst { .. }
         | hir::ExprKind::TimeConst(..)
+        | hir::ExprKind::RealConst(..)
         | hir::ExprKind::StringConst(..)
         | hir::ExprKind::Ident(..)
         | hir::ExprKind::Scope(..)
@@ -1091,6 +1087,16 @@ pub(crate) fn type_of_expr<'a>(
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Dimensions(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::UnpackedDimensions(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Rtoi(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Itor(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::RealToBits(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::BitsToReal(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ShortRealToBits(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::BitsToShortReal(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Display(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem(..))
         | hir::ExprKind::Field(..)
         | hir::ExprKind::Index(..)
         | hir::ExprKind::Assign { .. } => cx.need_self_determined_type(expr.id, env),
@@ -1231,6 +1237,9 @@ pub(crate) fn cast_expr_type<'a>(
                 CastOp::PickModport => format!("implicitly picking modport `{}`", ty),
                 CastOp::PackString => format!("pack as string `{}`", ty),
                 CastOp::UnpackString => format!("unpack string as `{}`", ty),
+                CastOp::RealToInt => format!("convert real to integer `{}`", ty),
+                CastOp::IntToReal => format!("convert integer to real `{}`", ty),
+                CastOp::RealPrecision => format!("cast real precision to `{}`", ty),
             };
             d = d.add_note(msg);
         }
@@ -1316,6 +1325,48 @@ fn cast_expr_type_inner<'gcx>(
         }
     }
 
+    // Convert reals to other real types, booleans, or their integer
+    // equivalent. See §6.12.1 "Conversion".
+    let inferred = if inferred.is_real() {
+        match context {
+            TypeContext::Type(ty) if ty.is_real() => {
+                trace!("  Casting real precision to {}", ty);
+                cast.add_cast(CastOp::RealPrecision, ty);
+                return cast;
+            }
+            TypeContext::Bool => {
+                trace!("  Casting real to bool ({})", context.ty());
+                cast.add_cast(CastOp::Bool, context.ty());
+                return cast;
+            }
+            TypeContext::Type(ty) => match ty.get_simple_bit_vector() {
+                Some(sbvt) => {
+                    let ty = sbvt.forget().to_unpacked(cx);
+                    trace!("  Converting real to integer ({})", ty);
+                    cast.add_cast(CastOp::RealToInt, ty);
+                    ty
+                }
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "cannot cast a value of type `{}` to `{}`",
+                            inferred, context
+                        ))
+                        .code("SV0003")
+                        .span(expr.span)
+                        .add_note(format!(
+                            "`{}` has no simple bit-vector type representation",
+                            context
+                        )),
+                    );
+                    return ty::UnpackedType::make_error().into();
+                }
+            },
+        }
+    } else {
+        inferred
+    };
+
     // Cast strings to SBVTs.
     let inferred = match context.ty().get_simple_bit_vector() {
         Some(context_sbvt) if inferred.is_string() => {
@@ -1343,6 +1394,7 @@ fn cast_expr_type_inner<'gcx>(
                     "cannot cast a value of type `{}` to `{}`",
                     inferred, context
                 ))
+                .code("SV0003")
                 .span(expr.span)
                 .add_note(format!(
                     "`{}` has no simple bit-vector type representation",
@@ -1374,6 +1426,13 @@ fn cast_expr_type_inner<'gcx>(
         TypeContext::Type(ty) => ty,
     };
 
+    // Convert the SBVT to a real.
+    if context.is_real() {
+        trace!("  Converting integer to real ({})", context);
+        cast.add_cast(CastOp::IntToReal, context);
+        return cast;
+    }
+
     // Cast the SBVT to a string.
     if context.is_string() {
         trace!("  Unpacking string from SBVT ({})", context);
@@ -1387,6 +1446,7 @@ fn cast_expr_type_inner<'gcx>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("cannot cast to a value of type `{}`", context))
+                    .code("SV0003")
                     .span(expr.span)
                     .add_note(format!(
                         "`{}` has no simple bit-vector type representation",
@@ -1496,6 +1556,7 @@ fn cast_expr_type_inner<'gcx>(
         "cannot cast a value of type `{}` to `{}`",
         inferred, context
     ))
+    .code("SV0003")
     .span(expr.span);
     if !cast.casts.is_empty() {
         d = d.add_note(format!(
@@ -1550,13 +1611,15 @@ pub(crate) fn need_self_determined_type<'a>(
                 .unwrap_or_else(|_| format!("`{}`", extract));
             cx.emit(
                 DiagBuilder2::error(format!("{} has no self-determined type", desc))
+                    .code("SV0034")
                     .span(cx.span(node_id))
                     .add_note(format!(
                         "The type of {} must be inferred from context, but the location where you \
                          used it does not provide such information.",
                         desc
                     ))
-                    .add_note(format!("Try a cast: `T'({})`", extract)),
+                    .add_note("Try a cast, where `T` is the intended type:")
+                    .suggest(cx.span(node_id), format!("T'({})", extract)),
             );
             UnpackedType::make_error()
         }
@@ -1598,6 +1661,9 @@ fn self_determined_expr_type<'gcx>(
         // Time constants are of time type.
         hir::ExprKind::TimeConst(_) => Some(UnpackedType::make_time()),
 
+        // Real constants are of real type.
+        hir::ExprKind::RealConst(_) => Some(UnpackedType::make(cx, RealType::Real)),
+
         // String literals behave like a packed array containing the characters.
         hir::ExprKind::StringConst(string) => Some(
             ty::PackedType::make_dims(
@@ -1647,6 +1713,7 @@ fn self_determined_expr_type<'gcx>(
                                 "cannot concatenate a value of type `{}`",
                                 ty
                             ))
+                            .code("SV0036")
                             .span(cx.span(expr))
                             .add_note(format!(
                                 "`{}` has no simple bit-vector type representation",
@@ -1720,6 +1787,7 @@ fn self_determined_expr_type<'gcx>(
                             "cannot size-cast a value of type `{}`",
                             inner_ty
                         ))
+                        .code("SV0036")
                         .span(cx.span(arg))
                         .add_note(format!(
                             "`{}` has no simple bit-vector type representation",
@@ -1742,10 +1810,40 @@ fn self_determined_expr_type<'gcx>(
         | hir::ExprKind::Builtin(hir::BuiltinCall::Clog2(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::Bits(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::CountOnes(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..)) => {
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Dimensions(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::UnpackedDimensions(_)) => {
             Some(PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx))
         }
 
+        // The real conversion functions evaluate to a fixed type.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Rtoi(_)) => {
+            Some(PackedType::make(cx, ty::IntAtomType::Integer).to_unpacked(cx))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Itor(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::BitsToReal(_)) => {
+            Some(UnpackedType::make(cx, RealType::Real))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::BitsToShortReal(_)) => {
+            Some(UnpackedType::make(cx, RealType::ShortReal))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealToBits(_)) => {
+            Some(SbvType::new(Domain::TwoValued, Sign::Unsigned, 64).to_unpacked(cx))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::ShortRealToBits(_)) => {
+            Some(SbvType::new(Domain::TwoValued, Sign::Unsigned, 32).to_unpacked(cx))
+        }
+
+        // The display tasks return nothing, and `$sformatf` returns the
+        // formatted string.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Display(DisplayTask::Sformatf, ..)) => {
+            Some(UnpackedType::make(cx, UnpackedCore::String))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Display(..)) => Some(UnpackedType::make_void()),
+
+        // The memory load tasks return nothing.
+        hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem(..)) => Some(UnpackedType::make_void()),
+
         // These builtin functions evaluate to the bit type.
         hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_))
@@ -1769,6 +1867,7 @@ fn self_determined_expr_type<'gcx>(
                                 "value of type `{}` already has a modport; cannot change to {}",
                                 target_ty, modport
                             ))
+                            .code("SV0036")
                             .span(cx.span(target))
                             .add_note(format!(
                                 "`{}` refers to a modport in interface `{}` defined here:",
@@ -1935,6 +2034,13 @@ fn self_determined_expr_type<'gcx>(
                 unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
             }
 
+            // Exponentiation with a real exponent produces a real.
+            hir::BinaryOp::Pow if real_exponent(cx, rhs, env) => {
+                let tlhs = cx.self_determined_type(lhs, env);
+                let trhs = cx.self_determined_type(rhs, env);
+                unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+            }
+
             // Exponentiation and shifts operate on the left-hand side type.
             hir::BinaryOp::Pow
             | hir::BinaryOp::LogicShL
@@ -1983,6 +2089,7 @@ fn self_determined_sign_cast_type<'gcx>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("cannot sign-cast a value of type `{}`", ty))
+                    .code("SV0036")
                     .span(cx.span(arg))
                     .add_note(format!(
                         "`{}` has no simple bit-vector type representation",
@@ -2045,12 +2152,14 @@ pub(crate) fn operation_type<'a>(
             if ty.is_none() {
                 cx.emit(
                     DiagBuilder2::error(format!("type of {} cannot be inferred", expr.desc_full()))
+                        .code("SV0034")
                         .span(expr.human_span())
                         .add_note(
                             "The operand does not have a self-determined type, and the type \
                              cannot be inferred from the context.",
                         )
-                        .add_note(format!("Try a cast: `T'({})`", expr.span().extract())),
+                        .add_note("Try a cast, where `T` is the intended type:")
+                        .suggest(expr.span(), format!("T'({})", expr.span().extract())),
                 );
                 Some(UnpackedType::make_error())
             } else {
@@ -2112,6 +2221,7 @@ pub(crate) fn operation_type<'a>(
                 }
 
                 // Exponentiation and shifts operate on the left-hand side type.
+                // A real exponent turns the operation into a real one.
                 hir::BinaryOp::Pow
                 | hir::BinaryOp::LogicShL
                 | hir::BinaryOp::LogicShR
@@ -2119,7 +2229,17 @@ pub(crate) fn operation_type<'a>(
                 | hir::BinaryOp::ArithShR => {
                     let tc = cx.type_context(Ref(node), env).map(|x| x.ty());
                     let sdt = cx.self_determined_type(lhs, env);
-                    unify_operator_types(cx, env, tc.into_iter().chain(sdt.into_iter()))
+                    let texp = match op == hir::BinaryOp::Pow && real_exponent(cx, rhs, env) {
+                        true => cx.self_determined_type(rhs, env),
+                        false => None,
+                    };
+                    unify_operator_types(
+                        cx,
+                        env,
+                        tc.into_iter()
+                            .chain(sdt.into_iter())
+                            .chain(texp.into_iter()),
+                    )
                 }
             };
             if ty.is_none() {
@@ -2128,12 +2248,14 @@ pub(crate) fn operation_type<'a>(
                         "operation type of {} cannot be inferred",
                         expr.desc_full()
                     ))
+                    .code("SV0034")
                     .span(expr.human_span())
                     .add_note(
                         "Neither of the operands has a self-determined type, and the type cannot \
                          be inferred from the context.",
                     )
-                    .add_note(format!("Try a cast: `T'({})`", expr.span().extract())),
+                    .add_note("Try a cast, where `T` is the intended type:")
+                    .suggest(expr.span(), format!("T'({})", expr.span().extract())),
                 );
                 Some(UnpackedType::make_error())
             } else {
@@ -2196,6 +2318,7 @@ pub(crate) fn operation_type<'a>(
                                 "cannot index into a value of type `{}`",
                                 target_ty
                             ))
+                            .code("SV0012")
                             .span(expr.span)
                             .add_note(format!(
                                 "`{}` must be an array or have a simple bit-vector type \
@@ -2224,8 +2347,24 @@ fn unify_operator_types<'gcx>(
     _env: ParamEnv,
     types: impl Iterator<Item = &'gcx UnpackedType<'gcx>>,
 ) -> Option<&'gcx UnpackedType<'gcx>> {
+    let types: Vec<_> = types.collect();
+
+    // If any of the operands is a real, the operation is performed on reals.
+    // See §11.3.1 "Operators with real operands".
+    let reals: Vec<_> = types.iter().flat_map(|ty| ty.get_real()).collect();
+    if !reals.is_empty() {
+        let ty = match reals.iter().all(|&ty| ty == RealType::ShortReal) {
+            true => RealType::ShortReal,
+            false => RealType::Real,
+        };
+        return Some(UnpackedType::make(cx, ty));
+    }
+
     // Map the iterator to a sequence of sign, domain, and bit width tuples.
-    let inner: Vec<_> = types.flat_map(|ty| ty.get_simple_bit_vector()).collect();
+    let inner: Vec<_> = types
+        .iter()
+        .flat_map(|ty| ty.get_simple_bit_vector())
+        .collect();
 
     // Determine the maximum width, sign, and domain.
     let width: Option<usize> = inner.iter().map(|&sbv| sbv.size).max();
@@ -2242,6 +2381,13 @@ fn unify_operator_types<'gcx>(
     width.map(|w| SbvType::nice(domain, sign, w).to_unpacked(cx))
 }
 
+/// Check if the exponent of a `**` operator is a real.
+fn real_exponent<'gcx>(cx: &impl Context<'gcx>, rhs: NodeId, env: ParamEnv) -> bool {
+    cx.self_determined_type(rhs, env)
+        .map(|ty| ty.is_real())
+        .unwrap_or(false)
+}
+
 /// Require a node to have an operation type.
 ///
 /// Emits an error if the node has no operation type.
@@ -2405,13 +2551,15 @@ pub(crate) fn need_type_context<'a>(
             let extract = node.span().extract();
             cx.emit(
                 DiagBuilder2::error(format!("cannot infer type from context: {}", node))
+                    .code("SV0034")
                     .span(node.span())
                     .add_note(format!(
                         "The type of {} must be inferred from context, but the location where you \
                          used it does not provide such information.",
                         node
                     ))
-                    .add_note(format!("Try a cast: `T'({})`", extract)),
+                    .add_note("Try a cast, where `T` is the intended type:")
+                    .suggest(node.span(), format!("T'({})", extract)),
             );
             UnpackedType::make_error().into()
         }
@@ -2502,6 +2650,11 @@ fn type_context_imposed_by_expr<'gcx>(
             // The logic operators require boolean arguments.
             hir::BinaryOp::LogicAnd | hir::BinaryOp::LogicOr => Some(TypeContext::Bool),
 
+            // Real exponentiation imposes a type context on both sides.
+            hir::BinaryOp::Pow if cx.need_operation_type(expr.id, env).is_real() => {
+                Some(cx.need_operation_type(expr.id, env).into())
+            }
+
             // Exponentiation and shifts impose a type context on their left
             // hand side.
             hir::BinaryOp::Pow
@@ -2559,6 +2712,61 @@ fn type_context_imposed_by_expr<'gcx>(
             Some(cx.need_self_determined_type(expr.id, env).into())
         }
 
+        // The real conversion functions impose the type they convert from onto
+        // their argument.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Rtoi(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::RealToBits(_)) => {
+            Some(UnpackedType::make(cx, RealType::Real).into())
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::ShortRealToBits(_)) => {
+            Some(UnpackedType::make(cx, RealType::ShortReal).into())
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::BitsToReal(_)) => Some(
+            SbvType::new(Domain::TwoValued, Sign::Unsigned, 64)
+                .to_unpacked(cx)
+                .into(),
+        ),
+        hir::ExprKind::Builtin(hir::BuiltinCall::BitsToShortReal(_)) => Some(
+            SbvType::new(Domain::TwoValued, Sign::Unsigned, 32)
+                .to_unpacked(cx)
+                .into(),
+        ),
+        hir::ExprKind::Builtin(hir::BuiltinCall::Itor(_)) => {
+            let ty = cx.need_self_determined_type(onto, env);
+            if ty.is_error() {
+                return Some(ty.into());
+            }
+            match ty.get_simple_bit_vector() {
+                Some(sbvt) => Some(sbvt.to_unpacked(cx).into()),
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "cannot convert a value of type `{}` to real",
+                            ty
+                        ))
+                        .code("SV0036")
+                        .span(expr.span)
+                        .add_note(format!(
+                            "`{}` has no simple bit-vector type representation",
+                            ty
+                        )),
+                    );
+                    Some(UnpackedType::make_error().into())
+                }
+            }
+        }
+
+        // The memory load tasks take their addresses as integers.
+        hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem(_, _, _, start, end))
+            if Some(onto) == start || Some(onto) == end =>
+        {
+            Some(
+                SbvType::new(Domain::TwoValued, Sign::Signed, 32)
+                    .to_unpacked(cx)
+                    .into(),
+            )
+        }
+
         // Concatenations require their arguments (including repetition counts)
         // to map to a corresponding SBVT.
         hir::ExprKind::Concat(..) => {
@@ -2573,6 +2781,7 @@ fn type_context_imposed_by_expr<'gcx>(
                         DiagBuilder2::error(
                             format!("cannot concatenate a value of type `{}`", ty,),
                         )
+                        .code("SV0036")
                         .span(expr.span)
                         .add_note(format!(
                             "`{}` has no simple bit-vector type representation",
@@ -2762,6 +2971,12 @@ pub enum CastOp {
     PackString,
     /// Unpack a string from an SBVT.
     UnpackString,
+    /// Convert a real to an SBVT, rounding to the nearest integer.
+    RealToInt,
+    /// Convert an SBVT to a real.
+    IntToReal,
+    /// Cast a real to a real type of different precision.
+    RealPrecision,
 }
 
 impl<'a> CastType<'a> {
@@ -2839,6 +3054,7 @@ fn size_from_bounds_expr<'a>(
                     "array bound `{}` is not an integer",
                     span.extract()
                 ))
+                .code("SV0035")
                 .span(span),
             );
             return Err(());
@@ -2849,6 +3065,7 @@ fn size_from_bounds_expr<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("{} is too large", span.extract()))
+                    .code("SV0035")
                     .span(span)
                     .add_note(format!("array would contain {} elements", size)),
             );
@@ -2876,6 +3093,7 @@ fn range_from_bounds_exprs<'a>(
                         "array bound `{}` is not an integer",
                         span.extract()
                     ))
+                    .code("SV0035")
                     .span(span),
                 );
                 return Err(());
@@ -2895,6 +3113,7 @@ fn range_from_bounds_exprs<'a>(
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("{} is too large", span.extract()))
+                    .code("SV0035")
                     .span(span)
                     .add_note(format!("array would contain {} elements", size)),
             );
@@ -2914,7 +3133,9 @@ pub fn return_type_of_function<'a>(
         Some(ty) => cx.packed_type_from_ast(Ref(ty), env, Some(IntVecType::Logic.into())),
         None => {
             cx.emit(
-                DiagBuilder2::error(format!("no return type: {}", node)).span(node.name.span()),
+                DiagBuilder2::error(format!("no return type: {}", node))
+                    .code("SV0034")
+                    .span(node.name.span()),
             );
             UnpackedType::make_error()
         }
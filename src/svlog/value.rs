@@ -50,6 +50,7 @@ impl<'t> ValueData<'t> {
             ValueKind::Void => true,
             ValueKind::Int(ref v, ..) => v.is_zero(),
             ValueKind::Time(ref v) => v.is_zero(),
+            ValueKind::Real(v) => v.0 == 0.0,
             ValueKind::StructOrArray(_) => false,
             ValueKind::String(ref v) => v.is_empty(),
             ValueKind::Error => true,
@@ -63,6 +64,14 @@ impl<'t> ValueData<'t> {
             _ => None,
         }
     }
+
+    /// Convert the value to a real.
+    pub fn get_real(&self) -> Option<f64> {
+        match self.kind {
+            ValueKind::Real(v) => Some(v.0),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ValueData<'_> {
@@ -83,6 +92,8 @@ pub enum ValueKind<'t> {
     Int(BigInt, BitVec, BitVec),
     /// An arbitrary precision time interval.
     Time(BigRational),
+    /// A floating-point number.
+    Real(RealValue),
     /// A struct.
     StructOrArray(Vec<Value<'t>>),
     /// A string.
@@ -105,12 +116,38 @@ impl<'t> ValueKind<'t> {
     }
 }
 
+/// A floating-point number.
+///
+/// Compares and hashes the bit pattern of the contained `f64`, such that values
+/// can be interned.
+#[derive(Debug, Clone, Copy)]
+pub struct RealValue(pub f64);
+
+impl Eq for RealValue {}
+impl PartialEq for RealValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+impl std::hash::Hash for RealValue {
+    fn hash<H: std::hash::Hasher>(&self, h: &mut H) {
+        self.0.to_bits().hash(h)
+    }
+}
+
+impl std::fmt::Display for RealValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
 impl std::fmt::Display for ValueKind<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             ValueKind::Void => write!(f, "void"),
             ValueKind::Int(v, ..) => write!(f, "{}", v),
             ValueKind::Time(v) => write!(f, "{}", v),
+            ValueKind::Real(v) => write!(f, "{}", v),
             ValueKind::StructOrArray(v) => {
                 write!(f, "{{ {} }}", v.iter().map(|v| &v.kind).format(", "))
             }
@@ -178,6 +215,22 @@ pub fn make_time<'a>(value: BigRational) -> ValueData<'a> {
     }
 }
 
+/// Create a new real value.
+///
+/// Panics if `ty` is not a real type. Rounds the value to single precision if
+/// `ty` is a `shortreal`.
+pub fn make_real<'a>(ty: &'a UnpackedType<'a>, value: f64) -> ValueData<'a> {
+    let value = match ty.get_real() {
+        Some(ty::RealType::ShortReal) => value as f32 as f64,
+        Some(_) => value,
+        None => panic!("make_real got non-real type `{}`", ty),
+    };
+    ValueData {
+        ty,
+        kind: ValueKind::Real(RealValue(value)),
+    }
+}
+
 /// Create a new struct value.
 pub fn make_struct<'a>(ty: &'a UnpackedType<'a>, fields: Vec<Value<'a>>) -> ValueData<'a> {
     assert!(ty.dims().next().is_none() && ty.get_struct().is_some());
@@ -222,6 +275,70 @@ pub(crate) fn constant_value_of<'a>(
     v
 }
 
+/// Check whether the value of a parameter depends on itself.
+///
+/// Parameters that reference each other cyclically cause the evaluation of
+/// their values to recurse. This inspects the queries currently in flight and
+/// reports the cycle if the parameter is already being evaluated.
+pub(crate) fn check_param_cycle<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    env: ParamEnv,
+    span: Span,
+) -> Result<()> {
+    let tag = QueryTag::ConstantValueOf(ConstantValueOfQueryKey(node_id, env));
+    if !cx.storage().inflight.borrow().contains(&tag) {
+        return Ok(());
+    }
+
+    // Collect the parameters involved in the cycle, starting at the first
+    // evaluation of the offending parameter.
+    let ids: Vec<NodeId> = {
+        let stack = cx.storage().stack.borrow();
+        let start = stack.iter().position(|t| *t == tag).unwrap_or(0);
+        stack[start..]
+            .iter()
+            .flat_map(|t| match t {
+                QueryTag::ConstantValueOf(ConstantValueOfQueryKey(id, _)) => Some(*id),
+                _ => None,
+            })
+            .collect()
+    };
+    let mut params = vec![];
+    for id in ids {
+        if let Ok(HirNode::ValueParam(param)) = cx.hir_of(id) {
+            if !params.iter().any(|p: &&hir::ValueParam| p.id == param.id) {
+                params.push(param);
+            }
+        }
+    }
+
+    // Report the cycle.
+    let name = match cx.hir_of(node_id)? {
+        HirNode::ValueParam(param) => param.name,
+        _ => unreachable!(),
+    };
+    let mut d = DiagBuilder2::error(format!("parameter `{}` depends on its own value", name))
+        .code("SV0040")
+        .span(span)
+        .add_note(format!(
+            "The cycle is {} -> `{}`",
+            params
+                .iter()
+                .map(|p| format!("`{}`", p.name))
+                .collect::<Vec<_>>()
+                .join(" -> "),
+            name
+        ));
+    for param in params {
+        d = d
+            .add_note(format!("`{}` declared here:", param.name))
+            .span(param.human_span());
+    }
+    cx.emit(d);
+    Err(())
+}
+
 fn constant_value_of_inner<'a>(cx: &impl Context<'a>, node_id: NodeId, env: ParamEnv) -> Value<'a> {
     let hir = match cx.hir_of(node_id) {
         Ok(x) => x,
@@ -247,7 +364,8 @@ fn constant_value_of_inner<'a>(cx: &impl Context<'a>, node_id: NodeId, env: Para
             let d = DiagBuilder2::error(format!(
                 "{} not assigned and has no default",
                 param.desc_full(),
-            ));
+            ))
+            .code("SV0008");
             let contexts = cx.param_env_contexts(env);
             for &context in &contexts {
                 cx.emit(
@@ -276,6 +394,7 @@ fn constant_value_of_inner<'a>(cx: &impl Context<'a>, node_id: NodeId, env: Para
             }
             cx.emit(
                 DiagBuilder2::error(format!("{} not initialized", decl.desc_full()))
+                    .code("SV0030")
                     .span(decl.human_span()),
             );
             cx.intern_value(make_error(UnpackedType::make_error()))
@@ -293,6 +412,7 @@ fn constant_value_of_inner<'a>(cx: &impl Context<'a>, node_id: NodeId, env: Para
         _ => {
             cx.emit(
                 DiagBuilder2::error(format!("{} has no constant value", hir.desc_full()))
+                    .code("SV0030")
                     .span(hir.human_span()),
             );
             cx.intern_value(make_error(UnpackedType::make_error()))
@@ -317,6 +437,7 @@ pub(crate) fn const_mir_rvalue_int<'a>(
                     "`{}` is not a constant integer",
                     mir.span.extract()
                 ))
+                .code("SV0030")
                 .span(mir.span),
             );
             Err(())
@@ -338,6 +459,7 @@ pub(crate) fn const_mir_rvalue_string<'a>(
         _ => {
             cx.emit(
                 DiagBuilder2::error(format!("`{}` is not a constant string", mir.span.extract()))
+                    .code("SV0030")
                     .span(mir.span),
             );
             Err(())
@@ -351,7 +473,7 @@ pub(crate) fn const_mir_rvalue<'a>(
     cx: &impl Context<'a>,
     Ref(mir): Ref<'a, mir::Rvalue<'a>>,
 ) -> Value<'a> {
-    let v = const_mir_rvalue_inner(cx, mir);
+    let v = const_mir_rvalue_inner(cx, mir, &mut |v| cx.const_mir_rvalue(v.into()));
     if cx.sess().has_verbosity(Verbosity::CONSTS) {
         let ext = mir.span.extract();
         let line = mir.span.begin().human_line();
@@ -360,7 +482,17 @@ pub(crate) fn const_mir_rvalue<'a>(
     v
 }
 
-fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -> Value<'a> {
+/// Determine the constant value of an MIR rvalue, using `eval` to evaluate its
+/// operands.
+///
+/// This is the workhorse of `const_mir_rvalue`, but is also used to evaluate
+/// expressions during constant function calls, where the operands may refer to
+/// local variables.
+pub(crate) fn const_mir_rvalue_inner<'a>(
+    cx: &impl Context<'a>,
+    mir: &'a mir::Rvalue<'a>,
+    eval: &mut dyn FnMut(&'a mir::Rvalue<'a>) -> Value<'a>,
+) -> Value<'a> {
     // Propagate MIR tombstones immediately.
     if mir.is_error() {
         return cx.intern_value(make_error(mir.ty));
@@ -379,7 +511,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
                 value.ty,
                 mir.ty
             );
-            let v = cx.const_mir_rvalue(value.into());
+            let v = eval(value);
             // TODO: This is an incredibly ugly hack.
             cx.intern_value(ValueData {
                 ty: mir.ty,
@@ -388,7 +520,28 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::Transmute(value) => {
-            let v = cx.const_mir_rvalue(value.into());
+            let v = eval(value);
+            if v.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+
+            // Reinterpret reals as their IEEE 754 bit pattern and vice versa.
+            if let Some(real) = v.get_real() {
+                let bits = match value.ty.get_real() {
+                    Some(ty::RealType::ShortReal) => BigInt::from((real as f32).to_bits()),
+                    _ => BigInt::from(real.to_bits()),
+                };
+                return cx.intern_value(make_int(mir.ty, bits));
+            }
+            if let Some(real_ty) = mir.ty.get_real() {
+                let bits = v.get_int().and_then(|v| v.to_u64()).unwrap_or(0);
+                let real = match real_ty {
+                    ty::RealType::ShortReal => f32::from_bits(bits as u32) as f64,
+                    _ => f64::from_bits(bits),
+                };
+                return cx.intern_value(make_real(mir.ty, real));
+            }
+
             cx.intern_value(ValueData {
                 ty: mir.ty,
                 kind: v.kind.clone(),
@@ -396,15 +549,67 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::CastToBool(value) => {
-            let value = cx.const_mir_rvalue(value.into());
+            let value = eval(value);
             if value.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
             cx.intern_value(make_int(mir.ty, (value.is_true() as usize).into()))
         }
 
+        mir::RvalueKind::CastRealToInt { value, round } => {
+            let value = eval(value);
+            if value.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let real = value
+                .get_real()
+                .expect("value to be converted should be real");
+            let real = if round { real.round() } else { real.trunc() };
+            match num::FromPrimitive::from_f64(real) {
+                Some(int) => cx.intern_value(make_int(mir.ty, int)),
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "real value {:?} cannot be converted to an integer",
+                            real
+                        ))
+                        .code("SV0036")
+                        .span(mir.span),
+                    );
+                    cx.intern_value(make_error(mir.ty))
+                }
+            }
+        }
+
+        mir::RvalueKind::CastIntToReal(value) => {
+            let value_const = eval(value);
+            if value_const.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let sbvt = value.ty.simple_bit_vector(cx, value.span);
+            let modulus = BigInt::one() << sbvt.size;
+            let mut int = value_const
+                .get_int()
+                .expect("value to be converted should be int")
+                .mod_floor(&modulus);
+            if sbvt.is_signed() && int >= (&modulus >> 1) {
+                int -= modulus;
+            }
+            let real = int.to_f64().unwrap_or(std::f64::NAN);
+            cx.intern_value(make_real(mir.ty, real))
+        }
+
+        mir::RvalueKind::CastRealPrecision(value) => {
+            let value = eval(value);
+            if value.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let real = value.get_real().expect("value to be cast should be real");
+            cx.intern_value(make_real(mir.ty, real))
+        }
+
         mir::RvalueKind::ApplyTimescale(value, ref scale) => {
-            let value = cx.const_mir_rvalue(value.into());
+            let value = eval(value);
             if value.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -417,22 +622,19 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         mir::RvalueKind::ConstructArray(ref values) => cx.intern_value(make_array(
             mir.ty,
             (0..values.len())
-                .map(|index| cx.const_mir_rvalue(values[&index].into()))
+                .map(|index| eval(values[&index]))
                 .collect(),
         )),
 
         mir::RvalueKind::ConstructStruct(ref values) => cx.intern_value(make_struct(
             mir.ty,
-            values
-                .iter()
-                .map(|&value| cx.const_mir_rvalue(value.into()))
-                .collect(),
+            values.iter().map(|&value| eval(value)).collect(),
         )),
 
         mir::RvalueKind::Const(value) => value,
 
         mir::RvalueKind::UnaryBitwise { op, arg } => {
-            let arg_val = cx.const_mir_rvalue(arg.into());
+            let arg_val = eval(arg);
             if arg_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -451,8 +653,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -474,7 +676,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::IntUnaryArith { op, arg, .. } => {
-            let arg_val = cx.const_mir_rvalue(arg.into());
+            let arg_val = eval(arg);
             if arg_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -488,8 +690,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::IntBinaryArith { op, lhs, rhs, .. } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -511,8 +713,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::IntComp { op, lhs, rhs, .. } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -533,20 +735,66 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             }
         }
 
+        mir::RvalueKind::RealUnaryArith { op, arg } => {
+            let arg_val = eval(arg);
+            if arg_val.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let arg = arg_val.get_real().expect("real arith on non-real");
+            let result = match op {
+                mir::IntUnaryArithOp::Neg => -arg,
+            };
+            cx.intern_value(make_real(mir.ty, result))
+        }
+
+        mir::RvalueKind::RealBinaryArith { op, lhs, rhs } => {
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
+            if lhs_val.is_error() || rhs_val.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let lhs = lhs_val.get_real().expect("real arith on non-real");
+            let rhs = rhs_val.get_real().expect("real arith on non-real");
+            let result = match op {
+                mir::RealBinaryArithOp::Add => lhs + rhs,
+                mir::RealBinaryArithOp::Sub => lhs - rhs,
+                mir::RealBinaryArithOp::Mul => lhs * rhs,
+                mir::RealBinaryArithOp::Div => lhs / rhs,
+                mir::RealBinaryArithOp::Pow => lhs.powf(rhs),
+            };
+            cx.intern_value(make_real(mir.ty, result))
+        }
+
+        mir::RvalueKind::RealComp { op, lhs, rhs } => {
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
+            if lhs_val.is_error() || rhs_val.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let lhs = lhs_val.get_real().expect("real comparison on non-real");
+            let rhs = rhs_val.get_real().expect("real comparison on non-real");
+            let result = match op {
+                mir::IntCompOp::Eq => lhs == rhs,
+                mir::IntCompOp::Neq => lhs != rhs,
+                mir::IntCompOp::Lt => lhs < rhs,
+                mir::IntCompOp::Leq => lhs <= rhs,
+                mir::IntCompOp::Gt => lhs > rhs,
+                mir::IntCompOp::Geq => lhs >= rhs,
+            };
+            cx.intern_value(make_int(mir.ty, (result as usize).into()))
+        }
+
         mir::RvalueKind::Concat(ref values) => {
             let mut result = BigInt::zero();
             for &value in values {
                 result <<= value.ty.simple_bit_vector(cx, value.span).size;
-                result |= cx
-                    .const_mir_rvalue(value.into())
-                    .get_int()
-                    .expect("concat non-integer");
+                result |= eval(value).get_int().expect("concat non-integer");
             }
             cx.intern_value(make_int(mir.ty, result))
         }
 
         mir::RvalueKind::Repeat(count, value) => {
-            let value_const = cx.const_mir_rvalue(value.into());
+            let value_const = eval(value);
             if value_const.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -565,12 +813,16 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         | mir::RvalueKind::Arg(_)
         | mir::RvalueKind::IntfSignal(..)
         | mir::RvalueKind::Intf(..) => {
-            cx.emit(DiagBuilder2::error("value is not constant").span(mir.span));
+            cx.emit(
+                DiagBuilder2::error("value is not constant")
+                    .code("SV0030")
+                    .span(mir.span),
+            );
             cx.intern_value(make_error(mir.ty))
         }
 
         mir::RvalueKind::Member { value, field } => {
-            let value_const = cx.const_mir_rvalue(value.into());
+            let value_const = eval(value);
             if value_const.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -585,9 +837,9 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             true_value,
             false_value,
         } => {
-            let cond_val = cx.const_mir_rvalue(cond.into());
-            let true_val = cx.const_mir_rvalue(true_value.into());
-            let false_val = cx.const_mir_rvalue(false_value.into());
+            let cond_val = eval(cond);
+            let true_val = eval(true_value);
+            let false_val = eval(false_value);
             match cond_val.is_true() {
                 true => true_val,
                 false => false_val,
@@ -601,8 +853,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             amount,
             ..
         } => {
-            let value_val = cx.const_mir_rvalue(value.into());
-            let amount_val = cx.const_mir_rvalue(amount.into());
+            let value_val = eval(value);
+            let amount_val = eval(amount);
             if value_val.is_error() || amount_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -625,7 +877,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::Reduction { op, arg } => {
-            let arg_val = cx.const_mir_rvalue(arg.into());
+            let arg_val = eval(arg);
             if arg_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -638,19 +890,53 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             }
         }
 
+        mir::RvalueKind::Clog2(arg) => {
+            let arg_val = eval(arg);
+            if arg_val.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let arg_int = arg_val.get_int().expect("clog2 of non-integer");
+            let value = if arg_int <= &BigInt::one() {
+                BigInt::zero()
+            } else {
+                BigInt::from((arg_int - BigInt::one()).bits())
+            };
+            cx.intern_value(make_int(mir.ty, value))
+        }
+
+        mir::RvalueKind::CountOnes(arg) => {
+            let arg_val = eval(arg);
+            if arg_val.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            let width = arg.ty.simple_bit_vector(cx, arg.span).size;
+            let mut bits = arg_val
+                .get_int()
+                .expect("countones of non-integer")
+                .mod_floor(&(BigInt::one() << width));
+            let mut count = 0usize;
+            while !bits.is_zero() {
+                if bits.is_odd() {
+                    count += 1;
+                }
+                bits >>= 1;
+            }
+            cx.intern_value(make_int(mir.ty, count.into()))
+        }
+
         mir::RvalueKind::Index {
             value,
             base,
             length,
             ..
         } => {
-            let inner_val = cx.const_mir_rvalue(value.into());
+            let inner_val = eval(value);
             if inner_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
-            let base = match cx.const_mir_rvalue_int(Ref(base)) {
-                Ok(x) => x.to_isize().expect("base out of bounds"),
-                _ => return cx.intern_value(make_error(mir.ty)),
+            let base = match eval(base).get_int() {
+                Some(x) => x.to_isize().expect("base out of bounds"),
+                None => return cx.intern_value(make_error(mir.ty)),
             };
             match inner_val.kind {
                 // TODO: This magic should all be replaced by a dedicated
@@ -707,19 +993,19 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         // Pack a string into a vector.
-        mir::RvalueKind::PackString(value) => match cx.const_mir_rvalue_string(value.into()) {
-            Ok(v) => cx.intern_value(make_int(
+        mir::RvalueKind::PackString(value) => match eval(value).kind {
+            ValueKind::String(ref v) => cx.intern_value(make_int(
                 mir.ty,
                 BigInt::from_bytes_be(num::bigint::Sign::Plus, v),
             )),
-            Err(()) => cx.intern_value(make_error(mir.ty)),
+            _ => cx.intern_value(make_error(mir.ty)),
         },
 
         // Unpack a string from a vector.
         mir::RvalueKind::UnpackString(value) => {
-            let mut konst = match cx.const_mir_rvalue_int(value.into()) {
-                Ok(v) => v.clone(),
-                Err(()) => return cx.intern_value(make_error(mir.ty)),
+            let mut konst = match eval(value).get_int() {
+                Some(v) => v.clone(),
+                None => return cx.intern_value(make_error(mir.ty)),
             };
             let mut bytes = vec![];
             while !konst.is_zero() {
@@ -734,8 +1020,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::StringComp { op, lhs, rhs, .. } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -747,8 +1033,34 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             }
         }
 
-        // TODO(fschuiki): Fix this once we support constant function calls.
-        mir::RvalueKind::Call { .. } => cx.intern_value(make_error(mir.ty)),
+        // Display and memory load tasks produce no value.
+        mir::RvalueKind::Display { .. } | mir::RvalueKind::ReadMem { .. } => {
+            cx.intern_value(ValueData {
+                ty: mir.ty,
+                kind: ValueKind::Void,
+            })
+        }
+
+        // Format a message into a string.
+        mir::RvalueKind::Format(ref pieces) => {
+            let mut bytes = vec![];
+            for piece in pieces {
+                match *piece {
+                    mir::FormatPiece::Text(ref text) => bytes.extend(text.bytes()),
+                    mir::FormatPiece::Value(spec, value) => {
+                        match crate::format::format_value(spec, &eval(value)) {
+                            Some(text) => bytes.extend(text.chars().map(|c| c as u8)),
+                            None => return cx.intern_value(make_error(mir.ty)),
+                        }
+                    }
+                    mir::FormatPiece::Scope => return cx.intern_value(make_error(mir.ty)),
+                }
+            }
+            cx.intern_value(make_string(mir.ty, bytes))
+        }
+
+        // Function calls are executed by the interpreter.
+        mir::RvalueKind::Call { .. } => crate::interp::const_call(cx, mir, eval),
 
         // Propagate tombstones.
         mir::RvalueKind::Error => cx.intern_value(make_error(mir.ty)),
@@ -938,6 +1250,11 @@ pub(crate) fn type_default_value<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType
         }
     }
 
+    // Handle reals.
+    if ty.is_real() {
+        return cx.intern_value(make_real(ty, 0.0));
+    }
+
     // Handle arrays.
     if let Some(dim) = ty.outermost_dim() {
         let length = dim
@@ -0,0 +1,1013 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Emission of the elaborated design as structural Verilog-2001.
+//!
+//! This backend writes every module specialization of a design out as a
+//! separate Verilog-2001 module, with all parameters, generate blocks, and
+//! SystemVerilog types resolved. This allows the design to be processed by
+//! tools that only accept plain Verilog.
+//!
+//! Expressions are emitted from their MIR, such that all implicit casts are
+//! spelled out explicitly. Every signal is declared as an unsigned vector with
+//! its least significant bit at index 0, and signed operations reinterpret
+//! their operands with `$signed`. Intermediate values that cannot be expressed
+//! inline, such as a truncated sum, are assigned to temporary signals.
+
+use crate::{
+    crate_prelude::*,
+    design::{
+        fit, sanitize, to_bool, unique, unsupported, unsupported_expr, width, Design, DesignInst,
+        DesignModule, WidthExpr,
+    },
+    hir::HirNode,
+    value::{Value, ValueKind},
+    ParamEnv,
+};
+use num::{BigInt, One, Signed, ToPrimitive};
+use std::collections::{HashMap, HashSet};
+
+/// Serialize a design as Verilog-2001.
+pub fn emit_design<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<String> {
+    // Assign every module a unique name.
+    let mut used = HashSet::new();
+    let names: Vec<String> = design
+        .modules
+        .iter()
+        .map(|m| unique(&mut used, &sanitize(&m.name)))
+        .collect();
+
+    let mut out = String::new();
+    for (index, module) in design.modules.iter().enumerate() {
+        let mut emitter = Emitter {
+            cx,
+            design,
+            module,
+            module_names: &names,
+            names: HashSet::new(),
+            storage: HashMap::new(),
+            regs: HashSet::new(),
+            decls: String::new(),
+            body: String::new(),
+            pending: vec![],
+            procedural: false,
+            indent: 1,
+        };
+        if index > 0 {
+            out.push('\n');
+        }
+        emitter.emit_module(&names[index], &mut out)?;
+    }
+    Ok(out)
+}
+
+/// An emitted Verilog expression.
+#[derive(Debug, Clone)]
+struct Expr {
+    text: String,
+    width: usize,
+    /// Whether the expression is a signal name that can be indexed.
+    name: bool,
+}
+
+impl Expr {
+    /// Create an expression that needs no parentheses.
+    fn atom(text: impl Into<String>, width: usize) -> Self {
+        Expr {
+            text: text.into(),
+            width,
+            name: false,
+        }
+    }
+
+    /// Create an expression that is wrapped in parentheses.
+    fn wrapped(text: impl std::fmt::Display, width: usize) -> Self {
+        Expr::atom(format!("({})", text), width)
+    }
+
+    /// Create a reference to a signal.
+    fn signal(name: impl Into<String>, width: usize) -> Self {
+        Expr {
+            name: true,
+            ..Expr::atom(name, width)
+        }
+    }
+
+    /// The expression without any outer parentheses.
+    fn unwrapped(&self) -> &str {
+        if self.text.starts_with('(') && self.text.ends_with(')') {
+            &self.text[1..self.text.len() - 1]
+        } else {
+            &self.text
+        }
+    }
+}
+
+impl WidthExpr for Expr {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Select the lower bits of the expression, which must be a signal name.
+    fn truncate(self, width: usize) -> Self {
+        debug_assert!(self.name);
+        if width == 1 {
+            Expr::atom(format!("{}[0]", self.text), 1)
+        } else {
+            Expr::atom(format!("{}[{}:0]", self.text, width - 1), width)
+        }
+    }
+
+    fn zero_extend(self, width: usize) -> Self {
+        Expr::atom(
+            format!("{{{}'d0, {}}}", width - self.width, self.unwrapped()),
+            width,
+        )
+    }
+
+    fn reduce_or(self) -> Self {
+        Expr::wrapped(format!("|{}", self.text), 1)
+    }
+}
+
+/// A helper to emit a single module specialization.
+struct Emitter<'a, 'c, C> {
+    cx: &'c C,
+    design: &'c Design<'a>,
+    module: &'c DesignModule<'a>,
+    /// The names of all modules in the design.
+    module_names: &'c [String],
+    /// The names used within the module.
+    names: HashSet<String>,
+    /// The names and widths of the ports and variables of the module.
+    storage: HashMap<(NodeId, ParamEnv), (String, usize)>,
+    /// The variables assigned by procedures, which are declared as `reg`.
+    regs: HashSet<(NodeId, ParamEnv)>,
+    /// The declarations of the module.
+    decls: String,
+    /// The assignments, instances, and procedures of the module.
+    body: String,
+    /// The assignments to temporaries that have to precede the current line.
+    pending: Vec<String>,
+    /// Whether expressions are currently emitted within a procedure.
+    procedural: bool,
+    indent: usize,
+}
+
+impl<'a, 'c, C: Context<'a>> Emitter<'a, 'c, C> {
+    fn emit_module(&mut self, name: &str, out: &mut String) -> Result<()> {
+        let module = self.module;
+        let env = module.id.env();
+
+        // Find the variables assigned by procedures.
+        for &id in &module.procs {
+            let stmt = match self.cx.hir_of(id.id())? {
+                HirNode::Proc(x) => x.stmt,
+                _ => unreachable!(),
+            };
+            let acc = self.cx.accessed_nodes(stmt, id.env())?;
+            for node in &acc.written {
+                if let Some(key) = self.module.storage_key(&self.storage, node.id(), id.env()) {
+                    self.regs.insert(key);
+                }
+            }
+        }
+
+        // Emit the ports.
+        let mut ports = vec![];
+        for port in &module.ports {
+            let width = width(self.cx, port.ty, port.port.span)?;
+            let port_name = self.unique_name(&port.port.name.value.to_string());
+            let key = (port.port.id, env);
+            let kind = match port.port.dir {
+                ast::PortDir::Output if self.regs.contains(&key) => "reg",
+                ast::PortDir::Input | ast::PortDir::Output | ast::PortDir::Inout => "wire",
+                dir => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "{} port `{}` not supported by this output format",
+                            dir, port.port.name
+                        ))
+                        .code("SV0046")
+                        .span(port.port.span),
+                    );
+                    return Err(());
+                }
+            };
+            ports.push(format!(
+                "    {} {} {}{}",
+                port.port.dir,
+                kind,
+                range(width),
+                port_name
+            ));
+            self.storage.insert(key, (port_name, width));
+        }
+
+        // Emit the variables.
+        let mut inits = vec![];
+        for decl in &module.decls {
+            let width = width(self.cx, decl.ty, decl.hir.span)?;
+            let decl_name = self.unique_name(&decl.name);
+            let key = (decl.id, decl.env);
+            if self.regs.contains(&key) {
+                match decl.hir.init {
+                    Some(init) => {
+                        let value = self.rvalue(self.cx.mir_rvalue(init, decl.env))?;
+                        if !self.pending.is_empty() {
+                            self.cx.emit(
+                                DiagBuilder2::error(
+                                    "initial value not supported by this output format",
+                                )
+                                .code("SV0046")
+                                .span(self.cx.span(init)),
+                            );
+                            return Err(());
+                        }
+                        self.decl(format!(
+                            "reg {}{} = {};",
+                            range(width),
+                            decl_name,
+                            value.unwrapped()
+                        ));
+                    }
+                    None => self.decl(format!("reg {}{};", range(width), decl_name)),
+                }
+            } else {
+                self.decl(format!("wire {}{};", range(width), decl_name));
+                if let Some(init) = decl.hir.init {
+                    inits.push((decl_name.clone(), init, decl.env));
+                }
+            }
+            self.storage.insert(key, (decl_name, width));
+        }
+
+        // Emit the initial values of wires and the continuous assignments.
+        for (name, init, env) in inits {
+            let value = self.rvalue(self.cx.mir_rvalue(init, env))?;
+            self.flush();
+            self.line(format!("assign {} = {};", name, value.unwrapped()));
+        }
+        for &id in &module.assigns {
+            let hir = match self.cx.hir_of(id.id())? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+            let assign = self.cx.mir_assignment_from_concurrent(Ref(hir), id.env());
+            self.emit_assignment(assign, "assign ", "=")?;
+        }
+
+        // Emit the instances.
+        for inst in &module.insts {
+            self.emit_inst(inst)?;
+        }
+
+        // Emit the procedures.
+        for &id in &module.procs {
+            self.emit_proc(id.id(), id.env())?;
+        }
+
+        // Assemble the module.
+        if ports.is_empty() {
+            out.push_str(&format!("module {};\n", name));
+        } else {
+            out.push_str(&format!("module {} (\n{}\n);\n", name, ports.join(",\n")));
+        }
+        out.push_str(&self.decls);
+        if !self.decls.is_empty() && !self.body.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&self.body);
+        out.push_str("endmodule\n");
+        Ok(())
+    }
+
+    fn emit_inst(&mut self, inst: &DesignInst) -> Result<()> {
+        let target = &self.design.modules[inst.module];
+        let inner_env = target.id.env();
+        let mut conns = vec![];
+        for (port, &mapping) in target.ports.iter().zip(&inst.ports) {
+            let width = width(self.cx, port.ty, port.port.span)?;
+            let port_name = sanitize(&port.port.name.value.to_string());
+            let value = match port.port.dir {
+                ast::PortDir::Input => {
+                    match (mapping, port.port.data.as_ref().and_then(|d| d.default)) {
+                        (Some(mapping), _) => {
+                            let value =
+                                self.rvalue(self.cx.mir_rvalue(mapping.id(), mapping.env()))?;
+                            self.fit(value, width)?.unwrapped().to_string()
+                        }
+                        (None, Some(default)) => {
+                            let value = self.rvalue(self.cx.mir_rvalue(default, inner_env))?;
+                            self.fit(value, width)?.unwrapped().to_string()
+                        }
+                        (None, None) => String::new(),
+                    }
+                }
+                _ => match mapping {
+                    Some(mapping) => {
+                        let mir = self.cx.mir_lvalue(mapping.id(), mapping.env());
+                        self.lvalue(mir)?
+                    }
+                    None => String::new(),
+                },
+            };
+            conns.push(format!("        .{}({})", port_name, value));
+        }
+        let inst_name = self.unique_name(&inst.name);
+        self.flush();
+        if conns.is_empty() {
+            self.line(format!(
+                "{} {} ();",
+                self.module_names[inst.module], inst_name
+            ));
+        } else {
+            self.line(format!(
+                "{} {} (\n{}\n    );",
+                self.module_names[inst.module],
+                inst_name,
+                conns.join(",\n")
+            ));
+        }
+        Ok(())
+    }
+
+    fn emit_proc(&mut self, proc_id: NodeId, env: ParamEnv) -> Result<()> {
+        let hir = match self.cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        let timed = match self.cx.hir_of(hir.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Timed { control, stmt },
+                ..
+            }) => Some((*control, *stmt)),
+            _ => None,
+        };
+        let (header, body) = match (hir.kind, timed) {
+            (
+                ast::ProcedureKind::AlwaysComb | ast::ProcedureKind::AlwaysLatch,
+                None | Some((hir::TimingControl::ImplicitEvent, _)),
+            ) => ("always @*".to_string(), hir.stmt),
+            (
+                ast::ProcedureKind::Always | ast::ProcedureKind::AlwaysFf,
+                Some((hir::TimingControl::ImplicitEvent, body)),
+            ) => ("always @*".to_string(), body),
+            (
+                ast::ProcedureKind::Always | ast::ProcedureKind::AlwaysFf,
+                Some((hir::TimingControl::ExplicitEvent(event), body)),
+            ) => (format!("always @({})", self.event(event, env)?), body),
+            (ast::ProcedureKind::Initial, _) => ("initial".to_string(), hir.stmt),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "{} not supported by this output format",
+                        hir.desc_full()
+                    ))
+                    .code("SV0046")
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+        };
+        self.procedural = true;
+        self.line(format!("{} begin", header));
+        self.emit_body(body, env)?;
+        self.line("end");
+        self.procedural = false;
+        Ok(())
+    }
+
+    /// Emit the sensitivity list of a procedure.
+    fn event(&mut self, event_id: NodeId, env: ParamEnv) -> Result<String> {
+        let events = match self.cx.hir_of(event_id)? {
+            HirNode::EventExpr(x) => &x.events,
+            _ => unreachable!(),
+        };
+        let mut terms = vec![];
+        for event in events {
+            let edge = match event.edge {
+                ast::EdgeIdent::Implicit => "",
+                ast::EdgeIdent::Posedge => "posedge ",
+                ast::EdgeIdent::Negedge => "negedge ",
+                ast::EdgeIdent::Edge => {
+                    self.cx.emit(
+                        DiagBuilder2::error("`edge` events not supported by this output format")
+                            .code("SV0046")
+                            .span(event.span),
+                    );
+                    return Err(());
+                }
+            };
+            if !event.iff.is_empty() {
+                self.cx.emit(
+                    DiagBuilder2::error(
+                        "`iff` event qualifiers not supported by this output format",
+                    )
+                    .code("SV0046")
+                    .span(event.span),
+                );
+                return Err(());
+            }
+            let value = self.rvalue(self.cx.mir_rvalue(event.expr, env))?;
+            if !self.pending.is_empty() {
+                self.cx.emit(
+                    DiagBuilder2::error("event expression not supported by this output format")
+                        .code("SV0046")
+                        .span(event.span),
+                );
+                return Err(());
+            }
+            terms.push(format!("{}{}", edge, value.unwrapped()));
+        }
+        Ok(terms.join(" or "))
+    }
+
+    /// Emit the statements of a block, or a single statement.
+    fn emit_body(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
+        self.indent += 1;
+        match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Block(stmts),
+                ..
+            }) => {
+                for &stmt in stmts {
+                    self.emit_stmt(stmt, env)?;
+                }
+            }
+            _ => self.emit_stmt(stmt_id, env)?,
+        }
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn emit_stmt(&mut self, stmt_id: NodeId, env: ParamEnv) -> Result<()> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            HirNode::VarDecl(decl) => {
+                let ty = self.cx.type_of(stmt_id, env)?;
+                let width = width(self.cx, ty, decl.span)?;
+                let name = self.unique_name(&decl.name.value.to_string());
+                self.decl(format!("reg {}{};", range(width), name));
+                self.storage.insert((stmt_id, env), (name.clone(), width));
+                if let Some(init) = decl.init {
+                    let value = self.rvalue(self.cx.mir_rvalue(init, env))?;
+                    self.flush();
+                    self.line(format!("{} = {};", name, value.unwrapped()));
+                }
+                return Ok(());
+            }
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Null => (),
+            hir::StmtKind::Block(..) => {
+                self.line("begin");
+                self.emit_body(stmt_id, env)?;
+                self.line("end");
+            }
+            hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.emit_stmt(stmt, env)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, kind } => {
+                let op = match kind {
+                    hir::AssignKind::Block(_) => "=",
+                    hir::AssignKind::Nonblock => "<=",
+                    hir::AssignKind::NonblockDelay(_) => return unsupported(self.cx, hir),
+                };
+                let assign = self
+                    .cx
+                    .mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                self.emit_assignment(assign, "", op)?;
+            }
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ImplicitEvent,
+                stmt,
+            } => self.emit_stmt(stmt, env)?,
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                let cond = self.bool_expr(cond, env)?;
+                self.flush();
+                self.line(format!("if ({}) begin", cond.unwrapped()));
+                self.emit_if_tail(main_stmt, else_stmt, env)?;
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                let value = self.rvalue(self.cx.mir_rvalue(expr, env))?;
+                self.flush();
+                let keyword = match kind {
+                    ast::CaseKind::Normal => "case",
+                    ast::CaseKind::DontCareZ => "casez",
+                    ast::CaseKind::DontCareXZ => "casex",
+                };
+                self.line(format!("{} ({})", keyword, value.unwrapped()));
+                self.indent += 1;
+                for &(ref labels, stmt) in ways {
+                    let labels = labels
+                        .iter()
+                        .map(|&label| {
+                            let label_value = self.cx.constant_value_of(label, env);
+                            self.case_label(&label_value, value.width, label)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.line(format!("{}: begin", labels.join(", ")));
+                    self.emit_body(stmt, env)?;
+                    self.line("end");
+                }
+                if let Some(default) = default {
+                    self.line("default: begin");
+                    self.emit_body(default, env)?;
+                    self.line("end");
+                }
+                self.indent -= 1;
+                self.line("endcase");
+            }
+            _ => return unsupported(self.cx, hir),
+        }
+        Ok(())
+    }
+
+    /// Emit the branches of an `if` statement after its header.
+    fn emit_if_tail(
+        &mut self,
+        main_stmt: NodeId,
+        else_stmt: Option<NodeId>,
+        env: ParamEnv,
+    ) -> Result<()> {
+        self.emit_body(main_stmt, env)?;
+        let else_stmt = match else_stmt {
+            Some(x) => x,
+            None => {
+                self.line("end");
+                return Ok(());
+            }
+        };
+
+        // Chain `else if` branches, unless their condition needs temporaries
+        // which have to be assigned before the condition is checked.
+        if let HirNode::Stmt(hir::Stmt {
+            kind:
+                hir::StmtKind::If {
+                    cond,
+                    main_stmt,
+                    else_stmt,
+                },
+            ..
+        }) = self.cx.hir_of(else_stmt)?
+        {
+            self.indent += 1;
+            let value = self.bool_expr(*cond, env)?;
+            self.indent -= 1;
+            if self.pending.is_empty() {
+                self.line(format!("end else if ({}) begin", value.unwrapped()));
+                return self.emit_if_tail(*main_stmt, *else_stmt, env);
+            }
+            self.line("end else begin");
+            self.indent += 1;
+            self.flush();
+            self.line(format!("if ({}) begin", value.unwrapped()));
+            self.emit_if_tail(*main_stmt, *else_stmt, env)?;
+            self.indent -= 1;
+            self.line("end");
+            return Ok(());
+        }
+        self.line("end else begin");
+        self.emit_body(else_stmt, env)?;
+        self.line("end");
+        Ok(())
+    }
+
+    fn emit_assignment(
+        &mut self,
+        assign: &'a mir::Assignment<'a>,
+        prefix: &str,
+        op: &str,
+    ) -> Result<()> {
+        for assign in self.cx.mir_simplify_assignment(Ref(assign)) {
+            if assign.is_error() {
+                return Err(());
+            }
+            let lhs = self.lvalue(assign.lhs)?;
+            let rhs = self.rvalue(assign.rhs)?;
+            self.flush();
+            self.line(format!("{}{} {} {};", prefix, lhs, op, rhs.unwrapped()));
+        }
+        Ok(())
+    }
+
+    /// Emit a `case` label with `x` and `z` digits.
+    fn case_label(&self, value: &Value<'a>, width: usize, label_id: NodeId) -> Result<String> {
+        match value.kind {
+            ValueKind::Int(ref int, ref special, ref x) => Ok(constant(int, special, x, width)),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error("case label must be an integer")
+                        .code("SV0046")
+                        .span(self.cx.span(label_id)),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Emit an expression as a single-bit condition.
+    fn bool_expr(&mut self, expr_id: NodeId, env: ParamEnv) -> Result<Expr> {
+        let value = self.rvalue(self.cx.mir_rvalue(expr_id, env))?;
+        Ok(to_bool(value))
+    }
+
+    fn rvalue(&mut self, mir: &'a mir::Rvalue<'a>) -> Result<Expr> {
+        if mir.is_error() {
+            return Err(());
+        }
+        let width = width(self.cx, mir.ty, mir.span)?;
+
+        // Emit constants as their folded value.
+        if mir.is_const() {
+            let value = self.cx.const_mir_rvalue(mir.into());
+            return match value.kind {
+                ValueKind::Int(ref int, ref special, ref x) => {
+                    Ok(Expr::atom(constant(int, special, x, width), width))
+                }
+                _ => unsupported_expr(self.cx, mir),
+            };
+        }
+
+        let expr = match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
+                match self.module.storage_key(&self.storage, id, mir.env) {
+                    Some(key) => {
+                        let (ref name, width) = self.storage[&key];
+                        Expr::signal(name.clone(), width)
+                    }
+                    None => return unsupported_expr(self.cx, mir),
+                }
+            }
+            mir::RvalueKind::CastValueDomain { value, .. }
+            | mir::RvalueKind::Transmute(value)
+            | mir::RvalueKind::CastSign(_, value) => self.rvalue(value)?,
+            mir::RvalueKind::CastToBool(value) => to_bool(self.rvalue(value)?),
+            mir::RvalueKind::Truncate(_, value) => {
+                let value = self.rvalue(value)?;
+                self.fit(value, width)?
+            }
+            mir::RvalueKind::ZeroExtend(_, value) => {
+                let value = self.rvalue(value)?;
+                self.fit(value, width)?
+            }
+            mir::RvalueKind::SignExtend(_, value) => {
+                let value = self.rvalue(value)?;
+                if value.width >= width {
+                    value
+                } else {
+                    let value = self.named(value);
+                    let msb = self.select(&value, value.width - 1, 1);
+                    Expr::atom(
+                        format!(
+                            "{{{{{}{{{}}}}}, {}}}",
+                            width - value.width,
+                            msb.text,
+                            value.text
+                        ),
+                        width,
+                    )
+                }
+            }
+            mir::RvalueKind::UnaryBitwise { arg, .. } => {
+                let arg = self.rvalue(arg)?;
+                Expr::wrapped(format!("~{}", arg.text), arg.width)
+            }
+            mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let op = match op {
+                    mir::BinaryBitwiseOp::And => "&",
+                    mir::BinaryBitwiseOp::Or => "|",
+                    mir::BinaryBitwiseOp::Xor => "^",
+                };
+                binary(op, &lhs, &rhs, width)
+            }
+            mir::RvalueKind::IntUnaryArith { arg, .. } => {
+                let arg = self.rvalue(arg)?;
+                Expr::wrapped(format!("-{}", arg.text), arg.width)
+            }
+            mir::RvalueKind::IntBinaryArith {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                match (op, sign.is_signed()) {
+                    (mir::IntBinaryArithOp::Add, _) => binary("+", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Sub, _) => binary("-", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Mul, _) => binary("*", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Div, false) => binary("/", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Mod, false) => binary("%", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Pow, false) => binary("**", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Div, true) => signed_binary("/", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Mod, true) => signed_binary("%", &lhs, &rhs, width),
+                    (mir::IntBinaryArithOp::Pow, true) => signed_binary("**", &lhs, &rhs, width),
+                }
+            }
+            mir::RvalueKind::IntComp {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs)?;
+                let rhs = self.rvalue(rhs)?;
+                let op = match op {
+                    mir::IntCompOp::Eq => "==",
+                    mir::IntCompOp::Neq => "!=",
+                    mir::IntCompOp::Lt => "<",
+                    mir::IntCompOp::Leq => "<=",
+                    mir::IntCompOp::Gt => ">",
+                    mir::IntCompOp::Geq => ">=",
+                };
+                if sign.is_signed() {
+                    Expr::wrapped(
+                        format!(
+                            "$signed({}) {} $signed({})",
+                            lhs.unwrapped(),
+                            op,
+                            rhs.unwrapped()
+                        ),
+                        1,
+                    )
+                } else {
+                    binary(op, &lhs, &rhs, 1)
+                }
+            }
+            mir::RvalueKind::Concat(ref values) => {
+                let values = values
+                    .iter()
+                    .map(|&v| Ok(self.rvalue(v)?.unwrapped().to_string()))
+                    .collect::<Result<Vec<_>>>()?;
+                Expr::atom(format!("{{{}}}", values.join(", ")), width)
+            }
+            mir::RvalueKind::Repeat(times, value) => {
+                let value = self.rvalue(value)?;
+                Expr::atom(format!("{{{}{{{}}}}}", times, value.unwrapped()), width)
+            }
+            mir::RvalueKind::Index {
+                value,
+                base,
+                length,
+            } => {
+                let value = self.rvalue(value)?;
+                let value = self.named(value);
+                let length = std::cmp::max(1, length);
+                if base.is_const() {
+                    let offset = match self.cx.const_mir_rvalue(base.into()).get_int() {
+                        Some(x) if !x.is_negative() => x.to_usize().unwrap_or(usize::MAX),
+                        _ => return unsupported_expr(self.cx, mir),
+                    };
+                    self.select(&value, offset, length)
+                } else {
+                    let base = self.rvalue(base)?;
+                    if value.width == 1 {
+                        return unsupported_expr(self.cx, mir);
+                    }
+                    Expr::atom(dynamic_select(&value.text, &base, length), length)
+                }
+            }
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = to_bool(self.rvalue(cond)?);
+                let a = self.rvalue(true_value)?;
+                let b = self.rvalue(false_value)?;
+                Expr::wrapped(format!("{} ? {} : {}", cond.text, a.text, b.text), width)
+            }
+            mir::RvalueKind::Shift {
+                op,
+                arith,
+                value,
+                amount,
+            } => {
+                let value = self.rvalue(value)?;
+                let amount = self.rvalue(amount)?;
+                match (op, arith) {
+                    (mir::ShiftOp::Left, _) => binary("<<", &value, &amount, width),
+                    (mir::ShiftOp::Right, false) => binary(">>", &value, &amount, width),
+                    (mir::ShiftOp::Right, true) => Expr::atom(
+                        format!(
+                            "$unsigned($signed({}) >>> {})",
+                            value.unwrapped(),
+                            amount.text
+                        ),
+                        width,
+                    ),
+                }
+            }
+            mir::RvalueKind::Reduction { op, arg } => {
+                let arg = self.rvalue(arg)?;
+                let op = match op {
+                    mir::BinaryBitwiseOp::And => "&",
+                    mir::BinaryBitwiseOp::Or => "|",
+                    mir::BinaryBitwiseOp::Xor => "^",
+                };
+                Expr::wrapped(format!("{}{}", op, arg.text), 1)
+            }
+            _ => return unsupported_expr(self.cx, mir),
+        };
+        Ok(expr)
+    }
+
+    /// Emit an lvalue.
+    fn lvalue(&mut self, mir: &'a mir::Lvalue<'a>) -> Result<String> {
+        if mir.is_error() {
+            return Err(());
+        }
+        match mir.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                if let Some(key) = self.module.storage_key(&self.storage, id, mir.env) {
+                    return Ok(self.storage[&key].0.clone());
+                }
+            }
+            mir::LvalueKind::Transmute(value) => return self.lvalue(value),
+            mir::LvalueKind::Index {
+                value,
+                base,
+                length,
+            } => {
+                let width = width(self.cx, value.ty, value.span)?;
+                let value = Expr::signal(self.lvalue(value)?, width);
+                let length = std::cmp::max(1, length);
+                if base.is_const() {
+                    if let Some(offset) = self
+                        .cx
+                        .const_mir_rvalue(base.into())
+                        .get_int()
+                        .and_then(|x| x.to_usize())
+                    {
+                        return Ok(self.select(&value, offset, length).text);
+                    }
+                } else if width > 1 {
+                    let base = self.rvalue(base)?;
+                    return Ok(dynamic_select(&value.text, &base, length));
+                }
+            }
+            mir::LvalueKind::Concat(ref values) => {
+                let values = values
+                    .iter()
+                    .map(|&v| self.lvalue(v))
+                    .collect::<Result<Vec<_>>>()?;
+                return Ok(format!("{{{}}}", values.join(", ")));
+            }
+            _ => (),
+        }
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "assignment to `{}` not supported by this output format",
+                mir.span.extract()
+            ))
+            .code("SV0046")
+            .span(mir.span),
+        );
+        Err(())
+    }
+
+    /// Select a range of bits from a signal.
+    fn select(&self, value: &Expr, offset: usize, length: usize) -> Expr {
+        if value.width == 1 && offset == 0 && length == 1 {
+            value.clone()
+        } else if length == 1 {
+            Expr::atom(format!("{}[{}]", value.text, offset), 1)
+        } else {
+            Expr::atom(
+                format!("{}[{}:{}]", value.text, offset + length - 1, offset),
+                length,
+            )
+        }
+    }
+
+    /// Truncate or zero-extend an expression to a width, assigning it to a
+    /// temporary first if bits need to be selected from it.
+    fn fit(&mut self, value: Expr, width: usize) -> Result<Expr> {
+        let value = if value.width > width {
+            self.named(value)
+        } else {
+            value
+        };
+        Ok(fit(value, width))
+    }
+
+    /// Make an expression a signal that can be indexed, by assigning it to a
+    /// temporary if needed.
+    fn named(&mut self, value: Expr) -> Expr {
+        if value.name {
+            return value;
+        }
+        let name = self.unique_name("_t");
+        if self.procedural {
+            self.decl(format!("reg {}{};", range(value.width), name));
+            self.pending
+                .push(format!("{} = {};", name, value.unwrapped()));
+        } else {
+            self.decl(format!("wire {}{};", range(value.width), name));
+            self.pending
+                .push(format!("assign {} = {};", name, value.unwrapped()));
+        }
+        Expr::signal(name, value.width)
+    }
+
+    /// Allocate a unique name derived from a name in the source text.
+    fn unique_name(&mut self, name: &str) -> String {
+        unique(&mut self.names, &sanitize(name))
+    }
+
+    /// Emit the pending assignments to temporaries.
+    fn flush(&mut self) {
+        for line in std::mem::take(&mut self.pending) {
+            self.line(line);
+        }
+    }
+
+    fn decl(&mut self, line: impl AsRef<str>) {
+        self.decls.push_str("    ");
+        self.decls.push_str(line.as_ref());
+        self.decls.push('\n');
+    }
+
+    fn line(&mut self, line: impl AsRef<str>) {
+        for _ in 0..self.indent {
+            self.body.push_str("    ");
+        }
+        self.body.push_str(line.as_ref());
+        self.body.push('\n');
+    }
+}
+
+fn binary(op: &str, lhs: &Expr, rhs: &Expr, width: usize) -> Expr {
+    Expr::wrapped(format!("{} {} {}", lhs.text, op, rhs.text), width)
+}
+
+fn signed_binary(op: &str, lhs: &Expr, rhs: &Expr, width: usize) -> Expr {
+    Expr::atom(
+        format!(
+            "$unsigned($signed({}) {} $signed({}))",
+            lhs.unwrapped(),
+            op,
+            rhs.unwrapped()
+        ),
+        width,
+    )
+}
+
+/// Select a range of bits from a signal at a dynamic offset.
+fn dynamic_select(value: &str, base: &Expr, length: usize) -> String {
+    if length == 1 {
+        format!("{}[{}]", value, base.unwrapped())
+    } else {
+        format!("{}[{} +: {}]", value, base.unwrapped(), length)
+    }
+}
+
+/// Format the range of a vector declaration.
+fn range(width: usize) -> String {
+    if width == 1 {
+        String::new()
+    } else {
+        format!("[{}:0] ", width - 1)
+    }
+}
+
+/// Emit an integer constant of a given width. Unknown and high-impedance bits
+/// are emitted as binary `x` and `z` digits.
+fn constant(
+    value: &BigInt,
+    special: &bit_vec::BitVec,
+    x: &bit_vec::BitVec,
+    width: usize,
+) -> String {
+    let modulus = BigInt::one() << width;
+    let mut value = value % &modulus;
+    if value.is_negative() {
+        value += modulus;
+    }
+    if !special.any() {
+        return format!("{}'d{}", width, value);
+    }
+
+    // The special bits are stored with the most significant digit first and
+    // only cover the digits spelled out in the literal.
+    let mut digits = String::new();
+    for bit in (0..width).rev() {
+        let digit = match special.len().checked_sub(bit + 1) {
+            Some(i) if special[i] && x[i] => 'x',
+            Some(i) if special[i] => 'z',
+            _ if (&value >> bit) & BigInt::one() == BigInt::one() => '1',
+            _ => '0',
+        };
+        digits.push(digit);
+    }
+    format!("{}'b{}", width, digits)
+}
@@ -0,0 +1,283 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Cross-reference index of the SystemVerilog input.
+//!
+//! The index lists every definition of and reference to a module, interface,
+//! package, instance, port, signal, parameter, and macro, together with its
+//! location. Names in expressions are resolved to find the declaration they
+//! refer to. The index is written in the extended ctags format, where the
+//! `roles` field tells definitions from references, such that editors without
+//! language server support can navigate the input.
+
+use crate::{
+    ast::AcceptVisitor as _, common::design_units::DesignUnitKind, crate_prelude::*,
+    resolver::DefNode,
+};
+use std::io::{Result as IoResult, Write};
+
+/// A cross-reference index.
+#[derive(Debug, Default)]
+pub struct Xref {
+    /// The definitions and references, in the order they appear in the input.
+    pub entries: Vec<XrefEntry>,
+}
+
+/// A definition of or a reference to a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XrefEntry {
+    /// The name being defined or referenced.
+    pub name: Name,
+    /// What the name denotes.
+    pub kind: XrefKind,
+    /// Whether this is the definition of the name, rather than a reference.
+    pub def: bool,
+    /// The location of the name.
+    pub span: Span,
+}
+
+/// The things a name in the index may denote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XrefKind {
+    /// A module.
+    Module,
+    /// An interface.
+    Interface,
+    /// A package.
+    Package,
+    /// An instance of a module or interface.
+    Instance,
+    /// A port of a module or interface.
+    Port,
+    /// A net or variable.
+    Signal,
+    /// A parameter.
+    Param,
+    /// A text macro.
+    Macro,
+}
+
+impl XrefKind {
+    /// The kind letter used for SystemVerilog by Universal Ctags.
+    pub fn letter(self) -> char {
+        match self {
+            XrefKind::Module => 'm',
+            XrefKind::Interface => 'I',
+            XrefKind::Package => 'K',
+            XrefKind::Instance => 'i',
+            XrefKind::Port => 'p',
+            XrefKind::Signal => 'n',
+            XrefKind::Param => 'c',
+            XrefKind::Macro => 'd',
+        }
+    }
+}
+
+impl std::fmt::Display for XrefKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            XrefKind::Module => write!(f, "module"),
+            XrefKind::Interface => write!(f, "interface"),
+            XrefKind::Package => write!(f, "package"),
+            XrefKind::Instance => write!(f, "instance"),
+            XrefKind::Port => write!(f, "port"),
+            XrefKind::Signal => write!(f, "signal"),
+            XrefKind::Param => write!(f, "parameter"),
+            XrefKind::Macro => write!(f, "macro"),
+        }
+    }
+}
+
+impl Xref {
+    /// Index all files of the input.
+    pub fn new<'a>(cx: &impl Context<'a>) -> Self {
+        let mut collector = Collector {
+            cx,
+            entries: vec![],
+        };
+        for root in cx.gcx().roots() {
+            root.accept(&mut collector);
+        }
+        Xref {
+            entries: collector.entries,
+        }
+    }
+
+    /// Write the index in the extended ctags format, sorted by name.
+    pub fn write_ctags(&self, w: &mut impl Write) -> IoResult<()> {
+        let mut lines: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let loc = entry.span.begin();
+                (
+                    entry.name.to_string(),
+                    loc.source.get_path().to_string(),
+                    loc.human_line(),
+                    entry,
+                )
+            })
+            .collect();
+        lines.sort_by(|a, b| (&a.0, &a.1, a.2).cmp(&(&b.0, &b.1, b.2)));
+        lines.dedup_by(|a, b| (&a.0, &a.1, a.2, a.3) == (&b.0, &b.1, b.2, b.3));
+
+        writeln!(
+            w,
+            "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/"
+        )?;
+        writeln!(
+            w,
+            "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/"
+        )?;
+        writeln!(w, "!_TAG_PROGRAM_NAME\tmoore\t//")?;
+        for (name, path, line, entry) in lines {
+            writeln!(
+                w,
+                "{}\t{}\t{};\"\t{}\tline:{}\troles:{}",
+                name,
+                path,
+                line,
+                entry.kind.letter(),
+                line,
+                if entry.def { "def" } else { "ref" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A visitor that collects the definitions and references in the AST.
+struct Collector<'cx, C> {
+    cx: &'cx C,
+    entries: Vec<XrefEntry>,
+}
+
+impl<'cx, C> Collector<'cx, C> {
+    fn add(&mut self, name: Spanned<Name>, kind: XrefKind, def: bool) {
+        self.entries.push(XrefEntry {
+            name: name.value,
+            kind,
+            def,
+            span: name.span,
+        });
+    }
+}
+
+impl<'a, 'cx, C> ast::Visitor<'a> for Collector<'cx, C>
+where
+    C: Context<'a>,
+    'a: 'cx,
+{
+    fn pre_visit_source_file(&mut self, node: &'a ast::SourceFile<'a>) -> bool {
+        for m in &node.macros {
+            self.add(Spanned::new(m.name, m.span), XrefKind::Macro, m.def);
+        }
+        true
+    }
+
+    fn pre_visit_module(&mut self, node: &'a ast::Module<'a>) -> bool {
+        self.add(node.name, XrefKind::Module, true);
+        true
+    }
+
+    fn pre_visit_interface(&mut self, node: &'a ast::Interface<'a>) -> bool {
+        self.add(node.name, XrefKind::Interface, true);
+        true
+    }
+
+    fn pre_visit_package(&mut self, node: &'a ast::Package<'a>) -> bool {
+        self.add(node.name, XrefKind::Package, true);
+        true
+    }
+
+    fn pre_visit_inst(&mut self, node: &'a ast::Inst<'a>) -> bool {
+        let is_interface = self
+            .cx
+            .sess()
+            .units
+            .find(None, node.target.value)
+            .iter()
+            .any(|unit| unit.kind == DesignUnitKind::Interface);
+        let kind = if is_interface {
+            XrefKind::Interface
+        } else {
+            XrefKind::Module
+        };
+        self.add(node.target, kind, false);
+        true
+    }
+
+    fn pre_visit_inst_name(&mut self, node: &'a ast::InstName<'a>) -> bool {
+        self.add(node.name, XrefKind::Instance, true);
+        true
+    }
+
+    fn pre_visit_port(&mut self, node: &'a ast::Port<'a>) -> bool {
+        match node.data {
+            ast::PortData::Intf { name, .. }
+            | ast::PortData::Explicit { name, .. }
+            | ast::PortData::Named { name, .. } => self.add(name, XrefKind::Port, true),
+            ast::PortData::Implicit(_) => (),
+        }
+        true
+    }
+
+    fn pre_visit_var_decl_name(&mut self, node: &'a ast::VarDeclName<'a>) -> bool {
+        if let Some(kind) = var_decl_kind(node) {
+            self.add(Spanned::new(node.name, node.name_span), kind, true);
+        }
+        true
+    }
+
+    fn pre_visit_param_value_decl(&mut self, node: &'a ast::ParamValueDecl<'a>) -> bool {
+        self.add(node.name, XrefKind::Param, true);
+        true
+    }
+
+    fn pre_visit_param_type_decl(&mut self, node: &'a ast::ParamTypeDecl<'a>) -> bool {
+        self.add(node.name, XrefKind::Param, true);
+        true
+    }
+
+    fn pre_visit_expr(&mut self, node: &'a ast::Expr<'a>) -> bool {
+        if let ast::IdentExpr(name) = node.data {
+            let def = self
+                .cx
+                .resolve_local(name.value, self.cx.scope_location(node), false)
+                .unwrap_or(None);
+            if let Some(kind) = def.and_then(|def| def_kind(def.node)) {
+                self.add(name, kind, false);
+            }
+        }
+        true
+    }
+}
+
+/// Determine what a name declared by a variable or net declaration denotes.
+///
+/// Returns `None` for struct members.
+fn var_decl_kind<'a>(node: &'a ast::VarDeclName<'a>) -> Option<XrefKind> {
+    let parent = node.get_parent().map(|p| p.as_all());
+    match parent {
+        Some(ast::AllNode::StructMember(_)) => None,
+        Some(ast::AllNode::PortDecl(_)) => Some(XrefKind::Port),
+        _ => Some(XrefKind::Signal),
+    }
+}
+
+/// Determine what the definition a name resolved to denotes.
+fn def_kind<'a>(node: DefNode<'a>) -> Option<XrefKind> {
+    match node {
+        DefNode::IntPort(_) => Some(XrefKind::Port),
+        DefNode::Ast(node) => match node.as_all() {
+            ast::AllNode::VarDeclName(x) => var_decl_kind(x),
+            ast::AllNode::ParamValueDecl(_) | ast::AllNode::ParamTypeDecl(_) => {
+                Some(XrefKind::Param)
+            }
+            ast::AllNode::InstName(_) => Some(XrefKind::Instance),
+            ast::AllNode::Module(_) => Some(XrefKind::Module),
+            ast::AllNode::Interface(_) => Some(XrefKind::Interface),
+            ast::AllNode::Package(_) => Some(XrefKind::Package),
+            _ => None,
+        },
+    }
+}
@@ -0,0 +1,1346 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Emission of the elaborated design as a Yosys JSON netlist.
+//!
+//! This backend maps every module specialization of a design onto the cells
+//! of the Yosys internal cell library, such as `$add`, `$mux`, and `$dff`, and
+//! writes the result in the JSON format produced by Yosys' `write_json`. This
+//! allows the output to be consumed by tools of the open-source FPGA flow,
+//! such as nextpnr, or to be read back into Yosys for technology mapping.
+//!
+//! Procedures are converted into dataflow by evaluating their statements
+//! symbolically: every branch produces a set of assigned values, which are
+//! merged by multiplexers. Variables assigned by a clocked procedure are
+//! registered with a `$dff` or `$adff` cell. Signals are represented as lists
+//! of bits, where each bit is either a net or a constant. Assignments between
+//! signals merge their nets.
+
+use crate::{
+    crate_prelude::*,
+    design::{
+        clocked_events, unsupported, unsupported_expr, width, ClockedEvents, Design, DesignInst,
+        DesignModule,
+    },
+    hir::HirNode,
+    value::ValueKind,
+    ParamEnv,
+};
+use num::{BigInt, One, Signed, ToPrimitive};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::collections::{BTreeSet, HashMap};
+
+/// Serialize a design as a Yosys JSON netlist.
+pub fn emit_design<'a>(cx: &impl Context<'a>, design: &Design<'a>) -> Result<String> {
+    let mut modules = Map::default();
+    for (index, module) in design.modules.iter().enumerate() {
+        let mut emitter = Emitter {
+            cx,
+            design,
+            module,
+            nets: vec![],
+            consts: HashMap::new(),
+            signals: HashMap::new(),
+            cells: Map::default(),
+        };
+        let mut output = emitter.emit_module()?;
+        if design.tops.contains(&index) {
+            output.attributes.insert("top", param(1, 32));
+        }
+        modules.insert(&module.name, output);
+    }
+    let netlist = Netlist {
+        creator: format!("moore {}", env!("CARGO_PKG_VERSION")),
+        modules,
+    };
+    let mut text = vec![];
+    let mut ser = serde_json::Serializer::with_formatter(&mut text, Formatter::default());
+    netlist.serialize(&mut ser).unwrap();
+    text.push(b'\n');
+    Ok(String::from_utf8(text).unwrap())
+}
+
+/// A netlist in the Yosys JSON format.
+#[derive(Serialize)]
+struct Netlist {
+    creator: String,
+    modules: Map<Module>,
+}
+
+/// A module in the Yosys JSON format.
+#[derive(Serialize)]
+struct Module {
+    attributes: Map<String>,
+    ports: Map<Port>,
+    cells: Map<Cell>,
+    netnames: Map<NetName>,
+}
+
+/// A port of a module.
+#[derive(Serialize)]
+struct Port {
+    direction: &'static str,
+    bits: Vec<Bit>,
+}
+
+/// A cell within a module.
+#[derive(Serialize)]
+struct Cell {
+    hide_name: u8,
+    #[serde(rename = "type")]
+    kind: String,
+    parameters: Map<String>,
+    attributes: Map<String>,
+    port_directions: Map<&'static str>,
+    connections: Map<Vec<Bit>>,
+}
+
+/// A named signal within a module.
+#[derive(Serialize)]
+struct NetName {
+    hide_name: u8,
+    bits: Vec<Bit>,
+    attributes: Map<String>,
+}
+
+/// A map that serializes its entries in insertion order.
+struct Map<T>(Vec<(String, T)>);
+
+impl<T> Default for Map<T> {
+    fn default() -> Self {
+        Map(vec![])
+    }
+}
+
+impl<T> Map<T> {
+    fn insert(&mut self, key: impl Into<String>, value: T) {
+        self.0.push((key.into(), value));
+    }
+}
+
+impl<T: Serialize> Serialize for Map<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// A JSON formatter that follows the layout of Yosys' `write_json`, with the
+/// entries of objects on separate lines and arrays on a single line.
+#[derive(Default)]
+struct Formatter {
+    indent: usize,
+}
+
+impl Formatter {
+    fn newline<W: ?Sized + std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(b"\n")?;
+        for _ in 0..self.indent {
+            writer.write_all(b"  ")?;
+        }
+        Ok(())
+    }
+}
+
+impl serde_json::ser::Formatter for Formatter {
+    fn begin_object<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.indent += 1;
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.indent -= 1;
+        self.newline(writer)?;
+        writer.write_all(b"}")
+    }
+
+    fn begin_object_key<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        if !first {
+            writer.write_all(b",")?;
+        }
+        self.newline(writer)
+    }
+
+    fn begin_object_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        writer.write_all(b": ")
+    }
+
+    fn begin_array_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        writer.write_all(if first { b" " } else { b", " })
+    }
+
+    fn end_array<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(b" ]")
+    }
+}
+
+/// A single bit of a signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bit {
+    /// A net, identified by a number starting at 2.
+    Net(usize),
+    /// A constant `0`, `1`, `x`, or `z`.
+    Const(char),
+}
+
+impl Serialize for Bit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match *self {
+            Bit::Net(id) => serializer.serialize_u64(id as u64),
+            Bit::Const(c) => serializer.serialize_str(c.encode_utf8(&mut [0; 4])),
+        }
+    }
+}
+
+/// A list of bits, with the least significant bit first.
+type Bits = Vec<Bit>;
+
+/// The values assigned to variables while evaluating a procedure.
+#[derive(Default, Clone)]
+struct State {
+    /// The values assigned with blocking assignments, which are visible to
+    /// subsequent statements.
+    blocking: HashMap<(NodeId, ParamEnv), Bits>,
+    /// The values assigned with nonblocking assignments.
+    nonblocking: HashMap<(NodeId, ParamEnv), Bits>,
+}
+
+/// A helper to emit a single module specialization.
+struct Emitter<'a, 'c, C> {
+    cx: &'c C,
+    design: &'c Design<'a>,
+    module: &'c DesignModule<'a>,
+    /// The union-find forest of merged nets, indexed by net number.
+    nets: Vec<usize>,
+    /// The constant values merged into nets, keyed by the root net.
+    consts: HashMap<usize, char>,
+    /// The bits of the ports and variables of the module.
+    signals: HashMap<(NodeId, ParamEnv), Bits>,
+    cells: Map<Cell>,
+}
+
+impl<'a, 'c, C: Context<'a>> Emitter<'a, 'c, C> {
+    fn emit_module(&mut self) -> Result<Module> {
+        let module = self.module;
+        let env = module.id.env();
+
+        // Allocate the nets of the ports and variables.
+        for port in &module.ports {
+            let width = width(self.cx, port.ty, port.port.span)?;
+            let bits = self.alloc(width);
+            self.signals.insert((port.port.id, env), bits);
+        }
+        for decl in &module.decls {
+            let width = width(self.cx, decl.ty, decl.hir.span)?;
+            let bits = self.alloc(width);
+            self.signals.insert((decl.id, decl.env), bits);
+        }
+
+        // Variables with an initial value that are not driven by a procedure
+        // are treated as continuously assigned.
+        let mut driven = BTreeSet::new();
+        for &id in &module.procs {
+            let stmt = match self.cx.hir_of(id.id())? {
+                HirNode::Proc(x) => x.stmt,
+                _ => unreachable!(),
+            };
+            let acc = self.cx.accessed_nodes(stmt, id.env())?;
+            for node in &acc.written {
+                if let Some(key) = self.signal_key(node.id(), id.env()) {
+                    driven.insert(key);
+                }
+            }
+        }
+        for decl in &module.decls {
+            if let (Some(init), false) = (decl.hir.init, driven.contains(&(decl.id, decl.env))) {
+                let value = self.rvalue(self.cx.mir_rvalue(init, decl.env), None)?;
+                let bits = self.signals[&(decl.id, decl.env)].clone();
+                self.connect(&bits, &value);
+            }
+        }
+
+        // Emit the continuous assignments, instances, and procedures.
+        for &id in &module.assigns {
+            let hir = match self.cx.hir_of(id.id())? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+            let assign = self.cx.mir_assignment_from_concurrent(Ref(hir), id.env());
+            for assign in self.cx.mir_simplify_assignment(Ref(assign)) {
+                if assign.is_error() {
+                    return Err(());
+                }
+                let lhs = self.lvalue_bits(assign.lhs)?;
+                let rhs = self.rvalue(assign.rhs, None)?;
+                self.connect(&lhs, &rhs);
+            }
+        }
+        for inst in &module.insts {
+            self.emit_inst(inst)?;
+        }
+        for &id in &module.procs {
+            self.emit_proc(id.id(), id.env())?;
+        }
+
+        // Assemble the module, replacing merged nets by their representative.
+        let mut ports = Map::default();
+        let mut netnames = Map::default();
+        for port in &module.ports {
+            let bits = self.signals[&(port.port.id, env)].clone();
+            let bits = self.resolve(&bits);
+            let direction = match port.port.dir {
+                ast::PortDir::Input => "input",
+                ast::PortDir::Output => "output",
+                ast::PortDir::Inout => "inout",
+                dir => {
+                    self.cx.emit(
+                        DiagBuilder2::error(format!(
+                            "{} port `{}` not supported by this output format",
+                            dir, port.port.name
+                        ))
+                        .code("SV0046")
+                        .span(port.port.span),
+                    );
+                    return Err(());
+                }
+            };
+            let name = port.port.name.value.to_string();
+            ports.insert(
+                name.clone(),
+                Port {
+                    direction,
+                    bits: bits.clone(),
+                },
+            );
+            netnames.insert(
+                name,
+                NetName {
+                    hide_name: 0,
+                    bits,
+                    attributes: Map::default(),
+                },
+            );
+        }
+        for decl in &module.decls {
+            let bits = self.signals[&(decl.id, decl.env)].clone();
+            let bits = self.resolve(&bits);
+            netnames.insert(
+                decl.name.clone(),
+                NetName {
+                    hide_name: 0,
+                    bits,
+                    attributes: Map::default(),
+                },
+            );
+        }
+        let mut cells = std::mem::take(&mut self.cells);
+        for (_, cell) in &mut cells.0 {
+            for (_, bits) in &mut cell.connections.0 {
+                *bits = self.resolve(bits);
+            }
+        }
+        Ok(Module {
+            attributes: Map::default(),
+            ports,
+            cells,
+            netnames,
+        })
+    }
+
+    fn emit_inst(&mut self, inst: &DesignInst) -> Result<()> {
+        let target = &self.design.modules[inst.module];
+        let inner_env = target.id.env();
+        let mut port_directions = Map::default();
+        let mut connections = Map::default();
+        for (port, &mapping) in target.ports.iter().zip(&inst.ports) {
+            let width = width(self.cx, port.ty, port.port.span)?;
+            let (direction, bits) = match port.port.dir {
+                ast::PortDir::Input => {
+                    let value = match (mapping, port.port.data.as_ref().and_then(|d| d.default)) {
+                        (Some(mapping), _) => {
+                            self.rvalue(self.cx.mir_rvalue(mapping.id(), mapping.env()), None)?
+                        }
+                        (None, Some(default)) => {
+                            self.rvalue(self.cx.mir_rvalue(default, inner_env), None)?
+                        }
+                        (None, None) => vec![Bit::Const('x'); width],
+                    };
+                    ("input", extend(value, width, Bit::Const('0')))
+                }
+                dir => {
+                    let bits = match mapping {
+                        Some(mapping) => {
+                            let mir = self.cx.mir_lvalue(mapping.id(), mapping.env());
+                            self.lvalue_bits(mir)?
+                        }
+                        None => vec![],
+                    };
+                    let mut bits = bits;
+                    bits.truncate(width);
+                    let extra = width - bits.len();
+                    bits.extend(self.alloc(extra));
+                    let dir = match dir {
+                        ast::PortDir::Inout => "inout",
+                        _ => "output",
+                    };
+                    (dir, bits)
+                }
+            };
+            let name = port.port.name.value.to_string();
+            port_directions.insert(name.clone(), direction);
+            connections.insert(name, bits);
+        }
+        self.cells.insert(
+            inst.name.clone(),
+            Cell {
+                hide_name: 0,
+                kind: target.name.clone(),
+                parameters: Map::default(),
+                attributes: Map::default(),
+                port_directions,
+                connections,
+            },
+        );
+        Ok(())
+    }
+
+    fn emit_proc(&mut self, proc_id: NodeId, env: ParamEnv) -> Result<()> {
+        let hir = match self.cx.hir_of(proc_id)? {
+            HirNode::Proc(x) => x,
+            _ => unreachable!(),
+        };
+        let timed = match self.cx.hir_of(hir.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind: hir::StmtKind::Timed { control, stmt },
+                ..
+            }) => Some((*control, *stmt)),
+            _ => None,
+        };
+        match (hir.kind, timed) {
+            (
+                ast::ProcedureKind::AlwaysComb,
+                None | Some((hir::TimingControl::ImplicitEvent, _)),
+            )
+            | (ast::ProcedureKind::Always, Some((hir::TimingControl::ImplicitEvent, _))) => {
+                let mut state = State::default();
+                self.exec(hir.stmt, env, &mut state, false)?;
+                for (key, value) in finish(state) {
+                    if let Some(bits) = self.signals.get(&key).cloned() {
+                        self.connect(&bits, &value);
+                    }
+                }
+                Ok(())
+            }
+            (
+                ast::ProcedureKind::Always | ast::ProcedureKind::AlwaysFf,
+                Some((hir::TimingControl::ExplicitEvent(event), body)),
+            ) => self.emit_clocked_proc(event, body, env),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "{} not supported by this output format",
+                        hir.desc_full()
+                    ))
+                    .code("SV0046")
+                    .span(hir.span)
+                    .add_note(
+                        "Only `always_comb`, `always @*`, and procedures triggered by a clock \
+                         edge can be mapped to cells",
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Emit a procedure triggered by a clock edge and an optional
+    /// asynchronous reset.
+    fn emit_clocked_proc(&mut self, event_id: NodeId, body: NodeId, env: ParamEnv) -> Result<()> {
+        let ClockedEvents {
+            clock: clock_event,
+            reset,
+        } = clocked_events(self.cx, event_id, body, env)?;
+        for event in Some(clock_event)
+            .into_iter()
+            .chain(reset.as_ref().map(|r| r.event))
+        {
+            if !event.iff.is_empty() {
+                self.cx.emit(
+                    DiagBuilder2::error(
+                        "`iff` event qualifiers not supported by this output format",
+                    )
+                    .code("SV0046")
+                    .span(event.span),
+                );
+                return Err(());
+            }
+        }
+        let clock = self.edge_signal(clock_event, env)?;
+
+        // Evaluate the body, or the reset and non-reset branches separately.
+        let mut state = State::default();
+        let mut reset_values = HashMap::new();
+        let mut arst = None;
+        match reset {
+            Some(reset) => {
+                let mut reset_state = State::default();
+                self.exec(reset.main_stmt, env, &mut reset_state, true)?;
+                reset_values = finish(reset_state);
+                if let Some(else_stmt) = reset.else_stmt {
+                    self.exec(else_stmt, env, &mut state, true)?;
+                }
+                arst = Some((self.edge_signal(reset.event, env)?, reset.cond));
+            }
+            None => self.exec(body, env, &mut state, true)?,
+        }
+
+        // Register every assigned variable.
+        let mut next = finish(state);
+        for key in reset_values.keys() {
+            if !next.contains_key(key) {
+                next.insert(*key, self.signals[key].clone());
+            }
+        }
+        let mut keys: Vec<_> = next.keys().cloned().collect();
+        keys.sort();
+        let mut hold_cond = None;
+        for key in keys {
+            let q = match self.signals.get(&key) {
+                Some(bits) => bits.clone(),
+                None => continue,
+            };
+            let mut d = next.remove(&key).unwrap();
+            let mut cell = Cell::new("$dff");
+            cell.param("WIDTH", param(q.len(), 32));
+            cell.param("CLK_POLARITY", param(clock.1 as usize, 1));
+            match (&arst, reset_values.get(&key)) {
+                (Some(((arst, polarity), _)), Some(value)) => {
+                    let value = match const_bits(value) {
+                        Some(v) => v,
+                        None => {
+                            self.cx.emit(
+                                DiagBuilder2::error(format!(
+                                    "reset value of `{}` must be a constant for this output \
+                                     format",
+                                    self.signal_name(key)
+                                ))
+                                .code("SV0046")
+                                .span(self.cx.span(body)),
+                            );
+                            return Err(());
+                        }
+                    };
+                    cell.kind = "$adff".to_string();
+                    cell.param("ARST_POLARITY", param(*polarity as usize, 1));
+                    cell.param("ARST_VALUE", value);
+                    cell.conn("ARST", "input", vec![*arst]);
+                }
+                // Variables that are not reset hold their value while the
+                // reset is asserted.
+                (Some((_, cond)), None) => {
+                    let cond = match hold_cond {
+                        Some(x) => x,
+                        None => {
+                            let bits = self.rvalue(self.cx.mir_rvalue(*cond, env), None)?;
+                            let bit = self.reduce("$reduce_bool", bits);
+                            hold_cond = Some(bit);
+                            bit
+                        }
+                    };
+                    d = self.mux(&d, &q, cond);
+                }
+                _ => (),
+            }
+            cell.conn("CLK", "input", vec![clock.0]);
+            cell.conn("D", "input", d);
+            cell.conn("Q", "output", q);
+            self.add_cell(cell);
+        }
+        Ok(())
+    }
+
+    /// Determine the bit and polarity of a clock or reset event.
+    fn edge_signal(&mut self, event: &hir::Event, env: ParamEnv) -> Result<(Bit, bool)> {
+        let polarity = match event.edge {
+            ast::EdgeIdent::Posedge => true,
+            ast::EdgeIdent::Negedge => false,
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(
+                        "only `posedge` and `negedge` events are supported by this output format",
+                    )
+                    .code("SV0046")
+                    .span(event.span),
+                );
+                return Err(());
+            }
+        };
+        let bits = self.rvalue(self.cx.mir_rvalue(event.expr, env), None)?;
+        Ok((bits[0], polarity))
+    }
+
+    /// Evaluate a statement symbolically.
+    fn exec(
+        &mut self,
+        stmt_id: NodeId,
+        env: ParamEnv,
+        state: &mut State,
+        clocked: bool,
+    ) -> Result<()> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            HirNode::VarDecl(decl) => {
+                let ty = self.cx.type_of(stmt_id, env)?;
+                let width = width(self.cx, ty, decl.span)?;
+                let value = match decl.init {
+                    Some(init) => self.rvalue(self.cx.mir_rvalue(init, env), Some(state))?,
+                    None => vec![Bit::Const('x'); width],
+                };
+                state.blocking.insert((stmt_id, env), value);
+                return Ok(());
+            }
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Null => (),
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    self.exec(stmt, env, state, clocked)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, kind } => {
+                if let hir::AssignKind::NonblockDelay(_) = kind {
+                    return unsupported(self.cx, hir);
+                }
+                let assign = self
+                    .cx
+                    .mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                for assign in self.cx.mir_simplify_assignment(Ref(assign)) {
+                    if assign.is_error() {
+                        return Err(());
+                    }
+                    let value = self.rvalue(assign.rhs, Some(state))?;
+                    self.assign(assign.lhs, value, kind, state)?;
+                }
+            }
+            hir::StmtKind::Timed {
+                control: hir::TimingControl::ImplicitEvent,
+                stmt,
+            } => self.exec(stmt, env, state, clocked)?,
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                let cond = self.rvalue(self.cx.mir_rvalue(cond, env), Some(state))?;
+                let cond = self.reduce("$reduce_bool", cond);
+                let mut main_state = state.clone();
+                self.exec(main_stmt, env, &mut main_state, clocked)?;
+                let mut else_state = state.clone();
+                if let Some(else_stmt) = else_stmt {
+                    self.exec(else_stmt, env, &mut else_state, clocked)?;
+                }
+                *state = self.merge(cond, main_state, else_state, clocked);
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                let value = self.rvalue(self.cx.mir_rvalue(expr, env), Some(state))?;
+                let mut branches = vec![];
+                for &(ref labels, stmt) in ways {
+                    let mut checks = vec![];
+                    for &label in labels {
+                        checks.push(self.case_check(&value, label, env, kind)?);
+                    }
+                    let cond = match checks.len() {
+                        0 => continue,
+                        1 => checks[0],
+                        _ => self.reduce("$reduce_or", checks),
+                    };
+                    let mut way_state = state.clone();
+                    self.exec(stmt, env, &mut way_state, clocked)?;
+                    branches.push((cond, way_state));
+                }
+                let mut result = state.clone();
+                if let Some(default) = default {
+                    self.exec(default, env, &mut result, clocked)?;
+                }
+                for (cond, way_state) in branches.into_iter().rev() {
+                    result = self.merge(cond, way_state, result, clocked);
+                }
+                *state = result;
+            }
+            _ => return unsupported(self.cx, hir),
+        }
+        Ok(())
+    }
+
+    /// Merge the states of two branches of a conditional statement.
+    fn merge(&mut self, cond: Bit, then_state: State, else_state: State, clocked: bool) -> State {
+        let mut result = State::default();
+        let merge_map = |this: &mut Self,
+                         a: HashMap<(NodeId, ParamEnv), Bits>,
+                         mut b: HashMap<(NodeId, ParamEnv), Bits>| {
+            let mut keys: Vec<_> = a.keys().chain(b.keys()).cloned().collect();
+            keys.sort();
+            keys.dedup();
+            let mut a = a;
+            let mut merged = HashMap::new();
+            for key in keys {
+                let hold = || match this.signals.get(&key) {
+                    Some(bits) if clocked => bits.clone(),
+                    Some(bits) => vec![Bit::Const('x'); bits.len()],
+                    None => vec![],
+                };
+                let t = a.remove(&key).unwrap_or_else(hold);
+                let f = b.remove(&key).unwrap_or_else(hold);
+                if t.is_empty() || f.is_empty() {
+                    continue;
+                }
+                let value = if t == f { t } else { this.mux(&f, &t, cond) };
+                merged.insert(key, value);
+            }
+            merged
+        };
+        result.blocking = merge_map(self, then_state.blocking, else_state.blocking);
+        result.nonblocking = merge_map(self, then_state.nonblocking, else_state.nonblocking);
+        result
+    }
+
+    /// Assign a value to an lvalue within a procedure.
+    fn assign(
+        &mut self,
+        lhs: &'a mir::Lvalue<'a>,
+        value: Bits,
+        kind: hir::AssignKind,
+        state: &mut State,
+    ) -> Result<()> {
+        if lhs.is_error() {
+            return Err(());
+        }
+        match lhs.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                if let Some(key) = self
+                    .signal_key(id, lhs.env)
+                    .or_else(|| Some((id, lhs.env)).filter(|key| state.blocking.contains_key(key)))
+                {
+                    let map = match kind {
+                        hir::AssignKind::Nonblock => &mut state.nonblocking,
+                        _ => &mut state.blocking,
+                    };
+                    map.insert(key, value);
+                    return Ok(());
+                }
+            }
+            mir::LvalueKind::Transmute(inner) => return self.assign(inner, value, kind, state),
+            mir::LvalueKind::Index {
+                value: inner,
+                base,
+                length,
+            } if base.is_const() => {
+                let offset = self
+                    .cx
+                    .const_mir_rvalue(base.into())
+                    .get_int()
+                    .and_then(|x| x.to_usize());
+                if let Some(offset) = offset {
+                    let mut bits = self.lvalue_current(inner, kind, state)?;
+                    let length = std::cmp::max(1, length);
+                    for (i, bit) in value.into_iter().take(length).enumerate() {
+                        if let Some(b) = bits.get_mut(offset + i) {
+                            *b = bit;
+                        }
+                    }
+                    return self.assign(inner, bits, kind, state);
+                }
+            }
+            _ => (),
+        }
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "assignment to `{}` not supported by this output format",
+                lhs.span.extract()
+            ))
+            .code("SV0046")
+            .span(lhs.span),
+        );
+        Err(())
+    }
+
+    /// Determine the value of an lvalue as seen by an assignment to a part of
+    /// it.
+    fn lvalue_current(
+        &mut self,
+        lhs: &'a mir::Lvalue<'a>,
+        kind: hir::AssignKind,
+        state: &State,
+    ) -> Result<Bits> {
+        match lhs.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                let key = (id, lhs.env);
+                let key = self.signal_key(id, lhs.env).unwrap_or(key);
+                let pending = match kind {
+                    hir::AssignKind::Nonblock => state.nonblocking.get(&key),
+                    _ => None,
+                };
+                if let Some(bits) = pending.or_else(|| state.blocking.get(&key)) {
+                    return Ok(bits.clone());
+                }
+                if let Some(bits) = self.signals.get(&key) {
+                    return Ok(bits.clone());
+                }
+            }
+            mir::LvalueKind::Transmute(inner) => return self.lvalue_current(inner, kind, state),
+            mir::LvalueKind::Index {
+                value,
+                base,
+                length,
+            } if base.is_const() => {
+                let bits = self.lvalue_current(value, kind, state)?;
+                let offset = self
+                    .cx
+                    .const_mir_rvalue(base.into())
+                    .get_int()
+                    .and_then(|x| x.to_usize());
+                if let Some(offset) = offset {
+                    return Ok(slice(&bits, offset, std::cmp::max(1, length)));
+                }
+            }
+            _ => (),
+        }
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "assignment to `{}` not supported by this output format",
+                lhs.span.extract()
+            ))
+            .code("SV0046")
+            .span(lhs.span),
+        );
+        Err(())
+    }
+
+    /// Determine the bits driven by a continuous assignment to an lvalue.
+    fn lvalue_bits(&mut self, lhs: &'a mir::Lvalue<'a>) -> Result<Bits> {
+        if lhs.is_error() {
+            return Err(());
+        }
+        match lhs.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                if let Some(key) = self.signal_key(id, lhs.env) {
+                    return Ok(self.signals[&key].clone());
+                }
+            }
+            mir::LvalueKind::Transmute(inner) => return self.lvalue_bits(inner),
+            mir::LvalueKind::Index {
+                value,
+                base,
+                length,
+            } if base.is_const() => {
+                let bits = self.lvalue_bits(value)?;
+                let offset = self
+                    .cx
+                    .const_mir_rvalue(base.into())
+                    .get_int()
+                    .and_then(|x| x.to_usize());
+                if let Some(offset) = offset {
+                    return Ok(slice(&bits, offset, std::cmp::max(1, length)));
+                }
+            }
+            mir::LvalueKind::Concat(ref values) => {
+                let mut bits = vec![];
+                for &value in values.iter().rev() {
+                    bits.extend(self.lvalue_bits(value)?);
+                }
+                return Ok(bits);
+            }
+            _ => (),
+        }
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "assignment to `{}` not supported by this output format",
+                lhs.span.extract()
+            ))
+            .code("SV0046")
+            .span(lhs.span),
+        );
+        Err(())
+    }
+
+    /// Emit the comparison of a `case` expression against a label.
+    fn case_check(
+        &mut self,
+        value: &Bits,
+        label_id: NodeId,
+        env: ParamEnv,
+        kind: ast::CaseKind,
+    ) -> Result<Bit> {
+        let label = self.cx.constant_value_of(label_id, env);
+        let (int, special, x) = match label.kind {
+            ValueKind::Int(ref int, ref special, ref x) => (int, special, x),
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error("case label must be an integer")
+                        .code("SV0046")
+                        .span(self.cx.span(label_id)),
+                );
+                return Err(());
+            }
+        };
+
+        // Only compare the bits that are not wildcards. The special bits are
+        // stored with the most significant digit first and only cover the
+        // digits spelled out in the literal.
+        let label_bits = int_bits(int, value.len());
+        let mut a = vec![];
+        let mut b = vec![];
+        for (bit, (&v, &l)) in value.iter().zip(&label_bits).enumerate() {
+            let ignore = match special.len().checked_sub(bit + 1) {
+                Some(i) if special[i] => match kind {
+                    ast::CaseKind::Normal => false,
+                    ast::CaseKind::DontCareZ => !x[i],
+                    ast::CaseKind::DontCareXZ => true,
+                },
+                _ => false,
+            };
+            if !ignore {
+                a.push(v);
+                b.push(l);
+            }
+        }
+        if a.is_empty() {
+            return Ok(Bit::Const('1'));
+        }
+        Ok(self.binary("$eq", a, b, false, 1)[0])
+    }
+
+    /// Emit an expression. Variables assigned earlier in a procedure are read
+    /// from its state.
+    fn rvalue(&mut self, mir: &'a mir::Rvalue<'a>, state: Option<&State>) -> Result<Bits> {
+        if mir.is_error() {
+            return Err(());
+        }
+        let width = width(self.cx, mir.ty, mir.span)?;
+
+        // Emit constants as their folded value.
+        if mir.is_const() {
+            let value = self.cx.const_mir_rvalue(mir.into());
+            return match value.kind {
+                ValueKind::Int(ref int, ref special, ref x) => {
+                    let mut bits = int_bits(int, width);
+                    for (bit, b) in bits.iter_mut().enumerate() {
+                        match special.len().checked_sub(bit + 1) {
+                            Some(i) if special[i] && x[i] => *b = Bit::Const('x'),
+                            Some(i) if special[i] => *b = Bit::Const('z'),
+                            _ => (),
+                        }
+                    }
+                    Ok(bits)
+                }
+                _ => unsupported_expr(self.cx, mir),
+            };
+        }
+
+        let bits = match mir.kind {
+            mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => {
+                let blocking = state.and_then(|s| {
+                    let key = self.signal_key(id, mir.env).unwrap_or((id, mir.env));
+                    s.blocking.get(&key)
+                });
+                match blocking {
+                    Some(bits) => bits.clone(),
+                    None => match self.signal_key(id, mir.env) {
+                        Some(key) => self.signals[&key].clone(),
+                        None => return unsupported_expr(self.cx, mir),
+                    },
+                }
+            }
+            mir::RvalueKind::CastValueDomain { value, .. }
+            | mir::RvalueKind::Transmute(value)
+            | mir::RvalueKind::CastSign(_, value)
+            | mir::RvalueKind::Truncate(_, value)
+            | mir::RvalueKind::ZeroExtend(_, value) => {
+                extend(self.rvalue(value, state)?, width, Bit::Const('0'))
+            }
+            mir::RvalueKind::SignExtend(_, value) => {
+                let bits = self.rvalue(value, state)?;
+                let msb = *bits.last().unwrap();
+                extend(bits, width, msb)
+            }
+            mir::RvalueKind::CastToBool(value) => {
+                let bits = self.rvalue(value, state)?;
+                vec![self.reduce("$reduce_bool", bits)]
+            }
+            mir::RvalueKind::UnaryBitwise { arg, .. } => {
+                let arg = self.rvalue(arg, state)?;
+                self.unary("$not", arg, false, width)
+            }
+            mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
+                let lhs = self.rvalue(lhs, state)?;
+                let rhs = self.rvalue(rhs, state)?;
+                let kind = match op {
+                    mir::BinaryBitwiseOp::And => "$and",
+                    mir::BinaryBitwiseOp::Or => "$or",
+                    mir::BinaryBitwiseOp::Xor => "$xor",
+                };
+                self.binary(kind, lhs, rhs, false, width)
+            }
+            mir::RvalueKind::IntUnaryArith { arg, .. } => {
+                let arg = self.rvalue(arg, state)?;
+                self.unary("$neg", arg, false, width)
+            }
+            mir::RvalueKind::IntBinaryArith {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs, state)?;
+                let rhs = self.rvalue(rhs, state)?;
+                let kind = match op {
+                    mir::IntBinaryArithOp::Add => "$add",
+                    mir::IntBinaryArithOp::Sub => "$sub",
+                    mir::IntBinaryArithOp::Mul => "$mul",
+                    mir::IntBinaryArithOp::Div => "$div",
+                    mir::IntBinaryArithOp::Mod => "$mod",
+                    mir::IntBinaryArithOp::Pow => "$pow",
+                };
+                self.binary(kind, lhs, rhs, sign.is_signed(), width)
+            }
+            mir::RvalueKind::IntComp {
+                op, sign, lhs, rhs, ..
+            } => {
+                let lhs = self.rvalue(lhs, state)?;
+                let rhs = self.rvalue(rhs, state)?;
+                let kind = match op {
+                    mir::IntCompOp::Eq => "$eq",
+                    mir::IntCompOp::Neq => "$ne",
+                    mir::IntCompOp::Lt => "$lt",
+                    mir::IntCompOp::Leq => "$le",
+                    mir::IntCompOp::Gt => "$gt",
+                    mir::IntCompOp::Geq => "$ge",
+                };
+                let bit = self.binary(kind, lhs, rhs, sign.is_signed(), 1);
+                extend(bit, width, Bit::Const('0'))
+            }
+            mir::RvalueKind::Concat(ref values) => {
+                let mut bits = vec![];
+                for &value in values.iter().rev() {
+                    bits.extend(self.rvalue(value, state)?);
+                }
+                bits
+            }
+            mir::RvalueKind::Repeat(times, value) => {
+                let bits = self.rvalue(value, state)?;
+                bits.repeat(times)
+            }
+            mir::RvalueKind::Index {
+                value,
+                base,
+                length,
+            } => {
+                let bits = self.rvalue(value, state)?;
+                let length = std::cmp::max(1, length);
+                if base.is_const() {
+                    let offset = match self.cx.const_mir_rvalue(base.into()).get_int() {
+                        Some(x) if !x.is_negative() => x.to_usize().unwrap_or(usize::MAX),
+                        _ => return unsupported_expr(self.cx, mir),
+                    };
+                    slice(&bits, offset, length)
+                } else {
+                    let base = self.rvalue(base, state)?;
+                    self.binary("$shiftx", bits, base, false, length)
+                }
+            }
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = self.rvalue(cond, state)?;
+                let cond = self.reduce("$reduce_bool", cond);
+                let t = self.rvalue(true_value, state)?;
+                let f = self.rvalue(false_value, state)?;
+                self.mux(&f, &t, cond)
+            }
+            mir::RvalueKind::Shift {
+                op,
+                arith,
+                value,
+                amount,
+            } => {
+                let bits = self.rvalue(value, state)?;
+                if amount.is_const() {
+                    let amount = match self.cx.const_mir_rvalue(amount.into()).get_int() {
+                        Some(x) if !x.is_negative() => x.to_usize().unwrap_or(usize::MAX),
+                        _ => return unsupported_expr(self.cx, mir),
+                    };
+                    let amount = amount.min(bits.len());
+                    let fill = match (op, arith) {
+                        (mir::ShiftOp::Right, true) => *bits.last().unwrap(),
+                        _ => Bit::Const('0'),
+                    };
+                    match op {
+                        mir::ShiftOp::Left => {
+                            let mut shifted = vec![Bit::Const('0'); amount];
+                            shifted.extend(&bits[..bits.len() - amount]);
+                            shifted
+                        }
+                        mir::ShiftOp::Right => extend(bits[amount..].to_vec(), width, fill),
+                    }
+                } else {
+                    let amount = self.rvalue(amount, state)?;
+                    let (kind, signed) = match (op, arith) {
+                        (mir::ShiftOp::Left, _) => ("$shl", false),
+                        (mir::ShiftOp::Right, false) => ("$shr", false),
+                        (mir::ShiftOp::Right, true) => ("$sshr", true),
+                    };
+                    let mut cell = Cell::new(kind);
+                    cell.param("A_SIGNED", param(signed as usize, 32));
+                    cell.param("A_WIDTH", param(bits.len(), 32));
+                    cell.param("B_SIGNED", param(0, 32));
+                    cell.param("B_WIDTH", param(amount.len(), 32));
+                    cell.param("Y_WIDTH", param(width, 32));
+                    let y = self.alloc(width);
+                    cell.conn("A", "input", bits);
+                    cell.conn("B", "input", amount);
+                    cell.conn("Y", "output", y.clone());
+                    self.add_cell(cell);
+                    y
+                }
+            }
+            mir::RvalueKind::Reduction { op, arg } => {
+                let arg = self.rvalue(arg, state)?;
+                let kind = match op {
+                    mir::BinaryBitwiseOp::And => "$reduce_and",
+                    mir::BinaryBitwiseOp::Or => "$reduce_or",
+                    mir::BinaryBitwiseOp::Xor => "$reduce_xor",
+                };
+                extend(vec![self.reduce(kind, arg)], width, Bit::Const('0'))
+            }
+            _ => return unsupported_expr(self.cx, mir),
+        };
+        Ok(extend(bits, width, Bit::Const('0')))
+    }
+
+    /// Emit a cell with one input.
+    fn unary(&mut self, kind: &str, a: Bits, signed: bool, width: usize) -> Bits {
+        let mut cell = Cell::new(kind);
+        cell.param("A_SIGNED", param(signed as usize, 32));
+        cell.param("A_WIDTH", param(a.len(), 32));
+        cell.param("Y_WIDTH", param(width, 32));
+        let y = self.alloc(width);
+        cell.conn("A", "input", a);
+        cell.conn("Y", "output", y.clone());
+        self.add_cell(cell);
+        y
+    }
+
+    /// Emit a cell with two inputs.
+    fn binary(&mut self, kind: &str, a: Bits, b: Bits, signed: bool, width: usize) -> Bits {
+        let mut cell = Cell::new(kind);
+        cell.param("A_SIGNED", param(signed as usize, 32));
+        cell.param("A_WIDTH", param(a.len(), 32));
+        cell.param("B_SIGNED", param(signed as usize, 32));
+        cell.param("B_WIDTH", param(b.len(), 32));
+        cell.param("Y_WIDTH", param(width, 32));
+        let y = self.alloc(width);
+        cell.conn("A", "input", a);
+        cell.conn("B", "input", b);
+        cell.conn("Y", "output", y.clone());
+        self.add_cell(cell);
+        y
+    }
+
+    /// Reduce a value to a single bit.
+    fn reduce(&mut self, kind: &str, a: Bits) -> Bit {
+        if a.len() == 1 && kind != "$reduce_xor" {
+            return a[0];
+        }
+        self.unary(kind, a, false, 1)[0]
+    }
+
+    /// Select between two values.
+    fn mux(&mut self, a: &Bits, b: &Bits, s: Bit) -> Bits {
+        match s {
+            Bit::Const('0') => return a.clone(),
+            Bit::Const('1') => return b.clone(),
+            _ => (),
+        }
+        let mut cell = Cell::new("$mux");
+        cell.param("WIDTH", param(a.len(), 32));
+        let y = self.alloc(a.len());
+        cell.conn("A", "input", a.clone());
+        cell.conn("B", "input", b.clone());
+        cell.conn("S", "input", vec![s]);
+        cell.conn("Y", "output", y.clone());
+        self.add_cell(cell);
+        y
+    }
+
+    fn add_cell(&mut self, cell: Cell) {
+        let name = format!("{}${}", cell.kind, self.cells.0.len());
+        self.cells.insert(name, cell);
+    }
+
+    /// Allocate new nets.
+    fn alloc(&mut self, width: usize) -> Bits {
+        (0..width)
+            .map(|_| {
+                let id = self.nets.len();
+                self.nets.push(id);
+                Bit::Net(id + 2)
+            })
+            .collect()
+    }
+
+    /// Connect two signals by merging their nets.
+    fn connect(&mut self, a: &[Bit], b: &[Bit]) {
+        for (&a, &b) in a.iter().zip(b) {
+            match (self.find(a), self.find(b)) {
+                (Bit::Net(a), Bit::Net(b)) if a != b => {
+                    let (keep, drop) = (a.min(b), a.max(b));
+                    self.nets[drop - 2] = keep - 2;
+                }
+                (Bit::Net(n), Bit::Const(c)) | (Bit::Const(c), Bit::Net(n)) => {
+                    self.consts.insert(n, c);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Find the representative of a bit.
+    fn find(&mut self, bit: Bit) -> Bit {
+        let mut id = match bit {
+            Bit::Net(id) => id - 2,
+            Bit::Const(_) => return bit,
+        };
+        while self.nets[id] != id {
+            self.nets[id] = self.nets[self.nets[id]];
+            id = self.nets[id];
+        }
+        match self.consts.get(&(id + 2)) {
+            Some(&c) => Bit::Const(c),
+            None => Bit::Net(id + 2),
+        }
+    }
+
+    /// Replace the bits of a signal by their representatives.
+    fn resolve(&mut self, bits: &[Bit]) -> Bits {
+        bits.iter().map(|&b| self.find(b)).collect()
+    }
+
+    /// Find the signal of a declaration or port as seen from an environment.
+    fn signal_key(&self, id: NodeId, env: ParamEnv) -> Option<(NodeId, ParamEnv)> {
+        if self.signals.contains_key(&(id, env)) {
+            return Some((id, env));
+        }
+        if let Some(decl) = self.module.find_decl(id, env) {
+            return Some((decl.id, decl.env));
+        }
+        self.module
+            .find_port(id)
+            .map(|_| (id, self.module.id.env()))
+    }
+
+    /// Determine the name of a signal for diagnostics.
+    fn signal_name(&self, key: (NodeId, ParamEnv)) -> String {
+        if let Some(decl) = self.module.find_decl(key.0, key.1) {
+            return decl.name.clone();
+        }
+        match self.module.find_port(key.0) {
+            Some(index) => self.module.ports[index].port.name.value.to_string(),
+            None => format!("{:?}", key.0),
+        }
+    }
+}
+
+impl Cell {
+    fn new(kind: &str) -> Self {
+        Cell {
+            hide_name: 1,
+            kind: kind.to_string(),
+            parameters: Map::default(),
+            attributes: Map::default(),
+            port_directions: Map::default(),
+            connections: Map::default(),
+        }
+    }
+
+    fn param(&mut self, name: &str, value: String) {
+        self.parameters.insert(name, value);
+    }
+
+    fn conn(&mut self, name: &str, direction: &'static str, bits: Bits) {
+        self.port_directions.insert(name, direction);
+        self.connections.insert(name, bits);
+    }
+}
+
+/// Determine the final value of every variable assigned by a procedure.
+fn finish(state: State) -> HashMap<(NodeId, ParamEnv), Bits> {
+    let mut values = state.blocking;
+    values.extend(state.nonblocking);
+    values
+}
+
+/// Truncate or extend a list of bits to a width.
+fn extend(mut bits: Bits, width: usize, fill: Bit) -> Bits {
+    bits.resize(width, fill);
+    bits
+}
+
+/// Select a range of bits, filling bits out of range with `x`.
+fn slice(bits: &[Bit], offset: usize, length: usize) -> Bits {
+    (offset..offset.saturating_add(length))
+        .map(|i| bits.get(i).cloned().unwrap_or(Bit::Const('x')))
+        .collect()
+}
+
+/// Convert an integer to constant bits.
+fn int_bits(value: &BigInt, width: usize) -> Bits {
+    let modulus = BigInt::one() << width;
+    let mut value = value % &modulus;
+    if value.is_negative() {
+        value += modulus;
+    }
+    (0..width)
+        .map(|i| {
+            if (&value >> i) & BigInt::one() == BigInt::one() {
+                Bit::Const('1')
+            } else {
+                Bit::Const('0')
+            }
+        })
+        .collect()
+}
+
+/// Format constant bits as a parameter value, or return `None` if any of the
+/// bits is not constant.
+fn const_bits(bits: &[Bit]) -> Option<String> {
+    bits.iter()
+        .rev()
+        .map(|b| match *b {
+            Bit::Const(c) => Some(c),
+            Bit::Net(_) => None,
+        })
+        .collect()
+}
+
+/// Format an integer parameter value as a binary string.
+fn param(value: usize, width: usize) -> String {
+    format!("{:0width$b}", value, width = width)
+}
@@ -4,7 +4,7 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use num::BigInt;
@@ -16,8 +16,8 @@ use crate::common::source::*;
 use crate::op::*;
 use crate::scope::Scope;
 use crate::score::{
-    BuiltinOpRef, BuiltinPkgRef, Def, EnumRef, LibRef, ResolvableName, ScopeRef, ScoreBoard,
-    TypeDeclRef, TypeMarkRef, UnitRef,
+    BuiltinOpRef, BuiltinPkgRef, Def, EnumRef, FileDeclRef, LibRef, ResolvableName, ScopeRef,
+    ScoreBoard, TypeDeclRef, TypeMarkRef, UnitRef,
 };
 use crate::ty::*;
 
@@ -33,11 +33,23 @@ lazy_static! {
     pub static ref TEXTIO_PKG_REF: BuiltinPkgRef = BuiltinPkgRef::alloc();
     /// A reference to the package `ENV`.
     pub static ref ENV_PKG_REF: BuiltinPkgRef = BuiltinPkgRef::alloc();
+    /// A reference to the file `INPUT` of package `TEXTIO`.
+    pub static ref INPUT_FILE_REF: FileDeclRef = FileDeclRef::alloc();
+    /// A reference to the file `OUTPUT` of package `TEXTIO`.
+    pub static ref OUTPUT_FILE_REF: FileDeclRef = FileDeclRef::alloc();
+    /// A reference to the library `IEEE`.
+    pub static ref IEEE_LIB_REF: LibRef = LibRef::alloc();
+    /// A reference to the package `STD_LOGIC_1164`.
+    pub static ref STD_LOGIC_1164_PKG_REF: BuiltinPkgRef = BuiltinPkgRef::alloc();
+    /// A reference to the package `NUMERIC_STD`.
+    pub static ref NUMERIC_STD_PKG_REF: BuiltinPkgRef = BuiltinPkgRef::alloc();
 
     /// The builtin `BOOLEAN` type.
     pub static ref BOOLEAN_TYPE: BuiltinType = BuiltinType::new_enum("BOOLEAN");
     /// The builtin `BIT` type.
     pub static ref BIT_TYPE: BuiltinType = BuiltinType::new_enum("BIT");
+    /// The builtin `CHARACTER` type.
+    pub static ref CHARACTER_TYPE: BuiltinType = BuiltinType::new_enum("CHARACTER");
     /// The builtin `SEVERITY_LEVEL` type.
     pub static ref SEVERITY_LEVEL_TYPE: BuiltinType = BuiltinType::new_enum("SEVERITY_LEVEL");
     /// A reference to the type `INTEGER`.
@@ -46,6 +58,12 @@ lazy_static! {
         i32::min_value().into(),
         i32::max_value().into()
     ));
+    /// The builtin `REAL` type.
+    pub static ref REAL_TYPE: BuiltinType = BuiltinType::new("REAL", FloatTy::new(
+        Dir::To,
+        std::f64::MIN,
+        std::f64::MAX
+    ));
     /// The builtin `TIME` type.
     pub static ref TIME_TYPE: BuiltinType = {
         let id = TypeDeclRef::alloc();
@@ -82,6 +100,11 @@ lazy_static! {
         1.into(),
         i32::max_value().into()
     ));
+    /// The builtin `STRING` type.
+    pub static ref STRING_TYPE: BuiltinType = BuiltinType::new("STRING", ArrayTy::new(
+        vec![ArrayIndex::Unbounded(Box::new(POSITIVE_TYPE.named_ty()))],
+        Box::new(CHARACTER_TYPE.named_ty())
+    ));
     /// The builtin `BOOLEAN_VECTOR` type.
     pub static ref BOOLEAN_VECTOR_TYPE: BuiltinType = BuiltinType::new("BOOLEAN_VECTOR", ArrayTy::new(
         vec![ArrayIndex::Unbounded(Box::new(NATURAL_TYPE.named_ty()))],
@@ -106,6 +129,35 @@ lazy_static! {
     pub static ref FILE_OPEN_KIND_TYPE: BuiltinType = BuiltinType::new_enum("FILE_OPEN_KIND");
     /// The builtin `FILE_OPEN_STATUS` type.
     pub static ref FILE_OPEN_STATUS_TYPE: BuiltinType = BuiltinType::new_enum("FILE_OPEN_STATUS");
+    /// The builtin `LINE` type.
+    pub static ref LINE_TYPE: BuiltinType = BuiltinType::new(
+        "LINE",
+        Ty::Access(Box::new(STRING_TYPE.named_ty()))
+    );
+    /// The builtin `TEXT` type.
+    pub static ref TEXT_TYPE: BuiltinType = BuiltinType::new(
+        "TEXT",
+        Ty::File(Box::new(STRING_TYPE.named_ty()))
+    );
+    /// The builtin `SIDE` type.
+    pub static ref SIDE_TYPE: BuiltinType = BuiltinType::new_enum("SIDE");
+    /// The builtin `STD_ULOGIC` type.
+    pub static ref STD_ULOGIC_TYPE: BuiltinType = BuiltinType::new_enum("STD_ULOGIC");
+    /// The builtin `STD_ULOGIC_VECTOR` type.
+    pub static ref STD_ULOGIC_VECTOR_TYPE: BuiltinType = BuiltinType::new("STD_ULOGIC_VECTOR", ArrayTy::new(
+        vec![ArrayIndex::Unbounded(Box::new(NATURAL_TYPE.named_ty()))],
+        Box::new(STD_ULOGIC_TYPE.named_ty())
+    ));
+    /// The builtin `UNSIGNED` type.
+    pub static ref UNSIGNED_TYPE: BuiltinType = BuiltinType::new("UNSIGNED", ArrayTy::new(
+        vec![ArrayIndex::Unbounded(Box::new(NATURAL_TYPE.named_ty()))],
+        Box::new(STD_ULOGIC_TYPE.named_ty())
+    ));
+    /// The builtin `SIGNED` type.
+    pub static ref SIGNED_TYPE: BuiltinType = BuiltinType::new("SIGNED", ArrayTy::new(
+        vec![ArrayIndex::Unbounded(Box::new(NATURAL_TYPE.named_ty()))],
+        Box::new(STD_ULOGIC_TYPE.named_ty())
+    ));
 
     // A list of builtin unary operators.
     static ref BUILTIN_UNARY_OPS: Vec<BuiltinUnaryOp> = vec![
@@ -163,12 +215,15 @@ lazy_static! {
         let mut bi = Vec::new();
         bi.push(wrapup_type_builtin(&BOOLEAN_TYPE));
         bi.push(wrapup_type_builtin(&BIT_TYPE));
+        bi.push(wrapup_type_builtin(&CHARACTER_TYPE));
         bi.push(wrapup_type_builtin(&SEVERITY_LEVEL_TYPE));
         bi.push(wrapup_type_builtin(&INTEGER_TYPE));
+        bi.push(wrapup_type_builtin(&REAL_TYPE));
         bi.push(wrapup_type_builtin(&TIME_TYPE));
         bi.push(wrapup_type_builtin(&DELAY_LENGTH_TYPE));
-        bi.push(wrapup_type_builtin(&NATURAL_TYPE));
-        bi.push(wrapup_type_builtin(&POSITIVE_TYPE));
+        bi.push(wrapup_subtype_builtin(&NATURAL_TYPE));
+        bi.push(wrapup_subtype_builtin(&POSITIVE_TYPE));
+        bi.push(wrapup_type_builtin(&STRING_TYPE));
         bi.push(wrapup_type_builtin(&BOOLEAN_VECTOR_TYPE));
         bi.push(wrapup_type_builtin(&BIT_VECTOR_TYPE));
         bi.push(wrapup_type_builtin(&INTEGER_VECTOR_TYPE));
@@ -177,6 +232,301 @@ lazy_static! {
         bi.push(wrapup_type_builtin(&FILE_OPEN_STATUS_TYPE));
         bi
     };
+
+    /// The builtins of package `TEXTIO`.
+    static ref TEXTIO_BUILTINS: Vec<(Builtin, Vec<Builtin>)> = {
+        let mut bi = Vec::new();
+        bi.push(wrapup_type_builtin(&LINE_TYPE));
+        bi.push(wrapup_type_builtin(&TEXT_TYPE));
+        bi.push(wrapup_type_builtin(&SIDE_TYPE));
+        let line = LINE_TYPE.named_ty();
+        let text = TEXT_TYPE.named_ty();
+        let side = SIDE_TYPE.named_ty();
+        let width = NATURAL_TYPE.named_ty();
+        let boolean = BOOLEAN_TYPE.named_ty();
+        let bv = BIT_VECTOR_TYPE.named_ty();
+        let string = STRING_TYPE.named_ty();
+
+        // `file INPUT : TEXT open READ_MODE is "STD_INPUT"`
+        // `file OUTPUT : TEXT open WRITE_MODE is "STD_OUTPUT"`
+        bi.push((Builtin::file(*INPUT_FILE_REF, "INPUT").ty(text.clone()), vec![]));
+        bi.push((Builtin::file(*OUTPUT_FILE_REF, "OUTPUT").ty(text.clone()), vec![]));
+
+        // `procedure DEALLOCATE (P: inout LINE)`
+        // `function ENDFILE (file F: TEXT) return BOOLEAN`
+        // `procedure READLINE (file F: TEXT; L: inout LINE)`
+        // `procedure WRITELINE (file F: TEXT; L: inout LINE)`
+        bi.push((Builtin::function("DEALLOCATE").ty(procedure_ty(&[("P", &line)])), vec![]));
+        bi.push((Builtin::function("ENDFILE").ty(function_ty(&[("F", &text)], &boolean)), vec![]));
+        for &name in &["READLINE", "WRITELINE"] {
+            bi.push((Builtin::function(name).ty(procedure_ty(&[("F", &text), ("L", &line)])), vec![]));
+        }
+
+        // `procedure READ (L: inout LINE; VALUE: out T; GOOD: out BOOLEAN)`
+        // `procedure READ (L: inout LINE; VALUE: out T)`
+        // `procedure WRITE (L: inout LINE; VALUE: in T; JUSTIFIED: in SIDE := RIGHT; FIELD: in WIDTH := 0)`
+        // Default values of arguments are provided as separate overloads that
+        // omit the argument. The `SREAD`, `SWRITE`, `HREAD`, `HWRITE`,
+        // `OREAD`, and `OWRITE` procedures only accept strings and bit
+        // vectors, respectively.
+        let mut procs = Vec::new();
+        for ty in &[
+            BIT_TYPE.named_ty(),
+            bv.clone(),
+            boolean.clone(),
+            CHARACTER_TYPE.named_ty(),
+            INTEGER_TYPE.named_ty(),
+            REAL_TYPE.named_ty(),
+            string.clone(),
+            TIME_TYPE.named_ty(),
+        ] {
+            procs.push(("READ", ty.clone()));
+            procs.push(("WRITE", ty.clone()));
+        }
+        procs.push(("SREAD", string.clone()));
+        procs.push(("SWRITE", string.clone()));
+        for &name in &["HREAD", "HWRITE", "OREAD", "OWRITE"] {
+            procs.push((name, bv.clone()));
+        }
+        for (name, ty) in procs {
+            let args = if name.ends_with("READ") {
+                vec![("L", &line), ("VALUE", &ty), ("GOOD", &boolean)]
+            } else {
+                vec![("L", &line), ("VALUE", &ty), ("JUSTIFIED", &side), ("FIELD", &width)]
+            };
+            for len in 2..=args.len() {
+                bi.push((Builtin::function(name).ty(procedure_ty(&args[..len])), vec![]));
+            }
+        }
+        bi
+    };
+
+    /// The builtins of package `ENV`.
+    static ref ENV_BUILTINS: Vec<(Builtin, Vec<Builtin>)> = {
+        let mut bi = Vec::new();
+        let int = INTEGER_TYPE.named_ty();
+
+        // `procedure STOP (STATUS: INTEGER)`
+        // `procedure STOP`
+        // `procedure FINISH (STATUS: INTEGER)`
+        // `procedure FINISH`
+        for &name in &["STOP", "FINISH"] {
+            bi.push((Builtin::function(name).ty(procedure_ty(&[("STATUS", &int)])), vec![]));
+            bi.push((Builtin::function(name).ty(procedure_ty(&[])), vec![]));
+        }
+        bi
+    };
+
+    /// The builtins of package `STD_LOGIC_1164`.
+    static ref STD_LOGIC_1164_BUILTINS: Vec<(Builtin, Vec<Builtin>)> = {
+        let mut bi = Vec::new();
+        bi.push(wrapup_type_builtin(&STD_ULOGIC_TYPE));
+        bi.push(wrapup_type_builtin(&STD_ULOGIC_VECTOR_TYPE));
+        let sul = STD_ULOGIC_TYPE.named_ty();
+        let sulv = STD_ULOGIC_VECTOR_TYPE.named_ty();
+        let bit = BIT_TYPE.named_ty();
+        let bv = BIT_VECTOR_TYPE.named_ty();
+        let boolean = BOOLEAN_TYPE.named_ty();
+
+        // `function resolved (s : STD_ULOGIC_VECTOR) return STD_ULOGIC`
+        bi.push((Builtin::function("RESOLVED").ty(function_ty(&[("S", &sulv)], &sul)), vec![]));
+
+        // The conversion functions. Default values of arguments are provided
+        // as separate overloads that omit the argument.
+        for &(name, arg, ret) in &[
+            ("TO_BIT", &sul, &bit),
+            ("TO_BITVECTOR", &sulv, &bv),
+        ] {
+            bi.push((Builtin::function(name).ty(function_ty(&[("S", arg)], ret)), vec![]));
+            bi.push((
+                Builtin::function(name).ty(function_ty(&[("S", arg), ("XMAP", &bit)], ret)),
+                vec![],
+            ));
+        }
+        for &(name, arg, ret) in &[
+            ("TO_STDULOGIC", &bit, &sul),
+            ("TO_STDLOGICVECTOR", &bv, &sulv),
+            ("TO_STDULOGICVECTOR", &bv, &sulv),
+        ] {
+            bi.push((Builtin::function(name).ty(function_ty(&[("B", arg)], ret)), vec![]));
+        }
+        for &name in &["TO_X01", "TO_X01Z", "TO_UX01"] {
+            for &(arg_name, arg, ret) in &[
+                ("S", &sul, &sul),
+                ("S", &sulv, &sulv),
+                ("B", &bit, &sul),
+                ("B", &bv, &sulv),
+            ] {
+                bi.push((Builtin::function(name).ty(function_ty(&[(arg_name, arg)], ret)), vec![]));
+            }
+        }
+
+        // The edge detection and the test for unknown values.
+        for &name in &["RISING_EDGE", "FALLING_EDGE"] {
+            bi.push((Builtin::function(name).ty(function_ty(&[("S", &sul)], &boolean)), vec![]));
+        }
+        for &arg in &[&sul, &sulv] {
+            bi.push((Builtin::function("IS_X").ty(function_ty(&[("S", arg)], &boolean)), vec![]));
+        }
+        bi
+    };
+
+    /// The builtins of package `NUMERIC_STD`.
+    static ref NUMERIC_STD_BUILTINS: Vec<(Builtin, Vec<Builtin>)> = {
+        let mut bi = Vec::new();
+        bi.push(wrapup_type_builtin(&UNSIGNED_TYPE));
+        bi.push(wrapup_type_builtin(&SIGNED_TYPE));
+        let uns = UNSIGNED_TYPE.named_ty();
+        let sig = SIGNED_TYPE.named_ty();
+        let nat = NATURAL_TYPE.named_ty();
+        let int = INTEGER_TYPE.named_ty();
+        let boolean = BOOLEAN_TYPE.named_ty();
+
+        // `function "abs" (ARG: SIGNED) return SIGNED`
+        // `function "-" (ARG: SIGNED) return SIGNED`
+        let unary_ty = SubprogTy::new(vec![SubprogTyArg::positional(sig.clone())], Some(sig.clone()));
+        bi.push((Builtin::operator(UnaryOp::Abs).ty(unary_ty.clone()), vec![]));
+        bi.push((Builtin::operator(UnaryOp::Neg).ty(unary_ty.clone()), vec![]));
+
+        // The arithmetic and relational operators. Each accepts two numbers,
+        // or a number and an integer on either side.
+        for &(ty, int) in &[(&uns, &nat), (&sig, &int)] {
+            for &(lhs, rhs) in &[(ty, ty), (ty, int), (int, ty)] {
+                let args = vec![
+                    SubprogTyArg::positional(lhs.clone()),
+                    SubprogTyArg::positional(rhs.clone()),
+                ];
+                for &op in &[BinaryOp::Add, BinaryOp::Sub, BinaryOp::Mul, BinaryOp::Div, BinaryOp::Mod, BinaryOp::Rem] {
+                    bi.push((Builtin::operator(op).ty(SubprogTy::new(args.clone(), Some(ty.clone()))), vec![]));
+                }
+                for &op in &[RelationalOp::Eq, RelationalOp::Neq, RelationalOp::Lt, RelationalOp::Leq, RelationalOp::Gt, RelationalOp::Geq] {
+                    bi.push((Builtin::operator(BinaryOp::Rel(op)).ty(SubprogTy::new(args.clone(), Some(boolean.clone()))), vec![]));
+                }
+            }
+        }
+
+        // The conversion, resizing, and shift functions.
+        for &(ty, int) in &[(&uns, &nat), (&sig, &int)] {
+            bi.push((Builtin::function("TO_INTEGER").ty(function_ty(&[("ARG", ty)], int)), vec![]));
+            bi.push((Builtin::function("RESIZE").ty(function_ty(&[("ARG", ty), ("NEW_SIZE", &nat)], ty)), vec![]));
+            for &name in &["SHIFT_LEFT", "SHIFT_RIGHT", "ROTATE_LEFT", "ROTATE_RIGHT"] {
+                bi.push((Builtin::function(name).ty(function_ty(&[("ARG", ty), ("COUNT", &nat)], ty)), vec![]));
+            }
+        }
+        bi.push((Builtin::function("TO_UNSIGNED").ty(function_ty(&[("ARG", &nat), ("SIZE", &nat)], &uns)), vec![]));
+        bi.push((Builtin::function("TO_SIGNED").ty(function_ty(&[("ARG", &int), ("SIZE", &nat)], &sig)), vec![]));
+        bi
+    };
+
+    /// The builtin operators and functions by their ID.
+    static ref BUILTIN_OPS: HashMap<BuiltinOpRef, &'static Builtin> = all_builtins()
+        .filter_map(|bi| match bi.def {
+            Def::BuiltinOp(id) => Some((id, bi)),
+            _ => None,
+        })
+        .collect();
+}
+
+/// The position of severity level `NOTE`, the default of report statements.
+pub const SEVERITY_NOTE: usize = 0;
+
+/// The position of severity level `ERROR`, the default of assertions.
+pub const SEVERITY_ERROR: usize = 2;
+
+/// The position of severity level `FAILURE`.
+pub const SEVERITY_FAILURE: usize = 3;
+
+/// The names of the control characters of type `CHARACTER`, which are the
+/// positions 0 to 31 and 127 to 159.
+static CONTROL_CHARACTERS: [&str; 65] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FSP", "GSP", "RSP", "USP", "DEL", "C128", "C129", "C130", "C131", "C132", "C133", "C134",
+    "C135", "C136", "C137", "C138", "C139", "C140", "C141", "C142", "C143", "C144", "C145", "C146",
+    "C147", "C148", "C149", "C150", "C151", "C152", "C153", "C154", "C155", "C156", "C157", "C158",
+    "C159",
+];
+
+/// Iterate over all builtin types, operators, and functions.
+fn all_builtins() -> impl Iterator<Item = &'static Builtin> {
+    use std::iter::once;
+    (*STANDARD_BUILTINS)
+        .iter()
+        .chain((*TEXTIO_BUILTINS).iter())
+        .chain((*ENV_BUILTINS).iter())
+        .chain((*STD_LOGIC_1164_BUILTINS).iter())
+        .chain((*NUMERIC_STD_BUILTINS).iter())
+        .flat_map(|&(ref bi, ref aux)| once(bi).chain(aux.iter()))
+}
+
+/// Find the builtin operator or function with a given ID.
+pub fn builtin_op(id: BuiltinOpRef) -> Option<&'static Builtin> {
+    (*BUILTIN_OPS).get(&id).cloned()
+}
+
+/// Determine whether a builtin operates on `NUMERIC_STD` numbers.
+///
+/// Returns `Some(true)` for `SIGNED` and `Some(false)` for `UNSIGNED` numbers,
+/// based on the first argument or return type that is one of the two.
+pub fn numeric_std_signedness(bi: &Builtin) -> Option<bool> {
+    let ty = match bi.ty {
+        Some(Ty::Subprog(ref ty)) => ty,
+        _ => return None,
+    };
+    ty.args
+        .iter()
+        .map(|arg| &arg.ty)
+        .chain(ty.ret.iter().map(AsRef::as_ref))
+        .filter_map(|ty| match *ty {
+            Ty::Named(_, TypeMarkRef::Type(id)) if id == UNSIGNED_TYPE.id => Some(false),
+            Ty::Named(_, TypeMarkRef::Type(id)) if id == SIGNED_TYPE.id => Some(true),
+            _ => None,
+        })
+        .next()
+}
+
+/// Determine the builtin library with a given name.
+///
+/// Returns `None` if there is no such builtin library.
+pub fn builtin_lib(name: Name) -> Option<LibRef> {
+    let table = get_name_table();
+    if name == table.intern("STD", false) {
+        Some(*STD_LIB_REF)
+    } else if name == table.intern("IEEE", false) {
+        Some(*IEEE_LIB_REF)
+    } else {
+        None
+    }
+}
+
+/// Determine the number of literals of a builtin enumeration type.
+///
+/// Returns `None` if the type is not a builtin enumeration.
+pub fn builtin_enum_len(decl: TypeDeclRef) -> Option<usize> {
+    (*BUILTIN_SCOPES)
+        .iter()
+        .flat_map(|&(_, scope)| scope.defs.values())
+        .flat_map(|defs| defs.iter())
+        .filter_map(|def| match def.value {
+            Def::Enum(EnumRef(d, index)) if d == decl => Some(index + 1),
+            _ => None,
+        })
+        .max()
+}
+
+/// Determine the name of a literal of a builtin enumeration type.
+///
+/// Returns `None` if the type is not a builtin enumeration.
+pub fn builtin_enum_literal(decl: TypeDeclRef, index: usize) -> Option<ResolvableName> {
+    (*BUILTIN_SCOPES)
+        .iter()
+        .flat_map(|&(_, scope)| scope.defs.iter())
+        .filter(|&(_, defs)| {
+            defs.iter()
+                .any(|def| def.value == Def::Enum(EnumRef(decl, index)))
+        })
+        .map(|(&name, _)| name)
+        .next()
 }
 
 /// Add the definition for a builtin resolvable name to a scope.
@@ -225,6 +575,23 @@ where
         .push(Spanned::new(Def::BuiltinOp(id), INVALID_SPAN));
 }
 
+/// Create the type of a builtin function with named arguments.
+fn function_ty(args: &[(&str, &Ty)], ret: &Ty) -> SubprogTy {
+    SubprogTy::new(named_args(args), Some(ret.clone()))
+}
+
+/// Create the type of a builtin procedure with named arguments.
+fn procedure_ty(args: &[(&str, &Ty)]) -> SubprogTy {
+    SubprogTy::new(named_args(args), None)
+}
+
+/// Create the named arguments of a builtin subprogram.
+fn named_args(args: &[(&str, &Ty)]) -> Vec<SubprogTyArg> {
+    args.iter()
+        .map(|&(name, ty)| SubprogTyArg::named(ty.clone(), get_name_table().intern(name, false)))
+        .collect()
+}
+
 /// Takes a builtin type and produces the builtin and its auxiliary defs.
 fn wrapup_type_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
     let bi = Builtin::new(Def::Type(bt.id), bt.name).ty(bt.ty.clone());
@@ -234,13 +601,15 @@ fn wrapup_type_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
     match bt.ty {
         Ty::Enum(_) => enum_type_builtins(&bt.named_ty(), &mut aux),
         Ty::Int(_) => integer_type_builtins(&bt.named_ty(), &mut aux),
+        Ty::Float(_) => float_type_builtins(&bt.named_ty(), &mut aux),
         Ty::Physical(_) => physical_type_builtins(&bt.named_ty(), &mut aux),
         Ty::Array(ref at) => array_type_builtins(&bt.named_ty(), at, &mut aux),
         _ => (),
     }
 
-    // Add the predefined operators for BIT and BOOLEAN.
-    if bt.id == BOOLEAN_TYPE.id || bt.id == BIT_TYPE.id {
+    // Add the predefined operators for BIT and BOOLEAN, and the logical
+    // operators of STD_ULOGIC.
+    if bt.id == BOOLEAN_TYPE.id || bt.id == BIT_TYPE.id || bt.id == STD_ULOGIC_TYPE.id {
         // The type `(T) return T`.
         let unary_ty = SubprogTy::new(
             vec![SubprogTyArg::positional(bt.named_ty())],
@@ -265,8 +634,8 @@ fn wrapup_type_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
         aux.push(Builtin::operator(BinaryOp::Logical(LogicalOp::Xnor)).ty(binary_ty.clone()));
     }
 
-    // Add the predefined `??` operator for BIT.
-    if bt.id == BIT_TYPE.id {
+    // Add the predefined `??` operator for BIT and STD_ULOGIC.
+    if bt.id == BIT_TYPE.id || bt.id == STD_ULOGIC_TYPE.id {
         // The type `(T) return BOOLEAN`.
         let op_ty = SubprogTy::new(
             vec![SubprogTyArg::positional(bt.named_ty())],
@@ -278,6 +647,17 @@ fn wrapup_type_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
     (bi, aux)
 }
 
+/// Takes a builtin subtype and produces the builtin without auxiliary defs.
+///
+/// Subtypes declare no operators of their own; they use those of their base
+/// type.
+fn wrapup_subtype_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
+    (
+        Builtin::new(Def::Type(bt.id), bt.name).ty(bt.ty.clone()),
+        vec![],
+    )
+}
+
 // Define the scopes of the builtins.
 lazy_static! {
     /// The root scope.
@@ -328,6 +708,18 @@ lazy_static! {
         define_builtin_bit(&mut scope, '0', Def::Enum(EnumRef(BIT_TYPE.id, 0)));
         define_builtin_bit(&mut scope, '1', Def::Enum(EnumRef(BIT_TYPE.id, 1)));
 
+        // `type CHARACTER is (NUL, SOH, ..., ' ', '!', ..., 'ÿ')`, which are the
+        // 256 characters of ISO 8859-1. The control characters are named
+        // identifiers.
+        for (i, &name) in CONTROL_CHARACTERS.iter().enumerate() {
+            let i = if i < 32 { i } else { i + 95 };
+            define_builtin_ident(&mut scope, name, Def::Enum(EnumRef(CHARACTER_TYPE.id, i)));
+        }
+        for i in (32..127).chain(160..256) {
+            let chr = std::char::from_u32(i as u32).unwrap();
+            define_builtin_bit(&mut scope, chr, Def::Enum(EnumRef(CHARACTER_TYPE.id, i)));
+        }
+
         // `type SEVERITY_LEVEL is (NOTE, WARNING, ERROR, FAILURE)`
         // define_builtin_ident(&mut scope, "SEVERITY_LEVEL", Def::Type(SEVERITY_LEVEL_TYPE.id));
         define_builtin_ident(&mut scope, "NOTE", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, 0)));
@@ -386,6 +778,101 @@ lazy_static! {
         scope
     };
 
+    /// The scope of the package `TEXTIO`.
+    pub static ref TEXTIO_PKG_SCOPE: Scope = {
+        let mut scope = Scope::new(Some((*STD_LIB_REF).into()));
+        for &(ref bt, ref aux) in &*TEXTIO_BUILTINS {
+            define_builtin(&mut scope, bt.name, bt.def);
+            for a in aux {
+                define_builtin(&mut scope, a.name, a.def);
+            }
+        }
+
+        // `type SIDE is (RIGHT, LEFT)`
+        define_builtin_ident(&mut scope, "RIGHT", Def::Enum(EnumRef(SIDE_TYPE.id, 0)));
+        define_builtin_ident(&mut scope, "LEFT", Def::Enum(EnumRef(SIDE_TYPE.id, 1)));
+
+        // `subtype WIDTH is NATURAL`
+        define_builtin_ident(&mut scope, "WIDTH", Def::Type(NATURAL_TYPE.id));
+
+        scope
+    };
+
+    /// The scope of the package `ENV`.
+    pub static ref ENV_PKG_SCOPE: Scope = {
+        let mut scope = Scope::new(Some((*STD_LIB_REF).into()));
+        for &(ref bt, ref aux) in &*ENV_BUILTINS {
+            define_builtin(&mut scope, bt.name, bt.def);
+            for a in aux {
+                define_builtin(&mut scope, a.name, a.def);
+            }
+        }
+        scope
+    };
+
+    /// The scope of the library `IEEE`.
+    pub static ref IEEE_LIB_SCOPE: Scope = {
+        let mut scope = Scope::new(Some(*ROOT_SCOPE_REF));
+        define_builtin_ident(&mut scope, "STD_LOGIC_1164", Def::BuiltinPkg(*STD_LOGIC_1164_PKG_REF));
+        define_builtin_ident(&mut scope, "NUMERIC_STD", Def::BuiltinPkg(*NUMERIC_STD_PKG_REF));
+        scope
+    };
+
+    /// The scope of the package `STD_LOGIC_1164`.
+    pub static ref STD_LOGIC_1164_PKG_SCOPE: Scope = {
+        let mut scope = Scope::new(Some((*IEEE_LIB_REF).into()));
+        for &(ref bt, ref aux) in &*STD_LOGIC_1164_BUILTINS {
+            define_builtin(&mut scope, bt.name, bt.def);
+            for a in aux {
+                define_builtin(&mut scope, a.name, a.def);
+            }
+        }
+
+        // `type STD_ULOGIC is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-')`
+        for (i, &bit) in ['U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-'].iter().enumerate() {
+            define_builtin_bit(&mut scope, bit, Def::Enum(EnumRef(STD_ULOGIC_TYPE.id, i)));
+        }
+
+        // `subtype STD_LOGIC is resolved STD_ULOGIC`
+        // `subtype STD_LOGIC_VECTOR is (resolved) STD_ULOGIC_VECTOR`
+        // `subtype X01 is resolved STD_ULOGIC range 'X' to '1'`
+        // `subtype X01Z is resolved STD_ULOGIC range 'X' to 'Z'`
+        // `subtype UX01 is resolved STD_ULOGIC range 'U' to '1'`
+        // `subtype UX01Z is resolved STD_ULOGIC range 'U' to 'Z'`
+        // These refer to the unresolved types directly. The range constraints
+        // of the subtypes are not checked.
+        for &name in &["STD_LOGIC", "X01", "X01Z", "UX01", "UX01Z"] {
+            define_builtin_ident(&mut scope, name, Def::Type(STD_ULOGIC_TYPE.id));
+        }
+        define_builtin_ident(&mut scope, "STD_LOGIC_VECTOR", Def::Type(STD_ULOGIC_VECTOR_TYPE.id));
+
+        scope
+    };
+
+    /// The scope of the package `NUMERIC_STD`.
+    pub static ref NUMERIC_STD_PKG_SCOPE: Scope = {
+        let mut scope = Scope::new(Some((*IEEE_LIB_REF).into()));
+        for &(ref bt, ref aux) in &*NUMERIC_STD_BUILTINS {
+            define_builtin(&mut scope, bt.name, bt.def);
+            for a in aux {
+                define_builtin(&mut scope, a.name, a.def);
+            }
+        }
+
+        // `subtype UNRESOLVED_UNSIGNED is UNSIGNED`
+        // `subtype UNRESOLVED_SIGNED is SIGNED`
+        // `alias U_UNSIGNED is UNRESOLVED_UNSIGNED`
+        // `alias U_SIGNED is UNRESOLVED_SIGNED`
+        for &name in &["UNRESOLVED_UNSIGNED", "U_UNSIGNED"] {
+            define_builtin_ident(&mut scope, name, Def::Type(UNSIGNED_TYPE.id));
+        }
+        for &name in &["UNRESOLVED_SIGNED", "U_SIGNED"] {
+            define_builtin_ident(&mut scope, name, Def::Type(SIGNED_TYPE.id));
+        }
+
+        scope
+    };
+
     /// All builtin scopes.
     ///
     /// These are added to the scoreboard upon construction.
@@ -393,6 +880,11 @@ lazy_static! {
         (*ROOT_SCOPE_REF, &*ROOT_SCOPE),
         ((*STD_LIB_REF).into(), &*STD_LIB_SCOPE),
         ((*STANDARD_PKG_REF).into(), &*STANDARD_PKG_SCOPE),
+        ((*TEXTIO_PKG_REF).into(), &*TEXTIO_PKG_SCOPE),
+        ((*ENV_PKG_REF).into(), &*ENV_PKG_SCOPE),
+        ((*IEEE_LIB_REF).into(), &*IEEE_LIB_SCOPE),
+        ((*STD_LOGIC_1164_PKG_REF).into(), &*STD_LOGIC_1164_PKG_SCOPE),
+        ((*NUMERIC_STD_PKG_REF).into(), &*NUMERIC_STD_PKG_SCOPE),
     ];
 
     /// All builtin scope references.
@@ -404,8 +896,6 @@ lazy_static! {
 
 /// Add the builtins to a scoreboard.
 pub fn register_builtins<'ast, 'ctx>(sb: &ScoreBoard<'ast, 'ctx>) {
-    use std::iter::once;
-
     // Add the builtin scopes.
     sb.scope2_table.borrow_mut().extend(
         (*BUILTIN_SCOPES)
@@ -414,15 +904,12 @@ pub fn register_builtins<'ast, 'ctx>(sb: &ScoreBoard<'ast, 'ctx>) {
     );
 
     // Add the builtin types.
-    sb.typeval_table.borrow_mut().extend(
-        (*STANDARD_BUILTINS)
-            .iter()
-            .flat_map(|&(ref bi, ref aux)| once(bi).chain(aux.iter()))
-            .filter_map(|bi| match bi.ty {
-                Some(ref ty) => Some((bi.def.into(), Ok(sb.intern_ty(ty.clone())))),
-                None => None,
-            }),
-    );
+    sb.typeval_table
+        .borrow_mut()
+        .extend(all_builtins().filter_map(|bi| match bi.ty {
+            Some(ref ty) => Some((bi.def.into(), Ok(sb.intern_ty(ty.clone())))),
+            None => None,
+        }));
 }
 
 /// Create a physical type with time units.
@@ -505,6 +992,18 @@ impl Builtin {
         Builtin::new(Def::BuiltinOp(BuiltinOpRef::alloc()), op.into())
     }
 
+    /// Create a new builtin function.
+    pub fn function(name: &str) -> Builtin {
+        let name = get_name_table().intern(name, false);
+        Builtin::new(Def::BuiltinOp(BuiltinOpRef::alloc()), name)
+    }
+
+    /// Create a new builtin file object.
+    pub fn file(id: FileDeclRef, name: &str) -> Builtin {
+        let name = get_name_table().intern(name, false);
+        Builtin::new(Def::File(id), name)
+    }
+
     /// Assign a type to the builtin.
     ///
     /// Panics if the builtin already has a type.
@@ -528,7 +1027,8 @@ impl fmt::Debug for Builtin {
     }
 }
 
-fn integer_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
+/// Add the predefined operators of an integer type.
+pub fn integer_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     numerical_type_builtins(ty, into);
     equality_builtins(ty, into);
     ordering_builtins(ty, into);
@@ -588,7 +1088,37 @@ fn real_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     into.push(Builtin::operator(BinaryOp::Pow).ty(op_ty.clone()));
 }
 
-fn physical_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
+/// Add the predefined operators of a floating-point type.
+pub fn float_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
+    numerical_type_builtins(ty, into);
+    equality_builtins(ty, into);
+    ordering_builtins(ty, into);
+
+    // `T * T -> T`
+    // `T / T -> T`
+    let op_ty = SubprogTy::new(
+        vec![
+            SubprogTyArg::positional(ty.clone()),
+            SubprogTyArg::positional(ty.clone()),
+        ],
+        Some(ty.clone()),
+    );
+    into.push(Builtin::operator(BinaryOp::Mul).ty(op_ty.clone()));
+    into.push(Builtin::operator(BinaryOp::Div).ty(op_ty.clone()));
+
+    // `T ** INTEGER -> T`
+    let op_ty = SubprogTy::new(
+        vec![
+            SubprogTyArg::positional(ty.clone()),
+            SubprogTyArg::positional(INTEGER_TYPE.named_ty()),
+        ],
+        Some(ty.clone()),
+    );
+    into.push(Builtin::operator(BinaryOp::Pow).ty(op_ty.clone()));
+}
+
+/// Add the predefined operators of a physical type.
+pub fn physical_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     numerical_type_builtins(ty, into);
     equality_builtins(ty, into);
     ordering_builtins(ty, into);
@@ -638,7 +1168,8 @@ fn physical_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     into.push(Builtin::operator(BinaryOp::Div).ty(op_ty.clone()));
 }
 
-fn enum_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
+/// Add the predefined operators of an enumeration type.
+pub fn enum_type_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     equality_builtins(ty, into);
     ordering_builtins(ty, into);
 }
@@ -676,9 +1207,11 @@ fn array_type_builtins(ty: &Ty, aty: &ArrayTy, into: &mut Vec<Builtin>) {
     into.push(Builtin::operator(BinaryOp::Concat).ty(concat_right_ty.clone()));
     into.push(Builtin::operator(BinaryOp::Concat).ty(concat_left_ty.clone()));
 
-    // Add additional builtins for arrays of BIT and BOOLEAN.
+    // Add additional builtins for arrays of BIT, BOOLEAN, and STD_ULOGIC.
     if aty.indices.len() == 1
-        && (eid == Some(BOOLEAN_TYPE.id.into()) || eid == Some(BIT_TYPE.id.into()))
+        && (eid == Some(BOOLEAN_TYPE.id.into())
+            || eid == Some(BIT_TYPE.id.into())
+            || eid == Some(STD_ULOGIC_TYPE.id.into()))
     {
         // The type `(A) return T`.
         let reduce_ty = SubprogTy::new(
@@ -756,7 +1289,8 @@ fn array_type_builtins(ty: &Ty, aty: &ArrayTy, into: &mut Vec<Builtin>) {
     }
 }
 
-fn equality_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
+/// Add the predefined `=` and `/=` operators of a type.
+pub fn equality_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     // The type of the operator `(T, T) return BOOLEAN`.
     let op_ty = SubprogTy::new(
         vec![
@@ -770,7 +1304,8 @@ fn equality_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     into.push(Builtin::operator(BinaryOp::Rel(RelationalOp::Neq)).ty(op_ty.clone()));
 }
 
-fn ordering_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
+/// Add the predefined ordering operators of a type.
+pub fn ordering_builtins(ty: &Ty, into: &mut Vec<Builtin>) {
     // The type of the operator `(T, T) return BOOLEAN`.
     let op_ty = SubprogTy::new(
         vec![
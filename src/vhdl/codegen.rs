@@ -2,14 +2,22 @@
 
 //! LLHD code generation for VHDL.
 
+use crate::builtin::BOOLEAN_TYPE;
+use crate::external::{ExportedSignal, ExternalTarget};
+use crate::generic_env::{GenericEnv, GenericEnvData};
 use crate::hir;
 use crate::konst::*;
+use crate::op::{BinaryOp, LogicalOp, RelationalOp, UnaryOp};
 use crate::score::*;
 use crate::ty::*;
 use llhd;
 use moore_common::errors::*;
+use moore_common::name::Name;
 use moore_common::score::Result;
-use num::{Signed, ToPrimitive, Zero};
+use moore_common::source::{Span, Spanned};
+use moore_common::NodeId;
+use num::{BigInt, BigRational, Signed, ToPrimitive, Zero};
+use std::collections::HashMap;
 
 /// Generates LLHD code.
 pub trait Codegen<I, C> {
@@ -49,122 +57,2607 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         Ok(match *ty {
             Ty::Named(..) => unreachable!(),
             Ty::Null => llhd::void_ty(),
-            Ty::Int(ref ty) => {
-                let diff = match ty.dir {
-                    hir::Dir::To => &ty.right_bound - &ty.left_bound,
-                    hir::Dir::Downto => &ty.left_bound - &ty.right_bound,
+            // Integers are represented in two's complement if their range
+            // includes negative values, and as unsigned numbers otherwise.
+            Ty::Int(ref ty) => match int_bounds(ty) {
+                Some((lo, hi)) => llhd::int_ty(int_width(lo, hi)),
+                None => llhd::void_ty(),
+            },
+            // Enumeration values are represented by their position, like the
+            // constants produced by `map_const`.
+            Ty::Enum(ref ty) => llhd::int_ty(self.enum_width(ty.decl)?),
+            // Physical values are represented as integer multiples of the
+            // primary unit.
+            Ty::Physical(ref ty) => self.map_type(&Ty::Int(ty.base.clone()))?,
+            Ty::Float(ref ty) => {
+                self.emit(DiagBuilder2::error(format!(
+                    "cannot generate code for floating-point type `{}`",
+                    ty
+                )));
+                return Err(());
+            }
+            Ty::Protected(_) => {
+                self.emit(DiagBuilder2::error(
+                    "cannot generate code for objects of a protected type",
+                ));
+                return Err(());
+            }
+            Ty::Access(ref ty) => llhd::pointer_ty(self.map_type(ty)?),
+            Ty::Array(ref ty) => {
+                let mut llty = self.map_type(&ty.element)?;
+                for index in ty.indices.iter().rev() {
+                    match *index {
+                        ArrayIndex::Unbounded(_) => {
+                            self.emit(
+                                DiagBuilder2::error(format!("type `{}` is unbounded", ty)), // TODO: What span should we use here?
+                            );
+                            return Err(());
+                        }
+                        ArrayIndex::Constrained(ref ty) => {
+                            let num = match **ty {
+                                Ty::Int(ref ty) => {
+                                    let l = ty.len();
+                                    if l.is_negative() || l.is_zero() {
+                                        return Ok(llhd::void_ty());
+                                    }
+                                    match l.to_usize() {
+                                        Some(l) => l,
+                                        None => {
+                                            self.emit(
+                                                DiagBuilder2::error(format!(
+                                                    "array index `{}` is too large; {} elements",
+                                                    ty, l
+                                                )), // TODO: What span should we use here?
+                                            );
+                                            return Err(());
+                                        }
+                                    }
+                                }
+                                Ty::Enum(ref ty) => match ty.range {
+                                    Some((hir::Dir::To, l, r)) if l <= r => r - l + 1,
+                                    Some((hir::Dir::Downto, l, r)) if l >= r => l - r + 1,
+                                    Some(_) => return Ok(llhd::void_ty()),
+                                    None => self.enum_len(ty.decl)?,
+                                },
+                                _ => {
+                                    self.emit(
+                                        DiagBuilder2::error(format!(
+                                            "`{}` is an invalid array index type",
+                                            ty
+                                        )), // TODO: What span should we use here?
+                                    );
+                                    return Err(());
+                                }
+                            };
+                            llty = llhd::array_ty(num, llty);
+                        }
+                    }
+                }
+                llty
+            }
+            Ty::File(ref _ty) => llhd::int_ty(32),
+            Ty::Record(ref ty) => {
+                let fields = ty
+                    .fields
+                    .iter()
+                    .map(|&(_, ref ty)| self.map_type(ty))
+                    .collect::<Result<_>>()?;
+                llhd::struct_ty(fields)
+            }
+            Ty::Subprog(..) => unimplemented!(),
+            // Unbounded integers cannot be mapped to LLHD. All cases where
+            // such an int can leak through to codegen should actually be caught
+            // beforehand in the type check.
+            Ty::UnboundedInt | Ty::UniversalInt | Ty::UniversalReal => unreachable!(),
+        })
+    }
+
+    /// Map a constant value to the LLHD counterpart.
+    pub fn map_const(
+        &self,
+        builder: &mut llhd::ir::UnitBuilder,
+        konst: &Const,
+    ) -> Result<llhd::ir::Value> {
+        Ok(match *konst {
+            // TODO: Map this to llhd::const_void once available.
+            Const::Null => builder.ins().const_int((0, 0)),
+            // Integers assume the width of their type.
+            Const::Int(ref k) => {
+                let width = match k.ty {
+                    Some(ref ty) => match self.map_type(&Ty::Int(ty.clone()))? {
+                        ref llty if llty.is_int() => llty.unwrap_int(),
+                        _ => 0,
+                    },
+                    None => 999,
+                };
+                builder.ins().const_int((width, k.value.clone()))
+            }
+            Const::Enum(ref k) => builder.ins().const_int((self.enum_width(k.decl)?, k.index)),
+            Const::Record(ref k) => {
+                let fields = k
+                    .fields
+                    .iter()
+                    .map(|&(_, ref k)| self.map_const(builder, k))
+                    .collect::<Result<Vec<_>>>()?;
+                builder.ins().strukt(fields)
+            }
+            // TODO: Map empty arrays to llhd::const_void once available.
+            Const::Array(ref k) if k.elements.is_empty() => builder.ins().const_int((0, 0)),
+            Const::Array(ref k) => {
+                let elements = k
+                    .elements
+                    .iter()
+                    .map(|k| self.map_const(builder, k))
+                    .collect::<Result<Vec<_>>>()?;
+                builder.ins().array(elements)
+            }
+            Const::Float(ref _k) => panic!("cannot map float constant"),
+            Const::Physical(ref k) => {
+                let width = match self.map_type(&Ty::Physical(k.ty.clone()))? {
+                    ref llty if llty.is_int() => llty.unwrap_int(),
+                    _ => 0,
                 };
-                if diff.is_negative() {
-                    llhd::void_ty()
+                builder.ins().const_int((width, k.value.clone()))
+            }
+            Const::IntRange(_) | Const::FloatRange(_) => panic!("cannot map range constant"),
+        }
+        .into())
+    }
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Generate code for an expression.
+    ///
+    /// Signals are probed, variables are loaded, and the operators and
+    /// functions of package `NUMERIC_STD` are lowered to integer arithmetic on
+    /// the bits of their operands. The predefined operators on scalar types are
+    /// lowered if they are applied to signals or variables. All other
+    /// expressions must have a constant value.
+    pub fn codegen_expr(
+        &self,
+        id: ExprRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        match hir.data {
+            hir::ExprData::SignalName(sig) => return Ok(self.codegen_signal_value(sig, builder)),
+            hir::ExprData::ExternalName(ref ext)
+                if ext.class.value == hir::ExternalClass::Signal =>
+            {
+                if let ExternalTarget::Signal(export) = self.external_target(id)? {
+                    return Ok(self.codegen_signal_value(export.signal, builder));
+                }
+            }
+            hir::ExprData::VarName(var) => {
+                if let Some(&ptr) = self.sb.llvar_table.borrow().get(&var.into()) {
+                    return Ok(builder.ins().ld(ptr));
+                }
+            }
+            hir::ExprData::MethodCall(..) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot generate code for method call `{}`",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span)
+                    .add_note("Objects of a protected type cannot be elaborated to LLHD."),
+                );
+                return Err(());
+            }
+            hir::ExprData::SignalAttr(sig, attr) => {
+                let current = self.codegen_signal_value(sig.value, builder);
+                let delayed = self.codegen_delayed_signal(sig.value, current, builder)?;
+                let last = if builder.value_type(delayed).is_pointer() {
+                    builder.ins().ld(delayed)
                 } else {
-                    llhd::int_ty(diff.bits() as usize)
+                    builder.ins().prb(delayed)
+                };
+                return match attr.value {
+                    hir::SignalAttr::Event => {
+                        let changed = builder.ins().neq(current, last);
+                        self.codegen_boolean(changed, builder)
+                    }
+                    hir::SignalAttr::Stable => {
+                        let unchanged = builder.ins().eq(current, last);
+                        self.codegen_boolean(unchanged, builder)
+                    }
+                    hir::SignalAttr::LastValue => Ok(last),
+                };
+            }
+            hir::ExprData::Unary(op, _, arg) if self.numeric_std_overload(id)?.is_some() => {
+                let len = self.numeric_std_len(self.lazy_typeval(arg)?)?;
+                let value = self.codegen_numeric_std_operand(arg, len, true, builder)?;
+                let neg = builder.ins().neg(value);
+                let result = match op.value {
+                    UnaryOp::Neg => neg,
+                    UnaryOp::Abs if len > 0 => {
+                        let sign = builder.ins().ext_slice(value, len - 1, 1);
+                        let choices = builder.ins().array(vec![value, neg]);
+                        builder.ins().mux(choices, sign)
+                    }
+                    _ => value,
+                };
+                return Ok(self.codegen_numeric_std_array(result, len, builder));
+            }
+            hir::ExprData::Unary(op, _, arg) if self.is_dynamic_expr(arg)? => {
+                return self.codegen_dynamic_unary(id, op.value, arg, builder);
+            }
+            hir::ExprData::Binary(op, _, lhs, rhs) => {
+                if let Some(signed) = self.numeric_std_overload(id)? {
+                    return self.codegen_numeric_std_binary(op.value, signed, lhs, rhs, builder);
+                }
+                if self.is_dynamic_expr(id)? {
+                    return self.codegen_dynamic_binary(id, op.value, lhs, rhs, builder);
+                }
+            }
+            hir::ExprData::FuncCall(_, ref args) => {
+                if let Some(signed) = self.numeric_std_overload(id)? {
+                    // Type checking has ensured that all arguments are
+                    // positional.
+                    let args: Vec<_> = args
+                        .value
+                        .iter()
+                        .map(|arg| match arg.actual.value {
+                            hir::AssocActual::Expr(id) => id,
+                            _ => unreachable!(),
+                        })
+                        .collect();
+                    return self.codegen_numeric_std_call(id, signed, &args, builder);
+                }
+            }
+            _ => (),
+        }
+
+        // Integer constants assume the width of their type.
+        let value = self.const_value(id)?;
+        if let Const::Int(ref k) = *value {
+            let ty = self.lazy_typeval(id)?;
+            if let Ty::Int(_) = *self.deref_named_type(ty)? {
+                let width = self.map_type(ty)?.unwrap_int();
+                return Ok(builder.ins().const_int((width, k.value.clone())));
+            }
+        }
+        self.map_const(builder, value)
+    }
+
+    /// Read the current value of a signal.
+    fn codegen_signal_value(
+        &self,
+        sig: SignalRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> llhd::ir::Value {
+        let sig = self.sb.llsig_table.borrow()[&sig.into()];
+        builder.ins().prb(sig)
+    }
+
+    /// Get a copy of a signal that lags behind it by one delta cycle.
+    ///
+    /// This approximates the signal attributes: `S'event` holds if the signal
+    /// differs from its delayed copy, `S'stable` if it does not, and
+    /// `S'last_value` is the value of the delayed copy. All are exact in the
+    /// delta cycle of an event, which is what the usual
+    /// `clk'event and clk = '1'` relies on. Processes sensitive to the signal
+    /// instead use a variable that holds the value of the signal when the
+    /// process last suspended, see `codegen_process_body`.
+    fn codegen_delayed_signal(
+        &self,
+        sig: SignalRef,
+        current: llhd::ir::Value,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        if let Some(&delayed) = self.sb.lldelayed_table.borrow().get(&sig.into()) {
+            return Ok(delayed);
+        }
+        let init = self.default_value_for_type(self.ty(sig)?)?;
+        let init = self.map_const(builder, init)?;
+        let delayed = builder.ins().sig(init);
+        let delay = builder
+            .ins()
+            .const_time(llhd::value::TimeValue::new(num::zero(), 1, 0));
+        builder.ins().drv(delayed, current, delay);
+        self.sb
+            .lldelayed_table
+            .borrow_mut()
+            .insert(sig.into(), delayed);
+        Ok(delayed)
+    }
+
+    /// Convert a single bit to a `BOOLEAN`.
+    fn codegen_boolean(
+        &self,
+        holds: llhd::ir::Value,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        // Booleans are represented as enumeration values.
+        let width = self.enum_width(BOOLEAN_TYPE.id)?;
+        let zero = builder.ins().const_int((width, 0));
+        Ok(builder.ins().ins_slice(zero, holds, 0, 1))
+    }
+
+    /// Determine the length of a `SIGNED` or `UNSIGNED` number.
+    fn numeric_std_len(&self, ty: &Ty) -> Result<usize> {
+        let llty = self.map_type(ty)?;
+        Ok(if llty.is_array() {
+            llty.unwrap_array().0
+        } else {
+            0
+        })
+    }
+
+    /// Convert a `SIGNED` or `UNSIGNED` number to an integer.
+    ///
+    /// The leftmost element becomes the most significant bit. Elements other
+    /// than `'1'` and `'H'` map to a zero bit.
+    fn codegen_numeric_std_int(
+        &self,
+        array: llhd::ir::Value,
+        len: usize,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> llhd::ir::Value {
+        let one = builder.ins().const_int((STD_ULOGIC_WIDTH, 3));
+        let high = builder.ins().const_int((STD_ULOGIC_WIDTH, 7));
+        let mut value = builder.ins().const_int((len, 0));
+        for i in 0..len {
+            let element = builder.ins().ext_field(array, i);
+            let is_one = builder.ins().eq(element, one);
+            let is_high = builder.ins().eq(element, high);
+            let bit = builder.ins().or(is_one, is_high);
+            value = builder.ins().ins_slice(value, bit, len - 1 - i, 1);
+        }
+        value
+    }
+
+    /// Convert an integer to a `SIGNED` or `UNSIGNED` number.
+    fn codegen_numeric_std_array(
+        &self,
+        value: llhd::ir::Value,
+        len: usize,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> llhd::ir::Value {
+        if len == 0 {
+            return builder.ins().const_int((0, 0));
+        }
+        let zero = builder.ins().const_int((STD_ULOGIC_WIDTH, 2));
+        let one = builder.ins().const_int((STD_ULOGIC_WIDTH, 3));
+        let choices = builder.ins().array(vec![zero, one]);
+        let elements = (0..len)
+            .map(|i| {
+                let bit = builder.ins().ext_slice(value, len - 1 - i, 1);
+                builder.ins().mux(choices, bit)
+            })
+            .collect();
+        builder.ins().array(elements)
+    }
+
+    /// Truncate or extend an integer to a different width.
+    fn codegen_resize_int(
+        &self,
+        value: llhd::ir::Value,
+        from: usize,
+        to: usize,
+        signed: bool,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> llhd::ir::Value {
+        if to == from {
+            return value;
+        }
+        if to < from {
+            return builder.ins().ext_slice(value, 0, to);
+        }
+        let zero = builder.ins().const_int((to, 0));
+        let fill = if signed && from > 0 {
+            let ones = builder.ins().const_int(llhd::IntValue::all_ones(to));
+            let sign = builder.ins().ext_slice(value, from - 1, 1);
+            let choices = builder.ins().array(vec![zero, ones]);
+            builder.ins().mux(choices, sign)
+        } else {
+            zero
+        };
+        builder.ins().ins_slice(fill, value, 0, from)
+    }
+
+    /// Generate code for an operand of a `NUMERIC_STD` operator or function as
+    /// an integer of a given width.
+    ///
+    /// The operand is either a `SIGNED` or `UNSIGNED` number, or an integer.
+    fn codegen_numeric_std_operand(
+        &self,
+        id: ExprRef,
+        width: usize,
+        signed: bool,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let ty = self.lazy_typeval(id)?;
+        match *self.deref_named_type(ty)? {
+            Ty::Array(_) => {
+                let len = self.numeric_std_len(ty)?;
+                let array = self.codegen_expr(id, builder)?;
+                let value = self.codegen_numeric_std_int(array, len, builder);
+                Ok(self.codegen_resize_int(value, len, width, signed, builder))
+            }
+            Ty::Int(ref int_ty) => {
+                let from = self.map_type(ty)?.unwrap_int();
+                let value = self.codegen_expr(id, builder)?;
+                let signed = int_ty.left_bound.is_negative() || int_ty.right_bound.is_negative();
+                Ok(self.codegen_resize_int(value, from, width, signed, builder))
+            }
+            _ => match *self.const_value(id)? {
+                Const::Int(ref k) => Ok(builder.ins().const_int((width, k.value.clone()))),
+                _ => unreachable!("numeric operand is neither array nor integer"),
+            },
+        }
+    }
+
+    /// Generate code for a `NUMERIC_STD` binary operator.
+    ///
+    /// The result lengths match the ones of the constant evaluation. Integer
+    /// operands assume the length of the other operand.
+    fn codegen_numeric_std_binary(
+        &self,
+        op: BinaryOp,
+        signed: bool,
+        lhs: ExprRef,
+        rhs: ExprRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let len_of = |id: ExprRef| -> Result<Option<usize>> {
+            let ty = self.lazy_typeval(id)?;
+            Ok(match *self.deref_named_type(ty)? {
+                Ty::Array(_) => Some(self.numeric_std_len(ty)?),
+                _ => None,
+            })
+        };
+        let (llen, rlen) = match (len_of(lhs)?, len_of(rhs)?) {
+            (Some(l), Some(r)) => (l, r),
+            (Some(l), None) => (l, l),
+            (None, Some(r)) => (r, r),
+            (None, None) => unreachable!("numeric operator without array operand"),
+        };
+        let result_len = match op {
+            BinaryOp::Mul => llen + rlen,
+            BinaryOp::Div => llen,
+            BinaryOp::Mod | BinaryOp::Rem => rlen,
+            _ => std::cmp::max(llen, rlen),
+        };
+        let width = std::cmp::max(std::cmp::max(llen, rlen), result_len);
+        let l = self.codegen_numeric_std_operand(lhs, width, signed, builder)?;
+        let r = self.codegen_numeric_std_operand(rhs, width, signed, builder)?;
+        let mut ins = builder.ins();
+        let result = match op {
+            BinaryOp::Add => ins.add(l, r),
+            BinaryOp::Sub => ins.sub(l, r),
+            BinaryOp::Mul if signed => ins.smul(l, r),
+            BinaryOp::Mul => ins.umul(l, r),
+            BinaryOp::Div if signed => ins.sdiv(l, r),
+            BinaryOp::Div => ins.udiv(l, r),
+            BinaryOp::Mod if signed => ins.smod(l, r),
+            BinaryOp::Mod => ins.umod(l, r),
+            BinaryOp::Rem if signed => ins.srem(l, r),
+            BinaryOp::Rem => ins.urem(l, r),
+            BinaryOp::Rel(rel) => return self.codegen_relation(rel, signed, l, r, builder),
+            _ => unreachable!("{:?} is not a numeric operator", op),
+        };
+        let result = self.codegen_resize_int(result, width, result_len, signed, builder);
+        Ok(self.codegen_numeric_std_array(result, result_len, builder))
+    }
+
+    /// Generate code for a call to one of the functions of package
+    /// `NUMERIC_STD`.
+    ///
+    /// The size arguments of the conversion functions must be constant.
+    fn codegen_numeric_std_call(
+        &self,
+        id: ExprRef,
+        signed: bool,
+        args: &[ExprRef],
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        let name = match self.overload(id)?.value {
+            Def::BuiltinOp(op) => match builtin_op(op).map(|bi| bi.name) {
+                Some(ResolvableName::Ident(name)) => name.as_str().to_uppercase(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let size = |id: ExprRef| -> Result<usize> {
+            match *self.const_value(id)? {
+                Const::Int(ref k) if k.value.to_usize().is_some() => {
+                    Ok(k.value.to_usize().unwrap())
+                }
+                _ => {
+                    let span = self.lazy_hir(id)?.span;
+                    self.emit(
+                        DiagBuilder2::error(format!("`{}` is not a valid size", span.extract()))
+                            .span(span),
+                    );
+                    Err(())
+                }
+            }
+        };
+        match (&*name, args) {
+            ("TO_INTEGER", &[arg]) => {
+                let width = self.map_type(self.lazy_typeval(id)?)?.unwrap_int();
+                self.codegen_numeric_std_operand(arg, width, signed, builder)
+            }
+            ("TO_UNSIGNED", &[arg, len]) | ("TO_SIGNED", &[arg, len]) => {
+                let len = size(len)?;
+                let value = self.codegen_numeric_std_operand(arg, len, signed, builder)?;
+                Ok(self.codegen_numeric_std_array(value, len, builder))
+            }
+            ("RESIZE", &[arg, len]) => {
+                let len = size(len)?;
+                let from = self.numeric_std_len(self.lazy_typeval(arg)?)?;
+                let value = self.codegen_numeric_std_operand(arg, from, signed, builder)?;
+                let mut result = self.codegen_resize_int(value, from, len, signed, builder);
+                if signed && len > 0 && len < from {
+                    // Keep the sign bit when truncating.
+                    let sign = builder.ins().ext_slice(value, from - 1, 1);
+                    result = builder.ins().ins_slice(result, sign, len - 1, 1);
+                }
+                Ok(self.codegen_numeric_std_array(result, len, builder))
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot generate code for `{}`",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span)
+                    .add_note(format!(
+                        "`{}` is only supported in constant expressions",
+                        name
+                    )),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Check whether an expression reads a signal or a variable, such that it
+    /// has no constant value.
+    fn is_dynamic_expr(&self, id: ExprRef) -> Result<bool> {
+        let mut dynamic = false;
+        self.walk_expr(id, &mut |_, data| match *data {
+            hir::ExprData::SignalName(_)
+            | hir::ExprData::SignalAttr(..)
+            | hir::ExprData::VarName(_) => dynamic = true,
+            hir::ExprData::ExternalName(ref ext)
+                if ext.class.value != hir::ExternalClass::Constant =>
+            {
+                dynamic = true
+            }
+            _ => (),
+        })?;
+        Ok(dynamic)
+    }
+
+    /// Call a function for an expression and each of its subexpressions.
+    fn walk_expr(
+        &self,
+        id: ExprRef,
+        f: &mut dyn FnMut(ExprRef, &'ctx hir::ExprData),
+    ) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        f(id, &hir.data);
+        match hir.data {
+            hir::ExprData::Select(arg, _)
+            | hir::ExprData::Attr(arg, _)
+            | hir::ExprData::Unary(_, _, arg)
+            | hir::ExprData::Qualified(_, arg)
+            | hir::ExprData::Allocator(_, Some(arg))
+            | hir::ExprData::Cast(_, arg)
+            | hir::ExprData::Slice(arg, _)
+            | hir::ExprData::TypeAttr(_, _, arg) => self.walk_expr(arg, f)?,
+            hir::ExprData::Binary(_, _, lhs, rhs) | hir::ExprData::Range(_, lhs, rhs) => {
+                self.walk_expr(lhs, f)?;
+                self.walk_expr(rhs, f)?;
+            }
+            hir::ExprData::Index(prefix, ref indices) => {
+                self.walk_expr(prefix, f)?;
+                for &index in indices {
+                    self.walk_expr(index, f)?;
+                }
+            }
+            hir::ExprData::Call(_, ref args)
+            | hir::ExprData::FuncCall(_, ref args)
+            | hir::ExprData::MethodCall(_, _, ref args) => {
+                if let hir::ExprData::MethodCall(object, ..) = hir.data {
+                    self.walk_expr(object, f)?;
+                }
+                for elem in &args.value {
+                    match elem.actual.value {
+                        hir::AssocActual::Expr(arg) | hir::AssocActual::InertialExpr(arg) => {
+                            self.walk_expr(arg, f)?
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            hir::ExprData::Aggregate(agg) => {
+                let agg: &hir::Aggregate = self.lazy_hir(agg)?;
+                let named: Vec<ExprRef> = match agg.named {
+                    hir::AggregateKind::Both => vec![],
+                    hir::AggregateKind::Record(ref fields) => {
+                        fields.iter().map(|field| field.value.1.value).collect()
+                    }
+                    hir::AggregateKind::Array(ref fields) => {
+                        fields.iter().map(|field| field.value.1.value).collect()
+                    }
+                };
+                for elem in agg.positional.iter().chain(agg.others.iter()) {
+                    self.walk_expr(elem.value, f)?;
+                }
+                for elem in named {
+                    self.walk_expr(elem, f)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Emit an error for an expression that has no constant value and cannot
+    /// be lowered otherwise.
+    fn codegen_unsupported<T>(&self, id: ExprRef) -> Result<T> {
+        let span = self.span(id).unwrap();
+        self.emit(
+            DiagBuilder2::error(format!("cannot generate code for `{}`", span.extract()))
+                .span(span)
+                .add_note(
+                    "Only the predefined operators on scalar types can be applied to signals \
+                     and variables.",
+                ),
+        );
+        Err(())
+    }
+
+    /// Invert a value of a two-valued enumeration type such as `BIT` or
+    /// `BOOLEAN`.
+    fn codegen_invert(
+        &self,
+        value: llhd::ir::Value,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> llhd::ir::Value {
+        let width = builder.value_type(value).unwrap_int();
+        let one = builder.ins().const_int((width, 1));
+        builder.ins().xor(value, one)
+    }
+
+    /// Generate code for a relational operator applied to two integers.
+    fn codegen_relation(
+        &self,
+        rel: RelationalOp,
+        signed: bool,
+        l: llhd::ir::Value,
+        r: llhd::ir::Value,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let mut ins = builder.ins();
+        let holds = match rel {
+            RelationalOp::Eq => ins.eq(l, r),
+            RelationalOp::Neq => ins.neq(l, r),
+            RelationalOp::Lt if signed => ins.slt(l, r),
+            RelationalOp::Lt => ins.ult(l, r),
+            RelationalOp::Leq if signed => ins.sle(l, r),
+            RelationalOp::Leq => ins.ule(l, r),
+            RelationalOp::Gt if signed => ins.sgt(l, r),
+            RelationalOp::Gt => ins.ugt(l, r),
+            RelationalOp::Geq if signed => ins.sge(l, r),
+            RelationalOp::Geq => ins.uge(l, r),
+        };
+        self.codegen_boolean(holds, builder)
+    }
+
+    /// Generate code for an integer operand of a predefined operator.
+    ///
+    /// Returns the value, its width, and whether it is signed.
+    fn codegen_int_operand(
+        &self,
+        id: ExprRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<(llhd::ir::Value, usize, bool)> {
+        let signed = match *self.deref_named_type(self.lazy_typeval(id)?)? {
+            Ty::Int(ref ty) => is_signed_int(ty),
+            _ => false,
+        };
+        let value = self.codegen_expr(id, builder)?;
+        Ok((value, builder.value_type(value).unwrap_int(), signed))
+    }
+
+    /// Generate code for the two integer operands of a predefined operator,
+    /// extended to a common width of at least `min_width` bits.
+    ///
+    /// Returns the operands, their width, and whether they are signed.
+    fn codegen_int_operands(
+        &self,
+        lhs: ExprRef,
+        rhs: ExprRef,
+        min_width: usize,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<(llhd::ir::Value, llhd::ir::Value, usize, bool)> {
+        let (l, lw, ls) = self.codegen_int_operand(lhs, builder)?;
+        let (r, rw, rs) = self.codegen_int_operand(rhs, builder)?;
+        let signed = ls || rs;
+        // Unsigned operands need an additional bit to be treated as signed.
+        let width = std::cmp::max(
+            min_width,
+            std::cmp::max(lw + (signed && !ls) as usize, rw + (signed && !rs) as usize),
+        );
+        let l = self.codegen_resize_int(l, lw, width, ls, builder);
+        let r = self.codegen_resize_int(r, rw, width, rs, builder);
+        Ok((l, r, width, signed))
+    }
+
+    /// Generate code for a predefined unary operator applied to an operand that
+    /// is not constant.
+    fn codegen_dynamic_unary(
+        &self,
+        id: ExprRef,
+        op: UnaryOp,
+        arg: ExprRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        if self.subprog_overload(id)?.is_some() {
+            return self.codegen_unsupported(id);
+        }
+        let ty = self.deref_named_type(self.lazy_typeval(arg)?)?;
+        match (op, ty) {
+            (UnaryOp::Pos, _) => self.codegen_expr(arg, builder),
+            (UnaryOp::Neg, &Ty::Int(_)) => {
+                let value = self.codegen_expr(arg, builder)?;
+                Ok(builder.ins().neg(value))
+            }
+            (UnaryOp::Abs, &Ty::Int(ref int_ty)) => {
+                let value = self.codegen_expr(arg, builder)?;
+                let width = builder.value_type(value).unwrap_int();
+                if !is_signed_int(int_ty) || width == 0 {
+                    return Ok(value);
+                }
+                let neg = builder.ins().neg(value);
+                let sign = builder.ins().ext_slice(value, width - 1, 1);
+                let choices = builder.ins().array(vec![value, neg]);
+                Ok(builder.ins().mux(choices, sign))
+            }
+            (UnaryOp::Not, &Ty::Enum(ref enum_ty)) if self.enum_len(enum_ty.decl)? == 2 => {
+                let value = self.codegen_expr(arg, builder)?;
+                Ok(self.codegen_invert(value, builder))
+            }
+            _ => self.codegen_unsupported(id),
+        }
+    }
+
+    /// Generate code for a predefined binary operator applied to operands that
+    /// are not constant.
+    ///
+    /// The relational operators apply to scalar types, with the equality
+    /// operators also applying to composite types. The logical operators apply
+    /// to two-valued enumeration types such as `BIT` and `BOOLEAN`, and the
+    /// adding and multiplying operators to integers.
+    fn codegen_dynamic_binary(
+        &self,
+        id: ExprRef,
+        op: BinaryOp,
+        lhs: ExprRef,
+        rhs: ExprRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        if self.subprog_overload(id)?.is_some() {
+            return self.codegen_unsupported(id);
+        }
+        let ty = self.deref_named_type(self.lazy_typeval(lhs)?)?;
+        match (op, ty) {
+            (BinaryOp::Rel(rel), &Ty::Int(_)) => {
+                let (l, r, _, signed) = self.codegen_int_operands(lhs, rhs, 0, builder)?;
+                self.codegen_relation(rel, signed, l, r, builder)
+            }
+            (BinaryOp::Rel(rel), &Ty::Enum(_))
+            | (BinaryOp::Rel(rel @ RelationalOp::Eq), _)
+            | (BinaryOp::Rel(rel @ RelationalOp::Neq), _) => {
+                let l = self.codegen_expr(lhs, builder)?;
+                let r = self.codegen_expr(rhs, builder)?;
+                self.codegen_relation(rel, false, l, r, builder)
+            }
+            (BinaryOp::Logical(logical), &Ty::Enum(ref enum_ty))
+                if self.enum_len(enum_ty.decl)? == 2 =>
+            {
+                let l = self.codegen_expr(lhs, builder)?;
+                let r = self.codegen_expr(rhs, builder)?;
+                Ok(match logical {
+                    LogicalOp::And => builder.ins().and(l, r),
+                    LogicalOp::Or => builder.ins().or(l, r),
+                    LogicalOp::Xor => builder.ins().xor(l, r),
+                    LogicalOp::Nand => {
+                        let value = builder.ins().and(l, r);
+                        self.codegen_invert(value, builder)
+                    }
+                    LogicalOp::Nor => {
+                        let value = builder.ins().or(l, r);
+                        self.codegen_invert(value, builder)
+                    }
+                    LogicalOp::Xnor => {
+                        let value = builder.ins().xor(l, r);
+                        self.codegen_invert(value, builder)
+                    }
+                })
+            }
+            (BinaryOp::Add, &Ty::Int(_))
+            | (BinaryOp::Sub, &Ty::Int(_))
+            | (BinaryOp::Mul, &Ty::Int(_)) => {
+                let result_width = self.map_type(self.lazy_typeval(id)?)?.unwrap_int();
+                let (l, r, width, signed) =
+                    self.codegen_int_operands(lhs, rhs, result_width, builder)?;
+                let result = match op {
+                    BinaryOp::Add => builder.ins().add(l, r),
+                    BinaryOp::Sub => builder.ins().sub(l, r),
+                    _ if signed => builder.ins().smul(l, r),
+                    _ => builder.ins().umul(l, r),
+                };
+                Ok(self.codegen_resize_int(result, width, result_width, signed, builder))
+            }
+            _ => self.codegen_unsupported(id),
+        }
+    }
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Determine the architecture of an instantiated entity.
+    ///
+    /// This is the architecture named in the instantiation or configuration,
+    /// or the most recently analyzed architecture of the entity otherwise.
+    fn inst_arch(
+        &self,
+        inst_entity: Spanned<EntityRef>,
+        inst_arch: Option<Spanned<Name>>,
+    ) -> Result<ArchRef> {
+        let entity = inst_entity.value;
+        let archs = self
+            .archs(self.hir(entity)?.lib)?
+            .by_entity
+            .get(&entity)
+            .unwrap();
+        let found = match inst_arch {
+            Some(name) => archs.by_name.get(&name.value),
+            None => archs.ordered.last(),
+        };
+        match (found, inst_arch) {
+            (Some(&arch), _) => Ok(arch),
+            (None, Some(name)) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not an architecture of entity `{}`",
+                        name.value,
+                        self.hir(entity)?.name.value
+                    ))
+                    .span(name.span),
+                );
+                Err(())
+            }
+            (None, None) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "entity `{}` has no architecture",
+                        self.hir(entity)?.name.value
+                    ))
+                    .span(inst_entity.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the architecture selected by a configuration declaration.
+    ///
+    /// The architecture is generated with the block configuration applied,
+    /// which binds the component instances throughout the hierarchy below it.
+    pub fn cfg_llunit(&self, id: CfgRef) -> Result<llhd::ir::UnitId> {
+        let hir = self.hir(id)?;
+        let arch = self.inst_arch(hir.entity, Some(hir.block.name))?;
+        self.with_block_cfg(Some(&hir.block), || self.llunit(arch))
+    }
+
+    /// Determine the design entity an instance is bound to during elaboration.
+    ///
+    /// A component configuration in the current block configuration may bind
+    /// the instance to a different entity or architecture. The actuals are
+    /// then associated with the generics and ports of the same name of that
+    /// entity. See IEEE 1076-2008 section 3.4.3.
+    pub(crate) fn inst_binding(&self, id: CompInstStmtRef) -> Result<InstBinding> {
+        let hir = self.hir(id)?;
+        let cfg = self.inst_comp_cfg(hir);
+        let block = cfg.and_then(|cfg| cfg.block.as_ref());
+        let block_name = block.map(|block| block.name);
+        let (entity, arch) = match cfg.and_then(|cfg| cfg.entity) {
+            Some((entity, arch)) => (entity, arch.or(block_name)),
+            None => (hir.entity, block_name.or(hir.arch)),
+        };
+        let arch = self.inst_arch(entity, arch)?;
+        let entity_hir = self.hir(entity.value)?;
+        let rebound = entity.value != hir.entity.value;
+        let unbound = |name: Name, kind: &str| {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "entity `{}` has no {} `{}`",
+                    entity_hir.name.value, kind, name
+                ))
+                .span(entity.span)
+                .add_note(format!(
+                    "The configuration binds instance `{}` to entity `{}`, which must have \
+                     a {} of the same name for every associated {}.",
+                    hir.label.map(|l| l.value.to_string()).unwrap_or_default(),
+                    entity_hir.name.value,
+                    kind,
+                    kind
+                )),
+            );
+        };
+
+        let mut data = GenericEnvData::default();
+        for &(generic, actual) in &hir.generic_map {
+            let generic = if rebound {
+                let name = self.hir(generic)?.name.value;
+                let found = entity_hir.generics.iter().find_map(|&g| match g {
+                    GenericRef::Const(id) if self.ast(id).3.name == name => Some(id),
+                    _ => None,
+                });
+                match found {
+                    Some(generic) => generic,
+                    None => {
+                        unbound(name, "generic");
+                        return Err(());
+                    }
+                }
+            } else {
+                generic
+            };
+            data.set_value(generic, self.const_value(actual)?);
+        }
+        data.set_block_cfg(block);
+
+        let mut port_map = Vec::new();
+        for &(port, actual) in &hir.port_map {
+            let port = if rebound {
+                let name = self.hir(port)?.name.value;
+                let found = entity_hir
+                    .ports
+                    .iter()
+                    .cloned()
+                    .find(|&p| self.ast(p).3.name == name);
+                match found {
+                    Some(port) => port,
+                    None => {
+                        unbound(name, "port");
+                        return Err(());
+                    }
+                }
+            } else {
+                port
+            };
+            port_map.push((port, actual));
+        }
+
+        Ok(InstBinding {
+            entity: entity.value,
+            arch,
+            env: self.intern_generic_env(data),
+            port_map,
+        })
+    }
+
+    /// Find the component configuration that applies to a component instance
+    /// in the current block configuration.
+    ///
+    /// A configuration that names the label of the instance takes precedence
+    /// over `all` and `others`. See IEEE 1076-2008 section 3.4.3.
+    fn inst_comp_cfg(&self, hir: &hir::CompInstStmt) -> Option<&'ctx hir::CompCfg> {
+        let comp = hir.comp?.value;
+        let label = hir.label.map(|l| l.value);
+        let cfgs = self
+            .block_cfg()?
+            .comps
+            .iter()
+            .filter(|cfg| cfg.comp == comp);
+        let named = cfgs.clone().find(|cfg| match cfg.insts {
+            hir::CfgInsts::Labels(ref labels) => labels.iter().any(|l| Some(l.value) == label),
+            _ => false,
+        });
+        named.or_else(|| {
+            cfgs.clone().find(|cfg| match cfg.insts {
+                hir::CfgInsts::Labels(_) => false,
+                _ => true,
+            })
+        })
+    }
+
+    /// Evaluate nodes with the block configuration of a generate statement.
+    ///
+    /// The instances in a generate statement that the current block
+    /// configuration does not configure keep their default binding.
+    fn with_gen_block_cfg<R>(&self, label: Option<Spanned<Name>>, f: impl FnOnce() -> R) -> R {
+        let cfg = match (self.block_cfg(), label) {
+            (Some(cfg), Some(label)) => cfg.blocks.iter().find(|b| b.name.value == label.value),
+            _ => None,
+        };
+        self.with_block_cfg(cfg, f)
+    }
+
+    /// Determine the name of the unit generated for a generate statement.
+    ///
+    /// The name nests the generate label, and the value of the parameter of a
+    /// for generate statement, within the name of the enclosing unit, such
+    /// that the emitted hierarchy mirrors the generate statements.
+    fn gen_unit_name(
+        &self,
+        ctx: &llhd::ir::UnitBuilder<'_>,
+        label: Option<Spanned<Name>>,
+        index: Option<String>,
+    ) -> String {
+        let mut name = format!(
+            "{}.{}",
+            ctx.name().get_name().unwrap_or("anonymous"),
+            label
+                .map(|l| l.value.to_string())
+                .unwrap_or_else(|| "gen".to_string())
+        );
+        if let Some(index) = index {
+            name.push_str(&format!("({})", index));
+        }
+        name
+    }
+
+    /// Generate the body of a generate statement.
+    ///
+    /// The body becomes a separate entity that is instantiated in the
+    /// enclosing unit. The signals visible in the enclosing unit are passed to
+    /// the entity as inputs.
+    fn codegen_gen_body(
+        &self,
+        id: GenBodyRef,
+        name: String,
+        ctx: &mut llhd::ir::UnitBuilder<'_>,
+    ) -> Result<()> {
+        let hir = self.hir(id)?;
+        let mut outer: Vec<(NodeId, llhd::ir::Value)> = self
+            .sb
+            .llsig_table
+            .borrow()
+            .iter()
+            .map(|(&id, &value)| (id, value))
+            .collect();
+        outer.sort_by_key(|&(id, _)| id);
+        let mut sig = llhd::ir::Signature::new();
+        for &(_, value) in &outer {
+            sig.add_input(ctx.value_type(value));
+        }
+        let mut unit = llhd::ir::UnitData::new(
+            llhd::ir::UnitKind::Entity,
+            llhd::ir::UnitName::Global(name),
+            sig,
+        );
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut unit);
+        let args: Vec<_> = builder.input_args().collect();
+        let mut inner = HashMap::new();
+        for (&(id, value), &arg) in outer.iter().zip(args.iter()) {
+            if let Some(name) = ctx.get_name(value) {
+                builder.set_name(arg, name.to_string());
+            }
+            inner.insert(id, arg);
+        }
+
+        // Generate the declarations and statements of the body with the
+        // signals mapped to the arguments of the entity.
+        let outer_sigs = self.sb.llsig_table.replace(inner);
+        let outer_delayed = self.sb.lldelayed_table.replace(HashMap::new());
+        let outer_exports = self.sb.llexport_table.replace(Vec::new());
+        let result = (|| {
+            for &decl_id in &hir.decls {
+                self.codegen(decl_id, &mut builder)?;
+            }
+            for &stmt_id in &hir.stmts {
+                self.codegen(stmt_id, &mut builder)?;
+            }
+            Ok(())
+        })();
+        self.sb.llsig_table.replace(outer_sigs);
+        self.sb.lldelayed_table.replace(outer_delayed);
+        self.sb.llexport_table.replace(outer_exports);
+        result?;
+
+        let ext_unit = ctx.add_extern(builder.name().clone(), builder.sig().clone());
+        ctx.ins().inst(
+            ext_unit,
+            outer.iter().map(|&(_, value)| value).collect(),
+            vec![],
+        );
+        self.sb.llmod.borrow_mut().add_unit(unit);
+        Ok(())
+    }
+
+    /// Generate the signals accessed through external names in an
+    /// architecture.
+    ///
+    /// Each signal the architecture exports is either declared in it, and
+    /// connected to the corresponding output, or passed on to the instance
+    /// next on its path. Each signal referred to by an external name in a
+    /// process of the architecture is mirrored by a fresh signal, which the
+    /// instance next on its path drives.
+    pub fn codegen_external_signals(
+        &self,
+        id: ArchRef,
+        exports: &[ExportedSignal],
+        args: &[llhd::ir::Value],
+        builder: &mut llhd::ir::UnitBuilder<'_>,
+    ) -> Result<()> {
+        for (export, &arg) in exports.iter().zip(args) {
+            if export.path.is_empty() {
+                let sig = self.sb.llsig_table.borrow()[&export.signal.into()];
+                builder.ins().con(arg, sig);
+            } else {
+                self.sb
+                    .llexport_table
+                    .borrow_mut()
+                    .push((export.clone(), arg));
+            }
+        }
+
+        let mut sigs = ProcessSignals::default();
+        for &stmt in &self.hir(id)?.stmts {
+            match stmt {
+                ConcStmtRef::Process(id) => {
+                    self.collect_stmt_signals(&self.hir(id)?.stmts, &mut sigs)?
+                }
+                ConcStmtRef::ConcAssert(id) => {
+                    self.collect_stmt_signals(&self.hir(id)?.stmts, &mut sigs)?
+                }
+                ConcStmtRef::Psl(id) => self.collect_psl_signals(self.hir(id)?, &mut sigs)?,
+                _ => (),
+            }
+        }
+        let mut mirrored: Vec<ExportedSignal> = Vec::new();
+        for ext_id in sigs.external {
+            let export = match self.external_target(ext_id)? {
+                ExternalTarget::Signal(export) => export,
+                _ => continue,
+            };
+            if export.path.is_empty() || mirrored.contains(&export) {
+                continue;
+            }
+            if mirrored.iter().any(|other| other.signal == export.signal) {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "external names refer to signal `{}` in multiple instances",
+                        self.signal_name(export.signal)?
+                    ))
+                    .span(self.lazy_hir(ext_id)?.span)
+                    .add_note(
+                        "Only one instance of a signal may be referred to by the external \
+                         names of an architecture.",
+                    ),
+                );
+                return Err(());
+            }
+            let init = self.with_generic_env(export.env, || {
+                self.default_value_for_type(self.ty(export.signal)?)
+            })?;
+            let init = self.map_const(builder, init)?;
+            let value = builder.ins().sig(init);
+            let mut name: Vec<String> = export.path.iter().map(|n| n.to_string()).collect();
+            name.push(self.signal_name(export.signal)?.to_string());
+            builder.set_name(value, name.join("."));
+            self.sb
+                .llsig_table
+                .borrow_mut()
+                .insert(export.signal.into(), value);
+            self.sb
+                .llexport_table
+                .borrow_mut()
+                .push((export.clone(), value));
+            mirrored.push(export);
+        }
+        Ok(())
+    }
+}
+
+/// The design entity an instance is bound to during elaboration.
+pub(crate) struct InstBinding {
+    /// The bound entity.
+    pub entity: EntityRef,
+    /// The bound architecture of the entity.
+    pub arch: ArchRef,
+    /// The generic environment in which the architecture is generated.
+    pub env: GenericEnv,
+    /// The actuals associated with the ports of the entity.
+    pub port_map: Vec<(IntfSignalRef, ExprRef)>,
+}
+
+/// The signals accessed by the statements of a process.
+#[derive(Default)]
+struct ProcessSignals {
+    /// The signals whose value is read.
+    read: Vec<SignalRef>,
+    /// The signals whose previous value is read through an attribute such as
+    /// `S'event`.
+    delayed: Vec<SignalRef>,
+    /// The signals that are driven.
+    driven: Vec<SignalRef>,
+    /// The external names that refer to signals.
+    external: Vec<ExprRef>,
+}
+
+/// The state of the code generation for the statements of a process.
+struct ProcessContext {
+    /// Whether the process has a sensitivity list, which rules out wait
+    /// statements.
+    sensitized: bool,
+    /// Whether the process contains a wait statement.
+    waits: bool,
+    /// The enclosing loops, together with the blocks that `next` and `exit`
+    /// statements branch to.
+    loops: Vec<(LoopStmtRef, llhd::ir::Block, llhd::ir::Block)>,
+    /// The condition under which signals are currently driven. This is set
+    /// while generating an `if` statement that only assigns signals.
+    drive_enable: Option<llhd::ir::Value>,
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Collect the signals read by an expression.
+    fn collect_expr_signals(&self, id: ExprRef, sigs: &mut ProcessSignals) -> Result<()> {
+        let mut external = Vec::new();
+        self.walk_expr(id, &mut |id, data| match *data {
+            hir::ExprData::SignalName(sig) => sigs.read.push(sig),
+            hir::ExprData::SignalAttr(sig, _) => {
+                sigs.read.push(sig.value);
+                sigs.delayed.push(sig.value);
+            }
+            hir::ExprData::ExternalName(ref ext)
+                if ext.class.value == hir::ExternalClass::Signal =>
+            {
+                external.push(id)
+            }
+            _ => (),
+        })?;
+        for id in external {
+            if let ExternalTarget::Signal(export) = self.external_target(id)? {
+                sigs.read.push(export.signal);
+                sigs.external.push(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect the signals read and driven by a sequence of statements.
+    fn collect_stmt_signals(&self, stmts: &[SeqStmtRef], sigs: &mut ProcessSignals) -> Result<()> {
+        let mut exprs = Vec::new();
+        for &stmt in stmts {
+            match stmt {
+                SeqStmtRef::Wait(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    if let Some(ref sens) = hir.stmt.sens {
+                        sigs.read.extend(sens.value.iter().map(|sig| sig.value));
+                    }
+                    exprs.extend(hir.stmt.cond);
+                    exprs.extend(hir.stmt.timeout);
+                }
+                SeqStmtRef::SigAssign(id) => {
+                    let hir = self.hir(id)?;
+                    if let hir::SigAssignTarget::Name(sig) = hir.target {
+                        sigs.driven.push(sig);
+                    }
+                    if let hir::SigAssignKind::SimpleWave(_, ref wave) = hir.kind {
+                        for elem in wave {
+                            exprs.extend(elem.value);
+                            exprs.extend(elem.after);
+                        }
+                    }
+                }
+                SeqStmtRef::VarAssign(id) => match self.lazy_hir(id)?.stmt.kind {
+                    hir::VarAssignKind::Simple(expr) => exprs.push(expr),
+                    hir::VarAssignKind::Cond(ref cond) => {
+                        for &(expr, when) in &cond.when {
+                            exprs.push(expr);
+                            exprs.push(when);
+                        }
+                        exprs.extend(cond.other);
+                    }
+                    hir::VarAssignKind::Sel(ref sel) => {
+                        exprs.push(sel.disc);
+                        exprs.extend(sel.when.iter().map(|&(expr, _)| expr));
+                    }
+                },
+                SeqStmtRef::If(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    for &(cond, ref stmts) in &hir.stmt.branches {
+                        exprs.push(cond);
+                        self.collect_stmt_signals(stmts, sigs)?;
+                    }
+                    if let Some(ref stmts) = hir.stmt.otherwise {
+                        self.collect_stmt_signals(stmts, sigs)?;
+                    }
+                }
+                SeqStmtRef::Case(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    exprs.push(hir.stmt.switch);
+                    for &(_, ref stmts) in &hir.stmt.cases {
+                        self.collect_stmt_signals(stmts, sigs)?;
+                    }
+                }
+                SeqStmtRef::Loop(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    if let hir::LoopScheme::While(cond) = hir.stmt.scheme {
+                        exprs.push(cond);
+                    }
+                    self.collect_stmt_signals(&hir.stmt.stmts, sigs)?;
+                }
+                SeqStmtRef::Nexit(id) => exprs.extend(self.lazy_hir(id)?.stmt.cond),
+                SeqStmtRef::Return(id) => exprs.extend(self.lazy_hir(id)?.stmt.expr),
+                SeqStmtRef::Assert(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    exprs.push(hir.stmt.cond);
+                    exprs.extend(hir.stmt.report);
+                    exprs.extend(hir.stmt.severity);
+                }
+                SeqStmtRef::Report(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    exprs.push(hir.stmt.report);
+                    exprs.extend(hir.stmt.severity);
+                }
+                SeqStmtRef::ProcCall(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    exprs.extend(hir.stmt.object);
+                    exprs.extend(call_args(&hir.stmt.params));
+                }
+                SeqStmtRef::Null(_) => (),
+            }
+        }
+        for expr in exprs {
+            self.collect_expr_signals(expr, sigs)?;
+        }
+        Ok(())
+    }
+
+    /// Collect the signals read by the conditions of a PSL directive.
+    fn collect_psl_signals(
+        &self,
+        hir: &hir::PslDirective,
+        sigs: &mut ProcessSignals,
+    ) -> Result<()> {
+        let mut exprs = Vec::new();
+        hir.prop.conds(&mut exprs);
+        exprs.extend(hir.clock);
+        for expr in exprs {
+            self.collect_expr_signals(expr, sigs)?;
+        }
+        Ok(())
+    }
+
+    /// Look up the LLHD values of a list of signals.
+    fn signal_values(&self, sigs: &[SignalRef]) -> Vec<llhd::ir::Value> {
+        let table = self.sb.llsig_table.borrow();
+        sigs.iter().map(|&sig| table[&sig.into()]).collect()
+    }
+
+    /// Generate the report of a violated assertion or of a report statement.
+    ///
+    /// The message is printed through the `moore.print.text` intrinsic, and
+    /// then reported through the `moore.report` intrinsic together with the
+    /// severity level, upon which the simulator decides whether to stop. The
+    /// message must be constant, but the severity level may vary.
+    fn codegen_report(
+        &self,
+        report: Option<ExprRef>,
+        severity: Option<ExprRef>,
+        default_severity: usize,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        match report {
+            Some(report) if self.is_dynamic_expr(report)? => {
+                let span = self.span(report).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "report message `{}` must be constant",
+                        span.extract()
+                    ))
+                    .span(span)
+                    .add_note("Messages that read signals or variables are not supported."),
+                );
+                return Err(());
+            }
+            _ => (),
+        }
+        let text = self.report_message(report)?;
+        self.codegen_print_text(&text, builder);
+        let severity = match severity {
+            Some(severity) => self.codegen_expr(severity, builder)?,
+            None => {
+                let level = ConstEnum::new(SEVERITY_LEVEL_TYPE.id, default_severity);
+                self.map_const(builder, &level.into())?
+            }
+        };
+        self.codegen_intrinsic("moore.report", vec![severity], builder);
+        Ok(())
+    }
+
+    /// Print a piece of text through the `moore.print.text` intrinsic.
+    ///
+    /// The text is passed as an integer with one byte per character.
+    fn codegen_print_text(&self, text: &str, builder: &mut llhd::ir::UnitBuilder) {
+        if text.is_empty() {
+            return;
+        }
+        let value = self.codegen_text(text, builder);
+        self.codegen_intrinsic("moore.print.text", vec![value], builder);
+    }
+
+    /// Generate a piece of text as an integer with one byte per character.
+    fn codegen_text(&self, text: &str, builder: &mut llhd::ir::UnitBuilder) -> llhd::ir::Value {
+        let value = BigInt::from_bytes_be(num::bigint::Sign::Plus, text.as_bytes());
+        builder.ins().const_int((text.len() * 8, value))
+    }
+
+    /// Generate a call to an intrinsic function provided by the simulator.
+    ///
+    /// The argument types are appended to the name of the intrinsic, such that
+    /// every declaration has a unique signature.
+    fn codegen_intrinsic(
+        &self,
+        name: &str,
+        args: Vec<llhd::ir::Value>,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) {
+        let mut name = name.to_string();
+        let mut sig = llhd::ir::Signature::new();
+        for &arg in &args {
+            let ty = builder.value_type(arg);
+            name.push_str(&format!(".{}", ty));
+            sig.add_input(ty);
+        }
+        sig.set_return_type(llhd::void_ty());
+        let ext_unit = builder.add_extern(llhd::ir::UnitName::global(name), sig);
+        builder.ins().call(ext_unit, args);
+    }
+
+    /// Generate a process and instantiate it in the enclosing unit.
+    fn codegen_process(
+        &self,
+        hir: &hir::ProcessStmt,
+        ctx: &mut llhd::ir::UnitBuilder<'_>,
+    ) -> Result<()> {
+        let name = match hir.label {
+            Some(n) => format!("{}_{}", ctx.name(), n.value),
+            None => format!("{}_proc", ctx.name()),
+        };
+        let mut sigs = ProcessSignals::default();
+        self.collect_stmt_signals(&hir.stmts, &mut sigs)?;
+        if let hir::ProcessSensitivity::List(ref list) = hir.sensitivity {
+            sigs.read.extend(list.iter().map(|sig| sig.value));
+        }
+        self.codegen_process_unit(
+            name,
+            sigs,
+            &hir.sensitivity,
+            ctx,
+            |read, sampled, builder| self.codegen_process_body(hir, read, sampled, builder),
+        )
+    }
+
+    /// Generate a process unit and instantiate it in the enclosing unit.
+    ///
+    /// The signals that the process drives become its outputs, the others that
+    /// it reads its inputs. The `body` generates the blocks of the process,
+    /// given the signals it reads and those it samples itself.
+    fn codegen_process_unit(
+        &self,
+        name: String,
+        mut sigs: ProcessSignals,
+        sensitivity: &hir::ProcessSensitivity,
+        ctx: &mut llhd::ir::UnitBuilder<'_>,
+        body: impl FnOnce(&[SignalRef], &[SignalRef], &mut llhd::ir::UnitBuilder) -> Result<()>,
+    ) -> Result<()> {
+        let name = llhd::ir::UnitName::Global(name);
+        debugln!("generating process `{}`", name);
+
+        sigs.read.sort();
+        sigs.read.dedup();
+        sigs.delayed.sort();
+        sigs.delayed.dedup();
+        sigs.driven.sort();
+        sigs.driven.dedup();
+        let inputs: Vec<_> = sigs
+            .read
+            .iter()
+            .cloned()
+            .filter(|sig| !sigs.driven.contains(sig))
+            .collect();
+        let mut input_values = self.signal_values(&inputs);
+        let output_values = self.signal_values(&sigs.driven);
+
+        // Signal attributes compare a signal against its previous value. A process
+        // samples the signals it is sensitive to itself. For all others, a delayed
+        // copy is created outside of the process and passed to it as an input.
+        let (sampled, delayed): (Vec<_>, Vec<_>) =
+            sigs.delayed
+                .iter()
+                .cloned()
+                .partition(|&sig| match *sensitivity {
+                    hir::ProcessSensitivity::None => false,
+                    hir::ProcessSensitivity::All => true,
+                    hir::ProcessSensitivity::List(ref list) => list.iter().any(|s| s.value == sig),
+                });
+        for &sig in &delayed {
+            let current = self.codegen_signal_value(sig, ctx);
+            input_values.push(self.codegen_delayed_signal(sig, current, ctx)?);
+        }
+
+        let mut sig = llhd::ir::Signature::new();
+        for &value in &input_values {
+            sig.add_input(ctx.value_type(value));
+        }
+        for &value in &output_values {
+            sig.add_output(ctx.value_type(value));
+        }
+        let mut prok = llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name, sig);
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
+
+        // Map the signals to the arguments of the process.
+        let mut inner_sigs = HashMap::new();
+        let mut inner_delayed = HashMap::new();
+        let input_args: Vec<_> = builder.input_args().collect();
+        let output_args: Vec<_> = builder.output_args().collect();
+        for (&sig, &arg) in inputs.iter().zip(input_args.iter()) {
+            inner_sigs.insert(sig.into(), arg);
+        }
+        for (&sig, &arg) in delayed.iter().zip(input_args[inputs.len()..].iter()) {
+            inner_delayed.insert(sig.into(), arg);
+        }
+        for (&sig, &arg) in sigs.driven.iter().zip(output_args.iter()) {
+            inner_sigs.insert(sig.into(), arg);
+        }
+        let values = input_values.iter().chain(output_values.iter());
+        for (&value, &arg) in values.zip(input_args.iter().chain(output_args.iter())) {
+            if let Some(name) = ctx.get_name(value) {
+                builder.set_name(arg, name.to_string());
+            }
+        }
+
+        // Generate the declarations and statements with the signals mapped to the
+        // arguments of the process.
+        let outer_sigs = self.sb.llsig_table.replace(inner_sigs);
+        let outer_delayed = self.sb.lldelayed_table.replace(inner_delayed);
+        let outer_vars = self.sb.llvar_table.replace(HashMap::new());
+        let result = body(&sigs.read, &sampled, &mut builder);
+        self.sb.llsig_table.replace(outer_sigs);
+        self.sb.lldelayed_table.replace(outer_delayed);
+        self.sb.llvar_table.replace(outer_vars);
+        result?;
+
+        let ext_unit = ctx.add_extern(builder.name().clone(), builder.sig().clone());
+        ctx.ins().inst(ext_unit, input_values, output_values);
+        self.sb.llmod.borrow_mut().add_unit(prok);
+        Ok(())
+    }
+
+    /// Generate the declarations and statements of a process.
+    ///
+    /// The declarations are generated once upon entry into the process, after
+    /// which the statements repeat forever. A sensitivity list is equivalent
+    /// to a wait statement on its signals at the end of the statements, where
+    /// `all` stands for the signals read by the process. See IEEE 1076-2008
+    /// section 11.3.
+    ///
+    /// The `sampled` signals are probed whenever the process suspends, such
+    /// that their attributes compare against the value they had before the
+    /// process resumed. This is the same form event controls in SystemVerilog
+    /// are lowered to, which allows clocked processes to become registers.
+    fn codegen_process_body(
+        &self,
+        hir: &hir::ProcessStmt,
+        read: &[SignalRef],
+        sampled: &[SignalRef],
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let entry_bb = builder.named_block("entry");
+        builder.append_to(entry_bb);
+        for &decl in &hir.decls {
+            self.codegen(decl, builder)?;
+        }
+        let mut samples = Vec::new();
+        for &sig in sampled {
+            let init = self.codegen_signal_value(sig, builder);
+            let var = builder.ins().var(init);
+            self.sb.lldelayed_table.borrow_mut().insert(sig.into(), var);
+            samples.push((sig, var));
+        }
+        let body_bb = builder.named_block("body");
+        builder.ins().br(body_bb);
+        builder.append_to(body_bb);
+
+        let sens = match hir.sensitivity {
+            hir::ProcessSensitivity::None => None,
+            hir::ProcessSensitivity::All => Some(read.to_vec()),
+            hir::ProcessSensitivity::List(ref list) => {
+                Some(list.iter().map(|sig| sig.value).collect::<Vec<_>>())
+            }
+        };
+        let mut pc = ProcessContext {
+            sensitized: sens.is_some(),
+            waits: false,
+            loops: vec![],
+            drive_enable: None,
+        };
+        self.codegen_seq_stmts(&hir.stmts, &mut pc, builder)?;
+        match sens {
+            Some(sens) => {
+                for &(sig, var) in &samples {
+                    let value = self.codegen_signal_value(sig, builder);
+                    builder.ins().st(var, value);
+                }
+                let sens = self.signal_values(&sens);
+                builder.ins().wait(body_bb, sens);
+            }
+            None if pc.waits => {
+                builder.ins().br(body_bb);
+            }
+            // A process without any wait statement would loop forever without
+            // advancing time, so it runs its statements only once.
+            None => {
+                builder.ins().halt();
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate a process that verifies a PSL directive.
+    ///
+    /// Formal back ends pick up the directive from a call to the
+    /// `moore.psl.assert`, `moore.psl.assume`, `moore.psl.restrict`, or
+    /// `moore.psl.cover` intrinsic. Its arguments are the property as text, in
+    /// which `$n` stands for the nth condition, the report message, the
+    /// severity level, and the values of the conditions. The process evaluates
+    /// the conditions whenever a signal they read changes.
+    fn codegen_psl_directive(
+        &self,
+        hir: &hir::PslDirective,
+        ctx: &mut llhd::ir::UnitBuilder<'_>,
+    ) -> Result<()> {
+        let name = match hir.label {
+            Some(n) => format!("{}_{}", ctx.name(), n.value),
+            None => format!("{}_psl", ctx.name()),
+        };
+        let mut sigs = ProcessSignals::default();
+        self.collect_psl_signals(hir, &mut sigs)?;
+        let mut conds = Vec::new();
+        let mut text = psl_text(&hir.prop, &mut conds);
+        if let Some(clock) = hir.clock {
+            text = format!("({}) @ ${}", text, conds.len());
+            conds.push(clock);
+        }
+        let sens = hir::ProcessSensitivity::All;
+        self.codegen_process_unit(name, sigs, &sens, ctx, |read, sampled, builder| {
+            let entry_bb = builder.named_block("entry");
+            builder.append_to(entry_bb);
+            let mut samples = Vec::new();
+            for &sig in sampled {
+                let init = self.codegen_signal_value(sig, builder);
+                let var = builder.ins().var(init);
+                self.sb.lldelayed_table.borrow_mut().insert(sig.into(), var);
+                samples.push((sig, var));
+            }
+            let body_bb = builder.named_block("body");
+            builder.ins().br(body_bb);
+            builder.append_to(body_bb);
+
+            let mut args = vec![
+                self.codegen_text(&text, builder),
+                self.codegen_text(&self.report_message(hir.report)?, builder),
+            ];
+            args.push(match hir.severity {
+                Some(severity) => self.codegen_expr(severity, builder)?,
+                None => {
+                    let default = match hir.kind {
+                        hir::PslDirectiveKind::Cover => SEVERITY_NOTE,
+                        _ => SEVERITY_ERROR,
+                    };
+                    let level = ConstEnum::new(SEVERITY_LEVEL_TYPE.id, default);
+                    self.map_const(builder, &level.into())?
+                }
+            });
+            for &cond in &conds {
+                args.push(self.codegen_condition(cond, builder)?);
+            }
+            let name = match hir.kind {
+                hir::PslDirectiveKind::Assert => "moore.psl.assert",
+                hir::PslDirectiveKind::Assume => "moore.psl.assume",
+                hir::PslDirectiveKind::Restrict => "moore.psl.restrict",
+                hir::PslDirectiveKind::Cover => "moore.psl.cover",
+            };
+            self.codegen_intrinsic(name, args, builder);
+
+            for &(sig, var) in &samples {
+                let value = self.codegen_signal_value(sig, builder);
+                builder.ins().st(var, value);
+            }
+            let sens = self.signal_values(read);
+            builder.ins().wait(body_bb, sens);
+            Ok(())
+        })
+    }
+
+    /// Generate code for a sequence of statements in a process.
+    fn codegen_seq_stmts(
+        &self,
+        stmts: &[SeqStmtRef],
+        pc: &mut ProcessContext,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        for &stmt in stmts {
+            self.codegen_seq_stmt(stmt, pc, builder)?;
+        }
+        Ok(())
+    }
+
+    /// Generate code for a statement in a process.
+    fn codegen_seq_stmt(
+        &self,
+        id: SeqStmtRef,
+        pc: &mut ProcessContext,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        match id {
+            SeqStmtRef::Wait(id) => self.codegen_wait_stmt(id, pc, builder),
+            SeqStmtRef::SigAssign(id) => self.codegen_sig_assign_stmt(id, pc, builder),
+            SeqStmtRef::VarAssign(id) => self.codegen_var_assign_stmt(id, builder),
+            SeqStmtRef::If(id) if self.is_drive_only(&[SeqStmtRef::If(id)])? => {
+                self.codegen_drive_if(id, pc, builder)
+            }
+            SeqStmtRef::If(id) => {
+                let hir = self.lazy_hir(id)?;
+                let done_bb = builder.block();
+                for &(cond, ref stmts) in &hir.stmt.branches {
+                    let holds = self.codegen_condition(cond, builder)?;
+                    let then_bb = builder.block();
+                    let else_bb = builder.block();
+                    builder.ins().br_cond(holds, else_bb, then_bb);
+                    builder.append_to(then_bb);
+                    self.codegen_seq_stmts(stmts, pc, builder)?;
+                    builder.ins().br(done_bb);
+                    builder.append_to(else_bb);
+                }
+                if let Some(ref stmts) = hir.stmt.otherwise {
+                    self.codegen_seq_stmts(stmts, pc, builder)?;
+                }
+                builder.ins().br(done_bb);
+                builder.append_to(done_bb);
+                Ok(())
+            }
+            SeqStmtRef::Case(id) => {
+                let hir = self.lazy_hir(id)?;
+                let switch = self.codegen_expr(hir.stmt.switch, builder)?;
+                let done_bb = builder.block();
+                for &(ref choices, ref stmts) in &hir.stmt.cases {
+                    let holds =
+                        self.codegen_choices_match(hir.stmt.switch, switch, choices, builder)?;
+                    let then_bb = builder.block();
+                    let else_bb = builder.block();
+                    builder.ins().br_cond(holds, else_bb, then_bb);
+                    builder.append_to(then_bb);
+                    self.codegen_seq_stmts(stmts, pc, builder)?;
+                    builder.ins().br(done_bb);
+                    builder.append_to(else_bb);
+                }
+                builder.ins().br(done_bb);
+                builder.append_to(done_bb);
+                Ok(())
+            }
+            SeqStmtRef::Loop(id) => self.codegen_loop_stmt(id, pc, builder),
+            SeqStmtRef::Nexit(id) => {
+                let hir = self.lazy_hir(id)?;
+                let target = hir.stmt.target.map(|t| t.value);
+                let &(_, next_bb, exit_bb) = pc
+                    .loops
+                    .iter()
+                    .rev()
+                    .find(|&&(loop_id, _, _)| target.map_or(true, |t| t == loop_id))
+                    .unwrap();
+                let target_bb = match hir.stmt.mode {
+                    hir::NexitMode::Next => next_bb,
+                    hir::NexitMode::Exit => exit_bb,
+                };
+                let cont_bb = builder.block();
+                match hir.stmt.cond {
+                    Some(cond) => {
+                        let holds = self.codegen_condition(cond, builder)?;
+                        builder.ins().br_cond(holds, cont_bb, target_bb);
+                    }
+                    None => {
+                        builder.ins().br(target_bb);
+                    }
+                }
+                builder.append_to(cont_bb);
+                Ok(())
+            }
+            SeqStmtRef::Assert(id) => {
+                let hir = self.lazy_hir(id)?;
+                let done_bb = builder.block();
+                let holds = self.codegen_condition(hir.stmt.cond, builder)?;
+                let fail_bb = builder.block();
+                builder.ins().br_cond(holds, fail_bb, done_bb);
+                builder.append_to(fail_bb);
+                self.codegen_report(hir.stmt.report, hir.stmt.severity, SEVERITY_ERROR, builder)?;
+                builder.ins().br(done_bb);
+                builder.append_to(done_bb);
+                Ok(())
+            }
+            SeqStmtRef::Report(id) => {
+                let hir = self.lazy_hir(id)?;
+                self.codegen_report(
+                    Some(hir.stmt.report),
+                    hir.stmt.severity,
+                    SEVERITY_NOTE,
+                    builder,
+                )
+            }
+            SeqStmtRef::Null(_) => Ok(()),
+            SeqStmtRef::ProcCall(id) => self.codegen_proc_call(id, builder),
+            SeqStmtRef::Return(_) => {
+                let span = self.span(id).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!("cannot generate code for `{}`", span.extract()))
+                        .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate code for a procedure call statement.
+    ///
+    /// Only the procedures of packages `TEXTIO` and `ENV` are supported, which
+    /// are lowered to intrinsics provided by the simulator. `STOP` and
+    /// `FINISH` end the simulation through the `moore.stop` and
+    /// `moore.finish` intrinsics. The simulator keeps a single line buffer,
+    /// which stands in for all variables of type `LINE`: the `WRITE`
+    /// procedures print to it, and `WRITELINE` emits it through the
+    /// `moore.display` intrinsic.
+    fn codegen_proc_call(
+        &self,
+        id: CallStmtRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        let name = match self.call_overload(id)?.value {
+            Def::BuiltinOp(op) => match builtin_op(op).map(|bi| bi.name) {
+                Some(ResolvableName::Ident(name)) => name.as_str().to_uppercase(),
+                _ => unreachable!(),
+            },
+            _ => String::new(),
+        };
+        let args: Vec<_> = call_args(&hir.stmt.params).collect();
+        match (&*name, &args[..]) {
+            ("STOP", _) | ("FINISH", _) => {
+                let status = match args.first() {
+                    Some(&status) => self.codegen_expr(status, builder)?,
+                    None => builder.ins().const_int((32, 0)),
+                };
+                let name = format!("moore.{}", name.to_lowercase());
+                self.codegen_intrinsic(&name, vec![status], builder);
+                Ok(())
+            }
+            ("WRITE", _) | ("SWRITE", _) => self.codegen_textio_write(&args, 2, builder),
+            ("OWRITE", _) => self.codegen_textio_write(&args, 8, builder),
+            ("HWRITE", _) => self.codegen_textio_write(&args, 16, builder),
+            ("WRITELINE", &[file, _]) => {
+                match self.lazy_hir(file)?.data {
+                    hir::ExprData::FileName(id) if id == *OUTPUT_FILE_REF => (),
+                    _ => {
+                        let span = self.span(file).unwrap();
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "cannot generate code for writing to file `{}`",
+                                span.extract()
+                            ))
+                            .span(span)
+                            .add_note("Only lines written to file `OUTPUT` are supported."),
+                        );
+                        return Err(());
+                    }
+                }
+                self.codegen_intrinsic("moore.display", vec![], builder);
+                Ok(())
+            }
+            ("DEALLOCATE", _) => Ok(()),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot generate code for `{}`",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span)
+                    .add_note(
+                        "Only the procedures `STOP` and `FINISH` of package `ENV`, and writing \
+                         lines to file `OUTPUT` through package `TEXTIO` are supported.",
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate code for a call to the `WRITE`, `SWRITE`, `OWRITE`, or `HWRITE`
+    /// procedure of package `TEXTIO`.
+    ///
+    /// Values known at compile time are formatted right away and printed as
+    /// text. Integers, bits, and characters that read signals or variables are
+    /// printed through the `moore.print.*` intrinsics, which pad them to the
+    /// field width. The justification and field width must be constant.
+    fn codegen_textio_write(
+        &self,
+        args: &[ExprRef],
+        radix: u32,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let value = args[1];
+        let left = match args.get(2) {
+            Some(&justified) => match *self.const_value(justified)? {
+                Const::Enum(ref k) => k.index == 1,
+                _ => unreachable!(),
+            },
+            None => false,
+        };
+        let field = match args.get(3) {
+            Some(&field) => match *self.const_value(field)? {
+                Const::Int(ref k) => k.value.to_usize().unwrap_or(0),
+                _ => unreachable!(),
+            },
+            None => 0,
+        };
+        let span = self.span(value).unwrap();
+
+        // Format constant values right away.
+        if !self.is_dynamic_expr(value)? {
+            let image = self.write_image(self.const_value(value)?, radix, span)?;
+            let pad = " ".repeat(field.saturating_sub(image.chars().count()));
+            let text = if left { image + &pad } else { pad + &image };
+            self.codegen_print_text(&text, builder);
+            return Ok(());
+        }
+
+        // Print other values through the simulator.
+        let ty = self.deref_named_type(self.lazy_typeval(value)?)?;
+        let name = match *ty {
+            Ty::Int(ref ty) if is_signed_int(ty) => Some("moore.print.sdec"),
+            Ty::Int(_) => Some("moore.print.dec"),
+            Ty::Enum(ref ty) if ty.decl == BIT_TYPE.id => Some("moore.print.bin"),
+            Ty::Enum(ref ty) if ty.decl == CHARACTER_TYPE.id => Some("moore.print.char"),
+            _ => None,
+        };
+        let name = match name {
+            Some(name) if !left || field == 0 => name,
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot generate code for writing `{}`",
+                        span.extract()
+                    ))
+                    .span(span)
+                    .add_note(
+                        "Only constant values, and right-justified integers, bits, and \
+                             characters that read signals or variables are supported.",
+                    ),
+                );
+                return Err(());
+            }
+        };
+        let mut llargs = vec![self.codegen_expr(value, builder)?];
+        if name != "moore.print.char" {
+            llargs.push(builder.ins().const_int((32, BigInt::from(field))));
+        }
+        self.codegen_intrinsic(name, llargs, builder);
+        Ok(())
+    }
+
+    /// Check whether a sequence of statements only assigns signals.
+    ///
+    /// Such statements can be guarded by an enable condition on their drives,
+    /// rather than by a branch around them.
+    fn is_drive_only(&self, stmts: &[SeqStmtRef]) -> Result<bool> {
+        for &stmt in stmts {
+            match stmt {
+                SeqStmtRef::SigAssign(_) | SeqStmtRef::Null(_) => (),
+                SeqStmtRef::If(id) => {
+                    let hir = self.lazy_hir(id)?;
+                    let branches = hir.stmt.branches.iter().map(|&(_, ref stmts)| stmts);
+                    for stmts in branches.chain(&hir.stmt.otherwise) {
+                        if !self.is_drive_only(stmts)? {
+                            return Ok(false);
+                        }
+                    }
                 }
+                _ => return Ok(false),
             }
-            Ty::Enum(ref ty) => {
-                let hir = self.lazy_hir(ty.decl)?;
-                match hir.data.as_ref().unwrap().value {
-                    hir::TypeData::Enum(ref lits) => llhd::enum_ty(lits.len()),
-                    _ => unreachable!(),
+        }
+        Ok(true)
+    }
+
+    /// Generate code for an `if` statement that only assigns signals.
+    ///
+    /// Rather than branching, each branch drives its signals under the
+    /// condition that it is taken. This matches how `if` statements in
+    /// SystemVerilog `always_ff` blocks are lowered, and yields registers for
+    /// the usual clocked processes.
+    fn codegen_drive_if(
+        &self,
+        id: IfStmtRef,
+        pc: &mut ProcessContext,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        let outer = pc.drive_enable;
+
+        // The condition under which none of the previous branches is taken.
+        let mut rest = outer;
+        for &(cond, ref stmts) in &hir.stmt.branches {
+            let holds = self.codegen_condition(cond, builder)?;
+            let taken = match rest {
+                Some(rest) => builder.ins().and(rest, holds),
+                None => holds,
+            };
+            pc.drive_enable = Some(taken);
+            self.codegen_seq_stmts(stmts, pc, builder)?;
+            let not_holds = self.codegen_invert(holds, builder);
+            rest = Some(match rest {
+                Some(rest) => builder.ins().and(rest, not_holds),
+                None => not_holds,
+            });
+        }
+        if let Some(ref stmts) = hir.stmt.otherwise {
+            pc.drive_enable = rest;
+            self.codegen_seq_stmts(stmts, pc, builder)?;
+        }
+        pc.drive_enable = outer;
+        Ok(())
+    }
+
+    /// Generate code for a condition, yielding a single bit.
+    fn codegen_condition(
+        &self,
+        id: ExprRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        // Booleans are represented as enumeration values, such that `TRUE`
+        // has its lowest bit set.
+        let value = self.codegen_expr(id, builder)?;
+        Ok(builder.ins().ext_slice(value, 0, 1))
+    }
+
+    /// Generate code that checks whether the value of the expression of a
+    /// case statement matches any of a list of choices.
+    fn codegen_choices_match(
+        &self,
+        switch: ExprRef,
+        value: llhd::ir::Value,
+        choices: &Spanned<hir::Choices>,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let ty = self.lazy_typeval(switch)?;
+        let signed = match *self.deref_named_type(ty)? {
+            Ty::Int(ref ty) => is_signed_int(ty),
+            _ => false,
+        };
+        let mut matched = builder.ins().const_int((1, 0));
+        for choice in &choices.value {
+            let holds = match choice.value {
+                hir::Choice::Expr(expr) => {
+                    // The choices assume the type of the expression, which
+                    // picks among overloaded enumeration literals.
+                    if self.type_context(expr).is_none() {
+                        self.set_type_context(expr, ty);
+                    }
+                    let k = self.codegen_expr(expr, builder)?;
+                    builder.ins().eq(value, k)
+                }
+                hir::Choice::DiscreteRange(ref range) => {
+                    let (dir, left, right) = match *range {
+                        hir::DiscreteRange::Range(ref r) => self.range_bounds(r, choice.span)?,
+                        hir::DiscreteRange::Subtype(subty) => {
+                            self.discrete_bounds(self.ty(subty)?, choice.span)?
+                        }
+                    };
+                    let (low, high) = match dir {
+                        hir::Dir::To => (left, right),
+                        hir::Dir::Downto => (right, left),
+                    };
+                    let width = builder.value_type(value).unwrap_int();
+                    let low = builder.ins().const_int((width, low));
+                    let high = builder.ins().const_int((width, high));
+                    let mut ins = builder.ins();
+                    let (above, below) = if signed {
+                        (ins.sge(value, low), ins.sle(value, high))
+                    } else {
+                        (ins.uge(value, low), ins.ule(value, high))
+                    };
+                    ins.and(above, below)
                 }
+                hir::Choice::Others => builder.ins().const_int((1, 1)),
+                hir::Choice::Element(_) => builder.ins().const_int((1, 0)),
+            };
+            matched = builder.ins().or(matched, holds);
+        }
+        Ok(matched)
+    }
+
+    /// Generate code for a loop statement.
+    ///
+    /// For loops are unrolled, with the loop parameter bound to one of its
+    /// values in each copy of the statements.
+    fn codegen_loop_stmt(
+        &self,
+        id: LoopStmtRef,
+        pc: &mut ProcessContext,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        let exit_bb = builder.block();
+        match hir.stmt.scheme {
+            hir::LoopScheme::Loop => {
+                let body_bb = builder.block();
+                builder.ins().br(body_bb);
+                builder.append_to(body_bb);
+                pc.loops.push((id, body_bb, exit_bb));
+                let result = self.codegen_seq_stmts(&hir.stmt.stmts, pc, builder);
+                pc.loops.pop();
+                result?;
+                builder.ins().br(body_bb);
             }
-            Ty::Physical(ref ty) => {
-                self.emit(DiagBuilder2::error(format!(
-                    "cannot generate code for physical type `{}`",
-                    ty
-                )));
-                return Err(());
+            hir::LoopScheme::While(cond) => {
+                let head_bb = builder.block();
+                let body_bb = builder.block();
+                builder.ins().br(head_bb);
+                builder.append_to(head_bb);
+                let holds = self.codegen_condition(cond, builder)?;
+                builder.ins().br_cond(holds, exit_bb, body_bb);
+                builder.append_to(body_bb);
+                pc.loops.push((id, head_bb, exit_bb));
+                let result = self.codegen_seq_stmts(&hir.stmt.stmts, pc, builder);
+                pc.loops.pop();
+                result?;
+                builder.ins().br(head_bb);
             }
-            Ty::Access(ref ty) => llhd::pointer_ty(self.map_type(ty)?),
-            Ty::Array(ref ty) => {
-                let mut llty = self.map_type(&ty.element)?;
-                for index in ty.indices.iter().rev() {
-                    match *index {
-                        ArrayIndex::Unbounded(_) => {
-                            self.emit(
-                                DiagBuilder2::error(format!("type `{}` is unbounded", ty)), // TODO: What span should we use here?
-                            );
-                            return Err(());
-                        }
-                        ArrayIndex::Constrained(ref ty) => {
-                            let num = match **ty {
-                                Ty::Int(ref ty) => {
-                                    let l = ty.len();
-                                    if l.is_negative() || l.is_zero() {
-                                        return Ok(llhd::void_ty());
-                                    }
-                                    match l.to_usize() {
-                                        Some(l) => l,
-                                        None => {
-                                            self.emit(
-                                                DiagBuilder2::error(format!(
-                                                    "array index `{}` is too large; {} elements",
-                                                    ty, l
-                                                )), // TODO: What span should we use here?
-                                            );
-                                            return Err(());
-                                        }
-                                    }
-                                }
-                                Ty::Enum(ref ty) => {
-                                    match self.lazy_hir(ty.decl)?.data.as_ref().unwrap().value {
-                                        hir::TypeData::Enum(ref lits) => lits.len(),
-                                        _ => unreachable!(),
-                                    }
-                                }
-                                _ => {
-                                    self.emit(
-                                        DiagBuilder2::error(format!(
-                                            "`{}` is an invalid array index type",
-                                            ty
-                                        )), // TODO: What span should we use here?
-                                    );
-                                    return Err(());
-                                }
-                            };
-                            llty = llhd::array_ty(num, llty);
-                        }
-                    }
+            hir::LoopScheme::For(..) => {
+                for value in self.loop_param_values(id)? {
+                    let next_bb = builder.block();
+                    pc.loops.push((id, next_bb, exit_bb));
+                    let result = self.with_local_value(id.into(), value, || {
+                        self.codegen_seq_stmts(&hir.stmt.stmts, pc, builder)
+                    });
+                    pc.loops.pop();
+                    result?;
+                    builder.ins().br(next_bb);
+                    builder.append_to(next_bb);
                 }
-                llty
+                builder.ins().br(exit_bb);
             }
-            Ty::File(ref _ty) => llhd::int_ty(32),
-            Ty::Record(ref ty) => {
-                let fields = ty
-                    .fields
-                    .iter()
-                    .map(|&(_, ref ty)| self.map_type(ty))
-                    .collect::<Result<_>>()?;
-                llhd::struct_ty(fields)
+        }
+        builder.append_to(exit_bb);
+        Ok(())
+    }
+
+    /// Generate code for a wait statement.
+    ///
+    /// A condition without a sensitivity clause waits on the signals read by
+    /// the condition. Unless a timeout is given, the process suspends again
+    /// as long as the condition does not hold. See IEEE 1076-2008 section
+    /// 10.2.
+    fn codegen_wait_stmt(
+        &self,
+        id: WaitStmtRef,
+        pc: &mut ProcessContext,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        if pc.sensitized {
+            self.emit(
+                DiagBuilder2::error("wait statement in a process with a sensitivity list")
+                    .span(hir.span)
+                    .add_note(
+                        "A process with a sensitivity list cannot contain wait statements. See \
+                         IEEE 1076-2008 section 11.3.",
+                    ),
+            );
+            return Err(());
+        }
+        pc.waits = true;
+
+        let mut sens = match hir.stmt.sens {
+            Some(ref sens) => sens.value.iter().map(|sig| sig.value).collect(),
+            None => {
+                let mut sigs = ProcessSignals::default();
+                if let Some(cond) = hir.stmt.cond {
+                    self.collect_expr_signals(cond, &mut sigs)?;
+                }
+                sigs.read
             }
-            Ty::Subprog(..) => unimplemented!(),
-            // Unbounded integers cannot be mapped to LLHD. All cases where
-            // such an int can leak through to codegen should actually be caught
-            // beforehand in the type check.
-            Ty::UnboundedInt | Ty::UniversalInt => unreachable!(),
-        })
+        };
+        sens.sort();
+        sens.dedup();
+        let sens = self.signal_values(&sens);
+
+        // A plain `wait;` suspends the process forever.
+        if sens.is_empty() && hir.stmt.cond.is_none() && hir.stmt.timeout.is_none() {
+            builder.ins().halt();
+            let dead_bb = builder.block();
+            builder.append_to(dead_bb);
+            return Ok(());
+        }
+
+        let wait_bb = builder.block();
+        let resume_bb = builder.block();
+        builder.ins().br(wait_bb);
+        builder.append_to(wait_bb);
+        match hir.stmt.timeout {
+            Some(timeout) => {
+                let time = self.codegen_time(timeout, builder)?;
+                builder.ins().wait_time(resume_bb, time, sens);
+            }
+            None => {
+                builder.ins().wait(resume_bb, sens);
+            }
+        }
+        builder.append_to(resume_bb);
+        if let (Some(cond), None) = (hir.stmt.cond, hir.stmt.timeout) {
+            let holds = self.codegen_condition(cond, builder)?;
+            let done_bb = builder.block();
+            builder.ins().br_cond(holds, wait_bb, done_bb);
+            builder.append_to(done_bb);
+        }
+        Ok(())
     }
 
-    /// Map a constant value to the LLHD counterpart.
-    pub fn map_const(
+    /// Generate code for a constant `TIME` value.
+    fn codegen_time(
         &self,
+        id: ExprRef,
         builder: &mut llhd::ir::UnitBuilder,
-        konst: &Const,
     ) -> Result<llhd::ir::Value> {
-        Ok(match *konst {
-            // TODO: Map this to llhd::const_void once available.
-            Const::Null => builder.ins().const_int((0, 0)),
-            Const::Int(ref k) => builder.ins().const_int((999, k.value.clone())),
-            Const::Enum(ref k) => {
-                let size = match self.lazy_hir(k.decl)?.data.as_ref().unwrap().value {
-                    hir::TypeData::Enum(ref lits) => lits.len(),
-                    _ => unreachable!(),
-                };
-                builder.ins().const_int((size, k.index))
+        let fs = self.time_value(id)?;
+        Ok(self.codegen_time_value(&fs, builder))
+    }
+
+    /// Evaluate a constant `TIME` expression to a number of femtoseconds.
+    fn time_value(&self, id: ExprRef) -> Result<BigInt> {
+        match *self.const_value(id)? {
+            // Times are multiples of the primary unit `fs`.
+            Const::Physical(ref k) => Ok(k.value.clone()),
+            ref k => {
+                let span = self.span(id).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a time", span.extract()))
+                        .span(span)
+                        .add_note(format!(
+                            "`{}` evaluates to {} {}",
+                            span.extract(),
+                            k.kind_desc(),
+                            k
+                        )),
+                );
+                Err(())
             }
-            Const::Float(ref _k) => panic!("cannot map float constant"),
-            Const::IntRange(_) | Const::FloatRange(_) => panic!("cannot map range constant"),
         }
-        .into())
+    }
+
+    /// Generate code for a time given in femtoseconds.
+    ///
+    /// A time of zero advances by one delta cycle, such that `wait for 0 ns`
+    /// and assignments without an `after` clause take effect in the next
+    /// simulation cycle.
+    fn codegen_time_value(
+        &self,
+        fs: &BigInt,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> llhd::ir::Value {
+        let time = if fs.is_zero() {
+            llhd::value::TimeValue::new(num::zero(), 1, 0)
+        } else {
+            let secs = BigRational::new(fs.clone(), num::pow(BigInt::from(10), 15));
+            llhd::value::TimeValue::new(secs, 0, 0)
+        };
+        builder.ins().const_time(time)
+    }
+
+    /// Generate code for a sequential signal assignment.
+    ///
+    /// Each element of the waveform drives the signal after its delay, or
+    /// after one delta cycle if it has none. Null elements are skipped. Since
+    /// drives in LLHD have the semantics of a transport delay, an inertial
+    /// delay is only exact if its pulse rejection limit is zero. See IEEE
+    /// 1076-2008 section 10.5.2.
+    fn codegen_sig_assign_stmt(
+        &self,
+        id: SigAssignStmtRef,
+        pc: &ProcessContext,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let hir = self.hir(id)?;
+        let (sig, target) = match hir.target {
+            hir::SigAssignTarget::Name(sig) => {
+                (self.sb.llsig_table.borrow()[&sig.into()], self.ty(sig)?)
+            }
+            // Caught by the type check.
+            hir::SigAssignTarget::Aggregate => unreachable!("assignment to aggregate signal"),
+        };
+        let (mech, wave) = match hir.kind {
+            hir::SigAssignKind::SimpleWave(mech, ref wave) => (mech, wave),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot generate code for `{}`",
+                        hir.kind_span.extract()
+                    ))
+                    .span(hir.kind_span),
+                );
+                return Err(());
+            }
+        };
+
+        // Determine the delay of each element, which must be ascending. See
+        // IEEE 1076-2008 section 10.5.2.2.
+        let mut delays: Vec<BigInt> = Vec::new();
+        for elem in wave {
+            let (delay, span) = match elem.after {
+                Some(after) => (self.time_value(after)?, self.span(after).unwrap()),
+                None => (BigInt::zero(), hir.kind_span),
+            };
+            if delay.is_negative() {
+                self.emit(
+                    DiagBuilder2::error(format!("delay `{}` is negative", span.extract()))
+                        .span(span),
+                );
+                return Err(());
+            }
+            if delays.last().map_or(false, |last| delay <= *last) {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "delay `{}` is not greater than the delay of the previous waveform \
+                         element",
+                        span.extract()
+                    ))
+                    .span(span)
+                    .add_note(
+                        "The delays of a waveform must be in ascending order. See IEEE \
+                         1076-2008 section 10.5.2.2.",
+                    ),
+                );
+                return Err(());
+            }
+            delays.push(delay);
+        }
+
+        // The pulse rejection limit of an inertial delay defaults to the delay
+        // of the first element. See IEEE 1076-2008 section 10.5.2.1.
+        let first = delays.first().cloned().unwrap_or_else(BigInt::zero);
+        let reject = match mech {
+            hir::DelayMechanism::Transport => BigInt::zero(),
+            hir::DelayMechanism::Inertial => first.clone(),
+            hir::DelayMechanism::RejectInertial(limit) => {
+                let reject = self.time_value(limit)?;
+                if reject.is_negative() || reject > first {
+                    let span = self.span(limit).unwrap();
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "pulse rejection limit `{}` is negative or greater than the first \
+                             delay",
+                            span.extract()
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+                reject
+            }
+        };
+        if !reject.is_zero() {
+            self.emit(
+                DiagBuilder2::warning(format!(
+                    "inertial delay of `{}` is treated as transport delay",
+                    hir.kind_span.extract()
+                ))
+                .span(hir.kind_span)
+                .add_note("Pulses shorter than the pulse rejection limit are not rejected."),
+            );
+        }
+
+        let ty = builder.value_type(sig).unwrap_signal().clone();
+        for (elem, delay) in wave.iter().zip(delays.iter()) {
+            let value = match elem.value {
+                Some(value) => self.codegen_assigned_value(value, target, &ty, builder)?,
+                None => continue,
+            };
+            let delay = self.codegen_time_value(delay, builder);
+            match pc.drive_enable {
+                Some(enable) => builder.ins().drv_cond(sig, value, delay, enable),
+                None => builder.ins().drv(sig, value, delay),
+            };
+        }
+        Ok(())
+    }
+
+    /// Generate code for a value assigned to a signal or variable of a given
+    /// type.
+    ///
+    /// Integers are resized to the width of the target, since the result of
+    /// an operator has the width of the base type rather than the subtype. If
+    /// range checks are enabled, the value is checked against the range of
+    /// the target subtype before.
+    fn codegen_assigned_value(
+        &self,
+        id: ExprRef,
+        target: &Ty,
+        ty: &llhd::Type,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<llhd::ir::Value> {
+        let value = self.codegen_expr(id, builder)?;
+        if self.sess.opts.range_checks {
+            self.codegen_range_check(id, value, target, builder)?;
+        }
+        let value_ty = builder.value_type(value);
+        if !value_ty.is_int() || !ty.is_int() || value_ty == *ty {
+            return Ok(value);
+        }
+        let signed = match *self.deref_named_type(self.lazy_typeval(id)?)? {
+            Ty::Int(ref int_ty) => is_signed_int(int_ty),
+            _ => false,
+        };
+        let (from, to) = (value_ty.unwrap_int(), ty.unwrap_int());
+        Ok(self.codegen_resize_int(value, from, to, signed, builder))
+    }
+
+    /// Generate code that reports an error if a value lies outside the range
+    /// of a scalar subtype.
+    ///
+    /// Constant values and values whose type lies within the subtype are not
+    /// checked. See IEEE 1076-2008 sections 10.5.2.1 and 10.6.2.1.
+    fn codegen_range_check(
+        &self,
+        id: ExprRef,
+        value: llhd::ir::Value,
+        target: &Ty,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let span = self.span(id).unwrap();
+        let (lo, hi) = match self.scalar_range(target, span)? {
+            Some(range) => range,
+            None => return Ok(()),
+        };
+        if !self.is_dynamic_expr(id)? {
+            return Ok(());
+        }
+        let ty = self.lazy_typeval(id)?;
+        if let Some((value_lo, value_hi)) = self.scalar_range(ty, span)? {
+            if value_lo >= lo && value_hi <= hi {
+                return Ok(());
+            }
+        }
+        let signed = match *self.deref_named_type(ty)? {
+            Ty::Int(ref int_ty) => is_signed_int(int_ty),
+            Ty::Physical(ref phys_ty) => is_signed_int(&phys_ty.base),
+            _ => false,
+        };
+
+        let text = format!(
+            "Value of `{}` is outside of the range {} to {}.",
+            span.extract(),
+            lo,
+            hi
+        );
+
+        // Compare as signed numbers, with an additional bit such that neither
+        // an unsigned value nor the bounds change sign.
+        let from = builder.value_type(value).unwrap_int();
+        let width = std::cmp::max(from, int_width(&lo, &hi)) + 1;
+        let value = self.codegen_resize_int(value, from, width, signed, builder);
+        let lo = builder.ins().const_int((width, lo));
+        let hi = builder.ins().const_int((width, hi));
+        let above = builder.ins().sge(value, lo);
+        let below = builder.ins().sle(value, hi);
+        let holds = builder.ins().and(above, below);
+
+        let done_bb = builder.block();
+        let fail_bb = builder.block();
+        builder.ins().br_cond(holds, fail_bb, done_bb);
+        builder.append_to(fail_bb);
+        self.codegen_print_text(&text, builder);
+        let level = ConstEnum::new(SEVERITY_LEVEL_TYPE.id, SEVERITY_FAILURE);
+        let severity = self.map_const(builder, &level.into())?;
+        self.codegen_intrinsic("moore.report", vec![severity], builder);
+        builder.ins().br(done_bb);
+        builder.append_to(done_bb);
+        Ok(())
+    }
+
+    /// Determine the lowest and highest position number of a scalar type.
+    ///
+    /// Returns `None` for null ranges and types that are neither discrete nor
+    /// physical.
+    fn scalar_range(&self, ty: &Ty, span: Span) -> Result<Option<(BigInt, BigInt)>> {
+        match *self.deref_named_type(ty)? {
+            Ty::Int(_) | Ty::Enum(_) | Ty::Physical(_) => (),
+            _ => return Ok(None),
+        }
+        let (lo, hi) = match self.scalar_bounds(ty, span)? {
+            (hir::Dir::To, l, r) => (l, r),
+            (hir::Dir::Downto, l, r) => (r, l),
+        };
+        Ok(if lo > hi { None } else { Some((lo, hi)) })
+    }
+
+    /// Generate code for a variable assignment.
+    fn codegen_var_assign_stmt(
+        &self,
+        id: VarAssignStmtRef,
+        builder: &mut llhd::ir::UnitBuilder,
+    ) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        let target = hir.stmt.target.span;
+        let var = match hir.stmt.target.value {
+            hir::Target::Name(expr) => match self.lazy_hir(expr)?.data {
+                hir::ExprData::VarName(var) => {
+                    self.sb.llvar_table.borrow().get(&var.into()).cloned()
+                }
+                _ => None,
+            },
+            hir::Target::Aggregate(_) => None,
+        };
+        let var = match var {
+            Some(var) => var,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot generate code for assignment to `{}`",
+                        target.extract()
+                    ))
+                    .span(target),
+                );
+                return Err(());
+            }
+        };
+        let ty = builder.value_type(var).unwrap_pointer().clone();
+        let target = match hir.stmt.target.value {
+            hir::Target::Name(expr) => self.lazy_typeval(expr)?,
+            hir::Target::Aggregate(_) => unreachable!(),
+        };
+        match hir.stmt.kind {
+            hir::VarAssignKind::Simple(expr) => {
+                let value = self.codegen_assigned_value(expr, target, &ty, builder)?;
+                builder.ins().st(var, value);
+            }
+            hir::VarAssignKind::Cond(ref cond) => {
+                let done_bb = builder.block();
+                for &(expr, when) in &cond.when {
+                    let holds = self.codegen_condition(when, builder)?;
+                    let then_bb = builder.block();
+                    let else_bb = builder.block();
+                    builder.ins().br_cond(holds, else_bb, then_bb);
+                    builder.append_to(then_bb);
+                    let value = self.codegen_assigned_value(expr, target, &ty, builder)?;
+                    builder.ins().st(var, value);
+                    builder.ins().br(done_bb);
+                    builder.append_to(else_bb);
+                }
+                if let Some(expr) = cond.other {
+                    let value = self.codegen_assigned_value(expr, target, &ty, builder)?;
+                    builder.ins().st(var, value);
+                }
+                builder.ins().br(done_bb);
+                builder.append_to(done_bb);
+            }
+            hir::VarAssignKind::Sel(_) => {
+                self.emit(
+                    DiagBuilder2::error("cannot generate code for selected variable assignment")
+                        .span(hir.span),
+                );
+                return Err(());
+            }
+        }
+        Ok(())
     }
 }
 
@@ -177,6 +2670,7 @@ impl_codegen!(self, id: DeclInBlockRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
         DeclInBlockRef::PkgBody(id)     => self.codegen(id, &mut ()),
         DeclInBlockRef::PkgInst(id)     => self.codegen(id, &mut ()),
         DeclInBlockRef::Type(_id)       => Ok(()),
+        DeclInBlockRef::ProtectedBody(_id) => Ok(()),
         DeclInBlockRef::Subtype(_id)    => Ok(()),
         DeclInBlockRef::Const(id)       => self.codegen(id, ctx),
         DeclInBlockRef::Signal(id)      => self.codegen(id, ctx),
@@ -193,12 +2687,56 @@ impl_codegen!(self, id: DeclInBlockRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
     }
 });
 
-impl_codegen!(self, id: ConstDeclRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+impl_codegen!(self, id: DeclInProcRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    match id {
+        DeclInProcRef::Subprog(id)     => self.codegen(id, &mut ()),
+        DeclInProcRef::SubprogBody(id) => self.codegen(id, &mut ()),
+        DeclInProcRef::SubprogInst(id) => self.codegen(id, &mut ()),
+        DeclInProcRef::Pkg(id)         => self.codegen(id, &mut ()),
+        DeclInProcRef::PkgBody(id)     => self.codegen(id, &mut ()),
+        DeclInProcRef::PkgInst(id)     => self.codegen(id, &mut ()),
+        DeclInProcRef::Type(_id)       => Ok(()),
+        DeclInProcRef::ProtectedBody(_id) => Ok(()),
+        DeclInProcRef::Subtype(_id)    => Ok(()),
+        DeclInProcRef::Const(id)       => self.codegen(id, ctx),
+        DeclInProcRef::Var(id)         => self.codegen(id, ctx),
+        DeclInProcRef::File(id)        => self.codegen(id, ctx),
+        DeclInProcRef::Alias(_id)      => Ok(()),
+        DeclInProcRef::Attr(_id)       => Ok(()),
+        DeclInProcRef::AttrSpec(_id)   => Ok(()),
+        DeclInProcRef::GroupTemp(_id)  => Ok(()),
+        DeclInProcRef::Group(_id)      => Ok(()),
+    }
 });
 
-impl_codegen!(self, id: VarDeclRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+impl_codegen!(self, _id: ConstDeclRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    // Constants are folded into the expressions that use them.
+    Ok(())
+});
+
+impl_codegen!(self, id: VarDeclRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    // Shared variables are not supported, since they would have to be
+    // accessible from multiple processes.
+    if !ctx.is_process() {
+        unimp!(self, id);
+    }
+    // Variables of type `LINE` are not materialized, since the line buffer of
+    // the simulator stands in for them.
+    if let Ty::Named(_, TypeMarkRef::Type(ty)) = *self.lazy_typeval(id)? {
+        if ty == LINE_TYPE.id {
+            return Ok(());
+        }
+    }
+    let hir = self.lazy_hir(id)?;
+    let init = match hir.decl.init {
+        Some(init) => self.const_value(init)?,
+        None => self.default_value_for_type(self.lazy_typeval(id)?)?,
+    };
+    let init = self.map_const(ctx, init)?;
+    let var = ctx.ins().var(init);
+    ctx.set_name(var, hir.name.value.to_string());
+    self.sb.llvar_table.borrow_mut().insert(id.into(), var);
+    Ok(())
 });
 
 impl_codegen!(self, id: SignalDeclRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
@@ -222,7 +2760,8 @@ impl_codegen!(self, id: SignalDeclRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
     // );
     // ctx.add_inst(inst, llhd::InstPosition::End);
     let k = self.map_const(ctx, init)?;
-    ctx.ins().sig(k);
+    let sig = ctx.ins().sig(k);
+    self.sb.llsig_table.borrow_mut().insert(id.into(), sig);
     Ok(())
 });
 
@@ -241,6 +2780,7 @@ impl_codegen!(self, id: ConcStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
         ConcStmtRef::ForGen(id)        => self.codegen(id, ctx),
         ConcStmtRef::IfGen(id)         => self.codegen(id, ctx),
         ConcStmtRef::CaseGen(id)       => self.codegen(id, ctx),
+        ConcStmtRef::Psl(id)           => self.codegen(id, ctx),
     }
 });
 
@@ -249,75 +2789,149 @@ impl_codegen!(self, id: BlockStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
 });
 
 impl_codegen!(self, id: ProcessStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    let hir = self.hir(id)?;
-    let name = match hir.label {
-        Some(n) => format!("{}_{}", ctx.name(), n.value),
-        None => format!("{}_proc", ctx.name()),
-    };
-    let name = llhd::ir::UnitName::Global(name);
-    debugln!("generating process `{}`", name);
-    // TODO: Check which signals are actually read and written.
-    // let ty = llhd::entity_ty(vec![], vec![]);
-    let sig = llhd::ir::Signature::new();
-    let mut prok = llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name.clone(), sig.clone());
-    // let mut prok = llhd::Process::new(name, ty.clone());
-    let mut prok_builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
-    // TODO: define the process as a local name
-    // TOOD: codegen declarations
-    // TOOD: codegen statements
-    let entry_bb = prok_builder.named_block("entry");
-    prok_builder.append_to(entry_bb);
-    for &stmt in &hir.stmts {
-        self.codegen(stmt, &mut prok_builder)?;
-    }
-    // TODO: codegen wait statements implied by sensitivity list
-
-    // TODO: wire instantiation with signals in the process' port.
-    let ext_unit = ctx.add_extern(
-        prok_builder.name().clone(),
-        prok_builder.sig().clone(),
-    );
-    ctx.ins().inst(ext_unit, vec![], vec![]);
-    self.sb.llmod.borrow_mut().add_unit(prok);
-    // ctx.add_inst(
-    //     llhd::Inst::new(hir.label.map(|l| l.value.into()), llhd::InstKind::InstanceInst(
-    //         ty, prok_ref.into(), vec![], vec![]
-    //     )),
-    //     llhd::InstPosition::End
-    // );
-    Ok(())
+    self.codegen_process(self.hir(id)?, ctx)
 });
 
 impl_codegen!(self, id: ConcCallStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
     unimp!(self, id);
 });
 
-impl_codegen!(self, id: ConcAssertStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+// A concurrent assertion whose condition reads no signal only needs to be
+// checked once, which is done during elaboration.
+impl_codegen!(self, id: ConcAssertStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    let hir = self.hir(id)?;
+    if let [SeqStmtRef::Assert(assert)] = *hir.stmts.as_slice() {
+        let assert = self.lazy_hir(assert)?;
+        if !self.is_dynamic_expr(assert.stmt.cond)? {
+            return self.check_assertion(
+                Some(assert.stmt.cond),
+                assert.stmt.report,
+                assert.stmt.severity,
+                assert.span,
+            );
+        }
+    }
+    self.codegen_process(hir, ctx)
 });
 
-impl_codegen!(self, id: ConcSigAssignStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+impl_codegen!(self, id: PslDirectiveRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    self.codegen_psl_directive(self.hir(id)?, ctx)
 });
 
-impl_codegen!(self, id: CompInstStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
+impl_codegen!(self, id: ConcSigAssignStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
     unimp!(self, id);
 });
 
-impl_codegen!(self, id: ForGenStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+impl_codegen!(self, id: CompInstStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    let InstBinding { entity, arch, env, port_map } = self.inst_binding(id)?;
+
+    // The signals exported through this instance continue along the rest of
+    // their path within the instantiated architecture.
+    let label = self.hir(id)?.label.map(|l| l.value);
+    let (exports, export_values): (Vec<_>, Vec<_>) = self
+        .sb
+        .llexport_table
+        .borrow()
+        .iter()
+        .filter(|(export, _)| export.path.first().cloned() == label)
+        .map(|&(ref export, value)| {
+            let export = ExportedSignal { path: export.path[1..].to_vec(), ..export.clone() };
+            (export, value)
+        })
+        .unzip();
+
+    // Generate the architecture in the generic environment of the instance.
+    // Stand-ins for design units declared in other languages refer to the
+    // unit by name instead.
+    let (name, sig) = match self.foreign_entity(entity) {
+        Some(unit) => (
+            llhd::ir::UnitName::global(unit.name.to_string()),
+            self.with_generic_env(env, || self.foreign_sig(entity))?,
+        ),
+        None => {
+            let unit = self.with_generic_env(env, || {
+                self.with_exported_signals(exports, || self.llunit(arch))
+            })?;
+            let llmod = self.sb.llmod.borrow();
+            let unit = llmod.unit(unit);
+            (unit.name().clone(), unit.sig().clone())
+        }
+    };
+
+    // Connect the ports to the signals in the generic map. Ports that are left
+    // open are connected to a fresh signal carrying their default value.
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    for &port in &self.hir(entity)?.ports {
+        let port_hir = self.hir(port)?;
+        let value = match port_map.iter().find(|&&(formal, _)| formal == port) {
+            Some(&(_, actual)) => match self.lazy_hir(actual)?.data {
+                hir::ExprData::SignalName(sig) => {
+                    self.sb.llsig_table.borrow()[&sig.into()]
+                }
+                // Caught by the type check.
+                _ => unreachable!("port actual is not a signal"),
+            },
+            None => {
+                let init = self.with_generic_env(env, || -> Result<_> {
+                    match port_hir.init {
+                        Some(init) => self.const_value(init),
+                        None => self.default_value_for_type(self.ty(port)?),
+                    }
+                })?;
+                let k = self.map_const(ctx, init)?;
+                ctx.ins().sig(k)
+            }
+        };
+        match port_hir.mode {
+            hir::IntfSignalMode::In
+            | hir::IntfSignalMode::Inout
+            | hir::IntfSignalMode::Linkage => inputs.push(value),
+            _ => (),
+        }
+        match port_hir.mode {
+            hir::IntfSignalMode::Out
+            | hir::IntfSignalMode::Inout
+            | hir::IntfSignalMode::Buffer => outputs.push(value),
+            _ => (),
+        }
+    }
+    outputs.extend(export_values);
+    let ext_unit = ctx.add_extern(name, sig);
+    ctx.ins().inst(ext_unit, inputs, outputs);
+    Ok(())
 });
 
-impl_codegen!(self, id: IfGenStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+// Every value of the parameter yields a separate copy of the generated
+// statements.
+impl_codegen!(self, id: ForGenStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    let hir = self.hir(id)?;
+    for value in self.gen_param_values(id)? {
+        let index = self.image(value, hir.span)?;
+        let name = self.gen_unit_name(ctx, hir.label, Some(index));
+        self.with_local_value(id.into(), value, || {
+            self.with_gen_block_cfg(hir.label, || self.codegen_gen_body(hir.body, name, ctx))
+        })?;
+    }
+    Ok(())
 });
 
-impl_codegen!(self, id: CaseGenStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
+impl_codegen!(self, id: IfGenStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    let hir = self.hir(id)?;
+    if let Some(body) = self.if_gen_body(id)? {
+        let name = self.gen_unit_name(ctx, hir.label, None);
+        self.with_gen_block_cfg(hir.label, || self.codegen_gen_body(body, name, ctx))?;
+    }
+    Ok(())
 });
 
-impl_codegen!(self, id: SeqStmtRef, _ctx: &'a mut llhd::ir::UnitBuilder<'a> => {
-    unimp!(self, id);
+impl_codegen!(self, id: CaseGenStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
+    let hir = self.hir(id)?;
+    if let Some(body) = self.case_gen_body(id)? {
+        let name = self.gen_unit_name(ctx, hir.label, None);
+        self.with_gen_block_cfg(hir.label, || self.codegen_gen_body(body, name, ctx))?;
+    }
+    Ok(())
 });
 
 impl_codegen!(self, id: SubprogDeclRef, _ctx: &mut () => {
@@ -344,10 +2958,62 @@ impl_codegen!(self, id: PkgInstRef, _ctx: &mut () => {
     unimp!(self, id);
 });
 
-impl_codegen!(self, id: CompDeclRef, _ctx: &mut () => {
-    unimp!(self, id);
+// Components are replaced by the entities they are bound to.
+impl_codegen!(self, _id: CompDeclRef, _ctx: &mut () => {
+    Ok(())
 });
 
+/// The number of bits of a `STD_ULOGIC` value, which is one of nine literals.
+const STD_ULOGIC_WIDTH: usize = 4;
+
+/// Check whether an integer type has negative values.
+fn is_signed_int(ty: &IntTy) -> bool {
+    ty.left_bound.is_negative() || ty.right_bound.is_negative()
+}
+
+/// Determine the lowest and highest value of an integer type, or `None` if
+/// its range is null.
+fn int_bounds(ty: &IntTy) -> Option<(&BigInt, &BigInt)> {
+    let (lo, hi) = match ty.dir {
+        hir::Dir::To => (&ty.left_bound, &ty.right_bound),
+        hir::Dir::Downto => (&ty.right_bound, &ty.left_bound),
+    };
+    if lo > hi {
+        None
+    } else {
+        Some((lo, hi))
+    }
+}
+
+/// Determine the number of bits needed to represent all integers between two
+/// bounds.
+///
+/// The integers are signed if the lower bound is negative, and unsigned
+/// otherwise.
+fn int_width(lo: &BigInt, hi: &BigInt) -> usize {
+    let bits = |v: &BigInt| -> usize {
+        if v.is_negative() {
+            (-v - 1u32).bits() as usize + 1
+        } else {
+            v.bits() as usize + lo.is_negative() as usize
+        }
+    };
+    std::cmp::max(bits(lo), bits(hi))
+}
+
+/// Iterate over the arguments of a subprogram call.
+///
+/// Type checking has ensured that all arguments are positional.
+fn call_args(params: &Spanned<hir::AssocList>) -> impl Iterator<Item = ExprRef> + '_ {
+    params
+        .value
+        .iter()
+        .filter_map(|arg| match arg.actual.value {
+            hir::AssocActual::Expr(id) => Some(id),
+            _ => None,
+        })
+}
+
 // /// An helper to build sequences of instructions.
 // pub struct InstBuilder<'ctx> {
 //     pub body: &'ctx mut llhd::SeqBody,
@@ -380,3 +3046,88 @@ impl_codegen!(self, id: CompDeclRef, _ctx: &mut () => {
 //         self.block = block
 //     }
 // }
+
+/// Render a PSL property as text, in which `$n` stands for the nth condition.
+///
+/// The conditions are appended to `conds` in the order they appear. Operands
+/// are wrapped in parentheses or, for sequence operators, braces, such that
+/// the text does not depend on the precedence of the operators.
+fn psl_text(prop: &hir::PslProp, conds: &mut Vec<ExprRef>) -> String {
+    let cond = |id, conds: &mut Vec<ExprRef>| {
+        conds.push(id);
+        format!("${}", conds.len() - 1)
+    };
+    match *prop {
+        hir::PslProp::Bool(id) => cond(id, conds),
+        hir::PslProp::Braced(ref arg) => format!("{{{}}}", psl_text(arg, conds)),
+        hir::PslProp::Unary(op, ref arg) => {
+            let op = match op {
+                hir::PslUnaryOp::Always => "always",
+                hir::PslUnaryOp::Never => "never",
+                hir::PslUnaryOp::Eventually => "eventually!",
+            };
+            format!("{} ({})", op, psl_text(arg, conds))
+        }
+        hir::PslProp::Next {
+            strong,
+            count,
+            ref arg,
+        } => format!(
+            "next{}{} ({})",
+            if strong { "!" } else { "" },
+            count.map(|n| format!("[{}]", n)).unwrap_or_default(),
+            psl_text(arg, conds)
+        ),
+        hir::PslProp::Binary(op, ref lhs, ref rhs) => {
+            let (op, seq) = match op {
+                hir::PslBinaryOp::Implication => ("->", false),
+                hir::PslBinaryOp::Equivalence => ("<->", false),
+                hir::PslBinaryOp::Until { strong, inclusive } => {
+                    let op = match (strong, inclusive) {
+                        (false, false) => "until",
+                        (true, false) => "until!",
+                        (false, true) => "until_",
+                        (true, true) => "until!_",
+                    };
+                    (op, false)
+                }
+                hir::PslBinaryOp::OverlapImpl => ("|->", false),
+                hir::PslBinaryOp::NonOverlapImpl => ("|=>", false),
+                hir::PslBinaryOp::Concat => (";", true),
+                hir::PslBinaryOp::Fusion => (":", true),
+                hir::PslBinaryOp::Or => ("|", true),
+                hir::PslBinaryOp::And => ("&&", true),
+            };
+            let lhs = psl_text(lhs, conds);
+            let rhs = psl_text(rhs, conds);
+            if seq {
+                format!("{{{}}} {} {{{}}}", lhs, op, rhs)
+            } else {
+                format!("({}) {} ({})", lhs, op, rhs)
+            }
+        }
+        hir::PslProp::Repeat(ref arg, kind, count) => {
+            let op = match kind {
+                hir::PslRepeatKind::Consecutive => "*",
+                hir::PslRepeatKind::Plus => "+",
+                hir::PslRepeatKind::NonConsecutive => "=",
+                hir::PslRepeatKind::Goto => "->",
+            };
+            let count = match count {
+                Some((low, Some(high))) if low == high => format!("{}", low),
+                Some((low, Some(high))) => format!("{} to {}", low, high),
+                Some((low, None)) => format!("{} to inf", low),
+                None => String::new(),
+            };
+            format!("{{{}}}[{}{}]", psl_text(arg, conds), op, count)
+        }
+        hir::PslProp::Abort(ref arg, id) => {
+            let arg = psl_text(arg, conds);
+            format!("({}) abort {}", arg, cond(id, conds))
+        }
+        hir::PslProp::Clocked(ref arg, id) => {
+            let arg = psl_text(arg, conds);
+            format!("({}) @ {}", arg, cond(id, conds))
+        }
+    }
+}
@@ -6,8 +6,10 @@ use crate::hir;
 use crate::score::*;
 use crate::syntax::ast;
 use moore_common::errors::*;
-use moore_common::score::Result;
+use moore_common::score::{NodeRef, Result};
 use moore_common::source::*;
+use moore_common::util::HasSpan;
+use moore_common::NodeId;
 #[deny(missing_docs)]
 use std::collections::HashMap;
 
@@ -64,7 +66,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }
         match def {
             // Handle overloadable cases.
-            Def::Enum(_) => {
+            Def::Enum(_) | Def::Subprog(_) => {
                 self.defs
                     .entry(name.value)
                     .or_insert_with(|| Vec::new())
@@ -108,46 +110,6 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         self.declare(self.ctx.ast(id).1.name.map_into(), Def::PkgInst(id))
     }
 
-    /// Handle type declarations.
-    pub fn declare_type(&mut self, id: TypeDeclRef) {
-        let ast = self.ctx.ast(id).1;
-        self.declare(ast.name.map_into(), Def::Type(id));
-        // This is a rather hacky way of declaring the variant names for enum
-        // literals, but it does not require the HIR to be constructed, which is
-        // a requirement to avoid infinite loops.
-        match ast.data {
-            Some(Spanned {
-                value: ast::EnumType(ref paren_elems),
-                ..
-            }) => {
-                for (i, lit) in paren_elems.value.iter().enumerate() {
-                    match lit.expr.data {
-                        ast::NameExpr(ref name) => self.declare(
-                            match self.ctx.resolvable_from_primary_name(&name.primary) {
-                                Ok(n) => n,
-                                Err(()) => continue,
-                            },
-                            Def::Enum(EnumRef(id, i)),
-                        ),
-                        _ => (),
-                    }
-                }
-            }
-            Some(Spanned {
-                value: ast::RangeType(_, Some(ref units)),
-                ..
-            }) => {
-                for (i, unit) in units.iter().enumerate() {
-                    self.declare(
-                        Spanned::new(unit.0.name.into(), unit.0.span),
-                        Def::Unit(UnitRef(id, i)),
-                    );
-                }
-            }
-            _ => (),
-        }
-    }
-
     /// Handle subtype declarations.
     pub fn declare_subtype(&mut self, id: SubtypeDeclRef) {
         self.declare(self.ctx.ast(id).1.name.map_into(), Def::Subtype(id))
@@ -158,13 +120,15 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         match id {
             DeclInBlockRef::Subprog(id) => self.declare_subprog(id),
             DeclInBlockRef::SubprogInst(id) => self.declare_subprog_inst(id),
-            DeclInBlockRef::SubprogBody(_id) => (),
+            DeclInBlockRef::SubprogBody(id) => self.declare_subprog_body(id),
             DeclInBlockRef::Pkg(id) => self.declare_pkg(id),
             DeclInBlockRef::PkgInst(id) => self.declare_pkg_inst(id),
             DeclInBlockRef::PkgBody(_id) => (),
-            DeclInBlockRef::Type(id) => self.declare_type(id),
+            // Types and constants are defined as they are added.
+            DeclInBlockRef::Type(_id) => (),
+            DeclInBlockRef::ProtectedBody(_id) => (),
             DeclInBlockRef::Subtype(id) => self.declare_subtype(id),
-            DeclInBlockRef::Const(id) => self.declare_const(id),
+            DeclInBlockRef::Const(_id) => (),
             DeclInBlockRef::Signal(id) => self.declare_signal(id),
             DeclInBlockRef::Var(id) => self.declare_var(id),
             DeclInBlockRef::File(id) => self.declare_file(id),
@@ -186,9 +150,10 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             DeclInPkgRef::SubprogInst(id) => self.declare_subprog_inst(id),
             DeclInPkgRef::Pkg(id) => self.declare_pkg(id),
             DeclInPkgRef::PkgInst(id) => self.declare_pkg_inst(id),
-            DeclInPkgRef::Type(id) => self.declare_type(id),
+            // Types and constants are defined as they are added.
+            DeclInPkgRef::Type(_id) => (),
             DeclInPkgRef::Subtype(id) => self.declare_subtype(id),
-            DeclInPkgRef::Const(id) => self.declare_const(id),
+            DeclInPkgRef::Const(_id) => (),
             DeclInPkgRef::Signal(id) => self.declare_signal(id),
             DeclInPkgRef::Var(id) => self.declare_var(id),
             DeclInPkgRef::File(id) => self.declare_file(id),
@@ -206,14 +171,16 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     pub fn declare_any_in_pkg_body(&mut self, id: DeclInPkgBodyRef) {
         match id {
             DeclInPkgBodyRef::Subprog(id) => self.declare_subprog(id),
-            DeclInPkgBodyRef::SubprogBody(_id) => (),
+            DeclInPkgBodyRef::SubprogBody(id) => self.declare_subprog_body(id),
             DeclInPkgBodyRef::SubprogInst(id) => self.declare_subprog_inst(id),
             DeclInPkgBodyRef::Pkg(id) => self.declare_pkg(id),
             DeclInPkgBodyRef::PkgBody(_id) => (),
             DeclInPkgBodyRef::PkgInst(id) => self.declare_pkg_inst(id),
-            DeclInPkgBodyRef::Type(id) => self.declare_type(id),
+            // Types and constants are defined as they are added.
+            DeclInPkgBodyRef::Type(_id) => (),
+            DeclInPkgBodyRef::ProtectedBody(_id) => (),
             DeclInPkgBodyRef::Subtype(id) => self.declare_subtype(id),
-            DeclInPkgBodyRef::Const(id) => self.declare_const(id),
+            DeclInPkgBodyRef::Const(_id) => (),
             DeclInPkgBodyRef::Var(id) => self.declare_var(id),
             DeclInPkgBodyRef::File(id) => self.declare_file(id),
             DeclInPkgBodyRef::Alias(id) => self.declare_alias(id),
@@ -228,14 +195,16 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     pub fn declare_any_in_subprog(&mut self, id: DeclInSubprogRef) {
         match id {
             DeclInSubprogRef::Subprog(id) => self.declare_subprog(id),
-            DeclInSubprogRef::SubprogBody(_id) => (),
+            DeclInSubprogRef::SubprogBody(id) => self.declare_subprog_body(id),
             DeclInSubprogRef::SubprogInst(id) => self.declare_subprog_inst(id),
             DeclInSubprogRef::Pkg(id) => self.declare_pkg(id),
             DeclInSubprogRef::PkgBody(_id) => (),
             DeclInSubprogRef::PkgInst(id) => self.declare_pkg_inst(id),
-            DeclInSubprogRef::Type(id) => self.declare_type(id),
+            // Types and constants are defined as they are added.
+            DeclInSubprogRef::Type(_id) => (),
+            DeclInSubprogRef::ProtectedBody(_id) => (),
             DeclInSubprogRef::Subtype(id) => self.declare_subtype(id),
-            DeclInSubprogRef::Const(id) => self.declare_const(id),
+            DeclInSubprogRef::Const(_id) => (),
             DeclInSubprogRef::Var(id) => self.declare_var(id),
             DeclInSubprogRef::File(id) => self.declare_file(id),
             DeclInSubprogRef::Alias(id) => self.declare_alias(id),
@@ -251,13 +220,15 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         match id {
             DeclInProcRef::Subprog(id) => self.declare_subprog(id),
             DeclInProcRef::SubprogInst(id) => self.declare_subprog_inst(id),
-            DeclInProcRef::SubprogBody(_id) => (),
+            DeclInProcRef::SubprogBody(id) => self.declare_subprog_body(id),
             DeclInProcRef::Pkg(id) => self.declare_pkg(id),
             DeclInProcRef::PkgInst(id) => self.declare_pkg_inst(id),
             DeclInProcRef::PkgBody(_id) => (),
-            DeclInProcRef::Type(id) => self.declare_type(id),
+            // Types and constants are defined as they are added.
+            DeclInProcRef::Type(_id) => (),
+            DeclInProcRef::ProtectedBody(_id) => (),
             DeclInProcRef::Subtype(id) => self.declare_subtype(id),
-            DeclInProcRef::Const(id) => self.declare_const(id),
+            DeclInProcRef::Const(_id) => (),
             DeclInProcRef::Var(id) => self.declare_var(id),
             DeclInProcRef::File(id) => self.declare_file(id),
             DeclInProcRef::Alias(id) => self.declare_alias(id),
@@ -268,9 +239,15 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
-    /// Handle a constant declaration.
-    pub fn declare_const(&mut self, id: ConstDeclRef) {
-        let hir = match self.ctx.lazy_hir(id) {
+    /// Handle a generic declaration.
+    ///
+    /// Only constant generics can be named at the moment.
+    pub fn declare_generic(&mut self, id: GenericRef) {
+        let id = match id {
+            GenericRef::Const(id) => id,
+            _ => return,
+        };
+        let hir = match self.ctx.hir(id) {
             Ok(h) => h,
             Err(()) => {
                 self.failed = true;
@@ -280,6 +257,18 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         self.declare(hir.name.map_into(), Def::Const(id.into()))
     }
 
+    /// Handle a port declaration.
+    pub fn declare_port(&mut self, id: IntfSignalRef) {
+        let hir = match self.ctx.hir(id) {
+            Ok(h) => h,
+            Err(()) => {
+                self.failed = true;
+                return;
+            }
+        };
+        self.declare(hir.name.map_into(), Def::Signal(id.into()))
+    }
+
     /// Handle a signal declaration.
     pub fn declare_signal(&mut self, id: SignalDeclRef) {
         let hir = match self.ctx.lazy_hir(id) {
@@ -346,6 +335,73 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         self.declare_primary_name(&self.ctx.ast(id).1.spec.name, Def::Subprog(id))
     }
 
+    /// Handle subprogram bodies.
+    ///
+    /// A body completes the conforming declaration of the subprogram made
+    /// earlier in the same declarative region or, for bodies in a package body,
+    /// in the package. Otherwise the body also declares the subprogram. See
+    /// IEEE 1076-2008 sections 4.3 and 4.10.
+    pub fn declare_subprog_body(&mut self, id: SubprogBodyRef) {
+        let (scope_id, ast) = self.ctx.ast(id);
+        let name = match self.ctx.resolvable_from_primary_name(&ast.spec.name) {
+            Ok(n) => n,
+            Err(()) => {
+                self.failed = true;
+                return;
+            }
+        };
+        let mut decls: Vec<SubprogDeclRef> = self
+            .defs
+            .get(&name.value)
+            .into_iter()
+            .flatten()
+            .filter_map(|def| match def.value {
+                Def::Subprog(id) => Some(id),
+                _ => None,
+            })
+            .collect();
+        if let ScopeRef::PkgBody(body) = scope_id {
+            match self.pkg_subprogs(body) {
+                Ok(pkg_decls) => decls.extend(pkg_decls),
+                Err(()) => {
+                    self.failed = true;
+                    return;
+                }
+            }
+        }
+        let completed = decls
+            .into_iter()
+            .find(|&decl| specs_conform(&self.ctx.ast(decl).1.spec, &ast.spec));
+        let decl = match completed {
+            Some(decl) => decl,
+            None => {
+                let decl = <SubprogDeclRef as NodeRef>::new(NodeId::alloc());
+                self.ctx.set_ast(decl, (scope_id, ast));
+                self.declare(name, Def::Subprog(decl));
+                decl
+            }
+        };
+        self.ctx.sb.subprog_body_table.borrow_mut().insert(decl, id);
+    }
+
+    /// Determine the subprograms declared in the package of a package body.
+    fn pkg_subprogs(&self, body: PkgBodyRef) -> Result<Vec<SubprogDeclRef>> {
+        let pkg = match self.ctx.hir(self.ctx.hir(body)?.pkg.value)?.value {
+            PkgRef::Decl(pkg) => pkg,
+            PkgRef::Inst(_) => return Ok(vec![]),
+        };
+        Ok(self
+            .ctx
+            .hir(pkg)?
+            .decls
+            .iter()
+            .filter_map(|&decl| match decl {
+                DeclInPkgRef::Subprog(id) => Some(id),
+                _ => None,
+            })
+            .collect())
+    }
+
     /// Handle subprogram instantiations.
     pub fn declare_subprog_inst(&mut self, id: SubprogInstRef) {
         self.declare_primary_name(&self.ctx.ast(id).1.spec.name, Def::SubprogInst(id))
@@ -364,15 +420,70 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     ///
     /// These are mainly subprogram parameters and entity ports.
     pub fn declare_intf_objs(&mut self, ids: &[IntfObjRef]) {
-        if !ids.is_empty() {
-            unimplemented!();
+        for &id in ids {
+            self.declare_intf_obj(id);
         }
     }
 
+    /// Handle an interface object declaration.
+    ///
+    /// Only constants can be named at the moment.
+    pub fn declare_intf_obj(&mut self, id: IntfObjRef) {
+        let id = match id {
+            IntfObjRef::Const(id) => id,
+            _ => return,
+        };
+        let hir = match self.ctx.hir(id) {
+            Ok(h) => h,
+            Err(()) => {
+                self.failed = true;
+                return;
+            }
+        };
+        self.declare(hir.name.map_into(), Def::Const(id.into()))
+    }
+
     /// Handle generics.
     pub fn declare_generics(&mut self, ids: &[GenericRef]) {
-        if !ids.is_empty() {
-            unimplemented!();
+        for &id in ids {
+            self.declare_generic(id);
         }
     }
 }
+
+/// Check whether two subprogram specifications conform.
+///
+/// The kinds, parameter lists, and return types of the specifications are
+/// compared as sequences of lexical elements, ignoring whitespace and case.
+/// See IEEE 1076-2008 section 4.10.
+fn specs_conform(a: &ast::SubprogSpec, b: &ast::SubprogSpec) -> bool {
+    a.kind == b.kind
+        && spec_params(a) == spec_params(b)
+        && a.retty.as_ref().map(|t| lexical(t.span)) == b.retty.as_ref().map(|t| lexical(t.span))
+}
+
+/// Flatten the parameter list of a subprogram specification into its
+/// parameter names and subtype indications.
+fn spec_params(spec: &ast::SubprogSpec) -> Vec<String> {
+    spec.params
+        .iter()
+        .flatten()
+        .flat_map(|decl| match *decl {
+            ast::IntfDecl::ObjDecl(ref decl) => decl
+                .names
+                .iter()
+                .map(|name| format!("{}:{}", name.name, lexical(decl.ty.span)))
+                .collect(),
+            ref other => vec![lexical(other.span())],
+        })
+        .collect()
+}
+
+/// Normalize the source text of a span for lexical comparison.
+fn lexical(span: Span) -> String {
+    span.extract()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,271 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! External names.
+//!
+//! An external name such as `<< signal .top.dut.state : BIT >>` refers to an
+//! object declared elsewhere in the design hierarchy. Its path is resolved
+//! during elaboration, by following the instance labels along the path to the
+//! architectures the instances are bound to. See IEEE 1076-2008 section 8.7.
+//!
+//! Constants assume the value they have in the instance they are declared in.
+//! Signals are only visible within the unit they are declared in, since LLHD
+//! units communicate through their ports alone. Every instance along the path
+//! therefore exports the signal through an additional output, which the unit
+//! that contains the external name connects to a signal of its own.
+
+use crate::common::errors::*;
+use crate::common::name::Name;
+use crate::common::score::Result;
+use crate::common::source::{Span, Spanned};
+use crate::generic_env::GenericEnv;
+use crate::hir;
+use crate::score::*;
+use crate::typeck::TypeckContext;
+
+/// A signal exported to an external name in an enclosing unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExportedSignal {
+    /// The labels of the instances from the exporting unit down to the
+    /// architecture that declares the signal.
+    pub path: Vec<Name>,
+    /// The signal.
+    pub signal: SignalRef,
+    /// The generic environment of the architecture that declares the signal.
+    pub env: GenericEnv,
+}
+
+/// The object an external name refers to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalTarget {
+    /// A constant or generic, and the generic environment of the
+    /// architecture it is declared in.
+    Const(ConstRef, GenericEnv),
+    /// A signal, and the instances through which it is reached.
+    Signal(ExportedSignal),
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Resolve an external name against the design hierarchy.
+    ///
+    /// The path is followed from the architecture that contains the external
+    /// name, in the current generic environment. An absolute path starts with
+    /// the name of the entity of this architecture, which must be the root of
+    /// the design hierarchy. The subtype of the external name must match the
+    /// type of the object it refers to.
+    pub fn external_target(&self, id: ExprRef) -> Result<ExternalTarget> {
+        let hir = self.lazy_hir(id)?;
+        let ext = match hir.data {
+            hir::ExprData::ExternalName(ref ext) => ext,
+            _ => unreachable!("not an external name"),
+        };
+        self.mark_generic_dep();
+        let mut arch = self.external_arch(hir.parent, hir.span)?;
+        let mut path = &ext.path[..];
+        match ext.kind {
+            hir::ExternalPathKind::Absolute => {
+                let entity = self.hir(self.hir(arch)?.entity)?;
+                if path[0].value != entity.name.value {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "external name `{}` does not start at entity `{}`",
+                            hir.span.extract(),
+                            entity.name.value
+                        ))
+                        .span(path[0].span)
+                        .add_note(
+                            "Absolute paths are resolved from the entity that contains the \
+                             external name, which must be the root of the design hierarchy.",
+                        ),
+                    );
+                    return Err(());
+                }
+                path = &path[1..];
+            }
+            hir::ExternalPathKind::Relative(0) => (),
+            hir::ExternalPathKind::Relative(_) | hir::ExternalPathKind::Package => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "external name `{}` is not supported",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span)
+                    .add_note(
+                        "Only absolute paths and relative paths without `^` are supported in \
+                         external names.",
+                    ),
+                );
+                return Err(());
+            }
+        }
+        let (object, labels) = match path.split_last() {
+            Some(x) => x,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "external name `{}` does not name an object",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+        };
+
+        // Follow the instances along the path.
+        let mut env = self.generic_env();
+        for &label in labels {
+            let inst = self.external_inst(arch, label)?;
+            let binding = self.with_generic_env(env, || self.inst_binding(inst))?;
+            arch = binding.arch;
+            env = binding.env;
+        }
+
+        // Find the object in the architecture or its entity.
+        let path = labels.iter().map(|label| label.value).collect();
+        let (target, ty) = self.with_generic_env(env, || -> Result<_> {
+            match (ext.class.value, self.external_object(arch, object.value)?) {
+                (hir::ExternalClass::Signal, Some(Def::Signal(signal))) => Ok((
+                    ExternalTarget::Signal(ExportedSignal { path, signal, env }),
+                    self.ty(signal)?,
+                )),
+                (hir::ExternalClass::Constant, Some(Def::Const(id))) => {
+                    let ty = match id {
+                        ConstRef::Intf(id) => self.ty(id)?,
+                        ConstRef::Decl(id) => self.lazy_typeval(id)?,
+                        _ => unreachable!(),
+                    };
+                    Ok((ExternalTarget::Const(id, env), ty))
+                }
+                (hir::ExternalClass::Variable, _) => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "external name `{}` refers to a variable, which is not supported",
+                            hir.span.extract()
+                        ))
+                        .span(ext.class.span),
+                    );
+                    Err(())
+                }
+                (class, _) => {
+                    let arch_hir = self.hir(arch)?;
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "no {} `{}` in architecture `{}` of entity `{}`",
+                            match class {
+                                hir::ExternalClass::Signal => "signal",
+                                _ => "constant",
+                            },
+                            object.value,
+                            arch_hir.name.value,
+                            self.hir(arch_hir.entity)?.name.value
+                        ))
+                        .span(object.span),
+                    );
+                    Err(())
+                }
+            }
+        })?;
+        let tyc = TypeckContext::new(self);
+        tyc.must_match(self.lazy_typeval(ext.subtype)?, ty, hir.span);
+        if !tyc.finish() {
+            return Err(());
+        }
+        Ok(target)
+    }
+
+    /// Find the architecture that contains an external name.
+    fn external_arch(&self, mut scope: ScopeRef, span: Span) -> Result<ArchRef> {
+        loop {
+            match scope {
+                ScopeRef::Arch(id) => return Ok(id),
+                ScopeRef::GenBody(_) => {
+                    self.emit(
+                        DiagBuilder2::error(
+                            "external names within generate statements are not supported",
+                        )
+                        .span(span),
+                    );
+                    return Err(());
+                }
+                _ => (),
+            }
+            scope = match self.scope(scope)?.parent {
+                Some(parent) => parent,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(
+                            "external names outside of architectures are not supported",
+                        )
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            };
+        }
+    }
+
+    /// Find the instance with a given label in an architecture.
+    fn external_inst(&self, arch: ArchRef, label: Spanned<Name>) -> Result<CompInstStmtRef> {
+        let hir = self.hir(arch)?;
+        for &stmt in &hir.stmts {
+            if let ConcStmtRef::CompInst(id) = stmt {
+                if self.hir(id)?.label.map(|l| l.value) == Some(label.value) {
+                    return Ok(id);
+                }
+            }
+        }
+        self.emit(
+            DiagBuilder2::error(format!(
+                "no instance `{}` in architecture `{}` of entity `{}`",
+                label.value,
+                hir.name.value,
+                self.hir(hir.entity)?.name.value
+            ))
+            .span(label.span)
+            .add_note(
+                "The path of an external name may only pass through component and entity \
+                 instances.",
+            ),
+        );
+        Err(())
+    }
+
+    /// Find a signal or constant declared in an architecture, or a port or
+    /// generic of its entity.
+    fn external_object(&self, arch: ArchRef, name: Name) -> Result<Option<Def>> {
+        let hir = self.hir(arch)?;
+        for &decl in &hir.decls {
+            match decl {
+                DeclInBlockRef::Signal(id) if self.lazy_hir(id)?.name.value == name => {
+                    return Ok(Some(Def::Signal(id.into())));
+                }
+                DeclInBlockRef::Const(id) if self.lazy_hir(id)?.name.value == name => {
+                    return Ok(Some(Def::Const(id.into())));
+                }
+                _ => (),
+            }
+        }
+        let entity = self.hir(hir.entity)?;
+        for &port in &entity.ports {
+            if self.hir(port)?.name.value == name {
+                return Ok(Some(Def::Signal(port.into())));
+            }
+        }
+        for &generic in &entity.generics {
+            if let GenericRef::Const(id) = generic {
+                if self.hir(id)?.name.value == name {
+                    return Ok(Some(Def::Const(id.into())));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Determine the name of a signal.
+    pub fn signal_name(&self, id: SignalRef) -> Result<Name> {
+        Ok(match id {
+            SignalRef::Decl(id) => self.lazy_hir(id)?.name.value,
+            SignalRef::Intf(id) => self.hir(id)?.name.value,
+        })
+    }
+}
@@ -0,0 +1,512 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Instantiation of design units across language boundaries.
+//!
+//! An entity instantiated from another language, such as SystemVerilog, is
+//! described by the language-neutral interface of `moore_common::foreign`.
+//! Generics become parameters and ports keep their mode. Ports and generics of
+//! type `BIT`, `BOOLEAN`, and `STD_ULOGIC` become single bits, arrays thereof
+//! become vectors with the same bounds, and integers keep their width. Port
+//! types that depend on generics are evaluated with the default values of the
+//! generics.
+//!
+//! Conversely, a design unit declared in another language is instantiated
+//! through a stand-in entity with the same interface, which is added to the
+//! library before it is analyzed. Single bits become `STD_ULOGIC` or `BIT`,
+//! vectors become `STD_ULOGIC_VECTOR` or `BIT_VECTOR` with the same bounds,
+//! and integers become `INTEGER` or `NATURAL` constrained to their width.
+//! Instances of the stand-in refer to the unit by name.
+//!
+//! Both directions describe the conversion of every port and generic as a
+//! `Crossing`, noting the caveats of the conversion such as the nine values
+//! of `STD_ULOGIC` collapsing to four, or integer ranges that are no longer
+//! enforced.
+
+use crate::builtin::{BIT_TYPE, BOOLEAN_TYPE, STD_ULOGIC_TYPE};
+use crate::common::errors::*;
+use crate::common::foreign::*;
+use crate::common::name::Name;
+use crate::common::score::Result;
+use crate::common::source::{get_source_manager, Span};
+use crate::hir;
+use crate::konst::*;
+use crate::score::*;
+use crate::syntax::{self, ast};
+use crate::ty::*;
+use num::{BigInt, One, Signed, ToPrimitive, Zero};
+use std::collections::HashSet;
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Describe the interface of an entity for instantiation from another
+    /// language.
+    pub fn foreign_unit(&self, id: EntityRef) -> Result<ForeignUnit> {
+        let hir = self.hir(id)?;
+        let mut params = vec![];
+        for &generic in &hir.generics {
+            let id = match generic {
+                GenericRef::Const(id) => id,
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "entity `{}` has a generic that is not a constant",
+                            hir.name.value
+                        ))
+                        .span(hir.name.span)
+                        .add_note("Only constant generics can be assigned from other languages."),
+                    );
+                    return Err(());
+                }
+            };
+            let generic = self.hir(id)?;
+            let ty = self.foreign_type(self.ty(id)?, generic.name.span)?;
+            let subty = self.lazy_hir(generic.ty)?;
+            let default = match generic.init {
+                Some(_) => Some(self.foreign_value(
+                    self.generic_value(id, generic.name.span)?,
+                    generic.name.span,
+                )?),
+                None => None,
+            };
+            params.push(ForeignParam {
+                name: generic.name.value,
+                ty,
+                decl_ty: subty.span.extract(),
+                default,
+                notes: self.foreign_notes(self.ty(id)?, subty)?,
+            });
+        }
+        let mut ports = vec![];
+        for &port in &hir.ports {
+            let port_hir = self.hir(port)?;
+            let dir = match port_hir.mode {
+                hir::IntfSignalMode::In => ForeignDir::Input,
+                hir::IntfSignalMode::Out | hir::IntfSignalMode::Buffer => ForeignDir::Output,
+                hir::IntfSignalMode::Inout => ForeignDir::Inout,
+                hir::IntfSignalMode::Linkage => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "linkage port `{}` cannot be connected from another language",
+                            port_hir.name.value
+                        ))
+                        .span(port_hir.name.span),
+                    );
+                    return Err(());
+                }
+            };
+            let subty = self.lazy_hir(port_hir.ty)?;
+            ports.push(ForeignPort {
+                name: port_hir.name.value,
+                dir,
+                ty: self.foreign_type(self.ty(port)?, port_hir.name.span)?,
+                decl_ty: subty.span.extract(),
+                notes: self.foreign_notes(self.ty(port)?, subty)?,
+            });
+        }
+        Ok(ForeignUnit {
+            name: hir.name.value,
+            span: hir.name.span,
+            lang: ForeignLang::Vhdl,
+            params,
+            ports,
+        })
+    }
+
+    /// Determine the signature of the design unit an instance of a stand-in
+    /// entity refers to.
+    pub(crate) fn foreign_sig(&self, id: EntityRef) -> Result<llhd::ir::Signature> {
+        let mut sig = llhd::ir::Signature::new();
+        let mut outputs = Vec::new();
+        for &port in &self.hir(id)?.ports {
+            let hir = self.hir(port)?;
+            let ty = llhd::signal_ty(self.map_type(self.ty(hir.ty)?)?);
+            match hir.mode {
+                hir::IntfSignalMode::In
+                | hir::IntfSignalMode::Inout
+                | hir::IntfSignalMode::Linkage => {
+                    sig.add_input(ty.clone());
+                }
+                _ => (),
+            }
+            match hir.mode {
+                hir::IntfSignalMode::Out
+                | hir::IntfSignalMode::Inout
+                | hir::IntfSignalMode::Buffer => outputs.push(ty),
+                _ => (),
+            }
+        }
+        for ty in outputs {
+            sig.add_output(ty);
+        }
+        Ok(sig)
+    }
+
+    /// Map the type of a port or generic to a language-neutral type.
+    fn foreign_type(&self, ty: &Ty, span: Span) -> Result<ForeignType> {
+        if let Some(ty) = self.foreign_bit_type(ty)? {
+            return Ok(ty);
+        }
+        match *self.deref_named_type(ty)? {
+            Ty::Int(ref int_ty) => {
+                let llty = self.map_type(ty)?;
+                if llty.is_int() {
+                    return Ok(ForeignType::Int {
+                        width: llty.unwrap_int(),
+                        signed: int_ty.left_bound.is_negative() || int_ty.right_bound.is_negative(),
+                    });
+                }
+            }
+            Ty::Array(ref array_ty) if array_ty.indices.len() == 1 => {
+                let logic = match self.foreign_bit_type(&array_ty.element)? {
+                    Some(ForeignType::Bit { logic }) => Some(logic),
+                    _ => None,
+                };
+                let index = match array_ty.indices[0] {
+                    ArrayIndex::Constrained(ref index) => match **index {
+                        Ty::Int(ref index) => {
+                            match (index.left_bound.to_isize(), index.right_bound.to_isize()) {
+                                (Some(left), Some(right)) => Some((left, right)),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    },
+                    ArrayIndex::Unbounded(_) => None,
+                };
+                if let (Some(logic), Some((left, right))) = (logic, index) {
+                    return Ok(ForeignType::Vector { logic, left, right });
+                }
+            }
+            _ => (),
+        }
+        self.emit(
+            DiagBuilder2::error(format!("type `{}` cannot cross a language boundary", ty))
+                .span(span)
+                .add_note(
+                    "Only `BIT`, `BOOLEAN`, `STD_ULOGIC`, constrained one-dimensional arrays \
+                     thereof, and integers are supported.",
+                ),
+        );
+        Err(())
+    }
+
+    /// Map a type to a single bit, if it is `BIT`, `BOOLEAN`, or `STD_ULOGIC`.
+    fn foreign_bit_type(&self, ty: &Ty) -> Result<Option<ForeignType>> {
+        Ok(match *self.deref_named_type(ty)? {
+            Ty::Enum(ref ty) if ty.decl == STD_ULOGIC_TYPE.id => {
+                Some(ForeignType::Bit { logic: true })
+            }
+            Ty::Enum(ref ty) if ty.decl == BIT_TYPE.id || ty.decl == BOOLEAN_TYPE.id => {
+                Some(ForeignType::Bit { logic: false })
+            }
+            _ => None,
+        })
+    }
+
+    /// Determine the caveats of mapping the type of a port or generic to a
+    /// language-neutral type.
+    fn foreign_notes(&self, ty: &Ty, subty: &hir::SubtypeInd) -> Result<Vec<String>> {
+        let mut notes = vec![];
+        let mark = subty.type_mark.span.extract();
+        let mark = mark.rsplit('.').next().unwrap().trim().to_uppercase();
+        if mark == "SIGNED" || mark == "UNSIGNED" {
+            notes.push(format!(
+                "the numeric interpretation of `{}` is dropped, leaving a plain vector",
+                mark
+            ));
+        }
+        let element = match *self.deref_named_type(ty)? {
+            Ty::Array(ref array_ty) => self.deref_named_type(&array_ty.element)?,
+            ref ty => ty,
+        };
+        match *element {
+            Ty::Enum(ref enum_ty) if enum_ty.decl == STD_ULOGIC_TYPE.id => notes.push(
+                "the nine values of `STD_ULOGIC` map to four-valued logic, with `L` and `H` \
+                 read as `0` and `1`, and `U`, `W`, and `-` as `X`"
+                    .to_string(),
+            ),
+            Ty::Enum(ref enum_ty) if enum_ty.decl == BOOLEAN_TYPE.id => {
+                notes.push("`FALSE` and `TRUE` map to `0` and `1`".to_string())
+            }
+            Ty::Int(ref int_ty) => {
+                let width = self.map_type(ty)?.unwrap_int();
+                let signed = int_ty.left_bound.is_negative() || int_ty.right_bound.is_negative();
+                let (min, max) = if signed {
+                    let half = BigInt::one() << (width - 1);
+                    (-half.clone(), half - 1)
+                } else {
+                    (BigInt::zero(), (BigInt::one() << width) - 1)
+                };
+                let (lo, hi) = match int_ty.dir {
+                    Dir::To => (&int_ty.left_bound, &int_ty.right_bound),
+                    Dir::Downto => (&int_ty.right_bound, &int_ty.left_bound),
+                };
+                if *lo != min || *hi != max {
+                    notes.push(format!(
+                        "the range `{}` is not enforced beyond the boundary",
+                        int_ty
+                    ));
+                }
+            }
+            _ => (),
+        }
+        Ok(notes)
+    }
+
+    /// Map the value of a generic to a language-neutral value.
+    fn foreign_value(&self, value: &Const, span: Span) -> Result<ForeignValue> {
+        let mut bits = String::new();
+        match *value {
+            Const::Int(ref k) => {
+                if let Some(v) = k.value.to_i64() {
+                    return Ok(ForeignValue::Int(v));
+                }
+            }
+            Const::Enum(ref k) => {
+                if foreign_bit(k, &mut bits) {
+                    return Ok(ForeignValue::Bits(bits));
+                }
+            }
+            Const::Array(ref k) => {
+                let all_bits = k.elements.iter().all(|k| match *k {
+                    Const::Enum(ref k) => foreign_bit(k, &mut bits),
+                    _ => false,
+                });
+                if all_bits {
+                    return Ok(ForeignValue::Bits(bits));
+                }
+            }
+            _ => (),
+        }
+        self.emit(
+            DiagBuilder2::error(format!(
+                "value `{}` cannot cross a language boundary",
+                value
+            ))
+            .span(span),
+        );
+        Err(())
+    }
+}
+
+/// Append the bit corresponding to a `BIT`, `BOOLEAN`, or `STD_ULOGIC` value.
+///
+/// The weak values `L` and `H` become `0` and `1`, and all values that are
+/// neither `0`, `1`, nor `Z` become `x`. Returns `false` for values of other
+/// types.
+fn foreign_bit(k: &ConstEnum, into: &mut String) -> bool {
+    let bit = if k.decl == BIT_TYPE.id || k.decl == BOOLEAN_TYPE.id {
+        if k.index == 1 {
+            '1'
+        } else {
+            '0'
+        }
+    } else if k.decl == STD_ULOGIC_TYPE.id {
+        // `type STD_ULOGIC is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-')`
+        match k.index {
+            2 | 6 => '0',
+            3 | 7 => '1',
+            4 => 'z',
+            _ => 'x',
+        }
+    } else {
+        return false;
+    };
+    into.push(bit);
+    true
+}
+
+/// Parse the stand-in entity for a design unit declared in another language.
+pub fn stand_in(unit: &ForeignUnit) -> Result<Vec<ast::DesignUnit>> {
+    let text = entity_text(unit);
+    let source = get_source_manager().add(&format!("<{} {}>", unit.lang, unit.name), &text);
+    syntax::parse(source)
+}
+
+/// Describe the conversions made when instantiating a design unit declared in
+/// another language through its stand-in entity.
+pub fn crossing(unit: &ForeignUnit) -> Crossing {
+    Crossing::new(unit, ForeignLang::Vhdl, |ty, notes| {
+        let bound = match *ty {
+            ForeignType::Int { width, signed } if signed && width > 32 => Some("INTEGER"),
+            ForeignType::Int { width, signed } if !signed && width > 31 => Some("NATURAL"),
+            _ => None,
+        };
+        if let Some(bound) = bound {
+            notes.push(format!(
+                "values beyond the range of `{}` cannot be represented",
+                bound
+            ));
+        }
+        vhdl_type(ty)
+    })
+}
+
+/// Generate the declaration of the stand-in entity for a foreign unit.
+fn entity_text(unit: &ForeignUnit) -> String {
+    let generics: Vec<_> = unit
+        .params
+        .iter()
+        .map(|param| {
+            let mut decl = format!("{} : {}", param.name, vhdl_type(&param.ty));
+            if let Some(ref value) = param.default {
+                decl.push_str(&format!(" := {}", vhdl_value(value, &param.ty)));
+            }
+            decl
+        })
+        .collect();
+    let ports: Vec<_> = unit
+        .ports
+        .iter()
+        .map(|port| {
+            let mode = match port.dir {
+                ForeignDir::Input => "in",
+                ForeignDir::Output => "out",
+                ForeignDir::Inout => "inout",
+            };
+            format!("{} : {} {}", port.name, mode, vhdl_type(&port.ty))
+        })
+        .collect();
+    let mut text = format!(
+        "library ieee;\nuse ieee.std_logic_1164.all;\nentity {} is\n",
+        unit.name
+    );
+    if !generics.is_empty() {
+        text.push_str(&format!("generic ({});\n", generics.join("; ")));
+    }
+    if !ports.is_empty() {
+        text.push_str(&format!("port ({});\n", ports.join("; ")));
+    }
+    text.push_str(&format!(
+        "end;\narchitecture foreign of {} is\nbegin\nend;\n",
+        unit.name
+    ));
+    text
+}
+
+/// Map a type of a foreign unit to a VHDL type.
+fn vhdl_type(ty: &ForeignType) -> String {
+    match *ty {
+        ForeignType::Bit { logic: true } => "STD_ULOGIC".to_string(),
+        ForeignType::Bit { logic: false } => "BIT".to_string(),
+        ForeignType::Vector { logic, left, right } => {
+            let name = if logic {
+                "STD_ULOGIC_VECTOR"
+            } else {
+                "BIT_VECTOR"
+            };
+            let dir = if left >= right { "downto" } else { "to" };
+            format!("{}({} {} {})", name, left, dir, right)
+        }
+        ForeignType::Int { width, signed } => match (signed, width.max(1)) {
+            (true, width) if width < 32 => format!(
+                "INTEGER range {} to {}",
+                -(1i64 << (width - 1)),
+                (1i64 << (width - 1)) - 1
+            ),
+            (true, _) => "INTEGER".to_string(),
+            (false, width) if width < 31 => format!("NATURAL range 0 to {}", (1i64 << width) - 1),
+            (false, _) => "NATURAL".to_string(),
+        },
+    }
+}
+
+/// Map a parameter value of a foreign unit to a VHDL literal.
+///
+/// Unknown and high-impedance bits become `0` in two-valued types.
+fn vhdl_value(value: &ForeignValue, ty: &ForeignType) -> String {
+    let logic = match *ty {
+        ForeignType::Bit { logic } | ForeignType::Vector { logic, .. } => logic,
+        ForeignType::Int { .. } => false,
+    };
+    match *value {
+        ForeignValue::Int(v) => v.to_string(),
+        ForeignValue::Bits(ref bits) => {
+            let bits: String = bits
+                .chars()
+                .map(|bit| match bit {
+                    '0' | '1' => bit,
+                    _ if !logic => '0',
+                    _ => bit.to_ascii_uppercase(),
+                })
+                .collect();
+            match *ty {
+                ForeignType::Bit { .. } => format!("'{}'", bits),
+                _ => format!("\"{}\"", bits),
+            }
+        }
+    }
+}
+
+/// Collect the names of the design units that a design file may instantiate
+/// from other languages.
+///
+/// These are the names of the declared components and of the entities named
+/// in entity instantiations.
+pub fn inst_target_names(units: &[ast::DesignUnit], into: &mut HashSet<Name>) {
+    for unit in units {
+        match unit.data {
+            ast::DesignUnitData::ArchBody(ref body) => {
+                decl_target_names(&body.decls, into);
+                stmt_target_names(&body.stmts, into);
+            }
+            ast::DesignUnitData::PkgDecl(ref decl) => decl_target_names(&decl.decls, into),
+            _ => (),
+        }
+    }
+}
+
+fn decl_target_names(decls: &[ast::DeclItem], into: &mut HashSet<Name>) {
+    for decl in decls {
+        if let ast::DeclItem::CompDecl(ref decl) = *decl {
+            into.insert(decl.name.value);
+        }
+    }
+}
+
+fn stmt_target_names(stmts: &[ast::Stmt], into: &mut HashSet<Name>) {
+    for stmt in stmts {
+        match stmt.data {
+            ast::InstOrCallStmt {
+                target: Some(ast::InstTarget::Entity),
+                ref name,
+                ..
+            } => {
+                // The entity is named as in `work.foo`.
+                let primary = match name.parts.first() {
+                    Some(&ast::NamePart::Select(ref primary)) => primary,
+                    _ => &name.primary,
+                };
+                if let ast::PrimaryNameKind::Ident(name) = primary.kind {
+                    into.insert(name);
+                }
+            }
+            ast::BlockStmt {
+                ref decls,
+                ref stmts,
+                ..
+            } => {
+                decl_target_names(decls, into);
+                stmt_target_names(stmts, into);
+            }
+            ast::IfGenStmt {
+                ref conds, ref alt, ..
+            } => {
+                for body in conds.iter().map(|(_, body)| body).chain(alt.iter()) {
+                    decl_target_names(&body.decls, into);
+                    stmt_target_names(&body.stmts, into);
+                }
+            }
+            ast::CaseGenStmt { ref cases, .. } => {
+                for (_, body) in cases {
+                    decl_target_names(&body.decls, into);
+                    stmt_target_names(&body.stmts, into);
+                }
+            }
+            ast::ForGenStmt { ref body, .. } => {
+                decl_target_names(&body.decls, into);
+                stmt_target_names(&body.stmts, into);
+            }
+            _ => (),
+        }
+    }
+}
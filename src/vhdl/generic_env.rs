@@ -0,0 +1,302 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A generic environment generated by an entity instantiation.
+//!
+//! The values of the generics of an entity are only known once the entity is
+//! instantiated. Everything that consults them, such as the port types in
+//! `bit_vector(WIDTH-1 downto 0)`, is evaluated once per generic environment.
+//! Results that do not consult any generic are shared among all environments.
+//!
+//! Subprograms that are interpreted during elaboration extend the environment
+//! with the values of their parameters and local objects, such that the
+//! expressions in their bodies are evaluated in the same way. The same holds
+//! for the parameter of a for generate statement, which assumes a different
+//! value for every copy of the generated statements.
+//!
+//! A configuration declaration that is elaborated adds the block configuration
+//! of the current architecture to the environment, which determines how the
+//! component instances within it are bound.
+//!
+//! The signals that an entity exports to external names in the instantiating
+//! unit are part of the environment as well, since each of them adds an output
+//! to the entity.
+
+use std::fmt;
+
+use crate::common::errors::*;
+use crate::common::score::Result;
+use crate::common::source::Span;
+use crate::common::NodeId;
+use crate::external::ExportedSignal;
+use crate::hir;
+use crate::konst::Const;
+use crate::score::{CompInstStmtRef, EntityRef, GenericRef, IntfConstRef, ScoreContext};
+
+/// A generic environment.
+///
+/// This is merely an handle that is cheap to copy and pass around. Use the
+/// [`ScoreContext`] to resolve this to the actual [`GenericEnvData`]. In the
+/// default environment all generics assume their default value.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GenericEnv(pub(crate) u32);
+
+impl fmt::Display for GenericEnv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "g{}", self.0)
+    }
+}
+
+impl fmt::Debug for GenericEnv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The values assigned to generics.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GenericEnvData<'ctx> {
+    values: Vec<(IntfConstRef, &'ctx Const)>,
+    locals: Vec<(NodeId, &'ctx Const)>,
+    cfg: Option<&'ctx hir::BlockCfg>,
+    exports: Vec<ExportedSignal>,
+}
+
+impl<'ctx> GenericEnvData<'ctx> {
+    /// Find the value assigned to a generic.
+    pub fn find_value(&self, id: IntfConstRef) -> Option<&'ctx Const> {
+        self.values
+            .iter()
+            .find(|&&(generic, _)| generic == id)
+            .map(|&(_, value)| value)
+    }
+
+    /// Assign a value to a generic.
+    pub fn set_value(&mut self, id: IntfConstRef, value: &'ctx Const) {
+        self.values.retain(|&(generic, _)| generic != id);
+        self.values.push((id, value));
+    }
+
+    /// Find the value of a local object of an interpreted subprogram.
+    pub fn find_local(&self, id: NodeId) -> Option<&'ctx Const> {
+        self.locals
+            .iter()
+            .find(|&&(local, _)| local == id)
+            .map(|&(_, value)| value)
+    }
+
+    /// Assign a value to a local object of an interpreted subprogram.
+    pub fn set_local(&mut self, id: NodeId, value: &'ctx Const) {
+        match self.locals.iter_mut().find(|&&mut (local, _)| local == id) {
+            Some(local) => local.1 = value,
+            None => self.locals.push((id, value)),
+        }
+    }
+
+    /// Remove the local objects of an interpreted subprogram.
+    pub fn clear_locals(&mut self) {
+        self.locals.clear();
+    }
+
+    /// Get the configuration of the block being elaborated.
+    pub fn block_cfg(&self) -> Option<&'ctx hir::BlockCfg> {
+        self.cfg
+    }
+
+    /// Set the configuration of the block being elaborated.
+    pub fn set_block_cfg(&mut self, cfg: Option<&'ctx hir::BlockCfg>) {
+        self.cfg = cfg;
+    }
+
+    /// Get the signals exported to external names in the instantiating unit.
+    pub fn exports(&self) -> &[ExportedSignal] {
+        &self.exports
+    }
+
+    /// Set the signals exported to external names in the instantiating unit.
+    pub fn set_exports(&mut self, exports: Vec<ExportedSignal>) {
+        self.exports = exports;
+    }
+
+    /// Check whether no values are assigned, no configuration applies, and no
+    /// signals are exported.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+            && self.locals.is_empty()
+            && self.cfg.is_none()
+            && self.exports.is_empty()
+    }
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Internalize a generic environment.
+    ///
+    /// Environments that assign the same values map to the same handle, such
+    /// that an entity is only elaborated once per distinct set of generics.
+    pub fn intern_generic_env(&self, data: GenericEnvData<'ctx>) -> GenericEnv {
+        let mut envs = self.sb.generic_envs.borrow_mut();
+        if let Some(index) = envs.iter().position(|env| *env == data) {
+            return GenericEnv(index as u32);
+        }
+        envs.push(data);
+        GenericEnv(envs.len() as u32 - 1)
+    }
+
+    /// Get the generic environment in which nodes are currently evaluated.
+    pub fn generic_env(&self) -> GenericEnv {
+        self.sb.generic_env.get()
+    }
+
+    /// Evaluate nodes in a different generic environment.
+    pub fn with_generic_env<R>(&self, env: GenericEnv, f: impl FnOnce() -> R) -> R {
+        let outer_env = self.sb.generic_env.replace(env);
+        let outer_dep = self.sb.generic_dep.replace(false);
+        let result = f();
+        self.sb.generic_env.set(outer_env);
+        self.sb.generic_dep.set(outer_dep);
+        result
+    }
+
+    /// Determine the value of a generic in the current environment.
+    ///
+    /// Falls back to the default value of the generic if the environment does
+    /// not assign it a value.
+    pub fn generic_value(&self, id: IntfConstRef, span: Span) -> Result<&'ctx Const> {
+        self.mark_generic_dep();
+        let env = self.generic_env();
+        if let Some(value) = self.sb.generic_envs.borrow()[env.0 as usize].find_value(id) {
+            return Ok(value);
+        }
+        let hir = self.hir(id)?;
+        match hir.init {
+            Some(init) => self.const_value(init),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!("generic `{}` has no value", hir.name.value))
+                        .span(span)
+                        .add_note(
+                            "Assign a value in the generic map of the instantiation, or declare \
+                             a default value for the generic:",
+                        )
+                        .span(hir.name.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the value of a local object of an interpreted subprogram, or
+    /// of a generate parameter, in the current environment.
+    pub fn local_value(&self, id: NodeId) -> Option<&'ctx Const> {
+        self.mark_generic_dep();
+        let env = self.generic_env();
+        self.sb.generic_envs.borrow()[env.0 as usize].find_local(id)
+    }
+
+    /// Assign a value to a local object of an interpreted subprogram.
+    ///
+    /// Switches to the environment that differs from the current one only in
+    /// the value of the local.
+    pub fn set_local_value(&self, id: NodeId, value: &'ctx Const) {
+        let env = self.generic_env();
+        let mut data = self.sb.generic_envs.borrow()[env.0 as usize].clone();
+        data.set_local(id, value);
+        self.sb.generic_env.set(self.intern_generic_env(data));
+    }
+
+    /// Evaluate nodes with a local bound to a value.
+    ///
+    /// The current environment is restored afterwards.
+    pub fn with_local_value<R>(&self, id: NodeId, value: &'ctx Const, f: impl FnOnce() -> R) -> R {
+        self.with_generic_env(self.generic_env(), || {
+            self.set_local_value(id, value);
+            f()
+        })
+    }
+
+    /// Determine the configuration of the block being elaborated, if any.
+    pub fn block_cfg(&self) -> Option<&'ctx hir::BlockCfg> {
+        self.mark_generic_dep();
+        let env = self.generic_env();
+        self.sb.generic_envs.borrow()[env.0 as usize].block_cfg()
+    }
+
+    /// Evaluate nodes with a different block configuration.
+    ///
+    /// The current environment is restored afterwards.
+    pub fn with_block_cfg<R>(&self, cfg: Option<&'ctx hir::BlockCfg>, f: impl FnOnce() -> R) -> R {
+        let env = self.generic_env();
+        let mut data = self.sb.generic_envs.borrow()[env.0 as usize].clone();
+        data.set_block_cfg(cfg);
+        self.with_generic_env(self.intern_generic_env(data), f)
+    }
+
+    /// Determine the signals exported to external names in the instantiating
+    /// unit.
+    pub fn exported_signals(&self) -> Vec<ExportedSignal> {
+        self.mark_generic_dep();
+        let env = self.generic_env();
+        self.sb.generic_envs.borrow()[env.0 as usize]
+            .exports()
+            .to_vec()
+    }
+
+    /// Evaluate nodes with a different set of exported signals.
+    ///
+    /// The current environment is restored afterwards.
+    pub fn with_exported_signals<R>(
+        &self,
+        exports: Vec<ExportedSignal>,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        let env = self.generic_env();
+        let mut data = self.sb.generic_envs.borrow()[env.0 as usize].clone();
+        data.set_exports(exports);
+        self.with_generic_env(self.intern_generic_env(data), f)
+    }
+
+    /// Determine the generic environment of an entity instantiation.
+    ///
+    /// The actuals in the generic map are evaluated in the current environment.
+    pub fn inst_generic_env(&self, id: CompInstStmtRef) -> Result<GenericEnv> {
+        let mut data = GenericEnvData::default();
+        for &(generic, actual) in &self.hir(id)?.generic_map {
+            data.set_value(generic, self.const_value(actual)?);
+        }
+        Ok(self.intern_generic_env(data))
+    }
+
+    /// Check whether all generics of an entity have a value in the current
+    /// environment, either assigned or as their default value.
+    pub fn generics_bound(&self, entity: EntityRef) -> Result<bool> {
+        let env = self.generic_env();
+        for &generic in &self.hir(entity)?.generics {
+            if let GenericRef::Const(id) = generic {
+                let assigned = self.sb.generic_envs.borrow()[env.0 as usize]
+                    .find_value(id)
+                    .is_some();
+                if !assigned && self.hir(id)?.init.is_none() {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Record that the current evaluation consults the generic environment.
+    pub fn mark_generic_dep(&self) {
+        self.sb.generic_dep.set(true);
+    }
+
+    /// Evaluate something and determine whether it consults the generic
+    /// environment.
+    ///
+    /// Evaluations nest, such that an enclosing evaluation also consults the
+    /// environment if any evaluation within it does.
+    pub fn track_generic_dep<R>(&self, f: impl FnOnce() -> R) -> (R, bool) {
+        let outer = self.sb.generic_dep.replace(false);
+        let result = f();
+        let dep = self.sb.generic_dep.get();
+        self.sb.generic_dep.set(outer || dep);
+        (result, dep)
+    }
+}
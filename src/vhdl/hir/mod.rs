@@ -2,7 +2,7 @@
 
 //! The High-level Intermediate Representation of a VHDL design.
 
-use num::BigInt;
+use num::{BigInt, BigRational};
 
 use crate::common::name::*;
 use crate::common::source::*;
@@ -11,7 +11,9 @@ use crate::common::util::HasSpan;
 use crate::konst::*;
 use crate::op::*;
 use crate::score::*;
-pub use crate::syntax::ast::Dir;
+pub use crate::syntax::ast::{
+    Dir, ExternalClass, ExternalPathKind, PslBinaryOp, PslDirectiveKind, PslRepeatKind, PslUnaryOp,
+};
 
 mod alloc_ctx;
 mod arena;
@@ -46,11 +48,13 @@ make_arenas!(
         entity: Entity,
         arch: Arch,
         intf_sig: IntfSignal,
+        intf_const: IntfConst,
         subtype_ind: SubtypeInd,
         package: Package,
         package_body: PackageBody,
         package_inst: PackageInst,
         type_decl: TypeDecl,
+        protected_body: ProtectedBody,
         subtype_decl: SubtypeDecl,
         expr: Expr,
         aggregate: Aggregate,
@@ -60,6 +64,13 @@ make_arenas!(
         file_decl: Decl<FileDecl>,
         type_decl2: Decl<Option<TypeData>>,
         process_stmt: ProcessStmt,
+        psl_directive: PslDirective,
+        cfg: Cfg,
+        comp_inst_stmt: CompInstStmt,
+        for_gen_stmt: ForGenStmt,
+        if_gen_stmt: IfGenStmt,
+        case_gen_stmt: CaseGenStmt,
+        gen_body: GenBody,
         sig_assign_stmt: SigAssignStmt,
         array_type_index: Spanned<ArrayTypeIndex>,
         subprog: Subprog,
@@ -134,6 +145,60 @@ pub struct Arch {
     pub stmts: Vec<ConcStmtRef>,
 }
 
+/// A configuration declaration.
+///
+/// See IEEE 1076-2008 section 3.4.
+#[derive(Debug)]
+pub struct Cfg {
+    /// The configuration name.
+    pub name: Spanned<Name>,
+    /// The configured entity.
+    pub entity: Spanned<EntityRef>,
+    /// The configuration of the architecture of the entity.
+    pub block: BlockCfg,
+}
+
+/// A block configuration.
+///
+/// Configures the component instances within an architecture or a generate
+/// statement. See IEEE 1076-2008 section 3.4.2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockCfg {
+    /// The name of the architecture, or the label of the generate statement.
+    pub name: Spanned<Name>,
+    /// The configurations of the generate statements within the block.
+    pub blocks: Vec<BlockCfg>,
+    /// The configurations of the component instances within the block.
+    pub comps: Vec<CompCfg>,
+}
+
+/// A component configuration.
+///
+/// See IEEE 1076-2008 section 3.4.3.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompCfg {
+    /// The configured instances.
+    pub insts: CfgInsts,
+    /// The component of the configured instances.
+    pub comp: CompDeclRef,
+    /// The entity the instances are bound to, and optionally its architecture.
+    /// If absent, the instances keep the binding they have by default.
+    pub entity: Option<(Spanned<EntityRef>, Option<Spanned<Name>>)>,
+    /// The configuration of the architecture the instances are bound to.
+    pub block: Option<BlockCfg>,
+}
+
+/// The instances a component configuration applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgInsts {
+    /// The instances with the given labels.
+    Labels(Vec<Spanned<Name>>),
+    /// The instances not configured by name, `others`.
+    Others,
+    /// All instances, `all`.
+    All,
+}
+
 #[derive(Debug)]
 pub struct IntfSignal {
     /// The name of this signal.
@@ -148,6 +213,16 @@ pub struct IntfSignal {
     pub init: Option<ExprRef>,
 }
 
+#[derive(Debug)]
+pub struct IntfConst {
+    /// The name of this constant.
+    pub name: Spanned<Name>,
+    /// The type of this constant.
+    pub ty: SubtypeIndRef,
+    /// The expression determining the default value of this constant.
+    pub init: Option<ExprRef>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum IntfSignalMode {
     In,
@@ -292,7 +367,9 @@ impl From<Range> for DiscreteRange {
 /// ```
 #[derive(Debug)]
 pub enum Range {
-    // Attr(AttrRef),
+    /// A range attribute of an array or a scalar type, e.g. `A'range` or
+    /// `T'reverse_range`, with an optional dimension.
+    Attr(Spanned<AttrPrefix>, Spanned<BoundAttr>, Option<ExprRef>),
     Immediate(Dir, ExprRef, ExprRef),
 }
 
@@ -388,6 +465,23 @@ pub enum TypeData {
     File(Spanned<TypeMarkRef>),
     /// A record type.
     Record(Vec<(Spanned<Name>, SubtypeIndRef)>),
+    /// A protected type, with the subprograms declared as its methods.
+    Protected(Vec<SubprogDeclRef>),
+}
+
+/// A protected type body.
+///
+/// See IEEE 1076-2008 section 5.6.3.
+#[derive(Debug)]
+pub struct ProtectedBody {
+    /// The parent scope.
+    pub parent: ScopeRef,
+    /// The name of the protected type.
+    pub name: Spanned<Name>,
+    /// The protected type declaration the body belongs to.
+    pub decl: TypeDeclRef,
+    /// The list of declarations in the body.
+    pub decls: Vec<DeclInSubprogRef>,
 }
 
 /// An enumeration literal as listed in a type declaration.
@@ -447,7 +541,7 @@ pub enum ExprData {
     // #[deprecated]
     Name(Def, Span),
     /// A resolved constant name.
-    ConstName(ConstDeclRef),
+    ConstName(ConstRef),
     /// A resolved signal name.
     SignalName(SignalRef),
     /// A resolved variable name.
@@ -469,6 +563,8 @@ pub enum ExprData {
     IntegerLiteral(ConstInt),
     /// A float literal.
     FloatLiteral(ConstFloat),
+    /// A physical literal, e.g. `1.5 ns`.
+    PhysicalLiteral(BigRational, Spanned<UnitRef>),
     /// A unary operator expression.
     Unary(Spanned<UnaryOp>, Vec<Spanned<Def>>, ExprRef),
     /// A binary operator expression.
@@ -485,6 +581,189 @@ pub enum ExprData {
     Cast(Spanned<TypeMarkRef>, ExprRef),
     /// A function call expression.
     Call(ExprRef, Spanned<AssocList>),
+    /// A call to one of a set of overloaded functions, e.g. `f(a, b)`.
+    FuncCall(Vec<Spanned<Def>>, Spanned<AssocList>),
+    /// A call to one of a set of overloaded methods of a protected type, e.g.
+    /// `v.f(a, b)`.
+    MethodCall(ExprRef, Vec<Spanned<Def>>, Spanned<AssocList>),
+    /// An indexed name, e.g. `a(1)`.
+    Index(ExprRef, Vec<ExprRef>),
+    /// A slice name, e.g. `a(1 to 2)`.
+    Slice(ExprRef, Spanned<DiscreteRange>),
+    /// A predefined attribute of a type applied to an argument, e.g.
+    /// `T'pos(X)`.
+    TypeAttr(Spanned<TypeMarkRef>, Spanned<TypeAttr>, ExprRef),
+    /// A predefined attribute of an array or a scalar type that yields a bound
+    /// or the length, with an optional dimension, e.g. `A'length(2)`.
+    BoundAttr(Spanned<AttrPrefix>, Spanned<BoundAttr>, Option<ExprRef>),
+    /// A predefined attribute of a signal, e.g. `S'event`.
+    SignalAttr(Spanned<SignalRef>, Spanned<SignalAttr>),
+    /// An external name, e.g. `<< signal .top.dut.state : BIT >>`.
+    ExternalName(ExternalName),
+}
+
+/// An external name.
+///
+/// The path is only resolved against the design hierarchy during
+/// elaboration. See IEEE 1076-2008 section 8.7.
+#[derive(Debug)]
+pub struct ExternalName {
+    /// The class of the object.
+    pub class: Spanned<ExternalClass>,
+    /// How the path is anchored in the design hierarchy.
+    pub kind: ExternalPathKind,
+    /// The names along the path, the last of which is the object.
+    pub path: Vec<Spanned<Name>>,
+    /// The subtype of the object.
+    pub subtype: SubtypeIndRef,
+}
+
+/// The prefix of a predefined attribute.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttrPrefix {
+    /// A type or subtype, e.g. the `T` in `T'left`.
+    Type(TypeMarkRef),
+    /// An object, e.g. the `A` in `A'length`.
+    Expr(ExprRef),
+}
+
+/// A predefined attribute of a type that maps values to values.
+///
+/// See IEEE 1076-2008 section 16.2.2.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TypeAttr {
+    /// The position number of a value, `T'pos(X)`.
+    Pos,
+    /// The value at a position number, `T'val(X)`.
+    Val,
+    /// The value at the next higher position, `T'succ(X)`.
+    Succ,
+    /// The value at the next lower position, `T'pred(X)`.
+    Pred,
+    /// The string representation of a value, `T'image(X)`.
+    Image,
+}
+
+impl TypeAttr {
+    /// Look up a predefined type attribute by its name.
+    pub fn from_name(name: Name) -> Option<TypeAttr> {
+        match &*name.as_str().to_lowercase() {
+            "pos" => Some(TypeAttr::Pos),
+            "val" => Some(TypeAttr::Val),
+            "succ" => Some(TypeAttr::Succ),
+            "pred" => Some(TypeAttr::Pred),
+            "image" => Some(TypeAttr::Image),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            TypeAttr::Pos => write!(f, "pos"),
+            TypeAttr::Val => write!(f, "val"),
+            TypeAttr::Succ => write!(f, "succ"),
+            TypeAttr::Pred => write!(f, "pred"),
+            TypeAttr::Image => write!(f, "image"),
+        }
+    }
+}
+
+/// A predefined attribute of an array or a scalar type that yields a bound,
+/// the length, or the range.
+///
+/// See IEEE 1076-2008 sections 16.2.2 and 16.2.3.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoundAttr {
+    /// The left bound, `A'left`.
+    Left,
+    /// The right bound, `A'right`.
+    Right,
+    /// The upper bound, `A'high`.
+    High,
+    /// The lower bound, `A'low`.
+    Low,
+    /// The number of elements, `A'length`.
+    Length,
+    /// The range, `A'range`.
+    Range,
+    /// The range in reverse direction, `A'reverse_range`.
+    ReverseRange,
+}
+
+impl BoundAttr {
+    /// Look up a predefined bound attribute by its name.
+    pub fn from_name(name: Name) -> Option<BoundAttr> {
+        match &*name.as_str().to_lowercase() {
+            "left" => Some(BoundAttr::Left),
+            "right" => Some(BoundAttr::Right),
+            "high" => Some(BoundAttr::High),
+            "low" => Some(BoundAttr::Low),
+            "length" => Some(BoundAttr::Length),
+            "range" => Some(BoundAttr::Range),
+            "reverse_range" => Some(BoundAttr::ReverseRange),
+            _ => None,
+        }
+    }
+
+    /// Check whether the attribute yields a range rather than a value.
+    pub fn is_range(self) -> bool {
+        match self {
+            BoundAttr::Range | BoundAttr::ReverseRange => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for BoundAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            BoundAttr::Left => write!(f, "left"),
+            BoundAttr::Right => write!(f, "right"),
+            BoundAttr::High => write!(f, "high"),
+            BoundAttr::Low => write!(f, "low"),
+            BoundAttr::Length => write!(f, "length"),
+            BoundAttr::Range => write!(f, "range"),
+            BoundAttr::ReverseRange => write!(f, "reverse_range"),
+        }
+    }
+}
+
+/// A predefined attribute of a signal.
+///
+/// See IEEE 1076-2008 section 16.2.4.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignalAttr {
+    /// Whether the signal changed in the current simulation cycle, `S'event`.
+    Event,
+    /// Whether the signal did not change in the current simulation cycle,
+    /// `S'stable`.
+    Stable,
+    /// The value of the signal before its last change, `S'last_value`.
+    LastValue,
+}
+
+impl SignalAttr {
+    /// Look up a predefined signal attribute by its name.
+    pub fn from_name(name: Name) -> Option<SignalAttr> {
+        match &*name.as_str().to_lowercase() {
+            "event" => Some(SignalAttr::Event),
+            "stable" => Some(SignalAttr::Stable),
+            "last_value" => Some(SignalAttr::LastValue),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SignalAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SignalAttr::Event => write!(f, "event"),
+            SignalAttr::Stable => write!(f, "stable"),
+            SignalAttr::LastValue => write!(f, "last_value"),
+        }
+    }
 }
 
 /// An object declaration.
@@ -579,6 +858,172 @@ pub struct ProcessStmt {
     pub stmts: Vec<SeqStmtRef>,
 }
 
+/// A PSL verification directive.
+///
+/// The boolean operands of the property are VHDL conditions. A directive
+/// without a clock of its own is evaluated on the default clock declared in
+/// the enclosing block, if any. See IEEE 1076-2008 section 11.9.
+#[derive(Debug)]
+pub struct PslDirective {
+    /// The scope within which the directive appears.
+    pub parent: ScopeRef,
+    /// The span of the directive.
+    pub span: Span,
+    /// The optional directive label.
+    pub label: Option<Spanned<Name>>,
+    /// Whether this is an `assert`, `assume`, `restrict`, or `cover`.
+    pub kind: PslDirectiveKind,
+    /// The verified property.
+    pub prop: PslProp,
+    /// The default clock.
+    pub clock: Option<ExprRef>,
+    /// The report message.
+    pub report: Option<ExprRef>,
+    /// The severity level.
+    pub severity: Option<ExprRef>,
+}
+
+/// A PSL property or sequence.
+///
+/// The names of declared properties and sequences are replaced by the
+/// property or sequence they stand for. See IEEE 1850-2010 section 6.
+#[derive(Debug)]
+pub enum PslProp {
+    /// A boolean condition.
+    Bool(ExprRef),
+    /// A braced sequence `{s}`.
+    Braced(Box<PslProp>),
+    /// An `always`, `never`, or `eventually!` property.
+    Unary(PslUnaryOp, Box<PslProp>),
+    /// A `next` property over a number of cycles.
+    Next {
+        strong: bool,
+        count: Option<u64>,
+        arg: Box<PslProp>,
+    },
+    /// An implication, `until` property, or sequence composition.
+    Binary(PslBinaryOp, Box<PslProp>, Box<PslProp>),
+    /// A repeated sequence, with the lower and optional upper count.
+    Repeat(Box<PslProp>, PslRepeatKind, Option<(u64, Option<u64>)>),
+    /// A property that is aborted when a condition holds.
+    Abort(Box<PslProp>, ExprRef),
+    /// A property or sequence evaluated on the edges of a clock.
+    Clocked(Box<PslProp>, ExprRef),
+}
+
+impl PslProp {
+    /// Collect the conditions in the property, in the order they appear.
+    pub fn conds(&self, into: &mut Vec<ExprRef>) {
+        match *self {
+            PslProp::Bool(id) => into.push(id),
+            PslProp::Braced(ref arg)
+            | PslProp::Unary(_, ref arg)
+            | PslProp::Next { ref arg, .. }
+            | PslProp::Repeat(ref arg, ..) => arg.conds(into),
+            PslProp::Binary(_, ref lhs, ref rhs) => {
+                lhs.conds(into);
+                rhs.conds(into);
+            }
+            PslProp::Abort(ref arg, id) | PslProp::Clocked(ref arg, id) => {
+                arg.conds(into);
+                into.push(id);
+            }
+        }
+    }
+}
+
+/// A component instantiation statement.
+///
+/// The instance of a component is represented by the entity that the
+/// component is bound to. A configuration declaration may bind the instance to
+/// a different entity during elaboration. See IEEE 1076-2008 section 11.7.
+#[derive(Debug)]
+pub struct CompInstStmt {
+    /// The scope within which the statement is declared.
+    pub parent: ScopeRef,
+    /// The instance label.
+    pub label: Option<Spanned<Name>>,
+    /// The instantiated component, if any.
+    pub comp: Option<Spanned<CompDeclRef>>,
+    /// The instantiated entity.
+    pub entity: Spanned<EntityRef>,
+    /// The architecture named in parentheses after the entity, if any.
+    pub arch: Option<Spanned<Name>>,
+    /// The actuals associated with the generics of the entity.
+    pub generic_map: Vec<(IntfConstRef, ExprRef)>,
+    /// The actuals associated with the ports of the entity. Ports that are
+    /// left open do not appear.
+    pub port_map: Vec<(IntfSignalRef, ExprRef)>,
+}
+
+/// A for generate statement.
+///
+/// See IEEE 1076-2008 section 11.8.
+#[derive(Debug)]
+pub struct ForGenStmt {
+    /// The scope within which the statement is declared.
+    pub parent: ScopeRef,
+    /// The span this statement covers.
+    pub span: Span,
+    /// The generate label.
+    pub label: Option<Spanned<Name>>,
+    /// The name of the generate parameter.
+    pub param: Spanned<Name>,
+    /// The range of the generate parameter.
+    pub range: Spanned<DiscreteRange>,
+    /// The generated statements.
+    pub body: GenBodyRef,
+}
+
+/// An if generate statement.
+///
+/// See IEEE 1076-2008 section 11.8.
+#[derive(Debug)]
+pub struct IfGenStmt {
+    /// The scope within which the statement is declared.
+    pub parent: ScopeRef,
+    /// The span this statement covers.
+    pub span: Span,
+    /// The generate label.
+    pub label: Option<Spanned<Name>>,
+    /// The condition and generated statements of each branch.
+    pub branches: Vec<(ExprRef, GenBodyRef)>,
+    /// The optional else branch.
+    pub otherwise: Option<GenBodyRef>,
+}
+
+/// A case generate statement.
+///
+/// See IEEE 1076-2008 section 11.8.
+#[derive(Debug)]
+pub struct CaseGenStmt {
+    /// The scope within which the statement is declared.
+    pub parent: ScopeRef,
+    /// The span this statement covers.
+    pub span: Span,
+    /// The generate label.
+    pub label: Option<Spanned<Name>>,
+    /// The expression being switched over.
+    pub switch: ExprRef,
+    /// The choices and generated statements of each alternative.
+    pub cases: Vec<(Spanned<Choices>, GenBodyRef)>,
+}
+
+/// The body of a generate statement.
+///
+/// See IEEE 1076-2008 section 11.8.
+#[derive(Debug)]
+pub struct GenBody {
+    /// The scope within which the generate statement is declared.
+    pub parent: ScopeRef,
+    /// The optional alternative label.
+    pub label: Option<Spanned<Name>>,
+    /// The list of declarations in the body.
+    pub decls: Vec<DeclInBlockRef>,
+    /// The list of statements in the body.
+    pub stmts: Vec<ConcStmtRef>,
+}
+
 /// A process sensitivity specification.
 ///
 /// See IEEE 1076-2008 section 11.3.
@@ -589,7 +1034,7 @@ pub enum ProcessSensitivity {
     /// The `all` sensitivity list.
     All,
     /// Explicitly enumerated signals.
-    List(Vec<Def>),
+    List(SensitivityList),
 }
 
 /// A sequential signal assignment.
@@ -860,10 +1305,13 @@ pub enum VarAssignKind {
 /// See IEEE 1076-2008 section 10.7.
 #[derive(Debug)]
 pub struct CallStmt {
-    /// The target subprogram.
-    pub subprog: SubprogRef,
-    /// The optional call parameters.
-    pub params: (),
+    /// The object whose method is called, if the procedure is a method of a
+    /// protected type.
+    pub object: Option<ExprRef>,
+    /// The overloaded procedures that may be called.
+    pub subprogs: Vec<Spanned<Def>>,
+    /// The call parameters.
+    pub params: Spanned<AssocList>,
 }
 
 /// An if statement.
@@ -340,7 +340,7 @@ fn unpack_units<'t>(
             let rel = if let Some(ref expr) = *expr {
                 let term = term_ctx.termify_expr(expr)?;
                 let (value, unit) = match term.value {
-                    Term::PhysLit(value, unit) => (value, unit),
+                    Term::PhysLit(value, unit) if value.is_integer() => (value.to_integer(), unit),
                     _ => {
                         ctx.emit(
                             DiagBuilder2::error(format!(
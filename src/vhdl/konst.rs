@@ -2,10 +2,14 @@
 
 //! This module implements constant value calculation for VHDL.
 
+use crate::builtin::{BOOLEAN_TYPE, STD_ULOGIC_TYPE};
+use crate::common::name::Name;
 pub use crate::hir::Dir;
+use crate::op::{BinaryOp, RelationalOp, UnaryOp};
 use crate::score::TypeDeclRef;
 use crate::ty::*;
-use num::BigInt;
+use num::{BigInt, FromPrimitive, Integer, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
 use std::fmt;
 
 /// A constant value.
@@ -14,7 +18,10 @@ pub enum Const {
     Null,
     Int(ConstInt),
     Float(ConstFloat),
+    Physical(ConstPhysical),
     Enum(ConstEnum),
+    Record(ConstRecord),
+    Array(ConstArray),
     IntRange(ConstIntRange),
     FloatRange(ConstFloatRange),
 }
@@ -25,19 +32,154 @@ impl Const {
             Const::Null => panic!("cannot negate null"),
             Const::Int(c) => Const::Int(c.negate()),
             Const::Float(c) => Const::Float(c.negate()),
+            Const::Physical(c) => Const::Physical(c.negate()),
             Const::Enum(_) => panic!("cannot negate enumeration literal"),
+            Const::Record(_) => panic!("cannot negate record"),
+            Const::Array(_) => panic!("cannot negate array"),
             Const::IntRange(_) => panic!("cannot negate integer range"),
             Const::FloatRange(_) => panic!("cannot negate float range"),
         }
     }
 
+    /// Compute the absolute value of a constant.
+    pub fn abs(self) -> Result<Const, ConstError> {
+        match self {
+            Const::Int(c) => Ok(Const::Int(ConstInt::new(c.ty, c.value.abs()))),
+            Const::Float(c) => Ok(Const::Float(ConstFloat::new(c.ty, c.value.abs()))),
+            Const::Physical(c) => Ok(Const::Physical(ConstPhysical::new(c.ty, c.value.abs()))),
+            _ => Err(ConstError::Unsupported),
+        }
+    }
+
+    /// Apply a binary operator to two constants.
+    ///
+    /// Relational operators yield a constant of type `BOOLEAN`; records and
+    /// arrays may only be compared for equality. Physical values may be scaled
+    /// by integers and reals, and dividing two physical values yields a
+    /// universal integer. Concatenation joins arrays and their elements.
+    pub fn binary(&self, op: BinaryOp, other: &Const) -> Result<Const, ConstError> {
+        if op == BinaryOp::Concat {
+            return Ok(self.concat(other).into());
+        }
+        let composite_eq = match (self, other) {
+            (&Const::Record(ref a), &Const::Record(ref b)) => Some(a == b),
+            (&Const::Array(ref a), &Const::Array(ref b)) => Some(a.elements == b.elements),
+            _ => None,
+        };
+        if let Some(eq) = composite_eq {
+            let result = match op {
+                BinaryOp::Rel(RelationalOp::Eq) => eq,
+                BinaryOp::Rel(RelationalOp::Neq) => !eq,
+                _ => return Err(ConstError::Unsupported),
+            };
+            return Ok(ConstEnum::new(BOOLEAN_TYPE.id, result as usize).into());
+        }
+        if let BinaryOp::Rel(rel) = op {
+            let ord = self.compare(other).ok_or(ConstError::Unsupported)?;
+            let result = relation_holds(rel, ord);
+            return Ok(ConstEnum::new(BOOLEAN_TYPE.id, result as usize).into());
+        }
+        match (self, other) {
+            (&Const::Int(ref a), &Const::Int(ref b)) => Ok(match op {
+                BinaryOp::Add => a.add(b)?,
+                BinaryOp::Sub => a.sub(b)?,
+                BinaryOp::Mul => a.mul(b)?,
+                BinaryOp::Div => a.div(b)?,
+                BinaryOp::Mod => a.modulo(b)?,
+                BinaryOp::Rem => a.rem(b)?,
+                BinaryOp::Pow => a.pow(b)?,
+                _ => return Err(ConstError::Unsupported),
+            }
+            .into()),
+            (&Const::Float(ref a), &Const::Float(ref b)) => Ok(match op {
+                BinaryOp::Add => a.add(b),
+                BinaryOp::Sub => a.sub(b),
+                BinaryOp::Mul => a.mul(b),
+                BinaryOp::Div => a.div(b).ok_or(ConstError::DivisionByZero)?,
+                _ => return Err(ConstError::Unsupported),
+            }
+            .into()),
+            (&Const::Float(ref a), &Const::Int(ref b)) if op == BinaryOp::Pow => {
+                let exp = b.value.to_i32().ok_or(ConstError::OutOfRange)?;
+                Ok(ConstFloat::new(a.ty.clone(), a.value.powi(exp)).into())
+            }
+            (&Const::Physical(ref a), &Const::Physical(ref b)) => Ok(match op {
+                BinaryOp::Add => a.add(b)?.into(),
+                BinaryOp::Sub => a.sub(b)?.into(),
+                BinaryOp::Div => a.div(b)?.into(),
+                BinaryOp::Mod => a.modulo(b)?.into(),
+                BinaryOp::Rem => a.rem(b)?.into(),
+                _ => return Err(ConstError::Unsupported),
+            }),
+            (&Const::Physical(ref a), &Const::Int(ref b)) => Ok(match op {
+                BinaryOp::Mul => a.mul_int(b)?,
+                BinaryOp::Div => a.div_int(b)?,
+                _ => return Err(ConstError::Unsupported),
+            }
+            .into()),
+            (&Const::Physical(ref a), &Const::Float(ref b)) => Ok(match op {
+                BinaryOp::Mul => a.mul_float(b)?,
+                BinaryOp::Div => a.div_float(b)?,
+                _ => return Err(ConstError::Unsupported),
+            }
+            .into()),
+            (&Const::Int(ref a), &Const::Physical(ref b)) if op == BinaryOp::Mul => {
+                Ok(b.mul_int(a)?.into())
+            }
+            (&Const::Float(ref a), &Const::Physical(ref b)) if op == BinaryOp::Mul => {
+                Ok(b.mul_float(a)?.into())
+            }
+            _ => Err(ConstError::Unsupported),
+        }
+    }
+
+    /// Concatenate two constants, each being an array or a single element.
+    ///
+    /// The result starts at the left bound of the left array, or at the left
+    /// bound of the right array if the left operand is an element.
+    pub fn concat(&self, other: &Const) -> ConstArray {
+        let (dir, left) = match (self, other) {
+            (&Const::Array(ref a), _) | (_, &Const::Array(ref a)) => (a.dir, a.left.clone()),
+            _ => (Dir::To, BigInt::zero()),
+        };
+        let mut elements = Vec::new();
+        for k in &[self, other] {
+            match **k {
+                Const::Array(ref a) => elements.extend(a.elements.iter().cloned()),
+                ref k => elements.push(k.clone()),
+            }
+        }
+        ConstArray::new(dir, left, elements)
+    }
+
+    /// Compare two constants.
+    ///
+    /// Returns `None` if the constants cannot be compared, for example because
+    /// they are of different kinds.
+    pub fn compare(&self, other: &Const) -> Option<Ordering> {
+        match (self, other) {
+            (&Const::Int(ref a), &Const::Int(ref b)) => Some(a.value.cmp(&b.value)),
+            (&Const::Float(ref a), &Const::Float(ref b)) => a.partial_cmp(b),
+            (&Const::Physical(ref a), &Const::Physical(ref b)) if a.ty.decl == b.ty.decl => {
+                Some(a.value.cmp(&b.value))
+            }
+            (&Const::Enum(ref a), &Const::Enum(ref b)) if a.decl == b.decl => {
+                Some(a.index.cmp(&b.index))
+            }
+            _ => None,
+        }
+    }
+
     /// Provide a textual description of the kind of constant.
     pub fn kind_desc(&self) -> &'static str {
         match *self {
             Const::Null => "null",
             Const::Int(_) => "integer",
             Const::Float(_) => "float",
+            Const::Physical(_) => "physical value",
             Const::Enum(_) => "enumeration literal",
+            Const::Record(_) => "record",
+            Const::Array(_) => "array",
             Const::IntRange(_) => "integer range",
             Const::FloatRange(_) => "float range",
         }
@@ -56,12 +198,30 @@ impl From<ConstFloat> for Const {
     }
 }
 
+impl From<ConstPhysical> for Const {
+    fn from(k: ConstPhysical) -> Const {
+        Const::Physical(k)
+    }
+}
+
 impl From<ConstEnum> for Const {
     fn from(k: ConstEnum) -> Const {
         Const::Enum(k)
     }
 }
 
+impl From<ConstRecord> for Const {
+    fn from(k: ConstRecord) -> Const {
+        Const::Record(k)
+    }
+}
+
+impl From<ConstArray> for Const {
+    fn from(k: ConstArray) -> Const {
+        Const::Array(k)
+    }
+}
+
 impl From<ConstIntRange> for Const {
     fn from(k: ConstIntRange) -> Const {
         Const::IntRange(k)
@@ -96,15 +256,278 @@ impl ConstInt {
     pub fn negate(self) -> ConstInt {
         ConstInt::new(self.ty, -self.value)
     }
+
+    /// Add two constants.
+    pub fn add(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        self.combine(other, &self.value + &other.value)
+    }
+
+    /// Subtract one constant from another.
+    pub fn sub(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        self.combine(other, &self.value - &other.value)
+    }
+
+    /// Multiply two constants.
+    pub fn mul(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        self.combine(other, &self.value * &other.value)
+    }
+
+    /// Divide one constant by another, rounding towards zero.
+    pub fn div(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.combine(other, &self.value / &other.value)
+    }
+
+    /// Compute the `mod` of two constants, which has the sign of the divisor.
+    pub fn modulo(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.combine(other, self.value.mod_floor(&other.value))
+    }
+
+    /// Compute the `rem` of two constants, which has the sign of the dividend.
+    pub fn rem(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.combine(other, &self.value % &other.value)
+    }
+
+    /// Raise a constant to the power of another.
+    pub fn pow(&self, other: &ConstInt) -> Result<ConstInt, ConstError> {
+        if other.value.is_negative() {
+            return Err(ConstError::NegativeExponent);
+        }
+        let exp = other.value.to_usize().ok_or(ConstError::OutOfRange)?;
+        ConstInt::checked(self.ty.clone(), num::pow::pow(self.value.clone(), exp))
+    }
+
+    /// Create the result of an operation on two constants, which has the
+    /// type of whichever of them is not universal.
+    fn combine(&self, other: &ConstInt, value: BigInt) -> Result<ConstInt, ConstError> {
+        ConstInt::checked(self.ty.clone().or_else(|| other.ty.clone()), value)
+    }
+
+    /// Create a new constant integer, making sure the value lies within its
+    /// type.
+    pub fn checked(ty: Option<IntTy>, value: BigInt) -> Result<ConstInt, ConstError> {
+        if let Some(ref ty) = ty {
+            let (lo, hi) = match ty.dir {
+                Dir::To => (&ty.left_bound, &ty.right_bound),
+                Dir::Downto => (&ty.right_bound, &ty.left_bound),
+            };
+            if value < *lo || value > *hi {
+                return Err(ConstError::OutOfRange);
+            }
+        }
+        Ok(ConstInt::new(ty, value))
+    }
+}
+
+/// An error that occurred while operating on constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstError {
+    /// The result lies outside the range of its type.
+    OutOfRange,
+    /// The divisor of a division, `mod`, or `rem` is zero.
+    DivisionByZero,
+    /// An integer is raised to a negative power.
+    NegativeExponent,
+    /// The operator cannot be applied to the constants.
+    Unsupported,
 }
 
 /// A constant float value.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ConstFloat {}
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstFloat {
+    /// The type of the constant. If `None`, the constant is assumed to be a
+    /// universal real.
+    pub ty: Option<FloatTy>,
+    /// The value of the constant.
+    pub value: f64,
+}
+
+// We have to explicitly implement this, since f64 by default does not, causing
+// the usual derive(Eq) to fail.
+impl Eq for ConstFloat {}
 
 impl ConstFloat {
+    /// Create a new constant float.
+    pub fn new(ty: Option<FloatTy>, value: f64) -> ConstFloat {
+        ConstFloat {
+            ty: ty,
+            value: value,
+        }
+    }
+
     pub fn negate(self) -> ConstFloat {
-        ConstFloat {}
+        ConstFloat::new(self.ty, -self.value)
+    }
+
+    /// Add two constants.
+    pub fn add(&self, other: &ConstFloat) -> ConstFloat {
+        self.combine(other, self.value + other.value)
+    }
+
+    /// Subtract one constant from another.
+    pub fn sub(&self, other: &ConstFloat) -> ConstFloat {
+        self.combine(other, self.value - other.value)
+    }
+
+    /// Multiply two constants.
+    pub fn mul(&self, other: &ConstFloat) -> ConstFloat {
+        self.combine(other, self.value * other.value)
+    }
+
+    /// Divide one constant by another.
+    ///
+    /// Returns `None` if the divisor is zero.
+    pub fn div(&self, other: &ConstFloat) -> Option<ConstFloat> {
+        if other.value == 0.0 {
+            None
+        } else {
+            Some(self.combine(other, self.value / other.value))
+        }
+    }
+
+    /// Create the result of an operation on two constants, which has the
+    /// type of whichever of them is not universal.
+    fn combine(&self, other: &ConstFloat, value: f64) -> ConstFloat {
+        ConstFloat::new(self.ty.clone().or_else(|| other.ty.clone()), value)
+    }
+}
+
+impl PartialOrd for ConstFloat {
+    fn partial_cmp(&self, other: &ConstFloat) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+/// A constant physical value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstPhysical {
+    /// The physical type of the constant.
+    pub ty: PhysicalTy,
+    /// The value of the constant, as a multiple of the primary unit.
+    pub value: BigInt,
+}
+
+impl ConstPhysical {
+    /// Create a new constant physical value.
+    pub fn new(ty: PhysicalTy, value: BigInt) -> ConstPhysical {
+        ConstPhysical {
+            ty: ty,
+            value: value,
+        }
+    }
+
+    /// Create a new constant physical value, making sure the value lies within
+    /// its type.
+    pub fn checked(ty: PhysicalTy, value: BigInt) -> Result<ConstPhysical, ConstError> {
+        let (lo, hi) = match ty.base.dir {
+            Dir::To => (&ty.base.left_bound, &ty.base.right_bound),
+            Dir::Downto => (&ty.base.right_bound, &ty.base.left_bound),
+        };
+        if value < *lo || value > *hi {
+            return Err(ConstError::OutOfRange);
+        }
+        Ok(ConstPhysical::new(ty, value))
+    }
+
+    pub fn negate(self) -> ConstPhysical {
+        ConstPhysical::new(self.ty, -self.value)
+    }
+
+    /// Add two constants of the same physical type.
+    pub fn add(&self, other: &ConstPhysical) -> Result<ConstPhysical, ConstError> {
+        self.same_ty(other)?;
+        self.check(&self.value + &other.value)
+    }
+
+    /// Subtract one constant from another of the same physical type.
+    pub fn sub(&self, other: &ConstPhysical) -> Result<ConstPhysical, ConstError> {
+        self.same_ty(other)?;
+        self.check(&self.value - &other.value)
+    }
+
+    /// Divide one constant by another of the same physical type, rounding
+    /// towards zero. The result is a universal integer.
+    pub fn div(&self, other: &ConstPhysical) -> Result<ConstInt, ConstError> {
+        self.same_ty(other)?;
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        Ok(ConstInt::new(None, &self.value / &other.value))
+    }
+
+    /// Compute the `mod` of two constants, which has the sign of the divisor.
+    pub fn modulo(&self, other: &ConstPhysical) -> Result<ConstPhysical, ConstError> {
+        self.same_ty(other)?;
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.check(self.value.mod_floor(&other.value))
+    }
+
+    /// Compute the `rem` of two constants, which has the sign of the dividend.
+    pub fn rem(&self, other: &ConstPhysical) -> Result<ConstPhysical, ConstError> {
+        self.same_ty(other)?;
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.check(&self.value % &other.value)
+    }
+
+    /// Multiply the constant by an integer.
+    pub fn mul_int(&self, other: &ConstInt) -> Result<ConstPhysical, ConstError> {
+        self.check(&self.value * &other.value)
+    }
+
+    /// Divide the constant by an integer, rounding towards zero.
+    pub fn div_int(&self, other: &ConstInt) -> Result<ConstPhysical, ConstError> {
+        if other.value.is_zero() {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.check(&self.value / &other.value)
+    }
+
+    /// Multiply the constant by a real, rounding to the nearest multiple of
+    /// the primary unit.
+    pub fn mul_float(&self, other: &ConstFloat) -> Result<ConstPhysical, ConstError> {
+        self.check_float(self.value.to_f64().unwrap_or(std::f64::INFINITY) * other.value)
+    }
+
+    /// Divide the constant by a real, rounding to the nearest multiple of the
+    /// primary unit.
+    pub fn div_float(&self, other: &ConstFloat) -> Result<ConstPhysical, ConstError> {
+        if other.value == 0.0 {
+            return Err(ConstError::DivisionByZero);
+        }
+        self.check_float(self.value.to_f64().unwrap_or(std::f64::INFINITY) / other.value)
+    }
+
+    /// Make sure another constant is of the same physical type.
+    fn same_ty(&self, other: &ConstPhysical) -> Result<(), ConstError> {
+        if self.ty.decl == other.ty.decl {
+            Ok(())
+        } else {
+            Err(ConstError::Unsupported)
+        }
+    }
+
+    /// Create a constant of the same type from a real multiple of the primary
+    /// unit, rounding to the nearest integer.
+    fn check_float(&self, value: f64) -> Result<ConstPhysical, ConstError> {
+        let value = BigInt::from_f64(value.round()).ok_or(ConstError::OutOfRange)?;
+        self.check(value)
+    }
+
+    /// Create a constant of the same type.
+    fn check(&self, value: BigInt) -> Result<ConstPhysical, ConstError> {
+        ConstPhysical::checked(self.ty.clone(), value)
     }
 }
 
@@ -125,6 +548,129 @@ impl ConstEnum {
             index: index,
         }
     }
+
+    /// Create a new constant enumeration value from a position number, making
+    /// sure the position lies within the inclusive range `lo` to `hi`.
+    pub fn checked(
+        decl: TypeDeclRef,
+        lo: usize,
+        hi: usize,
+        pos: &BigInt,
+    ) -> Result<ConstEnum, ConstError> {
+        match pos.to_usize() {
+            Some(index) if index >= lo && index <= hi => Ok(ConstEnum::new(decl, index)),
+            _ => Err(ConstError::OutOfRange),
+        }
+    }
+}
+
+/// A constant record value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstRecord {
+    /// The name and value of each field, in the order of the record type.
+    pub fields: Vec<(Name, Const)>,
+}
+
+impl ConstRecord {
+    /// Create a new constant record.
+    pub fn new(fields: Vec<(Name, Const)>) -> ConstRecord {
+        ConstRecord { fields: fields }
+    }
+
+    /// Get the value of a field.
+    pub fn field(&self, name: Name) -> Option<&Const> {
+        self.fields
+            .iter()
+            .find(|&&(n, _)| n == name)
+            .map(|&(_, ref k)| k)
+    }
+}
+
+/// A constant one-dimensional array value.
+///
+/// The elements of arrays with multiple dimensions are arrays themselves.
+/// Indices are position numbers, such that arrays indexed by enumeration types
+/// are addressed by the position of the literals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstArray {
+    /// The direction of the index range.
+    pub dir: Dir,
+    /// The index of the leftmost element.
+    pub left: BigInt,
+    /// The elements, from left to right.
+    pub elements: Vec<Const>,
+}
+
+impl ConstArray {
+    /// Create a new constant array.
+    pub fn new(dir: Dir, left: BigInt, elements: Vec<Const>) -> ConstArray {
+        ConstArray {
+            dir: dir,
+            left: left,
+            elements: elements,
+        }
+    }
+
+    /// The index of the rightmost element.
+    pub fn right(&self) -> BigInt {
+        let len = BigInt::from(self.elements.len());
+        match self.dir {
+            Dir::To => &self.left + len - 1,
+            Dir::Downto => &self.left - len + 1,
+        }
+    }
+
+    /// Get the element at an index.
+    pub fn get(&self, index: &BigInt) -> Result<&Const, ConstError> {
+        self.offset(index)
+            .and_then(|i| self.elements.get(i))
+            .ok_or(ConstError::OutOfRange)
+    }
+
+    /// Replace the element at an index.
+    pub fn set(&mut self, index: &BigInt, value: Const) -> Result<(), ConstError> {
+        let element = self
+            .offset(index)
+            .and_then(|i| self.elements.get_mut(i))
+            .ok_or(ConstError::OutOfRange)?;
+        *element = value;
+        Ok(())
+    }
+
+    /// Get the elements within an index range.
+    ///
+    /// The range must have the direction of the array, unless it is a null
+    /// range, which yields an empty array.
+    pub fn slice(&self, range: &ConstIntRange) -> Result<ConstArray, ConstError> {
+        let (left, right) = (&range.left_bound.value, &range.right_bound.value);
+        let null = match range.dir {
+            Dir::To => left > right,
+            Dir::Downto => left < right,
+        };
+        if null {
+            return Ok(ConstArray::new(range.dir, left.clone(), vec![]));
+        }
+        if range.dir != self.dir {
+            return Err(ConstError::Unsupported);
+        }
+        match (self.offset(left), self.offset(right)) {
+            (Some(l), Some(r)) if r < self.elements.len() => Ok(ConstArray::new(
+                self.dir,
+                left.clone(),
+                self.elements[l..=r].to_vec(),
+            )),
+            _ => Err(ConstError::OutOfRange),
+        }
+    }
+
+    /// Map an index to the offset of the element from the left.
+    fn offset(&self, index: &BigInt) -> Option<usize> {
+        match self.dir {
+            Dir::To => index - &self.left,
+            Dir::Downto => &self.left - index,
+        }
+        .to_usize()
+    }
 }
 
 /// A constant range value.
@@ -152,6 +698,257 @@ where
 pub type ConstIntRange = ConstRange<ConstInt>;
 pub type ConstFloatRange = ConstRange<ConstFloat>;
 
+impl ConstIntRange {
+    /// Iterate over the values in the range, from left to right.
+    ///
+    /// A null range yields no values.
+    pub fn positions(&self) -> impl Iterator<Item = BigInt> {
+        let dir = self.dir;
+        let right = self.right_bound.value.clone();
+        let step = match dir {
+            Dir::To => BigInt::from(1),
+            Dir::Downto => BigInt::from(-1),
+        };
+        std::iter::successors(Some(self.left_bound.value.clone()), move |pos| {
+            Some(pos + &step)
+        })
+        .take_while(move |pos| match dir {
+            Dir::To => *pos <= right,
+            Dir::Downto => *pos >= right,
+        })
+    }
+}
+
+/// Check whether a relational operator holds for two values of an ordering.
+fn relation_holds(rel: RelationalOp, ord: Ordering) -> bool {
+    match rel {
+        RelationalOp::Eq => ord == Ordering::Equal,
+        RelationalOp::Neq => ord != Ordering::Equal,
+        RelationalOp::Lt => ord == Ordering::Less,
+        RelationalOp::Leq => ord != Ordering::Greater,
+        RelationalOp::Gt => ord == Ordering::Greater,
+        RelationalOp::Geq => ord != Ordering::Less,
+    }
+}
+
+// ----- NUMERIC_STD -----------------------------------------------------------
+
+/// The position numbers of the `STD_ULOGIC` literals `'X'`, `'0'`, and `'1'`.
+const STD_ULOGIC_X: usize = 1;
+const STD_ULOGIC_0: usize = 2;
+const STD_ULOGIC_1: usize = 3;
+
+/// Create a constant `STD_ULOGIC` literal.
+fn std_ulogic(index: usize) -> Const {
+    ConstEnum::new(STD_ULOGIC_TYPE.id, index).into()
+}
+
+/// Interpret a `STD_ULOGIC` literal as a bit.
+///
+/// Returns `None` for metavalues, i.e. all literals except `'0'`, `'1'`, `'L'`,
+/// and `'H'`.
+fn std_ulogic_bit(k: &Const) -> Option<bool> {
+    match *k {
+        Const::Enum(ConstEnum { index: 2, .. }) | Const::Enum(ConstEnum { index: 6, .. }) => {
+            Some(false)
+        }
+        Const::Enum(ConstEnum { index: 3, .. }) | Const::Enum(ConstEnum { index: 7, .. }) => {
+            Some(true)
+        }
+        _ => None,
+    }
+}
+
+impl ConstArray {
+    /// Create a `NUMERIC_STD` number from its elements, indexed `N-1 downto 0`.
+    fn number(elements: Vec<Const>) -> ConstArray {
+        ConstArray::new(Dir::Downto, BigInt::from(elements.len()) - 1, elements)
+    }
+
+    /// Create a `NUMERIC_STD` number of a given length from a value.
+    ///
+    /// The value is truncated to the rightmost `len` bits of its two's
+    /// complement representation.
+    pub fn from_number(value: &BigInt, len: usize) -> ConstArray {
+        let modulus = BigInt::from(1) << len;
+        let mut value = value.mod_floor(&modulus);
+        let mut elements = vec![std_ulogic(STD_ULOGIC_0); len];
+        for element in elements.iter_mut().rev() {
+            if value.is_odd() {
+                *element = std_ulogic(STD_ULOGIC_1);
+            }
+            value = value >> 1usize;
+        }
+        ConstArray::number(elements)
+    }
+
+    /// Interpret the array as a `SIGNED` or `UNSIGNED` number.
+    ///
+    /// The leftmost element is the most significant bit. Returns `None` if the
+    /// array contains a metavalue.
+    pub fn to_number(&self, signed: bool) -> Option<BigInt> {
+        let mut value = BigInt::zero();
+        for element in &self.elements {
+            value = value * 2 + std_ulogic_bit(element)? as u8;
+        }
+        if signed && self.elements.first().and_then(std_ulogic_bit) == Some(true) {
+            value -= BigInt::from(1) << self.elements.len();
+        }
+        Some(value)
+    }
+}
+
+/// Determine the value and length of a `NUMERIC_STD` operand.
+///
+/// Integers assume the length of the other operand.
+fn numeric_std_operand(
+    k: &Const,
+    other: &Const,
+    signed: bool,
+) -> Result<(Option<BigInt>, usize), ConstError> {
+    match (k, other) {
+        (&Const::Array(ref a), _) => Ok((a.to_number(signed), a.elements.len())),
+        (&Const::Int(ref i), &Const::Array(ref b)) => Ok((Some(i.value.clone()), b.elements.len())),
+        _ => Err(ConstError::Unsupported),
+    }
+}
+
+/// Apply a `NUMERIC_STD` binary operator to two constants.
+///
+/// The operands are `SIGNED` or `UNSIGNED` numbers, one of which may be an
+/// integer. If an operand contains a metavalue, arithmetic operators yield a
+/// result of all `'X'` and relational operators yield `FALSE`.
+pub fn numeric_std_binary(
+    op: BinaryOp,
+    signed: bool,
+    lhs: &Const,
+    rhs: &Const,
+) -> Result<Const, ConstError> {
+    let (l, llen) = numeric_std_operand(lhs, rhs, signed)?;
+    let (r, rlen) = numeric_std_operand(rhs, lhs, signed)?;
+    if let BinaryOp::Rel(rel) = op {
+        let result = match (l, r) {
+            (Some(l), Some(r)) => relation_holds(rel, l.cmp(&r)),
+            _ => rel == RelationalOp::Neq,
+        };
+        return Ok(ConstEnum::new(BOOLEAN_TYPE.id, result as usize).into());
+    }
+    let len = match op {
+        BinaryOp::Add | BinaryOp::Sub => std::cmp::max(llen, rlen),
+        BinaryOp::Mul => llen + rlen,
+        BinaryOp::Div => llen,
+        BinaryOp::Mod | BinaryOp::Rem => rlen,
+        _ => return Err(ConstError::Unsupported),
+    };
+    if llen == 0 || rlen == 0 {
+        return Ok(ConstArray::number(vec![]).into());
+    }
+
+    // Truncate integer operands to the length of the other operand.
+    let wrap =
+        |v: Option<BigInt>, len| v.and_then(|v| ConstArray::from_number(&v, len).to_number(signed));
+    let (l, r) = match (wrap(l, llen), wrap(r, rlen)) {
+        (Some(l), Some(r)) => (l, r),
+        _ => return Ok(ConstArray::number(vec![std_ulogic(STD_ULOGIC_X); len]).into()),
+    };
+    let value = match op {
+        BinaryOp::Add => l + r,
+        BinaryOp::Sub => l - r,
+        BinaryOp::Mul => l * r,
+        _ if r.is_zero() => return Err(ConstError::DivisionByZero),
+        BinaryOp::Div => l / r,
+        BinaryOp::Mod => l.mod_floor(&r),
+        BinaryOp::Rem => l % r,
+        _ => unreachable!(),
+    };
+    Ok(ConstArray::from_number(&value, len).into())
+}
+
+/// Apply a `NUMERIC_STD` unary operator to a `SIGNED` number.
+pub fn numeric_std_unary(op: UnaryOp, arg: &ConstArray) -> Result<Const, ConstError> {
+    let len = arg.elements.len();
+    let value = match arg.to_number(true) {
+        Some(v) => v,
+        None => return Ok(ConstArray::number(vec![std_ulogic(STD_ULOGIC_X); len]).into()),
+    };
+    let value = match op {
+        UnaryOp::Abs => value.abs(),
+        UnaryOp::Neg => -value,
+        _ => return Err(ConstError::Unsupported),
+    };
+    Ok(ConstArray::from_number(&value, len).into())
+}
+
+/// Evaluate a call to one of the functions of package `NUMERIC_STD`.
+///
+/// `TO_INTEGER` maps numbers containing metavalues to 0.
+pub fn numeric_std_call(name: &str, signed: bool, args: &[&Const]) -> Result<Const, ConstError> {
+    let size = |k: &ConstInt| k.value.to_usize().ok_or(ConstError::OutOfRange);
+    let zero = std_ulogic(STD_ULOGIC_0);
+    let elements = match (name, args) {
+        ("TO_INTEGER", &[&Const::Array(ref a)]) => {
+            let value = a.to_number(signed).unwrap_or_else(BigInt::zero);
+            return Ok(ConstInt::new(None, value).into());
+        }
+        ("TO_UNSIGNED", &[&Const::Int(ref v), &Const::Int(ref s)])
+        | ("TO_SIGNED", &[&Const::Int(ref v), &Const::Int(ref s)]) => {
+            return Ok(ConstArray::from_number(&v.value, size(s)?).into());
+        }
+        ("RESIZE", &[&Const::Array(ref a), &Const::Int(ref s)]) => {
+            let (size, len) = (size(s)?, a.elements.len());
+            if len == 0 {
+                vec![zero; size]
+            } else if size > len {
+                // Extend with zeros, or with the sign bit.
+                let fill = if signed { a.elements[0].clone() } else { zero };
+                let mut elements = vec![fill; size - len];
+                elements.extend(a.elements.iter().cloned());
+                elements
+            } else if signed && size > 0 {
+                // Keep the sign bit and the rightmost bits.
+                let mut elements = vec![a.elements[0].clone()];
+                elements.extend(a.elements[len - size + 1..].iter().cloned());
+                elements
+            } else {
+                a.elements[len - size..].to_vec()
+            }
+        }
+        ("SHIFT_LEFT", &[&Const::Array(ref a), &Const::Int(ref c)]) => {
+            let count = std::cmp::min(size(c)?, a.elements.len());
+            let mut elements = a.elements[count..].to_vec();
+            elements.extend(vec![zero; count]);
+            elements
+        }
+        ("SHIFT_RIGHT", &[&Const::Array(ref a), &Const::Int(ref c)]) => {
+            let len = a.elements.len();
+            let count = std::cmp::min(size(c)?, len);
+            let fill = match a.elements.first() {
+                Some(sign) if signed => sign.clone(),
+                _ => zero,
+            };
+            let mut elements = vec![fill; count];
+            elements.extend(a.elements[..len - count].iter().cloned());
+            elements
+        }
+        ("ROTATE_LEFT", &[&Const::Array(ref a), &Const::Int(ref c)])
+        | ("ROTATE_RIGHT", &[&Const::Array(ref a), &Const::Int(ref c)]) => {
+            let mut elements = a.elements.clone();
+            if !elements.is_empty() {
+                let len = BigInt::from(elements.len());
+                let count = c.value.mod_floor(&len).to_usize().unwrap();
+                if name == "ROTATE_LEFT" {
+                    elements.rotate_left(count);
+                } else {
+                    elements.rotate_right(count);
+                }
+            }
+            elements
+        }
+        _ => return Err(ConstError::Unsupported),
+    };
+    Ok(ConstArray::number(elements).into())
+}
+
 // ----- FORMATTING ------------------------------------------------------------
 
 impl fmt::Display for Const {
@@ -160,13 +957,27 @@ impl fmt::Display for Const {
             Const::Null => write!(f, "null"),
             Const::Int(ref k) => k.fmt(f),
             Const::Float(ref k) => k.fmt(f),
+            Const::Physical(ref k) => k.fmt(f),
             Const::Enum(ref k) => k.fmt(f),
+            Const::Record(ref k) => k.fmt(f),
+            Const::Array(ref k) => k.fmt(f),
             Const::IntRange(ref k) => k.fmt(f),
             Const::FloatRange(ref k) => k.fmt(f),
         }
     }
 }
 
+impl fmt::Display for ConstError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConstError::OutOfRange => write!(f, "value out of range"),
+            ConstError::DivisionByZero => write!(f, "division by zero"),
+            ConstError::NegativeExponent => write!(f, "negative exponent"),
+            ConstError::Unsupported => write!(f, "operator not supported on these operands"),
+        }
+    }
+}
+
 impl fmt::Display for ConstInt {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.value.fmt(f)
@@ -179,9 +990,52 @@ impl fmt::Display for ConstEnum {
     }
 }
 
+impl fmt::Display for ConstRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, &(name, ref value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} => {}", name, value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for ConstArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, value) in self.elements.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        write!(f, ")")
+    }
+}
+
 impl fmt::Display for ConstFloat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "<float>")
+        self.value.fmt(f)
+    }
+}
+
+impl fmt::Display for ConstPhysical {
+    /// Formats the value in the largest unit of which it is a whole multiple.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = if self.value.is_zero() {
+            &self.ty.units[self.ty.primary]
+        } else {
+            self.ty
+                .units
+                .iter()
+                .filter(|u| self.value.is_multiple_of(&u.abs))
+                .max_by(|a, b| a.abs.cmp(&b.abs))
+                .unwrap_or(&self.ty.units[self.ty.primary])
+        };
+        write!(f, "{} {}", &self.value / &unit.abs, unit.name)
     }
 }
 
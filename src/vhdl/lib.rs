@@ -25,6 +25,9 @@ pub mod builtin;
 pub mod codegen;
 pub mod debug;
 pub mod defs;
+pub mod external;
+pub mod foreign;
+pub mod generic_env;
 pub mod hir;
 pub mod konst;
 pub mod konst2;
@@ -4,15 +4,19 @@
 
 use std::collections::HashMap;
 
+use num::ToPrimitive;
+
 use crate::common::errors::*;
 use crate::common::name::Name;
 use crate::common::score::Result;
 use crate::common::source::{Span, Spanned};
-use crate::common::Verbosity;
+use crate::common::{NodeId, Verbosity};
 
 use crate::add_ctx::AddContext;
 use crate::hir;
+use crate::konst::Const;
 use crate::make_ctx::MakeContext;
+use crate::op::BinaryOp;
 use crate::overload_resolver::*;
 use crate::score::*;
 use crate::syntax::ast;
@@ -186,7 +190,7 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
     tyctx: Option<&'ctx Ty>,
 ) -> Result<&'ctx Ty> {
     match hir.data {
-        hir::ExprData::ConstName(id) => tyc.ctx.lazy_typeval(id),
+        hir::ExprData::ConstName(id) => tyc.ctx.ty(id),
         hir::ExprData::SignalName(id) => tyc.ctx.ty(id),
         hir::ExprData::VarName(id) => tyc.ctx.lazy_typeval(id),
         hir::ExprData::FileName(id) => tyc.ctx.lazy_typeval(id),
@@ -247,7 +251,7 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
                                 Ty::Int(ref it) => Some(it.clone()),
                                 _ => None,
                             };
-                            match *at.element.as_ref() {
+                            match *tyc.ctx.deref_named_type(&at.element)? {
                                 Ty::Enum(ref et) => (
                                     index_ty,
                                     defs.iter()
@@ -303,6 +307,125 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
             // );
             // Err(())
         }
+        hir::ExprData::FloatLiteral(ref value) => {
+            if let Some(ref ty) = value.ty {
+                return Ok(tyc.ctx.intern_ty(ty.clone()));
+            } else {
+                return Ok(tyc.ctx.intern_ty(Ty::UniversalReal));
+            }
+        }
+        hir::ExprData::PhysicalLiteral(_, unit) => tyc.ctx.ty(unit.value.0),
+        hir::ExprData::TypeAttr(ref tm, attr, arg) => {
+            let ty = tyc.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value));
+            let arg_ty = tyc.lazy_typeval(arg)?;
+            let arg_span = tyc.ctx.span(arg).unwrap();
+            match *tyc.ctx.deref_named_type(ty)? {
+                Ty::Enum(..) | Ty::Int(..) | Ty::Physical(..) => (),
+                Ty::Float(..) if attr.value == hir::TypeAttr::Image => (),
+                ref other => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "attribute `{}` requires a discrete or physical type, but `{}` is a {}",
+                            attr.value,
+                            tm.span.extract(),
+                            other.kind_desc()
+                        ))
+                        .span(hir.span),
+                    );
+                    return Err(());
+                }
+            }
+            match attr.value {
+                // The argument of `T'val` is a position number.
+                hir::TypeAttr::Val => {
+                    match *tyc.ctx.deref_named_type(arg_ty)? {
+                        Ty::Int(..) | Ty::UniversalInt => (),
+                        _ => {
+                            tyc.emit(
+                                DiagBuilder2::error(format!(
+                                    "expected an integer position, but `{}` has type {}",
+                                    arg_span.extract(),
+                                    arg_ty
+                                ))
+                                .span(arg_span),
+                            );
+                            return Err(());
+                        }
+                    }
+                    Ok(ty)
+                }
+                hir::TypeAttr::Pos => {
+                    tyc.must_match(ty, arg_ty, arg_span);
+                    Ok(tyc.ctx.intern_ty(Ty::UniversalInt))
+                }
+                hir::TypeAttr::Succ | hir::TypeAttr::Pred => {
+                    tyc.must_match(ty, arg_ty, arg_span);
+                    Ok(ty)
+                }
+                hir::TypeAttr::Image => {
+                    tyc.must_match(ty, arg_ty, arg_span);
+                    Ok(tyc.ctx.intern_ty(STRING_TYPE.named_ty()))
+                }
+            }
+        }
+        hir::ExprData::BoundAttr(prefix, attr, dim) => {
+            let ty = match prefix.value {
+                hir::AttrPrefix::Type(tm) => tyc.ctx.intern_ty(Ty::Named(prefix.span.into(), tm)),
+                hir::AttrPrefix::Expr(id) => tyc.lazy_typeval(id)?,
+            };
+            let bound_ty = match *tyc.ctx.deref_named_type(ty)? {
+                Ty::Array(ref array) => {
+                    let index = match dim {
+                        Some(dim) => match *tyc.ctx.const_value(dim)? {
+                            Const::Int(ref k) => k.value.to_usize().and_then(|d| d.checked_sub(1)),
+                            _ => None,
+                        },
+                        None => Some(0),
+                    };
+                    match index.and_then(|i| array.indices.get(i)) {
+                        Some(index) => tyc.ctx.deref_named_type(index.ty())?,
+                        None => {
+                            let dim_span = tyc.ctx.span(dim.unwrap()).unwrap();
+                            tyc.emit(
+                                DiagBuilder2::error(format!(
+                                    "`{}` has no dimension `{}`",
+                                    prefix.span.extract(),
+                                    dim_span.extract()
+                                ))
+                                .span(dim_span),
+                            );
+                            return Err(());
+                        }
+                    }
+                }
+                Ty::Enum(..) | Ty::Int(..) | Ty::Physical(..) if dim.is_none() => ty,
+                ref other => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "attribute `{}` requires an array or a scalar type, but `{}` is a {}",
+                            attr.value,
+                            prefix.span.extract(),
+                            other.kind_desc()
+                        ))
+                        .span(hir.span),
+                    );
+                    return Err(());
+                }
+            };
+            match (attr.value, bound_ty) {
+                (hir::BoundAttr::Length, _) | (_, &Ty::Int(..)) => {
+                    Ok(tyc.ctx.intern_ty(Ty::UniversalInt))
+                }
+                _ => Ok(bound_ty),
+            }
+        }
+        hir::ExprData::SignalAttr(sig, attr) => match attr.value {
+            hir::SignalAttr::Event | hir::SignalAttr::Stable => {
+                Ok(tyc.ctx.intern_ty(BOOLEAN_TYPE.named_ty()))
+            }
+            hir::SignalAttr::LastValue => tyc.ctx.ty(sig.value),
+        },
+        hir::ExprData::ExternalName(ref ext) => tyc.ctx.lazy_typeval(ext.subtype),
         hir::ExprData::Qualified(ref tm, expr) => {
             let ty = tyc.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value));
             let expr_ty = tyc.lazy_typeval(expr)?;
@@ -323,80 +446,425 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
             tyc.must_cast(ty, expr_ty, tyc.ctx.span(expr).unwrap());
             Ok(ty)
         }
+        hir::ExprData::Select(prefix, name) => {
+            let prefix_ty = tyc.lazy_typeval(prefix)?;
+            let record_ty = match *tyc.ctx.deref_named_type(prefix_ty)? {
+                Ty::Record(ref ty) => ty,
+                _ => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a record and has no field `{}`",
+                            tyc.ctx.span(prefix).unwrap().extract(),
+                            name.value
+                        ))
+                        .span(hir.span)
+                        .add_note(format!(
+                            "`{}` has type {}",
+                            tyc.ctx.span(prefix).unwrap().extract(),
+                            prefix_ty
+                        )),
+                    );
+                    return Err(());
+                }
+            };
+            let index = match name.value {
+                ResolvableName::Ident(n) => record_ty.lookup.get(&n).cloned(),
+                _ => None,
+            };
+            match index {
+                Some(index) => Ok(tyc
+                    .ctx
+                    .intern_ty(record_ty.fields[index].1.as_ref().clone())),
+                None => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a field of {}",
+                            name.value, prefix_ty
+                        ))
+                        .span(name.span),
+                    );
+                    Err(())
+                }
+            }
+        }
+        hir::ExprData::Index(prefix, ref indices) => {
+            let array_ty = typeval_array_prefix(tyc, prefix, hir)?;
+            if indices.len() != array_ty.indices.len() {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` has {} indices, but the array has {} dimensions",
+                        hir.span.extract(),
+                        indices.len(),
+                        array_ty.indices.len()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+            let mut had_fails = false;
+            for (&index, array_index) in indices.iter().zip(&array_ty.indices) {
+                let ty = array_index.ty();
+                tyc.ctx.set_type_context(index, ty);
+                let index_ty = match tyc.lazy_typeval(index) {
+                    Ok(t) => t,
+                    Err(()) => {
+                        had_fails = true;
+                        continue;
+                    }
+                };
+                // Integer types carry no identity beyond their range, so any
+                // integer may index an integer dimension.
+                match (
+                    tyc.ctx.deref_named_type(ty)?,
+                    tyc.ctx.deref_named_type(index_ty)?,
+                ) {
+                    (&Ty::Int(..), &Ty::Int(..)) => (),
+                    _ => {
+                        if !tyc.must_match(ty, index_ty, tyc.ctx.span(index).unwrap()) {
+                            had_fails = true;
+                        }
+                    }
+                }
+            }
+            if had_fails {
+                return Err(());
+            }
+            Ok(array_ty.element.as_ref())
+        }
+        hir::ExprData::Slice(prefix, ref range) => {
+            let array_ty = typeval_array_prefix(tyc, prefix, hir)?;
+            if array_ty.indices.len() != 1 {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` slices an array with {} dimensions",
+                        hir.span.extract(),
+                        array_ty.indices.len()
+                    ))
+                    .span(hir.span)
+                    .add_note(
+                        "Only one-dimensional arrays can be sliced. See IEEE 1076-2008 \
+                         section 8.5.",
+                    ),
+                );
+                return Err(());
+            }
+            let range_ty = tyc.type_from_discrete_range(range.as_ref())?;
+            Ok(tyc.ctx.intern_ty(
+                ArrayTy::new(
+                    vec![ArrayIndex::Constrained(Box::new(range_ty.clone()))],
+                    array_ty.element.clone(),
+                )
+                .with_base(array_ty.base),
+            ))
+        }
         hir::ExprData::Aggregate(id) => {
             tyc.ctx
                 .set_type_context(id, TypeCtx::Inherit(expr_id.into()));
             tyc.ctx.lazy_typeval(id)
         }
-        hir::ExprData::Unary(op, ref defs, arg) => {
-            // Assemble an overload resolution requirement based on the
-            // operator's types.
-            let req = OverloadReq::Subprog(SignatureReq {
-                return_type: match tyctx {
-                    Some(tyctx) => TypeReq::One(tyctx),
-                    None => TypeReq::Any,
-                },
-                positional: vec![TypeReq::One(tyc.lazy_typeval(arg)?)],
-                named: HashMap::new(),
-            });
-
-            // Resolve the overload.
-            let def = resolve_overloads(tyc.ctx, defs, &req, hir.span)?;
-            debugln!("unary operator `{}` resolved to {:?}", op.value, def);
-
+        hir::ExprData::Unary(_, ref defs, arg) => {
+            typeval_overloaded(tyc, expr_id, hir, defs, &[arg], tyctx)
+        }
+        // The predefined concatenation operators of array types are not
+        // declared, so concatenations are checked directly.
+        hir::ExprData::Binary(op, _, lhs, rhs) if op.value == BinaryOp::Concat => {
+            typeval_concat(tyc, hir, lhs, rhs, tyctx)
+        }
+        hir::ExprData::Binary(_, ref defs, lhs, rhs) => {
+            typeval_overloaded(tyc, expr_id, hir, defs, &[lhs, rhs], tyctx)
+        }
+        hir::ExprData::FuncCall(ref defs, ref args) => {
+            let positional = positional_args(tyc, args)?;
+            typeval_overloaded(tyc, expr_id, hir, defs, &positional, tyctx)
+        }
+        // The methods have been looked up in the protected type of the object.
+        hir::ExprData::MethodCall(object, ref defs, ref args) => {
+            tyc.lazy_typeval(object)?;
+            let positional = positional_args(tyc, args)?;
+            typeval_overloaded(tyc, expr_id, hir, defs, &positional, tyctx)
+        }
+        _ => {
             tyc.emit(
                 DiagBuilder2::bug(format!(
-                    "typeval for unary operation `{}` not implemented",
+                    "typeval for expression `{}` not implemented",
                     hir.span.extract()
                 ))
                 .span(hir.span),
             );
-            debugln!("Defs are {:?}", defs);
+            debugln!("It is a {:#?}", hir.data);
             Err(())
         }
-        hir::ExprData::Binary(op, ref defs, lhs, rhs) => {
-            // Assemble an overload resolution requirement based on the
-            // operator's types.
-            let req = OverloadReq::Subprog(SignatureReq {
-                return_type: match tyctx {
-                    Some(tyctx) => TypeReq::One(tyctx),
-                    None => TypeReq::Any,
-                },
-                positional: vec![
-                    TypeReq::One(tyc.lazy_typeval(lhs)?),
-                    TypeReq::One(tyc.lazy_typeval(rhs)?),
-                ],
-                named: HashMap::new(),
-            });
-
-            // Resolve the overload.
-            let def = resolve_overloads(tyc.ctx, defs, &req, hir.span)?;
-            debugln!("binary operator `{}` resolved to {:?}", op.value, def);
+    }
+}
 
+/// Extract the arguments of a subprogram call, which must all be positional.
+pub(crate) fn positional_args<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    args: &Spanned<hir::AssocList>,
+) -> Result<Vec<ExprRef>> {
+    let mut positional = Vec::new();
+    for arg in &args.value {
+        match (arg.formal, &arg.actual.value) {
+            (None, &hir::AssocActual::Expr(id)) => positional.push(id),
+            _ => {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a positional argument",
+                        arg.span.extract()
+                    ))
+                    .span(arg.span)
+                    .add_note("Only positional arguments are supported in subprogram calls"),
+                );
+                return Err(());
+            }
+        }
+    }
+    Ok(positional)
+}
+
+/// Evaluate the type of an operator or function call by resolving its
+/// overloads.
+///
+/// The resolved definition is recorded in the scoreboard, such that constant
+/// evaluation and code generation can pick the matching implementation.
+fn typeval_overloaded<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    id: ExprRef,
+    hir: &hir::Expr,
+    defs: &[Spanned<Def>],
+    args: &[ExprRef],
+    tyctx: Option<&'ctx Ty>,
+) -> Result<&'ctx Ty> {
+    let return_type = match tyctx {
+        Some(tyctx) => TypeReq::One(tyctx),
+        None => TypeReq::Any,
+    };
+    let ty = resolve_call(tyc, id.into(), hir.span, defs, args, return_type)?;
+    match *ty {
+        Ty::Subprog(SubprogTy {
+            ret: Some(ref ret), ..
+        }) => Ok(ret),
+        _ => {
             tyc.emit(
-                DiagBuilder2::bug(format!(
-                    "typeval for binary operation `{}` not implemented",
-                    hir.span.extract()
-                ))
-                .span(hir.span),
+                DiagBuilder2::error(format!("`{}` does not return a value", hir.span.extract()))
+                    .span(hir.span),
             );
-            debugln!("Defs are {:?}", defs);
             Err(())
         }
+    }
+}
+
+/// Check a procedure call by resolving the overloads of the procedure.
+///
+/// Only the procedures among the definitions are considered, since functions
+/// cannot be called as statements. See IEEE 1076-2008 section 10.7.
+pub(crate) fn typeck_proc_call<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    id: CallStmtRef,
+    span: Span,
+    defs: &[Spanned<Def>],
+    args: &[ExprRef],
+) -> Result<()> {
+    let mut procs = Vec::new();
+    for &def in defs {
+        let ty = match def.value {
+            Def::Subprog(id) => tyc.ctx.subprog_ty(id)?,
+            Def::BuiltinOp(id) => tyc.ctx.lazy_typeval(id)?,
+            _ => continue,
+        };
+        if let Ty::Subprog(SubprogTy { ret: None, .. }) = *ty {
+            procs.push(def);
+        }
+    }
+    if procs.is_empty() {
+        tyc.emit(
+            DiagBuilder2::error(format!("`{}` is not a procedure", span.extract()))
+                .span(span)
+                .add_note(
+                    "Only procedures can be called as statements. See IEEE 1076-2008 \
+                     section 10.7.",
+                ),
+        );
+        return Err(());
+    }
+    resolve_call(tyc, id.into(), span, &procs, args, TypeReq::Any)?;
+    Ok(())
+}
+
+/// Resolve the overloads of an operator or subprogram call and determine the
+/// type of the called subprogram.
+fn resolve_call<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    id: NodeId,
+    span: Span,
+    defs: &[Spanned<Def>],
+    args: &[ExprRef],
+    return_type: TypeReq<'ctx>,
+) -> Result<&'ctx Ty> {
+    // Assemble an overload resolution requirement based on the types of the
+    // arguments.
+    let req = OverloadReq::Subprog(SignatureReq {
+        return_type,
+        positional: args
+            .iter()
+            .map(|&arg| typeval_operand(tyc, arg))
+            .collect::<Result<_>>()?,
+        named: HashMap::new(),
+    });
+
+    // Resolve the overload.
+    let def = resolve_overloads(tyc.ctx, defs, &req, span)?;
+    debugln!("`{}` resolved to {:?}", span.extract(), def);
+    tyc.ctx.sb.overload_table.borrow_mut().insert(id, def);
+    let ty = match def.value {
+        Def::BuiltinOp(id) => tyc.ctx.lazy_typeval(id)?,
+        Def::Subprog(id) => tyc.ctx.subprog_ty(id)?,
+        _ => unreachable!(),
+    };
+
+    // The overloaded enumeration literals among the arguments assume the type
+    // of the corresponding argument of the resolved overload.
+    if let Ty::Subprog(ref subprog_ty) = *ty {
+        for (&arg, arg_ty) in args.iter().zip(subprog_ty.args.iter()) {
+            if let TypeReq::Many(..) = typeval_operand(tyc, arg)? {
+                tyc.ctx.set_type_context(arg, &arg_ty.ty);
+            }
+        }
+    }
+    Ok(ty)
+}
+
+/// Determine the type requirement an argument imposes on the overloads of an
+/// operator or function.
+///
+/// Overloaded enumeration literals such as `'1'` admit the type of each of
+/// their definitions, since their type is only known once the overload has
+/// been resolved. See IEEE 1076-2008 section 12.5.
+fn typeval_operand<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    arg: ExprRef,
+) -> Result<TypeReq<'ctx>> {
+    match tyc.ctx.lazy_hir(arg)?.data {
+        hir::ExprData::EnumName(ref defs) if defs.len() > 1 => Ok(TypeReq::Many(
+            defs.iter()
+                .map(|def| tyc.ctx.intern_ty(EnumTy::new(def.value.0)))
+                .collect(),
+        )),
+        _ => Ok(TypeReq::One(tyc.lazy_typeval(arg)?)),
+    }
+}
+
+/// Evaluate the type of the prefix of an indexed or slice name, which must be
+/// an array.
+fn typeval_array_prefix<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    prefix: ExprRef,
+    hir: &hir::Expr,
+) -> Result<&'ctx ArrayTy> {
+    let prefix_ty = tyc.lazy_typeval(prefix)?;
+    match *tyc.ctx.deref_named_type(prefix_ty)? {
+        Ty::Array(ref ty) => Ok(ty),
         _ => {
+            let prefix_span = tyc.ctx.span(prefix).unwrap();
             tyc.emit(
-                DiagBuilder2::bug(format!(
-                    "typeval for expression `{}` not implemented",
-                    hir.span.extract()
+                DiagBuilder2::error(format!(
+                    "`{}` is not an array and cannot be indexed or sliced",
+                    prefix_span.extract()
                 ))
-                .span(hir.span),
+                .span(hir.span)
+                .add_note(format!(
+                    "`{}` has type {}",
+                    prefix_span.extract(),
+                    prefix_ty
+                )),
             );
-            debugln!("It is a {:#?}", hir.data);
             Err(())
         }
     }
 }
 
+/// Evaluate the type of a concatenation.
+///
+/// Each operand is either an array or an element of the array type, which is
+/// taken from the first array operand or from the type context.
+fn typeval_concat<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    hir: &hir::Expr,
+    lhs: ExprRef,
+    rhs: ExprRef,
+    tyctx: Option<&'ctx Ty>,
+) -> Result<&'ctx Ty> {
+    let is_array = |ty| match tyc.ctx.deref_named_type(ty) {
+        Ok(&Ty::Array(ref ty)) => ty.indices.len() == 1,
+        _ => false,
+    };
+
+    // Aggregates and nested concatenations take the unconstrained array type
+    // from context, since their length is independent of the result's.
+    if let Some(&Ty::Array(ref ty)) = tyctx
+        .filter(|&ty| is_array(ty))
+        .and_then(|ty| tyc.ctx.deref_named_type(ty).ok())
+    {
+        let indices = ty
+            .indices
+            .iter()
+            .map(|index| ArrayIndex::Unbounded(Box::new(index.ty().clone())))
+            .collect();
+        let base = tyc.ctx.base_type(tyctx.unwrap())?;
+        let ty = tyc
+            .ctx
+            .intern_ty(ArrayTy::new(indices, ty.element.clone()).with_base(base));
+        for &id in &[lhs, rhs] {
+            match tyc.ctx.lazy_hir(id)?.data {
+                hir::ExprData::Aggregate(..) => tyc.ctx.set_type_context(id, ty),
+                hir::ExprData::Binary(op, ..) if op.value == BinaryOp::Concat => {
+                    tyc.ctx.set_type_context(id, ty)
+                }
+                _ => (),
+            }
+        }
+    }
+    let lhs_ty = tyc.lazy_typeval(lhs)?;
+    let rhs_ty = tyc.lazy_typeval(rhs)?;
+    let ty = if is_array(lhs_ty) {
+        lhs_ty
+    } else if is_array(rhs_ty) {
+        rhs_ty
+    } else {
+        match tyctx {
+            Some(ty) if is_array(ty) => ty,
+            _ => {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot determine the array type of concatenation `{}`",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+        }
+    };
+    let element = match *tyc.ctx.deref_named_type(ty)? {
+        Ty::Array(ref ty) => ty.element.as_ref(),
+        _ => unreachable!(),
+    };
+    let mut ok = true;
+    for &(id, op_ty) in &[(lhs, lhs_ty), (rhs, rhs_ty)] {
+        let span = tyc.ctx.span(id).unwrap();
+        ok &= if is_array(op_ty) {
+            tyc.must_match(ty, op_ty, span)
+        } else {
+            tyc.must_match(element, op_ty, span)
+        };
+    }
+    if ok {
+        Ok(ty)
+    } else {
+        Err(())
+    }
+}
+
 /// Evaluate the type of a record aggregate.
 pub fn typeval_record_aggregate<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
     tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
@@ -497,6 +965,22 @@ pub fn typeval_record_aggregate<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx:
             mapping.insert(type_index, FieldIndex::Others);
         }
     }
+
+    // Make sure every field of the record has been assigned.
+    for (index, &(name, _)) in record_ty.fields.iter().enumerate() {
+        if !mapping.contains_key(&index) {
+            tyc.emit(
+                DiagBuilder2::error(format!(
+                    "field `{}` of {} is not assigned in aggregate `{}`",
+                    name,
+                    tyctx,
+                    hir.span.extract()
+                ))
+                .span(hir.span),
+            );
+            had_fails = true;
+        }
+    }
     debugln!("aggregate: record type mapping {:?}", mapping);
 
     // Forward the type context and check the type of elements.
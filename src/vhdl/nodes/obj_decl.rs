@@ -32,6 +32,11 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             .iter()
             .map(|dn| {
                 let (mk, id, scope) = self.make::<ConstDeclRef>(dn.span);
+                self.ctx.define(
+                    scope,
+                    Spanned::new(dn.name.into(), dn.span),
+                    Def::Const(id.into()),
+                )?;
                 mk.lower_to_hir(Box::new(move |_sbc| {
                     Ok(hir::Decl {
                         parent: scope,
@@ -6,12 +6,13 @@ use std::iter::FromIterator;
 
 use crate::common::errors::*;
 use crate::common::name::Name;
-use crate::common::score::Result;
+use crate::common::score::{NodeRef, Result};
 use crate::common::source::Spanned;
 use crate::common::util::{HasDesc, HasSpan};
 
 use crate::add_ctx::AddContext;
 use crate::hir;
+use crate::nodes::expr::{positional_args, typeck_proc_call};
 use crate::score::*;
 use crate::syntax::ast;
 use crate::term::TermContext;
@@ -164,10 +165,8 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             })
         }));
         mk.typeck(Box::new(move |tyc| {
-            let _hir = tyc.ctx.lazy_hir(id)?;
-            // tyc.must_match(tyc.ctx.lazy_typeval(hir.stmt.cond), tyc.ctx.builtin_boolean_type());
-            // hir.stmt.report.map(|id| tyc.must_match(tyc.ctx.lazy_typeval(id), tyc.ctx.builtin_string_type()));
-            // hir.stmt.severity.map(|id| tyc.must_match(tyc.ctx.lazy_typeval(id), tyc.ctx.builtin_severity_type()));
+            let hir: &hir::Stmt<hir::AssertStmt> = tyc.ctx.lazy_hir(id)?;
+            tyc.typeck_report(Some(hir.stmt.cond), hir.stmt.report, hir.stmt.severity);
             Ok(())
         }));
         Ok(mk.finish())
@@ -201,17 +200,26 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             })
         }));
         mk.typeck(Box::new(move |tyc| {
-            let _hir = tyc.ctx.lazy_hir(id)?;
-            // tyc.must_match(tyc.ctx.lazy_typeval(hir.stmt.report), tyc.ctx.builtin_string_type());
-            // hir.stmt.severity.map(|id| tyc.must_match(tyc.ctx.lazy_typeval(id), tyc.ctx.builtin_severity_type()));
+            let hir: &hir::Stmt<hir::ReportStmt> = tyc.ctx.lazy_hir(id)?;
+            tyc.typeck_report(None, Some(hir.stmt.report), hir.stmt.severity);
             Ok(())
         }));
         Ok(mk.finish())
     }
 
     /// Add a sig_assign statement.
+    ///
+    /// The statement is lowered to HIR by the scoreboard, from the AST stored
+    /// here.
     pub fn add_sig_assign_stmt(&self, stmt: &'ast ast::Stmt) -> Result<SigAssignStmtRef> {
-        self.unimp(stmt)
+        match stmt.data {
+            ast::AssignStmt { .. } => {
+                let id = SigAssignStmtRef::alloc();
+                self.ctx.set_ast(id, (self.scope, stmt));
+                Ok(id)
+            }
+            _ => self.unimp(stmt),
+        }
     }
 
     /// Add a var_assign statement.
@@ -343,7 +351,30 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
 
     /// Add a call statement.
     pub fn add_call_stmt(&self, stmt: &'ast ast::Stmt) -> Result<CallStmtRef> {
-        self.unimp(stmt)
+        let (mk, id, scope) = self.make(stmt.span);
+        let name = match stmt.data {
+            ast::InstOrCallStmt { ref name, .. } => name,
+            _ => unreachable!(),
+        };
+        mk.lower_to_hir(Box::new(move |sbc| {
+            let ctx = TermContext::new(sbc, scope);
+            let term = ctx.termify_compound_name(name)?;
+            Ok(hir::Stmt {
+                parent: scope,
+                span: stmt.span,
+                label: stmt.label,
+                stmt: ctx.term_to_call_stmt(term)?,
+            })
+        }));
+        mk.typeck(Box::new(move |tyc| {
+            let hir: &hir::Stmt<hir::CallStmt> = tyc.ctx.lazy_hir(id)?;
+            if let Some(object) = hir.stmt.object {
+                tyc.lazy_typeval(object)?;
+            }
+            let args = positional_args(tyc, &hir.stmt.params)?;
+            typeck_proc_call(tyc, id, hir.stmt.params.span, &hir.stmt.subprogs, &args)
+        }));
+        Ok(mk.finish())
     }
 
     /// Add an if statement.
@@ -462,7 +493,12 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             _ => unreachable!(),
         };
         mk.lower_to_hir(Box::new(move |sbc| {
-            // TODO: Make a subscope here! No clue how...
+            // The loop parameter is a constant visible only within the loop.
+            // See IEEE 1076-2008 section 10.10.
+            sbc.subscope(id.into(), scope);
+            if let ast::LoopScheme::For(name, _) = *scheme {
+                sbc.define(id.into(), name.map_into(), Def::Const(id.into()))?;
+            }
             let ctx = AddContext::new(sbc, scope);
             let scheme = (|| match *scheme {
                 ast::LoopScheme::Loop => Ok(hir::LoopScheme::Loop),
@@ -472,7 +508,9 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                     ctx.add_discrete_range(range)?,
                 )),
             })();
-            let stmts = ctx.add_seq_stmts(&body.stmts, "a loop body");
+            let stmts = ctx
+                .with_scope(id.into())
+                .add_seq_stmts(&body.stmts, "a loop body");
             let (scheme, stmts) = (scheme?, stmts?);
             Ok(hir::Stmt {
                 parent: scope,
@@ -486,7 +524,10 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }));
         mk.typeck(Box::new(move |tyc| {
             let hir = tyc.ctx.lazy_hir(id)?;
-            // TODO: Check the types of the loop scheme.
+            // TODO: Check the type of the loop condition.
+            if let hir::LoopScheme::For(..) = hir.stmt.scheme {
+                tyc.ctx.ty(ConstRef::Loop(id))?;
+            }
             tyc.typeck_slice(&hir.stmt.stmts);
             Ok(())
         }));
@@ -6,21 +6,80 @@ use crate::common::errors::*;
 use crate::common::name::Name;
 use crate::common::score::{NodeRef, Result};
 use crate::common::source::Spanned;
+use crate::common::util::{HasDesc, HasSpan};
 
 use num::BigInt;
 
 use crate::add_ctx::AddContext;
+use crate::builtin::{
+    enum_type_builtins, equality_builtins, float_type_builtins, integer_type_builtins,
+    ordering_builtins, physical_type_builtins,
+};
 use crate::hir;
 use crate::score::*;
 use crate::syntax::ast;
+use crate::syntax::lexer::token::Literal;
 use crate::term::{Term, TermContext};
+use crate::ty::Ty;
 
 impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Add a type declaration.
     pub fn add_type_decl(&self, decl: &'ast ast::TypeDecl) -> Result<TypeDeclRef> {
+        // Protected type bodies are unpacked separately where they may appear.
+        if let Some(Spanned {
+            value: ast::ProtectedBody(..),
+            span,
+        }) = decl.data
+        {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "protected type body `{}` cannot appear here",
+                    decl.name.value
+                ))
+                .span(span)
+                .add_note(
+                    "A protected type body may only appear where a subprogram body may appear. \
+                     See IEEE 1076-2008 section 5.6.3.",
+                ),
+            );
+            return Err(());
+        }
         let (mk, id, scope) = self.make(decl.span);
         self.ctx
             .define(scope, decl.name.map_into(), Def::Type(id))?;
+        // The literals of an enumeration type and the units of a physical type
+        // are declared alongside the type.
+        match decl.data {
+            Some(Spanned {
+                value: ast::EnumType(ref elems),
+                ..
+            }) => {
+                for (i, elem) in elems.value.iter().enumerate() {
+                    if let ast::NameExpr(ref name) = elem.expr.data {
+                        if let Ok(lit_name) = self.ctx.resolvable_from_primary_name(&name.primary) {
+                            self.ctx
+                                .define(scope, lit_name, Def::Enum(EnumRef(id, i)))?;
+                        }
+                    }
+                }
+            }
+            Some(Spanned {
+                value: ast::RangeType(_, Some(ref units)),
+                ..
+            }) => {
+                for (i, &(unit_name, _)) in units.iter().enumerate() {
+                    self.ctx.define(
+                        scope,
+                        Spanned::new(unit_name.name.into(), unit_name.span),
+                        Def::Unit(UnitRef(id, i)),
+                    )?;
+                }
+            }
+            _ => (),
+        }
+        if let Some(ref data) = decl.data {
+            self.add_implicit_ops(id, decl.name, &data.value, scope)?;
+        }
         mk.lower_to_hir(Box::new(move |sbc| {
             let ctx = AddContext::new(sbc, scope);
             Ok(hir::TypeDecl {
@@ -30,12 +89,63 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             })
         }));
         mk.typeck(Box::new(move |tyc| {
-            let _hir = tyc.ctx.lazy_hir(id)?;
+            let hir: &hir::TypeDecl = tyc.ctx.lazy_hir(id)?;
+            if let Some(Spanned {
+                value: hir::TypeData::Protected(ref methods),
+                ..
+            }) = hir.data
+            {
+                tyc.typeck_slice(methods);
+            }
             Ok(())
         }));
         Ok(mk.finish())
     }
 
+    /// Add the predefined operators of a type declaration.
+    ///
+    /// The operators are declared alongside the type and refer to it by name,
+    /// such that their types are known before the type itself is. Which
+    /// operators exist is determined from the kind of type definition. See IEEE
+    /// 1076-2008 section 9.2.
+    fn add_implicit_ops(
+        &self,
+        id: TypeDeclRef,
+        name: Spanned<Name>,
+        data: &'ast ast::TypeData,
+        scope: ScopeRef,
+    ) -> Result<()> {
+        let ty = Ty::Named(name.value.into(), id.into());
+        let mut ops = Vec::new();
+        match *data {
+            ast::EnumType(..) => enum_type_builtins(&ty, &mut ops),
+            ast::RangeType(_, Some(..)) => physical_type_builtins(&ty, &mut ops),
+            ast::RangeType(ref range, None) if is_real_range(range) => {
+                float_type_builtins(&ty, &mut ops)
+            }
+            ast::RangeType(_, None) => integer_type_builtins(&ty, &mut ops),
+            ast::ArrayType(ref indices, _) => {
+                equality_builtins(&ty, &mut ops);
+                if indices.value.len() == 1 {
+                    ordering_builtins(&ty, &mut ops);
+                }
+            }
+            ast::RecordType(..) | ast::AccessType(..) => equality_builtins(&ty, &mut ops),
+            ast::FileType(..) | ast::ProtectedType(..) | ast::ProtectedBody(..) => (),
+        }
+        for op in ops {
+            self.ctx
+                .define(scope, Spanned::new(op.name, name.span), op.def)?;
+            let ty = self.ctx.intern_ty(op.ty.unwrap());
+            self.ctx
+                .sb
+                .typeval_table
+                .borrow_mut()
+                .insert(op.def.into(), Ok(ty));
+        }
+        Ok(())
+    }
+
     /// Add a type definition.
     pub fn add_type_data(
         &self,
@@ -124,7 +234,9 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                             let rel = if let Some(ref expr) = *expr {
                                 let term = term_ctx.termify_expr(expr)?;
                                 let (value, unit) = match term.value {
-                                    Term::PhysLit(value, unit) => (value, unit),
+                                    Term::PhysLit(value, unit) if value.is_integer() => {
+                                        (value.to_integer(), unit)
+                                    }
                                     _ => {
                                         self.emit(
                                             DiagBuilder2::error(format!(
@@ -272,11 +384,78 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                 hir::TypeData::Record(fields)
             }
 
-            ast::ProtectedType(..) => {
-                self.emit(DiagBuilder2::fatal("protected types not implemented").span(name.span));
-                return Err(());
+            // The methods of a protected type are declared in the scope of the
+            // type, but are only visible as selected names of its objects.
+            ast::ProtectedType(ref items) => {
+                let mut methods = Vec::new();
+                let mut had_fails = false;
+                for item in items {
+                    match *item {
+                        ast::DeclItem::SubprogDecl(ref decl) => match decl.data {
+                            ast::SubprogData::Decl => {
+                                let id = SubprogDeclRef::alloc();
+                                self.ctx.set_ast(id, (self.scope, decl));
+                                methods.push(id);
+                            }
+                            ast::SubprogData::Body { .. } => {
+                                self.emit(
+                                    DiagBuilder2::error(format!(
+                                        "subprogram body cannot appear in the declaration of \
+                                         protected type `{}`",
+                                        name.value
+                                    ))
+                                    .span(decl.span)
+                                    .add_note(
+                                        "The bodies of the methods go into the protected type \
+                                         body. See IEEE 1076-2008 section 5.6.3.",
+                                    ),
+                                );
+                                had_fails = true;
+                            }
+                            ast::SubprogData::Inst { .. } => {
+                                self.emit(
+                                    DiagBuilder2::error(
+                                        "subprogram instantiations in protected types are not \
+                                         supported",
+                                    )
+                                    .span(decl.span),
+                                );
+                                had_fails = true;
+                            }
+                        },
+                        ast::DeclItem::UseClause(..) => (),
+                        ref wrong => {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "a {} cannot appear in a protected type declaration",
+                                    wrong.desc()
+                                ))
+                                .span(wrong.human_span()),
+                            );
+                            had_fails = true;
+                        }
+                    }
+                }
+                if had_fails {
+                    return Err(());
+                }
+                hir::TypeData::Protected(methods)
             }
+
+            // Caught in `add_type_decl`.
+            ast::ProtectedBody(..) => unreachable!(),
         };
         Ok(Spanned::new(td, data.span))
     }
 }
+
+/// Check whether the bounds of a range are real literals, in which case the
+/// range defines a floating-point type rather than an integer type.
+fn is_real_range(range: &ast::Expr) -> bool {
+    match range.data {
+        ast::BinaryExpr(_, ref lb, ref rb) => is_real_range(lb) || is_real_range(rb),
+        ast::UnaryExpr(_, ref arg) => is_real_range(arg),
+        ast::LitExpr(Literal::Abstract(_, _, Some(_), _), _) => true,
+        _ => false,
+    }
+}
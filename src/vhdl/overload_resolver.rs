@@ -9,7 +9,7 @@ use std::collections::{HashMap, HashSet};
 use crate::common::errors::*;
 use crate::common::name::Name;
 use crate::common::score::Result;
-use crate::common::source::{Span, Spanned};
+use crate::common::source::{Span, Spanned, INVALID_SPAN};
 
 use crate::score::{Def, ScoreContext};
 use crate::ty::Ty;
@@ -29,10 +29,13 @@ pub enum OverloadReq<'ctx> {
 
 impl<'ctx> OverloadReq<'ctx> {
     /// Check if a type matches this requirement.
-    pub fn matches(&self, ty: &Ty) -> bool {
+    ///
+    /// Returns the number of types that match exactly, or `None` if the type
+    /// does not match at all.
+    pub fn matches(&self, ctx: &ScoreContext, ty: &Ty) -> Result<Option<usize>> {
         match *self {
-            OverloadReq::Enum(ref req) => req.matches(ty),
-            OverloadReq::Subprog(ref req) => req.matches(ty),
+            OverloadReq::Enum(ref req) => Ok(req.matches(ctx, ty)?.map(Fit::score)),
+            OverloadReq::Subprog(ref req) => req.matches(ctx, ty),
         }
     }
 }
@@ -50,34 +53,46 @@ pub struct SignatureReq<'ctx> {
 
 impl<'ctx> SignatureReq<'ctx> {
     /// Check if a type matches this requirement.
-    pub fn matches(&self, ty: &Ty) -> bool {
+    ///
+    /// Returns the number of argument and return types that match exactly, or
+    /// `None` if the type does not match at all.
+    pub fn matches(&self, ctx: &ScoreContext, ty: &Ty) -> Result<Option<usize>> {
         if let Ty::Subprog(ref ty) = *ty {
-            if !self.return_type.is_any()
-                && !ty
-                    .ret
-                    .as_ref()
-                    .map(|t| self.return_type.matches(t))
-                    .unwrap_or(false)
-            {
-                debugln!("return type mismatch: {} vs {:?}", ty, self);
-                return false;
+            let mut score = 0;
+            if !self.return_type.is_any() {
+                match ty.ret {
+                    Some(ref ret) => match self.return_type.matches(ctx, ret)? {
+                        Some(fit) => score += fit.score(),
+                        None => {
+                            debugln!("return type mismatch: {} vs {:?}", ty, self);
+                            return Ok(None);
+                        }
+                    },
+                    None => {
+                        debugln!("return type mismatch: {} vs {:?}", ty, self);
+                        return Ok(None);
+                    }
+                }
             }
             if self.positional.len() > ty.args.len() {
                 debugln!("positional length mismatch: {} vs {:?}", ty, self);
-                return false;
+                return Ok(None);
             }
             let mut arg_iter = ty.args.iter();
             for req in &self.positional {
                 let arg = arg_iter.next().unwrap(); // never fails due to above check
-                if !req.matches(&arg.ty) {
-                    debugln!(
-                        "positional mismatch: {} vs {:?} in {} vs {:?}",
-                        arg.ty,
-                        req,
-                        ty,
-                        self
-                    );
-                    return false;
+                match req.matches(ctx, &arg.ty)? {
+                    Some(fit) => score += fit.score(),
+                    None => {
+                        debugln!(
+                            "positional mismatch: {} vs {:?} in {} vs {:?}",
+                            arg.ty,
+                            req,
+                            ty,
+                            self
+                        );
+                        return Ok(None);
+                    }
                 }
             }
             let mut unhandled_names: HashSet<_> = self.named.keys().collect();
@@ -91,32 +106,39 @@ impl<'ctx> SignatureReq<'ctx> {
                             ty,
                             self
                         );
-                        return false;
+                        return Ok(None);
                     }
                 };
                 let req = match self.named.get(&name) {
                     Some(req) => req,
                     None => {
                         debugln!("unknown named arg: {} in {} vs {:?}", name, ty, self);
-                        return false;
+                        return Ok(None);
                     }
                 };
-                if !req.matches(&arg.ty) {
-                    debugln!(
-                        "named mismatch `{}`: {} vs {:?} in {} vs {:?}",
-                        name,
-                        arg.ty,
-                        req,
-                        ty,
-                        self
-                    );
-                    return false;
+                match req.matches(ctx, &arg.ty)? {
+                    Some(fit) => score += fit.score(),
+                    None => {
+                        debugln!(
+                            "named mismatch `{}`: {} vs {:?} in {} vs {:?}",
+                            name,
+                            arg.ty,
+                            req,
+                            ty,
+                            self
+                        );
+                        return Ok(None);
+                    }
                 }
                 unhandled_names.remove(&name);
             }
-            unhandled_names.is_empty()
+            Ok(if unhandled_names.is_empty() {
+                Some(score)
+            } else {
+                None
+            })
         } else {
-            false
+            Ok(None)
         }
     }
 }
@@ -142,11 +164,17 @@ impl<'ctx> TypeReq<'ctx> {
     }
 
     /// Check if a type matches this requirement.
-    pub fn matches(&self, ty: &Ty) -> bool {
+    pub fn matches(&self, ctx: &ScoreContext, ty: &Ty) -> Result<Option<Fit>> {
         match *self {
-            TypeReq::Any => true,
-            TypeReq::One(req) => are_types_matching(req, ty),
-            TypeReq::Many(ref reqs) => reqs.iter().any(|&req| are_types_matching(req, ty)),
+            TypeReq::Any => Ok(Some(Fit::Exact)),
+            TypeReq::One(req) => are_types_matching(ctx, req, ty),
+            TypeReq::Many(ref reqs) => {
+                let mut best = None;
+                for &req in reqs {
+                    best = best.max(are_types_matching(ctx, req, ty)?);
+                }
+                Ok(best)
+            }
         }
     }
 }
@@ -157,64 +185,194 @@ impl<'ctx> Default for TypeReq<'ctx> {
     }
 }
 
+/// How well a type matches a requirement.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Fit {
+    /// The type matches after an implicit conversion, for example of a
+    /// universal integer or between integer subtypes.
+    Implicit,
+    /// The type matches exactly.
+    Exact,
+}
+
+impl Fit {
+    /// The contribution of this fit to the score of an overload.
+    fn score(self) -> usize {
+        match self {
+            Fit::Implicit => 0,
+            Fit::Exact => 1,
+        }
+    }
+}
+
 /// Check if two types match.
-fn are_types_matching(a: &Ty, b: &Ty) -> bool {
-    match (a, b) {
-        (&Ty::Named(_, ia), &Ty::Named(_, ib)) => ia == ib,
-        (a, b) => a == b,
+///
+/// Types match exactly if they have the same base type. Integer types, whose
+/// subtypes are not tracked, match each other and the universal integer
+/// implicitly. The same holds for floating-point types and the universal real.
+/// Anonymous arrays match arrays of the same element type implicitly.
+fn are_types_matching(ctx: &ScoreContext, a: &Ty, b: &Ty) -> Result<Option<Fit>> {
+    match (ctx.base_type(a)?, ctx.base_type(b)?) {
+        (Some(ia), Some(ib)) if ia == ib => return Ok(Some(Fit::Exact)),
+        _ => (),
+    }
+    let (a_flat, b_flat) = (ctx.deref_named_type(a)?, ctx.deref_named_type(b)?);
+    Ok(match (a_flat, b_flat) {
+        (&Ty::Int(..), &Ty::Int(..))
+        | (&Ty::Int(..), &Ty::UniversalInt)
+        | (&Ty::UniversalInt, &Ty::Int(..))
+        | (&Ty::Float(..), &Ty::Float(..))
+        | (&Ty::Float(..), &Ty::UniversalReal)
+        | (&Ty::UniversalReal, &Ty::Float(..)) => Some(Fit::Implicit),
+        (&Ty::Enum(ref ea), &Ty::Enum(ref eb)) if ea.decl == eb.decl => Some(Fit::Exact),
+        (&Ty::Physical(ref pa), &Ty::Physical(ref pb)) if pa.decl == pb.decl => Some(Fit::Exact),
+        (&Ty::Array(ref aa), &Ty::Array(ref ab))
+            if ctx.base_type(a)?.is_none() || ctx.base_type(b)?.is_none() =>
+        {
+            if aa.indices.len() == ab.indices.len()
+                && ctx.deref_named_type(&aa.element)? == ctx.deref_named_type(&ab.element)?
+            {
+                Some(Fit::Implicit)
+            } else {
+                None
+            }
+        }
+        (a_flat, b_flat)
+            if a_flat == b_flat && ctx.base_type(a)?.is_none() && ctx.base_type(b)?.is_none() =>
+        {
+            Some(Fit::Exact)
+        }
+        _ => None,
+    })
+}
+
+/// Check if two subprogram types are homographs.
+///
+/// This is the case if they have the same number of arguments, and the
+/// arguments and the return type have the same base types. See IEEE 1076-2008
+/// section 4.5.1.
+fn are_homographs(ctx: &ScoreContext, a: &Ty, b: &Ty) -> Result<bool> {
+    let (a, b) = match (a, b) {
+        (&Ty::Subprog(ref a), &Ty::Subprog(ref b)) => (a, b),
+        _ => return Ok(false),
+    };
+    if a.args.len() != b.args.len() {
+        return Ok(false);
     }
+    for (aa, ab) in a.args.iter().zip(b.args.iter()) {
+        if are_types_matching(ctx, &aa.ty, &ab.ty)? != Some(Fit::Exact) {
+            return Ok(false);
+        }
+    }
+    Ok(match (&a.ret, &b.ret) {
+        (&Some(ref ra), &Some(ref rb)) => are_types_matching(ctx, ra, rb)? == Some(Fit::Exact),
+        (&None, &None) => true,
+        _ => false,
+    })
 }
 
-/// Reduce overloaded definitions.
-pub fn reduce_overloads(
-    ctx: &ScoreContext,
+/// Determine the type of the overloadable definitions.
+///
+/// Definitions which cannot apply to the requirement are omitted.
+fn overload_types<'ctx>(
+    ctx: &ScoreContext<'_, '_, '_, 'ctx>,
     defs: &[Spanned<Def>],
     req: &OverloadReq,
-    _span: Span,
-) -> Result<Vec<Spanned<Def>>> {
-    debugln!("resolving overloaded {:?} with requirement {:?}", defs, req);
-
-    // Filter the definitions by kind such that only those remain which have any
-    // chance of applying to the requirement.
-    let filtered: Vec<_> = defs
-        .iter()
-        .enumerate()
-        .filter(|&(_, def)| match (def.value, req) {
+) -> Result<Vec<(Spanned<Def>, &'ctx Ty)>> {
+    defs.iter()
+        .filter(|def| match (def.value, req) {
             (Def::Enum(..), &OverloadReq::Enum(..)) => true,
             (Def::BuiltinOp(..), &OverloadReq::Subprog(..)) => true,
             (Def::Subprog(..), &OverloadReq::Subprog(..)) => true,
             _ => false,
         })
-        .collect();
-    if filtered.is_empty() {
-        return Ok(vec![]);
-    }
-
-    // Determine the type of the applicable definitions.
-    let types = filtered
-        .iter()
-        .map(|&(i, def)| {
+        .map(|&def| {
             Ok((
-                i,
+                def,
                 match def.value {
                     Def::Enum(id) => ctx.lazy_typeval(id)?,
                     Def::BuiltinOp(id) => ctx.lazy_typeval(id)?,
-                    Def::Subprog(id) => ctx.lazy_typeval(id)?,
+                    Def::Subprog(id) => ctx.subprog_ty(id)?,
                     _ => unreachable!(),
                 },
             ))
         })
-        .collect::<Vec<Result<_>>>()
-        .into_iter()
-        .collect::<Result<Vec<_>>>()?;
-
-    // Match each of the types against the requirement.
-    let matched = types
-        .into_iter()
-        .filter_map(|(i, ty)| if req.matches(ty) { Some(defs[i]) } else { None })
+        .collect()
+}
+
+/// Reduce overloaded definitions.
+///
+/// Keeps the definitions whose type matches the requirement most closely.
+/// Explicitly declared subprograms hide the predefined operators which are
+/// homographs of them. See IEEE 1076-2008 section 12.3.
+pub fn reduce_overloads(
+    ctx: &ScoreContext,
+    defs: &[Spanned<Def>],
+    req: &OverloadReq,
+    _span: Span,
+) -> Result<Vec<Spanned<Def>>> {
+    debugln!("resolving overloaded {:?} with requirement {:?}", defs, req);
+
+    // Match each of the types against the requirement and keep the ones that
+    // match most closely.
+    let mut matched = Vec::new();
+    let mut best = 0;
+    for (def, ty) in overload_types(ctx, defs, req)? {
+        let score = match req.matches(ctx, ty)? {
+            Some(score) => score,
+            None => continue,
+        };
+        if score > best {
+            matched.clear();
+            best = score;
+        }
+        if score == best {
+            matched.push((def, ty));
+        }
+    }
+
+    // Drop the predefined operators that are hidden by an explicit
+    // declaration.
+    let explicit: Vec<_> = matched
+        .iter()
+        .filter(|(def, _)| match def.value {
+            Def::Subprog(..) => true,
+            _ => false,
+        })
+        .map(|&(_, ty)| ty)
         .collect();
+    let mut reduced = Vec::new();
+    for (def, ty) in matched {
+        if let Def::BuiltinOp(..) = def.value {
+            let mut hidden = false;
+            for &other in &explicit {
+                hidden |= are_homographs(ctx, ty, other)?;
+            }
+            if hidden {
+                debugln!("{:?} is hidden by an explicit declaration", def);
+                continue;
+            }
+        }
+        reduced.push(def);
+    }
+
+    Ok(reduced)
+}
 
-    Ok(matched)
+/// Add the candidates of an overload resolution to a diagnostic.
+fn add_candidates(
+    ctx: &ScoreContext,
+    mut diag: DiagBuilder2,
+    defs: &[Spanned<Def>],
+    req: &OverloadReq,
+) -> Result<DiagBuilder2> {
+    for (def, ty) in overload_types(ctx, defs, req)? {
+        diag = diag.add_note(format!("Candidate: {}", ty));
+        if def.span != INVALID_SPAN {
+            diag = diag.span(def.span);
+        }
+    }
+    Ok(diag)
 }
 
 /// Resolve overloaded definitions to exactly one unambiguous definition.
@@ -226,15 +384,14 @@ pub fn resolve_overloads(
 ) -> Result<Spanned<Def>> {
     let reduced = reduce_overloads(ctx, defs, req, span)?;
     if reduced.is_empty() {
-        ctx.emit(
-            DiagBuilder2::error("no overload applies").span(span), // TODO: Show available implementations.
-        );
+        let diag =
+            DiagBuilder2::error(format!("no overload of `{}` applies", span.extract())).span(span);
+        ctx.emit(add_candidates(ctx, diag, defs, req)?);
         debugln!("available definitions: {:#?}", defs);
         Err(())
     } else if reduced.len() > 1 {
-        ctx.emit(
-            DiagBuilder2::error(format!("`{}` is ambiguous", span.extract())).span(span), // TODO: Show implementations that matched.
-        );
+        let diag = DiagBuilder2::error(format!("`{}` is ambiguous", span.extract())).span(span);
+        ctx.emit(add_candidates(ctx, diag, &reduced, req)?);
         debugln!("matching definitions: {:#?}", reduced);
         Err(())
     } else {
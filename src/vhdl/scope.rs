@@ -76,7 +76,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
         self.with_scope(scope, |scope| match def {
             // Handle overloadable cases.
-            Def::Enum(_) => {
+            Def::Enum(_) | Def::BuiltinOp(_) => {
                 scope
                     .defs
                     .entry(name.value)
@@ -2,7 +2,9 @@
 
 //! This module implements constant value computation.
 
+use crate::external::ExternalTarget;
 use crate::score::*;
+use num::{BigInt, BigRational, ToPrimitive, Zero};
 
 // Calculate the constant value of an expression.
 impl_make!(self, id: ExprRef => &Const {
@@ -14,14 +16,61 @@ impl_make!(self, id: ExprRef => &Const {
         // Float literals.
         hir::ExprData::FloatLiteral(ref c) => self.intern_const(c.clone()),
 
+        // Physical literals, which are rounded to the nearest multiple of the
+        // primary unit.
+        hir::ExprData::PhysicalLiteral(ref value, unit) => {
+            let ty = match *self.ty(unit.value.0)? {
+                Ty::Physical(ref ty) => ty,
+                _ => unreachable!(),
+            };
+            let scale = BigRational::from_integer(ty.units[unit.value.1].abs.clone());
+            match ConstPhysical::checked(ty.clone(), (value * scale).round().to_integer()) {
+                Ok(k) => self.intern_const(k),
+                Err(e) => {
+                    self.emit(
+                        DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            }
+        }
+
         // Unary operators.
         hir::ExprData::Unary(op, _, arg_id) => {
             let arg = self.const_value(arg_id)?;
+            if let Some(decl) = self.subprog_overload(id)? {
+                return self.const_call(decl, &[arg], hir.span);
+            }
+            if let Const::Array(ref k) = *arg {
+                if self.numeric_std_overload(id)?.is_some() {
+                    match numeric_std_unary(op.value, k) {
+                        Ok(k) => return Ok(self.intern_const(k)),
+                        Err(e) => {
+                            self.emit(
+                                DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                                .span(hir.span)
+                            );
+                            return Err(());
+                        }
+                    }
+                }
+            }
             // TODO: Lookup the type of the current expression and perform
             // the operation accordingly.
             match op.value {
                 UnaryOp::Pos => arg,
                 UnaryOp::Neg => self.intern_const(arg.clone().negate()),
+                UnaryOp::Abs => match arg.clone().abs() {
+                    Ok(k) => self.intern_const(k),
+                    Err(e) => {
+                        self.emit(
+                            DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                            .span(hir.span)
+                        );
+                        return Err(());
+                    }
+                },
                 _ => {
                     self.emit(
                         DiagBuilder2::bug(format!("constant unary operator {:?} not yet implemented", op.value))
@@ -32,6 +81,78 @@ impl_make!(self, id: ExprRef => &Const {
             }
         }
 
+        // Binary operators.
+        hir::ExprData::Binary(op, _, lhs_id, rhs_id) => {
+            let lhs = self.const_value(lhs_id)?;
+            let rhs = self.const_value(rhs_id)?;
+            if let Some(decl) = self.subprog_overload(id)? {
+                return self.const_call(decl, &[lhs, rhs], hir.span);
+            }
+            // TODO: Lookup the type of the current expression and perform
+            // the operation accordingly.
+            let numeric = match (lhs, rhs) {
+                (&Const::Array(_), _) | (_, &Const::Array(_)) => self.numeric_std_overload(id)?,
+                _ => None,
+            };
+            let result = match numeric {
+                Some(signed) => numeric_std_binary(op.value, signed, lhs, rhs),
+                None => lhs.binary(op.value, rhs),
+            };
+            match result {
+                Ok(k) => self.intern_const(k),
+                Err(e) => {
+                    self.emit(
+                        DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            }
+        }
+
+        // Function calls, which are interpreted for user-defined functions
+        // and evaluated directly for the functions of package `NUMERIC_STD`.
+        hir::ExprData::FuncCall(_, ref args) => {
+            // Type checking has ensured that all arguments are positional.
+            let arg_values = || args.value
+                .iter()
+                .map(|arg| match arg.actual.value {
+                    hir::AssocActual::Expr(id) => self.const_value(id),
+                    _ => unreachable!(),
+                })
+                .collect::<Result<Vec<_>>>();
+            let builtin = match self.overload(id)?.value {
+                Def::Subprog(decl) => return self.const_call(decl, &arg_values()?, hir.span),
+                Def::BuiltinOp(op) => builtin_op(op),
+                _ => None,
+            };
+            let call = builtin.and_then(|builtin| match builtin.name {
+                ResolvableName::Ident(name) => Some((name, numeric_std_signedness(builtin)?)),
+                _ => None,
+            });
+            let (name, signed) = match call {
+                Some(call) => call,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error("expression does not have a constant value")
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            };
+            let args = arg_values()?;
+            match numeric_std_call(&name.as_str().to_uppercase(), signed, &args) {
+                Ok(k) => self.intern_const(k),
+                Err(e) => {
+                    self.emit(
+                        DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            }
+        }
+
         // Ranges.
         hir::ExprData::Range(dir, lb_id, rb_id) => {
             // TODO: Determine the type of ourself, then make sure the const
@@ -56,8 +177,171 @@ impl_make!(self, id: ExprRef => &Const {
         }
 
         // Names.
+        hir::ExprData::ConstName(id) => self.const_object_value(id, hir.span)?,
+
+        // External names of constants, which assume their value in the
+        // instance they are declared in.
+        hir::ExprData::ExternalName(_) => match self.external_target(id)? {
+            ExternalTarget::Const(konst, env) => {
+                self.with_generic_env(env, || self.const_object_value(konst, hir.span))?
+            }
+            ExternalTarget::Signal(_) => {
+                self.emit(
+                    DiagBuilder2::error("expression does not have a constant value")
+                    .span(hir.span)
+                );
+                return Err(());
+            }
+        },
+        hir::ExprData::VarName(id) => self.const_local(id.into(), hir.span)?,
         hir::ExprData::Name(Def::Enum(EnumRef(decl, index)), _) => self.intern_const(ConstEnum::new(decl, index)),
 
+        // Enumeration literals, which may be overloaded. The type of the
+        // expression picks one of them.
+        hir::ExprData::EnumName(ref defs) => {
+            let decl = match *self.deref_named_type(self.lazy_typeval(id)?)? {
+                Ty::Enum(ref ty) => ty.decl,
+                _ => unreachable!(),
+            };
+            match defs.iter().find(|def| def.value.0 == decl) {
+                Some(def) => self.intern_const(ConstEnum::new(decl, def.value.1)),
+                None => unreachable!(),
+            }
+        }
+
+        // Predefined attributes of types.
+        hir::ExprData::TypeAttr(tm, attr, arg_id) => {
+            let arg = self.const_value(arg_id)?;
+            let ty = self.deref_named_type(self.ty(tm.value)?)?;
+            self.const_type_attr(attr.value, ty, arg, hir.span)?
+        }
+        hir::ExprData::BoundAttr(prefix, attr, dim) => {
+            self.const_bound_attr(prefix, attr.value, dim, hir.span)?
+        }
+
+        // Record and array aggregates.
+        hir::ExprData::Aggregate(agg_id) => {
+            let ty = self.deref_named_type(self.lazy_typeval(id)?)?;
+            let agg = self.lazy_hir(agg_id)?;
+            match *ty {
+                Ty::Record(ref ty) => self.const_record_aggregate(ty, agg)?,
+                Ty::Array(ref ty) => self.const_array_aggregate(ty, agg)?,
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error("expression does not have a constant value")
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            }
+        }
+
+        // String literals, which may be overloaded. The element type of the
+        // expression picks one of them.
+        hir::ExprData::StringLiteral(ref maps) => {
+            let ty = match *self.deref_named_type(self.lazy_typeval(id)?)? {
+                Ty::Array(ref ty) if ty.indices.len() == 1 => ty,
+                _ => unreachable!(),
+            };
+            let decl = match *self.deref_named_type(&ty.element)? {
+                Ty::Enum(ref ty) => ty.decl,
+                _ => unreachable!(),
+            };
+            let indices = match maps.iter().find(|&&(d, _)| d == decl) {
+                Some(&(_, ref indices)) => indices,
+                None => unreachable!(),
+            };
+            let (dir, left, len) = match ty.indices[0] {
+                ArrayIndex::Constrained(ref index) => {
+                    let (dir, left, right) = self.discrete_bounds(index, hir.span)?;
+                    let len: BigInt = match dir {
+                        Dir::To => &right - &left + 1,
+                        Dir::Downto => &left - &right + 1,
+                    };
+                    (dir, left, len.to_usize().unwrap_or(0))
+                }
+                ArrayIndex::Unbounded(ref index) => {
+                    let (dir, left, _) = self.discrete_bounds(index, hir.span)?;
+                    (dir, left, indices.len())
+                }
+            };
+            if len != indices.len() {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "string literal `{}` has {} elements, but the array has {}",
+                        hir.span.extract(),
+                        indices.len(),
+                        len
+                    ))
+                    .span(hir.span)
+                );
+                return Err(());
+            }
+            let elements = indices.iter().map(|&i| ConstEnum::new(decl, i).into()).collect();
+            self.intern_const(ConstArray::new(dir, left, elements))
+        }
+
+        // Record fields.
+        hir::ExprData::Select(prefix_id, name) => {
+            self.lazy_typeval(id)?;
+            let field = match (self.const_value(prefix_id)?, name.value) {
+                (&Const::Record(ref k), ResolvableName::Ident(n)) => k.field(n),
+                _ => None,
+            };
+            match field {
+                Some(k) => self.intern_const(k.clone()),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error("expression does not have a constant value")
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            }
+        }
+
+        // Array elements and slices.
+        hir::ExprData::Index(prefix_id, ref indices) => {
+            self.lazy_typeval(id)?;
+            let mut value = self.const_value(prefix_id)?;
+            for &index in indices {
+                let pos = self.const_position(index)?;
+                let element = match *value {
+                    Const::Array(ref k) => k.get(&pos),
+                    _ => Err(ConstError::Unsupported),
+                };
+                value = match element {
+                    Ok(k) => k,
+                    Err(e) => {
+                        self.emit(
+                            DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                            .span(hir.span)
+                        );
+                        return Err(());
+                    }
+                };
+            }
+            self.intern_const(value.clone())
+        }
+        hir::ExprData::Slice(prefix_id, ref range) => {
+            self.lazy_typeval(id)?;
+            let range = self.const_discrete_range(range)?;
+            let slice = match *self.const_value(prefix_id)? {
+                Const::Array(ref k) => k.slice(&range),
+                _ => Err(ConstError::Unsupported),
+            };
+            match slice {
+                Ok(k) => self.intern_const(k),
+                Err(e) => {
+                    self.emit(
+                        DiagBuilder2::error(format!("cannot evaluate `{}`: {}", hir.span.extract(), e))
+                        .span(hir.span)
+                    );
+                    return Err(());
+                }
+            }
+        }
+
         // All other expressions cannot be turned into a constant value.
         _ => {
             self.emit(
@@ -68,3 +352,856 @@ impl_make!(self, id: ExprRef => &Const {
         }
     })
 });
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Determine the user-defined function an operator resolved to, if any.
+    ///
+    /// Such operators are evaluated by interpreting the function body.
+    pub fn subprog_overload(&self, id: ExprRef) -> Result<Option<SubprogDeclRef>> {
+        self.lazy_typeval(id)?;
+        Ok(match self.sb.overload_table.borrow().get(&id.into()) {
+            Some(&Spanned {
+                value: Def::Subprog(decl),
+                ..
+            }) => Some(decl),
+            _ => None,
+        })
+    }
+
+    /// Determine whether an operator resolved to one of the operators of
+    /// package `NUMERIC_STD`, and whether it operates on `SIGNED` numbers.
+    ///
+    /// Operators without a recorded overload, such as concatenation, yield
+    /// `None`.
+    pub fn numeric_std_overload(&self, id: ExprRef) -> Result<Option<bool>> {
+        self.lazy_typeval(id)?;
+        Ok(match self.sb.overload_table.borrow().get(&id.into()) {
+            Some(&Spanned {
+                value: Def::BuiltinOp(op),
+                ..
+            }) => builtin_op(op).and_then(numeric_std_signedness),
+            _ => None,
+        })
+    }
+
+    /// Apply a predefined attribute of a type to a constant.
+    ///
+    /// The attributes operate on the position numbers of enumeration literals,
+    /// the values of integers, and the multiples of the primary unit of
+    /// physical values.
+    fn const_type_attr(
+        &self,
+        attr: hir::TypeAttr,
+        ty: &Ty,
+        arg: &Const,
+        span: Span,
+    ) -> Result<&'ctx Const> {
+        if attr == hir::TypeAttr::Image {
+            return self.const_image(arg, span);
+        }
+        let result = match (ty, arg) {
+            (&Ty::Enum(ref ty), &Const::Int(ref k)) if attr == hir::TypeAttr::Val => {
+                let len = self.enum_len(ty.decl)?;
+                ConstEnum::checked(ty.decl, 0, len - 1, &k.value).map(Const::from)
+            }
+            (&Ty::Enum(ref ty), &Const::Enum(ref k)) if k.decl == ty.decl => {
+                let len = self.enum_len(ty.decl)?;
+                let pos = BigInt::from(k.index);
+                match attr {
+                    hir::TypeAttr::Pos => Ok(ConstInt::new(None, pos).into()),
+                    hir::TypeAttr::Succ => {
+                        ConstEnum::checked(ty.decl, 0, len - 1, &(pos + 1)).map(Const::from)
+                    }
+                    hir::TypeAttr::Pred => {
+                        ConstEnum::checked(ty.decl, 0, len - 1, &(pos - 1)).map(Const::from)
+                    }
+                    hir::TypeAttr::Val | hir::TypeAttr::Image => Err(ConstError::Unsupported),
+                }
+            }
+            (&Ty::Int(ref ty), &Const::Int(ref k)) => {
+                let ty = Some(ty.clone());
+                match attr {
+                    hir::TypeAttr::Pos => Ok(ConstInt::new(None, k.value.clone())),
+                    hir::TypeAttr::Val => ConstInt::checked(ty, k.value.clone()),
+                    hir::TypeAttr::Succ => ConstInt::checked(ty, &k.value + 1),
+                    hir::TypeAttr::Pred => ConstInt::checked(ty, &k.value - 1),
+                    hir::TypeAttr::Image => Err(ConstError::Unsupported),
+                }
+                .map(Const::from)
+            }
+            (&Ty::Physical(ref ty), &Const::Int(ref k)) if attr == hir::TypeAttr::Val => {
+                ConstPhysical::checked(ty.clone(), k.value.clone()).map(Const::from)
+            }
+            (&Ty::Physical(ref ty), &Const::Physical(ref k)) if k.ty.decl == ty.decl => {
+                match attr {
+                    hir::TypeAttr::Pos => Ok(ConstInt::new(None, k.value.clone()).into()),
+                    hir::TypeAttr::Succ => {
+                        ConstPhysical::checked(ty.clone(), &k.value + 1).map(Const::from)
+                    }
+                    hir::TypeAttr::Pred => {
+                        ConstPhysical::checked(ty.clone(), &k.value - 1).map(Const::from)
+                    }
+                    hir::TypeAttr::Val | hir::TypeAttr::Image => Err(ConstError::Unsupported),
+                }
+            }
+            _ => Err(ConstError::Unsupported),
+        };
+        match result {
+            Ok(k) => Ok(self.intern_const(k)),
+            Err(e) => {
+                self.emit(
+                    DiagBuilder2::error(format!("cannot evaluate `{}`: {}", span.extract(), e))
+                        .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Evaluate the `'image` attribute of a scalar value to a string.
+    fn const_image(&self, arg: &Const, span: Span) -> Result<&'ctx Const> {
+        let elements = self
+            .image(arg, span)?
+            .chars()
+            .map(|chr| ConstEnum::new(CHARACTER_TYPE.id, chr as usize).into())
+            .collect();
+        Ok(self.intern_const(ConstArray::new(Dir::To, BigInt::from(1), elements)))
+    }
+
+    /// Evaluate a string expression to text.
+    ///
+    /// The characters map onto the first 256 code points of Unicode, which
+    /// agree with the ISO 8859-1 character set of type `CHARACTER`.
+    pub fn const_string(&self, id: ExprRef) -> Result<String> {
+        let value = self.const_value(id)?;
+        let mut text = String::new();
+        if let Const::Array(ref k) = *value {
+            for element in &k.elements {
+                match *element {
+                    Const::Enum(ref c) if c.decl == CHARACTER_TYPE.id => {
+                        text.push(char::from(c.index as u8))
+                    }
+                    _ => break,
+                }
+            }
+            if text.len() == k.elements.len() {
+                return Ok(text);
+            }
+        }
+        let span = self.span(id).unwrap();
+        self.emit(
+            DiagBuilder2::error(format!(
+                "`{}` is not a string, but evaluates to {}",
+                span.extract(),
+                value
+            ))
+            .span(span),
+        );
+        Err(())
+    }
+
+    /// Determine the string representation of a scalar value, as yielded by
+    /// the `'image` attribute.
+    ///
+    /// Enumeration literals are represented by their lowercase identifier or
+    /// their quoted character, and physical values as a multiple of the
+    /// primary unit.
+    pub fn image(&self, arg: &Const, span: Span) -> Result<String> {
+        Ok(match *arg {
+            Const::Int(ref k) => k.value.to_string(),
+            Const::Float(ref k) => float_image(k.value),
+            Const::Physical(ref k) => format!(
+                "{} {}",
+                k.value,
+                k.ty.units[k.ty.primary].name.as_str().to_lowercase()
+            ),
+            Const::Enum(ref k) => match self.enum_literal(k.decl, k.index)? {
+                ResolvableName::Bit(chr) => format!("'{}'", chr),
+                name => name.to_string().to_lowercase(),
+            },
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot evaluate `{}`: {}",
+                        span.extract(),
+                        ConstError::Unsupported
+                    ))
+                    .span(span),
+                );
+                return Err(());
+            }
+        })
+    }
+
+    /// Determine the string representation of a value, as written by the
+    /// `WRITE`, `HWRITE`, and `OWRITE` procedures of package `TEXTIO`.
+    ///
+    /// Characters and bits are written without quotes, and strings and bit
+    /// vectors as a sequence of them. Bit vectors are written in binary, octal,
+    /// or hexadecimal, depending on the radix. Times are written in
+    /// nanoseconds if they are a multiple thereof. See IEEE 1076-2008 section
+    /// 16.4.
+    pub fn write_image(&self, arg: &Const, radix: u32, span: Span) -> Result<String> {
+        match *arg {
+            Const::Enum(ref k) if k.decl == CHARACTER_TYPE.id => {
+                return Ok(char::from(k.index as u8).to_string());
+            }
+            Const::Enum(ref k) => {
+                if let ResolvableName::Bit(chr) = self.enum_literal(k.decl, k.index)? {
+                    return Ok(chr.to_string());
+                }
+            }
+            Const::Physical(ref k) => {
+                let ns = k.ty.units.iter().find(|unit| &*unit.name.as_str() == "ns");
+                if let Some(ns) = ns {
+                    if (&k.value % &ns.abs).is_zero() {
+                        return Ok(format!("{} ns", &k.value / &ns.abs));
+                    }
+                }
+            }
+            Const::Array(ref k) => {
+                let mut digits = String::new();
+                for element in &k.elements {
+                    digits.push_str(&self.write_image(element, 2, span)?);
+                }
+                if radix == 2 || digits.is_empty() {
+                    return Ok(digits);
+                }
+                let width = if radix == 8 { 3 } else { 4 };
+                let len = (digits.len() + width - 1) / width;
+                if let Some(value) = BigInt::parse_bytes(digits.as_bytes(), 2) {
+                    let image = value.to_str_radix(radix).to_uppercase();
+                    return Ok(format!("{:0>1$}", image, len));
+                }
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a bit vector", span.extract()))
+                        .span(span),
+                );
+                return Err(());
+            }
+            _ => (),
+        }
+        self.image(arg, span)
+    }
+
+    /// Evaluate a predefined bound or length attribute of an array or a
+    /// scalar type.
+    fn const_bound_attr(
+        &self,
+        prefix: Spanned<hir::AttrPrefix>,
+        attr: hir::BoundAttr,
+        dim: Option<ExprRef>,
+        span: Span,
+    ) -> Result<&'ctx Const> {
+        let (ty, dir, left, right) = self.attr_bounds(prefix, dim, span)?;
+        let (low, high) = match dir {
+            Dir::To => (left.clone(), right.clone()),
+            Dir::Downto => (right.clone(), left.clone()),
+        };
+        let pos = match attr {
+            hir::BoundAttr::Left => left,
+            hir::BoundAttr::Right => right,
+            hir::BoundAttr::High => high,
+            hir::BoundAttr::Low => low,
+            hir::BoundAttr::Length => {
+                let len: BigInt = high - low + 1;
+                let len = len.max(BigInt::from(0));
+                return Ok(self.intern_const(ConstInt::new(None, len)));
+            }
+            hir::BoundAttr::Range | hir::BoundAttr::ReverseRange => unreachable!(),
+        };
+        Ok(self.intern_const(scalar_const(ty, pos)))
+    }
+
+    /// Evaluate the bounds of a range to constants.
+    pub fn const_range(
+        &self,
+        range: &hir::Range,
+        span: Span,
+    ) -> Result<(Dir, &'ctx Const, &'ctx Const)> {
+        match *range {
+            hir::Range::Immediate(dir, lb, rb) => {
+                Ok((dir, self.const_value(lb)?, self.const_value(rb)?))
+            }
+            hir::Range::Attr(prefix, attr, dim) => {
+                let (ty, dir, left, right) =
+                    self.attr_range_bounds(prefix, attr.value, dim, span)?;
+                Ok((
+                    dir,
+                    self.intern_const(scalar_const(ty, left)),
+                    self.intern_const(scalar_const(ty, right)),
+                ))
+            }
+        }
+    }
+
+    /// Determine the direction and the left and right position number of a
+    /// range.
+    pub fn range_bounds(&self, range: &hir::Range, span: Span) -> Result<(Dir, BigInt, BigInt)> {
+        match *range {
+            hir::Range::Immediate(dir, lb, rb) => {
+                Ok((dir, self.const_position(lb)?, self.const_position(rb)?))
+            }
+            hir::Range::Attr(prefix, attr, dim) => {
+                let (_, dir, left, right) =
+                    self.attr_range_bounds(prefix, attr.value, dim, span)?;
+                Ok((dir, left, right))
+            }
+        }
+    }
+
+    /// Determine the range that a `'range` or `'reverse_range` attribute
+    /// refers to.
+    fn attr_range_bounds(
+        &self,
+        prefix: Spanned<hir::AttrPrefix>,
+        attr: hir::BoundAttr,
+        dim: Option<ExprRef>,
+        span: Span,
+    ) -> Result<(&'ctx Ty, Dir, BigInt, BigInt)> {
+        let (ty, dir, left, right) = self.attr_bounds(prefix, dim, span)?;
+        Ok(match (attr, dir) {
+            (hir::BoundAttr::ReverseRange, Dir::To) => (ty, Dir::Downto, right, left),
+            (hir::BoundAttr::ReverseRange, Dir::Downto) => (ty, Dir::To, right, left),
+            _ => (ty, dir, left, right),
+        })
+    }
+
+    /// Determine the scalar type, the direction, and the left and right
+    /// position number that a predefined attribute refers to.
+    ///
+    /// For arrays this is the index range of the requested dimension. The
+    /// index range of an object of an unconstrained array type is taken from
+    /// its value.
+    fn attr_bounds(
+        &self,
+        prefix: Spanned<hir::AttrPrefix>,
+        dim: Option<ExprRef>,
+        span: Span,
+    ) -> Result<(&'ctx Ty, Dir, BigInt, BigInt)> {
+        let ty = match prefix.value {
+            hir::AttrPrefix::Type(tm) => self.ty(tm)?,
+            hir::AttrPrefix::Expr(id) => self.lazy_typeval(id)?,
+        };
+        let array = match *self.deref_named_type(ty)? {
+            Ty::Array(ref array) => array,
+            ref ty => {
+                if dim.is_some() {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not an array; only arrays have dimensions",
+                            prefix.span.extract()
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+                let (dir, left, right) = self.scalar_bounds(ty, prefix.span)?;
+                return Ok((ty, dir, left, right));
+            }
+        };
+        let index = match dim {
+            Some(dim) => match self.const_position(dim)?.to_usize() {
+                Some(d) if d >= 1 && d <= array.indices.len() => d - 1,
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` has no dimension `{}`",
+                            prefix.span.extract(),
+                            self.span(dim).unwrap().extract()
+                        ))
+                        .span(span)
+                        .add_note(format!(
+                            "`{}` has {} dimension(s)",
+                            prefix.span.extract(),
+                            array.indices.len()
+                        )),
+                    );
+                    return Err(());
+                }
+            },
+            None => 0,
+        };
+        let (index_ty, (dir, left, right)) = match (&array.indices[index], prefix.value) {
+            (&ArrayIndex::Constrained(ref ty), _) => (ty, self.scalar_bounds(ty, prefix.span)?),
+            (&ArrayIndex::Unbounded(ref ty), hir::AttrPrefix::Expr(id)) if index == 0 => {
+                match *self.const_value(id)? {
+                    Const::Array(ref k) => (ty, (k.dir, k.left.clone(), k.right())),
+                    _ => unreachable!(),
+                }
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "the bounds of `{}` are not known",
+                        prefix.span.extract()
+                    ))
+                    .span(span)
+                    .add_note(format!(
+                        "`{}` is an unconstrained array",
+                        prefix.span.extract()
+                    )),
+                );
+                return Err(());
+            }
+        };
+        Ok((self.deref_named_type(index_ty)?, dir, left, right))
+    }
+
+    /// Determine the direction and the left and right position number of a
+    /// scalar type.
+    ///
+    /// This is the same as `discrete_bounds`, but also accepts physical types
+    /// whose position numbers are multiples of the primary unit.
+    pub fn scalar_bounds(&self, ty: &Ty, span: Span) -> Result<(Dir, BigInt, BigInt)> {
+        match *self.deref_named_type(ty)? {
+            Ty::Physical(ref ty) => Ok((
+                ty.base.dir,
+                ty.base.left_bound.clone(),
+                ty.base.right_bound.clone(),
+            )),
+            _ => self.discrete_bounds(ty, span),
+        }
+    }
+
+    /// Evaluate a record aggregate.
+    ///
+    /// The aggregate is assumed to have passed type checking, such that each
+    /// field of the record is assigned exactly once.
+    fn const_record_aggregate(&self, ty: &RecordTy, agg: &hir::Aggregate) -> Result<&'ctx Const> {
+        let mut values = vec![None; ty.fields.len()];
+        for (index, expr) in agg.positional.iter().enumerate() {
+            values[index] = Some(expr.value);
+        }
+        if let hir::AggregateKind::Record(ref fields) = agg.named {
+            for field in fields {
+                for choice in &field.value.0 {
+                    if let Some(&index) = ty.lookup.get(&choice.value) {
+                        values[index] = Some(field.value.1.value);
+                    }
+                }
+            }
+        }
+        let mut fields = Vec::new();
+        for (&(name, _), value) in ty.fields.iter().zip(values) {
+            let expr = match value.or(agg.others.map(|o| o.value)) {
+                Some(expr) => expr,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!("field `{}` is not assigned", name))
+                            .span(agg.span),
+                    );
+                    return Err(());
+                }
+            };
+            fields.push((name, self.const_value(expr)?.clone()));
+        }
+        Ok(self.intern_const(ConstRecord::new(fields)))
+    }
+
+    /// Evaluate an array aggregate.
+    ///
+    /// The index range is that of the array type. Unconstrained arrays start
+    /// at the left bound of the index subtype if the aggregate is positional,
+    /// and span the lowest to the highest choice otherwise.
+    fn const_array_aggregate(&self, ty: &ArrayTy, agg: &hir::Aggregate) -> Result<&'ctx Const> {
+        let named = match agg.named {
+            hir::AggregateKind::Array(ref fields) => &fields[..],
+            _ => &[],
+        };
+
+        // Evaluate the choices to the position numbers they assign.
+        let mut choices = Vec::new();
+        for field in named {
+            for choice in &field.value.0 {
+                let (lo, hi) = match choice.value {
+                    hir::ArrayChoice::Expr(expr) => {
+                        let pos = self.const_position(expr)?;
+                        (pos.clone(), pos)
+                    }
+                    hir::ArrayChoice::DiscreteRange(hir::DiscreteRange::Range(ref r)) => {
+                        match self.range_bounds(r, choice.span)? {
+                            (Dir::To, l, r) => (l, r),
+                            (Dir::Downto, l, r) => (r, l),
+                        }
+                    }
+                    hir::ArrayChoice::DiscreteRange(hir::DiscreteRange::Subtype(subty)) => {
+                        match self.discrete_bounds(self.ty(subty)?, choice.span)? {
+                            (Dir::To, l, r) => (l, r),
+                            (Dir::Downto, l, r) => (r, l),
+                        }
+                    }
+                };
+                choices.push((lo, hi, field.value.1.value));
+            }
+        }
+
+        // Determine the index range of the aggregate.
+        let (dir, left, len) = match ty.indices[0] {
+            ArrayIndex::Constrained(ref index) => {
+                let (dir, left, right) = self.discrete_bounds(index, agg.span)?;
+                let len: BigInt = match dir {
+                    Dir::To => &right - &left + 1,
+                    Dir::Downto => &left - &right + 1,
+                };
+                (dir, left, len.to_usize().unwrap_or(0))
+            }
+            ArrayIndex::Unbounded(ref index) => {
+                let (dir, left, _) = self.discrete_bounds(index, agg.span)?;
+                if agg.others.is_some() {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`others` in aggregate `{}` requires a constrained array type",
+                            agg.span.extract()
+                        ))
+                        .span(agg.span),
+                    );
+                    return Err(());
+                }
+                match (
+                    choices.iter().map(|c| &c.0).min(),
+                    choices.iter().map(|c| &c.1).max(),
+                ) {
+                    (Some(lo), Some(hi)) if agg.positional.is_empty() => {
+                        let left = match dir {
+                            Dir::To => lo.clone(),
+                            Dir::Downto => hi.clone(),
+                        };
+                        let len: BigInt = hi - lo + 1;
+                        (dir, left, len.to_usize().unwrap_or(0))
+                    }
+                    _ => (dir, left, agg.positional.len()),
+                }
+            }
+        };
+        let offset = |pos: &BigInt| {
+            match dir {
+                Dir::To => pos - &left,
+                Dir::Downto => &left - pos,
+            }
+            .to_usize()
+            .filter(|&i| i < len)
+        };
+
+        // Assign the elements.
+        if agg.positional.len() > len {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "aggregate `{}` has {} elements, but the array only has {}",
+                    agg.span.extract(),
+                    agg.positional.len(),
+                    len
+                ))
+                .span(agg.span),
+            );
+            return Err(());
+        }
+        let mut exprs = vec![None; len];
+        for (i, expr) in agg.positional.iter().enumerate() {
+            exprs[i] = Some(expr.value);
+        }
+        for (lo, hi, expr) in choices {
+            let mut pos = lo;
+            while pos <= hi {
+                match offset(&pos) {
+                    Some(i) => exprs[i] = Some(expr),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "cannot evaluate `{}`: index {} out of range",
+                                agg.span.extract(),
+                                pos
+                            ))
+                            .span(agg.span),
+                        );
+                        return Err(());
+                    }
+                }
+                pos = pos + 1;
+            }
+        }
+        let mut elements = Vec::new();
+        for (i, expr) in exprs.into_iter().enumerate() {
+            match expr.or(agg.others.map(|o| o.value)) {
+                Some(expr) => elements.push(self.const_value(expr)?.clone()),
+                None => {
+                    let pos = match dir {
+                        Dir::To => &left + i,
+                        Dir::Downto => &left - i,
+                    };
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "element {} is not assigned in aggregate `{}`",
+                            pos,
+                            agg.span.extract()
+                        ))
+                        .span(agg.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Ok(self.intern_const(ConstArray::new(dir, left, elements)))
+    }
+
+    /// Convert an array value to the index range of a constrained array type,
+    /// as happens when it is assigned to an object of that type.
+    pub fn const_array_conversion(
+        &self,
+        value: &'ctx Const,
+        ty: &Ty,
+        span: Span,
+    ) -> Result<&'ctx Const> {
+        let (array, index) = match (value, self.deref_named_type(ty)?) {
+            (&Const::Array(ref k), &Ty::Array(ref ty)) => match ty.indices[0] {
+                ArrayIndex::Constrained(ref index) => (k, index),
+                ArrayIndex::Unbounded(_) => return Ok(value),
+            },
+            _ => return Ok(value),
+        };
+        let (dir, left, right) = self.discrete_bounds(index, span)?;
+        let len: BigInt = match dir {
+            Dir::To => &right - &left + 1,
+            Dir::Downto => &left - &right + 1,
+        };
+        if len.to_usize().unwrap_or(0) != array.elements.len() {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` has {} elements, but its type `{}` has {}",
+                    span.extract(),
+                    array.elements.len(),
+                    ty,
+                    len.max(BigInt::from(0))
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+        if array.dir == dir && array.left == left {
+            return Ok(value);
+        }
+        Ok(self.intern_const(ConstArray::new(dir, left, array.elements.clone())))
+    }
+
+    /// Evaluate an expression to a position number, which is the value of
+    /// an integer or the position of an enumeration literal.
+    pub fn const_position(&self, id: ExprRef) -> Result<BigInt> {
+        match *self.const_value(id)? {
+            Const::Int(ref k) => Ok(k.value.clone()),
+            Const::Enum(ref k) => Ok(BigInt::from(k.index)),
+            ref k => {
+                let span = self.span(id).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a discrete value", span.extract()))
+                        .span(span)
+                        .add_note(format!(
+                            "`{}` evaluates to {} {}",
+                            span.extract(),
+                            k.kind_desc(),
+                            k
+                        )),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Evaluate a discrete range to the range of position numbers it covers.
+    pub fn const_discrete_range(
+        &self,
+        range: &Spanned<hir::DiscreteRange>,
+    ) -> Result<ConstIntRange> {
+        let (dir, left, right) = match range.value {
+            hir::DiscreteRange::Range(ref r) => self.range_bounds(r, range.span)?,
+            hir::DiscreteRange::Subtype(subty) => {
+                self.discrete_bounds(self.ty(subty)?, range.span)?
+            }
+        };
+        Ok(ConstIntRange::new(
+            dir,
+            ConstInt::new(None, left),
+            ConstInt::new(None, right),
+        ))
+    }
+
+    /// Determine the values the parameter of a for generate statement assumes,
+    /// in the order of its range.
+    pub fn gen_param_values(&self, id: ForGenStmtRef) -> Result<Vec<&'ctx Const>> {
+        let hir = self.hir(id)?;
+        let range = self.const_discrete_range(&hir.range)?;
+        let ty = self.deref_named_type(self.ty(ConstRef::Gen(id))?)?;
+        Ok(range
+            .positions()
+            .map(|pos| self.intern_const(scalar_const(ty, pos)))
+            .collect())
+    }
+
+    /// Determine the values the parameter of a for loop assumes, in the order
+    /// of its range.
+    pub fn loop_param_values(&self, id: LoopStmtRef) -> Result<Vec<&'ctx Const>> {
+        let hir = self.lazy_hir(id)?;
+        let range = match hir.stmt.scheme {
+            hir::LoopScheme::For(_, ref range) => self.const_discrete_range(range)?,
+            _ => unreachable!("{:?} is not a for loop", id),
+        };
+        let ty = self.deref_named_type(self.ty(ConstRef::Loop(id))?)?;
+        Ok(range
+            .positions()
+            .map(|pos| self.intern_const(scalar_const(ty, pos)))
+            .collect())
+    }
+
+    /// Determine the body of an if generate statement that is elaborated, if
+    /// any.
+    ///
+    /// This is the body of the first branch whose condition holds.
+    pub fn if_gen_body(&self, id: IfGenStmtRef) -> Result<Option<GenBodyRef>> {
+        let hir = self.hir(id)?;
+        for &(cond, body) in &hir.branches {
+            if self.const_bool(cond)? {
+                return Ok(Some(body));
+            }
+        }
+        Ok(hir.otherwise)
+    }
+
+    /// Determine the body of a case generate statement that is elaborated, if
+    /// any.
+    pub fn case_gen_body(&self, id: CaseGenStmtRef) -> Result<Option<GenBodyRef>> {
+        let hir = self.hir(id)?;
+        for &(ref choices, body) in &hir.cases {
+            if self.const_choices_match(hir.switch, choices)? {
+                return Ok(Some(body));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Determine the direction and the left and right position number of a
+    /// discrete type.
+    pub fn discrete_bounds(&self, ty: &Ty, span: Span) -> Result<(Dir, BigInt, BigInt)> {
+        match *self.deref_named_type(ty)? {
+            Ty::Int(ref ty) => Ok((ty.dir, ty.left_bound.clone(), ty.right_bound.clone())),
+            Ty::Enum(ref ty) => Ok(match ty.range {
+                Some((dir, l, r)) => (dir, l.into(), r.into()),
+                None => (
+                    Dir::To,
+                    BigInt::from(0),
+                    BigInt::from(self.enum_len(ty.decl)?) - 1,
+                ),
+            }),
+            Ty::Null => Ok((Dir::To, BigInt::from(0), BigInt::from(-1))),
+            ref ty => {
+                self.emit(
+                    DiagBuilder2::error(format!("{} is not a discrete type", ty.kind_desc()))
+                        .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the value of a variable, loop parameter, or generate
+    /// parameter.
+    ///
+    /// These only have a value while a subprogram is being interpreted, or
+    /// while the statements of a for generate statement are elaborated.
+    /// Determine the value of a constant, generic, or loop or generate
+    /// parameter in the current environment.
+    pub fn const_object_value(&self, id: ConstRef, span: Span) -> Result<&'ctx Const> {
+        match id {
+            ConstRef::Intf(id) => {
+                let value = self.generic_value(id, span)?;
+                self.const_array_conversion(value, self.ty(id)?, span)
+            }
+            ConstRef::Decl(id) => {
+                let decl = self.lazy_hir(id)?;
+                match self.const_init(id)? {
+                    Some(init) => {
+                        let value = self.const_value(init)?;
+                        self.const_array_conversion(value, self.lazy_typeval(id)?, span)
+                    }
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is a deferred constant without a value",
+                                decl.name.value
+                            ))
+                            .span(span)
+                            .add_note(
+                                "A deferred constant must be fully declared in the package body. \
+                                 See IEEE 1076-2008 section 4.8.",
+                            )
+                            .span(decl.span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+            ConstRef::Loop(id) => self.const_local(id.into(), span),
+            ConstRef::Gen(id) => self.const_local(id.into(), span),
+        }
+    }
+
+    fn const_local(&self, id: NodeId, span: Span) -> Result<&'ctx Const> {
+        match self.local_value(id) {
+            Some(value) => Ok(value),
+            None => {
+                self.emit(
+                    DiagBuilder2::error("expression does not have a constant value").span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the initial value of a constant declaration.
+    ///
+    /// The value of a deferred constant in a package is given by the full
+    /// constant declaration of the same name in the package body.
+    fn const_init(&self, id: ConstDeclRef) -> Result<Option<ExprRef>> {
+        let decl = self.lazy_hir(id)?;
+        if decl.decl.init.is_some() {
+            return Ok(decl.decl.init);
+        }
+        let body = match decl.parent {
+            ScopeRef::Pkg(pkg) => self.pkg_body(pkg)?,
+            _ => None,
+        };
+        let body = match body {
+            Some(body) => self.hir(body)?,
+            None => return Ok(None),
+        };
+        for &body_decl in &body.decls {
+            if let DeclInPkgBodyRef::Const(full) = body_decl {
+                let full = self.lazy_hir(full)?;
+                if full.name.value == decl.name.value {
+                    return Ok(full.decl.init);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Create the constant at a position number of a scalar type.
+pub(super) fn scalar_const(ty: &Ty, pos: BigInt) -> Const {
+    match *ty {
+        Ty::Enum(ref ty) => ConstEnum::new(ty.decl, pos.to_usize().unwrap()).into(),
+        Ty::Physical(ref ty) => ConstPhysical::new(ty.clone(), pos).into(),
+        _ => ConstInt::new(None, pos).into(),
+    }
+}
+
+/// Format a real number in the exponential notation `d.dddddde+dd`.
+fn float_image(value: f64) -> String {
+    let image = format!("{:.6e}", value);
+    match image.find('e') {
+        Some(i) => {
+            let exp: i32 = image[i + 1..].parse().unwrap_or(0);
+            let sign = if exp < 0 { '-' } else { '+' };
+            format!("{}e{}{:02}", &image[..i], sign, exp.abs())
+        }
+        None => image,
+    }
+}
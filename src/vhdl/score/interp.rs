@@ -0,0 +1,574 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! This module implements the interpretation of functions during elaboration.
+//!
+//! Calls to pure functions in constant expressions, such as the logarithm of a
+//! generic in the range of a port, are evaluated by executing the sequential
+//! statements of the function body. The parameters, variables, and loop
+//! parameters of the function are kept in the generic environment, such that
+//! the expressions in the body are evaluated by the regular constant value
+//! computation. This also allows functions to be recursive.
+//!
+//! To guard against runaway evaluations, the interpreter imposes a limit on the
+//! call depth and on the number of statements executed.
+
+use super::cval::scalar_const;
+use crate::builtin::BOOLEAN_TYPE;
+use crate::score::*;
+use crate::typeck::TypeckContext;
+use num::BigInt;
+
+/// The maximum nesting depth of interpreted function calls.
+pub const MAX_CALL_DEPTH: usize = 128;
+
+/// The maximum number of statements executed during the evaluation of the
+/// outermost function call.
+pub const MAX_STEPS: usize = 1_000_000;
+
+/// The control flow resulting from the execution of a statement.
+enum Flow<'ctx> {
+    /// Continue with the next statement.
+    Next,
+    /// Continue with the next iteration of a loop, or the innermost loop if
+    /// `None`.
+    NextIter(Option<LoopStmtRef>),
+    /// Exit a loop, or the innermost loop if `None`.
+    Exit(Option<LoopStmtRef>),
+    /// Return from the function with a value.
+    Return(&'ctx Const),
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Evaluate a call to a function during elaboration.
+    ///
+    /// The arguments have already been evaluated in the environment of the
+    /// caller. See IEEE 1076-2008 section 9.4.
+    pub fn const_call(
+        &self,
+        id: SubprogDeclRef,
+        args: &[&'ctx Const],
+        span: Span,
+    ) -> Result<&'ctx Const> {
+        let decl = self.hir(id)?;
+        let name = decl.spec.name.value;
+        if decl.spec.kind != hir::SubprogKind::PureFunc {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` does not have a constant value",
+                    span.extract()
+                ))
+                .span(span)
+                .add_note(format!(
+                    "Only pure functions can be evaluated during elaboration, but `{}` is \
+                         impure.",
+                    name
+                )),
+            );
+            return Err(());
+        }
+        let body = match self.subprog_body(id)? {
+            Some(body) => self.hir(body)?,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!("function `{}` has no body", name))
+                        .span(span)
+                        .add_note("The function is declared here:")
+                        .span(decl.spec.name.span),
+                );
+                return Err(());
+            }
+        };
+        let depth = self.sb.call_depth.get();
+        if depth >= MAX_CALL_DEPTH {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "evaluation of function `{}` exceeds the maximum call depth of {}",
+                    name, MAX_CALL_DEPTH
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+        if depth == 0 {
+            self.sb.call_steps.set(0);
+        }
+
+        // Bind the arguments to the parameters of the body in an environment
+        // without any of the caller's locals. Type checking has ensured that
+        // there is one argument per parameter.
+        let mut data = self.sb.generic_envs.borrow()[self.generic_env().0 as usize].clone();
+        data.clear_locals();
+        for (&param, &arg) in body.spec.params.iter().zip(args) {
+            if let IntfObjRef::Const(param) = param {
+                data.set_value(param, arg);
+            }
+        }
+        let env = self.intern_generic_env(data);
+
+        // The body may refer to generics visible at the declaration of the
+        // function, such that the result is specific to the current
+        // environment.
+        self.mark_generic_dep();
+        self.sb.call_depth.set(depth + 1);
+        let result = self.with_generic_env(env, || self.exec_func_body(name, body, span));
+        self.sb.call_depth.set(depth);
+        let value = result?;
+        match body.spec.return_type {
+            Some(tm) => self.const_array_conversion(value, self.ty(tm.value)?, span),
+            None => Ok(value),
+        }
+    }
+
+    /// Execute the body of a function and determine its return value.
+    fn exec_func_body(
+        &self,
+        name: ResolvableName,
+        body: &hir::SubprogBody,
+        span: Span,
+    ) -> Result<&'ctx Const> {
+        for &decl in &body.decls {
+            if let DeclInSubprogRef::Var(id) = decl {
+                let hir = self.lazy_hir(id)?;
+                let ty = self.lazy_typeval(id)?;
+                let value = match hir.decl.init {
+                    Some(init) => {
+                        self.const_array_conversion(self.const_value(init)?, ty, hir.span)?
+                    }
+                    None => self.default_value_for_type(ty)?,
+                };
+                self.set_local_value(id.into(), value);
+            }
+        }
+        match self.exec_stmts(&body.stmts)? {
+            Flow::Return(value) => Ok(value),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "function `{}` completes without a return statement",
+                        name
+                    ))
+                    .span(span)
+                    .add_note(
+                        "A function must complete by executing a return statement. See IEEE \
+                         1076-2008 section 4.3.",
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Execute a sequence of statements.
+    fn exec_stmts(&self, stmts: &[SeqStmtRef]) -> Result<Flow<'ctx>> {
+        for &stmt in stmts {
+            match self.exec_stmt(stmt)? {
+                Flow::Next => (),
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    /// Execute a single statement.
+    fn exec_stmt(&self, id: SeqStmtRef) -> Result<Flow<'ctx>> {
+        self.count_step(id.into())?;
+        match id {
+            SeqStmtRef::VarAssign(id) => {
+                let hir = self.lazy_hir(id)?;
+                let value = match hir.stmt.kind {
+                    hir::VarAssignKind::Simple(expr) => Some(self.const_value(expr)?),
+                    hir::VarAssignKind::Cond(ref cond) => {
+                        let mut value = None;
+                        for &(expr, when) in &cond.when {
+                            if self.const_bool(when)? {
+                                value = Some(expr);
+                                break;
+                            }
+                        }
+                        match value.or(cond.other) {
+                            Some(expr) => Some(self.const_value(expr)?),
+                            None => None,
+                        }
+                    }
+                    hir::VarAssignKind::Sel(ref sel) => {
+                        let mut value = None;
+                        for &(expr, ref choices) in &sel.when {
+                            if self.const_choices_match(sel.disc, choices)? {
+                                value = Some(self.const_value(expr)?);
+                                break;
+                            }
+                        }
+                        value
+                    }
+                };
+                if let Some(value) = value {
+                    match hir.stmt.target.value {
+                        hir::Target::Name(expr) => {
+                            self.exec_assign(expr, value, hir.stmt.target.span)?
+                        }
+                        hir::Target::Aggregate(_) => {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "`{}` cannot be assigned during elaboration",
+                                    hir.stmt.target.span.extract()
+                                ))
+                                .span(hir.stmt.target.span),
+                            );
+                            return Err(());
+                        }
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            SeqStmtRef::If(id) => {
+                let hir = self.lazy_hir(id)?;
+                for &(cond, ref stmts) in &hir.stmt.branches {
+                    if self.const_bool(cond)? {
+                        return self.exec_stmts(stmts);
+                    }
+                }
+                match hir.stmt.otherwise {
+                    Some(ref stmts) => self.exec_stmts(stmts),
+                    None => Ok(Flow::Next),
+                }
+            }
+            SeqStmtRef::Case(id) => {
+                let hir = self.lazy_hir(id)?;
+                for &(ref choices, ref stmts) in &hir.stmt.cases {
+                    if self.const_choices_match(hir.stmt.switch, choices)? {
+                        return self.exec_stmts(stmts);
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            SeqStmtRef::Loop(id) => self.exec_loop(id),
+            SeqStmtRef::Nexit(id) => {
+                let hir = self.lazy_hir(id)?;
+                if let Some(cond) = hir.stmt.cond {
+                    if !self.const_bool(cond)? {
+                        return Ok(Flow::Next);
+                    }
+                }
+                let target = hir.stmt.target.map(|t| t.value);
+                Ok(match hir.stmt.mode {
+                    hir::NexitMode::Next => Flow::NextIter(target),
+                    hir::NexitMode::Exit => Flow::Exit(target),
+                })
+            }
+            SeqStmtRef::Return(id) => {
+                let hir = self.lazy_hir(id)?;
+                match hir.stmt.expr {
+                    Some(expr) => Ok(Flow::Return(self.const_value(expr)?)),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error("return statement in a function requires a value")
+                                .span(hir.span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+            SeqStmtRef::Assert(id) => {
+                let hir = self.lazy_hir(id)?;
+                let stmt = &hir.stmt;
+                self.check_assertion(Some(stmt.cond), stmt.report, stmt.severity, hir.span)?;
+                Ok(Flow::Next)
+            }
+            SeqStmtRef::Report(id) => {
+                let hir = self.lazy_hir(id)?;
+                self.check_assertion(None, Some(hir.stmt.report), hir.stmt.severity, hir.span)?;
+                Ok(Flow::Next)
+            }
+            SeqStmtRef::Null(_) => Ok(Flow::Next),
+            _ => {
+                let span = self.span(id).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be executed during elaboration",
+                        span.extract()
+                    ))
+                    .span(span)
+                    .add_note(
+                        "Only variable assignments and control flow statements can be evaluated \
+                         in a function called in a constant expression.",
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Execute a loop statement.
+    fn exec_loop(&self, id: LoopStmtRef) -> Result<Flow<'ctx>> {
+        let hir = self.lazy_hir(id)?;
+        match hir.stmt.scheme {
+            hir::LoopScheme::Loop => loop {
+                if let Some(flow) = self.exec_loop_body(id, &hir.stmt.stmts)? {
+                    return Ok(flow);
+                }
+            },
+            hir::LoopScheme::While(cond) => {
+                while self.const_bool(cond)? {
+                    if let Some(flow) = self.exec_loop_body(id, &hir.stmt.stmts)? {
+                        return Ok(flow);
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            hir::LoopScheme::For(_, ref range) => {
+                let (dir, left, right) = match range.value {
+                    hir::DiscreteRange::Range(ref r) => self.range_bounds(r, range.span)?,
+                    hir::DiscreteRange::Subtype(subty) => {
+                        self.discrete_bounds(self.ty(subty)?, range.span)?
+                    }
+                };
+                let ty = self.deref_named_type(self.ty(ConstRef::Loop(id))?)?;
+                let mut pos = left;
+                while match dir {
+                    Dir::To => pos <= right,
+                    Dir::Downto => pos >= right,
+                } {
+                    let value = self.intern_const(scalar_const(ty, pos.clone()));
+                    self.set_local_value(id.into(), value);
+                    if let Some(flow) = self.exec_loop_body(id, &hir.stmt.stmts)? {
+                        return Ok(flow);
+                    }
+                    match dir {
+                        Dir::To => pos += 1,
+                        Dir::Downto => pos -= 1,
+                    }
+                }
+                Ok(Flow::Next)
+            }
+        }
+    }
+
+    /// Execute one iteration of a loop.
+    ///
+    /// Returns the control flow after the loop if the iteration leaves the
+    /// loop, or `None` if the loop continues with the next iteration.
+    fn exec_loop_body(&self, id: LoopStmtRef, stmts: &[SeqStmtRef]) -> Result<Option<Flow<'ctx>>> {
+        self.count_step(id.into())?;
+        Ok(match self.exec_stmts(stmts)? {
+            Flow::Next => None,
+            Flow::NextIter(target) if target.map_or(true, |t| t == id) => None,
+            Flow::Exit(target) if target.map_or(true, |t| t == id) => Some(Flow::Next),
+            flow => Some(flow),
+        })
+    }
+
+    /// Assign a value to the variable, or the element of a variable, named by
+    /// an expression.
+    fn exec_assign(&self, id: ExprRef, value: &'ctx Const, span: Span) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        match hir.data {
+            hir::ExprData::VarName(var) => {
+                let value = self.const_array_conversion(value, self.lazy_typeval(var)?, span)?;
+                self.set_local_value(var.into(), value);
+                Ok(())
+            }
+            hir::ExprData::Index(prefix, ref indices) => {
+                let indices = indices
+                    .iter()
+                    .map(|&index| self.const_position(index))
+                    .collect::<Result<Vec<_>>>()?;
+                match replace_element(self.const_value(prefix)?, &indices, value) {
+                    Ok(k) => self.exec_assign(prefix, self.intern_const(k), span),
+                    Err(e) => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "cannot assign to `{}`: {}",
+                                hir.span.extract(),
+                                e
+                            ))
+                            .span(hir.span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be assigned during elaboration",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Check an assertion, or execute a report statement if `cond` is `None`,
+    /// during elaboration.
+    ///
+    /// The severity levels `note`, `warning`, and `error` map onto diagnostics
+    /// of the same severity. Severity level `failure` maps onto a fatal
+    /// diagnostic and aborts the elaboration. See IEEE 1076-2008 sections 10.3
+    /// and 10.4.
+    pub fn check_assertion(
+        &self,
+        cond: Option<ExprRef>,
+        report: Option<ExprRef>,
+        severity: Option<ExprRef>,
+        span: Span,
+    ) -> Result<()> {
+        if let Some(cond) = cond {
+            if self.const_bool(cond)? {
+                return Ok(());
+            }
+        }
+        let level = match severity {
+            Some(severity) => match *self.const_value(severity)? {
+                Const::Enum(ref k) if k.decl == SEVERITY_LEVEL_TYPE.id => k.index,
+                ref k => {
+                    let span = self.span(severity).unwrap();
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a severity level, but evaluates to {}",
+                            span.extract(),
+                            k
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            },
+            None if cond.is_some() => SEVERITY_ERROR,
+            None => SEVERITY_NOTE,
+        };
+        let severity = match level {
+            SEVERITY_NOTE => Severity::Note,
+            SEVERITY_ERROR => Severity::Error,
+            SEVERITY_FAILURE => Severity::Fatal,
+            _ => Severity::Warning,
+        };
+        self.emit(DiagBuilder2::new(severity, self.report_message(report)?).span(span));
+        match level {
+            SEVERITY_FAILURE => Err(()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Determine the message of an assertion or report statement.
+    ///
+    /// Assertions without a report expression yield the message `Assertion
+    /// violation.` See IEEE 1076-2008 section 10.3.
+    pub fn report_message(&self, report: Option<ExprRef>) -> Result<String> {
+        match report {
+            Some(report) => self.const_string(report),
+            None => Ok("Assertion violation.".to_string()),
+        }
+    }
+
+    /// Evaluate a condition.
+    pub(super) fn const_bool(&self, id: ExprRef) -> Result<bool> {
+        match *self.const_value(id)? {
+            Const::Enum(ref k) if k.decl == BOOLEAN_TYPE.id => Ok(k.index == 1),
+            ref k => {
+                let span = self.span(id).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a boolean, but evaluates to {} of kind {}",
+                        span.extract(),
+                        k,
+                        k.kind_desc()
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Check whether the value of an expression matches any of the choices of
+    /// a case statement or selected assignment.
+    pub(super) fn const_choices_match(
+        &self,
+        id: ExprRef,
+        choices: &Spanned<hir::Choices>,
+    ) -> Result<bool> {
+        let value = self.const_value(id)?;
+        let ty = self.lazy_typeval(id)?;
+        let pos = match *value {
+            Const::Int(ref k) => Some(k.value.clone()),
+            Const::Enum(ref k) => Some(BigInt::from(k.index)),
+            _ => None,
+        };
+        for choice in &choices.value {
+            let matched = match choice.value {
+                hir::Choice::Expr(expr) => {
+                    // The choices assume the type of the expression, which
+                    // picks among overloaded enumeration literals.
+                    if self.type_context(expr).is_none() {
+                        self.set_type_context(expr, ty);
+                    }
+                    match pos {
+                        Some(ref pos) => *pos == self.const_position(expr)?,
+                        None => value == self.const_value(expr)?,
+                    }
+                }
+                hir::Choice::DiscreteRange(ref range) => {
+                    let (dir, left, right) = match *range {
+                        hir::DiscreteRange::Range(ref r) => self.range_bounds(r, choice.span)?,
+                        hir::DiscreteRange::Subtype(subty) => {
+                            self.discrete_bounds(self.ty(subty)?, choice.span)?
+                        }
+                    };
+                    let (low, high) = match dir {
+                        Dir::To => (left, right),
+                        Dir::Downto => (right, left),
+                    };
+                    pos.as_ref()
+                        .map_or(false, |pos| low <= *pos && *pos <= high)
+                }
+                hir::Choice::Others => true,
+                hir::Choice::Element(_) => false,
+            };
+            if matched {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Count a step of the interpretation towards the limit.
+    fn count_step(&self, id: NodeId) -> Result<()> {
+        let steps = self.sb.call_steps.get() + 1;
+        self.sb.call_steps.set(steps);
+        if steps <= MAX_STEPS {
+            return Ok(());
+        }
+        let span = self.span(id).unwrap();
+        self.emit(
+            DiagBuilder2::error(format!(
+                "constant function evaluation exceeds the limit of {} statements",
+                MAX_STEPS
+            ))
+            .span(span),
+        );
+        Err(())
+    }
+}
+
+/// Replace an element of a possibly nested array.
+fn replace_element(
+    k: &Const,
+    indices: &[BigInt],
+    value: &Const,
+) -> std::result::Result<Const, ConstError> {
+    let (index, rest) = match indices.split_first() {
+        Some(split) => split,
+        None => return Ok(value.clone()),
+    };
+    match *k {
+        Const::Array(ref k) => {
+            let mut array = k.clone();
+            let element = replace_element(k.get(index)?, rest, value)?;
+            array.set(index, element)?;
+            Ok(array.into())
+        }
+        _ => Err(ConstError::Unsupported),
+    }
+}
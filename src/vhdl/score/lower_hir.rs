@@ -153,10 +153,11 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     self.set_ast(subid, (scope_id, decl));
                     refs.push(subid.into());
                 }
+                ast::DeclItem::TypeDecl(ref decl) if is_protected_body(decl) => {
+                    refs.push(self.unpack_protected_body(scope_id, decl).into());
+                }
                 ast::DeclItem::TypeDecl(ref decl) => {
-                    let subid = TypeDeclRef(NodeId::alloc());
-                    self.set_ast(subid, (scope_id, decl));
-                    refs.push(subid.into());
+                    refs.push(ctx.add_type_decl(decl)?.into());
                 }
                 ast::DeclItem::SubtypeDecl(ref decl) => {
                     let subid = SubtypeDeclRef(NodeId::alloc());
@@ -231,7 +232,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                         refs.push(subid.into());
                     }
                 },
-                ast::DeclItem::UseClause(..) => (),
+                ast::DeclItem::UseClause(..) | ast::DeclItem::PslDecl(..) => (),
                 ref wrong => {
                     self.emit(
                         DiagBuilder2::error(format!(
@@ -301,10 +302,11 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     self.set_ast(subid, (scope_id, decl));
                     refs.push(subid.into());
                 }
+                ast::DeclItem::TypeDecl(ref decl) if is_protected_body(decl) => {
+                    refs.push(self.unpack_protected_body(scope_id, decl).into());
+                }
                 ast::DeclItem::TypeDecl(ref decl) => {
-                    let subid = TypeDeclRef(NodeId::alloc());
-                    self.set_ast(subid, (scope_id, decl));
-                    refs.push(subid.into());
+                    refs.push(ctx.add_type_decl(decl)?.into());
                 }
                 ast::DeclItem::SubtypeDecl(ref decl) => {
                     let subid = SubtypeDeclRef(NodeId::alloc());
@@ -440,10 +442,11 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     self.set_ast(subid, (scope_id, decl));
                     refs.push(subid.into());
                 }
+                ast::DeclItem::TypeDecl(ref decl) if is_protected_body(decl) => {
+                    refs.push(self.unpack_protected_body(scope_id, decl).into());
+                }
                 ast::DeclItem::TypeDecl(ref decl) => {
-                    let subid = TypeDeclRef(NodeId::alloc());
-                    self.set_ast(subid, (scope_id, decl));
-                    refs.push(subid.into());
+                    refs.push(ctx.add_type_decl(decl)?.into());
                 }
                 ast::DeclItem::SubtypeDecl(ref decl) => {
                     let subid = SubtypeDeclRef(NodeId::alloc());
@@ -551,13 +554,53 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     unimp(stmt);
                     had_fails = true;
                 }
+                ast::InstOrCallStmt {
+                    target: Some(ast::InstTarget::Entity),
+                    ..
+                }
+                | ast::InstOrCallStmt {
+                    target: Some(ast::InstTarget::Comp),
+                    ..
+                } => {
+                    let id = CompInstStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
+                }
+                // Without the `component` keyword, only a generic or port
+                // map distinguishes an instance from a procedure call.
+                ast::InstOrCallStmt {
+                    target: None,
+                    ref generics,
+                    ref ports,
+                    ..
+                } if generics.is_some() || ports.is_some() => {
+                    let id = CompInstStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
+                }
                 ast::InstOrCallStmt { .. } => {
                     unimp(stmt);
                     had_fails = true;
                 }
+                // An assertion of a declared property is a PSL directive.
+                ast::AssertStmt { ref cond, .. }
+                    if psl_decl_name(cond)
+                        .and_then(|name| self.psl_decl(scope_id, Some(name)))
+                        .is_some() =>
+                {
+                    let id = PslDirectiveRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
+                }
                 ast::AssertStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = ConcAssertStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
+                }
+                ast::PslDirective { .. } => {
+                    let id = PslDirectiveRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
                 ast::AssignStmt { .. } => {
                     unimp(stmt);
@@ -568,16 +611,19 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     had_fails = true;
                 }
                 ast::IfGenStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = IfGenStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
                 ast::CaseGenStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = CaseGenStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
                 ast::ForGenStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = ForGenStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
 
                 ast::ProcStmt { .. } => {
@@ -611,6 +657,194 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Unpack the body of a generate statement.
+    ///
+    /// The body receives its own scope, such that its declarations and the
+    /// parameter of a for generate statement are only visible within it. See
+    /// IEEE 1076-2008 section 11.8.
+    pub fn unpack_gen_body(&self, scope_id: ScopeRef, body: &'ast ast::GenBody) -> GenBodyRef {
+        let id = GenBodyRef(NodeId::alloc());
+        self.set_ast(id, (scope_id, body));
+        self.subscope(id.into(), scope_id);
+        id
+    }
+
+    /// Find a PSL declaration in a block or one of its enclosing blocks.
+    ///
+    /// Looks for the property or sequence with the given name, or for the
+    /// default clock if no name is given. See IEEE 1850-2010 section 7.3.
+    pub fn psl_decl(
+        &self,
+        mut scope_id: ScopeRef,
+        name: Option<Name>,
+    ) -> Option<&'ast ast::PslDecl> {
+        loop {
+            let (decls, parent) = match scope_id {
+                ScopeRef::Arch(id) => (&self.ast(id).2.decls, None),
+                ScopeRef::GenBody(id) => {
+                    let (parent, ast) = self.ast(id);
+                    (&ast.decls, Some(parent))
+                }
+                _ => return None,
+            };
+            for decl in decls {
+                let decl = match *decl {
+                    ast::DeclItem::PslDecl(ref decl) => decl,
+                    _ => continue,
+                };
+                let found = match (decl.kind, name) {
+                    (ast::PslDeclKind::DefaultClock, None) => true,
+                    (ast::PslDeclKind::DefaultClock, Some(_)) => false,
+                    (_, name) => decl.name.map(|n| n.value) == name,
+                };
+                if found {
+                    return Some(decl);
+                }
+            }
+            scope_id = parent?;
+        }
+    }
+
+    /// Lower a PSL property to HIR.
+    ///
+    /// The names of declared properties and sequences are replaced by their
+    /// declaration, whose conditions are lowered in the scope of the directive.
+    /// The `expanding` declarations are those currently being replaced, which
+    /// catches declarations that refer to themselves.
+    pub fn lower_psl_prop(
+        &self,
+        scope_id: ScopeRef,
+        prop: &'ast ast::PslProp,
+        expanding: &mut Vec<Name>,
+    ) -> Result<hir::PslProp> {
+        let mut lower = |prop| -> Result<Box<hir::PslProp>> {
+            Ok(Box::new(self.lower_psl_prop(scope_id, prop, expanding)?))
+        };
+        Ok(match prop.data {
+            ast::PslPropData::Bool(ref expr) => self.lower_psl_bool(scope_id, expr, expanding)?,
+            ast::PslPropData::Braced(ref arg) => hir::PslProp::Braced(lower(arg)?),
+            ast::PslPropData::Unary(op, ref arg) => hir::PslProp::Unary(op.value, lower(arg)?),
+            ast::PslPropData::Next {
+                strong,
+                ref count,
+                ref arg,
+            } => hir::PslProp::Next {
+                strong: strong,
+                count: match *count {
+                    Some(ref count) => Some(self.psl_count(count, false)?.unwrap()),
+                    None => None,
+                },
+                arg: lower(arg)?,
+            },
+            ast::PslPropData::Binary(op, ref lhs, ref rhs) => {
+                hir::PslProp::Binary(op.value, lower(lhs)?, lower(rhs)?)
+            }
+            ast::PslPropData::Repeat(ref arg, ref rep) => {
+                let count = match (&rep.low, &rep.high) {
+                    (Some(low), Some(high)) => Some((
+                        self.psl_count(low, false)?.unwrap(),
+                        self.psl_count(high, true)?,
+                    )),
+                    (Some(low), None) => {
+                        let low = self.psl_count(low, false)?.unwrap();
+                        Some((low, Some(low)))
+                    }
+                    _ => None,
+                };
+                hir::PslProp::Repeat(lower(arg)?, rep.kind, count)
+            }
+            ast::PslPropData::Abort(ref arg, ref cond) => {
+                hir::PslProp::Abort(lower(arg)?, self.lower_psl_cond(scope_id, cond)?)
+            }
+            ast::PslPropData::Clocked(ref arg, ref clock) => {
+                hir::PslProp::Clocked(lower(arg)?, self.lower_psl_cond(scope_id, clock)?)
+            }
+        })
+    }
+
+    /// Lower a boolean operand of a PSL property, which may be the name of a
+    /// declared property or sequence.
+    pub fn lower_psl_bool(
+        &self,
+        scope_id: ScopeRef,
+        expr: &'ast ast::Expr,
+        expanding: &mut Vec<Name>,
+    ) -> Result<hir::PslProp> {
+        let name = match psl_decl_name(expr) {
+            Some(name) => name,
+            None => return Ok(hir::PslProp::Bool(self.lower_psl_cond(scope_id, expr)?)),
+        };
+        let decl = match self.psl_decl(scope_id, Some(name)) {
+            Some(decl) => decl,
+            None => return Ok(hir::PslProp::Bool(self.lower_psl_cond(scope_id, expr)?)),
+        };
+        if expanding.contains(&name) {
+            let what = match decl.kind {
+                ast::PslDeclKind::Sequence => "sequence",
+                _ => "property",
+            };
+            self.emit(
+                DiagBuilder2::error(format!("{} `{}` refers to itself", what, name))
+                    .span(expr.span),
+            );
+            return Err(());
+        }
+        expanding.push(name);
+        let prop = self.lower_psl_prop(scope_id, &decl.prop, expanding);
+        expanding.pop();
+        prop
+    }
+
+    /// Lower a condition in a PSL property.
+    fn lower_psl_cond(&self, scope_id: ScopeRef, expr: &'ast ast::Expr) -> Result<ExprRef> {
+        let id = AddContext::new(self, scope_id).add_expr(expr)?;
+        self.set_type_context(id, TypeCtx::Type(self.builtin_boolean_type()));
+        Ok(id)
+    }
+
+    /// Determine the value of a count in a PSL property, which must be an
+    /// integer literal. An upper count may also be `inf`, which yields `None`.
+    fn psl_count(&self, expr: &'ast ast::Expr, upper: bool) -> Result<Option<u64>> {
+        match expr.data {
+            ast::LitExpr(Literal::Abstract(None, int, None, None), None) => {
+                if let Ok(count) = int.as_str().replace('_', "").parse() {
+                    return Ok(Some(count));
+                }
+            }
+            _ if upper
+                && psl_decl_name(expr).map(|n| n.as_str().eq_ignore_ascii_case("inf"))
+                    == Some(true) =>
+            {
+                return Ok(None);
+            }
+            _ => (),
+        }
+        self.emit(
+            DiagBuilder2::error(format!(
+                "`{}` is not a valid count in a PSL property",
+                expr.span.extract()
+            ))
+            .span(expr.span)
+            .add_note("Counts must be integer literals, or `inf` for an unbounded range."),
+        );
+        Err(())
+    }
+
+    /// Unpack the body of a protected type.
+    ///
+    /// The body receives its own scope, in which the methods of the protected
+    /// type are completed by their bodies. See IEEE 1076-2008 section 5.6.3.
+    pub fn unpack_protected_body(
+        &self,
+        scope_id: ScopeRef,
+        decl: &'ast ast::TypeDecl,
+    ) -> ProtectedBodyRef {
+        let id = ProtectedBodyRef(NodeId::alloc());
+        self.set_ast(id, (scope_id, decl));
+        self.subscope(id.into(), scope_id);
+        id
+    }
+
     /// Unpack a slice of sequential statements.
     ///
     /// See IEEE 1076-2008 section 10.
@@ -738,6 +972,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 ast::DelayMech::Inertial => hir::DelayMechanism::Inertial,
                 ast::DelayMech::InertialReject(ref expr) => {
                     let expr = self.unpack_expr(expr, scope_id)?;
+                    self.set_type_context(expr, self.builtin_time_type());
                     hir::DelayMechanism::RejectInertial(expr)
                 }
             })
@@ -794,8 +1029,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     after: match *after {
                         Some(ref expr) => {
                             let expr = self.unpack_expr(expr, scope_id)?;
-                            // TODO: Set the type context for the expression.
-                            // self.set_type_context(expr, TypeCtx::Type(/* somehow refer to builtin `time` type */));
+                            self.set_type_context(expr, self.builtin_time_type());
                             Some(expr)
                         }
                         None => None,
@@ -873,12 +1107,9 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             );
         }
         let generic_map = vec![];
-        if ast.params.is_some() {
-            unimp_msg!(
-                self,
-                "lowering of parameters in subprogram specifications",
-                name.span
-            );
+        let mut params = Vec::new();
+        if let Some(ref ps) = ast.params {
+            self.unpack_subprog_params(scope_id, ps, &mut params)?;
         }
         let return_type = match ast.retty {
             Some(ref name) => Some(self.unpack_type_mark(name.into(), scope_id)?),
@@ -900,7 +1131,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             kind: kind,
             generics: generics,
             generic_map: generic_map,
-            params: vec![],
+            params: params,
             return_type: return_type,
         })
     }
@@ -970,33 +1201,663 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                         kind: ast::IntfObjKind::Const,
                         ..
                     },
-                ) => {
-                    let ty = ctx.add_subtype_ind(&decl.ty)?;
-                    // let ty = SubtypeIndRef(NodeId::alloc());
-                    // self.set_ast(ty, (scope_id, &decl.ty));
-                    for name in &decl.names {
-                        let id = IntfConstRef(NodeId::alloc());
-                        self.set_ast(id, (scope_id, decl, ty, name));
-                        into.push(id.into());
+                ) => {
+                    let ty = ctx.add_subtype_ind(&decl.ty)?;
+                    // let ty = SubtypeIndRef(NodeId::alloc());
+                    // self.set_ast(ty, (scope_id, &decl.ty));
+                    for name in &decl.names {
+                        let id = IntfConstRef(NodeId::alloc());
+                        self.set_ast(id, (scope_id, decl, ty, name));
+                        into.push(id.into());
+                    }
+                }
+                ref wrong => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "a {} cannot appear in a generic clause",
+                            wrong.desc()
+                        ))
+                        .span(wrong.human_span()),
+                    );
+                    had_fails = true;
+                }
+            }
+        }
+        if had_fails {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Unpack the parameters of a subprogram from a list of interface
+    /// declarations.
+    ///
+    /// Only parameters of mode `in` are supported, which are constants unless
+    /// declared otherwise. See IEEE 1076-2008 section 4.2.2.
+    pub fn unpack_subprog_params(
+        &self,
+        scope_id: ScopeRef,
+        decls: &'ast [ast::IntfDecl],
+        into: &mut Vec<IntfObjRef>,
+    ) -> Result<()> {
+        let ctx = AddContext::new(self, scope_id);
+        let mut had_fails = false;
+        for decl in decls {
+            match *decl {
+                ast::IntfDecl::ObjDecl(ref decl)
+                    if decl.kind != ast::IntfObjKind::Signal
+                        && decl.kind != ast::IntfObjKind::File
+                        && decl.mode.unwrap_or(ast::IntfMode::In) == ast::IntfMode::In =>
+                {
+                    let ty = ctx.add_subtype_ind(&decl.ty)?;
+                    for name in &decl.names {
+                        let id = IntfConstRef(NodeId::alloc());
+                        self.set_ast(id, (scope_id, decl, ty, name));
+                        into.push(id.into());
+                    }
+                }
+                ref wrong => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "a {} cannot appear in a parameter list",
+                            wrong.desc()
+                        ))
+                        .span(wrong.human_span())
+                        .add_note(
+                            "Only constant and variable parameters of mode `in` are supported.",
+                        ),
+                    );
+                    had_fails = true;
+                }
+            }
+        }
+        if had_fails {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Unpack the associations in a generic or port map aspect.
+    ///
+    /// Associates each actual with one of the `formals`, either by name or by
+    /// position. Returns the index of the formal together with the actual.
+    /// Actuals that are `open` are omitted. The `owner` describes the entity
+    /// or component the formals belong to. See IEEE 1076-2008 section 6.5.7.
+    pub fn unpack_assoc_list(
+        &self,
+        elems: &'ast ast::ParenElems,
+        formals: &[Spanned<Name>],
+        kind: &str,
+        owner: &str,
+    ) -> Result<Vec<(usize, &'ast ast::Expr)>> {
+        let mut assocs: Vec<(usize, &'ast ast::ParenElem)> = Vec::new();
+        let mut named = None;
+        let mut had_fails = false;
+        for (pos, elem) in elems.value.iter().enumerate() {
+            let index = match elem.choices.value.as_slice() {
+                &[] => {
+                    if let Some(span) = named {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "positional {} association after named association",
+                                kind
+                            ))
+                            .span(elem.span)
+                            .add_note("Named association begins here:")
+                            .span(span),
+                        );
+                        had_fails = true;
+                        continue;
+                    }
+                    if pos >= formals.len() {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "{} has only {} {}s",
+                                owner,
+                                formals.len(),
+                                kind
+                            ))
+                            .span(elem.span),
+                        );
+                        had_fails = true;
+                        continue;
+                    }
+                    pos
+                }
+                &[ast::Expr {
+                    data:
+                        ast::NameExpr(ast::CompoundName {
+                            primary:
+                                ast::PrimaryName {
+                                    kind: ast::PrimaryNameKind::Ident(name),
+                                    ..
+                                },
+                            ref parts,
+                            ..
+                        }),
+                    span,
+                }] if parts.is_empty() => {
+                    named = named.or(Some(elem.span));
+                    match formals.iter().position(|f| f.value == name) {
+                        Some(index) => index,
+                        None => {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "`{}` is not a {} of {}",
+                                    name, kind, owner
+                                ))
+                                .span(span),
+                            );
+                            had_fails = true;
+                            continue;
+                        }
+                    }
+                }
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a valid formal {}",
+                            elem.choices.span.extract(),
+                            kind
+                        ))
+                        .span(elem.choices.span),
+                    );
+                    had_fails = true;
+                    continue;
+                }
+            };
+            if let Some(&(_, prev)) = assocs.iter().find(|&&(i, _)| i == index) {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "{} `{}` is associated more than once",
+                        kind, formals[index].value
+                    ))
+                    .span(elem.span)
+                    .add_note("Previous association was here:")
+                    .span(prev.span),
+                );
+                had_fails = true;
+                continue;
+            }
+            assocs.push((index, elem));
+        }
+        if had_fails {
+            return Err(());
+        }
+        Ok(assocs
+            .into_iter()
+            .filter(|&(_, elem)| elem.expr.data != ast::OpenExpr)
+            .map(|(index, elem)| (index, &elem.expr))
+            .collect())
+    }
+
+    /// Unpack the names of the generics or ports of a component declaration,
+    /// together with their default values.
+    pub fn unpack_comp_formals(
+        &self,
+        decls: &'ast Option<Spanned<Vec<ast::IntfDecl>>>,
+        kind: &str,
+        owner: &str,
+    ) -> Result<Vec<(Spanned<Name>, Option<&'ast ast::Expr>)>> {
+        let mut formals = Vec::new();
+        for decl in decls.iter().flat_map(|decls| decls.value.iter()) {
+            match *decl {
+                ast::IntfDecl::ObjDecl(ref decl) => {
+                    for name in &decl.names {
+                        formals.push((Spanned::new(name.name, name.span), decl.default.as_ref()));
+                    }
+                }
+                ref wrong => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "{} has {}s other than objects, which cannot be associated",
+                            owner, kind
+                        ))
+                        .span(wrong.human_span()),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Ok(formals)
+    }
+
+    /// Resolve the name of an entity, and the architecture that may be given
+    /// in parentheses after it, as in `entity work.foo(rtl)`.
+    pub fn resolve_entity_aspect(
+        &self,
+        name: &'ast ast::CompoundName,
+        scope_id: ScopeRef,
+    ) -> Result<(Spanned<EntityRef>, Option<Spanned<Name>>)> {
+        let (_, defs, name_span, tail) = self.resolve_compound_name(name, scope_id, false)?;
+        let entity = match defs.as_slice() {
+            &[Spanned {
+                value: Def::Entity(id),
+                ..
+            }] => Spanned::new(id, name_span),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not an entity", name_span.extract()))
+                        .span(name_span),
+                );
+                return Err(());
+            }
+        };
+        let arch = match tail {
+            &[] => None,
+            &[ast::NamePart::Call(ref elems)] => match elems.value.as_slice() {
+                &[ast::ParenElem {
+                    ref choices,
+                    expr:
+                        ast::Expr {
+                            data:
+                                ast::NameExpr(ast::CompoundName {
+                                    primary:
+                                        ast::PrimaryName {
+                                            kind: ast::PrimaryNameKind::Ident(n),
+                                            span,
+                                            ..
+                                        },
+                                    ref parts,
+                                    ..
+                                }),
+                            ..
+                        },
+                    ..
+                }] if choices.value.is_empty() && parts.is_empty() => Some(Spanned::new(n, span)),
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not an architecture name",
+                            elems.span.extract()
+                        ))
+                        .span(elems.span),
+                    );
+                    return Err(());
+                }
+            },
+            _ => {
+                let span = Span::union(name_span.end().into(), name.span.end());
+                self.emit(DiagBuilder2::error("invalid name suffix").span(span));
+                return Err(());
+            }
+        };
+        Ok((entity, arch))
+    }
+
+    /// Resolve the name of a component.
+    pub fn resolve_comp_name(
+        &self,
+        name: &'ast ast::CompoundName,
+        scope_id: ScopeRef,
+    ) -> Result<Spanned<CompDeclRef>> {
+        let (_, defs, name_span, tail) = self.resolve_compound_name(name, scope_id, false)?;
+        match defs.as_slice() {
+            &[Spanned {
+                value: Def::Comp(id),
+                ..
+            }] if tail.is_empty() => Ok(Spanned::new(id, name_span)),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a component", name.span.extract()))
+                        .span(name.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the entity, and optionally the architecture, that a component
+    /// instance is bound to.
+    ///
+    /// A configuration specification in the declarative region of the
+    /// instance binds it explicitly, where one that lists the label of the
+    /// instance takes precedence over `all` and `others`. Otherwise the
+    /// default binding applies. See IEEE 1076-2008 section 7.3.
+    pub fn comp_binding(
+        &self,
+        scope_id: ScopeRef,
+        label: Option<Spanned<Name>>,
+        comp: Spanned<CompDeclRef>,
+    ) -> Result<(Spanned<EntityRef>, Option<Spanned<Name>>)> {
+        let decls = match scope_id {
+            ScopeRef::Arch(id) => &self.hir(id)?.decls,
+            ScopeRef::GenBody(id) => &self.hir(id)?.decls,
+            _ => unreachable!("component instance outside of a block"),
+        };
+        let (mut named, mut all, mut others) = (None, None, None);
+        for &decl in decls {
+            let spec = match decl {
+                DeclInBlockRef::CfgSpec(id) => self.ast(id).1,
+                _ => continue,
+            };
+            let name = match spec.spec.value {
+                ast::BlockCompSpec::CompNames(_, ref name)
+                | ast::BlockCompSpec::CompAll(ref name)
+                | ast::BlockCompSpec::CompOthers(ref name) => name,
+                ast::BlockCompSpec::Block(_) => continue,
+            };
+            if self.resolve_comp_name(name, scope_id)?.value != comp.value {
+                continue;
+            }
+            match spec.spec.value {
+                ast::BlockCompSpec::CompNames(ref labels, _) => {
+                    let label = label.map(|l| l.value);
+                    if labels.iter().any(|l| Some(l.name) == label) {
+                        named = Some(spec);
+                    }
+                }
+                ast::BlockCompSpec::CompAll(_) => all = Some(spec),
+                _ => others = Some(spec),
+            }
+        }
+        let spec = match named.or(all).or(others) {
+            Some(spec) => spec,
+            None => return self.default_binding(scope_id, comp),
+        };
+
+        if let Some(elems) = spec.bind.generics.as_ref().or(spec.bind.ports.as_ref()) {
+            self.emit(
+                DiagBuilder2::error("generic and port maps in binding indications not supported")
+                    .span(elems.span),
+            );
+            return Err(());
+        }
+        match spec.bind.entity {
+            Some(ast::EntityAspect::Entity(ref name)) => self.resolve_entity_aspect(name, scope_id),
+            Some(ast::EntityAspect::Cfg(ref name)) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "binding to configuration `{}` not supported",
+                        name.span.extract()
+                    ))
+                    .span(name.span),
+                );
+                Err(())
+            }
+            Some(ast::EntityAspect::Open) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "instance of component `{}` is left unbound",
+                        comp.span.extract()
+                    ))
+                    .span(spec.bind.span)
+                    .add_note("Open bindings are not supported."),
+                );
+                Err(())
+            }
+            None => self.default_binding(scope_id, comp),
+        }
+    }
+
+    /// Determine the entity a component is bound to by default.
+    ///
+    /// This is the entity with the same name as the component in the library
+    /// of the design unit that contains the instance. See IEEE 1076-2008
+    /// section 7.3.3.
+    pub fn default_binding(
+        &self,
+        scope_id: ScopeRef,
+        comp: Spanned<CompDeclRef>,
+    ) -> Result<(Spanned<EntityRef>, Option<Spanned<Name>>)> {
+        let name = self.ast(comp.value).1.name.value;
+        let found = self
+            .hir(self.block_lib(scope_id)?)?
+            .entities
+            .iter()
+            .cloned()
+            .find(|&id| self.ast(id).2.name.value == name);
+        match found {
+            Some(entity) => Ok((Spanned::new(entity, comp.span), None)),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "no entity `{}` to bind component `{}` to",
+                        name, name
+                    ))
+                    .span(comp.span)
+                    .add_note(
+                        "Without a configuration specification, a component is bound to the \
+                         entity of the same name in the same library. See IEEE 1076-2008 \
+                         section 7.3.3.",
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the library of the design unit that contains a block.
+    fn block_lib(&self, scope_id: ScopeRef) -> Result<LibRef> {
+        match scope_id {
+            ScopeRef::Arch(id) => Ok(self.hir(self.hir(id)?.entity)?.lib),
+            ScopeRef::GenBody(id) => self.block_lib(self.hir(id)?.parent),
+            _ => unreachable!("block outside of an architecture"),
+        }
+    }
+
+    /// Unpack the block configuration of an architecture of an entity.
+    ///
+    /// See IEEE 1076-2008 section 3.4.2.
+    pub fn unpack_arch_cfg(
+        &self,
+        entity: EntityRef,
+        cfg: &'ast ast::BlockCompCfg,
+    ) -> Result<hir::BlockCfg> {
+        let name = self.unpack_block_cfg_name(cfg)?;
+        let found = self
+            .archs(self.hir(entity)?.lib)?
+            .by_entity
+            .get(&entity)
+            .and_then(|archs| archs.by_name.get(&name.value));
+        match found {
+            Some(&arch) => self.unpack_block_cfg(arch, name, &cfg.decls),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not an architecture of entity `{}`",
+                        name.value,
+                        self.hir(entity)?.name.value
+                    ))
+                    .span(name.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Unpack the configurations of the generate statements and component
+    /// instances within a block.
+    ///
+    /// The names of components and entities are resolved within the
+    /// architecture, including the ones in nested generate statements.
+    pub fn unpack_block_cfg(
+        &self,
+        arch: ArchRef,
+        name: Spanned<Name>,
+        decls: &'ast [ast::DeclItem],
+    ) -> Result<hir::BlockCfg> {
+        let mut blocks = Vec::new();
+        let mut comps = Vec::new();
+        for decl in decls {
+            match *decl {
+                ast::DeclItem::BlockCompCfg(ref cfg) => match cfg.spec.value {
+                    ast::BlockCompSpec::Block(_) => {
+                        let name = self.unpack_block_cfg_name(cfg)?;
+                        blocks.push(self.unpack_block_cfg(arch, name, &cfg.decls)?);
+                    }
+                    _ => comps.push(self.unpack_comp_cfg(arch, cfg)?),
+                },
+                ast::DeclItem::UseClause(..) | ast::DeclItem::AttrDecl(..) => (),
+                ref wrong => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "{} not allowed in a block configuration",
+                            wrong.desc()
+                        ))
+                        .span(wrong.human_span()),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Ok(hir::BlockCfg {
+            name: name,
+            blocks: blocks,
+            comps: comps,
+        })
+    }
+
+    /// Unpack the name of the architecture or generate statement configured
+    /// by a block configuration.
+    fn unpack_block_cfg_name(&self, cfg: &'ast ast::BlockCompCfg) -> Result<Spanned<Name>> {
+        let name = match cfg.spec.value {
+            ast::BlockCompSpec::Block(ast::CompoundName {
+                primary:
+                    ast::PrimaryName {
+                        kind: ast::PrimaryNameKind::Ident(name),
+                        span,
+                        ..
+                    },
+                ref parts,
+                ..
+            }) if parts.is_empty() => Spanned::new(name, span),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not an architecture or generate label",
+                        cfg.spec.span.extract()
+                    ))
+                    .span(cfg.spec.span)
+                    .add_note(
+                        "Block configurations of individual generate iterations are not supported.",
+                    ),
+                );
+                return Err(());
+            }
+        };
+        if cfg.bind.entity.is_some() || cfg.bind.generics.is_some() || cfg.bind.ports.is_some() {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "block configuration of `{}` cannot have a binding indication",
+                    name.value
+                ))
+                .span(cfg.bind.span),
+            );
+            return Err(());
+        }
+        Ok(name)
+    }
+
+    /// Unpack a component configuration.
+    ///
+    /// See IEEE 1076-2008 section 3.4.3.
+    pub fn unpack_comp_cfg(
+        &self,
+        arch: ArchRef,
+        cfg: &'ast ast::BlockCompCfg,
+    ) -> Result<hir::CompCfg> {
+        let (insts, name) = match cfg.spec.value {
+            ast::BlockCompSpec::CompNames(ref labels, ref name) => (
+                hir::CfgInsts::Labels(
+                    labels
+                        .iter()
+                        .map(|l| Spanned::new(l.name, l.span))
+                        .collect(),
+                ),
+                name,
+            ),
+            ast::BlockCompSpec::CompOthers(ref name) => (hir::CfgInsts::Others, name),
+            ast::BlockCompSpec::CompAll(ref name) => (hir::CfgInsts::All, name),
+            ast::BlockCompSpec::Block(_) => unreachable!("block configuration"),
+        };
+        let comp = self.resolve_comp_name(name, arch.into())?;
+        if let Some(elems) = cfg.bind.generics.as_ref().or(cfg.bind.ports.as_ref()) {
+            self.emit(
+                DiagBuilder2::error("generic and port maps in binding indications not supported")
+                    .span(elems.span),
+            );
+            return Err(());
+        }
+
+        // A binding to a configuration adopts the entity, architecture, and
+        // block configuration of that configuration.
+        let (entity, mut block) = match cfg.bind.entity {
+            Some(ast::EntityAspect::Entity(ref name)) => {
+                (Some(self.resolve_entity_aspect(name, arch.into())?), None)
+            }
+            Some(ast::EntityAspect::Cfg(ref name)) => {
+                let (_, defs, name_span, tail) =
+                    self.resolve_compound_name(name, arch.into(), false)?;
+                let id = match defs.as_slice() {
+                    &[Spanned {
+                        value: Def::Cfg(id),
+                        ..
+                    }] if tail.is_empty() => id,
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is not a configuration",
+                                name.span.extract()
+                            ))
+                            .span(name.span),
+                        );
+                        return Err(());
                     }
+                };
+                let hir = self.hir(id)?;
+                let entity = Spanned::new(hir.entity.value, name_span);
+                (
+                    Some((entity, Some(hir.block.name))),
+                    Some(hir.block.clone()),
+                )
+            }
+            Some(ast::EntityAspect::Open) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "instances of component `{}` are left unbound",
+                        comp.span.extract()
+                    ))
+                    .span(cfg.bind.span)
+                    .add_note("Open bindings are not supported."),
+                );
+                return Err(());
+            }
+            None => (None, None),
+        };
+
+        // The nested block configuration configures the architecture of the
+        // entity that the instances are bound to.
+        for decl in &cfg.decls {
+            match *decl {
+                ast::DeclItem::BlockCompCfg(ref nested) if block.is_none() => {
+                    let bound = match entity {
+                        Some((entity, _)) => entity,
+                        None => self.default_binding(arch.into(), comp)?.0,
+                    };
+                    block = Some(self.unpack_arch_cfg(bound.value, nested)?);
                 }
+                ast::DeclItem::UseClause(..) | ast::DeclItem::AttrDecl(..) => (),
                 ref wrong => {
                     self.emit(
                         DiagBuilder2::error(format!(
-                            "a {} cannot appear in a generic clause",
+                            "{} not allowed in a component configuration",
                             wrong.desc()
                         ))
                         .span(wrong.human_span()),
                     );
-                    had_fails = true;
+                    return Err(());
                 }
             }
         }
-        if had_fails {
-            Err(())
-        } else {
-            Ok(())
-        }
+        Ok(hir::CompCfg {
+            insts: insts,
+            comp: comp.value,
+            entity: entity,
+            block: block,
+        })
     }
 
     /// Unpack a generic map from a parenthesized list of elements.
@@ -1104,6 +1965,19 @@ impl_make!(self, id: IntfSignalRef => &hir::IntfSignal {
     Ok(self.sb.arenas.hir.intf_sig.alloc(sig))
 });
 
+// Lower an interface constant to HIR.
+impl_make!(self, id: IntfConstRef => &hir::IntfConst {
+    let (scope_id, decl, subty_id, ident) = self.ast(id);
+    let ctx = AddContext::new(self, scope_id);
+    let init = ctx.add_optional(&decl.default, AddContext::add_expr)?;
+    self.set_type_context_optional(init, TypeCtx::TypeOf(subty_id.into()));
+    Ok(self.sb.arenas.hir.intf_const.alloc(hir::IntfConst {
+        name: Spanned::new(ident.name, ident.span),
+        ty: subty_id,
+        init: init,
+    }))
+});
+
 // Lower a package declaration to HIR.
 impl_make!(self, id: PkgDeclRef => &hir::Package {
     let (outer_scope, ast) = self.ast(id);
@@ -1171,14 +2045,10 @@ impl_make!(self, id: PkgDeclRef => &hir::Package {
                     ast::ObjKind::Signal => {
                         decls.extend(ctx.add_signal_decl::<DeclInPkgRef>(decl)?);
                     }
-                    ast::ObjKind::SharedVar => {
-                        self.emit(
-                            DiagBuilder2::error("not a variable; shared variables may not appear in a package declaration")
-                            .span(decl.human_span())
-                        );
-                        had_fails = true;
-                    }
-                    ast::ObjKind::Var => {
+                    // Shared variables typically hold the objects of protected
+                    // types that are used throughout a design. See IEEE
+                    // 1076-2008 section 6.4.2.4.
+                    ast::ObjKind::Var | ast::ObjKind::SharedVar => {
                         decls.extend(ctx.add_var_decl::<DeclInPkgRef>(decl)?);
                     }
                     ast::ObjKind::File => {
@@ -1256,10 +2126,17 @@ impl_make!(self, id: PkgDeclRef => &hir::Package {
 // Lower a package body to HIR.
 impl_make!(self, id: PkgBodyRef => &hir::PackageBody {
     let (scope_id, ast) = self.ast(id);
-    let pkg = self.unpack_package_name((&ast.name).into(), scope_id)?;
+    // The package of a body that is a design unit is declared in the same
+    // library. See IEEE 1076-2008 section 4.8.
+    let pkg_scope = match scope_id {
+        ScopeRef::CtxItems(ctx_id) => self.ast(ctx_id).0,
+        other => other,
+    };
+    let pkg = self.unpack_package_name((&ast.name).into(), pkg_scope)?;
+    self.subscope(id.into(), scope_id);
     let mut decls = Vec::new();
     let mut had_fails = false;
-    let ctx = AddContext::new(self, scope_id);
+    let ctx = AddContext::new(self, id.into());
     for decl in &ast.decls {
         match *decl {
             ast::DeclItem::SubprogDecl(ref decl) => {
@@ -1296,10 +2173,11 @@ impl_make!(self, id: PkgBodyRef => &hir::PackageBody {
                 self.set_ast(subid, (id.into(), decl));
                 decls.push(subid.into());
             }
+            ast::DeclItem::TypeDecl(ref decl) if is_protected_body(decl) => {
+                decls.push(self.unpack_protected_body(id.into(), decl).into());
+            }
             ast::DeclItem::TypeDecl(ref decl) => {
-                let subid = TypeDeclRef(NodeId::alloc());
-                self.set_ast(subid, (id.into(), decl));
-                decls.push(subid.into());
+                decls.push(ctx.add_type_decl(decl)?.into());
             }
             ast::DeclItem::SubtypeDecl(ref decl) => {
                 let subid = SubtypeDeclRef(NodeId::alloc());
@@ -1318,14 +2196,7 @@ impl_make!(self, id: PkgBodyRef => &hir::PackageBody {
                         );
                         had_fails = true;
                     }
-                    ast::ObjKind::SharedVar => {
-                        self.emit(
-                            DiagBuilder2::error("not a variable; shared variables may not appear in a package body")
-                            .span(decl.human_span())
-                        );
-                        had_fails = true;
-                    }
-                    ast::ObjKind::Var => {
+                    ast::ObjKind::Var | ast::ObjKind::SharedVar => {
                         decls.extend(ctx.add_var_decl::<DeclInPkgBodyRef>(decl)?);
                     }
                     ast::ObjKind::File => {
@@ -1822,6 +2693,7 @@ impl_make!(self, id: SubtypeDeclRef => &hir::SubtypeDecl {
 // Lower an architecture to HIR.
 impl_make!(self, id: ArchRef => &hir::Arch {
     let (lib_id, ctx_id, ast) = self.ast(id);
+    self.subscope(id.into(), ctx_id.into());
     let decls = self.unpack_block_decls(id.into(), &ast.decls, "an architecture")?;
     let stmts = self.unpack_concurrent_stmts(id.into(), &ast.stmts, "an architecture")?;
     let entity_id = *self.archs(lib_id)?.by_arch.get(&id).unwrap();
@@ -1834,24 +2706,96 @@ impl_make!(self, id: ArchRef => &hir::Arch {
     }))
 });
 
+// Lower a configuration declaration to HIR. Its block configuration must
+// configure an architecture of the entity. See IEEE 1076-2008 section 3.4.1.
+impl_make!(self, id: CfgRef => &hir::Cfg {
+    let (_, ctx_id, ast) = self.ast(id);
+    let (entity, arch) = self.resolve_entity_aspect(&ast.target, ctx_id.into())?;
+    if let Some(arch) = arch {
+        self.emit(
+            DiagBuilder2::error(format!(
+                "configuration `{}` must name an entity without architecture",
+                ast.name.value
+            ))
+            .span(arch.span)
+        );
+        return Err(());
+    }
+    let mut block = None;
+    for decl in &ast.decls {
+        match *decl {
+            ast::DeclItem::BlockCompCfg(ref cfg) if block.is_none() => {
+                block = Some(self.unpack_arch_cfg(entity.value, cfg)?);
+            }
+            ast::DeclItem::UseClause(..) | ast::DeclItem::AttrDecl(..) => (),
+            ref wrong => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "{} not allowed in configuration `{}`",
+                        wrong.desc(),
+                        ast.name.value
+                    ))
+                    .span(wrong.human_span())
+                    .add_note(
+                    "A configuration contains exactly one block configuration, for an \
+                     architecture of the entity."
+                )
+                );
+                return Err(());
+            }
+        }
+    }
+    let block = match block {
+        Some(block) => block,
+        None => {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "configuration `{}` does not configure an architecture",
+                    ast.name.value
+                ))
+                .span(ast.name.span)
+                .add_note(format!(
+                    "Add a block configuration `for <architecture> ... end for;` for an \
+                     architecture of entity `{}`.",
+                    entity.span.extract()
+                ))
+            );
+            return Err(());
+        }
+    };
+    Ok(self.sb.arenas.hir.cfg.alloc(hir::Cfg {
+        name: ast.name,
+        entity: entity,
+        block: block,
+    }))
+});
+
 impl_make!(self, id: ProcessStmtRef => &hir::ProcessStmt {
     let (scope_id, ast) = self.ast(id);
     match ast.data {
         ast::ProcStmt {
-            // ref sensitivity,
+            ref sensitivity,
             ref decls,
             ref stmts,
             postponed,
-            ..
         } => {
-            // TODO: map sensititivty
+            self.subscope(id.into(), scope_id);
+            let sensitivity = match *sensitivity {
+                None => hir::ProcessSensitivity::None,
+                Some(ast::Sensitivity::All) => hir::ProcessSensitivity::All,
+                Some(ast::Sensitivity::List(ref names)) => hir::ProcessSensitivity::List(
+                    AddContext::new(self, id.into())
+                        .add_sensitivity_list(Spanned::new(names.iter(), ast.span))?
+                        .value
+                ),
+            };
             let decls = self.unpack_process_decls(id.into(), decls, "a process")?;
             let stmts = self.unpack_sequential_stmts(id.into(), stmts, "a process")?;
             Ok(self.sb.arenas.hir.process_stmt.alloc(hir::ProcessStmt {
                 parent: scope_id,
                 label: ast.label,
                 postponed: postponed,
-                sensitivity: hir::ProcessSensitivity::None,
+                sensitivity: sensitivity,
                 decls: decls,
                 stmts: stmts,
             }))
@@ -1860,6 +2804,338 @@ impl_make!(self, id: ProcessStmtRef => &hir::ProcessStmt {
     }
 });
 
+// Lower a for generate statement to HIR.
+impl_make!(self, id: ForGenStmtRef => &hir::ForGenStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (param, range, body) = match ast.data {
+        ast::ForGenStmt { param, ref range, ref body } => (param, range, body),
+        _ => unreachable!()
+    };
+    let range = AddContext::new(self, scope_id).add_discrete_range(range)?;
+    let body = self.unpack_gen_body(scope_id, body);
+    self.define(body.into(), param.map_into(), Def::Const(id.into()))?;
+    Ok(self.sb.arenas.hir.for_gen_stmt.alloc(hir::ForGenStmt {
+        parent: scope_id,
+        span: ast.span,
+        label: ast.label,
+        param: param,
+        range: range,
+        body: body,
+    }))
+});
+
+// Lower an if generate statement to HIR.
+impl_make!(self, id: IfGenStmtRef => &hir::IfGenStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (conds, alt) = match ast.data {
+        ast::IfGenStmt { ref conds, ref alt } => (conds, alt),
+        _ => unreachable!()
+    };
+    let ctx = AddContext::new(self, scope_id);
+    let mut branches = Vec::new();
+    for &(ref cond, ref body) in conds {
+        let cond = ctx.add_expr(cond)?;
+        self.set_type_context(cond, self.builtin_boolean_type());
+        branches.push((cond, self.unpack_gen_body(scope_id, body)));
+    }
+    let otherwise = alt.as_ref().map(|body| self.unpack_gen_body(scope_id, body));
+    Ok(self.sb.arenas.hir.if_gen_stmt.alloc(hir::IfGenStmt {
+        parent: scope_id,
+        span: ast.span,
+        label: ast.label,
+        branches: branches,
+        otherwise: otherwise,
+    }))
+});
+
+// Lower a case generate statement to HIR.
+impl_make!(self, id: CaseGenStmtRef => &hir::CaseGenStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (switch, cases) = match ast.data {
+        ast::CaseGenStmt { ref switch, ref cases } => (switch, cases),
+        _ => unreachable!()
+    };
+    let ctx = AddContext::new(self, scope_id);
+    let switch = ctx.add_expr(switch)?;
+    let mut lowered = Vec::new();
+    for &(ref choices, ref body) in cases {
+        let choices = ctx.add_choices(choices.as_ref().map(|c| c.iter()))?;
+        lowered.push((choices, self.unpack_gen_body(scope_id, body)));
+    }
+    Ok(self.sb.arenas.hir.case_gen_stmt.alloc(hir::CaseGenStmt {
+        parent: scope_id,
+        span: ast.span,
+        label: ast.label,
+        switch: switch,
+        cases: lowered,
+    }))
+});
+
+// Lower the body of a generate statement to HIR.
+impl_make!(self, id: GenBodyRef => &hir::GenBody {
+    let (scope_id, ast) = self.ast(id);
+    let decls = self.unpack_block_decls(id.into(), &ast.decls, "a generate statement")?;
+    let stmts = self.unpack_concurrent_stmts(id.into(), &ast.stmts, "a generate statement")?;
+    Ok(self.sb.arenas.hir.gen_body.alloc(hir::GenBody {
+        parent: scope_id,
+        label: ast.label,
+        decls: decls,
+        stmts: stmts,
+    }))
+});
+
+impl_make!(self, id: ProtectedBodyRef => &hir::ProtectedBody {
+    let (scope_id, ast) = self.ast(id);
+    let decls = match ast.data {
+        Some(Spanned { value: ast::ProtectedBody(ref decls), .. }) => decls,
+        _ => unreachable!(),
+    };
+
+    // Find the protected type declaration this body belongs to.
+    let mut decl = None;
+    for def in self.resolve_name(ast.name.map_into(), scope_id, false, false)? {
+        if let Def::Type(id) = def.value {
+            if self.protected_methods(id)?.is_some() {
+                decl = Some(id);
+                break;
+            }
+        }
+    }
+    let decl = match decl {
+        Some(d) => d,
+        None => {
+            self.emit(
+                DiagBuilder2::error(format!("`{}` is not a protected type", ast.name.value))
+                .span(ast.name.span)
+                .add_note("A protected type body must follow the declaration of its protected type. See IEEE 1076-2008 section 5.6.3.")
+            );
+            return Err(());
+        }
+    };
+
+    let decls = self.unpack_subprog_decls(id.into(), decls)?;
+    Ok(self.sb.arenas.hir.protected_body.alloc(hir::ProtectedBody {
+        parent: scope_id,
+        name: ast.name,
+        decl: decl,
+        decls: decls,
+    }))
+});
+
+// Lower a concurrent assertion to its equivalent process, which is sensitive to
+// all signals that the assertion reads. See IEEE 1076-2008 section 11.5.
+impl_make!(self, id: ConcAssertStmtRef => &hir::ProcessStmt {
+    let (scope_id, ast) = self.ast(id);
+    let assert = AddContext::new(self, scope_id).add_assert_stmt(ast)?;
+    Ok(self.sb.arenas.hir.process_stmt.alloc(hir::ProcessStmt {
+        parent: scope_id,
+        label: ast.label,
+        postponed: false,
+        sensitivity: hir::ProcessSensitivity::All,
+        decls: Vec::new(),
+        stmts: vec![assert.into()],
+    }))
+});
+
+// Lower a PSL directive to HIR. See IEEE 1076-2008 section 11.9.
+impl_make!(self, id: PslDirectiveRef => &hir::PslDirective {
+    let (scope_id, ast) = self.ast(id);
+    let (kind, prop, report, severity) = match ast.data {
+        ast::PslDirective { kind, ref prop, ref report, ref severity } => {
+            (kind, self.lower_psl_prop(scope_id, prop, &mut vec![])?, report, severity)
+        }
+        ast::AssertStmt { ref cond, ref report, ref severity } => {
+            let prop = self.lower_psl_bool(scope_id, cond, &mut vec![])?;
+            (ast::PslDirectiveKind::Assert, prop, report, severity)
+        }
+        _ => unreachable!(),
+    };
+    let clock = match self.psl_decl(scope_id, None) {
+        Some(&ast::PslDecl { prop: ast::PslProp { data: ast::PslPropData::Bool(ref expr), .. }, .. }) => {
+            Some(self.lower_psl_cond(scope_id, expr)?)
+        }
+        _ => None,
+    };
+    let ctx = AddContext::new(self, scope_id);
+    let report = ctx.add_optional(report, AddContext::add_expr)?;
+    let severity = ctx.add_optional(severity, AddContext::add_expr)?;
+    self.set_type_context_optional(report, self.builtin_string_type());
+    self.set_type_context_optional(severity, self.builtin_severity_type());
+    Ok(self.sb.arenas.hir.psl_directive.alloc(hir::PslDirective {
+        parent: scope_id,
+        span: ast.span,
+        label: ast.label,
+        kind: kind,
+        prop: prop,
+        clock: clock,
+        report: report,
+        severity: severity,
+    }))
+});
+
+// Lower an entity or component instantiation to HIR. A component is replaced
+// by the entity it is bound to, such that the instance looks like a direct
+// instantiation of that entity.
+impl_make!(self, id: CompInstStmtRef => &hir::CompInstStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (target, name, generics, ports) = match ast.data {
+        ast::InstOrCallStmt { target, ref name, ref generics, ref ports } => (target, name, generics, ports),
+        _ => unreachable!()
+    };
+
+    // Resolve the instantiated entity, or the component and the entity it is
+    // bound to.
+    let comp = match target {
+        Some(ast::InstTarget::Entity) => None,
+        _ => Some(self.resolve_comp_name(name, scope_id)?),
+    };
+    let (entity, arch) = match comp {
+        None => self.resolve_entity_aspect(name, scope_id)?,
+        Some(comp) => self.comp_binding(scope_id, ast.label, comp)?,
+    };
+    let entity_hir = self.hir(entity.value)?;
+    let ctx = AddContext::new(self, scope_id);
+    let mut generic_map = Vec::new();
+    let mut port_map = Vec::new();
+    match comp {
+        // Associate the actuals with the generics and ports of the entity. The
+        // type of each generic determines the type of its actual. The port
+        // types are only known in the generic environment of the instance, so
+        // they provide no type context.
+        None => {
+            let owner = format!("entity `{}`", entity_hir.name.value);
+            if let Some(ref elems) = *generics {
+                let mut formals = Vec::new();
+                for &generic in &entity_hir.generics {
+                    formals.push(match generic {
+                        GenericRef::Const(id) => {
+                            let ident = self.ast(id).3;
+                            Spanned::new(ident.name, ident.span)
+                        }
+                        _ => {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "entity `{}` has generics other than constants, which \
+                                     cannot be associated",
+                                    entity_hir.name.value
+                                ))
+                                .span(elems.span)
+                            );
+                            return Err(());
+                        }
+                    });
+                }
+                let actuals = self.unpack_assoc_list(elems, &formals, "generic", &owner)?;
+                for (index, actual) in actuals {
+                    let generic = match entity_hir.generics[index] {
+                        GenericRef::Const(id) => id,
+                        _ => unreachable!()
+                    };
+                    let actual = ctx.add_expr(actual)?;
+                    self.set_type_context(actual, TypeCtx::TypeOf(self.ast(generic).2.into()));
+                    generic_map.push((generic, actual));
+                }
+            }
+            if let Some(ref elems) = *ports {
+                let formals: Vec<_> = entity_hir.ports.iter().map(|&id| {
+                    let ident = self.ast(id).3;
+                    Spanned::new(ident.name, ident.span)
+                }).collect();
+                let actuals = self.unpack_assoc_list(elems, &formals, "port", &owner)?;
+                for (index, actual) in actuals {
+                    let port = entity_hir.ports[index];
+                    port_map.push((port, ctx.add_expr(actual)?));
+                }
+            }
+        }
+
+        // Associate the actuals with the generics and ports of the component,
+        // which correspond to the generics and ports of the entity with the
+        // same name. See IEEE 1076-2008 section 7.3.3.
+        Some(comp) => {
+            let (comp_scope, decl) = self.ast(comp.value);
+            let owner = format!("component `{}`", decl.name.value);
+            let unbound = |name: Spanned<Name>, kind: &str| {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "entity `{}` has no {} `{}`",
+                        entity_hir.name.value, kind, name.value
+                    ))
+                    .span(name.span)
+                    .add_note(format!(
+                        "The {} of {} corresponds to the {} of the same name of entity `{}`, \
+                         which it is bound to. See IEEE 1076-2008 section 7.3.3.",
+                        kind, owner, kind, entity_hir.name.value
+                    ))
+                );
+            };
+
+            // Generics that are left open assume the default value given in
+            // the component declaration.
+            let formals = self.unpack_comp_formals(&decl.generics, "generic", &owner)?;
+            let names: Vec<_> = formals.iter().map(|&(name, _)| name).collect();
+            let mut actuals = Vec::new();
+            if let Some(ref elems) = *generics {
+                for (index, actual) in self.unpack_assoc_list(elems, &names, "generic", &owner)? {
+                    actuals.push((index, ctx.add_expr(actual)?));
+                }
+            }
+            for (index, &(_, default)) in formals.iter().enumerate() {
+                match default {
+                    Some(default) if actuals.iter().all(|&(i, _)| i != index) => {
+                        let actual = AddContext::new(self, comp_scope).add_expr(default)?;
+                        actuals.push((index, actual));
+                    }
+                    _ => (),
+                }
+            }
+            for (index, actual) in actuals {
+                let generic = entity_hir.generics.iter().find_map(|&generic| match generic {
+                    GenericRef::Const(id) if self.ast(id).3.name == names[index].value => Some(id),
+                    _ => None,
+                });
+                let generic = match generic {
+                    Some(generic) => generic,
+                    None => {
+                        unbound(names[index], "generic");
+                        return Err(());
+                    }
+                };
+                self.set_type_context(actual, TypeCtx::TypeOf(self.ast(generic).2.into()));
+                generic_map.push((generic, actual));
+            }
+
+            if let Some(ref elems) = *ports {
+                let formals = self.unpack_comp_formals(&decl.ports, "port", &owner)?;
+                let names: Vec<_> = formals.iter().map(|&(name, _)| name).collect();
+                for (index, actual) in self.unpack_assoc_list(elems, &names, "port", &owner)? {
+                    let port = entity_hir.ports.iter().cloned().find(|&port| {
+                        self.ast(port).3.name == names[index].value
+                    });
+                    let port = match port {
+                        Some(port) => port,
+                        None => {
+                            unbound(names[index], "port");
+                            return Err(());
+                        }
+                    };
+                    port_map.push((port, ctx.add_expr(actual)?));
+                }
+            }
+        }
+    }
+
+    Ok(self.sb.arenas.hir.comp_inst_stmt.alloc(hir::CompInstStmt {
+        parent: scope_id,
+        label: ast.label,
+        comp: comp,
+        entity: entity,
+        arch: arch,
+        generic_map: generic_map,
+        port_map: port_map,
+    }))
+});
+
 impl_make!(self, id: SigAssignStmtRef => &hir::SigAssignStmt {
     let (scope_id, ast) = self.ast(id);
     match ast.data {
@@ -1957,6 +3233,7 @@ impl_make!(self, id: SubprogBodyRef => &hir::SubprogBody {
         ast::SubprogData::Body { ref decls, ref stmts } => (decls, stmts),
         _ => unreachable!(),
     };
+    self.subscope(id.into(), scope_id);
     let decls = self.unpack_subprog_decls(id.into(), decls)?;
     let stmts = self.unpack_sequential_stmts(id.into(), stmts, "a subprogram")?;
     Ok(self.sb.arenas.hir.subprog_body.alloc(hir::SubprogBody {
@@ -1999,9 +3276,63 @@ impl_make!(self, id: SubprogInstRef => &hir::SubprogInst {
     }))
 });
 
+impl_make!(self, id: LatentPkgRef => Spanned<PkgRef> {
+    let (scope_id, ast) = self.ast(id);
+    let (defs, span) = match ast {
+        LatentName::Simple(n) => (self.resolve_name(n.map_into(), scope_id, false, false)?, n.span),
+        LatentName::Primary(n) => {
+            let name = self.resolvable_from_primary_name(n)?;
+            (self.resolve_name(name, scope_id, false, false)?, n.span)
+        }
+        LatentName::Compound(n) => {
+            let (_, defs, span, tail) = self.resolve_compound_name(n, scope_id, false)?;
+            if !tail.is_empty() {
+                let span = Span::union(span.end().into(), n.span.end());
+                self.emit(DiagBuilder2::error("invalid name suffix").span(span));
+                return Err(());
+            }
+            (defs, span)
+        }
+    };
+    match defs.as_slice() {
+        &[Spanned{ value: Def::Pkg(id), .. }] => Ok(Spanned::new(PkgRef::Decl(id), span)),
+        &[Spanned{ value: Def::PkgInst(id), .. }] => Ok(Spanned::new(PkgRef::Inst(id), span)),
+        _ => {
+            self.emit(
+                DiagBuilder2::error(format!("`{}` is not a package", span.extract()))
+                .span(span)
+            );
+            Err(())
+        }
+    }
+});
+
 impl_make!(self, id: LatentTypeMarkRef => Spanned<TypeMarkRef> {
     let (scope_id, ast) = self.ast(id);
     let ctx = TermContext::new(self, scope_id);
     let term = ctx.termify_latent_name(ast)?;
     ctx.term_to_type_mark(term)
 });
+
+/// Check whether a type declaration is the body of a protected type.
+fn is_protected_body(decl: &ast::TypeDecl) -> bool {
+    match decl.data {
+        Some(Spanned {
+            value: ast::ProtectedBody(..),
+            ..
+        }) => true,
+        _ => false,
+    }
+}
+
+/// Determine the name that a boolean operand of a PSL property consists of,
+/// which may refer to a declared property or sequence.
+fn psl_decl_name(expr: &ast::Expr) -> Option<Name> {
+    match expr.data {
+        ast::NameExpr(ref name) if name.parts.is_empty() => match name.primary.kind {
+            ast::PrimaryNameKind::Ident(name) => Some(name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
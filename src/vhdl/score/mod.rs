@@ -10,7 +10,9 @@ use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+use moore_common::design_units::{DesignUnit, DesignUnitKind};
 use moore_common::errors::*;
+use moore_common::foreign::{ForeignLang, ForeignUnit};
 use moore_common::name::*;
 use moore_common::score::{GenericContext, NodeMaker, NodeStorage, Result};
 use moore_common::source::*;
@@ -19,13 +21,15 @@ use moore_common::NodeId;
 use moore_common::{Session, Verbosity};
 
 use llhd;
-use num::{BigInt, Signed};
+use num::{BigInt, Signed, ToPrimitive};
 use typed_arena::Arena;
 
 use crate::arenas::Alloc;
 use crate::builtin;
 pub use crate::builtin::*;
 use crate::codegen::Codegen;
+use crate::external::ExportedSignal;
+use crate::generic_env::*;
 use crate::hir;
 use crate::konst::*;
 use crate::lazy::*;
@@ -50,6 +54,7 @@ macro_rules! impl_make {
 }
 
 mod cval;
+mod interp;
 mod lower_hir;
 mod scope;
 
@@ -81,6 +86,9 @@ pub struct ScoreBoard<'ast, 'ctx> {
     libs: RefCell<HashMap<LibRef, Vec<&'ast ast::DesignUnit>>>,
     /// A lookup table of library names.
     lib_names: RefCell<HashMap<Name, LibRef>>,
+    /// A table of the design units declared in other languages, keyed by the
+    /// span of the entity declarations that stand in for them.
+    foreign_table: RefCell<HashMap<Span, ForeignUnit>>,
     /// A table of AST nodes.
     ast_table: RefCell<AstTable<'ast>>,
     /// A table of HIR nodes.
@@ -98,8 +106,20 @@ pub struct ScoreBoard<'ast, 'ctx> {
     lldecl_table: RefCell<HashMap<NodeId, llhd::ir::Value>>,
     /// A table of LLHD definitions.
     lldef_table: RefCell<HashMap<NodeId, llhd::ir::Value>>,
-    /// A table of LLHD definitions.
-    llunit_table: RefCell<HashMap<NodeId, llhd::ir::UnitId>>,
+    /// A table of LLHD units, one per generic environment.
+    llunit_table: RefCell<HashMap<(NodeId, GenericEnv), llhd::ir::UnitId>>,
+    /// A table of the LLHD values of the signals in the unit currently being
+    /// generated.
+    pub llsig_table: RefCell<HashMap<NodeId, llhd::ir::Value>>,
+    /// A table of the copies of signals delayed by one delta cycle in the unit
+    /// currently being generated. These implement the signal attributes.
+    pub lldelayed_table: RefCell<HashMap<NodeId, llhd::ir::Value>>,
+    /// A table of the LLHD values of the variables in the process currently
+    /// being generated.
+    pub llvar_table: RefCell<HashMap<NodeId, llhd::ir::Value>>,
+    /// A table of the signals in the unit currently being generated that carry
+    /// the value of signals exported by the instances within it.
+    pub llexport_table: RefCell<Vec<(ExportedSignal, llhd::ir::Value)>>,
     /// A table of types.
     pub ty_table: RefCell<HashMap<NodeId, &'ctx Ty>>,
     /// A table of scopes.
@@ -112,8 +132,30 @@ pub struct ScoreBoard<'ast, 'ctx> {
     pub typeck_table: RefCell<HashMap<NodeId, Result<()>>>,
     /// A table of typeval results.
     pub typeval_table: RefCell<HashMap<NodeId, Result<&'ctx Ty>>>,
+    /// A table of the definitions that overloaded operators and function
+    /// calls resolved to.
+    pub overload_table: RefCell<HashMap<NodeId, Spanned<Def>>>,
     /// A table of scopes. Revised; will replace `scope_table` and `def_table`.
     pub scope2_table: RefCell<HashMap<ScopeRef, crate::scope::Scope>>,
+    /// The interned generic environments.
+    pub generic_envs: RefCell<Vec<GenericEnvData<'ctx>>>,
+    /// The generic environment in which nodes are currently evaluated.
+    pub generic_env: Cell<GenericEnv>,
+    /// Whether the current evaluation has consulted the generic environment.
+    pub generic_dep: Cell<bool>,
+    /// A table of types that depend on the generic environment.
+    pub env_ty_table: RefCell<HashMap<(NodeId, GenericEnv), &'ctx Ty>>,
+    /// A table of typeval results that depend on the generic environment.
+    pub env_typeval_table: RefCell<HashMap<(NodeId, GenericEnv), Result<&'ctx Ty>>>,
+    /// A table of constant values that depend on the generic environment.
+    pub env_const_table: RefCell<HashMap<(NodeId, GenericEnv), &'ctx Const>>,
+    /// A table of the bodies of subprogram declarations.
+    pub subprog_body_table: RefCell<HashMap<SubprogDeclRef, SubprogBodyRef>>,
+    /// The number of nested subprogram calls currently being interpreted.
+    pub call_depth: Cell<usize>,
+    /// The number of statements interpreted during the evaluation of the
+    /// outermost subprogram call.
+    pub call_steps: Cell<usize>,
 }
 
 impl<'ast, 'ctx> ScoreBoard<'ast, 'ctx> {
@@ -124,6 +166,7 @@ impl<'ast, 'ctx> ScoreBoard<'ast, 'ctx> {
             span_table: RefCell::new(HashMap::new()),
             libs: RefCell::new(HashMap::new()),
             lib_names: RefCell::new(HashMap::new()),
+            foreign_table: RefCell::new(HashMap::new()),
             ast_table: RefCell::new(AstTable::new()),
             hir_table: RefCell::new(HirTable::new()),
             def_table: RefCell::new(HashMap::new()),
@@ -132,13 +175,27 @@ impl<'ast, 'ctx> ScoreBoard<'ast, 'ctx> {
             lldecl_table: RefCell::new(HashMap::new()),
             lldef_table: RefCell::new(HashMap::new()),
             llunit_table: RefCell::new(HashMap::new()),
+            llsig_table: RefCell::new(HashMap::new()),
+            lldelayed_table: RefCell::new(HashMap::new()),
+            llvar_table: RefCell::new(HashMap::new()),
+            llexport_table: RefCell::new(Vec::new()),
             ty_table: RefCell::new(HashMap::new()),
             scope_table: RefCell::new(HashMap::new()),
             const_table: RefCell::new(HashMap::new()),
             tyctx_table: RefCell::new(HashMap::new()),
             typeck_table: RefCell::new(HashMap::new()),
             typeval_table: RefCell::new(HashMap::new()),
+            overload_table: RefCell::new(HashMap::new()),
             scope2_table: RefCell::new(HashMap::new()),
+            generic_envs: RefCell::new(vec![Default::default()]),
+            generic_env: Cell::new(Default::default()),
+            generic_dep: Cell::new(false),
+            env_ty_table: RefCell::new(HashMap::new()),
+            env_typeval_table: RefCell::new(HashMap::new()),
+            env_const_table: RefCell::new(HashMap::new()),
+            subprog_body_table: RefCell::new(HashMap::new()),
+            call_depth: Cell::new(0),
+            call_steps: Cell::new(0),
         };
         builtin::register_builtins(&sb);
         sb
@@ -181,6 +238,50 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         self.sb.lib_names.borrow_mut().insert(name, id);
     }
 
+    /// Add the stand-in for a design unit declared in another language to a
+    /// library. This must happen before the library is lowered to HIR.
+    pub fn add_foreign_entity(
+        &self,
+        id: LibRef,
+        unit: ForeignUnit,
+        stand_in: &'ast [ast::DesignUnit],
+    ) {
+        for du in stand_in {
+            if let ast::DesignUnitData::EntityDecl(ref decl) = du.data {
+                self.sb
+                    .foreign_table
+                    .borrow_mut()
+                    .insert(decl.span, unit.clone());
+            }
+        }
+        self.sb
+            .libs
+            .borrow_mut()
+            .get_mut(&id)
+            .unwrap()
+            .extend(stand_in);
+    }
+
+    /// Register a design unit with the registry shared by all languages.
+    fn register_unit(&self, lib: Name, name: Spanned<Name>, kind: DesignUnitKind, id: NodeId) {
+        self.sess.units.add(DesignUnit {
+            lib,
+            name: name.value,
+            lang: ForeignLang::Vhdl,
+            kind,
+            id,
+            span: name.span,
+            interface: None,
+        });
+    }
+
+    /// Find the design unit declared in another language that an entity
+    /// stands in for, if any.
+    pub fn foreign_entity(&self, id: EntityRef) -> Option<ForeignUnit> {
+        let decl = self.ast(id).2;
+        self.sb.foreign_table.borrow().get(&decl.span).cloned()
+    }
+
     /// Obtain the span associated with a node ID.
     pub fn span<I>(&self, id: I) -> Option<Span>
     where
@@ -348,6 +449,44 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         Ok(node)
     }
 
+    /// Find the body of a package declared in a library.
+    pub fn pkg_body(&self, id: PkgDeclRef) -> Result<Option<PkgBodyRef>> {
+        let (scope_id, ast) = self.ast(id);
+        let lib_id = match scope_id {
+            ScopeRef::CtxItems(ctx_id) => match self.ast(ctx_id).0 {
+                ScopeRef::Lib(lib_id) => lib_id,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        Ok(self
+            .hir(lib_id)?
+            .pkg_bodies
+            .iter()
+            .cloned()
+            .find(|&body| self.ast(body).1.name.value == ast.name.value))
+    }
+
+    /// Find the body of a subprogram declaration.
+    ///
+    /// Bodies are associated with their declarations as the definitions of the
+    /// enclosing declarative region are gathered. For subprograms declared in
+    /// a package, this is the region of the package body.
+    pub fn subprog_body(&self, id: SubprogDeclRef) -> Result<Option<SubprogBodyRef>> {
+        if let Some(&body) = self.sb.subprog_body_table.borrow().get(&id) {
+            return Ok(Some(body));
+        }
+        let region = match self.hir(id)?.parent {
+            ScopeRef::Pkg(pkg) => match self.pkg_body(pkg)? {
+                Some(body) => body.into(),
+                None => return Ok(None),
+            },
+            parent => parent,
+        };
+        self.defs(region)?;
+        Ok(self.sb.subprog_body_table.borrow().get(&id).cloned())
+    }
+
     pub fn lldecl<I>(&self, id: I) -> Result<llhd::ir::Value>
     where
         I: 'ctx + Copy + Debug + Into<NodeId>,
@@ -410,7 +549,8 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         I: 'ctx + Copy + Debug + Into<NodeId>,
         ScoreContext<'lazy, 'sb, 'ast, 'ctx>: NodeMaker<I, llhd::ir::UnitId>,
     {
-        if let Some(node) = self.sb.llunit_table.borrow().get(&id.into()).cloned() {
+        let key = (id.into(), self.generic_env());
+        if let Some(node) = self.sb.llunit_table.borrow().get(&key).cloned() {
             return Ok(node);
         }
         if self.sess.opts.trace_scoreboard {
@@ -424,7 +564,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             .sb
             .llunit_table
             .borrow_mut()
-            .insert(id.into(), node.clone())
+            .insert(key, node.clone())
             .is_some()
         {
             panic!("node should not exist");
@@ -441,26 +581,43 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         I: 'ctx + Copy + Debug + Into<NodeId>,
         ScoreContext<'lazy, 'sb, 'ast, 'ctx>: NodeMaker<I, &'ctx Ty>,
     {
+        let key = (id.into(), self.generic_env());
         if let Some(node) = self.sb.ty_table.borrow().get(&id.into()).cloned() {
             return Ok(node);
         }
         if let Some(&node) = self.sb.typeval_table.borrow().get(&id.into()) {
             return node;
         }
+        if let Some(node) = self.sb.env_ty_table.borrow().get(&key).cloned() {
+            self.mark_generic_dep();
+            return Ok(node);
+        }
+        if let Some(&node) = self.sb.env_typeval_table.borrow().get(&key) {
+            self.mark_generic_dep();
+            return node;
+        }
         if self.sess.opts.trace_scoreboard {
             debugln!("[SB][VHDL] make ty for {:?}", id);
         }
-        let node = self.make(id)?;
+        let (node, dep) = self.track_generic_dep(|| self.make(id));
+        let node = node?;
         if self.sess.opts.trace_scoreboard {
             debugln!("[SB][VHDL] ty for {:?} is {:?}", id, node);
         }
-        if self
-            .sb
-            .ty_table
-            .borrow_mut()
-            .insert(id.into(), node)
-            .is_some()
-        {
+        let existed = if dep {
+            self.sb
+                .env_ty_table
+                .borrow_mut()
+                .insert(key, node)
+                .is_some()
+        } else {
+            self.sb
+                .ty_table
+                .borrow_mut()
+                .insert(id.into(), node)
+                .is_some()
+        };
+        if existed {
             self.emit(DiagBuilder2::bug(format!(
                 "type for {:?} already in the scoreboard",
                 id
@@ -527,23 +684,36 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         I: 'ctx + Copy + Debug + Into<NodeId>,
         ScoreContext<'lazy, 'sb, 'ast, 'ctx>: NodeMaker<I, &'ctx Const>,
     {
+        let key = (id.into(), self.generic_env());
         if let Some(node) = self.sb.const_table.borrow().get(&id.into()).cloned() {
             return Ok(node);
         }
+        if let Some(node) = self.sb.env_const_table.borrow().get(&key).cloned() {
+            self.mark_generic_dep();
+            return Ok(node);
+        }
         if self.sess.opts.trace_scoreboard {
             debugln!("[SB][VHDL] make const for {:?}", id);
         }
-        let node = self.make(id)?;
+        let (node, dep) = self.track_generic_dep(|| self.make(id));
+        let node = node?;
         if self.sess.opts.trace_scoreboard {
             debugln!("[SB][VHDL] const for {:?} is {:?}", id, node);
         }
-        if self
-            .sb
-            .const_table
-            .borrow_mut()
-            .insert(id.into(), node)
-            .is_some()
-        {
+        let existed = if dep {
+            self.sb
+                .env_const_table
+                .borrow_mut()
+                .insert(key, node)
+                .is_some()
+        } else {
+            self.sb
+                .const_table
+                .borrow_mut()
+                .insert(id.into(), node)
+                .is_some()
+        };
+        if existed {
             panic!("node should not exist");
         }
         Ok(node)
@@ -603,6 +773,35 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             None => (),
         }
     }
+
+    /// Determine the definition an overloaded operator or function call
+    /// resolved to.
+    ///
+    /// The overload is resolved as part of the expression's typeval.
+    pub fn overload(&self, id: ExprRef) -> Result<Spanned<Def>> {
+        self.lazy_typeval(id)?;
+        match self.sb.overload_table.borrow().get(&id.into()) {
+            Some(&def) => Ok(def),
+            None => {
+                self.bug(id, format!("{:?} has no resolved overload", id));
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the procedure a procedure call statement resolved to.
+    ///
+    /// The overload is resolved as part of the statement's typeck.
+    pub fn call_overload(&self, id: CallStmtRef) -> Result<Spanned<Def>> {
+        self.lazy_typeck(id)?;
+        match self.sb.overload_table.borrow().get(&id.into()) {
+            Some(&def) => Ok(def),
+            None => {
+                self.bug(id, format!("{:?} has no resolved overload", id));
+                Err(())
+            }
+        }
+    }
 }
 
 // Wrapper types around ValueRef such that we can distinguish in the
@@ -619,6 +818,14 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<LibRef, &'ctx hir::Lib>
 {
     fn make(&self, id: LibRef) -> Result<&'ctx hir::Lib> {
         let mut lib = hir::Lib::new();
+        let lib_name = self
+            .sb
+            .lib_names
+            .borrow()
+            .iter()
+            .find(|&(_, &lib_id)| lib_id == id)
+            .map(|(&name, _)| name)
+            .unwrap();
         for du in &self.sb.libs.borrow()[&id] {
             let ctx_id = CtxItemsRef(NodeId::alloc());
             self.set_ast(ctx_id, (id.into(), du.ctx.as_slice()));
@@ -627,16 +834,23 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<LibRef, &'ctx hir::Lib>
                     let subid = EntityRef(NodeId::alloc());
                     self.set_ast(subid, (id, ctx_id, decl));
                     lib.entities.push(subid);
+                    // Stand-ins are registered in the language they stand in
+                    // for.
+                    if !self.sb.foreign_table.borrow().contains_key(&decl.span) {
+                        self.register_unit(lib_name, decl.name, DesignUnitKind::Entity, subid.0);
+                    }
                 }
                 ast::DesignUnitData::CfgDecl(ref decl) => {
                     let subid = CfgRef(NodeId::alloc());
                     self.set_ast(subid, (id, ctx_id, decl));
                     lib.cfgs.push(subid);
+                    self.register_unit(lib_name, decl.name, DesignUnitKind::Config, subid.0);
                 }
                 ast::DesignUnitData::PkgDecl(ref decl) => {
                     let subid = PkgDeclRef(NodeId::alloc());
                     self.set_ast(subid, (ctx_id.into(), decl));
                     lib.pkg_decls.push(subid);
+                    self.register_unit(lib_name, decl.name, DesignUnitKind::Package, subid.0);
                 }
                 ast::DesignUnitData::PkgInst(ref decl) => {
                     let subid = PkgInstRef(NodeId::alloc());
@@ -755,6 +969,20 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 Err(())
             }
         } else {
+            // Enumeration literals and subprograms are overloadable, such that
+            // the definitions in the enclosing scopes remain visible. See IEEE
+            // 1076-2008 section 12.3.
+            let overloadable = |def: &Spanned<Def>| match def.value {
+                Def::Enum(..) | Def::BuiltinOp(..) | Def::Subprog(..) => true,
+                _ => false,
+            };
+            if let Some(parent_id) = parent_id.filter(|_| found_defs.iter().all(overloadable)) {
+                for def in self.resolve_name(name, parent_id, only_defs, true)? {
+                    if overloadable(&def) && !found_defs.iter().any(|d| d.value == def.value) {
+                        found_defs.push(def);
+                    }
+                }
+            }
             if self.sess.opts.trace_scoreboard {
                 debugln!("[SB][VHDL] resolved {:?} to {:?}", name.value, found_defs);
             }
@@ -850,22 +1078,22 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
 
     /// Get the builtin type `standard.boolean`.
     pub fn builtin_boolean_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(BOOLEAN_TYPE.named_ty())
     }
 
     /// Get the builtin type `standard.time`.
     pub fn builtin_time_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(TIME_TYPE.named_ty())
     }
 
     /// Get the builtin type `standard.string`.
     pub fn builtin_string_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(STRING_TYPE.named_ty())
     }
 
-    /// Get the builtin type `standard.severity`.
+    /// Get the builtin type `standard.severity_level`.
     pub fn builtin_severity_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(SEVERITY_LEVEL_TYPE.named_ty())
     }
 }
 
@@ -985,11 +1213,12 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ArchRef, llhd::ir::UnitId>
         let mut out_tys = Vec::new();
         let mut in_names = Vec::new();
         let mut out_names = Vec::new();
+        let mut in_ports = Vec::new();
+        let mut out_ports = Vec::new();
         let mut sig = llhd::ir::Signature::new();
         for &port in &entity.ports {
             let hir = self.hir(port)?;
-            let ty = self.map_type(self.ty(hir.ty)?)?;
-            // let ty = llhd::void_ty();
+            let ty = llhd::signal_ty(self.map_type(self.ty(hir.ty)?)?);
             match hir.mode {
                 hir::IntfSignalMode::In
                 | hir::IntfSignalMode::Inout
@@ -997,6 +1226,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ArchRef, llhd::ir::UnitId>
                     sig.add_input(ty.clone());
                     in_tys.push(ty.clone());
                     in_names.push(hir.name.value);
+                    in_ports.push(port);
                 }
                 _ => (),
             }
@@ -1007,13 +1237,28 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ArchRef, llhd::ir::UnitId>
                     sig.add_output(ty.clone());
                     out_tys.push(ty.clone());
                     out_names.push(hir.name.value);
+                    out_ports.push(port);
                 }
                 _ => (),
             }
         }
 
-        // Create a new entity into which we will generate all the code.
-        let name = format!("{}_{}", entity.name.value, hir.name.value);
+        // Signals exported to external names in the instantiating unit become
+        // additional outputs.
+        let exports = self.exported_signals();
+        for export in &exports {
+            let ty = self.with_generic_env(export.env, || self.ty(export.signal))?;
+            sig.add_output(llhd::signal_ty(self.map_type(ty)?));
+        }
+
+        // Create a new entity into which we will generate all the code. Every
+        // generic environment other than the default one yields a separate
+        // entity.
+        let mut name = format!("{}_{}", entity.name.value, hir.name.value);
+        let env = self.generic_env();
+        if env != GenericEnv::default() {
+            name.push_str(&format!(".generic{}", env.0));
+        }
         let mut entity = llhd::ir::UnitData::new(
             llhd::ir::UnitKind::Entity,
             llhd::ir::UnitName::Global(name),
@@ -1029,16 +1274,39 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ArchRef, llhd::ir::UnitId>
         for (arg, &name) in builder.output_args().zip(out_names.iter()) {
             builder.set_name(arg, name.as_str().to_string());
         }
+        let export_args: Vec<_> = builder.output_args().skip(out_ports.len()).collect();
 
-        // Generate the code for the declarations in the architecture.
-        for &decl_id in &hir.decls {
-            self.codegen(decl_id, &mut builder)?;
+        // Make the ports available as signals. Ports of mode `inout` are
+        // driven through their output argument.
+        let outer_sigs = self.sb.llsig_table.replace(HashMap::new());
+        let outer_delayed = self.sb.lldelayed_table.replace(HashMap::new());
+        let outer_exports = self.sb.llexport_table.replace(Vec::new());
+        {
+            let mut sigs = self.sb.llsig_table.borrow_mut();
+            for (arg, &port) in builder.input_args().zip(in_ports.iter()) {
+                sigs.insert(port.into(), arg);
+            }
+            for (arg, &port) in builder.output_args().zip(out_ports.iter()) {
+                sigs.insert(port.into(), arg);
+            }
         }
 
-        // Generate the code for the statements in the architecture.
-        for &stmt_id in &hir.stmts {
-            self.codegen(stmt_id, &mut builder)?;
-        }
+        // Generate the code for the declarations and statements in the
+        // architecture.
+        let result = (|| {
+            for &decl_id in &hir.decls {
+                self.codegen(decl_id, &mut builder)?;
+            }
+            self.codegen_external_signals(id, &exports, &export_args, &mut builder)?;
+            for &stmt_id in &hir.stmts {
+                self.codegen(stmt_id, &mut builder)?;
+            }
+            Ok(())
+        })();
+        self.sb.llsig_table.replace(outer_sigs);
+        self.sb.lldelayed_table.replace(outer_delayed);
+        self.sb.llexport_table.replace(outer_exports);
+        result?;
 
         // Add the entity to the module and return a reference to it.
         Ok(self.sb.llmod.borrow_mut().add_unit(entity))
@@ -1055,24 +1323,46 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 // TODO: Replace with the first literal in the enum.
                 Ok(self.intern_const(Const::Null))
             }
-            Ty::Physical(ref ty) => Ok(self.intern_const(ConstInt::new(
-                Some(ty.base.clone()),
-                ty.base.left_bound.clone(),
-            ))),
+            Ty::Physical(ref ty) => {
+                Ok(self.intern_const(ConstPhysical::new(ty.clone(), ty.base.left_bound.clone())))
+            }
             Ty::Int(ref ty) => {
                 Ok(self.intern_const(ConstInt::new(Some(ty.clone()), ty.left_bound.clone())))
             }
             Ty::UniversalInt => panic!("universal integer has no default value"),
+            Ty::Float(ref ty) => {
+                Ok(self.intern_const(ConstFloat::new(Some(ty.clone()), ty.left_bound)))
+            }
+            Ty::UniversalReal => panic!("universal real has no default value"),
             Ty::UnboundedInt => panic!("unbounded integer has no default value"),
             Ty::Subprog(..) => panic!("subprogram type has no default value"),
+            Ty::Protected(_) => {
+                self.emit(DiagBuilder2::error(
+                    "objects of a protected type have no default value",
+                ));
+                Err(())
+            }
             Ty::Access(_) => Ok(self.intern_const(Const::Null)),
             Ty::Array(ref ty) => {
-                self.emit(DiagBuilder2::bug(format!(
-                    "default value for type `{}` not implemented",
-                    ty
-                )));
-                // TODO: Use the correct default value.
-                Ok(self.intern_const(Const::Null))
+                // Every element assumes the default value of the element type.
+                let mut value = self.default_value_for_type(&ty.element)?.clone();
+                for index in ty.indices.iter().rev() {
+                    let index = match *index {
+                        ArrayIndex::Constrained(ref index) => index,
+                        ArrayIndex::Unbounded(_) => {
+                            self.emit(DiagBuilder2::error(format!("type `{}` is unbounded", ty)));
+                            return Err(());
+                        }
+                    };
+                    let (dir, left, right) = self.discrete_bounds(index, INVALID_SPAN)?;
+                    let len: BigInt = match dir {
+                        hir::Dir::To => &right - &left + 1,
+                        hir::Dir::Downto => &left - &right + 1,
+                    };
+                    let len = len.to_usize().unwrap_or(0);
+                    value = ConstArray::new(dir, left, vec![value; len]).into();
+                }
+                Ok(self.intern_const(value))
             }
             Ty::File(ref ty) => {
                 self.emit(DiagBuilder2::bug(format!(
@@ -1083,16 +1373,75 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 Ok(self.intern_const(Const::Null))
             }
             Ty::Record(ref ty) => {
-                self.emit(DiagBuilder2::bug(format!(
-                    "default value for type `{}` not implemented",
-                    ty
-                )));
-                // TODO: Use the correct default value.
-                Ok(self.intern_const(Const::Null))
+                let fields = ty
+                    .fields
+                    .iter()
+                    .map(|&(name, ref ty)| Ok((name, self.default_value_for_type(ty)?.clone())))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(self.intern_const(ConstRecord::new(fields)))
             }
         }
     }
 
+    /// Determine the number of literals of an enumeration type.
+    pub fn enum_len(&self, decl: TypeDeclRef) -> Result<usize> {
+        if let Some(len) = builtin_enum_len(decl) {
+            return Ok(len);
+        }
+        match self.lazy_hir(decl)?.data {
+            Some(Spanned {
+                value: hir::TypeData::Enum(ref lits),
+                ..
+            }) => Ok(lits.len()),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Determine the number of bits needed to represent the position of any
+    /// literal of an enumeration type.
+    ///
+    /// For example, the nine values of `STD_ULOGIC` take four bits, and the
+    /// two values of `BIT` and `BOOLEAN` a single bit.
+    pub fn enum_width(&self, decl: TypeDeclRef) -> Result<usize> {
+        let len = self.enum_len(decl)?;
+        let mut width = 0;
+        while (1 << width) < len {
+            width += 1;
+        }
+        Ok(width)
+    }
+
+    /// Determine the name of a literal of an enumeration type.
+    pub fn enum_literal(&self, decl: TypeDeclRef, index: usize) -> Result<ResolvableName> {
+        if let Some(name) = builtin_enum_literal(decl, index) {
+            return Ok(name);
+        }
+        match self.lazy_hir(decl)?.data {
+            Some(Spanned {
+                value: hir::TypeData::Enum(ref lits),
+                ..
+            }) => Ok(match lits[index] {
+                hir::EnumLit::Ident(name) => name.value.into(),
+                hir::EnumLit::Char(chr) => chr.value.into(),
+            }),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Determine the methods of a protected type.
+    ///
+    /// Returns `None` if the type is not a protected type.
+    pub fn protected_methods(&self, decl: TypeDeclRef) -> Result<Option<&'ctx [SubprogDeclRef]>> {
+        let hir: &'ctx hir::TypeDecl = self.lazy_hir(decl)?;
+        Ok(match hir.data {
+            Some(Spanned {
+                value: hir::TypeData::Protected(ref methods),
+                ..
+            }) => Some(methods),
+            _ => None,
+        })
+    }
+
     /// Internalize a constant.
     ///
     /// See `ScoreBoard::intern_const`.
@@ -1335,6 +1684,7 @@ node_ref!(SubprogDeclRef);
 node_ref!(SubprogInstRef);
 node_ref!(SubtypeIndRef);
 node_ref!(TypeDeclRef);
+node_ref!(ProtectedBodyRef);
 node_ref!(SubtypeDeclRef);
 node_ref!(WaitStmtRef);
 node_ref!(AssertStmtRef);
@@ -1352,11 +1702,13 @@ node_ref!(BlockStmtRef);
 node_ref!(ProcessStmtRef);
 node_ref!(ConcCallStmtRef);
 node_ref!(ConcAssertStmtRef);
+node_ref!(PslDirectiveRef);
 node_ref!(ConcSigAssignStmtRef);
 node_ref!(CompInstStmtRef);
 node_ref!(ForGenStmtRef);
 node_ref!(IfGenStmtRef);
 node_ref!(CaseGenStmtRef);
+node_ref!(GenBodyRef);
 node_ref!(ConstDeclRef);
 node_ref!(SignalDeclRef);
 node_ref!(VarDeclRef);
@@ -1414,7 +1766,7 @@ node_ref_group!(
     Subtype(SubtypeDeclRef),
     Enum(EnumRef),
     Unit(UnitRef),
-    Const(ConstDeclRef),
+    Const(ConstRef),
     Signal(SignalRef),
     File(FileDeclRef),
     Var(VarDeclRef),
@@ -1439,6 +1791,9 @@ node_ref_group!(
     Process(ProcessStmtRef),
     Subprog(SubprogDeclRef),
     SubprogBody(SubprogBodyRef),
+    Loop(LoopStmtRef),
+    GenBody(GenBodyRef),
+    ProtectedBody(ProtectedBodyRef),
 );
 
 node_ref_group!(
@@ -1467,6 +1822,13 @@ impl From<TypeMarkRef> for Def {
     }
 }
 
+node_ref_group!(
+    ConstRef: Intf(IntfConstRef),
+    Decl(ConstDeclRef),
+    Loop(LoopStmtRef),
+    Gen(ForGenStmtRef),
+);
+
 node_ref_group!(SignalRef: Intf(IntfSignalRef), Decl(SignalDeclRef),);
 
 node_ref_group!(PkgRef: Decl(PkgDeclRef), Inst(PkgInstRef),);
@@ -1546,6 +1908,7 @@ node_ref_group!(
     PkgBody(PkgBodyRef),
     PkgInst(PkgInstRef),
     Type(TypeDeclRef),
+    ProtectedBody(ProtectedBodyRef),
     Subtype(SubtypeDeclRef),
     Const(ConstDeclRef),
     Var(VarDeclRef),
@@ -1587,6 +1950,7 @@ node_ref_group!(
     PkgBody(PkgBodyRef),
     PkgInst(PkgInstRef),
     Type(TypeDeclRef),
+    ProtectedBody(ProtectedBodyRef),
     Subtype(SubtypeDeclRef),
     Const(ConstDeclRef),
     Var(VarDeclRef),
@@ -1632,6 +1996,7 @@ node_ref_group!(
     PkgBody(PkgBodyRef),
     PkgInst(PkgInstRef),
     Type(TypeDeclRef),
+    ProtectedBody(ProtectedBodyRef),
     Subtype(SubtypeDeclRef),
     Const(ConstDeclRef),
     Signal(SignalDeclRef),
@@ -1677,6 +2042,7 @@ node_ref_group!(
     PkgBody(PkgBodyRef),
     PkgInst(PkgInstRef),
     Type(TypeDeclRef),
+    ProtectedBody(ProtectedBodyRef),
     Subtype(SubtypeDeclRef),
     Const(ConstDeclRef),
     Var(VarDeclRef),
@@ -1709,6 +2075,7 @@ node_ref_group!(
     ForGen(ForGenStmtRef),
     IfGen(IfGenStmtRef),
     CaseGen(CaseGenStmtRef),
+    Psl(PslDirectiveRef),
 );
 
 /// All sequential statements. See IEEE 1076-2008 section 10.
@@ -1773,6 +2140,7 @@ node_storage!(AstTable<'ast>:
 
     // Declarations
     type_decls:            TypeDeclRef           => (ScopeRef, &'ast ast::TypeDecl),
+    protected_bodies:      ProtectedBodyRef      => (ScopeRef, &'ast ast::TypeDecl),
     subtype_decls:         SubtypeDeclRef        => (ScopeRef, &'ast ast::SubtypeDecl),
     subprog_bodies:        SubprogBodyRef        => (ScopeRef, &'ast ast::Subprog),
     subprog_decls:         SubprogDeclRef        => (ScopeRef, &'ast ast::Subprog),
@@ -1790,6 +2158,13 @@ node_storage!(AstTable<'ast>:
 
     // Statements
     proc_stmts:       ProcessStmtRef   => (ScopeRef, &'ast ast::Stmt),
+    conc_assert_stmts: ConcAssertStmtRef => (ScopeRef, &'ast ast::Stmt),
+    psl_directives:   PslDirectiveRef  => (ScopeRef, &'ast ast::Stmt),
+    comp_inst_stmts:  CompInstStmtRef  => (ScopeRef, &'ast ast::Stmt),
+    for_gen_stmts:    ForGenStmtRef    => (ScopeRef, &'ast ast::Stmt),
+    if_gen_stmts:     IfGenStmtRef     => (ScopeRef, &'ast ast::Stmt),
+    case_gen_stmts:   CaseGenStmtRef   => (ScopeRef, &'ast ast::Stmt),
+    gen_bodies:       GenBodyRef       => (ScopeRef, &'ast ast::GenBody),
     sig_assign_stmts: SigAssignStmtRef => (ScopeRef, &'ast ast::Stmt),
     var_assign_stmts: VarAssignStmtRef => (ScopeRef, &'ast ast::Stmt),
 
@@ -1804,11 +2179,13 @@ node_storage!(HirTable<'ctx>:
     entities:              EntityRef             => &'ctx hir::Entity,
     archs:                 ArchRef               => &'ctx hir::Arch,
     intf_sigs:             IntfSignalRef         => &'ctx hir::IntfSignal,
+    intf_consts:           IntfConstRef          => &'ctx hir::IntfConst,
     subtype_inds:          SubtypeIndRef         => &'ctx hir::SubtypeInd,
     pkgs:                  PkgDeclRef            => &'ctx hir::Package,
     pkg_bodies:            PkgBodyRef            => &'ctx hir::PackageBody,
     pkg_insts:             PkgInstRef            => &'ctx hir::PackageInst,
     type_decls:            TypeDeclRef           => &'ctx hir::TypeDecl,
+    protected_bodies:      ProtectedBodyRef      => &'ctx hir::ProtectedBody,
     subtype_decls:         SubtypeDeclRef        => &'ctx hir::SubtypeDecl,
     exprs:                 ExprRef               => &'ctx hir::Expr,
     aggregate:             AggregateRef          => &'ctx hir::Aggregate,
@@ -1819,6 +2196,14 @@ node_storage!(HirTable<'ctx>:
     variable_decls:        VarDeclRef            => &'ctx hir::Decl<hir::VarDecl>,
     file_decls:            FileDeclRef           => &'ctx hir::Decl<hir::FileDecl>,
     process_stmts:         ProcessStmtRef        => &'ctx hir::ProcessStmt,
+    conc_assert_stmts:     ConcAssertStmtRef     => &'ctx hir::ProcessStmt,
+    psl_directives:        PslDirectiveRef       => &'ctx hir::PslDirective,
+    cfgs:                  CfgRef                => &'ctx hir::Cfg,
+    comp_inst_stmts:       CompInstStmtRef       => &'ctx hir::CompInstStmt,
+    for_gen_stmts:         ForGenStmtRef         => &'ctx hir::ForGenStmt,
+    if_gen_stmts:          IfGenStmtRef          => &'ctx hir::IfGenStmt,
+    case_gen_stmts:        CaseGenStmtRef        => &'ctx hir::CaseGenStmt,
+    gen_bodies:            GenBodyRef            => &'ctx hir::GenBody,
     sig_assign_stmts:      SigAssignStmtRef      => &'ctx hir::SigAssignStmt,
     array_type_indices:    ArrayTypeIndexRef     => &'ctx Spanned<hir::ArrayTypeIndex>,
     subprogs:              SubprogDeclRef        => &'ctx hir::Subprog,
@@ -1850,27 +2235,26 @@ lazy_static! {
     /// A table of the scopes of all builtin packages.
     static ref BUILTIN_PKG_SCOPES: HashMap<BuiltinPkgRef, Scope> = {
         let mut scopes = HashMap::new();
-        scopes.insert(*STANDARD_PKG_REF, Scope{
-            parent: None,
-            defs: vec![(*STANDARD_PKG_REF).into()],
-            explicit_defs: HashMap::new(),
-        });
+        for &(id, _) in &*BUILTIN_SCOPES {
+            if let ScopeRef::BuiltinPkg(id) = id {
+                scopes.insert(id, Scope{
+                    parent: None,
+                    defs: vec![id.into()],
+                    explicit_defs: HashMap::new(),
+                });
+            }
+        }
         scopes
     };
 
     /// A table of the definitions of all builtin packages.
     static ref BUILTIN_PKG_DEFS: HashMap<BuiltinPkgRef, Defs> = {
-        // let nt = get_name_table();
         let mut table = HashMap::new();
-        table.insert(*STANDARD_PKG_REF, {
-            let defs = HashMap::new();
-            // TODO: Insert builtin definitions here.
-            // defs.insert(
-            //     nt.intern("integer", false).into(),
-            //     vec![Spanned::new(Def::BuiltinTy(IntTy), INVALID_SPAN)]
-            // );
-            defs
-        });
+        for &(id, scope) in &*BUILTIN_SCOPES {
+            if let ScopeRef::BuiltinPkg(id) = id {
+                table.insert(id, scope.defs.clone());
+            }
+        }
         table
     };
 }
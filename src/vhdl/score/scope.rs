@@ -29,6 +29,9 @@ impl_make_defs!(self, id: ScopeRef => {
         ScopeRef::Process(id)     => self.make(id),
         ScopeRef::Subprog(id)     => self.make(id),
         ScopeRef::SubprogBody(id) => self.make(id),
+        ScopeRef::Loop(id)        => self.make(id),
+        ScopeRef::GenBody(id)     => self.make(id),
+        ScopeRef::ProtectedBody(id) => self.make(id),
     }
 });
 
@@ -44,6 +47,9 @@ impl_make_scope!(self, id: ScopeRef => {
         ScopeRef::Process(id)     => self.make(id),
         ScopeRef::Subprog(id)     => self.make(id),
         ScopeRef::SubprogBody(id) => self.make(id),
+        ScopeRef::Loop(id)        => self.make(id),
+        ScopeRef::GenBody(id)     => self.make(id),
+        ScopeRef::ProtectedBody(id) => self.make(id),
     }
 });
 
@@ -97,16 +103,20 @@ impl_make_defs!(self, id: LibRef => {
 
 // Definitions made by the context items that appear before design units.
 impl_make_defs!(self, id: CtxItemsRef => {
-    let (_, ast) = self.ast(id);
+    let (scope_id, ast) = self.ast(id);
     let mut defs = HashMap::new();
     let mut has_fails = false;
-    for item in ast {
+    for item in self.expand_ctx_items(scope_id, ast)? {
         // For each name in a library clause, find the corresponding library
         // and create a definition for it.
         match *item {
             ast::CtxItem::LibClause(Spanned{ value: ref names, .. }) => {
                 for ident in names {
-                    if let Some(&lib_id) = self.sb.lib_names.borrow().get(&ident.name) {
+                    // Libraries given on the command line take precedence over
+                    // the builtin ones.
+                    let lib_id = self.sb.lib_names.borrow().get(&ident.name).cloned()
+                        .or_else(|| builtin_lib(ident.name));
+                    if let Some(lib_id) = lib_id {
                         let defs = defs.entry(ident.name.into()).or_insert_with(||vec![]);
                         if !defs.is_empty() {
                             self.emit(
@@ -131,6 +141,19 @@ impl_make_defs!(self, id: CtxItemsRef => {
             _ => ()
         }
     }
+
+    // The library that contains the design unit is implicitly visible as
+    // `work`, and the library `std` is implicitly visible as well. See IEEE
+    // 1076-2008 section 13.2.
+    if let ScopeRef::Lib(lib_id) = scope_id {
+        let work = get_name_table().intern("WORK", false);
+        defs.entry(work.into())
+            .or_insert_with(|| vec![Spanned::new(Def::Lib(lib_id), INVALID_SPAN)]);
+    }
+    let std = get_name_table().intern("STD", false);
+    defs.entry(std.into())
+        .or_insert_with(|| vec![Spanned::new(Def::Lib(*STD_LIB_REF), INVALID_SPAN)]);
+
     if has_fails {
         Err(())
     } else {
@@ -147,9 +170,16 @@ impl_make_scope!(self, _id: CtxItemsRef => {
 });
 
 // Definitions in an entity.
-impl_make_defs!(self, _id: EntityRef => {
-    // TODO: Implement this.
-    Ok(self.sb.arenas.defs.alloc(HashMap::new()))
+impl_make_defs!(self, id: EntityRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    for &generic in &hir.generics {
+        ctx.declare_generic(generic);
+    }
+    for &port in &hir.ports {
+        ctx.declare_port(port);
+    }
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
 });
 
 // Definitions in an architecture.
@@ -163,13 +193,21 @@ impl_make_defs!(self, id: ArchRef => {
 });
 
 // Definitions in a package declaration.
-impl_make_defs!(self, _id: PkgDeclRef => {
-    let ctx = DefsContext::new(self);
-    // let hir = self.hir(id)?;
-    // for &decl in &hir.decls {
-    //     ctx.declare_any_in_pkg(decl);
-    // }
-    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+impl_make_defs!(self, id: PkgDeclRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    for &decl in &hir.decls {
+        ctx.declare_any_in_pkg(decl);
+    }
+    let mut defs = ctx.finish()?;
+    // Types and constants have been defined in the package's scope while
+    // lowering it to HIR. Make them visible to `use` clauses as well.
+    if let Some(scope) = self.sb.scope2_table.borrow().get(&id.into()) {
+        for (name, d) in &scope.defs {
+            defs.entry(*name).or_insert_with(|| Vec::new()).extend(d.iter().cloned());
+        }
+    }
+    Ok(self.sb.arenas.defs.alloc(defs))
 });
 
 // Definitions in a package body.
@@ -220,11 +258,11 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         id: CtxItemsRef,
         parent: Option<ScopeRef>,
     ) -> Result<CtxItemsRef> {
-        let (_, items) = self.ast(id);
+        let (scope_id, items) = self.ast(id);
         let mut defs = Vec::new();
         let mut explicit_defs = HashMap::new();
         defs.push(id.into());
-        for item in items {
+        for item in self.expand_ctx_items(scope_id, items)? {
             if let &ast::CtxItem::UseClause(Spanned {
                 value: ref names, ..
             }) = item
@@ -245,6 +283,12 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                                 }) => {
                                     defs.push(id.into());
                                 }
+                                Some(Spanned {
+                                    value: Def::BuiltinPkg(id),
+                                    ..
+                                }) => {
+                                    defs.push(id.into());
+                                }
                                 Some(_) => {
                                     self.emit(
                                         DiagBuilder2::error(format!(
@@ -285,6 +329,116 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         );
         Ok(id)
     }
+
+    /// Expand the context references in a list of context items.
+    ///
+    /// A context reference is equivalent to the context items of the
+    /// referenced context declaration, in place of the reference. See IEEE
+    /// 1076-2008 section 13.4.
+    pub fn expand_ctx_items(
+        &self,
+        scope_id: ScopeRef,
+        items: &'ast [ast::CtxItem],
+    ) -> Result<Vec<&'ast ast::CtxItem>> {
+        let lib = match scope_id {
+            ScopeRef::Lib(id) => id,
+            _ => unreachable!("context items outside of a library"),
+        };
+        let mut expanded = Vec::new();
+        self.expand_ctx_items_into(lib, items, &mut Vec::new(), &mut expanded)?;
+        Ok(expanded)
+    }
+
+    fn expand_ctx_items_into(
+        &self,
+        lib: LibRef,
+        items: &'ast [ast::CtxItem],
+        stack: &mut Vec<CtxRef>,
+        expanded: &mut Vec<&'ast ast::CtxItem>,
+    ) -> Result<()> {
+        for item in items {
+            let names = match *item {
+                ast::CtxItem::CtxRef(Spanned {
+                    value: ref names, ..
+                }) => names,
+                _ => {
+                    expanded.push(item);
+                    continue;
+                }
+            };
+            for name in names {
+                let id = self.resolve_ctx_ref(lib, name)?;
+                let (ctx_lib, _, decl) = self.ast(id);
+                if stack.contains(&id) {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "context `{}` references itself",
+                            decl.name.value
+                        ))
+                        .span(name.span),
+                    );
+                    return Err(());
+                }
+                stack.push(id);
+                self.expand_ctx_items_into(ctx_lib, &decl.items, stack, expanded)?;
+                stack.pop();
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the name in a context reference, such as `work.proj_ctx`.
+    ///
+    /// The library is looked up directly rather than in a scope, since the
+    /// library clause that makes it visible may stem from a context reference
+    /// itself.
+    pub fn resolve_ctx_ref(&self, lib: LibRef, name: &'ast ast::CompoundName) -> Result<CtxRef> {
+        let (lib_name, ctx_name) = match (name.primary.kind, name.parts.as_slice()) {
+            (
+                ast::PrimaryNameKind::Ident(lib_name),
+                &[ast::NamePart::Select(ast::PrimaryName {
+                    kind: ast::PrimaryNameKind::Ident(ctx_name),
+                    ..
+                })],
+            ) => (lib_name, ctx_name),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a context name", name.span.extract()))
+                        .span(name.span)
+                        .add_note("Refer to a context declaration as `<library>.<context>`."),
+                );
+                return Err(());
+            }
+        };
+        // The builtin libraries do not declare any contexts.
+        let lib_id = if lib_name == get_name_table().intern("WORK", false) {
+            Some(lib)
+        } else {
+            self.sb.lib_names.borrow().get(&lib_name).cloned()
+        };
+        let found = match lib_id {
+            Some(lib_id) => self
+                .hir(lib_id)?
+                .ctxs
+                .iter()
+                .cloned()
+                .find(|&id| self.ast(id).2.name.value == ctx_name),
+            None => None,
+        };
+        match found {
+            Some(id) => Ok(id),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "no context `{}` in library `{}`",
+                        ctx_name, lib_name
+                    ))
+                    .span(name.span),
+                );
+                Err(())
+            }
+        }
+    }
 }
 
 // Populate the scope of an entity.
@@ -334,8 +488,14 @@ impl_make_scope!(self, id: PkgBodyRef => {
     let hir = self.hir(id)?;
     let mut defs = Vec::new();
     defs.push(id.into());
+    // The declarations of the package are visible in its body. See IEEE
+    // 1076-2008 section 12.2.
+    let pkg = match self.hir(hir.pkg.value)?.value {
+        PkgRef::Decl(pkg) => Some(pkg.into()),
+        PkgRef::Inst(_) => None,
+    };
     let parent = match hir.parent {
-        ScopeRef::CtxItems(id) => self.make_ctx_items_scope(id, None)?.into(),
+        ScopeRef::CtxItems(id) => self.make_ctx_items_scope(id, pkg)?.into(),
         others => others
     };
     Ok(self.sb.arenas.scope.alloc(Scope{
@@ -377,6 +537,73 @@ impl_make_scope!(self, id: SubprogBodyRef => {
     }))
 });
 
+// Definitions in a loop. The loop parameter is defined as the loop is added.
+impl_make_defs!(self, _id: LoopStmtRef => {
+    Ok(self.sb.arenas.defs.alloc(Defs::new()))
+});
+
+// Populate the scope of a loop.
+impl_make_scope!(self, id: LoopStmtRef => {
+    let hir = self.lazy_hir(id)?;
+    let mut defs = Vec::new();
+    defs.push(id.into());
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent: Some(hir.parent),
+        defs: defs,
+        explicit_defs: HashMap::new(),
+    }))
+});
+
+// Definitions in the body of a generate statement. The parameter of a for
+// generate statement is defined as the statement is lowered.
+impl_make_defs!(self, id: GenBodyRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    for &decl in &hir.decls {
+        ctx.declare_any_in_block(decl);
+    }
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+});
+
+// Populate the scope of the body of a generate statement.
+impl_make_scope!(self, id: GenBodyRef => {
+    let mut defs = Vec::new();
+    defs.push(id.into());
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent: Some(self.ast(id).0),
+        defs: defs,
+        explicit_defs: HashMap::new(),
+    }))
+});
+
+// Definitions in the body of a protected type. The methods of the type are
+// visible throughout the body, where they are completed by their bodies. See
+// IEEE 1076-2008 section 5.6.3.
+impl_make_defs!(self, id: ProtectedBodyRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    if let Some(methods) = self.protected_methods(hir.decl)? {
+        for &method in methods {
+            ctx.declare_subprog(method);
+        }
+    }
+    for &decl in &hir.decls {
+        ctx.declare_any_in_subprog(decl);
+    }
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+});
+
+// Populate the scope of the body of a protected type.
+impl_make_scope!(self, id: ProtectedBodyRef => {
+    let mut defs = Vec::new();
+    defs.push(id.into());
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent: Some(self.ast(id).0),
+        defs: defs,
+        explicit_defs: HashMap::new(),
+    }))
+});
+
 impl_make_defs!(self, id: ProcessStmtRef => {
     let mut ctx = DefsContext::new(self);
     let hir = self.hir(id)?;
@@ -10,6 +10,7 @@ use crate::lexer::token::Literal;
 use moore_common::name::Name;
 use moore_common::source::{Span, Spanned};
 use moore_common::util::{HasDesc, HasSpan};
+use serde::Serialize;
 
 pub use self::ExprData::*;
 pub use self::StmtData::*;
@@ -18,7 +19,7 @@ pub use self::TypeData::*;
 /// A positive, small ID assigned to each node in the AST. Used as a lightweight
 /// way to refer to individual nodes, e.g. during symbol table construction and
 /// name resolution.
-#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Serialize)]
 pub struct NodeId(u32);
 
 impl NodeId {
@@ -61,14 +62,14 @@ pub const DUMMY_NODE_ID: NodeId = NodeId(0);
 /// A design unit. Multiple design units make up a design file. Each unit
 /// consists of an optional context clause followed by a primary or secondary
 /// unit.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct DesignUnit {
     pub id: NodeId,
     pub ctx: Vec<CtxItem>,
     pub data: DesignUnitData,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum DesignUnitData {
     EntityDecl(EntityDecl),
     CfgDecl(CfgDecl),
@@ -80,7 +81,7 @@ pub enum DesignUnitData {
 }
 
 /// A context item, multiple of which make up a context clause.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum CtxItem {
     LibClause(Spanned<Vec<Ident>>),
     UseClause(Spanned<Vec<CompoundName>>),
@@ -88,7 +89,7 @@ pub enum CtxItem {
 }
 
 /// An identifier. Has a node ID such that it may be referenced later on.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 // #[deprecated(note="use `Spanned<Name>` instead of `Ident`")]
 pub struct Ident {
     pub id: NodeId,
@@ -128,7 +129,7 @@ impl Into<Spanned<Name>> for Ident {
 /// | attribute_name      | `NamePart::Attribute`           |
 /// | external_name       | not implemented                 |
 ///
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CompoundName {
     pub id: NodeId,
     pub span: Span,
@@ -148,21 +149,21 @@ impl HasDesc for CompoundName {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct PrimaryName {
     pub id: NodeId,
     pub span: Span,
     pub kind: PrimaryNameKind,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum PrimaryNameKind {
     Ident(Name),
     Char(char),
     String(Name),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum NamePart {
     Select(PrimaryName),
     SelectAll(Span),
@@ -173,7 +174,7 @@ pub enum NamePart {
 }
 
 /// A context declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CtxDecl {
     pub id: NodeId,
     pub span: Span,
@@ -182,7 +183,7 @@ pub struct CtxDecl {
 }
 
 /// An entity declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct EntityDecl {
     pub id: NodeId,
     pub span: Span,
@@ -192,7 +193,7 @@ pub struct EntityDecl {
 }
 
 /// A configuration declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CfgDecl {
     pub id: NodeId,
     pub span: Span,
@@ -202,7 +203,7 @@ pub struct CfgDecl {
 }
 
 /// An architecture body.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ArchBody {
     pub id: NodeId,
     pub span: Span,
@@ -213,7 +214,7 @@ pub struct ArchBody {
 }
 
 /// A package declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct PkgDecl {
     pub id: NodeId,
     pub span: Span,
@@ -222,7 +223,7 @@ pub struct PkgDecl {
 }
 
 /// A package body.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct PkgBody {
     pub id: NodeId,
     pub span: Span,
@@ -231,7 +232,7 @@ pub struct PkgBody {
 }
 
 /// A package instantiation declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct PkgInst {
     pub id: NodeId,
     pub span: Span,
@@ -241,7 +242,7 @@ pub struct PkgInst {
 }
 
 /// An interface declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum IntfDecl {
     TypeDecl(TypeDecl),
     SubprogSpec(IntfSubprogDecl),
@@ -279,7 +280,7 @@ impl HasDesc for IntfDecl {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IntfSubprogDecl {
     pub id: NodeId,
     pub span: Span,
@@ -287,14 +288,14 @@ pub struct IntfSubprogDecl {
     pub default: Option<SubprogDefault>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum SubprogDefault {
     Any,
     Name(CompoundName),
 }
 
 /// An interface object declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IntfObjDecl {
     pub kind: IntfObjKind,
     pub span: Span,
@@ -316,7 +317,7 @@ impl HasDesc for IntfObjDecl {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum IntfObjKind {
     Const,
     Signal,
@@ -324,7 +325,7 @@ pub enum IntfObjKind {
     File,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum IntfMode {
     In,
     Out,
@@ -334,7 +335,7 @@ pub enum IntfMode {
 }
 
 /// A declarative item.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum DeclItem {
     PkgBody(PkgBody),
     PkgInst(PkgInst),
@@ -354,6 +355,7 @@ pub enum DeclItem {
     GroupDecl(GroupDecl),
     VunitBindInd(()),
     BlockCompCfg(BlockCompCfg),
+    PslDecl(PslDecl),
 }
 
 impl HasSpan for DeclItem {
@@ -377,6 +379,7 @@ impl HasSpan for DeclItem {
             DeclItem::GroupDecl(ref n) => n.span,
             DeclItem::VunitBindInd(_) => unimplemented!(),
             DeclItem::BlockCompCfg(ref n) => n.span,
+            DeclItem::PslDecl(ref n) => n.span,
         }
     }
 
@@ -392,6 +395,7 @@ impl HasSpan for DeclItem {
             DeclItem::SubprogDecl(ref n) => n.human_span(),
             DeclItem::PortgenMap(_, Spanned { span, .. }, _) => span,
             DeclItem::PortgenClause(_, Spanned { span, .. }, _) => span,
+            DeclItem::PslDecl(ref n) => n.name.map(|n| n.span).unwrap_or(n.span),
             _ => self.span(),
         }
     }
@@ -448,17 +452,22 @@ impl HasDesc for DeclItem {
             DeclItem::GroupDecl(..) => "group declaration",
             DeclItem::VunitBindInd(..) => "vunit binding indication",
             DeclItem::BlockCompCfg(..) => "block component configuration",
+            DeclItem::PslDecl(ref n) => match n.kind {
+                PslDeclKind::Property => "property declaration",
+                PslDeclKind::Sequence => "sequence declaration",
+                PslDeclKind::DefaultClock => "default clock declaration",
+            },
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum PortgenKind {
     Port,
     Generic,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Subprog {
     pub id: NodeId,
     pub span: Span,
@@ -482,7 +491,7 @@ impl HasDesc for Subprog {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum SubprogData {
     Decl,
     Inst {
@@ -505,7 +514,7 @@ impl HasDesc for SubprogData {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct SubprogSpec {
     pub span: Span,
     pub name: PrimaryName,
@@ -517,19 +526,19 @@ pub struct SubprogSpec {
     pub retty: Option<CompoundName>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum SubprogPurity {
     Pure,
     Impure,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum SubprogKind {
     Proc,
     Func,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct SubtypeInd {
     pub span: Span,
     pub res: Option<ResolInd>,
@@ -548,7 +557,7 @@ impl HasDesc for SubtypeInd {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct SubtypeDecl {
     pub id: NodeId,
     pub span: Span,
@@ -556,14 +565,14 @@ pub struct SubtypeDecl {
     pub subtype: SubtypeInd,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum ResolInd {
     Exprs(ParenElems),
     Name(CompoundName),
 }
 
 /// An alias declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct AliasDecl {
     pub id: NodeId,
     pub span: Span,
@@ -572,7 +581,7 @@ pub struct AliasDecl {
     pub target: CompoundName,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ObjDecl {
     pub span: Span,
     pub kind: ObjKind,
@@ -582,7 +591,7 @@ pub struct ObjDecl {
     pub init: Option<Expr>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum ObjKind {
     Const,
     Signal,
@@ -593,7 +602,7 @@ pub enum ObjKind {
 
 /// Additional mutually exclusive details that may be provided with an object
 /// declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum ObjDetail {
     Register,
     Bus,
@@ -627,7 +636,7 @@ impl HasDesc for ObjDecl {
 }
 
 /// A component declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CompDecl {
     pub id: NodeId,
     pub span: Span,
@@ -636,7 +645,7 @@ pub struct CompDecl {
     pub ports: Option<Spanned<Vec<IntfDecl>>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct DisconSpec {
     pub span: Span,
     pub target: DisconTarget,
@@ -644,14 +653,14 @@ pub struct DisconSpec {
     pub after: Expr,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum DisconTarget {
     Others,
     All,
     Signals(Vec<CompoundName>),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct BlockCompCfg {
     pub span: Span,
     pub spec: Spanned<BlockCompSpec>,
@@ -659,7 +668,7 @@ pub struct BlockCompCfg {
     pub decls: Vec<DeclItem>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum BlockCompSpec {
     CompOthers(CompoundName),
     CompAll(CompoundName),
@@ -667,7 +676,7 @@ pub enum BlockCompSpec {
     Block(CompoundName),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct BindingInd {
     pub span: Span,
     pub entity: Option<EntityAspect>,
@@ -675,14 +684,14 @@ pub struct BindingInd {
     pub ports: Option<ParenElems>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum EntityAspect {
     Entity(CompoundName),
     Cfg(CompoundName),
     Open,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CfgSpec {
     pub span: Span,
     pub spec: Spanned<BlockCompSpec>,
@@ -690,7 +699,7 @@ pub struct CfgSpec {
     pub vunits: Vec<()>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct AttrDecl {
     pub id: NodeId,
     pub span: Span,
@@ -698,7 +707,7 @@ pub struct AttrDecl {
     pub data: AttrData,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum AttrData {
     Decl(CompoundName),
     Spec {
@@ -708,14 +717,14 @@ pub enum AttrData {
     },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum AttrTarget {
     Others,
     All,
     List(Vec<(CompoundName, Option<Signature>)>),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum EntityClass {
     Arch,
     Comp,
@@ -738,7 +747,7 @@ pub enum EntityClass {
     Var,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GroupDecl {
     pub id: NodeId,
     pub span: Span,
@@ -746,7 +755,7 @@ pub struct GroupDecl {
     pub data: GroupData,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum GroupData {
     /// A group declaration.
     Decl(CompoundName),
@@ -758,7 +767,7 @@ pub enum GroupData {
 /// A parenthesized expression element. A parenthesized expression contains
 /// elements of which each may either be a simple `<expr>`, or an association of
 /// the form `<choices> => <expr>`.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ParenElem {
     pub span: Span,
     pub choices: Choices,
@@ -770,7 +779,7 @@ pub struct ParenElem {
 pub type ParenElems = Spanned<Vec<ParenElem>>;
 
 /// An expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Expr {
     pub span: Span,
     pub data: ExprData,
@@ -789,7 +798,7 @@ impl HasDesc for Expr {
 }
 
 /// The data associated with a specific expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum ExprData {
     NullExpr,
     OpenExpr,
@@ -805,6 +814,7 @@ pub enum ExprData {
     NameExpr(CompoundName),
     UnaryExpr(Spanned<UnaryOp>, Box<Expr>),
     BinaryExpr(Spanned<BinaryOp>, Box<Expr>, Box<Expr>),
+    ExternalNameExpr(Box<ExternalName>),
 }
 
 impl HasDesc for ExprData {
@@ -813,12 +823,53 @@ impl HasDesc for ExprData {
             NullExpr => "null expression",
             UnaryExpr(..) => "unary expression",
             BinaryExpr(..) => "binary expression",
+            ExternalNameExpr(..) => "external name",
             _ => "expression",
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// An external name, e.g. `<< signal .top.dut.state : BIT >>`.
+///
+/// See IEEE 1076-2008 section 8.7.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ExternalName {
+    pub span: Span,
+    pub class: Spanned<ExternalClass>,
+    pub path: ExternalPath,
+    pub subtype: SubtypeInd,
+}
+
+/// The class of the object an external name refers to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ExternalClass {
+    Constant,
+    Signal,
+    Variable,
+}
+
+/// The path of an external name.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ExternalPath {
+    pub span: Span,
+    pub kind: ExternalPathKind,
+    /// The names along the path, the last of which is the object.
+    pub parts: Vec<Spanned<Name>>,
+}
+
+/// How the path of an external name is anchored in the design hierarchy.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ExternalPathKind {
+    /// A path into a package, e.g. `@lib.pkg.obj`.
+    Package,
+    /// A path from the root of the design hierarchy, e.g. `.top.dut.obj`.
+    Absolute,
+    /// A path from the enclosing concurrent region, or the one the given
+    /// number of `^.` prefixes above it, e.g. `^.dut.obj`.
+    Relative(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum UnaryOp {
     Not,
     Abs,
@@ -828,7 +879,7 @@ pub enum UnaryOp {
     Condition,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum BinaryOp {
     Dir(Dir),
     Logical(LogicalOp),
@@ -845,7 +896,7 @@ pub enum BinaryOp {
     Pow,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Dir {
     To,
     Downto,
@@ -860,13 +911,13 @@ impl std::fmt::Display for Dir {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Sign {
     Pos,
     Neg,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum LogicalOp {
     And,
     Or,
@@ -889,7 +940,7 @@ impl fmt::Display for LogicalOp {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum RelationalOp {
     Eq,
     Neq,
@@ -912,7 +963,7 @@ impl fmt::Display for RelationalOp {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum ShiftOp {
     Sll,
     Srl,
@@ -937,7 +988,7 @@ impl fmt::Display for ShiftOp {
 
 /// A type declaration. If the `data` field is omitted, this is an incomplete
 /// declaration.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeDecl {
     pub id: NodeId,
     pub span: Span,
@@ -964,7 +1015,7 @@ impl HasDesc for TypeDecl {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TypeData {
     EnumType(ParenElems),
     RangeType(Box<Expr>, Option<Vec<(Ident, Option<Box<Expr>>)>>),
@@ -973,6 +1024,7 @@ pub enum TypeData {
     AccessType(SubtypeInd),
     FileType(CompoundName),
     ProtectedType(Vec<DeclItem>),
+    ProtectedBody(Vec<DeclItem>),
 }
 
 impl HasDesc for TypeData {
@@ -985,11 +1037,12 @@ impl HasDesc for TypeData {
             TypeData::AccessType(..) => "access type declaration",
             TypeData::FileType(..) => "file type declaration",
             TypeData::ProtectedType(..) => "protected type declaration",
+            TypeData::ProtectedBody(..) => "protected type body",
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Stmt {
     pub id: NodeId,
     pub span: Span,
@@ -1016,7 +1069,7 @@ impl HasDesc for Stmt {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum StmtData {
     WaitStmt {
         on: Option<Spanned<Vec<CompoundName>>>,
@@ -1097,6 +1150,12 @@ pub enum StmtData {
         generics: Option<ParenElems>,
         ports: Option<ParenElems>,
     },
+    PslDirective {
+        kind: PslDirectiveKind,
+        prop: PslProp,
+        report: Option<Expr>,
+        severity: Option<Expr>,
+    },
 }
 
 impl HasDesc for StmtData {
@@ -1126,31 +1185,151 @@ impl HasDesc for StmtData {
             StmtData::AssignStmt { .. } => "assign statement",
             StmtData::SelectAssignStmt { .. } => "assign statement",
             StmtData::InstOrCallStmt { .. } => "instantiation or call statement",
+            StmtData::PslDirective { .. } => "PSL directive",
         }
     }
 }
 
+/// A PSL verification directive. See IEEE 1850-2010 section 7.1.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum PslDirectiveKind {
+    Assert,
+    Assume,
+    Restrict,
+    Cover,
+}
+
+/// A PSL property or sequence. See IEEE 1850-2010 sections 6.1 and 6.2.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PslProp {
+    pub span: Span,
+    pub data: PslPropData,
+}
+
+impl HasSpan for PslProp {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum PslPropData {
+    /// A boolean expression, or the name of a property or sequence.
+    Bool(Expr),
+    /// A braced sequence `{s}`.
+    Braced(Box<PslProp>),
+    /// An `always`, `never`, or `eventually!` property.
+    Unary(Spanned<PslUnaryOp>, Box<PslProp>),
+    /// A `next` property with an optional number of cycles.
+    Next {
+        strong: bool,
+        count: Option<Expr>,
+        arg: Box<PslProp>,
+    },
+    /// An implication, `until` property, or sequence composition.
+    Binary(Spanned<PslBinaryOp>, Box<PslProp>, Box<PslProp>),
+    /// A repeated sequence such as `s[*2 to 3]`.
+    Repeat(Box<PslProp>, PslRepeat),
+    /// A property that is aborted when a condition holds.
+    Abort(Box<PslProp>, Expr),
+    /// A property or sequence evaluated on the edges of a clock.
+    Clocked(Box<PslProp>, Expr),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum PslUnaryOp {
+    Always,
+    Never,
+    Eventually,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum PslBinaryOp {
+    /// The logical implication `->`.
+    Implication,
+    /// The logical equivalence `<->`.
+    Equivalence,
+    /// The `until`, `until!`, `until_`, and `until!_` operators.
+    Until { strong: bool, inclusive: bool },
+    /// The overlapping suffix implication `|->`.
+    OverlapImpl,
+    /// The non-overlapping suffix implication `|=>`.
+    NonOverlapImpl,
+    /// The sequence concatenation `;`.
+    Concat,
+    /// The sequence fusion `:`.
+    Fusion,
+    /// The sequence disjunction `|`.
+    Or,
+    /// The length-matching sequence conjunction `&&`.
+    And,
+}
+
+/// A sequence repetition. See IEEE 1850-2010 section 6.1.2.
+///
+/// The counts are absent for `[*]`, and the upper count is `inf` for an
+/// unbounded range.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PslRepeat {
+    pub span: Span,
+    pub kind: PslRepeatKind,
+    pub low: Option<Box<Expr>>,
+    pub high: Option<Box<Expr>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum PslRepeatKind {
+    /// The consecutive repetition `[*n]`.
+    Consecutive,
+    /// The repetition `[+]`, which is short for `[*1 to inf]`.
+    Plus,
+    /// The non-consecutive repetition `[=n]`.
+    NonConsecutive,
+    /// The goto repetition `[->n]`.
+    Goto,
+}
+
+/// A PSL declaration of a property, sequence, or the default clock. See IEEE
+/// 1076-2008 section 6.8 and IEEE 1850-2010 sections 6.1.5, 6.2.4, and 7.3.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PslDecl {
+    pub id: NodeId,
+    pub span: Span,
+    pub kind: PslDeclKind,
+    /// The name of a property or sequence.
+    pub name: Option<Spanned<Name>>,
+    /// The property or sequence, or the clock condition.
+    pub prop: PslProp,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum PslDeclKind {
+    Property,
+    Sequence,
+    DefaultClock,
+}
+
 /// The body of an if, loop, or case statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct StmtBody {
     pub id: NodeId,
     pub stmts: Vec<Stmt>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum LoopScheme {
     While(Expr),
     For(Spanned<Name>, Expr),
     Loop,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum NexitMode {
     Next,
     Exit,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenBody {
     pub id: NodeId,
     pub label: Option<Spanned<Name>>,
@@ -1159,73 +1338,73 @@ pub struct GenBody {
     pub stmts: Vec<Stmt>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Sensitivity {
     All,
     List(Vec<CompoundName>),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum AssignTarget {
     Name(CompoundName),
     Aggregate(ParenElems),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum InstTarget {
     Comp,
     Entity,
     Cfg,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum AssignKind {
     Signal,
     Var,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum AssignMode {
     Release(Option<Spanned<ForceMode>>),
     Force(Option<Spanned<ForceMode>>, Vec<CondWave>),
     Normal(Option<Spanned<DelayMech>>, Vec<CondWave>),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum SelectAssignMode {
     Force(Option<Spanned<ForceMode>>),
     Normal(Option<Spanned<DelayMech>>),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum ForceMode {
     In,
     Out,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum DelayMech {
     Transport,
     Inertial,
     InertialReject(Expr),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Wave {
     pub span: Span,
     pub elems: Option<Vec<(Expr, Option<Expr>)>>,
 }
 
 /// A conditional wave.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CondWave(pub Wave, pub Option<Expr>);
 
 /// A selected wave. The second element of the tuple represents the choices for
 /// which this wave would be selected.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct SelectWave(pub Wave, pub Choices);
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Signature {
     pub span: Span,
     pub args: Vec<CompoundName>,
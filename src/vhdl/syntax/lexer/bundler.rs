@@ -26,6 +26,33 @@ impl<T: Grinder> Bundler<T> {
     }
 }
 
+impl<T> Bundler<T>
+where
+    T: Grinder<Item = Option<(usize, char, u8, Category)>, Error = DiagBuilder2>,
+{
+    /// Check whether the comment that follows starts with the word `psl`, and
+    /// return the number of characters up to and including that word.
+    fn psl_prefix(&mut self) -> Option<usize> {
+        let mut n = 0;
+        while let Some((_, c, _, Category::Space)) = *self.inner.lookahead(n) {
+            if c == '\n' {
+                return None;
+            }
+            n += 1;
+        }
+        for &p in &['p', 's', 'l'] {
+            match *self.inner.lookahead(n) {
+                Some((_, c, _, _)) if c.to_ascii_lowercase() == p => n += 1,
+                _ => return None,
+            }
+        }
+        match *self.inner.lookahead(n) {
+            Some((_, _, _, Category::Space)) | None => Some(n),
+            _ => None,
+        }
+    }
+}
+
 impl<T> Grinder for Bundler<T>
 where
     T: Grinder<Item = Option<(usize, char, u8, Category)>, Error = DiagBuilder2>,
@@ -48,6 +75,19 @@ where
         if c == '-' {
             if let Some((_, '-', _, _)) = *self.inner.lookahead(0) {
                 self.inner.next();
+
+                // A comment that starts with the word `psl` contains a PSL
+                // directive or declaration, which is lexed as source text. See
+                // IEEE 1076-2008 section 11.9.
+                if let Some(n) = self.psl_prefix() {
+                    for _ in 0..n {
+                        if let Some((offset, _, sz, _)) = self.inner.next() {
+                            sp.end = offset + sz as usize;
+                        }
+                    }
+                    return Some(Spanned::new(Bundle::Space, sp));
+                }
+
                 while let &Some((offset, d, sz, _)) = self.inner.lookahead(0) {
                     if d == '\n' {
                         break;
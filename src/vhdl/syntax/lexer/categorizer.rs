@@ -36,8 +36,10 @@ where
             None => return None,
         };
         let cat = match c {
-            '"' | '#' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | '-' | '.' | '/' | ':' | ';'
-            | '<' | '=' | '>' | '?' | '@' | '[' | ']' | '_' | '`' | '|' => Category::Special,
+            '!' | '"' | '#' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | '-' | '.' | '/' | ':'
+            | ';' | '<' | '=' | '>' | '?' | '@' | '[' | ']' | '^' | '_' | '`' | '{' | '|' | '}' => {
+                Category::Special
+            }
             c if c.is_alphabetic() => Category::Letter,
             c if c.is_digit(10) => Category::Digit,
             c if c.is_whitespace() => Category::Space,
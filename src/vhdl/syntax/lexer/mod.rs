@@ -76,14 +76,10 @@ mod test {
     }
 
     fn check(input: &str, expected: &[Token]) {
-        use std::cell::Cell;
-        thread_local!(static INDEX: Cell<usize> = Cell::new(0));
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static INDEX: AtomicUsize = AtomicUsize::new(0);
         let sm = get_source_manager();
-        let idx = INDEX.with(|i| {
-            let v = i.get();
-            i.set(v + 1);
-            v
-        });
+        let idx = INDEX.fetch_add(1, Ordering::SeqCst);
         let source = sm.add(&format!("test_{}.vhd", idx), input);
         let actual = lex(source);
         assert_eq!(actual.len(), expected.len());
@@ -405,6 +401,7 @@ mod test {
             =    /=   <    <=   >    >=
             ?=   ?/=  ?<   ?<=  ?>   ?>=
             +    -    *    /    **
+            @    ^
         ",
             &[
                 OpenDelim(Paren),
@@ -438,6 +435,51 @@ mod test {
                 Mul,
                 Div,
                 Pow,
+                Commat,
+                Circumflex,
+            ],
+        );
+    }
+
+    #[test]
+    fn psl_symbols() {
+        check(
+            "
+            {    }    [    ]    !
+            ->   <->  |->  |=>  &&
+        ",
+            &[
+                OpenDelim(Brace),
+                CloseDelim(Brace),
+                OpenDelim(Brack),
+                CloseDelim(Brack),
+                Excl,
+                Implication,
+                Equivalence,
+                OverlapImpl,
+                NonOverlapImpl,
+                DoubleAmpersand,
+            ],
+        );
+    }
+
+    #[test]
+    fn psl_comments() {
+        check(
+            "
+            -- psl assert always a;
+            --PSL cover b;
+            -- pslx
+            -- not psl
+        ",
+            &[
+                Keyword(Kw::Assert),
+                Ident(name("always")),
+                Ident(name("a")),
+                Semicolon,
+                Keyword(Kw::Cover),
+                Ident(name("b")),
+                Semicolon,
             ],
         );
     }
@@ -3,6 +3,7 @@
 pub use self::DelimToken::*;
 pub use self::Token::*;
 use moore_common::name::*;
+use serde::Serialize;
 use std;
 use std::fmt::{Display, Formatter, Result};
 
@@ -51,6 +52,14 @@ pub enum Token {
     Pow,
     Pipe,
     Qmark,
+    Commat,
+    Circumflex,
+    Excl,
+    Implication,
+    Equivalence,
+    OverlapImpl,
+    NonOverlapImpl,
+    DoubleAmpersand,
 
     /// The end of the input file.
     Eof,
@@ -65,6 +74,8 @@ impl Token {
             CloseDelim(Paren) => ")",
             OpenDelim(Brack) => "[",
             CloseDelim(Brack) => "]",
+            OpenDelim(Brace) => "{",
+            CloseDelim(Brace) => "}",
             Keyword(kw) => kw.as_str(),
 
             Period => ".",
@@ -98,6 +109,14 @@ impl Token {
             Pow => "**",
             Pipe => "|",
             Qmark => "?",
+            Commat => "@",
+            Circumflex => "^",
+            Excl => "!",
+            Implication => "->",
+            Equivalence => "<->",
+            OverlapImpl => "|->",
+            NonOverlapImpl => "|=>",
+            DoubleAmpersand => "&&",
 
             Eof => "end of file",
         }
@@ -123,7 +142,7 @@ impl Display for Token {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub enum Literal {
     Abstract(
         /// Base
@@ -162,9 +181,10 @@ impl Literal {
 pub enum DelimToken {
     Paren,
     Brack,
+    Brace,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub struct Exponent(
     /// Sign
     pub ExponentSign,
@@ -172,13 +192,13 @@ pub struct Exponent(
     pub Name,
 );
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub enum ExponentSign {
     Positive,
     Negative,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub enum BitStringBase {
     B,
     O,
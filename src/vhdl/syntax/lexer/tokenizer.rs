@@ -246,6 +246,9 @@ where
                 ('?', '/', '=') => Some(MatchNeq),
                 ('?', '<', '=') => Some(MatchLeq),
                 ('?', '>', '=') => Some(MatchGeq),
+                ('<', '-', '>') => Some(Equivalence),
+                ('|', '-', '>') => Some(OverlapImpl),
+                ('|', '=', '>') => Some(NonOverlapImpl),
                 _ => None,
             } {
                 span.expand(sp);
@@ -274,6 +277,8 @@ where
                 ('?', '<') => Some(MatchLt),
                 ('?', '>') => Some(MatchGt),
                 ('*', '*') => Some(Pow),
+                ('-', '>') => Some(Implication),
+                ('&', '&') => Some(DoubleAmpersand),
                 _ => None,
             } {
                 span.expand(sp);
@@ -288,6 +293,8 @@ where
             ')' => Some(CloseDelim(Paren)),
             '[' => Some(OpenDelim(Brack)),
             ']' => Some(CloseDelim(Brack)),
+            '{' => Some(OpenDelim(Brace)),
+            '}' => Some(CloseDelim(Brace)),
             '.' => Some(Period),
             ',' => Some(Comma),
             ':' => Some(Colon),
@@ -303,6 +310,9 @@ where
             '/' => Some(Div),
             '|' => Some(Pipe),
             '?' => Some(Qmark),
+            '@' => Some(Commat),
+            '^' => Some(Circumflex),
+            '!' => Some(Excl),
             _ => None,
         } {
             return Some(Spanned::new(tkn, span));
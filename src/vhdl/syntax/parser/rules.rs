@@ -325,7 +325,15 @@ pub fn parse_name_suffix<P: Parser>(
         return parse_name_suffix(p, name);
     }
 
-    // Try to parse a signature.
+    // Try to parse a signature. A bracket followed by one of these tokens is
+    // the repetition of a PSL sequence instead.
+    let is_repeat = match p.peek(1).value {
+        Mul | Add | Eq | Implication => true,
+        _ => false,
+    };
+    if is_repeat {
+        return Ok(name);
+    }
     if let Some(sig) = try_flanked(p, Brack, parse_signature)? {
         name.span.expand(p.last_span());
         name.parts.push(ast::NamePart::Signature(sig));
@@ -839,6 +847,12 @@ pub fn try_decl_item<P: Parser>(p: &mut P) -> ReportedResult<Option<ast::DeclIte
         }
         // group_decl := "group" ...
         Keyword(Kw::Group) => Some(ast::DeclItem::GroupDecl(parse_group_decl(p)?)),
+        // psl_decl := "property" ...
+        // psl_decl := "sequence" ...
+        // psl_decl := "default" "clock" ...
+        Keyword(Kw::Property) | Keyword(Kw::Sequence) | Keyword(Kw::Default) => {
+            Some(ast::DeclItem::PslDecl(parse_psl_decl(p)?))
+        }
         _ => None,
     })
 }
@@ -1124,6 +1138,12 @@ pub fn parse_primary_expr<P: Parser>(p: &mut P) -> ReportedResult<ast::Expr> {
             Some(ast::LitExpr(l, None))
         }
 
+        Lshift => {
+            let name = parse_external_name(p)?;
+            span.expand(p.last_span());
+            Some(ast::ExternalNameExpr(Box::new(name)))
+        }
+
         OpenDelim(Paren) => {
             let expr = parse_paren_expr(p)?;
 
@@ -1169,6 +1189,87 @@ pub fn parse_primary_expr<P: Parser>(p: &mut P) -> ReportedResult<ast::Expr> {
     Err(Reported)
 }
 
+/// Parse an external name. See IEEE 1076-2008 section 8.7.
+///
+/// ```text
+/// external_name := "<<" ("constant"|"signal"|"variable") external_path ":" subtype_ind ">>"
+/// external_path
+///   := "@" ident "." ident {"." ident}
+///   := "." ident {"." ident}
+///   := {"^" "."} ident {"." ident}
+/// ```
+pub fn parse_external_name<P: Parser>(p: &mut P) -> ReportedResult<ast::ExternalName> {
+    let mut span = p.peek(0).span;
+    require(p, Lshift)?;
+
+    // Parse the object class.
+    let Spanned {
+        value: tkn,
+        span: class_span,
+    } = p.peek(0);
+    let class = match tkn {
+        Keyword(Kw::Constant) => ast::ExternalClass::Constant,
+        Keyword(Kw::Signal) => ast::ExternalClass::Signal,
+        Keyword(Kw::Variable) => ast::ExternalClass::Variable,
+        wrong => {
+            p.emit(
+                DiagBuilder2::error(format!(
+                    "Expected `constant`, `signal`, or `variable` after `<<`, found {} instead",
+                    wrong
+                ))
+                .span(class_span),
+            );
+            return Err(Reported);
+        }
+    };
+    p.bump();
+
+    // Parse the path, which determines where in the design hierarchy the
+    // object is looked up.
+    let mut path_span = p.peek(0).span;
+    let kind = if accept(p, Commat) {
+        ast::ExternalPathKind::Package
+    } else if accept(p, Period) {
+        ast::ExternalPathKind::Absolute
+    } else {
+        let mut ups = 0;
+        while accept(p, Circumflex) {
+            require(p, Period)?;
+            ups += 1;
+        }
+        ast::ExternalPathKind::Relative(ups)
+    };
+    let mut parts = vec![parse_ident(p, "name in the path of an external name")?];
+    while accept(p, Period) {
+        parts.push(parse_ident(p, "name in the path of an external name")?);
+    }
+    path_span.expand(p.last_span());
+    if kind == ast::ExternalPathKind::Package && parts.len() < 3 {
+        p.emit(
+            DiagBuilder2::error(
+                "Expected a library, package, and object name after `@` in external name",
+            )
+            .span(path_span),
+        );
+        return Err(Reported);
+    }
+
+    require(p, Colon)?;
+    let subtype = parse_subtype_ind(p)?;
+    require(p, Rshift)?;
+    span.expand(p.last_span());
+    Ok(ast::ExternalName {
+        span,
+        class: Spanned::new(class, class_span),
+        path: ast::ExternalPath {
+            span: path_span,
+            kind,
+            parts,
+        },
+        subtype,
+    })
+}
+
 pub fn try_name_or_qualified_primary_expr<P: Parser>(
     p: &mut P,
 ) -> ReportedResult<Option<ast::Expr>> {
@@ -1177,8 +1278,9 @@ pub fn try_name_or_qualified_primary_expr<P: Parser>(
     // Try to parse a name.
     if let Some(name) = try_name(p)? {
         // Try to parse another name, for things that look like element
-        // resolutions.
-        if let Some(suffix_name) = try_name(p)? {
+        // resolutions. PSL operators such as `abort` end the name instead.
+        let psl_op = is_psl_word(p, 0, "abort") || is_psl_word(p, 0, "until_");
+        if let Some(suffix_name) = if psl_op { None } else { try_name(p)? } {
             span.expand(p.last_span());
             return Ok(Some(ast::Expr {
                 span: span,
@@ -1643,7 +1745,11 @@ pub fn parse_type_decl<P: Parser>(
                     require(p, Keyword(Kw::Body))?;
                 }
                 parse_optional_matching_ident(p, name, "type", "section 5.6");
-                ast::ProtectedType(decl_items)
+                if body {
+                    ast::ProtectedBody(decl_items)
+                } else {
+                    ast::ProtectedType(decl_items)
+                }
             }
 
             // Emit an error for anything else.
@@ -2504,6 +2610,7 @@ pub fn parse_stmt<P: Parser>(p: &mut P) -> ReportedResult<ast::Stmt> {
     let data = match p.peek(0).value {
         Keyword(Kw::Wait) => parse_wait_stmt(p)?,
         Keyword(Kw::Assert) => parse_assert_stmt(p)?,
+        Keyword(Kw::Assume) | Keyword(Kw::Restrict) | Keyword(Kw::Cover) => parse_psl_directive(p)?,
         Keyword(Kw::Report) => parse_report_stmt(p)?,
 
         // For the if statement, check if the `generate` or the `then` keyword
@@ -2669,12 +2776,16 @@ pub fn parse_wait_stmt<P: Parser>(p: &mut P) -> ReportedResult<ast::StmtData> {
 
 /// Parse an assertion statement. See IEEE 1076-2008 section 10.3.
 ///
+/// An assertion of a PSL property other than a plain condition is a PSL
+/// directive. See IEEE 1076-2008 section 11.9.
+///
 /// ```text
 /// assert_stmt := "assert" expr ["report" expr] ["severity" expr] ";"
+/// psl_directive := "assert" psl_prop ["report" expr] ["severity" expr] ";"
 /// ```
 pub fn parse_assert_stmt<P: Parser>(p: &mut P) -> ReportedResult<ast::StmtData> {
     require(p, Keyword(Kw::Assert))?;
-    let cond = parse_expr(p)?;
+    let prop = parse_psl_prop(p)?;
 
     // Parse the optional "report" part.
     let report = if accept(p, Keyword(Kw::Report)) {
@@ -2691,13 +2802,461 @@ pub fn parse_assert_stmt<P: Parser>(p: &mut P) -> ReportedResult<ast::StmtData>
     };
 
     require(p, Semicolon)?;
-    Ok(ast::AssertStmt {
-        cond: cond,
+    Ok(match prop.data {
+        ast::PslPropData::Bool(cond) => ast::AssertStmt {
+            cond: cond,
+            report: report,
+            severity: severity,
+        },
+        _ => ast::PslDirective {
+            kind: ast::PslDirectiveKind::Assert,
+            prop: prop,
+            report: report,
+            severity: severity,
+        },
+    })
+}
+
+/// Parse a PSL `assume`, `restrict`, or `cover` directive. See IEEE 1850-2010
+/// section 7.1.
+///
+/// ```text
+/// psl_directive
+///   := "assume" psl_prop ";"
+///   := "restrict" psl_prop ";"
+///   := "cover" psl_prop ["report" expr] ";"
+/// ```
+pub fn parse_psl_directive<P: Parser>(p: &mut P) -> ReportedResult<ast::StmtData> {
+    let kind = match p.peek(0).value {
+        Keyword(Kw::Assume) => ast::PslDirectiveKind::Assume,
+        Keyword(Kw::Restrict) => ast::PslDirectiveKind::Restrict,
+        Keyword(Kw::Cover) => ast::PslDirectiveKind::Cover,
+        _ => unreachable!(),
+    };
+    p.bump();
+    let prop = parse_psl_prop(p)?;
+    let report = if kind == ast::PslDirectiveKind::Cover && accept(p, Keyword(Kw::Report)) {
+        Some(parse_expr(p)?)
+    } else {
+        None
+    };
+    require(p, Semicolon)?;
+    Ok(ast::PslDirective {
+        kind: kind,
+        prop: prop,
         report: report,
-        severity: severity,
+        severity: None,
+    })
+}
+
+/// Parse a PSL declaration. See IEEE 1850-2010 sections 6.1.5, 6.2.4, and
+/// 7.3.
+///
+/// ```text
+/// psl_decl
+///   := "property" ident "is" psl_prop ";"
+///   := "sequence" ident "is" psl_prop ";"
+///   := "default" "clock" "is" expr ";"
+/// ```
+pub fn parse_psl_decl<P: Parser>(p: &mut P) -> ReportedResult<ast::PslDecl> {
+    let mut span = p.peek(0).span;
+    let (kind, name) = match p.peek(0).value {
+        Keyword(Kw::Property) => {
+            p.bump();
+            let name = parse_ident(p, "property name")?;
+            (ast::PslDeclKind::Property, Some(name))
+        }
+        Keyword(Kw::Sequence) => {
+            p.bump();
+            let name = parse_ident(p, "sequence name")?;
+            (ast::PslDeclKind::Sequence, Some(name))
+        }
+        _ => {
+            require(p, Keyword(Kw::Default))?;
+            if !is_psl_word(p, 0, "clock") {
+                let q = p.peek(0);
+                p.emit(
+                    DiagBuilder2::error(format!(
+                        "Expected `clock` after `default`, found {} instead",
+                        q.value
+                    ))
+                    .span(q.span),
+                );
+                return Err(Reported);
+            }
+            p.bump();
+            (ast::PslDeclKind::DefaultClock, None)
+        }
+    };
+    require(p, Keyword(Kw::Is))?;
+    let prop = match kind {
+        ast::PslDeclKind::DefaultClock => {
+            let expr = parse_expr(p)?;
+            ast::PslProp {
+                span: expr.span,
+                data: ast::PslPropData::Bool(expr),
+            }
+        }
+        _ => parse_psl_prop(p)?,
+    };
+    require(p, Semicolon)?;
+    span.expand(p.last_span());
+    Ok(ast::PslDecl {
+        id: Default::default(),
+        span: span,
+        kind: kind,
+        name: name,
+        prop: prop,
     })
 }
 
+/// Check whether a token is an identifier that PSL treats as a keyword.
+fn is_psl_word<P: Parser>(p: &mut P, offset: usize, word: &str) -> bool {
+    match p.peek(offset).value {
+        Ident(n) => n.as_str().eq_ignore_ascii_case(word),
+        _ => false,
+    }
+}
+
+/// Wrap a PSL property around its operand.
+fn psl_prop(span: Span, data: ast::PslPropData) -> ast::PslProp {
+    ast::PslProp {
+        span: span,
+        data: data,
+    }
+}
+
+/// Parse a PSL property. See IEEE 1850-2010 section 6.2. The operators are
+/// parsed in order of increasing precedence, as listed in section 5.2.3.
+///
+/// ```text
+/// psl_prop := ("always"|"never") psl_prop | psl_impl_prop
+/// ```
+pub fn parse_psl_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let Spanned { span, .. } = p.peek(0);
+    let op = if is_psl_word(p, 0, "always") {
+        ast::PslUnaryOp::Always
+    } else if is_psl_word(p, 0, "never") {
+        ast::PslUnaryOp::Never
+    } else {
+        return parse_psl_impl_prop(p);
+    };
+    p.bump();
+    let arg = parse_psl_prop(p)?;
+    Ok(psl_prop(
+        Span::union(span, p.last_span()),
+        ast::PslPropData::Unary(Spanned::new(op, span), Box::new(arg)),
+    ))
+}
+
+/// Parse a PSL property with a logical implication.
+///
+/// ```text
+/// psl_impl_prop := psl_suffix_prop [("->"|"<->") psl_impl_prop]
+/// ```
+fn parse_psl_impl_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let lhs = parse_psl_suffix_prop(p)?;
+    let Spanned { value, span } = p.peek(0);
+    let op = match value {
+        Implication => ast::PslBinaryOp::Implication,
+        Equivalence => ast::PslBinaryOp::Equivalence,
+        _ => return Ok(lhs),
+    };
+    p.bump();
+    let rhs = parse_psl_impl_prop(p)?;
+    Ok(psl_prop(
+        Span::union(lhs.span, p.last_span()),
+        ast::PslPropData::Binary(Spanned::new(op, span), Box::new(lhs), Box::new(rhs)),
+    ))
+}
+
+/// Parse a PSL property with a suffix implication.
+///
+/// ```text
+/// psl_suffix_prop := psl_until_prop [("|->"|"|=>") psl_suffix_prop]
+/// ```
+fn parse_psl_suffix_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let lhs = parse_psl_until_prop(p)?;
+    let Spanned { value, span } = p.peek(0);
+    let op = match value {
+        OverlapImpl => ast::PslBinaryOp::OverlapImpl,
+        NonOverlapImpl => ast::PslBinaryOp::NonOverlapImpl,
+        _ => return Ok(lhs),
+    };
+    p.bump();
+    let rhs = parse_psl_suffix_prop(p)?;
+    Ok(psl_prop(
+        Span::union(lhs.span, p.last_span()),
+        ast::PslPropData::Binary(Spanned::new(op, span), Box::new(lhs), Box::new(rhs)),
+    ))
+}
+
+/// Parse a PSL property with `until` operators.
+///
+/// ```text
+/// psl_until_prop := psl_next_prop {("until"|"until!"|"until_") psl_next_prop}
+/// ```
+fn parse_psl_until_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut lhs = parse_psl_next_prop(p)?;
+    loop {
+        let mut span = p.peek(0).span;
+        let op = if accept(p, Keyword(Kw::Until)) {
+            let strong = accept(p, Excl);
+            ast::PslBinaryOp::Until {
+                strong: strong,
+                inclusive: false,
+            }
+        } else if is_psl_word(p, 0, "until_") {
+            p.bump();
+            ast::PslBinaryOp::Until {
+                strong: false,
+                inclusive: true,
+            }
+        } else {
+            return Ok(lhs);
+        };
+        span.expand(p.last_span());
+        let rhs = parse_psl_next_prop(p)?;
+        lhs = psl_prop(
+            Span::union(lhs.span, p.last_span()),
+            ast::PslPropData::Binary(Spanned::new(op, span), Box::new(lhs), Box::new(rhs)),
+        );
+    }
+}
+
+/// Parse a PSL property with occurrence operators.
+///
+/// ```text
+/// psl_next_prop
+///   := ("next"|"next!") ["[" expr "]"] psl_next_prop
+///   := "eventually" "!" psl_next_prop
+///   := psl_abort_prop
+/// ```
+fn parse_psl_next_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let span = p.peek(0).span;
+    if accept(p, Keyword(Kw::Next)) {
+        let strong = accept(p, Excl);
+        let count = try_flanked(p, Brack, parse_expr)?;
+        let arg = parse_psl_next_prop(p)?;
+        return Ok(psl_prop(
+            Span::union(span, p.last_span()),
+            ast::PslPropData::Next {
+                strong: strong,
+                count: count,
+                arg: Box::new(arg),
+            },
+        ));
+    }
+    if is_psl_word(p, 0, "eventually") {
+        p.bump();
+        require(p, Excl)?;
+        let op = Spanned::new(
+            ast::PslUnaryOp::Eventually,
+            Span::union(span, p.last_span()),
+        );
+        let arg = parse_psl_next_prop(p)?;
+        return Ok(psl_prop(
+            Span::union(span, p.last_span()),
+            ast::PslPropData::Unary(op, Box::new(arg)),
+        ));
+    }
+    parse_psl_abort_prop(p)
+}
+
+/// Parse a PSL property with abort conditions.
+///
+/// ```text
+/// psl_abort_prop := psl_clocked_prop {"abort" expr}
+/// ```
+fn parse_psl_abort_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut prop = parse_psl_clocked_prop(p)?;
+    while is_psl_word(p, 0, "abort") {
+        p.bump();
+        let cond = parse_expr(p)?;
+        prop = psl_prop(
+            Span::union(prop.span, p.last_span()),
+            ast::PslPropData::Abort(Box::new(prop), cond),
+        );
+    }
+    Ok(prop)
+}
+
+/// Parse a PSL property or sequence with a clock.
+///
+/// ```text
+/// psl_clocked_prop := psl_repeat_prop {"@" expr}
+/// ```
+fn parse_psl_clocked_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut prop = parse_psl_repeat_prop(p)?;
+    while accept(p, Commat) {
+        let clock = parse_expr(p)?;
+        prop = psl_prop(
+            Span::union(prop.span, p.last_span()),
+            ast::PslPropData::Clocked(Box::new(prop), clock),
+        );
+    }
+    Ok(prop)
+}
+
+/// Parse a PSL sequence with repetitions. See IEEE 1850-2010 section 6.1.2.
+///
+/// ```text
+/// psl_repeat_prop := psl_primary_prop {psl_repeat}
+/// psl_repeat
+///   := "[" "*" [expr ["to" expr]] "]"
+///   := "[" "+" "]"
+///   := "[" "=" expr ["to" expr] "]"
+///   := "[" "->" [expr ["to" expr]] "]"
+/// ```
+fn parse_psl_repeat_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut prop = parse_psl_primary_prop(p)?;
+    while p.peek(0).value == OpenDelim(Brack) {
+        let mut span = p.peek(0).span;
+        let kind = match p.peek(1).value {
+            Mul => ast::PslRepeatKind::Consecutive,
+            Add => ast::PslRepeatKind::Plus,
+            Eq => ast::PslRepeatKind::NonConsecutive,
+            Implication => ast::PslRepeatKind::Goto,
+            _ => break,
+        };
+        p.bump();
+        p.bump();
+        let (mut low, mut high) = (None, None);
+        let has_count = match kind {
+            ast::PslRepeatKind::Plus => false,
+            ast::PslRepeatKind::NonConsecutive => true,
+            _ => p.peek(0).value != CloseDelim(Brack),
+        };
+        if has_count {
+            low = Some(Box::new(parse_expr_prec(p, ExprPrec::Add)?));
+            if accept(p, Keyword(Kw::To)) {
+                high = Some(Box::new(parse_expr_prec(p, ExprPrec::Add)?));
+            }
+        }
+        require(p, CloseDelim(Brack))?;
+        span.expand(p.last_span());
+        prop = psl_prop(
+            Span::union(prop.span, span),
+            ast::PslPropData::Repeat(
+                Box::new(prop),
+                ast::PslRepeat {
+                    span: span,
+                    kind: kind,
+                    low: low,
+                    high: high,
+                },
+            ),
+        );
+    }
+    Ok(prop)
+}
+
+/// Parse a primary PSL property or sequence.
+///
+/// A parenthesized property that turns out to be a plain condition may be
+/// continued by VHDL operators, as in `(a or b) and c`. Parentheses that
+/// contain an aggregate or association list are left to the VHDL expression
+/// parser.
+///
+/// ```text
+/// psl_primary_prop
+///   := "{" psl_sere "}"
+///   := "(" psl_prop ")"
+///   := expr
+/// ```
+fn parse_psl_primary_prop<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut span = p.peek(0).span;
+    if p.peek(0).value == OpenDelim(Brace) {
+        let sere = flanked(p, Brace, parse_psl_sere)?;
+        span.expand(p.last_span());
+        return Ok(psl_prop(span, ast::PslPropData::Braced(Box::new(sere))));
+    }
+    if p.peek(0).value == OpenDelim(Paren) && is_psl_paren(p) {
+        let prop = flanked(p, Paren, parse_psl_prop)?;
+        span.expand(p.last_span());
+        return match prop.data {
+            ast::PslPropData::Bool(expr) => {
+                let paren = ast::Expr {
+                    span: span,
+                    data: ast::ParenExpr(Spanned::new(
+                        vec![ast::ParenElem {
+                            span: expr.span,
+                            choices: Spanned::new(vec![], INVALID_SPAN),
+                            expr: expr,
+                        }],
+                        span,
+                    )),
+                };
+                let expr = parse_expr_suffix(p, paren, ExprPrec::lowest())?;
+                Ok(psl_prop(expr.span, ast::PslPropData::Bool(expr)))
+            }
+            _ => Ok(prop),
+        };
+    }
+    let expr = parse_expr(p)?;
+    Ok(psl_prop(expr.span, ast::PslPropData::Bool(expr)))
+}
+
+/// Check whether the parenthesis that follows contains a single expression
+/// rather than an aggregate or association list.
+fn is_psl_paren<P: Parser>(p: &mut P) -> bool {
+    let mut depth = 0;
+    for i in 0.. {
+        match p.peek(i).value {
+            OpenDelim(Paren) => depth += 1,
+            CloseDelim(Paren) if depth == 1 => return true,
+            CloseDelim(Paren) => depth -= 1,
+            Comma | Arrow | Keyword(Kw::Others) | Keyword(Kw::Open) if depth == 1 => return false,
+            Eof => return false,
+            _ => (),
+        }
+    }
+    unreachable!();
+}
+
+/// Parse a sequential extended regular expression. See IEEE 1850-2010
+/// section 6.1.1.
+///
+/// ```text
+/// psl_sere := psl_sere_term {(";"|":") psl_sere_term}
+/// psl_sere_term := psl_clocked_prop {("|"|"&&") psl_clocked_prop}
+/// ```
+fn parse_psl_sere<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut lhs = parse_psl_sere_term(p)?;
+    loop {
+        let Spanned { value, span } = p.peek(0);
+        let op = match value {
+            Semicolon => ast::PslBinaryOp::Concat,
+            Colon => ast::PslBinaryOp::Fusion,
+            _ => return Ok(lhs),
+        };
+        p.bump();
+        let rhs = parse_psl_sere_term(p)?;
+        lhs = psl_prop(
+            Span::union(lhs.span, p.last_span()),
+            ast::PslPropData::Binary(Spanned::new(op, span), Box::new(lhs), Box::new(rhs)),
+        );
+    }
+}
+
+fn parse_psl_sere_term<P: Parser>(p: &mut P) -> ReportedResult<ast::PslProp> {
+    let mut lhs = parse_psl_clocked_prop(p)?;
+    loop {
+        let Spanned { value, span } = p.peek(0);
+        let op = match value {
+            Pipe => ast::PslBinaryOp::Or,
+            DoubleAmpersand => ast::PslBinaryOp::And,
+            _ => return Ok(lhs),
+        };
+        p.bump();
+        let rhs = parse_psl_clocked_prop(p)?;
+        lhs = psl_prop(
+            Span::union(lhs.span, p.last_span()),
+            ast::PslPropData::Binary(Spanned::new(op, span), Box::new(lhs), Box::new(rhs)),
+        );
+    }
+}
+
 /// Parse a report statement. See IEEE 1076-2008 section 10.4.
 ///
 /// ```text
@@ -489,6 +489,17 @@ fn expr() {
     parse!("others", parse_expr);
 }
 
+#[test]
+fn external_name() {
+    parse!("<< signal .tb.dut.state : state_t >>", parse_expr);
+    parse!("<< constant ^.^.WIDTH : natural >>", parse_expr);
+    parse!("<< variable @work.pkg.count : integer >>", parse_expr);
+    parse!(
+        "<< signal dut.data : std_logic_vector(7 downto 0) >> = x\"00\"",
+        parse_expr
+    );
+}
+
 #[test]
 fn subtype_decl() {
     parse!("subtype foo is integer;", parse_subtype_decl);
@@ -538,3 +549,45 @@ fn config_decl() {
         parse_config_decl
     );
 }
+
+#[test]
+fn psl_prop() {
+    parse!("always a -> next b", parse_psl_prop);
+    parse!(
+        "always (req -> next[2] (ack = '1')) @ rising_edge(clk)",
+        parse_psl_prop
+    );
+    parse!("never {a; b[*2 to 3]; c}", parse_psl_prop);
+    parse!("always {req; ack[->1]} |=> {done[+]}", parse_psl_prop);
+    parse!("always (a or b) and c until! d", parse_psl_prop);
+    parse!("{{a[=2] : b} && {c | d[*]}}", parse_psl_prop);
+    parse!("eventually! done abort rst = '1'", parse_psl_prop);
+}
+
+#[test]
+fn psl_directive() {
+    match parse!("assert a = b report \"mismatch\";", parse_stmt).data {
+        ast::AssertStmt { .. } => (),
+        ref data => panic!("expected an assertion, got {:?}", data),
+    }
+    match parse!("check: assert always a -> next b;", parse_stmt).data {
+        ast::PslDirective {
+            kind: ast::PslDirectiveKind::Assert,
+            ..
+        } => (),
+        ref data => panic!("expected a PSL directive, got {:?}", data),
+    }
+    parse!("assume never (a and b);", parse_stmt);
+    parse!("restrict {rst; not rst[*]};", parse_stmt);
+    parse!("cover {req; ack} report \"handshake\";", parse_stmt);
+}
+
+#[test]
+fn psl_decl() {
+    parse!(
+        "property handshake is always req -> eventually! ack;",
+        parse_psl_decl
+    );
+    parse!("sequence burst is {valid[*4]};", parse_psl_decl);
+    parse!("default clock is rising_edge(clk);", parse_psl_decl);
+}
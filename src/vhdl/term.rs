@@ -7,7 +7,7 @@
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
-use num::{BigInt, BigRational};
+use num::{BigInt, BigRational, ToPrimitive};
 
 use crate::common::errors::*;
 use crate::common::name::Name;
@@ -19,7 +19,7 @@ use crate::common::SessionContext;
 use crate::add_ctx::AddContext;
 use crate::arenas::Alloc;
 use crate::hir;
-use crate::konst::ConstInt;
+use crate::konst::{ConstFloat, ConstInt};
 use crate::op::*;
 use crate::scope2::{Def2, ScopeData, TypeVariantDef};
 use crate::score::*;
@@ -48,7 +48,7 @@ pub enum Term<'t> {
     /// A floating point literal.
     FloatLit(BigRational),
     /// A physical literal.
-    PhysLit(BigInt, Spanned<EitherUnit<'t>>),
+    PhysLit(BigRational, Spanned<EitherUnit<'t>>),
     /// A bit string literal.
     StrLit(Name),
     /// An unresolved name.
@@ -63,6 +63,11 @@ pub enum Term<'t> {
     Enum(Vec<Spanned<EnumRef>>),
     /// A term that refers to an enum variant.
     Enum2(Vec<Spanned<Def2<'t>>>),
+    /// A term that refers to a set of overloaded subprograms.
+    Subprog(Vec<Spanned<Def>>),
+    /// A term of the form `T.<name>`, where `T` is an object of a protected
+    /// type and `<name>` refers to a set of its overloaded methods.
+    Method(Subterm<'t>, Vec<Spanned<Def>>),
     /// A term of the form `T.<name>`.
     Select(Subterm<'t>, Spanned<ResolvableName>),
     /// A term of the form `T.all`.
@@ -96,6 +101,23 @@ pub enum Term<'t> {
     Qual(Subterm<'t>, Subterm<'t>),
     /// A term of the form `new T`.
     New(Subterm<'t>),
+    /// A term of the form `<type_mark>'<attr>`, where the attribute is one of
+    /// the predefined attributes of the type.
+    TypeAttr(Spanned<TypeMarkRef>, Spanned<hir::TypeAttr>),
+    /// A term of the form `T'<attr>`, where the attribute is one of the
+    /// predefined bound, length, or range attributes of arrays and scalar
+    /// types.
+    BoundAttr(Subterm<'t>, Spanned<hir::BoundAttr>),
+    /// A term of the form `<signal>'<attr>`, where the attribute is one of the
+    /// predefined attributes of signals.
+    SignalAttr(Spanned<SignalRef>, Spanned<hir::SignalAttr>),
+    /// A term of the form `<< <class> <path> : T >>`.
+    External(
+        Spanned<hir::ExternalClass>,
+        hir::ExternalPathKind,
+        Vec<Spanned<Name>>,
+        Subterm<'t>,
+    ),
 }
 
 #[allow(missing_docs)]
@@ -324,7 +346,10 @@ where
                             let t = self.ensure_resolved(self.termify_name_in_scope(n, id)?)?;
                             Spanned::new(t.value, sp)
                         }
-                        None => Spanned::new(Term::Select(Box::new(term), n), sp),
+                        None => match self.maybe_methods(&term.value, n)? {
+                            Some(defs) => Spanned::new(Term::Method(Box::new(term), defs), sp),
+                            None => Spanned::new(Term::Select(Box::new(term), n), sp),
+                        },
                     }
                 }
                 ast::NamePart::SelectAll(span) => {
@@ -341,6 +366,56 @@ where
                     );
                     return Err(());
                 }
+                // Predefined attributes of types.
+                ast::NamePart::Attribute(ident)
+                    if match term.value {
+                        Term::TypeMark(_) => hir::TypeAttr::from_name(ident.name).is_some(),
+                        _ => false,
+                    } =>
+                {
+                    let tm = match term.value {
+                        Term::TypeMark(tm) => tm,
+                        _ => unreachable!(),
+                    };
+                    let attr = hir::TypeAttr::from_name(ident.name).unwrap();
+                    let sp = Span::union(term.span, ident.span);
+                    Spanned::new(Term::TypeAttr(tm, Spanned::new(attr, ident.span)), sp)
+                }
+                // Predefined attributes of signals.
+                ast::NamePart::Attribute(ident)
+                    if match term.value {
+                        Term::Ident(Spanned {
+                            value: Def::Signal(_),
+                            ..
+                        }) => hir::SignalAttr::from_name(ident.name).is_some(),
+                        _ => false,
+                    } =>
+                {
+                    let sig = match term.value {
+                        Term::Ident(Spanned {
+                            value: Def::Signal(sig),
+                            span,
+                        }) => Spanned::new(sig, span),
+                        _ => unreachable!(),
+                    };
+                    let attr = hir::SignalAttr::from_name(ident.name).unwrap();
+                    let sp = Span::union(term.span, ident.span);
+                    Spanned::new(Term::SignalAttr(sig, Spanned::new(attr, ident.span)), sp)
+                }
+                // Predefined attributes of arrays and scalar types.
+                ast::NamePart::Attribute(ident)
+                    if match term.value {
+                        Term::TypeMark(_) => true,
+                        ref t => is_object_term(t),
+                    } && hir::BoundAttr::from_name(ident.name).is_some() =>
+                {
+                    let attr = hir::BoundAttr::from_name(ident.name).unwrap();
+                    let sp = Span::union(term.span, ident.span);
+                    Spanned::new(
+                        Term::BoundAttr(Box::new(term), Spanned::new(attr, ident.span)),
+                        sp,
+                    )
+                }
                 ast::NamePart::Attribute(ident) => {
                     let attr = self.termify_name(Spanned::new(ident.name.into(), ident.span))?;
                     match attr.value {
@@ -483,7 +558,8 @@ where
                         }
                     };
                     let lit = match lit.value {
-                        Term::IntLit(v) => v,
+                        Term::IntLit(v) => BigRational::from_integer(v),
+                        Term::FloatLit(v) => v,
                         _ => {
                             self.emit(
                                 DiagBuilder2::error(format!(
@@ -533,6 +609,12 @@ where
                 self.termify_paren_elems(arg)?.into(),
             ),
             ast::NewExpr(ref expr) => Term::New(self.termify_expr(expr)?.into()),
+            ast::ExternalNameExpr(ref name) => Term::External(
+                name.class,
+                name.path.kind,
+                name.path.parts.clone(),
+                self.termify_subtype_ind(&name.subtype)?.into(),
+            ),
             ref wrong => {
                 self.emit(
                     DiagBuilder2::bug(format!(
@@ -579,6 +661,17 @@ impl<'t, C: DiagEmitter, S> DefSpecificTermContext<'t, Def> for TermContext<C, S
             }
         }
         let all_enum = defs.iter().all(is_enum);
+        fn is_subprog(def: &Spanned<Def>) -> bool {
+            match def.value {
+                Def::BuiltinOp(..) | Def::Subprog(..) => true,
+                _ => false,
+            }
+        }
+
+        // Overloaded subprograms are resolved once their arguments are known.
+        if defs.iter().all(is_subprog) {
+            return Ok(self.fold(Spanned::new(Term::Subprog(defs), name.span)));
+        }
 
         // Handle overloading. Basically if the definitions are all enum fields
         // or functions, that's fine. For everything else the name must be
@@ -683,6 +776,12 @@ pub trait ScopeSpecificTermContext<'t, S, D> {
     ) -> Result<Spanned<Term<'t>>>;
 
     fn maybe_selectable_scope(&self, term: &Term<'t>) -> Option<S>;
+
+    fn maybe_methods(
+        &self,
+        term: &Term<'t>,
+        name: Spanned<ResolvableName>,
+    ) -> Result<Option<Vec<Spanned<Def>>>>;
 }
 
 impl<'t, 'sbc, 'lazy, 'sb, 'ast, 'ctx> ScopeSpecificTermContext<'t, ScopeRef, Def>
@@ -715,6 +814,45 @@ where
             None
         }
     }
+
+    /// Find the methods of a given name, if the term is a variable of a
+    /// protected type.
+    fn maybe_methods(
+        &self,
+        term: &Term<'t>,
+        name: Spanned<ResolvableName>,
+    ) -> Result<Option<Vec<Spanned<Def>>>> {
+        let var = match *term {
+            Term::Ident(Spanned {
+                value: Def::Var(id),
+                ..
+            }) => id,
+            _ => return Ok(None),
+        };
+        let decl = match *self.ctx.deref_named_type(self.ctx.lazy_typeval(var)?)? {
+            Ty::Protected(decl) => decl,
+            _ => return Ok(None),
+        };
+        let mut defs = Vec::new();
+        for &method in self.ctx.protected_methods(decl)?.unwrap_or(&[]) {
+            let method_name = &self.ctx.ast(method).1.spec.name;
+            if self.ctx.resolvable_from_primary_name(method_name)?.value == name.value {
+                defs.push(Spanned::new(Def::Subprog(method), method_name.span));
+            }
+        }
+        if defs.is_empty() {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "protected type `{}` has no method `{}`",
+                    self.ctx.lazy_hir(decl)?.name.value,
+                    name.value
+                ))
+                .span(name.span),
+            );
+            return Err(());
+        }
+        Ok(Some(defs))
+    }
 }
 
 impl<'t> ScopeSpecificTermContext<'t, &'t ScopeData<'t>, Def2<'t>>
@@ -741,6 +879,14 @@ impl<'t> ScopeSpecificTermContext<'t, &'t ScopeData<'t>, Def2<'t>>
             None
         }
     }
+
+    fn maybe_methods(
+        &self,
+        _term: &Term<'t>,
+        _name: Spanned<ResolvableName>,
+    ) -> Result<Option<Vec<Spanned<Def>>>> {
+        Ok(None)
+    }
 }
 
 impl<'sbc, 'lazy, 'sb, 'ast, 'ctx>
@@ -792,6 +938,14 @@ where
                 return Err(());
             }
             Term::IntLit(value) => hir::ExprData::IntegerLiteral(ConstInt::new(None, value)),
+            Term::FloatLit(value) => hir::ExprData::FloatLiteral(ConstFloat::new(
+                None,
+                value.to_f64().unwrap_or(std::f64::INFINITY),
+            )),
+            Term::PhysLit(value, unit) => hir::ExprData::PhysicalLiteral(
+                value,
+                Spanned::new(unit.value.unwrap_old(), unit.span),
+            ),
             Term::StrLit(value) => {
                 // Create a set of characters used in the literal. Then resolve
                 // each as an individual bit literal. This yields multiple enums
@@ -909,6 +1063,12 @@ where
                 }
             },
             Term::Enum(defs) => hir::ExprData::EnumName(defs),
+            Term::Subprog(defs) => hir::ExprData::FuncCall(defs, Spanned::new(vec![], term_span)),
+            Term::Method(object, defs) => hir::ExprData::MethodCall(
+                self.term_to_expr(*object)?,
+                defs,
+                Spanned::new(vec![], term_span),
+            ),
             Term::Select(term, name) => hir::ExprData::Select(self.term_to_expr(*term)?, name),
             Term::Paren(subterm) => {
                 // A parenthesis with only one element is just a parenthesized
@@ -960,48 +1120,49 @@ where
             // way to differentiate them is to look at the kind of the callee.
             Term::SuffixParen(callee, args) => {
                 let callee = *callee;
+                if is_object_term(&callee.value) {
+                    return Ok(hir::Expr {
+                        parent: self.scope,
+                        span: term_span,
+                        data: self.term_to_index_or_slice(callee, *args)?,
+                    });
+                }
                 let args = self.term_to_assoc_list(*args)?;
                 match callee.value {
                     Term::TypeMark(tm) => {
-                        if args.value.len() != 1 {
-                            self.emit(
-                                DiagBuilder2::error(format!(
-                                    "cast `{}` must have exactly one argument",
-                                    term_span.extract()
-                                ))
-                                .span(args.span),
-                            );
-                            return Err(());
-                        }
-                        let arg = args.value.into_iter().next().unwrap();
-                        if let Some(formal) = arg.formal {
-                            self.emit(
-                                DiagBuilder2::error(format!(
-                                    "cast argument `{}` cannot have a formal part",
-                                    arg.span.extract()
-                                ))
-                                .span(formal.span),
-                            );
-                        }
-                        let arg = match arg.actual.value {
-                            hir::AssocActual::Expr(id) => id,
-                            _ => {
-                                self.emit(
-                                    DiagBuilder2::error(format!(
-                                        "`{}` is not a valid cast argument",
-                                        arg.actual.span.extract()
-                                    ))
-                                    .span(arg.actual.span),
-                                );
-                                return Err(());
-                            }
-                        };
+                        let arg = self.term_to_single_arg(args, "cast", term_span)?;
                         self.ctx.set_type_context(
                             arg,
                             self.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value)),
                         );
                         hir::ExprData::Cast(tm, arg)
                     }
+                    Term::TypeAttr(tm, attr) => {
+                        let arg = self.term_to_single_arg(args, "attribute", term_span)?;
+                        if attr.value != hir::TypeAttr::Val {
+                            self.ctx.set_type_context(
+                                arg,
+                                self.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value)),
+                            );
+                        }
+                        hir::ExprData::TypeAttr(tm, attr, arg)
+                    }
+                    Term::BoundAttr(prefix, attr) => {
+                        if attr.value.is_range() {
+                            self.emit_range_attr_in_expr(term_span);
+                            return Err(());
+                        }
+                        let dim = self.term_to_single_arg(args, "attribute", term_span)?;
+                        hir::ExprData::BoundAttr(
+                            self.term_to_attr_prefix(*prefix)?,
+                            attr,
+                            Some(dim),
+                        )
+                    }
+                    Term::Subprog(defs) => hir::ExprData::FuncCall(defs, args),
+                    Term::Method(object, defs) => {
+                        hir::ExprData::MethodCall(self.term_to_expr(*object)?, defs, args)
+                    }
                     other => hir::ExprData::Call(
                         self.term_to_expr(Spanned::new(other, callee.span))?,
                         args,
@@ -1009,6 +1170,37 @@ where
                 }
             }
 
+            Term::BoundAttr(prefix, attr) => {
+                if attr.value.is_range() {
+                    self.emit_range_attr_in_expr(term_span);
+                    return Err(());
+                }
+                hir::ExprData::BoundAttr(self.term_to_attr_prefix(*prefix)?, attr, None)
+            }
+            Term::SignalAttr(sig, attr) => hir::ExprData::SignalAttr(sig, attr),
+            Term::External(class, kind, path, subtype) => {
+                let subtype = self.term_to_subtype_ind(*subtype)?.value;
+                let ctx = AddContext::new(self.ctx, self.scope);
+                hir::ExprData::ExternalName(hir::ExternalName {
+                    class,
+                    kind,
+                    path,
+                    subtype: ctx.add_subtype_ind_hir(subtype)?,
+                })
+            }
+
+            Term::TypeAttr(..) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "attribute `{}` requires an argument",
+                        term.span.extract()
+                    ))
+                    .span(term.span)
+                    .add_note(format!("Use `{}(X)` instead", term.span.extract())),
+                );
+                return Err(());
+            }
+
             _ => {
                 self.emit(
                     DiagBuilder2::error(format!(
@@ -1028,6 +1220,113 @@ where
         })
     }
 
+    /// Map the prefix of a predefined attribute to a type mark or an object.
+    fn term_to_attr_prefix(&self, term: Spanned<Term>) -> Result<Spanned<hir::AttrPrefix>> {
+        let span = term.span;
+        Ok(Spanned::new(
+            match term.value {
+                Term::TypeMark(tm) => hir::AttrPrefix::Type(tm.value),
+                _ => hir::AttrPrefix::Expr(self.term_to_expr(term)?),
+            },
+            span,
+        ))
+    }
+
+    /// Emit an error for a range attribute used as an expression.
+    fn emit_range_attr_in_expr(&self, span: Span) {
+        self.emit(
+            DiagBuilder2::error(format!(
+                "`{}` is a range, not an expression",
+                span.extract()
+            ))
+            .span(span)
+            .add_note("Range attributes can only be used where a range is expected"),
+        );
+    }
+
+    /// Map the prefix and suffix of an indexed or slice name to an expression.
+    ///
+    /// A suffix that consists of a single discrete range makes this a slice.
+    fn term_to_index_or_slice(
+        &self,
+        prefix: Spanned<Term>,
+        suffix: Spanned<Term>,
+    ) -> Result<hir::ExprData> {
+        let prefix = self.term_to_expr(prefix)?;
+        let elems = match suffix.value {
+            Term::Paren(elems) => elems,
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a valid index or slice",
+                        suffix.span.extract()
+                    ))
+                    .span(suffix.span),
+                );
+                return Err(());
+            }
+        };
+        let is_range = match elems[0].value {
+            Term::Range(..) | Term::TypeMark(..) | Term::SubtypeInd(..) => true,
+            ref t => is_range_attr_term(t),
+        };
+        if elems.len() == 1 && is_range {
+            let range = self.term_to_discrete_range(elems.into_iter().next().unwrap())?;
+            return Ok(hir::ExprData::Slice(prefix, range));
+        }
+        let indices = elems
+            .into_iter()
+            .map(|elem| self.term_to_expr(elem))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(hir::ExprData::Index(prefix, indices))
+    }
+
+    /// Map the arguments of a cast or attribute to the single argument
+    /// expression.
+    fn term_to_single_arg(
+        &self,
+        args: Spanned<hir::AssocList>,
+        what: &str,
+        term_span: Span,
+    ) -> Result<ExprRef> {
+        if args.value.len() != 1 {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "{} `{}` must have exactly one argument",
+                    what,
+                    term_span.extract()
+                ))
+                .span(args.span),
+            );
+            return Err(());
+        }
+        let arg = args.value.into_iter().next().unwrap();
+        if let Some(formal) = arg.formal {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "{} argument `{}` cannot have a formal part",
+                    what,
+                    arg.span.extract()
+                ))
+                .span(formal.span),
+            );
+        }
+        match arg.actual.value {
+            hir::AssocActual::Expr(id) => Ok(id),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a valid {} argument",
+                        arg.actual.span.extract(),
+                        what
+                    ))
+                    .span(arg.actual.span),
+                );
+                Err(())
+            }
+        }
+    }
+
     /// Map a term to a type mark.
     pub fn term_to_type_mark(&self, term: Spanned<Term>) -> Result<Spanned<TypeMarkRef>> {
         match term.value {
@@ -1368,6 +1667,7 @@ where
                 Spanned::new(add_ctx.add_subtype_ind_hir(hir.value)?.into(), hir.span)
             }
             Term::Range(..) => self.term_to_range(term)?.map_into(),
+            ref t if is_range_attr_term(t) => self.term_to_range(term)?.map_into(),
             _ => {
                 self.emit(
                     DiagBuilder2::error(format!(
@@ -1393,12 +1693,23 @@ where
     pub fn term_to_range(&self, term: Spanned<Term>) -> Result<Spanned<hir::Range>> {
         Ok(Spanned::new(
             match term.value {
-                // Term::Attr(..) => ...
                 Term::Range(dir, lb, rb) => hir::Range::Immediate(
                     dir.value,
                     self.term_to_expr(*lb)?,
                     self.term_to_expr(*rb)?,
                 ),
+                Term::BoundAttr(prefix, attr) if attr.value.is_range() => {
+                    hir::Range::Attr(self.term_to_attr_prefix(*prefix)?, attr, None)
+                }
+                Term::SuffixParen(callee, args) if is_range_attr_term(&callee.value) => {
+                    let (prefix, attr) = match callee.value {
+                        Term::BoundAttr(prefix, attr) => (prefix, attr),
+                        _ => unreachable!(),
+                    };
+                    let args = self.term_to_assoc_list(*args)?;
+                    let dim = self.term_to_single_arg(args, "attribute", term.span)?;
+                    hir::Range::Attr(self.term_to_attr_prefix(*prefix)?, attr, Some(dim))
+                }
                 _ => {
                     self.emit(
                         DiagBuilder2::error(format!(
@@ -1504,6 +1815,34 @@ where
         ))
     }
 
+    /// Map a term to a procedure call.
+    ///
+    /// The procedure may be a method of a protected type, called on an object
+    /// of that type. See IEEE 1076-2008 section 10.7.
+    pub fn term_to_call_stmt(&self, term: Spanned<Term>) -> Result<hir::CallStmt> {
+        let span = term.span;
+        let (callee, params) = match term.value {
+            Term::SuffixParen(callee, args) => (*callee, self.term_to_assoc_list(*args)?),
+            other => (Spanned::new(other, span), Spanned::new(vec![], span)),
+        };
+        let (object, subprogs) = match callee.value {
+            Term::Subprog(defs) => (None, defs),
+            Term::Method(object, defs) => (Some(self.term_to_expr(*object)?), defs),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a procedure", callee.span.extract()))
+                        .span(callee.span),
+                );
+                return Err(());
+            }
+        };
+        Ok(hir::CallStmt {
+            object,
+            subprogs,
+            params,
+        })
+    }
+
     /// Map a term to a choice.
     ///
     /// See IEEE 1076-2008 section 9.3.3.1. A choice can be a simple expression,
@@ -1517,9 +1856,15 @@ where
                 Term::SubtypeInd(..) | Term::TypeMark(..) | Term::Range(..) => {
                     hir::Choice::DiscreteRange(self.term_to_discrete_range(term)?.value)
                 }
-                Term::IntLit(..) | Term::Unary(..) | Term::Binary(..) => {
-                    hir::Choice::Expr(self.term_to_expr(term)?)
+                ref t if is_range_attr_term(t) => {
+                    hir::Choice::DiscreteRange(self.term_to_discrete_range(term)?.value)
                 }
+                Term::IntLit(..)
+                | Term::Enum(..)
+                | Term::Ident(..)
+                | Term::Unary(..)
+                | Term::Binary(..)
+                | Term::BoundAttr(..) => hir::Choice::Expr(self.term_to_expr(term)?),
                 _ => {
                     self.emit(
                         DiagBuilder2::error(format!(
@@ -1860,6 +2205,45 @@ impl<'t> TermContext<hir::AllocContext<'t>, &'t ScopeData<'t>, Def2<'t>> {
     }
 }
 
+/// Check if a term is a range attribute, e.g. `A'range` or `A'range(2)`.
+fn is_range_attr_term(term: &Term) -> bool {
+    match *term {
+        Term::BoundAttr(_, attr) => attr.value.is_range(),
+        Term::SuffixParen(ref callee, _) => match callee.value {
+            Term::BoundAttr(_, attr) => attr.value.is_range(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check whether a term refers to an object, such that a parenthesized suffix
+/// indexes or slices it rather than calling a function.
+fn is_object_term(term: &Term) -> bool {
+    match *term {
+        Term::Ident(Spanned {
+            value: Def::Const(..),
+            ..
+        })
+        | Term::Ident(Spanned {
+            value: Def::Signal(..),
+            ..
+        })
+        | Term::Ident(Spanned {
+            value: Def::Var(..),
+            ..
+        })
+        | Term::Ident(Spanned {
+            value: Def::File(..),
+            ..
+        })
+        | Term::Select(..)
+        | Term::External(..) => true,
+        Term::SuffixParen(ref prefix, _) => is_object_term(&prefix.value),
+        _ => false,
+    }
+}
+
 /// Map a term to a range.
 pub fn term_to_range<'t, C>(term: Spanned<Term<'t>>, ctx: C) -> Result<Spanned<hir::Range2<'t>>>
 where
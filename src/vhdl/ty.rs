@@ -32,6 +32,11 @@ pub enum Ty {
     /// evaluated at compile time, e.g. as part of a range expression. Cannot be
     /// mapped to LLHD.
     UnboundedInt,
+    /// A floating-point type.
+    Float(FloatTy),
+    /// A universal real type. This is the type of real literals before they
+    /// are converted to a floating-point type.
+    UniversalReal,
     /// An enumeration type.
     Enum(EnumTy),
     /// A physical type.
@@ -46,6 +51,9 @@ pub enum Ty {
     Record(RecordTy),
     /// A subprogram type.
     Subprog(SubprogTy),
+    /// A protected type. Refers to the declaration of the type, which lists
+    /// its methods.
+    Protected(TypeDeclRef),
 }
 
 impl Ty {
@@ -57,6 +65,7 @@ impl Ty {
             Ty::Named(..) => "named type",
             Ty::Null => "null type",
             Ty::Int(_) | Ty::UnboundedInt | Ty::UniversalInt => "integer type",
+            Ty::Float(_) | Ty::UniversalReal => "floating-point type",
             Ty::Enum(_) => "enumeration type",
             Ty::Physical(_) => "physical type",
             Ty::Access(_) => "access type",
@@ -64,6 +73,7 @@ impl Ty {
             Ty::File(..) => "file type",
             Ty::Record(_) => "record type",
             Ty::Subprog(_) => "subprogram type",
+            Ty::Protected(_) => "protected type",
         }
     }
 
@@ -78,6 +88,7 @@ impl Ty {
     /// Check if this type is a real.
     pub fn is_real(&self) -> bool {
         match *self {
+            Ty::Float(..) | Ty::UniversalReal => true,
             _ => false,
         }
     }
@@ -89,6 +100,12 @@ impl From<IntTy> for Ty {
     }
 }
 
+impl From<FloatTy> for Ty {
+    fn from(t: FloatTy) -> Ty {
+        Ty::Float(t)
+    }
+}
+
 impl From<EnumTy> for Ty {
     fn from(t: EnumTy) -> Ty {
         Ty::Enum(t)
@@ -127,6 +144,8 @@ impl fmt::Display for Ty {
             Ty::Int(ref ty) => write!(f, "{}", ty),
             Ty::UniversalInt => write!(f, "{{universal integer}}"),
             Ty::UnboundedInt => write!(f, "{{integer}}"),
+            Ty::Float(ref ty) => write!(f, "{}", ty),
+            Ty::UniversalReal => write!(f, "{{universal real}}"),
             Ty::Enum(ref ty) => write!(f, "{}", ty),
             Ty::Physical(ref ty) => write!(f, "{}", ty),
             Ty::Access(ref ty) => write!(f, "access {}", ty),
@@ -134,6 +153,7 @@ impl fmt::Display for Ty {
             Ty::File(ref ty) => write!(f, "file of {}", ty),
             Ty::Record(ref ty) => write!(f, "{}", ty),
             Ty::Subprog(ref ty) => write!(f, "{}", ty),
+            Ty::Protected(_) => write!(f, "protected"),
         }
     }
 }
@@ -189,12 +209,12 @@ impl IntTy {
         }
     }
 
-    /// Map the type to itself if the range has a positive length, or to `null`
-    /// if the range has a negative or zero length.
+    /// Map the type to itself if the range contains at least one value, or
+    /// to `null` if it is a null range.
     pub fn maybe_null(self) -> Ty {
         match self.dir {
-            Dir::To if self.left_bound >= self.right_bound => Ty::Null,
-            Dir::Downto if self.left_bound <= self.right_bound => Ty::Null,
+            Dir::To if self.left_bound > self.right_bound => Ty::Null,
+            Dir::Downto if self.left_bound < self.right_bound => Ty::Null,
             _ => self.into(),
         }
     }
@@ -202,8 +222,16 @@ impl IntTy {
     /// The length of the range.
     pub fn len(&self) -> BigInt {
         match self.dir {
-            Dir::To => &self.left_bound + BigInt::one() - &self.right_bound,
-            Dir::Downto => &self.right_bound + BigInt::one() - &self.left_bound,
+            Dir::To => &self.right_bound + BigInt::one() - &self.left_bound,
+            Dir::Downto => &self.left_bound + BigInt::one() - &self.right_bound,
+        }
+    }
+
+    /// The lower and upper bound of the range, regardless of its direction.
+    pub fn bounds(&self) -> (&BigInt, &BigInt) {
+        match self.dir {
+            Dir::To => (&self.left_bound, &self.right_bound),
+            Dir::Downto => (&self.right_bound, &self.left_bound),
         }
     }
 }
@@ -214,18 +242,71 @@ impl fmt::Display for IntTy {
     }
 }
 
+/// A floating-point type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatTy {
+    pub dir: Dir,
+    pub left_bound: f64,
+    pub right_bound: f64,
+}
+
+// We have to explicitly implement this, since f64 by default does not, causing
+// the usual derive(Eq) to fail.
+impl Eq for FloatTy {}
+
+impl FloatTy {
+    /// Create a new floating-point type.
+    pub fn new(dir: Dir, left_bound: f64, right_bound: f64) -> FloatTy {
+        FloatTy {
+            dir: dir,
+            left_bound: left_bound,
+            right_bound: right_bound,
+        }
+    }
+
+    /// Map the type to itself if the range has a positive length, or to `null`
+    /// if the range has a negative length.
+    pub fn maybe_null(self) -> Ty {
+        match self.dir {
+            Dir::To if self.left_bound > self.right_bound => Ty::Null,
+            Dir::Downto if self.left_bound < self.right_bound => Ty::Null,
+            _ => self.into(),
+        }
+    }
+}
+
+impl fmt::Display for FloatTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.left_bound, self.dir, self.right_bound)
+    }
+}
+
 /// An enumeration type. Rather than keeping track of each enumeration value in
 /// here, we simply point at the type declaration.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnumTy {
     /// The declaration of the enum.
     pub decl: TypeDeclRef,
+    /// The direction and the positions of the left and right literal, if the
+    /// type is constrained to a range of the enum's literals.
+    pub range: Option<(Dir, usize, usize)>,
 }
 
 impl EnumTy {
     /// Create a new enumeration type.
     pub fn new(decl: TypeDeclRef) -> EnumTy {
-        EnumTy { decl: decl }
+        EnumTy {
+            decl: decl,
+            range: None,
+        }
+    }
+
+    /// Create a new enumeration type constrained to a range of literals.
+    pub fn with_range(decl: TypeDeclRef, dir: Dir, left: usize, right: usize) -> EnumTy {
+        EnumTy {
+            decl: decl,
+            range: Some((dir, left, right)),
+        }
     }
 }
 
@@ -313,6 +394,10 @@ pub struct ArrayTy {
     pub indices: Vec<ArrayIndex>,
     /// The type of the array element.
     pub element: Box<Ty>,
+    /// The array type this array was constrained from, if any. Constrained
+    /// subtypes keep their base type such that `UNSIGNED(7 downto 0)` and
+    /// `SIGNED(7 downto 0)` remain distinct types.
+    pub base: Option<TypeMarkRef>,
 }
 
 impl ArrayTy {
@@ -322,8 +407,14 @@ impl ArrayTy {
         ArrayTy {
             indices: indices,
             element: element,
+            base: None,
         }
     }
+
+    /// Set the base type of the array.
+    pub fn with_base(self, base: Option<TypeMarkRef>) -> ArrayTy {
+        ArrayTy { base: base, ..self }
+    }
 }
 
 impl fmt::Display for ArrayTy {
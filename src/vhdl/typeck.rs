@@ -6,7 +6,9 @@ use std::cell::Cell;
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+use crate::builtin::INTEGER_TYPE;
 use crate::common::errors::*;
+use crate::common::name::get_name_table;
 use crate::common::score::{NodeMaker, NodeStorage, Result};
 use crate::common::source::{Span, Spanned, INVALID_SPAN};
 use crate::common::{NodeId, Verbosity};
@@ -14,6 +16,7 @@ use crate::hir;
 use crate::konst::*;
 use crate::lazy::LazyNode;
 use crate::score::*;
+use crate::syntax::ast;
 use crate::ty::*;
 
 /// A context to typecheck things in.
@@ -101,20 +104,36 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         let id = id.into();
 
         // If the typeval has already been performed, return its result.
+        let key = (id, self.ctx.generic_env());
         if let Some(&node) = self.ctx.sb.typeval_table.borrow().get(&id) {
             return node;
         }
+        if let Some(&node) = self.ctx.sb.env_typeval_table.borrow().get(&key) {
+            self.ctx.mark_generic_dep();
+            return node;
+        }
 
         // Otherwise run the task scheduled in the lazy typeval table, then store
-        // the result.
+        // the result. The task is kept around, since it runs again for every
+        // generic environment if the result depends on it.
         let task = self
             .ctx
             .lazy
             .typeval
             .borrow_mut()
             .set(id, LazyNode::Running);
+        let mut dep = false;
         let result = match task {
-            Some(LazyNode::Pending(f)) => f(self),
+            Some(LazyNode::Pending(f)) => {
+                let (result, d) = self.ctx.track_generic_dep(|| f(self));
+                self.ctx
+                    .lazy
+                    .typeval
+                    .borrow_mut()
+                    .set(id, LazyNode::Pending(f));
+                dep = d;
+                result
+            }
             Some(LazyNode::Running) => {
                 self.ctx
                     .bug(id, format!("recursion on typeval of {:?}", id));
@@ -144,7 +163,15 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             }
         }
 
-        self.ctx.sb.typeval_table.borrow_mut().insert(id, result);
+        if dep {
+            self.ctx
+                .sb
+                .env_typeval_table
+                .borrow_mut()
+                .insert(key, result);
+        } else {
+            self.ctx.sb.typeval_table.borrow_mut().insert(id, result);
+        }
         result
     }
 
@@ -171,6 +198,21 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             (e, a) if e == a => return true,
             // (e,a) if a.is_subtype_of(e) => return true,
             (&Ty::Int(..), &Ty::UniversalInt) => return true,
+            (&Ty::Float(..), &Ty::UniversalReal) => return true,
+            // Integer subtypes are not distinguished from their base type, so
+            // integers of different ranges are compatible.
+            (&Ty::Int(..), &Ty::Int(..)) => return true,
+            (&Ty::Physical(ref e), &Ty::Physical(ref a)) if e.decl == a.decl => return true,
+            (&Ty::Enum(ref e), &Ty::Enum(ref a)) if e.decl == a.decl => return true,
+            // Arrays of the same element type only differ in their index
+            // constraints, which are checked when the array is indexed.
+            (&Ty::Array(ref e), &Ty::Array(ref a))
+                if e.indices.len() == a.indices.len()
+                    && self.ctx.deref_named_type(&e.element).ok()
+                        == self.ctx.deref_named_type(&a.element).ok() =>
+            {
+                return true
+            }
             _ => (),
         }
         self.emit(
@@ -193,8 +235,10 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     }
 
     /// Type check the time expression in a delay mechanism.
-    pub fn typeck_delay_mechanism(&self, _node: &'ctx hir::DelayMechanism) {
-        // TODO: implement this
+    pub fn typeck_delay_mechanism(&self, node: &'ctx hir::DelayMechanism) {
+        if let hir::DelayMechanism::RejectInertial(expr) = *node {
+            self.typeck_time_expr(expr);
+        }
     }
 
     /// Type check a waveform.
@@ -212,9 +256,35 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             // let ty = self.lazy_typeval(value);
             // self.must_match(exp, ty, node.span);
         }
-        if let Some(_after) = node.after {
-            // TODO: type check time expression
-            // self.typeck_node(after, /* time type */);
+        if let Some(after) = node.after {
+            self.typeck_time_expr(after);
+        }
+    }
+
+    /// Type check an expression that must evaluate to a `TIME`.
+    pub fn typeck_time_expr(&self, id: ExprRef) {
+        if let (Ok(ty), Ok(hir)) = (self.lazy_typeval(id), self.ctx.lazy_hir(id)) {
+            self.must_match(self.ctx.builtin_time_type(), ty, hir.span);
+        }
+    }
+
+    /// Type check the condition, message, and severity level of an assertion
+    /// or report statement.
+    pub fn typeck_report(
+        &self,
+        cond: Option<ExprRef>,
+        report: Option<ExprRef>,
+        severity: Option<ExprRef>,
+    ) {
+        let exprs = cond
+            .map(|id| (id, self.ctx.builtin_boolean_type()))
+            .into_iter()
+            .chain(report.map(|id| (id, self.ctx.builtin_string_type())))
+            .chain(severity.map(|id| (id, self.ctx.builtin_severity_type())));
+        for (id, ty) in exprs {
+            if let (Ok(actual), Ok(hir)) = (self.lazy_typeval(id), self.ctx.lazy_hir(id)) {
+                self.must_match(ty, actual, hir.span);
+            }
         }
     }
 
@@ -262,11 +332,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Apply a range constraint to a type.
     pub fn apply_range_constraint(&self, ty: &Ty, con: Spanned<&hir::Range>) -> Result<&'ctx Ty> {
         // Determine the applied range.
-        let (dir, lb, rb) = match *con.value {
-            hir::Range::Immediate(dir, lb, rb) => (dir, lb, rb),
-        };
-        let lb = self.ctx.const_value(lb)?;
-        let rb = self.ctx.const_value(rb)?;
+        let (dir, lb, rb) = self.ctx.const_range(con.value, con.span)?;
 
         // Determine the inner type to which the constraint shall be applied.
         let ty = self.ctx.deref_named_type(ty)?;
@@ -305,6 +371,99 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                     .intern_ty(IntTy::new(ty.dir, lb.value.clone(), rb.value.clone()).maybe_null()))
             }
 
+            Ty::Physical(ref ty) => {
+                // Make sure we have a range of the same physical type.
+                let (lb, rb) = match (lb, rb) {
+                    (&Const::Physical(ref lb), &Const::Physical(ref rb))
+                        if lb.ty.decl == ty.decl && rb.ty.decl == ty.decl =>
+                    {
+                        (lb, rb)
+                    }
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "range `{} {} {}` cannot constrain physical type `{}`",
+                                lb, dir, rb, ty
+                            ))
+                            .span(con.span),
+                        );
+                        return Err(());
+                    }
+                };
+
+                // Make sure that this is actually a subtype.
+                let base = &ty.base;
+                if base.dir != dir || base.left_bound > lb.value || base.right_bound < rb.value {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{} {} {}` is not a subrange of `{}`",
+                            lb, dir, rb, ty
+                        ))
+                        .span(con.span),
+                    );
+                    return Err(());
+                }
+
+                // Create the new type.
+                Ok(self.ctx.intern_ty(PhysicalTy::new(
+                    ty.decl,
+                    IntTy::new(dir, lb.value.clone(), rb.value.clone()),
+                    ty.units.clone(),
+                    ty.primary,
+                )))
+            }
+
+            Ty::Enum(ref ty) => {
+                // Make sure we have a range of literals of the same enum.
+                let (lb, rb) = match (lb, rb) {
+                    (&Const::Enum(ref lb), &Const::Enum(ref rb))
+                        if lb.decl == ty.decl && rb.decl == ty.decl =>
+                    {
+                        (lb, rb)
+                    }
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "range `{} {} {}` cannot constrain enumeration type `{}`",
+                                lb, dir, rb, ty
+                            ))
+                            .span(con.span),
+                        );
+                        return Err(());
+                    }
+                };
+
+                // Make sure that this is actually a subtype.
+                let (base_dir, left, right) = match ty.range {
+                    Some(r) => r,
+                    None => (hir::Dir::To, 0, self.ctx.enum_len(ty.decl)? - 1),
+                };
+                let (lo, hi) = match base_dir {
+                    hir::Dir::To => (left, right),
+                    hir::Dir::Downto => (right, left),
+                };
+                if base_dir != dir
+                    || lb.index < lo
+                    || lb.index > hi
+                    || rb.index < lo
+                    || rb.index > hi
+                {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{} {} {}` is not a subrange of `{}`",
+                            lb, dir, rb, ty
+                        ))
+                        .span(con.span),
+                    );
+                    return Err(());
+                }
+
+                // Create the new type.
+                Ok(self
+                    .ctx
+                    .intern_ty(EnumTy::with_range(ty.decl, dir, lb.index, rb.index)))
+            }
+
             // All other types we simply cannot constrain by range.
             _ => {
                 self.emit(
@@ -326,6 +485,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         con: Spanned<&hir::ArrayConstraint>,
     ) -> Result<&'ctx Ty> {
         // Determine the inner type to which the constraint shall be applied.
+        let base = self.ctx.base_type(ty)?;
         let ty = self.ctx.deref_named_type(ty)?;
         match *ty {
             Ty::Array(ref ty) => {
@@ -370,7 +530,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                 };
                 Ok(self
                     .ctx
-                    .intern_ty(ArrayTy::new(indices, Box::new(element.clone()))))
+                    .intern_ty(ArrayTy::new(indices, Box::new(element.clone())).with_base(base)))
             }
             _ => {
                 self.emit(
@@ -466,11 +626,35 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             con.span,
         );
 
-        // Impose the type as a subtype on the index.
-        let index_ty = match *index {
-            ArrayIndex::Unbounded(ref ty) | ArrayIndex::Constrained(ref ty) => {
-                self.apply_subtype(&*ty, con_ty)?
+        // The constraint replaces the range of the index, but its bounds must
+        // lie within the index subtype unless it is a null range. See IEEE
+        // 1076-2008 section 5.3.2.2.
+        let ty = match *index {
+            ArrayIndex::Unbounded(ref ty) | ArrayIndex::Constrained(ref ty) => &**ty,
+        };
+        let within = match (self.ctx.deref_named_type(ty)?, con_ty.value) {
+            (&Ty::Int(ref ty), &Ty::Int(ref con)) => {
+                let (lo, hi) = ty.bounds();
+                let (con_lo, con_hi) = con.bounds();
+                Some(lo <= con_lo && con_hi <= hi)
+            }
+            (_, &Ty::Null) => Some(true),
+            (&Ty::Enum(ref ty), &Ty::Enum(ref con)) if ty.decl == con.decl => Some(true),
+            _ => None,
+        };
+        let index_ty = match within {
+            Some(true) => con_ty.value,
+            Some(false) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a subrange of `{}`",
+                        con_ty.value, ty
+                    ))
+                    .span(con.span),
+                );
+                return Err(());
             }
+            None => self.apply_subtype(ty, con_ty)?,
         };
 
         Ok(ArrayIndex::Constrained(Box::new(index_ty.clone())))
@@ -555,27 +739,43 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Determine the type of the values in a discrete range without evaluating
+    /// its bounds.
+    ///
+    /// Bounds of a universal integer type are converted to `INTEGER`. See IEEE
+    /// 1076-2008 section 10.10.
+    pub fn type_of_discrete_range(&self, range: Spanned<&hir::DiscreteRange>) -> Result<&'ctx Ty> {
+        let (lb, rb) = match *range.value {
+            hir::DiscreteRange::Range(hir::Range::Immediate(_, lb, rb)) => (lb, rb),
+            _ => return self.type_from_discrete_range(range),
+        };
+        for &bound in &[lb, rb] {
+            let ty = self.lazy_typeval(bound)?;
+            if *self.ctx.deref_named_type(ty)? != Ty::UniversalInt {
+                return Ok(ty);
+            }
+        }
+        Ok(self.ctx.intern_ty(INTEGER_TYPE.named_ty()))
+    }
+
     /// Evaluate a range as a type.
     pub fn type_from_range(&self, range: Spanned<&hir::Range>) -> Result<&'ctx Ty> {
-        match *range.value {
-            hir::Range::Immediate(dir, lb, rb) => {
-                let lb = self.ctx.const_value(lb)?;
-                let rb = self.ctx.const_value(rb)?;
-                match (lb, rb) {
-                    (&Const::Int(ref lb), &Const::Int(ref rb)) => Ok(self
-                        .ctx
-                        .intern_ty(IntTy::new(dir, lb.value.clone(), rb.value.clone()))),
-                    _ => {
-                        self.emit(
-                            DiagBuilder2::error(format!(
-                                "`{} {} {}` is not a valid range",
-                                lb, dir, rb
-                            ))
-                            .span(range.span),
-                        );
-                        return Err(());
-                    }
-                }
+        let (dir, lb, rb) = self.ctx.const_range(range.value, range.span)?;
+        match (lb, rb) {
+            (&Const::Int(ref lb), &Const::Int(ref rb)) => {
+                Ok(self
+                    .ctx
+                    .intern_ty(IntTy::new(dir, lb.value.clone(), rb.value.clone())))
+            }
+            (&Const::Enum(ref lb), &Const::Enum(ref rb)) if lb.decl == rb.decl => Ok(self
+                .ctx
+                .intern_ty(EnumTy::with_range(lb.decl, dir, lb.index, rb.index))),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{} {} {}` is not a valid range", lb, dir, rb))
+                        .span(range.span),
+                );
+                return Err(());
             }
         }
     }
@@ -709,18 +909,59 @@ impl_typeck_err!(self, id: PkgBodyRef => {
     Ok(())
 });
 
+impl_typeck_err!(self, id: ProtectedBodyRef => {
+    let hir = self.ctx.hir(id)?;
+    self.typeck_slice(&hir.decls);
+    Ok(())
+});
+
 impl_typeck_err!(self, id: PkgInstRef => {
     let _hir = self.ctx.hir(id)?;
     // self.typeck_slice(&hir.generic_map);
     Ok(())
 });
 
-impl_typeck!(self, id: CtxRef => {
-    unimp!(self, id)
+// The use clauses in a context declaration are resolved wherever the context is
+// referenced, which is why they cannot refer to the library `work`. See IEEE
+// 1076-2008 section 13.3.
+impl_typeck_err!(self, id: CtxRef => {
+    let (lib, _, decl) = self.ctx.ast(id);
+    let work = get_name_table().intern("WORK", false);
+    for item in &decl.items {
+        let spans: Vec<_> = match *item {
+            ast::CtxItem::LibClause(ref idents) => idents
+                .value
+                .iter()
+                .filter(|ident| ident.name == work)
+                .map(|ident| ident.span)
+                .collect(),
+            ast::CtxItem::UseClause(ref names) | ast::CtxItem::CtxRef(ref names) => names
+                .value
+                .iter()
+                .filter(|name| name.primary.kind == ast::PrimaryNameKind::Ident(work))
+                .map(|name| name.primary.span)
+                .collect(),
+        };
+        for span in spans {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "context `{}` cannot refer to library `work`",
+                    decl.name.value
+                ))
+                .span(span)
+                .add_note("Name the library that contains the design units instead."),
+            );
+        }
+    }
+    self.ctx.expand_ctx_items(lib.into(), &decl.items)?;
+    Ok(())
 });
 
-impl_typeck!(self, id: CfgRef => {
-    unimp!(self, id)
+// Lowering resolves the names in the configuration. Whether the bound entities
+// fit the instances is only known once the configuration is elaborated.
+impl_typeck_err!(self, id: CfgRef => {
+    self.ctx.hir(id)?;
+    Ok(())
 });
 
 impl_typeck_err!(self, id: EntityRef => {
@@ -728,6 +969,11 @@ impl_typeck_err!(self, id: EntityRef => {
     for &generic in &hir.generics {
         self.typeck(generic);
     }
+    // Ports may depend on generics without a default value, in which case
+    // they are checked once the entity is instantiated.
+    if !self.ctx.generics_bound(id)? {
+        return Ok(());
+    }
     for &port in &hir.ports {
         self.typeck(port);
     }
@@ -737,6 +983,9 @@ impl_typeck_err!(self, id: EntityRef => {
 impl_typeck_err!(self, id: ArchRef => {
     let hir = self.ctx.hir(id)?;
     self.typeck(hir.entity);
+    if !self.ctx.generics_bound(hir.entity)? {
+        return Ok(());
+    }
     for &decl in &hir.decls {
         self.typeck(decl);
     }
@@ -775,8 +1024,17 @@ impl_typeck!(self, id: IntfPkgRef => {
 });
 
 impl_make!(self, id: IntfConstRef => &Ty {
-    // TODO: Implement this.
-    unimp_err!(self, id)
+    let hir = self.hir(id)?;
+    let ty = self.ty(hir.ty)?;
+    if let Some(init) = hir.init {
+        let tyc = TypeckContext::new(self);
+        let init_ty = tyc.lazy_typeval(init)?;
+        tyc.must_match(ty, init_ty, self.lazy_hir(init)?.span);
+        if !tyc.finish() {
+            return Err(());
+        }
+    }
+    Ok(ty)
 });
 
 impl_make!(self, id: IntfVarRef => &Ty {
@@ -825,6 +1083,7 @@ impl_typeck!(self, id: DeclInPkgBodyRef => {
         DeclInPkgBodyRef::PkgBody(id)     => self.typeck(id),
         DeclInPkgBodyRef::PkgInst(id)     => self.typeck(id),
         DeclInPkgBodyRef::Type(id)        => self.typeck(id),
+        DeclInPkgBodyRef::ProtectedBody(id) => self.typeck(id),
         DeclInPkgBodyRef::Subtype(id)     => self.typeck(id),
         DeclInPkgBodyRef::Const(id)       => self.typeck(id),
         DeclInPkgBodyRef::Var(id)         => self.typeck(id),
@@ -846,6 +1105,7 @@ impl_typeck!(self, id: DeclInSubprogRef => {
         DeclInSubprogRef::PkgBody(id)     => self.typeck(id),
         DeclInSubprogRef::PkgInst(id)     => self.typeck(id),
         DeclInSubprogRef::Type(id)        => self.typeck(id),
+        DeclInSubprogRef::ProtectedBody(id) => self.typeck(id),
         DeclInSubprogRef::Subtype(id)     => self.typeck(id),
         DeclInSubprogRef::Const(id)       => self.typeck(id),
         DeclInSubprogRef::Var(id)         => self.typeck(id),
@@ -867,6 +1127,7 @@ impl_typeck!(self, id: DeclInBlockRef => {
         DeclInBlockRef::PkgBody(id)     => self.typeck(id),
         DeclInBlockRef::PkgInst(id)     => self.typeck(id),
         DeclInBlockRef::Type(id)        => self.typeck(id),
+        DeclInBlockRef::ProtectedBody(id) => self.typeck(id),
         DeclInBlockRef::Subtype(id)     => self.typeck(id),
         DeclInBlockRef::Const(id)       => self.typeck(id),
         DeclInBlockRef::Signal(id)      => self.typeck(id),
@@ -892,6 +1153,7 @@ impl_typeck!(self, id: DeclInProcRef => {
         DeclInProcRef::PkgBody(id)     => self.typeck(id),
         DeclInProcRef::PkgInst(id)     => self.typeck(id),
         DeclInProcRef::Type(id)        => self.typeck(id),
+        DeclInProcRef::ProtectedBody(id) => self.typeck(id),
         DeclInProcRef::Subtype(id)     => self.typeck(id),
         DeclInProcRef::Const(id)       => self.typeck(id),
         DeclInProcRef::Var(id)         => self.typeck(id),
@@ -915,6 +1177,7 @@ impl_typeck!(self, id: ConcStmtRef => {
         ConcStmtRef::ForGen(id)        => self.typeck(id),
         ConcStmtRef::IfGen(id)         => self.typeck(id),
         ConcStmtRef::CaseGen(id)       => self.typeck(id),
+        ConcStmtRef::Psl(id)           => self.typeck(id),
     }
 });
 
@@ -923,7 +1186,7 @@ impl_typeck!(self, id: SeqStmtRef => {
         SeqStmtRef::Wait(id)      => self.lazy_typeck(id),
         SeqStmtRef::Assert(id)    => self.lazy_typeck(id),
         SeqStmtRef::Report(id)    => self.lazy_typeck(id),
-        SeqStmtRef::SigAssign(id) => self.lazy_typeck(id),
+        SeqStmtRef::SigAssign(id) => self.typeck(id),
         SeqStmtRef::VarAssign(id) => self.lazy_typeck(id),
         SeqStmtRef::ProcCall(id)  => self.lazy_typeck(id),
         SeqStmtRef::If(id)        => self.lazy_typeck(id),
@@ -979,9 +1242,9 @@ impl_typeck!(self, id: AliasDeclRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: CompDeclRef => {
-    unimp!(self, id)
-});
+// Components are checked through their instances, whose actuals are
+// associated with the entity the component is bound to.
+impl_typeck!(self, _id: CompDeclRef => {});
 
 impl_typeck!(self, id: AttrDeclRef => {
     unimp!(self, id)
@@ -991,9 +1254,9 @@ impl_typeck!(self, id: AttrSpecRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: CfgSpecRef => {
-    unimp!(self, id)
-});
+// Configuration specifications are applied as the instances they bind are
+// lowered to HIR.
+impl_typeck!(self, _id: CfgSpecRef => {});
 
 impl_typeck!(self, id: DisconSpecRef => {
     unimp!(self, id)
@@ -1026,28 +1289,108 @@ impl_typeck!(self, id: ConcCallStmtRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: ConcAssertStmtRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: ConcAssertStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    for &stmt in &hir.stmts {
+        self.typeck(stmt);
+    }
+    Ok(())
+});
+
+// The conditions of a PSL directive, including its clock, are booleans.
+impl_typeck_err!(self, id: PslDirectiveRef => {
+    let hir = self.ctx.hir(id)?;
+    let mut conds = Vec::new();
+    hir.prop.conds(&mut conds);
+    conds.extend(hir.clock);
+    for cond in conds {
+        self.typeck_report(Some(cond), None, None);
+    }
+    self.typeck_report(None, hir.report, hir.severity);
+    Ok(())
 });
 
 impl_typeck!(self, id: ConcSigAssignStmtRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: CompInstStmtRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: CompInstStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    for &(generic, actual) in &hir.generic_map {
+        let ty = self.ctx.ty(generic)?;
+        let actual_ty = self.lazy_typeval(actual)?;
+        self.must_match(ty, actual_ty, self.ctx.lazy_hir(actual)?.span);
+    }
+
+    // The ports are checked in the generic environment of the instance, since
+    // their types may depend on the generics.
+    let env = self.ctx.inst_generic_env(id)?;
+    for &(port, actual) in &hir.port_map {
+        let actual_hir = self.ctx.lazy_hir(actual)?;
+        if let hir::ExprData::SignalName(_) = actual_hir.data {
+        } else {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` cannot be associated with port `{}`",
+                    actual_hir.span.extract(),
+                    self.ctx.hir(port)?.name.value
+                ))
+                .span(actual_hir.span)
+                .add_note("Only signals can be associated with ports."),
+            );
+            continue;
+        }
+        let ty = self.ctx.with_generic_env(env, || self.ctx.ty(port))?;
+        let actual_ty = self.lazy_typeval(actual)?;
+        self.must_match(ty, actual_ty, actual_hir.span);
+    }
+    self.ctx.with_generic_env(env, || self.typeck(hir.entity.value));
+    Ok(())
 });
 
-impl_typeck!(self, id: ForGenStmtRef => {
-    unimp!(self, id)
+// The generated statements are checked once for every value of the parameter,
+// since their types may depend on it.
+impl_typeck_err!(self, id: ForGenStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    self.ctx.ty(ConstRef::Gen(id))?;
+    for value in self.ctx.gen_param_values(id)? {
+        self.ctx.with_local_value(id.into(), value, || self.typeck(hir.body));
+    }
+    Ok(())
 });
 
-impl_typeck!(self, id: IfGenStmtRef => {
-    unimp!(self, id)
+// Only the branch that is elaborated is checked, since the others may not be
+// valid for the current generics.
+impl_typeck_err!(self, id: IfGenStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    for &(cond, _) in &hir.branches {
+        let ty = self.lazy_typeval(cond)?;
+        self.must_match(self.ctx.builtin_boolean_type(), ty, self.ctx.lazy_hir(cond)?.span);
+    }
+    if let Some(body) = self.ctx.if_gen_body(id)? {
+        self.typeck(body);
+    }
+    Ok(())
 });
 
-impl_typeck!(self, id: CaseGenStmtRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: CaseGenStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    self.lazy_typeval(hir.switch)?;
+    if let Some(body) = self.ctx.case_gen_body(id)? {
+        self.typeck(body);
+    }
+    Ok(())
+});
+
+impl_typeck_err!(self, id: GenBodyRef => {
+    let hir = self.ctx.hir(id)?;
+    for &decl in &hir.decls {
+        self.typeck(decl);
+    }
+    for &stmt in &hir.stmts {
+        self.typeck(stmt);
+    }
+    Ok(())
 });
 
 impl_typeck_err!(self, id: SigAssignStmtRef => {
@@ -1103,6 +1446,39 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             other => Ok(other),
         }
     }
+
+    /// Determine the type of a subprogram declaration.
+    pub fn subprog_ty(&self, id: SubprogDeclRef) -> Result<&'ctx Ty> {
+        let hir = self.hir(id)?;
+        let mut args = Vec::new();
+        for &param in &hir.spec.params {
+            let name = match param {
+                IntfObjRef::Const(id) => self.hir(id)?.name.value,
+                _ => unreachable!(),
+            };
+            args.push(SubprogTyArg::named(self.ty(param)?.clone(), name));
+        }
+        let ret = match hir.spec.return_type {
+            Some(tm) => Some(self.ty(tm.value)?.clone()),
+            None => None,
+        };
+        Ok(self.intern_ty(SubprogTy::new(args, ret)))
+    }
+
+    /// Determine the type mark that names the base type of a type.
+    ///
+    /// Constrained arrays report the array type they were constrained from.
+    /// Returns `None` for anonymous types.
+    pub fn base_type(&self, ty: &Ty) -> Result<Option<TypeMarkRef>> {
+        Ok(match *ty {
+            Ty::Named(_, tmr) => match *self.deref_named_type(ty)? {
+                Ty::Array(ref at) if at.base.is_some() => at.base,
+                _ => Some(tmr),
+            },
+            Ty::Array(ref at) => at.base,
+            _ => None,
+        })
+    }
 }
 
 /// Determine the type of a type mark.
@@ -1210,6 +1586,10 @@ impl_make!(self, id: TypeDeclRef => &Ty {
             Ok(self.intern_ty(ArrayTy::new(indices, Box::new(elem_ty))))
         }
 
+        hir::TypeData::Protected(_) => {
+            Ok(self.intern_ty(Ty::Protected(id)))
+        }
+
         hir::TypeData::File(tm) => {
             let inner = self.ty(tm.value)?.clone();
             Ok(self.intern_ty(Ty::File(Box::new(inner))))
@@ -1263,9 +1643,14 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 self.intern_ty(IntTy::new(dir, lb.value.clone(), rb.value.clone()).maybe_null())
             }
 
-            (&Const::Float(ref _lb), &Const::Float(ref _rb)) => {
-                self.emit(DiagBuilder2::error("Float range bounds not yet supported").span(span));
-                return Err(());
+            (&Const::Float(ref lb), &Const::Float(ref rb)) => {
+                debugln!("type from range `{}`", span.extract());
+                self.intern_ty(FloatTy::new(dir, lb.value, rb.value).maybe_null())
+            }
+
+            (&Const::Enum(ref lb), &Const::Enum(ref rb)) if lb.decl == rb.decl => {
+                debugln!("type from range `{}`", span.extract());
+                self.intern_ty(EnumTy::with_range(lb.decl, dir, lb.index, rb.index))
             }
 
             _ => {
@@ -1343,6 +1728,41 @@ impl_make!(self, id: TypedNodeRef => &Ty {
     }
 });
 
+impl_make!(self, id: ConstRef => &Ty {
+    match id {
+        ConstRef::Intf(id) => self.make(id),
+        ConstRef::Decl(id) => self.lazy_typeval(id),
+        ConstRef::Loop(id) => self.make(id),
+        // The parameter of a for generate statement.
+        ConstRef::Gen(id) => {
+            let tyc = TypeckContext::new(self);
+            let ty = tyc.type_of_discrete_range(self.hir(id)?.range.as_ref());
+            if !tyc.finish() {
+                return Err(());
+            }
+            ty
+        }
+    }
+});
+
+/// Determine the type of the parameter of a for loop.
+///
+/// The range of the loop may depend on values only known once the loop
+/// executes, so the type is determined without evaluating its bounds.
+impl_make!(self, id: LoopStmtRef => &Ty {
+    let hir = self.lazy_hir(id)?;
+    let range = match hir.stmt.scheme {
+        hir::LoopScheme::For(_, ref range) => range,
+        _ => unreachable!(),
+    };
+    let tyc = TypeckContext::new(self);
+    let ty = tyc.type_of_discrete_range(range.as_ref());
+    if !tyc.finish() {
+        return Err(());
+    }
+    ty
+});
+
 impl_make!(self, id: SignalRef => &Ty {
     match id {
         SignalRef::Intf(id) => self.make(id),
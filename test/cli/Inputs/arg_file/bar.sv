@@ -0,0 +1,7 @@
+// IGNORE  only read through the argument files of `arg_file.sv`
+`include "bar_defs.svh"
+
+module bar;
+    logic [`WIDTH-1:0] x;
+    logic [`NARROW_WIDTH-1:0] y;
+endmodule
@@ -0,0 +1,4 @@
+// Arguments for the `arg_file.sv` test.
++incdir+include
++define+WIDTH=8+NARROW
+-F nested.f  # paths in here are relative to this directory
@@ -0,0 +1,26 @@
+module adder (
+input logic [7:0] a,
+  input logic [7:0] b,
+	output logic [8:0] sum
+);
+// Add the operands.
+assign sum = a + b;   
+endmodule
+
+module top;
+logic [7:0] x, y;
+logic [8:0] z;
+adder i_adder (
+.a(x),
+.b(y),
+.sum(z)
+);
+  always_comb begin
+if (z[8])
+x = 0;
+else begin
+x = 1;
+y   =   2;
+end
+  end
+endmodule
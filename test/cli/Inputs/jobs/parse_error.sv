@@ -0,0 +1,5 @@
+// FAIL
+
+module bar (input logic a, output logic z);
+  assign z = a +;
+endmodule
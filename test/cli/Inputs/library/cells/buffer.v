@@ -0,0 +1,5 @@
+module buffer (input logic a, output logic z);
+    logic n;
+    inverter i0 (a, n);
+    inverter i1 (n, z);
+endmodule
@@ -0,0 +1,3 @@
+module inverter (input logic a, output logic z);
+    assign z = ~a;
+endmodule
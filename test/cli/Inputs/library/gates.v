@@ -0,0 +1,6 @@
+module nand2 (input logic a, input logic b, output logic z);
+    assign z = ~(a & b);
+endmodule
+
+module unused;
+endmodule
@@ -0,0 +1,24 @@
+-- Instantiates the SystemVerilog module of `svlog_module_inst.sv`.
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+	port (
+		a, b : in STD_ULOGIC_VECTOR(7 downto 0);
+		sum : out STD_ULOGIC_VECTOR(7 downto 0);
+		carry : out STD_ULOGIC
+	);
+end;
+
+architecture rtl of top is
+	component adder is
+		generic (WIDTH : INTEGER := 8);
+		port (
+			a, b : in STD_ULOGIC_VECTOR(7 downto 0);
+			sum : out STD_ULOGIC_VECTOR(7 downto 0);
+			carry : out STD_ULOGIC
+		);
+	end component;
+begin
+	i_adder : adder port map (a => a, b => b, sum => sum, carry => carry);
+end;
@@ -0,0 +1,15 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity counter is
+	generic (WIDTH : NATURAL := 8; INIT : STD_LOGIC := '0');
+	port (
+		clk : in STD_LOGIC;
+		en : in BIT;
+		count : out STD_LOGIC_VECTOR(WIDTH-1 downto 0)
+	);
+end;
+
+architecture rtl of counter is
+begin
+end;
@@ -0,0 +1,11 @@
+// RUN: moore %s -F Inputs/arg_file/sources.f -e foo
+
+module foo;
+    bar u_bar ();
+endmodule
+
+// CHECK: entity @bar.param1 () -> () {
+// CHECK:     %x = sig i8 %0
+// CHECK:     %y = sig i4 %1
+// CHECK: entity @foo () -> () {
+// CHECK:     inst @bar.param1 () -> ()
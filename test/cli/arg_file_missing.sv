@@ -0,0 +1,8 @@
+// RUN: moore %s -F test/cli/Inputs/arg_file/missing.f -e foo
+// FAIL
+
+module foo;
+endmodule
+
+// CHECK-ERR: fatal: unable to open argument file `test/cli/Inputs/arg_file/missing.f`
+// CHECK-ERR: = note: No such file or directory (os error 2)
@@ -0,0 +1,7 @@
+// RUN: moore %s -F test/cli/Inputs/arg_file/recursive.f -e foo
+// FAIL
+
+module foo;
+endmodule
+
+// CHECK-ERR: fatal: argument file `test/cli/Inputs/arg_file/recursive.f` includes itself
@@ -0,0 +1,13 @@
+// RUN: moore %s Inputs/mixed/counter.vhd -e top --boundary-report note
+
+module top (input logic clk, output logic [7:0] count);
+    counter i_counter (.clk(clk), .en(1'b1), .count(count));
+endmodule
+
+// CHECK-ERR: note: VHDL unit `counter` instantiated from SystemVerilog
+// CHECK-ERR: = note: parameter `WIDTH`: `NATURAL` -> `bit [30:0]`
+// CHECK-ERR: = note: parameter `INIT`: `STD_LOGIC` -> `logic`
+// CHECK-ERR: = note: input port `clk`: `STD_LOGIC` -> `logic`
+// CHECK-ERR: = note: input port `clk`: the nine values of `STD_ULOGIC` map to four-valued logic, with `L` and `H` read as `0` and `1`, and `U`, `W`, and `-` as `X`
+// CHECK-ERR: = note: input port `en`: `BIT` -> `bit`
+// CHECK-ERR: = note: output port `count`: `STD_LOGIC_VECTOR(WIDTH-1 downto 0)` -> `logic [7:0]`
@@ -0,0 +1,39 @@
+// RUN: moore %s --call-graph dot
+
+package util;
+    import "DPI-C" function int c_rand();
+
+    function automatic int fact(int n);
+        if (n <= 1) return 1;
+        return n * fact(n - 1);
+    endfunction
+
+    function int unused_fn(int x);
+        return x;
+    endfunction
+endpackage
+
+module top;
+    int x;
+
+    task reset;
+        x = 0;
+    endtask
+
+    initial begin
+        reset;
+        x = util::fact(util::c_rand());
+    end
+endmodule
+
+// CHECK: digraph calls {
+// CHECK-NEXT:   n0 [label="util::c_rand\nDPI function", shape=hexagon];
+// CHECK-NEXT:   n1 [label="util::fact\nfunction, automatic", color=red];
+// CHECK-NEXT:   n2 [label="util::unused_fn\nfunction, static", style=dashed];
+// CHECK-NEXT:   n3 [label="top.reset\ntask, static"];
+// CHECK-NEXT:   n4 [label="top", shape=box];
+// CHECK-NEXT:   n1 -> n1;
+// CHECK-NEXT:   n4 -> n3;
+// CHECK-NEXT:   n4 -> n1;
+// CHECK-NEXT:   n4 -> n0;
+// CHECK-NEXT: }
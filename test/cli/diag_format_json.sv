@@ -0,0 +1,9 @@
+// RUN: moore test/cli/diag_format_json.sv -e foo --diag-format json
+// FAIL
+
+module foo;
+    logic [3:0] x;
+    assign x = y;
+endmodule
+
+// CHECK-ERR: {"code":"SV0001","group":null,"message":"`y` not found","notes":[],"severity":"error","spans":[{"column":16,"end_column":17,"end_line":6,"file":"test/cli/diag_format_json.sv","line":6,"primary":true}],"suggestions":[]}
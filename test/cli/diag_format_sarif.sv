@@ -0,0 +1,19 @@
+// RUN: moore test/cli/diag_format_sarif.sv -e foo --diag-format sarif
+// FAIL
+
+module foo;
+    logic [3:0] x;
+    assign x = y;
+endmodule
+
+// CHECK-ERR: "results": [
+// CHECK-ERR: "level": "error",
+// CHECK-ERR: "uri": "test/cli/diag_format_sarif.sv"
+// CHECK-ERR: "endColumn": 17,
+// CHECK-ERR: "endLine": 6,
+// CHECK-ERR: "startColumn": 16,
+// CHECK-ERR: "startLine": 6
+// CHECK-ERR: "text": "`y` not found"
+// CHECK-ERR: "ruleId": "SV0001"
+// CHECK-ERR: "name": "moore",
+// CHECK-ERR: "version": "2.1.0"
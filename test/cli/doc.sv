@@ -0,0 +1,48 @@
+// RUN: moore doc %s
+
+/// Constants shared by the design.
+package consts;
+    /// The width of a data word.
+    localparam int WIDTH = 8;
+endpackage
+
+/// A register with a synchronous enable.
+///
+/// Stores `d` on every rising edge of `clk` while `en` is set.
+module register #(
+    /// The number of bits stored.
+    parameter int N = consts::WIDTH,
+    parameter logic [N-1:0] INIT = '0
+) (
+    /// The clock.
+    input logic clk,
+    /// Whether to store `d`.
+    input logic en,
+    input logic [N-1:0] d,
+    /** The stored value. */
+    output logic [N-1:0] q
+);
+    localparam int M = N;
+endmodule
+
+// CHECK: ## Package `consts`
+// CHECK: Constants shared by the design.
+// CHECK: ### Parameters
+// CHECK: | Name | Type | Default | Description |
+// CHECK-NEXT: |------|------|---------|-------------|
+// CHECK-NEXT: | `WIDTH` | `int` | `8` | The width of a data word. |
+// CHECK: ## Module `register`
+// CHECK: A register with a synchronous enable.
+// CHECK: Stores `d` on every rising edge of `clk` while `en` is set.
+// CHECK: ### Parameters
+// CHECK: | Name | Type | Default | Description |
+// CHECK-NEXT: |------|------|---------|-------------|
+// CHECK-NEXT: | `N` | `int` | `consts::WIDTH` | The number of bits stored. |
+// CHECK-NEXT: | `INIT` | `logic [N-1:0]` | `'0` |  |
+// CHECK: ### Ports
+// CHECK: | Name | Direction | Type | Description |
+// CHECK-NEXT: |------|-----------|------|-------------|
+// CHECK-NEXT: | `clk` | input | `logic` | The clock. |
+// CHECK-NEXT: | `en` | input | `logic` | Whether to store `d`. |
+// CHECK-NEXT: | `d` | input | `logic [N-1:0]` |  |
+// CHECK-NEXT: | `q` | output | `logic [N-1:0]` | The stored value. |
@@ -0,0 +1,30 @@
+// RUN: moore %s --dump-ast=json --syntax
+
+module foo;
+    assign y = ~a;
+endmodule
+
+// CHECK: [
+// CHECK-NEXT: {
+// CHECK-NEXT: "language": "svlog",
+// CHECK-NEXT: "ast": {
+// CHECK-NEXT: "kind": "SourceFile",
+// CHECK: "kind": "Module",
+// CHECK: "span": {
+// CHECK: "begin": 43,
+// CHECK-NEXT: "end": 73
+// CHECK-NEXT: },
+// CHECK-NEXT: "data": {
+// CHECK-NEXT: "lifetime": "Static",
+// CHECK-NEXT: "name": {
+// CHECK-NEXT: "value": "foo",
+// CHECK: "kind": "ContAssign",
+// CHECK: "IdentExpr": {
+// CHECK-NEXT: "value": "y",
+// CHECK: "UnaryExpr": {
+// CHECK-NEXT: "op": "BitNot",
+// CHECK-NEXT: "expr": {
+// CHECK-NEXT: "kind": "Expr",
+// CHECK: "IdentExpr": {
+// CHECK-NEXT: "value": "a",
+// CHECK: "postfix": false
@@ -0,0 +1,32 @@
+// RUN: moore %s -e foo --dump-hir json
+
+module foo;
+    wire [3:0] x;
+    assign x = ~x;
+endmodule
+
+// CHECK:     "id": 16,
+// CHECK:       "Module": {
+// CHECK:           "kind": "Module",
+// CHECK:             "value": "foo",
+// CHECK:           "decls": [
+// CHECK:             4
+// CHECK:           "assigns": [
+// CHECK:             14
+// CHECK:     "id": 4,
+// CHECK:       "VarDecl": {
+// CHECK:             "value": "x",
+// CHECK:         "ty": 3,
+// CHECK:             "ty": "Wire",
+// CHECK:     "id": 3,
+// CHECK:       "Type": {
+// CHECK:           "PackedArray": [
+// CHECK:             "Implicit",
+// CHECK:     "id": 14,
+// CHECK:       "Assign": {
+// CHECK:         "lhs": 9,
+// CHECK:         "rhs": 13
+// CHECK:     "id": 13,
+// CHECK:           "Unary": [
+// CHECK:             "BitNot",
+// CHECK:             12
@@ -0,0 +1,27 @@
+// RUN: moore %s -e foo --dump-hir text
+
+module foo (input logic clk, input logic [3:0] a, output logic [3:0] q);
+    logic [3:0] r;
+    assign q = r ^ {4{a[0]}};
+    always_ff @(posedge clk) begin
+        if (a[0]) r <= a;
+        else r <= ~r;
+    end
+endmodule
+
+// CHECK: module foo (
+// CHECK:     input logic clk,
+// CHECK:     input logic [3:0] a,
+// CHECK:     output logic [3:0] q
+// CHECK: );
+// CHECK:     var logic [3:0] r;
+// CHECK:     assign q = (r ^ {4{a[0]}});
+// CHECK:     always_ff
+// CHECK:         @(posedge clk)
+// CHECK:             begin
+// CHECK:                 if (a[0])
+// CHECK:                     r <= a;
+// CHECK:                 else
+// CHECK:                     r <= ~r;
+// CHECK:             end
+// CHECK: endmodule
@@ -0,0 +1,15 @@
+// RUN: moore %s --elaborate-only
+// FAIL
+
+module foo (input logic [7:0] a, output logic [7:0] z);
+    bar #(.N(8)) u (a, z);
+endmodule
+
+module bar #(parameter int N) (input logic [N-1:0] a, output logic [N-1:0] z);
+    logic [N-1:0] x;
+    assign x = a + y;
+    assign z = x;
+endmodule
+
+// CHECK-ERR: note: using `foo` as top-level module
+// CHECK-ERR: error[SV0001]: `y` not found
@@ -0,0 +1,58 @@
+// RUN: moore %s -e foo --emit dot-dataflow
+
+module bar #(parameter int W = 4, parameter type T = logic) (input logic clk, input logic [W-1:0] d, output logic [W-1:0] q);
+    localparam int X = 2;
+    logic [W-1:0] n;
+    assign n = q + d;
+    always_ff @(posedge clk) q <= n;
+endmodule
+
+module foo (input logic clk, input logic [3:0] a, input logic s, output logic [3:0] y, output logic [7:0] z);
+    logic [3:0] m;
+    always_comb begin
+        if (s) m = a;
+        else m = ~a;
+    end
+    bar u0 (.clk(clk), .d(m), .q(y));
+    bar #(.W(8)) u1 (.clk(clk), .d({m, a}), .q(z));
+endmodule
+
+// CHECK: digraph "bar.param1" {
+// CHECK:   n0 [label="input clk", shape=box];
+// CHECK:   n1 [label="input d", shape=box];
+// CHECK:   n2 [label="output q", shape=box];
+// CHECK:   n3 [label="n"];
+// CHECK:   n0 -> n2 [style=dashed];
+// CHECK:   n1 -> n3;
+// CHECK:   n2 -> n3;
+// CHECK:   n3 -> n2 [style=dashed];
+// CHECK: }
+// CHECK: digraph "bar.param2" {
+// CHECK:   n0 [label="input clk", shape=box];
+// CHECK:   n1 [label="input d", shape=box];
+// CHECK:   n2 [label="output q", shape=box];
+// CHECK:   n3 [label="n"];
+// CHECK:   n0 -> n2 [style=dashed];
+// CHECK:   n1 -> n3;
+// CHECK:   n2 -> n3;
+// CHECK:   n3 -> n2 [style=dashed];
+// CHECK: }
+// CHECK: digraph "foo" {
+// CHECK:   n0 [label="input clk", shape=box];
+// CHECK:   n1 [label="input a", shape=box];
+// CHECK:   n2 [label="input s", shape=box];
+// CHECK:   n3 [label="output y", shape=box];
+// CHECK:   n4 [label="output z", shape=box];
+// CHECK:   n5 [label="m"];
+// CHECK:   n6 [label="u0\nbar.param1", shape=component];
+// CHECK:   n7 [label="u1\nbar.param2", shape=component];
+// CHECK:   n0 -> n6 [label="clk"];
+// CHECK:   n0 -> n7 [label="clk"];
+// CHECK:   n1 -> n5;
+// CHECK:   n1 -> n7 [label="d"];
+// CHECK:   n2 -> n5;
+// CHECK:   n5 -> n6 [label="d"];
+// CHECK:   n5 -> n7 [label="d"];
+// CHECK:   n6 -> n3 [label="q"];
+// CHECK:   n7 -> n4 [label="q"];
+// CHECK: }
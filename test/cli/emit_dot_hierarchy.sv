@@ -0,0 +1,27 @@
+// RUN: moore %s -e foo --emit dot-hierarchy
+
+module bar #(parameter int W = 4, parameter type T = logic) (input logic clk, input logic [W-1:0] d, output logic [W-1:0] q);
+    localparam int X = 2;
+    logic [W-1:0] n;
+    assign n = q + d;
+    always_ff @(posedge clk) q <= n;
+endmodule
+
+module foo (input logic clk, input logic [3:0] a, input logic s, output logic [3:0] y, output logic [7:0] z);
+    logic [3:0] m;
+    always_comb begin
+        if (s) m = a;
+        else m = ~a;
+    end
+    bar u0 (.clk(clk), .d(m), .q(y));
+    bar #(.W(8)) u1 (.clk(clk), .d({m, a}), .q(z));
+endmodule
+
+// CHECK: digraph hierarchy {
+// CHECK:   node [shape=box];
+// CHECK:   m0 [label="bar.param1\nW = 4\nT = logic"];
+// CHECK:   m1 [label="bar.param2\nW = 8\nT = logic"];
+// CHECK:   m2 [label="foo"];
+// CHECK:   m2 -> m0 [label="u0"];
+// CHECK:   m2 -> m1 [label="u1\nW = 8"];
+// CHECK: }
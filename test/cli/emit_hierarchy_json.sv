@@ -0,0 +1,63 @@
+// RUN: moore %s -e top --emit hierarchy.json
+
+module leaf #(parameter int W = 4, parameter type T = logic) (input logic [W-1:0] d);
+endmodule
+
+module mid (input logic [7:0] d);
+    leaf #(.W(8)) u_leaf (.d(d));
+endmodule
+
+module top (input logic [7:0] a);
+    mid u0 (.d(a));
+    mid u1 (.d(a));
+endmodule
+
+// CHECK: [
+// CHECK-NEXT:   {
+// CHECK-NEXT:     "name": "top",
+// CHECK-NEXT:     "path": "top",
+// CHECK-NEXT:     "module": "top",
+// CHECK-NEXT:     "entity": "top",
+// CHECK-NEXT:     "params": [],
+// CHECK-NEXT:     "location": {
+// CHECK:       "line": 10,
+// CHECK-NEXT:       "column": 8
+// CHECK-NEXT:     },
+// CHECK-NEXT:     "children": [
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "u0",
+// CHECK-NEXT:         "path": "top.u0",
+// CHECK-NEXT:         "module": "mid",
+// CHECK:         "params": [],
+// CHECK-NEXT:         "location": {
+// CHECK:           "line": 11,
+// CHECK-NEXT:           "column": 9
+// CHECK-NEXT:         },
+// CHECK-NEXT:         "children": [
+// CHECK-NEXT:           {
+// CHECK-NEXT:             "name": "u_leaf",
+// CHECK-NEXT:             "path": "top.u0.u_leaf",
+// CHECK-NEXT:             "module": "leaf",
+// CHECK:             "params": [
+// CHECK-NEXT:               {
+// CHECK-NEXT:                 "name": "W",
+// CHECK-NEXT:                 "value": "8"
+// CHECK-NEXT:               },
+// CHECK-NEXT:               {
+// CHECK-NEXT:                 "name": "T",
+// CHECK-NEXT:                 "value": "logic"
+// CHECK-NEXT:               }
+// CHECK-NEXT:             ],
+// CHECK-NEXT:             "location": {
+// CHECK:               "line": 7,
+// CHECK-NEXT:               "column": 19
+// CHECK-NEXT:             },
+// CHECK-NEXT:             "children": []
+// CHECK-NEXT:           }
+// CHECK-NEXT:         ]
+// CHECK-NEXT:       },
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "u1",
+// CHECK-NEXT:         "path": "top.u1",
+// CHECK:             "path": "top.u1.u_leaf",
+// CHECK-NEXT:             "module": "leaf",
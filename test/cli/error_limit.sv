@@ -0,0 +1,22 @@
+// RUN: moore %s --error-limit 2 -e foo -e bar -e baz
+// FAIL
+
+module foo;
+    logic [3:0] x;
+    assign x = a;
+endmodule
+
+module bar;
+    logic [3:0] y;
+    assign y = b;
+endmodule
+
+module baz;
+    logic [3:0] z;
+    assign z = c;
+endmodule
+
+// CHECK-ERR: error[SV0001]: `a` not found
+// CHECK-ERR: error[SV0001]: `b` not found
+// CHECK-ERR: fatal: too many errors emitted, stopping after 2
+// CHECK-ERR: = note: Use `--error-limit` to change the limit, or 0 to disable it
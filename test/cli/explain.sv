@@ -0,0 +1,6 @@
+// RUN: moore --explain SV0001
+
+// CHECK: A name could not be found.
+// CHECK: Example:
+// CHECK: assign x = y;
+// CHECK: Declare the name, check its spelling, or import the package that declares it.
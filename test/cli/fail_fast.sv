@@ -0,0 +1,14 @@
+// RUN: moore %s --fail-fast -e foo -e bar
+// FAIL
+
+module foo;
+    logic [3:0] x;
+    assign x = a;
+endmodule
+
+module bar;
+    logic [3:0] y;
+    assign y = b;
+endmodule
+
+// CHECK-ERR: error[SV0001]: `a` not found
@@ -0,0 +1,26 @@
+// RUN: moore fmt Inputs/fmt/messy.sv --fmt-indent 2
+
+// CHECK: module adder (
+// CHECK-NEXT:   input  logic [7:0] a,
+// CHECK-NEXT:   input  logic [7:0] b,
+// CHECK-NEXT:   output logic [8:0] sum
+// CHECK-NEXT: );
+// CHECK:        assign sum = a + b;
+// CHECK-NEXT: endmodule
+// CHECK:      module top;
+// CHECK-NEXT:   logic [7:0] x, y;
+// CHECK-NEXT:   logic [8:0] z;
+// CHECK-NEXT:   adder i_adder (
+// CHECK-NEXT:     .a  (x),
+// CHECK-NEXT:     .b  (y),
+// CHECK-NEXT:     .sum(z)
+// CHECK-NEXT:   );
+// CHECK-NEXT:   always_comb begin
+// CHECK-NEXT:     if (z[8])
+// CHECK-NEXT:       x = 0;
+// CHECK-NEXT:     else begin
+// CHECK-NEXT:       x = 1;
+// CHECK-NEXT:       y = 2;
+// CHECK-NEXT:     end
+// CHECK-NEXT:   end
+// CHECK-NEXT: endmodule
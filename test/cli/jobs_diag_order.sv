@@ -0,0 +1,12 @@
+// RUN: moore %s Inputs/jobs/parse_error.sv -j 2
+// FAIL
+
+// The diagnostics of the input files are reported in the order of the files,
+// regardless of which thread finishes parsing first.
+
+module foo;
+  logic q
+endmodule
+
+// CHECK-ERR: error[SV0016]: expected , or ; after variable name
+// CHECK-ERR: error[SV0016]: expected expression, found `;` instead
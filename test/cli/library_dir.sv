@@ -0,0 +1,12 @@
+// RUN: moore %s -y Inputs/library/cells -e foo
+
+module foo (input logic a, output logic z);
+    buffer b (a, z);
+endmodule
+
+// CHECK: entity @inverter.param2 (i1$ %a) -> (i1$ %z) {
+// CHECK: entity @buffer.param1 (i1$ %a) -> (i1$ %z) {
+// CHECK:     inst @inverter.param2 (i1$ %a) -> (i1$ %n)
+// CHECK:     inst @inverter.param2 (i1$ %n) -> (i1$ %z)
+// CHECK: entity @foo (i1$ %a) -> (i1$ %z) {
+// CHECK:     inst @buffer.param1 (i1$ %a) -> (i1$ %z)
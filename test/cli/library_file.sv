@@ -0,0 +1,12 @@
+// RUN: moore %s -v Inputs/library/gates.v -y Inputs/library/cells +libext+.vlib -e foo
+
+module foo (input logic a, input logic b, output logic z, output logic q);
+    nand2 g (a, b, z);
+    latch l (a, b, q);
+endmodule
+
+// CHECK: entity @nand2.param1 (i1$ %a, i1$ %b) -> (i1$ %z) {
+// CHECK: entity @latch.param2 (i1$ %d, i1$ %en) -> (i1$ %q) {
+// CHECK: entity @foo (i1$ %a, i1$ %b) -> (i1$ %z, i1$ %q) {
+// CHECK:     inst @nand2.param1 (i1$ %a, i1$ %b) -> (i1$ %z)
+// CHECK:     inst @latch.param2 (i1$ %a, i1$ %b) -> (i1$ %q)
@@ -0,0 +1,13 @@
+// RUN: moore %s --lib mylib -e work.top
+
+// The `work` library refers to the library the design is compiled into.
+module top;
+    leaf u ();
+endmodule
+
+module leaf;
+endmodule
+
+// CHECK: entity @leaf.param1 () -> () {
+// CHECK: entity @top () -> () {
+// CHECK:     inst @leaf.param1 () -> ()
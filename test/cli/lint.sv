@@ -0,0 +1,16 @@
+// RUN: moore lint %s -e foo --lint-option latch.bogus=1
+
+module foo (input logic a, input logic s, output logic x, output logic y);
+    bar #(.N(1)) i0 (a, s, x);
+    bar #(.N(2)) i1 (a, s, y);
+endmodule
+
+module bar #(parameter int N = 0) (input logic a, input logic s, output logic x);
+    always_comb begin
+        if (s) x = a;
+    end
+endmodule
+
+// CHECK-ERR: warning: unknown lint option `latch.bogus`
+// CHECK-ERR: warning: `x` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
+// CHECK-ERR: = note: Occurs in 2 parameterizations: `bar #(N = 1)`, `bar #(N = 2)`
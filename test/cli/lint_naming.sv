@@ -0,0 +1,17 @@
+// RUN: moore lint %s -e foo -Wnaming --lint-config Inputs/lint/naming.cfg --lint-option naming.clock=^clk_
+
+module foo #(parameter int width = 8) (input logic clk, input logic rst_n, input logic [width-1:0] dataIn, output logic [width-1:0] q);
+    always_ff @(posedge clk or negedge rst_n) begin
+        if (!rst_n) q <= 0;
+        else q <= dataIn;
+    end
+    Bar i0 ();
+endmodule
+
+module Bar;
+endmodule
+
+// CHECK-ERR: warning: module `Bar` does not match the naming convention `^[a-z][a-z0-9_]*$` [-Wnaming]
+// CHECK-ERR: warning: parameter `width` does not match the naming convention `^[A-Z][A-Z0-9_]*$` [-Wnaming]
+// CHECK-ERR: warning: clock `clk` does not match the naming convention `^clk_` [-Wnaming]
+// CHECK-ERR: warning: signal `dataIn` does not match the naming convention `^[a-z][a-z0-9_]*$` [-Wnaming]
@@ -0,0 +1,19 @@
+// RUN: moore %s -e foo
+
+module foo (input logic a, input logic s, output logic x, output logic y, output logic z);
+    // moore lint_off latch
+    always_comb begin
+        if (s) x = a;
+    end
+    // moore lint_on latch
+
+    always_comb begin
+        if (s) y = a; // verilator lint_off_line LATCH
+    end
+
+    always_comb begin
+        if (s) z = a;
+    end
+endmodule
+
+// CHECK-ERR: warning: `z` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
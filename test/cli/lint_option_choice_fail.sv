@@ -0,0 +1,7 @@
+// RUN: moore lint %s -e foo --lint-option reset.style=both
+// FAIL
+
+module foo;
+endmodule
+
+// CHECK-ERR: fatal: invalid value `both` for lint option `reset.style`: expected one of `any`, `async`, `sync`
@@ -0,0 +1,7 @@
+// RUN: moore lint %s -e foo --lint-option naming.signal=[a-z
+// FAIL
+
+module foo;
+endmodule
+
+// CHECK-ERR: fatal: invalid value `[a-z` for lint option `naming.signal`: regex parse error:
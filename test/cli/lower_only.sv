@@ -0,0 +1,15 @@
+// RUN: moore %s --lower-only
+// FAIL
+
+module foo;
+    logic [3:0] x;
+    assign x = f(1);
+endmodule
+
+module bar;
+    logic [3:0] y;
+    assign y = g(2);
+endmodule
+
+// CHECK-ERR: error[SV0001]: `f` not found
+// CHECK-ERR: error[SV0001]: `g` not found
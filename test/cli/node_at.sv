@@ -0,0 +1,10 @@
+// RUN: moore %s --node-at test/cli/node_at.sv:233 --node-at test/cli/node_at.sv:234 --node-at test/cli/node_at.sv:230 --node-at test/cli/node_at.sv:153
+
+module foo (input logic [7:0] a, b, c, output logic [7:0] y);
+  assign y = a + b * c;
+endmodule
+
+// CHECK: 233: identifier `b`
+// CHECK: 234: expression `b * c`
+// CHECK: 230: expression `a + b * c`
+// CHECK: 153: no node
@@ -0,0 +1,60 @@
+// RUN: moore %s -e foo --format firrtl
+
+module bar #(parameter int W = 4) (input logic clk, input logic rst_n, input logic [W-1:0] d, output logic [W-1:0] q);
+    always_ff @(posedge clk or negedge rst_n) begin
+        if (!rst_n) q <= 0;
+        else q <= q + d;
+    end
+endmodule
+
+module foo (input logic clk, input logic rst_n, input logic [3:0] a, input logic [1:0] sel, output logic [3:0] y, output logic z);
+    logic [3:0] t;
+    logic [3:0] m;
+    assign t = a ^ 4'b1010;
+    always_comb begin
+        casez (sel)
+            2'b00: m = t;
+            2'b?1: m = a << 1;
+            default: m = '0;
+        endcase
+        z = |m;
+    end
+    bar #(.W(4)) u (.clk(clk), .rst_n(rst_n), .d(m), .q(y));
+endmodule
+
+// CHECK: FIRRTL version 3.3.0
+// CHECK: circuit foo :
+// CHECK:   module bar_param1 :
+// CHECK:     input clk : Clock
+// CHECK:     input rst_n : UInt<1>
+// CHECK:     input d : UInt<4>
+// CHECK:     output q : UInt<4>
+// CHECK:     regreset q_reg : UInt<4>, clk, asAsyncReset(not(rst_n)), UInt<4>(0)
+// CHECK:     connect q_reg, bits(add(q_reg, d), 3, 0)
+// CHECK:     connect q, q_reg
+// CHECK:   module foo :
+// CHECK:     input clk : Clock
+// CHECK:     input rst_n : UInt<1>
+// CHECK:     input a : UInt<4>
+// CHECK:     input sel : UInt<2>
+// CHECK:     output y : UInt<4>
+// CHECK:     output z : UInt<1>
+// CHECK:     invalidate y
+// CHECK:     invalidate z
+// CHECK:     wire t : UInt<4>
+// CHECK:     invalidate t
+// CHECK:     wire m : UInt<4>
+// CHECK:     invalidate m
+// CHECK:     inst u of bar_param1
+// CHECK:     connect t, xor(a, UInt<4>(10))
+// CHECK:     connect u.clk, clk
+// CHECK:     connect u.rst_n, rst_n
+// CHECK:     connect u.d, m
+// CHECK:     connect y, u.q
+// CHECK:     when eq(sel, UInt<2>(0)) :
+// CHECK:       connect m, t
+// CHECK:     else when eq(and(sel, UInt<2>(1)), UInt<2>(1)) :
+// CHECK:       connect m, bits(shl(a, 1), 3, 0)
+// CHECK:     else :
+// CHECK:       connect m, UInt<4>(0)
+// CHECK:     connect z, orr(m)
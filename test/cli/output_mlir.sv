@@ -1,18 +1,50 @@
 // RUN: moore %s -e foo --format mlir
 
-module foo (output int x);
-    initial x = 42;
+module bar (input logic [3:0] a, output logic [3:0] y);
+    assign y = ~a;
 endmodule
 
-// CHECK: llhd.proc @foo.initial.15.0() -> (%x: !llhd.sig<i32> ) {
-// CHECK:     br ^0
-// CHECK: ^0:
-// CHECK:     %1 = hw.constant 42 : i32
-// CHECK:     %2 = llhd.constant_time #llhd.time<0s, 0d, 1e>
-// CHECK:     llhd.drv %x, %1 after %2 : !llhd.sig<i32>
-// CHECK:     llhd.halt
-// CHECK: }
-// CHECK:
-// CHECK: llhd.entity @foo() -> (%x: !llhd.sig<i32> ) {
-// CHECK:     llhd.inst "inst" @foo.initial.15.0() -> (%x) : () -> (!llhd.sig<i32>)
+module foo (input logic clk, input logic [3:0] d, output logic [3:0] q);
+    logic [3:0] r;
+    always_ff @(posedge clk) begin
+        if (d[0])
+            r <= d + 1;
+    end
+    bar u0 (.a(r), .y(q));
+endmodule
+
+// CHECK: module {
+// CHECK:   moore.module @bar.param1(in %a : !moore.l4, out y : !moore.l4) {
+// CHECK:     %y = moore.variable : <l4>
+// CHECK:     %0 = moore.not %a : l4
+// CHECK:     moore.assign %y, %0 : l4
+// CHECK:     %1 = moore.read %y : <l4>
+// CHECK:     moore.output %1 : !moore.l4
+// CHECK:   }
+// CHECK:   moore.module @foo(in %clk : !moore.l1, in %d : !moore.l4, out q : !moore.l4) {
+// CHECK:     %q = moore.variable : <l4>
+// CHECK:     %r = moore.variable : <l4>
+// CHECK:     %0 = moore.read %r : <l4>
+// CHECK:     %1 = moore.instance "u0" @bar.param1(a: %0: !moore.l4) -> (y: !moore.l4)
+// CHECK:     moore.assign %q, %1 : l4
+// CHECK:     moore.procedure always_ff {
+// CHECK:       moore.wait_event {
+// CHECK:         moore.detect_event posedge %clk : l1
+// CHECK:       }
+// CHECK:       %2 = moore.extract %d from 0 : l4 -> l1
+// CHECK:       %3 = moore.to_builtin_bool %2 : l1
+// CHECK:       cf.cond_br %3, ^bb1, ^bb2
+// CHECK:     ^bb1:
+// CHECK:       %4 = moore.zext %d : l4 -> l32
+// CHECK:       %5 = moore.constant 1 : l32
+// CHECK:       %6 = moore.add %4, %5 : l32
+// CHECK:       %7 = moore.trunc %6 : l32 -> l4
+// CHECK:       moore.nonblocking_assign %r, %7 : l4
+// CHECK:       cf.br ^bb2
+// CHECK:     ^bb2:
+// CHECK:       moore.return
+// CHECK:     }
+// CHECK:     %8 = moore.read %q : <l4>
+// CHECK:     moore.output %8 : !moore.l4
+// CHECK:   }
 // CHECK: }
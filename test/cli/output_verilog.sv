@@ -0,0 +1,84 @@
+// RUN: moore %s -e foo --format verilog
+
+module bar #(parameter int W = 4) (input logic clk, input logic rst_n, input logic [W-1:0] d, output logic [W-1:0] q);
+    always_ff @(posedge clk or negedge rst_n) begin
+        if (!rst_n) q <= 0;
+        else q <= q + d;
+    end
+endmodule
+
+module foo (input logic clk, input logic rst_n, input logic [3:0] a, input logic [1:0] sel, output logic [2:0] y, output logic z);
+    logic [3:0] t;
+    logic [3:0] m;
+    assign t = a ^ 4'b1010;
+    always_comb begin
+        casez (sel)
+            2'b00: m = t;
+            2'b?1: m = a << 1;
+            default: m = '0;
+        endcase
+        z = |m;
+    end
+    for (genvar i = 0; i < 2; i++) begin : g
+        logic [3:0] c;
+        assign c = t + i;
+    end
+    bar #(.W(3)) u (.clk(clk), .rst_n(rst_n), .d(m + a), .q(y));
+endmodule
+
+// CHECK: module bar_param3 (
+// CHECK:     input wire clk,
+// CHECK:     input wire rst_n,
+// CHECK:     input wire [2:0] d,
+// CHECK:     output reg [2:0] q
+// CHECK: );
+// CHECK:     always @(posedge clk or negedge rst_n) begin
+// CHECK:         if (~rst_n) begin
+// CHECK:             q <= 3'd0;
+// CHECK:         end else begin
+// CHECK:             q <= q + d;
+// CHECK:         end
+// CHECK:     end
+// CHECK: endmodule
+// CHECK: module foo (
+// CHECK:     input wire clk,
+// CHECK:     input wire rst_n,
+// CHECK:     input wire [3:0] a,
+// CHECK:     input wire [1:0] sel,
+// CHECK:     output wire [2:0] y,
+// CHECK:     output reg z
+// CHECK: );
+// CHECK:     wire [3:0] t;
+// CHECK:     reg [3:0] m;
+// CHECK:     wire [3:0] g_0__c;
+// CHECK:     wire [3:0] g_1__c;
+// CHECK:     wire [31:0] _t;
+// CHECK:     wire [31:0] _t_1;
+// CHECK:     wire [3:0] _t_2;
+// CHECK:     assign _t = {28'd0, t} + 32'd0;
+// CHECK:     assign g_0__c = _t[3:0];
+// CHECK:     assign _t_1 = {28'd0, t} + 32'd1;
+// CHECK:     assign g_1__c = _t_1[3:0];
+// CHECK:     assign t = a ^ 4'd10;
+// CHECK:     assign _t_2 = m + a;
+// CHECK:     bar_param3 u (
+// CHECK:         .clk(clk),
+// CHECK:         .rst_n(rst_n),
+// CHECK:         .d(_t_2[2:0]),
+// CHECK:         .q(y)
+// CHECK:     );
+// CHECK:     always @* begin
+// CHECK:         casez (sel)
+// CHECK:             2'd0: begin
+// CHECK:                 m = t;
+// CHECK:             end
+// CHECK:             2'bz1: begin
+// CHECK:                 m = a << 32'd1;
+// CHECK:             end
+// CHECK:             default: begin
+// CHECK:                 m = 4'd0;
+// CHECK:             end
+// CHECK:         endcase
+// CHECK:         z = |m;
+// CHECK:     end
+// CHECK: endmodule
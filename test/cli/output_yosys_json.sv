@@ -0,0 +1,224 @@
+// RUN: moore %s -e foo --format yosys-json
+
+module bar (input logic clk, input logic rst_n, input logic [1:0] d, output logic [1:0] q);
+    always_ff @(posedge clk or negedge rst_n) begin
+        if (!rst_n) q <= 0;
+        else q <= d;
+    end
+endmodule
+
+module foo (input logic clk, input logic rst_n, input logic [1:0] a, input logic s, output logic [1:0] y);
+    logic [1:0] m;
+    always_comb begin
+        if (s) m = a;
+        else m = ~a;
+    end
+    bar u (.clk(clk), .rst_n(rst_n), .d(m), .q(y));
+endmodule
+
+// CHECK: {
+// CHECK:   "creator": "moore 0.14.0",
+// CHECK:   "modules": {
+// CHECK:     "bar.param1": {
+// CHECK:       "attributes": {
+// CHECK:       },
+// CHECK:       "ports": {
+// CHECK:         "clk": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 2 ]
+// CHECK:         },
+// CHECK:         "rst_n": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 3 ]
+// CHECK:         },
+// CHECK:         "d": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 4, 5 ]
+// CHECK:         },
+// CHECK:         "q": {
+// CHECK:           "direction": "output",
+// CHECK:           "bits": [ 6, 7 ]
+// CHECK:         }
+// CHECK:       },
+// CHECK:       "cells": {
+// CHECK:         "$adff$0": {
+// CHECK:           "hide_name": 1,
+// CHECK:           "type": "$adff",
+// CHECK:           "parameters": {
+// CHECK:             "WIDTH": "00000000000000000000000000000010",
+// CHECK:             "CLK_POLARITY": "1",
+// CHECK:             "ARST_POLARITY": "0",
+// CHECK:             "ARST_VALUE": "00"
+// CHECK:           },
+// CHECK:           "attributes": {
+// CHECK:           },
+// CHECK:           "port_directions": {
+// CHECK:             "ARST": "input",
+// CHECK:             "CLK": "input",
+// CHECK:             "D": "input",
+// CHECK:             "Q": "output"
+// CHECK:           },
+// CHECK:           "connections": {
+// CHECK:             "ARST": [ 3 ],
+// CHECK:             "CLK": [ 2 ],
+// CHECK:             "D": [ 4, 5 ],
+// CHECK:             "Q": [ 6, 7 ]
+// CHECK:           }
+// CHECK:         }
+// CHECK:       },
+// CHECK:       "netnames": {
+// CHECK:         "clk": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 2 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "rst_n": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 3 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "d": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 4, 5 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "q": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 6, 7 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         }
+// CHECK:       }
+// CHECK:     },
+// CHECK:     "foo": {
+// CHECK:       "attributes": {
+// CHECK:         "top": "00000000000000000000000000000001"
+// CHECK:       },
+// CHECK:       "ports": {
+// CHECK:         "clk": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 2 ]
+// CHECK:         },
+// CHECK:         "rst_n": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 3 ]
+// CHECK:         },
+// CHECK:         "a": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 4, 5 ]
+// CHECK:         },
+// CHECK:         "s": {
+// CHECK:           "direction": "input",
+// CHECK:           "bits": [ 6 ]
+// CHECK:         },
+// CHECK:         "y": {
+// CHECK:           "direction": "output",
+// CHECK:           "bits": [ 7, 8 ]
+// CHECK:         }
+// CHECK:       },
+// CHECK:       "cells": {
+// CHECK:         "u": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "type": "bar.param1",
+// CHECK:           "parameters": {
+// CHECK:           },
+// CHECK:           "attributes": {
+// CHECK:           },
+// CHECK:           "port_directions": {
+// CHECK:             "clk": "input",
+// CHECK:             "rst_n": "input",
+// CHECK:             "d": "input",
+// CHECK:             "q": "output"
+// CHECK:           },
+// CHECK:           "connections": {
+// CHECK:             "clk": [ 2 ],
+// CHECK:             "rst_n": [ 3 ],
+// CHECK:             "d": [ 9, 10 ],
+// CHECK:             "q": [ 7, 8 ]
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "$not$1": {
+// CHECK:           "hide_name": 1,
+// CHECK:           "type": "$not",
+// CHECK:           "parameters": {
+// CHECK:             "A_SIGNED": "00000000000000000000000000000000",
+// CHECK:             "A_WIDTH": "00000000000000000000000000000010",
+// CHECK:             "Y_WIDTH": "00000000000000000000000000000010"
+// CHECK:           },
+// CHECK:           "attributes": {
+// CHECK:           },
+// CHECK:           "port_directions": {
+// CHECK:             "A": "input",
+// CHECK:             "Y": "output"
+// CHECK:           },
+// CHECK:           "connections": {
+// CHECK:             "A": [ 4, 5 ],
+// CHECK:             "Y": [ 11, 12 ]
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "$mux$2": {
+// CHECK:           "hide_name": 1,
+// CHECK:           "type": "$mux",
+// CHECK:           "parameters": {
+// CHECK:             "WIDTH": "00000000000000000000000000000010"
+// CHECK:           },
+// CHECK:           "attributes": {
+// CHECK:           },
+// CHECK:           "port_directions": {
+// CHECK:             "A": "input",
+// CHECK:             "B": "input",
+// CHECK:             "S": "input",
+// CHECK:             "Y": "output"
+// CHECK:           },
+// CHECK:           "connections": {
+// CHECK:             "A": [ 11, 12 ],
+// CHECK:             "B": [ 4, 5 ],
+// CHECK:             "S": [ 6 ],
+// CHECK:             "Y": [ 9, 10 ]
+// CHECK:           }
+// CHECK:         }
+// CHECK:       },
+// CHECK:       "netnames": {
+// CHECK:         "clk": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 2 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "rst_n": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 3 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "a": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 4, 5 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "s": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 6 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "y": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 7, 8 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         },
+// CHECK:         "m": {
+// CHECK:           "hide_name": 0,
+// CHECK:           "bits": [ 9, 10 ],
+// CHECK:           "attributes": {
+// CHECK:           }
+// CHECK:         }
+// CHECK:       }
+// CHECK:     }
+// CHECK:   }
+// CHECK: }
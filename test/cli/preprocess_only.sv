@@ -0,0 +1,11 @@
+// RUN: moore %s --preprocess-only
+// FAIL
+
+`define FOO 42
+
+module foo;
+    localparam int N = `FOO;
+    localparam int M = `BAR;
+endmodule
+
+// CHECK-ERR: fatal[SV0020]: unknown compiler directive '`BAR'
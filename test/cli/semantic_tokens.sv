@@ -0,0 +1,54 @@
+// RUN: moore %s --semantic-tokens
+
+`define W 8
+
+package p;
+    typedef logic [`W-1:0] word_t;
+endpackage
+
+module m #(parameter int N = 2) (input p::word_t a, output logic y);
+    assign y = a[N];
+endmodule
+
+// CHECK: {
+// CHECK-NEXT:   "legend": {"tokenTypes": ["keyword","type","macro","parameter","module","interface","package","instance","port","signal","function","number","string","comment"], "tokenModifiers": ["declaration"]},
+// CHECK-NEXT:   "files": [
+// CHECK:      "tokens": [
+// CHECK-NEXT:       {"begin": 0, "end": 34, "type": "comment", "modifiers": []},
+// CHECK-NEXT:       {"begin": 36, "end": 43, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 44, "end": 45, "type": "macro", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 46, "end": 47, "type": "number", "modifiers": []},
+// CHECK-NEXT:       {"begin": 49, "end": 56, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 57, "end": 58, "type": "package", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 64, "end": 71, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 72, "end": 77, "type": "type", "modifiers": []},
+// CHECK-NEXT:       {"begin": 79, "end": 81, "type": "macro", "modifiers": []},
+// CHECK-NEXT:       {"begin": 82, "end": 83, "type": "number", "modifiers": []},
+// CHECK-NEXT:       {"begin": 84, "end": 85, "type": "number", "modifiers": []},
+// CHECK-NEXT:       {"begin": 87, "end": 93, "type": "type", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 95, "end": 105, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 107, "end": 113, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 114, "end": 115, "type": "module", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 118, "end": 127, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 128, "end": 131, "type": "type", "modifiers": []},
+// CHECK-NEXT:       {"begin": 132, "end": 133, "type": "parameter", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 136, "end": 137, "type": "number", "modifiers": []},
+// CHECK-NEXT:       {"begin": 140, "end": 145, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 146, "end": 147, "type": "package", "modifiers": []},
+// CHECK-NEXT:       {"begin": 149, "end": 155, "type": "type", "modifiers": []},
+// CHECK-NEXT:       {"begin": 156, "end": 157, "type": "port", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 159, "end": 165, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 166, "end": 171, "type": "type", "modifiers": []},
+// CHECK-NEXT:       {"begin": 172, "end": 173, "type": "port", "modifiers": ["declaration"]},
+// CHECK-NEXT:       {"begin": 180, "end": 186, "type": "keyword", "modifiers": []},
+// CHECK-NEXT:       {"begin": 187, "end": 188, "type": "port", "modifiers": []},
+// CHECK-NEXT:       {"begin": 191, "end": 192, "type": "port", "modifiers": []},
+// CHECK-NEXT:       {"begin": 193, "end": 194, "type": "parameter", "modifiers": []},
+// CHECK-NEXT:       {"begin": 197, "end": 206, "type": "keyword", "modifiers": []},
+// CHECK:      "data": [
+// CHECK-NEXT:       0, 0, 34, 13, 0,
+// CHECK-NEXT:       2, 0, 7, 0, 0,
+// CHECK-NEXT:       0, 8, 1, 2, 1,
+// CHECK-NEXT:       0, 2, 1, 11, 0,
+// CHECK-NEXT:       2, 0, 7, 0, 0,
+// CHECK-NEXT:       0, 8, 1, 6, 1,
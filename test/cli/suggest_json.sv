@@ -0,0 +1,6 @@
+// RUN: moore test/cli/suggest_json.sv -e foo --diag-format json
+
+module foo (input logic a, input logic b,);
+endmodule
+
+// CHECK-ERR: {"code":null,"group":null,"message":"superfluous trailing comma","notes":[],"severity":"warning","spans":[{"column":41,"end_column":42,"end_line":3,"file":"test/cli/suggest_json.sv","line":3,"primary":true}],"suggestions":[{"column":41,"end_column":42,"end_line":3,"file":"test/cli/suggest_json.sv","line":3,"replacement":""}]}
@@ -0,0 +1,10 @@
+// RUN: moore %s
+// FAIL
+
+module foo (ouput logic y);
+endmodule
+
+// CHECK-ERR: error[SV0016]: expected , or ) after port
+// CHECK-ERR: = note: `ouput` is not a port direction
+// CHECK-ERR: = help: replace `ouput` with `output`
+// CHECK-ERR: 4 | module foo (output logic y);
@@ -0,0 +1,10 @@
+// RUN: moore %s
+// FAIL
+
+module foo (input logic a, output logic y);
+    assign y = a
+endmodule
+
+// CHECK-ERR: error[SV0016]: expected , or ; after continuous assignment
+// CHECK-ERR: = help: insert `;`
+// CHECK-ERR: 5 |     assign y = a;
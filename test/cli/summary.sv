@@ -0,0 +1,64 @@
+// RUN: moore summary %s --top fifo --param WIDTH=16
+
+module fifo #(parameter int WIDTH = 8, parameter int DEPTH = 4) (
+    input logic clk,
+    input logic [WIDTH-1:0] din,
+    output logic [WIDTH/8-1:0] strb
+);
+    localparam int BYTES = WIDTH / 8;
+endmodule
+
+module unrelated (input logic a);
+endmodule
+
+// CHECK: [
+// CHECK-NEXT:   {
+// CHECK-NEXT:     "name": "fifo",
+// CHECK-NEXT:     "params": [
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "WIDTH",
+// CHECK-NEXT:         "local": false,
+// CHECK-NEXT:         "kind": "value",
+// CHECK-NEXT:         "type": "int",
+// CHECK-NEXT:         "value": "16",
+// CHECK-NEXT:         "overridden": true
+// CHECK-NEXT:       },
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "DEPTH",
+// CHECK-NEXT:         "local": false,
+// CHECK-NEXT:         "kind": "value",
+// CHECK-NEXT:         "type": "int",
+// CHECK-NEXT:         "value": "4",
+// CHECK-NEXT:         "overridden": false
+// CHECK-NEXT:       },
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "BYTES",
+// CHECK-NEXT:         "local": true,
+// CHECK-NEXT:         "kind": "value",
+// CHECK-NEXT:         "type": "int",
+// CHECK-NEXT:         "value": "2",
+// CHECK-NEXT:         "overridden": false
+// CHECK-NEXT:       }
+// CHECK-NEXT:     ],
+// CHECK-NEXT:     "ports": [
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "clk",
+// CHECK-NEXT:         "dir": "input",
+// CHECK-NEXT:         "type": "logic",
+// CHECK-NEXT:         "width": 1
+// CHECK-NEXT:       },
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "din",
+// CHECK-NEXT:         "dir": "input",
+// CHECK-NEXT:         "type": "logic [15:0]",
+// CHECK-NEXT:         "width": 16
+// CHECK-NEXT:       },
+// CHECK-NEXT:       {
+// CHECK-NEXT:         "name": "strb",
+// CHECK-NEXT:         "dir": "output",
+// CHECK-NEXT:         "type": "logic [1:0]",
+// CHECK-NEXT:         "width": 2
+// CHECK-NEXT:       }
+// CHECK-NEXT:     ]
+// CHECK-NEXT:   }
+// CHECK-NEXT: ]
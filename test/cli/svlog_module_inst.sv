@@ -0,0 +1,12 @@
+// RUN: moore %s Inputs/mixed/adder_top.vhd -e top
+
+// The module is instantiated from VHDL through an entity with the same
+// interface, with `logic` mapped to `STD_ULOGIC` and `int` to `INTEGER`.
+module adder #(parameter int WIDTH = 8) (
+    input logic [WIDTH-1:0] a,
+    input logic [WIDTH-1:0] b,
+    output logic [WIDTH-1:0] sum,
+    output logic carry
+);
+    assign {carry, sum} = a + b;
+endmodule
@@ -0,0 +1,13 @@
+// RUN: moore %s --format llhd
+
+module foo;
+    bar u ();
+endmodule
+
+module bar;
+endmodule
+
+// CHECK: entity @bar.param1 () -> () {
+// CHECK: entity @foo () -> () {
+// CHECK:     inst @bar.param1 () -> ()
+// CHECK-ERR: note: using `foo` as top-level module
@@ -0,0 +1,19 @@
+// RUN: moore %s --format llhd
+// FAIL
+
+module tb_a;
+    shared u ();
+endmodule
+
+module tb_b;
+    shared u ();
+endmodule
+
+module shared;
+endmodule
+
+// CHECK-ERR: error: cannot determine the top-level module
+// CHECK-ERR: = note: The following modules are never instantiated:
+// CHECK-ERR: = note: - tb_a
+// CHECK-ERR: = note: - tb_b
+// CHECK-ERR: = note: Select the top-level modules with `--top`
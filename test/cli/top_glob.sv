@@ -0,0 +1,25 @@
+// RUN: moore %s --top 'tb_?' --top bar
+
+module tb_a;
+    shared u ();
+endmodule
+
+module tb_b;
+    shared u ();
+endmodule
+
+module shared;
+endmodule
+
+module foo;
+endmodule
+
+module bar;
+endmodule
+
+// CHECK: entity @shared.param1 () -> () {
+// CHECK: entity @tb_a () -> () {
+// CHECK:     inst @shared.param1 () -> ()
+// CHECK: entity @tb_b () -> () {
+// CHECK:     inst @shared.param1 () -> ()
+// CHECK: entity @bar () -> () {
@@ -0,0 +1,9 @@
+// RUN: moore %s --lib mylib --top 'mylib.tb_?'
+
+module tb_a;
+endmodule
+
+module foo;
+endmodule
+
+// CHECK: entity @tb_a () -> () {
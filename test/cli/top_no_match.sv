@@ -0,0 +1,9 @@
+// RUN: moore %s --top 'tb_?'
+// FAIL
+
+module foo;
+endmodule
+
+// CHECK-ERR: error: no module matches `tb_?`
+// CHECK-ERR: = note: The following modules are defined:
+// CHECK-ERR: = note: - foo
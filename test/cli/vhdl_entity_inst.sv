@@ -0,0 +1,11 @@
+// RUN: moore %s Inputs/mixed/counter.vhd -e top
+
+// The VHDL entity is instantiated through a module with the same interface,
+// with `STD_LOGIC` mapped to `logic` and `BIT` to `bit`.
+module top (input logic clk, input bit en, output logic [7:0] count);
+    counter #(.WIDTH(8)) i_counter (.clk(clk), .en(en), .count(count));
+endmodule
+
+// CHECK: entity @counter.param1 (i1$ %clk, i1$ %en) -> (i8$ %count) {
+// CHECK: entity @top (i1$ %clk, i1$ %en) -> (i8$ %count) {
+// CHECK:     inst @counter.param1 (i1$ %clk, i1$ %en) -> (i8$ %count)
@@ -0,0 +1,14 @@
+// RUN: moore %s --waiver-file Inputs/waiver/waivers.txt -e foo
+
+module foo (input logic a, input logic s, output logic x, output logic y, output logic [3:0] z);
+    always_comb begin
+        if (s) x = a;
+    end
+    assign z = 4'd35;
+
+    always_comb begin
+        if (s) y = a;
+    end
+endmodule
+
+// CHECK-ERR: warning: `y` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
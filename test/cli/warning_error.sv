@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo -Werror=latch
+// FAIL
+
+module foo (input logic a, input logic s, output logic y);
+    always_comb begin
+        if (s) y = a;
+    end
+endmodule
+
+// CHECK-ERR: error: `y` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Werror=latch]
@@ -0,0 +1,8 @@
+// RUN: moore %s -Wno-foo
+
+module foo;
+endmodule
+
+// CHECK-ERR: warning: unknown warning group `foo`
+// CHECK-ERR: = note: The following warning groups exist:
+// CHECK-ERR: = note: - latch: latches inferred in `always_comb` procedures
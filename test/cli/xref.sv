@@ -0,0 +1,26 @@
+// RUN: moore test/cli/xref.sv --xref -
+
+`define WIDTH 8
+
+module top;
+    logic [`WIDTH-1:0] a, b;
+    adder #(.N(`WIDTH)) u_adder (.x(a), .y(b));
+endmodule
+
+module adder #(parameter int N = 1) (input logic [N-1:0] x, output logic [N-1:0] y);
+    assign y = x + N;
+endmodule
+
+// CHECK: !_TAG_FILE_SORTED	1	/0=unsorted, 1=sorted, 2=foldcase/
+// CHECK: N	test/cli/xref.sv	10;"	c	line:10	roles:def
+// CHECK: N	test/cli/xref.sv	11;"	c	line:11	roles:ref
+// CHECK: WIDTH	test/cli/xref.sv	3;"	d	line:3	roles:def
+// CHECK: WIDTH	test/cli/xref.sv	6;"	d	line:6	roles:ref
+// CHECK: a	test/cli/xref.sv	6;"	n	line:6	roles:def
+// CHECK: a	test/cli/xref.sv	7;"	n	line:7	roles:ref
+// CHECK: adder	test/cli/xref.sv	7;"	m	line:7	roles:ref
+// CHECK: adder	test/cli/xref.sv	10;"	m	line:10	roles:def
+// CHECK: top	test/cli/xref.sv	5;"	m	line:5	roles:def
+// CHECK: u_adder	test/cli/xref.sv	7;"	i	line:7	roles:def
+// CHECK: x	test/cli/xref.sv	10;"	p	line:10	roles:def
+// CHECK: x	test/cli/xref.sv	11;"	p	line:11	roles:ref
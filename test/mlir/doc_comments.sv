@@ -0,0 +1,39 @@
+// RUN: moore %s -e foo --dump-hir text | FileCheck %s
+
+/// A simple register.
+///
+/// Stores `d` on every rising edge.
+module foo (
+    /// The clock.
+    input logic clk,
+    input logic [3:0] d, // not a doc comment
+    /** The stored value. */
+    output logic [3:0] q
+);
+    /**
+     * Internal state.
+     */
+    logic [3:0] a, b;
+    // Regular comment.
+    logic c;
+    /// Width of the register.
+    localparam int W = 4;
+endmodule
+
+// CHECK: /// A simple register.
+// CHECK-NEXT: ///
+// CHECK-NEXT: /// Stores `d` on every rising edge.
+// CHECK-NEXT: module foo (
+// CHECK-NEXT: /// The clock.
+// CHECK-NEXT: input logic clk,
+// CHECK-NEXT: input logic [3:0] d,
+// CHECK-NEXT: /// The stored value.
+// CHECK-NEXT: output logic [3:0] q
+// CHECK: /// Width of the register.
+// CHECK-NEXT: localparam int W = 4;
+// CHECK: /// Internal state.
+// CHECK-NEXT: var logic [3:0] a;
+// CHECK: /// Internal state.
+// CHECK-NEXT: var logic [3:0] b;
+// CHECK-NOT: ///
+// CHECK: var logic c;
@@ -0,0 +1,18 @@
+// RUN: moore -e foo -O2 %s | FileCheck %s
+
+module foo (input logic [7:0] a, output logic [7:0] y);
+    logic [7:0] x;
+    wire [7:0] w = x;
+    assign x = a;
+    assign y = w + w;
+endmodule
+
+// The probes of `w` are merged, and `w` is replaced with `x`.
+// CHECK-LABEL: entity @foo (i8$ %a) -> (i8$ %y) {
+// CHECK-NOT: %w = sig
+// CHECK-NOT: con
+// CHECK: [[PRB:%.+]] = prb i8$ %x
+// CHECK-NOT: prb i8$ %x
+// CHECK: add i8 [[PRB]], [[PRB]]
+// CHECK-NOT: con
+// CHECK: }
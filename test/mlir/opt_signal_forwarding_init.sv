@@ -0,0 +1,13 @@
+// RUN: moore -e foo -O2 %s | FileCheck %s
+
+module foo (output logic [7:0] y);
+    logic [7:0] x = 8'd5;
+    wire [7:0] w = x;
+    assign y = w;
+endmodule
+
+// `w` starts out as 0 and `x` as 5, so `w` is kept.
+// CHECK-LABEL: entity @foo () -> (i8$ %y) {
+// CHECK: %w = sig i8
+// CHECK: con i8$ %w, %x
+// CHECK: }
@@ -0,0 +1,25 @@
+// RUN: moore -e ram -O0 %s | FileCheck %s
+
+module ram (
+    input logic clk,
+    input logic we,
+    input logic [9:0] waddr,
+    input logic [9:0] raddr,
+    input logic [7:0] wdata,
+    output logic [7:0] rdata
+);
+    logic [7:0] mem [0:1023];
+    always_ff @(posedge clk) begin
+        if (we) mem[waddr] <= wdata;
+        rdata <= mem[raddr];
+    end
+endmodule
+
+// The write enable guards the drive of the memory element, rather than a
+// branch around it.
+// CHECK-LABEL: proc
+// CHECK-NOT: if_true
+// CHECK: [[ELEM:%.+]] = extf i8$, [1024 x i8]$ {{%.+}}, 0
+// CHECK-NOT: if_true
+// CHECK: drv i8$ [[ELEM]] if {{%.+}}, {{%.+}}, {{%.+}}
+// CHECK-LABEL: entity @ram
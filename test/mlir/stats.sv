@@ -0,0 +1,13 @@
+// RUN: moore %s --stats -e foo --format llhd 2>&1 >/dev/null | FileCheck %s
+
+module foo (input logic [7:0] a, output logic [7:0] z);
+    assign z = a + 8'd1;
+endmodule
+
+// CHECK:      phase time queries peak memory
+// CHECK-NEXT: preprocess {{[0-9]+\.[0-9]+}} ms -
+// CHECK-NEXT: parse {{[0-9]+\.[0-9]+}} ms -
+// CHECK-NEXT: lower {{[0-9]+\.[0-9]+}} ms {{[0-9]+}}
+// CHECK-NEXT: typecheck {{[0-9]+\.[0-9]+}} ms {{[0-9]+}}
+// CHECK-NEXT: codegen {{[0-9]+\.[0-9]+}} ms {{[0-9]+}}
+// CHECK-NEXT: total {{[0-9]+\.[0-9]+}} ms {{[0-9]+}}
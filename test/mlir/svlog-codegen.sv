@@ -299,10 +299,21 @@ endmodule
 
 // `@*` implicit event statement
 // CHECK-LABEL: llhd.proc @Foo.initial.
-// CHECK-NEXT:    llhd.wait ([[X:%.+]], [[C:%.+]] : !llhd.sig<i1>, !llhd.sig<i32>), [[BB:\^.+]]
-// CHECK-NEXT:  [[BB]]:
-// CHECK-NEXT:    llhd.prb [[X:%.+]] :
-// CHECK-NEXT:    llhd.prb [[C:%.+]] :
+// CHECK-NEXT:    cf.br [[BB_INIT:\^.+]]
+// CHECK-NEXT:  [[BB_INIT]]:
+// CHECK-NEXT:    [[X_BEFORE:%.+]] = llhd.prb [[X:%.+]] :
+// CHECK-NEXT:    [[C_BEFORE:%.+]] = llhd.prb [[C:%.+]] :
+// CHECK-NEXT:    llhd.wait ([[X]], [[C]] : !llhd.sig<i1>, !llhd.sig<i32>), [[BB_CHECK:\^.+]]
+// CHECK-NEXT:  [[BB_CHECK]]:
+// CHECK-NEXT:    [[X_AFTER:%.+]] = llhd.prb [[X]]
+// CHECK-NEXT:    [[X_COND:%.+]] = comb.icmp ne [[X_BEFORE]], [[X_AFTER]]
+// CHECK-NEXT:    [[C_AFTER:%.+]] = llhd.prb [[C]]
+// CHECK-NEXT:    [[C_COND:%.+]] = comb.icmp ne [[C_BEFORE]], [[C_AFTER]]
+// CHECK-NEXT:    [[COND:%.+]] = comb.or [[X_COND]], [[C_COND]]
+// CHECK-NEXT:    cf.cond_br [[COND]], [[BB_INIT]], [[BB_EXIT:\^.+]]
+// CHECK-NEXT:  [[BB_EXIT]]:
+// CHECK-NEXT:    llhd.prb [[X]] :
+// CHECK-NEXT:    llhd.prb [[C]] :
 // CHECK-NEXT:    llhd.halt
 // CHECK-NEXT:  }
 
@@ -1,4 +1,4 @@
-// RUN: moore -e A --format=mlir %s | FileCheck %s
+// RUN: moore -e A --format=llhd-mlir %s | FileCheck %s
 
 module A();
   wire [1:0][2:0] x = {3'h0, 3'h0};
@@ -0,0 +1,43 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    function automatic int bits_needed(int n);
+        return $clog2(n) + $countones(n);
+    endfunction
+
+    localparam int P = 37;
+    localparam int C0 = $countones(8'b10110100);
+    localparam int C1 = $countones(P);
+    localparam bit C2 = $onehot(8'b00010000);
+    localparam bit C3 = $onehot(8'b00110000);
+    localparam bit C4 = $onehot0(8'b00000000);
+    localparam int C5 = $dimensions(P);
+    localparam int C6 = $unpacked_dimensions(P);
+    localparam int C7 = $high(P);
+    localparam int C8 = $size(P);
+    localparam int C9 = $clog2(P);
+    localparam int C10 = bits_needed(P);
+
+    int v0 = C0;
+    // CHECK: %0 = const i32 4
+    int v1 = C1;
+    // CHECK: %1 = const i32 3
+    bit v2 = C2;
+    // CHECK: %2 = const i1 1
+    bit v3 = C3;
+    // CHECK: %3 = const i1 0
+    bit v4 = C4;
+    // CHECK: %4 = const i1 1
+    int v5 = C5;
+    // CHECK: %5 = const i32 1
+    int v6 = C6;
+    // CHECK: %6 = const i32 0
+    int v7 = C7;
+    // CHECK: %7 = const i32 31
+    int v8 = C8;
+    // CHECK: %8 = const i32 32
+    int v9 = C9;
+    // CHECK: %9 = const i32 6
+    int v10 = C10;
+    // CHECK: %10 = const i32 9
+endmodule
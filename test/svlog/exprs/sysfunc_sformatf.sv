@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo --format mlir
+
+module foo;
+    localparam string S = $sformatf("%0d-%h|%5s|%b|%t", 42, 8'hab, "hi", 3'b1x0, 64'd17);
+    localparam string T = $sformatf("%s!", S);
+    initial $write(T);
+endmodule
+
+// CHECK: %0 = moore.fmt.literal "42-ab|   hi|1x0|                  17!"
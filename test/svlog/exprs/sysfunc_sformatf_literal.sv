@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo --format mlir
+// FAIL
+
+module foo (input logic [7:0] a);
+    localparam string F = "%d";
+    localparam string S = $sformatf(F, a);
+    initial $display(S);
+endmodule
+
+// CHECK-ERR: error[SV0028]: format string of `$sformatf` must be a string literal
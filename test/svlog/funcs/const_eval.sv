@@ -0,0 +1,55 @@
+// RUN: moore %s -e foo -O0
+
+module foo #(parameter int DEPTH = 100);
+    function automatic int clog2_func(input int value);
+        int result = 0;
+        value = value - 1;
+        while (value > 0) begin
+            result++;
+            value = value >> 1;
+        end
+        return result;
+    endfunction
+
+    function automatic int fact(int n);
+        if (n <= 1) return 1;
+        return n * fact(n - 1);
+    endfunction
+
+    function automatic int sum_to(int n);
+        int s = 0;
+        for (int i = 0; i < n; i++) begin
+            if (i == 3) continue;
+            s += i;
+        end
+        return s;
+    endfunction
+
+    function automatic bit [7:0] pattern(int n);
+        bit [7:0] x;
+        x[3:0] = n;
+        x[7] = 1;
+        case (n)
+            1: x[6] = 1;
+            default: x[5] = 1;
+        endcase
+        return x;
+    endfunction
+
+    localparam int W = clog2_func(DEPTH);
+    localparam int F = fact(5);
+    localparam int S = sum_to(6);
+    localparam int P1 = pattern(1);
+    localparam int P2 = pattern(2);
+
+    int v0 = W;
+    // CHECK: %0 = const i32 7
+    int v1 = F;
+    // CHECK: %1 = const i32 120
+    int v2 = S;
+    // CHECK: %2 = const i32 12
+    int v3 = P1;
+    // CHECK: %3 = const i32 193
+    int v4 = P2;
+    // CHECK: %4 = const i32 162
+endmodule
@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    int g;
+    function automatic int bar(int n);
+        return g + n;
+        // CHECK-ERR: error[SV0030]: `g` is not constant
+    endfunction
+    localparam int K = bar(1);
+    int x = K;
+endmodule
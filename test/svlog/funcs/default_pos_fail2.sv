@@ -7,6 +7,6 @@ endfunction
 module foo;
     initial begin
         bar(1,,7);  // error; k has no default value
-        // CHECK-ERR: error: argument without default: `k` must be passed a value
+        // CHECK-ERR: error[SV0027]: argument without default: `k` must be passed a value
     end
 endmodule
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    for (genvar i = 0; i < 2; i++) begin : h
+        int z = i;
+    end
+    int w;
+    assign w = h.z;
+    // CHECK-ERR: error[SV0045]: hierarchical references into for-generate block `h` are not supported
+endmodule
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    if (0) begin : g
+        int y = 5;
+    end
+    int w;
+    assign w = g.y;
+    // CHECK-ERR: error[SV0042]: generate block `g` is not elaborated
+endmodule
@@ -0,0 +1,36 @@
+// RUN: moore %s -e foo
+
+module foo;
+    localparam int P = 1;
+    if (P == 1) begin : g
+        localparam int P = 2;
+        int y = P;
+        if (1) begin : k
+            int q = 7;
+        end
+    end else begin : e
+        int y = 9;
+    end
+    for (genvar i = 0; i < 2; i++) begin : h
+        int z = i;
+    end
+    int w = P;
+    assign w = g.y + g.k.q;
+endmodule
+
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i32 1
+// CHECK:     %w = sig i32 %0
+// CHECK:     %1 = const i32 2
+// CHECK:     %g.y = sig i32 %1
+// CHECK:     %2 = const i32 7
+// CHECK:     %g.k.q = sig i32 %2
+// CHECK:     %3 = const i32 0
+// CHECK:     %h\5b0\5d.z = sig i32 %3
+// CHECK:     %4 = const i32 1
+// CHECK:     %h\5b1\5d.z = sig i32 %4
+// CHECK:     %g.y.prb = prb i32$ %g.y
+// CHECK:     %g.k.q.prb = prb i32$ %g.k.q
+// CHECK:     %6 = add i32 %g.y.prb, %g.k.q.prb
+// CHECK:     drv i32$ %w, %6, %5
+// CHECK: }
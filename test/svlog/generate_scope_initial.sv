@@ -0,0 +1,22 @@
+// RUN: moore %s -e foo
+
+// Signals in a generate block may be accessed by name before the block itself
+// is elaborated.
+
+module foo;
+    if (1) begin : g
+        int y = 2;
+    end
+    int v;
+    int w;
+    initial v = 3;
+    assign w = g.y + v;
+endmodule
+
+// CHECK: entity @foo () -> () {
+// CHECK:     %2 = const i32 2
+// CHECK:     %g.y = sig i32 %2
+// CHECK:     %g.y.prb = prb i32$ %g.y
+// CHECK:     %v.prb = prb i32$ %v
+// CHECK:     %4 = add i32 %g.y.prb, %v.prb
+// CHECK:     drv i32$ %w, %4, %3
@@ -0,0 +1,40 @@
+// RUN: moore %s -e foo --allow-blackbox --format verilog
+
+module foo (input logic clk, input logic [3:0] x, output logic [7:0] y);
+    vendor_ram #(.DEPTH(16)) u0 (.clk, .addr(x), .q(y), .unused());
+    vendor_pll p (clk, x);
+endmodule
+
+// CHECK: module vendor_ram_param1 (
+// CHECK:     input wire clk,
+// CHECK:     input wire [3:0] addr,
+// CHECK:     input wire [7:0] q,
+// CHECK:     input wire unused
+// CHECK: );
+// CHECK: endmodule
+// CHECK: module vendor_pll_param2 (
+// CHECK:     input wire port0,
+// CHECK:     input wire [3:0] port1
+// CHECK: );
+// CHECK: endmodule
+// CHECK: module foo (
+// CHECK:     input wire clk,
+// CHECK:     input wire [3:0] x,
+// CHECK:     output wire [7:0] y
+// CHECK: );
+// CHECK:     vendor_ram_param1 u0 (
+// CHECK:         .clk(clk),
+// CHECK:         .addr(x),
+// CHECK:         .q(y),
+// CHECK:         .unused()
+// CHECK:     );
+// CHECK:     vendor_pll_param2 p (
+// CHECK:         .port0(clk),
+// CHECK:         .port1(x)
+// CHECK:     );
+// CHECK: endmodule
+
+// CHECK-ERR: warning: module `vendor_ram` not found; instantiating an empty blackbox [-Wblackbox]
+// CHECK-ERR: = note: The blackbox has ports inferred from this instantiation: input logic clk, input logic [3:0] addr, input logic [7:0] q, input logic unused
+// CHECK-ERR: warning: module `vendor_pll` not found; instantiating an empty blackbox [-Wblackbox]
+// CHECK-ERR: = note: The blackbox has ports inferred from this instantiation: input logic port0, input logic [3:0] port1
@@ -0,0 +1,22 @@
+// RUN: moore %s -e foo --elab-report text
+
+module foo;
+    bar #(1) b0();
+    bar #(.T(logic [3:0])) b1();
+endmodule
+
+module bar #(parameter int N = 0, parameter type T = bit);
+    localparam int M = N * 2;
+endmodule
+
+// CHECK: module `bar` (2 specializations)
+// CHECK:   entity @bar.param1 (p1)
+// CHECK:     N = 1 (int)
+// CHECK:     T = type bit
+// CHECK:     localparam M = 2 (int)
+// CHECK:   entity @bar.param2 (p2)
+// CHECK:     N = 0 (int)
+// CHECK:     T = type logic [3:0]
+// CHECK:     localparam M = 0 (int)
+// CHECK: module `foo` (1 specialization)
+// CHECK:   entity @foo (p0)
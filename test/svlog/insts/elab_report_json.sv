@@ -0,0 +1,17 @@
+// RUN: moore %s -e foo --elab-report json
+
+module foo;
+    bar #(3) b();
+endmodule
+
+module bar #(parameter int N = 0);
+endmodule
+
+// CHECK: [
+// CHECK:   {"module": "bar", "specializations": [
+// CHECK:     {"entity": "bar.param1", "env": "p1", "params": [{"name": "N", "local": false, "kind": "value", "type": "int", "value": "3"}]}
+// CHECK:   ]},
+// CHECK:   {"module": "foo", "specializations": [
+// CHECK:     {"entity": "foo", "env": "p0", "params": []}
+// CHECK:   ]}
+// CHECK: ]
@@ -0,0 +1,26 @@
+// RUN: moore %s -e foo --flatten
+
+module foo (input logic a, output logic z);
+    logic x;
+    bar u_bar (.a(a), .z(x));
+    baz u_baz (.a(x), .z(z));
+endmodule
+
+module bar (input logic a, output logic z);
+    logic x;
+    assign x = ~a;
+    assign z = x;
+endmodule
+
+module baz (input logic a, output logic z);
+    bar u_inner (.a(a), .z(z));
+endmodule
+
+// CHECK: entity @foo (i1$ %a) -> (i1$ %z) {
+// CHECK:     %x = sig i1 %0
+// CHECK:     %u_bar.x = sig i1 %1
+// CHECK:     drv i1$ %u_bar.x, %3, %2
+// CHECK:     drv i1$ %x, %u_bar.x.prb, %4
+// CHECK:     %u_baz.u_inner.x = sig i1 %5
+// CHECK:     drv i1$ %z, %u_baz.u_inner.x.prb, %8
+// CHECK: }
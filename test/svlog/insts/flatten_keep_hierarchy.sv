@@ -0,0 +1,24 @@
+// RUN: moore %s -e foo --flatten
+
+module foo (input logic a, output logic z0, z1, z2);
+    (* keep_hierarchy *) bar u_kept (.a(a), .z(z0));
+    (* keep_hierarchy = 0 *) baz u_inlined (.a(a), .z(z1));
+    baz u_baz (.a(a), .z(z2));
+endmodule
+
+module bar (input logic a, output logic z);
+    assign z = ~a;
+endmodule
+
+(* keep_hierarchy *)
+module baz (input logic a, output logic z);
+    assign z = a;
+endmodule
+
+// CHECK: entity @bar.param1 (i1$ %a) -> (i1$ %z) {
+// CHECK: entity @baz.param2 (i1$ %a) -> (i1$ %z) {
+// CHECK: entity @foo (i1$ %a) -> (i1$ %z0, i1$ %z1, i1$ %z2) {
+// CHECK:     inst @bar.param1 (i1$ %a) -> (i1$ %z0)
+// CHECK:     drv i1$ %z1, %a.prb, %0
+// CHECK:     inst @baz.param2 (i1$ %a) -> (i1$ %z2)
+// CHECK: }
@@ -0,0 +1,21 @@
+// RUN: moore %s -e foo -e baz -e qux
+// FAIL
+
+module foo;
+    bar #(.L(2)) b();
+    // CHECK-ERR: error[SV0009]: cannot override localparam `L`
+endmodule
+
+module baz;
+    bar #(1, 2) b();
+    // CHECK-ERR: error[SV0009]: cannot override localparam `L`
+endmodule
+
+module qux;
+    bar #(.T(int)) b();
+    // CHECK-ERR: error[SV0009]: cannot override localparam `T`
+endmodule
+
+module bar #(parameter int P = 1, localparam int L = P + 1, type T = bit);
+    T x = L;
+endmodule
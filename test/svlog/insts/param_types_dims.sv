@@ -0,0 +1,46 @@
+// RUN: moore %s -e A0
+
+// Type parameter overrides with packed dimensions on named types
+
+typedef logic [4:0] five_t;
+
+module A0;
+    A1 #(.T(five_t [1:0])) a1();
+    A1 #(five_t [2:0][1:0]) a2();
+endmodule
+
+module A1 #(type T = int);
+    int w = $bits(T);
+    A2 #(.U(T [3:0])) a();
+endmodule
+
+module A2 #(type U = bit);
+    int w = $bits(U);
+endmodule
+
+// CHECK: entity @A2.param3 () -> () {
+// CHECK:     %0 = const i32 40
+// CHECK:     %w = sig i32 %0
+// CHECK: }
+// CHECK:
+// CHECK: entity @A1.param1 () -> () {
+// CHECK:     %0 = const i32 10
+// CHECK:     %w = sig i32 %0
+// CHECK:     inst @A2.param3 () -> ()
+// CHECK: }
+// CHECK:
+// CHECK: entity @A2.param4 () -> () {
+// CHECK:     %0 = const i32 120
+// CHECK:     %w = sig i32 %0
+// CHECK: }
+// CHECK:
+// CHECK: entity @A1.param2 () -> () {
+// CHECK:     %0 = const i32 30
+// CHECK:     %w = sig i32 %0
+// CHECK:     inst @A2.param4 () -> ()
+// CHECK: }
+// CHECK:
+// CHECK: entity @A0 () -> () {
+// CHECK:     inst @A1.param1 () -> ()
+// CHECK:     inst @A1.param2 () -> ()
+// CHECK: }
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    bar #(.P(int)) b();
+    // CHECK-ERR: error[SV0014]: `int` is not a value
+endmodule
+
+module bar #(parameter int P = 1, parameter type T = bit);
+    T x = P;
+endmodule
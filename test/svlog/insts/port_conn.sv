@@ -0,0 +1,21 @@
+// RUN: moore %s -e foo
+
+module foo (input logic [3:0] a, input logic [15:0] b, output logic [7:0] y);
+    logic [7:0] w;
+    // Unsized literals and ports left open explicitly are fine.
+    bar i0 (.i(a), .o(w), .e(0));
+    bar i1 (.i(b), .o(y), .e());
+    bar i2 (.i(8'd3));
+endmodule
+
+module bar (input logic [7:0] i, output logic [7:0] o, input logic e, input logic f = 0);
+    assign o = e ? i : ~i;
+endmodule
+
+// CHECK-ERR: warning: `a` is narrower than input port `i` of module `bar` [-Wport-conn]
+// CHECK-ERR: = note: `a` has 4 bits, but the port has 8 bits
+// CHECK-ERR: = note: Port `i` of instance `i0` declared here:
+// CHECK-ERR: warning: `b` is wider than input port `i` of module `bar` [-Wport-conn]
+// CHECK-ERR: warning: port `o` of module `bar` is not connected [-Wport-conn]
+// CHECK-ERR: = note: Connect it, or leave it open explicitly with `.o()`
+// CHECK-ERR: warning: port `e` of module `bar` is not connected [-Wport-conn]
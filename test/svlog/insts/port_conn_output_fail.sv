@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo (input logic [7:0] a, output logic [7:0] y);
+    bar i0 (.i(a), .o(y & a));
+endmodule
+
+module bar (input logic [7:0] i, output logic [7:0] o);
+    assign o = i;
+endmodule
+
+// CHECK-ERR: warning: output port `o` of module `bar` is connected to `y & a`, which is not a variable or net [-Wport-conn]
+// CHECK-ERR: = note: Port `o` of instance `i0` declared here:
@@ -0,0 +1,25 @@
+// RUN: moore %s -e foo
+
+// Modules may instantiate themselves as long as the recursion terminates.
+module foo;
+    tree #(1) t();
+endmodule
+
+module tree #(parameter int N = 0);
+    int n = N;
+    if (N > 0) begin : g
+        tree #(N-1) l();
+        tree #(N-1) r();
+    end
+endmodule
+
+// CHECK: entity @tree.param2 () -> () {
+// CHECK:     %0 = const i32 0
+// CHECK: entity @tree.param3 () -> () {
+// CHECK:     %0 = const i32 0
+// CHECK: entity @tree.param1 () -> () {
+// CHECK:     %0 = const i32 1
+// CHECK:     inst @tree.param2 () -> ()
+// CHECK:     inst @tree.param3 () -> ()
+// CHECK: entity @foo () -> () {
+// CHECK:     inst @tree.param1 () -> ()
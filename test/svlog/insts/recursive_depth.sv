@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo --max-inst-depth 8
+// FAIL
+
+module foo;
+    bar #(0) b();
+endmodule
+
+module bar #(parameter int N = 0);
+    bar #(N+1) b();
+    // CHECK-ERR: error[SV0032]: instance hierarchy exceeds the maximum depth of 8
+endmodule
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    bar #(4) b();
+endmodule
+
+module bar #(parameter int N = 0, parameter type T = bit);
+    bar #(N, T) b();
+    // CHECK-ERR: error[SV0010]: module `bar` instantiates itself with identical parameters
+endmodule
@@ -0,0 +1,20 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo ();
+    bar x();
+    baz y();
+    fee u0(y);
+    // CHECK-ERR: error[SV0039]: cannot connect interface `baz` to port `x` of interface `bar`
+endmodule
+
+module fee (bar x);
+endmodule
+
+interface bar;
+    logic data;
+endinterface
+
+interface baz;
+    logic data;
+endinterface
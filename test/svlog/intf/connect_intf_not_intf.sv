@@ -0,0 +1,15 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo ();
+    logic z;
+    fee u0(z);
+    // CHECK-ERR: error[SV0039]: `z` is not an interface; cannot connect it to interface port `x`
+endmodule
+
+module fee (bar x);
+endmodule
+
+interface bar;
+    logic data;
+endinterface
@@ -0,0 +1,21 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo ();
+    bar x(), y();
+    fee u0(x.out, y.out);
+    // CHECK-ERR: error[SV0039]: modport `out` does not match modport `in` of port `x`
+    // CHECK-ERR: = note: `data` is output in modport `out`, but input in modport `in`
+endmodule
+
+module fee (bar.in x, bar.out y);
+endmodule
+
+interface bar;
+    logic [31:0] data;
+    logic valid;
+    logic ready;
+
+    modport in (input data, valid, output ready);
+    modport out (output data, valid, input ready);
+endinterface
@@ -0,0 +1,16 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo ();
+    bar x();
+    fee u0(x);
+endmodule
+
+module fee (bar.data x);
+    // CHECK-ERR: error[SV0029]: `data` is not a modport of interface `bar`
+endmodule
+
+interface bar;
+    logic data;
+    modport in (input data);
+endinterface
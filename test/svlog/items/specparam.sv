@@ -0,0 +1,19 @@
+// RUN: moore %s -e foo
+
+module foo;
+    specparam tRise = 10, tFall = tRise * 2;
+    specify
+        specparam tSetup = 3;
+        specparam tHold = tSetup + 1;
+        (a => b) = (tRise, tFall);
+        $setup(a, posedge b, tSetup);
+    endspecify
+    int x = tFall;
+    int y = tHold;
+endmodule
+
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i32 20
+// CHECK:     %x = sig i32 %0
+// CHECK:     %1 = const i32 4
+// CHECK:     %y = sig i32 %1
@@ -0,0 +1,19 @@
+// RUN: moore lint %s -e foo
+
+module foo (input logic clk, input logic en, input logic [7:0] a, output logic [7:0] q, output logic [7:0] y, output logic [7:0] z);
+    // Blocking assignments to variables local to the procedure are fine.
+    always_ff @(posedge clk) begin
+        logic [7:0] tmp;
+        tmp = a + 1;
+        q = tmp;
+    end
+    always_comb y <= a;
+    always_latch if (en) z <= a;
+endmodule
+
+// CHECK-ERR: warning: blocking assignment in `always_ff` procedure [-Wblocking-ff]
+// CHECK-ERR: 8 |         q <= tmp;
+// CHECK-ERR: warning: nonblocking assignment in `always_comb` procedure [-Wnonblocking-comb]
+// CHECK-ERR: 10 |     always_comb y = a;
+// CHECK-ERR: warning: nonblocking assignment in `always_latch` procedure [-Wnonblocking-comb]
+// CHECK-ERR: 11 |     always_latch if (en) z = a;
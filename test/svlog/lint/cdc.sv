@@ -0,0 +1,46 @@
+// RUN: moore lint %s -e foo -Wno-unused
+
+module foo (
+    input logic clk_a,
+    input logic clk_b,
+    input logic rst_b,
+    input logic d
+);
+    logic a, b, c;
+    always_ff @(posedge clk_a) a <= d;
+    assign b = a & d;
+
+    // A register of the other domain sampled directly.
+    logic q0;
+    always_ff @(posedge clk_b) q0 <= a;
+
+    // Combinational logic derived from the other domain.
+    logic q1;
+    always_ff @(posedge clk_b) q1 <= b;
+
+    // A two-stage synchronizer.
+    logic s1, s2;
+    always_ff @(posedge clk_b, negedge rst_b)
+        if (!rst_b) begin
+            s1 <= 0;
+            s2 <= 0;
+        end else begin
+            s1 <= a;
+            s2 <= s1;
+        end
+
+    // A synchronizer whose first stage is also read combinationally.
+    logic t1, t2;
+    always_ff @(posedge clk_b) begin
+        t1 <= a;
+        t2 <= t1;
+    end
+    assign c = t1;
+endmodule
+
+// CHECK: warning: `a` crosses from the domain of `clk_a` into the domain of `clk_b` without a synchronizer [-Wcdc]
+// CHECK: 15 |     always_ff @(posedge clk_b) q0 <= a;
+// CHECK: warning: `b` crosses from the domain of `clk_a` into the domain of `clk_b` without a synchronizer [-Wcdc]
+// CHECK: 19 |     always_ff @(posedge clk_b) q1 <= b;
+// CHECK: warning: `a` crosses from the domain of `clk_a` into the domain of `clk_b` without a synchronizer [-Wcdc]
+// CHECK: 35 |         t1 <= a;
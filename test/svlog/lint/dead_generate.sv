@@ -0,0 +1,36 @@
+// RUN: moore lint %s -e foo -Wno-unused
+
+module foo;
+    bar #(4) i0 ();
+    bar #(8) i1 ();
+endmodule
+
+module bar #(parameter int W = 1);
+    if (W > 16) begin : g_wide
+        // Statements in dead branches are not reported themselves.
+        if (W > 32) begin
+            logic g;
+        end
+        logic [W-1:0] a;
+    end else begin : g_narrow
+        logic b;
+    end
+    if (W > 2) begin : g_some
+        logic c;
+    end else begin : g_none
+        logic d;
+    end
+    for (genvar i = 0; i < 2; i++) begin : g_loop
+        if (i < 5) begin
+            logic e;
+        end else begin
+            logic f;
+        end
+    end
+endmodule
+
+// CHECK-ERR: warning: the `if` branch of this generate statement is never elaborated [-Wdead-generate]
+// CHECK-ERR: = note: `W > 16` is false in every specialization of module `bar`
+// CHECK-ERR: warning: the `else` branch of this generate statement is never elaborated [-Wdead-generate]
+// CHECK-ERR: = note: `W > 2` is true in every specialization of module `bar`
+// CHECK-ERR: = note: `i < 5` is true in every specialization of module `bar`
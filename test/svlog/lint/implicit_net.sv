@@ -0,0 +1,14 @@
+// RUN: moore lint %s -e foo
+
+module foo (input logic a, input logic b, output logic y);
+    assign w = a & b;
+    bar i0 (.i(w), .o(v));
+    assign y = v;
+endmodule
+
+module bar (input logic i, output logic o);
+    assign o = ~i;
+endmodule
+
+// CHECK-ERR: warning: `w` is implicitly declared as a net [-Wimplicit-net]
+// CHECK-ERR: warning: `v` is implicitly declared as a net [-Wimplicit-net]
@@ -0,0 +1,40 @@
+// RUN: moore lint %s -e foo
+
+module foo (input logic [1:0] s, input logic [2:0] t, output logic [7:0] y, output logic [7:0] z, output logic [7:0] w);
+    typedef enum logic [1:0] { IDLE, RUN, DONE } state_t;
+    state_t state;
+    assign state = state_t'(s);
+
+    always_comb begin
+        y = 0;
+        case (state)
+            IDLE: y = 1;
+            RUN: y = 2;
+        endcase
+    end
+
+    always_comb begin
+        z = 0;
+        case (t)
+            3'd0: z = 1;
+            3'd5: z = 2;
+        endcase
+    end
+
+    // Complete cases and cases with a `default` are fine.
+    always_comb begin
+        case (s)
+            0, 1: w = 1;
+            2, 3: w = 2;
+        endcase
+        case (state)
+            IDLE: w = 3;
+            default: w = 4;
+        endcase
+    end
+endmodule
+
+// CHECK-ERR: warning: `case` statement does not cover all enumerators of `state` and has no `default` [-Wincomplete-case]
+// CHECK-ERR: = note: Missing enumerators: `DONE`
+// CHECK-ERR: warning: `case` statement does not cover all values of `t` and has no `default` [-Wincomplete-case]
+// CHECK-ERR: = note: Missing values: `3'd1`, `3'd2`, `3'd3`, `3'd4`, `3'd6`, `3'd7`
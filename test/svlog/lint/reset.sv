@@ -0,0 +1,42 @@
+// RUN: moore lint %s -e foo -Wno-unused --lint-option reset.required=true
+
+module foo (
+    input logic clk,
+    input logic rst_n,
+    input logic srst,
+    input logic en,
+    input logic [3:0] d
+);
+    logic [3:0] a, b, c, e, f;
+
+    // The first reset sets the style of the module.
+    always_ff @(posedge clk, negedge rst_n)
+        if (!rst_n) a <= '0;
+        else a <= d;
+
+    // Resets of a different style are reported.
+    always_ff @(posedge clk)
+        if (srst) b <= '0;
+        else b <= d;
+
+    // An enable is not a reset.
+    always_ff @(posedge clk)
+        if (en) c <= d;
+        else c <= a;
+
+    // Registers missing from the reset branch have no reset.
+    always_ff @(posedge clk, negedge rst_n)
+        if (!rst_n) begin
+            e <= '0;
+        end else begin
+            e <= d;
+            f <= e;
+        end
+endmodule
+
+// CHECK: warning: `srst` is a synchronous active-high reset, unlike the asynchronous active-low reset `rst_n` of module `foo` [-Wreset]
+// CHECK: 19 |         if (srst) b <= '0;
+// CHECK: warning: register `c` has no reset [-Wreset]
+// CHECK: 10 |     logic [3:0] a, b, c, e, f;
+// CHECK: warning: register `f` has no reset [-Wreset]
+// CHECK: 10 |     logic [3:0] a, b, c, e, f;
@@ -0,0 +1,19 @@
+// RUN: moore lint %s -e foo -Wno-unused --lint-option reset.style=sync --lint-option reset.polarity=low
+
+module foo (
+    input logic clk,
+    input logic rst_n,
+    input logic [3:0] d
+);
+    logic [3:0] a, b;
+
+    always_ff @(posedge clk)
+        if (!rst_n) a <= '0;
+        else a <= d;
+
+    always_ff @(posedge clk, negedge rst_n)
+        if (!rst_n) b <= '0;
+        else b <= d;
+endmodule
+
+// CHECK: warning: `rst_n` is an asynchronous active-low reset, but resets must be synchronous active-low [-Wreset]
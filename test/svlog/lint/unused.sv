@@ -0,0 +1,33 @@
+// RUN: moore lint %s -e foo
+
+module foo #(parameter int W = 4, parameter int UNUSED_P = 1, localparam int L = W * 2) (
+    input logic clk,
+    input logic a,
+    input logic b,
+    (* unused *) input logic c,
+    output logic [L-1:0] x,
+    output logic y,
+    output logic z
+);
+    logic t, u, v;
+    (* unused *) logic w;
+    logic init_only = 1;
+    assign t = a;
+    always_ff @(posedge clk) x <= {L{t}};
+    bar #(.N(W)) i0 (.i(u), .o(y));
+    g: if (1) begin : g
+        logic q;
+        assign q = b;
+    end
+    assign z = g.q;
+endmodule
+
+module bar #(parameter int N = 0) (input logic i, output logic o, input d);
+    assign o = d;
+endmodule
+
+// CHECK-ERR: warning: input port `i` is never read [-Wunused]
+// CHECK-ERR: warning: parameter `N` is never used [-Wunused]
+// CHECK-ERR: warning: `v` is never used [-Wunused]
+// CHECK-ERR: warning: `init_only` is assigned but never read [-Wunused]
+// CHECK-ERR: warning: parameter `UNUSED_P` is never used [-Wunused]
@@ -0,0 +1,48 @@
+// RUN: moore lint %s -e foo -Wno-unused
+
+module foo (
+    input logic clk,
+    input logic rst_n,
+    input logic [1:0] sel,
+    input logic [3:0] a
+);
+    localparam logic [3:0] UNKNOWN = 4'bxxxx;
+    logic [3:0] b, c, d, e;
+
+    // X is fine as a default assignment and in a `default` arm.
+    always_comb begin
+        b = 'x;
+        case (sel)
+            2'd0: b = a;
+            2'd1: b = ~a;
+            default: b = 'x;
+        endcase
+    end
+
+    // X is fine in the reset branch.
+    always_ff @(posedge clk, negedge rst_n)
+        if (!rst_n) c <= 'x;
+        else c <= a;
+
+    // X elsewhere is reported.
+    always_comb begin
+        if (sel == 2'd0) d = a;
+        else d = 4'b10x0;
+    end
+    assign e = UNKNOWN;
+
+    // Comparisons with X or Z.
+    logic f, g, h;
+    assign f = a == 4'b1x0x;
+    assign g = a != 'z;
+    assign h = a === 4'b1x0x;
+endmodule
+
+// CHECK: warning: `4'b10x0` assigned outside of a `default` case or reset [-Wx-assign]
+// CHECK: 30 |         else d = 4'b10x0;
+// CHECK: warning: `UNKNOWN` assigned outside of a `default` case or reset [-Wx-assign]
+// CHECK: 32 |     assign e = UNKNOWN;
+// CHECK: warning: comparison with `4'b1x0x` using `==` is never true [-Wx-compare]
+// CHECK: 36 |     assign f = a == 4'b1x0x;
+// CHECK: warning: comparison with `'z` using `!=` is never true [-Wx-compare]
+// CHECK: 37 |     assign g = a != 'z;
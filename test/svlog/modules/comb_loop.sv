@@ -0,0 +1,26 @@
+// RUN: moore %s -e foo
+
+module foo (input logic a, output logic y);
+    logic b, c, d, e;
+    assign b = a & d;
+    assign c = ~b;
+    always_comb begin
+        d = c | a;
+        e = d;
+    end
+    assign y = e;
+    logic f;
+    assign f = f ^ a;
+    logic g, h;
+    always_comb begin
+        g = a;
+        h = g;
+    end
+endmodule
+
+// CHECK-ERR: warning: combinational loop through `b` -> `c` -> `d` -> `b` [-Wcomb-loop]
+// CHECK-ERR: = note: `b` drives `c` here:
+// CHECK-ERR: = note: `c` drives `d` here:
+// CHECK-ERR: = note: `d` drives `b` here:
+// CHECK-ERR: warning: combinational loop through `f` -> `f` [-Wcomb-loop]
+// CHECK-ERR: = note: `f` drives `f` here:
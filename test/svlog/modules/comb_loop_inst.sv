@@ -0,0 +1,21 @@
+// RUN: moore %s -e foo
+
+module foo (input logic a, output logic y);
+    logic x, z;
+    pass p0 (.i(x), .o(z));
+    assign x = z ^ a;
+    assign y = x;
+    logic q, r;
+    pass p1 (.i(q), .o(r));
+    always_ff @(posedge a) q <= r;
+endmodule
+
+module pass (input logic i, output logic o);
+    logic t;
+    assign t = ~i;
+    assign o = ~t;
+endmodule
+
+// CHECK-ERR: warning: combinational loop through `x` -> `z` -> `x` [-Wcomb-loop]
+// CHECK-ERR: = note: `x` drives `z` here:
+// CHECK-ERR: = note: `z` drives `x` here:
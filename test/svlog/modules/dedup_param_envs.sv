@@ -0,0 +1,17 @@
+// RUN: moore %s -e foo
+
+module foo;
+    bar #(.N(4)) a();
+    bar #(.N(8)) b();
+    bar #(.N(16)) c();
+endmodule
+
+module bar #(parameter int N = 2);
+    logic s;
+    logic [N-1:0] x;
+    always_comb if (s) x = '0;
+endmodule
+
+// CHECK-ERR: warning: `x` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
+// CHECK-ERR: = note: `x` is not assigned if the condition is false
+// CHECK-ERR: = note: Occurs in 3 parameterizations: `bar #(N = 4)`, `bar #(N = 8)`, `bar #(N = 16)`
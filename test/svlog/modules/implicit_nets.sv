@@ -0,0 +1,18 @@
+// RUN: moore %s -e foo -O0
+
+module foo (input logic a, input logic b, output logic y);
+    assign w = a & b;
+    bar i0 (.i(w), .o(v));
+    assign y = v;
+endmodule
+
+module bar (input logic i, output logic o);
+    assign o = ~i;
+endmodule
+
+// CHECK: entity @foo (i1$ %a, i1$ %b) -> (i1$ %y) {
+// CHECK:     %0 = const i1 0
+// CHECK:     %w = sig i1 %0
+// CHECK:     %1 = const i1 0
+// CHECK:     %v = sig i1 %1
+// CHECK:     inst @bar.param1 (i1$ %w) -> (i1$ %v)
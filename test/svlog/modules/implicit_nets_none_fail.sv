@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo
+// FAIL
+
+`default_nettype none
+
+module foo (input logic a, input logic b, output logic y);
+    assign w = a & b;
+    assign y = b;
+endmodule
+
+// CHECK-ERR: error[SV0001]: `w` not found
+// CHECK-ERR: = note: Implicit nets are disallowed by `default_nettype none`:
@@ -0,0 +1,35 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo (input logic clk, input logic a, output logic y);
+    logic b, c;
+    wire w;
+    logic [1:0] v;
+
+    assign b = a;
+    always_comb b = ~a;
+    // CHECK-ERR: error[SV0038]: `b` is driven by multiple processes or assignments
+    // CHECK-ERR: |           ^ declared here
+    // CHECK-ERR: |            ----- driven by a continuous assignment here
+    // CHECK-ERR: 10 |     always_comb b = ~a;
+    // CHECK-ERR: |                 ------- driven by an `always_comb` procedure here
+
+    always_ff @(posedge clk) c <= a;
+    always_ff @(posedge clk) c <= b;
+    // CHECK-ERR: error[SV0038]: `c` is driven by multiple processes or assignments
+    // CHECK-ERR: |              ^ declared here
+    // CHECK-ERR: 17 |     always_ff @(posedge clk) c <= a;
+    // CHECK-ERR: |                              ------- driven by an `always_ff` procedure here
+    // CHECK-ERR: 18 |     always_ff @(posedge clk) c <= b;
+    // CHECK-ERR: |                              ------- driven by an `always_ff` procedure here
+
+    // Nets and distinct bits may have multiple drivers.
+    assign w = a;
+    assign w = b;
+    assign v[0] = a;
+    always_comb v[1] = b;
+
+    // Plain `always` procedures may share a variable.
+    always @(posedge clk) y <= a;
+    always @(negedge clk) y <= b;
+endmodule
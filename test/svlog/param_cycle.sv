@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    localparam int P = f();
+    localparam int Q = P + 1;
+    // CHECK-ERR: error[SV0040]: parameter `P` depends on its own value
+    // CHECK-ERR: = note: The cycle is `P` -> `Q` -> `P`
+    function int f();
+        return Q * 2;
+    endfunction
+    int x = P;
+endmodule
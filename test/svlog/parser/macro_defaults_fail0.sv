@@ -4,4 +4,4 @@
 
 `define MACRO1(a=5,b="B",c) $display(a,,b,,c);
 `MACRO1 ( 1 )
-// CHECK: fatal: macro expansion missing value for `c`
+// CHECK: fatal[SV0022]: macro expansion missing value for `c`
@@ -4,4 +4,4 @@
 
 `define MACRO3(a=5, b=0, c="C") $display(a,,b,,c);
 `MACRO3
-// CHECK: fatal: expected macro arguments for `MACRO3`
+// CHECK: fatal[SV0022]: expected macro arguments for `MACRO3`
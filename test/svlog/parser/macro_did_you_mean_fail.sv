@@ -0,0 +1,10 @@
+// RUN: moore %s
+// FAIL
+`define WIDTH 8
+
+module foo;
+  logic [`WIDHT-1:0] x;
+endmodule
+
+// CHECK-ERR: fatal[SV0020]: unknown compiler directive '`WIDHT'
+// CHECK-ERR: = note: did you mean `WIDTH`?
@@ -4,4 +4,4 @@
 
 `define D(x,y) initial $display("start", x , y, "end");
 `D()
-// CHECK: fatal: macro expansion missing value for `y`
+// CHECK: fatal[SV0022]: macro expansion missing value for `y`
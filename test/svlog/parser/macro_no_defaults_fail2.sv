@@ -4,4 +4,4 @@
 
 `define D(x,y) initial $display("start", x , y, "end");
 `D(,,)
-// CHECK: fatal: macro expansion with 3 arguments, but `D` expects 2 arguments
+// CHECK: fatal[SV0022]: macro expansion with 3 arguments, but `D` expects 2 arguments
@@ -2,4 +2,4 @@
 // FAIL
 `define foo(x)
 `foo (a,b)
-// CHECK: fatal: macro expansion with 2 arguments, but `foo` expects 1 arguments
+// CHECK: fatal[SV0022]: macro expansion with 2 arguments, but `foo` expects 1 arguments
@@ -0,0 +1,29 @@
+// RUN: moore %s
+// FAIL
+
+module foo (input logic a, output logic y, output logic z);
+  assign y = a
+  logic b;
+  assign z = a +;
+  always_comb begin
+    b = ~a;
+    b = ;
+    b = a;
+  end
+  logic q
+endmodule
+
+module bar;
+  logic x
+endmodule
+
+// CHECK-ERR: error[SV0016]: expected , or ; after continuous assignment
+// CHECK-ERR: 6 |   logic b;
+// CHECK-ERR: error[SV0016]: expected expression, found `;` instead
+// CHECK-ERR: 7 |   assign z = a +;
+// CHECK-ERR: error[SV0016]: expected statement, found `identifier` instead
+// CHECK-ERR: 10 |     b = ;
+// CHECK-ERR: error[SV0016]: expected , or ; after variable name
+// CHECK-ERR: 14 | endmodule
+// CHECK-ERR: error[SV0016]: expected , or ; after variable name
+// CHECK-ERR: 18 | endmodule
@@ -3,5 +3,5 @@
 module foo (
     input .a(x)
 );
-    // CHECK: error: `x` not found in module `foo`
+    // CHECK: error[SV0001]: `x` not found in module `foo`
 endmodule
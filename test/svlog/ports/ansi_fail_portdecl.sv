@@ -2,5 +2,5 @@
 // FAIL
 module foo(input a);
     input a;
-    // CHECK: error: port declaration in body of ANSI-style module
+    // CHECK: error[SV0024]: port declaration in body of ANSI-style module
 endmodule
@@ -1,5 +1,5 @@
 // RUN: moore %s -e foo
 // FAIL
 module foo(input a, input a);
-    // CHECK: error: port `a` declared multiple times
+    // CHECK: error[SV0025]: port `a` declared multiple times
 endmodule
@@ -5,8 +5,8 @@ module foo(a, b);
     input logic b;
 
     wire a;
-    // CHECK: error: port `a` is complete; additional declaration forbidden
+    // CHECK: error[SV0025]: port `a` is complete; additional declaration forbidden
 
     logic b;
-    // CHECK: error: port `b` is complete; additional declaration forbidden
+    // CHECK: error[SV0025]: port `b` is complete; additional declaration forbidden
 endmodule
@@ -1,5 +1,5 @@
 // RUN: moore %s -e foo
 // FAIL
 module foo(a[$]);
-    // CHECK: error: invalid port range `[$]`; on port `a`
+    // CHECK: error[SV0025]: invalid port range `[$]`; on port `a`
 endmodule
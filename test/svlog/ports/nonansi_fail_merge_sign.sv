@@ -3,9 +3,9 @@
 module foo(a, b);
     input signed a;
     logic unsigned a;
-    // CHECK: error: port `a` has contradicting signs
+    // CHECK: error[SV0025]: port `a` has contradicting signs
 
     input signed b;
     wire unsigned b;
-    // CHECK: error: port `b` has contradicting signs
+    // CHECK: error[SV0025]: port `b` has contradicting signs
 endmodule
@@ -1,5 +1,5 @@
 // RUN: moore %s -e foo
 // FAIL
 module foo(a);
-    // CHECK: error: port `a` not declared in module body
+    // CHECK: error[SV0025]: port `a` not declared in module body
 endmodule
@@ -2,5 +2,5 @@
 // FAIL
 
 module mh4 (var x);
-    // CHECK: error: inout port `x` must be a net; but is declared as variable
+    // CHECK: error[SV0025]: inout port `x` must be a net; but is declared as variable
 endmodule
@@ -0,0 +1,57 @@
+module A #(real f = 2.5e3, shortreal r = 0.25);
+	B #(f * r) b1();
+	B #($rtoi(f / 3.0)) b2();
+	B #(f > 1e3) b3();
+	B #(2.0 ** 10) b4();
+	B #($realtobits(1.0) >> 52) b5();
+	B #($shortrealtobits(r) >> 23) b6();
+	B #($bitstoreal(64'h4004000000000000) * 2) b7();
+	B #($itor(-3) - 0.5) b8();
+endmodule
+
+module B #(int K);
+	int x = K;
+endmodule
+
+//| entity @B.param1 () () {
+//|     %x = sig i32 625
+//| }
+//|
+//| entity @B.param2 () () {
+//|     %x = sig i32 833
+//| }
+//|
+//| entity @B.param3 () () {
+//|     %x = sig i32 1
+//| }
+//|
+//| entity @B.param4 () () {
+//|     %x = sig i32 1024
+//| }
+//|
+//| entity @B.param5 () () {
+//|     %x = sig i32 1023
+//| }
+//|
+//| entity @B.param6 () () {
+//|     %x = sig i32 125
+//| }
+//|
+//| entity @B.param7 () () {
+//|     %x = sig i32 5
+//| }
+//|
+//| entity @B.param8 () () {
+//|     %x = sig i32 -4
+//| }
+//|
+//| entity @A () () {
+//|     %b1 = inst @B.param1 () ()
+//|     %b2 = inst @B.param2 () ()
+//|     %b3 = inst @B.param3 () ()
+//|     %b4 = inst @B.param4 () ()
+//|     %b5 = inst @B.param5 () ()
+//|     %b6 = inst @B.param6 () ()
+//|     %b7 = inst @B.param7 () ()
+//|     %b8 = inst @B.param8 () ()
+//| }
@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// Real arithmetic is only supported in constant expressions, since LLHD has no
+// floating-point operations.
+module foo (input real a, output real y);
+    assign y = a * 2.0;
+endmodule
+
+// CHECK-ERR: error[SV0045]: real arithmetic is only supported on constant values
+// CHECK-ERR: 7 |     assign y = a * 2.0;
+// CHECK-ERR: = note: LLHD has no floating-point operations; real values can only be used in constant expressions, such as parameters
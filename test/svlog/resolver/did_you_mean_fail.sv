@@ -0,0 +1,19 @@
+// RUN: moore %s -e foo -e bar
+// FAIL
+module fifo_ctrl;
+endmodule
+
+module foo;
+  logic data_valid;
+  logic y;
+  assign y = data_vaild;
+endmodule
+
+module bar;
+  fifo_ctlr i_fifo ();
+endmodule
+
+// CHECK-ERR: error[SV0001]: `data_vaild` not found
+// CHECK-ERR: = note: did you mean `data_valid`?
+// CHECK-ERR: error[SV0001]: `fifo_ctlr` not found
+// CHECK-ERR: = note: did you mean `fifo_ctrl`?
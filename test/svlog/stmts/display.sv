@@ -0,0 +1,43 @@
+// RUN: moore %s -e foo --format mlir
+
+module foo (input logic [7:0] a, input int b);
+    initial begin
+        $display("a = %d %h %o %b%%", a, a, a, a);
+        $write("b = %0d", b);
+        $display("x", 4'd3, , "y");
+        $displayh(a, " ", 12);
+        $display("%c%c|%5s|%3d|%0.2f %e", 8'h41, 66, "hi", 7, 3.14159, 12345.678);
+        $display("tab\tquote\"");
+        $display;
+    end
+endmodule
+
+// CHECK: moore.procedure initial {
+// CHECK: %0 = moore.fmt.literal "a = "
+// CHECK: %1 = moore.fmt.int decimal %a, width 3, align right, pad space : l8
+// CHECK: %2 = moore.fmt.literal " "
+// CHECK: %3 = moore.fmt.int hex_lower %a, width 2, align right, pad zero : l8
+// CHECK: %4 = moore.fmt.literal " "
+// CHECK: %5 = moore.fmt.int octal %a, width 3, align right, pad zero : l8
+// CHECK: %6 = moore.fmt.literal " "
+// CHECK: %7 = moore.fmt.int binary %a, width 8, align right, pad zero : l8
+// CHECK: %8 = moore.fmt.literal "%\n"
+// CHECK: %9 = moore.fmt.concat (%0, %1, %2, %3, %4, %5, %6, %7, %8)
+// CHECK: moore.builtin.display %9
+// CHECK: %10 = moore.fmt.literal "b = "
+// CHECK: %11 = moore.fmt.int decimal %b, width 0, align right, pad space : i32
+// CHECK: %12 = moore.fmt.concat (%10, %11)
+// CHECK: moore.builtin.display %12
+// CHECK: %13 = moore.fmt.literal "x 3 y\n"
+// CHECK: moore.builtin.display %13
+// CHECK: %14 = moore.fmt.int hex_lower %a, width 2, align right, pad zero : l8
+// CHECK: %15 = moore.fmt.literal " 0000000c\n"
+// CHECK: %16 = moore.fmt.concat (%14, %15)
+// CHECK: moore.builtin.display %16
+// CHECK: %17 = moore.fmt.literal "AB|   hi|  7|3.14 1.234568e+04\n"
+// CHECK: moore.builtin.display %17
+// CHECK: %18 = moore.fmt.literal "tab\tquote\"\n"
+// CHECK: moore.builtin.display %18
+// CHECK: %19 = moore.fmt.literal "\n"
+// CHECK: moore.builtin.display %19
+// CHECK: moore.return
@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo --format mlir
+// FAIL
+
+module foo (input logic [7:0] a);
+    initial $display("%d %h", a);
+endmodule
+
+// CHECK-ERR: error[SV0028]: missing argument for format specifier `%h`
@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo --format mlir
+// FAIL
+
+module foo (input logic [7:0] a);
+    localparam real R = 1.5;
+    initial $display("%s", R);
+endmodule
+
+// CHECK-ERR: error[SV0028]: `R` cannot be printed as a string
+// CHECK-ERR: = note: `R` has type `real`
@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo --format mlir
+// FAIL
+
+module foo (input logic [7:0] a);
+    initial $display("%q", a);
+endmodule
+
+// CHECK-ERR: error[SV0028]: unknown format specifier `%q`
@@ -0,0 +1,31 @@
+// RUN: moore %s -e foo --fsm-report note
+
+module foo (input logic clk, input logic rst, input logic go);
+    typedef enum logic [1:0] { IDLE, BUSY, DONE, DEAD } state_t;
+    state_t state_q, state_d;
+
+    always_ff @(posedge clk) begin
+        if (rst)
+            state_q <= IDLE;
+        else
+            state_q <= state_d;
+    end
+
+    always_comb begin
+        state_d = state_q;
+        case (state_q)
+            IDLE: if (go) state_d = BUSY;
+            BUSY: state_d = DONE;
+            default: state_d = IDLE;
+        endcase
+    end
+endmodule
+
+// CHECK-ERR: note: found state machine with register `state_q` in `foo`
+// CHECK-ERR: = note: states: `IDLE`, `BUSY`, `DONE`, `DEAD`
+// CHECK-ERR: = note: reset: `IDLE`
+// CHECK-ERR: = note: transition `IDLE` -> `BUSY`
+// CHECK-ERR: = note: transition `BUSY` -> `DONE`
+// CHECK-ERR: = note: transition `DONE` -> `IDLE`
+// CHECK-ERR: = note: transition `DEAD` -> `IDLE`
+// CHECK-ERR: = note: unreachable: `DEAD`
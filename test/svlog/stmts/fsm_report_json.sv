@@ -0,0 +1,22 @@
+// RUN: moore %s -e bar --fsm-report json
+
+module bar (input logic clk, input logic rst);
+    logic [1:0] s;
+    always_ff @(posedge clk) begin
+        if (rst) s <= 0;
+        else case (s)
+            0: s <= 1;
+            1: s <= 2;
+            2: s <= 0;
+            3: s <= s;
+        endcase
+    end
+endmodule
+
+// CHECK: "module": "bar",
+// CHECK: "next": null,
+// CHECK: "name": "3",
+// CHECK: "reachable": false
+// CHECK: "reset": [
+// CHECK: "from": 3,
+// CHECK: "to": 3,
@@ -0,0 +1,25 @@
+// RUN: moore %s -e foo -O0
+
+// The implicit event control `@*` is sensitive to all signals read by the
+// statement, except for variables declared within it.
+module foo (input logic [7:0] a, input logic [7:0] b, input logic [1:0] s, output logic [7:0] y);
+    always @* begin
+        logic [7:0] t;
+        t = s ? a : b;
+        y = t + s;
+    end
+endmodule
+
+// CHECK: proc %foo.always.80.0 (i8$ %a, i8$ %b, i2$ %s) -> (i8$ %y) {
+// CHECK: init:
+// CHECK:     %a.prb = prb i8$ %a
+// CHECK:     %b.prb = prb i8$ %b
+// CHECK:     %s.prb = prb i2$ %s
+// CHECK:     wait %check, %a, %b, %s
+// CHECK: check:
+// CHECK:     %impledge = neq i8 %a.prb, %a.prb1
+// CHECK:     %impledge1 = neq i8 %b.prb, %b.prb1
+// CHECK:     %impledge2 = neq i2 %s.prb, %s.prb1
+// CHECK:     br %event_or1, %event, %init
+// CHECK: event:
+// CHECK:     %t = var i8 %1
@@ -0,0 +1,36 @@
+// RUN: moore %s -e foo -O0
+
+module foo (input logic clk);
+    logic [7:0] q;
+    logic [7:0] r = 8'h01;
+    logic [7:0] s;
+    int k;
+
+    initial begin
+        q = 8'h42;
+        r = r + 1;
+        k = 9001;
+    end
+
+    initial begin
+        s[0] = 1;
+    end
+
+    always_ff @(posedge clk) q <= q + 1;
+endmodule
+
+// CHECK: proc %foo.initial.67.0 () -> (i8$ %q, i8$ %r, i32$ %k) {
+// CHECK:     %1 = prb i8$ %r
+// CHECK:     drv i8$ %r, %8, %9
+// CHECK:     %10 = const i32 9001
+// CHECK:     drv i32$ %k, %10, %11
+// CHECK: proc %foo.initial.87.0 () -> (i8$ %s) {
+// CHECK: entity @foo (i1$ %clk) -> () {
+// CHECK:     %0 = const i8 66
+// CHECK:     %q = sig i8 %0
+// CHECK:     %1 = const i8 1
+// CHECK:     %r = sig i8 %1
+// CHECK:     %2 = const i8 0
+// CHECK:     %s = sig i8 %2
+// CHECK:     %3 = const i32 0
+// CHECK:     %k = sig i32 %3
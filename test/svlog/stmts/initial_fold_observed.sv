@@ -0,0 +1,32 @@
+// RUN: moore %s -e foo -O0
+
+// Variables read by an event control or a continuous assignment keep their
+// assignment at time zero, such that the value change is observed.
+module foo (output logic [7:0] y, output logic [7:0] z);
+    logic [7:0] a;
+    logic [7:0] b;
+    logic [7:0] c;
+
+    initial begin
+        c = 8'd3;
+        a = 8'd1;
+        b = 8'd2;
+    end
+
+    always @(a) y = a + c;
+    assign z = b;
+endmodule
+
+// CHECK: proc %foo.initial.66.0 () -> (i8$ %a, i8$ %b, i8$ %c) {
+// CHECK:     %1 = const i8 1
+// CHECK:     drv i8$ %a, %1, %2
+// CHECK:     %3 = const i8 2
+// CHECK:     drv i8$ %b, %3, %4
+// CHECK:     halt
+// CHECK: entity @foo () -> (i8$ %y, i8$ %z) {
+// CHECK:     %0 = const i8 0
+// CHECK:     %a = sig i8 %0
+// CHECK:     %1 = const i8 0
+// CHECK:     %b = sig i8 %1
+// CHECK:     %2 = const i8 3
+// CHECK:     %c = sig i8 %2
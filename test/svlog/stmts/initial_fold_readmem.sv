@@ -0,0 +1,27 @@
+// RUN: moore %s -e foo -O0
+
+module foo;
+    logic [7:0] mem [0:7];
+    logic [7:0] ptr;
+
+    initial begin
+        ptr = 8'd3;
+        $readmemh("readmemh.hex", mem);
+        mem[ptr] = 8'h55;
+    end
+endmodule
+
+// CHECK: proc %foo.initial.71.0 () -> ([8 x i8]$ %mem, i8$ %ptr) {
+// CHECK:     %1 = prb i8$ %ptr
+// CHECK:     %7 = const i8 85
+// CHECK:     drv i8$ %6, %7, %8
+// CHECK:     halt
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i8 1
+// CHECK:     %1 = const i8 171
+// CHECK:     %4 = const i8 16
+// CHECK:     %6 = const i8 255
+// CHECK:     %8 = [i8 %0, %1, %2, %3, %4, %5, %6, %7]
+// CHECK:     %mem = sig [8 x i8] %8
+// CHECK:     %9 = const i8 3
+// CHECK:     %ptr = sig i8 %9
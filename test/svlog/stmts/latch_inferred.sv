@@ -0,0 +1,38 @@
+// RUN: moore %s -e foo
+
+module foo (input logic a, input logic b, input logic [1:0] s, output logic y, output logic z);
+    logic q, r, t, v;
+
+    always_comb begin
+        if (a) y = b;
+        // CHECK-ERR: warning: `y` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
+        // CHECK-ERR: = note: `y` is not assigned if the condition is false
+        z = 0;
+        if (a) q = 1; else q = 0;
+    end
+
+    always_comb begin
+        case (s)
+            0: r = a;
+            1: r = b;
+            2: r = a;
+        endcase
+        // CHECK-ERR: warning: `r` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
+        // CHECK-ERR: = note: `r` is not assigned if none of the cases match; consider adding a `default`
+        case (s)
+            0: t = a;
+            1: t = b;
+            2: t = a;
+            3: t = b;
+        endcase
+        if (a) begin
+            v = 1;
+        end else if (b) begin
+            r = 1;
+        end else begin
+            v = 0;
+        end
+        // CHECK-ERR: warning: `v` is not assigned on all paths through this `always_comb`; a latch will be inferred [-Wlatch]
+        // CHECK-ERR: = note: `v` is not assigned in this branch
+    end
+endmodule
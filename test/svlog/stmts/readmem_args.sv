@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [7:0] mem [0:3];
+    initial $readmemh("readmemh.hex");
+endmodule
+
+// CHECK-ERR: error[SV0027]: `readmemh` takes two to four arguments
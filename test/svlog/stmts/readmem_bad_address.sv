@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [7:0] mem [0:7];
+    initial $readmemh("readmem_bad_address.hex", mem, 0, 3);
+endmodule
+
+// CHECK-ERR: error[SV0044]: address 9 is outside the loaded address range 0:3
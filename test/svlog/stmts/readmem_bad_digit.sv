@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [7:0] mem [0:3];
+    initial $readmemh("readmem_bad_digit.hex", mem);
+endmodule
+
+// CHECK-ERR: error[SV0044]: `g` is not a valid hexadecimal digit
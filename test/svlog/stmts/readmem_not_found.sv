@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [7:0] mem [0:3];
+    initial $readmemh("missing.hex", mem);
+endmodule
+
+// CHECK-ERR: error[SV0044]: memory file `missing.hex` not found
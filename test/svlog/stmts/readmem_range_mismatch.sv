@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo
+
+module foo;
+    logic [3:0] mem [8];
+    initial $readmemb("readmemb.bin", mem, 7, 4);
+endmodule
+
+// CHECK-ERR: warning: memory file `readmemb.bin` has 2 words, but the loaded address range 7:4 has 4 [-Wreadmem]
@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    logic [3:0] mem [0:3];
+    initial $readmemh("readmemh.hex", mem);
+endmodule
+
+// CHECK-ERR: error[SV0044]: word `ab` does not fit into 4 bits
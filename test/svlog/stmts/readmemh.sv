@@ -0,0 +1,42 @@
+// RUN: moore %s -e foo
+
+module foo;
+    logic [7:0] mem [0:7];
+    logic [3:0] nib [4];
+    initial begin
+        $readmemh("readmemh.hex", mem);
+        $readmemb("readmemb.bin", nib, 3, 2);
+    end
+    initial begin
+        #1ns;
+        $readmemh("readmemh.hex", mem, 2);
+    end
+endmodule
+
+// CHECK: proc %foo.initial.60.0 () -> ([8 x i8]$ %mem, [4 x i4]$ %nib) {
+// CHECK: 0:
+// CHECK:     halt
+// CHECK: proc %foo.initial.87.0 () -> ([8 x i8]$ %mem) {
+// CHECK:     %3 = const i96 35403852549520488124815795576
+// CHECK:     %4 = const i32 0
+// CHECK:     %5 = const i32 7
+// CHECK:     %6 = const i32 2
+// CHECK:     %7 = call void @moore.readmemh.i96.[8 x i8]$.i32.i32 (i96 %3, [8 x i8]$ %mem, i32 %6, i32 %5)
+// CHECK: entity @foo () -> () {
+// CHECK:     %0 = const i8 1
+// CHECK:     %1 = const i8 171
+// CHECK:     %2 = const i8 0
+// CHECK:     %3 = const i8 0
+// CHECK:     %4 = const i8 16
+// CHECK:     %5 = const i8 0
+// CHECK:     %6 = const i8 255
+// CHECK:     %7 = const i8 0
+// CHECK:     %8 = [i8 %0, %1, %2, %3, %4, %5, %6, %7]
+// CHECK:     %mem = sig [8 x i8] %8
+// CHECK:     %9 = const i4 0
+// CHECK:     %10 = const i4 0
+// CHECK:     %11 = const i4 5
+// CHECK:     %12 = const i4 10
+// CHECK:     %13 = [i4 %9, %10, %11, %12]
+// CHECK:     %nib = sig [4 x i4] %13
+// CHECK: declare @moore.readmemh.i96.[8 x i8]$.i32.i32 (i96, [8 x i8]$, i32, i32) void
@@ -0,0 +1,49 @@
+entity counter is
+	generic (WIDTH : INTEGER := 4; LIMIT : INTEGER := 10);
+	port (clk : in BIT; q : out INTEGER);
+end;
+
+architecture rtl of counter is
+	signal count : INTEGER := 0;
+begin
+	-- Static conditions are checked during elaboration.
+	assert WIDTH > 0 report "WIDTH must be positive" severity failure;
+	assert LIMIT < 2 * WIDTH * WIDTH report "LIMIT does not fit into WIDTH bits";
+	assert LIMIT > 1 report "LIMIT is trivial" severity note;
+
+	-- Dynamic conditions become simulation assertions.
+	assert count <= LIMIT report "count overflow" severity error;
+
+	p_count: process (clk)
+	begin
+		if clk = '1' then
+			if count = LIMIT then
+				report "count wrapped" severity warning;
+				count <= 0;
+			else
+				count <= count + 1;
+			end if;
+		end if;
+		q <= count;
+	end process;
+end;
+
+entity top is
+end;
+
+architecture tb of top is
+	signal clk : BIT;
+	signal q : INTEGER;
+begin
+	dut: entity work.counter generic map (WIDTH => 3, LIMIT => 7) port map (clk => clk, q => q);
+
+	p_check: process
+	begin
+		wait for 10 ns;
+		assert q /= 0;
+		report "done";
+		wait;
+	end process;
+end;
+
+-- @elab top
@@ -0,0 +1,49 @@
+entity inv is
+	generic (INVERT : BOOLEAN := TRUE);
+	port (a : in BIT; y : out BIT);
+end;
+
+architecture rtl of inv is
+begin
+end;
+
+architecture other of inv is
+begin
+end;
+
+entity buf is
+	port (a : in BIT; y, y_n : out BIT);
+end;
+
+architecture rtl of buf is
+begin
+end;
+
+entity top is
+end;
+
+architecture rtl of top is
+	component inv is
+		generic (INVERT : BOOLEAN := FALSE);
+		port (a : in BIT; y : out BIT);
+	end component;
+
+	component buf
+		port (a : in BIT; y : out BIT);
+	end component;
+
+	signal a, b, c, d, e : BIT;
+
+	for i1 : inv use entity work.inv(other);
+	for others : inv use entity work.inv;
+begin
+	-- Bound by the configuration specifications above.
+	i0: component inv generic map (INVERT => TRUE) port map (a => a, y => b);
+	i1: inv port map (b, c);
+	i2: inv port map (a => c, y => d);
+
+	-- Bound by default, leaving the `y_n` port of the entity open.
+	b0: buf port map (a => d, y => e);
+end;
+
+-- @elab top
@@ -0,0 +1,72 @@
+entity inv is
+	port (a : in BIT; y : out BIT);
+end;
+
+architecture rtl of inv is
+begin
+end;
+
+architecture gate of inv is
+begin
+end;
+
+entity inv_n is
+	generic (INVERT : BOOLEAN := TRUE);
+	port (a : in BIT; y : out BIT);
+end;
+
+architecture rtl of inv_n is
+begin
+end;
+
+entity top is
+end;
+
+architecture rtl of top is
+	component inv
+		port (a : in BIT; y : out BIT);
+	end component;
+
+	signal a, b, c, d, e : BIT;
+begin
+	i0: inv port map (a => a, y => b);
+	i1: inv port map (a => b, y => c);
+
+	g0: for i in 0 to 1 generate
+		i2: inv port map (a => c, y => d);
+	end generate;
+
+	i3: inv port map (a => d, y => e);
+end;
+
+-- Selects the gate-level architecture for all instances.
+configuration top_gate of top is
+	for rtl
+		for all : inv
+			use entity work.inv(gate);
+		end for;
+		for g0
+			for i2 : inv
+				use entity work.inv(gate);
+			end for;
+		end for;
+	end for;
+end;
+
+-- Mixes architectures per instance, and binds another entity.
+configuration top_mixed of top is
+	for rtl
+		for i0 : inv
+			use entity work.inv(rtl);
+		end for;
+		for i3 : inv
+			use entity work.inv_n;
+		end for;
+		for others : inv
+			for gate
+			end for;
+		end for;
+	end for;
+end;
+
+-- @elab top_gate
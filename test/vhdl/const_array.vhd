@@ -0,0 +1,36 @@
+package pkg is
+	type COLOR is (RED, GREEN, BLUE);
+	type WORD is array (0 to 3) of INTEGER;
+	type VEC is array (NATURAL range <>) of INTEGER;
+	type MAT is array (0 to 1, 0 to 2) of INTEGER;
+	type CV is array (COLOR) of INTEGER;
+
+	-- Aggregates with positional, named, range, and `others` elements.
+	constant W0 : WORD := (1, 2, 3, 4);
+	constant W1 : WORD := (0 => 5, others => 0);
+	constant W2 : WORD := (1 to 2 => 7, others => 1);
+	constant V0 : VEC := (1, 2);
+	constant V1 : VEC := (3 => 8, 4 => 9);
+	constant C0 : CV := (GREEN => 6, others => 0);
+	constant M0 : MAT := ((1, 2, 3), (4, 5, 6));
+
+	-- Concatenations and slices.
+	constant V2 : VEC(0 to 3) := V0 & V0;
+	constant V3 : VEC(0 to 1) := W0(1 to 2);
+	constant W3 : WORD := (1, 2) & 3 & 4;
+	constant V4 : VEC(3 downto 0) := (10, 11, 12, 13);
+
+	-- Index constraints in either direction.
+	subtype VEC4 is VEC(3 downto 0);
+	type A0 is array (0 to W0(3)) of BIT;
+
+	type T0 is range W0(0) to W0(2);
+	type T1 is range 0 to W2(1) + W1(0);
+	type T2 is range V2(3) to V3(1);
+	type T3 is range V1(3) to V1(4);
+	type T4 is range 0 to C0(GREEN) + M0(1, 2);
+	type T5 is range W3(3) to V4(0);
+	type T6 is range 0 to W2(0 to 1)(1);
+end;
+
+-- @elab pkg
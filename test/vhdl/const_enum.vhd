@@ -0,0 +1,23 @@
+package pkg is
+	type COLOR is (RED, GREEN, BLUE);
+
+	constant C0 : COLOR := GREEN;
+	constant C1 : COLOR := COLOR'succ(RED);
+	constant C2 : COLOR := COLOR'pred(BLUE);
+	constant C3 : COLOR := COLOR'val(2);
+
+	type T0 is range COLOR'pos(GREEN) to COLOR'pos(COLOR'succ(GREEN));
+	type T1 is range 0 to BOOLEAN'pos(TRUE);
+	type T2 is range 0 to INTEGER'succ(4);
+	type T3 is range 0 to 2 * COLOR'pos(BLUE);
+
+	subtype S0 is COLOR range RED to GREEN;
+	subtype S1 is BOOLEAN range FALSE to FALSE;
+
+	type A0 is array (COLOR) of BIT;
+	type A1 is array (RED to GREEN) of BIT;
+	type A2 is array (COLOR'succ(RED) to COLOR'val(2)) of BIT;
+	type A3 is array (BOOLEAN) of BIT;
+end;
+
+-- @elab pkg
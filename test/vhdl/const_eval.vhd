@@ -0,0 +1,9 @@
+package pkg is
+	type NIBBLE is range 0 to 2**4 - 1;
+	type SIGNED_BYTE is range -(2**7) to 2**7 - 1;
+	type THIRDS is range 10 / 3 to (10 rem 4) * 3;
+	type MAGNITUDE is range abs (-3) to 8 mod 5;
+	type SQUARED is range 0.5 * 2.0 to 1.5 ** 2;
+end;
+
+-- @elab pkg
@@ -0,0 +1,16 @@
+package pkg is
+	type DISTANCE is range 0 to 1_000_000_000 units
+		um;
+		mm = 1000 um;
+		m = 1000 mm;
+	end units;
+	subtype SHORT is DISTANCE range 0 um to 2 mm;
+	subtype PERIOD is TIME range 10 ns to 1.5 us;
+	subtype SCALED is TIME range 2 * 5 ns to 1 us / 4 + 1 ns;
+	subtype HALVED is DISTANCE range 1.5 * 2 mm to 3 m / 2.0;
+	constant D : TIME := 10 ns;
+	constant E : TIME := 1.5 us;
+	constant F : DISTANCE := 2 m;
+end;
+
+-- @elab pkg
@@ -0,0 +1,21 @@
+package pkg is
+	type COLOR is (RED, GREEN, BLUE);
+	type PAIR is record
+		A : INTEGER;
+		B : COLOR;
+	end record;
+
+	constant P0 : PAIR := (1, GREEN);
+	constant P1 : PAIR := (A => 2, B => BLUE);
+	constant P2 : PAIR := (A => 3, others => RED);
+	constant P3 : PAIR := (4, B => GREEN);
+	constant P4 : PAIR := P0;
+
+	type T0 is range 0 to P1.A + P2.A;
+	type T1 is range P4.A to P3.A;
+	subtype S0 is COLOR range RED to P1.B;
+	subtype S1 is BOOLEAN range (P0 /= P1) to TRUE;
+	type A0 is array (P0.B to P1.B) of BIT;
+end;
+
+-- @elab pkg
@@ -0,0 +1,24 @@
+context logic_ctx is
+	library ieee;
+	use ieee.std_logic_1164.all;
+end context;
+
+context arith_ctx is
+	library ieee;
+	use ieee.numeric_std.all;
+end context arith_ctx;
+
+context work.logic_ctx, work.arith_ctx;
+entity top is
+	port (clk : in std_logic; q : out unsigned(3 downto 0));
+end;
+
+-- The context items of the entity apply to the architecture as well.
+context work.logic_ctx;
+architecture rtl of top is
+	signal s : std_ulogic_vector(3 downto 0);
+	signal n : unsigned(3 downto 0);
+begin
+end;
+
+-- @elab top
@@ -0,0 +1,40 @@
+entity counter is
+	generic (WIDTH : INTEGER := 4);
+	port (clk : in BIT);
+end;
+
+architecture rtl of counter is
+	constant LIMIT : INTEGER := WIDTH * 2;
+	signal state : BIT;
+begin
+	p_count: process (clk)
+	begin
+		if clk'event and clk = '1' then
+			state <= not state;
+		end if;
+	end process;
+end;
+
+entity top is
+end;
+
+architecture tb of top is
+	signal clk, seen : BIT;
+begin
+	dut: entity work.counter generic map (WIDTH => 8) port map (clk => clk);
+
+	-- Resolved during elaboration, since constants have a static value.
+	assert << constant dut.LIMIT : INTEGER >> = 16;
+	assert << constant .top.dut.WIDTH : INTEGER >> = 8;
+
+	-- Observes the internal state of the counter.
+	p_check: process
+	begin
+		clk <= '1' after 5 ns;
+		wait for 10 ns;
+		seen <= << signal .top.dut.state : BIT >>;
+		wait;
+	end process;
+end;
+
+-- @elab top
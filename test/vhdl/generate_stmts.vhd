@@ -0,0 +1,54 @@
+entity stage is
+	generic (INDEX : INTEGER := 0);
+	port (d : in BIT_VECTOR(INDEX downto 0); q : out BIT);
+end;
+
+architecture rtl of stage is
+begin
+end;
+
+entity top is
+	generic (N : INTEGER := 4; MODE : INTEGER := 1);
+end;
+
+architecture rtl of top is
+	type COLOR is (RED, GREEN, BLUE);
+	signal s : BIT_VECTOR(N-1 downto 0);
+begin
+	g0: for i in 0 to N-1 generate
+		signal t : BIT_VECTOR(i downto 0);
+	begin
+		u: entity work.stage generic map (INDEX => i) port map (d => t);
+	end generate;
+
+	g1: for c in COLOR generate
+		g2: if c = GREEN generate
+			p: process
+			begin
+				wait;
+			end process;
+		end generate;
+	end generate;
+
+	g3: if N > 8 generate
+		signal t : BIT_VECTOR(N-9 downto 0);
+	begin
+	elsif N > 2 generate
+		u: entity work.stage generic map (INDEX => N-3);
+	else generate
+	end generate;
+
+	g4: case MODE generate
+		when 0 => a0: entity work.stage;
+		when 1 | 2 => a1: entity work.stage generic map (INDEX => MODE);
+		when others =>
+	end generate;
+
+	g5: for i in 3 downto 1 generate
+		g6: for j in 1 to i generate
+			u: entity work.stage generic map (INDEX => i * j);
+		end generate;
+	end generate;
+end;
+
+-- @elab top
@@ -0,0 +1,26 @@
+entity reg is
+    generic (WIDTH : INTEGER := 8);
+    port (
+        D : in BIT_VECTOR(WIDTH-1 downto 0);
+        Q : out BIT_VECTOR(WIDTH-1 downto 0)
+    );
+end entity;
+
+architecture rtl of reg is
+    signal S : BIT_VECTOR(WIDTH-1 downto 0);
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+    signal A, B : BIT_VECTOR(3 downto 0);
+    signal C, E : BIT_VECTOR(7 downto 0);
+begin
+    R0: entity work.reg generic map (WIDTH => 4) port map (D => A, Q => B);
+    R1: entity work.reg generic map (4) port map (A, B);
+    R2: entity work.reg port map (D => C, Q => E);
+end architecture;
+
+-- @elab top
@@ -0,0 +1,35 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity top is
+    generic (N : natural := 8);
+    port (
+        a : in unsigned(N-1 downto 0);
+        b : in ieee.numeric_std.signed(N-1 downto 0);
+        q : out std_logic_vector(N-1 downto 0)
+    );
+end entity;
+
+architecture rtl of top is
+    constant A0 : unsigned(N-1 downto 0) := to_unsigned(200, N);
+    constant B0 : unsigned(N-1 downto 0) := to_unsigned(100, N);
+    constant SUM : unsigned(N-1 downto 0) := A0 + B0;
+    constant PROD : unsigned(2*N-1 downto 0) := A0 * B0;
+    constant DIFF : unsigned(N-1 downto 0) := A0 - 1;
+    constant NEG : signed(7 downto 0) := to_signed(-5, 8);
+    constant SMALL : signed(3 downto 0) := resize(NEG, 4);
+    constant ABSV : signed(7 downto 0) := abs NEG;
+    constant HALF : signed(7 downto 0) := shift_right(NEG, 1);
+    constant REM0 : signed(7 downto 0) := NEG rem 2;
+    constant MOD0 : signed(7 downto 0) := NEG mod 3;
+    constant GT : boolean := A0 > B0;
+    constant LT : boolean := NEG < 0;
+    constant LEN : integer := to_integer(SUM);
+    signal s : bit_vector(0 to LEN);
+    signal t : unsigned(N-1 downto 0) := DIFF;
+    signal u : signed(3 downto 0) := SMALL;
+begin
+end architecture;
+
+-- @elab top
@@ -0,0 +1,28 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+    port (
+        clk : in std_logic;
+        d : in std_logic_vector(7 downto 0);
+        q : out std_ulogic_vector(7 downto 0);
+        e : out ieee.std_logic_1164.std_ulogic
+    );
+end entity;
+
+architecture rtl of top is
+    constant INIT : std_logic_vector(3 downto 0) := "01ZX";
+    constant H : std_logic := 'H';
+    constant K : std_logic_vector(0 to 3) := (others => '-');
+    constant B : BIT_VECTOR(3 downto 0) := "0110";
+    constant Z : BIT := '0';
+    constant W : std_logic := '0';
+    signal s : std_logic;
+    signal t : std_logic_vector(3 downto 0) := INIT;
+    signal u : X01 := W;
+    signal v : BIT := Z;
+    signal x : std.standard.BIT_VECTOR(3 downto 0) := B;
+begin
+end architecture;
+
+-- @elab top
@@ -0,0 +1,69 @@
+package pkg is
+	type MVL is ('0', '1', 'Z', 'X');
+	type COUNT is range 0 to 15;
+
+	function "+" (l, r : MVL) return MVL;
+	function "+" (l : COUNT; r : MVL) return COUNT;
+	function "not" (v : MVL) return MVL;
+	function "=" (l, r : COUNT) return BOOLEAN;
+end;
+
+package body pkg is
+	function "+" (l, r : MVL) return MVL is
+	begin
+		if l = '1' or r = '1' then
+			return '1';
+		elsif l = 'Z' then
+			return r;
+		elsif r = 'Z' then
+			return l;
+		end if;
+		return '0';
+	end;
+
+	function "+" (l : COUNT; r : MVL) return COUNT is
+	begin
+		if r = '1' then
+			return l + 1;
+		end if;
+		return l;
+	end;
+
+	function "not" (v : MVL) return MVL is
+	begin
+		case v is
+			when '0' => return '1';
+			when '1' => return '0';
+			when others => return 'X';
+		end case;
+	end;
+
+	-- Hides the predefined equality of COUNT.
+	function "=" (l, r : COUNT) return BOOLEAN is
+	begin
+		return l < r + 1 and r < l + 1;
+	end;
+end;
+
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+use work.pkg.all;
+
+entity top is
+end;
+
+architecture rtl of top is
+	constant M0 : MVL := '0' + 'Z';
+	constant M1 : MVL := not M0;
+	constant C0 : COUNT := 3 + '1';
+	constant C1 : COUNT := C0 + 2;
+	constant B0 : BOOLEAN := C1 = 6 and M1 /= 'X';
+	constant U0 : UNSIGNED(3 downto 0) := to_unsigned(3, 4) + 1;
+	constant S0 : SIGNED(3 downto 0) := -to_signed(3, 4);
+	signal x : BIT_VECTOR(to_integer(U0) downto 0);
+	signal y : BIT_VECTOR(to_integer(S0 + 8) downto 0);
+begin
+end;
+
+-- @elab top
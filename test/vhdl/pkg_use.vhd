@@ -0,0 +1,32 @@
+package pkg is
+    constant WIDTH : INTEGER := 4;
+    type WORD is array (WIDTH-1 downto 0) of BIT;
+    constant DEPTH : INTEGER;
+    constant SIZE : INTEGER;
+end package;
+
+package body pkg is
+    constant DEPTH : INTEGER := 16;
+    constant SIZE : INTEGER := WIDTH;
+end package body;
+
+use work.pkg.all;
+
+entity top is
+    port (
+        D : in WORD;
+        Q : out BIT_VECTOR(DEPTH-1 downto 0);
+        R : out BIT_VECTOR(work.pkg.SIZE-1 downto 0)
+    );
+end entity;
+
+architecture rtl of top is
+    constant N : INTEGER := work.pkg.WIDTH;
+    type HALF is array (N/2-1 downto 0) of BIT;
+    signal S : WORD;
+    signal T : BIT_VECTOR(DEPTH-1 downto 0);
+    signal U : HALF;
+begin
+end architecture;
+
+-- @elab top
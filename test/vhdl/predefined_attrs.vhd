@@ -0,0 +1,53 @@
+package pkg is
+	type COLOR is (RED, GREEN, BLUE);
+	type T0 is range 2 to 9;
+	type A0 is array (7 downto 0) of BIT;
+	type A1 is array (COLOR, 0 to 3) of BIT;
+
+	constant V0 : BIT_VECTOR(3 downto 0) := "1010";
+	constant S0 : STRING := "hello";
+
+	constant C0 : INTEGER := A0'left;
+	constant C1 : INTEGER := A0'right;
+	constant C2 : INTEGER := A0'high;
+	constant C3 : INTEGER := A0'low;
+	constant C4 : INTEGER := A0'length;
+	constant C5 : INTEGER := A1'length(2);
+	constant C6 : COLOR := A1'high;
+	constant C7 : INTEGER := V0'length;
+	constant C8 : INTEGER := S0'length;
+	constant C9 : T0 := T0'high;
+	constant C10 : COLOR := COLOR'left;
+
+	constant I0 : STRING := INTEGER'image(42);
+	constant I1 : STRING := COLOR'image(GREEN);
+	constant I2 : STRING := BIT'image('1');
+	constant I3 : STRING := REAL'image(1.5);
+
+	type A2 is array (V0'range) of BIT;
+	type A3 is array (V0'reverse_range) of BIT;
+	subtype S1 is COLOR range COLOR'range;
+	constant V1 : BIT_VECTOR(A0'range) := (A0'low => '1', others => '0');
+end;
+
+entity top is
+	port (clk : in BIT; q : out BIT);
+end entity;
+
+architecture rtl of top is
+	signal d : BIT_VECTOR(0 to 3);
+begin
+	process
+		variable n : INTEGER;
+	begin
+		for i in d'range loop
+			n := i;
+		end loop;
+		if clk'event and clk = '1' and clk'last_value = '0' then
+			q <= '1';
+		end if;
+		wait on clk;
+	end process;
+end architecture;
+
+-- @elab pkg
@@ -0,0 +1,63 @@
+entity toggle is
+	port (clk, rst : in BIT; q : out BIT);
+end;
+
+architecture rtl of toggle is
+	signal state : BIT;
+begin
+	-- Sensitive to the clock and the asynchronous reset.
+	p_reg: process (clk, rst)
+	begin
+		if rst = '1' then
+			state <= '0';
+		elsif clk'event and clk = '1' then
+			state <= not state;
+		end if;
+	end process;
+
+	-- Sensitive to all signals read by the process.
+	p_comb: process (all)
+	begin
+		q <= state xor rst;
+	end process;
+end;
+
+entity top is
+end;
+
+architecture tb of top is
+	signal clk, rst, q : BIT;
+	signal count : INTEGER range 0 to 15;
+begin
+	dut: entity work.toggle port map (clk => clk, rst => rst, q => q);
+
+	p_clk: process
+	begin
+		clk <= '0';
+		wait for 5 ns;
+		clk <= '1';
+		wait for 5 ns;
+	end process;
+
+	p_rst: process
+	begin
+		rst <= '1';
+		wait until clk = '1';
+		wait on clk;
+		rst <= '0' after 1 ns;
+		wait;
+	end process;
+
+	p_count: process (clk)
+		variable n : INTEGER range 0 to 15 := 0;
+	begin
+		if clk = '1' and q = '1' then
+			for i in 0 to 1 loop
+				n := n + 1;
+			end loop;
+			count <= n;
+		end if;
+	end process;
+end;
+
+-- @elab top
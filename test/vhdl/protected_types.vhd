@@ -0,0 +1,50 @@
+package counters is
+	type counter_t is protected
+		procedure increment;
+		procedure add (n : INTEGER);
+		impure function get return INTEGER;
+	end protected;
+
+	shared variable events : counter_t;
+end;
+
+package body counters is
+	type counter_t is protected body
+		variable count : INTEGER := 0;
+
+		procedure increment is
+		begin
+			add(1);
+		end;
+
+		procedure add (n : INTEGER) is
+		begin
+			count := count + n;
+		end;
+
+		impure function get return INTEGER is
+		begin
+			return count;
+		end;
+	end protected body;
+end;
+
+library work;
+use work.counters.all;
+
+entity top is
+end;
+
+architecture tb of top is
+	shared variable errors : counter_t;
+begin
+	p_check: process
+	begin
+		events.increment;
+		events.add(2);
+		errors.increment;
+		assert events.get = 3 report "three events expected";
+		assert errors.get /= 0;
+		wait;
+	end process;
+end;
@@ -0,0 +1,35 @@
+entity handshake is
+	port (clk, req, ack : in BIT);
+end;
+
+architecture rtl of handshake is
+	signal busy : BIT;
+
+	-- psl default clock is clk'event and clk = '1';
+	-- psl sequence request is {req = '1'; ack = '0'[*1 to 3]};
+
+	property acknowledged is always {request} |=> {ack = '1'};
+	property exclusive is never (busy = '1' and req = '1');
+begin
+	-- psl check_ack: assert acknowledged
+	-- psl   report "request not acknowledged";
+	-- psl assume always req = '1' -> next (req = '1' until ack = '1');
+	-- psl cover {req = '1'; ack = '1'} report "handshake completed";
+
+	check_excl: assert exclusive severity warning;
+	restrict {req = '0'[*2]; req = '1'};
+
+	-- A plain condition remains a concurrent assertion.
+	assert (req or ack) = '1' or busy = '0';
+end;
+
+entity top is
+end;
+
+architecture tb of top is
+	signal clk, req, ack : BIT;
+begin
+	dut: entity work.handshake port map (clk => clk, req => req, ack => ack);
+end;
+
+-- @elab top
@@ -0,0 +1,44 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+	port (clk : in std_ulogic; q : out std_ulogic);
+end;
+
+architecture rtl of top is
+	type STATE is (IDLE, LOAD, SHIFT, DONE);
+	subtype BUSY is STATE range LOAD to SHIFT;
+	subtype COUNT is INTEGER range 10 to 12;
+	subtype OFFSET is INTEGER range -4 to 3;
+	subtype DELAY is TIME range 1 ns to 10 ns;
+
+	-- Enumerations take as many bits as the position of their last literal.
+	signal s : STATE := IDLE;
+	signal b : BUSY := LOAD;
+
+	-- Integers take as many bits as their bounds need, signed if the range
+	-- includes negative values.
+	signal c : COUNT := 12;
+	signal o : OFFSET := -4;
+
+	-- Physical values are multiples of the primary unit.
+	signal d : DELAY := 5 ns;
+	signal e : TIME;
+begin
+	process (clk)
+		variable v : COUNT := 10;
+	begin
+		if clk = '1' then
+			-- Range checks are only inserted if enabled, and only if the
+			-- value may lie outside the range of the target.
+			v := c;
+			c <= v + 1;
+			o <= o - 1;
+			b <= s;
+			e <= d;
+			q <= clk;
+		end if;
+	end process;
+end;
+
+-- @elab top
@@ -0,0 +1,48 @@
+entity dff is
+	port (clk, rst, d : in BIT; q, q_n : out BIT);
+end;
+
+architecture rtl of dff is
+begin
+	-- Only assigns signals, so the drives are enabled by the conditions.
+	p_reg: process (clk, rst)
+	begin
+		if rst = '1' then
+			q <= '0';
+		elsif clk'event and clk = '1' then
+			q <= d;
+		end if;
+	end process;
+
+	-- Samples the previous value of the clock whenever it suspends.
+	p_reg_n: process (clk)
+	begin
+		if not clk'stable and clk = '0' then
+			q_n <= not d;
+		end if;
+	end process;
+end;
+
+entity top is
+end;
+
+architecture tb of top is
+	signal clk, rst, d, q, q_n, pulse, glitch : BIT;
+begin
+	dut: entity work.dff port map (clk => clk, rst => rst, d => d, q => q, q_n => q_n);
+
+	p_stim: process
+	begin
+		clk <= '0', '1' after 5 ns;
+		rst <= transport '1', '0' after 2 ns;
+		d <= reject 0 ns inertial '1' after 3 ns;
+		pulse <= transport '1' after 1 ns, '0' after 2 ns, '1' after 4 ns;
+		glitch <= inertial '1' after 1 ns;
+		wait for 10 ns;
+		wait on clk until clk'stable;
+		wait for 0 ns;
+		wait;
+	end process;
+end;
+
+-- @elab top
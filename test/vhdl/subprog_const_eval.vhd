@@ -0,0 +1,88 @@
+package pkg is
+	function log2 (n : INTEGER) return INTEGER;
+	function onehot (n : INTEGER) return BIT_VECTOR;
+	function max (a, b : INTEGER) return INTEGER;
+	function max (a, b : BIT) return BIT;
+	function fact (n : NATURAL) return NATURAL;
+end;
+
+package body pkg is
+	function log2 (n : INTEGER) return INTEGER is
+		variable v : INTEGER := 1;
+		variable r : INTEGER := 0;
+	begin
+		while v < n loop
+			v := v * 2;
+			r := r + 1;
+		end loop;
+		return r;
+	end;
+
+	function onehot (n : INTEGER) return BIT_VECTOR is
+		variable v : BIT_VECTOR(7 downto 0) := (others => '0');
+	begin
+		for i in v'range loop
+			if i = n then
+				v(i) := '1';
+				exit;
+			end if;
+		end loop;
+		return v;
+	end;
+
+	function max (a, b : INTEGER) return INTEGER is
+	begin
+		if a > b then
+			return a;
+		else
+			return b;
+		end if;
+	end;
+
+	function max (a, b : BIT) return BIT is
+	begin
+		case a is
+			when '1' => return '1';
+			when others => return b;
+		end case;
+	end;
+
+	function fact (n : NATURAL) return NATURAL is
+	begin
+		if n = 0 then
+			return 1;
+		end if;
+		return n * fact(n - 1);
+	end;
+end;
+
+use work.pkg.all;
+
+entity foo is
+	generic (N : INTEGER := 12);
+	port (x : in BIT_VECTOR(log2(N)-1 downto 0));
+end;
+
+architecture rtl of foo is
+	constant C0 : INTEGER := max(log2(N), 3);
+	constant C1 : BIT := max('0', '1');
+	constant C2 : BIT_VECTOR(7 downto 0) := onehot(C0);
+	constant C3 : NATURAL := fact(5);
+	signal y : BIT_VECTOR(max(C0, 2) downto 0);
+begin
+end;
+
+use work.pkg.all;
+
+entity top is
+end;
+
+architecture rtl of top is
+	signal a : BIT_VECTOR(log2(16)-1 downto 0);
+	signal b : BIT_VECTOR(log2(100)-1 downto 0);
+begin
+	i0: entity work.foo generic map (N => 16) port map (x => a);
+	i1: entity work.foo generic map (N => 100) port map (x => b);
+end;
+
+-- @elab top
@@ -0,0 +1,29 @@
+use std.textio.all;
+
+entity top is
+end;
+
+architecture tb of top is
+	signal done : BIT;
+begin
+	p_main: process
+		variable l : LINE;
+		variable count : INTEGER := 0;
+	begin
+		for i in 1 to 3 loop
+			count := count + i;
+			write(l, string'("count = "));
+			write(l, count, RIGHT, 4);
+			writeline(OUTPUT, l);
+		end loop;
+		write(l, BIT_VECTOR'("1010"));
+		write(l, ' ');
+		hwrite(l, BIT_VECTOR'("1010"));
+		write(l, 10 ns, LEFT, 8);
+		write(l, done);
+		writeline(OUTPUT, l);
+		std.env.finish;
+	end process;
+end;
+
+-- @elab top
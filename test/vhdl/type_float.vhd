@@ -0,0 +1,11 @@
+package pkg is
+	type PROBABILITY is range 0.0 to 1.0;
+	type SYMMETRIC is range -1.5 to 1.5;
+	type SCALE is range 2.5e3 downto 1.0e-3;
+
+	constant HALF : PROBABILITY := 0.5;
+	constant PI : REAL := 3.14159;
+	constant LOW : SYMMETRIC := 1.25;
+end;
+
+-- @elab pkg